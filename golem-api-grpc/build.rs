@@ -36,8 +36,12 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 "proto/golem/component/function_parameter.proto",
                 "proto/golem/component/function_result.proto",
                 "proto/golem/component/component.proto",
+                "proto/golem/component/component_compatibility.proto",
                 "proto/golem/component/component_id.proto",
                 "proto/golem/component/component_metadata.proto",
+                "proto/golem/component/component_observability.proto",
+                "proto/golem/component/component_update_policy.proto",
+                "proto/golem/component/project.proto",
                 "proto/golem/component/versioned_name.proto",
                 "proto/golem/component/versioned_component_id.proto",
                 "proto/golem/component/v1/component_service.proto",
@@ -45,16 +49,19 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 "proto/golem/componentcompilation/v1/component_compilation_service.proto",
                 "proto/golem/worker/complete_parameters.proto",
                 "proto/golem/worker/idempotency_key.proto",
+                "proto/golem/worker/invocation_priority.proto",
                 "proto/golem/worker/invoke_parameters.proto",
                 "proto/golem/worker/invoke_result.proto",
                 "proto/golem/worker/log_event.proto",
                 "proto/golem/worker/promise_id.proto",
                 "proto/golem/worker/public_oplog.proto",
+                "proto/golem/worker/public_worker_status.proto",
                 "proto/golem/worker/update_mode.proto",
                 "proto/golem/worker/worker_id.proto",
                 "proto/golem/worker/worker_metadata.proto",
                 "proto/golem/worker/worker_filter.proto",
                 "proto/golem/worker/worker_status.proto",
+                "proto/golem/worker/worker_statistics.proto",
                 "proto/golem/worker/v1/worker_service.proto",
                 "proto/golem/worker/v1/worker_execution_error.proto",
                 "proto/golem/worker/v1/worker_error.proto",
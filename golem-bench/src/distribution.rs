@@ -0,0 +1,141 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap::ValueEnum;
+use rand::Rng;
+
+/// The distribution of the sizes of the written payloads
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SizeDistribution {
+    /// Every payload has the mean size
+    Fixed,
+    /// Sizes are uniformly distributed between 1 byte and twice the mean size
+    Uniform,
+    /// Sizes follow a log-normal distribution around the mean size, so most payloads are small
+    /// with a long tail of large ones, like the entries of a real oplog
+    LogNormal,
+}
+
+/// Standard deviation of the logarithm of the sizes in the log-normal distribution
+const LOG_NORMAL_SIGMA: f64 = 1.0;
+
+/// No payload is larger than this multiple of the mean size
+const MAX_SIZE_FACTOR: f64 = 32.0;
+
+impl SizeDistribution {
+    /// The largest size sampled for the given mean size
+    pub fn max_size(mean_size: usize) -> usize {
+        (mean_size.max(1) as f64 * MAX_SIZE_FACTOR) as usize
+    }
+
+    pub fn sample(&self, mean_size: usize, rng: &mut impl Rng) -> usize {
+        let mean = mean_size.max(1) as f64;
+        let size = match self {
+            SizeDistribution::Fixed => mean,
+            SizeDistribution::Uniform => rng.gen_range(1.0..=(2.0 * mean)),
+            SizeDistribution::LogNormal => {
+                let mu = mean.ln() - LOG_NORMAL_SIGMA * LOG_NORMAL_SIGMA / 2.0;
+                (mu + LOG_NORMAL_SIGMA * standard_normal(rng)).exp()
+            }
+        };
+        size.clamp(1.0, mean * MAX_SIZE_FACTOR).round() as usize
+    }
+}
+
+/// Samples the standard normal distribution with the Box-Muller transform
+fn standard_normal(rng: &mut impl Rng) -> f64 {
+    let u1: f64 = 1.0 - rng.gen::<f64>();
+    let u2: f64 = rng.gen();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+/// Chooses the accessed workers following Zipf's law, so a few hot workers get most of the
+/// operations, as in a typical deployment
+pub struct Zipf {
+    cumulative_weights: Vec<f64>,
+}
+
+impl Zipf {
+    /// An exponent of 0 accesses every worker uniformly, larger exponents skew the accesses
+    /// towards the first workers
+    pub fn new(count: usize, exponent: f64) -> Self {
+        let mut total = 0.0;
+        let cumulative_weights = (1..=count.max(1))
+            .map(|rank| {
+                total += 1.0 / (rank as f64).powf(exponent);
+                total
+            })
+            .collect();
+        Self { cumulative_weights }
+    }
+
+    pub fn sample(&self, rng: &mut impl Rng) -> usize {
+        let total = self.cumulative_weights[self.cumulative_weights.len() - 1];
+        let point = rng.gen_range(0.0..total);
+        self.cumulative_weights
+            .partition_point(|weight| *weight <= point)
+            .min(self.cumulative_weights.len() - 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use crate::distribution::{SizeDistribution, Zipf};
+
+    #[test]
+    fn sizes_stay_within_bounds() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for distribution in [
+            SizeDistribution::Fixed,
+            SizeDistribution::Uniform,
+            SizeDistribution::LogNormal,
+        ] {
+            for _ in 0..1000 {
+                let size = distribution.sample(100, &mut rng);
+                assert!((1..=3200).contains(&size), "{distribution:?}: {size}");
+            }
+        }
+        assert_eq!(SizeDistribution::Fixed.sample(100, &mut rng), 100);
+    }
+
+    #[test]
+    fn log_normal_sizes_have_the_requested_mean() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let samples = 100_000;
+        let total: usize = (0..samples)
+            .map(|_| SizeDistribution::LogNormal.sample(1000, &mut rng))
+            .sum();
+        let mean = total as f64 / samples as f64;
+        assert!((900.0..1100.0).contains(&mean), "{mean}");
+    }
+
+    #[test]
+    fn zipf_prefers_the_first_workers() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let zipf = Zipf::new(100, 1.0);
+        let mut counts = [0usize; 100];
+        for _ in 0..100_000 {
+            counts[zipf.sample(&mut rng)] += 1;
+        }
+        assert!(counts[0] > counts[1]);
+        assert!(counts[1] > counts[10]);
+        assert!(counts[10] > counts[99]);
+        assert!(counts[99] > 0);
+    }
+}
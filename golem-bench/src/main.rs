@@ -0,0 +1,52 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use clap::{Parser, Subcommand};
+
+use golem_bench::storage::{self, StorageBenchmarkArgs};
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "golem-bench",
+    version,
+    about = "Benchmarks of the Golem backends"
+)]
+struct Cli {
+    /// Prints the report as JSON
+    #[arg(long, global = true)]
+    json: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Measures the throughput and latencies of the storage backends configured for the worker
+    /// executor
+    Storage(StorageBenchmarkArgs),
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+    let report = match &cli.command {
+        Command::Storage(args) => storage::run(args).await?,
+    };
+    if cli.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print!("{report}");
+    }
+    Ok(())
+}
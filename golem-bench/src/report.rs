@@ -0,0 +1,148 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use serde::Serialize;
+
+/// The measured throughput and latencies of one kind of storage operation
+#[derive(Debug, Clone, Serialize)]
+pub struct OperationReport {
+    pub operation: String,
+    pub count: usize,
+    pub errors: usize,
+    pub bytes: u64,
+    pub ops_per_sec: f64,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+impl OperationReport {
+    /// Summarizes the latencies of the successful operations, the throughput being measured
+    /// over the elapsed time of the whole phase
+    pub fn new(
+        operation: &str,
+        mut latencies: Vec<Duration>,
+        errors: usize,
+        bytes: u64,
+        elapsed: Duration,
+    ) -> Self {
+        latencies.sort();
+        let count = latencies.len();
+        let elapsed = elapsed.as_secs_f64();
+        Self {
+            operation: operation.to_string(),
+            count,
+            errors,
+            bytes,
+            ops_per_sec: if elapsed > 0.0 {
+                count as f64 / elapsed
+            } else {
+                0.0
+            },
+            p50_ms: millis(percentile(&latencies, 50.0)),
+            p90_ms: millis(percentile(&latencies, 90.0)),
+            p99_ms: millis(percentile(&latencies, 99.0)),
+            max_ms: millis(latencies.last().copied().unwrap_or_default()),
+        }
+    }
+}
+
+/// The nearest-rank percentile of sorted latencies
+pub fn percentile(sorted: &[Duration], percentile: f64) -> Duration {
+    if sorted.is_empty() {
+        Duration::ZERO
+    } else {
+        let rank = (percentile / 100.0 * sorted.len() as f64).ceil() as usize;
+        sorted[rank.clamp(1, sorted.len()) - 1]
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Report {
+    pub key_value_storage: String,
+    pub indexed_storage: String,
+    pub blob_storage: String,
+    pub operations: Vec<OperationReport>,
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "key-value storage: {}", self.key_value_storage)?;
+        writeln!(f, "indexed storage:   {}", self.indexed_storage)?;
+        writeln!(f, "blob storage:      {}", self.blob_storage)?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{:<16} {:>8} {:>7} {:>12} {:>10} {:>9} {:>9} {:>9} {:>9}",
+            "operation", "count", "errors", "MiB", "ops/s", "p50 ms", "p90 ms", "p99 ms", "max ms"
+        )?;
+        for op in &self.operations {
+            writeln!(
+                f,
+                "{:<16} {:>8} {:>7} {:>12.2} {:>10.1} {:>9.2} {:>9.2} {:>9.2} {:>9.2}",
+                op.operation,
+                op.count,
+                op.errors,
+                op.bytes as f64 / (1024.0 * 1024.0),
+                op.ops_per_sec,
+                op.p50_ms,
+                op.p90_ms,
+                op.p99_ms,
+                op.max_ms
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::time::Duration;
+
+    use crate::report::{percentile, OperationReport};
+
+    #[test]
+    fn percentiles_use_the_nearest_rank() {
+        let latencies: Vec<Duration> = (1..=100).map(Duration::from_millis).collect();
+        assert_eq!(percentile(&latencies, 50.0), Duration::from_millis(50));
+        assert_eq!(percentile(&latencies, 99.0), Duration::from_millis(99));
+        assert_eq!(percentile(&latencies, 100.0), Duration::from_millis(100));
+        assert_eq!(percentile(&latencies, 0.0), Duration::from_millis(1));
+        assert_eq!(percentile(&[], 50.0), Duration::ZERO);
+    }
+
+    #[test]
+    fn report_sorts_the_latencies() {
+        let latencies = vec![
+            Duration::from_millis(30),
+            Duration::from_millis(10),
+            Duration::from_millis(20),
+        ];
+        let report = OperationReport::new("op", latencies, 1, 0, Duration::from_secs(2));
+        assert_eq!(report.count, 3);
+        assert_eq!(report.ops_per_sec, 1.5);
+        assert_eq!(report.p50_ms, 20.0);
+        assert_eq!(report.max_ms, 30.0);
+    }
+}
@@ -0,0 +1,623 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Benchmark of the storage abstractions used by the worker executor, simulating the storage
+//! traffic of a component's workers: appending and reading oplog entries, writing and reading
+//! worker statuses, enumerating the workers and uploading blobs.
+//!
+//! All the data is written under a freshly generated component id, so the benchmark can be run
+//! against a backend already used by executors, and is deleted at the end unless asked
+//! otherwise.
+
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::anyhow;
+use futures::stream::{self, StreamExt};
+use rand::rngs::StdRng;
+use rand::{Rng, RngCore, SeedableRng};
+use uuid::Builder;
+
+use golem_common::config::ConfigLoader;
+use golem_common::model::{AccountId, ComponentId, WorkerId, WorkerName};
+use golem_common::redis::RedisPool;
+use golem_worker_executor_base::services::golem_config::{
+    BlobStorageConfig, GolemConfig, IndexedStorageConfig, KeyValueStorageConfig,
+};
+use golem_worker_executor_base::services::oplog::PrimaryOplogService;
+use golem_worker_executor_base::storage::blob::fs::FileSystemBlobStorage;
+use golem_worker_executor_base::storage::blob::memory::InMemoryBlobStorage;
+use golem_worker_executor_base::storage::blob::s3::S3BlobStorage;
+use golem_worker_executor_base::storage::blob::{BlobStorage, BlobStorageNamespace};
+use golem_worker_executor_base::storage::indexed::memory::InMemoryIndexedStorage;
+use golem_worker_executor_base::storage::indexed::redis::RedisIndexedStorage;
+use golem_worker_executor_base::storage::indexed::{IndexedStorage, IndexedStorageNamespace};
+use golem_worker_executor_base::storage::keyvalue::memory::InMemoryKeyValueStorage;
+use golem_worker_executor_base::storage::keyvalue::redis::RedisKeyValueStorage;
+use golem_worker_executor_base::storage::keyvalue::sqlite::SqliteKeyValueStorage;
+use golem_worker_executor_base::storage::keyvalue::{KeyValueStorage, KeyValueStorageNamespace};
+use golem_worker_executor_base::storage::sqlite_types::SqlitePool;
+
+use crate::distribution::{SizeDistribution, Zipf};
+use crate::report::{OperationReport, Report};
+
+const SVC_NAME: &str = "bench";
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct StorageBenchmarkArgs {
+    /// Worker executor configuration selecting the benchmarked backends, which can be
+    /// overridden by the usual GOLEM__ environment variables
+    #[arg(long, default_value = "config/worker-executor.toml")]
+    pub config: PathBuf,
+    /// Number of simulated workers
+    #[arg(long, default_value_t = 100)]
+    pub workers: usize,
+    /// Number of oplog entries appended to each worker
+    #[arg(long, default_value_t = 100)]
+    pub oplog_entries: u64,
+    /// Mean size of an oplog entry in bytes
+    #[arg(long, default_value_t = 256)]
+    pub oplog_entry_size: usize,
+    /// Number of oplog range reads
+    #[arg(long, default_value_t = 1000)]
+    pub oplog_reads: usize,
+    /// Number of entries fetched by an oplog range read
+    #[arg(long, default_value_t = 20)]
+    pub oplog_read_range: u64,
+    /// Number of worker status writes, and of worker status reads
+    #[arg(long, default_value_t = 1000)]
+    pub status_operations: usize,
+    /// Mean size of a worker status in bytes
+    #[arg(long, default_value_t = 512)]
+    pub status_size: usize,
+    /// Number of full enumerations of the workers
+    #[arg(long, default_value_t = 10)]
+    pub enumerations: usize,
+    /// Number of keys requested per page when enumerating the workers
+    #[arg(long, default_value_t = 100)]
+    pub enumeration_page_size: u64,
+    /// Number of uploaded blobs
+    #[arg(long, default_value_t = 50)]
+    pub blobs: usize,
+    /// Mean size of an uploaded blob in bytes
+    #[arg(long, default_value_t = 1024 * 1024)]
+    pub blob_size: usize,
+    /// Distribution of the payload sizes around their mean size
+    #[arg(long, value_enum, default_value_t = SizeDistribution::LogNormal)]
+    pub size_distribution: SizeDistribution,
+    /// Exponent of the Zipf distribution choosing the accessed workers, 0 accessing all of
+    /// them uniformly
+    #[arg(long, default_value_t = 1.0)]
+    pub worker_skew: f64,
+    /// Maximum number of operations running at the same time
+    #[arg(long, default_value_t = 16)]
+    pub concurrency: usize,
+    /// Seed of the generated workload, to repeat the same run against different backends
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// Keeps the written data instead of deleting it at the end of the run
+    #[arg(long)]
+    pub keep_data: bool,
+}
+
+/// Runs the benchmark against the backends of the configuration
+pub async fn run(args: &StorageBenchmarkArgs) -> anyhow::Result<Report> {
+    let config = ConfigLoader::<GolemConfig>::new(&args.config)
+        .load()
+        .map_err(|err| anyhow!("Failed to load {}: {err}", args.config.display()))?;
+    let storages = Storages::configured(&config).await?;
+    let mut rng = match args.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let benchmark = StorageBenchmark::new(args, storages, &mut rng);
+
+    let mut report = Report {
+        key_value_storage: describe_key_value_storage(&config.key_value_storage),
+        indexed_storage: describe_indexed_storage(&config.indexed_storage),
+        blob_storage: describe_blob_storage(&config.blob_storage),
+        operations: Vec::new(),
+    };
+    benchmark.run_all(&mut report, &mut rng).await;
+    if !args.keep_data {
+        benchmark.delete_data().await;
+    }
+    Ok(report)
+}
+
+struct Storages {
+    key_value: Arc<dyn KeyValueStorage + Send + Sync>,
+    indexed: Arc<dyn IndexedStorage + Send + Sync>,
+    blob: Arc<dyn BlobStorage + Send + Sync>,
+}
+
+impl Storages {
+    /// Creates the storages the same way the worker executor does
+    async fn configured(config: &GolemConfig) -> anyhow::Result<Self> {
+        let (redis, key_value): (Option<RedisPool>, Arc<dyn KeyValueStorage + Send + Sync>) =
+            match &config.key_value_storage {
+                KeyValueStorageConfig::Redis(redis) => {
+                    let pool = RedisPool::configured(redis)
+                        .await
+                        .map_err(|err| anyhow!(err))?;
+                    (
+                        Some(pool.clone()),
+                        Arc::new(RedisKeyValueStorage::new(pool)),
+                    )
+                }
+                KeyValueStorageConfig::InMemory => (None, Arc::new(InMemoryKeyValueStorage::new())),
+                KeyValueStorageConfig::Sqlite(sqlite) => {
+                    let pool = SqlitePool::configured(sqlite)
+                        .await
+                        .map_err(|err| anyhow!(err))?;
+                    (None, Arc::new(SqliteKeyValueStorage::new(pool)))
+                }
+            };
+
+        let indexed: Arc<dyn IndexedStorage + Send + Sync> = match &config.indexed_storage {
+            IndexedStorageConfig::KVStoreRedis => {
+                let redis = redis.ok_or_else(|| {
+                    anyhow!("Redis must be configured key-value storage when using KVStoreRedis")
+                })?;
+                Arc::new(RedisIndexedStorage::new(redis))
+            }
+            IndexedStorageConfig::Redis(redis) => {
+                let pool = RedisPool::configured(redis).await?;
+                Arc::new(RedisIndexedStorage::new(pool))
+            }
+            IndexedStorageConfig::InMemory => Arc::new(InMemoryIndexedStorage::new()),
+        };
+
+        let blob: Arc<dyn BlobStorage + Send + Sync> = match &config.blob_storage {
+            BlobStorageConfig::S3(config) => Arc::new(S3BlobStorage::new(config.clone()).await),
+            BlobStorageConfig::LocalFileSystem(config) => Arc::new(
+                FileSystemBlobStorage::new(&config.root)
+                    .await
+                    .map_err(|err| anyhow!(err))?,
+            ),
+            BlobStorageConfig::InMemory => Arc::new(InMemoryBlobStorage::new()),
+        };
+
+        Ok(Self {
+            key_value,
+            indexed,
+            blob,
+        })
+    }
+}
+
+fn describe_key_value_storage(config: &KeyValueStorageConfig) -> String {
+    match config {
+        KeyValueStorageConfig::Redis(redis) => format!("Redis at {}", redis.url()),
+        KeyValueStorageConfig::Sqlite(sqlite) => format!("Sqlite at {}", sqlite.database),
+        KeyValueStorageConfig::InMemory => "in-memory".to_string(),
+    }
+}
+
+fn describe_indexed_storage(config: &IndexedStorageConfig) -> String {
+    match config {
+        IndexedStorageConfig::KVStoreRedis => "the key-value storage's Redis".to_string(),
+        IndexedStorageConfig::Redis(redis) => format!("Redis at {}", redis.url()),
+        IndexedStorageConfig::InMemory => "in-memory".to_string(),
+    }
+}
+
+fn describe_blob_storage(config: &BlobStorageConfig) -> String {
+    match config {
+        BlobStorageConfig::S3(_) => "S3".to_string(),
+        BlobStorageConfig::LocalFileSystem(config) => {
+            format!("local file system at {}", config.root.display())
+        }
+        BlobStorageConfig::InMemory => "in-memory".to_string(),
+    }
+}
+
+/// The duration of an operation and the number of bytes it transferred
+type Sample = (Duration, Result<u64, String>);
+
+async fn timed(operation: impl Future<Output = Result<u64, String>>) -> Sample {
+    let start = Instant::now();
+    let result = operation.await;
+    (start.elapsed(), result)
+}
+
+/// Runs the tasks with the configured concurrency, each task producing the samples of one or
+/// more operations
+async fn measure<I, F, Fut>(
+    operation: &str,
+    concurrency: usize,
+    tasks: I,
+    run_task: F,
+) -> OperationReport
+where
+    I: IntoIterator,
+    F: Fn(I::Item) -> Fut,
+    Fut: Future<Output = Vec<Sample>>,
+{
+    let start = Instant::now();
+    let samples: Vec<Vec<Sample>> = stream::iter(tasks)
+        .map(run_task)
+        .buffer_unordered(concurrency.max(1))
+        .collect()
+        .await;
+    let elapsed = start.elapsed();
+
+    let mut latencies = Vec::new();
+    let mut errors = 0;
+    let mut bytes = 0;
+    for (latency, result) in samples.into_iter().flatten() {
+        match result {
+            Ok(transferred) => {
+                latencies.push(latency);
+                bytes += transferred;
+            }
+            Err(err) => {
+                if errors == 0 {
+                    eprintln!("{operation} failed: {err}");
+                }
+                errors += 1;
+            }
+        }
+    }
+    OperationReport::new(operation, latencies, errors, bytes, elapsed)
+}
+
+struct StorageBenchmark<'a> {
+    args: &'a StorageBenchmarkArgs,
+    storages: Storages,
+    component_id: ComponentId,
+    account_id: AccountId,
+    workers: Vec<WorkerId>,
+    /// Random bytes the written payloads are sliced from
+    payload: Vec<u8>,
+}
+
+impl<'a> StorageBenchmark<'a> {
+    fn new(args: &'a StorageBenchmarkArgs, storages: Storages, rng: &mut StdRng) -> Self {
+        let component_id = ComponentId(Builder::from_random_bytes(rng.gen()).into_uuid());
+        let workers = (0..args.workers)
+            .map(|idx| WorkerId {
+                component_id: component_id.clone(),
+                worker_name: WorkerName::from_existing(format!("bench-worker-{idx}")),
+            })
+            .collect();
+        let max_size = [args.oplog_entry_size, args.status_size, args.blob_size]
+            .into_iter()
+            .max()
+            .unwrap_or_default();
+        let mut payload = vec![0; SizeDistribution::max_size(max_size)];
+        rng.fill_bytes(&mut payload);
+
+        Self {
+            args,
+            storages,
+            component_id,
+            account_id: AccountId::from("golem-bench"),
+            workers,
+            payload,
+        }
+    }
+
+    async fn run_all(&self, report: &mut Report, rng: &mut StdRng) {
+        report.operations.push(self.oplog_append(rng).await);
+        report.operations.push(self.oplog_read(rng).await);
+        report.operations.push(self.status_write(rng).await);
+        report.operations.push(self.status_read(rng).await);
+        report.operations.push(self.enumeration().await);
+        report.operations.push(self.blob_upload(rng).await);
+    }
+
+    fn sample_size(&self, mean_size: usize, rng: &mut StdRng) -> usize {
+        self.args
+            .size_distribution
+            .sample(mean_size, rng)
+            .min(self.payload.len())
+    }
+
+    fn zipf_workers(&self, count: usize, rng: &mut StdRng) -> Vec<&WorkerId> {
+        let zipf = Zipf::new(self.workers.len(), self.args.worker_skew);
+        (0..count)
+            .filter_map(|_| self.workers.get(zipf.sample(rng)))
+            .collect()
+    }
+
+    fn oplog_key(worker_id: &WorkerId) -> String {
+        worker_id.to_redis_key()
+    }
+
+    fn status_key(worker_id: &WorkerId) -> String {
+        format!("worker:status:{}", worker_id.to_redis_key())
+    }
+
+    fn blob_path(&self, idx: usize) -> PathBuf {
+        PathBuf::from(format!("golem-bench/{}/{idx}", self.component_id))
+    }
+
+    /// Appends the entries of each worker one by one, the workers' oplogs growing in parallel
+    async fn oplog_append(&self, rng: &mut StdRng) -> OperationReport {
+        let tasks: Vec<(&WorkerId, Vec<usize>)> = self
+            .workers
+            .iter()
+            .map(|worker_id| {
+                let sizes = (0..self.args.oplog_entries)
+                    .map(|_| self.sample_size(self.args.oplog_entry_size, rng))
+                    .collect();
+                (worker_id, sizes)
+            })
+            .collect();
+
+        measure(
+            "oplog append",
+            self.args.concurrency,
+            tasks,
+            |(worker_id, sizes)| async move {
+                let key = Self::oplog_key(worker_id);
+                let mut samples = Vec::with_capacity(sizes.len());
+                for (idx, size) in sizes.into_iter().enumerate() {
+                    samples.push(
+                        timed(async {
+                            self.storages
+                                .indexed
+                                .append(
+                                    SVC_NAME,
+                                    "append",
+                                    "entry",
+                                    IndexedStorageNamespace::OpLog,
+                                    &key,
+                                    idx as u64 + 1,
+                                    &self.payload[..size],
+                                )
+                                .await
+                                .map(|_| size as u64)
+                        })
+                        .await,
+                    );
+                }
+                samples
+            },
+        )
+        .await
+    }
+
+    /// Reads ranges of entries from the oplogs, like workers being recovered
+    async fn oplog_read(&self, rng: &mut StdRng) -> OperationReport {
+        let entries = self.args.oplog_entries.max(1);
+        let tasks: Vec<(&WorkerId, u64)> = self
+            .zipf_workers(self.args.oplog_reads, rng)
+            .into_iter()
+            .map(|worker_id| (worker_id, rng.gen_range(1..=entries)))
+            .collect();
+
+        measure(
+            "oplog read",
+            self.args.concurrency,
+            tasks,
+            |(worker_id, start)| async move {
+                let end = (start + self.args.oplog_read_range.max(1) - 1).min(entries);
+                vec![
+                    timed(async {
+                        self.storages
+                            .indexed
+                            .read(
+                                SVC_NAME,
+                                "read",
+                                "entry",
+                                IndexedStorageNamespace::OpLog,
+                                &Self::oplog_key(worker_id),
+                                start,
+                                end,
+                            )
+                            .await
+                            .map(|entries| {
+                                entries.iter().map(|(_, entry)| entry.len() as u64).sum()
+                            })
+                    })
+                    .await,
+                ]
+            },
+        )
+        .await
+    }
+
+    async fn status_write(&self, rng: &mut StdRng) -> OperationReport {
+        let tasks: Vec<(&WorkerId, usize)> = self
+            .zipf_workers(self.args.status_operations, rng)
+            .into_iter()
+            .map(|worker_id| (worker_id, self.sample_size(self.args.status_size, rng)))
+            .collect();
+
+        measure(
+            "status write",
+            self.args.concurrency,
+            tasks,
+            |(worker_id, size)| async move {
+                vec![
+                    timed(async {
+                        self.storages
+                            .key_value
+                            .set(
+                                SVC_NAME,
+                                "set",
+                                "worker_status",
+                                KeyValueStorageNamespace::Worker,
+                                &Self::status_key(worker_id),
+                                &self.payload[..size],
+                            )
+                            .await
+                            .map(|_| size as u64)
+                    })
+                    .await,
+                ]
+            },
+        )
+        .await
+    }
+
+    /// Reads the statuses of the workers, missing statuses of workers which were never written
+    /// counting as successful reads
+    async fn status_read(&self, rng: &mut StdRng) -> OperationReport {
+        let tasks = self.zipf_workers(self.args.status_operations, rng);
+
+        measure(
+            "status read",
+            self.args.concurrency,
+            tasks,
+            |worker_id| async move {
+                vec![
+                    timed(async {
+                        self.storages
+                            .key_value
+                            .get(
+                                SVC_NAME,
+                                "get",
+                                "worker_status",
+                                KeyValueStorageNamespace::Worker,
+                                &Self::status_key(worker_id),
+                            )
+                            .await
+                            .map(|status| status.map_or(0, |status| status.len() as u64))
+                    })
+                    .await,
+                ]
+            },
+        )
+        .await
+    }
+
+    /// Enumerates the workers of the component page by page, each page being one operation
+    async fn enumeration(&self) -> OperationReport {
+        measure(
+            "enumeration",
+            self.args.concurrency,
+            0..self.args.enumerations,
+            |_| async move {
+                let pattern = PrimaryOplogService::key_pattern(&self.component_id);
+                let mut samples = Vec::new();
+                let mut cursor = 0;
+                loop {
+                    let start = Instant::now();
+                    match self
+                        .storages
+                        .indexed
+                        .scan(
+                            SVC_NAME,
+                            "scan",
+                            IndexedStorageNamespace::OpLog,
+                            &pattern,
+                            cursor,
+                            self.args.enumeration_page_size,
+                        )
+                        .await
+                    {
+                        Ok((next_cursor, keys)) => {
+                            let bytes = keys.iter().map(|key| key.len() as u64).sum();
+                            samples.push((start.elapsed(), Ok(bytes)));
+                            if next_cursor == 0 {
+                                break;
+                            }
+                            cursor = next_cursor;
+                        }
+                        Err(err) => {
+                            samples.push((start.elapsed(), Err(err)));
+                            break;
+                        }
+                    }
+                }
+                samples
+            },
+        )
+        .await
+    }
+
+    async fn blob_upload(&self, rng: &mut StdRng) -> OperationReport {
+        let tasks: Vec<(usize, usize)> = (0..self.args.blobs)
+            .map(|idx| (idx, self.sample_size(self.args.blob_size, rng)))
+            .collect();
+
+        measure(
+            "blob upload",
+            self.args.concurrency,
+            tasks,
+            |(idx, size)| async move {
+                vec![
+                    timed(async {
+                        self.storages
+                            .blob
+                            .put_raw(
+                                SVC_NAME,
+                                "put_raw",
+                                BlobStorageNamespace::CustomStorage(self.account_id.clone()),
+                                &self.blob_path(idx),
+                                &self.payload[..size],
+                            )
+                            .await
+                            .map(|_| size as u64)
+                    })
+                    .await,
+                ]
+            },
+        )
+        .await
+    }
+
+    /// Deletes everything the benchmark may have written, reporting but otherwise ignoring the
+    /// failures
+    async fn delete_data(&self) {
+        let mut failures = 0;
+        for worker_id in &self.workers {
+            let deleted_oplog = self
+                .storages
+                .indexed
+                .delete(
+                    SVC_NAME,
+                    "delete",
+                    IndexedStorageNamespace::OpLog,
+                    &Self::oplog_key(worker_id),
+                )
+                .await;
+            let deleted_status = self
+                .storages
+                .key_value
+                .del(
+                    SVC_NAME,
+                    "del",
+                    KeyValueStorageNamespace::Worker,
+                    &Self::status_key(worker_id),
+                )
+                .await;
+            failures += deleted_oplog.is_err() as usize + deleted_status.is_err() as usize;
+        }
+        for idx in 0..self.args.blobs {
+            let deleted_blob = self
+                .storages
+                .blob
+                .delete(
+                    SVC_NAME,
+                    "delete",
+                    BlobStorageNamespace::CustomStorage(self.account_id.clone()),
+                    &self.blob_path(idx),
+                )
+                .await;
+            failures += deleted_blob.is_err() as usize;
+        }
+        if failures > 0 {
+            eprintln!(
+                "Failed to delete {failures} entries written for component {}",
+                self.component_id
+            );
+        }
+    }
+}
@@ -105,6 +105,7 @@ pub trait WorkerClient {
 pub fn worker_name_required(urn: &WorkerUrn) -> Result<String, GolemError> {
     urn.id
         .worker_name
-        .clone()
+        .as_ref()
+        .map(|worker_name| worker_name.to_string())
         .ok_or_else(|| GolemError("Must specify the worker's name".to_string()))
 }
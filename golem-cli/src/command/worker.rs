@@ -125,7 +125,9 @@ impl From<&OssWorkerNameOrUriArg> for OssWorkerUriArg {
                         let uri = WorkerUri::URN(WorkerUrn {
                             id: TargetWorkerId {
                                 component_id: component_urn.id.clone(),
-                                worker_name: Some(worker_name),
+                                worker_name: Some(golem_common::model::WorkerName::from_existing(
+                                    worker_name,
+                                )),
                             },
                         });
                         OssWorkerUriArg {
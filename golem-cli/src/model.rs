@@ -591,6 +591,8 @@ impl From<golem_client::model::WorkerMetadata> for WorkerMetadata {
             component_size,
             total_linear_memory_size,
             owned_resources,
+            corrupted_oplog_regions: _,
+            last_invoked_function: _,
         } = value;
 
         WorkerMetadata {
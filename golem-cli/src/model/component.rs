@@ -50,6 +50,8 @@ impl From<golem_client::model::Component> for Component {
             component_type,
             metadata,
             created_at,
+            compatibility_report: _,
+            initial_files_hash: _,
         } = value;
 
         Component {
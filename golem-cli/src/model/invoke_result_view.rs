@@ -111,6 +111,8 @@ impl InvokeResultView {
 mod tests {
     use test_r::test;
 
+    use std::collections::HashMap;
+
     use chrono::Utc;
     use golem_wasm_ast::analysis::analysed_type::{bool, handle};
     use golem_wasm_ast::analysis::{
@@ -166,6 +168,10 @@ mod tests {
                     results: func_res,
                 })],
                 memories: vec![],
+                labels: HashMap::new(),
+                rpc_dependencies: vec![],
+                imports: vec![],
+                plugs: vec![],
             },
             project_id: None,
             created_at: Some(Utc::now()),
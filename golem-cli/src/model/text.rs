@@ -827,7 +827,13 @@ pub mod worker {
                 component_urn: ComponentUrn {
                     id: value.worker_urn.id.component_id.clone(),
                 },
-                worker_name: value.worker_urn.id.worker_name.clone().unwrap_or_default(),
+                worker_name: value
+                    .worker_urn
+                    .id
+                    .worker_name
+                    .as_ref()
+                    .map(|worker_name| worker_name.to_string())
+                    .unwrap_or_default(),
                 status: format_status(&value.status),
                 component_version: value.component_version,
                 created_at: value.created_at,
@@ -888,7 +894,12 @@ pub mod worker {
         fn from(value: &WorkerUrn) -> Self {
             WorkerUrnTableView {
                 worker_urn: value.clone(),
-                worker_name: value.id.worker_name.clone().unwrap_or_default(),
+                worker_name: value
+                    .id
+                    .worker_name
+                    .as_ref()
+                    .map(|worker_name| worker_name.to_string())
+                    .unwrap_or_default(),
             }
         }
     }
@@ -1202,6 +1213,63 @@ pub mod worker {
                     println!("{}", format_message_highlight("RESTART"));
                     println!("{pad}at:                {}", format_id(&params.timestamp));
                 }
+                PublicOplogEntry::Snapshot(params) => {
+                    println!("{}", format_message_highlight("SNAPSHOT"));
+                    println!("{pad}at:                {}", format_id(&params.timestamp));
+                    println!(
+                        "{pad}component version: {}",
+                        format_id(&params.component_version)
+                    );
+                }
+                PublicOplogEntry::NetworkEgressDenied(params) => {
+                    println!("{}", format_message_highlight("NETWORK EGRESS DENIED"));
+                    println!("{pad}at:                {}", format_id(&params.timestamp));
+                    println!("{pad}host:              {}", format_id(&params.host));
+                    println!(
+                        "{pad}rule:              {}",
+                        format_id(&params.rule.clone().unwrap_or("-".to_string()))
+                    );
+                }
+                PublicOplogEntry::Revive(params) => {
+                    println!("{}", format_message_highlight("REVIVE"));
+                    println!("{pad}at:                {}", format_id(&params.timestamp));
+                    if let Some(region) = &params.trimmed_region {
+                        println!("{pad}trimmed from:      {}", format_id(&region.start));
+                        println!("{pad}trimmed to:        {}", format_id(&region.end));
+                    }
+                }
+                PublicOplogEntry::ChangeEnvironment(params) => {
+                    println!("{}", format_message_highlight("CHANGE ENVIRONMENT"));
+                    println!("{pad}at:                {}", format_id(&params.timestamp));
+                    println!(
+                        "{pad}args:              {}",
+                        format_id(&params.args.join(", "))
+                    );
+                    println!("{pad}env:");
+                    for (k, v) in &params.env {
+                        println!("{pad}  - {}: {}", k, format_id(&v));
+                    }
+                }
+                PublicOplogEntry::InvocationContext(params) => {
+                    println!("{}", format_message_highlight("INVOCATION CONTEXT"));
+                    println!("{pad}at:                {}", format_id(&params.timestamp));
+                    println!("{pad}context:");
+                    for (k, v) in &params.context {
+                        println!("{pad}  - {}: {}", k, format_id(&v));
+                    }
+                }
+                PublicOplogEntry::Forked(params) => {
+                    println!("{}", format_message_highlight("FORKED"));
+                    println!("{pad}at:                {}", format_id(&params.timestamp));
+                    println!(
+                        "{pad}source worker:     {}",
+                        format_id(&params.source_worker_id)
+                    );
+                    println!(
+                        "{pad}source index:      {}",
+                        format_id(&params.source_oplog_index)
+                    );
+                }
             }
         }
     }
@@ -72,7 +72,10 @@ impl<C: golem_client::api::ComponentClient + Sync + Send> ComponentClient
 
         let name = name.map(|n| n.0);
 
-        let components = self.client.get_components(name.as_deref()).await?;
+        let components = self
+            .client
+            .get_components(name.as_deref(), None, None)
+            .await?;
         Ok(components.into_iter().map(|c| c.into()).collect())
     }
 
@@ -92,7 +95,7 @@ impl<C: golem_client::api::ComponentClient + Sync + Send> ComponentClient
                     .map_err(|e| GolemError(format!("Can't open component file: {e}")))?;
 
                 self.client
-                    .create_component(&name.0, Some(&component_type), file)
+                    .create_component(None, None, &name.0, Some(&component_type), file)
                     .await?
             }
             PathBufOrStdin::Stdin => {
@@ -103,7 +106,7 @@ impl<C: golem_client::api::ComponentClient + Sync + Send> ComponentClient
                     .map_err(|e| GolemError(format!("Failed to read stdin: {e:?}")))?;
 
                 self.client
-                    .create_component(&name.0, Some(&component_type), bytes)
+                    .create_component(None, None, &name.0, Some(&component_type), bytes)
                     .await?
             }
         };
@@ -126,7 +129,7 @@ impl<C: golem_client::api::ComponentClient + Sync + Send> ComponentClient
                     .map_err(|e| GolemError(format!("Can't open component file: {e}")))?;
 
                 self.client
-                    .update_component(&urn.id.0, component_type.as_ref(), file)
+                    .update_component(&urn.id.0, component_type.as_ref(), None, file)
                     .await?
             }
             PathBufOrStdin::Stdin => {
@@ -137,7 +140,7 @@ impl<C: golem_client::api::ComponentClient + Sync + Send> ComponentClient
                     .map_err(|e| GolemError(format!("Failed to read stdin: {e:?}")))?;
 
                 self.client
-                    .update_component(&urn.id.0, component_type.as_ref(), bytes)
+                    .update_component(&urn.id.0, component_type.as_ref(), None, bytes)
                     .await?
             }
         };
@@ -18,12 +18,14 @@ use golem_client::api::{
 };
 use golem_client::model::{
     GolemError, GolemErrorComponentDownloadFailed, GolemErrorComponentParseFailed,
-    GolemErrorFailedToResumeWorker, GolemErrorGetLatestVersionOfComponentFailed,
-    GolemErrorInterrupted, GolemErrorInvalidRequest, GolemErrorInvalidShardId,
+    GolemErrorExecutorBusy, GolemErrorFailedToResumeWorker,
+    GolemErrorGetLatestVersionOfComponentFailed, GolemErrorInterrupted, GolemErrorInvalidRequest,
+    GolemErrorInvalidShardId, GolemErrorInvocationNotFound, GolemErrorInvocationTimedOut,
     GolemErrorPromiseAlreadyCompleted, GolemErrorPromiseDropped, GolemErrorPromiseNotFound,
-    GolemErrorRuntimeError, GolemErrorUnexpectedOplogEntry, GolemErrorUnknown,
-    GolemErrorValueMismatch, GolemErrorWorkerAlreadyExists, GolemErrorWorkerCreationFailed,
-    GolemErrorWorkerNotFound, PromiseId, WorkerId, WorkerServiceErrorsBody,
+    GolemErrorRuntimeError, GolemErrorTooManyPendingInvocations, GolemErrorUnexpectedOplogEntry,
+    GolemErrorUnknown, GolemErrorValueMismatch, GolemErrorWorkerAlreadyExists,
+    GolemErrorWorkerCreationFailed, GolemErrorWorkerNotFound, PromiseId, WorkerId,
+    WorkerServiceErrorsBody,
 };
 use itertools::Itertools;
 
@@ -48,7 +50,10 @@ impl ResponseContentErrorMapper for WorkerError {
             WorkerError::Error403(error) => error.error,
             WorkerError::Error404(error) => error.error,
             WorkerError::Error409(error) => error.error,
+            WorkerError::Error429(error) => display_golem_error(error.golem_error),
             WorkerError::Error500(error) => display_golem_error(error.golem_error),
+            WorkerError::Error503(error) => display_golem_error(error.golem_error),
+            WorkerError::Error504(error) => display_golem_error(error.golem_error),
         }
     }
 }
@@ -193,6 +198,42 @@ fn display_golem_error(error: GolemError) -> String {
         }
         GolemError::InvalidAccount(_) => "Invalid account".to_string(),
         GolemError::ShardingNotReady(_) => "Sharding not ready".to_string(),
+        GolemError::TooManyPendingInvocations(GolemErrorTooManyPendingInvocations {
+            worker_id,
+            pending_invocations,
+        }) => {
+            format!(
+                "Too many pending invocations for worker {}: {}",
+                display_worker_id(worker_id),
+                pending_invocations
+            )
+        }
+        GolemError::InvocationTimedOut(GolemErrorInvocationTimedOut {
+            worker_id,
+            timeout_millis,
+        }) => {
+            format!(
+                "Invocation of worker {} timed out after {} ms",
+                display_worker_id(worker_id),
+                timeout_millis
+            )
+        }
+        GolemError::ExecutorBusy(GolemErrorExecutorBusy { max_active_workers }) => {
+            format!(
+                "Worker executor is busy, already running {} active workers, retry later",
+                max_active_workers
+            )
+        }
+        GolemError::InvocationNotFound(GolemErrorInvocationNotFound {
+            worker_id,
+            idempotency_key,
+        }) => {
+            format!(
+                "No invocation with idempotency key {} was found for worker {}",
+                idempotency_key,
+                display_worker_id(worker_id)
+            )
+        }
     }
 }
 
@@ -65,6 +65,7 @@ impl<C: golem_client::api::WorkerClient + Sync + Send> WorkerClient for WorkerCl
                     name: name.0,
                     args,
                     env: env.into_iter().collect(),
+                    component_version_alias: None,
                 },
             )
             .await?
@@ -88,6 +89,9 @@ impl<C: golem_client::api::WorkerClient + Sync + Send> WorkerClient for WorkerCl
                     worker_name,
                     idempotency_key.as_ref().map(|k| k.0.as_str()),
                     &function,
+                    None,
+                    None,
+                    None,
                     &parameters,
                 )
                 .await?)
@@ -98,6 +102,11 @@ impl<C: golem_client::api::WorkerClient + Sync + Send> WorkerClient for WorkerCl
                     &worker_urn.id.component_id.0,
                     idempotency_key.as_ref().map(|k| k.0.as_str()),
                     &function,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
                     &parameters,
                 )
                 .await?)
@@ -121,6 +130,7 @@ impl<C: golem_client::api::WorkerClient + Sync + Send> WorkerClient for WorkerCl
                     worker_name,
                     idempotency_key.as_ref().map(|k| k.0.as_str()),
                     &function,
+                    None,
                     &parameters,
                 )
                 .await?;
@@ -131,6 +141,9 @@ impl<C: golem_client::api::WorkerClient + Sync + Send> WorkerClient for WorkerCl
                     &worker_urn.id.component_id.0,
                     idempotency_key.as_ref().map(|k| k.0.as_str()),
                     &function,
+                    None,
+                    None,
+                    None,
                     &parameters,
                 )
                 .await?;
@@ -440,6 +453,7 @@ impl<C: golem_client::api::WorkerClient + Sync + Send> WorkerClient for WorkerCl
                                 WorkerEvent::Close => {}
                                 WorkerEvent::InvocationStart { .. } => {}
                                 WorkerEvent::InvocationFinished { .. } => {}
+                                WorkerEvent::ResultChunk { .. } => {}
                             },
                         }
                     }
@@ -496,6 +510,7 @@ impl<C: golem_client::api::WorkerClient + Sync + Send> WorkerClient for WorkerCl
                     from,
                     100,
                     cursor.as_ref(),
+                    None,
                 )
                 .await?;
 
@@ -531,7 +546,10 @@ fn get_worker_golem_error(status: u16, body: Vec<u8>) -> GolemError {
         403 => serde_json::from_slice(&body).map(|body| Error::Item(WorkerError::Error403(body))),
         404 => serde_json::from_slice(&body).map(|body| Error::Item(WorkerError::Error404(body))),
         409 => serde_json::from_slice(&body).map(|body| Error::Item(WorkerError::Error409(body))),
+        429 => serde_json::from_slice(&body).map(|body| Error::Item(WorkerError::Error429(body))),
         500 => serde_json::from_slice(&body).map(|body| Error::Item(WorkerError::Error500(body))),
+        503 => serde_json::from_slice(&body).map(|body| Error::Item(WorkerError::Error503(body))),
+        504 => serde_json::from_slice(&body).map(|body| Error::Item(WorkerError::Error504(body))),
         _ => Ok(Error::unexpected(status, body.into())),
     };
     error.unwrap_or_else(Error::from).into()
@@ -142,7 +142,7 @@ impl<ProjectContext: Display + Send + Sync> DeployService for DeployServiceLive<
             self.worker_service
                 .add_by_urn(
                     component_urn.clone(),
-                    WorkerName(worker.worker_id.worker_name.clone()),
+                    WorkerName(worker.worker_id.worker_name.to_string()),
                     worker
                         .env
                         .iter()
@@ -222,7 +222,7 @@ async fn resolve_worker_component_version<ProjectContext: Send + Sync>(
                 component_urn.clone(),
                 Some(WorkerFilter::Name(WorkerNameFilter {
                     comparator: StringFilterComparator::Equal,
-                    value: worker_name,
+                    value: worker_name.into_string(),
                 })),
                 None,
                 Some(2),
@@ -455,7 +455,8 @@ impl<ProjectContext: Send + Sync + 'static> WorkerService for WorkerServiceLive<
                 Ok(WorkerUrn {
                     id: TargetWorkerId {
                         component_id: component_urn.id,
-                        worker_name,
+                        worker_name: worker_name
+                            .map(golem_common::model::WorkerName::from_existing),
                     },
                 })
             }
@@ -508,7 +509,10 @@ impl<ProjectContext: Send + Sync + 'static> WorkerService for WorkerServiceLive<
             .invoke_and_await(
                 worker_urn.clone(),
                 function.clone(),
-                InvokeParameters { params: parameters },
+                InvokeParameters {
+                    params: parameters,
+                    context: None,
+                },
                 idempotency_key,
             )
             .await?;
@@ -557,7 +561,10 @@ impl<ProjectContext: Send + Sync + 'static> WorkerService for WorkerServiceLive<
             .invoke(
                 worker_urn,
                 function,
-                InvokeParameters { params: parameters },
+                InvokeParameters {
+                    params: parameters,
+                    context: None,
+                },
                 idempotency_key,
             )
             .await?;
@@ -23,7 +23,7 @@ use golem_cli::model::component::ComponentView;
 use golem_client::model::{
     GolemWorkerBinding, GolemWorkerBindingWithTypeInfo, HttpApiDefinitionRequest,
     HttpApiDefinitionWithTypeInfo, MethodPattern, RibInputTypeInfo, Route, RouteWithTypeInfo,
-    VersionedComponentId,
+    VersionedComponentId, WorkerBindingMode,
 };
 use golem_test_framework::config::{EnvBasedTestDependencies, TestDependencies};
 use serde_json::json;
@@ -142,6 +142,8 @@ fn golem_def_with_response(
         id: id.to_string(),
         version: "0.1.0".to_string(),
         draft: true,
+        auth: None,
+        cors: None,
         routes: vec![Route {
             method: MethodPattern::Get,
             path: "/{user-id}/get-cart-contents".to_string(),
@@ -153,7 +155,9 @@ fn golem_def_with_response(
                 worker_name: "\"foo\"".to_string(),
                 idempotency_key: None,
                 response,
+                mode: None,
             },
+            cache: None,
         }],
     }
 }
@@ -259,6 +263,8 @@ pub fn to_definition(
         id: request.id,
         version: request.version,
         draft: request.draft,
+        auth: request.auth,
+        cors: request.cors,
         routes: request
             .routes
             .iter()
@@ -280,7 +286,9 @@ pub fn to_definition(
                             types: HashMap::new(),
                         }),
                         idempotency_key_input: None,
+                        mode: v.binding.mode.unwrap_or(WorkerBindingMode::Json),
                     },
+                    cache: v.cache,
                 }
             })
             .collect(),
@@ -24,7 +24,7 @@ use assert2::assert;
 use golem_cli::model::component::ComponentView;
 use golem_cli::model::Format;
 use golem_client::model::{ApiDeployment, HttpApiDefinitionWithTypeInfo};
-use golem_common::model::TargetWorkerId;
+use golem_common::model::{TargetWorkerId, WorkerName};
 use golem_common::uri::oss::urn::{ComponentUrn, WorkerUrn};
 use golem_test_framework::config::{EnvBasedTestDependencies, TestDependencies};
 use indoc::formatdoc;
@@ -346,7 +346,7 @@ fn text_worker_add(
             WorkerUrn {
                 id: TargetWorkerId {
                     component_id: component_urn.id.clone(),
-                    worker_name: Some(worker_name.clone())
+                    worker_name: Some(WorkerName::from_existing(worker_name.clone()))
                 }
             }
         ),
@@ -445,7 +445,7 @@ fn text_worker_get(
             WorkerUrn {
                 id: TargetWorkerId {
                     component_id: component_urn.id.clone(),
-                    worker_name: Some(worker_name.clone())
+                    worker_name: Some(WorkerName::from_existing(worker_name.clone()))
                 }
             }
         ),
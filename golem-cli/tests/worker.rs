@@ -23,7 +23,7 @@ use golem_cli::model::text::fmt::TextFormat;
 use golem_cli::model::text::worker::WorkerGetView;
 use golem_cli::model::{Format, IdempotencyKey, WorkersMetadataResponseView};
 use golem_client::model::{PublicOplogEntry, UpdateRecord};
-use golem_common::model::TargetWorkerId;
+use golem_common::model::{TargetWorkerId, WorkerName};
 use golem_common::uri::oss::url::{ComponentUrl, WorkerUrl};
 use golem_common::uri::oss::urn::WorkerUrn;
 use golem_test_framework::config::{EnvBasedTestDependencies, TestDependencies};
@@ -295,7 +295,10 @@ fn worker_new_instance(
     ])?;
 
     assert_eq!(worker_urn.id.component_id, component.component_urn.id);
-    assert_eq!(worker_urn.id.worker_name, Some(worker_name));
+    assert_eq!(
+        worker_urn.id.worker_name,
+        Some(WorkerName::from_existing(worker_name))
+    );
     Ok(())
 }
 
@@ -328,7 +331,7 @@ fn worker_ref(
             let urn = WorkerUrn {
                 id: TargetWorkerId {
                     component_id: component.component_urn.id.clone(),
-                    worker_name: Some(worker_name),
+                    worker_name: Some(WorkerName::from_existing(worker_name)),
                 },
             };
 
@@ -903,7 +906,7 @@ fn worker_list(
             &component_ref_key(cfg, ref_kind),
             &component_ref_value(&component, ref_kind),
             &cfg.arg('f', "filter"),
-            format!("name = {}", worker_urn.id.worker_name.unwrap_or_default()).as_str(),
+            format!("name = {}", worker_urn.id.worker_name.unwrap()).as_str(),
             &cfg.arg('f', "filter"),
             "version >= 0",
             "--precise",
@@ -68,6 +68,10 @@ fn generate(yaml_path: PathBuf, out_dir: OsString) {
                 "PublicOplogEntry",
                 "golem_common::model::public_oplog::PublicOplogEntry",
             ),
+            (
+                "PublicWorkerStatusRecord",
+                "golem_common::model::public_worker_status::PublicWorkerStatusRecord",
+            ),
         ],
     )
     .expect("Failed to generate client code from OpenAPI spec.");
@@ -0,0 +1,339 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use golem_common::config::RetryConfig;
+use golem_common::model::WorkerFilter;
+use golem_common::retries::with_retries;
+use std::future::Future;
+use uuid::Uuid;
+
+use crate::api::{WorkerClient, WorkerClientLive};
+use crate::model::{ScanCursor, WorkerMetadata, WorkersMetadataRequest};
+use crate::worker_handle::{ComponentBinding, WorkerHandle, WorkerHandleError};
+use crate::Context;
+
+/// The workers of the component of the binding `C`
+pub struct ComponentWorkers<C: ComponentBinding + Clone> {
+    binding: C,
+    context: Context,
+    client: WorkerClientLive,
+    retry_config: RetryConfig,
+}
+
+impl<C: ComponentBinding + Clone> ComponentWorkers<C> {
+    pub fn new(context: Context, binding: C) -> Self {
+        Self {
+            binding,
+            client: WorkerClientLive {
+                context: context.clone(),
+            },
+            context,
+            retry_config: RetryConfig::default(),
+        }
+    }
+
+    pub fn with_retry_config(self, retry_config: RetryConfig) -> Self {
+        Self {
+            retry_config,
+            ..self
+        }
+    }
+
+    pub fn component_id(&self) -> Uuid {
+        self.binding.component_id()
+    }
+
+    /// Handle of a single worker of the component, using the same retry configuration
+    pub fn worker(&self, worker_name: impl Into<String>) -> WorkerHandle<C> {
+        WorkerHandle::new(self.context.clone(), self.binding.clone(), worker_name)
+            .with_retry_config(self.retry_config.clone())
+    }
+
+    /// Query of all the workers of the component, which can be narrowed down with filters
+    pub fn find(&self) -> WorkerQuery<'_, C> {
+        WorkerQuery {
+            workers: self,
+            pages: WorkerPages::new(),
+        }
+    }
+
+    /// Requests a single page of the workers of the component, retrying the transient failures
+    async fn find_metadata(
+        &self,
+        request: WorkersMetadataRequest,
+    ) -> Result<(Vec<WorkerMetadata>, Option<ScanCursor>), WorkerHandleError> {
+        let response = with_retries(
+            "worker",
+            "find_metadata",
+            Some(self.component_id().to_string()),
+            &self.retry_config,
+            &(self, request),
+            |(workers, request)| {
+                Box::pin(async move {
+                    workers
+                        .client
+                        .find_workers_metadata(&workers.component_id(), request)
+                        .await
+                        .map_err(WorkerHandleError::from)
+                })
+            },
+            WorkerHandleError::is_retriable,
+        )
+        .await?;
+
+        Ok((response.workers, response.cursor))
+    }
+}
+
+const DEFAULT_PAGE_SIZE: u64 = 50;
+
+/// Query of the workers of a component, fetched page by page following the scan cursor
+/// returned by the service
+pub struct WorkerQuery<'a, C: ComponentBinding + Clone> {
+    workers: &'a ComponentWorkers<C>,
+    pages: WorkerPages,
+}
+
+impl<'a, C: ComponentBinding + Clone> WorkerQuery<'a, C> {
+    /// Only returns the workers matching the filter, in addition to the already added filters
+    pub fn filter(mut self, filter: WorkerFilter) -> Self {
+        self.pages.filter = Some(match self.pages.filter {
+            Some(existing) => existing.and(filter),
+            None => filter,
+        });
+        self
+    }
+
+    /// Calculates the status of the workers from their oplogs instead of returning the last
+    /// stored one. Slower, but the status filters match the actual status of the workers.
+    pub fn precise(mut self, precise: bool) -> Self {
+        self.pages.precise = precise;
+        self
+    }
+
+    /// Maximum number of workers requested at once
+    pub fn page_size(mut self, page_size: u64) -> Self {
+        self.pages.page_size = page_size;
+        self
+    }
+
+    /// Fetches the next page of matching workers, or returns `None` once all the workers of
+    /// the component were scanned. A page may be empty even if there are more workers.
+    pub async fn next_page(&mut self) -> Result<Option<Vec<WorkerMetadata>>, WorkerHandleError> {
+        let workers = self.workers;
+        self.pages
+            .next_page(|request| workers.find_metadata(request))
+            .await
+    }
+
+    /// Fetches all the matching workers, following the cursors of the pages
+    pub async fn collect(mut self) -> Result<Vec<WorkerMetadata>, WorkerHandleError> {
+        let mut workers = Vec::new();
+        while let Some(page) = self.next_page().await? {
+            workers.extend(page);
+        }
+        Ok(workers)
+    }
+}
+
+/// The paging state of a worker query
+struct WorkerPages {
+    filter: Option<WorkerFilter>,
+    precise: bool,
+    page_size: u64,
+    cursor: Option<ScanCursor>,
+    finished: bool,
+}
+
+impl WorkerPages {
+    fn new() -> Self {
+        Self {
+            filter: None,
+            precise: false,
+            page_size: DEFAULT_PAGE_SIZE,
+            cursor: None,
+            finished: false,
+        }
+    }
+
+    /// Fetches the page continuing from the cursor of the previous one, until a page is
+    /// returned without a cursor
+    async fn next_page<T, E, F, Fut>(&mut self, fetch: F) -> Result<Option<Vec<T>>, E>
+    where
+        F: FnOnce(WorkersMetadataRequest) -> Fut,
+        Fut: Future<Output = Result<(Vec<T>, Option<ScanCursor>), E>>,
+    {
+        if self.finished {
+            return Ok(None);
+        }
+
+        let (page, cursor) = fetch(WorkersMetadataRequest {
+            filter: self.filter.clone(),
+            cursor: self.cursor.clone(),
+            count: Some(self.page_size),
+            precise: Some(self.precise),
+        })
+        .await?;
+
+        self.finished = cursor.is_none();
+        self.cursor = cursor;
+
+        Ok(Some(page))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use super::WorkerPages;
+    use crate::model::{ScanCursor, WorkersMetadataRequest};
+    use golem_common::model::{StringFilterComparator, WorkerFilter};
+    use std::sync::Mutex;
+
+    /// Serves the pages of `total` workers, using the number of workers returned so far as
+    /// the cursor
+    async fn serve(
+        total: u64,
+        request: WorkersMetadataRequest,
+        requests: &Mutex<Vec<WorkersMetadataRequest>>,
+    ) -> Result<(Vec<u64>, Option<ScanCursor>), String> {
+        requests.lock().unwrap().push(request.clone());
+        let start = request.cursor.map(|cursor| cursor.cursor).unwrap_or(0);
+        let end = (start + request.count.unwrap()).min(total);
+        let cursor = (end < total).then_some(ScanCursor {
+            cursor: end,
+            layer: 0,
+        });
+        Ok(((start..end).collect(), cursor))
+    }
+
+    #[test]
+    async fn pages_follow_the_cursor_until_it_ends() {
+        let requests = Mutex::new(Vec::new());
+        let mut pages = WorkerPages::new();
+        pages.page_size = 10;
+        pages.precise = true;
+
+        let mut workers = Vec::new();
+        while let Some(page) = pages
+            .next_page(|request| serve(25, request, &requests))
+            .await
+            .unwrap()
+        {
+            workers.extend(page);
+        }
+
+        assert_eq!(workers, (0..25).collect::<Vec<_>>());
+        let requests = requests.into_inner().unwrap();
+        assert_eq!(
+            requests
+                .iter()
+                .map(|request| request.cursor.clone().map(|cursor| cursor.cursor))
+                .collect::<Vec<_>>(),
+            vec![None, Some(10), Some(20)]
+        );
+        assert!(requests
+            .iter()
+            .all(|request| request.count == Some(10) && request.precise == Some(true)));
+    }
+
+    #[test]
+    async fn no_more_requests_after_the_last_page() {
+        let requests = Mutex::new(Vec::new());
+        let mut pages = WorkerPages::new();
+
+        let first = pages
+            .next_page(|request| serve(3, request, &requests))
+            .await
+            .unwrap();
+        assert_eq!(first, Some(vec![0, 1, 2]));
+
+        let next = pages
+            .next_page(|request| serve(3, request, &requests))
+            .await
+            .unwrap();
+        assert_eq!(next, None);
+        assert_eq!(requests.into_inner().unwrap().len(), 1);
+    }
+
+    #[test]
+    async fn empty_pages_with_a_cursor_do_not_end_the_query() {
+        let mut pages = WorkerPages::new();
+        pages.filter = Some(WorkerFilter::new_name(
+            StringFilterComparator::Equal,
+            "worker-1".to_string(),
+        ));
+
+        let empty = pages
+            .next_page(|request| async move {
+                assert!(request.filter.is_some());
+                Ok::<_, String>((
+                    Vec::<u64>::new(),
+                    Some(ScanCursor {
+                        cursor: 7,
+                        layer: 1,
+                    }),
+                ))
+            })
+            .await
+            .unwrap();
+        assert_eq!(empty, Some(vec![]));
+
+        let last = pages
+            .next_page(|request| async move {
+                assert_eq!(
+                    request.cursor,
+                    Some(ScanCursor {
+                        cursor: 7,
+                        layer: 1,
+                    })
+                );
+                Ok::<_, String>((vec![1u64], None))
+            })
+            .await
+            .unwrap();
+        assert_eq!(last, Some(vec![1]));
+
+        let failing = pages
+            .next_page(|_| async {
+                Err::<(Vec<u64>, Option<ScanCursor>), _>("unexpected".to_string())
+            })
+            .await
+            .unwrap();
+        assert_eq!(failing, None);
+    }
+
+    #[test]
+    async fn failed_pages_can_be_retried_from_the_same_cursor() {
+        let requests = Mutex::new(Vec::new());
+        let mut pages = WorkerPages::new();
+        pages.page_size = 2;
+
+        pages
+            .next_page(|request| serve(5, request, &requests))
+            .await
+            .unwrap();
+        let failed = pages
+            .next_page(|_| async { Err::<(Vec<u64>, Option<ScanCursor>), _>("failed".to_string()) })
+            .await;
+        assert!(failed.is_err());
+
+        let retried = pages
+            .next_page(|request| serve(5, request, &requests))
+            .await
+            .unwrap();
+        assert_eq!(retried, Some(vec![2, 3]));
+    }
+}
@@ -1 +1,7 @@
 include!(concat!(env!("OUT_DIR"), "/src/lib.rs"));
+
+pub mod component_workers;
+pub mod worker_handle;
+
+#[cfg(test)]
+test_r::enable!();
@@ -0,0 +1,492 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::{Display, Formatter};
+
+use futures_util::StreamExt;
+use golem_common::config::RetryConfig;
+use golem_common::model::public_oplog::{OplogCursor, PublicOplogEntry};
+use golem_common::model::{IdempotencyKey, WorkerEvent};
+use golem_common::retries::{get_delay, with_retries};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::protocol::Message;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::api::{WorkerClient, WorkerClientLive, WorkerError};
+use crate::model::{
+    InvokeParameters, InvokeResult, UpdateWorkerRequest, WorkerMetadata, WorkerUpdateMode,
+};
+use crate::{Context, Error};
+
+/// Binding of a component, generated from the interface it exports
+pub trait ComponentBinding: Send + Sync {
+    fn component_id(&self) -> Uuid;
+}
+
+/// Typed call of a function exported by the component of the binding `C`
+pub trait FunctionCall<C: ComponentBinding> {
+    type Result;
+
+    /// Fully qualified name of the called function
+    fn function_name(&self) -> String;
+
+    fn parameters(&self) -> InvokeParameters;
+
+    fn decode_result(result: InvokeResult) -> Result<Self::Result, String>;
+}
+
+#[derive(Debug)]
+pub enum WorkerHandleError {
+    Client(Error<WorkerError>),
+    InvalidResult(String),
+    Connection(String),
+}
+
+impl WorkerHandleError {
+    // Requests failing because the worker is being moved between executors, or because
+    // the service cannot be reached, succeed once the routing settled
+    pub fn is_retriable(&self) -> bool {
+        match self {
+            WorkerHandleError::Client(Error::Item(WorkerError::Error503(_))) => true,
+            WorkerHandleError::Client(Error::Reqwest(error)) => {
+                error.is_connect() || error.is_timeout()
+            }
+            WorkerHandleError::Client(Error::Unexpected { code, .. }) => {
+                matches!(*code, 502..=504)
+            }
+            WorkerHandleError::Client(_) => false,
+            WorkerHandleError::InvalidResult(_) => false,
+            WorkerHandleError::Connection(_) => true,
+        }
+    }
+}
+
+impl Display for WorkerHandleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WorkerHandleError::Client(Error::Reqwest(error)) => write!(f, "{error}"),
+            WorkerHandleError::Client(Error::ReqwestHeader(error)) => write!(f, "{error}"),
+            WorkerHandleError::Client(Error::Serde(error)) => {
+                write!(f, "Unexpected serialization error: {error}")
+            }
+            WorkerHandleError::Client(Error::Item(error)) => write!(f, "{error:?}"),
+            WorkerHandleError::Client(Error::Unexpected { code, data }) => write!(
+                f,
+                "Unexpected http error. Code: {code}, content: {}",
+                String::from_utf8_lossy(data)
+            ),
+            WorkerHandleError::InvalidResult(error) => {
+                write!(f, "Invalid invocation result: {error}")
+            }
+            WorkerHandleError::Connection(error) => {
+                write!(f, "Worker connection failure: {error}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for WorkerHandleError {}
+
+impl From<Error<WorkerError>> for WorkerHandleError {
+    fn from(value: Error<WorkerError>) -> Self {
+        WorkerHandleError::Client(value)
+    }
+}
+
+/// Handle of a single worker of the component of the binding `C`, retrying requests
+/// interrupted by routing changes in the cluster
+pub struct WorkerHandle<C: ComponentBinding> {
+    binding: C,
+    worker_name: String,
+    context: Context,
+    client: WorkerClientLive,
+    retry_config: RetryConfig,
+}
+
+impl<C: ComponentBinding> WorkerHandle<C> {
+    pub fn new(context: Context, binding: C, worker_name: impl Into<String>) -> Self {
+        Self {
+            binding,
+            worker_name: worker_name.into(),
+            client: WorkerClientLive {
+                context: context.clone(),
+            },
+            context,
+            retry_config: RetryConfig::default(),
+        }
+    }
+
+    pub fn with_retry_config(self, retry_config: RetryConfig) -> Self {
+        Self {
+            retry_config,
+            ..self
+        }
+    }
+
+    pub fn component_id(&self) -> Uuid {
+        self.binding.component_id()
+    }
+
+    pub fn worker_name(&self) -> &str {
+        &self.worker_name
+    }
+
+    /// Invokes a function and awaits its result. Without an explicit idempotency key a fresh one
+    /// is used for all the attempts, so that the function is never invoked more than once
+    pub async fn invoke_and_await<F: FunctionCall<C>>(
+        &self,
+        call: &F,
+        idempotency_key: Option<IdempotencyKey>,
+    ) -> Result<F::Result, WorkerHandleError> {
+        let idempotency_key = idempotency_key.unwrap_or_else(IdempotencyKey::fresh);
+
+        let result = with_retries(
+            "worker",
+            "invoke_and_await",
+            Some(format!("{}/{}", self.worker_name, call.function_name())),
+            &self.retry_config,
+            &(
+                self,
+                call.function_name(),
+                call.parameters(),
+                idempotency_key,
+            ),
+            |(handle, function_name, parameters, idempotency_key)| {
+                Box::pin(async move {
+                    handle
+                        .client
+                        .invoke_and_await_function(
+                            &handle.component_id(),
+                            &handle.worker_name,
+                            Some(idempotency_key.value.as_str()),
+                            function_name,
+                            None,
+                            None,
+                            None,
+                            parameters,
+                        )
+                        .await
+                        .map_err(WorkerHandleError::from)
+                })
+            },
+            WorkerHandleError::is_retriable,
+        )
+        .await?;
+
+        F::decode_result(result).map_err(WorkerHandleError::InvalidResult)
+    }
+
+    /// Enqueues the invocation of a function without awaiting its result
+    pub async fn enqueue<F: FunctionCall<C>>(
+        &self,
+        call: &F,
+        idempotency_key: Option<IdempotencyKey>,
+    ) -> Result<(), WorkerHandleError> {
+        let idempotency_key = idempotency_key.unwrap_or_else(IdempotencyKey::fresh);
+
+        with_retries(
+            "worker",
+            "enqueue",
+            Some(format!("{}/{}", self.worker_name, call.function_name())),
+            &self.retry_config,
+            &(
+                self,
+                call.function_name(),
+                call.parameters(),
+                idempotency_key,
+            ),
+            |(handle, function_name, parameters, idempotency_key)| {
+                Box::pin(async move {
+                    handle
+                        .client
+                        .invoke_function(
+                            &handle.component_id(),
+                            &handle.worker_name,
+                            Some(idempotency_key.value.as_str()),
+                            function_name,
+                            None,
+                            parameters,
+                        )
+                        .await
+                        .map_err(WorkerHandleError::from)
+                })
+            },
+            WorkerHandleError::is_retriable,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn metadata(&self) -> Result<WorkerMetadata, WorkerHandleError> {
+        with_retries(
+            "worker",
+            "get_metadata",
+            Some(self.worker_name.clone()),
+            &self.retry_config,
+            self,
+            |handle| {
+                Box::pin(async move {
+                    handle
+                        .client
+                        .get_worker_metadata(&handle.component_id(), &handle.worker_name)
+                        .await
+                        .map_err(WorkerHandleError::from)
+                })
+            },
+            WorkerHandleError::is_retriable,
+        )
+        .await
+    }
+
+    /// Updates the worker to the target version of its component. The update is applied
+    /// asynchronously, its outcome is visible in the worker's metadata.
+    pub async fn update(
+        &self,
+        target_version: u64,
+        mode: WorkerUpdateMode,
+    ) -> Result<(), WorkerHandleError> {
+        with_retries(
+            "worker",
+            "update",
+            Some(self.worker_name.clone()),
+            &self.retry_config,
+            &(
+                self,
+                UpdateWorkerRequest {
+                    mode,
+                    target_version,
+                },
+            ),
+            |(handle, request)| {
+                Box::pin(async move {
+                    handle
+                        .client
+                        .update_worker(&handle.component_id(), &handle.worker_name, request)
+                        .await
+                        .map_err(WorkerHandleError::from)
+                })
+            },
+            WorkerHandleError::is_retriable,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Gets all the oplog entries of the worker starting from the given index,
+    /// following the cursors of the chunks
+    pub async fn oplog(
+        &self,
+        from: u64,
+    ) -> Result<Vec<(u64, PublicOplogEntry)>, WorkerHandleError> {
+        self.oplog_chunks(from, None).await
+    }
+
+    /// Gets the oplog entries of the worker's last `count` invocations, starting with the
+    /// invocation of the earliest one
+    pub async fn oplog_of_last_invocations(
+        &self,
+        count: u64,
+    ) -> Result<Vec<(u64, PublicOplogEntry)>, WorkerHandleError> {
+        self.oplog_chunks(0, Some(count)).await
+    }
+
+    async fn oplog_chunks(
+        &self,
+        from: u64,
+        invocations_before: Option<u64>,
+    ) -> Result<Vec<(u64, PublicOplogEntry)>, WorkerHandleError> {
+        let mut entries = Vec::new();
+        let mut cursor: Option<OplogCursor> = None;
+
+        loop {
+            let chunk = with_retries(
+                "worker",
+                "get_oplog",
+                Some(self.worker_name.clone()),
+                &self.retry_config,
+                &(self, from, cursor.clone(), invocations_before),
+                |(handle, from, cursor, invocations_before)| {
+                    Box::pin(async move {
+                        handle
+                            .client
+                            .get_oplog(
+                                &handle.component_id(),
+                                &handle.worker_name,
+                                *from,
+                                OPLOG_CHUNK_SIZE,
+                                cursor.as_ref(),
+                                // Only seeking to the invocations on the first chunk
+                                (*invocations_before).filter(|_| cursor.is_none()),
+                            )
+                            .await
+                            .map_err(WorkerHandleError::from)
+                    })
+                },
+                WorkerHandleError::is_retriable,
+            )
+            .await?;
+
+            if chunk.entries.is_empty() {
+                break;
+            }
+
+            entries.extend(
+                chunk
+                    .entries
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, e)| (chunk.first_index_in_chunk + i as u64, e)),
+            );
+            cursor = chunk.next;
+        }
+
+        Ok(entries)
+    }
+
+    /// Subscribes to the events of the worker. The connection is reestablished when lost,
+    /// until the retry configuration gives up
+    pub fn subscribe_events(&self) -> Result<WorkerEventSubscription, WorkerHandleError> {
+        let mut url = self.context.base_url.clone();
+        let ws_schema = if url.scheme() == "http" { "ws" } else { "wss" };
+        url.set_scheme(ws_schema)
+            .map_err(|_| WorkerHandleError::Connection("Can't set schema".to_string()))?;
+        url.path_segments_mut()
+            .map_err(|_| WorkerHandleError::Connection("Can't get path".to_string()))?
+            .push("v1")
+            .push("components")
+            .push(&self.component_id().to_string())
+            .push("workers")
+            .push(&self.worker_name)
+            .push("connect");
+
+        let bearer_token = self.context.bearer_token().map(|token| token.to_string());
+        let retry_config = self.retry_config.clone();
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        let task = tokio::spawn(async move {
+            let mut attempts = 0;
+
+            loop {
+                attempts += 1;
+
+                let result = forward_events(
+                    url.as_str(),
+                    bearer_token.as_deref(),
+                    &sender,
+                    &mut attempts,
+                )
+                .await;
+
+                if sender.is_closed() {
+                    break;
+                }
+
+                let error = match result {
+                    Ok(()) => WorkerHandleError::Connection("Connection closed".to_string()),
+                    Err(error) => error,
+                };
+
+                match get_delay(&retry_config, attempts) {
+                    Some(delay) => {
+                        warn!("Reconnecting to worker events in {delay:?}: {error}");
+                        tokio::time::sleep(delay).await;
+                    }
+                    None => {
+                        let _ = sender.send(Err(error));
+                        break;
+                    }
+                }
+            }
+        });
+
+        Ok(WorkerEventSubscription { receiver, task })
+    }
+}
+
+const OPLOG_CHUNK_SIZE: u64 = 100;
+
+// Forwards the events of a single connection, resetting the attempts once connected
+async fn forward_events(
+    url: &str,
+    bearer_token: Option<&str>,
+    sender: &mpsc::UnboundedSender<Result<WorkerEvent, WorkerHandleError>>,
+    attempts: &mut u64,
+) -> Result<(), WorkerHandleError> {
+    let mut request = url
+        .into_client_request()
+        .map_err(|e| WorkerHandleError::Connection(format!("Can't create request: {e}")))?;
+
+    if let Some(token) = bearer_token {
+        request.headers_mut().insert(
+            "Authorization",
+            format!("Bearer {token}")
+                .parse()
+                .map_err(|e| WorkerHandleError::Connection(format!("Invalid token: {e}")))?,
+        );
+    }
+
+    let (mut ws_stream, _) = connect_async(request)
+        .await
+        .map_err(|e| WorkerHandleError::Connection(e.to_string()))?;
+
+    *attempts = 0;
+
+    while let Some(message) = ws_stream.next().await {
+        let message = message.map_err(|e| WorkerHandleError::Connection(e.to_string()))?;
+
+        let event: Option<serde_json::Result<WorkerEvent>> = match message {
+            Message::Text(str) => Some(serde_json::from_str(&str)),
+            Message::Binary(data) => Some(serde_json::from_slice(&data)),
+            Message::Close(_) => break,
+            _ => None,
+        };
+
+        match event {
+            Some(Ok(event)) => {
+                if sender.send(Ok(event)).is_err() {
+                    break;
+                }
+            }
+            Some(Err(err)) => debug!("Failed to parse worker event: {err}"),
+            None => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Events of a worker, received through a connection reestablished when lost
+pub struct WorkerEventSubscription {
+    receiver: mpsc::UnboundedReceiver<Result<WorkerEvent, WorkerHandleError>>,
+    task: JoinHandle<()>,
+}
+
+impl WorkerEventSubscription {
+    /// Returns the next event, or None once the subscription gave up reconnecting
+    pub async fn next(&mut self) -> Option<Result<WorkerEvent, WorkerHandleError>> {
+        self.receiver.recv().await
+    }
+}
+
+impl Drop for WorkerEventSubscription {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
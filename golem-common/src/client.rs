@@ -13,15 +13,24 @@
 // limitations under the License.
 
 use crate::config::RetryConfig;
+use crate::metrics::grpc_client_pool::{
+    record_grpc_client_pool_connect, record_grpc_client_pool_eviction, record_grpc_client_pool_size,
+};
 use crate::retries::RetryState;
 use dashmap::DashMap;
+use std::collections::HashSet;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::Mutex;
+use tokio::task::JoinSet;
 use tonic::transport::{Channel, Endpoint};
 use tonic::{Code, Status};
+use tonic_health::pb::health_check_response::ServingStatus;
+use tonic_health::pb::health_client::HealthClient;
+use tonic_health::pb::HealthCheckRequest;
+use tracing::debug;
 
 #[derive(Clone)]
 pub struct GrpcClient<T: Clone> {
@@ -84,8 +93,8 @@ impl<T: Clone> GrpcClient<T> {
                 let endpoint = Endpoint::new(self.endpoint.clone())?
                     .connect_timeout(self.config.connect_timeout);
                 let channel = endpoint.connect_lazy();
-                let client = (self.client_factory)(channel);
-                let connection = GrpcClientConnection { client };
+                let client = (self.client_factory)(channel.clone());
+                let connection = GrpcClientConnection { client, channel };
                 *entry = Some(connection.clone());
                 Ok(connection)
             }
@@ -93,8 +102,13 @@ impl<T: Clone> GrpcClient<T> {
     }
 }
 
+/// Pool of gRPC clients keyed by target endpoint, sharing one channel per target.
+///
+/// Connections are evicted when the target becomes unavailable, fails a health check or
+/// is no longer part of the set of known targets (see [MultiTargetGrpcClient::retain_endpoints]).
 #[derive(Clone)]
 pub struct MultiTargetGrpcClient<T: Clone> {
+    name: &'static str,
     config: GrpcClientConfig,
     clients: Arc<DashMap<http_02::Uri, GrpcClientConnection<T>>>,
     client_factory: Arc<dyn Fn(Channel) -> T + Send + Sync>,
@@ -102,10 +116,12 @@ pub struct MultiTargetGrpcClient<T: Clone> {
 
 impl<T: Clone> MultiTargetGrpcClient<T> {
     pub fn new(
+        name: &'static str,
         client_factory: impl Fn(Channel) -> T + Send + Sync + 'static,
         config: GrpcClientConfig,
     ) -> Self {
         Self {
+            name,
             config,
             clients: Arc::new(DashMap::new()),
             client_factory: Arc::new(client_factory),
@@ -127,7 +143,7 @@ impl<T: Clone> MultiTargetGrpcClient<T> {
                 Ok(result) => break Ok(result),
                 Err(e) => {
                     if requires_reconnect(&e) {
-                        self.clients.remove(&endpoint);
+                        self.evict(&endpoint, "unavailable");
                         if !retries.failed_attempt().await {
                             break Err(e);
                         } else {
@@ -141,10 +157,53 @@ impl<T: Clone> MultiTargetGrpcClient<T> {
         }
     }
 
+    /// Number of targets currently having a pooled connection
+    pub fn size(&self) -> usize {
+        self.clients.len()
+    }
+
+    /// Drops the pooled connections of every target not contained in `endpoints`,
+    /// typically called when a new routing table has been fetched.
+    pub fn retain_endpoints(&self, endpoints: &HashSet<http_02::Uri>) {
+        self.clients.retain(|endpoint, _| {
+            let keep = endpoints.contains(endpoint);
+            if !keep {
+                debug!(pool = self.name, endpoint = %endpoint, "Evicting gRPC client of removed target");
+                record_grpc_client_pool_eviction(self.name, "removed");
+            }
+            keep
+        });
+        record_grpc_client_pool_size(self.name, self.clients.len());
+    }
+
+    /// Checks the health of every pooled target using the standard gRPC health service,
+    /// and evicts the connections of those not responding as serving within `timeout`.
+    pub async fn evict_unhealthy(&self, timeout: Duration) {
+        let mut checks = JoinSet::new();
+        for entry in self.clients.iter() {
+            let endpoint = entry.key().clone();
+            let channel = entry.value().channel.clone();
+            checks.spawn(async move {
+                let healthy = is_serving(channel, timeout).await;
+                (endpoint, healthy)
+            });
+        }
+
+        while let Some(result) = checks.join_next().await {
+            if let Ok((endpoint, false)) = result {
+                self.evict(&endpoint, "health_check");
+            }
+        }
+    }
+
     fn get(
         &self,
         endpoint: http_02::Uri,
     ) -> Result<GrpcClientConnection<T>, tonic::transport::Error> {
+        if let Some(entry) = self.clients.get(&endpoint) {
+            return Ok(entry.clone());
+        }
+
         let connect_timeout = self.config.connect_timeout;
         let entry = self
             .clients
@@ -152,16 +211,28 @@ impl<T: Clone> MultiTargetGrpcClient<T> {
             .or_try_insert_with(move || {
                 let endpoint = Endpoint::new(endpoint)?.connect_timeout(connect_timeout);
                 let channel = endpoint.connect_lazy();
-                let client = (self.client_factory)(channel);
-                Ok(GrpcClientConnection { client })
-            })?;
-        Ok(entry.clone())
+                let client = (self.client_factory)(channel.clone());
+                record_grpc_client_pool_connect(self.name);
+                Ok(GrpcClientConnection { client, channel })
+            })?
+            .clone();
+        record_grpc_client_pool_size(self.name, self.clients.len());
+        Ok(entry)
+    }
+
+    fn evict(&self, endpoint: &http_02::Uri, reason: &'static str) {
+        if self.clients.remove(endpoint).is_some() {
+            debug!(pool = self.name, endpoint = %endpoint, reason, "Evicting gRPC client");
+            record_grpc_client_pool_eviction(self.name, reason);
+            record_grpc_client_pool_size(self.name, self.clients.len());
+        }
     }
 }
 
 #[derive(Clone)]
 pub struct GrpcClientConnection<T: Clone> {
     client: T,
+    channel: Channel,
 }
 
 #[derive(Debug, Clone)]
@@ -182,3 +253,40 @@ impl Default for GrpcClientConfig {
 fn requires_reconnect(e: &Status) -> bool {
     e.code() == Code::Unavailable
 }
+
+async fn is_serving(channel: Channel, timeout: Duration) -> bool {
+    let mut client = HealthClient::new(channel);
+    let request = HealthCheckRequest {
+        service: "".to_string(),
+    };
+    match tokio::time::timeout(timeout, client.check(request)).await {
+        Ok(Ok(response)) => response.into_inner().status == ServingStatus::Serving as i32,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::collections::HashSet;
+
+    use crate::client::{GrpcClientConfig, MultiTargetGrpcClient};
+
+    #[test]
+    async fn retain_endpoints_evicts_removed_targets() {
+        let pool =
+            MultiTargetGrpcClient::new("test", |channel| channel, GrpcClientConfig::default());
+        let endpoint1: http_02::Uri = "http://pod1:9000/".parse().unwrap();
+        let endpoint2: http_02::Uri = "http://pod2:9000/".parse().unwrap();
+
+        pool.get(endpoint1.clone()).unwrap();
+        pool.get(endpoint2.clone()).unwrap();
+        pool.get(endpoint1.clone()).unwrap();
+        assert_eq!(pool.size(), 2);
+
+        pool.retain_endpoints(&HashSet::from([endpoint2.clone()]));
+        assert_eq!(pool.size(), 1);
+        assert!(pool.clients.contains_key(&endpoint2));
+    }
+}
@@ -17,7 +17,9 @@ use golem_api_grpc::proto::golem::common;
 use golem_api_grpc::proto::golem::component;
 use golem_api_grpc::proto::golem::worker;
 
-use crate::model::{AccountId, ComponentId, IdempotencyKey, PromiseId, TargetWorkerId, WorkerId};
+use crate::model::{
+    AccountId, ComponentId, IdempotencyKey, ProjectId, PromiseId, TargetWorkerId, WorkerId,
+};
 
 pub fn proto_component_id_string(component_id: &Option<component::ComponentId>) -> Option<String> {
     component_id
@@ -54,6 +56,13 @@ pub fn proto_account_id_string(account_id: &Option<common::AccountId>) -> Option
         .map(|v| Into::<AccountId>::into(v).to_string())
 }
 
+pub fn proto_project_id_string(project_id: &Option<common::ProjectId>) -> Option<String> {
+    project_id
+        .clone()
+        .and_then(|v| TryInto::<ProjectId>::try_into(v).ok())
+        .map(|v| v.to_string())
+}
+
 pub fn proto_promise_id_string(promise_id: &Option<worker::PromiseId>) -> Option<String> {
     promise_id
         .clone()
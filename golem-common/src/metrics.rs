@@ -182,6 +182,50 @@ pub mod redis {
     }
 }
 
+pub mod grpc_client_pool {
+    use lazy_static::lazy_static;
+    use prometheus::*;
+
+    lazy_static! {
+        static ref GRPC_CLIENT_POOL_SIZE: GaugeVec = register_gauge_vec!(
+            "grpc_client_pool_size",
+            "Number of targets with a pooled gRPC connection",
+            &["pool"]
+        )
+        .unwrap();
+        static ref GRPC_CLIENT_POOL_CONNECT_TOTAL: CounterVec = register_counter_vec!(
+            "grpc_client_pool_connect_total",
+            "Number of gRPC connections created by the pool",
+            &["pool"]
+        )
+        .unwrap();
+        static ref GRPC_CLIENT_POOL_EVICTION_TOTAL: CounterVec = register_counter_vec!(
+            "grpc_client_pool_eviction_total",
+            "Number of gRPC connections evicted from the pool",
+            &["pool", "reason"]
+        )
+        .unwrap();
+    }
+
+    pub fn record_grpc_client_pool_size(pool_name: &'static str, size: usize) {
+        GRPC_CLIENT_POOL_SIZE
+            .with_label_values(&[pool_name])
+            .set(size as f64);
+    }
+
+    pub fn record_grpc_client_pool_connect(pool_name: &'static str) {
+        GRPC_CLIENT_POOL_CONNECT_TOTAL
+            .with_label_values(&[pool_name])
+            .inc();
+    }
+
+    pub fn record_grpc_client_pool_eviction(pool_name: &'static str, reason: &'static str) {
+        GRPC_CLIENT_POOL_EVICTION_TOTAL
+            .with_label_values(&[pool_name, reason])
+            .inc();
+    }
+}
+
 pub mod caching {
     use lazy_static::lazy_static;
     use prometheus::*;
@@ -0,0 +1,335 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use uuid::Uuid;
+
+use crate::model::{ComponentId, TargetWorkerId, WorkerId, WorkerName};
+
+/// Actions that can be performed on workers, checked against the scope of the caller's token
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum WorkerAction {
+    /// Creating workers and invoking their exported functions, including completing promises
+    Invoke,
+    /// Reading the metadata and status of workers
+    ReadMetadata,
+    /// Reading the oplog of workers
+    ReadOplog,
+    /// Updating, interrupting and resuming workers
+    Update,
+    Delete,
+    /// Connecting to the event stream of workers
+    Connect,
+}
+
+impl WorkerAction {
+    pub const ALL: [WorkerAction; 6] = [
+        WorkerAction::Invoke,
+        WorkerAction::ReadMetadata,
+        WorkerAction::ReadOplog,
+        WorkerAction::Update,
+        WorkerAction::Delete,
+        WorkerAction::Connect,
+    ];
+}
+
+impl Display for WorkerAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WorkerAction::Invoke => write!(f, "invoke"),
+            WorkerAction::ReadMetadata => write!(f, "read-metadata"),
+            WorkerAction::ReadOplog => write!(f, "read-oplog"),
+            WorkerAction::Update => write!(f, "update"),
+            WorkerAction::Delete => write!(f, "delete"),
+            WorkerAction::Connect => write!(f, "connect"),
+        }
+    }
+}
+
+impl FromStr for WorkerAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        WorkerAction::ALL
+            .into_iter()
+            .find(|action| action.to_string() == s)
+            .ok_or_else(|| format!("Unknown worker action: {s}"))
+    }
+}
+
+/// The set of workers a scope grant applies to
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ScopeTarget {
+    All,
+    Component(ComponentId),
+    Worker(WorkerId),
+}
+
+impl ScopeTarget {
+    fn matches_worker(&self, worker_id: &WorkerId) -> bool {
+        match self {
+            ScopeTarget::All => true,
+            ScopeTarget::Component(component_id) => component_id == &worker_id.component_id,
+            ScopeTarget::Worker(target) => target == worker_id,
+        }
+    }
+
+    fn matches_component(&self, component_id: &ComponentId) -> bool {
+        match self {
+            ScopeTarget::All => true,
+            ScopeTarget::Component(target) => target == component_id,
+            ScopeTarget::Worker(_) => false,
+        }
+    }
+}
+
+impl Display for ScopeTarget {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScopeTarget::All => write!(f, "*"),
+            ScopeTarget::Component(component_id) => write!(f, "component/{component_id}"),
+            ScopeTarget::Worker(worker_id) => write!(
+                f,
+                "worker/{}/{}",
+                worker_id.component_id, worker_id.worker_name
+            ),
+        }
+    }
+}
+
+impl FromStr for ScopeTarget {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_component_id = |s: &str| {
+            Uuid::from_str(s)
+                .map(ComponentId)
+                .map_err(|_| format!("Invalid component id in scope target: {s}"))
+        };
+
+        if s == "*" {
+            Ok(ScopeTarget::All)
+        } else if let Some(component_id) = s.strip_prefix("component/") {
+            Ok(ScopeTarget::Component(parse_component_id(component_id)?))
+        } else if let Some(worker) = s.strip_prefix("worker/") {
+            let (component_id, worker_name) = worker
+                .split_once('/')
+                .ok_or_else(|| format!("Invalid worker scope target: {s}"))?;
+            Ok(ScopeTarget::Worker(WorkerId {
+                component_id: parse_component_id(component_id)?,
+                worker_name: WorkerName::from_existing(worker_name),
+            }))
+        } else {
+            Err(format!(
+                "Invalid scope target: {s} - expected *, component/<component-id> or worker/<component-id>/<worker-name>"
+            ))
+        }
+    }
+}
+
+/// Permits a single action on a set of workers
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ScopeGrant {
+    pub action: WorkerAction,
+    pub target: ScopeTarget,
+}
+
+impl Display for ScopeGrant {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.action, self.target)
+    }
+}
+
+/// The permissions of a token on workers.
+///
+/// The string representation is a space separated list of `<action>:<target>` grants, where the
+/// action can be `*` to grant every action on the target, for example
+/// `invoke:component/<component-id> read-metadata:*`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TokenScope {
+    pub grants: Vec<ScopeGrant>,
+}
+
+impl TokenScope {
+    /// Scope permitting every action on every worker
+    pub fn unrestricted() -> Self {
+        Self::for_target(&WorkerAction::ALL, ScopeTarget::All)
+    }
+
+    pub fn for_target(actions: &[WorkerAction], target: ScopeTarget) -> Self {
+        Self {
+            grants: actions
+                .iter()
+                .map(|action| ScopeGrant {
+                    action: *action,
+                    target: target.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn allows_worker(&self, action: WorkerAction, worker_id: &WorkerId) -> bool {
+        self.grants
+            .iter()
+            .any(|grant| grant.action == action && grant.target.matches_worker(worker_id))
+    }
+
+    /// Checks if the action is permitted on every worker of the component
+    pub fn allows_component(&self, action: WorkerAction, component_id: &ComponentId) -> bool {
+        self.grants
+            .iter()
+            .any(|grant| grant.action == action && grant.target.matches_component(component_id))
+    }
+
+    /// Checks if the action is permitted on every worker, which is required for accessing
+    /// state shared by all the workers of the account
+    pub fn allows_all_workers(&self, action: WorkerAction) -> bool {
+        self.grants
+            .iter()
+            .any(|grant| grant.action == action && grant.target == ScopeTarget::All)
+    }
+
+    /// Targets without a worker name can end up on any worker of the component, so they
+    /// require a grant on the whole component
+    pub fn allows_target(&self, action: WorkerAction, target_worker_id: &TargetWorkerId) -> bool {
+        match &target_worker_id.worker_name {
+            Some(worker_name) => self.allows_worker(
+                action,
+                &WorkerId {
+                    component_id: target_worker_id.component_id.clone(),
+                    worker_name: worker_name.clone(),
+                },
+            ),
+            None => self.allows_component(action, &target_worker_id.component_id),
+        }
+    }
+}
+
+impl Display for TokenScope {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let grants = self
+            .grants
+            .iter()
+            .map(|grant| grant.to_string())
+            .collect::<Vec<_>>();
+        write!(f, "{}", grants.join(" "))
+    }
+}
+
+impl FromStr for TokenScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut grants = Vec::new();
+        for grant in s.split_whitespace() {
+            let (actions, target) = grant.split_once(':').ok_or_else(|| {
+                format!("Invalid scope grant: {grant} - expected <action>:<target>")
+            })?;
+            let target = ScopeTarget::from_str(target)?;
+            if actions == "*" {
+                grants.extend(TokenScope::for_target(&WorkerAction::ALL, target).grants);
+            } else {
+                grants.push(ScopeGrant {
+                    action: WorkerAction::from_str(actions)?,
+                    target,
+                });
+            }
+        }
+        Ok(Self { grants })
+    }
+}
+
+/// Implemented by the authentication contexts of the services, exposing the scope of the
+/// token a request was made with
+pub trait HasTokenScope {
+    fn token_scope(&self) -> TokenScope;
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::str::FromStr;
+
+    use crate::model::auth::{ScopeTarget, TokenScope, WorkerAction};
+    use crate::model::{ComponentId, TargetWorkerId, WorkerId, WorkerName};
+
+    #[test]
+    fn invoke_only_scope() {
+        let component_id = ComponentId::new_v4();
+        let scope = TokenScope::for_target(
+            &[WorkerAction::Invoke],
+            ScopeTarget::Component(component_id.clone()),
+        );
+        let worker_id = WorkerId {
+            component_id: component_id.clone(),
+            worker_name: WorkerName::from_existing("worker1"),
+        };
+
+        assert!(scope.allows_worker(WorkerAction::Invoke, &worker_id));
+        assert!(scope.allows_target(
+            WorkerAction::Invoke,
+            &TargetWorkerId {
+                component_id: component_id.clone(),
+                worker_name: None,
+            }
+        ));
+        assert!(!scope.allows_worker(WorkerAction::ReadOplog, &worker_id));
+        assert!(!scope.allows_worker(WorkerAction::Delete, &worker_id));
+        assert!(!scope.allows_worker(
+            WorkerAction::Invoke,
+            &WorkerId {
+                component_id: ComponentId::new_v4(),
+                worker_name: WorkerName::from_existing("worker1"),
+            }
+        ));
+    }
+
+    #[test]
+    fn worker_grants_do_not_cover_the_component() {
+        let worker_id = WorkerId {
+            component_id: ComponentId::new_v4(),
+            worker_name: WorkerName::from_existing("worker1"),
+        };
+        let scope = TokenScope::for_target(
+            &[WorkerAction::ReadMetadata],
+            ScopeTarget::Worker(worker_id.clone()),
+        );
+
+        assert!(scope.allows_worker(WorkerAction::ReadMetadata, &worker_id));
+        assert!(!scope.allows_component(WorkerAction::ReadMetadata, &worker_id.component_id));
+        assert!(!scope.allows_all_workers(WorkerAction::ReadMetadata));
+        assert!(TokenScope::unrestricted().allows_all_workers(WorkerAction::ReadMetadata));
+    }
+
+    #[test]
+    fn string_roundtrip() {
+        let component_id = ComponentId::new_v4();
+        let scope = TokenScope::from_str(&format!(
+            "invoke:component/{component_id} connect:worker/{component_id}/worker1 read-metadata:*"
+        ))
+        .unwrap();
+
+        assert_eq!(scope.grants.len(), 3);
+        assert_eq!(TokenScope::from_str(&scope.to_string()).unwrap(), scope);
+        assert_eq!(
+            TokenScope::from_str("*:*").unwrap(),
+            TokenScope::unrestricted()
+        );
+        assert!(TokenScope::from_str("invoke").is_err());
+        assert!(TokenScope::from_str("destroy:*").is_err());
+    }
+}
@@ -0,0 +1,507 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use golem_wasm_ast::analysis::{AnalysedExport, AnalysedFunction, AnalysedType};
+use poem_openapi::{Enum, Object};
+use serde::{Deserialize, Serialize};
+
+use crate::model::component_metadata::ComponentMetadata;
+
+/// Differences between the exported functions and the imported interfaces of a component version
+/// and its previous version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct ComponentCompatibilityReport {
+    /// The version the component was compared to
+    pub previous_version: u64,
+    pub changes: Vec<ExportChange>,
+    pub import_changes: Vec<ImportChange>,
+    /// Whether any of the changes makes automatic updates of existing workers fail
+    pub breaking: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct ExportChange {
+    /// Fully qualified name of the exported function
+    pub function_name: String,
+    pub kind: ExportChangeKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum ExportChangeKind {
+    Added,
+    Removed,
+    SignatureChanged,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct ImportChange {
+    /// Name of the imported interface
+    pub interface_name: String,
+    pub kind: ImportChangeKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum ImportChangeKind {
+    Added,
+    Removed,
+}
+
+impl ImportChangeKind {
+    /// Host calls recorded through a removed interface cannot be replayed by the new version
+    pub fn is_breaking(&self) -> bool {
+        match self {
+            ImportChangeKind::Added => false,
+            ImportChangeKind::Removed => true,
+        }
+    }
+}
+
+impl ExportChangeKind {
+    /// Removed functions and changed signatures break the replay of invocations recorded
+    /// with the previous version
+    pub fn is_breaking(&self) -> bool {
+        match self {
+            ExportChangeKind::Added => false,
+            ExportChangeKind::Removed | ExportChangeKind::SignatureChanged => true,
+        }
+    }
+}
+
+impl ComponentCompatibilityReport {
+    pub fn compare(
+        previous_version: u64,
+        previous: &ComponentMetadata,
+        current: &ComponentMetadata,
+    ) -> Self {
+        let previous_functions = exported_functions(&previous.exports);
+        let current_functions = exported_functions(&current.exports);
+
+        let mut changes = Vec::new();
+        for (name, previous_function) in &previous_functions {
+            match current_functions.get(name) {
+                None => changes.push(ExportChange {
+                    function_name: name.clone(),
+                    kind: ExportChangeKind::Removed,
+                }),
+                Some(current_function) => {
+                    if signature(previous_function) != signature(current_function) {
+                        changes.push(ExportChange {
+                            function_name: name.clone(),
+                            kind: ExportChangeKind::SignatureChanged,
+                        })
+                    }
+                }
+            }
+        }
+        for name in current_functions.keys() {
+            if !previous_functions.contains_key(name) {
+                changes.push(ExportChange {
+                    function_name: name.clone(),
+                    kind: ExportChangeKind::Added,
+                })
+            }
+        }
+        changes.sort_by(|a, b| a.function_name.cmp(&b.function_name));
+
+        let import_changes = import_changes(previous, current);
+
+        let breaking = changes.iter().any(|change| change.kind.is_breaking())
+            || import_changes
+                .iter()
+                .any(|change| change.kind.is_breaking());
+        Self {
+            previous_version,
+            changes,
+            import_changes,
+            breaking,
+        }
+    }
+}
+
+/// Versions uploaded before the imports were recorded in the metadata have no imports, so their
+/// imports are not compared
+fn import_changes(previous: &ComponentMetadata, current: &ComponentMetadata) -> Vec<ImportChange> {
+    if previous.imports.is_empty() {
+        return vec![];
+    }
+
+    let previous_imports = previous.imports.iter().collect::<BTreeSet<_>>();
+    let current_imports = current.imports.iter().collect::<BTreeSet<_>>();
+
+    let removed = previous_imports
+        .difference(&current_imports)
+        .map(|name| ImportChange {
+            interface_name: name.to_string(),
+            kind: ImportChangeKind::Removed,
+        });
+    let added = current_imports
+        .difference(&previous_imports)
+        .map(|name| ImportChange {
+            interface_name: name.to_string(),
+            kind: ImportChangeKind::Added,
+        });
+
+    let mut changes = removed.chain(added).collect::<Vec<_>>();
+    changes.sort_by(|a, b| a.interface_name.cmp(&b.interface_name));
+    changes
+}
+
+fn exported_functions(exports: &[AnalysedExport]) -> BTreeMap<String, &AnalysedFunction> {
+    let mut result = BTreeMap::new();
+    for export in exports {
+        match export {
+            AnalysedExport::Instance(instance) => {
+                for function in &instance.functions {
+                    result.insert(format!("{}.{{{}}}", instance.name, function.name), function);
+                }
+            }
+            AnalysedExport::Function(function) => {
+                result.insert(function.name.clone(), function);
+            }
+        }
+    }
+    result
+}
+
+/// Parameter names are not part of the signature, as invocations pass the parameters by position
+fn signature(function: &AnalysedFunction) -> (Vec<&AnalysedType>, Vec<&AnalysedType>) {
+    (
+        function.parameters.iter().map(|p| &p.typ).collect(),
+        function.results.iter().map(|r| &r.typ).collect(),
+    )
+}
+
+impl From<golem_api_grpc::proto::golem::component::ComponentCompatibilityReport>
+    for ComponentCompatibilityReport
+{
+    fn from(value: golem_api_grpc::proto::golem::component::ComponentCompatibilityReport) -> Self {
+        Self {
+            previous_version: value.previous_version,
+            changes: value.changes.into_iter().map(ExportChange::from).collect(),
+            import_changes: value
+                .import_changes
+                .into_iter()
+                .map(ImportChange::from)
+                .collect(),
+            breaking: value.breaking,
+        }
+    }
+}
+
+impl From<ComponentCompatibilityReport>
+    for golem_api_grpc::proto::golem::component::ComponentCompatibilityReport
+{
+    fn from(value: ComponentCompatibilityReport) -> Self {
+        Self {
+            previous_version: value.previous_version,
+            changes: value
+                .changes
+                .into_iter()
+                .map(|change| change.into())
+                .collect(),
+            import_changes: value
+                .import_changes
+                .into_iter()
+                .map(|change| change.into())
+                .collect(),
+            breaking: value.breaking,
+        }
+    }
+}
+
+impl From<golem_api_grpc::proto::golem::component::ExportChange> for ExportChange {
+    fn from(value: golem_api_grpc::proto::golem::component::ExportChange) -> Self {
+        let kind = value.kind().into();
+        Self {
+            function_name: value.function_name,
+            kind,
+        }
+    }
+}
+
+impl From<ExportChange> for golem_api_grpc::proto::golem::component::ExportChange {
+    fn from(value: ExportChange) -> Self {
+        let kind: golem_api_grpc::proto::golem::component::ExportChangeKind = value.kind.into();
+        Self {
+            function_name: value.function_name,
+            kind: kind as i32,
+        }
+    }
+}
+
+impl From<golem_api_grpc::proto::golem::component::ExportChangeKind> for ExportChangeKind {
+    fn from(value: golem_api_grpc::proto::golem::component::ExportChangeKind) -> Self {
+        match value {
+            golem_api_grpc::proto::golem::component::ExportChangeKind::Added => {
+                ExportChangeKind::Added
+            }
+            golem_api_grpc::proto::golem::component::ExportChangeKind::Removed => {
+                ExportChangeKind::Removed
+            }
+            golem_api_grpc::proto::golem::component::ExportChangeKind::SignatureChanged => {
+                ExportChangeKind::SignatureChanged
+            }
+        }
+    }
+}
+
+impl From<ExportChangeKind> for golem_api_grpc::proto::golem::component::ExportChangeKind {
+    fn from(value: ExportChangeKind) -> Self {
+        match value {
+            ExportChangeKind::Added => {
+                golem_api_grpc::proto::golem::component::ExportChangeKind::Added
+            }
+            ExportChangeKind::Removed => {
+                golem_api_grpc::proto::golem::component::ExportChangeKind::Removed
+            }
+            ExportChangeKind::SignatureChanged => {
+                golem_api_grpc::proto::golem::component::ExportChangeKind::SignatureChanged
+            }
+        }
+    }
+}
+
+impl From<golem_api_grpc::proto::golem::component::ImportChange> for ImportChange {
+    fn from(value: golem_api_grpc::proto::golem::component::ImportChange) -> Self {
+        let kind = value.kind().into();
+        Self {
+            interface_name: value.interface_name,
+            kind,
+        }
+    }
+}
+
+impl From<ImportChange> for golem_api_grpc::proto::golem::component::ImportChange {
+    fn from(value: ImportChange) -> Self {
+        let kind: golem_api_grpc::proto::golem::component::ImportChangeKind = value.kind.into();
+        Self {
+            interface_name: value.interface_name,
+            kind: kind as i32,
+        }
+    }
+}
+
+impl From<golem_api_grpc::proto::golem::component::ImportChangeKind> for ImportChangeKind {
+    fn from(value: golem_api_grpc::proto::golem::component::ImportChangeKind) -> Self {
+        match value {
+            golem_api_grpc::proto::golem::component::ImportChangeKind::ImportAdded => {
+                ImportChangeKind::Added
+            }
+            golem_api_grpc::proto::golem::component::ImportChangeKind::ImportRemoved => {
+                ImportChangeKind::Removed
+            }
+        }
+    }
+}
+
+impl From<ImportChangeKind> for golem_api_grpc::proto::golem::component::ImportChangeKind {
+    fn from(value: ImportChangeKind) -> Self {
+        match value {
+            ImportChangeKind::Added => {
+                golem_api_grpc::proto::golem::component::ImportChangeKind::ImportAdded
+            }
+            ImportChangeKind::Removed => {
+                golem_api_grpc::proto::golem::component::ImportChangeKind::ImportRemoved
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::collections::BTreeMap;
+
+    use golem_wasm_ast::analysis::analysed_type::{str, u32, u64};
+    use golem_wasm_ast::analysis::{
+        AnalysedExport, AnalysedFunction, AnalysedFunctionParameter, AnalysedFunctionResult,
+        AnalysedInstance, AnalysedType,
+    };
+
+    use crate::model::component_compatibility::{
+        ComponentCompatibilityReport, ExportChange, ExportChangeKind, ImportChange,
+        ImportChangeKind,
+    };
+    use crate::model::component_metadata::ComponentMetadata;
+
+    fn function(name: &str, parameter: AnalysedType, result: AnalysedType) -> AnalysedFunction {
+        AnalysedFunction {
+            name: name.to_string(),
+            parameters: vec![AnalysedFunctionParameter {
+                name: "input".to_string(),
+                typ: parameter,
+            }],
+            results: vec![AnalysedFunctionResult {
+                name: None,
+                typ: result,
+            }],
+        }
+    }
+
+    fn metadata(functions: Vec<AnalysedFunction>) -> ComponentMetadata {
+        ComponentMetadata {
+            exports: vec![AnalysedExport::Instance(AnalysedInstance {
+                name: "golem:it/api".to_string(),
+                functions,
+            })],
+            producers: vec![],
+            memories: vec![],
+            labels: BTreeMap::new(),
+            rpc_dependencies: vec![],
+            imports: vec![
+                "wasi:io/streams@0.2.0".to_string(),
+                "golem:api/host@0.2.0".to_string(),
+            ],
+            plugs: vec![],
+        }
+    }
+
+    fn with_imports(metadata: ComponentMetadata, imports: &[&str]) -> ComponentMetadata {
+        ComponentMetadata {
+            imports: imports.iter().map(|name| name.to_string()).collect(),
+            ..metadata
+        }
+    }
+
+    #[test]
+    fn identical_exports_are_compatible() {
+        let metadata = metadata(vec![function("add", u64(), u64())]);
+        let report = ComponentCompatibilityReport::compare(0, &metadata, &metadata);
+
+        assert!(report.changes.is_empty());
+        assert!(!report.breaking);
+    }
+
+    #[test]
+    fn added_functions_are_not_breaking() {
+        let previous = metadata(vec![function("add", u64(), u64())]);
+        let current = metadata(vec![
+            function("add", u64(), u64()),
+            function("get", str(), u64()),
+        ]);
+        let report = ComponentCompatibilityReport::compare(1, &previous, &current);
+
+        assert_eq!(
+            report.changes,
+            vec![ExportChange {
+                function_name: "golem:it/api.{get}".to_string(),
+                kind: ExportChangeKind::Added,
+            }]
+        );
+        assert!(!report.breaking);
+    }
+
+    #[test]
+    fn removed_and_changed_functions_are_breaking() {
+        let previous = metadata(vec![
+            function("add", u64(), u64()),
+            function("get", str(), u64()),
+        ]);
+        let current = metadata(vec![function("add", u32(), u64())]);
+        let report = ComponentCompatibilityReport::compare(2, &previous, &current);
+
+        assert_eq!(
+            report.changes,
+            vec![
+                ExportChange {
+                    function_name: "golem:it/api.{add}".to_string(),
+                    kind: ExportChangeKind::SignatureChanged,
+                },
+                ExportChange {
+                    function_name: "golem:it/api.{get}".to_string(),
+                    kind: ExportChangeKind::Removed,
+                },
+            ]
+        );
+        assert!(report.breaking);
+
+        let proto: golem_api_grpc::proto::golem::component::ComponentCompatibilityReport =
+            report.clone().into();
+        assert_eq!(ComponentCompatibilityReport::from(proto), report);
+    }
+
+    #[test]
+    fn removed_imports_are_breaking() {
+        let previous = with_imports(
+            metadata(vec![function("add", u64(), u64())]),
+            &["golem:api/host@0.2.0", "wasi:http/outgoing-handler@0.2.0"],
+        );
+        let current = with_imports(
+            metadata(vec![function("add", u64(), u64())]),
+            &["golem:api/host@0.2.0", "wasi:keyvalue/eventual@0.1.0"],
+        );
+        let report = ComponentCompatibilityReport::compare(3, &previous, &current);
+
+        assert!(report.changes.is_empty());
+        assert_eq!(
+            report.import_changes,
+            vec![
+                ImportChange {
+                    interface_name: "wasi:http/outgoing-handler@0.2.0".to_string(),
+                    kind: ImportChangeKind::Removed,
+                },
+                ImportChange {
+                    interface_name: "wasi:keyvalue/eventual@0.1.0".to_string(),
+                    kind: ImportChangeKind::Added,
+                },
+            ]
+        );
+        assert!(report.breaking);
+
+        let proto: golem_api_grpc::proto::golem::component::ComponentCompatibilityReport =
+            report.clone().into();
+        assert_eq!(ComponentCompatibilityReport::from(proto), report);
+    }
+
+    #[test]
+    fn added_imports_are_not_breaking() {
+        let previous = with_imports(
+            metadata(vec![function("add", u64(), u64())]),
+            &["golem:api/host@0.2.0"],
+        );
+        let current = with_imports(
+            metadata(vec![function("add", u64(), u64())]),
+            &["golem:api/host@0.2.0", "wasi:clocks/wall-clock@0.2.0"],
+        );
+        let report = ComponentCompatibilityReport::compare(4, &previous, &current);
+
+        assert_eq!(
+            report.import_changes,
+            vec![ImportChange {
+                interface_name: "wasi:clocks/wall-clock@0.2.0".to_string(),
+                kind: ImportChangeKind::Added,
+            }]
+        );
+        assert!(!report.breaking);
+    }
+
+    #[test]
+    fn imports_are_not_compared_to_versions_without_recorded_imports() {
+        let previous = with_imports(metadata(vec![function("add", u64(), u64())]), &[]);
+        let current = metadata(vec![function("add", u64(), u64())]);
+        let report = ComponentCompatibilityReport::compare(5, &previous, &current);
+
+        assert!(report.import_changes.is_empty());
+        assert!(!report.breaking);
+    }
+}
@@ -13,8 +13,10 @@
 // limitations under the License.
 
 use bincode::{Decode, Encode};
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::{self, Display, Formatter};
 
+use crate::model::{ComponentId, ComponentVersion};
 use crate::SafeDisplay;
 use golem_wasm_ast::analysis::AnalysedFunctionParameter;
 use golem_wasm_ast::core::Mem;
@@ -32,6 +34,51 @@ pub struct ComponentMetadata {
     pub exports: Vec<AnalysedExport>,
     pub producers: Vec<Producers>,
     pub memories: Vec<LinearMemory>,
+    /// User defined key-value annotations of the component, inherited by its new versions
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    /// Packages of the components called through generated wasm-rpc stubs, detected from the
+    /// stub interfaces imported by the component
+    #[serde(default)]
+    pub rpc_dependencies: Vec<String>,
+    /// Names of the interfaces imported by the component and its nested components
+    #[serde(default)]
+    pub imports: Vec<String>,
+    /// Exact versions of the components plugged into this one when it was composed
+    #[serde(default)]
+    pub plugs: Vec<ComponentPlug>,
+}
+
+/// A component version whose exports were plugged into the imports of a composed component
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object, Encode, Decode)]
+pub struct ComponentPlug {
+    pub component_id: ComponentId,
+    pub version: ComponentVersion,
+}
+
+impl TryFrom<golem_api_grpc::proto::golem::component::VersionedComponentId> for ComponentPlug {
+    type Error = String;
+
+    fn try_from(
+        value: golem_api_grpc::proto::golem::component::VersionedComponentId,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self {
+            component_id: value
+                .component_id
+                .ok_or("Missing plugged component id")?
+                .try_into()?,
+            version: value.version,
+        })
+    }
+}
+
+impl From<ComponentPlug> for golem_api_grpc::proto::golem::component::VersionedComponentId {
+    fn from(value: ComponentPlug) -> Self {
+        Self {
+            component_id: Some(value.component_id.into()),
+            version: value.version,
+        }
+    }
 }
 
 impl ComponentMetadata {
@@ -39,6 +86,19 @@ impl ComponentMetadata {
         let raw = RawComponentMetadata::analyse_component(data)?;
         Ok(raw.into())
     }
+
+    /// Packages of the interfaces exported by the component, which other components can depend on
+    /// through wasm-rpc stubs
+    pub fn exported_packages(&self) -> BTreeSet<String> {
+        self.exports
+            .iter()
+            .filter_map(|export| match export {
+                AnalysedExport::Instance(instance) => interface_package(&instance.name),
+                AnalysedExport::Function(_) => None,
+            })
+            .map(|package| package.to_string())
+            .collect()
+    }
 }
 
 #[derive(
@@ -259,10 +319,17 @@ impl From<RawComponentMetadata> for ComponentMetadata {
 
         let memories = value.memories.into_iter().map(LinearMemory::from).collect();
 
+        let rpc_dependencies = value.rpc_dependencies();
+        let imports = value.imported_interfaces();
+
         ComponentMetadata {
             exports,
             producers,
             memories,
+            labels: BTreeMap::new(),
+            rpc_dependencies,
+            imports,
+            plugs: vec![],
         }
     }
 }
@@ -289,6 +356,14 @@ impl TryFrom<golem_api_grpc::proto::golem::component::ComponentMetadata> for Com
                 .into_iter()
                 .map(|memory| memory.into())
                 .collect(),
+            labels: value.labels.into_iter().collect(),
+            rpc_dependencies: value.rpc_dependencies,
+            imports: value.imports,
+            plugs: value
+                .plugs
+                .into_iter()
+                .map(|plug| plug.try_into())
+                .collect::<Result<_, _>>()?,
         })
     }
 }
@@ -311,6 +386,10 @@ impl From<ComponentMetadata> for golem_api_grpc::proto::golem::component::Compon
                 .into_iter()
                 .map(|memory| memory.into())
                 .collect(),
+            labels: value.labels.into_iter().collect(),
+            rpc_dependencies: value.rpc_dependencies,
+            imports: value.imports,
+            plugs: value.plugs.into_iter().map(|plug| plug.into()).collect(),
         }
     }
 }
@@ -320,6 +399,8 @@ pub struct RawComponentMetadata {
     pub exports: Vec<AnalysedExport>,
     pub producers: Vec<WasmAstProducers>,
     pub memories: Vec<Mem>,
+    /// Names of the imports of the component and of its nested components
+    pub imports: Vec<String>,
 }
 
 impl RawComponentMetadata {
@@ -334,6 +415,8 @@ impl RawComponentMetadata {
             .into_iter()
             .collect::<Vec<_>>();
 
+        let imports = collect_import_names(&component);
+
         let state = AnalysisContext::new(component);
 
         let mut exports = state
@@ -354,8 +437,64 @@ impl RawComponentMetadata {
             exports,
             producers,
             memories,
+            imports,
         })
     }
+
+    /// Packages of the components called through wasm-rpc stubs. The stub generator puts the stub
+    /// of a `ns:name` package into `ns:name-stub`, with interfaces named `stub-<world>`.
+    pub fn rpc_dependencies(&self) -> Vec<String> {
+        self.imports
+            .iter()
+            .filter_map(|import| rpc_stub_target_package(import))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// Distinct names of the imports, as the same interface can be imported on several levels
+    pub fn imported_interfaces(&self) -> Vec<String> {
+        self.imports
+            .iter()
+            .cloned()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+}
+
+/// Composing a component with a wasm-rpc stub moves the stub imports into the nested components,
+/// so they are collected from every level
+fn collect_import_names(component: &Component<IgnoreAllButMetadata>) -> Vec<String> {
+    let mut names = component
+        .imports()
+        .iter()
+        .map(|import| import.name.clone().into())
+        .collect::<Vec<String>>();
+    for nested in component.components() {
+        names.extend(collect_import_names(&nested));
+    }
+    names
+}
+
+/// The `ns:name` package of an interface named `ns:name/interface` or `ns:name/interface@version`
+fn interface_package(interface_name: &str) -> Option<&str> {
+    interface_name
+        .split_once('/')
+        .map(|(package, _)| package)
+        .filter(|package| package.contains(':'))
+}
+
+/// The package of the component called through an imported wasm-rpc stub interface, if the
+/// import is one
+fn rpc_stub_target_package(import_name: &str) -> Option<String> {
+    let (_, interface) = import_name.split_once('/')?;
+    if !interface.starts_with("stub-") {
+        return None;
+    }
+    interface_package(import_name)?
+        .strip_suffix("-stub")
+        .map(|package| package.to_string())
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -429,3 +568,40 @@ fn drop_from_constructor(constructor: &AnalysedFunction) -> AnalysedFunction {
         results: vec![],
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::model::component_metadata::{interface_package, rpc_stub_target_package};
+
+    #[test]
+    fn rpc_stub_imports_are_detected() {
+        assert_eq!(
+            rpc_stub_target_package("rpc:counters-stub/stub-counters"),
+            Some("rpc:counters".to_string())
+        );
+        assert_eq!(
+            rpc_stub_target_package("auction:auction-stub/stub-auction@0.1.0"),
+            Some("auction:auction".to_string())
+        );
+    }
+
+    #[test]
+    fn other_imports_are_not_rpc_stubs() {
+        assert_eq!(rpc_stub_target_package("wasi:io/streams@0.2.0"), None);
+        assert_eq!(rpc_stub_target_package("golem:rpc/types@0.1.0"), None);
+        assert_eq!(rpc_stub_target_package("rpc:counters-stub/types"), None);
+        assert_eq!(rpc_stub_target_package("stub-counters"), None);
+    }
+
+    #[test]
+    fn interface_packages() {
+        assert_eq!(interface_package("rpc:counters/api"), Some("rpc:counters"));
+        assert_eq!(
+            interface_package("rpc:counters/api@1.0.0"),
+            Some("rpc:counters")
+        );
+        assert_eq!(interface_package("run"), None);
+    }
+}
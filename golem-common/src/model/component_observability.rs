@@ -0,0 +1,229 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::model::LogLevel;
+use poem_openapi::Object;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Observability settings applied to every worker of a component
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct ComponentObservability {
+    /// Minimum level of the log entries forwarded to the worker's event stream
+    pub log_level: LogLevel,
+    /// Whether invocation metrics are recorded for the workers
+    pub metrics_enabled: bool,
+    /// Ratio of the invocations traced, between 0 and 1
+    pub trace_sampling_rate: f64,
+    /// Ratio of the invocations having their input and output captured in the executor's log, between 0 and 1
+    pub payload_capture_sampling_rate: f64,
+    /// Exported function parameters whose values are masked in the public oplog
+    #[serde(default)]
+    #[oai(default)]
+    pub sensitive_parameters: Vec<SensitiveParameter>,
+}
+
+/// Identifies a parameter of an exported function by the function's fully qualified name
+/// and the parameter's name
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct SensitiveParameter {
+    pub function_name: String,
+    pub parameter_name: String,
+}
+
+impl ComponentObservability {
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, rate) in [
+            ("trace sampling rate", self.trace_sampling_rate),
+            (
+                "payload capture sampling rate",
+                self.payload_capture_sampling_rate,
+            ),
+        ] {
+            if !(0.0..=1.0).contains(&rate) {
+                return Err(format!("The {name} must be between 0 and 1, got {rate}"));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn forwards_log(&self, level: &LogLevel) -> bool {
+        *level >= self.log_level
+    }
+
+    pub fn sample_trace(&self) -> bool {
+        sample(self.trace_sampling_rate)
+    }
+
+    pub fn sample_payload_capture(&self) -> bool {
+        sample(self.payload_capture_sampling_rate)
+    }
+
+    pub fn is_sensitive_parameter(&self, function_name: &str, parameter_name: &str) -> bool {
+        self.sensitive_parameters.iter().any(|parameter| {
+            parameter.function_name == function_name && parameter.parameter_name == parameter_name
+        })
+    }
+}
+
+impl Default for ComponentObservability {
+    fn default() -> Self {
+        Self {
+            log_level: LogLevel::Trace,
+            metrics_enabled: true,
+            trace_sampling_rate: 1.0,
+            payload_capture_sampling_rate: 0.0,
+            sensitive_parameters: Vec::new(),
+        }
+    }
+}
+
+fn sample(rate: f64) -> bool {
+    if rate >= 1.0 {
+        true
+    } else if rate <= 0.0 {
+        false
+    } else {
+        rand::thread_rng().gen::<f64>() < rate
+    }
+}
+
+impl From<golem_api_grpc::proto::golem::component::ComponentObservability>
+    for ComponentObservability
+{
+    fn from(value: golem_api_grpc::proto::golem::component::ComponentObservability) -> Self {
+        Self {
+            log_level: value.log_level().into(),
+            metrics_enabled: value.metrics_enabled,
+            trace_sampling_rate: value.trace_sampling_rate,
+            payload_capture_sampling_rate: value.payload_capture_sampling_rate,
+            sensitive_parameters: value
+                .sensitive_parameters
+                .into_iter()
+                .map(SensitiveParameter::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<ComponentObservability>
+    for golem_api_grpc::proto::golem::component::ComponentObservability
+{
+    fn from(value: ComponentObservability) -> Self {
+        let log_level: golem_api_grpc::proto::golem::worker::Level = value.log_level.into();
+        Self {
+            log_level: log_level as i32,
+            metrics_enabled: value.metrics_enabled,
+            trace_sampling_rate: value.trace_sampling_rate,
+            payload_capture_sampling_rate: value.payload_capture_sampling_rate,
+            sensitive_parameters: value
+                .sensitive_parameters
+                .into_iter()
+                .map(|parameter| parameter.into())
+                .collect(),
+        }
+    }
+}
+
+impl From<golem_api_grpc::proto::golem::component::SensitiveParameter> for SensitiveParameter {
+    fn from(value: golem_api_grpc::proto::golem::component::SensitiveParameter) -> Self {
+        Self {
+            function_name: value.function_name,
+            parameter_name: value.parameter_name,
+        }
+    }
+}
+
+impl From<SensitiveParameter> for golem_api_grpc::proto::golem::component::SensitiveParameter {
+    fn from(value: SensitiveParameter) -> Self {
+        Self {
+            function_name: value.function_name,
+            parameter_name: value.parameter_name,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::model::component_observability::{ComponentObservability, SensitiveParameter};
+    use crate::model::LogLevel;
+
+    #[test]
+    fn forwards_logs_at_or_above_the_configured_level() {
+        let observability = ComponentObservability {
+            log_level: LogLevel::Warn,
+            ..ComponentObservability::default()
+        };
+
+        assert!(!observability.forwards_log(&LogLevel::Info));
+        assert!(observability.forwards_log(&LogLevel::Warn));
+        assert!(observability.forwards_log(&LogLevel::Critical));
+    }
+
+    #[test]
+    fn rejects_out_of_range_sampling_rates() {
+        let observability = ComponentObservability {
+            trace_sampling_rate: 1.5,
+            ..ComponentObservability::default()
+        };
+        assert!(observability.validate().is_err());
+
+        let observability = ComponentObservability {
+            payload_capture_sampling_rate: f64::NAN,
+            ..ComponentObservability::default()
+        };
+        assert!(observability.validate().is_err());
+
+        assert!(ComponentObservability::default().validate().is_ok());
+    }
+
+    #[test]
+    fn matches_sensitive_parameters_by_function_and_name() {
+        let observability = ComponentObservability {
+            sensitive_parameters: vec![SensitiveParameter {
+                function_name: "golem:it/api.{login}".to_string(),
+                parameter_name: "password".to_string(),
+            }],
+            ..ComponentObservability::default()
+        };
+
+        assert!(observability.is_sensitive_parameter("golem:it/api.{login}", "password"));
+        assert!(!observability.is_sensitive_parameter("golem:it/api.{login}", "username"));
+        assert!(!observability.is_sensitive_parameter("golem:it/api.{register}", "password"));
+    }
+
+    #[test]
+    fn proto_roundtrip() {
+        let observability = ComponentObservability {
+            log_level: LogLevel::Error,
+            metrics_enabled: false,
+            trace_sampling_rate: 0.25,
+            payload_capture_sampling_rate: 0.01,
+            sensitive_parameters: vec![SensitiveParameter {
+                function_name: "golem:it/api.{login}".to_string(),
+                parameter_name: "password".to_string(),
+            }],
+        };
+
+        let proto: golem_api_grpc::proto::golem::component::ComponentObservability =
+            observability.clone().into();
+        assert_eq!(ComponentObservability::from(proto), observability);
+    }
+}
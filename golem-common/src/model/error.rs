@@ -0,0 +1,286 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Error taxonomy shared by the REST and gRPC APIs.
+//!
+//! Every error returned by the services belongs to one of the [ErrorCode]s, which determines the
+//! HTTP status and the gRPC status code it is returned with, and whether the failed request can
+//! be retried. REST errors can be described as RFC 7807 [ProblemDetails].
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+use poem::http::header::CONTENT_TYPE;
+use poem::http::StatusCode;
+use poem_openapi::{Enum, Object};
+use serde::{Deserialize, Serialize};
+use tonic::Code;
+
+/// Content type of the RFC 7807 problem details responses
+pub const PROBLEM_JSON_CONTENT_TYPE: &str = "application/problem+json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Enum)]
+#[serde(rename_all = "kebab-case")]
+#[oai(rename_all = "kebab-case")]
+pub enum ErrorCode {
+    InvalidRequest,
+    Unauthorized,
+    Forbidden,
+    NotFound,
+    AlreadyExists,
+    Conflict,
+    PayloadTooLarge,
+    TooManyRequests,
+    Timeout,
+    Unavailable,
+    Internal,
+}
+
+impl ErrorCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::InvalidRequest => "invalid-request",
+            ErrorCode::Unauthorized => "unauthorized",
+            ErrorCode::Forbidden => "forbidden",
+            ErrorCode::NotFound => "not-found",
+            ErrorCode::AlreadyExists => "already-exists",
+            ErrorCode::Conflict => "conflict",
+            ErrorCode::PayloadTooLarge => "payload-too-large",
+            ErrorCode::TooManyRequests => "too-many-requests",
+            ErrorCode::Timeout => "timeout",
+            ErrorCode::Unavailable => "unavailable",
+            ErrorCode::Internal => "internal",
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            ErrorCode::InvalidRequest => "Invalid request",
+            ErrorCode::Unauthorized => "Unauthorized",
+            ErrorCode::Forbidden => "Forbidden",
+            ErrorCode::NotFound => "Not found",
+            ErrorCode::AlreadyExists => "Already exists",
+            ErrorCode::Conflict => "Conflict",
+            ErrorCode::PayloadTooLarge => "Payload too large",
+            ErrorCode::TooManyRequests => "Too many requests",
+            ErrorCode::Timeout => "Timeout",
+            ErrorCode::Unavailable => "Unavailable",
+            ErrorCode::Internal => "Internal error",
+        }
+    }
+
+    pub fn http_status(&self) -> StatusCode {
+        match self {
+            ErrorCode::InvalidRequest => StatusCode::BAD_REQUEST,
+            ErrorCode::Unauthorized => StatusCode::UNAUTHORIZED,
+            ErrorCode::Forbidden => StatusCode::FORBIDDEN,
+            ErrorCode::NotFound => StatusCode::NOT_FOUND,
+            ErrorCode::AlreadyExists | ErrorCode::Conflict => StatusCode::CONFLICT,
+            ErrorCode::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            ErrorCode::TooManyRequests => StatusCode::TOO_MANY_REQUESTS,
+            ErrorCode::Timeout => StatusCode::GATEWAY_TIMEOUT,
+            ErrorCode::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+            ErrorCode::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    pub fn grpc_code(&self) -> Code {
+        match self {
+            ErrorCode::InvalidRequest => Code::InvalidArgument,
+            ErrorCode::Unauthorized => Code::Unauthenticated,
+            ErrorCode::Forbidden => Code::PermissionDenied,
+            ErrorCode::NotFound => Code::NotFound,
+            ErrorCode::AlreadyExists => Code::AlreadyExists,
+            ErrorCode::Conflict => Code::FailedPrecondition,
+            ErrorCode::PayloadTooLarge => Code::OutOfRange,
+            ErrorCode::TooManyRequests => Code::ResourceExhausted,
+            ErrorCode::Timeout => Code::DeadlineExceeded,
+            ErrorCode::Unavailable => Code::Unavailable,
+            ErrorCode::Internal => Code::Internal,
+        }
+    }
+
+    /// Gets the error code of a gRPC status code returned by another service
+    pub fn from_grpc_code(code: Code) -> Self {
+        match code {
+            Code::InvalidArgument => ErrorCode::InvalidRequest,
+            Code::Unauthenticated => ErrorCode::Unauthorized,
+            Code::PermissionDenied => ErrorCode::Forbidden,
+            Code::NotFound => ErrorCode::NotFound,
+            Code::AlreadyExists => ErrorCode::AlreadyExists,
+            Code::FailedPrecondition | Code::Aborted => ErrorCode::Conflict,
+            Code::OutOfRange => ErrorCode::PayloadTooLarge,
+            Code::ResourceExhausted => ErrorCode::TooManyRequests,
+            Code::DeadlineExceeded | Code::Cancelled => ErrorCode::Timeout,
+            Code::Unavailable => ErrorCode::Unavailable,
+            Code::Ok | Code::Unknown | Code::Internal | Code::Unimplemented | Code::DataLoss => {
+                ErrorCode::Internal
+            }
+        }
+    }
+
+    /// Returns true if a request failing with this error can be retried without changes.
+    /// Errors returned for a given reason can override this hint, see [ProblemDetails::retriable].
+    pub fn is_retriable(&self) -> bool {
+        match self {
+            ErrorCode::InvalidRequest
+            | ErrorCode::Unauthorized
+            | ErrorCode::Forbidden
+            | ErrorCode::NotFound
+            | ErrorCode::AlreadyExists
+            | ErrorCode::Conflict
+            | ErrorCode::PayloadTooLarge => false,
+            ErrorCode::TooManyRequests
+            | ErrorCode::Timeout
+            | ErrorCode::Unavailable
+            | ErrorCode::Internal => true,
+        }
+    }
+
+    /// URI identifying the error code in the `type` field of the problem details
+    pub fn type_uri(&self) -> String {
+        format!("urn:golem:error:{}", self.as_str())
+    }
+}
+
+impl Display for ErrorCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// RFC 7807 description of an error, extended with the error code, the retriability hint and
+/// the parameters of the error
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[oai(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+pub struct ProblemDetails {
+    #[oai(rename = "type")]
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+    pub code: ErrorCode,
+    pub retriable: bool,
+    #[oai(default)]
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub params: BTreeMap<String, String>,
+}
+
+impl ProblemDetails {
+    pub fn new(code: ErrorCode, detail: impl Into<String>) -> Self {
+        Self {
+            type_: code.type_uri(),
+            title: code.title().to_string(),
+            status: code.http_status().as_u16(),
+            detail: detail.into(),
+            code,
+            retriable: code.is_retriable(),
+            params: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_param(mut self, name: impl Into<String>, value: impl ToString) -> Self {
+        self.params.insert(name.into(), value.to_string());
+        self
+    }
+
+    pub fn with_retriable(mut self, retriable: bool) -> Self {
+        self.retriable = retriable;
+        self
+    }
+
+    /// Converts the problem to a gRPC status, keeping the detail as the status message
+    pub fn to_grpc_status(&self) -> tonic::Status {
+        tonic::Status::new(self.code.grpc_code(), self.detail.clone())
+    }
+
+    /// Converts the problem to an `application/problem+json` HTTP response
+    pub fn to_http_response(&self) -> poem::Response {
+        poem::Response::builder()
+            .status(StatusCode::from_u16(self.status).unwrap_or(self.code.http_status()))
+            .header(CONTENT_TYPE, PROBLEM_JSON_CONTENT_TYPE)
+            .body(serde_json::to_string(self).unwrap_or_default())
+    }
+}
+
+impl Display for ProblemDetails {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.title, self.detail)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use poem::http::StatusCode;
+    use tonic::Code;
+
+    use crate::model::error::{ErrorCode, ProblemDetails, PROBLEM_JSON_CONTENT_TYPE};
+
+    #[test]
+    fn grpc_codes_round_trip() {
+        for code in [
+            ErrorCode::InvalidRequest,
+            ErrorCode::Unauthorized,
+            ErrorCode::Forbidden,
+            ErrorCode::NotFound,
+            ErrorCode::AlreadyExists,
+            ErrorCode::Conflict,
+            ErrorCode::PayloadTooLarge,
+            ErrorCode::TooManyRequests,
+            ErrorCode::Timeout,
+            ErrorCode::Unavailable,
+            ErrorCode::Internal,
+        ] {
+            assert_eq!(ErrorCode::from_grpc_code(code.grpc_code()), code);
+        }
+    }
+
+    #[test]
+    fn problem_details_serialization() {
+        let problem = ProblemDetails::new(ErrorCode::NotFound, "Worker not found: c/w")
+            .with_param("workerId", "c/w");
+        let json = serde_json::to_value(&problem).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "type": "urn:golem:error:not-found",
+                "title": "Not found",
+                "status": 404,
+                "detail": "Worker not found: c/w",
+                "code": "not-found",
+                "retriable": false,
+                "params": { "workerId": "c/w" }
+            })
+        );
+        let deserialized: ProblemDetails = serde_json::from_value(json).unwrap();
+        assert_eq!(deserialized, problem);
+    }
+
+    #[test]
+    fn problem_details_responses() {
+        let problem = ProblemDetails::new(ErrorCode::TooManyRequests, "slow down");
+        assert_eq!(problem.to_grpc_status().code(), Code::ResourceExhausted);
+
+        let response = problem.to_http_response();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(
+            response.headers().get("content-type").unwrap(),
+            PROBLEM_JSON_CONTENT_TYPE
+        );
+    }
+}
@@ -0,0 +1,139 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Initial file system contents of workers, attached to component versions as a zip archive.
+
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+use zip::ZipArchive;
+
+/// Content hash identifying an initial file archive, used as its storage key and cache key
+pub fn archive_hash(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// Checks that the archive can be read and that none of its entries would be extracted
+/// outside of the worker's root directory
+pub fn validate_archive(data: &[u8]) -> Result<(), String> {
+    let mut archive = open_archive(data)?;
+    for index in 0..archive.len() {
+        let entry = archive
+            .by_index(index)
+            .map_err(|err| format!("Failed to read initial file archive entry: {err}"))?;
+        entry_path(entry.name(), entry.enclosed_name())?;
+    }
+    Ok(())
+}
+
+/// Extracts the archive into the target directory. The extracted files are read-only, the
+/// directories remain writable so workers can still create their own files next to them.
+pub fn extract_archive(data: &[u8], target: &Path) -> Result<(), String> {
+    let mut archive = open_archive(data)?;
+    for index in 0..archive.len() {
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|err| format!("Failed to read initial file archive entry: {err}"))?;
+        let path = target.join(entry_path(entry.name(), entry.enclosed_name())?);
+
+        if entry.is_dir() {
+            fs::create_dir_all(&path)
+                .map_err(|err| format!("Failed to create directory {path:?}: {err}"))?;
+        } else {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|err| format!("Failed to create directory {parent:?}: {err}"))?;
+            }
+            let mut file = fs::File::create(&path)
+                .map_err(|err| format!("Failed to create file {path:?}: {err}"))?;
+            std::io::copy(&mut entry, &mut file)
+                .map_err(|err| format!("Failed to write file {path:?}: {err}"))?;
+
+            let mut permissions = file
+                .metadata()
+                .map_err(|err| format!("Failed to get metadata of file {path:?}: {err}"))?
+                .permissions();
+            permissions.set_readonly(true);
+            fs::set_permissions(&path, permissions)
+                .map_err(|err| format!("Failed to make file {path:?} read-only: {err}"))?;
+        }
+    }
+    Ok(())
+}
+
+fn open_archive(data: &[u8]) -> Result<ZipArchive<Cursor<&[u8]>>, String> {
+    ZipArchive::new(Cursor::new(data)).map_err(|err| format!("Invalid initial file archive: {err}"))
+}
+
+fn entry_path(name: &str, enclosed_name: Option<PathBuf>) -> Result<PathBuf, String> {
+    enclosed_name.ok_or_else(|| {
+        format!("Initial file archive entry {name} points outside of the worker's root directory")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::io::Write;
+
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    use crate::model::initial_files::{archive_hash, extract_archive, validate_archive};
+
+    fn archive(entries: &[(&str, &str)]) -> Vec<u8> {
+        let mut writer = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        for (name, content) in entries {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn extracts_read_only_files() {
+        let data = archive(&[("config.json", "{}"), ("static/index.html", "<html/>")]);
+        assert!(validate_archive(&data).is_ok());
+
+        let target = tempfile::tempdir().unwrap();
+        extract_archive(&data, target.path()).unwrap();
+
+        let index = target.path().join("static/index.html");
+        assert_eq!(std::fs::read_to_string(&index).unwrap(), "<html/>");
+        assert!(std::fs::metadata(&index).unwrap().permissions().readonly());
+        assert_eq!(
+            std::fs::read_to_string(target.path().join("config.json")).unwrap(),
+            "{}"
+        );
+    }
+
+    #[test]
+    fn rejects_entries_outside_of_the_root() {
+        assert!(validate_archive(&archive(&[("../escape.txt", "x")])).is_err());
+        assert!(validate_archive(b"not a zip").is_err());
+    }
+
+    #[test]
+    fn hash_depends_on_content() {
+        let first = archive(&[("a.txt", "1")]);
+        let second = archive(&[("a.txt", "2")]);
+        assert_eq!(archive_hash(&first), archive_hash(&first));
+        assert_ne!(archive_hash(&first), archive_hash(&second));
+    }
+}
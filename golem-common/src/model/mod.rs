@@ -14,9 +14,9 @@
 
 use std::borrow::Cow;
 use std::cmp::Ordering;
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::fmt::{Display, Formatter};
-use std::ops::Add;
+use std::ops::{Add, Deref};
 use std::str::FromStr;
 use std::time::Duration;
 
@@ -41,18 +41,25 @@ use golem_wasm_rpc::IntoValue;
 use poem::http::Uri;
 use poem_openapi::registry::{MetaSchema, MetaSchemaRef};
 use poem_openapi::types::{ParseFromJSON, ParseFromParameter, ParseResult, ToJSON};
-use poem_openapi::{Enum, Object, Union};
+use poem_openapi::{Enum, NewType, Object, Union};
 use rand::prelude::IteratorRandom;
 use serde::{Deserialize, Serialize, Serializer};
 use serde_json::Value;
 use uuid::{uuid, Uuid};
 
+pub mod auth;
+pub mod component_compatibility;
 pub mod component_metadata;
+pub mod component_observability;
+pub mod error;
 pub mod exports;
+pub mod initial_files;
 pub mod oplog;
 pub mod public_oplog;
+pub mod public_worker_status;
 pub mod regions;
 pub mod trim_date;
+pub mod worker_statistics;
 
 use crate::uri::oss::urn::WorkerUrn;
 use golem_api_grpc::proto::golem::shardmanager::{
@@ -234,12 +241,179 @@ impl From<u64> for Timestamp {
 
 pub type ComponentVersion = u64;
 
+/// Name of a worker.
+///
+/// Names of new workers are 1 to 100 alphanumeric characters, underscores and dashes, not starting
+/// with a dash. These are the names the worker APIs always accepted, so they keep addressing the
+/// same workers. Workers created through other paths before these rules were enforced can be
+/// addressed by the escaped form of their names, see `WorkerName::escape`.
+#[derive(
+    Clone,
+    Debug,
+    Eq,
+    PartialEq,
+    Hash,
+    Ord,
+    PartialOrd,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+    NewType,
+)]
+pub struct WorkerName(String);
+
+impl WorkerName {
+    /// Escaped names start with a character never accepted by the worker APIs, so they can't
+    /// be confused with the name of any worker created through them
+    const ESCAPED_PREFIX: &'static str = "~";
+    const MAX_LENGTH: usize = 100;
+
+    /// Validates the name of a new worker
+    pub fn new(name: impl Into<String>) -> Result<Self, &'static str> {
+        let name = name.into();
+        Self::validate(&name)?;
+        Ok(Self(name))
+    }
+
+    /// Wraps the name of an already existing worker, or one already validated by another service,
+    /// without validating it again
+    pub fn from_existing(name: impl Into<String>) -> Self {
+        Self(name.into())
+    }
+
+    /// Parses a worker name coming from an API, which is either a valid name or the escaped form
+    /// of the name of an existing worker not conforming to the rules
+    pub fn parse(name: &str) -> Result<Self, &'static str> {
+        match name.strip_prefix(Self::ESCAPED_PREFIX) {
+            Some(escaped) => Self::unescape(escaped)
+                .map(Self)
+                .ok_or("Escaped worker name is malformed"),
+            None => Self::new(name),
+        }
+    }
+
+    pub fn validate(name: &str) -> Result<(), &'static str> {
+        let length = name.len();
+        if !(1..=Self::MAX_LENGTH).contains(&length) {
+            Err("Worker name must be between 1 and 100 characters")
+        } else if name.contains(' ') {
+            Err("Worker name must not contain spaces")
+        } else if !name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+        {
+            Err("Worker name must contain only alphanumeric characters, underscores, and dashes")
+        } else if name.starts_with('-') {
+            Err("Worker name must not start with a dash")
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Escapes an arbitrary worker name into a form accepted by `WorkerName::parse`: a tilde
+    /// followed by the name, with every character other than alphanumerics and dashes written as
+    /// an underscore and the hex code of each of its UTF-8 bytes.
+    /// For example `order:1` is escaped as `~order_3a1`.
+    pub fn escape(name: &str) -> String {
+        let mut escaped = Self::ESCAPED_PREFIX.to_string();
+        for c in name.chars() {
+            if c.is_alphanumeric() || c == '-' {
+                escaped.push(c);
+            } else {
+                let mut bytes = [0; 4];
+                for byte in c.encode_utf8(&mut bytes).bytes() {
+                    escaped.push_str(&format!("_{byte:02x}"));
+                }
+            }
+        }
+        escaped
+    }
+
+    fn unescape(escaped: &str) -> Option<String> {
+        let mut bytes = Vec::with_capacity(escaped.len());
+        let mut rest = escaped;
+        while let Some(c) = rest.chars().next() {
+            if c == '_' {
+                let hex = rest.get(1..3)?;
+                bytes.push(u8::from_str_radix(hex, 16).ok()?);
+                rest = &rest[3..];
+            } else if c.is_alphanumeric() || c == '-' {
+                let mut buf = [0; 4];
+                bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                rest = &rest[c.len_utf8()..];
+            } else {
+                return None;
+            }
+        }
+        String::from_utf8(bytes)
+            .ok()
+            .filter(|name| !name.is_empty())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl Display for WorkerName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Deref for WorkerName {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsRef<str> for WorkerName {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq<str> for WorkerName {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for WorkerName {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl IntoValue for WorkerName {
+    fn into_value(self) -> golem_wasm_rpc::Value {
+        golem_wasm_rpc::Value::String(self.0)
+    }
+
+    fn get_type() -> AnalysedType {
+        str()
+    }
+}
+
+impl From<WorkerName> for String {
+    fn from(value: WorkerName) -> Self {
+        value.0
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Hash, Encode, Decode, Serialize, Deserialize, Object)]
 #[serde(rename_all = "camelCase")]
 #[oai(rename_all = "camelCase")]
 pub struct WorkerId {
     pub component_id: ComponentId,
-    pub worker_name: String,
+    pub worker_name: WorkerName,
 }
 
 impl WorkerId {
@@ -263,31 +437,40 @@ impl WorkerId {
     }
 }
 
+/// Parses the canonical `<component_id>/<worker_name>` form written by `Display`, a worker URN,
+/// and the legacy `<component_id>:<worker_name>` form
 impl FromStr for WorkerId {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts: Vec<&str> = s.split(':').collect();
-        if parts.len() == 2 {
-            let component_id_uuid = Uuid::from_str(parts[0])
-                .map_err(|_| format!("invalid component id: {s} - expected uuid"))?;
-            let component_id = ComponentId(component_id_uuid);
-            let worker_name = parts[1].to_string();
-            Ok(Self {
-                component_id,
-                worker_name,
-            })
-        } else {
-            Err(format!(
-                "invalid worker id: {s} - expected format: <component_id>:<worker_name>"
-            ))
+        if s.starts_with("urn:") {
+            return WorkerUrn::from_str(s)
+                .and_then(|urn| urn.worker_id())
+                .map_err(|err| format!("invalid worker id: {s} - {err}"));
+        }
+
+        // Component ids never contain the separators, so the worker name is everything after
+        // the first one
+        match s.split_once(['/', ':']) {
+            Some((component_id, worker_name)) if !worker_name.is_empty() => {
+                let component_id_uuid = Uuid::from_str(component_id)
+                    .map_err(|_| format!("invalid component id: {s} - expected uuid"))?;
+                Ok(Self {
+                    component_id: ComponentId(component_id_uuid),
+                    worker_name: WorkerName::from_existing(worker_name),
+                })
+            }
+            _ => Err(format!(
+                "invalid worker id: {s} - expected format: <component_id>/<worker_name>"
+            )),
         }
     }
 }
 
+/// The canonical textual form of a worker id, `<component_id>/<worker_name>`
 impl Display for WorkerId {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&format!("{}/{}", self.component_id, self.worker_name))
+        write!(f, "{}/{}", self.component_id, self.worker_name)
     }
 }
 
@@ -295,7 +478,7 @@ impl From<WorkerId> for golem_api_grpc::proto::golem::worker::WorkerId {
     fn from(value: WorkerId) -> Self {
         Self {
             component_id: Some(value.component_id.into()),
-            name: value.worker_name,
+            name: value.worker_name.into_string(),
         }
     }
 }
@@ -308,7 +491,7 @@ impl TryFrom<golem_api_grpc::proto::golem::worker::WorkerId> for WorkerId {
     ) -> Result<Self, Self::Error> {
         Ok(Self {
             component_id: value.component_id.unwrap().try_into()?,
-            worker_name: value.name,
+            worker_name: WorkerName::from_existing(value.name),
         })
     }
 }
@@ -356,7 +539,7 @@ impl OwnedWorkerId {
         self.worker_id.component_id.clone()
     }
 
-    pub fn worker_name(&self) -> String {
+    pub fn worker_name(&self) -> WorkerName {
         self.worker_id.worker_name.clone()
     }
 }
@@ -370,7 +553,7 @@ impl Display for OwnedWorkerId {
 #[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, Encode, Decode)]
 pub struct TargetWorkerId {
     pub component_id: ComponentId,
-    pub worker_name: Option<String>,
+    pub worker_name: Option<WorkerName>,
 }
 
 impl TargetWorkerId {
@@ -390,11 +573,16 @@ impl TargetWorkerId {
     /// it generates a new unique one, and if the `force_in_shard` set is not empty, it guarantees
     /// that the generated worker ID will belong to one of the provided shards.
     ///
-    /// If the worker name was specified, `force_in_shard` is ignored.
+    /// The `placement_hint` further narrows down the shard of the generated worker ID. A hint
+    /// which cannot be satisfied within `force_in_shard` is ignored, and so is every hint if
+    /// `number_of_shards` is 0.
+    ///
+    /// If the worker name was specified, `force_in_shard` and `placement_hint` are ignored.
     pub fn into_worker_id(
         self,
         force_in_shard: &HashSet<ShardId>,
         number_of_shards: usize,
+        placement_hint: Option<&WorkerPlacementHint>,
     ) -> WorkerId {
         let TargetWorkerId {
             component_id,
@@ -406,8 +594,25 @@ impl TargetWorkerId {
                 worker_name,
             },
             None => {
-                if force_in_shard.is_empty() || number_of_shards == 0 {
-                    let worker_name = Uuid::new_v4().to_string();
+                let allowed_shards = if number_of_shards == 0 {
+                    HashSet::new()
+                } else {
+                    match placement_hint {
+                        Some(placement_hint) => {
+                            let hinted_shards =
+                                placement_hint.allowed_shards(force_in_shard, number_of_shards);
+                            if hinted_shards.is_empty() {
+                                force_in_shard.clone()
+                            } else {
+                                hinted_shards
+                            }
+                        }
+                        None => force_in_shard.clone(),
+                    }
+                };
+
+                if allowed_shards.is_empty() {
+                    let worker_name = WorkerName::from_existing(Uuid::new_v4().to_string());
                     WorkerId {
                         component_id,
                         worker_name,
@@ -416,13 +621,13 @@ impl TargetWorkerId {
                     let mut current = Uuid::new_v4().to_u128_le();
                     loop {
                         let uuid = Uuid::from_u128_le(current);
-                        let worker_name = uuid.to_string();
+                        let worker_name = WorkerName::from_existing(uuid.to_string());
                         let worker_id = WorkerId {
                             component_id: component_id.clone(),
                             worker_name,
                         };
                         let shard_id = ShardId::from_worker_id(&worker_id, number_of_shards);
-                        if force_in_shard.contains(&shard_id) {
+                        if allowed_shards.contains(&shard_id) {
                             return worker_id;
                         }
                         current += 1;
@@ -433,6 +638,85 @@ impl TargetWorkerId {
     }
 }
 
+/// Environment variable of the workers created through a component version alias, holding the
+/// alias. Update policies following an alias only update the workers pinned to it.
+pub const COMPONENT_VERSION_ALIAS_ENV_VAR: &str = "GOLEM_COMPONENT_VERSION_ALIAS";
+
+/// Hint on the placement of a new worker with a generated name, relative to other workers.
+///
+/// Workers are assigned to executors by shard, so the hints are constraints on the shard of the
+/// generated worker ID:
+/// - `Affinity` places the worker in the same shard as the given worker, so both are always
+///   running on the same executor, and RPC calls between them do not leave the executor
+/// - `AntiAffinity` places the worker in a shard not used by any of the given workers, so they
+///   are not affected by the failure or the load of a single shard together
+///
+/// Hints only affect where the worker is created. They are recorded in the environment of the
+/// created worker, in the `GOLEM_PLACEMENT_HINT` variable, as `affinity:<worker id>` or
+/// `anti-affinity:<worker id>,<worker id>,...`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WorkerPlacementHint {
+    Affinity(WorkerId),
+    AntiAffinity(Vec<WorkerId>),
+}
+
+impl WorkerPlacementHint {
+    /// Environment variable of the created worker holding the hint it was placed with
+    pub const ENV_VAR: &'static str = "GOLEM_PLACEMENT_HINT";
+
+    /// Gets the shards satisfying the hint, out of `force_in_shard` or out of all the shards if
+    /// `force_in_shard` is empty
+    fn allowed_shards(
+        &self,
+        force_in_shard: &HashSet<ShardId>,
+        number_of_shards: usize,
+    ) -> HashSet<ShardId> {
+        let candidates: HashSet<ShardId> = if force_in_shard.is_empty() {
+            (0..number_of_shards)
+                .map(|shard| ShardId::new(shard as i64))
+                .collect()
+        } else {
+            force_in_shard.clone()
+        };
+        match self {
+            WorkerPlacementHint::Affinity(worker_id) => {
+                let shard_id = ShardId::from_worker_id(worker_id, number_of_shards);
+                candidates
+                    .into_iter()
+                    .filter(|candidate| *candidate == shard_id)
+                    .collect()
+            }
+            WorkerPlacementHint::AntiAffinity(worker_ids) => {
+                let avoided: HashSet<ShardId> = worker_ids
+                    .iter()
+                    .map(|worker_id| ShardId::from_worker_id(worker_id, number_of_shards))
+                    .collect();
+                candidates
+                    .into_iter()
+                    .filter(|candidate| !avoided.contains(candidate))
+                    .collect()
+            }
+        }
+    }
+}
+
+impl Display for WorkerPlacementHint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WorkerPlacementHint::Affinity(worker_id) => write!(f, "affinity:{worker_id}"),
+            WorkerPlacementHint::AntiAffinity(worker_ids) => write!(
+                f,
+                "anti-affinity:{}",
+                worker_ids
+                    .iter()
+                    .map(|worker_id| worker_id.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+        }
+    }
+}
+
 impl Display for TargetWorkerId {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match &self.worker_name {
@@ -463,7 +747,7 @@ impl TryFrom<golem::worker::TargetWorkerId> for TargetWorkerId {
                 .component_id
                 .ok_or("Missing component_id")?
                 .try_into()?,
-            worker_name: value.name,
+            worker_name: value.name.map(WorkerName::from_existing),
         })
     }
 }
@@ -472,7 +756,7 @@ impl From<TargetWorkerId> for golem::worker::TargetWorkerId {
     fn from(value: TargetWorkerId) -> Self {
         Self {
             component_id: Some(value.component_id.into()),
-            name: value.worker_name,
+            name: value.worker_name.map(WorkerName::into_string),
         }
     }
 }
@@ -582,7 +866,7 @@ impl Display for ScheduledAction {
     }
 }
 
-#[derive(Debug, Encode, Decode)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
 pub struct ScheduleId {
     pub timestamp: i64,
     pub action: ScheduledAction,
@@ -688,9 +972,15 @@ pub struct NumberOfShards {
 pub struct Pod {
     host: String,
     port: u16,
+    zone: Option<String>,
 }
 
 impl Pod {
+    /// The availability zone (or region) the pod is running in, if it reported one
+    pub fn zone(&self) -> Option<&str> {
+        self.zone.as_deref()
+    }
+
     pub fn uri(&self) -> Uri {
         Uri::builder()
             .scheme("http")
@@ -715,6 +1005,7 @@ impl From<GrpcPod> for Pod {
         Self {
             host: value.host,
             port: value.port as u16,
+            zone: value.zone,
         }
     }
 }
@@ -723,6 +1014,7 @@ impl From<GrpcPod> for Pod {
 pub struct RoutingTable {
     pub number_of_shards: NumberOfShards,
     shard_assignments: HashMap<ShardId, Pod>,
+    fetched_at: std::time::Instant,
 }
 
 impl RoutingTable {
@@ -733,12 +1025,74 @@ impl RoutingTable {
         ))
     }
 
+    /// Looks up a pod to serve a read-only request for the given worker.
+    ///
+    /// Reads are served from storage, so any executor can answer them; a pod other than the
+    /// shard owner is preferred to take load off the primary. Returns `None` if this table is
+    /// older than `max_staleness`, in which case the caller should fall back to `lookup`.
+    pub fn lookup_read(&self, worker_id: &WorkerId, max_staleness: Duration) -> Option<&Pod> {
+        if self.age() > max_staleness {
+            return None;
+        }
+
+        let primary = self.lookup(worker_id)?;
+        self.all()
+            .into_iter()
+            .filter(|pod| *pod != primary)
+            .choose(&mut rand::thread_rng())
+            .or(Some(primary))
+    }
+
+    /// Looks up a pod to serve a read-only request for the given worker, like `lookup_read`,
+    /// but preferring the pods of the given zone to avoid cross-zone traffic.
+    ///
+    /// Falls back to the pods of the other zones if there are no pods in the given zone.
+    pub fn lookup_read_in_zone(
+        &self,
+        worker_id: &WorkerId,
+        max_staleness: Duration,
+        zone: &str,
+    ) -> Option<&Pod> {
+        if self.age() > max_staleness {
+            return None;
+        }
+
+        let primary = self.lookup(worker_id)?;
+        let same_zone_pods: Vec<&Pod> = self
+            .all()
+            .into_iter()
+            .filter(|pod| pod.zone() == Some(zone))
+            .collect();
+        same_zone_pods
+            .iter()
+            .filter(|pod| **pod != primary)
+            .choose(&mut rand::thread_rng())
+            .or(same_zone_pods.first())
+            .copied()
+            .or_else(|| self.lookup_read(worker_id, max_staleness))
+    }
+
+    /// Time elapsed since this routing table was fetched from the shard manager
+    pub fn age(&self) -> Duration {
+        self.fetched_at.elapsed()
+    }
+
     pub fn random(&self) -> Option<&Pod> {
         self.shard_assignments
             .values()
             .choose(&mut rand::thread_rng())
     }
 
+    /// Chooses a random pod which is not in `excluded`, falling back to any pod if all of them
+    /// are excluded
+    pub fn random_except(&self, excluded: &HashSet<Pod>) -> Option<&Pod> {
+        self.shard_assignments
+            .values()
+            .filter(|pod| !excluded.contains(*pod))
+            .choose(&mut rand::thread_rng())
+            .or_else(|| self.random())
+    }
+
     pub fn first(&self) -> Option<&Pod> {
         self.shard_assignments.values().next()
     }
@@ -760,6 +1114,7 @@ impl From<GrpcRoutingTable> for RoutingTable {
                 .map(RoutingTableEntry::from)
                 .map(|routing_table_entry| (routing_table_entry.shard_id, routing_table_entry.pod))
                 .collect(),
+            fetched_at: std::time::Instant::now(),
         }
     }
 }
@@ -860,13 +1215,51 @@ impl IdempotencyKey {
     /// we generate a UUIDv5 in the ROOT_NS namespace and use that as unique namespace for generating
     /// the new idempotency key.
     pub fn derived(base: &IdempotencyKey, oplog_index: OplogIndex) -> Self {
-        let namespace = if let Ok(base_uuid) = Uuid::parse_str(&base.value) {
+        let name = format!("oplog-index-{}", oplog_index);
+        Self::from_uuid(Uuid::new_v5(&base.namespace(), name.as_bytes()))
+    }
+
+    /// Generates a deterministic idempotency key for one item of a batch of invocations
+    /// performed by a single host function call at the given oplog index.
+    ///
+    /// Works the same way as [IdempotencyKey::derived], with the item's position in the batch
+    /// being part of the name.
+    pub fn derived_batch_item(
+        base: &IdempotencyKey,
+        oplog_index: OplogIndex,
+        item_index: usize,
+    ) -> Self {
+        let name = format!("oplog-index-{}-item-{}", oplog_index, item_index);
+        Self::from_uuid(Uuid::new_v5(&base.namespace(), name.as_bytes()))
+    }
+
+    /// Generates a deterministic idempotency key for one of a sequence of invocations
+    /// performed on behalf of a single request, such as a Rib script calling multiple
+    /// worker functions.
+    ///
+    /// Works the same way as [IdempotencyKey::derived], with the invocation's position in the
+    /// sequence being the name.
+    pub fn derived_invocation(base: &IdempotencyKey, invocation_index: usize) -> Self {
+        let name = format!("invocation-{}", invocation_index);
+        Self::from_uuid(Uuid::new_v5(&base.namespace(), name.as_bytes()))
+    }
+
+    /// Generates a deterministic idempotency key for a request identified by a client-supplied
+    /// request id, such as the `X-Request-Id` header.
+    ///
+    /// The key is scoped to the target of the request, so the same request id sent to
+    /// different targets gives different keys.
+    pub fn from_request_id(request_id: &str, target: &str) -> Self {
+        let namespace = Uuid::new_v5(&Self::ROOT_NS, format!("request-{target}").as_bytes());
+        Self::from_uuid(Uuid::new_v5(&namespace, request_id.as_bytes()))
+    }
+
+    fn namespace(&self) -> Uuid {
+        if let Ok(base_uuid) = Uuid::parse_str(&self.value) {
             base_uuid
         } else {
-            Uuid::new_v5(&Self::ROOT_NS, base.value.as_bytes())
-        };
-        let name = format!("oplog-index-{}", oplog_index);
-        Self::from_uuid(Uuid::new_v5(&namespace, name.as_bytes()))
+            Uuid::new_v5(&Self::ROOT_NS, self.value.as_bytes())
+        }
     }
 }
 
@@ -1005,7 +1398,10 @@ pub struct WorkerResourceDescription {
 /// This status is just cached information, all fields must be computable by the oplog alone.
 /// By having an associated oplog_idx, the cached information can be used together with the
 /// tail of the oplog to determine the actual status of the worker.
-#[derive(Clone, Debug, PartialEq, Encode, Decode)]
+///
+/// The last invoked function and the changed environment are encoded last, and are missing from
+/// records stored before they were added.
+#[derive(Clone, Debug, PartialEq)]
 pub struct WorkerStatusRecord {
     pub status: WorkerStatus,
     pub deleted_regions: DeletedRegions,
@@ -1021,6 +1417,96 @@ pub struct WorkerStatusRecord {
     pub total_linear_memory_size: u64,
     pub owned_resources: HashMap<WorkerResourceId, WorkerResourceDescription>,
     pub oplog_idx: OplogIndex,
+    pub last_invoked_function: Option<LastInvokedFunction>,
+    /// Arguments and environment variables set by the last `ChangeEnvironment` oplog entry,
+    /// replacing the ones the worker was created with
+    pub changed_environment: Option<(Vec<String>, Vec<(String, String)>)>,
+}
+
+impl Encode for WorkerStatusRecord {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        self.status.encode(encoder)?;
+        self.deleted_regions.encode(encoder)?;
+        self.overridden_retry_config.encode(encoder)?;
+        self.pending_invocations.encode(encoder)?;
+        self.pending_updates.encode(encoder)?;
+        self.failed_updates.encode(encoder)?;
+        self.successful_updates.encode(encoder)?;
+        self.invocation_results.encode(encoder)?;
+        self.current_idempotency_key.encode(encoder)?;
+        self.component_version.encode(encoder)?;
+        self.component_size.encode(encoder)?;
+        self.total_linear_memory_size.encode(encoder)?;
+        self.owned_resources.encode(encoder)?;
+        self.oplog_idx.encode(encoder)?;
+        self.last_invoked_function.encode(encoder)?;
+        self.changed_environment.encode(encoder)
+    }
+}
+
+impl Decode for WorkerStatusRecord {
+    fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
+        Ok(Self {
+            status: Decode::decode(decoder)?,
+            deleted_regions: Decode::decode(decoder)?,
+            overridden_retry_config: Decode::decode(decoder)?,
+            pending_invocations: Decode::decode(decoder)?,
+            pending_updates: Decode::decode(decoder)?,
+            failed_updates: Decode::decode(decoder)?,
+            successful_updates: Decode::decode(decoder)?,
+            invocation_results: Decode::decode(decoder)?,
+            current_idempotency_key: Decode::decode(decoder)?,
+            component_version: Decode::decode(decoder)?,
+            component_size: Decode::decode(decoder)?,
+            total_linear_memory_size: Decode::decode(decoder)?,
+            owned_resources: Decode::decode(decoder)?,
+            oplog_idx: Decode::decode(decoder)?,
+            // Records stored before these fields were added end here
+            last_invoked_function: decode_optional_tail(decoder)?,
+            changed_environment: decode_optional_tail(decoder)?,
+        })
+    }
+}
+
+bincode::impl_borrow_decode!(WorkerStatusRecord);
+
+/// Decodes an optional field appended to an encoding, which is `None` if the encoded value ends
+/// before it
+fn decode_optional_tail<D: Decoder, T: Decode>(decoder: &mut D) -> Result<Option<T>, DecodeError> {
+    match Option::<T>::decode(decoder) {
+        Ok(value) => Ok(value),
+        Err(DecodeError::UnexpectedEnd { .. }) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// The exported function a worker was most recently invoked with
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct LastInvokedFunction {
+    pub function_name: String,
+    pub timestamp: Timestamp,
+}
+
+impl TryFrom<golem::worker::LastInvokedFunction> for LastInvokedFunction {
+    type Error = String;
+
+    fn try_from(value: golem::worker::LastInvokedFunction) -> Result<Self, Self::Error> {
+        Ok(Self {
+            function_name: value.function_name,
+            timestamp: value.timestamp.ok_or("Missing timestamp")?.into(),
+        })
+    }
+}
+
+impl From<LastInvokedFunction> for golem::worker::LastInvokedFunction {
+    fn from(value: LastInvokedFunction) -> Self {
+        Self {
+            function_name: value.function_name,
+            timestamp: Some(value.timestamp.into()),
+        }
+    }
 }
 
 impl Default for WorkerStatusRecord {
@@ -1040,6 +1526,8 @@ impl Default for WorkerStatusRecord {
             total_linear_memory_size: 0,
             owned_resources: HashMap::new(),
             oplog_idx: OplogIndex::default(),
+            last_invoked_function: None,
+            changed_environment: None,
         }
     }
 }
@@ -1173,18 +1661,174 @@ impl From<WorkerStatus> for i32 {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Encode, Decode)]
+/// Priority of an invocation within a worker's invocation queue.
+///
+/// Pending invocations are started in the order of their priority, and in the order of their
+/// arrival within the same priority. The variants are ordered from the most to the least urgent.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+    Enum,
+)]
+pub enum InvocationPriority {
+    High,
+    #[default]
+    Normal,
+    Low,
+}
+
+impl From<golem_api_grpc::proto::golem::worker::InvocationPriority> for InvocationPriority {
+    fn from(value: golem_api_grpc::proto::golem::worker::InvocationPriority) -> Self {
+        match value {
+            golem_api_grpc::proto::golem::worker::InvocationPriority::High => {
+                InvocationPriority::High
+            }
+            golem_api_grpc::proto::golem::worker::InvocationPriority::Normal => {
+                InvocationPriority::Normal
+            }
+            golem_api_grpc::proto::golem::worker::InvocationPriority::Low => {
+                InvocationPriority::Low
+            }
+        }
+    }
+}
+
+impl From<InvocationPriority> for golem_api_grpc::proto::golem::worker::InvocationPriority {
+    fn from(value: InvocationPriority) -> Self {
+        match value {
+            InvocationPriority::High => {
+                golem_api_grpc::proto::golem::worker::InvocationPriority::High
+            }
+            InvocationPriority::Normal => {
+                golem_api_grpc::proto::golem::worker::InvocationPriority::Normal
+            }
+            InvocationPriority::Low => {
+                golem_api_grpc::proto::golem::worker::InvocationPriority::Low
+            }
+        }
+    }
+}
+
+impl From<InvocationPriority> for i32 {
+    fn from(value: InvocationPriority) -> Self {
+        golem_api_grpc::proto::golem::worker::InvocationPriority::from(value) as i32
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub enum WorkerInvocation {
     ExportedFunction {
         idempotency_key: IdempotencyKey,
         full_function_name: String,
         function_input: Vec<golem_wasm_rpc::Value>,
+        priority: InvocationPriority,
+        /// Key-value pairs passed by the caller, available to the worker during the invocation
+        context: BTreeMap<String, String>,
     },
     ManualUpdate {
         target_version: ComponentVersion,
     },
 }
 
+// Exported function invocations with normal priority and no context keep their original
+// encoding, and the ones without context keep the encoding introduced with priorities, so
+// invocations persisted by earlier versions can still be decoded.
+impl Encode for WorkerInvocation {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        match self {
+            WorkerInvocation::ExportedFunction {
+                idempotency_key,
+                full_function_name,
+                function_input,
+                priority: InvocationPriority::Normal,
+                context,
+            } if context.is_empty() => {
+                0u32.encode(encoder)?;
+                idempotency_key.encode(encoder)?;
+                full_function_name.encode(encoder)?;
+                function_input.encode(encoder)
+            }
+            WorkerInvocation::ManualUpdate { target_version } => {
+                1u32.encode(encoder)?;
+                target_version.encode(encoder)
+            }
+            WorkerInvocation::ExportedFunction {
+                idempotency_key,
+                full_function_name,
+                function_input,
+                priority,
+                context,
+            } if context.is_empty() => {
+                2u32.encode(encoder)?;
+                idempotency_key.encode(encoder)?;
+                full_function_name.encode(encoder)?;
+                function_input.encode(encoder)?;
+                priority.encode(encoder)
+            }
+            WorkerInvocation::ExportedFunction {
+                idempotency_key,
+                full_function_name,
+                function_input,
+                priority,
+                context,
+            } => {
+                3u32.encode(encoder)?;
+                idempotency_key.encode(encoder)?;
+                full_function_name.encode(encoder)?;
+                function_input.encode(encoder)?;
+                priority.encode(encoder)?;
+                context.encode(encoder)
+            }
+        }
+    }
+}
+
+impl Decode for WorkerInvocation {
+    fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
+        let tag = u32::decode(decoder)?;
+        match tag {
+            0 => Ok(WorkerInvocation::ExportedFunction {
+                idempotency_key: IdempotencyKey::decode(decoder)?,
+                full_function_name: String::decode(decoder)?,
+                function_input: Vec::decode(decoder)?,
+                priority: InvocationPriority::Normal,
+                context: BTreeMap::new(),
+            }),
+            1 => Ok(WorkerInvocation::ManualUpdate {
+                target_version: ComponentVersion::decode(decoder)?,
+            }),
+            2 => Ok(WorkerInvocation::ExportedFunction {
+                idempotency_key: IdempotencyKey::decode(decoder)?,
+                full_function_name: String::decode(decoder)?,
+                function_input: Vec::decode(decoder)?,
+                priority: InvocationPriority::decode(decoder)?,
+                context: BTreeMap::new(),
+            }),
+            3 => Ok(WorkerInvocation::ExportedFunction {
+                idempotency_key: IdempotencyKey::decode(decoder)?,
+                full_function_name: String::decode(decoder)?,
+                function_input: Vec::decode(decoder)?,
+                priority: InvocationPriority::decode(decoder)?,
+                context: BTreeMap::decode(decoder)?,
+            }),
+            _ => Err(DecodeError::Other("Invalid WorkerInvocation")),
+        }
+    }
+}
+
+bincode::impl_borrow_decode!(WorkerInvocation);
+
 impl WorkerInvocation {
     pub fn is_idempotency_key(&self, key: &IdempotencyKey) -> bool {
         match self {
@@ -1203,6 +1847,13 @@ impl WorkerInvocation {
             _ => None,
         }
     }
+
+    pub fn priority(&self) -> InvocationPriority {
+        match self {
+            Self::ExportedFunction { priority, .. } => *priority,
+            _ => InvocationPriority::Normal,
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Encode, Decode)]
@@ -1385,6 +2036,28 @@ impl Display for WorkerCreatedAtFilter {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Encode, Decode, Object)]
+pub struct WorkerLastInvokedFunctionFilter {
+    pub comparator: StringFilterComparator,
+    pub value: String,
+}
+
+impl WorkerLastInvokedFunctionFilter {
+    pub fn new(comparator: StringFilterComparator, value: String) -> Self {
+        Self { comparator, value }
+    }
+}
+
+impl Display for WorkerLastInvokedFunctionFilter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "last_invoked_function {} {}",
+            self.comparator, self.value
+        )
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Encode, Decode, Object)]
 pub struct WorkerEnvFilter {
     pub name: String,
@@ -1489,6 +2162,7 @@ pub enum WorkerFilter {
     And(WorkerAndFilter),
     Or(WorkerOrFilter),
     Not(WorkerNotFilter),
+    LastInvokedFunction(WorkerLastInvokedFunctionFilter),
 }
 
 impl WorkerFilter {
@@ -1517,7 +2191,7 @@ impl WorkerFilter {
     pub fn matches(&self, metadata: &WorkerMetadata) -> bool {
         match self.clone() {
             WorkerFilter::Name(WorkerNameFilter { comparator, value }) => {
-                comparator.matches(&metadata.worker_id.worker_name, &value)
+                comparator.matches(&metadata.worker_id.worker_name.as_str(), &value.as_str())
             }
             WorkerFilter::Version(WorkerVersionFilter { comparator, value }) => {
                 let version: ComponentVersion = metadata.last_known_status.component_version;
@@ -1545,6 +2219,15 @@ impl WorkerFilter {
             WorkerFilter::Status(WorkerStatusFilter { comparator, value }) => {
                 comparator.matches(&metadata.last_known_status.status, &value)
             }
+            WorkerFilter::LastInvokedFunction(WorkerLastInvokedFunctionFilter {
+                comparator,
+                value,
+            }) => match &metadata.last_known_status.last_invoked_function {
+                Some(last_invoked_function) => {
+                    comparator.matches(&last_invoked_function.function_name, &value)
+                }
+                None => false,
+            },
             WorkerFilter::Not(WorkerNotFilter { filter }) => !filter.matches(metadata),
             WorkerFilter::And(WorkerAndFilter { filters }) => {
                 let mut result = true;
@@ -1604,6 +2287,10 @@ impl WorkerFilter {
         WorkerFilter::CreatedAt(WorkerCreatedAtFilter::new(comparator, value))
     }
 
+    pub fn new_last_invoked_function(comparator: StringFilterComparator, value: String) -> Self {
+        WorkerFilter::LastInvokedFunction(WorkerLastInvokedFunctionFilter::new(comparator, value))
+    }
+
     pub fn from(filters: Vec<String>) -> Result<WorkerFilter, String> {
         let mut fs = Vec::new();
         for f in filters {
@@ -1640,6 +2327,9 @@ impl Display for WorkerFilter {
             WorkerFilter::Or(filter) => {
                 write!(f, "{}", filter)
             }
+            WorkerFilter::LastInvokedFunction(filter) => {
+                write!(f, "{}", filter)
+            }
         }
     }
 }
@@ -1673,6 +2363,9 @@ impl FromStr for WorkerFilter {
                     comparator.parse()?,
                     value.parse()?,
                 )),
+                "last_invoked_function" | "lastInvokedFunction" => Ok(
+                    WorkerFilter::new_last_invoked_function(comparator.parse()?, value.to_string()),
+                ),
                 _ if arg.starts_with("env.") => {
                     let name = &arg[4..];
                     Ok(WorkerFilter::new_env(
@@ -1722,6 +2415,12 @@ impl TryFrom<golem_api_grpc::proto::golem::worker::WorkerFilter> for WorkerFilte
                 golem_api_grpc::proto::golem::worker::worker_filter::Filter::Env(filter) => Ok(
                     WorkerFilter::new_env(filter.name, filter.comparator.try_into()?, filter.value),
                 ),
+                golem_api_grpc::proto::golem::worker::worker_filter::Filter::LastInvokedFunction(
+                    filter,
+                ) => Ok(WorkerFilter::new_last_invoked_function(
+                    filter.comparator.try_into()?,
+                    filter.value,
+                )),
                 golem_api_grpc::proto::golem::worker::worker_filter::Filter::Not(filter) => {
                     let filter = *filter.filter.ok_or_else(|| "Missing filter".to_string())?;
                     Ok(WorkerFilter::new_not(filter.try_into()?))
@@ -1822,6 +2521,15 @@ impl From<WorkerFilter> for golem_api_grpc::proto::golem::worker::WorkerFilter {
                     },
                 )
             }
+            WorkerFilter::LastInvokedFunction(WorkerLastInvokedFunctionFilter {
+                comparator,
+                value,
+            }) => golem_api_grpc::proto::golem::worker::worker_filter::Filter::LastInvokedFunction(
+                golem_api_grpc::proto::golem::worker::WorkerLastInvokedFunctionFilter {
+                    comparator: comparator.into(),
+                    value,
+                },
+            ),
         };
 
         golem_api_grpc::proto::golem::worker::WorkerFilter {
@@ -2083,7 +2791,10 @@ impl From<ScanCursor> for Cursor {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Encode, Decode, Serialize, Deserialize)]
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Encode, Decode, Serialize, Deserialize, Enum,
+)]
+#[oai(rename = "WorkerLogLevel")]
 pub enum LogLevel {
     Trace,
     Debug,
@@ -2145,6 +2856,12 @@ pub enum WorkerEvent {
         function: String,
         idempotency_key: IdempotencyKey,
     },
+    /// Chunk of the stream returned as the result of an invocation
+    ResultChunk {
+        timestamp: Timestamp,
+        idempotency_key: IdempotencyKey,
+        bytes: Vec<u8>,
+    },
     Close,
 }
 
@@ -2188,6 +2905,14 @@ impl WorkerEvent {
         }
     }
 
+    pub fn result_chunk(idempotency_key: &IdempotencyKey, bytes: Vec<u8>) -> WorkerEvent {
+        WorkerEvent::ResultChunk {
+            timestamp: Timestamp::now_utc(),
+            idempotency_key: idempotency_key.clone(),
+            bytes,
+        }
+    }
+
     pub fn as_oplog_entry(&self) -> Option<OplogEntry> {
         match self {
             WorkerEvent::StdOut { timestamp, bytes } => Some(OplogEntry::Log {
@@ -2222,9 +2947,51 @@ impl WorkerEvent {
             }),
             WorkerEvent::InvocationStart { .. } => None,
             WorkerEvent::InvocationFinished { .. } => None,
+            WorkerEvent::ResultChunk { .. } => None,
             WorkerEvent::Close => None,
         }
     }
+
+    /// Reconstructs the stdout, stderr or log event persisted as the given oplog entry,
+    /// the inverse of `as_oplog_entry`
+    pub fn from_oplog_entry(entry: &OplogEntry) -> Option<WorkerEvent> {
+        match entry {
+            OplogEntry::Log {
+                timestamp,
+                level,
+                context,
+                message,
+            } => {
+                let level = match level {
+                    oplog::LogLevel::Stdout => {
+                        return Some(WorkerEvent::StdOut {
+                            timestamp: *timestamp,
+                            bytes: message.as_bytes().to_vec(),
+                        })
+                    }
+                    oplog::LogLevel::Stderr => {
+                        return Some(WorkerEvent::StdErr {
+                            timestamp: *timestamp,
+                            bytes: message.as_bytes().to_vec(),
+                        })
+                    }
+                    oplog::LogLevel::Trace => LogLevel::Trace,
+                    oplog::LogLevel::Debug => LogLevel::Debug,
+                    oplog::LogLevel::Info => LogLevel::Info,
+                    oplog::LogLevel::Warn => LogLevel::Warn,
+                    oplog::LogLevel::Error => LogLevel::Error,
+                    oplog::LogLevel::Critical => LogLevel::Critical,
+                };
+                Some(WorkerEvent::Log {
+                    timestamp: *timestamp,
+                    level,
+                    context: context.clone(),
+                    message: message.clone(),
+                })
+            }
+            _ => None,
+        }
+    }
 }
 
 impl Display for WorkerEvent {
@@ -2266,6 +3033,18 @@ impl Display for WorkerEvent {
             } => {
                 write!(f, "<invocation-finished> {} {}", function, idempotency_key)
             }
+            WorkerEvent::ResultChunk {
+                idempotency_key,
+                bytes,
+                ..
+            } => {
+                write!(
+                    f,
+                    "<result-chunk> {} {} bytes",
+                    idempotency_key,
+                    bytes.len()
+                )
+            }
             WorkerEvent::Close => {
                 write!(f, "<close>")
             }
@@ -2396,6 +3175,9 @@ impl TryFrom<WorkerEvent> for golem_api_grpc::proto::golem::worker::LogEvent {
                     },
                 )),
             }),
+            WorkerEvent::ResultChunk { .. } => {
+                Err("Result chunk event is not supported via protobuf".to_string())
+            }
             WorkerEvent::Close => Err("Close event is not supported via protobuf".to_string()),
         }
     }
@@ -2472,21 +3254,28 @@ impl FromStr for ComponentType {
 mod tests {
     use test_r::test;
 
-    use std::collections::HashSet;
+    use std::collections::{BTreeMap, HashSet};
     use std::str::FromStr;
     use std::time::SystemTime;
     use std::vec;
 
     use crate::model::oplog::OplogIndex;
     use crate::model::{
-        AccountId, ComponentId, FilterComparator, IdempotencyKey, ShardId, StringFilterComparator,
-        TargetWorkerId, Timestamp, WorkerFilter, WorkerId, WorkerMetadata, WorkerStatus,
-        WorkerStatusRecord,
+        AccountId, ComponentId, FilterComparator, IdempotencyKey, InvocationPriority,
+        LastInvokedFunction, LogLevel, Pod, RoutingTable, ShardId, StringFilterComparator,
+        TargetWorkerId, Timestamp, WorkerEvent, WorkerFilter, WorkerId, WorkerInvocation,
+        WorkerMetadata, WorkerName, WorkerStatus, WorkerStatusRecord,
     };
+    use crate::serialization::{deserialize, serialize};
     use bincode::{Decode, Encode};
+    use golem_api_grpc::proto::golem::shardmanager::{
+        Pod as GrpcPod, RoutingTable as GrpcRoutingTable,
+        RoutingTableEntry as GrpcRoutingTableEntry,
+    };
     use poem_openapi::types::ToJSON;
     use rand::{thread_rng, Rng};
     use serde::{Deserialize, Serialize};
+    use std::time::Duration;
 
     #[test]
     fn timestamp_conversion() {
@@ -2499,6 +3288,24 @@ mod tests {
         assert_eq!(ts2, ts);
     }
 
+    #[test]
+    fn idempotency_key_from_request_id_is_stable_per_target() {
+        let key = IdempotencyKey::from_request_id("request-1", "component-1/f");
+
+        assert_eq!(
+            key,
+            IdempotencyKey::from_request_id("request-1", "component-1/f")
+        );
+        assert_ne!(
+            key,
+            IdempotencyKey::from_request_id("request-2", "component-1/f")
+        );
+        assert_ne!(
+            key,
+            IdempotencyKey::from_request_id("request-1", "component-1/g")
+        );
+    }
+
     #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Encode, Decode)]
     struct ExampleWithAccountId {
         account_id: AccountId,
@@ -2528,6 +3335,71 @@ mod tests {
         assert_eq!(json, "{\"account_id\":\"account-1\"}");
     }
 
+    #[test]
+    fn worker_id_display_parse_roundtrip() {
+        let worker_id = WorkerId {
+            component_id: ComponentId::try_from("679ae459-8700-41d9-920c-7e2887459c94").unwrap(),
+            worker_name: WorkerName::from_existing("worker-1"),
+        };
+
+        assert_eq!(
+            worker_id.to_string(),
+            "679ae459-8700-41d9-920c-7e2887459c94/worker-1"
+        );
+        assert_eq!(
+            WorkerId::from_str(&worker_id.to_string()).unwrap(),
+            worker_id
+        );
+    }
+
+    #[test]
+    fn worker_id_parse_legacy_and_urn_forms() {
+        let worker_id = WorkerId {
+            component_id: ComponentId::try_from("679ae459-8700-41d9-920c-7e2887459c94").unwrap(),
+            worker_name: WorkerName::from_existing("worker-1"),
+        };
+
+        assert_eq!(
+            WorkerId::from_str("679ae459-8700-41d9-920c-7e2887459c94:worker-1").unwrap(),
+            worker_id
+        );
+        assert_eq!(WorkerId::from_str(&worker_id.uri()).unwrap(), worker_id);
+        assert!(WorkerId::from_str("679ae459-8700-41d9-920c-7e2887459c94").is_err());
+        assert!(WorkerId::from_str("679ae459-8700-41d9-920c-7e2887459c94/").is_err());
+        assert!(WorkerId::from_str("not-a-uuid/worker-1").is_err());
+    }
+
+    #[test]
+    fn worker_name_validation() {
+        assert!(WorkerName::new("worker_1-a").is_ok());
+        assert!(WorkerName::new("").is_err());
+        assert!(WorkerName::new("a".repeat(101)).is_err());
+        assert!(WorkerName::new("worker 1").is_err());
+        assert!(WorkerName::new("worker:1").is_err());
+        assert!(WorkerName::new("worker/1").is_err());
+        assert!(WorkerName::new("-worker").is_err());
+        assert!(WorkerName::new("~worker").is_err());
+        assert!(WorkerName::new("__worker").is_ok());
+    }
+
+    #[test]
+    fn worker_name_escape_roundtrip() {
+        for name in ["order:1", "a/b c", "under_score", "üzlet:ä", "~x"] {
+            let escaped = WorkerName::escape(name);
+            assert!(escaped
+                .strip_prefix('~')
+                .unwrap()
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == '-'));
+            assert_eq!(WorkerName::parse(&escaped).unwrap().as_str(), name);
+        }
+        assert_eq!(WorkerName::escape("order:1"), "~order_3a1");
+        assert_eq!(WorkerName::parse("worker-1").unwrap().as_str(), "worker-1");
+        assert_eq!(WorkerName::parse("__legacy").unwrap().as_str(), "__legacy");
+        assert!(WorkerName::parse("~order_3").is_err());
+        assert!(WorkerName::parse("~").is_err());
+    }
+
     #[test]
     fn worker_filter_parse() {
         assert_eq!(
@@ -2651,7 +3523,7 @@ mod tests {
         let component_id = ComponentId::new_v4();
         let worker_metadata = WorkerMetadata {
             worker_id: WorkerId {
-                worker_name: "worker-1".to_string(),
+                worker_name: WorkerName::from_existing("worker-1"),
                 component_id,
             },
             args: vec![],
@@ -2746,7 +3618,7 @@ mod tests {
             };
 
             let start = SystemTime::now();
-            let worker_id = target_worker_id.into_worker_id(&shard_ids, SHARD_COUNT);
+            let worker_id = target_worker_id.into_worker_id(&shard_ids, SHARD_COUNT, None);
             let end = SystemTime::now();
             println!(
                 "Time with {count} valid shards: {:?}",
@@ -2759,6 +3631,92 @@ mod tests {
         }
     }
 
+    #[test]
+    fn target_worker_id_affinity_hint() {
+        const SHARD_COUNT: usize = 1000;
+        let component_id = ComponentId::new_v4();
+        let other_worker_id = WorkerId {
+            component_id: ComponentId::new_v4(),
+            worker_name: WorkerName::from_existing("other"),
+        };
+        let other_shard_id = ShardId::from_worker_id(&other_worker_id, SHARD_COUNT);
+
+        let worker_id = TargetWorkerId {
+            component_id: component_id.clone(),
+            worker_name: None,
+        }
+        .into_worker_id(
+            &HashSet::new(),
+            SHARD_COUNT,
+            Some(&WorkerPlacementHint::Affinity(other_worker_id.clone())),
+        );
+        assert_eq!(
+            ShardId::from_worker_id(&worker_id, SHARD_COUNT),
+            other_shard_id
+        );
+
+        // Not satisfiable within the forced shards, so it is ignored
+        let forced_shard_id = ShardId::new((other_shard_id.value + 1) % SHARD_COUNT as i64);
+        let worker_id = TargetWorkerId {
+            component_id,
+            worker_name: None,
+        }
+        .into_worker_id(
+            &HashSet::from([forced_shard_id]),
+            SHARD_COUNT,
+            Some(&WorkerPlacementHint::Affinity(other_worker_id)),
+        );
+        assert_eq!(
+            ShardId::from_worker_id(&worker_id, SHARD_COUNT),
+            forced_shard_id
+        );
+    }
+
+    #[test]
+    fn placement_hints_are_recorded_with_their_workers() {
+        let component_id = ComponentId::new_v4();
+        let worker_id = |worker_name: &str| WorkerId {
+            component_id: component_id.clone(),
+            worker_name: WorkerName::from_existing(worker_name),
+        };
+
+        assert_eq!(
+            WorkerPlacementHint::Affinity(worker_id("a")).to_string(),
+            format!("affinity:{}", worker_id("a"))
+        );
+        assert_eq!(
+            WorkerPlacementHint::AntiAffinity(vec![worker_id("a"), worker_id("b")]).to_string(),
+            format!("anti-affinity:{},{}", worker_id("a"), worker_id("b"))
+        );
+    }
+
+    #[test]
+    fn target_worker_id_anti_affinity_hint() {
+        const SHARD_COUNT: usize = 16;
+        let component_id = ComponentId::new_v4();
+        let other_worker_ids: Vec<WorkerId> = (0..8)
+            .map(|idx| WorkerId {
+                component_id: component_id.clone(),
+                worker_name: WorkerName::from_existing(format!("other-{idx}")),
+            })
+            .collect();
+        let avoided_shard_ids: HashSet<ShardId> = other_worker_ids
+            .iter()
+            .map(|worker_id| ShardId::from_worker_id(worker_id, SHARD_COUNT))
+            .collect();
+        let hint = WorkerPlacementHint::AntiAffinity(other_worker_ids);
+
+        for _ in 0..100 {
+            let worker_id = TargetWorkerId {
+                component_id: component_id.clone(),
+                worker_name: None,
+            }
+            .into_worker_id(&HashSet::new(), SHARD_COUNT, Some(&hint));
+            let shard_id = ShardId::from_worker_id(&worker_id, SHARD_COUNT);
+            assert!(!avoided_shard_ids.contains(&shard_id));
+        }
+    }
+
     #[test]
     fn derived_idempotency_key() {
         let base1 = IdempotencyKey::fresh();
@@ -2804,6 +3762,30 @@ mod tests {
         assert_ne!(derived31, derived32);
     }
 
+    #[test]
+    fn derived_batch_item_idempotency_key() {
+        let base1 = IdempotencyKey::fresh();
+        let base2 = IdempotencyKey::fresh();
+
+        let idx1 = OplogIndex::from_u64(2);
+        let idx2 = OplogIndex::from_u64(11);
+
+        let item11a = IdempotencyKey::derived_batch_item(&base1, idx1, 0);
+        let item11b = IdempotencyKey::derived_batch_item(&base1, idx1, 0);
+        let item12 = IdempotencyKey::derived_batch_item(&base1, idx1, 1);
+        let item21 = IdempotencyKey::derived_batch_item(&base1, idx2, 0);
+        let item31 = IdempotencyKey::derived_batch_item(&base2, idx1, 0);
+
+        assert_eq!(item11a, item11b);
+
+        assert_ne!(item11a, item12);
+        assert_ne!(item11a, item21);
+        assert_ne!(item11a, item31);
+
+        assert_ne!(item11a, IdempotencyKey::derived(&base1, idx1));
+        assert_ne!(item12, IdempotencyKey::derived(&base1, idx1));
+    }
+
     #[test]
     fn worker_status_serialization_poem_serde_equivalence() {
         let status = WorkerStatus::Retrying;
@@ -2819,4 +3801,265 @@ mod tests {
         let deserialized: IdempotencyKey = serde_json::from_str(&serialized).unwrap();
         assert_eq!(key, deserialized);
     }
+
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    enum LegacyWorkerInvocation {
+        ExportedFunction {
+            idempotency_key: IdempotencyKey,
+            full_function_name: String,
+            function_input: Vec<golem_wasm_rpc::Value>,
+        },
+        #[allow(dead_code)]
+        ManualUpdate { target_version: u64 },
+    }
+
+    #[test]
+    fn worker_invocation_normal_priority_keeps_legacy_encoding() {
+        let idempotency_key = IdempotencyKey::fresh();
+        let invocation = WorkerInvocation::ExportedFunction {
+            idempotency_key: idempotency_key.clone(),
+            full_function_name: "golem:it/api.{f}".to_string(),
+            function_input: vec![golem_wasm_rpc::Value::U32(1)],
+            priority: InvocationPriority::Normal,
+            context: BTreeMap::new(),
+        };
+        let legacy = LegacyWorkerInvocation::ExportedFunction {
+            idempotency_key,
+            full_function_name: "golem:it/api.{f}".to_string(),
+            function_input: vec![golem_wasm_rpc::Value::U32(1)],
+        };
+
+        let serialized = serialize(&invocation).unwrap();
+        assert_eq!(serialized, serialize(&legacy).unwrap());
+
+        let deserialized: WorkerInvocation = deserialize(&serialized).unwrap();
+        assert_eq!(deserialized, invocation);
+    }
+
+    #[test]
+    fn worker_status_record_roundtrip() {
+        let record = WorkerStatusRecord {
+            last_invoked_function: Some(LastInvokedFunction {
+                function_name: "golem:it/api.{f}".to_string(),
+                timestamp: Timestamp::from(1724701938466),
+            }),
+            changed_environment: Some((
+                vec!["arg".to_string()],
+                vec![("KEY".to_string(), "value".to_string())],
+            )),
+            oplog_idx: OplogIndex::from_u64(10),
+            ..WorkerStatusRecord::default()
+        };
+        let serialized = serialize(&record).unwrap();
+        let deserialized: WorkerStatusRecord = deserialize(&serialized).unwrap();
+        assert_eq!(deserialized, record);
+    }
+
+    #[test]
+    fn worker_status_record_without_changed_environment_decodes() {
+        let record = WorkerStatusRecord::default();
+        let serialized = serialize(&record).unwrap();
+        // Dropping the encoded `None`s gives the encoding of records stored before the fields
+        // existed
+        let legacy = &serialized[..serialized.len() - 2];
+        let deserialized: WorkerStatusRecord = deserialize(legacy).unwrap();
+        assert_eq!(deserialized, record);
+
+        // Records stored with the last invoked function but before the changed environment
+        let with_last_invoked_function = WorkerStatusRecord {
+            last_invoked_function: Some(LastInvokedFunction {
+                function_name: "golem:it/api.{f}".to_string(),
+                timestamp: Timestamp::from(1724701938466),
+            }),
+            ..WorkerStatusRecord::default()
+        };
+        let serialized = serialize(&with_last_invoked_function).unwrap();
+        let legacy = &serialized[..serialized.len() - 1];
+        let deserialized: WorkerStatusRecord = deserialize(legacy).unwrap();
+        assert_eq!(deserialized, with_last_invoked_function);
+    }
+
+    #[test]
+    fn worker_invocation_with_priority_roundtrip() {
+        for priority in [InvocationPriority::High, InvocationPriority::Low] {
+            let invocation = WorkerInvocation::ExportedFunction {
+                idempotency_key: IdempotencyKey::fresh(),
+                full_function_name: "golem:it/api.{f}".to_string(),
+                function_input: vec![],
+                priority,
+                context: BTreeMap::new(),
+            };
+            let serialized = serialize(&invocation).unwrap();
+            let deserialized: WorkerInvocation = deserialize(&serialized).unwrap();
+            assert_eq!(deserialized, invocation);
+            assert_eq!(deserialized.priority(), priority);
+        }
+    }
+
+    #[test]
+    fn worker_event_oplog_entry_roundtrip() {
+        let events = vec![
+            WorkerEvent::stdout("hello\n".as_bytes().to_vec()),
+            WorkerEvent::stderr("oops\n".as_bytes().to_vec()),
+            WorkerEvent::log(LogLevel::Warn, "ctx", "message"),
+        ];
+
+        for event in events {
+            let entry = event.as_oplog_entry().unwrap();
+            assert_eq!(WorkerEvent::from_oplog_entry(&entry), Some(event));
+        }
+
+        let invocation_start =
+            WorkerEvent::invocation_start("f", &IdempotencyKey::fresh()).as_oplog_entry();
+        assert_eq!(invocation_start, None);
+    }
+
+    #[test]
+    fn worker_invocation_with_context_roundtrip() {
+        for priority in [InvocationPriority::Normal, InvocationPriority::High] {
+            let invocation = WorkerInvocation::ExportedFunction {
+                idempotency_key: IdempotencyKey::fresh(),
+                full_function_name: "golem:it/api.{f}".to_string(),
+                function_input: vec![],
+                priority,
+                context: BTreeMap::from([
+                    ("locale".to_string(), "en-US".to_string()),
+                    ("tenant".to_string(), "acme".to_string()),
+                ]),
+            };
+            let serialized = serialize(&invocation).unwrap();
+            let deserialized: WorkerInvocation = deserialize(&serialized).unwrap();
+            assert_eq!(deserialized, invocation);
+        }
+    }
+
+    fn routing_table(pods: &[u32], number_of_shards: u32) -> RoutingTable {
+        routing_table_with_zones(pods, &[], number_of_shards)
+    }
+
+    fn routing_table_with_zones(
+        pods: &[u32],
+        zones: &[&str],
+        number_of_shards: u32,
+    ) -> RoutingTable {
+        GrpcRoutingTable {
+            number_of_shards,
+            shard_assignments: (0..number_of_shards)
+                .map(|shard| GrpcRoutingTableEntry {
+                    shard_id: Some(ShardId::new(shard as i64).into()),
+                    pod: Some(GrpcPod {
+                        host: "localhost".to_string(),
+                        port: pods[shard as usize % pods.len()],
+                        pod_name: None,
+                        zone: zones
+                            .get(shard as usize % pods.len())
+                            .map(|zone| zone.to_string()),
+                    }),
+                })
+                .collect(),
+        }
+        .into()
+    }
+
+    #[test]
+    fn lookup_read_prefers_non_owner_pod() {
+        let routing_table = routing_table(&[9000, 9001, 9002], 10);
+        let worker_id = WorkerId {
+            component_id: ComponentId::new_v4(),
+            worker_name: WorkerName::from_existing("worker"),
+        };
+        let primary = routing_table.lookup(&worker_id).unwrap().clone();
+
+        for _ in 0..10 {
+            let pod = routing_table
+                .lookup_read(&worker_id, Duration::from_secs(60))
+                .unwrap();
+            assert_ne!(pod, &primary);
+        }
+    }
+
+    #[test]
+    fn lookup_read_falls_back_to_owner_with_single_pod() {
+        let routing_table = routing_table(&[9000], 10);
+        let worker_id = WorkerId {
+            component_id: ComponentId::new_v4(),
+            worker_name: WorkerName::from_existing("worker"),
+        };
+
+        let pod = routing_table.lookup_read(&worker_id, Duration::from_secs(60));
+        assert_eq!(
+            pod,
+            Some(&Pod {
+                host: "localhost".to_string(),
+                port: 9000,
+                zone: None
+            })
+        );
+    }
+
+    #[test]
+    fn lookup_read_rejects_stale_routing_table() {
+        let routing_table = routing_table(&[9000, 9001], 10);
+        let worker_id = WorkerId {
+            component_id: ComponentId::new_v4(),
+            worker_name: WorkerName::from_existing("worker"),
+        };
+
+        std::thread::sleep(Duration::from_millis(10));
+        let pod = routing_table.lookup_read(&worker_id, Duration::from_millis(1));
+        assert_eq!(pod, None);
+    }
+
+    #[test]
+    fn lookup_read_in_zone_prefers_same_zone_pods() {
+        let routing_table =
+            routing_table_with_zones(&[9000, 9001, 9002, 9003], &["a", "b", "a", "b"], 16);
+        let worker_id = WorkerId {
+            component_id: ComponentId::new_v4(),
+            worker_name: WorkerName::from_existing("worker"),
+        };
+
+        for _ in 0..10 {
+            let pod = routing_table
+                .lookup_read_in_zone(&worker_id, Duration::from_secs(60), "b")
+                .unwrap();
+            assert_eq!(pod.zone(), Some("b"));
+        }
+    }
+
+    #[test]
+    fn lookup_read_in_zone_falls_back_to_other_zones() {
+        let routing_table = routing_table_with_zones(&[9000, 9001], &["a", "a"], 10);
+        let worker_id = WorkerId {
+            component_id: ComponentId::new_v4(),
+            worker_name: WorkerName::from_existing("worker"),
+        };
+
+        let pod = routing_table.lookup_read_in_zone(&worker_id, Duration::from_secs(60), "b");
+        assert_eq!(pod.and_then(|pod| pod.zone()), Some("a"));
+    }
+
+    #[test]
+    fn random_except_skips_excluded_pods() {
+        let routing_table = routing_table(&[9000, 9001, 9002], 10);
+        let busy: HashSet<Pod> = routing_table
+            .all()
+            .into_iter()
+            .filter(|pod| pod.port != 9001)
+            .cloned()
+            .collect();
+
+        for _ in 0..10 {
+            let pod = routing_table.random_except(&busy).unwrap();
+            assert_eq!(pod.port, 9001);
+        }
+    }
+
+    #[test]
+    fn random_except_falls_back_to_excluded_pods() {
+        let routing_table = routing_table(&[9000, 9001], 10);
+        let busy: HashSet<Pod> = routing_table.all().into_iter().cloned().collect();
+
+        assert!(routing_table.random_except(&busy).is_some());
+    }
 }
@@ -23,6 +23,7 @@ use golem_wasm_ast::analysis::AnalysedType;
 use golem_wasm_rpc::{IntoValue, Value};
 use poem_openapi::{Enum, NewType};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter};
 use std::sync::atomic::AtomicU64;
 use std::sync::Arc;
@@ -33,6 +34,7 @@ use crate::model::regions::OplogRegion;
 use crate::model::{
     AccountId, ComponentVersion, IdempotencyKey, Timestamp, WorkerId, WorkerInvocation,
 };
+use crate::serialization::{try_deserialize, SERIALIZATION_VERSION_V2};
 
 #[derive(
     Debug,
@@ -404,6 +406,48 @@ pub enum OplogEntry {
         response: OplogPayload,
         wrapped_function_type: WrappedFunctionType,
     },
+    /// Snapshot of the worker's state saved by its `save-snapshot` export. Recovery can load the
+    /// latest snapshot and replay only the entries following it
+    Snapshot {
+        timestamp: Timestamp,
+        component_version: ComponentVersion,
+        payload: OplogPayload,
+    },
+    /// An outgoing HTTP request was denied by the component's network egress policy. `rule` is
+    /// the matching deny rule, or `None` if the host matched none of the allow rules
+    NetworkEgressDenied {
+        timestamp: Timestamp,
+        host: String,
+        rule: Option<String>,
+    },
+    /// A failed worker was revived. The entries of `trimmed_region`, typically the failed
+    /// invocation, are deleted, and like jumps the entry itself is skipped on replay
+    Revive {
+        timestamp: Timestamp,
+        trimmed_region: Option<OplogRegion>,
+    },
+    /// Changes the worker's command line arguments and environment variables, visible to the
+    /// worker from its next invocation. Replayed invocations get the values recorded by their
+    /// host calls, so the entry itself is only a hint.
+    ChangeEnvironment {
+        timestamp: Timestamp,
+        args: Vec<String>,
+        env: Vec<(String, String)>,
+    },
+    /// The context passed by the caller with the invocation started by the preceding
+    /// `ExportedFunctionInvoked` entry. Replayed invocations get the context recorded by their
+    /// host calls, so the entry itself is only a hint.
+    InvocationContext {
+        timestamp: Timestamp,
+        context: BTreeMap<String, String>,
+    },
+    /// The worker was forked from the state `source_worker_id` had at `source_oplog_index`. The
+    /// entries preceding it were copied from the oplog of the source worker.
+    Forked {
+        timestamp: Timestamp,
+        source_worker_id: WorkerId,
+        source_oplog_index: OplogIndex,
+    },
 }
 
 impl OplogEntry {
@@ -581,6 +625,52 @@ impl OplogEntry {
         }
     }
 
+    pub fn snapshot(component_version: ComponentVersion, payload: OplogPayload) -> OplogEntry {
+        OplogEntry::Snapshot {
+            timestamp: Timestamp::now_utc(),
+            component_version,
+            payload,
+        }
+    }
+
+    pub fn network_egress_denied(host: String, rule: Option<String>) -> OplogEntry {
+        OplogEntry::NetworkEgressDenied {
+            timestamp: Timestamp::now_utc(),
+            host,
+            rule,
+        }
+    }
+
+    pub fn revive(trimmed_region: Option<OplogRegion>) -> OplogEntry {
+        OplogEntry::Revive {
+            timestamp: Timestamp::now_utc(),
+            trimmed_region,
+        }
+    }
+
+    pub fn change_environment(args: Vec<String>, env: Vec<(String, String)>) -> OplogEntry {
+        OplogEntry::ChangeEnvironment {
+            timestamp: Timestamp::now_utc(),
+            args,
+            env,
+        }
+    }
+
+    pub fn invocation_context(context: BTreeMap<String, String>) -> OplogEntry {
+        OplogEntry::InvocationContext {
+            timestamp: Timestamp::now_utc(),
+            context,
+        }
+    }
+
+    pub fn forked(source_worker_id: WorkerId, source_oplog_index: OplogIndex) -> OplogEntry {
+        OplogEntry::Forked {
+            timestamp: Timestamp::now_utc(),
+            source_worker_id,
+            source_oplog_index,
+        }
+    }
+
     pub fn is_end_atomic_region(&self, idx: OplogIndex) -> bool {
         matches!(self, OplogEntry::EndAtomicRegion { begin_index, .. } if *begin_index == idx)
     }
@@ -630,6 +720,11 @@ impl OplogEntry {
                 | OplogEntry::DescribeResource { .. }
                 | OplogEntry::Log { .. }
                 | OplogEntry::Restart { .. }
+                | OplogEntry::Snapshot { .. }
+                | OplogEntry::NetworkEgressDenied { .. }
+                | OplogEntry::ChangeEnvironment { .. }
+                | OplogEntry::InvocationContext { .. }
+                | OplogEntry::Forked { .. }
         )
     }
 
@@ -660,11 +755,51 @@ impl OplogEntry {
             | OplogEntry::DescribeResource { timestamp, .. }
             | OplogEntry::Log { timestamp, .. }
             | OplogEntry::Restart { timestamp }
-            | OplogEntry::ImportedFunctionInvoked { timestamp, .. } => *timestamp,
+            | OplogEntry::ImportedFunctionInvoked { timestamp, .. }
+            | OplogEntry::Snapshot { timestamp, .. }
+            | OplogEntry::NetworkEgressDenied { timestamp, .. }
+            | OplogEntry::Revive { timestamp, .. }
+            | OplogEntry::ChangeEnvironment { timestamp, .. }
+            | OplogEntry::InvocationContext { timestamp, .. }
+            | OplogEntry::Forked { timestamp, .. } => *timestamp,
         }
     }
 }
 
+/// The beginning of every encoded `OplogEntry`: the index of its variant and its timestamp.
+///
+/// Can be decoded from the raw bytes of a stored entry without decoding the rest of it, which is
+/// useful when only a few entries of a long oplog are needed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Decode)]
+pub struct OplogEntryHeader {
+    pub variant: u32,
+    pub timestamp: Timestamp,
+}
+
+impl OplogEntryHeader {
+    pub const CREATE: u32 = 0;
+    pub const EXPORTED_FUNCTION_INVOKED: u32 = 2;
+    pub const SUCCESSFUL_UPDATE: u32 = 17;
+
+    /// Decodes the header of a serialized oplog entry, starting with its serialization version
+    pub fn decode(bytes: &[u8]) -> Result<Self, String> {
+        let version = bytes.first().copied().unwrap_or_default();
+        if version == SERIALIZATION_VERSION_V2 {
+            try_deserialize(bytes)?
+                .ok_or_else(|| format!("Unknown serialization version: {version}"))
+        } else {
+            Err(format!(
+                "Unsupported serialization version of oplog entry header: {version}"
+            ))
+        }
+    }
+
+    /// True if the entry may change the component version of the worker
+    pub fn changes_component_version(&self) -> bool {
+        self.variant == Self::CREATE || self.variant == Self::SUCCESSFUL_UPDATE
+    }
+}
+
 /// Describes a pending update
 #[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
 pub enum UpdateDescription {
@@ -704,6 +839,12 @@ pub enum OplogPayload {
         payload_id: PayloadId,
         md5_hash: Vec<u8>,
     },
+
+    /// The wrapped payload is encrypted with the owning account's data key
+    Encrypted {
+        nonce: Vec<u8>,
+        payload: Box<OplogPayload>,
+    },
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
@@ -734,6 +875,21 @@ pub enum WorkerError {
     InvalidRequest(String),
     StackOverflow,
     OutOfMemory,
+    /// The worker tried to grow its linear memory over the configured limit
+    MemoryLimitExceeded {
+        limit: u64,
+    },
+    /// An exported function call consumed more fuel than the budget of the worker's component
+    FuelBudgetExceeded {
+        budget: u64,
+    },
+    /// Replaying the oplog diverged from the recorded history: the side effect recorded at
+    /// `oplog_index` was `expected`, but the worker attempted `actual`
+    ReplayDiverged {
+        oplog_index: OplogIndex,
+        expected: String,
+        actual: String,
+    },
 }
 
 impl WorkerError {
@@ -748,6 +904,19 @@ impl WorkerError {
             WorkerError::InvalidRequest(message) => format!("{message}{error_logs}"),
             WorkerError::StackOverflow => format!("Stack overflow{error_logs}"),
             WorkerError::OutOfMemory => format!("Out of memory{error_logs}"),
+            WorkerError::MemoryLimitExceeded { limit } => {
+                format!("Exceeded the linear memory limit of {limit} bytes{error_logs}")
+            }
+            WorkerError::FuelBudgetExceeded { budget } => {
+                format!("Exceeded the invocation fuel budget of {budget}{error_logs}")
+            }
+            WorkerError::ReplayDiverged {
+                oplog_index,
+                expected,
+                actual,
+            } => {
+                format!("Replay diverged at oplog index {oplog_index}: expected {expected}, got {actual}{error_logs}")
+            }
         }
     }
 }
@@ -255,6 +255,45 @@ pub struct LogParameters {
     pub message: String,
 }
 
+#[derive(Clone, Debug, Serialize, PartialEq, Deserialize, Object)]
+pub struct SnapshotParameters {
+    pub timestamp: Timestamp,
+    pub component_version: ComponentVersion,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq, Deserialize, Object)]
+pub struct NetworkEgressDeniedParameters {
+    pub timestamp: Timestamp,
+    pub host: String,
+    pub rule: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq, Deserialize, Object)]
+pub struct ReviveParameters {
+    pub timestamp: Timestamp,
+    pub trimmed_region: Option<OplogRegion>,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq, Deserialize, Object)]
+pub struct ChangeEnvironmentParameters {
+    pub timestamp: Timestamp,
+    pub args: Vec<String>,
+    pub env: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq, Deserialize, Object)]
+pub struct InvocationContextParameters {
+    pub timestamp: Timestamp,
+    pub context: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq, Deserialize, Object)]
+pub struct ForkedParameters {
+    pub timestamp: Timestamp,
+    pub source_worker_id: WorkerId,
+    pub source_oplog_index: OplogIndex,
+}
+
 /// A mirror of the core `OplogEntry` type, without the undefined arbitrary payloads.
 ///
 /// Instead, it encodes all payloads with wasm-rpc `Value` types. This makes this the base type
@@ -326,6 +365,18 @@ pub enum PublicOplogEntry {
     Log(LogParameters),
     /// Marks the point where the worker was restarted from clean initial state
     Restart(TimestampParameter),
+    /// The worker's state was saved as a snapshot to speed up its recovery
+    Snapshot(SnapshotParameters),
+    /// An outgoing HTTP request was denied by the component's network egress policy
+    NetworkEgressDenied(NetworkEgressDeniedParameters),
+    /// A failed worker was revived, optionally deleting its failed invocation
+    Revive(ReviveParameters),
+    /// The worker's arguments and environment variables were changed
+    ChangeEnvironment(ChangeEnvironmentParameters),
+    /// The context passed by the caller with the started invocation
+    InvocationContext(InvocationContextParameters),
+    /// The worker was forked from the state of another worker
+    Forked(ForkedParameters),
 }
 
 impl TryFrom<golem_api_grpc::proto::golem::worker::OplogEntry> for PublicOplogEntry {
@@ -578,6 +629,56 @@ impl TryFrom<golem_api_grpc::proto::golem::worker::OplogEntry> for PublicOplogEn
                     timestamp: restart.timestamp.ok_or("Missing timestamp field")?.into(),
                 }))
             }
+            oplog_entry::Entry::Snapshot(snapshot) => {
+                Ok(PublicOplogEntry::Snapshot(SnapshotParameters {
+                    timestamp: snapshot.timestamp.ok_or("Missing timestamp field")?.into(),
+                    component_version: snapshot.component_version,
+                }))
+            }
+            oplog_entry::Entry::NetworkEgressDenied(denied) => Ok(
+                PublicOplogEntry::NetworkEgressDenied(NetworkEgressDeniedParameters {
+                    timestamp: denied.timestamp.ok_or("Missing timestamp field")?.into(),
+                    host: denied.host,
+                    rule: denied.rule,
+                }),
+            ),
+            oplog_entry::Entry::Revive(revive) => Ok(PublicOplogEntry::Revive(ReviveParameters {
+                timestamp: revive.timestamp.ok_or("Missing timestamp field")?.into(),
+                trimmed_region: match (revive.trimmed_start, revive.trimmed_end) {
+                    (Some(start), Some(end)) => Some(OplogRegion {
+                        start: OplogIndex::from_u64(start),
+                        end: OplogIndex::from_u64(end),
+                    }),
+                    _ => None,
+                },
+            })),
+            oplog_entry::Entry::ChangeEnvironment(change_environment) => Ok(
+                PublicOplogEntry::ChangeEnvironment(ChangeEnvironmentParameters {
+                    timestamp: change_environment
+                        .timestamp
+                        .ok_or("Missing timestamp field")?
+                        .into(),
+                    args: change_environment.args,
+                    env: change_environment.env.into_iter().collect(),
+                }),
+            ),
+            oplog_entry::Entry::InvocationContext(invocation_context) => Ok(
+                PublicOplogEntry::InvocationContext(InvocationContextParameters {
+                    timestamp: invocation_context
+                        .timestamp
+                        .ok_or("Missing timestamp field")?
+                        .into(),
+                    context: invocation_context.context.into_iter().collect(),
+                }),
+            ),
+            oplog_entry::Entry::Forked(forked) => Ok(PublicOplogEntry::Forked(ForkedParameters {
+                timestamp: forked.timestamp.ok_or("Missing timestamp field")?.into(),
+                source_worker_id: forked
+                    .source_worker_id
+                    .ok_or("Missing source_worker_id field")?
+                    .try_into()?,
+                source_oplog_index: OplogIndex::from_u64(forked.source_oplog_index),
+            })),
         }
     }
 }
@@ -873,6 +974,69 @@ impl TryFrom<PublicOplogEntry> for golem_api_grpc::proto::golem::worker::OplogEn
                     )),
                 }
             }
+            PublicOplogEntry::Snapshot(snapshot) => {
+                golem_api_grpc::proto::golem::worker::OplogEntry {
+                    entry: Some(oplog_entry::Entry::Snapshot(
+                        golem_api_grpc::proto::golem::worker::SnapshotParameters {
+                            timestamp: Some(snapshot.timestamp.into()),
+                            component_version: snapshot.component_version,
+                        },
+                    )),
+                }
+            }
+            PublicOplogEntry::NetworkEgressDenied(denied) => {
+                golem_api_grpc::proto::golem::worker::OplogEntry {
+                    entry: Some(oplog_entry::Entry::NetworkEgressDenied(
+                        golem_api_grpc::proto::golem::worker::NetworkEgressDeniedParameters {
+                            timestamp: Some(denied.timestamp.into()),
+                            host: denied.host,
+                            rule: denied.rule,
+                        },
+                    )),
+                }
+            }
+            PublicOplogEntry::Revive(revive) => golem_api_grpc::proto::golem::worker::OplogEntry {
+                entry: Some(oplog_entry::Entry::Revive(
+                    golem_api_grpc::proto::golem::worker::ReviveParameters {
+                        timestamp: Some(revive.timestamp.into()),
+                        trimmed_start: revive
+                            .trimmed_region
+                            .as_ref()
+                            .map(|region| region.start.into()),
+                        trimmed_end: revive.trimmed_region.map(|region| region.end.into()),
+                    },
+                )),
+            },
+            PublicOplogEntry::ChangeEnvironment(change_environment) => {
+                golem_api_grpc::proto::golem::worker::OplogEntry {
+                    entry: Some(oplog_entry::Entry::ChangeEnvironment(
+                        golem_api_grpc::proto::golem::worker::ChangeEnvironmentParameters {
+                            timestamp: Some(change_environment.timestamp.into()),
+                            args: change_environment.args,
+                            env: change_environment.env.into_iter().collect(),
+                        },
+                    )),
+                }
+            }
+            PublicOplogEntry::InvocationContext(invocation_context) => {
+                golem_api_grpc::proto::golem::worker::OplogEntry {
+                    entry: Some(oplog_entry::Entry::InvocationContext(
+                        golem_api_grpc::proto::golem::worker::InvocationContextParameters {
+                            timestamp: Some(invocation_context.timestamp.into()),
+                            context: invocation_context.context.into_iter().collect(),
+                        },
+                    )),
+                }
+            }
+            PublicOplogEntry::Forked(forked) => golem_api_grpc::proto::golem::worker::OplogEntry {
+                entry: Some(oplog_entry::Entry::Forked(
+                    golem_api_grpc::proto::golem::worker::ForkedParameters {
+                        timestamp: Some(forked.timestamp.into()),
+                        source_worker_id: Some(forked.source_worker_id.into()),
+                        source_oplog_index: forked.source_oplog_index.into(),
+                    },
+                )),
+            },
         })
     }
 }
@@ -1179,18 +1343,20 @@ impl From<OplogCursor> for golem_api_grpc::proto::golem::worker::OplogCursor {
 mod tests {
 
     use super::{
-        ChangeRetryPolicyParameters, CreateParameters, DescribeResourceParameters, Empty,
-        EndRegionParameters, ErrorParameters, ExportedFunctionCompletedParameters,
-        ExportedFunctionInvokedParameters, ExportedFunctionParameters, FailedUpdateParameters,
-        GrowMemoryParameters, ImportedFunctionInvokedParameters, JumpParameters, LogParameters,
-        PendingUpdateParameters, PendingWorkerInvocationParameters, PublicOplogEntry,
-        PublicRetryConfig, PublicUpdateDescription, PublicWorkerInvocation,
-        PublicWrappedFunctionType, ResourceParameters, SnapshotBasedUpdateParameters,
+        ChangeEnvironmentParameters, ChangeRetryPolicyParameters, CreateParameters,
+        DescribeResourceParameters, Empty, EndRegionParameters, ErrorParameters,
+        ExportedFunctionCompletedParameters, ExportedFunctionInvokedParameters,
+        ExportedFunctionParameters, FailedUpdateParameters, ForkedParameters, GrowMemoryParameters,
+        ImportedFunctionInvokedParameters, InvocationContextParameters, JumpParameters,
+        LogParameters, NetworkEgressDeniedParameters, PendingUpdateParameters,
+        PendingWorkerInvocationParameters, PublicOplogEntry, PublicRetryConfig,
+        PublicUpdateDescription, PublicWorkerInvocation, PublicWrappedFunctionType,
+        ResourceParameters, ReviveParameters, SnapshotBasedUpdateParameters, SnapshotParameters,
         SuccessfulUpdateParameters, TimestampParameter,
     };
     use crate::model::oplog::{LogLevel, OplogIndex, WorkerResourceId};
     use crate::model::regions::OplogRegion;
-    use crate::model::{AccountId, ComponentId, IdempotencyKey, Timestamp, WorkerId};
+    use crate::model::{AccountId, ComponentId, IdempotencyKey, Timestamp, WorkerId, WorkerName};
     use golem_wasm_ast::analysis::analysed_type::{field, list, r#enum, record, s16, str, u64};
     use golem_wasm_rpc::{Value, ValueAndType};
     use poem_openapi::types::ToJSON;
@@ -1209,7 +1375,7 @@ mod tests {
                 component_id: ComponentId(
                     Uuid::parse_str("13A5C8D4-F05E-4E23-B982-F4D413E181CB").unwrap(),
                 ),
-                worker_name: "test1".to_string(),
+                worker_name: WorkerName::from_existing("test1"),
             },
             component_version: 1,
             args: vec!["a".to_string(), "b".to_string()],
@@ -1223,7 +1389,7 @@ mod tests {
                 component_id: ComponentId(
                     Uuid::parse_str("13A5C8D4-F05E-4E23-B982-F4D413E181CB").unwrap(),
                 ),
-                worker_name: "test2".to_string(),
+                worker_name: WorkerName::from_existing("test2"),
             }),
             component_size: 100_000_000,
             initial_total_linear_memory_size: 200_000_000,
@@ -1581,4 +1747,85 @@ mod tests {
         let deserialized: PublicOplogEntry = serde_json::from_str(&serialized).unwrap();
         assert_eq!(entry, deserialized);
     }
+
+    #[test]
+    fn snapshot_serialization_poem_serde_equivalence() {
+        let entry = PublicOplogEntry::Snapshot(SnapshotParameters {
+            timestamp: rounded_ts(Timestamp::now_utc()),
+            component_version: 3,
+        });
+        let serialized = entry.to_json_string();
+        let deserialized: PublicOplogEntry = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(entry, deserialized);
+    }
+
+    #[test]
+    fn network_egress_denied_serialization_poem_serde_equivalence() {
+        let entry = PublicOplogEntry::NetworkEgressDenied(NetworkEgressDeniedParameters {
+            timestamp: rounded_ts(Timestamp::now_utc()),
+            host: "internal.example.com".to_string(),
+            rule: Some("*.example.com".to_string()),
+        });
+        let serialized = entry.to_json_string();
+        let deserialized: PublicOplogEntry = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(entry, deserialized);
+    }
+
+    #[test]
+    fn change_environment_serialization_poem_serde_equivalence() {
+        let entry = PublicOplogEntry::ChangeEnvironment(ChangeEnvironmentParameters {
+            timestamp: rounded_ts(Timestamp::now_utc()),
+            args: vec!["--verbose".to_string()],
+            env: vec![("API_KEY".to_string(), "rotated".to_string())]
+                .into_iter()
+                .collect(),
+        });
+        let serialized = entry.to_json_string();
+        let deserialized: PublicOplogEntry = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(entry, deserialized);
+    }
+
+    #[test]
+    fn invocation_context_serialization_poem_serde_equivalence() {
+        let entry = PublicOplogEntry::InvocationContext(InvocationContextParameters {
+            timestamp: rounded_ts(Timestamp::now_utc()),
+            context: vec![("tenant".to_string(), "acme".to_string())]
+                .into_iter()
+                .collect(),
+        });
+        let serialized = entry.to_json_string();
+        let deserialized: PublicOplogEntry = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(entry, deserialized);
+    }
+
+    #[test]
+    fn forked_serialization_poem_serde_equivalence() {
+        let entry = PublicOplogEntry::Forked(ForkedParameters {
+            timestamp: rounded_ts(Timestamp::now_utc()),
+            source_worker_id: WorkerId {
+                component_id: ComponentId(
+                    Uuid::parse_str("13A5C8D4-F05E-4E23-B982-F4D413E181CB").unwrap(),
+                ),
+                worker_name: WorkerName::from_existing("test1"),
+            },
+            source_oplog_index: OplogIndex::from_u64(12),
+        });
+        let serialized = entry.to_json_string();
+        let deserialized: PublicOplogEntry = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(entry, deserialized);
+    }
+
+    #[test]
+    fn revive_serialization_poem_serde_equivalence() {
+        let entry = PublicOplogEntry::Revive(ReviveParameters {
+            timestamp: rounded_ts(Timestamp::now_utc()),
+            trimmed_region: Some(OplogRegion {
+                start: OplogIndex::from_u64(5),
+                end: OplogIndex::from_u64(9),
+            }),
+        });
+        let serialized = entry.to_json_string();
+        let deserialized: PublicOplogEntry = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(entry, deserialized);
+    }
 }
@@ -0,0 +1,451 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::model::oplog::{IndexedResourceKey, OplogIndex};
+use crate::model::public_oplog::PublicRetryConfig;
+use crate::model::regions::{CorruptedOplogRegion, OplogRegion};
+use crate::model::{
+    ComponentVersion, IdempotencyKey, Timestamp, TimestampedWorkerInvocation, WorkerInvocation,
+    WorkerStatus, WorkerStatusRecord,
+};
+use golem_api_grpc::proto::golem::worker::update_record;
+use poem_openapi::Object;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Version of the schema of `PublicWorkerStatusRecord`.
+///
+/// It is incremented whenever a field is removed or its meaning changes. New fields can be
+/// added without changing it.
+pub const PUBLIC_WORKER_STATUS_SCHEMA_VERSION: u32 = 1;
+
+/// Status of a worker as last recorded by its executor
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct PublicWorkerStatusRecord {
+    /// Version of the schema of this record
+    pub schema_version: u32,
+    pub status: WorkerStatus,
+    pub component_version: ComponentVersion,
+    pub component_size: u64,
+    pub total_linear_memory_size: u64,
+    /// Index of the last oplog entry the record reflects
+    pub oplog_index: OplogIndex,
+    /// Idempotency key of the invocation currently being processed
+    pub current_idempotency_key: Option<IdempotencyKey>,
+    /// Retry policy set by the worker, overriding the one of the executor
+    pub overridden_retry_config: Option<PublicRetryConfig>,
+    pub pending_invocation_count: u64,
+    /// Number of finished invocations whose results are kept for their idempotency keys
+    pub invocation_result_count: u64,
+    pub pending_updates: Vec<PublicPendingUpdate>,
+    pub successful_updates: Vec<PublicSuccessfulUpdate>,
+    pub failed_updates: Vec<PublicFailedUpdate>,
+    pub deleted_regions: PublicDeletedRegions,
+    pub owned_resources: Vec<PublicOwnedResource>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct PublicPendingUpdate {
+    pub timestamp: Timestamp,
+    pub target_version: ComponentVersion,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct PublicSuccessfulUpdate {
+    pub timestamp: Timestamp,
+    pub target_version: ComponentVersion,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct PublicFailedUpdate {
+    pub timestamp: Timestamp,
+    pub target_version: ComponentVersion,
+    pub details: Option<String>,
+}
+
+/// Oplog regions skipped when the worker is recovered
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct PublicDeletedRegions {
+    pub regions: Vec<OplogRegion>,
+    /// Total number of oplog entries in the deleted regions
+    pub deleted_entry_count: u64,
+    /// Regions quarantined because their entries could not be decoded
+    pub corrupted_regions: Vec<CorruptedOplogRegion>,
+}
+
+impl PublicDeletedRegions {
+    fn new(regions: Vec<OplogRegion>, corrupted_regions: Vec<CorruptedOplogRegion>) -> Self {
+        let deleted_entry_count = regions
+            .iter()
+            .map(|region| {
+                let range = region.to_range();
+                (range.end() + 1).saturating_sub(*range.start())
+            })
+            .sum();
+
+        Self {
+            regions,
+            deleted_entry_count,
+            corrupted_regions,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct PublicOwnedResource {
+    pub id: u64,
+    pub created_at: Timestamp,
+    pub indexed: Option<PublicIndexedResourceKey>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct PublicIndexedResourceKey {
+    pub resource_name: String,
+    pub resource_params: Vec<String>,
+}
+
+impl From<IndexedResourceKey> for PublicIndexedResourceKey {
+    fn from(value: IndexedResourceKey) -> Self {
+        Self {
+            resource_name: value.resource_name,
+            resource_params: value.resource_params,
+        }
+    }
+}
+
+impl From<WorkerStatusRecord> for PublicWorkerStatusRecord {
+    fn from(value: WorkerStatusRecord) -> Self {
+        // Manual updates are enqueued as invocations until the worker gets to process them
+        let mut pending_updates = value
+            .pending_invocations
+            .iter()
+            .filter_map(|pending_invocation| match pending_invocation {
+                TimestampedWorkerInvocation {
+                    timestamp,
+                    invocation: WorkerInvocation::ManualUpdate { target_version },
+                } => Some(PublicPendingUpdate {
+                    timestamp: *timestamp,
+                    target_version: *target_version,
+                }),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        pending_updates.extend(value.pending_updates.iter().map(|pending_update| {
+            PublicPendingUpdate {
+                timestamp: pending_update.timestamp,
+                target_version: *pending_update.description.target_version(),
+            }
+        }));
+
+        let mut owned_resources = value
+            .owned_resources
+            .into_iter()
+            .map(|(id, description)| PublicOwnedResource {
+                id: id.0,
+                created_at: description.created_at,
+                indexed: description.indexed_resource_key.map(|key| key.into()),
+            })
+            .collect::<Vec<_>>();
+        owned_resources.sort_by_key(|resource| resource.id);
+
+        Self {
+            schema_version: PUBLIC_WORKER_STATUS_SCHEMA_VERSION,
+            status: value.status,
+            component_version: value.component_version,
+            component_size: value.component_size,
+            total_linear_memory_size: value.total_linear_memory_size,
+            oplog_index: value.oplog_idx,
+            current_idempotency_key: value.current_idempotency_key,
+            overridden_retry_config: value.overridden_retry_config.map(|config| config.into()),
+            pending_invocation_count: value.pending_invocations.len() as u64,
+            invocation_result_count: value.invocation_results.len() as u64,
+            pending_updates,
+            successful_updates: value
+                .successful_updates
+                .into_iter()
+                .map(|update| PublicSuccessfulUpdate {
+                    timestamp: update.timestamp,
+                    target_version: update.target_version,
+                })
+                .collect(),
+            failed_updates: value
+                .failed_updates
+                .into_iter()
+                .map(|update| PublicFailedUpdate {
+                    timestamp: update.timestamp,
+                    target_version: update.target_version,
+                    details: update.details,
+                })
+                .collect(),
+            deleted_regions: PublicDeletedRegions::new(
+                value.deleted_regions.regions().cloned().collect(),
+                value.deleted_regions.corrupted_regions().cloned().collect(),
+            ),
+            owned_resources,
+        }
+    }
+}
+
+impl From<PublicWorkerStatusRecord>
+    for golem_api_grpc::proto::golem::worker::PublicWorkerStatusRecord
+{
+    fn from(value: PublicWorkerStatusRecord) -> Self {
+        let mut updates = Vec::new();
+        for update in value.pending_updates {
+            updates.push(golem_api_grpc::proto::golem::worker::UpdateRecord {
+                timestamp: Some(update.timestamp.into()),
+                target_version: update.target_version,
+                update: Some(update_record::Update::Pending(
+                    golem_api_grpc::proto::golem::worker::PendingUpdate {},
+                )),
+            });
+        }
+        for update in value.successful_updates {
+            updates.push(golem_api_grpc::proto::golem::worker::UpdateRecord {
+                timestamp: Some(update.timestamp.into()),
+                target_version: update.target_version,
+                update: Some(update_record::Update::Successful(
+                    golem_api_grpc::proto::golem::worker::SuccessfulUpdate {},
+                )),
+            });
+        }
+        for update in value.failed_updates {
+            updates.push(golem_api_grpc::proto::golem::worker::UpdateRecord {
+                timestamp: Some(update.timestamp.into()),
+                target_version: update.target_version,
+                update: Some(update_record::Update::Failed(
+                    golem_api_grpc::proto::golem::worker::FailedUpdate {
+                        details: update.details,
+                    },
+                )),
+            });
+        }
+
+        Self {
+            schema_version: value.schema_version,
+            status: value.status.into(),
+            component_version: value.component_version,
+            component_size: value.component_size,
+            total_linear_memory_size: value.total_linear_memory_size,
+            oplog_index: value.oplog_index.into(),
+            current_idempotency_key: value.current_idempotency_key.map(|key| key.into()),
+            overridden_retry_policy: value.overridden_retry_config.map(|config| config.into()),
+            pending_invocation_count: value.pending_invocation_count,
+            invocation_result_count: value.invocation_result_count,
+            updates,
+            deleted_regions: value
+                .deleted_regions
+                .regions
+                .into_iter()
+                .map(
+                    |region| golem_api_grpc::proto::golem::worker::DeletedOplogRegion {
+                        start: region.start.into(),
+                        end: region.end.into(),
+                    },
+                )
+                .collect(),
+            corrupted_oplog_regions: value
+                .deleted_regions
+                .corrupted_regions
+                .into_iter()
+                .map(|region| region.into())
+                .collect(),
+            owned_resources: HashMap::from_iter(value.owned_resources.into_iter().map(
+                |resource| {
+                    (
+                        resource.id,
+                        golem_api_grpc::proto::golem::worker::ResourceMetadata {
+                            created_at: Some(resource.created_at.into()),
+                            indexed: resource.indexed.map(|key| {
+                                golem_api_grpc::proto::golem::worker::IndexedResourceMetadata {
+                                    resource_name: key.resource_name,
+                                    resource_params: key.resource_params,
+                                }
+                            }),
+                        },
+                    )
+                },
+            )),
+        }
+    }
+}
+
+impl TryFrom<golem_api_grpc::proto::golem::worker::PublicWorkerStatusRecord>
+    for PublicWorkerStatusRecord
+{
+    type Error = String;
+
+    fn try_from(
+        value: golem_api_grpc::proto::golem::worker::PublicWorkerStatusRecord,
+    ) -> Result<Self, Self::Error> {
+        let mut pending_updates = Vec::new();
+        let mut successful_updates = Vec::new();
+        let mut failed_updates = Vec::new();
+        for update in value.updates {
+            let timestamp: Timestamp = update.timestamp.ok_or("Missing timestamp")?.into();
+            match update.update.ok_or("Missing update")? {
+                update_record::Update::Pending(_) => pending_updates.push(PublicPendingUpdate {
+                    timestamp,
+                    target_version: update.target_version,
+                }),
+                update_record::Update::Successful(_) => {
+                    successful_updates.push(PublicSuccessfulUpdate {
+                        timestamp,
+                        target_version: update.target_version,
+                    })
+                }
+                update_record::Update::Failed(failed) => failed_updates.push(PublicFailedUpdate {
+                    timestamp,
+                    target_version: update.target_version,
+                    details: failed.details,
+                }),
+            }
+        }
+
+        let mut owned_resources = value
+            .owned_resources
+            .into_iter()
+            .map(|(id, resource)| {
+                Ok(PublicOwnedResource {
+                    id,
+                    created_at: resource.created_at.ok_or("Missing created_at")?.into(),
+                    indexed: resource.indexed.map(|key| PublicIndexedResourceKey {
+                        resource_name: key.resource_name,
+                        resource_params: key.resource_params,
+                    }),
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        owned_resources.sort_by_key(|resource| resource.id);
+
+        Ok(Self {
+            schema_version: value.schema_version,
+            status: value.status.try_into()?,
+            component_version: value.component_version,
+            component_size: value.component_size,
+            total_linear_memory_size: value.total_linear_memory_size,
+            oplog_index: OplogIndex::from_u64(value.oplog_index),
+            current_idempotency_key: value.current_idempotency_key.map(|key| key.into()),
+            overridden_retry_config: value
+                .overridden_retry_policy
+                .map(|policy| policy.try_into())
+                .transpose()?,
+            pending_invocation_count: value.pending_invocation_count,
+            invocation_result_count: value.invocation_result_count,
+            pending_updates,
+            successful_updates,
+            failed_updates,
+            deleted_regions: PublicDeletedRegions::new(
+                value
+                    .deleted_regions
+                    .into_iter()
+                    .map(|region| OplogRegion {
+                        start: OplogIndex::from_u64(region.start),
+                        end: OplogIndex::from_u64(region.end),
+                    })
+                    .collect(),
+                value
+                    .corrupted_oplog_regions
+                    .into_iter()
+                    .map(|region| region.try_into())
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+            owned_resources,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::model::oplog::{IndexedResourceKey, OplogIndex, WorkerResourceId};
+    use crate::model::public_worker_status::{
+        PublicWorkerStatusRecord, PUBLIC_WORKER_STATUS_SCHEMA_VERSION,
+    };
+    use crate::model::regions::{DeletedRegions, OplogRegion};
+    use crate::model::{
+        FailedUpdateRecord, IdempotencyKey, Timestamp, TimestampedWorkerInvocation,
+        WorkerInvocation, WorkerResourceDescription, WorkerStatus, WorkerStatusRecord,
+    };
+    use std::collections::HashMap;
+
+    #[test]
+    fn projects_worker_status_record() {
+        let timestamp = Timestamp::from(1_700_000_000_000);
+        let record = WorkerStatusRecord {
+            status: WorkerStatus::Suspended,
+            deleted_regions: DeletedRegions::from_regions(vec![
+                OplogRegion::from_range(2..=4),
+                OplogRegion::from_range(10..=10),
+            ]),
+            pending_invocations: vec![TimestampedWorkerInvocation {
+                timestamp,
+                invocation: WorkerInvocation::ManualUpdate { target_version: 3 },
+            }],
+            failed_updates: vec![FailedUpdateRecord {
+                timestamp,
+                target_version: 2,
+                details: Some("incompatible".to_string()),
+            }],
+            invocation_results: HashMap::from_iter(vec![
+                (IdempotencyKey::fresh(), OplogIndex::from_u64(5)),
+                (IdempotencyKey::fresh(), OplogIndex::from_u64(8)),
+            ]),
+            owned_resources: HashMap::from_iter(vec![(
+                WorkerResourceId(7),
+                WorkerResourceDescription {
+                    created_at: timestamp,
+                    indexed_resource_key: Some(IndexedResourceKey {
+                        resource_name: "counter".to_string(),
+                        resource_params: vec!["a".to_string()],
+                    }),
+                },
+            )]),
+            oplog_idx: OplogIndex::from_u64(12),
+            ..WorkerStatusRecord::default()
+        };
+
+        let public = PublicWorkerStatusRecord::from(record);
+
+        assert_eq!(public.schema_version, PUBLIC_WORKER_STATUS_SCHEMA_VERSION);
+        assert_eq!(public.pending_invocation_count, 1);
+        assert_eq!(public.invocation_result_count, 2);
+        assert_eq!(public.pending_updates.len(), 1);
+        assert_eq!(public.pending_updates[0].target_version, 3);
+        assert_eq!(public.failed_updates.len(), 1);
+        assert_eq!(public.deleted_regions.regions.len(), 2);
+        assert_eq!(public.deleted_regions.deleted_entry_count, 4);
+        assert_eq!(public.owned_resources.len(), 1);
+        assert_eq!(public.owned_resources[0].id, 7);
+
+        let proto: golem_api_grpc::proto::golem::worker::PublicWorkerStatusRecord =
+            public.clone().into();
+        assert_eq!(PublicWorkerStatusRecord::try_from(proto), Ok(public));
+    }
+}
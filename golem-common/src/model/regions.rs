@@ -19,6 +19,9 @@ use std::ops::Bound::{Included, Unbounded};
 use std::ops::RangeInclusive;
 
 use crate::model::oplog::OplogIndex;
+use bincode::de::Decoder;
+use bincode::enc::Encoder;
+use bincode::error::{DecodeError, EncodeError};
 use bincode::{Decode, Encode};
 use poem_openapi::Object;
 use range_set_blaze::RangeSetBlaze;
@@ -32,7 +35,7 @@ pub struct OplogRegion {
 
 impl OplogRegion {
     pub fn contains(&self, target: OplogIndex) -> bool {
-        target > self.end && target <= self.start
+        target >= self.start && target <= self.end
     }
 
     pub fn union(&self, other: &OplogRegion) -> Option<OplogRegion> {
@@ -71,10 +74,61 @@ impl Display for OplogRegion {
     }
 }
 
+/// A region of the oplog which has been quarantined because its entries could not be decoded.
+#[derive(Clone, Debug, Eq, PartialEq, Encode, Decode, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct CorruptedOplogRegion {
+    pub region: OplogRegion,
+    /// The serialization version recorded in the first corrupted entry
+    pub serialization_version: u8,
+    pub details: String,
+}
+
+impl Display for CorruptedOplogRegion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} (serialization version {}): {}",
+            self.region, self.serialization_version, self.details
+        )
+    }
+}
+
+impl From<CorruptedOplogRegion> for golem_api_grpc::proto::golem::worker::CorruptedOplogRegion {
+    fn from(value: CorruptedOplogRegion) -> Self {
+        Self {
+            start: value.region.start.into(),
+            end: value.region.end.into(),
+            serialization_version: value.serialization_version as u32,
+            details: value.details,
+        }
+    }
+}
+
+impl TryFrom<golem_api_grpc::proto::golem::worker::CorruptedOplogRegion> for CorruptedOplogRegion {
+    type Error = String;
+
+    fn try_from(
+        value: golem_api_grpc::proto::golem::worker::CorruptedOplogRegion,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self {
+            region: OplogRegion {
+                start: OplogIndex::from_u64(value.start),
+                end: OplogIndex::from_u64(value.end),
+            },
+            serialization_version: u8::try_from(value.serialization_version)
+                .map_err(|_| "Invalid serialization version".to_string())?,
+            details: value.details,
+        })
+    }
+}
+
 /// Temporary builder for building up a `DeletedRegions` structure using an efficient data
 /// structure for merging ranges as they are added.
 pub struct DeletedRegionsBuilder {
     regions: RangeSetBlaze<u64>,
+    corrupted: BTreeMap<OplogIndex, CorruptedOplogRegion>,
 }
 
 impl Default for DeletedRegionsBuilder {
@@ -87,32 +141,75 @@ impl DeletedRegionsBuilder {
     pub fn new() -> Self {
         Self {
             regions: RangeSetBlaze::new(),
+            corrupted: BTreeMap::new(),
         }
     }
 
     pub fn from_regions(regions: impl IntoIterator<Item = OplogRegion>) -> Self {
         Self {
             regions: RangeSetBlaze::from_iter(regions.into_iter().map(|region| region.to_range())),
+            corrupted: BTreeMap::new(),
         }
     }
 
+    /// Initializes the builder from an existing set of deleted regions, keeping its
+    /// corruption markers. Overrides are not preserved.
+    pub fn from_deleted_regions(deleted_regions: DeletedRegions) -> Self {
+        let corrupted = deleted_regions.corrupted.clone();
+        let mut builder = Self::from_regions(deleted_regions.into_regions());
+        builder.corrupted = corrupted;
+        builder
+    }
+
     /// Adds a new region to the list of deleted regions
     pub fn add(&mut self, region: OplogRegion) {
         self.regions.ranges_insert(region.to_range());
     }
 
+    /// Adds a region which could not be decoded to the list of deleted regions,
+    /// marking it as corrupted
+    pub fn add_corrupted(&mut self, corrupted: CorruptedOplogRegion) {
+        self.add(corrupted.region.clone());
+        self.corrupted.insert(corrupted.region.start, corrupted);
+    }
+
     pub fn build(self) -> DeletedRegions {
-        DeletedRegions::from_regions(self.regions.into_ranges().map(OplogRegion::from_range))
+        let mut result =
+            DeletedRegions::from_regions(self.regions.into_ranges().map(OplogRegion::from_range));
+        result.corrupted = self.corrupted;
+        result
     }
 }
 
 /// Structure holding all the regions deleted from the oplog by jumps. Deleted regions
 /// can be stacked to introduce temporary overrides.
-#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+///
+/// Regions quarantined because their entries could not be decoded are also deleted, and
+/// they are additionally recorded with a corruption marker. The markers are not part of the
+/// binary encoding of this structure, to keep it compatible with previously stored values.
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct DeletedRegions {
     regions: Vec<BTreeMap<OplogIndex, OplogRegion>>,
+    corrupted: BTreeMap<OplogIndex, CorruptedOplogRegion>,
 }
 
+impl Encode for DeletedRegions {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        self.regions.encode(encoder)
+    }
+}
+
+impl Decode for DeletedRegions {
+    fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
+        Ok(Self {
+            regions: Vec::decode(decoder)?,
+            corrupted: BTreeMap::new(),
+        })
+    }
+}
+
+bincode::impl_borrow_decode!(DeletedRegions);
+
 impl Default for DeletedRegions {
     fn default() -> Self {
         Self::new()
@@ -124,6 +221,7 @@ impl DeletedRegions {
     pub fn new() -> Self {
         Self {
             regions: vec![BTreeMap::new()],
+            corrupted: BTreeMap::new(),
         }
     }
 
@@ -133,6 +231,7 @@ impl DeletedRegions {
             regions: vec![BTreeMap::from_iter(
                 regions.into_iter().map(|region| (region.start, region)),
             )],
+            corrupted: BTreeMap::new(),
         }
     }
 
@@ -146,6 +245,33 @@ impl DeletedRegions {
         self.regions.push(temp.pop().unwrap());
     }
 
+    /// Adds a region which could not be decoded to the list of deleted regions,
+    /// marking it as corrupted
+    pub fn add_corrupted(&mut self, corrupted: CorruptedOplogRegion) {
+        self.add(corrupted.region.clone());
+        self.corrupted.insert(corrupted.region.start, corrupted);
+    }
+
+    /// Sets the corruption markers, for example when restoring them from a separate storage
+    pub fn set_corrupted_regions(
+        &mut self,
+        corrupted: impl IntoIterator<Item = CorruptedOplogRegion>,
+    ) {
+        self.corrupted = corrupted
+            .into_iter()
+            .map(|corrupted| (corrupted.region.start, corrupted))
+            .collect();
+    }
+
+    /// Returns the list of regions quarantined because of decoding failures
+    pub fn corrupted_regions(&self) -> Values<'_, OplogIndex, CorruptedOplogRegion> {
+        self.corrupted.values()
+    }
+
+    pub fn has_corrupted_regions(&self) -> bool {
+        !self.corrupted.is_empty()
+    }
+
     /// Sets an override of the deleted regions.This is not stacked, if there was an override already
     /// it is going to be replaced. The override can be dropped using `drop_override`.
     pub fn set_override(&mut self, other: DeletedRegions) {
@@ -230,7 +356,8 @@ mod tests {
     use test_r::test;
 
     use crate::model::oplog::OplogIndex;
-    use crate::model::regions::{DeletedRegionsBuilder, OplogRegion};
+    use crate::model::regions::{CorruptedOplogRegion, DeletedRegionsBuilder, OplogRegion};
+    use crate::serialization::{deserialize, serialize};
 
     fn oplog_region(start: u64, end: u64) -> OplogRegion {
         OplogRegion {
@@ -239,6 +366,21 @@ mod tests {
         }
     }
 
+    #[test]
+    pub fn region_contains_its_bounds() {
+        let region = oplog_region(5, 8);
+        assert!(!region.contains(OplogIndex::from_u64(4)));
+        assert!(region.contains(OplogIndex::from_u64(5)));
+        assert!(region.contains(OplogIndex::from_u64(6)));
+        assert!(region.contains(OplogIndex::from_u64(8)));
+        assert!(!region.contains(OplogIndex::from_u64(9)));
+
+        let single = oplog_region(3, 3);
+        assert!(!single.contains(OplogIndex::from_u64(2)));
+        assert!(single.contains(OplogIndex::from_u64(3)));
+        assert!(!single.contains(OplogIndex::from_u64(4)));
+    }
+
     #[test]
     pub fn builder_from_overlapping_ranges() {
         let mut builder = DeletedRegionsBuilder::new();
@@ -296,4 +438,60 @@ mod tests {
             None
         );
     }
+
+    #[test]
+    pub fn corruption_markers_are_preserved_by_builder() {
+        let corrupted = CorruptedOplogRegion {
+            region: oplog_region(10, 12),
+            serialization_version: 7,
+            details: "invalid serialization version".to_string(),
+        };
+        let mut deleted_regions =
+            DeletedRegionsBuilder::from_regions(vec![oplog_region(2, 8)]).build();
+        deleted_regions.add_corrupted(corrupted.clone());
+
+        assert!(deleted_regions.is_in_deleted_region(OplogIndex::from_u64(11)));
+
+        let mut builder = DeletedRegionsBuilder::from_deleted_regions(deleted_regions);
+        builder.add(oplog_region(20, 22));
+        let deleted_regions = builder.build();
+
+        assert_eq!(
+            deleted_regions.regions().cloned().collect::<Vec<_>>(),
+            vec![
+                oplog_region(2, 8),
+                oplog_region(10, 12),
+                oplog_region(20, 22)
+            ]
+        );
+        assert_eq!(
+            deleted_regions
+                .corrupted_regions()
+                .cloned()
+                .collect::<Vec<_>>(),
+            vec![corrupted]
+        );
+    }
+
+    #[test]
+    pub fn corruption_markers_are_not_encoded() {
+        let mut with_markers =
+            DeletedRegionsBuilder::from_regions(vec![oplog_region(2, 8)]).build();
+        with_markers.add_corrupted(CorruptedOplogRegion {
+            region: oplog_region(10, 10),
+            serialization_version: 2,
+            details: "unexpected end".to_string(),
+        });
+        let without_markers =
+            DeletedRegionsBuilder::from_regions(vec![oplog_region(2, 8), oplog_region(10, 10)])
+                .build();
+
+        assert_eq!(
+            serialize(&with_markers).unwrap(),
+            serialize(&without_markers).unwrap()
+        );
+        let decoded: super::DeletedRegions =
+            deserialize(&serialize(&with_markers).unwrap()).unwrap();
+        assert_eq!(decoded, without_markers);
+    }
 }
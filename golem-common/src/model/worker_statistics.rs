@@ -0,0 +1,273 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Aggregated statistics of the workers of a component. Each executor computes them for the
+//! workers of its own shards, and the worker service merges the results of all executors.
+
+use crate::model::{ComponentId, ComponentVersion, WorkerStatus, WorkerStatusRecord};
+use golem_api_grpc::proto::golem::worker;
+use poem_openapi::Object;
+use serde::{Deserialize, Serialize};
+
+/// Statistics of the workers of a component, in total and per component version
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct ComponentWorkerStatistics {
+    pub component_id: ComponentId,
+    pub total: WorkerStatistics,
+    /// Statistics of the workers of each component version having workers, ordered by version
+    pub versions: Vec<ComponentVersionWorkerStatistics>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct ComponentVersionWorkerStatistics {
+    pub component_version: ComponentVersion,
+    pub statistics: WorkerStatistics,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct WorkerStatistics {
+    pub worker_count: u64,
+    /// Number of workers in each status, ordered by status and omitting the statuses without workers
+    pub status_counts: Vec<WorkerStatusCount>,
+    pub pending_invocation_count: u64,
+    pub total_linear_memory_size: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct WorkerStatusCount {
+    pub status: WorkerStatus,
+    pub count: u64,
+}
+
+impl ComponentWorkerStatistics {
+    pub fn new(component_id: ComponentId) -> Self {
+        Self {
+            component_id,
+            total: WorkerStatistics::default(),
+            versions: Vec::new(),
+        }
+    }
+
+    /// Adds a worker with the given last known status to the statistics
+    pub fn add_worker(&mut self, status: &WorkerStatusRecord) {
+        self.total.add_worker(status);
+        self.version_mut(status.component_version)
+            .add_worker(status);
+    }
+
+    /// Merges the statistics of another set of workers of the same component
+    pub fn merge(&mut self, other: ComponentWorkerStatistics) {
+        self.total.merge(other.total);
+        for version in other.versions {
+            self.version_mut(version.component_version)
+                .merge(version.statistics);
+        }
+    }
+
+    fn version_mut(&mut self, component_version: ComponentVersion) -> &mut WorkerStatistics {
+        let idx = match self
+            .versions
+            .binary_search_by_key(&component_version, |version| version.component_version)
+        {
+            Ok(idx) => idx,
+            Err(idx) => {
+                self.versions.insert(
+                    idx,
+                    ComponentVersionWorkerStatistics {
+                        component_version,
+                        statistics: WorkerStatistics::default(),
+                    },
+                );
+                idx
+            }
+        };
+        &mut self.versions[idx].statistics
+    }
+}
+
+impl WorkerStatistics {
+    pub fn add_worker(&mut self, status: &WorkerStatusRecord) {
+        self.worker_count += 1;
+        self.add_status_count(&status.status, 1);
+        self.pending_invocation_count += status.pending_invocations.len() as u64;
+        self.total_linear_memory_size += status.total_linear_memory_size;
+    }
+
+    pub fn merge(&mut self, other: WorkerStatistics) {
+        self.worker_count += other.worker_count;
+        for status_count in other.status_counts {
+            self.add_status_count(&status_count.status, status_count.count);
+        }
+        self.pending_invocation_count += other.pending_invocation_count;
+        self.total_linear_memory_size += other.total_linear_memory_size;
+    }
+
+    fn add_status_count(&mut self, status: &WorkerStatus, count: u64) {
+        match self
+            .status_counts
+            .binary_search_by(|status_count| status_count.status.cmp(status))
+        {
+            Ok(idx) => self.status_counts[idx].count += count,
+            Err(idx) => self.status_counts.insert(
+                idx,
+                WorkerStatusCount {
+                    status: status.clone(),
+                    count,
+                },
+            ),
+        }
+    }
+}
+
+impl TryFrom<worker::ComponentWorkerStatistics> for ComponentWorkerStatistics {
+    type Error = String;
+
+    fn try_from(value: worker::ComponentWorkerStatistics) -> Result<Self, Self::Error> {
+        Ok(Self {
+            component_id: value
+                .component_id
+                .ok_or("Missing component_id")?
+                .try_into()?,
+            total: value.total.unwrap_or_default().try_into()?,
+            versions: value
+                .versions
+                .into_iter()
+                .map(|version| {
+                    Ok(ComponentVersionWorkerStatistics {
+                        component_version: version.component_version,
+                        statistics: version.statistics.unwrap_or_default().try_into()?,
+                    })
+                })
+                .collect::<Result<Vec<_>, String>>()?,
+        })
+    }
+}
+
+impl From<ComponentWorkerStatistics> for worker::ComponentWorkerStatistics {
+    fn from(value: ComponentWorkerStatistics) -> Self {
+        Self {
+            component_id: Some(value.component_id.into()),
+            total: Some(value.total.into()),
+            versions: value
+                .versions
+                .into_iter()
+                .map(|version| worker::ComponentVersionWorkerStatistics {
+                    component_version: version.component_version,
+                    statistics: Some(version.statistics.into()),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<worker::WorkerStatistics> for WorkerStatistics {
+    type Error = String;
+
+    fn try_from(value: worker::WorkerStatistics) -> Result<Self, Self::Error> {
+        Ok(Self {
+            worker_count: value.worker_count,
+            status_counts: value
+                .status_counts
+                .into_iter()
+                .map(|status_count| {
+                    Ok(WorkerStatusCount {
+                        status: status_count.status.try_into()?,
+                        count: status_count.count,
+                    })
+                })
+                .collect::<Result<Vec<_>, String>>()?,
+            pending_invocation_count: value.pending_invocation_count,
+            total_linear_memory_size: value.total_linear_memory_size,
+        })
+    }
+}
+
+impl From<WorkerStatistics> for worker::WorkerStatistics {
+    fn from(value: WorkerStatistics) -> Self {
+        Self {
+            worker_count: value.worker_count,
+            status_counts: value
+                .status_counts
+                .into_iter()
+                .map(|status_count| worker::WorkerStatusCount {
+                    status: status_count.status.into(),
+                    count: status_count.count,
+                })
+                .collect(),
+            pending_invocation_count: value.pending_invocation_count,
+            total_linear_memory_size: value.total_linear_memory_size,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::model::worker_statistics::{ComponentWorkerStatistics, WorkerStatusCount};
+    use crate::model::{ComponentId, WorkerStatus, WorkerStatusRecord};
+
+    fn status(status: WorkerStatus, component_version: u64, memory: u64) -> WorkerStatusRecord {
+        WorkerStatusRecord {
+            status,
+            component_version,
+            total_linear_memory_size: memory,
+            ..WorkerStatusRecord::default()
+        }
+    }
+
+    #[test]
+    fn statistics_of_executors_are_merged() {
+        let component_id = ComponentId::new_v4();
+
+        let mut first = ComponentWorkerStatistics::new(component_id.clone());
+        first.add_worker(&status(WorkerStatus::Idle, 1, 100));
+        first.add_worker(&status(WorkerStatus::Running, 0, 200));
+
+        let mut second = ComponentWorkerStatistics::new(component_id.clone());
+        second.add_worker(&status(WorkerStatus::Idle, 0, 300));
+
+        first.merge(second);
+
+        assert_eq!(first.total.worker_count, 3);
+        assert_eq!(first.total.total_linear_memory_size, 600);
+        assert_eq!(
+            first.total.status_counts,
+            vec![
+                WorkerStatusCount {
+                    status: WorkerStatus::Running,
+                    count: 1
+                },
+                WorkerStatusCount {
+                    status: WorkerStatus::Idle,
+                    count: 2
+                },
+            ]
+        );
+        assert_eq!(
+            first
+                .versions
+                .iter()
+                .map(|version| (version.component_version, version.statistics.worker_count))
+                .collect::<Vec<_>>(),
+            vec![(0, 2), (1, 1)]
+        );
+    }
+}
@@ -665,6 +665,23 @@ impl<'a> RedisLabelledApi<'a> {
         )
     }
 
+    pub async fn eval<R, K, V>(&self, script: &str, keys: Vec<K>, args: V) -> RedisResult<R>
+    where
+        R: FromRedis,
+        K: AsRef<str>,
+        V: TryInto<MultipleValues> + Send,
+        V::Error: Into<RedisError> + Send,
+    {
+        self.ensure_connected().await?;
+        let start = Instant::now();
+        let keys: Vec<String> = keys.into_iter().map(|k| self.prefixed_key(k)).collect();
+        self.record(
+            start,
+            "EVAL",
+            self.pool.eval(script.to_string(), keys, args).await,
+        )
+    }
+
     pub async fn transaction<R, F, Fu>(&self, func: F) -> RedisResult<R>
     where
         R: FromRedis,
@@ -883,4 +900,25 @@ impl RedisTransaction {
     {
         self.trx.scard(self.prefixed_key(key)).await
     }
+
+    pub async fn xadd<K, C, I, F>(
+        &self,
+        key: K,
+        nomkstream: bool,
+        cap: C,
+        id: I,
+        fields: F,
+    ) -> RedisResult<()>
+    where
+        K: AsRef<str>,
+        I: Into<XID> + Send,
+        F: TryInto<MultipleOrderedPairs> + Send,
+        F::Error: Into<RedisError> + Send,
+        C: TryInto<XCap> + Send,
+        C::Error: Into<RedisError> + Send,
+    {
+        self.trx
+            .xadd(self.prefixed_key(key), nomkstream, cap, id, fields)
+            .await
+    }
 }
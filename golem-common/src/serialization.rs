@@ -22,6 +22,9 @@ pub const SERIALIZATION_VERSION_V1: u8 = 1u8;
 /// bincode 2 with bincode::config::standard()
 pub const SERIALIZATION_VERSION_V2: u8 = 2u8;
 
+/// Serialization versions which can still be decoded
+pub const SUPPORTED_SERIALIZATION_VERSIONS: [u8; 1] = [SERIALIZATION_VERSION_V2];
+
 pub fn serialize_with_version<T: Encode>(value: &T, version: u8) -> Result<Bytes, String> {
     let data = bincode::encode_to_vec(value, bincode::config::standard())
         .map_err(|e| format!("Failed to serialize value: {e}"))?;
@@ -80,6 +83,41 @@ pub fn try_deserialize_with_version<T: Decode>(
     }
 }
 
+/// Tries to decode a value with every supported serialization version, ignoring the recorded
+/// version prefix if it does not work. Both the prefixed and the unprefixed interpretation of the
+/// data is attempted. Returns the version that succeeded, or the collected error messages.
+///
+/// This is meant for diagnosing stored data which failed to decode, and never panics.
+pub fn try_deserialize_with_any_version<T: Decode>(bytes: &[u8]) -> Result<(u8, T), String> {
+    let mut errors = Vec::new();
+
+    if let Some((version, data)) = bytes.split_first() {
+        for candidate in SUPPORTED_SERIALIZATION_VERSIONS {
+            match try_deserialize_with_version(data, candidate) {
+                Ok(Some(value)) => return Ok((candidate, value)),
+                Ok(None) => {}
+                Err(err) => errors.push(format!(
+                    "version {candidate} (recorded version {version}): {err}"
+                )),
+            }
+        }
+    }
+
+    for candidate in SUPPORTED_SERIALIZATION_VERSIONS {
+        match try_deserialize_with_version(bytes, candidate) {
+            Ok(Some(value)) => return Ok((candidate, value)),
+            Ok(None) => {}
+            Err(err) => errors.push(format!("version {candidate} (without prefix): {err}")),
+        }
+    }
+
+    if errors.is_empty() {
+        Err("No supported serialization version could decode the data".to_string())
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use test_r::test;
@@ -135,4 +173,37 @@ mod tests {
             assert_eq!(result, None);
         }
     }
+
+    #[test]
+    pub fn try_deserialize_with_any_version_ignores_corrupted_prefix() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let example = Example::random(&mut rng);
+            let mut serialized = super::serialize(&example).unwrap().to_vec();
+            serialized[0] = 17;
+
+            let result: Option<Example> = super::try_deserialize(&serialized).unwrap();
+            assert_eq!(result, None);
+
+            let (version, recovered): (u8, Example) =
+                super::try_deserialize_with_any_version(&serialized).unwrap();
+            assert_eq!(version, super::SERIALIZATION_VERSION_V2);
+            assert_eq!(recovered, example);
+        }
+    }
+
+    #[test]
+    pub fn try_deserialize_with_any_version_handles_missing_prefix() {
+        let example = Example::Second {
+            x: 42,
+            y: true,
+            z: Box::new(Example::First("hello".to_string())),
+        };
+        let serialized = bincode::encode_to_vec(&example, bincode::config::standard()).unwrap();
+
+        let (version, recovered): (u8, Example) =
+            super::try_deserialize_with_any_version(&serialized).unwrap();
+        assert_eq!(version, super::SERIALIZATION_VERSION_V2);
+        assert_eq!(recovered, example);
+    }
 }
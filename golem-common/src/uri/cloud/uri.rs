@@ -179,7 +179,7 @@ uri_from_into!(ResourceUri);
 mod tests {
     use test_r::test;
 
-    use crate::model::{ComponentId, TargetWorkerId, WorkerId};
+    use crate::model::{ComponentId, TargetWorkerId, WorkerId, WorkerName};
     use crate::uri::cloud::uri::{
         ApiDefinitionUri, ApiDeploymentUri, ComponentOrVersionUri, ComponentUri,
         ComponentVersionUri, ResourceUri, WorkerFunctionUri, WorkerOrFunctionUri, WorkerUri,
@@ -385,7 +385,7 @@ mod tests {
                 component_id: ComponentId(
                     Uuid::parse_str("679ae459-8700-41d9-920c-7e2887459c94").unwrap(),
                 ),
-                worker_name: Some("my worker".to_string()),
+                worker_name: Some(WorkerName::from_existing("my worker")),
             },
         });
 
@@ -444,7 +444,10 @@ mod tests {
             typed_urn.id.component_id.0.to_string(),
             "679ae459-8700-41d9-920c-7e2887459c94"
         );
-        assert_eq!(typed_urn.id.worker_name, Some("my worker".to_string()));
+        assert_eq!(
+            typed_urn.id.worker_name,
+            Some(WorkerName::from_existing("my worker"))
+        );
     }
 
     #[test]
@@ -489,7 +492,10 @@ mod tests {
             typed_urn.id.component_id.0.to_string(),
             "679ae459-8700-41d9-920c-7e2887459c94"
         );
-        assert_eq!(typed_urn.id.worker_name, Some("my worker".to_string()));
+        assert_eq!(
+            typed_urn.id.worker_name,
+            Some(WorkerName::from_existing("my worker"))
+        );
     }
 
     #[test]
@@ -526,7 +532,7 @@ mod tests {
                 component_id: ComponentId(
                     Uuid::parse_str("679ae459-8700-41d9-920c-7e2887459c94").unwrap(),
                 ),
-                worker_name: "my worker".to_string(),
+                worker_name: WorkerName::from_existing("my worker"),
             },
             function: "fn a".to_string(),
         });
@@ -605,7 +611,7 @@ mod tests {
                 component_id: ComponentId(
                     Uuid::parse_str("679ae459-8700-41d9-920c-7e2887459c94").unwrap(),
                 ),
-                worker_name: Some("my worker".to_string()),
+                worker_name: Some(WorkerName::from_existing("my worker")),
             },
         }));
 
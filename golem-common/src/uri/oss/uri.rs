@@ -133,7 +133,7 @@ uri_from_into!(ResourceUri);
 mod tests {
     use test_r::test;
 
-    use crate::model::{ComponentId, TargetWorkerId, WorkerId};
+    use crate::model::{ComponentId, TargetWorkerId, WorkerId, WorkerName};
     use crate::uri::oss::uri::{
         ApiDefinitionUri, ApiDeploymentUri, ComponentOrVersionUri, ComponentUri,
         ComponentVersionUri, ResourceUri, WorkerFunctionUri, WorkerOrFunctionUri, WorkerUri,
@@ -335,7 +335,7 @@ mod tests {
                 component_id: ComponentId(
                     Uuid::parse_str("679ae459-8700-41d9-920c-7e2887459c94").unwrap(),
                 ),
-                worker_name: Some("my worker".to_string()),
+                worker_name: Some(WorkerName::from_existing("my worker")),
             },
         });
 
@@ -393,7 +393,10 @@ mod tests {
             typed_urn.id.component_id.0.to_string(),
             "679ae459-8700-41d9-920c-7e2887459c94"
         );
-        assert_eq!(typed_urn.id.worker_name, Some("my worker".to_string()));
+        assert_eq!(
+            typed_urn.id.worker_name,
+            Some(WorkerName::from_existing("my worker"))
+        );
     }
 
     #[test]
@@ -438,7 +441,10 @@ mod tests {
             typed_urn.id.component_id.0.to_string(),
             "679ae459-8700-41d9-920c-7e2887459c94"
         );
-        assert_eq!(typed_urn.id.worker_name, Some("my worker".to_string()));
+        assert_eq!(
+            typed_urn.id.worker_name,
+            Some(WorkerName::from_existing("my worker"))
+        );
     }
 
     #[test]
@@ -474,7 +480,7 @@ mod tests {
                 component_id: ComponentId(
                     Uuid::parse_str("679ae459-8700-41d9-920c-7e2887459c94").unwrap(),
                 ),
-                worker_name: "my worker".to_string(),
+                worker_name: WorkerName::from_existing("my worker"),
             },
             function: "fn a".to_string(),
         });
@@ -552,7 +558,7 @@ mod tests {
                 component_id: ComponentId(
                     Uuid::parse_str("679ae459-8700-41d9-920c-7e2887459c94").unwrap(),
                 ),
-                worker_name: Some("my worker".to_string()),
+                worker_name: Some(WorkerName::from_existing("my worker")),
             },
         }));
 
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::model::{ComponentId, ComponentVersion, TargetWorkerId, WorkerId};
+use crate::model::{ComponentId, ComponentVersion, TargetWorkerId, WorkerId, WorkerName};
 use crate::uri::{
     try_from_golem_urn, urldecode, urlencode, GolemUrn, GolemUrnTransformError, TypedGolemUrn,
     API_DEFINITION_TYPE_NAME, API_DEPLOYMENT_TYPE_NAME, COMPONENT_TYPE_NAME, WORKER_TYPE_NAME,
@@ -164,7 +164,9 @@ impl TypedGolemUrn for WorkerUrn {
     }
 
     fn try_from_name(resource_name: &str) -> Result<Self, GolemUrnTransformError> {
-        if let Some((id, worker_name)) = resource_name.split_once('/') {
+        // Also accepting the legacy `<component_id>:<worker_name>` form; component ids never
+        // contain either separator
+        if let Some((id, worker_name)) = resource_name.split_once(['/', ':']) {
             let id = Uuid::parse_str(id).map_err(|err| {
                 GolemUrnTransformError::invalid_name(
                     Self::resource_type(),
@@ -172,7 +174,7 @@ impl TypedGolemUrn for WorkerUrn {
                 )
             })?;
 
-            let worker_name = urldecode(worker_name);
+            let worker_name = WorkerName::from_existing(urldecode(worker_name));
 
             Ok(Self {
                 id: TargetWorkerId {
@@ -231,7 +233,7 @@ impl TypedGolemUrn for WorkerFunctionUrn {
             })?;
 
             if let Some((worker_name, function)) = rest.split_once('/') {
-                let worker_name = urldecode(worker_name);
+                let worker_name = WorkerName::from_existing(urldecode(worker_name));
                 let function = urldecode(function);
 
                 Ok(Self {
@@ -283,10 +285,10 @@ impl TypedGolemUrn for WorkerOrFunctionUrn {
     }
 
     fn try_from_name(resource_name: &str) -> Result<Self, GolemUrnTransformError> {
-        let has_function = if let Some((_, rest)) = resource_name.split_once('/') {
-            rest.contains('/')
-        } else {
-            false
+        // The legacy `<component_id>:<worker_name>` form never has a function part
+        let has_function = match resource_name.split_once('/') {
+            Some((id, rest)) => !id.contains(':') && rest.contains('/'),
+            None => false,
         };
 
         if has_function {
@@ -460,7 +462,7 @@ impl Display for ResourceUrn {
 mod tests {
     use test_r::test;
 
-    use crate::model::{ComponentId, TargetWorkerId, WorkerId};
+    use crate::model::{ComponentId, TargetWorkerId, WorkerId, WorkerName};
     use crate::uri::oss::urn::{
         ApiDefinitionUrn, ApiDeploymentUrn, ComponentOrVersionUrn, ComponentUrn,
         ComponentVersionUrn, ResourceUrn, WorkerFunctionUrn, WorkerOrFunctionUrn, WorkerUrn,
@@ -607,7 +609,7 @@ mod tests {
                 component_id: ComponentId(
                     Uuid::parse_str("679ae459-8700-41d9-920c-7e2887459c94").unwrap(),
                 ),
-                worker_name: Some("my:worker/1".to_string()),
+                worker_name: Some(WorkerName::from_existing("my:worker/1")),
             },
         };
 
@@ -647,7 +649,10 @@ mod tests {
             typed.id.component_id.0.to_string(),
             "679ae459-8700-41d9-920c-7e2887459c94"
         );
-        assert_eq!(typed.id.worker_name, Some("my:worker/1".to_string()));
+        assert_eq!(
+            typed.id.worker_name,
+            Some(WorkerName::from_existing("my:worker/1"))
+        );
     }
 
     #[test]
@@ -673,7 +678,37 @@ mod tests {
             typed.id.component_id.0.to_string(),
             "679ae459-8700-41d9-920c-7e2887459c94"
         );
-        assert_eq!(typed.id.worker_name, Some("my:worker/1".to_string()));
+        assert_eq!(
+            typed.id.worker_name,
+            Some(WorkerName::from_existing("my:worker/1"))
+        );
+    }
+
+    #[test]
+    pub fn worker_urn_from_legacy_str() {
+        let typed =
+            WorkerUrn::from_str("urn:worker:679ae459-8700-41d9-920c-7e2887459c94:my-worker/1")
+                .unwrap();
+
+        assert_eq!(
+            typed.id.component_id.0.to_string(),
+            "679ae459-8700-41d9-920c-7e2887459c94"
+        );
+        assert_eq!(
+            typed.id.worker_name,
+            Some(WorkerName::from_existing("my-worker/1"))
+        );
+        assert_eq!(
+            typed.to_string(),
+            "urn:worker:679ae459-8700-41d9-920c-7e2887459c94/my-worker%2F1"
+        );
+
+        let typed = WorkerOrFunctionUrn::from_str(
+            "urn:worker:679ae459-8700-41d9-920c-7e2887459c94:my-worker/1/2",
+        )
+        .unwrap();
+
+        assert!(matches!(typed, WorkerOrFunctionUrn::Worker(_)));
     }
 
     #[test]
@@ -694,7 +729,7 @@ mod tests {
                 component_id: ComponentId(
                     Uuid::parse_str("679ae459-8700-41d9-920c-7e2887459c94").unwrap(),
                 ),
-                worker_name: "my:worker/1".to_string(),
+                worker_name: WorkerName::from_existing("my:worker/1"),
             },
             function: "fn a".to_string(),
         };
@@ -744,7 +779,7 @@ mod tests {
                 component_id: ComponentId(
                     Uuid::parse_str("679ae459-8700-41d9-920c-7e2887459c94").unwrap(),
                 ),
-                worker_name: Some("my:worker/1".to_string()),
+                worker_name: Some(WorkerName::from_existing("my:worker/1")),
             },
         });
         let typed_f = WorkerOrFunctionUrn::Function(WorkerFunctionUrn {
@@ -752,7 +787,7 @@ mod tests {
                 component_id: ComponentId(
                     Uuid::parse_str("679ae459-8700-41d9-920c-7e2887459c94").unwrap(),
                 ),
-                worker_name: "my:worker/1".to_string(),
+                worker_name: WorkerName::from_existing("my:worker/1"),
             },
             function: "fn a".to_string(),
         });
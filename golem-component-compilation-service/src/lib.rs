@@ -84,8 +84,11 @@ async fn run(config: ServerConfig, prometheus: Registry) -> Result<(), Box<dyn s
             Arc::new(storage::blob::memory::InMemoryBlobStorage::new())
         }
     };
-    let compiled_component =
-        compiled_component::configured(&config.compiled_component_service, blob_storage.clone());
+    let compiled_component = compiled_component::configured(
+        &config.compiled_component_service,
+        blob_storage.clone(),
+        None,
+    );
     let engine = wasmtime::Engine::new(&create_wasmtime_config()).expect("Failed to create engine");
 
     // Start metrics and healthcheck server.
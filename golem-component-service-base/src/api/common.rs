@@ -35,13 +35,18 @@ mod conversion {
     impl From<component::ComponentError> for ComponentError {
         fn from(value: component::ComponentError) -> Self {
             let error = match value {
-                component::ComponentError::AlreadyExists(_) => {
+                component::ComponentError::AlreadyExists(_)
+                | component::ComponentError::ProjectAlreadyExists(_) => {
                     component_error::Error::AlreadyExists(ErrorBody {
                         error: value.to_safe_string(),
                     })
                 }
                 component::ComponentError::UnknownComponentId(_)
-                | component::ComponentError::UnknownVersionedComponentId(_) => {
+                | component::ComponentError::UnknownVersionedComponentId(_)
+                | component::ComponentError::NoInitialFiles(_)
+                | component::ComponentError::UnknownTransformationPlugin(_)
+                | component::ComponentError::UnknownVersionAlias { .. }
+                | component::ComponentError::UnknownProject(_) => {
                     component_error::Error::NotFound(ErrorBody {
                         error: value.to_safe_string(),
                     })
@@ -51,6 +56,20 @@ mod conversion {
                         errors: vec![error.to_safe_string()],
                     })
                 }
+                component::ComponentError::InvalidObservability(_)
+                | component::ComponentError::InvalidUpdatePolicy(_)
+                | component::ComponentError::InvalidLabels(_)
+                | component::ComponentError::InvalidInitialFiles(_)
+                | component::ComponentError::ComponentCompositionFailed(_)
+                | component::ComponentError::InvalidTransformationPlugin(_)
+                | component::ComponentError::InvalidProject(_)
+                | component::ComponentError::InvalidVersionAlias(_)
+                | component::ComponentError::ComponentNotDeleted(_)
+                | component::ComponentError::ComponentTransformationFailed { .. } => {
+                    component_error::Error::BadRequest(ErrorsBody {
+                        errors: vec![value.to_safe_string()],
+                    })
+                }
                 component::ComponentError::InternalRepoError(_) => {
                     component_error::Error::InternalError(ErrorBody {
                         error: value.to_safe_string(),
@@ -14,6 +14,7 @@
 
 use golem_service_base::model::Empty;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(tag = "type", content = "config")]
@@ -47,3 +48,70 @@ impl ComponentCompilationEnabledConfig {
             .expect("Failed to build ComponentCompilationService URI")
     }
 }
+
+/// Connection to the worker service, used to update the existing workers of components
+/// having an enabled update policy
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", content = "config")]
+pub enum WorkerUpdateRolloutConfig {
+    Enabled(WorkerUpdateRolloutEnabledConfig),
+    Disabled(Empty),
+}
+
+/// Disabled by default, as the worker service has to be reachable from the component service
+impl Default for WorkerUpdateRolloutConfig {
+    fn default() -> Self {
+        Self::Disabled(Empty {})
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WorkerUpdateRolloutEnabledConfig {
+    pub host: String,
+    pub port: u16,
+}
+
+impl WorkerUpdateRolloutEnabledConfig {
+    pub fn uri(&self) -> http_02::Uri {
+        http_02::Uri::builder()
+            .scheme("http")
+            .authority(format!("{}:{}", self.host, self.port).as_str())
+            .path_and_query("/")
+            .build()
+            .expect("Failed to build WorkerService URI")
+    }
+}
+
+/// Limits of the calls to the transformation webhooks registered by the accounts
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransformationWebhookConfig {
+    #[serde(with = "humantime_serde")]
+    pub timeout: Duration,
+    /// Maximum size in bytes of the WASM returned by a webhook
+    pub max_response_size: usize,
+    /// Whether webhooks can target loopback, private and link-local addresses, which is only safe
+    /// if the accounts registering the plugins are trusted with access to the internal network
+    pub allow_private_addresses: bool,
+}
+
+impl Default for TransformationWebhookConfig {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(60),
+            max_response_size: 100 * 1024 * 1024,
+            allow_private_addresses: false,
+        }
+    }
+}
+
+/// Scope in which the names of components must be unique
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ComponentNameUniqueness {
+    /// Component names are unique in the whole namespace (account)
+    #[default]
+    Namespace,
+    /// Component names are unique per project, components not assigned to any project
+    /// form a scope of their own. Switching back to `Namespace` fails on startup if the names
+    /// are no longer unique in the whole namespace.
+    Project,
+}
@@ -1,3 +1,4 @@
+use golem_common::model::component_compatibility::ComponentCompatibilityReport;
 use golem_common::model::component_metadata::ComponentMetadata;
 use golem_common::model::ComponentType;
 use golem_service_base::model::{ComponentName, VersionedComponentId};
@@ -13,6 +14,10 @@ pub struct Component<Namespace> {
     pub metadata: ComponentMetadata,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub component_type: ComponentType,
+    /// Compatibility of the exports with the previous version, only present for updates
+    pub compatibility_report: Option<ComponentCompatibilityReport>,
+    /// Content hash of the archive of initial files materialized into the workers' file system
+    pub initial_files_hash: Option<String>,
 }
 
 impl<Namespace> Component<Namespace> {
@@ -23,6 +28,7 @@ impl<Namespace> Component<Namespace> {
         };
         Self {
             versioned_component_id: new_version.clone(),
+            compatibility_report: None,
             ..self
         }
     }
@@ -37,6 +43,8 @@ impl<Namespace> From<Component<Namespace>> for golem_service_base::model::Compon
             metadata: value.metadata,
             created_at: Some(value.created_at),
             component_type: Some(value.component_type),
+            compatibility_report: value.compatibility_report,
+            initial_files_hash: value.initial_files_hash,
         }
     }
 }
@@ -55,6 +63,8 @@ impl<Namespace> From<Component<Namespace>> for golem_api_grpc::proto::golem::com
                 value.created_at,
             ))),
             component_type: Some(component_type.into()),
+            compatibility_report: value.compatibility_report.map(|report| report.into()),
+            initial_files_hash: value.initial_files_hash,
         }
     }
 }
@@ -15,10 +15,18 @@
 use crate::model::Component;
 use async_trait::async_trait;
 use conditional_trait_gen::{trait_gen, when};
+use golem_api_grpc::proto::golem::component::ComponentCompatibilityReport as ComponentCompatibilityReportProto;
+use golem_api_grpc::proto::golem::component::SensitiveParameters as SensitiveParametersProto;
+use golem_api_grpc::proto::golem::worker::WorkerFilter as WorkerFilterProto;
 use golem_common::model::component_metadata::ComponentMetadata;
-use golem_common::model::{ComponentId, ComponentType};
-use golem_service_base::model::{ComponentName, VersionedComponentId};
+use golem_common::model::component_observability::{ComponentObservability, SensitiveParameter};
+use golem_common::model::{ComponentId, ComponentType, LogLevel};
+use golem_service_base::model::{
+    ComponentName, ComponentTransformationPlugin, ComponentUpdatePolicy, ComponentVersionAlias,
+    ComponentVersionAliasChange, VersionedComponentId, WorkerUpdateMode,
+};
 use golem_service_base::repo::RepoError;
+use prost::Message;
 use sqlx::{Database, Pool, Row};
 use std::fmt::Display;
 use std::ops::Deref;
@@ -37,6 +45,8 @@ pub struct ComponentRecord {
     pub metadata: Vec<u8>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub component_type: i32,
+    pub compatibility_report: Option<Vec<u8>>,
+    pub initial_files_hash: Option<String>,
 }
 
 impl<Namespace> TryFrom<ComponentRecord> for Component<Namespace>
@@ -52,6 +62,15 @@ where
             version: value.version as u64,
         };
         let namespace = Namespace::try_from(value.namespace).map_err(|e| e.to_string())?;
+        let compatibility_report = match value.compatibility_report {
+            Some(bytes) => {
+                let proto_value: ComponentCompatibilityReportProto =
+                    Message::decode(bytes.as_slice())
+                        .map_err(|e| format!("Failed to deserialize compatibility report: {e}"))?;
+                Some(proto_value.into())
+            }
+            None => None,
+        };
         Ok(Component {
             namespace,
             component_name: ComponentName(value.name),
@@ -60,6 +79,8 @@ where
             versioned_component_id,
             created_at: value.created_at,
             component_type: ComponentType::try_from(value.component_type)?,
+            compatibility_report,
+            initial_files_hash: value.initial_files_hash,
         })
     }
 }
@@ -90,18 +111,235 @@ where
             metadata: metadata.into(),
             created_at: value.created_at,
             component_type: value.component_type as i32,
+            compatibility_report: value.compatibility_report.map(|report| {
+                let proto_value: ComponentCompatibilityReportProto = report.into();
+                proto_value.encode_to_vec()
+            }),
+            initial_files_hash: value.initial_files_hash,
+        })
+    }
+}
+
+#[derive(sqlx::FromRow, Debug, Clone)]
+pub struct ComponentObservabilityRecord {
+    pub component_id: Uuid,
+    pub log_level: i32,
+    pub metrics_enabled: bool,
+    pub trace_sampling_rate: f64,
+    pub payload_capture_sampling_rate: f64,
+    pub sensitive_parameters: Option<Vec<u8>>,
+}
+
+impl TryFrom<ComponentObservabilityRecord> for ComponentObservability {
+    type Error = String;
+
+    fn try_from(value: ComponentObservabilityRecord) -> Result<Self, Self::Error> {
+        let log_level = golem_api_grpc::proto::golem::worker::Level::try_from(value.log_level)
+            .map_err(|_| format!("Invalid log level: {}", value.log_level))?;
+        let sensitive_parameters = match value.sensitive_parameters {
+            Some(bytes) => {
+                let proto_value: SensitiveParametersProto = Message::decode(bytes.as_slice())
+                    .map_err(|e| format!("Failed to deserialize sensitive parameters: {e}"))?;
+                proto_value
+                    .parameters
+                    .into_iter()
+                    .map(SensitiveParameter::from)
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+        Ok(ComponentObservability {
+            log_level: LogLevel::from(log_level),
+            metrics_enabled: value.metrics_enabled,
+            trace_sampling_rate: value.trace_sampling_rate,
+            payload_capture_sampling_rate: value.payload_capture_sampling_rate,
+            sensitive_parameters,
+        })
+    }
+}
+
+impl ComponentObservabilityRecord {
+    pub fn new(component_id: &ComponentId, observability: ComponentObservability) -> Self {
+        let log_level: golem_api_grpc::proto::golem::worker::Level = observability.log_level.into();
+        let sensitive_parameters = SensitiveParametersProto {
+            parameters: observability
+                .sensitive_parameters
+                .into_iter()
+                .map(|parameter| parameter.into())
+                .collect(),
+        };
+        Self {
+            component_id: component_id.0,
+            log_level: log_level as i32,
+            metrics_enabled: observability.metrics_enabled,
+            trace_sampling_rate: observability.trace_sampling_rate,
+            payload_capture_sampling_rate: observability.payload_capture_sampling_rate,
+            sensitive_parameters: Some(sensitive_parameters.encode_to_vec()),
+        }
+    }
+}
+
+#[derive(sqlx::FromRow, Debug, Clone)]
+pub struct ComponentUpdatePolicyRecord {
+    pub component_id: Uuid,
+    pub enabled: bool,
+    pub update_mode: i32,
+    pub filter: Option<Vec<u8>>,
+    pub max_concurrent_updates_per_executor: i32,
+    pub version_alias: Option<String>,
+}
+
+impl TryFrom<ComponentUpdatePolicyRecord> for ComponentUpdatePolicy {
+    type Error = String;
+
+    fn try_from(value: ComponentUpdatePolicyRecord) -> Result<Self, Self::Error> {
+        let update_mode =
+            golem_api_grpc::proto::golem::worker::UpdateMode::try_from(value.update_mode)
+                .map_err(|_| format!("Invalid update mode: {}", value.update_mode))?;
+        let filter = match value.filter {
+            Some(bytes) => {
+                let proto_value: WorkerFilterProto = Message::decode(bytes.as_slice())
+                    .map_err(|e| format!("Failed to deserialize worker filter: {e}"))?;
+                Some(proto_value.try_into()?)
+            }
+            None => None,
+        };
+        Ok(ComponentUpdatePolicy {
+            enabled: value.enabled,
+            mode: WorkerUpdateMode::from(update_mode),
+            filter,
+            max_concurrent_updates_per_executor: value.max_concurrent_updates_per_executor as u32,
+            version_alias: value.version_alias,
+        })
+    }
+}
+
+impl ComponentUpdatePolicyRecord {
+    pub fn new(component_id: &ComponentId, policy: ComponentUpdatePolicy) -> Self {
+        let update_mode: golem_api_grpc::proto::golem::worker::UpdateMode = policy.mode.into();
+        Self {
+            component_id: component_id.0,
+            enabled: policy.enabled,
+            update_mode: update_mode as i32,
+            filter: policy.filter.map(|filter| {
+                let proto_value: WorkerFilterProto = filter.into();
+                proto_value.encode_to_vec()
+            }),
+            max_concurrent_updates_per_executor: policy.max_concurrent_updates_per_executor as i32,
+            version_alias: policy.version_alias,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow, Debug, Clone)]
+pub struct ComponentTransformationPluginRecord {
+    pub namespace: String,
+    pub name: String,
+    pub priority: i32,
+    pub transformation: String,
+}
+
+impl TryFrom<ComponentTransformationPluginRecord> for ComponentTransformationPlugin {
+    type Error = String;
+
+    fn try_from(value: ComponentTransformationPluginRecord) -> Result<Self, Self::Error> {
+        let transformation = serde_json::from_str(&value.transformation)
+            .map_err(|e| format!("Failed to deserialize transformation: {e}"))?;
+        Ok(ComponentTransformationPlugin {
+            name: value.name,
+            priority: value.priority,
+            transformation,
+        })
+    }
+}
+
+impl ComponentTransformationPluginRecord {
+    pub fn new(namespace: &str, plugin: ComponentTransformationPlugin) -> Result<Self, String> {
+        let transformation = serde_json::to_string(&plugin.transformation)
+            .map_err(|e| format!("Failed to serialize transformation: {e}"))?;
+        Ok(Self {
+            namespace: namespace.to_string(),
+            name: plugin.name,
+            priority: plugin.priority,
+            transformation,
         })
     }
 }
 
+#[derive(sqlx::FromRow, Debug, Clone)]
+pub struct ComponentVersionAliasRecord {
+    pub component_id: Uuid,
+    pub name: String,
+    pub version: i64,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<ComponentVersionAliasRecord> for ComponentVersionAlias {
+    fn from(value: ComponentVersionAliasRecord) -> Self {
+        ComponentVersionAlias {
+            name: value.name,
+            version: value.version as u64,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+impl ComponentVersionAliasRecord {
+    pub fn new(component_id: &ComponentId, alias: ComponentVersionAlias) -> Self {
+        Self {
+            component_id: component_id.0,
+            name: alias.name,
+            version: alias.version as i64,
+            updated_at: alias.updated_at,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow, Debug, Clone)]
+pub struct ComponentVersionAliasChangeRecord {
+    pub component_id: Uuid,
+    pub name: String,
+    pub previous_version: Option<i64>,
+    pub version: Option<i64>,
+    pub changed_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<ComponentVersionAliasChangeRecord> for ComponentVersionAliasChange {
+    fn from(value: ComponentVersionAliasChangeRecord) -> Self {
+        ComponentVersionAliasChange {
+            name: value.name,
+            previous_version: value.previous_version.map(|v| v as u64),
+            version: value.version.map(|v| v as u64),
+            changed_at: value.changed_at,
+        }
+    }
+}
+
 #[async_trait]
 pub trait ComponentRepo {
     async fn create(&self, component: &ComponentRecord) -> Result<(), RepoError>;
 
+    /// Creates a component like `create`, but a new component is also assigned to the project,
+    /// and its name has to be unique only within `name_scope`. The empty scope is the one of the
+    /// whole namespace.
+    async fn create_in_project(
+        &self,
+        component: &ComponentRecord,
+        project_id: Option<&Uuid>,
+        name_scope: &str,
+    ) -> Result<(), RepoError>;
+
+    /// Recalculates the name scopes of all the components, making names unique per project
+    /// or in the whole namespace. Fails if the names are not unique in the new scopes.
+    async fn update_name_scopes(&self, per_project: bool) -> Result<(), RepoError>;
+
     async fn get(&self, component_id: &Uuid) -> Result<Vec<ComponentRecord>, RepoError>;
 
     async fn get_all(&self, namespace: &str) -> Result<Vec<ComponentRecord>, RepoError>;
 
+    /// Gets the latest version of each component of the namespace
+    async fn get_all_latest(&self, namespace: &str) -> Result<Vec<ComponentRecord>, RepoError>;
+
     async fn get_latest_version(
         &self,
         component_id: &Uuid,
@@ -119,11 +357,120 @@ pub trait ComponentRepo {
         name: &str,
     ) -> Result<Vec<ComponentRecord>, RepoError>;
 
-    async fn get_id_by_name(&self, namespace: &str, name: &str) -> Result<Option<Uuid>, RepoError>;
+    /// Returns the ids of all the components with the given name, which can be more than one if
+    /// the names are only unique per project
+    async fn get_ids_by_name(&self, namespace: &str, name: &str) -> Result<Vec<Uuid>, RepoError>;
 
     async fn get_namespace(&self, component_id: &Uuid) -> Result<Option<String>, RepoError>;
 
     async fn delete(&self, namespace: &str, component_id: &Uuid) -> Result<(), RepoError>;
+
+    /// Marks the component as deleted, returns whether it existed and was not deleted yet
+    async fn soft_delete(
+        &self,
+        namespace: &str,
+        component_id: &Uuid,
+        deleted_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<bool, RepoError>;
+
+    /// Removes the deletion mark of the component, returns whether it was deleted
+    async fn restore(&self, namespace: &str, component_id: &Uuid) -> Result<bool, RepoError>;
+
+    async fn is_deleted(&self, component_id: &Uuid) -> Result<bool, RepoError>;
+
+    async fn get_deleted_ids(&self, namespace: &str) -> Result<Vec<Uuid>, RepoError>;
+
+    /// Returns the namespaces and ids of the components deleted before the given time
+    async fn get_deleted_before(
+        &self,
+        deleted_before: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<(String, Uuid)>, RepoError>;
+
+    /// Claims the purge of a deleted component, returns false if it is not deleted anymore or
+    /// if its purge was already claimed after `claimed_before`
+    async fn claim_purge(
+        &self,
+        component_id: &Uuid,
+        claimed_at: chrono::DateTime<chrono::Utc>,
+        claimed_before: chrono::DateTime<chrono::Utc>,
+    ) -> Result<bool, RepoError>;
+
+    async fn update_metadata(
+        &self,
+        component_id: &Uuid,
+        version: u64,
+        metadata: &[u8],
+    ) -> Result<(), RepoError>;
+
+    async fn get_observability(
+        &self,
+        component_id: &Uuid,
+    ) -> Result<Option<ComponentObservabilityRecord>, RepoError>;
+
+    async fn update_observability(
+        &self,
+        observability: &ComponentObservabilityRecord,
+    ) -> Result<(), RepoError>;
+
+    async fn get_update_policy(
+        &self,
+        component_id: &Uuid,
+    ) -> Result<Option<ComponentUpdatePolicyRecord>, RepoError>;
+
+    async fn update_update_policy(
+        &self,
+        policy: &ComponentUpdatePolicyRecord,
+    ) -> Result<(), RepoError>;
+
+    /// Returns the transformation plugins of the namespace in the order they have to be applied
+    async fn get_transformation_plugins(
+        &self,
+        namespace: &str,
+    ) -> Result<Vec<ComponentTransformationPluginRecord>, RepoError>;
+
+    async fn update_transformation_plugin(
+        &self,
+        plugin: &ComponentTransformationPluginRecord,
+    ) -> Result<(), RepoError>;
+
+    /// Returns whether the plugin existed
+    async fn delete_transformation_plugin(
+        &self,
+        namespace: &str,
+        name: &str,
+    ) -> Result<bool, RepoError>;
+
+    async fn get_version_aliases(
+        &self,
+        component_id: &Uuid,
+    ) -> Result<Vec<ComponentVersionAliasRecord>, RepoError>;
+
+    async fn get_version_alias(
+        &self,
+        component_id: &Uuid,
+        name: &str,
+    ) -> Result<Option<ComponentVersionAliasRecord>, RepoError>;
+
+    /// Moves or creates the alias and records the change, returns the version the alias pointed
+    /// to before
+    async fn update_version_alias(
+        &self,
+        alias: &ComponentVersionAliasRecord,
+    ) -> Result<Option<i64>, RepoError>;
+
+    /// Removes the alias and records the change, returns the version the alias pointed to
+    async fn delete_version_alias(
+        &self,
+        component_id: &Uuid,
+        name: &str,
+        deleted_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Option<i64>, RepoError>;
+
+    /// Returns the changes of the aliases of the component, in the order they happened
+    async fn get_version_alias_changes(
+        &self,
+        component_id: &Uuid,
+    ) -> Result<Vec<ComponentVersionAliasChangeRecord>, RepoError>;
 }
 
 pub struct DbComponentRepo<DB: Database> {
@@ -177,6 +524,24 @@ impl<Repo: ComponentRepo + Send + Sync> ComponentRepo for LoggedComponentRepo<Re
         Self::logged_with_id("create", &component.component_id, result)
     }
 
+    async fn create_in_project(
+        &self,
+        component: &ComponentRecord,
+        project_id: Option<&Uuid>,
+        name_scope: &str,
+    ) -> Result<(), RepoError> {
+        let result = self
+            .repo
+            .create_in_project(component, project_id, name_scope)
+            .await;
+        Self::logged_with_id("create_in_project", &component.component_id, result)
+    }
+
+    async fn update_name_scopes(&self, per_project: bool) -> Result<(), RepoError> {
+        let result = self.repo.update_name_scopes(per_project).await;
+        Self::logged("update_name_scopes", result)
+    }
+
     async fn get(&self, component_id: &Uuid) -> Result<Vec<ComponentRecord>, RepoError> {
         let result = self.repo.get(component_id).await;
         Self::logged_with_id("get", component_id, result)
@@ -187,6 +552,11 @@ impl<Repo: ComponentRepo + Send + Sync> ComponentRepo for LoggedComponentRepo<Re
         Self::logged("get_all", result)
     }
 
+    async fn get_all_latest(&self, namespace: &str) -> Result<Vec<ComponentRecord>, RepoError> {
+        let result = self.repo.get_all_latest(namespace).await;
+        Self::logged("get_all_latest", result)
+    }
+
     async fn get_latest_version(
         &self,
         component_id: &Uuid,
@@ -213,9 +583,9 @@ impl<Repo: ComponentRepo + Send + Sync> ComponentRepo for LoggedComponentRepo<Re
         Self::logged("get_by_name", result)
     }
 
-    async fn get_id_by_name(&self, namespace: &str, name: &str) -> Result<Option<Uuid>, RepoError> {
-        let result = self.repo.get_id_by_name(namespace, name).await;
-        Self::logged("get_id_by_name", result)
+    async fn get_ids_by_name(&self, namespace: &str, name: &str) -> Result<Vec<Uuid>, RepoError> {
+        let result = self.repo.get_ids_by_name(namespace, name).await;
+        Self::logged("get_ids_by_name", result)
     }
 
     async fn get_namespace(&self, component_id: &Uuid) -> Result<Option<String>, RepoError> {
@@ -227,12 +597,189 @@ impl<Repo: ComponentRepo + Send + Sync> ComponentRepo for LoggedComponentRepo<Re
         let result = self.repo.delete(namespace, component_id).await;
         Self::logged_with_id("delete", component_id, result)
     }
+
+    async fn soft_delete(
+        &self,
+        namespace: &str,
+        component_id: &Uuid,
+        deleted_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<bool, RepoError> {
+        let result = self
+            .repo
+            .soft_delete(namespace, component_id, deleted_at)
+            .await;
+        Self::logged_with_id("soft_delete", component_id, result)
+    }
+
+    async fn restore(&self, namespace: &str, component_id: &Uuid) -> Result<bool, RepoError> {
+        let result = self.repo.restore(namespace, component_id).await;
+        Self::logged_with_id("restore", component_id, result)
+    }
+
+    async fn is_deleted(&self, component_id: &Uuid) -> Result<bool, RepoError> {
+        let result = self.repo.is_deleted(component_id).await;
+        Self::logged_with_id("is_deleted", component_id, result)
+    }
+
+    async fn get_deleted_ids(&self, namespace: &str) -> Result<Vec<Uuid>, RepoError> {
+        let result = self.repo.get_deleted_ids(namespace).await;
+        Self::logged("get_deleted_ids", result)
+    }
+
+    async fn get_deleted_before(
+        &self,
+        deleted_before: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<(String, Uuid)>, RepoError> {
+        let result = self.repo.get_deleted_before(deleted_before).await;
+        Self::logged("get_deleted_before", result)
+    }
+
+    async fn claim_purge(
+        &self,
+        component_id: &Uuid,
+        claimed_at: chrono::DateTime<chrono::Utc>,
+        claimed_before: chrono::DateTime<chrono::Utc>,
+    ) -> Result<bool, RepoError> {
+        let result = self
+            .repo
+            .claim_purge(component_id, claimed_at, claimed_before)
+            .await;
+        Self::logged_with_id("claim_purge", component_id, result)
+    }
+
+    async fn update_metadata(
+        &self,
+        component_id: &Uuid,
+        version: u64,
+        metadata: &[u8],
+    ) -> Result<(), RepoError> {
+        let result = self
+            .repo
+            .update_metadata(component_id, version, metadata)
+            .await;
+        Self::logged_with_id("update_metadata", component_id, result)
+    }
+
+    async fn get_observability(
+        &self,
+        component_id: &Uuid,
+    ) -> Result<Option<ComponentObservabilityRecord>, RepoError> {
+        let result = self.repo.get_observability(component_id).await;
+        Self::logged_with_id("get_observability", component_id, result)
+    }
+
+    async fn update_observability(
+        &self,
+        observability: &ComponentObservabilityRecord,
+    ) -> Result<(), RepoError> {
+        let result = self.repo.update_observability(observability).await;
+        Self::logged_with_id("update_observability", &observability.component_id, result)
+    }
+
+    async fn get_update_policy(
+        &self,
+        component_id: &Uuid,
+    ) -> Result<Option<ComponentUpdatePolicyRecord>, RepoError> {
+        let result = self.repo.get_update_policy(component_id).await;
+        Self::logged_with_id("get_update_policy", component_id, result)
+    }
+
+    async fn update_update_policy(
+        &self,
+        policy: &ComponentUpdatePolicyRecord,
+    ) -> Result<(), RepoError> {
+        let result = self.repo.update_update_policy(policy).await;
+        Self::logged_with_id("update_update_policy", &policy.component_id, result)
+    }
+
+    async fn get_transformation_plugins(
+        &self,
+        namespace: &str,
+    ) -> Result<Vec<ComponentTransformationPluginRecord>, RepoError> {
+        let result = self.repo.get_transformation_plugins(namespace).await;
+        Self::logged("get_transformation_plugins", result)
+    }
+
+    async fn update_transformation_plugin(
+        &self,
+        plugin: &ComponentTransformationPluginRecord,
+    ) -> Result<(), RepoError> {
+        let result = self.repo.update_transformation_plugin(plugin).await;
+        Self::logged("update_transformation_plugin", result)
+    }
+
+    async fn delete_transformation_plugin(
+        &self,
+        namespace: &str,
+        name: &str,
+    ) -> Result<bool, RepoError> {
+        let result = self
+            .repo
+            .delete_transformation_plugin(namespace, name)
+            .await;
+        Self::logged("delete_transformation_plugin", result)
+    }
+
+    async fn get_version_aliases(
+        &self,
+        component_id: &Uuid,
+    ) -> Result<Vec<ComponentVersionAliasRecord>, RepoError> {
+        let result = self.repo.get_version_aliases(component_id).await;
+        Self::logged_with_id("get_version_aliases", component_id, result)
+    }
+
+    async fn get_version_alias(
+        &self,
+        component_id: &Uuid,
+        name: &str,
+    ) -> Result<Option<ComponentVersionAliasRecord>, RepoError> {
+        let result = self.repo.get_version_alias(component_id, name).await;
+        Self::logged_with_id("get_version_alias", component_id, result)
+    }
+
+    async fn update_version_alias(
+        &self,
+        alias: &ComponentVersionAliasRecord,
+    ) -> Result<Option<i64>, RepoError> {
+        let result = self.repo.update_version_alias(alias).await;
+        Self::logged_with_id("update_version_alias", &alias.component_id, result)
+    }
+
+    async fn delete_version_alias(
+        &self,
+        component_id: &Uuid,
+        name: &str,
+        deleted_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Option<i64>, RepoError> {
+        let result = self
+            .repo
+            .delete_version_alias(component_id, name, deleted_at)
+            .await;
+        Self::logged_with_id("delete_version_alias", component_id, result)
+    }
+
+    async fn get_version_alias_changes(
+        &self,
+        component_id: &Uuid,
+    ) -> Result<Vec<ComponentVersionAliasChangeRecord>, RepoError> {
+        let result = self.repo.get_version_alias_changes(component_id).await;
+        Self::logged_with_id("get_version_alias_changes", component_id, result)
+    }
 }
 
 #[trait_gen(sqlx::Postgres -> sqlx::Postgres, sqlx::Sqlite)]
 #[async_trait]
 impl ComponentRepo for DbComponentRepo<sqlx::Postgres> {
     async fn create(&self, component: &ComponentRecord) -> Result<(), RepoError> {
+        self.create_in_project(component, None, "").await
+    }
+
+    async fn create_in_project(
+        &self,
+        component: &ComponentRecord,
+        project_id: Option<&Uuid>,
+        name_scope: &str,
+    ) -> Result<(), RepoError> {
         let mut transaction = self.db_pool.begin().await?;
 
         let result = sqlx::query("SELECT namespace, name FROM components WHERE component_id = $1")
@@ -252,24 +799,40 @@ impl ComponentRepo for DbComponentRepo<sqlx::Postgres> {
             sqlx::query(
                 r#"
                   INSERT INTO components
-                    (namespace, component_id, name)
+                    (namespace, component_id, name, name_scope)
                   VALUES
-                    ($1, $2, $3)
+                    ($1, $2, $3, $4)
                    "#,
             )
             .bind(component.namespace.clone())
             .bind(component.component_id)
             .bind(component.name.clone())
+            .bind(name_scope)
             .execute(&mut *transaction)
             .await?;
+
+            if let Some(project_id) = project_id {
+                sqlx::query(
+                    r#"
+                      INSERT INTO component_projects
+                        (component_id, project_id)
+                      VALUES
+                        ($1, $2)
+                       "#,
+                )
+                .bind(component.component_id)
+                .bind(project_id)
+                .execute(&mut *transaction)
+                .await?;
+            }
         }
 
         sqlx::query(
             r#"
               INSERT INTO component_versions
-                (component_id, version, size, metadata, created_at, component_type)
+                (component_id, version, size, metadata, created_at, component_type, compatibility_report, initial_files_hash)
               VALUES
-                ($1, $2, $3, $4, $5, $6)
+                ($1, $2, $3, $4, $5, $6, $7, $8)
                "#,
         )
         .bind(component.component_id)
@@ -278,6 +841,8 @@ impl ComponentRepo for DbComponentRepo<sqlx::Postgres> {
         .bind(component.metadata.clone())
         .bind(component.created_at)
         .bind(component.component_type)
+        .bind(&component.compatibility_report)
+        .bind(&component.initial_files_hash)
         .execute(&mut *transaction)
         .await?;
 
@@ -297,7 +862,9 @@ impl ComponentRepo for DbComponentRepo<sqlx::Postgres> {
                     cv.size AS size,
                     cv.metadata AS metadata,
                     cv.created_at::timestamptz AS created_at,
-                    cv.component_type AS component_type
+                    cv.component_type AS component_type,
+                    cv.compatibility_report AS compatibility_report,
+                    cv.initial_files_hash AS initial_files_hash
                 FROM components c
                     JOIN component_versions cv ON c.component_id = cv.component_id
                 WHERE c.component_id = $1
@@ -321,7 +888,9 @@ impl ComponentRepo for DbComponentRepo<sqlx::Postgres> {
                     cv.size AS size,
                     cv.metadata AS metadata,
                     cv.created_at::timestamptz AS created_at,
-                    cv.component_type AS component_type
+                    cv.component_type AS component_type,
+                    cv.compatibility_report AS compatibility_report,
+                    cv.initial_files_hash AS initial_files_hash
                 FROM components c
                     JOIN component_versions cv ON c.component_id = cv.component_id
                 WHERE c.namespace = $1
@@ -345,7 +914,9 @@ impl ComponentRepo for DbComponentRepo<sqlx::Postgres> {
                     cv.size AS size,
                     cv.metadata AS metadata,
                     cv.created_at AS created_at,
-                    cv.component_type AS component_type
+                    cv.component_type AS component_type,
+                    cv.compatibility_report AS compatibility_report,
+                    cv.initial_files_hash AS initial_files_hash
                 FROM components c
                     JOIN component_versions cv ON c.component_id = cv.component_id
                 WHERE c.namespace = $1
@@ -357,11 +928,11 @@ impl ComponentRepo for DbComponentRepo<sqlx::Postgres> {
         .map_err(|e| e.into())
     }
 
-    #[when(sqlx::Postgres -> get_latest_version)]
-    async fn get_latest_version_postgres(
+    #[when(sqlx::Postgres -> get_all_latest)]
+    async fn get_all_latest_postgres(
         &self,
-        component_id: &Uuid,
-    ) -> Result<Option<ComponentRecord>, RepoError> {
+        namespace: &str,
+    ) -> Result<Vec<ComponentRecord>, RepoError> {
         sqlx::query_as::<_, ComponentRecord>(
             r#"
                 SELECT
@@ -372,24 +943,30 @@ impl ComponentRepo for DbComponentRepo<sqlx::Postgres> {
                     cv.size AS size,
                     cv.metadata AS metadata,
                     cv.created_at::timestamptz AS created_at,
-                    cv.component_type AS component_type
+                    cv.component_type AS component_type,
+                    cv.compatibility_report AS compatibility_report,
+                    cv.initial_files_hash AS initial_files_hash
                 FROM components c
                     JOIN component_versions cv ON c.component_id = cv.component_id
-                WHERE c.component_id = $1
-                ORDER BY cv.version DESC LIMIT 1
+                WHERE c.namespace = $1
+                    AND cv.version = (
+                        SELECT MAX(latest.version)
+                        FROM component_versions latest
+                        WHERE latest.component_id = c.component_id
+                    )
                 "#,
         )
-        .bind(component_id)
-        .fetch_optional(self.db_pool.deref())
+        .bind(namespace)
+        .fetch_all(self.db_pool.deref())
         .await
         .map_err(|e| e.into())
     }
 
-    #[when(sqlx::Sqlite -> get_latest_version)]
-    async fn get_latest_version_sqlite(
+    #[when(sqlx::Sqlite -> get_all_latest)]
+    async fn get_all_latest_sqlite(
         &self,
-        component_id: &Uuid,
-    ) -> Result<Option<ComponentRecord>, RepoError> {
+        namespace: &str,
+    ) -> Result<Vec<ComponentRecord>, RepoError> {
         sqlx::query_as::<_, ComponentRecord>(
             r#"
                 SELECT
@@ -400,24 +977,29 @@ impl ComponentRepo for DbComponentRepo<sqlx::Postgres> {
                     cv.size AS size,
                     cv.metadata AS metadata,
                     cv.created_at AS created_at,
-                    cv.component_type AS component_type
+                    cv.component_type AS component_type,
+                    cv.compatibility_report AS compatibility_report,
+                    cv.initial_files_hash AS initial_files_hash
                 FROM components c
                     JOIN component_versions cv ON c.component_id = cv.component_id
-                WHERE c.component_id = $1
-                ORDER BY cv.version DESC LIMIT 1
+                WHERE c.namespace = $1
+                    AND cv.version = (
+                        SELECT MAX(latest.version)
+                        FROM component_versions latest
+                        WHERE latest.component_id = c.component_id
+                    )
                 "#,
         )
-        .bind(component_id)
-        .fetch_optional(self.db_pool.deref())
+        .bind(namespace)
+        .fetch_all(self.db_pool.deref())
         .await
         .map_err(|e| e.into())
     }
 
-    #[when(sqlx::Postgres -> get_by_version)]
-    async fn get_by_version_postgres(
+    #[when(sqlx::Postgres -> get_latest_version)]
+    async fn get_latest_version_postgres(
         &self,
         component_id: &Uuid,
-        version: u64,
     ) -> Result<Option<ComponentRecord>, RepoError> {
         sqlx::query_as::<_, ComponentRecord>(
             r#"
@@ -429,24 +1011,25 @@ impl ComponentRepo for DbComponentRepo<sqlx::Postgres> {
                     cv.size AS size,
                     cv.metadata AS metadata,
                     cv.created_at::timestamptz AS created_at,
-                    cv.component_type AS component_type
+                    cv.component_type AS component_type,
+                    cv.compatibility_report AS compatibility_report,
+                    cv.initial_files_hash AS initial_files_hash
                 FROM components c
                     JOIN component_versions cv ON c.component_id = cv.component_id
-                WHERE c.component_id = $1 AND cv.version = $2
+                WHERE c.component_id = $1
+                ORDER BY cv.version DESC LIMIT 1
                 "#,
         )
         .bind(component_id)
-        .bind(version as i64)
         .fetch_optional(self.db_pool.deref())
         .await
         .map_err(|e| e.into())
     }
 
-    #[when(sqlx::Sqlite -> get_by_version)]
-    async fn get_by_version_sqlite(
+    #[when(sqlx::Sqlite -> get_latest_version)]
+    async fn get_latest_version_sqlite(
         &self,
         component_id: &Uuid,
-        version: u64,
     ) -> Result<Option<ComponentRecord>, RepoError> {
         sqlx::query_as::<_, ComponentRecord>(
             r#"
@@ -458,11 +1041,75 @@ impl ComponentRepo for DbComponentRepo<sqlx::Postgres> {
                     cv.size AS size,
                     cv.metadata AS metadata,
                     cv.created_at AS created_at,
-                    cv.component_type AS component_type
+                    cv.component_type AS component_type,
+                    cv.compatibility_report AS compatibility_report,
+                    cv.initial_files_hash AS initial_files_hash
                 FROM components c
                     JOIN component_versions cv ON c.component_id = cv.component_id
-                WHERE c.component_id = $1 AND cv.version = $2
-                "#,
+                WHERE c.component_id = $1
+                ORDER BY cv.version DESC LIMIT 1
+                "#,
+        )
+        .bind(component_id)
+        .fetch_optional(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+
+    #[when(sqlx::Postgres -> get_by_version)]
+    async fn get_by_version_postgres(
+        &self,
+        component_id: &Uuid,
+        version: u64,
+    ) -> Result<Option<ComponentRecord>, RepoError> {
+        sqlx::query_as::<_, ComponentRecord>(
+            r#"
+                SELECT
+                    c.namespace AS namespace,
+                    c.name AS name,
+                    c.component_id AS component_id,
+                    cv.version AS version,
+                    cv.size AS size,
+                    cv.metadata AS metadata,
+                    cv.created_at::timestamptz AS created_at,
+                    cv.component_type AS component_type,
+                    cv.compatibility_report AS compatibility_report,
+                    cv.initial_files_hash AS initial_files_hash
+                FROM components c
+                    JOIN component_versions cv ON c.component_id = cv.component_id
+                WHERE c.component_id = $1 AND cv.version = $2
+                "#,
+        )
+        .bind(component_id)
+        .bind(version as i64)
+        .fetch_optional(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+
+    #[when(sqlx::Sqlite -> get_by_version)]
+    async fn get_by_version_sqlite(
+        &self,
+        component_id: &Uuid,
+        version: u64,
+    ) -> Result<Option<ComponentRecord>, RepoError> {
+        sqlx::query_as::<_, ComponentRecord>(
+            r#"
+                SELECT
+                    c.namespace AS namespace,
+                    c.name AS name,
+                    c.component_id AS component_id,
+                    cv.version AS version,
+                    cv.size AS size,
+                    cv.metadata AS metadata,
+                    cv.created_at AS created_at,
+                    cv.component_type AS component_type,
+                    cv.compatibility_report AS compatibility_report,
+                    cv.initial_files_hash AS initial_files_hash
+                FROM components c
+                    JOIN component_versions cv ON c.component_id = cv.component_id
+                WHERE c.component_id = $1 AND cv.version = $2
+                "#,
         )
         .bind(component_id)
         .bind(version as i64)
@@ -487,7 +1134,9 @@ impl ComponentRepo for DbComponentRepo<sqlx::Postgres> {
                     cv.size AS size,
                     cv.metadata AS metadata,
                     cv.created_at::timestamptz AS created_at,
-                    cv.component_type AS component_type
+                    cv.component_type AS component_type,
+                    cv.compatibility_report AS compatibility_report,
+                    cv.initial_files_hash AS initial_files_hash
                 FROM components c
                     JOIN component_versions cv ON c.component_id = cv.component_id
                 WHERE c.namespace = $1 AND c.name = $2
@@ -516,7 +1165,9 @@ impl ComponentRepo for DbComponentRepo<sqlx::Postgres> {
                     cv.size AS size,
                     cv.metadata AS metadata,
                     cv.created_at AS created_at,
-                    cv.component_type AS component_type
+                    cv.component_type AS component_type,
+                    cv.compatibility_report AS compatibility_report,
+                    cv.initial_files_hash AS initial_files_hash
                 FROM components c
                     JOIN component_versions cv ON c.component_id = cv.component_id
                 WHERE c.namespace = $1 AND c.name = $2
@@ -529,15 +1180,51 @@ impl ComponentRepo for DbComponentRepo<sqlx::Postgres> {
         .map_err(|e| e.into())
     }
 
-    async fn get_id_by_name(&self, namespace: &str, name: &str) -> Result<Option<Uuid>, RepoError> {
+    #[when(sqlx::Postgres -> update_name_scopes)]
+    async fn update_name_scopes_postgres(&self, per_project: bool) -> Result<(), RepoError> {
+        let query = if per_project {
+            r#"
+                UPDATE components
+                SET name_scope = COALESCE(
+                    (SELECT replace(cp.project_id::text, '-', '') FROM component_projects cp
+                     WHERE cp.component_id = components.component_id),
+                    ''
+                )
+            "#
+        } else {
+            "UPDATE components SET name_scope = '' WHERE name_scope <> ''"
+        };
+        sqlx::query(query).execute(self.db_pool.deref()).await?;
+        Ok(())
+    }
+
+    #[when(sqlx::Sqlite -> update_name_scopes)]
+    async fn update_name_scopes_sqlite(&self, per_project: bool) -> Result<(), RepoError> {
+        let query = if per_project {
+            r#"
+                UPDATE components
+                SET name_scope = COALESCE(
+                    (SELECT lower(hex(cp.project_id)) FROM component_projects cp
+                     WHERE cp.component_id = components.component_id),
+                    ''
+                )
+            "#
+        } else {
+            "UPDATE components SET name_scope = '' WHERE name_scope <> ''"
+        };
+        sqlx::query(query).execute(self.db_pool.deref()).await?;
+        Ok(())
+    }
+
+    async fn get_ids_by_name(&self, namespace: &str, name: &str) -> Result<Vec<Uuid>, RepoError> {
         let result =
             sqlx::query("SELECT component_id FROM components WHERE namespace = $1 AND name = $2")
                 .bind(namespace)
                 .bind(name)
-                .fetch_optional(self.db_pool.deref())
+                .fetch_all(self.db_pool.deref())
                 .await?;
 
-        Ok(result.map(|x| x.get("component_id")))
+        Ok(result.into_iter().map(|x| x.get("component_id")).collect())
     }
 
     async fn get_namespace(&self, component_id: &Uuid) -> Result<Option<String>, RepoError> {
@@ -551,6 +1238,72 @@ impl ComponentRepo for DbComponentRepo<sqlx::Postgres> {
 
     async fn delete(&self, namespace: &str, component_id: &Uuid) -> Result<(), RepoError> {
         let mut transaction = self.db_pool.begin().await?;
+        sqlx::query(
+            r#"
+                DELETE FROM component_deletions
+                WHERE component_id IN (SELECT component_id FROM components WHERE namespace = $1 AND component_id = $2)
+            "#
+        )
+            .bind(namespace)
+            .bind(component_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        sqlx::query(
+            r#"
+                DELETE FROM component_version_aliases
+                WHERE component_id IN (SELECT component_id FROM components WHERE namespace = $1 AND component_id = $2)
+            "#
+        )
+            .bind(namespace)
+            .bind(component_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        sqlx::query(
+            r#"
+                DELETE FROM component_version_alias_changes
+                WHERE component_id IN (SELECT component_id FROM components WHERE namespace = $1 AND component_id = $2)
+            "#
+        )
+            .bind(namespace)
+            .bind(component_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        sqlx::query(
+            r#"
+                DELETE FROM component_projects
+                WHERE component_id IN (SELECT component_id FROM components WHERE namespace = $1 AND component_id = $2)
+            "#
+        )
+            .bind(namespace)
+            .bind(component_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        sqlx::query(
+            r#"
+                DELETE FROM component_update_policy
+                WHERE component_id IN (SELECT component_id FROM components WHERE namespace = $1 AND component_id = $2)
+            "#
+        )
+            .bind(namespace)
+            .bind(component_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        sqlx::query(
+            r#"
+                DELETE FROM component_observability
+                WHERE component_id IN (SELECT component_id FROM components WHERE namespace = $1 AND component_id = $2)
+            "#
+        )
+            .bind(namespace)
+            .bind(component_id)
+            .execute(&mut *transaction)
+            .await?;
+
         sqlx::query(
             r#"
                 DELETE FROM component_versions
@@ -571,6 +1324,501 @@ impl ComponentRepo for DbComponentRepo<sqlx::Postgres> {
         transaction.commit().await?;
         Ok(())
     }
+
+    async fn soft_delete(
+        &self,
+        namespace: &str,
+        component_id: &Uuid,
+        deleted_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<bool, RepoError> {
+        let result = sqlx::query(
+            r#"
+              INSERT INTO component_deletions
+                (component_id, deleted_at)
+              SELECT component_id, $3 FROM components WHERE namespace = $1 AND component_id = $2
+              ON CONFLICT (component_id) DO NOTHING
+               "#,
+        )
+        .bind(namespace)
+        .bind(component_id)
+        .bind(deleted_at)
+        .execute(self.db_pool.deref())
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn restore(&self, namespace: &str, component_id: &Uuid) -> Result<bool, RepoError> {
+        let result = sqlx::query(
+            r#"
+                DELETE FROM component_deletions
+                WHERE component_id IN (SELECT component_id FROM components WHERE namespace = $1 AND component_id = $2)
+            "#,
+        )
+        .bind(namespace)
+        .bind(component_id)
+        .execute(self.db_pool.deref())
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn is_deleted(&self, component_id: &Uuid) -> Result<bool, RepoError> {
+        let result =
+            sqlx::query("SELECT component_id FROM component_deletions WHERE component_id = $1")
+                .bind(component_id)
+                .fetch_optional(self.db_pool.deref())
+                .await?;
+
+        Ok(result.is_some())
+    }
+
+    async fn get_deleted_ids(&self, namespace: &str) -> Result<Vec<Uuid>, RepoError> {
+        let result: Vec<(Uuid,)> = sqlx::query_as(
+            r#"
+                SELECT d.component_id
+                FROM component_deletions d
+                JOIN components c ON c.component_id = d.component_id
+                WHERE c.namespace = $1
+            "#,
+        )
+        .bind(namespace)
+        .fetch_all(self.db_pool.deref())
+        .await?;
+
+        Ok(result.into_iter().map(|(id,)| id).collect())
+    }
+
+    async fn get_deleted_before(
+        &self,
+        deleted_before: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<(String, Uuid)>, RepoError> {
+        let result: Vec<(String, Uuid)> = sqlx::query_as(
+            r#"
+                SELECT c.namespace, d.component_id
+                FROM component_deletions d
+                JOIN components c ON c.component_id = d.component_id
+                WHERE d.deleted_at < $1
+            "#,
+        )
+        .bind(deleted_before)
+        .fetch_all(self.db_pool.deref())
+        .await?;
+
+        Ok(result)
+    }
+
+    async fn claim_purge(
+        &self,
+        component_id: &Uuid,
+        claimed_at: chrono::DateTime<chrono::Utc>,
+        claimed_before: chrono::DateTime<chrono::Utc>,
+    ) -> Result<bool, RepoError> {
+        let result = sqlx::query(
+            r#"
+                UPDATE component_deletions
+                SET purge_claimed_at = $2
+                WHERE component_id = $1 AND (purge_claimed_at IS NULL OR purge_claimed_at < $3)
+            "#,
+        )
+        .bind(component_id)
+        .bind(claimed_at)
+        .bind(claimed_before)
+        .execute(self.db_pool.deref())
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn update_metadata(
+        &self,
+        component_id: &Uuid,
+        version: u64,
+        metadata: &[u8],
+    ) -> Result<(), RepoError> {
+        sqlx::query(
+            r#"
+              UPDATE component_versions
+              SET metadata = $3
+              WHERE component_id = $1 AND version = $2
+               "#,
+        )
+        .bind(component_id)
+        .bind(version as i64)
+        .bind(metadata)
+        .execute(self.db_pool.deref())
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_observability(
+        &self,
+        component_id: &Uuid,
+    ) -> Result<Option<ComponentObservabilityRecord>, RepoError> {
+        sqlx::query_as::<_, ComponentObservabilityRecord>(
+            r#"
+                SELECT component_id, log_level, metrics_enabled, trace_sampling_rate, payload_capture_sampling_rate, sensitive_parameters
+                FROM component_observability
+                WHERE component_id = $1
+                "#,
+        )
+        .bind(component_id)
+        .fetch_optional(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+
+    async fn update_observability(
+        &self,
+        observability: &ComponentObservabilityRecord,
+    ) -> Result<(), RepoError> {
+        sqlx::query(
+            r#"
+              INSERT INTO component_observability
+                (component_id, log_level, metrics_enabled, trace_sampling_rate, payload_capture_sampling_rate, sensitive_parameters)
+              VALUES
+                ($1, $2, $3, $4, $5, $6)
+              ON CONFLICT (component_id) DO UPDATE
+              SET log_level = $2,
+                  metrics_enabled = $3,
+                  trace_sampling_rate = $4,
+                  payload_capture_sampling_rate = $5,
+                  sensitive_parameters = $6
+               "#,
+        )
+        .bind(observability.component_id)
+        .bind(observability.log_level)
+        .bind(observability.metrics_enabled)
+        .bind(observability.trace_sampling_rate)
+        .bind(observability.payload_capture_sampling_rate)
+        .bind(&observability.sensitive_parameters)
+        .execute(self.db_pool.deref())
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_update_policy(
+        &self,
+        component_id: &Uuid,
+    ) -> Result<Option<ComponentUpdatePolicyRecord>, RepoError> {
+        sqlx::query_as::<_, ComponentUpdatePolicyRecord>(
+            r#"
+                SELECT component_id, enabled, update_mode, filter, max_concurrent_updates_per_executor, version_alias
+                FROM component_update_policy
+                WHERE component_id = $1
+                "#,
+        )
+        .bind(component_id)
+        .fetch_optional(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+
+    async fn update_update_policy(
+        &self,
+        policy: &ComponentUpdatePolicyRecord,
+    ) -> Result<(), RepoError> {
+        sqlx::query(
+            r#"
+              INSERT INTO component_update_policy
+                (component_id, enabled, update_mode, filter, max_concurrent_updates_per_executor, version_alias)
+              VALUES
+                ($1, $2, $3, $4, $5, $6)
+              ON CONFLICT (component_id) DO UPDATE
+              SET enabled = $2,
+                  update_mode = $3,
+                  filter = $4,
+                  max_concurrent_updates_per_executor = $5,
+                  version_alias = $6
+               "#,
+        )
+        .bind(policy.component_id)
+        .bind(policy.enabled)
+        .bind(policy.update_mode)
+        .bind(&policy.filter)
+        .bind(policy.max_concurrent_updates_per_executor)
+        .bind(&policy.version_alias)
+        .execute(self.db_pool.deref())
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_transformation_plugins(
+        &self,
+        namespace: &str,
+    ) -> Result<Vec<ComponentTransformationPluginRecord>, RepoError> {
+        sqlx::query_as::<_, ComponentTransformationPluginRecord>(
+            r#"
+                SELECT namespace, name, priority, transformation
+                FROM component_transformation_plugins
+                WHERE namespace = $1
+                ORDER BY priority, name
+                "#,
+        )
+        .bind(namespace)
+        .fetch_all(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+
+    async fn update_transformation_plugin(
+        &self,
+        plugin: &ComponentTransformationPluginRecord,
+    ) -> Result<(), RepoError> {
+        sqlx::query(
+            r#"
+              INSERT INTO component_transformation_plugins
+                (namespace, name, priority, transformation)
+              VALUES
+                ($1, $2, $3, $4)
+              ON CONFLICT (namespace, name) DO UPDATE
+              SET priority = $3,
+                  transformation = $4
+               "#,
+        )
+        .bind(&plugin.namespace)
+        .bind(&plugin.name)
+        .bind(plugin.priority)
+        .bind(&plugin.transformation)
+        .execute(self.db_pool.deref())
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete_transformation_plugin(
+        &self,
+        namespace: &str,
+        name: &str,
+    ) -> Result<bool, RepoError> {
+        let result = sqlx::query(
+            "DELETE FROM component_transformation_plugins WHERE namespace = $1 AND name = $2",
+        )
+        .bind(namespace)
+        .bind(name)
+        .execute(self.db_pool.deref())
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    #[when(sqlx::Postgres -> get_version_aliases)]
+    async fn get_version_aliases_postgres(
+        &self,
+        component_id: &Uuid,
+    ) -> Result<Vec<ComponentVersionAliasRecord>, RepoError> {
+        sqlx::query_as::<_, ComponentVersionAliasRecord>(
+            r#"
+                SELECT component_id, name, version, updated_at::timestamptz
+                FROM component_version_aliases
+                WHERE component_id = $1
+                ORDER BY name
+                "#,
+        )
+        .bind(component_id)
+        .fetch_all(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+
+    #[when(sqlx::Sqlite -> get_version_aliases)]
+    async fn get_version_aliases_sqlite(
+        &self,
+        component_id: &Uuid,
+    ) -> Result<Vec<ComponentVersionAliasRecord>, RepoError> {
+        sqlx::query_as::<_, ComponentVersionAliasRecord>(
+            r#"
+                SELECT component_id, name, version, updated_at
+                FROM component_version_aliases
+                WHERE component_id = $1
+                ORDER BY name
+                "#,
+        )
+        .bind(component_id)
+        .fetch_all(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+
+    #[when(sqlx::Postgres -> get_version_alias)]
+    async fn get_version_alias_postgres(
+        &self,
+        component_id: &Uuid,
+        name: &str,
+    ) -> Result<Option<ComponentVersionAliasRecord>, RepoError> {
+        sqlx::query_as::<_, ComponentVersionAliasRecord>(
+            r#"
+                SELECT component_id, name, version, updated_at::timestamptz
+                FROM component_version_aliases
+                WHERE component_id = $1 AND name = $2
+                "#,
+        )
+        .bind(component_id)
+        .bind(name)
+        .fetch_optional(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+
+    #[when(sqlx::Sqlite -> get_version_alias)]
+    async fn get_version_alias_sqlite(
+        &self,
+        component_id: &Uuid,
+        name: &str,
+    ) -> Result<Option<ComponentVersionAliasRecord>, RepoError> {
+        sqlx::query_as::<_, ComponentVersionAliasRecord>(
+            r#"
+                SELECT component_id, name, version, updated_at
+                FROM component_version_aliases
+                WHERE component_id = $1 AND name = $2
+                "#,
+        )
+        .bind(component_id)
+        .bind(name)
+        .fetch_optional(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+
+    async fn update_version_alias(
+        &self,
+        alias: &ComponentVersionAliasRecord,
+    ) -> Result<Option<i64>, RepoError> {
+        let mut transaction = self.db_pool.begin().await?;
+
+        let previous_version: Option<(i64,)> = sqlx::query_as(
+            "SELECT version FROM component_version_aliases WHERE component_id = $1 AND name = $2",
+        )
+        .bind(alias.component_id)
+        .bind(&alias.name)
+        .fetch_optional(&mut *transaction)
+        .await?;
+        let previous_version = previous_version.map(|(version,)| version);
+
+        sqlx::query(
+            r#"
+              INSERT INTO component_version_aliases
+                (component_id, name, version, updated_at)
+              VALUES
+                ($1, $2, $3, $4)
+              ON CONFLICT (component_id, name) DO UPDATE
+              SET version = $3,
+                  updated_at = $4
+               "#,
+        )
+        .bind(alias.component_id)
+        .bind(&alias.name)
+        .bind(alias.version)
+        .bind(alias.updated_at)
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query(
+            r#"
+              INSERT INTO component_version_alias_changes
+                (component_id, name, previous_version, version, changed_at)
+              VALUES
+                ($1, $2, $3, $4, $5)
+               "#,
+        )
+        .bind(alias.component_id)
+        .bind(&alias.name)
+        .bind(previous_version)
+        .bind(Some(alias.version))
+        .bind(alias.updated_at)
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+        Ok(previous_version)
+    }
+
+    async fn delete_version_alias(
+        &self,
+        component_id: &Uuid,
+        name: &str,
+        deleted_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Option<i64>, RepoError> {
+        let mut transaction = self.db_pool.begin().await?;
+
+        let previous_version: Option<(i64,)> = sqlx::query_as(
+            "SELECT version FROM component_version_aliases WHERE component_id = $1 AND name = $2",
+        )
+        .bind(component_id)
+        .bind(name)
+        .fetch_optional(&mut *transaction)
+        .await?;
+        let previous_version = previous_version.map(|(version,)| version);
+
+        if previous_version.is_some() {
+            sqlx::query(
+                "DELETE FROM component_version_aliases WHERE component_id = $1 AND name = $2",
+            )
+            .bind(component_id)
+            .bind(name)
+            .execute(&mut *transaction)
+            .await?;
+
+            sqlx::query(
+                r#"
+                  INSERT INTO component_version_alias_changes
+                    (component_id, name, previous_version, version, changed_at)
+                  VALUES
+                    ($1, $2, $3, NULL, $4)
+                   "#,
+            )
+            .bind(component_id)
+            .bind(name)
+            .bind(previous_version)
+            .bind(deleted_at)
+            .execute(&mut *transaction)
+            .await?;
+        }
+
+        transaction.commit().await?;
+        Ok(previous_version)
+    }
+
+    #[when(sqlx::Postgres -> get_version_alias_changes)]
+    async fn get_version_alias_changes_postgres(
+        &self,
+        component_id: &Uuid,
+    ) -> Result<Vec<ComponentVersionAliasChangeRecord>, RepoError> {
+        sqlx::query_as::<_, ComponentVersionAliasChangeRecord>(
+            r#"
+                SELECT component_id, name, previous_version, version, changed_at::timestamptz
+                FROM component_version_alias_changes
+                WHERE component_id = $1
+                ORDER BY changed_at
+                "#,
+        )
+        .bind(component_id)
+        .fetch_all(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+
+    #[when(sqlx::Sqlite -> get_version_alias_changes)]
+    async fn get_version_alias_changes_sqlite(
+        &self,
+        component_id: &Uuid,
+    ) -> Result<Vec<ComponentVersionAliasChangeRecord>, RepoError> {
+        sqlx::query_as::<_, ComponentVersionAliasChangeRecord>(
+            r#"
+                SELECT component_id, name, previous_version, version, changed_at
+                FROM component_version_alias_changes
+                WHERE component_id = $1
+                ORDER BY changed_at
+                "#,
+        )
+        .bind(component_id)
+        .fetch_all(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
 }
 
 pub mod record_metadata_serde {
@@ -579,21 +1827,29 @@ pub mod record_metadata_serde {
     use golem_common::model::component_metadata::ComponentMetadata;
     use prost::Message;
 
+    /// Metadata stored before the wasm-rpc dependencies were tracked, which have to be
+    /// detected again from the component
     pub const SERIALIZATION_VERSION_V1: u8 = 1u8;
+    pub const SERIALIZATION_VERSION_V2: u8 = 2u8;
 
     pub fn serialize(value: &ComponentMetadata) -> Result<Bytes, String> {
         let proto_value: ComponentMetadataProto = value.clone().into();
         let mut bytes = BytesMut::new();
-        bytes.put_u8(SERIALIZATION_VERSION_V1);
+        bytes.put_u8(SERIALIZATION_VERSION_V2);
         bytes.extend_from_slice(&proto_value.encode_to_vec());
         Ok(bytes.freeze())
     }
 
+    /// Whether the serialized metadata contains the wasm-rpc dependencies of the component
+    pub fn has_rpc_dependencies(bytes: &[u8]) -> bool {
+        bytes.first() != Some(&SERIALIZATION_VERSION_V1)
+    }
+
     pub fn deserialize(bytes: &[u8]) -> Result<ComponentMetadata, String> {
         let (version, data) = bytes.split_at(1);
 
         match version[0] {
-            SERIALIZATION_VERSION_V1 => {
+            SERIALIZATION_VERSION_V1 | SERIALIZATION_VERSION_V2 => {
                 let proto_value: ComponentMetadataProto = Message::decode(data)
                     .map_err(|e| format!("Failed to deserialize value: {e}"))?;
                 let value = proto_value.try_into()?;
@@ -0,0 +1,420 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use conditional_trait_gen::{trait_gen, when};
+use golem_common::model::ProjectId;
+use golem_service_base::model::Project;
+use golem_service_base::repo::RepoError;
+use sqlx::{Database, Pool};
+use std::ops::Deref;
+use std::result::Result;
+use std::sync::Arc;
+use tracing::{debug, error};
+use uuid::Uuid;
+
+#[derive(sqlx::FromRow, Debug, Clone)]
+pub struct ProjectRecord {
+    pub project_id: Uuid,
+    pub namespace: String,
+    pub name: String,
+    pub description: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<ProjectRecord> for Project {
+    fn from(value: ProjectRecord) -> Self {
+        Project {
+            project_id: ProjectId(value.project_id),
+            name: value.name,
+            description: value.description,
+            created_at: value.created_at,
+        }
+    }
+}
+
+impl ProjectRecord {
+    pub fn new(namespace: &str, project: Project) -> Self {
+        Self {
+            project_id: project.project_id.0,
+            namespace: namespace.to_string(),
+            name: project.name,
+            description: project.description,
+            created_at: project.created_at,
+        }
+    }
+}
+
+#[async_trait]
+pub trait ProjectRepo {
+    async fn create(&self, project: &ProjectRecord) -> Result<(), RepoError>;
+
+    async fn get(
+        &self,
+        namespace: &str,
+        project_id: &Uuid,
+    ) -> Result<Option<ProjectRecord>, RepoError>;
+
+    async fn get_all(&self, namespace: &str) -> Result<Vec<ProjectRecord>, RepoError>;
+
+    /// Updates the name and the description of the project, returns whether it existed
+    async fn update(&self, project: &ProjectRecord) -> Result<bool, RepoError>;
+
+    /// Returns whether the project existed
+    async fn delete(&self, namespace: &str, project_id: &Uuid) -> Result<bool, RepoError>;
+
+    async fn get_component_ids(
+        &self,
+        namespace: &str,
+        project_id: &Uuid,
+    ) -> Result<Vec<Uuid>, RepoError>;
+
+    async fn get_component_project(&self, component_id: &Uuid) -> Result<Option<Uuid>, RepoError>;
+
+    /// Assigns the component to the project, or removes it from its project if `project_id` is `None`,
+    /// moving its name to `name_scope` at the same time
+    async fn update_component_project(
+        &self,
+        component_id: &Uuid,
+        project_id: Option<&Uuid>,
+        name_scope: &str,
+    ) -> Result<(), RepoError>;
+}
+
+pub struct DbProjectRepo<DB: Database> {
+    db_pool: Arc<Pool<DB>>,
+}
+
+impl<DB: Database> DbProjectRepo<DB> {
+    pub fn new(db_pool: Arc<Pool<DB>>) -> Self {
+        Self { db_pool }
+    }
+}
+
+pub struct LoggedProjectRepo<Repo: ProjectRepo> {
+    repo: Repo,
+}
+
+impl<Repo: ProjectRepo> LoggedProjectRepo<Repo> {
+    pub fn new(repo: Repo) -> Self {
+        Self { repo }
+    }
+
+    fn logged_with_id<R>(
+        message: &'static str,
+        project_id: &Uuid,
+        result: Result<R, RepoError>,
+    ) -> Result<R, RepoError> {
+        match &result {
+            Ok(_) => debug!(project_id = project_id.to_string(), "{}", message),
+            Err(error) => error!(
+                project_id = project_id.to_string(),
+                error = error.to_string(),
+                "{message}"
+            ),
+        }
+        result
+    }
+
+    fn logged_with_component_id<R>(
+        message: &'static str,
+        component_id: &Uuid,
+        result: Result<R, RepoError>,
+    ) -> Result<R, RepoError> {
+        match &result {
+            Ok(_) => debug!(component_id = component_id.to_string(), "{}", message),
+            Err(error) => error!(
+                component_id = component_id.to_string(),
+                error = error.to_string(),
+                "{message}"
+            ),
+        }
+        result
+    }
+}
+
+#[async_trait]
+impl<Repo: ProjectRepo + Send + Sync> ProjectRepo for LoggedProjectRepo<Repo> {
+    async fn create(&self, project: &ProjectRecord) -> Result<(), RepoError> {
+        let result = self.repo.create(project).await;
+        Self::logged_with_id("create", &project.project_id, result)
+    }
+
+    async fn get(
+        &self,
+        namespace: &str,
+        project_id: &Uuid,
+    ) -> Result<Option<ProjectRecord>, RepoError> {
+        let result = self.repo.get(namespace, project_id).await;
+        Self::logged_with_id("get", project_id, result)
+    }
+
+    async fn get_all(&self, namespace: &str) -> Result<Vec<ProjectRecord>, RepoError> {
+        let result = self.repo.get_all(namespace).await;
+        match &result {
+            Ok(_) => debug!("get_all"),
+            Err(error) => error!(error = error.to_string(), "get_all"),
+        }
+        result
+    }
+
+    async fn update(&self, project: &ProjectRecord) -> Result<bool, RepoError> {
+        let result = self.repo.update(project).await;
+        Self::logged_with_id("update", &project.project_id, result)
+    }
+
+    async fn delete(&self, namespace: &str, project_id: &Uuid) -> Result<bool, RepoError> {
+        let result = self.repo.delete(namespace, project_id).await;
+        Self::logged_with_id("delete", project_id, result)
+    }
+
+    async fn get_component_ids(
+        &self,
+        namespace: &str,
+        project_id: &Uuid,
+    ) -> Result<Vec<Uuid>, RepoError> {
+        let result = self.repo.get_component_ids(namespace, project_id).await;
+        Self::logged_with_id("get_component_ids", project_id, result)
+    }
+
+    async fn get_component_project(&self, component_id: &Uuid) -> Result<Option<Uuid>, RepoError> {
+        let result = self.repo.get_component_project(component_id).await;
+        Self::logged_with_component_id("get_component_project", component_id, result)
+    }
+
+    async fn update_component_project(
+        &self,
+        component_id: &Uuid,
+        project_id: Option<&Uuid>,
+        name_scope: &str,
+    ) -> Result<(), RepoError> {
+        let result = self
+            .repo
+            .update_component_project(component_id, project_id, name_scope)
+            .await;
+        Self::logged_with_component_id("update_component_project", component_id, result)
+    }
+}
+
+#[trait_gen(sqlx::Postgres -> sqlx::Postgres, sqlx::Sqlite)]
+#[async_trait]
+impl ProjectRepo for DbProjectRepo<sqlx::Postgres> {
+    async fn create(&self, project: &ProjectRecord) -> Result<(), RepoError> {
+        sqlx::query(
+            r#"
+              INSERT INTO projects
+                (project_id, namespace, name, description, created_at)
+              VALUES
+                ($1, $2, $3, $4, $5)
+               "#,
+        )
+        .bind(project.project_id)
+        .bind(&project.namespace)
+        .bind(&project.name)
+        .bind(&project.description)
+        .bind(project.created_at)
+        .execute(self.db_pool.deref())
+        .await?;
+
+        Ok(())
+    }
+
+    #[when(sqlx::Postgres -> get)]
+    async fn get_postgres(
+        &self,
+        namespace: &str,
+        project_id: &Uuid,
+    ) -> Result<Option<ProjectRecord>, RepoError> {
+        sqlx::query_as::<_, ProjectRecord>(
+            r#"
+                SELECT project_id, namespace, name, description, created_at::timestamptz
+                FROM projects
+                WHERE namespace = $1 AND project_id = $2
+                "#,
+        )
+        .bind(namespace)
+        .bind(project_id)
+        .fetch_optional(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+
+    #[when(sqlx::Sqlite -> get)]
+    async fn get_sqlite(
+        &self,
+        namespace: &str,
+        project_id: &Uuid,
+    ) -> Result<Option<ProjectRecord>, RepoError> {
+        sqlx::query_as::<_, ProjectRecord>(
+            r#"
+                SELECT project_id, namespace, name, description, created_at
+                FROM projects
+                WHERE namespace = $1 AND project_id = $2
+                "#,
+        )
+        .bind(namespace)
+        .bind(project_id)
+        .fetch_optional(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+
+    #[when(sqlx::Postgres -> get_all)]
+    async fn get_all_postgres(&self, namespace: &str) -> Result<Vec<ProjectRecord>, RepoError> {
+        sqlx::query_as::<_, ProjectRecord>(
+            r#"
+                SELECT project_id, namespace, name, description, created_at::timestamptz
+                FROM projects
+                WHERE namespace = $1
+                ORDER BY name
+                "#,
+        )
+        .bind(namespace)
+        .fetch_all(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+
+    #[when(sqlx::Sqlite -> get_all)]
+    async fn get_all_sqlite(&self, namespace: &str) -> Result<Vec<ProjectRecord>, RepoError> {
+        sqlx::query_as::<_, ProjectRecord>(
+            r#"
+                SELECT project_id, namespace, name, description, created_at
+                FROM projects
+                WHERE namespace = $1
+                ORDER BY name
+                "#,
+        )
+        .bind(namespace)
+        .fetch_all(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+
+    async fn update(&self, project: &ProjectRecord) -> Result<bool, RepoError> {
+        let result = sqlx::query(
+            r#"
+              UPDATE projects
+              SET name = $3, description = $4
+              WHERE namespace = $1 AND project_id = $2
+               "#,
+        )
+        .bind(&project.namespace)
+        .bind(project.project_id)
+        .bind(&project.name)
+        .bind(&project.description)
+        .execute(self.db_pool.deref())
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete(&self, namespace: &str, project_id: &Uuid) -> Result<bool, RepoError> {
+        let mut transaction = self.db_pool.begin().await?;
+
+        sqlx::query(
+            r#"
+                DELETE FROM component_projects
+                WHERE project_id IN (SELECT project_id FROM projects WHERE namespace = $1 AND project_id = $2)
+            "#,
+        )
+        .bind(namespace)
+        .bind(project_id)
+        .execute(&mut *transaction)
+        .await?;
+
+        let result = sqlx::query("DELETE FROM projects WHERE namespace = $1 AND project_id = $2")
+            .bind(namespace)
+            .bind(project_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        transaction.commit().await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_component_ids(
+        &self,
+        namespace: &str,
+        project_id: &Uuid,
+    ) -> Result<Vec<Uuid>, RepoError> {
+        let result: Vec<(Uuid,)> = sqlx::query_as(
+            r#"
+                SELECT cp.component_id FROM component_projects cp
+                JOIN projects p ON p.project_id = cp.project_id
+                WHERE p.namespace = $1 AND cp.project_id = $2
+            "#,
+        )
+        .bind(namespace)
+        .bind(project_id)
+        .fetch_all(self.db_pool.deref())
+        .await?;
+
+        Ok(result.into_iter().map(|(id,)| id).collect())
+    }
+
+    async fn get_component_project(&self, component_id: &Uuid) -> Result<Option<Uuid>, RepoError> {
+        let result: Option<(Uuid,)> =
+            sqlx::query_as("SELECT project_id FROM component_projects WHERE component_id = $1")
+                .bind(component_id)
+                .fetch_optional(self.db_pool.deref())
+                .await?;
+
+        Ok(result.map(|(id,)| id))
+    }
+
+    async fn update_component_project(
+        &self,
+        component_id: &Uuid,
+        project_id: Option<&Uuid>,
+        name_scope: &str,
+    ) -> Result<(), RepoError> {
+        let mut transaction = self.db_pool.begin().await?;
+
+        sqlx::query("UPDATE components SET name_scope = $1 WHERE component_id = $2")
+            .bind(name_scope)
+            .bind(component_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        match project_id {
+            Some(project_id) => {
+                sqlx::query(
+                    r#"
+                      INSERT INTO component_projects
+                        (component_id, project_id)
+                      VALUES
+                        ($1, $2)
+                      ON CONFLICT (component_id) DO UPDATE
+                      SET project_id = $2
+                       "#,
+                )
+                .bind(component_id)
+                .bind(project_id)
+                .execute(&mut *transaction)
+                .await?;
+            }
+            None => {
+                sqlx::query("DELETE FROM component_projects WHERE component_id = $1")
+                    .bind(component_id)
+                    .execute(&mut *transaction)
+                    .await?;
+            }
+        }
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+}
@@ -12,20 +12,44 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::{Debug, Display};
 use std::num::TryFromIntError;
+use std::str::FromStr;
 use std::sync::Arc;
 
+use crate::config::{ComponentNameUniqueness, TransformationWebhookConfig};
 use crate::model::Component;
-use crate::repo::component::ComponentRepo;
+use crate::repo::component::{
+    record_metadata_serde, ComponentObservabilityRecord, ComponentRepo,
+    ComponentTransformationPluginRecord, ComponentUpdatePolicyRecord, ComponentVersionAliasRecord,
+};
+use crate::repo::project::{ProjectRecord, ProjectRepo};
 use crate::service::component_compilation::ComponentCompilationService;
-use crate::service::component_processor::process_component;
+use crate::service::component_processor::{
+    call_transformation_webhook, compose_component_blocking, process_component,
+    strip_custom_sections,
+};
+use crate::service::worker_update_rollout::WorkerUpdateRolloutService;
 use async_trait::async_trait;
-use chrono::Utc;
-use golem_common::model::component_metadata::ComponentProcessingError;
-use golem_common::model::{ComponentId, ComponentType};
+use chrono::{DateTime, Utc};
+use golem_common::model::component_compatibility::ComponentCompatibilityReport;
+use golem_common::model::component_metadata::{
+    ComponentPlug, ComponentProcessingError, RawComponentMetadata,
+};
+use golem_common::model::component_observability::ComponentObservability;
+use golem_common::model::initial_files::{archive_hash, validate_archive};
+use golem_common::model::{
+    ComponentId, ComponentType, ProjectId, StringFilterComparator, WorkerFilter,
+    COMPONENT_VERSION_ALIAS_ENV_VAR,
+};
 use golem_common::SafeDisplay;
-use golem_service_base::model::{ComponentName, VersionedComponentId};
+use golem_service_base::model::{
+    validate_component_labels, validate_component_version_alias, ComponentDependency,
+    ComponentDependencyGraph, ComponentLabelFilter, ComponentName, ComponentTransformation,
+    ComponentTransformationPlugin, ComponentUpdatePolicy, ComponentVersionAlias,
+    ComponentVersionAliasChange, Project, ProjectData, VersionedComponentId,
+};
 use golem_service_base::repo::RepoError;
 use golem_service_base::service::component_object_store::ComponentObjectStore;
 use golem_service_base::stream::ByteStream;
@@ -42,6 +66,39 @@ pub enum ComponentError {
     UnknownVersionedComponentId(VersionedComponentId),
     #[error(transparent)]
     ComponentProcessingError(#[from] ComponentProcessingError),
+    #[error("Invalid observability configuration: {0}")]
+    InvalidObservability(String),
+    #[error("Invalid update policy: {0}")]
+    InvalidUpdatePolicy(String),
+    #[error("Invalid labels: {0}")]
+    InvalidLabels(String),
+    #[error("Invalid initial files: {0}")]
+    InvalidInitialFiles(String),
+    #[error("Failed to compose component: {0}")]
+    ComponentCompositionFailed(String),
+    #[error("Invalid transformation plugin: {0}")]
+    InvalidTransformationPlugin(String),
+    #[error("Unknown transformation plugin: {0}")]
+    UnknownTransformationPlugin(String),
+    #[error("Transformation plugin {plugin} failed: {error}")]
+    ComponentTransformationFailed { plugin: String, error: String },
+    #[error("Unknown project: {0}")]
+    UnknownProject(ProjectId),
+    #[error("Project already exists: {0}")]
+    ProjectAlreadyExists(String),
+    #[error("Invalid project: {0}")]
+    InvalidProject(String),
+    #[error("Unknown version alias {alias} of component {component_id}")]
+    UnknownVersionAlias {
+        component_id: ComponentId,
+        alias: String,
+    },
+    #[error("Invalid version alias: {0}")]
+    InvalidVersionAlias(String),
+    #[error("Component is not deleted: {0}")]
+    ComponentNotDeleted(ComponentId),
+    #[error("Component version has no initial files: {0}")]
+    NoInitialFiles(VersionedComponentId),
     #[error("Internal repository error: {0}")]
     InternalRepoError(RepoError),
     #[error("Internal error: failed to convert {what}: {error}")]
@@ -73,6 +130,21 @@ impl SafeDisplay for ComponentError {
             ComponentError::UnknownComponentId(_) => self.to_string(),
             ComponentError::UnknownVersionedComponentId(_) => self.to_string(),
             ComponentError::ComponentProcessingError(inner) => inner.to_safe_string(),
+            ComponentError::InvalidObservability(_) => self.to_string(),
+            ComponentError::InvalidUpdatePolicy(_) => self.to_string(),
+            ComponentError::InvalidLabels(_) => self.to_string(),
+            ComponentError::InvalidInitialFiles(_) => self.to_string(),
+            ComponentError::ComponentCompositionFailed(_) => self.to_string(),
+            ComponentError::InvalidTransformationPlugin(_) => self.to_string(),
+            ComponentError::UnknownTransformationPlugin(_) => self.to_string(),
+            ComponentError::ComponentTransformationFailed { .. } => self.to_string(),
+            ComponentError::UnknownProject(_) => self.to_string(),
+            ComponentError::ProjectAlreadyExists(_) => self.to_string(),
+            ComponentError::InvalidProject(_) => self.to_string(),
+            ComponentError::UnknownVersionAlias { .. } => self.to_string(),
+            ComponentError::InvalidVersionAlias(_) => self.to_string(),
+            ComponentError::ComponentNotDeleted(_) => self.to_string(),
+            ComponentError::NoInitialFiles(_) => self.to_string(),
             ComponentError::InternalRepoError(inner) => inner.to_safe_string(),
             ComponentError::InternalConversionError { .. } => self.to_string(),
             ComponentError::ComponentStoreError { .. } => self.to_string(),
@@ -86,6 +158,39 @@ impl From<RepoError> for ComponentError {
     }
 }
 
+/// Reference to a stored component to be plugged into an uploaded one, formatted as
+/// `<component-id>` or `<component-id>@<version>`. The latest version is used if the version is
+/// not specified.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentPlugReference {
+    pub component_id: ComponentId,
+    pub version: Option<u64>,
+}
+
+impl FromStr for ComponentPlugReference {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (component_id, version) = match s.split_once('@') {
+            Some((component_id, version)) => (
+                component_id,
+                Some(
+                    version
+                        .parse::<u64>()
+                        .map_err(|e| format!("Invalid plug version {version}: {e}"))?,
+                ),
+            ),
+            None => (s, None),
+        };
+        let component_id = ComponentId::from_str(component_id)
+            .map_err(|e| format!("Invalid plug component id {component_id}: {e}"))?;
+        Ok(Self {
+            component_id,
+            version,
+        })
+    }
+}
+
 pub fn create_new_component<Namespace>(
     component_id: &ComponentId,
     component_name: &ComponentName,
@@ -111,17 +216,21 @@ where
         created_at: Utc::now(),
         versioned_component_id,
         component_type,
+        compatibility_report: None,
+        initial_files_hash: None,
     })
 }
 
 #[async_trait]
 pub trait ComponentService<Namespace> {
+    /// Creates a component, assigning it to the project if one is given
     async fn create(
         &self,
         component_id: &ComponentId,
         component_name: &ComponentName,
         component_type: ComponentType,
         data: Vec<u8>,
+        project_id: Option<&ProjectId>,
         namespace: &Namespace,
     ) -> Result<Component<Namespace>, ComponentError>;
 
@@ -133,6 +242,31 @@ pub trait ComponentService<Namespace> {
         namespace: &Namespace,
     ) -> Result<Component<Namespace>, ComponentError>;
 
+    /// Creates a component from an uploaded one composed with stored components, plugging their
+    /// exports into the matching imports of the uploaded one. The plugged versions are recorded
+    /// in the metadata of the new component.
+    async fn create_composed(
+        &self,
+        component_id: &ComponentId,
+        component_name: &ComponentName,
+        component_type: ComponentType,
+        data: Vec<u8>,
+        plugs: &[ComponentPlugReference],
+        project_id: Option<&ProjectId>,
+        namespace: &Namespace,
+    ) -> Result<Component<Namespace>, ComponentError>;
+
+    /// Updates a component with an uploaded version composed with stored components, the same way
+    /// as `create_composed`
+    async fn update_composed(
+        &self,
+        component_id: &ComponentId,
+        data: Vec<u8>,
+        component_type: Option<ComponentType>,
+        plugs: &[ComponentPlugReference],
+        namespace: &Namespace,
+    ) -> Result<Component<Namespace>, ComponentError>;
+
     async fn download(
         &self,
         component_id: &ComponentId,
@@ -154,15 +288,23 @@ pub trait ComponentService<Namespace> {
         namespace: &Namespace,
     ) -> Result<Option<Vec<u8>>, ComponentError>;
 
+    /// Components are returned only if their labels match all the label filters, and if a
+    /// project is given, only if they belong to it
     async fn find_by_name(
         &self,
         component_name: Option<ComponentName>,
+        label_filters: &[ComponentLabelFilter],
+        project_id: Option<&ProjectId>,
         namespace: &Namespace,
     ) -> Result<Vec<Component<Namespace>>, ComponentError>;
 
+    /// Finds the component with the given name in the scope of the project, or among the
+    /// components without a project if none is given. If component names are unique in the whole
+    /// namespace, any component with the name matches when no project is given.
     async fn find_id_by_name(
         &self,
         component_name: &ComponentName,
+        project_id: Option<&ProjectId>,
         namespace: &Namespace,
     ) -> Result<Option<ComponentId>, ComponentError>;
 
@@ -194,24 +336,220 @@ pub trait ComponentService<Namespace> {
         component_id: &ComponentId,
         namespace: &Namespace,
     ) -> Result<(), ComponentError>;
+
+    /// Marks the component as deleted. Deleted components are hidden from the listings, but
+    /// their workers keep running until the component gets purged after the retention period.
+    /// The name of a deleted component stays reserved until it gets purged.
+    async fn soft_delete(
+        &self,
+        component_id: &ComponentId,
+        namespace: &Namespace,
+    ) -> Result<(), ComponentError>;
+
+    /// Restores a deleted component which was not purged yet, returning its latest version
+    async fn restore(
+        &self,
+        component_id: &ComponentId,
+        namespace: &Namespace,
+    ) -> Result<Component<Namespace>, ComponentError>;
+
+    /// Permanently deletes the components deleted before the given time, returning their number.
+    /// Each purge is claimed first so concurrent callers do not purge the same component, claims
+    /// made before `claimed_before` are considered abandoned.
+    async fn purge_deleted(
+        &self,
+        deleted_before: DateTime<Utc>,
+        claimed_before: DateTime<Utc>,
+    ) -> Result<usize, ComponentError>;
+
+    /// Replaces the labels of the latest version of the component
+    async fn update_labels(
+        &self,
+        component_id: &ComponentId,
+        labels: BTreeMap<String, String>,
+        namespace: &Namespace,
+    ) -> Result<Component<Namespace>, ComponentError>;
+
+    /// Creates a new version of the component with the same code as the latest one and the given
+    /// archive of initial files. New versions of the component inherit the initial files of the
+    /// version they update.
+    async fn update_initial_files(
+        &self,
+        component_id: &ComponentId,
+        data: Vec<u8>,
+        namespace: &Namespace,
+    ) -> Result<Component<Namespace>, ComponentError>;
+
+    async fn download_initial_files_stream(
+        &self,
+        component_id: &ComponentId,
+        version: Option<u64>,
+        namespace: &Namespace,
+    ) -> Result<ByteStream, ComponentError>;
+
+    async fn get_observability(
+        &self,
+        component_id: &ComponentId,
+        namespace: &Namespace,
+    ) -> Result<ComponentObservability, ComponentError>;
+
+    async fn update_observability(
+        &self,
+        component_id: &ComponentId,
+        observability: ComponentObservability,
+        namespace: &Namespace,
+    ) -> Result<ComponentObservability, ComponentError>;
+
+    async fn get_update_policy(
+        &self,
+        component_id: &ComponentId,
+        namespace: &Namespace,
+    ) -> Result<ComponentUpdatePolicy, ComponentError>;
+
+    async fn update_update_policy(
+        &self,
+        component_id: &ComponentId,
+        policy: ComponentUpdatePolicy,
+        namespace: &Namespace,
+    ) -> Result<ComponentUpdatePolicy, ComponentError>;
+
+    /// Dependencies between the latest version of the component and the latest versions of the
+    /// other components of the namespace, calling each other through wasm-rpc stubs
+    async fn get_version_aliases(
+        &self,
+        component_id: &ComponentId,
+        namespace: &Namespace,
+    ) -> Result<Vec<ComponentVersionAlias>, ComponentError>;
+
+    /// Returns the component version the alias points to
+    async fn get_by_version_alias(
+        &self,
+        component_id: &ComponentId,
+        alias: &str,
+        namespace: &Namespace,
+    ) -> Result<Component<Namespace>, ComponentError>;
+
+    /// Points the alias to the given version, creating it if it does not exist yet. If the update
+    /// policy of the component follows the alias, the workers created through the alias get
+    /// updated to the new version.
+    async fn update_version_alias(
+        &self,
+        component_id: &ComponentId,
+        alias: &str,
+        version: u64,
+        namespace: &Namespace,
+    ) -> Result<ComponentVersionAlias, ComponentError>;
+
+    async fn delete_version_alias(
+        &self,
+        component_id: &ComponentId,
+        alias: &str,
+        namespace: &Namespace,
+    ) -> Result<(), ComponentError>;
+
+    /// Returns the changes of all the aliases of the component, oldest first
+    async fn get_version_alias_history(
+        &self,
+        component_id: &ComponentId,
+        namespace: &Namespace,
+    ) -> Result<Vec<ComponentVersionAliasChange>, ComponentError>;
+
+    async fn get_dependency_graph(
+        &self,
+        component_id: &ComponentId,
+        namespace: &Namespace,
+    ) -> Result<ComponentDependencyGraph, ComponentError>;
+
+    /// The transformation plugins applied to the uploaded components, in the order they are applied
+    async fn get_transformation_plugins(
+        &self,
+        namespace: &Namespace,
+    ) -> Result<Vec<ComponentTransformationPlugin>, ComponentError>;
+
+    /// Registers a transformation plugin, replacing the one with the same name
+    async fn register_transformation_plugin(
+        &self,
+        plugin: ComponentTransformationPlugin,
+        namespace: &Namespace,
+    ) -> Result<ComponentTransformationPlugin, ComponentError>;
+
+    async fn unregister_transformation_plugin(
+        &self,
+        name: &str,
+        namespace: &Namespace,
+    ) -> Result<(), ComponentError>;
+
+    async fn get_projects(&self, namespace: &Namespace) -> Result<Vec<Project>, ComponentError>;
+
+    async fn get_project(
+        &self,
+        project_id: &ProjectId,
+        namespace: &Namespace,
+    ) -> Result<Project, ComponentError>;
+
+    async fn create_project(
+        &self,
+        data: ProjectData,
+        namespace: &Namespace,
+    ) -> Result<Project, ComponentError>;
+
+    async fn update_project(
+        &self,
+        project_id: &ProjectId,
+        data: ProjectData,
+        namespace: &Namespace,
+    ) -> Result<Project, ComponentError>;
+
+    /// Deletes the project, the components assigned to it are kept without a project
+    async fn delete_project(
+        &self,
+        project_id: &ProjectId,
+        namespace: &Namespace,
+    ) -> Result<(), ComponentError>;
+
+    async fn get_component_project(
+        &self,
+        component_id: &ComponentId,
+        namespace: &Namespace,
+    ) -> Result<Option<ProjectId>, ComponentError>;
+
+    /// Assigns the component to the project, or removes it from its project if no project is given
+    async fn update_component_project(
+        &self,
+        component_id: &ComponentId,
+        project_id: Option<ProjectId>,
+        namespace: &Namespace,
+    ) -> Result<Option<ProjectId>, ComponentError>;
 }
 
 pub struct ComponentServiceDefault {
     component_repo: Arc<dyn ComponentRepo + Sync + Send>,
+    project_repo: Arc<dyn ProjectRepo + Sync + Send>,
     object_store: Arc<dyn ComponentObjectStore + Sync + Send>,
     component_compilation: Arc<dyn ComponentCompilationService + Sync + Send>,
+    worker_update_rollout: Arc<dyn WorkerUpdateRolloutService + Sync + Send>,
+    name_uniqueness: ComponentNameUniqueness,
+    transformation_webhook: TransformationWebhookConfig,
 }
 
 impl ComponentServiceDefault {
     pub fn new(
         component_repo: Arc<dyn ComponentRepo + Sync + Send>,
+        project_repo: Arc<dyn ProjectRepo + Sync + Send>,
         object_store: Arc<dyn ComponentObjectStore + Sync + Send>,
         component_compilation: Arc<dyn ComponentCompilationService + Sync + Send>,
+        worker_update_rollout: Arc<dyn WorkerUpdateRolloutService + Sync + Send>,
+        name_uniqueness: ComponentNameUniqueness,
+        transformation_webhook: TransformationWebhookConfig,
     ) -> Self {
         ComponentServiceDefault {
             component_repo,
+            project_repo,
             object_store,
             component_compilation,
+            worker_update_rollout,
+            name_uniqueness,
+            transformation_webhook,
         }
     }
 }
@@ -228,44 +566,21 @@ where
         component_name: &ComponentName,
         component_type: ComponentType,
         data: Vec<u8>,
+        project_id: Option<&ProjectId>,
         namespace: &Namespace,
     ) -> Result<Component<Namespace>, ComponentError> {
         info!(namespace = %namespace, "Create component");
 
-        self.find_id_by_name(component_name, namespace)
-            .await?
-            .map_or(Ok(()), |id| Err(ComponentError::AlreadyExists(id)))?;
-
-        let component = create_new_component(
+        self.create_internal(
             component_id,
             component_name,
             component_type,
-            &data,
+            data,
+            vec![],
+            project_id,
             namespace,
-        )?;
-
-        info!(namespace = %namespace,"Uploaded component - exports {:?}",component.metadata.exports
-        );
-        tokio::try_join!(
-            self.upload_user_component(&component.versioned_component_id, data.clone()),
-            self.upload_protected_component(&component.versioned_component_id, data)
-        )?;
-
-        let record = component
-            .clone()
-            .try_into()
-            .map_err(|e| ComponentError::conversion_error("record", e))?;
-
-        let result = self.component_repo.create(&record).await;
-        if let Err(RepoError::UniqueViolation(_)) = result {
-            Err(ComponentError::AlreadyExists(component_id.clone()))?;
-        }
-
-        self.component_compilation
-            .enqueue_compilation(component_id, component.versioned_component_id.version)
-            .await;
-
-        Ok(component)
+        )
+        .await
     }
 
     async fn update(
@@ -276,52 +591,49 @@ where
         namespace: &Namespace,
     ) -> Result<Component<Namespace>, ComponentError> {
         info!(namespace = %namespace, "Update component");
-        let created_at = Utc::now();
-        let metadata =
-            process_component(&data).map_err(ComponentError::ComponentProcessingError)?;
-
-        let next_component = self
-            .component_repo
-            .get_latest_version(&component_id.0)
-            .await?
-            .filter(|c| c.namespace == namespace.to_string())
-            .ok_or(ComponentError::UnknownComponentId(component_id.clone()))
-            .and_then(|c| {
-                c.try_into()
-                    .map_err(|e| ComponentError::conversion_error("record", e))
-            })
-            .map(Component::next_version)?;
-
-        info!(namespace = %namespace, "Uploaded component - exports {:?}", metadata.exports);
 
-        let component_size: u64 = data.len().try_into().map_err(|e: TryFromIntError| {
-            ComponentError::conversion_error("data length", e.to_string())
-        })?;
-
-        tokio::try_join!(
-            self.upload_user_component(&next_component.versioned_component_id, data.clone()),
-            self.upload_protected_component(&next_component.versioned_component_id, data)
-        )?;
+        self.update_internal(component_id, data, component_type, vec![], namespace)
+            .await
+    }
 
-        let component = Component {
-            component_size,
-            metadata,
-            created_at,
-            component_type: component_type.unwrap_or(next_component.component_type),
-            ..next_component
-        };
-        let record = component
-            .clone()
-            .try_into()
-            .map_err(|e| ComponentError::conversion_error("record", e))?;
+    async fn create_composed(
+        &self,
+        component_id: &ComponentId,
+        component_name: &ComponentName,
+        component_type: ComponentType,
+        data: Vec<u8>,
+        plugs: &[ComponentPlugReference],
+        project_id: Option<&ProjectId>,
+        namespace: &Namespace,
+    ) -> Result<Component<Namespace>, ComponentError> {
+        info!(namespace = %namespace, "Create composed component");
 
-        self.component_repo.create(&record).await?;
+        let (data, plugs) = self.compose(data, plugs, namespace).await?;
+        self.create_internal(
+            component_id,
+            component_name,
+            component_type,
+            data,
+            plugs,
+            project_id,
+            namespace,
+        )
+        .await
+    }
 
-        self.component_compilation
-            .enqueue_compilation(component_id, component.versioned_component_id.version)
-            .await;
+    async fn update_composed(
+        &self,
+        component_id: &ComponentId,
+        data: Vec<u8>,
+        component_type: Option<ComponentType>,
+        plugs: &[ComponentPlugReference],
+        namespace: &Namespace,
+    ) -> Result<Component<Namespace>, ComponentError> {
+        info!(namespace = %namespace, "Update composed component");
 
-        Ok(component)
+        let (data, plugs) = self.compose(data, plugs, namespace).await?;
+        self.update_internal(component_id, data, component_type, plugs, namespace)
+            .await
     }
 
     async fn download(
@@ -398,10 +710,26 @@ where
     async fn find_by_name(
         &self,
         component_name: Option<ComponentName>,
+        label_filters: &[ComponentLabelFilter],
+        project_id: Option<&ProjectId>,
         namespace: &Namespace,
     ) -> Result<Vec<Component<Namespace>>, ComponentError> {
         info!(namespace = %namespace, "Find component by name");
 
+        let project_component_ids = match project_id {
+            Some(project_id) => {
+                self.get_project(project_id, namespace).await?;
+                Some(
+                    self.project_repo
+                        .get_component_ids(namespace.to_string().as_str(), &project_id.0)
+                        .await?
+                        .into_iter()
+                        .collect::<HashSet<_>>(),
+                )
+            }
+            None => None,
+        };
+
         let records = match component_name {
             Some(name) => {
                 self.component_repo
@@ -415,26 +743,63 @@ where
             }
         };
 
+        let deleted_component_ids = self
+            .component_repo
+            .get_deleted_ids(namespace.to_string().as_str())
+            .await?
+            .into_iter()
+            .collect::<HashSet<_>>();
+
         let values: Vec<Component<Namespace>> = records
             .iter()
             .map(|c| c.clone().try_into())
             .collect::<Result<Vec<Component<Namespace>>, _>>()
             .map_err(|e| ComponentError::conversion_error("record", e))?;
 
-        Ok(values)
+        Ok(values
+            .into_iter()
+            .filter(|component| {
+                !deleted_component_ids.contains(&component.versioned_component_id.component_id.0)
+            })
+            .filter(|component| {
+                project_component_ids.as_ref().map_or(true, |ids| {
+                    ids.contains(&component.versioned_component_id.component_id.0)
+                })
+            })
+            .filter(|component| {
+                label_filters
+                    .iter()
+                    .all(|filter| filter.matches(&component.metadata.labels))
+            })
+            .collect())
     }
 
     async fn find_id_by_name(
         &self,
         component_name: &ComponentName,
+        project_id: Option<&ProjectId>,
         namespace: &Namespace,
     ) -> Result<Option<ComponentId>, ComponentError> {
         info!(namespace = %namespace, "Find component id by name");
-        let records = self
+        let component_ids = self
             .component_repo
-            .get_id_by_name(namespace.to_string().as_str(), &component_name.0)
+            .get_ids_by_name(namespace.to_string().as_str(), &component_name.0)
             .await?;
-        Ok(records.map(ComponentId))
+
+        if project_id.is_none() && self.name_uniqueness == ComponentNameUniqueness::Namespace {
+            return Ok(component_ids.into_iter().next().map(ComponentId));
+        }
+
+        for component_id in component_ids {
+            let component_project = self
+                .project_repo
+                .get_component_project(&component_id)
+                .await?;
+            if component_project.as_ref() == project_id.map(|id| &id.0) {
+                return Ok(Some(ComponentId(component_id)));
+            }
+        }
+        Ok(None)
     }
 
     async fn get_by_version(
@@ -526,49 +891,1155 @@ where
         namespace: &Namespace,
     ) -> Result<(), ComponentError> {
         info!(namespace = %namespace, "Delete component");
+        self.delete_component(component_id, namespace.to_string().as_str())
+            .await
+    }
 
-        let records = self.component_repo.get(&component_id.0).await?;
+    async fn soft_delete(
+        &self,
+        component_id: &ComponentId,
+        namespace: &Namespace,
+    ) -> Result<(), ComponentError> {
+        info!(namespace = %namespace, "Soft delete component");
 
-        let versioned_component_ids: Vec<VersionedComponentId> = records
-            .into_iter()
-            .filter(|d| d.namespace == namespace.to_string())
-            .map(|c| c.into())
-            .collect();
+        let deleted = self
+            .component_repo
+            .soft_delete(namespace.to_string().as_str(), &component_id.0, Utc::now())
+            .await?;
 
-        if !versioned_component_ids.is_empty() {
-            for versioned_component_id in versioned_component_ids {
-                self.object_store
-                    .delete(&self.get_protected_object_store_key(&versioned_component_id))
-                    .await
-                    .map_err(|e| {
-                        ComponentError::component_store_error("Failed to delete component", e)
-                    })?;
-                self.object_store
-                    .delete(&self.get_user_object_store_key(&versioned_component_id))
-                    .await
-                    .map_err(|e| {
-                        ComponentError::component_store_error("Failed to delete component", e)
-                    })?;
-            }
-            self.component_repo
-                .delete(namespace.to_string().as_str(), &component_id.0)
-                .await?;
+        if deleted {
             Ok(())
         } else {
             Err(ComponentError::UnknownComponentId(component_id.clone()))
         }
     }
-}
 
-impl ComponentServiceDefault {
-    fn get_user_object_store_key(&self, id: &VersionedComponentId) -> String {
-        format!("{id}:user")
+    async fn restore(
+        &self,
+        component_id: &ComponentId,
+        namespace: &Namespace,
+    ) -> Result<Component<Namespace>, ComponentError> {
+        info!(namespace = %namespace, "Restore component");
+
+        self.check_namespace(component_id, namespace).await?;
+
+        let restored = self
+            .component_repo
+            .restore(namespace.to_string().as_str(), &component_id.0)
+            .await?;
+        if !restored {
+            return Err(ComponentError::ComponentNotDeleted(component_id.clone()));
+        }
+
+        self.get_latest_version(component_id, namespace)
+            .await?
+            .ok_or(ComponentError::UnknownComponentId(component_id.clone()))
+    }
+
+    async fn purge_deleted(
+        &self,
+        deleted_before: DateTime<Utc>,
+        claimed_before: DateTime<Utc>,
+    ) -> Result<usize, ComponentError> {
+        let deleted = self
+            .component_repo
+            .get_deleted_before(deleted_before)
+            .await?;
+
+        let mut count = 0;
+        for (namespace, component_id) in deleted {
+            let component_id = ComponentId(component_id);
+            match self
+                .component_repo
+                .claim_purge(&component_id.0, Utc::now(), claimed_before)
+                .await
+            {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(error) => {
+                    error!(namespace = %namespace, component_id = %component_id, "Failed to claim the purge of deleted component: {}", error);
+                    continue;
+                }
+            }
+
+            info!(namespace = %namespace, component_id = %component_id, "Purge deleted component");
+            match self.delete_component(&component_id, &namespace).await {
+                Ok(()) => count += 1,
+                Err(error) => {
+                    error!(namespace = %namespace, component_id = %component_id, "Failed to purge deleted component: {}", error)
+                }
+            }
+        }
+        Ok(count)
+    }
+
+    async fn get_observability(
+        &self,
+        component_id: &ComponentId,
+        namespace: &Namespace,
+    ) -> Result<ComponentObservability, ComponentError> {
+        info!(namespace = %namespace, "Get component observability");
+
+        self.check_namespace(component_id, namespace).await?;
+
+        // Components without an explicit configuration use the defaults
+        match self
+            .component_repo
+            .get_observability(&component_id.0)
+            .await?
+        {
+            Some(record) => record
+                .try_into()
+                .map_err(|e| ComponentError::conversion_error("observability record", e)),
+            None => Ok(ComponentObservability::default()),
+        }
+    }
+
+    async fn update_observability(
+        &self,
+        component_id: &ComponentId,
+        observability: ComponentObservability,
+        namespace: &Namespace,
+    ) -> Result<ComponentObservability, ComponentError> {
+        info!(namespace = %namespace, "Update component observability");
+
+        observability
+            .validate()
+            .map_err(ComponentError::InvalidObservability)?;
+
+        self.check_namespace(component_id, namespace).await?;
+
+        let record = ComponentObservabilityRecord::new(component_id, observability.clone());
+        self.component_repo.update_observability(&record).await?;
+
+        Ok(observability)
+    }
+
+    async fn update_labels(
+        &self,
+        component_id: &ComponentId,
+        labels: BTreeMap<String, String>,
+        namespace: &Namespace,
+    ) -> Result<Component<Namespace>, ComponentError> {
+        info!(namespace = %namespace, "Update component labels");
+
+        validate_component_labels(&labels).map_err(ComponentError::InvalidLabels)?;
+
+        let mut component = self
+            .get_latest_version(component_id, namespace)
+            .await?
+            .ok_or(ComponentError::UnknownComponentId(component_id.clone()))?;
+        component.metadata.labels = labels;
+
+        let metadata = record_metadata_serde::serialize(&component.metadata)
+            .map_err(|e| ComponentError::conversion_error("metadata", e))?;
+        self.component_repo
+            .update_metadata(
+                &component_id.0,
+                component.versioned_component_id.version,
+                &metadata,
+            )
+            .await?;
+
+        Ok(component)
+    }
+
+    async fn update_initial_files(
+        &self,
+        component_id: &ComponentId,
+        data: Vec<u8>,
+        namespace: &Namespace,
+    ) -> Result<Component<Namespace>, ComponentError> {
+        info!(namespace = %namespace, "Update component initial files");
+
+        validate_archive(&data).map_err(ComponentError::InvalidInitialFiles)?;
+
+        if self.component_repo.is_deleted(&component_id.0).await? {
+            return Err(ComponentError::UnknownComponentId(component_id.clone()));
+        }
+
+        let latest_component = self
+            .get_latest_version(component_id, namespace)
+            .await?
+            .ok_or(ComponentError::UnknownComponentId(component_id.clone()))?;
+
+        let hash = archive_hash(&data);
+        self.object_store
+            .put(&self.get_initial_files_object_store_key(&hash), data)
+            .await
+            .map_err(|e| {
+                ComponentError::component_store_error("Failed to upload initial files", e)
+            })?;
+
+        let (user_data, protected_data) = tokio::try_join!(
+            self.download_user_component(&latest_component.versioned_component_id),
+            self.download_protected_component(&latest_component.versioned_component_id)
+        )?;
+
+        // Only the files differ from the latest version, so its code stays compatible
+        let compatibility_report = ComponentCompatibilityReport::compare(
+            latest_component.versioned_component_id.version,
+            &latest_component.metadata,
+            &latest_component.metadata,
+        );
+        let next_component = latest_component.next_version();
+
+        tokio::try_join!(
+            self.upload_user_component(&next_component.versioned_component_id, user_data),
+            self.upload_protected_component(&next_component.versioned_component_id, protected_data)
+        )?;
+
+        let component = Component {
+            created_at: Utc::now(),
+            compatibility_report: Some(compatibility_report),
+            initial_files_hash: Some(hash),
+            ..next_component
+        };
+        let record = component
+            .clone()
+            .try_into()
+            .map_err(|e| ComponentError::conversion_error("record", e))?;
+
+        self.component_repo.create(&record).await?;
+
+        self.component_compilation
+            .enqueue_compilation(component_id, component.versioned_component_id.version)
+            .await;
+
+        self.start_update_rollout(component_id, component.versioned_component_id.version)
+            .await?;
+
+        Ok(component)
+    }
+
+    async fn download_initial_files_stream(
+        &self,
+        component_id: &ComponentId,
+        version: Option<u64>,
+        namespace: &Namespace,
+    ) -> Result<ByteStream, ComponentError> {
+        let versioned_component_id = self
+            .get_versioned_component_id(component_id, version, namespace)
+            .await?
+            .ok_or(ComponentError::UnknownComponentId(component_id.clone()))?;
+
+        info!(namespace = %namespace, "Download component initial files as stream");
+
+        let hash = self
+            .get_by_version(&versioned_component_id, namespace)
+            .await?
+            .and_then(|component| component.initial_files_hash)
+            .ok_or(ComponentError::NoInitialFiles(versioned_component_id))?;
+
+        let stream = self
+            .object_store
+            .get_stream(&self.get_initial_files_object_store_key(&hash))
+            .await;
+
+        Ok(stream)
+    }
+
+    async fn get_update_policy(
+        &self,
+        component_id: &ComponentId,
+        namespace: &Namespace,
+    ) -> Result<ComponentUpdatePolicy, ComponentError> {
+        info!(namespace = %namespace, "Get component update policy");
+
+        self.check_namespace(component_id, namespace).await?;
+        self.get_stored_update_policy(component_id).await
+    }
+
+    async fn update_update_policy(
+        &self,
+        component_id: &ComponentId,
+        policy: ComponentUpdatePolicy,
+        namespace: &Namespace,
+    ) -> Result<ComponentUpdatePolicy, ComponentError> {
+        info!(namespace = %namespace, "Update component update policy");
+
+        policy
+            .validate()
+            .map_err(ComponentError::InvalidUpdatePolicy)?;
+
+        self.check_namespace(component_id, namespace).await?;
+
+        let record = ComponentUpdatePolicyRecord::new(component_id, policy.clone());
+        self.component_repo.update_update_policy(&record).await?;
+
+        Ok(policy)
+    }
+
+    async fn get_dependency_graph(
+        &self,
+        component_id: &ComponentId,
+        namespace: &Namespace,
+    ) -> Result<ComponentDependencyGraph, ComponentError> {
+        info!(namespace = %namespace, "Get component dependency graph");
+
+        let records = self
+            .component_repo
+            .get_all_latest(namespace.to_string().as_str())
+            .await?;
+
+        let mut latest_components: HashMap<ComponentId, Component<Namespace>> = HashMap::new();
+        for record in records {
+            let has_rpc_dependencies =
+                record_metadata_serde::has_rpc_dependencies(&record.metadata);
+            let mut component: Component<Namespace> = record
+                .try_into()
+                .map_err(|e| ComponentError::conversion_error("record", e))?;
+            if !has_rpc_dependencies {
+                self.backfill_rpc_dependencies(&mut component).await?;
+            }
+            latest_components.insert(
+                component.versioned_component_id.component_id.clone(),
+                component,
+            );
+        }
+
+        let component = latest_components
+            .get(component_id)
+            .ok_or(ComponentError::UnknownComponentId(component_id.clone()))?;
+        let exported_packages = component.metadata.exported_packages();
+
+        let mut dependencies = Vec::new();
+        let mut dependents = Vec::new();
+        for other in latest_components.values() {
+            if other.versioned_component_id.component_id == *component_id {
+                continue;
+            }
+
+            let other_exported_packages = other.metadata.exported_packages();
+            for package in &component.metadata.rpc_dependencies {
+                if other_exported_packages.contains(package) {
+                    dependencies.push(ComponentDependency {
+                        versioned_component_id: other.versioned_component_id.clone(),
+                        component_name: other.component_name.clone(),
+                        package: package.clone(),
+                    });
+                }
+            }
+            for package in &other.metadata.rpc_dependencies {
+                if exported_packages.contains(package) {
+                    dependents.push(ComponentDependency {
+                        versioned_component_id: other.versioned_component_id.clone(),
+                        component_name: other.component_name.clone(),
+                        package: package.clone(),
+                    });
+                }
+            }
+        }
+        dependencies.sort_by(|a, b| a.component_name.cmp(&b.component_name));
+        dependents.sort_by(|a, b| a.component_name.cmp(&b.component_name));
+
+        Ok(ComponentDependencyGraph {
+            versioned_component_id: component.versioned_component_id.clone(),
+            dependencies,
+            dependents,
+        })
+    }
+
+    async fn get_transformation_plugins(
+        &self,
+        namespace: &Namespace,
+    ) -> Result<Vec<ComponentTransformationPlugin>, ComponentError> {
+        info!(namespace = %namespace, "Get component transformation plugins");
+
+        self.component_repo
+            .get_transformation_plugins(namespace.to_string().as_str())
+            .await?
+            .into_iter()
+            .map(|record| {
+                record
+                    .try_into()
+                    .map_err(|e| ComponentError::conversion_error("transformation plugin", e))
+            })
+            .collect()
+    }
+
+    async fn register_transformation_plugin(
+        &self,
+        plugin: ComponentTransformationPlugin,
+        namespace: &Namespace,
+    ) -> Result<ComponentTransformationPlugin, ComponentError> {
+        info!(namespace = %namespace, plugin = %plugin.name, "Register component transformation plugin");
+
+        plugin
+            .validate()
+            .map_err(ComponentError::InvalidTransformationPlugin)?;
+        if let ComponentTransformation::PlugComponent(plug) = &plugin.transformation {
+            self.check_namespace(&plug.component_id, namespace).await?;
+        }
+
+        let record = ComponentTransformationPluginRecord::new(
+            namespace.to_string().as_str(),
+            plugin.clone(),
+        )
+        .map_err(|e| ComponentError::conversion_error("transformation plugin", e))?;
+        self.component_repo
+            .update_transformation_plugin(&record)
+            .await?;
+
+        Ok(plugin)
+    }
+
+    async fn unregister_transformation_plugin(
+        &self,
+        name: &str,
+        namespace: &Namespace,
+    ) -> Result<(), ComponentError> {
+        info!(namespace = %namespace, plugin = name, "Unregister component transformation plugin");
+
+        let deleted = self
+            .component_repo
+            .delete_transformation_plugin(namespace.to_string().as_str(), name)
+            .await?;
+        if deleted {
+            Ok(())
+        } else {
+            Err(ComponentError::UnknownTransformationPlugin(
+                name.to_string(),
+            ))
+        }
+    }
+
+    async fn get_projects(&self, namespace: &Namespace) -> Result<Vec<Project>, ComponentError> {
+        info!(namespace = %namespace, "Get projects");
+
+        let records = self
+            .project_repo
+            .get_all(namespace.to_string().as_str())
+            .await?;
+        Ok(records.into_iter().map(|record| record.into()).collect())
+    }
+
+    async fn get_project(
+        &self,
+        project_id: &ProjectId,
+        namespace: &Namespace,
+    ) -> Result<Project, ComponentError> {
+        info!(namespace = %namespace, project_id = %project_id, "Get project");
+
+        self.project_repo
+            .get(namespace.to_string().as_str(), &project_id.0)
+            .await?
+            .map(|record| record.into())
+            .ok_or(ComponentError::UnknownProject(project_id.clone()))
+    }
+
+    async fn create_project(
+        &self,
+        data: ProjectData,
+        namespace: &Namespace,
+    ) -> Result<Project, ComponentError> {
+        info!(namespace = %namespace, project_name = data.name, "Create project");
+
+        data.validate().map_err(ComponentError::InvalidProject)?;
+
+        let project = Project {
+            project_id: ProjectId::new_v4(),
+            name: data.name,
+            description: data.description,
+            created_at: Utc::now(),
+        };
+        let record = ProjectRecord::new(namespace.to_string().as_str(), project.clone());
+        match self.project_repo.create(&record).await {
+            Ok(()) => Ok(project),
+            Err(RepoError::UniqueViolation(_)) => {
+                Err(ComponentError::ProjectAlreadyExists(project.name))
+            }
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    async fn update_project(
+        &self,
+        project_id: &ProjectId,
+        data: ProjectData,
+        namespace: &Namespace,
+    ) -> Result<Project, ComponentError> {
+        info!(namespace = %namespace, project_id = %project_id, "Update project");
+
+        data.validate().map_err(ComponentError::InvalidProject)?;
+
+        let project = Project {
+            name: data.name,
+            description: data.description,
+            ..self.get_project(project_id, namespace).await?
+        };
+        let record = ProjectRecord::new(namespace.to_string().as_str(), project.clone());
+        match self.project_repo.update(&record).await {
+            Ok(true) => Ok(project),
+            Ok(false) => Err(ComponentError::UnknownProject(project_id.clone())),
+            Err(RepoError::UniqueViolation(_)) => {
+                Err(ComponentError::ProjectAlreadyExists(project.name))
+            }
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    async fn delete_project(
+        &self,
+        project_id: &ProjectId,
+        namespace: &Namespace,
+    ) -> Result<(), ComponentError> {
+        info!(namespace = %namespace, project_id = %project_id, "Delete project");
+
+        let deleted = self
+            .project_repo
+            .delete(namespace.to_string().as_str(), &project_id.0)
+            .await?;
+        if deleted {
+            Ok(())
+        } else {
+            Err(ComponentError::UnknownProject(project_id.clone()))
+        }
+    }
+
+    async fn get_component_project(
+        &self,
+        component_id: &ComponentId,
+        namespace: &Namespace,
+    ) -> Result<Option<ProjectId>, ComponentError> {
+        info!(namespace = %namespace, "Get component project");
+
+        self.check_namespace(component_id, namespace).await?;
+
+        let project_id = self
+            .project_repo
+            .get_component_project(&component_id.0)
+            .await?;
+        Ok(project_id.map(ProjectId))
+    }
+
+    async fn update_component_project(
+        &self,
+        component_id: &ComponentId,
+        project_id: Option<ProjectId>,
+        namespace: &Namespace,
+    ) -> Result<Option<ProjectId>, ComponentError> {
+        info!(namespace = %namespace, "Update component project");
+
+        self.check_namespace(component_id, namespace).await?;
+        if let Some(project_id) = &project_id {
+            self.get_project(project_id, namespace).await?;
+        }
+
+        let name_scope = self.name_scope(project_id.as_ref());
+        let result = self
+            .project_repo
+            .update_component_project(
+                &component_id.0,
+                project_id.as_ref().map(|id| &id.0),
+                &name_scope,
+            )
+            .await;
+        match result {
+            Ok(()) => Ok(project_id),
+            Err(RepoError::UniqueViolation(_)) => {
+                let component = self
+                    .component_repo
+                    .get_latest_version(&component_id.0)
+                    .await?
+                    .ok_or(ComponentError::UnknownComponentId(component_id.clone()))?;
+                let same_name = self
+                    .find_id_by_name(
+                        &ComponentName(component.name),
+                        self.scope_project(project_id.as_ref()),
+                        namespace,
+                    )
+                    .await?;
+                Err(ComponentError::AlreadyExists(
+                    same_name.unwrap_or(component_id.clone()),
+                ))
+            }
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    async fn get_version_aliases(
+        &self,
+        component_id: &ComponentId,
+        namespace: &Namespace,
+    ) -> Result<Vec<ComponentVersionAlias>, ComponentError> {
+        info!(namespace = %namespace, "Get component version aliases");
+
+        self.check_namespace(component_id, namespace).await?;
+
+        let records = self
+            .component_repo
+            .get_version_aliases(&component_id.0)
+            .await?;
+        Ok(records.into_iter().map(|r| r.into()).collect())
+    }
+
+    async fn get_by_version_alias(
+        &self,
+        component_id: &ComponentId,
+        alias: &str,
+        namespace: &Namespace,
+    ) -> Result<Component<Namespace>, ComponentError> {
+        info!(namespace = %namespace, "Get component by version alias");
+
+        self.check_namespace(component_id, namespace).await?;
+
+        let record = self
+            .component_repo
+            .get_version_alias(&component_id.0, alias)
+            .await?
+            .ok_or_else(|| ComponentError::UnknownVersionAlias {
+                component_id: component_id.clone(),
+                alias: alias.to_string(),
+            })?;
+
+        let versioned_component_id = VersionedComponentId {
+            component_id: component_id.clone(),
+            version: record.version as u64,
+        };
+        self.get_by_version(&versioned_component_id, namespace)
+            .await?
+            .ok_or(ComponentError::UnknownVersionedComponentId(
+                versioned_component_id,
+            ))
+    }
+
+    async fn update_version_alias(
+        &self,
+        component_id: &ComponentId,
+        alias: &str,
+        version: u64,
+        namespace: &Namespace,
+    ) -> Result<ComponentVersionAlias, ComponentError> {
+        info!(namespace = %namespace, "Update component version alias");
+
+        validate_component_version_alias(alias).map_err(ComponentError::InvalidVersionAlias)?;
+
+        let versioned_component_id = VersionedComponentId {
+            component_id: component_id.clone(),
+            version,
+        };
+        self.get_by_version(&versioned_component_id, namespace)
+            .await?
+            .ok_or(ComponentError::UnknownVersionedComponentId(
+                versioned_component_id,
+            ))?;
+
+        let version_alias = ComponentVersionAlias {
+            name: alias.to_string(),
+            version,
+            updated_at: Utc::now(),
+        };
+        let previous_version = self
+            .component_repo
+            .update_version_alias(&ComponentVersionAliasRecord::new(
+                component_id,
+                version_alias.clone(),
+            ))
+            .await?
+            .map(|v| v as u64);
+
+        if previous_version.is_some_and(|v| v != version) {
+            let update_policy = self.get_stored_update_policy(component_id).await?;
+            if update_policy.enabled && update_policy.version_alias.as_deref() == Some(alias) {
+                // Only the workers created through the alias follow it
+                let pinned = WorkerFilter::new_env(
+                    COMPONENT_VERSION_ALIAS_ENV_VAR.to_string(),
+                    StringFilterComparator::Equal,
+                    alias.to_string(),
+                );
+                let policy = ComponentUpdatePolicy {
+                    filter: Some(match &update_policy.filter {
+                        Some(filter) => filter.and(pinned),
+                        None => pinned,
+                    }),
+                    ..update_policy
+                };
+                self.worker_update_rollout
+                    .start_rollout(component_id, version, &policy)
+                    .await;
+            }
+        }
+
+        Ok(version_alias)
+    }
+
+    async fn delete_version_alias(
+        &self,
+        component_id: &ComponentId,
+        alias: &str,
+        namespace: &Namespace,
+    ) -> Result<(), ComponentError> {
+        info!(namespace = %namespace, "Delete component version alias");
+
+        self.check_namespace(component_id, namespace).await?;
+
+        self.component_repo
+            .delete_version_alias(&component_id.0, alias, Utc::now())
+            .await?
+            .map(|_| ())
+            .ok_or_else(|| ComponentError::UnknownVersionAlias {
+                component_id: component_id.clone(),
+                alias: alias.to_string(),
+            })
+    }
+
+    async fn get_version_alias_history(
+        &self,
+        component_id: &ComponentId,
+        namespace: &Namespace,
+    ) -> Result<Vec<ComponentVersionAliasChange>, ComponentError> {
+        info!(namespace = %namespace, "Get component version alias history");
+
+        self.check_namespace(component_id, namespace).await?;
+
+        let records = self
+            .component_repo
+            .get_version_alias_changes(&component_id.0)
+            .await?;
+        Ok(records.into_iter().map(|r| r.into()).collect())
+    }
+}
+
+impl ComponentServiceDefault {
+    /// The project whose components' names have to differ from the name of a component in
+    /// `project_id`, or `None` if the names are unique in the whole namespace
+    fn scope_project<'a>(&self, project_id: Option<&'a ProjectId>) -> Option<&'a ProjectId> {
+        match self.name_uniqueness {
+            ComponentNameUniqueness::Namespace => None,
+            ComponentNameUniqueness::Project => project_id,
+        }
+    }
+
+    /// The scope stored with the name of a component in `project_id`, within which the database
+    /// keeps the names unique
+    fn name_scope(&self, project_id: Option<&ProjectId>) -> String {
+        self.scope_project(project_id)
+            .map(|project_id| project_id.0.simple().to_string())
+            .unwrap_or_default()
+    }
+
+    async fn create_internal<Namespace>(
+        &self,
+        component_id: &ComponentId,
+        component_name: &ComponentName,
+        component_type: ComponentType,
+        data: Vec<u8>,
+        mut plugs: Vec<ComponentPlug>,
+        project_id: Option<&ProjectId>,
+        namespace: &Namespace,
+    ) -> Result<Component<Namespace>, ComponentError>
+    where
+        Namespace: Display + TryFrom<String> + Eq + Clone + Send + Sync,
+        <Namespace as TryFrom<String>>::Error: Display + Debug + Send + Sync + 'static,
+    {
+        if let Some(project_id) = project_id {
+            self.get_project(project_id, namespace).await?;
+        }
+
+        self.find_id_by_name(component_name, self.scope_project(project_id), namespace)
+            .await?
+            .map_or(Ok(()), |id| Err(ComponentError::AlreadyExists(id)))?;
+
+        // The uploaded component is kept as the user component, and the transformed one is
+        // stored as the protected component used by the executors
+        let transformed = self
+            .apply_transformation_plugins(data.clone(), &mut plugs, namespace)
+            .await?;
+
+        let mut component = create_new_component(
+            component_id,
+            component_name,
+            component_type,
+            &transformed,
+            namespace,
+        )?;
+        component.metadata.plugs = plugs;
+
+        info!(namespace = %namespace,"Uploaded component - exports {:?}",component.metadata.exports
+        );
+        tokio::try_join!(
+            self.upload_user_component(&component.versioned_component_id, data),
+            self.upload_protected_component(&component.versioned_component_id, transformed)
+        )?;
+
+        let record = component
+            .clone()
+            .try_into()
+            .map_err(|e| ComponentError::conversion_error("record", e))?;
+
+        let result = self
+            .component_repo
+            .create_in_project(
+                &record,
+                project_id.map(|id| &id.0),
+                &self.name_scope(project_id),
+            )
+            .await;
+        match result {
+            Ok(()) => {}
+            Err(RepoError::UniqueViolation(_)) => {
+                let same_name = self
+                    .find_id_by_name(component_name, self.scope_project(project_id), namespace)
+                    .await?;
+                Err(ComponentError::AlreadyExists(
+                    same_name.unwrap_or(component_id.clone()),
+                ))?;
+            }
+            Err(error) => Err(error)?,
+        }
+
+        self.component_compilation
+            .enqueue_compilation(component_id, component.versioned_component_id.version)
+            .await;
+
+        Ok(component)
+    }
+
+    async fn update_internal<Namespace>(
+        &self,
+        component_id: &ComponentId,
+        data: Vec<u8>,
+        component_type: Option<ComponentType>,
+        mut plugs: Vec<ComponentPlug>,
+        namespace: &Namespace,
+    ) -> Result<Component<Namespace>, ComponentError>
+    where
+        Namespace: Display + TryFrom<String> + Eq + Clone + Send + Sync,
+        <Namespace as TryFrom<String>>::Error: Display + Debug + Send + Sync + 'static,
+    {
+        if self.component_repo.is_deleted(&component_id.0).await? {
+            return Err(ComponentError::UnknownComponentId(component_id.clone()));
+        }
+
+        let created_at = Utc::now();
+        let transformed = self
+            .apply_transformation_plugins(data.clone(), &mut plugs, namespace)
+            .await?;
+        let mut metadata =
+            process_component(&transformed).map_err(ComponentError::ComponentProcessingError)?;
+        metadata.plugs = plugs;
+
+        let latest_component: Component<Namespace> = self
+            .component_repo
+            .get_latest_version(&component_id.0)
+            .await?
+            .filter(|c| c.namespace == namespace.to_string())
+            .ok_or(ComponentError::UnknownComponentId(component_id.clone()))
+            .and_then(|c| {
+                c.try_into()
+                    .map_err(|e| ComponentError::conversion_error("record", e))
+            })?;
+
+        let compatibility_report = ComponentCompatibilityReport::compare(
+            latest_component.versioned_component_id.version,
+            &latest_component.metadata,
+            &metadata,
+        );
+        if compatibility_report.breaking {
+            info!(namespace = %namespace, "Uploaded component has breaking changes - exports: {:?}, imports: {:?}", compatibility_report.changes, compatibility_report.import_changes);
+        }
+        metadata.labels = latest_component.metadata.labels.clone();
+
+        let next_component = latest_component.next_version();
+
+        info!(namespace = %namespace, "Uploaded component - exports {:?}", metadata.exports);
+
+        let component_size: u64 = transformed.len().try_into().map_err(|e: TryFromIntError| {
+            ComponentError::conversion_error("data length", e.to_string())
+        })?;
+
+        tokio::try_join!(
+            self.upload_user_component(&next_component.versioned_component_id, data),
+            self.upload_protected_component(&next_component.versioned_component_id, transformed)
+        )?;
+
+        let component = Component {
+            component_size,
+            metadata,
+            created_at,
+            component_type: component_type.unwrap_or(next_component.component_type),
+            compatibility_report: Some(compatibility_report),
+            ..next_component
+        };
+        let record = component
+            .clone()
+            .try_into()
+            .map_err(|e| ComponentError::conversion_error("record", e))?;
+
+        self.component_repo.create(&record).await?;
+
+        self.component_compilation
+            .enqueue_compilation(component_id, component.versioned_component_id.version)
+            .await;
+
+        self.start_update_rollout(component_id, component.versioned_component_id.version)
+            .await?;
+
+        Ok(component)
+    }
+
+    /// Composes the uploaded component with the requested versions of stored components, returning
+    /// the composed component and the exact versions plugged into it
+    async fn compose<Namespace>(
+        &self,
+        data: Vec<u8>,
+        plugs: &[ComponentPlugReference],
+        namespace: &Namespace,
+    ) -> Result<(Vec<u8>, Vec<ComponentPlug>), ComponentError>
+    where
+        Namespace: Display + TryFrom<String> + Eq + Clone + Send + Sync,
+        <Namespace as TryFrom<String>>::Error: Display + Debug + Send + Sync + 'static,
+    {
+        if plugs.is_empty() {
+            return Ok((data, vec![]));
+        }
+
+        info!(namespace = %namespace, "Compose component with {} plugs", plugs.len());
+
+        let mut plug_data = Vec::with_capacity(plugs.len());
+        let mut plugged = Vec::with_capacity(plugs.len());
+        for plug in plugs {
+            let (data, plug) = self
+                .download_plug(&plug.component_id, plug.version, namespace)
+                .await?;
+            plug_data.push(data);
+            plugged.push(plug);
+        }
+
+        let data = compose_component_blocking(data, plug_data)
+            .await
+            .map_err(ComponentError::ComponentCompositionFailed)?;
+        Ok((data, plugged))
+    }
+
+    /// Downloads a version of a component to be plugged into another one, the latest one if the
+    /// version is not specified
+    async fn download_plug<Namespace>(
+        &self,
+        component_id: &ComponentId,
+        version: Option<u64>,
+        namespace: &Namespace,
+    ) -> Result<(Vec<u8>, ComponentPlug), ComponentError>
+    where
+        Namespace: Display + TryFrom<String> + Eq + Clone + Send + Sync,
+        <Namespace as TryFrom<String>>::Error: Display + Debug + Send + Sync + 'static,
+    {
+        let versioned_component_id = self
+            .get_versioned_component_id(component_id, version, namespace)
+            .await?
+            .ok_or(ComponentError::UnknownComponentId(component_id.clone()))?;
+        let data = self
+            .download(
+                component_id,
+                Some(versioned_component_id.version),
+                namespace,
+            )
+            .await?;
+
+        Ok((
+            data,
+            ComponentPlug {
+                component_id: versioned_component_id.component_id,
+                version: versioned_component_id.version,
+            },
+        ))
+    }
+
+    /// Runs the uploaded WASM through the transformation plugins of the namespace, the output of
+    /// each plugin being the input of the next one. The components plugged by the plugins are
+    /// added to `plugs`.
+    async fn apply_transformation_plugins<Namespace>(
+        &self,
+        data: Vec<u8>,
+        plugs: &mut Vec<ComponentPlug>,
+        namespace: &Namespace,
+    ) -> Result<Vec<u8>, ComponentError>
+    where
+        Namespace: Display + TryFrom<String> + Eq + Clone + Send + Sync,
+        <Namespace as TryFrom<String>>::Error: Display + Debug + Send + Sync + 'static,
+    {
+        let plugins = self.get_transformation_plugins(namespace).await?;
+
+        let mut data = data;
+        for plugin in plugins {
+            info!(namespace = %namespace, plugin = %plugin.name, "Applying component transformation plugin");
+
+            let result = match &plugin.transformation {
+                ComponentTransformation::StripCustomSections(strip) => {
+                    strip_custom_sections(&data, &strip.keep)
+                }
+                ComponentTransformation::PlugComponent(plug) => {
+                    let (plug_data, plug) = self
+                        .download_plug(&plug.component_id, None, namespace)
+                        .await?;
+                    plugs.push(plug);
+                    compose_component_blocking(data, vec![plug_data]).await
+                }
+                ComponentTransformation::Webhook(webhook) => {
+                    call_transformation_webhook(&webhook.url, data, &self.transformation_webhook)
+                        .await
+                }
+            };
+            data = result.map_err(|error| ComponentError::ComponentTransformationFailed {
+                plugin: plugin.name.clone(),
+                error,
+            })?;
+        }
+
+        Ok(data)
+    }
+
+    /// Components without an explicit update policy do not update their workers
+    async fn get_stored_update_policy(
+        &self,
+        component_id: &ComponentId,
+    ) -> Result<ComponentUpdatePolicy, ComponentError> {
+        match self
+            .component_repo
+            .get_update_policy(&component_id.0)
+            .await?
+        {
+            Some(record) => record
+                .try_into()
+                .map_err(|e| ComponentError::conversion_error("update policy record", e)),
+            None => Ok(ComponentUpdatePolicy::default()),
+        }
+    }
+
+    async fn delete_component(
+        &self,
+        component_id: &ComponentId,
+        namespace: &str,
+    ) -> Result<(), ComponentError> {
+        let records = self.component_repo.get(&component_id.0).await?;
+
+        let versioned_component_ids: Vec<VersionedComponentId> = records
+            .into_iter()
+            .filter(|d| d.namespace == namespace)
+            .map(|c| c.into())
+            .collect();
+
+        if !versioned_component_ids.is_empty() {
+            for versioned_component_id in versioned_component_ids {
+                self.object_store
+                    .delete(&self.get_protected_object_store_key(&versioned_component_id))
+                    .await
+                    .map_err(|e| {
+                        ComponentError::component_store_error("Failed to delete component", e)
+                    })?;
+                self.object_store
+                    .delete(&self.get_user_object_store_key(&versioned_component_id))
+                    .await
+                    .map_err(|e| {
+                        ComponentError::component_store_error("Failed to delete component", e)
+                    })?;
+            }
+            self.component_repo
+                .delete(namespace, &component_id.0)
+                .await?;
+            Ok(())
+        } else {
+            Err(ComponentError::UnknownComponentId(component_id.clone()))
+        }
+    }
+
+    async fn check_namespace<Namespace: Display>(
+        &self,
+        component_id: &ComponentId,
+        namespace: &Namespace,
+    ) -> Result<(), ComponentError> {
+        match self.component_repo.get_namespace(&component_id.0).await? {
+            Some(stored) if stored == namespace.to_string() => Ok(()),
+            _ => Err(ComponentError::UnknownComponentId(component_id.clone())),
+        }
+    }
+
+    fn get_user_object_store_key(&self, id: &VersionedComponentId) -> String {
+        format!("{id}:user")
     }
 
     fn get_protected_object_store_key(&self, id: &VersionedComponentId) -> String {
         format!("{id}:protected")
     }
 
+    /// Initial file archives are stored by their content hash, so versions sharing the same
+    /// files share the stored archive
+    fn get_initial_files_object_store_key(&self, hash: &str) -> String {
+        format!("initial-files:{hash}")
+    }
+
+    /// Starts updating the workers to a newly created version, unless the update policy waits
+    /// for a version alias to be moved
+    async fn start_update_rollout(
+        &self,
+        component_id: &ComponentId,
+        version: u64,
+    ) -> Result<(), ComponentError> {
+        let update_policy = self.get_stored_update_policy(component_id).await?;
+        if update_policy.enabled && update_policy.version_alias.is_none() {
+            self.worker_update_rollout
+                .start_rollout(component_id, version, &update_policy)
+                .await;
+        }
+        Ok(())
+    }
+
+    /// Detects the wasm-rpc dependencies of a component version stored before they were tracked,
+    /// and stores them in its metadata
+    async fn backfill_rpc_dependencies<Namespace: Display>(
+        &self,
+        component: &mut Component<Namespace>,
+    ) -> Result<(), ComponentError> {
+        let data = self
+            .download_protected_component(&component.versioned_component_id)
+            .await?;
+        let raw_metadata = RawComponentMetadata::analyse_component(&data)
+            .map_err(ComponentError::ComponentProcessingError)?;
+        component.metadata.rpc_dependencies = raw_metadata.rpc_dependencies();
+
+        info!(
+            component_id = %component.versioned_component_id.component_id,
+            version = component.versioned_component_id.version,
+            "Backfilled the wasm-rpc dependencies of the component"
+        );
+
+        let metadata = record_metadata_serde::serialize(&component.metadata)
+            .map_err(|e| ComponentError::conversion_error("metadata", e))?;
+        self.component_repo
+            .update_metadata(
+                &component.versioned_component_id.component_id.0,
+                component.versioned_component_id.version,
+                &metadata,
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn download_user_component(
+        &self,
+        user_component_id: &VersionedComponentId,
+    ) -> Result<Vec<u8>, ComponentError> {
+        self.object_store
+            .get(&self.get_user_object_store_key(user_component_id))
+            .await
+            .map_err(|e| {
+                ComponentError::component_store_error("Failed to download user component", e)
+            })
+    }
+
+    async fn download_protected_component(
+        &self,
+        protected_component_id: &VersionedComponentId,
+    ) -> Result<Vec<u8>, ComponentError> {
+        self.object_store
+            .get(&self.get_protected_object_store_key(protected_component_id))
+            .await
+            .map_err(|e| {
+                ComponentError::component_store_error("Failed to download protected component", e)
+            })
+    }
+
     async fn upload_user_component(
         &self,
         user_component_id: &VersionedComponentId,
@@ -632,9 +2103,11 @@ impl ComponentServiceDefault {
 mod tests {
     use test_r::test;
 
-    use crate::service::component::ComponentError;
+    use crate::service::component::{ComponentError, ComponentPlugReference};
+    use golem_common::model::ComponentId;
     use golem_common::SafeDisplay;
     use golem_service_base::repo::RepoError;
+    use std::str::FromStr;
 
     #[test]
     pub fn test_repo_error_to_service_error() {
@@ -645,4 +2118,26 @@ mod tests {
             "Internal repository error".to_string()
         );
     }
+
+    #[test]
+    pub fn test_parse_component_plug_reference() {
+        let component_id = ComponentId::new_v4();
+
+        assert_eq!(
+            ComponentPlugReference::from_str(&component_id.to_string()),
+            Ok(ComponentPlugReference {
+                component_id: component_id.clone(),
+                version: None,
+            })
+        );
+        assert_eq!(
+            ComponentPlugReference::from_str(&format!("{component_id}@3")),
+            Ok(ComponentPlugReference {
+                component_id: component_id.clone(),
+                version: Some(3),
+            })
+        );
+        assert!(ComponentPlugReference::from_str(&format!("{component_id}@latest")).is_err());
+        assert!(ComponentPlugReference::from_str("not-a-component").is_err());
+    }
 }
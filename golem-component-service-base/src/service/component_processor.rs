@@ -12,13 +12,24 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
+use std::net::{IpAddr, SocketAddr};
+
+use crate::config::TransformationWebhookConfig;
 use golem_common::model::component_metadata::{
     ComponentMetadata, ComponentProcessingError, LinearMemory, RawComponentMetadata,
 };
+use url::{Host, Url};
+use wac_graph::types::Package;
+use wac_graph::{plug, CompositionGraph, EncodeOptions};
+use wasmparser::{Chunk, Parser, Payload};
 
 pub fn process_component(data: &[u8]) -> Result<ComponentMetadata, ComponentProcessingError> {
     let raw_component_metadata = RawComponentMetadata::analyse_component(data)?;
 
+    let rpc_dependencies = raw_component_metadata.rpc_dependencies();
+    let imports = raw_component_metadata.imported_interfaces();
+
     let producers = raw_component_metadata
         .producers
         .into_iter()
@@ -40,5 +51,473 @@ pub fn process_component(data: &[u8]) -> Result<ComponentMetadata, ComponentProc
         exports,
         producers,
         memories,
+        labels: BTreeMap::new(),
+        rpc_dependencies,
+        imports,
+        plugs: vec![],
     })
 }
+
+/// Plugs the exports of the plug components into the matching imports of the socket component,
+/// the same way as `wac plug` does
+pub fn compose_component(socket: Vec<u8>, plugs: Vec<Vec<u8>>) -> Result<Vec<u8>, String> {
+    let mut graph = CompositionGraph::new();
+
+    let socket = Package::from_bytes("socket", None, socket, graph.types_mut())
+        .map_err(|e| format!("Failed to parse the uploaded component: {e:#}"))?;
+    let socket = graph
+        .register_package(socket)
+        .map_err(|e| format!("Failed to register the uploaded component: {e}"))?;
+
+    let mut plug_ids = Vec::new();
+    for (idx, plug) in plugs.into_iter().enumerate() {
+        let name = format!("plug{idx}");
+        let plug = Package::from_bytes(&name, None, plug, graph.types_mut())
+            .map_err(|e| format!("Failed to parse plug component #{idx}: {e:#}"))?;
+        let plug = graph
+            .register_package(plug)
+            .map_err(|e| format!("Failed to register plug component #{idx}: {e}"))?;
+        plug_ids.push(plug);
+    }
+
+    plug(&mut graph, plug_ids, socket).map_err(|e| format!("Failed to plug components: {e}"))?;
+
+    graph
+        .encode(EncodeOptions::default())
+        .map_err(|e| format!("Failed to encode the composed component: {e}"))
+}
+
+/// Runs `compose_component` on the blocking thread pool, as the composition is too CPU heavy to
+/// run on the async runtime
+pub async fn compose_component_blocking(
+    socket: Vec<u8>,
+    plugs: Vec<Vec<u8>>,
+) -> Result<Vec<u8>, String> {
+    tokio::task::spawn_blocking(move || compose_component(socket, plugs))
+        .await
+        .map_err(|e| format!("Composition task failed: {e}"))?
+}
+
+/// Removes the custom sections not listed in `keep` from the top level of a WASM component or
+/// module. Nested modules and components are kept as they are.
+pub fn strip_custom_sections(data: &[u8], keep: &[String]) -> Result<Vec<u8>, String> {
+    let mut result = Vec::with_capacity(data.len());
+    let mut parser = Parser::new(0);
+    let mut remaining = data;
+
+    loop {
+        let (consumed, payload) = match parser
+            .parse(remaining, true)
+            .map_err(|e| format!("Failed to parse WASM: {e}"))?
+        {
+            Chunk::Parsed { consumed, payload } => (consumed, payload),
+            Chunk::NeedMoreData(_) => return Err("Unexpected end of WASM".to_string()),
+        };
+        let (section, rest) = remaining.split_at(consumed);
+        remaining = rest;
+
+        match payload {
+            Payload::CustomSection(reader) => {
+                if keep.iter().any(|name| name == reader.name()) {
+                    result.extend_from_slice(section);
+                }
+            }
+            Payload::ModuleSection {
+                unchecked_range, ..
+            }
+            | Payload::ComponentSection {
+                unchecked_range, ..
+            } => {
+                let (nested, rest) = remaining.split_at(unchecked_range.len());
+                result.extend_from_slice(section);
+                result.extend_from_slice(nested);
+                remaining = rest;
+                parser.skip_section();
+            }
+            Payload::End(_) => break,
+            _ => result.extend_from_slice(section),
+        }
+    }
+
+    Ok(result)
+}
+
+/// Sends the WASM to an external transformation service, returning the WASM in its response.
+///
+/// Unless private addresses are allowed, the host of the webhook must only resolve to public
+/// addresses, and the request is sent to the checked address so the host can not be rebound to
+/// an internal one in between. Redirects are not followed for the same reason.
+pub async fn call_transformation_webhook(
+    url: &str,
+    data: Vec<u8>,
+    config: &TransformationWebhookConfig,
+) -> Result<Vec<u8>, String> {
+    let parsed =
+        Url::parse(url).map_err(|e| format!("Invalid transformation webhook URL {url}: {e}"))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(format!(
+            "Transformation webhook {url} must use the http or https scheme"
+        ));
+    }
+    let port = parsed.port_or_known_default().unwrap_or(80);
+
+    let mut client = reqwest::Client::builder()
+        .timeout(config.timeout)
+        .redirect(reqwest::redirect::Policy::none());
+    if !config.allow_private_addresses {
+        let check = |ip: IpAddr| {
+            if is_public_address(&ip) {
+                Ok(())
+            } else {
+                Err(format!(
+                    "Transformation webhook {url} targets the non-public address {ip}"
+                ))
+            }
+        };
+        match parsed.host() {
+            Some(Host::Domain(domain)) => {
+                let address = resolve_public_address(domain, port)
+                    .await
+                    .map_err(|e| format!("Transformation webhook {url} rejected: {e}"))?;
+                client = client.resolve(domain, address);
+            }
+            Some(Host::Ipv4(ip)) => check(IpAddr::V4(ip))?,
+            Some(Host::Ipv6(ip)) => check(IpAddr::V6(ip))?,
+            None => return Err(format!("Transformation webhook {url} has no host")),
+        }
+    }
+    let client = client
+        .build()
+        .map_err(|e| format!("Failed to create the client of transformation webhook {url}: {e}"))?;
+
+    let mut response = client
+        .post(parsed)
+        .header(reqwest::header::CONTENT_TYPE, "application/wasm")
+        .body(data)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("Transformation webhook {url} failed: {e}"))?;
+
+    let too_large = || {
+        format!(
+            "The response of transformation webhook {url} is larger than {} bytes",
+            config.max_response_size
+        )
+    };
+    if response
+        .content_length()
+        .is_some_and(|length| length > config.max_response_size as u64)
+    {
+        return Err(too_large());
+    }
+
+    let mut result = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Failed to read the response of transformation webhook {url}: {e}"))?
+    {
+        if result.len() + chunk.len() > config.max_response_size {
+            return Err(too_large());
+        }
+        result.extend_from_slice(&chunk);
+    }
+
+    Ok(result)
+}
+
+/// Resolves a host, failing if any of its addresses is not public
+async fn resolve_public_address(host: &str, port: u16) -> Result<SocketAddr, String> {
+    let addresses = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|e| format!("failed to resolve {host}: {e}"))?
+        .collect::<Vec<_>>();
+
+    match addresses
+        .iter()
+        .find(|address| !is_public_address(&address.ip()))
+    {
+        Some(address) => Err(format!(
+            "{host} resolves to the non-public address {}",
+            address.ip()
+        )),
+        None => addresses
+            .into_iter()
+            .next()
+            .ok_or_else(|| format!("{host} does not resolve to any address")),
+    }
+}
+
+/// Whether an address is outside of the loopback, private, link-local and other special-purpose
+/// ranges, which could give the webhooks access to the internal network of the service
+pub fn is_public_address(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [first, second, ..] = ip.octets();
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || first == 0
+                || (first == 100 && (second & 0xc0) == 64))
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_address(&IpAddr::V4(ip)),
+            None => {
+                let first = ip.segments()[0];
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || (first & 0xfe00) == 0xfc00
+                    || (first & 0xffc0) == 0xfe80)
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::config::TransformationWebhookConfig;
+    use crate::service::component_processor::{
+        call_transformation_webhook, compose_component, is_public_address, strip_custom_sections,
+    };
+    use std::io::{Read, Write};
+    use std::net::{IpAddr, TcpListener, TcpStream};
+    use std::time::Duration;
+    use wasmparser::{Parser, Payload};
+
+    fn custom_section(name: &str, payload: &[u8]) -> Vec<u8> {
+        let mut section = vec![0, (1 + name.len() + payload.len()) as u8, name.len() as u8];
+        section.extend_from_slice(name.as_bytes());
+        section.extend_from_slice(payload);
+        section
+    }
+
+    #[test]
+    fn strip_custom_sections_keeps_only_the_listed_ones() {
+        let header = b"\0asm\x01\0\0\0".to_vec();
+        let name = custom_section("name", b"names");
+        let producers = custom_section("producers", b"rustc");
+
+        let module = [header.clone(), name.clone(), producers].concat();
+        let stripped = strip_custom_sections(&module, &["name".to_string()]).unwrap();
+
+        assert_eq!(stripped, [header, name].concat());
+    }
+
+    #[test]
+    fn strip_custom_sections_rejects_invalid_wasm() {
+        assert!(strip_custom_sections(b"not wasm", &[]).is_err());
+    }
+
+    const COMPONENT_HEADER: &[u8] = b"\0asm\x0d\0\x01\0";
+
+    fn section(id: u8, contents: Vec<u8>) -> Vec<u8> {
+        [vec![id, contents.len() as u8], contents].concat()
+    }
+
+    fn name(name: &str) -> Vec<u8> {
+        [vec![0, name.len() as u8], name.as_bytes().to_vec()].concat()
+    }
+
+    /// A component importing an empty instance with the given interface name
+    fn socket(interface: &str) -> Vec<u8> {
+        let types = section(7, vec![1, 0x42, 0]);
+        let imports = section(10, [vec![1], name(interface), vec![5, 0]].concat());
+        [COMPONENT_HEADER.to_vec(), types, imports].concat()
+    }
+
+    /// A component exporting an empty instance with the given interface name
+    fn plug(interface: &str) -> Vec<u8> {
+        let instances = section(5, vec![1, 1, 0]);
+        let exports = section(11, [vec![1], name(interface), vec![5, 0, 0]].concat());
+        [COMPONENT_HEADER.to_vec(), instances, exports].concat()
+    }
+
+    fn top_level_imports(data: &[u8]) -> Vec<String> {
+        let mut imports = Vec::new();
+        let mut depth = 0;
+        for payload in Parser::new(0).parse_all(data) {
+            match payload.unwrap() {
+                Payload::ModuleSection { .. } | Payload::ComponentSection { .. } => depth += 1,
+                Payload::End(_) => depth -= 1,
+                Payload::ComponentImportSection(reader) if depth == 0 => {
+                    for import in reader {
+                        imports.push(import.unwrap().name.0.to_string());
+                    }
+                }
+                _ => {}
+            }
+        }
+        imports
+    }
+
+    #[test]
+    fn compose_component_plugs_matching_exports_into_imports() {
+        let socket = socket("test:plug/api");
+        assert_eq!(top_level_imports(&socket), vec!["test:plug/api"]);
+
+        let composed = compose_component(socket, vec![plug("test:plug/api")]).unwrap();
+
+        assert!(top_level_imports(&composed).is_empty());
+    }
+
+    #[test]
+    fn compose_component_rejects_plugs_without_matching_exports() {
+        let result = compose_component(socket("test:plug/api"), vec![plug("test:other/api")]);
+
+        assert!(result.is_err());
+    }
+
+    fn read_request(stream: &mut TcpStream) {
+        let mut request = Vec::new();
+        let mut buffer = [0; 4096];
+        loop {
+            let read = stream.read(&mut buffer).unwrap();
+            if read == 0 {
+                return;
+            }
+            request.extend_from_slice(&buffer[..read]);
+            if let Some(end) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                let headers = String::from_utf8_lossy(&request[..end]).to_lowercase();
+                let length = headers
+                    .lines()
+                    .find_map(|line| line.strip_prefix("content-length:"))
+                    .map(|length| length.trim().parse::<usize>().unwrap())
+                    .unwrap_or(0);
+                if request.len() >= end + 4 + length {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Answers a single webhook request on a local port after the given delay, returning the
+    /// URL of the webhook
+    fn serve_once(status: &str, body: &[u8], delay: Duration) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let response = [
+            format!(
+                "HTTP/1.1 {status}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+                body.len()
+            )
+            .into_bytes(),
+            body.to_vec(),
+        ]
+        .concat();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            read_request(&mut stream);
+            std::thread::sleep(delay);
+            let _ = stream.write_all(&response);
+        });
+        format!("http://{address}/transform")
+    }
+
+    fn local_webhooks() -> TransformationWebhookConfig {
+        TransformationWebhookConfig {
+            allow_private_addresses: true,
+            ..TransformationWebhookConfig::default()
+        }
+    }
+
+    #[test]
+    async fn webhook_returns_the_transformed_wasm() {
+        let url = serve_once("200 OK", b"transformed", Duration::ZERO);
+
+        let result =
+            call_transformation_webhook(&url, b"original".to_vec(), &local_webhooks()).await;
+
+        assert_eq!(result, Ok(b"transformed".to_vec()));
+    }
+
+    #[test]
+    async fn webhook_errors_are_reported() {
+        let url = serve_once("500 Internal Server Error", b"", Duration::ZERO);
+
+        let result =
+            call_transformation_webhook(&url, b"original".to_vec(), &local_webhooks()).await;
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    async fn webhook_responses_are_limited_in_size() {
+        let url = serve_once("200 OK", b"transformed", Duration::ZERO);
+        let config = TransformationWebhookConfig {
+            max_response_size: 4,
+            ..local_webhooks()
+        };
+
+        let result = call_transformation_webhook(&url, b"original".to_vec(), &config).await;
+
+        assert!(result.unwrap_err().contains("larger than 4 bytes"));
+    }
+
+    #[test]
+    async fn webhook_calls_time_out() {
+        let url = serve_once("200 OK", b"transformed", Duration::from_secs(5));
+        let config = TransformationWebhookConfig {
+            timeout: Duration::from_millis(100),
+            ..local_webhooks()
+        };
+
+        let result = call_transformation_webhook(&url, b"original".to_vec(), &config).await;
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    async fn webhooks_can_not_target_private_addresses_by_default() {
+        let config = TransformationWebhookConfig::default();
+
+        for url in [
+            "http://127.0.0.1:1/transform",
+            "http://[::1]:1/transform",
+            "http://localhost:1/transform",
+            "http://169.254.169.254/latest/meta-data",
+        ] {
+            let result = call_transformation_webhook(url, b"original".to_vec(), &config).await;
+            assert!(
+                result.as_ref().is_err_and(|e| e.contains("non-public")),
+                "{url}: {result:?}"
+            );
+        }
+
+        let result =
+            call_transformation_webhook("file:///etc/passwd", b"original".to_vec(), &config).await;
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn only_public_addresses_are_public() {
+        let public = ["8.8.8.8", "2001:4860:4860::8888"];
+        let non_public = [
+            "0.0.0.0",
+            "10.1.2.3",
+            "100.64.0.1",
+            "127.0.0.1",
+            "169.254.169.254",
+            "172.16.0.1",
+            "192.168.1.1",
+            "255.255.255.255",
+            "::",
+            "::1",
+            "::ffff:127.0.0.1",
+            "fc00::1",
+            "fe80::1",
+        ];
+
+        for ip in public {
+            assert!(is_public_address(&ip.parse::<IpAddr>().unwrap()), "{ip}");
+        }
+        for ip in non_public {
+            assert!(!is_public_address(&ip.parse::<IpAddr>().unwrap()), "{ip}");
+        }
+    }
+}
@@ -15,3 +15,4 @@
 pub mod component;
 pub mod component_compilation;
 pub mod component_processor;
+pub mod worker_update_rollout;
@@ -0,0 +1,96 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use golem_api_grpc::proto::golem::worker::v1::worker_service_client::WorkerServiceClient;
+use golem_api_grpc::proto::golem::worker::v1::{
+    start_update_rollout_response, StartUpdateRolloutRequest,
+};
+use golem_common::client::{GrpcClient, GrpcClientConfig};
+use golem_common::model::ComponentId;
+use golem_service_base::model::ComponentUpdatePolicy;
+use tonic::codec::CompressionEncoding;
+use tonic::transport::Channel;
+
+/// Starts updating the existing workers of a component after a new version has been uploaded
+#[async_trait]
+pub trait WorkerUpdateRolloutService {
+    async fn start_rollout(
+        &self,
+        component_id: &ComponentId,
+        target_version: u64,
+        policy: &ComponentUpdatePolicy,
+    );
+}
+
+/// Delegates the rollout to the worker service, which enqueues and throttles the worker updates
+pub struct WorkerUpdateRolloutServiceDefault {
+    client: GrpcClient<WorkerServiceClient<Channel>>,
+}
+
+impl WorkerUpdateRolloutServiceDefault {
+    pub fn new(uri: http_02::Uri) -> Self {
+        let client = GrpcClient::new(
+            |channel| {
+                WorkerServiceClient::new(channel)
+                    .send_compressed(CompressionEncoding::Gzip)
+                    .accept_compressed(CompressionEncoding::Gzip)
+            },
+            uri,
+            GrpcClientConfig::default(),
+        );
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl WorkerUpdateRolloutService for WorkerUpdateRolloutServiceDefault {
+    async fn start_rollout(
+        &self,
+        component_id: &ComponentId,
+        target_version: u64,
+        policy: &ComponentUpdatePolicy,
+    ) {
+        let component_id_clone = component_id.clone();
+        let policy_clone = policy.clone();
+        let result = self
+            .client
+            .call(move |client| {
+                let request = StartUpdateRolloutRequest {
+                    component_id: Some(component_id_clone.clone().into()),
+                    target_version,
+                    policy: Some(policy_clone.clone().into()),
+                };
+                Box::pin(client.start_update_rollout(request))
+            })
+            .await;
+        match result.map(|response| response.into_inner().result) {
+            Ok(Some(start_update_rollout_response::Result::Success(_))) => tracing::info!(
+                "Started updating the workers of component {component_id} to version {target_version}"
+            ),
+            Ok(Some(start_update_rollout_response::Result::Error(error))) => {
+                tracing::error!("Failed to start the worker update rollout: {error:?}")
+            }
+            Ok(None) => tracing::error!("Failed to start the worker update rollout: empty response"),
+            Err(e) => tracing::error!("Failed to start the worker update rollout: {e:?}"),
+        }
+    }
+}
+
+pub struct WorkerUpdateRolloutServiceDisabled;
+
+#[async_trait]
+impl WorkerUpdateRolloutService for WorkerUpdateRolloutServiceDisabled {
+    async fn start_rollout(&self, _: &ComponentId, _: u64, _: &ComponentUpdatePolicy) {}
+}
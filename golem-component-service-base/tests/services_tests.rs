@@ -5,17 +5,38 @@ use golem_service_base::auth::DefaultNamespace;
 use golem_service_base::config::ComponentStoreLocalConfig;
 use golem_service_base::db;
 
-use golem_common::model::{ComponentId, ComponentType};
+use golem_api_grpc::proto::golem::component::ComponentMetadata as ComponentMetadataProto;
+use golem_common::model::component_observability::{ComponentObservability, SensitiveParameter};
+use golem_common::model::{
+    ComponentId, ComponentType, FilterComparator, LogLevel, ProjectId, WorkerFilter, WorkerStatus,
+};
+use golem_component_service_base::config::{ComponentNameUniqueness, TransformationWebhookConfig};
 use golem_component_service_base::model::Component;
-use golem_component_service_base::repo::component::{ComponentRepo, DbComponentRepo};
+use golem_component_service_base::repo::component::{
+    record_metadata_serde, ComponentObservabilityRecord, ComponentRepo,
+    ComponentUpdatePolicyRecord, DbComponentRepo,
+};
+use golem_component_service_base::repo::project::{DbProjectRepo, ProjectRepo};
 use golem_component_service_base::service::component::{
-    create_new_component, ComponentService, ComponentServiceDefault,
+    create_new_component, ComponentError, ComponentPlugReference, ComponentService,
+    ComponentServiceDefault,
 };
 use golem_component_service_base::service::component_compilation::{
     ComponentCompilationService, ComponentCompilationServiceDisabled,
 };
-use golem_service_base::model::ComponentName;
+use golem_component_service_base::service::worker_update_rollout::{
+    WorkerUpdateRolloutService, WorkerUpdateRolloutServiceDisabled,
+};
+use golem_service_base::model::{
+    ComponentDependency, ComponentLabelFilter, ComponentName, ComponentTransformation,
+    ComponentTransformationPlugin, ComponentUpdatePolicy, ComponentVersionAliasChange, ProjectData,
+    StripCustomSections, TransformationWebhook, WorkerUpdateMode,
+};
+use golem_service_base::repo::RepoError;
 use golem_service_base::service::component_object_store;
+use prost::Message;
+use std::collections::BTreeMap;
+use std::str::FromStr;
 use std::sync::Arc;
 use testcontainers::runners::AsyncRunner;
 use testcontainers::{ContainerAsync, ImageExt};
@@ -80,9 +101,12 @@ pub async fn test_with_postgres_db() {
 
     let component_repo: Arc<dyn ComponentRepo + Sync + Send> =
         Arc::new(DbComponentRepo::new(db_pool.clone().into()));
+    let project_repo: Arc<dyn ProjectRepo + Sync + Send> =
+        Arc::new(DbProjectRepo::new(db_pool.clone().into()));
 
     test_repo(component_repo.clone()).await;
-    test_services(component_repo.clone()).await;
+    test_services(component_repo.clone(), project_repo.clone()).await;
+    test_project_scoped_names(component_repo.clone(), project_repo.clone()).await;
 }
 
 #[test]
@@ -101,9 +125,12 @@ pub async fn test_with_sqlite_db() {
 
     let component_repo: Arc<dyn ComponentRepo + Sync + Send> =
         Arc::new(DbComponentRepo::new(db_pool.clone().into()));
+    let project_repo: Arc<dyn ProjectRepo + Sync + Send> =
+        Arc::new(DbProjectRepo::new(db_pool.clone().into()));
 
     test_repo(component_repo.clone()).await;
-    test_services(component_repo.clone()).await;
+    test_services(component_repo.clone(), project_repo.clone()).await;
+    test_project_scoped_names(component_repo.clone(), project_repo.clone()).await;
 }
 
 fn get_component_data(name: &str) -> Vec<u8> {
@@ -111,7 +138,10 @@ fn get_component_data(name: &str) -> Vec<u8> {
     std::fs::read(path).unwrap()
 }
 
-async fn test_services(component_repo: Arc<dyn ComponentRepo + Sync + Send>) {
+async fn test_services(
+    component_repo: Arc<dyn ComponentRepo + Sync + Send>,
+    project_repo: Arc<dyn ProjectRepo + Sync + Send>,
+) {
     let object_store: Arc<dyn component_object_store::ComponentObjectStore + Sync + Send> =
         Arc::new(
             component_object_store::FsComponentObjectStore::new(&ComponentStoreLocalConfig {
@@ -124,11 +154,18 @@ async fn test_services(component_repo: Arc<dyn ComponentRepo + Sync + Send>) {
     let compilation_service: Arc<dyn ComponentCompilationService + Sync + Send> =
         Arc::new(ComponentCompilationServiceDisabled);
 
+    let worker_update_rollout_service: Arc<dyn WorkerUpdateRolloutService + Sync + Send> =
+        Arc::new(WorkerUpdateRolloutServiceDisabled);
+
     let component_service: Arc<dyn ComponentService<DefaultNamespace> + Sync + Send> =
         Arc::new(ComponentServiceDefault::new(
             component_repo.clone(),
+            project_repo.clone(),
             object_store.clone(),
             compilation_service.clone(),
+            worker_update_rollout_service.clone(),
+            ComponentNameUniqueness::Namespace,
+            TransformationWebhookConfig::default(),
         ));
 
     let component_name1 = ComponentName("shopping-cart".to_string());
@@ -140,6 +177,7 @@ async fn test_services(component_repo: Arc<dyn ComponentRepo + Sync + Send>) {
             &component_name1,
             ComponentType::Durable,
             get_component_data("shopping-cart"),
+            None,
             &DefaultNamespace::default(),
         )
         .await
@@ -151,6 +189,7 @@ async fn test_services(component_repo: Arc<dyn ComponentRepo + Sync + Send>) {
             &component_name2,
             ComponentType::Durable,
             get_component_data("rust-echo"),
+            None,
             &DefaultNamespace::default(),
         )
         .await
@@ -204,6 +243,16 @@ async fn test_services(component_repo: Arc<dyn ComponentRepo + Sync + Send>) {
         .await
         .unwrap();
 
+    let compatibility_report = component1v2.compatibility_report.clone().unwrap();
+    assert_eq!(
+        compatibility_report.previous_version,
+        component1.versioned_component_id.version
+    );
+    assert!(compatibility_report.changes.is_empty());
+    assert!(compatibility_report.import_changes.is_empty());
+    assert!(!compatibility_report.breaking);
+    assert!(!component1v2.metadata.imports.is_empty());
+
     let component1_result = component_service
         .get_latest_version(
             &component1.versioned_component_id.component_id,
@@ -288,7 +337,11 @@ async fn test_services(component_repo: Arc<dyn ComponentRepo + Sync + Send>) {
     assert!(component2_result.is_some());
 
     let component1_result = component_service
-        .find_id_by_name(&component1.component_name, &DefaultNamespace::default())
+        .find_id_by_name(
+            &component1.component_name,
+            None,
+            &DefaultNamespace::default(),
+        )
         .await
         .unwrap();
     assert_eq!(
@@ -297,7 +350,11 @@ async fn test_services(component_repo: Arc<dyn ComponentRepo + Sync + Send>) {
     );
 
     let component2_result = component_service
-        .find_id_by_name(&component2.component_name, &DefaultNamespace::default())
+        .find_id_by_name(
+            &component2.component_name,
+            None,
+            &DefaultNamespace::default(),
+        )
         .await
         .unwrap();
     assert_eq!(
@@ -308,6 +365,8 @@ async fn test_services(component_repo: Arc<dyn ComponentRepo + Sync + Send>) {
     let component1_result = component_service
         .find_by_name(
             Some(component1.component_name.clone()),
+            &[],
+            None,
             &DefaultNamespace::default(),
         )
         .await
@@ -320,6 +379,8 @@ async fn test_services(component_repo: Arc<dyn ComponentRepo + Sync + Send>) {
     let component2_result = component_service
         .find_by_name(
             Some(component2.component_name.clone()),
+            &[],
+            None,
             &DefaultNamespace::default(),
         )
         .await
@@ -327,11 +388,172 @@ async fn test_services(component_repo: Arc<dyn ComponentRepo + Sync + Send>) {
     assert_eq!(component2_result, vec![component2.clone()]);
 
     let component_result = component_service
-        .find_by_name(None, &DefaultNamespace::default())
+        .find_by_name(None, &[], None, &DefaultNamespace::default())
         .await
         .unwrap();
     assert_eq!(component_result.len(), 3);
 
+    let labels = BTreeMap::from([
+        ("team".to_string(), "echo".to_string()),
+        ("stage".to_string(), "dev".to_string()),
+    ]);
+    let labelled_component2 = component_service
+        .update_labels(
+            &component2.versioned_component_id.component_id,
+            labels.clone(),
+            &DefaultNamespace::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(labelled_component2.metadata.labels, labels);
+
+    let component_result = component_service
+        .find_by_name(
+            None,
+            &[ComponentLabelFilter::from_str("team=echo").unwrap()],
+            None,
+            &DefaultNamespace::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(component_result, vec![labelled_component2.clone()]);
+
+    let component_result = component_service
+        .find_by_name(
+            None,
+            &[
+                ComponentLabelFilter::from_str("stage").unwrap(),
+                ComponentLabelFilter::from_str("team=cart").unwrap(),
+            ],
+            None,
+            &DefaultNamespace::default(),
+        )
+        .await
+        .unwrap();
+    assert!(component_result.is_empty());
+
+    let component2v2 = component_service
+        .update(
+            &component2.versioned_component_id.component_id,
+            get_component_data("rust-echo"),
+            None,
+            &DefaultNamespace::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(component2v2.metadata.labels, labels);
+
+    let invalid_labels = component_service
+        .update_labels(
+            &component2.versioned_component_id.component_id,
+            BTreeMap::from([("team=echo".to_string(), "echo".to_string())]),
+            &DefaultNamespace::default(),
+        )
+        .await;
+    assert!(matches!(
+        invalid_labels,
+        Err(ComponentError::InvalidLabels(_))
+    ));
+
+    let initial_files = {
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        writer
+            .start_file("config.json", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        std::io::Write::write_all(&mut writer, b"{}").unwrap();
+        writer.finish().unwrap().into_inner()
+    };
+    let component2v3_with_files = component_service
+        .update_initial_files(
+            &component2.versioned_component_id.component_id,
+            initial_files,
+            &DefaultNamespace::default(),
+        )
+        .await
+        .unwrap();
+    assert!(component2v3_with_files.initial_files_hash.is_some());
+    assert_eq!(
+        component2v3_with_files.versioned_component_id.version,
+        component2v2.versioned_component_id.version + 1
+    );
+    assert_eq!(
+        component2v3_with_files.component_size,
+        component2v2.component_size
+    );
+
+    let component2v2_unchanged = component_service
+        .get_by_version(
+            &component2v2.versioned_component_id,
+            &DefaultNamespace::default(),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        component2v2_unchanged.initial_files_hash,
+        component2v2.initial_files_hash
+    );
+
+    let component2v4 = component_service
+        .update(
+            &component2.versioned_component_id.component_id,
+            get_component_data("rust-echo"),
+            None,
+            &DefaultNamespace::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        component2v4.initial_files_hash,
+        component2v3_with_files.initial_files_hash
+    );
+
+    let invalid_initial_files = component_service
+        .update_initial_files(
+            &component2.versioned_component_id.component_id,
+            b"not an archive".to_vec(),
+            &DefaultNamespace::default(),
+        )
+        .await;
+    assert!(matches!(
+        invalid_initial_files,
+        Err(ComponentError::InvalidInitialFiles(_))
+    ));
+
+    let unmatched_plug = component_service
+        .update_composed(
+            &component1.versioned_component_id.component_id,
+            get_component_data("shopping-cart"),
+            None,
+            &[ComponentPlugReference {
+                component_id: component2.versioned_component_id.component_id.clone(),
+                version: Some(component2.versioned_component_id.version),
+            }],
+            &DefaultNamespace::default(),
+        )
+        .await;
+    assert!(matches!(
+        unmatched_plug,
+        Err(ComponentError::ComponentCompositionFailed(_))
+    ));
+
+    let unknown_plug_version = component_service
+        .update_composed(
+            &component1.versioned_component_id.component_id,
+            get_component_data("shopping-cart"),
+            None,
+            &[ComponentPlugReference {
+                component_id: component2.versioned_component_id.component_id.clone(),
+                version: Some(component2.versioned_component_id.version + 100),
+            }],
+            &DefaultNamespace::default(),
+        )
+        .await;
+    assert!(matches!(
+        unknown_plug_version,
+        Err(ComponentError::UnknownComponentId(_))
+    ));
+
     component_service
         .delete(
             &component1v2.versioned_component_id.component_id,
@@ -358,12 +580,624 @@ async fn test_services(component_repo: Arc<dyn ComponentRepo + Sync + Send>) {
         .await
         .unwrap();
     assert!(component1_result.is_none());
+
+    let counters = component_service
+        .create(
+            &ComponentId::new_v4(),
+            &ComponentName("counters".to_string()),
+            ComponentType::Durable,
+            get_component_data("counters"),
+            None,
+            &DefaultNamespace::default(),
+        )
+        .await
+        .unwrap();
+    let caller = component_service
+        .create(
+            &ComponentId::new_v4(),
+            &ComponentName("caller".to_string()),
+            ComponentType::Durable,
+            get_component_data("caller_composed"),
+            None,
+            &DefaultNamespace::default(),
+        )
+        .await
+        .unwrap();
+    assert!(caller
+        .metadata
+        .rpc_dependencies
+        .contains(&"rpc:counters".to_string()));
+
+    let counters_graph = component_service
+        .get_dependency_graph(
+            &counters.versioned_component_id.component_id,
+            &DefaultNamespace::default(),
+        )
+        .await
+        .unwrap();
+    assert!(counters_graph.dependencies.is_empty());
+    assert_eq!(
+        counters_graph.dependents,
+        vec![ComponentDependency {
+            versioned_component_id: caller.versioned_component_id.clone(),
+            component_name: caller.component_name.clone(),
+            package: "rpc:counters".to_string(),
+        }]
+    );
+
+    let caller_graph = component_service
+        .get_dependency_graph(
+            &caller.versioned_component_id.component_id,
+            &DefaultNamespace::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        caller_graph.dependencies,
+        vec![ComponentDependency {
+            versioned_component_id: counters.versioned_component_id.clone(),
+            component_name: counters.component_name.clone(),
+            package: "rpc:counters".to_string(),
+        }]
+    );
+    assert!(caller_graph.dependents.is_empty());
+
+    // Metadata stored before the dependencies were tracked gets them detected again
+    let mut untracked_metadata: ComponentMetadataProto = caller.metadata.clone().into();
+    untracked_metadata.rpc_dependencies.clear();
+    let mut untracked_record = vec![record_metadata_serde::SERIALIZATION_VERSION_V1];
+    untracked_record.extend(untracked_metadata.encode_to_vec());
+    component_repo
+        .update_metadata(
+            &caller.versioned_component_id.component_id.0,
+            caller.versioned_component_id.version,
+            &untracked_record,
+        )
+        .await
+        .unwrap();
+
+    let counters_graph = component_service
+        .get_dependency_graph(
+            &counters.versioned_component_id.component_id,
+            &DefaultNamespace::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        counters_graph.dependents,
+        vec![ComponentDependency {
+            versioned_component_id: caller.versioned_component_id.clone(),
+            component_name: caller.component_name.clone(),
+            package: "rpc:counters".to_string(),
+        }]
+    );
+    let backfilled_record = component_repo
+        .get_latest_version(&caller.versioned_component_id.component_id.0)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(record_metadata_serde::has_rpc_dependencies(
+        &backfilled_record.metadata
+    ));
+
+    let invalid_plugin = component_service
+        .register_transformation_plugin(
+            ComponentTransformationPlugin {
+                name: "strip sections".to_string(),
+                priority: 0,
+                transformation: ComponentTransformation::StripCustomSections(StripCustomSections {
+                    keep: vec![],
+                }),
+            },
+            &DefaultNamespace::default(),
+        )
+        .await;
+    assert!(matches!(
+        invalid_plugin,
+        Err(ComponentError::InvalidTransformationPlugin(_))
+    ));
+
+    let strip_plugin = ComponentTransformationPlugin {
+        name: "strip-sections".to_string(),
+        priority: 0,
+        transformation: ComponentTransformation::StripCustomSections(StripCustomSections {
+            keep: vec![],
+        }),
+    };
+    component_service
+        .register_transformation_plugin(strip_plugin.clone(), &DefaultNamespace::default())
+        .await
+        .unwrap();
+    let plugins = component_service
+        .get_transformation_plugins(&DefaultNamespace::default())
+        .await
+        .unwrap();
+    assert_eq!(plugins, vec![strip_plugin.clone()]);
+
+    let stripped = component_service
+        .create(
+            &ComponentId::new_v4(),
+            &ComponentName("stripped".to_string()),
+            ComponentType::Durable,
+            get_component_data("shopping-cart"),
+            None,
+            &DefaultNamespace::default(),
+        )
+        .await
+        .unwrap();
+    assert!(stripped.metadata.producers.is_empty());
+    assert_eq!(stripped.metadata.exports, component1.metadata.exports);
+    assert!(stripped.component_size < get_component_data("shopping-cart").len() as u64);
+
+    // Webhooks targeting the internal network are rejected by the default configuration
+    let failing_webhook = |name: &str, priority: i32| ComponentTransformationPlugin {
+        name: name.to_string(),
+        priority,
+        transformation: ComponentTransformation::Webhook(TransformationWebhook {
+            url: "http://127.0.0.1:1/transform".to_string(),
+        }),
+    };
+    for plugin in [failing_webhook("second", 2), failing_webhook("first", 1)] {
+        component_service
+            .register_transformation_plugin(plugin, &DefaultNamespace::default())
+            .await
+            .unwrap();
+    }
+    let plugins = component_service
+        .get_transformation_plugins(&DefaultNamespace::default())
+        .await
+        .unwrap();
+    assert_eq!(
+        plugins,
+        vec![
+            strip_plugin.clone(),
+            failing_webhook("first", 1),
+            failing_webhook("second", 2)
+        ]
+    );
+
+    let failed_name = ComponentName("failed-transformation".to_string());
+    let failed = component_service
+        .create(
+            &ComponentId::new_v4(),
+            &failed_name,
+            ComponentType::Durable,
+            get_component_data("shopping-cart"),
+            None,
+            &DefaultNamespace::default(),
+        )
+        .await;
+    assert!(matches!(
+        failed,
+        Err(ComponentError::ComponentTransformationFailed { plugin, .. }) if plugin == "first"
+    ));
+    let failed_id = component_service
+        .find_id_by_name(&failed_name, None, &DefaultNamespace::default())
+        .await
+        .unwrap();
+    assert_eq!(failed_id, None);
+
+    for name in ["first", "second"] {
+        component_service
+            .unregister_transformation_plugin(name, &DefaultNamespace::default())
+            .await
+            .unwrap();
+    }
+
+    component_service
+        .unregister_transformation_plugin(&strip_plugin.name, &DefaultNamespace::default())
+        .await
+        .unwrap();
+    let unknown_plugin = component_service
+        .unregister_transformation_plugin(&strip_plugin.name, &DefaultNamespace::default())
+        .await;
+    assert!(matches!(
+        unknown_plugin,
+        Err(ComponentError::UnknownTransformationPlugin(_))
+    ));
+
+    let project = component_service
+        .create_project(
+            ProjectData {
+                name: "shop".to_string(),
+                description: "Shopping components".to_string(),
+            },
+            &DefaultNamespace::default(),
+        )
+        .await
+        .unwrap();
+    let duplicate_project = component_service
+        .create_project(
+            ProjectData {
+                name: "shop".to_string(),
+                description: "".to_string(),
+            },
+            &DefaultNamespace::default(),
+        )
+        .await;
+    assert!(matches!(
+        duplicate_project,
+        Err(ComponentError::ProjectAlreadyExists(_))
+    ));
+    let projects = component_service
+        .get_projects(&DefaultNamespace::default())
+        .await
+        .unwrap();
+    assert_eq!(projects, vec![project.clone()]);
+
+    let unknown_project = component_service
+        .update_component_project(
+            &component1.versioned_component_id.component_id,
+            Some(ProjectId::new_v4()),
+            &DefaultNamespace::default(),
+        )
+        .await;
+    assert!(matches!(
+        unknown_project,
+        Err(ComponentError::UnknownProject(_))
+    ));
+
+    component_service
+        .update_component_project(
+            &component1.versioned_component_id.component_id,
+            Some(project.project_id.clone()),
+            &DefaultNamespace::default(),
+        )
+        .await
+        .unwrap();
+    let component1_project = component_service
+        .get_component_project(
+            &component1.versioned_component_id.component_id,
+            &DefaultNamespace::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(component1_project, Some(project.project_id.clone()));
+
+    let component_result = component_service
+        .find_by_name(
+            None,
+            &[],
+            Some(&project.project_id),
+            &DefaultNamespace::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        component_result
+            .iter()
+            .map(|c| c.versioned_component_id.component_id.clone())
+            .collect::<std::collections::HashSet<_>>(),
+        std::collections::HashSet::from([component1.versioned_component_id.component_id.clone()])
+    );
+
+    component_service
+        .delete_project(&project.project_id, &DefaultNamespace::default())
+        .await
+        .unwrap();
+    let component1_project = component_service
+        .get_component_project(
+            &component1.versioned_component_id.component_id,
+            &DefaultNamespace::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(component1_project, None);
+    let deleted_project = component_service
+        .get_project(&project.project_id, &DefaultNamespace::default())
+        .await;
+    assert!(matches!(
+        deleted_project,
+        Err(ComponentError::UnknownProject(_))
+    ));
+
+    let component1_id = component1.versioned_component_id.component_id.clone();
+    let stable = component_service
+        .update_version_alias(&component1_id, "stable", 0, &DefaultNamespace::default())
+        .await
+        .unwrap();
+    assert_eq!(stable.version, 0);
+    let stable_component = component_service
+        .get_by_version_alias(&component1_id, "stable", &DefaultNamespace::default())
+        .await
+        .unwrap();
+    assert_eq!(
+        stable_component.versioned_component_id,
+        component1.versioned_component_id
+    );
+
+    component_service
+        .update_version_alias(&component1_id, "stable", 1, &DefaultNamespace::default())
+        .await
+        .unwrap();
+    let stable_component = component_service
+        .get_by_version_alias(&component1_id, "stable", &DefaultNamespace::default())
+        .await
+        .unwrap();
+    assert_eq!(stable_component.versioned_component_id.version, 1);
+    let aliases = component_service
+        .get_version_aliases(&component1_id, &DefaultNamespace::default())
+        .await
+        .unwrap();
+    assert_eq!(
+        aliases
+            .iter()
+            .map(|a| (a.name.as_str(), a.version))
+            .collect::<Vec<_>>(),
+        vec![("stable", 1)]
+    );
+
+    let invalid_alias = component_service
+        .update_version_alias(
+            &component1_id,
+            "not stable",
+            1,
+            &DefaultNamespace::default(),
+        )
+        .await;
+    assert!(matches!(
+        invalid_alias,
+        Err(ComponentError::InvalidVersionAlias(_))
+    ));
+    let unknown_version = component_service
+        .update_version_alias(&component1_id, "canary", 100, &DefaultNamespace::default())
+        .await;
+    assert!(matches!(
+        unknown_version,
+        Err(ComponentError::UnknownVersionedComponentId(_))
+    ));
+
+    component_service
+        .delete_version_alias(&component1_id, "stable", &DefaultNamespace::default())
+        .await
+        .unwrap();
+    let unknown_alias = component_service
+        .get_by_version_alias(&component1_id, "stable", &DefaultNamespace::default())
+        .await;
+    assert!(matches!(
+        unknown_alias,
+        Err(ComponentError::UnknownVersionAlias { .. })
+    ));
+    let history = component_service
+        .get_version_alias_history(&component1_id, &DefaultNamespace::default())
+        .await
+        .unwrap();
+    assert_eq!(
+        history
+            .into_iter()
+            .map(|change| ComponentVersionAliasChange {
+                changed_at: stable.updated_at,
+                ..change
+            })
+            .collect::<Vec<_>>(),
+        vec![
+            ComponentVersionAliasChange {
+                name: "stable".to_string(),
+                previous_version: None,
+                version: Some(0),
+                changed_at: stable.updated_at,
+            },
+            ComponentVersionAliasChange {
+                name: "stable".to_string(),
+                previous_version: Some(0),
+                version: Some(1),
+                changed_at: stable.updated_at,
+            },
+            ComponentVersionAliasChange {
+                name: "stable".to_string(),
+                previous_version: Some(1),
+                version: None,
+                changed_at: stable.updated_at,
+            },
+        ]
+    );
+
+    let stripped_id = stripped.versioned_component_id.component_id.clone();
+    let not_deleted = component_service
+        .restore(&stripped_id, &DefaultNamespace::default())
+        .await;
+    assert!(matches!(
+        not_deleted,
+        Err(ComponentError::ComponentNotDeleted(_))
+    ));
+
+    component_service
+        .soft_delete(&stripped_id, &DefaultNamespace::default())
+        .await
+        .unwrap();
+    let deleted_again = component_service
+        .soft_delete(&stripped_id, &DefaultNamespace::default())
+        .await;
+    assert!(matches!(
+        deleted_again,
+        Err(ComponentError::UnknownComponentId(_))
+    ));
+    let component_result = component_service
+        .find_by_name(
+            Some(ComponentName("stripped".to_string())),
+            &[],
+            None,
+            &DefaultNamespace::default(),
+        )
+        .await
+        .unwrap();
+    assert!(component_result.is_empty());
+    let deleted_component = component_service
+        .get_latest_version(&stripped_id, &DefaultNamespace::default())
+        .await
+        .unwrap();
+    assert_eq!(deleted_component, Some(stripped.clone()));
+
+    let restored = component_service
+        .restore(&stripped_id, &DefaultNamespace::default())
+        .await
+        .unwrap();
+    assert_eq!(restored, stripped);
+    let component_result = component_service
+        .find_by_name(
+            Some(ComponentName("stripped".to_string())),
+            &[],
+            None,
+            &DefaultNamespace::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(component_result, vec![stripped.clone()]);
+
+    component_service
+        .soft_delete(&stripped_id, &DefaultNamespace::default())
+        .await
+        .unwrap();
+    let purged = component_service
+        .purge_deleted(
+            chrono::Utc::now() + chrono::Duration::seconds(1),
+            chrono::Utc::now(),
+        )
+        .await
+        .unwrap();
+    assert!(purged >= 1);
+    let purged_component = component_service
+        .get_latest_version(&stripped_id, &DefaultNamespace::default())
+        .await
+        .unwrap();
+    assert!(purged_component.is_none());
+}
+
+async fn test_project_scoped_names(
+    component_repo: Arc<dyn ComponentRepo + Sync + Send>,
+    project_repo: Arc<dyn ProjectRepo + Sync + Send>,
+) {
+    let object_store: Arc<dyn component_object_store::ComponentObjectStore + Sync + Send> =
+        Arc::new(
+            component_object_store::FsComponentObjectStore::new(&ComponentStoreLocalConfig {
+                root_path: "/tmp/component".to_string(),
+                object_prefix: Uuid::new_v4().to_string(),
+            })
+            .unwrap(),
+        );
+
+    let component_service: Arc<dyn ComponentService<DefaultNamespace> + Sync + Send> =
+        Arc::new(ComponentServiceDefault::new(
+            component_repo,
+            project_repo,
+            object_store,
+            Arc::new(ComponentCompilationServiceDisabled),
+            Arc::new(WorkerUpdateRolloutServiceDisabled),
+            ComponentNameUniqueness::Project,
+            TransformationWebhookConfig::default(),
+        ));
+
+    let namespace = DefaultNamespace::default();
+    let component_name = ComponentName(format!("api-{}", Uuid::new_v4()));
+
+    let mut projects = Vec::new();
+    for team in ["team-a", "team-b"] {
+        let project = component_service
+            .create_project(
+                ProjectData {
+                    name: format!("{team}-{}", Uuid::new_v4()),
+                    description: "".to_string(),
+                },
+                &namespace,
+            )
+            .await
+            .unwrap();
+        projects.push(project.project_id);
+    }
+
+    let unassigned = component_service
+        .create(
+            &ComponentId::new_v4(),
+            &component_name,
+            ComponentType::Durable,
+            get_component_data("shopping-cart"),
+            None,
+            &namespace,
+        )
+        .await
+        .unwrap();
+    let unassigned_id = unassigned.versioned_component_id.component_id;
+
+    let mut component_ids = Vec::new();
+    for project_id in &projects {
+        let component = component_service
+            .create(
+                &ComponentId::new_v4(),
+                &component_name,
+                ComponentType::Durable,
+                get_component_data("shopping-cart"),
+                Some(project_id),
+                &namespace,
+            )
+            .await
+            .unwrap();
+        let component_id = component.versioned_component_id.component_id;
+        let component_project = component_service
+            .get_component_project(&component_id, &namespace)
+            .await
+            .unwrap();
+        assert_eq!(component_project, Some(project_id.clone()));
+        component_ids.push(component_id);
+    }
+
+    let duplicate_created_in_project = component_service
+        .create(
+            &ComponentId::new_v4(),
+            &component_name,
+            ComponentType::Durable,
+            get_component_data("shopping-cart"),
+            Some(&projects[1]),
+            &namespace,
+        )
+        .await;
+    assert!(matches!(
+        duplicate_created_in_project,
+        Err(ComponentError::AlreadyExists(id)) if id == component_ids[1]
+    ));
+
+    let duplicate_unassigned = component_service
+        .create(
+            &ComponentId::new_v4(),
+            &component_name,
+            ComponentType::Durable,
+            get_component_data("shopping-cart"),
+            None,
+            &namespace,
+        )
+        .await;
+    assert!(matches!(
+        duplicate_unassigned,
+        Err(ComponentError::AlreadyExists(id)) if id == unassigned_id
+    ));
+
+    let duplicate_in_project = component_service
+        .update_component_project(&unassigned_id, Some(projects[0].clone()), &namespace)
+        .await;
+    assert!(matches!(
+        duplicate_in_project,
+        Err(ComponentError::AlreadyExists(id)) if id == component_ids[0]
+    ));
+
+    for (project_id, component_id) in projects.iter().zip(&component_ids) {
+        let found = component_service
+            .find_id_by_name(&component_name, Some(project_id), &namespace)
+            .await
+            .unwrap();
+        assert_eq!(found, Some(component_id.clone()));
+    }
+    let found = component_service
+        .find_id_by_name(&component_name, None, &namespace)
+        .await
+        .unwrap();
+    assert_eq!(found, Some(unassigned_id));
 }
 
 async fn test_repo(component_repo: Arc<dyn ComponentRepo + Sync + Send>) {
     test_repo_component_id_unique(component_repo.clone()).await;
     test_repo_component_name_unique_in_namespace(component_repo.clone()).await;
     test_repo_component_delete(component_repo.clone()).await;
+    test_repo_component_observability(component_repo.clone()).await;
+    test_repo_component_update_policy(component_repo.clone()).await;
 }
 
 async fn test_repo_component_id_unique(component_repo: Arc<dyn ComponentRepo + Sync + Send>) {
@@ -450,6 +1284,28 @@ async fn test_repo_component_name_unique_in_namespace(
     assert!(result1.is_ok());
     assert!(result2.is_err());
     assert!(result3.is_ok());
+
+    let same_name = create_new_component(
+        &ComponentId::new_v4(),
+        &component_name1,
+        ComponentType::Durable,
+        &data,
+        &namespace1,
+    )
+    .unwrap();
+    let result4 = component_repo
+        .create(&same_name.clone().try_into().unwrap())
+        .await;
+    let result5 = component_repo
+        .create_in_project(
+            &same_name.try_into().unwrap(),
+            None,
+            &Uuid::new_v4().simple().to_string(),
+        )
+        .await;
+
+    assert!(matches!(result4, Err(RepoError::UniqueViolation(_))));
+    assert!(result5.is_ok());
 }
 
 async fn test_repo_component_delete(component_repo: Arc<dyn ComponentRepo + Sync + Send>) {
@@ -493,3 +1349,131 @@ async fn test_repo_component_delete(component_repo: Arc<dyn ComponentRepo + Sync
     assert!(result4.is_ok());
     assert!(result4.unwrap().is_empty());
 }
+
+async fn test_repo_component_observability(component_repo: Arc<dyn ComponentRepo + Sync + Send>) {
+    let namespace1 = Uuid::new_v4().to_string();
+
+    let component_name1 = ComponentName("shopping-cart1".to_string());
+    let data = get_component_data("shopping-cart");
+
+    let component1 = create_new_component(
+        &ComponentId::new_v4(),
+        &component_name1,
+        ComponentType::Durable,
+        &data,
+        &namespace1,
+    )
+    .unwrap();
+    let component_id = component1.versioned_component_id.component_id.clone();
+
+    let observability1 = ComponentObservability {
+        log_level: LogLevel::Warn,
+        metrics_enabled: false,
+        trace_sampling_rate: 0.5,
+        payload_capture_sampling_rate: 0.0,
+        sensitive_parameters: vec![],
+    };
+    let observability2 = ComponentObservability {
+        log_level: LogLevel::Error,
+        sensitive_parameters: vec![SensitiveParameter {
+            function_name: "golem:it/api.{checkout}".to_string(),
+            parameter_name: "card-number".to_string(),
+        }],
+        ..observability1.clone()
+    };
+
+    let result1 = component_repo
+        .create(&component1.clone().try_into().unwrap())
+        .await;
+
+    let result2 = component_repo.get_observability(&component_id.0).await;
+
+    let result3 = component_repo
+        .update_observability(&ComponentObservabilityRecord::new(
+            &component_id,
+            observability1.clone(),
+        ))
+        .await;
+    let result4 = component_repo.get_observability(&component_id.0).await;
+
+    let result5 = component_repo
+        .update_observability(&ComponentObservabilityRecord::new(
+            &component_id,
+            observability2.clone(),
+        ))
+        .await;
+    let result6 = component_repo.get_observability(&component_id.0).await;
+
+    let result7 = component_repo.delete(&namespace1, &component_id.0).await;
+    let result8 = component_repo.get_observability(&component_id.0).await;
+
+    assert!(result1.is_ok());
+    assert!(result2.unwrap().is_none());
+    assert!(result3.is_ok());
+    assert_eq!(
+        ComponentObservability::try_from(result4.unwrap().unwrap()).unwrap(),
+        observability1
+    );
+    assert!(result5.is_ok());
+    assert_eq!(
+        ComponentObservability::try_from(result6.unwrap().unwrap()).unwrap(),
+        observability2
+    );
+    assert!(result7.is_ok());
+    assert!(result8.unwrap().is_none());
+}
+
+async fn test_repo_component_update_policy(component_repo: Arc<dyn ComponentRepo + Sync + Send>) {
+    let namespace1 = Uuid::new_v4().to_string();
+
+    let component_name1 = ComponentName("shopping-cart1".to_string());
+    let data = get_component_data("shopping-cart");
+
+    let component1 = create_new_component(
+        &ComponentId::new_v4(),
+        &component_name1,
+        ComponentType::Durable,
+        &data,
+        &namespace1,
+    )
+    .unwrap();
+    let component_id = component1.versioned_component_id.component_id.clone();
+
+    let policy = ComponentUpdatePolicy {
+        enabled: true,
+        mode: WorkerUpdateMode::Manual,
+        filter: Some(WorkerFilter::new_status(
+            FilterComparator::Equal,
+            WorkerStatus::Idle,
+        )),
+        max_concurrent_updates_per_executor: 2,
+        version_alias: Some("stable".to_string()),
+    };
+
+    let result1 = component_repo
+        .create(&component1.clone().try_into().unwrap())
+        .await;
+
+    let result2 = component_repo.get_update_policy(&component_id.0).await;
+
+    let result3 = component_repo
+        .update_update_policy(&ComponentUpdatePolicyRecord::new(
+            &component_id,
+            policy.clone(),
+        ))
+        .await;
+    let result4 = component_repo.get_update_policy(&component_id.0).await;
+
+    let result5 = component_repo.delete(&namespace1, &component_id.0).await;
+    let result6 = component_repo.get_update_policy(&component_id.0).await;
+
+    assert!(result1.is_ok());
+    assert!(result2.unwrap().is_none());
+    assert!(result3.is_ok());
+    assert_eq!(
+        ComponentUpdatePolicy::try_from(result4.unwrap().unwrap()).unwrap(),
+        policy
+    );
+    assert!(result5.is_ok());
+    assert!(result6.unwrap().is_none());
+}
@@ -0,0 +1,8 @@
+CREATE TABLE component_observability
+(
+    component_id                  uuid             NOT NULL PRIMARY KEY REFERENCES components (component_id),
+    log_level                     integer          NOT NULL,
+    metrics_enabled               boolean          NOT NULL,
+    trace_sampling_rate           double precision NOT NULL,
+    payload_capture_sampling_rate double precision NOT NULL
+);
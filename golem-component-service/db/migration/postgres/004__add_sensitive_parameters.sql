@@ -0,0 +1,2 @@
+ALTER TABLE component_observability
+    ADD COLUMN IF NOT EXISTS sensitive_parameters bytea;
@@ -0,0 +1,2 @@
+ALTER TABLE component_versions
+    ADD COLUMN IF NOT EXISTS compatibility_report bytea;
@@ -0,0 +1,2 @@
+ALTER TABLE component_versions
+    ADD COLUMN IF NOT EXISTS initial_files_hash text;
@@ -0,0 +1,8 @@
+CREATE TABLE component_transformation_plugins
+(
+    namespace       text    NOT NULL,
+    name            text    NOT NULL,
+    priority        integer NOT NULL,
+    transformation  text    NOT NULL,
+    PRIMARY KEY (namespace, name)
+);
@@ -0,0 +1,18 @@
+CREATE TABLE projects
+(
+    project_id  uuid      NOT NULL PRIMARY KEY,
+    namespace   text      NOT NULL,
+    name        text      NOT NULL,
+    description text      NOT NULL,
+    created_at  timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX projects_namespace_name_idx ON projects (namespace, name);
+
+CREATE TABLE component_projects
+(
+    component_id uuid NOT NULL PRIMARY KEY REFERENCES components (component_id),
+    project_id   uuid NOT NULL REFERENCES projects (project_id)
+);
+
+CREATE INDEX component_projects_project_id_idx ON component_projects (project_id);
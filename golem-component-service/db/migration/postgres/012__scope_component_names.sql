@@ -0,0 +1,3 @@
+DROP INDEX components_namespace_name_idx;
+
+CREATE INDEX components_namespace_name_idx ON components (namespace, name);
@@ -0,0 +1,12 @@
+ALTER TABLE components ADD COLUMN name_scope text NOT NULL DEFAULT '';
+
+UPDATE components
+SET name_scope = replace(cp.project_id::text, '-', '')
+FROM component_projects cp
+WHERE cp.component_id = components.component_id
+  AND (components.namespace, components.name) IN
+      (SELECT namespace, name FROM components GROUP BY namespace, name HAVING count(*) > 1);
+
+DROP INDEX components_namespace_name_idx;
+
+CREATE UNIQUE INDEX components_namespace_name_scope_name_idx ON components (namespace, name_scope, name);
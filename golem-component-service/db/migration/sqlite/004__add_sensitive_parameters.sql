@@ -0,0 +1,2 @@
+ALTER TABLE component_observability
+    ADD COLUMN sensitive_parameters blob;
@@ -0,0 +1,2 @@
+ALTER TABLE component_versions
+    ADD COLUMN compatibility_report blob;
@@ -0,0 +1,8 @@
+CREATE TABLE component_update_policy
+(
+    component_id                        uuid    NOT NULL PRIMARY KEY REFERENCES components (component_id),
+    enabled                             boolean NOT NULL,
+    update_mode                         integer NOT NULL,
+    filter                              blob,
+    max_concurrent_updates_per_executor integer NOT NULL
+);
@@ -0,0 +1,2 @@
+ALTER TABLE component_versions
+    ADD COLUMN initial_files_hash text;
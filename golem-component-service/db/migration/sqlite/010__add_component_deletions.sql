@@ -0,0 +1,7 @@
+CREATE TABLE component_deletions
+(
+    component_id uuid NOT NULL PRIMARY KEY REFERENCES components (component_id),
+    deleted_at   timestamp without time zone NOT NULL
+);
+
+CREATE INDEX component_deletions_deleted_at_idx ON component_deletions (deleted_at);
@@ -0,0 +1,21 @@
+CREATE TABLE component_version_aliases
+(
+    component_id uuid   NOT NULL REFERENCES components (component_id),
+    name         text   NOT NULL,
+    version      bigint NOT NULL,
+    updated_at   timestamp without time zone DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    PRIMARY KEY (component_id, name)
+);
+
+CREATE TABLE component_version_alias_changes
+(
+    component_id     uuid   NOT NULL REFERENCES components (component_id),
+    name             text   NOT NULL,
+    previous_version bigint,
+    version          bigint,
+    changed_at       timestamp without time zone DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE INDEX component_version_alias_changes_component_id_idx ON component_version_alias_changes (component_id);
+
+ALTER TABLE component_update_policy ADD COLUMN version_alias text;
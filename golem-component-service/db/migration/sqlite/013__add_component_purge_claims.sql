@@ -0,0 +1 @@
+ALTER TABLE component_deletions ADD COLUMN purge_claimed_at timestamp without time zone;
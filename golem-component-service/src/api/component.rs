@@ -13,9 +13,9 @@
 // limitations under the License.
 
 use futures_util::TryStreamExt;
-use golem_common::model::{ComponentId, ComponentType};
+use golem_common::model::{ComponentId, ComponentType, ProjectId};
 use golem_component_service_base::service::component::{
-    ComponentError as ComponentServiceError, ComponentService,
+    ComponentError as ComponentServiceError, ComponentPlugReference, ComponentService,
 };
 use golem_service_base::api_tags::ApiTags;
 use golem_service_base::auth::DefaultNamespace;
@@ -26,7 +26,9 @@ use poem_openapi::param::{Path, Query};
 use poem_openapi::payload::{Binary, Json};
 use poem_openapi::types::multipart::Upload;
 use poem_openapi::*;
+use std::collections::BTreeMap;
 use std::fmt::Debug;
+use std::str::FromStr;
 use std::sync::Arc;
 use tracing::Instrument;
 
@@ -75,12 +77,17 @@ impl From<ComponentServiceError> for ComponentError {
     fn from(error: ComponentServiceError) -> Self {
         match error {
             ComponentServiceError::UnknownComponentId(_)
-            | ComponentServiceError::UnknownVersionedComponentId(_) => {
+            | ComponentServiceError::UnknownVersionedComponentId(_)
+            | ComponentServiceError::NoInitialFiles(_)
+            | ComponentServiceError::UnknownTransformationPlugin(_)
+            | ComponentServiceError::UnknownVersionAlias { .. }
+            | ComponentServiceError::UnknownProject(_) => {
                 ComponentError::NotFound(Json(ErrorBody {
                     error: error.to_safe_string(),
                 }))
             }
-            ComponentServiceError::AlreadyExists(_) => {
+            ComponentServiceError::AlreadyExists(_)
+            | ComponentServiceError::ProjectAlreadyExists(_) => {
                 ComponentError::AlreadyExists(Json(ErrorBody {
                     error: error.to_safe_string(),
                 }))
@@ -90,6 +97,20 @@ impl From<ComponentServiceError> for ComponentError {
                     errors: vec![error.to_safe_string()],
                 }))
             }
+            ComponentServiceError::InvalidObservability(_)
+            | ComponentServiceError::InvalidUpdatePolicy(_)
+            | ComponentServiceError::InvalidLabels(_)
+            | ComponentServiceError::InvalidInitialFiles(_)
+            | ComponentServiceError::ComponentCompositionFailed(_)
+            | ComponentServiceError::InvalidTransformationPlugin(_)
+            | ComponentServiceError::InvalidProject(_)
+            | ComponentServiceError::InvalidVersionAlias(_)
+            | ComponentServiceError::ComponentNotDeleted(_)
+            | ComponentServiceError::ComponentTransformationFailed { .. } => {
+                ComponentError::BadRequest(Json(ErrorsBody {
+                    errors: vec![error.to_safe_string()],
+                }))
+            }
             ComponentServiceError::InternalRepoError(_) => {
                 ComponentError::InternalError(Json(ErrorBody {
                     error: error.to_safe_string(),
@@ -129,25 +150,55 @@ pub struct ComponentApi {
     pub component_service: Arc<dyn ComponentService<DefaultNamespace> + Sync + Send>,
 }
 
+impl ComponentApi {
+    fn parse_plugs(plugs: Option<Vec<String>>) -> Result<Vec<ComponentPlugReference>> {
+        plugs
+            .unwrap_or_default()
+            .iter()
+            .map(|plug| ComponentPlugReference::from_str(plug))
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|error| {
+                ComponentError::BadRequest(Json(ErrorsBody {
+                    errors: vec![error],
+                }))
+            })
+    }
+}
+
 #[OpenApi(prefix_path = "/v1/components", tag = ApiTags::Component)]
 impl ComponentApi {
     /// Create a new component
     ///
     /// The request body is encoded as multipart/form-data containing metadata and the WASM binary.
     /// If the component type is not specified, it will be considered as a `Durable` component.
+    ///
+    /// The `plug` parameters reference stored components to be composed into the uploaded one before storing it,
+    /// plugging their exports into the matching imports, the same way as `wac plug` does. A plug is either a
+    /// component id, using its latest version, or `<component-id>@<version>`. The plugged versions are recorded
+    /// in the metadata of the created component.
+    ///
+    /// If a project ID is given, the component is created in that project.
     #[oai(path = "/", method = "post", operation_id = "create_component")]
-    async fn create_component(&self, payload: UploadPayload) -> Result<Json<Component>> {
+    async fn create_component(
+        &self,
+        payload: UploadPayload,
+        plug: Query<Option<Vec<String>>>,
+        #[oai(name = "project-id")] project_id: Query<Option<ProjectId>>,
+    ) -> Result<Json<Component>> {
         let record =
             recorded_http_api_request!("create_component", component_name = payload.name.0);
         let response = {
+            let plugs = Self::parse_plugs(plug.0)?;
             let data = payload.component.into_vec().await?;
             let component_name = payload.name;
             self.component_service
-                .create(
+                .create_composed(
                     &ComponentId::new_v4(),
                     &component_name,
                     payload.component_type.unwrap_or(ComponentType::Durable),
                     data,
+                    &plugs,
+                    project_id.0.as_ref(),
                     &DefaultNamespace::default(),
                 )
                 .instrument(record.span.clone())
@@ -159,6 +210,9 @@ impl ComponentApi {
     }
 
     /// Update a component
+    ///
+    /// The `plug` parameters reference stored components to be composed into the uploaded version before storing it,
+    /// the same way as when creating a component.
     #[oai(
         path = "/:component_id/upload",
         method = "put",
@@ -172,18 +226,24 @@ impl ComponentApi {
         /// Type of the new version of the component - if not specified, the type of the previous version
         /// is used.
         component_type: Query<Option<ComponentType>>,
+
+        /// Components to plug into the imports of the uploaded version, as `<component-id>` or
+        /// `<component-id>@<version>`
+        plug: Query<Option<Vec<String>>>,
     ) -> Result<Json<Component>> {
         let record = recorded_http_api_request!(
             "update_component",
             component_id = component_id.0.to_string()
         );
         let response = {
+            let plugs = Self::parse_plugs(plug.0)?;
             let data = wasm.0.into_vec().await?;
             self.component_service
-                .update(
+                .update_composed(
                     &component_id.0,
                     data,
                     component_type.0,
+                    &plugs,
                     &DefaultNamespace::default(),
                 )
                 .instrument(record.span.clone())
@@ -344,24 +404,399 @@ impl ComponentApi {
 
     /// Get all components
     ///
-    /// Gets all components, optionally filtered by component name.
+    /// Gets all components, optionally filtered by component name, labels and project.
+    ///
+    /// Each `label` filter is either a `key`, matching the components having the label, or
+    /// `key=value`, matching the components having the label with the given value. Components
+    /// are returned only if they match all the label filters.
     #[oai(path = "/", method = "get", operation_id = "get_components")]
     async fn get_components(
         &self,
         #[oai(name = "component-name")] component_name: Query<Option<ComponentName>>,
+        label: Query<Option<Vec<String>>>,
+        #[oai(name = "project-id")] project_id: Query<Option<ProjectId>>,
     ) -> Result<Json<Vec<Component>>> {
         let record = recorded_http_api_request!(
             "get_components",
-            component_name = component_name.0.as_ref().map(|n| n.0.clone())
+            component_name = component_name.0.as_ref().map(|n| n.0.clone()),
+            project_id = project_id.0.as_ref().map(|id| id.to_string())
+        );
+
+        let response = {
+            let label_filters = label
+                .0
+                .unwrap_or_default()
+                .iter()
+                .map(|filter| ComponentLabelFilter::from_str(filter))
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(|error| {
+                    ComponentError::BadRequest(Json(ErrorsBody {
+                        errors: vec![error],
+                    }))
+                })?;
+
+            self.component_service
+                .find_by_name(
+                    component_name.0,
+                    &label_filters,
+                    project_id.0.as_ref(),
+                    &DefaultNamespace::default(),
+                )
+                .instrument(record.span.clone())
+                .await
+                .map_err(|e| e.into())
+                .map(|components| Json(components.into_iter().map(|c| c.into()).collect()))
+        };
+
+        record.result(response)
+    }
+
+    /// Update the labels of a component
+    ///
+    /// Replaces the labels of the latest version of the component. New versions of the component inherit the labels of the previous version.
+    #[oai(
+        path = "/:component_id/labels",
+        method = "put",
+        operation_id = "update_component_labels"
+    )]
+    async fn update_component_labels(
+        &self,
+        component_id: Path<ComponentId>,
+        labels: Json<BTreeMap<String, String>>,
+    ) -> Result<Json<Component>> {
+        let record = recorded_http_api_request!(
+            "update_component_labels",
+            component_id = component_id.0.to_string()
+        );
+
+        let response = self
+            .component_service
+            .update_labels(&component_id.0, labels.0, &DefaultNamespace::default())
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|component| Json(component.into()));
+
+        record.result(response)
+    }
+
+    /// Get the project of a component
+    #[oai(
+        path = "/:component_id/project",
+        method = "get",
+        operation_id = "get_component_project"
+    )]
+    async fn get_component_project(
+        &self,
+        component_id: Path<ComponentId>,
+    ) -> Result<Json<ProjectAssignment>> {
+        let record = recorded_http_api_request!(
+            "get_component_project",
+            component_id = component_id.0.to_string()
+        );
+
+        let response = self
+            .component_service
+            .get_component_project(&component_id.0, &DefaultNamespace::default())
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|project_id| Json(ProjectAssignment { project_id }));
+
+        record.result(response)
+    }
+
+    /// Update the project of a component
+    ///
+    /// Assigns the component to a project, or removes it from its project if the project ID is null.
+    #[oai(
+        path = "/:component_id/project",
+        method = "put",
+        operation_id = "update_component_project"
+    )]
+    async fn update_component_project(
+        &self,
+        component_id: Path<ComponentId>,
+        assignment: Json<ProjectAssignment>,
+    ) -> Result<Json<ProjectAssignment>> {
+        let record = recorded_http_api_request!(
+            "update_component_project",
+            component_id = component_id.0.to_string(),
+            project_id = assignment.0.project_id.as_ref().map(|id| id.to_string())
         );
 
         let response = self
             .component_service
-            .find_by_name(component_name.0, &DefaultNamespace::default())
+            .update_component_project(
+                &component_id.0,
+                assignment.0.project_id,
+                &DefaultNamespace::default(),
+            )
             .instrument(record.span.clone())
             .await
             .map_err(|e| e.into())
-            .map(|components| Json(components.into_iter().map(|c| c.into()).collect()));
+            .map(|project_id| Json(ProjectAssignment { project_id }));
+
+        record.result(response)
+    }
+
+    /// Delete a component
+    ///
+    /// Marks the component as deleted. Deleted components are not listed anymore, and new versions cannot be uploaded, but the existing workers of the component keep running.
+    /// The component is permanently deleted after the configured retention period, until then it can be restored.
+    /// The name of the component stays reserved until it is permanently deleted.
+    #[oai(
+        path = "/:component_id",
+        method = "delete",
+        operation_id = "delete_component"
+    )]
+    async fn delete_component(&self, component_id: Path<ComponentId>) -> Result<Json<Empty>> {
+        let record = recorded_http_api_request!(
+            "delete_component",
+            component_id = component_id.0.to_string()
+        );
+
+        let response = self
+            .component_service
+            .soft_delete(&component_id.0, &DefaultNamespace::default())
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|_| Json(Empty {}));
+
+        record.result(response)
+    }
+
+    /// Restore a deleted component
+    ///
+    /// Restores a deleted component which was not permanently deleted yet, and returns its latest version.
+    #[oai(
+        path = "/:component_id/restore",
+        method = "post",
+        operation_id = "restore_component"
+    )]
+    async fn restore_component(&self, component_id: Path<ComponentId>) -> Result<Json<Component>> {
+        let record = recorded_http_api_request!(
+            "restore_component",
+            component_id = component_id.0.to_string()
+        );
+
+        let response = self
+            .component_service
+            .restore(&component_id.0, &DefaultNamespace::default())
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|component| Json(component.into()));
+
+        record.result(response)
+    }
+
+    /// Get the version aliases of a component
+    #[oai(
+        path = "/:component_id/aliases",
+        method = "get",
+        operation_id = "get_component_version_aliases"
+    )]
+    async fn get_component_version_aliases(
+        &self,
+        component_id: Path<ComponentId>,
+    ) -> Result<Json<Vec<ComponentVersionAlias>>> {
+        let record = recorded_http_api_request!(
+            "get_component_version_aliases",
+            component_id = component_id.0.to_string()
+        );
+
+        let response = self
+            .component_service
+            .get_version_aliases(&component_id.0, &DefaultNamespace::default())
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(Json);
+
+        record.result(response)
+    }
+
+    /// Get the component version a version alias points to
+    #[oai(
+        path = "/:component_id/aliases/:alias",
+        method = "get",
+        operation_id = "get_component_by_version_alias"
+    )]
+    async fn get_component_by_version_alias(
+        &self,
+        component_id: Path<ComponentId>,
+        alias: Path<String>,
+    ) -> Result<Json<Component>> {
+        let record = recorded_http_api_request!(
+            "get_component_by_version_alias",
+            component_id = component_id.0.to_string(),
+            alias = alias.0
+        );
+
+        let response = self
+            .component_service
+            .get_by_version_alias(&component_id.0, &alias.0, &DefaultNamespace::default())
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|component| Json(component.into()));
+
+        record.result(response)
+    }
+
+    /// Move a version alias of a component
+    ///
+    /// Points the version alias to the given version of the component, creating the alias if it does not exist yet.
+    /// If the update policy of the component follows this alias, the workers created through the alias get updated to the new version.
+    #[oai(
+        path = "/:component_id/aliases/:alias",
+        method = "put",
+        operation_id = "update_component_version_alias"
+    )]
+    async fn update_component_version_alias(
+        &self,
+        component_id: Path<ComponentId>,
+        alias: Path<String>,
+        target: Json<ComponentVersionAliasTarget>,
+    ) -> Result<Json<ComponentVersionAlias>> {
+        let record = recorded_http_api_request!(
+            "update_component_version_alias",
+            component_id = component_id.0.to_string(),
+            alias = alias.0
+        );
+
+        let response = self
+            .component_service
+            .update_version_alias(
+                &component_id.0,
+                &alias.0,
+                target.0.version,
+                &DefaultNamespace::default(),
+            )
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(Json);
+
+        record.result(response)
+    }
+
+    /// Delete a version alias of a component
+    #[oai(
+        path = "/:component_id/aliases/:alias",
+        method = "delete",
+        operation_id = "delete_component_version_alias"
+    )]
+    async fn delete_component_version_alias(
+        &self,
+        component_id: Path<ComponentId>,
+        alias: Path<String>,
+    ) -> Result<Json<Empty>> {
+        let record = recorded_http_api_request!(
+            "delete_component_version_alias",
+            component_id = component_id.0.to_string(),
+            alias = alias.0
+        );
+
+        let response = self
+            .component_service
+            .delete_version_alias(&component_id.0, &alias.0, &DefaultNamespace::default())
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|_| Json(Empty {}));
+
+        record.result(response)
+    }
+
+    /// Get the history of the version aliases of a component
+    ///
+    /// Returns every move and removal of the version aliases of the component, oldest first.
+    #[oai(
+        path = "/:component_id/alias-history",
+        method = "get",
+        operation_id = "get_component_version_alias_history"
+    )]
+    async fn get_component_version_alias_history(
+        &self,
+        component_id: Path<ComponentId>,
+    ) -> Result<Json<Vec<ComponentVersionAliasChange>>> {
+        let record = recorded_http_api_request!(
+            "get_component_version_alias_history",
+            component_id = component_id.0.to_string()
+        );
+
+        let response = self
+            .component_service
+            .get_version_alias_history(&component_id.0, &DefaultNamespace::default())
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(Json);
+
+        record.result(response)
+    }
+
+    /// Get the dependency graph of a component
+    ///
+    /// Returns the components called by the latest version of the component, and the components calling it, through generated wasm-rpc stubs.
+    /// The dependencies are detected from the stub interfaces imported by the uploaded components, and are resolved by the packages exported by the latest component versions.
+    /// The dependents are the components affected by a breaking change of the component's exports.
+    #[oai(
+        path = "/:component_id/dependency-graph",
+        method = "get",
+        operation_id = "get_component_dependency_graph"
+    )]
+    async fn get_component_dependency_graph(
+        &self,
+        component_id: Path<ComponentId>,
+    ) -> Result<Json<ComponentDependencyGraph>> {
+        let record = recorded_http_api_request!(
+            "get_component_dependency_graph",
+            component_id = component_id.0.to_string()
+        );
+
+        let response = self
+            .component_service
+            .get_dependency_graph(&component_id.0, &DefaultNamespace::default())
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(Json);
+
+        record.result(response)
+    }
+
+    /// Update the initial files of a component
+    ///
+    /// Uploads a zip archive of read-only files and creates a new version of the component with the same code as the latest version and the uploaded files. The files are placed into the file system of every worker of the new version, when the worker gets created or updated to it.
+    /// New versions of the component inherit the initial files of the previous version.
+    #[oai(
+        path = "/:component_id/files",
+        method = "put",
+        operation_id = "update_component_initial_files"
+    )]
+    async fn update_component_initial_files(
+        &self,
+        component_id: Path<ComponentId>,
+        archive: Binary<Body>,
+    ) -> Result<Json<Component>> {
+        let record = recorded_http_api_request!(
+            "update_component_initial_files",
+            component_id = component_id.0.to_string()
+        );
+
+        let response = {
+            let data = archive.0.into_vec().await?;
+            self.component_service
+                .update_initial_files(&component_id.0, data, &DefaultNamespace::default())
+                .instrument(record.span.clone())
+                .await
+                .map_err(|e| e.into())
+                .map(|component| Json(component.into()))
+        };
 
         record.result(response)
     }
@@ -22,6 +22,8 @@ use std::sync::Arc;
 
 pub mod component;
 pub mod healthcheck;
+pub mod project;
+pub mod transformation_plugin;
 
 pub fn combined_routes(prometheus_registry: Arc<Registry>, services: &Services) -> Route {
     let api_service = make_open_api_service(services);
@@ -37,7 +39,12 @@ pub fn combined_routes(prometheus_registry: Arc<Registry>, services: &Services)
         .nest("/metrics", metrics)
 }
 
-type ApiServices = (component::ComponentApi, healthcheck::HealthcheckApi);
+type ApiServices = (
+    component::ComponentApi,
+    transformation_plugin::ComponentTransformationPluginApi,
+    project::ProjectApi,
+    healthcheck::HealthcheckApi,
+);
 
 pub fn make_open_api_service(services: &Services) -> OpenApiService<ApiServices, ()> {
     OpenApiService::new(
@@ -45,6 +52,12 @@ pub fn make_open_api_service(services: &Services) -> OpenApiService<ApiServices,
             component::ComponentApi {
                 component_service: services.component_service.clone(),
             },
+            transformation_plugin::ComponentTransformationPluginApi {
+                component_service: services.component_service.clone(),
+            },
+            project::ProjectApi {
+                component_service: services.component_service.clone(),
+            },
             healthcheck::HealthcheckApi,
         ),
         "Golem API",
@@ -0,0 +1,136 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::api::component::ComponentError;
+use golem_common::model::ProjectId;
+use golem_component_service_base::service::component::ComponentService;
+use golem_service_base::api_tags::ApiTags;
+use golem_service_base::auth::DefaultNamespace;
+use golem_service_base::model::*;
+use poem_openapi::param::Path;
+use poem_openapi::payload::Json;
+use poem_openapi::*;
+use std::sync::Arc;
+use tracing::Instrument;
+
+use golem_common::recorded_http_api_request;
+
+type Result<T> = std::result::Result<T, ComponentError>;
+
+pub struct ProjectApi {
+    pub component_service: Arc<dyn ComponentService<DefaultNamespace> + Sync + Send>,
+}
+
+#[OpenApi(prefix_path = "/v1/projects", tag = ApiTags::Project)]
+impl ProjectApi {
+    /// Get all projects
+    ///
+    /// Returns the projects of the account, ordered by name.
+    #[oai(path = "/", method = "get", operation_id = "get_projects")]
+    async fn get_projects(&self) -> Result<Json<Vec<Project>>> {
+        let record = recorded_http_api_request!("get_projects",);
+
+        let response = self
+            .component_service
+            .get_projects(&DefaultNamespace::default())
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(Json);
+
+        record.result(response)
+    }
+
+    /// Create a project
+    ///
+    /// Creates a project for grouping components, API definitions and API deployments. The name of the project must be unique.
+    #[oai(path = "/", method = "post", operation_id = "create_project")]
+    async fn create_project(&self, data: Json<ProjectData>) -> Result<Json<Project>> {
+        let record =
+            recorded_http_api_request!("create_project", project_name = data.0.name.clone());
+
+        let response = self
+            .component_service
+            .create_project(data.0, &DefaultNamespace::default())
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(Json);
+
+        record.result(response)
+    }
+
+    /// Get a project
+    #[oai(path = "/:project_id", method = "get", operation_id = "get_project")]
+    async fn get_project(&self, project_id: Path<ProjectId>) -> Result<Json<Project>> {
+        let record =
+            recorded_http_api_request!("get_project", project_id = project_id.0.to_string());
+
+        let response = self
+            .component_service
+            .get_project(&project_id.0, &DefaultNamespace::default())
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(Json);
+
+        record.result(response)
+    }
+
+    /// Update a project
+    ///
+    /// Changes the name and the description of the project.
+    #[oai(path = "/:project_id", method = "put", operation_id = "update_project")]
+    async fn update_project(
+        &self,
+        project_id: Path<ProjectId>,
+        data: Json<ProjectData>,
+    ) -> Result<Json<Project>> {
+        let record =
+            recorded_http_api_request!("update_project", project_id = project_id.0.to_string());
+
+        let response = self
+            .component_service
+            .update_project(&project_id.0, data.0, &DefaultNamespace::default())
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(Json);
+
+        record.result(response)
+    }
+
+    /// Delete a project
+    ///
+    /// The components of the project are kept, without being assigned to any project.
+    #[oai(
+        path = "/:project_id",
+        method = "delete",
+        operation_id = "delete_project"
+    )]
+    async fn delete_project(&self, project_id: Path<ProjectId>) -> Result<Json<Empty>> {
+        let record =
+            recorded_http_api_request!("delete_project", project_id = project_id.0.to_string());
+
+        let response = self
+            .component_service
+            .delete_project(&project_id.0, &DefaultNamespace::default())
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|_| Json(Empty {}));
+
+        record.result(response)
+    }
+}
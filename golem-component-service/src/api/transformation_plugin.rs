@@ -0,0 +1,118 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::api::component::ComponentError;
+use golem_component_service_base::service::component::ComponentService;
+use golem_service_base::api_tags::ApiTags;
+use golem_service_base::auth::DefaultNamespace;
+use golem_service_base::model::*;
+use poem_openapi::param::Path;
+use poem_openapi::payload::Json;
+use poem_openapi::*;
+use std::sync::Arc;
+use tracing::Instrument;
+
+use golem_common::recorded_http_api_request;
+
+type Result<T> = std::result::Result<T, ComponentError>;
+
+pub struct ComponentTransformationPluginApi {
+    pub component_service: Arc<dyn ComponentService<DefaultNamespace> + Sync + Send>,
+}
+
+#[OpenApi(
+    prefix_path = "/v1/component-transformation-plugins",
+    tag = ApiTags::Component
+)]
+impl ComponentTransformationPluginApi {
+    /// Get the component transformation plugins
+    ///
+    /// Returns the registered transformation plugins in the order they are applied to the uploaded components.
+    #[oai(
+        path = "/",
+        method = "get",
+        operation_id = "get_component_transformation_plugins"
+    )]
+    async fn get_component_transformation_plugins(
+        &self,
+    ) -> Result<Json<Vec<ComponentTransformationPlugin>>> {
+        let record = recorded_http_api_request!("get_component_transformation_plugins",);
+
+        let response = self
+            .component_service
+            .get_transformation_plugins(&DefaultNamespace::default())
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(Json);
+
+        record.result(response)
+    }
+
+    /// Register a component transformation plugin
+    ///
+    /// Registers a transformation applied to the WASM of every new component and component version before storing it,
+    /// replacing the plugin with the same name. The plugins are applied in ascending order of their priority, each of
+    /// them transforming the output of the previous one. If any of them fails, the upload is rejected.
+    #[oai(
+        path = "/",
+        method = "put",
+        operation_id = "register_component_transformation_plugin"
+    )]
+    async fn register_component_transformation_plugin(
+        &self,
+        plugin: Json<ComponentTransformationPlugin>,
+    ) -> Result<Json<ComponentTransformationPlugin>> {
+        let record = recorded_http_api_request!(
+            "register_component_transformation_plugin",
+            plugin_name = plugin.0.name.clone()
+        );
+
+        let response = self
+            .component_service
+            .register_transformation_plugin(plugin.0, &DefaultNamespace::default())
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(Json);
+
+        record.result(response)
+    }
+
+    /// Unregister a component transformation plugin
+    #[oai(
+        path = "/:plugin_name",
+        method = "delete",
+        operation_id = "unregister_component_transformation_plugin"
+    )]
+    async fn unregister_component_transformation_plugin(
+        &self,
+        plugin_name: Path<String>,
+    ) -> Result<Json<Empty>> {
+        let record = recorded_http_api_request!(
+            "unregister_component_transformation_plugin",
+            plugin_name = plugin_name.0.clone()
+        );
+
+        let response = self
+            .component_service
+            .unregister_transformation_plugin(&plugin_name.0, &DefaultNamespace::default())
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|_| Json(Empty {}));
+
+        record.result(response)
+    }
+}
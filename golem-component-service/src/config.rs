@@ -14,12 +14,16 @@
 
 use serde::{Deserialize, Serialize};
 use std::path::Path;
+use std::time::Duration;
 
 use golem_common::config::{
     ConfigExample, ConfigLoader, DbConfig, DbSqliteConfig, HasConfigExamples,
 };
 use golem_common::tracing::TracingConfig;
-use golem_component_service_base::config::ComponentCompilationConfig;
+use golem_component_service_base::config::{
+    ComponentCompilationConfig, ComponentNameUniqueness, TransformationWebhookConfig,
+    WorkerUpdateRolloutConfig,
+};
 use golem_service_base::config::{
     ComponentStoreConfig, ComponentStoreLocalConfig, ComponentStoreS3Config,
 };
@@ -33,6 +37,29 @@ pub struct ComponentServiceConfig {
     pub db: DbConfig,
     pub component_store: ComponentStoreConfig,
     pub compilation: ComponentCompilationConfig,
+    pub worker_update_rollout: WorkerUpdateRolloutConfig,
+    pub component_deletion: ComponentDeletionConfig,
+    pub component_name_uniqueness: ComponentNameUniqueness,
+    pub transformation_webhook: TransformationWebhookConfig,
+}
+
+/// Deleted components can be restored during the retention period, and are permanently deleted
+/// by a background task checking for expired deletions at every purge interval
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ComponentDeletionConfig {
+    #[serde(with = "humantime_serde")]
+    pub retention: Duration,
+    #[serde(with = "humantime_serde")]
+    pub purge_interval: Duration,
+}
+
+impl Default for ComponentDeletionConfig {
+    fn default() -> Self {
+        Self {
+            retention: Duration::from_secs(30 * 24 * 60 * 60),
+            purge_interval: Duration::from_secs(60 * 60),
+        }
+    }
 }
 
 impl Default for ComponentServiceConfig {
@@ -50,6 +77,10 @@ impl Default for ComponentServiceConfig {
                 object_prefix: "".to_string(),
             }),
             compilation: ComponentCompilationConfig::default(),
+            worker_update_rollout: WorkerUpdateRolloutConfig::default(),
+            component_deletion: ComponentDeletionConfig::default(),
+            component_name_uniqueness: ComponentNameUniqueness::default(),
+            transformation_webhook: TransformationWebhookConfig::default(),
         }
     }
 }
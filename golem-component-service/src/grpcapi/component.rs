@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::str::FromStr;
 use std::sync::Arc;
 use tracing::Instrument;
 
@@ -23,18 +24,26 @@ use golem_api_grpc::proto::golem::component::v1::component_service_server::Compo
 use golem_api_grpc::proto::golem::component::v1::{
     component_error, create_component_request, create_component_response,
     download_component_response, get_component_metadata_all_versions_response,
-    get_component_metadata_response, get_components_response, update_component_request,
-    update_component_response, ComponentError, CreateComponentRequest,
+    get_component_metadata_response, get_component_observability_response,
+    get_component_update_policy_response, get_components_response, get_project_response,
+    update_component_observability_response, update_component_request, update_component_response,
+    update_component_update_policy_response, ComponentError, CreateComponentRequest,
     CreateComponentRequestHeader, CreateComponentResponse, DownloadComponentRequest,
-    DownloadComponentResponse, GetComponentMetadataAllVersionsResponse,
-    GetComponentMetadataResponse, GetComponentMetadataSuccessResponse, GetComponentRequest,
-    GetComponentSuccessResponse, GetComponentsRequest, GetComponentsResponse,
-    GetComponentsSuccessResponse, GetLatestComponentRequest, GetVersionedComponentRequest,
-    UpdateComponentRequest, UpdateComponentRequestHeader, UpdateComponentResponse,
+    DownloadComponentResponse, GetComponentByAliasRequest, GetComponentMetadataAllVersionsResponse,
+    GetComponentMetadataResponse, GetComponentMetadataSuccessResponse,
+    GetComponentObservabilityResponse, GetComponentRequest, GetComponentSuccessResponse,
+    GetComponentUpdatePolicyResponse, GetComponentsRequest, GetComponentsResponse,
+    GetComponentsSuccessResponse, GetLatestComponentRequest, GetProjectRequest, GetProjectResponse,
+    GetVersionedComponentRequest, UpdateComponentObservabilityRequest,
+    UpdateComponentObservabilityResponse, UpdateComponentRequest, UpdateComponentRequestHeader,
+    UpdateComponentResponse, UpdateComponentUpdatePolicyRequest,
+    UpdateComponentUpdatePolicyResponse,
 };
-use golem_api_grpc::proto::golem::component::Component;
-use golem_common::grpc::proto_component_id_string;
-use golem_common::model::{ComponentId, ComponentType};
+use golem_api_grpc::proto::golem::component::{
+    Component, ComponentObservability, ComponentUpdatePolicy, Project,
+};
+use golem_common::grpc::{proto_component_id_string, proto_project_id_string};
+use golem_common::model::{ComponentId, ComponentType, ProjectId};
 use golem_common::recorded_grpc_api_request;
 use golem_component_service_base::api::common::ComponentTraceErrorKind;
 use golem_component_service_base::service::component;
@@ -78,7 +87,7 @@ impl ComponentGrpcApi {
     async fn get_component_metadata(
         &self,
         request: GetVersionedComponentRequest,
-    ) -> Result<Option<Component>, ComponentError> {
+    ) -> Result<GetComponentMetadataSuccessResponse, ComponentError> {
         let id: ComponentId = request
             .component_id
             .and_then(|id| id.try_into().ok())
@@ -95,7 +104,25 @@ impl ComponentGrpcApi {
             .component_service
             .get_by_version(&versioned_component_id, &DefaultNamespace::default())
             .await?;
-        Ok(result.map(|p| p.into()))
+        match result {
+            Some(component) => {
+                let observability = self
+                    .component_service
+                    .get_observability(
+                        &versioned_component_id.component_id,
+                        &DefaultNamespace::default(),
+                    )
+                    .await?;
+                Ok(GetComponentMetadataSuccessResponse {
+                    component: Some(component.into()),
+                    observability: Some(observability.into()),
+                })
+            }
+            None => Ok(GetComponentMetadataSuccessResponse {
+                component: None,
+                observability: None,
+            }),
+        }
     }
 
     async fn get_all(
@@ -105,9 +132,25 @@ impl ComponentGrpcApi {
         let name: Option<golem_service_base::model::ComponentName> = request
             .component_name
             .map(golem_service_base::model::ComponentName);
+        let label_filters = request
+            .labels
+            .iter()
+            .map(|filter| golem_service_base::model::ComponentLabelFilter::from_str(filter))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|error| bad_request_error(&error))?;
+        let project_id: Option<ProjectId> = request
+            .project_id
+            .map(|id| id.try_into())
+            .transpose()
+            .map_err(|_| bad_request_error("Invalid project id"))?;
         let result = self
             .component_service
-            .find_by_name(name, &DefaultNamespace::default())
+            .find_by_name(
+                name,
+                &label_filters,
+                project_id.as_ref(),
+                &DefaultNamespace::default(),
+            )
             .await?;
         Ok(result.into_iter().map(|p| p.into()).collect())
     }
@@ -115,7 +158,7 @@ impl ComponentGrpcApi {
     async fn get_latest_component_metadata(
         &self,
         request: GetLatestComponentRequest,
-    ) -> Result<Component, ComponentError> {
+    ) -> Result<GetComponentMetadataSuccessResponse, ComponentError> {
         let id: ComponentId = request
             .component_id
             .and_then(|id| id.try_into().ok())
@@ -125,7 +168,16 @@ impl ComponentGrpcApi {
             .get_latest_version(&id, &DefaultNamespace::default())
             .await?;
         match result {
-            Some(component) => Ok(component.into()),
+            Some(component) => {
+                let observability = self
+                    .component_service
+                    .get_observability(&id, &DefaultNamespace::default())
+                    .await?;
+                Ok(GetComponentMetadataSuccessResponse {
+                    component: Some(component.into()),
+                    observability: Some(observability.into()),
+                })
+            }
             None => Err(ComponentError {
                 error: Some(component_error::Error::NotFound(ErrorBody {
                     error: "Component not found".to_string(),
@@ -150,12 +202,33 @@ impl ComponentGrpcApi {
         Ok(result)
     }
 
+    async fn download_files(
+        &self,
+        request: DownloadComponentRequest,
+    ) -> Result<ByteStream, ComponentError> {
+        let id: ComponentId = request
+            .component_id
+            .and_then(|id| id.try_into().ok())
+            .ok_or_else(|| bad_request_error("Missing component id"))?;
+        let version = request.version;
+        let result = self
+            .component_service
+            .download_initial_files_stream(&id, version, &DefaultNamespace::default())
+            .await?;
+        Ok(result)
+    }
+
     async fn create(
         &self,
         request: CreateComponentRequestHeader,
         data: Vec<u8>,
     ) -> Result<Component, ComponentError> {
         let name = golem_service_base::model::ComponentName(request.component_name.clone());
+        let project_id: Option<ProjectId> = request
+            .project_id
+            .map(|id| id.try_into())
+            .transpose()
+            .map_err(|_| bad_request_error("Invalid project id"))?;
         let result = self
             .component_service
             .create(
@@ -163,6 +236,7 @@ impl ComponentGrpcApi {
                 &name,
                 request.component_type().into(),
                 data,
+                project_id.as_ref(),
                 &DefaultNamespace::default(),
             )
             .await?;
@@ -191,6 +265,108 @@ impl ComponentGrpcApi {
             .await?;
         Ok(result.into())
     }
+
+    async fn get_observability(
+        &self,
+        request: GetComponentRequest,
+    ) -> Result<ComponentObservability, ComponentError> {
+        let id: ComponentId = request
+            .component_id
+            .and_then(|id| id.try_into().ok())
+            .ok_or_else(|| bad_request_error("Missing component id"))?;
+        let result = self
+            .component_service
+            .get_observability(&id, &DefaultNamespace::default())
+            .await?;
+        Ok(result.into())
+    }
+
+    async fn update_observability(
+        &self,
+        request: UpdateComponentObservabilityRequest,
+    ) -> Result<ComponentObservability, ComponentError> {
+        let id: ComponentId = request
+            .component_id
+            .and_then(|id| id.try_into().ok())
+            .ok_or_else(|| bad_request_error("Missing component id"))?;
+        let observability = request
+            .observability
+            .ok_or_else(|| bad_request_error("Missing observability"))?;
+        let result = self
+            .component_service
+            .update_observability(&id, observability.into(), &DefaultNamespace::default())
+            .await?;
+        Ok(result.into())
+    }
+
+    async fn get_update_policy(
+        &self,
+        request: GetComponentRequest,
+    ) -> Result<ComponentUpdatePolicy, ComponentError> {
+        let id: ComponentId = request
+            .component_id
+            .and_then(|id| id.try_into().ok())
+            .ok_or_else(|| bad_request_error("Missing component id"))?;
+        let result = self
+            .component_service
+            .get_update_policy(&id, &DefaultNamespace::default())
+            .await?;
+        Ok(result.into())
+    }
+
+    async fn get_component_metadata_by_alias(
+        &self,
+        request: GetComponentByAliasRequest,
+    ) -> Result<GetComponentMetadataSuccessResponse, ComponentError> {
+        let id: ComponentId = request
+            .component_id
+            .and_then(|id| id.try_into().ok())
+            .ok_or_else(|| bad_request_error("Missing component id"))?;
+        let component = self
+            .component_service
+            .get_by_version_alias(&id, &request.version_alias, &DefaultNamespace::default())
+            .await?;
+        let observability = self
+            .component_service
+            .get_observability(&id, &DefaultNamespace::default())
+            .await?;
+        Ok(GetComponentMetadataSuccessResponse {
+            component: Some(component.into()),
+            observability: Some(observability.into()),
+        })
+    }
+
+    async fn get_project(&self, request: GetProjectRequest) -> Result<Project, ComponentError> {
+        let id: ProjectId = request
+            .project_id
+            .and_then(|id| id.try_into().ok())
+            .ok_or_else(|| bad_request_error("Missing project id"))?;
+        let result = self
+            .component_service
+            .get_project(&id, &DefaultNamespace::default())
+            .await?;
+        Ok(result.into())
+    }
+
+    async fn update_update_policy(
+        &self,
+        request: UpdateComponentUpdatePolicyRequest,
+    ) -> Result<ComponentUpdatePolicy, ComponentError> {
+        let id: ComponentId = request
+            .component_id
+            .and_then(|id| id.try_into().ok())
+            .ok_or_else(|| bad_request_error("Missing component id"))?;
+        let policy = request
+            .policy
+            .ok_or_else(|| bad_request_error("Missing update policy"))?
+            .try_into()
+            .map_err(|e: String| bad_request_error(&e))?;
+        let result = self
+            .component_service
+            .update_update_policy(&id, policy, &DefaultNamespace::default())
+            .await?;
+        Ok(result.into())
+    }
 }
 
 #[async_trait::async_trait]
@@ -315,6 +491,56 @@ impl ComponentService for ComponentGrpcApi {
         Ok(Response::new(stream))
     }
 
+    type DownloadComponentFilesStream =
+        BoxStream<'static, Result<DownloadComponentResponse, Status>>;
+
+    async fn download_component_files(
+        &self,
+        request: Request<DownloadComponentRequest>,
+    ) -> Result<Response<Self::DownloadComponentFilesStream>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "download_component_files",
+            component_id = proto_component_id_string(&request.component_id)
+        );
+        let stream: Self::DownloadComponentFilesStream = match self
+            .download_files(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(response) => {
+                let stream = response.map(|content| {
+                    let res = match content {
+                        Ok(content) => DownloadComponentResponse {
+                            result: Some(download_component_response::Result::SuccessChunk(
+                                content,
+                            )),
+                        },
+                        Err(_) => DownloadComponentResponse {
+                            result: Some(download_component_response::Result::Error(
+                                internal_error("Internal error"),
+                            )),
+                        },
+                    };
+                    Ok(res)
+                });
+                let stream: Self::DownloadComponentFilesStream = Box::pin(stream);
+                record.succeed(stream)
+            }
+            Err(err) => {
+                let res = DownloadComponentResponse {
+                    result: Some(download_component_response::Result::Error(err.clone())),
+                };
+
+                let stream: Self::DownloadComponentFilesStream =
+                    Box::pin(tokio_stream::iter([Ok(res)]));
+                record.fail(stream, &ComponentTraceErrorKind(&err))
+            }
+        };
+
+        Ok(Response::new(stream))
+    }
+
     async fn get_component_metadata_all_versions(
         &self,
         request: Request<GetComponentRequest>,
@@ -357,11 +583,9 @@ impl ComponentService for ComponentGrpcApi {
             .instrument(record.span.clone())
             .await
         {
-            Ok(component) => record.succeed(get_component_metadata_response::Result::Success(
-                GetComponentMetadataSuccessResponse {
-                    component: Some(component),
-                },
-            )),
+            Ok(response) => {
+                record.succeed(get_component_metadata_response::Result::Success(response))
+            }
             Err(error) => record.fail(
                 get_component_metadata_response::Result::Error(error.clone()),
                 &ComponentTraceErrorKind(&error),
@@ -442,9 +666,182 @@ impl ComponentService for ComponentGrpcApi {
             .instrument(record.span.clone())
             .await
         {
-            Ok(component) => record.succeed(get_component_metadata_response::Result::Success(
-                GetComponentMetadataSuccessResponse { component },
+            Ok(response) => {
+                record.succeed(get_component_metadata_response::Result::Success(response))
+            }
+            Err(error) => record.fail(
+                get_component_metadata_response::Result::Error(error.clone()),
+                &ComponentTraceErrorKind(&error),
+            ),
+        };
+
+        Ok(Response::new(GetComponentMetadataResponse {
+            result: Some(response),
+        }))
+    }
+
+    async fn get_component_observability(
+        &self,
+        request: Request<GetComponentRequest>,
+    ) -> Result<Response<GetComponentObservabilityResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "get_component_observability",
+            component_id = proto_component_id_string(&request.component_id)
+        );
+
+        let response = match self
+            .get_observability(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(observability) => record.succeed(
+                get_component_observability_response::Result::Success(observability),
+            ),
+            Err(error) => record.fail(
+                get_component_observability_response::Result::Error(error.clone()),
+                &ComponentTraceErrorKind(&error),
+            ),
+        };
+
+        Ok(Response::new(GetComponentObservabilityResponse {
+            result: Some(response),
+        }))
+    }
+
+    async fn update_component_observability(
+        &self,
+        request: Request<UpdateComponentObservabilityRequest>,
+    ) -> Result<Response<UpdateComponentObservabilityResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "update_component_observability",
+            component_id = proto_component_id_string(&request.component_id)
+        );
+
+        let response = match self
+            .update_observability(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(observability) => record.succeed(
+                update_component_observability_response::Result::Success(observability),
+            ),
+            Err(error) => record.fail(
+                update_component_observability_response::Result::Error(error.clone()),
+                &ComponentTraceErrorKind(&error),
+            ),
+        };
+
+        Ok(Response::new(UpdateComponentObservabilityResponse {
+            result: Some(response),
+        }))
+    }
+
+    async fn get_component_update_policy(
+        &self,
+        request: Request<GetComponentRequest>,
+    ) -> Result<Response<GetComponentUpdatePolicyResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "get_component_update_policy",
+            component_id = proto_component_id_string(&request.component_id)
+        );
+
+        let response = match self
+            .get_update_policy(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(policy) => record.succeed(get_component_update_policy_response::Result::Success(
+                policy,
             )),
+            Err(error) => record.fail(
+                get_component_update_policy_response::Result::Error(error.clone()),
+                &ComponentTraceErrorKind(&error),
+            ),
+        };
+
+        Ok(Response::new(GetComponentUpdatePolicyResponse {
+            result: Some(response),
+        }))
+    }
+
+    async fn update_component_update_policy(
+        &self,
+        request: Request<UpdateComponentUpdatePolicyRequest>,
+    ) -> Result<Response<UpdateComponentUpdatePolicyResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "update_component_update_policy",
+            component_id = proto_component_id_string(&request.component_id)
+        );
+
+        let response = match self
+            .update_update_policy(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(policy) => record.succeed(update_component_update_policy_response::Result::Success(
+                policy,
+            )),
+            Err(error) => record.fail(
+                update_component_update_policy_response::Result::Error(error.clone()),
+                &ComponentTraceErrorKind(&error),
+            ),
+        };
+
+        Ok(Response::new(UpdateComponentUpdatePolicyResponse {
+            result: Some(response),
+        }))
+    }
+
+    async fn get_project(
+        &self,
+        request: Request<GetProjectRequest>,
+    ) -> Result<Response<GetProjectResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "get_project",
+            project_id = proto_project_id_string(&request.project_id)
+        );
+
+        let response = match self
+            .get_project(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(project) => record.succeed(get_project_response::Result::Success(project)),
+            Err(error) => record.fail(
+                get_project_response::Result::Error(error.clone()),
+                &ComponentTraceErrorKind(&error),
+            ),
+        };
+
+        Ok(Response::new(GetProjectResponse {
+            result: Some(response),
+        }))
+    }
+
+    async fn get_component_metadata_by_alias(
+        &self,
+        request: Request<GetComponentByAliasRequest>,
+    ) -> Result<Response<GetComponentMetadataResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "get_component_metadata_by_alias",
+            component_id = proto_component_id_string(&request.component_id),
+            alias = request.version_alias
+        );
+
+        let response = match self
+            .get_component_metadata_by_alias(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(response) => {
+                record.succeed(get_component_metadata_response::Result::Success(response))
+            }
             Err(error) => record.fail(
                 get_component_metadata_response::Result::Error(error.clone()),
                 &ComponentTraceErrorKind(&error),
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use chrono::{DateTime, Utc};
 use golem_common::config::DbConfig;
 use golem_common::tracing::init_tracing_with_default_env_filter;
 use golem_component_service::api::make_open_api_service;
@@ -26,6 +27,7 @@ use poem::EndpointExt;
 use prometheus::Registry;
 use std::net::{Ipv4Addr, SocketAddrV4};
 use std::sync::Arc;
+use std::time::SystemTime;
 use tokio::select;
 use tracing::{error, info};
 
@@ -109,6 +111,8 @@ async fn async_main(
 
     let http_services = services.clone();
     let grpc_services = services.clone();
+    let purge_services = services.clone();
+    let component_deletion = config.component_deletion.clone();
 
     let http_server = tokio::spawn(async move {
         let prometheus_registry = Arc::new(prometheus_registry);
@@ -131,9 +135,32 @@ async fn async_main(
         .expect("gRPC server failed");
     });
 
+    let purge_task = tokio::spawn(async move {
+        let mut interval = tokio::time::interval(component_deletion.purge_interval);
+        loop {
+            interval.tick().await;
+            let deleted_before: DateTime<Utc> =
+                (SystemTime::now() - component_deletion.retention).into();
+            // Every replica runs the purge, a purge claimed by another replica is retried
+            // only if it was not finished within a purge interval
+            let claimed_before: DateTime<Utc> =
+                (SystemTime::now() - component_deletion.purge_interval).into();
+            match purge_services
+                .component_service
+                .purge_deleted(deleted_before, claimed_before)
+                .await
+            {
+                Ok(0) => {}
+                Ok(count) => info!("Purged {} deleted components", count),
+                Err(e) => error!("Failed to purge deleted components: {}", e),
+            }
+        }
+    });
+
     select! {
         _ = http_server => {},
         _ = grpc_server => {},
+        _ = purge_task => {},
     }
 
     Ok(())
@@ -13,11 +13,17 @@
 // limitations under the License.
 
 use golem_common::config::DbConfig;
-use golem_component_service_base::config::ComponentCompilationConfig;
+use golem_component_service_base::config::{
+    ComponentCompilationConfig, ComponentNameUniqueness, WorkerUpdateRolloutConfig,
+};
 use golem_component_service_base::service::component_compilation::{
     ComponentCompilationService, ComponentCompilationServiceDefault,
     ComponentCompilationServiceDisabled,
 };
+use golem_component_service_base::service::worker_update_rollout::{
+    WorkerUpdateRolloutService, WorkerUpdateRolloutServiceDefault,
+    WorkerUpdateRolloutServiceDisabled,
+};
 use golem_service_base::config::ComponentStoreConfig;
 use golem_service_base::db;
 use golem_service_base::service::component_object_store;
@@ -27,6 +33,7 @@ use crate::config::ComponentServiceConfig;
 use golem_component_service_base::repo::component::{
     ComponentRepo, DbComponentRepo, LoggedComponentRepo,
 };
+use golem_component_service_base::repo::project::{DbProjectRepo, LoggedProjectRepo, ProjectRepo};
 use golem_component_service_base::service::component::{ComponentService, ComponentServiceDefault};
 use golem_service_base::auth::DefaultNamespace;
 
@@ -38,25 +45,42 @@ pub struct Services {
 
 impl Services {
     pub async fn new(config: &ComponentServiceConfig) -> Result<Services, String> {
-        let component_repo: Arc<dyn ComponentRepo + Sync + Send> = match config.db.clone() {
+        let (component_repo, project_repo) = match config.db.clone() {
             DbConfig::Postgres(c) => {
                 let db_pool = db::create_postgres_pool(&c)
                     .await
                     .map_err(|e| e.to_string())?;
-                Arc::new(LoggedComponentRepo::new(DbComponentRepo::new(
-                    db_pool.clone().into(),
-                )))
+                let component_repo: Arc<dyn ComponentRepo + Sync + Send> = Arc::new(
+                    LoggedComponentRepo::new(DbComponentRepo::new(db_pool.clone().into())),
+                );
+                let project_repo: Arc<dyn ProjectRepo + Sync + Send> = Arc::new(
+                    LoggedProjectRepo::new(DbProjectRepo::new(db_pool.clone().into())),
+                );
+                (component_repo, project_repo)
             }
             DbConfig::Sqlite(c) => {
                 let db_pool = db::create_sqlite_pool(&c)
                     .await
                     .map_err(|e| e.to_string())?;
-                Arc::new(LoggedComponentRepo::new(DbComponentRepo::new(
-                    db_pool.clone().into(),
-                )))
+                let component_repo: Arc<dyn ComponentRepo + Sync + Send> = Arc::new(
+                    LoggedComponentRepo::new(DbComponentRepo::new(db_pool.clone().into())),
+                );
+                let project_repo: Arc<dyn ProjectRepo + Sync + Send> = Arc::new(
+                    LoggedProjectRepo::new(DbProjectRepo::new(db_pool.clone().into())),
+                );
+                (component_repo, project_repo)
             }
         };
 
+        // The names of the components are moved to the scopes of the configured uniqueness,
+        // which fails if they are not unique in the whole namespace when switching back to it
+        component_repo
+            .update_name_scopes(
+                config.component_name_uniqueness == ComponentNameUniqueness::Project,
+            )
+            .await
+            .map_err(|e| format!("Failed to update the scopes of component names: {e}"))?;
+
         let object_store: Arc<dyn component_object_store::ComponentObjectStore + Sync + Send> =
             match config.component_store.clone() {
                 ComponentStoreConfig::S3(c) => {
@@ -77,11 +101,25 @@ impl Services {
                 }
             };
 
+        let worker_update_rollout_service: Arc<dyn WorkerUpdateRolloutService + Sync + Send> =
+            match config.worker_update_rollout.clone() {
+                WorkerUpdateRolloutConfig::Enabled(config) => {
+                    Arc::new(WorkerUpdateRolloutServiceDefault::new(config.uri()))
+                }
+                WorkerUpdateRolloutConfig::Disabled(_) => {
+                    Arc::new(WorkerUpdateRolloutServiceDisabled)
+                }
+            };
+
         let component_service: Arc<dyn ComponentService<DefaultNamespace> + Sync + Send> =
             Arc::new(ComponentServiceDefault::new(
                 component_repo.clone(),
+                project_repo.clone(),
                 object_store.clone(),
                 compilation_service.clone(),
+                worker_update_rollout_service,
+                config.component_name_uniqueness,
+                config.transformation_webhook.clone(),
             ));
 
         Ok(Services {
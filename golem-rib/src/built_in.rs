@@ -0,0 +1,351 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bincode::{Decode, Encode};
+use golem_wasm_ast::analysis::analysed_type::{str, u64};
+use golem_wasm_ast::analysis::AnalysedType;
+use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
+use std::fmt::Display;
+
+// Functions available in every Rib script, evaluated by the interpreter itself
+// rather than invoking a worker.
+// Timestamps are u64 milliseconds since the Unix epoch, the same representation
+// as `Timestamp::to_millis` in golem-common, and they are parsed and formatted as ISO 8601
+// strings the way a `Timestamp` is. Durations are u64 milliseconds, so timestamps and
+// durations can be compared using the usual comparison operators.
+// A function exported by the component with the same name takes precedence over a built-in function.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Encode, Decode)]
+pub enum BuiltInFunction {
+    // now() -> u64
+    Now,
+    // parse-timestamp(string) -> u64
+    ParseTimestamp,
+    // format-timestamp(u64) -> string
+    FormatTimestamp,
+    // add-duration(timestamp, duration) -> u64
+    AddDuration,
+    // subtract-duration(timestamp, duration) -> u64
+    SubtractDuration,
+    // duration-between(from, to) -> u64, which is 0 if `to` is before `from`
+    DurationBetween,
+    // seconds(u64) -> u64, and the same for the other units, giving a duration in milliseconds
+    Seconds,
+    Minutes,
+    Hours,
+    Days,
+    // use-worker(string) -> string, returning its argument. The worker function calls following
+    // it target the worker with the given name instead of the one the script is evaluated against.
+    // The worker has to belong to the same component.
+    UseWorker,
+}
+
+impl BuiltInFunction {
+    pub fn all() -> Vec<BuiltInFunction> {
+        vec![
+            BuiltInFunction::Now,
+            BuiltInFunction::ParseTimestamp,
+            BuiltInFunction::FormatTimestamp,
+            BuiltInFunction::AddDuration,
+            BuiltInFunction::SubtractDuration,
+            BuiltInFunction::DurationBetween,
+            BuiltInFunction::Seconds,
+            BuiltInFunction::Minutes,
+            BuiltInFunction::Hours,
+            BuiltInFunction::Days,
+            BuiltInFunction::UseWorker,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BuiltInFunction::Now => "now",
+            BuiltInFunction::ParseTimestamp => "parse-timestamp",
+            BuiltInFunction::FormatTimestamp => "format-timestamp",
+            BuiltInFunction::AddDuration => "add-duration",
+            BuiltInFunction::SubtractDuration => "subtract-duration",
+            BuiltInFunction::DurationBetween => "duration-between",
+            BuiltInFunction::Seconds => "seconds",
+            BuiltInFunction::Minutes => "minutes",
+            BuiltInFunction::Hours => "hours",
+            BuiltInFunction::Days => "days",
+            BuiltInFunction::UseWorker => "use-worker",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<BuiltInFunction> {
+        BuiltInFunction::all()
+            .into_iter()
+            .find(|function| function.name() == name)
+    }
+
+    pub fn parameter_types(&self) -> Vec<AnalysedType> {
+        match self {
+            BuiltInFunction::Now => vec![],
+            BuiltInFunction::ParseTimestamp => vec![str()],
+            BuiltInFunction::FormatTimestamp => vec![u64()],
+            BuiltInFunction::AddDuration
+            | BuiltInFunction::SubtractDuration
+            | BuiltInFunction::DurationBetween => vec![u64(), u64()],
+            BuiltInFunction::Seconds
+            | BuiltInFunction::Minutes
+            | BuiltInFunction::Hours
+            | BuiltInFunction::Days => vec![u64()],
+            BuiltInFunction::UseWorker => vec![str()],
+        }
+    }
+
+    pub fn return_type(&self) -> AnalysedType {
+        match self {
+            BuiltInFunction::FormatTimestamp | BuiltInFunction::UseWorker => str(),
+            _ => u64(),
+        }
+    }
+
+    pub fn call(&self, arguments: Vec<TypeAnnotatedValue>) -> Result<TypeAnnotatedValue, String> {
+        let expected = self.parameter_types().len();
+        if arguments.len() != expected {
+            return Err(format!(
+                "Incorrect number of arguments for built-in function `{}`. Expected {}, but provided {}",
+                self.name(),
+                expected,
+                arguments.len()
+            ));
+        }
+
+        match self {
+            BuiltInFunction::Now => internal::to_millis(iso8601_timestamp::Timestamp::now_utc())
+                .map(TypeAnnotatedValue::U64),
+            BuiltInFunction::ParseTimestamp => {
+                let value = internal::get_str(self, &arguments[0])?;
+                let timestamp = iso8601_timestamp::Timestamp::parse(value.trim())
+                    .ok_or(format!("Invalid ISO 8601 timestamp: {}", value))?;
+                internal::to_millis(timestamp).map(TypeAnnotatedValue::U64)
+            }
+            BuiltInFunction::FormatTimestamp => {
+                let millis = internal::get_u64(self, &arguments[0])?;
+                Ok(TypeAnnotatedValue::Str(
+                    internal::from_millis(millis)?.to_string(),
+                ))
+            }
+            BuiltInFunction::AddDuration => {
+                let timestamp = internal::get_u64(self, &arguments[0])?;
+                let duration = internal::get_u64(self, &arguments[1])?;
+                timestamp
+                    .checked_add(duration)
+                    .map(TypeAnnotatedValue::U64)
+                    .ok_or("Timestamp overflow when adding the duration".to_string())
+            }
+            BuiltInFunction::SubtractDuration => {
+                let timestamp = internal::get_u64(self, &arguments[0])?;
+                let duration = internal::get_u64(self, &arguments[1])?;
+                timestamp
+                    .checked_sub(duration)
+                    .map(TypeAnnotatedValue::U64)
+                    .ok_or(
+                        "Subtracting the duration results in a timestamp before the Unix epoch"
+                            .to_string(),
+                    )
+            }
+            BuiltInFunction::DurationBetween => {
+                let from = internal::get_u64(self, &arguments[0])?;
+                let to = internal::get_u64(self, &arguments[1])?;
+                Ok(TypeAnnotatedValue::U64(to.saturating_sub(from)))
+            }
+            BuiltInFunction::Seconds => internal::duration(self, &arguments[0], 1000),
+            BuiltInFunction::Minutes => internal::duration(self, &arguments[0], 60 * 1000),
+            BuiltInFunction::Hours => internal::duration(self, &arguments[0], 60 * 60 * 1000),
+            BuiltInFunction::Days => internal::duration(self, &arguments[0], 24 * 60 * 60 * 1000),
+            BuiltInFunction::UseWorker => {
+                let worker_name = internal::get_str(self, &arguments[0])?;
+                if worker_name.is_empty() {
+                    Err("Worker name must not be empty".to_string())
+                } else {
+                    Ok(TypeAnnotatedValue::Str(worker_name.to_string()))
+                }
+            }
+        }
+    }
+}
+
+impl Display for BuiltInFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl TryFrom<String> for BuiltInFunction {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        BuiltInFunction::from_name(&value).ok_or(format!("Unknown built-in function: {}", value))
+    }
+}
+
+mod internal {
+    use crate::BuiltInFunction;
+    use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
+    use std::ops::Add;
+    use std::time::Duration;
+
+    pub(crate) fn to_millis(timestamp: iso8601_timestamp::Timestamp) -> Result<u64, String> {
+        let millis = timestamp
+            .duration_since(iso8601_timestamp::Timestamp::UNIX_EPOCH)
+            .whole_milliseconds();
+
+        u64::try_from(millis)
+            .map_err(|_| "Timestamps before the Unix epoch are not supported".to_string())
+    }
+
+    // 9999-12-31T23:59:59.999Z, the latest timestamp which can be represented
+    const MAX_MILLIS: u64 = 253_402_300_799_999;
+
+    pub(crate) fn from_millis(millis: u64) -> Result<iso8601_timestamp::Timestamp, String> {
+        if millis > MAX_MILLIS {
+            Err(format!(
+                "Timestamp {} is out of range, the latest supported timestamp is {}",
+                millis, MAX_MILLIS
+            ))
+        } else {
+            Ok(iso8601_timestamp::Timestamp::UNIX_EPOCH.add(Duration::from_millis(millis)))
+        }
+    }
+
+    pub(crate) fn get_u64(
+        function: &BuiltInFunction,
+        value: &TypeAnnotatedValue,
+    ) -> Result<u64, String> {
+        match value {
+            TypeAnnotatedValue::U64(value) => Ok(*value),
+            _ => Err(format!(
+                "Built-in function `{}` expects a u64 argument",
+                function
+            )),
+        }
+    }
+
+    pub(crate) fn get_str<'a>(
+        function: &BuiltInFunction,
+        value: &'a TypeAnnotatedValue,
+    ) -> Result<&'a str, String> {
+        match value {
+            TypeAnnotatedValue::Str(value) => Ok(value),
+            _ => Err(format!(
+                "Built-in function `{}` expects a string argument",
+                function
+            )),
+        }
+    }
+
+    pub(crate) fn duration(
+        function: &BuiltInFunction,
+        value: &TypeAnnotatedValue,
+        millis_per_unit: u64,
+    ) -> Result<TypeAnnotatedValue, String> {
+        get_u64(function, value)?
+            .checked_mul(millis_per_unit)
+            .map(TypeAnnotatedValue::U64)
+            .ok_or(format!(
+                "Duration overflow in built-in function `{}`",
+                function
+            ))
+    }
+}
+
+#[cfg(test)]
+mod built_in_tests {
+    use test_r::test;
+
+    use crate::BuiltInFunction;
+    use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
+
+    #[test]
+    fn test_parse_and_format_timestamp() {
+        let parsed = BuiltInFunction::ParseTimestamp
+            .call(vec![TypeAnnotatedValue::Str(
+                "2024-01-01T00:00:01.500Z".to_string(),
+            )])
+            .unwrap();
+
+        assert_eq!(parsed, TypeAnnotatedValue::U64(1704067201500));
+
+        let formatted = BuiltInFunction::FormatTimestamp
+            .call(vec![parsed.clone()])
+            .unwrap();
+
+        let TypeAnnotatedValue::Str(formatted) = formatted else {
+            panic!("expected a string, got {formatted:?}");
+        };
+        assert!(formatted.starts_with("2024-01-01T00:00:01.5"));
+
+        let reparsed = BuiltInFunction::ParseTimestamp
+            .call(vec![TypeAnnotatedValue::Str(formatted)])
+            .unwrap();
+        assert_eq!(reparsed, parsed);
+    }
+
+    #[test]
+    fn test_invalid_timestamp() {
+        let result = BuiltInFunction::ParseTimestamp
+            .call(vec![TypeAnnotatedValue::Str("yesterday".to_string())]);
+
+        assert_eq!(
+            result,
+            Err("Invalid ISO 8601 timestamp: yesterday".to_string())
+        );
+    }
+
+    #[test]
+    fn test_duration_arithmetic() {
+        let hour = BuiltInFunction::Hours
+            .call(vec![TypeAnnotatedValue::U64(1)])
+            .unwrap();
+        assert_eq!(hour, TypeAnnotatedValue::U64(3600000));
+
+        let later = BuiltInFunction::AddDuration
+            .call(vec![TypeAnnotatedValue::U64(1000), hour.clone()])
+            .unwrap();
+        assert_eq!(later, TypeAnnotatedValue::U64(3601000));
+
+        let between = BuiltInFunction::DurationBetween
+            .call(vec![TypeAnnotatedValue::U64(1000), later.clone()])
+            .unwrap();
+        assert_eq!(between, hour);
+
+        let negative_between = BuiltInFunction::DurationBetween
+            .call(vec![later, TypeAnnotatedValue::U64(1000)])
+            .unwrap();
+        assert_eq!(negative_between, TypeAnnotatedValue::U64(0));
+
+        let before_epoch =
+            BuiltInFunction::SubtractDuration.call(vec![TypeAnnotatedValue::U64(1000), hour]);
+        assert!(before_epoch.is_err());
+    }
+
+    #[test]
+    fn test_format_out_of_range_timestamp() {
+        let latest = BuiltInFunction::FormatTimestamp
+            .call(vec![TypeAnnotatedValue::U64(253402300799999)])
+            .unwrap();
+        let TypeAnnotatedValue::Str(latest) = latest else {
+            panic!("expected a string, got {latest:?}");
+        };
+        assert!(latest.starts_with("9999-12-31T23:59:59.999"));
+
+        for millis in [253402300800000, u64::MAX] {
+            let result =
+                BuiltInFunction::FormatTimestamp.call(vec![TypeAnnotatedValue::U64(millis)]);
+            assert!(result.is_err());
+        }
+    }
+}
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{DynamicParsedFunctionName, ParsedFunctionName};
+use crate::{BuiltInFunction, DynamicParsedFunctionName, ParsedFunctionName};
 use bincode::{Decode, Encode};
 use std::convert::TryFrom;
 use std::fmt::Display;
@@ -22,6 +22,7 @@ pub enum CallType {
     Function(DynamicParsedFunctionName),
     VariantConstructor(String),
     EnumConstructor(String),
+    BuiltIn(BuiltInFunction),
 }
 
 impl Display for CallType {
@@ -30,6 +31,7 @@ impl Display for CallType {
             CallType::Function(parsed_fn_name) => write!(f, "{}", parsed_fn_name),
             CallType::VariantConstructor(name) => write!(f, "{}", name),
             CallType::EnumConstructor(name) => write!(f, "{}", name),
+            CallType::BuiltIn(built_in) => write!(f, "{}", built_in),
         }
     }
 }
@@ -48,6 +50,9 @@ impl TryFrom<golem_api_grpc::proto::golem::rib::CallType> for CallType {
             golem_api_grpc::proto::golem::rib::call_type::Name::EnumConstructor(name) => {
                 Ok(CallType::EnumConstructor(name))
             }
+            golem_api_grpc::proto::golem::rib::call_type::Name::BuiltIn(name) => {
+                Ok(CallType::BuiltIn(BuiltInFunction::try_from(name)?))
+            }
         }
     }
 }
@@ -70,6 +75,11 @@ impl From<CallType> for golem_api_grpc::proto::golem::rib::CallType {
                     golem_api_grpc::proto::golem::rib::call_type::Name::EnumConstructor(name),
                 ),
             },
+            CallType::BuiltIn(built_in) => golem_api_grpc::proto::golem::rib::CallType {
+                name: Some(golem_api_grpc::proto::golem::rib::call_type::Name::BuiltIn(
+                    built_in.name().to_string(),
+                )),
+            },
         }
     }
 }
@@ -82,7 +82,9 @@ impl From<RibByteCode> for ProtoRibByteCode {
 }
 
 mod internal {
-    use crate::compiler::desugar::desugar_pattern_match;
+    use crate::compiler::desugar::{
+        desugar_coalesce, desugar_optional_select_field, desugar_pattern_match,
+    };
     use crate::{
         AnalysedTypeWithUnit, DynamicParsedFunctionReference, Expr, FunctionReferenceType,
         InferredType, InstructionId, RibIR,
@@ -240,6 +242,16 @@ mod internal {
                 stack.push(ExprState::from_expr(sequence_expr.deref()));
                 instructions.push(RibIR::SelectIndex(*index));
             }
+            Expr::OptionalSelectField(inner_expr, field_name, inferred_type) => {
+                let desugared_optional_select_field =
+                    desugar_optional_select_field(inner_expr, field_name, inferred_type.clone())?;
+                stack.push(ExprState::from_expr(&desugared_optional_select_field));
+            }
+            Expr::Coalesce(inner_expr, default_expr, inferred_type) => {
+                let desugared_coalesce =
+                    desugar_coalesce(inner_expr, default_expr, inferred_type.clone())?;
+                stack.push(ExprState::from_expr(&desugared_coalesce));
+            }
             Expr::Option(Some(inner_expr), inferred_type) => {
                 stack.push(ExprState::from_expr(inner_expr.deref()));
                 instructions.push(RibIR::PushSome(convert_to_analysed_type_for(
@@ -414,6 +426,9 @@ mod internal {
                             convert_to_analysed_type_for(expr, inferred_type)?,
                         ));
                     }
+                    CallType::BuiltIn(built_in) => {
+                        instructions.push(RibIR::CallBuiltIn(*built_in));
+                    }
                 }
             }
 
@@ -12,7 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{Expr, InferredType, MatchArm};
+use crate::{Expr, InferredType, MatchArm, VariableId};
+
 pub fn desugar_pattern_match(
     pred: &Expr,
     match_arms: &[MatchArm],
@@ -30,6 +31,110 @@ pub fn desugar_pattern_match(
     internal::build_expr_from(if_else_branches).map(|expr| expr.add_infer_type(expr_type))
 }
 
+// `expr?.field` is desugared to
+// `let $optional-chain = expr; if get_tag($optional-chain) == "some" then some(unwrap($optional-chain).field) else none`
+// and similarly with `ok` and `err` for results, keeping the error as it is.
+pub fn desugar_optional_select_field(
+    expr: &Expr,
+    field: &str,
+    expr_type: InferredType,
+) -> Result<Expr, String> {
+    let variable_id = VariableId::local_with_no_id("$optional-chain");
+    let binding = internal::binding(&variable_id, expr);
+    let tmp = Expr::Identifier(variable_id, expr.inferred_type());
+
+    let select_field = |record_type: InferredType, field_type: InferredType| {
+        Expr::SelectField(
+            Box::new(Expr::Unwrap(Box::new(tmp.clone()), record_type)),
+            field.to_string(),
+            field_type,
+        )
+    };
+
+    let (tag, then_expr, else_expr) = match (expr.inferred_type(), &expr_type) {
+        (InferredType::Option(record_type), InferredType::Option(field_type)) => (
+            "some",
+            Expr::Option(
+                Some(Box::new(select_field(*record_type, *field_type.clone()))),
+                expr_type.clone(),
+            ),
+            Expr::Option(None, expr_type.clone()),
+        ),
+        (
+            InferredType::Result {
+                ok: Some(record_type),
+                error,
+            },
+            InferredType::Result {
+                ok: Some(field_type),
+                ..
+            },
+        ) => {
+            let else_expr = match error {
+                Some(error_type) => Expr::Result(
+                    Err(Box::new(Expr::Unwrap(Box::new(tmp.clone()), *error_type))),
+                    expr_type.clone(),
+                ),
+                None => tmp.clone(),
+            };
+
+            (
+                "ok",
+                Expr::Result(
+                    Ok(Box::new(select_field(*record_type, *field_type.clone()))),
+                    expr_type.clone(),
+                ),
+                else_expr,
+            )
+        }
+        (inferred_type, _) => {
+            return Err(format!(
+                "Cannot select {} from {} since it is not an option or a result of a record. Found: {:?}",
+                field, expr, inferred_type
+            ))
+        }
+    };
+
+    Ok(internal::bind_and_branch_on_tag(
+        binding, tmp, tag, then_expr, else_expr, expr_type,
+    ))
+}
+
+// `expr ?? default` is desugared to
+// `let $coalesce = expr; if get_tag($coalesce) == "some" then unwrap($coalesce) else default`
+// and similarly with `ok` for results.
+pub fn desugar_coalesce(
+    expr: &Expr,
+    default: &Expr,
+    expr_type: InferredType,
+) -> Result<Expr, String> {
+    let variable_id = VariableId::local_with_no_id("$coalesce");
+    let binding = internal::binding(&variable_id, expr);
+    let tmp = Expr::Identifier(variable_id, expr.inferred_type());
+
+    let tag = match expr.inferred_type() {
+        InferredType::Option(_) => "some",
+        InferredType::Result { ok: Some(_), .. } => "ok",
+        inferred_type => {
+            return Err(format!(
+                "Cannot use a default for {} since it is not an option or a result. Found: {:?}",
+                expr, inferred_type
+            ))
+        }
+    };
+
+    let then_expr = Expr::Unwrap(Box::new(tmp.clone()), expr_type.clone());
+
+    Ok(internal::bind_and_branch_on_tag(
+        binding,
+        tmp,
+        tag,
+        then_expr,
+        default.clone(),
+        expr_type,
+    ))
+}
+
 mod internal {
     use crate::call_type::CallType;
     use crate::{ArmPattern, Expr, InferredType, MatchArm, VariableId};
@@ -486,6 +591,43 @@ mod internal {
         }
     }
 
+    pub(crate) fn binding(variable_id: &VariableId, expr: &Expr) -> Expr {
+        Expr::Let(
+            variable_id.clone(),
+            None,
+            Box::new(expr.clone()),
+            expr.inferred_type(),
+        )
+    }
+
+    pub(crate) fn bind_and_branch_on_tag(
+        binding: Expr,
+        bound_expr: Expr,
+        tag: &str,
+        then_expr: Expr,
+        else_expr: Expr,
+        expr_type: InferredType,
+    ) -> Expr {
+        let condition = Expr::EqualTo(
+            Box::new(Expr::GetTag(Box::new(bound_expr), InferredType::Unknown)),
+            Box::new(Expr::Literal(tag.to_string(), InferredType::Str)),
+            InferredType::Bool,
+        );
+
+        Expr::Multiple(
+            vec![
+                binding,
+                Expr::Cond(
+                    Box::new(condition),
+                    Box::new(then_expr),
+                    Box::new(else_expr),
+                    expr_type.clone(),
+                ),
+            ],
+            expr_type,
+        )
+    }
+
     fn handle_as_pattern(
         name: &str,
         inner_pattern: &ArmPattern,
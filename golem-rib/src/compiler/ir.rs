@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{AnalysedTypeWithUnit, ParsedFunctionSite, VariableId};
+use crate::{AnalysedTypeWithUnit, BuiltInFunction, ParsedFunctionSite, VariableId};
 use bincode::{Decode, Encode};
 use golem_api_grpc::proto::golem::rib::rib_ir::Instruction;
 use golem_api_grpc::proto::golem::rib::{
@@ -54,6 +54,7 @@ pub enum RibIR {
     Deconstruct,
     CreateFunctionName(ParsedFunctionSite, FunctionReferenceType),
     InvokeFunction(usize, AnalysedTypeWithUnit),
+    CallBuiltIn(BuiltInFunction), // Arguments are popped based on the parameters of the built-in function
     PushVariant(String, AnalysedType), // There is no arg size since the type of each variant case is only 1 from beginning
     PushEnum(String, AnalysedType),
     Throw(String),
@@ -399,6 +400,7 @@ impl TryFrom<ProtoRibIR> for RibIR {
             Instruction::Concat(concat_instruction) => {
                 Ok(RibIR::Concat(concat_instruction.arg_size as usize))
             }
+            Instruction::CallBuiltIn(name) => Ok(RibIR::CallBuiltIn(name.try_into()?)),
             Instruction::CreateFunctionName(instruction) => {
                 let parsed_site = instruction.site.ok_or("Missing site".to_string())?;
                 let parsed_function_site = ParsedFunctionSite::try_from(parsed_site)?;
@@ -516,6 +518,7 @@ impl From<RibIR> for ProtoRibIR {
                 arg_size: concat as u64,
             }),
             RibIR::Negate => Instruction::Negate(Negate {}),
+            RibIR::CallBuiltIn(built_in) => Instruction::CallBuiltIn(built_in.name().to_string()),
             RibIR::CreateFunctionName(site, reference_type) => {
                 Instruction::CreateFunctionName(CreateFunctionNameInstruction {
                     site: Some(site.into()),
@@ -63,6 +63,11 @@ pub enum Expr {
     Unwrap(Box<Expr>, InferredType),
     Throw(String, InferredType),
     GetTag(Box<Expr>, InferredType),
+    // Selects a field of the record inside an option or the ok value of a result (`expr?.field`),
+    // leaving `none` and `err` as they are
+    OptionalSelectField(Box<Expr>, String, InferredType),
+    // The value inside an option or the ok value of a result, or the default if there is none (`expr ?? default`)
+    Coalesce(Box<Expr>, Box<Expr>, InferredType),
 }
 
 impl Expr {
@@ -359,6 +364,18 @@ impl Expr {
         )
     }
 
+    pub fn optional_select_field(expr: Expr, field: impl AsRef<str>) -> Self {
+        Expr::OptionalSelectField(
+            Box::new(expr),
+            field.as_ref().to_string(),
+            InferredType::Unknown,
+        )
+    }
+
+    pub fn coalesce(expr: Expr, default: Expr) -> Self {
+        Expr::Coalesce(Box::new(expr), Box::new(default), InferredType::Unknown)
+    }
+
     pub fn select_index(expr: Expr, index: usize) -> Self {
         Expr::SelectIndex(Box::new(expr), index, InferredType::Unknown)
     }
@@ -416,6 +433,8 @@ impl Expr {
             | Expr::Unwrap(_, inferred_type)
             | Expr::Throw(_, inferred_type)
             | Expr::GetTag(_, inferred_type)
+            | Expr::OptionalSelectField(_, _, inferred_type)
+            | Expr::Coalesce(_, _, inferred_type)
             | Expr::And(_, _, inferred_type)
             | Expr::Or(_, _, inferred_type)
             | Expr::Call(_, _, inferred_type) => inferred_type.clone(),
@@ -431,6 +450,10 @@ impl Expr {
             .map_err(|x| vec![x])?;
         type_inference::type_inference_fix_point(Self::inference_scan, self)
             .map_err(|x| vec![x])?;
+        if self.default_numeric_literals() {
+            type_inference::type_inference_fix_point(Self::inference_scan, self)
+                .map_err(|x| vec![x])?;
+        }
         self.unify_types()?;
         Ok(())
     }
@@ -442,8 +465,10 @@ impl Expr {
         self.bind_types();
         self.name_binding_pattern_match_variables();
         self.name_binding_local_variables();
+        self.inline_numeric_let_bindings();
         self.infer_variants(function_type_registry);
         self.infer_enums(function_type_registry);
+        self.infer_built_in_functions(function_type_registry);
 
         Ok(())
     }
@@ -469,6 +494,17 @@ impl Expr {
         type_inference::name_binding_pattern_matches(self);
     }
 
+    // Let bindings of numeric literals used more than once are inlined,
+    // so that each usage can be of a different numeric type
+    pub fn inline_numeric_let_bindings(&mut self) {
+        type_inference::inline_numeric_let_bindings(self);
+    }
+
+    // Numeric literals whose type is still ambiguous after the type inference get a default type
+    pub fn default_numeric_literals(&mut self) -> bool {
+        type_inference::default_numeric_literals(self)
+    }
+
     // Make sure the variable assignment (let binding) are given variable ids,
     // which will be tagged to the corresponding identifiers to avoid conflicts.
     // This is done only for local variables and not global variables
@@ -545,6 +581,8 @@ impl Expr {
             | Expr::Unwrap(_, inferred_type)
             | Expr::Throw(_, inferred_type)
             | Expr::GetTag(_, inferred_type)
+            | Expr::OptionalSelectField(_, _, inferred_type)
+            | Expr::Coalesce(_, _, inferred_type)
             | Expr::And(_, _, inferred_type)
             | Expr::Or(_, _, inferred_type)
             | Expr::Call(_, _, inferred_type) => {
@@ -589,6 +627,8 @@ impl Expr {
             | Expr::And(_, _, inferred_type)
             | Expr::Or(_, _, inferred_type)
             | Expr::GetTag(_, inferred_type)
+            | Expr::OptionalSelectField(_, _, inferred_type)
+            | Expr::Coalesce(_, _, inferred_type)
             | Expr::Call(_, _, inferred_type) => {
                 if new_inferred_type != InferredType::Unknown {
                     *inferred_type = new_inferred_type;
@@ -605,6 +645,10 @@ impl Expr {
         type_inference::infer_variants(self, function_type_registry);
     }
 
+    pub fn infer_built_in_functions(&mut self, function_type_registry: &FunctionTypeRegistry) {
+        type_inference::infer_built_in_functions(self, function_type_registry);
+    }
+
     pub fn visit_children_bottom_up<'a>(&'a self, queue: &mut VecDeque<&'a Expr>) {
         type_inference::visit_children_bottom_up(self, queue);
     }
@@ -1008,6 +1052,18 @@ impl TryFrom<golem_api_grpc::proto::golem::rib::Expr> for Expr {
                 expr.unwrap()
             }
 
+            golem_api_grpc::proto::golem::rib::expr::Expr::OptionalSelectField(expr) => {
+                let field = expr.field;
+                let expr = expr.expr.ok_or("Missing expr")?;
+                Expr::optional_select_field((*expr).try_into()?, field.as_str())
+            }
+
+            golem_api_grpc::proto::golem::rib::expr::Expr::Coalesce(expr) => {
+                let left = expr.left.ok_or("Missing left expr")?;
+                let right = expr.right.ok_or("Missing right expr")?;
+                Expr::coalesce((*left).try_into()?, (*right).try_into()?)
+            }
+
             golem_api_grpc::proto::golem::rib::expr::Expr::Number(number) => {
                 let type_name = number.type_name.map(TypeName::try_from).transpose()?;
                 if let Some(type_name) = type_name {
@@ -1316,6 +1372,24 @@ impl From<Expr> for golem_api_grpc::proto::golem::rib::Expr {
                     right: Some(Box::new((*right).into())),
                 }),
             )),
+
+            Expr::OptionalSelectField(expr, field, _) => Some(
+                golem_api_grpc::proto::golem::rib::expr::Expr::OptionalSelectField(Box::new(
+                    golem_api_grpc::proto::golem::rib::OptionalSelectFieldExpr {
+                        field,
+                        expr: Some(Box::new((*expr).into())),
+                    },
+                )),
+            ),
+
+            Expr::Coalesce(left, right, _) => {
+                Some(golem_api_grpc::proto::golem::rib::expr::Expr::Coalesce(
+                    Box::new(golem_api_grpc::proto::golem::rib::CoalesceExpr {
+                        left: Some(Box::new((*left).into())),
+                        right: Some(Box::new((*right).into())),
+                    }),
+                ))
+            }
         };
 
         golem_api_grpc::proto::golem::rib::Expr { expr }
@@ -885,7 +885,7 @@ impl InferredType {
             Err(errors)
         }
     }
-    fn is_number(&self) -> bool {
+    pub(crate) fn is_number(&self) -> bool {
         matches!(
             self,
             InferredType::S8
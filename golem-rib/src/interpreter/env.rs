@@ -24,18 +24,25 @@ use std::sync::Arc;
 pub struct InterpreterEnv {
     pub env: HashMap<EnvironmentKey, RibInterpreterResult>,
     pub call_worker_function_async: RibFunctionInvoke,
+    // The worker selected by the last `use-worker` call, if any
+    pub target_worker: Option<String>,
 }
 
 impl Debug for InterpreterEnv {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("InterpreterEnv")
             .field("env", &self.env)
+            .field("target_worker", &self.target_worker)
             .finish()
     }
 }
 
+// Invokes a worker function, given the name of the worker selected by `use-worker`
+// (or `None` to target the worker the script is evaluated against), the function name
+// and the arguments
 pub type RibFunctionInvoke = Arc<
     dyn Fn(
+            Option<String>,
             String,
             Vec<TypeAnnotatedValue>,
         ) -> Pin<Box<dyn Future<Output = Result<TypeAnnotatedValue, String>> + Send>>
@@ -48,6 +55,7 @@ impl Default for InterpreterEnv {
         InterpreterEnv {
             env: HashMap::new(),
             call_worker_function_async: internal::default_worker_invoke_async(),
+            target_worker: None,
         }
     }
 }
@@ -60,6 +68,7 @@ impl InterpreterEnv {
         InterpreterEnv {
             env,
             call_worker_function_async,
+            target_worker: None,
         }
     }
 
@@ -68,7 +77,7 @@ impl InterpreterEnv {
         function_name: String,
         args: Vec<TypeAnnotatedValue>,
     ) -> Pin<Box<dyn Future<Output = Result<TypeAnnotatedValue, String>> + Send>> {
-        (self.call_worker_function_async)(function_name, args)
+        (self.call_worker_function_async)(self.target_worker.clone(), function_name, args)
     }
 
     pub fn from_input(env: HashMap<String, TypeAnnotatedValue>) -> Self {
@@ -80,6 +89,7 @@ impl InterpreterEnv {
         InterpreterEnv {
             env,
             call_worker_function_async: internal::default_worker_invoke_async(),
+            target_worker: None,
         }
     }
 
@@ -125,7 +135,7 @@ mod internal {
     use std::sync::Arc;
 
     pub(crate) fn default_worker_invoke_async() -> RibFunctionInvoke {
-        Arc::new(|_, _| {
+        Arc::new(|_, _, _| {
             Box::pin(async {
                 Ok(TypeAnnotatedValue::Tuple(TypedTuple {
                     typ: vec![],
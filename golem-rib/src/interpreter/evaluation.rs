@@ -0,0 +1,184 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::interpreter::env::RibFunctionInvoke;
+use crate::interpreter::rib_interpreter::Interpreter;
+use crate::interpreter::RibInterpreterResult;
+use crate::parser::rib_expr::rib_program;
+use crate::{compiler, Expr, RibInputTypeInfo};
+use combine::stream::position;
+use combine::EasyParser;
+use golem_wasm_ast::analysis::AnalysedExport;
+use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
+use std::collections::HashMap;
+use std::fmt::Display;
+
+// A syntax error in a Rib script, with the line and column (both starting from 1)
+// where the parser failed
+#[derive(Debug, Clone, PartialEq)]
+pub struct RibSyntaxError {
+    pub line: i32,
+    pub column: i32,
+    pub message: String,
+}
+
+impl Display for RibSyntaxError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Parse error at line: {}, column: {}\n{}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RibEvaluationError {
+    Syntax(RibSyntaxError),
+    Compilation(String),
+    Input(String),
+    Runtime(String),
+}
+
+impl Display for RibEvaluationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RibEvaluationError::Syntax(error) => write!(f, "{}", error),
+            RibEvaluationError::Compilation(error) => write!(f, "Compilation error: {}", error),
+            RibEvaluationError::Input(error) => write!(f, "Invalid input: {}", error),
+            RibEvaluationError::Runtime(error) => write!(f, "Runtime error: {}", error),
+        }
+    }
+}
+
+// Same as `Expr::from_text`, keeping the position of a syntax error apart from its message
+pub fn parse_with_position(rib: &str) -> Result<Expr, RibSyntaxError> {
+    rib_program()
+        .easy_parse(position::Stream::new(rib))
+        .map(|t| t.0)
+        .map_err(|err| {
+            // The first line of the formatted error is the position itself
+            let message = err
+                .to_string()
+                .lines()
+                .skip(1)
+                .filter(|line| !line.is_empty())
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            RibSyntaxError {
+                line: err.position.line,
+                column: err.position.column,
+                message,
+            }
+        })
+}
+
+// Parses, compiles and runs a Rib script in one go, which is useful to try out
+// an expression before using it in an API definition.
+// The values of the global input variables are given by `rib_input`, based on the
+// types of the global variables the script uses.
+pub async fn evaluate<F>(
+    rib: &str,
+    export_metadata: &Vec<AnalysedExport>,
+    rib_input: F,
+    function_invoke: RibFunctionInvoke,
+) -> Result<RibInterpreterResult, RibEvaluationError>
+where
+    F: FnOnce(&RibInputTypeInfo) -> Result<HashMap<String, TypeAnnotatedValue>, String>,
+{
+    let expr = parse_with_position(rib).map_err(RibEvaluationError::Syntax)?;
+
+    let compiled =
+        compiler::compile(&expr, export_metadata).map_err(RibEvaluationError::Compilation)?;
+
+    let rib_input =
+        rib_input(&compiled.global_input_type_info).map_err(RibEvaluationError::Input)?;
+
+    let mut interpreter = Interpreter::new(rib_input, function_invoke);
+    interpreter
+        .run(compiled.byte_code)
+        .await
+        .map_err(RibEvaluationError::Runtime)
+}
+
+#[cfg(test)]
+mod evaluation_tests {
+    use test_r::test;
+
+    use crate::interpreter::evaluation::{evaluate, parse_with_position, RibEvaluationError};
+    use crate::RibFunctionInvoke;
+    use golem_wasm_ast::analysis::analysed_type::{field, record, str};
+    use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
+    use golem_wasm_rpc::type_annotated_value_from_str;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    fn no_function_calls() -> RibFunctionInvoke {
+        Arc::new(|_, _, _| Box::pin(async { Err("Unexpected function call".to_string()) }))
+    }
+
+    #[test]
+    fn syntax_errors_have_positions() {
+        let result = parse_with_position("let x = 1;\nlet y = some [x);\ny");
+        let error = result.unwrap_err();
+
+        assert_eq!((error.line, error.column), (2, 9));
+        assert!(!error.message.is_empty());
+    }
+
+    #[test]
+    async fn script_is_evaluated_with_sample_input() {
+        let result = evaluate(
+            r#"request.path.user"#,
+            &vec![],
+            |input_types| {
+                let request_type = input_types.types.get("request").unwrap();
+                assert_eq!(
+                    request_type,
+                    &record(vec![field("path", record(vec![field("user", str())]))])
+                );
+
+                let request =
+                    type_annotated_value_from_str(request_type, r#"{path: {user: "jon"}}"#)
+                        .map_err(|err| err.to_string())?;
+                Ok(HashMap::from([("request".to_string(), request)]))
+            },
+            no_function_calls(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            result.get_val().unwrap(),
+            TypeAnnotatedValue::Str("jon".to_string())
+        );
+    }
+
+    #[test]
+    async fn invalid_input_is_reported() {
+        let result = evaluate(
+            r#"request.path.user"#,
+            &vec![],
+            |_| Err("Missing request".to_string()),
+            no_function_calls(),
+        )
+        .await;
+
+        assert_eq!(
+            result.unwrap_err(),
+            RibEvaluationError::Input("Missing request".to_string())
+        );
+    }
+}
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 pub use env::RibFunctionInvoke;
+pub use evaluation::*;
 pub use literal::*;
 pub use result::*;
 pub use rib_interpreter::*;
@@ -21,6 +22,7 @@ use crate::RibByteCode;
 use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
 use std::collections::HashMap;
 mod env;
+mod evaluation;
 mod literal;
 mod result;
 mod rib_interpreter;
@@ -146,6 +146,10 @@ impl Interpreter {
                     internal::run_call_instruction(arg_size, self).await?;
                 }
 
+                RibIR::CallBuiltIn(built_in) => {
+                    internal::run_call_built_in_instruction(built_in, self)?;
+                }
+
                 RibIR::PushVariant(variant_name, analysed_type) => {
                     internal::run_variant_construction_instruction(
                         variant_name,
@@ -221,8 +225,8 @@ mod internal {
     use crate::interpreter::result::RibInterpreterResult;
     use crate::interpreter::stack::InterpreterStack;
     use crate::{
-        FunctionReferenceType, GetLiteralValue, InstructionId, Interpreter, ParsedFunctionName,
-        ParsedFunctionReference, ParsedFunctionSite, RibIR, VariableId,
+        BuiltInFunction, FunctionReferenceType, GetLiteralValue, InstructionId, Interpreter,
+        ParsedFunctionName, ParsedFunctionReference, ParsedFunctionSite, RibIR, VariableId,
     };
     use golem_wasm_ast::analysis::AnalysedType;
     use golem_wasm_ast::analysis::TypeResult;
@@ -832,6 +836,41 @@ mod internal {
         Ok(())
     }
 
+    pub(crate) fn run_call_built_in_instruction(
+        built_in: BuiltInFunction,
+        interpreter: &mut Interpreter,
+    ) -> Result<(), String> {
+        let last_n_elements = interpreter
+            .stack
+            .pop_n(built_in.parameter_types().len())
+            .ok_or(format!(
+                "Internal Error: Failed to get arguments for the built-in function {}",
+                built_in
+            ))?;
+
+        let type_annotated_values = last_n_elements
+            .iter()
+            .map(|interpreter_result| {
+                interpreter_result.get_val().ok_or(format!(
+                    "Internal Error: Failed to call built-in function {}",
+                    built_in
+                ))
+            })
+            .collect::<Result<Vec<TypeAnnotatedValue>, String>>()?;
+
+        let result = built_in.call(type_annotated_values)?;
+
+        if let (BuiltInFunction::UseWorker, TypeAnnotatedValue::Str(worker_name)) =
+            (built_in, &result)
+        {
+            interpreter.env.target_worker = Some(worker_name.clone());
+        }
+
+        interpreter.stack.push_val(result);
+
+        Ok(())
+    }
+
     pub(crate) fn run_jump_if_false_instruction(
         instruction_id: InstructionId,
         instruction_stack: &mut VecDeque<RibIR>,
@@ -1794,6 +1833,199 @@ mod interpreter_tests {
         }
     }
 
+    mod multiple_invocation_tests {
+        use test_r::test;
+
+        use crate::interpreter::rib_interpreter::interpreter_tests::internal;
+        use crate::{compiler, Expr, Interpreter, RibFunctionInvoke};
+        use golem_wasm_ast::analysis::analysed_type::str;
+        use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
+        use golem_wasm_rpc::protobuf::TypedTuple;
+        use std::collections::HashMap;
+        use std::sync::{Arc, Mutex};
+
+        type RecordedCalls = Arc<Mutex<Vec<(Option<String>, String)>>>;
+
+        #[test]
+        async fn test_interpreter_with_sequential_function_calls() {
+            let expr = r#"
+              let first = foo("a");
+              let second = bar(first);
+              second
+            "#;
+
+            let calls = RecordedCalls::default();
+            let result = run(expr, calls.clone()).await;
+
+            assert_eq!(result, TypeAnnotatedValue::Str("bar(foo(a))".to_string()));
+            assert_eq!(
+                *calls.lock().unwrap(),
+                vec![(None, "foo".to_string()), (None, "bar".to_string())]
+            );
+        }
+
+        #[test]
+        async fn test_interpreter_with_function_calls_on_multiple_workers() {
+            let expr = r#"
+              let first = foo("a");
+              use-worker("worker-2");
+              let second = bar(first);
+              use-worker("worker-3");
+              let third = foo(second);
+              third
+            "#;
+
+            let calls = RecordedCalls::default();
+            let result = run(expr, calls.clone()).await;
+
+            assert_eq!(
+                result,
+                TypeAnnotatedValue::Str("foo(bar(foo(a)))".to_string())
+            );
+            assert_eq!(
+                *calls.lock().unwrap(),
+                vec![
+                    (None, "foo".to_string()),
+                    (Some("worker-2".to_string()), "bar".to_string()),
+                    (Some("worker-3".to_string()), "foo".to_string())
+                ]
+            );
+        }
+
+        async fn run(expr: &str, calls: RecordedCalls) -> TypeAnnotatedValue {
+            let expr = Expr::from_text(expr).unwrap();
+
+            let mut component_metadata =
+                internal::get_component_metadata("foo", vec![str()], Some(str()));
+            component_metadata.extend(internal::get_component_metadata(
+                "bar",
+                vec![str()],
+                Some(str()),
+            ));
+
+            let compiled = compiler::compile(&expr, &component_metadata).unwrap();
+
+            let function_invoke: RibFunctionInvoke = Arc::new(
+                move |target_worker: Option<String>,
+                      function_name: String,
+                      args: Vec<TypeAnnotatedValue>| {
+                    calls
+                        .lock()
+                        .unwrap()
+                        .push((target_worker, function_name.clone()));
+                    Box::pin(async move {
+                        let arg = match args.first() {
+                            Some(TypeAnnotatedValue::Str(arg)) => arg.clone(),
+                            _ => return Err("Expected a string argument".to_string()),
+                        };
+                        Ok(TypeAnnotatedValue::Tuple(TypedTuple {
+                            typ: vec![golem_wasm_ast::analysis::protobuf::Type::from(&str())],
+                            value: vec![golem_wasm_rpc::protobuf::TypeAnnotatedValue {
+                                type_annotated_value: Some(TypeAnnotatedValue::Str(format!(
+                                    "{function_name}({arg})"
+                                ))),
+                            }],
+                        }))
+                    })
+                },
+            );
+
+            let mut rib_interpreter = Interpreter::new(HashMap::new(), function_invoke);
+            let result = rib_interpreter.run(compiled.byte_code).await.unwrap();
+            result.get_val().unwrap()
+        }
+    }
+
+    mod optional_chaining_tests {
+        use test_r::test;
+
+        use crate::{compiler, Expr, FunctionTypeRegistry, Interpreter};
+        use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
+
+        #[test]
+        async fn test_interpreter_for_optional_select_field_on_some() {
+            let mut interpreter = Interpreter::default();
+
+            let expr = r#"
+              let user = some({name: "foo", address: {city: "bar"}});
+              user?.address.city ?? "unknown"
+            "#;
+
+            let mut expr = Expr::from_text(expr).unwrap();
+            expr.infer_types(&FunctionTypeRegistry::empty()).unwrap();
+            let compiled = compiler::compile(&expr, &vec![]).unwrap();
+            let result = interpreter.run(compiled.byte_code).await.unwrap();
+
+            assert_eq!(
+                result.get_val().unwrap(),
+                TypeAnnotatedValue::Str("bar".to_string())
+            );
+        }
+
+        #[test]
+        async fn test_interpreter_for_coalesce_on_none() {
+            let mut interpreter = Interpreter::default();
+
+            let expr = r#"
+              let x: option<u64> = none;
+              x ?? 1u64
+            "#;
+
+            let mut expr = Expr::from_text(expr).unwrap();
+            expr.infer_types(&FunctionTypeRegistry::empty()).unwrap();
+            let compiled = compiler::compile(&expr, &vec![]).unwrap();
+            let result = interpreter.run(compiled.byte_code).await.unwrap();
+
+            assert_eq!(result.get_val().unwrap(), TypeAnnotatedValue::U64(1));
+        }
+    }
+
+    mod built_in_function_tests {
+        use test_r::test;
+
+        use crate::{compiler, Expr, Interpreter};
+        use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
+
+        #[test]
+        async fn test_interpreter_for_token_expiry_check() {
+            let mut interpreter = Interpreter::default();
+
+            let expr = r#"
+              let issued_at = parse-timestamp("2024-01-01T00:00:00Z");
+              let expires_at = add-duration(issued_at, hours(1));
+              let expired = now() > expires_at;
+              let remaining = duration-between(issued_at, expires_at);
+              { expired: expired, remaining: remaining, expires_at: format-timestamp(expires_at) }
+            "#;
+
+            let expr = Expr::from_text(expr).unwrap();
+            let compiled = compiler::compile(&expr, &vec![]).unwrap();
+            assert_eq!(compiled.byte_code.worker_invocation_count(), 0);
+
+            let result = interpreter.run(compiled.byte_code).await.unwrap();
+            let TypeAnnotatedValue::Record(record) = result.get_val().unwrap() else {
+                panic!("expected a record");
+            };
+
+            let field = |name: &str| {
+                record
+                    .value
+                    .iter()
+                    .find(|field| field.name == name)
+                    .and_then(|field| field.value.clone())
+                    .and_then(|value| value.type_annotated_value)
+                    .unwrap()
+            };
+
+            assert_eq!(field("expired"), TypeAnnotatedValue::Bool(true));
+            assert_eq!(field("remaining"), TypeAnnotatedValue::U64(3600000));
+            assert!(matches!(
+                field("expires_at"),
+                TypeAnnotatedValue::Str(expires_at) if expires_at.starts_with("2024-01-01T01:00:00")
+            ));
+        }
+    }
+
     mod internal {
         use crate::interpreter::env::InterpreterEnv;
         use crate::interpreter::stack::InterpreterStack;
@@ -2042,6 +2274,7 @@ mod interpreter_tests {
                 env: InterpreterEnv {
                     env: HashMap::new(),
                     call_worker_function_async: static_worker_invoke(result_type, result_value),
+                    target_worker: None,
                 },
             }
         }
@@ -2053,7 +2286,7 @@ mod interpreter_tests {
             let analysed_type = result_type.clone();
             let value = value.clone();
 
-            Arc::new(move |_, _| {
+            Arc::new(move |_, _, _| {
                 Box::pin({
                     let analysed_type = analysed_type.clone();
                     let value = value.clone();
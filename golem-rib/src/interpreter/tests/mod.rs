@@ -2269,7 +2269,7 @@ mod comprehensive_test {
         ) -> RibFunctionInvoke {
             let value = functions_and_result.clone();
 
-            Arc::new(move |a, _| {
+            Arc::new(move |_, a, _| {
                 Box::pin({
                     let value = value.get(&FunctionName(a)).cloned().flatten();
                     let analysed_type = value.clone().map(|x| AnalysedType::try_from(&x).unwrap());
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub use built_in::*;
 pub use compiler::*;
 pub use expr::*;
 pub use function_name::*;
@@ -23,6 +24,7 @@ pub use type_inference::*;
 pub use type_registry::*;
 pub use variable_id::*;
 
+mod built_in;
 mod call_type;
 mod compiler;
 mod expr;
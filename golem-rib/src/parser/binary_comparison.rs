@@ -27,6 +27,7 @@ where
         attempt(string(">=")),
         attempt(string("<=")),
         attempt(string("==")),
+        attempt(string("??")),
         string("<"),
         string(">"),
         string("&&"),
@@ -40,6 +41,7 @@ where
         "<=" => Ok(BinaryOp::LessThanOrEqualTo),
         "&&" => Ok(BinaryOp::And),
         "||" => Ok(BinaryOp::Or),
+        "??" => Ok(BinaryOp::Coalesce),
         _ => Err(RibParseError::Message(
             "Invalid binary operator".to_string(),
         )),
@@ -54,6 +56,7 @@ pub enum BinaryOp {
     EqualTo,
     And,
     Or,
+    Coalesce,
 }
 
 #[cfg(test)]
@@ -308,6 +311,99 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_coalesce() {
+        let input = "foo ?? bar";
+        let result = rib_expr().easy_parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Expr::coalesce(Expr::identifier("foo"), Expr::identifier("bar")),
+                ""
+            ))
+        );
+    }
+
+    #[test]
+    fn test_optional_select_field_with_coalesce() {
+        let input = "request.body?.user.name ?? \"anonymous\"";
+        let result = rib_expr().easy_parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Expr::coalesce(
+                    Expr::optional_select_field(
+                        Expr::optional_select_field(
+                            Expr::select_field(Expr::identifier("request"), "body"),
+                            "user"
+                        ),
+                        "name"
+                    ),
+                    Expr::literal("anonymous")
+                ),
+                ""
+            ))
+        );
+    }
+
+    #[test]
+    fn test_optional_select_field_binds_tighter_than_comparison() {
+        let input = "foo == bar?.baz";
+        let result = rib_expr().easy_parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Expr::equal_to(
+                    Expr::identifier("foo"),
+                    Expr::optional_select_field(Expr::identifier("bar"), "baz")
+                ),
+                ""
+            ))
+        );
+    }
+
+    #[test]
+    fn test_optional_select_field_on_left_of_comparison() {
+        let input = "foo?.bar.baz > qux";
+        let result = rib_expr().easy_parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Expr::greater_than(
+                    Expr::optional_select_field(
+                        Expr::optional_select_field(Expr::identifier("foo"), "bar"),
+                        "baz"
+                    ),
+                    Expr::identifier("qux")
+                ),
+                ""
+            ))
+        );
+    }
+
+    #[test]
+    fn test_optional_select_field_binds_tighter_than_coalesce() {
+        let input = "foo ?? bar?.baz";
+        let result = rib_expr().easy_parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                Expr::coalesce(
+                    Expr::identifier("foo"),
+                    Expr::optional_select_field(Expr::identifier("bar"), "baz")
+                ),
+                ""
+            ))
+        );
+    }
+
+    #[test]
+    fn test_optional_select_field_requires_field_name() {
+        let input = "foo?.\"bar\"";
+        let result = rib_expr().easy_parse(input);
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_binary_op_in_record() {
         let input = "{foo: bar > baz, baz: bar == foo}";
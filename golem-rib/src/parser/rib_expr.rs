@@ -44,7 +44,7 @@ where
     )
 }
 
-// A rib expression := (simple_expr, rib_expr_rest*)
+// A rib expression := (postfix_expr, rib_expr_rest*)
 parser! {
     pub fn rib_expr[Input]()(Input) -> Expr
     where [Input: combine::Stream<Token = char>, RibParseError: Into<<Input::Error as ParseError<Input::Token, Input::Range, Input::Position>>::StreamError>,]
@@ -62,7 +62,7 @@ where
 {
     spaces()
         .with(
-            (internal::simple_expr(), internal::rib_expr_rest()).map(|(expr, rest)| {
+            (internal::postfix_expr(), internal::rib_expr_rest()).map(|(expr, rest)| {
                 // FIXME: Respect operator precedence
                 rest.into_iter().fold(expr, |acc, (op, next)| match op {
                     BinaryOp::GreaterThan => Expr::greater_than(acc, next),
@@ -72,6 +72,7 @@ where
                     BinaryOp::EqualTo => Expr::equal_to(acc, next),
                     BinaryOp::And => Expr::and(acc, next),
                     BinaryOp::Or => Expr::or(acc, next),
+                    BinaryOp::Coalesce => Expr::coalesce(acc, next),
                 })
             }),
         )
@@ -101,7 +102,7 @@ mod internal {
     use crate::parser::sequence::sequence;
     use crate::parser::tuple::tuple;
     use crate::Expr;
-    use combine::parser::char::spaces;
+    use combine::parser::char::{spaces, string};
     use combine::{attempt, choice, many, parser, ParseError, Parser, Stream};
 
     // A simple expression is a composition of all parsers that doesn't involve left recursion
@@ -142,6 +143,32 @@ mod internal {
         }
     }
 
+    // A simple expression followed by optional field selections, which bind tighter than
+    // any binary operator: `a == b?.c` is `a == (b?.c)`
+    pub fn postfix_expr_<Input>() -> impl Parser<Input, Output = Expr>
+    where
+        Input: combine::Stream<Token = char>,
+        RibParseError: Into<
+            <Input::Error as ParseError<Input::Token, Input::Range, Input::Position>>::StreamError,
+        >,
+    {
+        (
+            simple_expr(),
+            many(attempt(string("?.")).with(simple_expr())),
+        )
+            .and_then(|(expr, selections): (Expr, Vec<Expr>)| {
+                selections.into_iter().try_fold(expr, optional_selector)
+            })
+    }
+
+    parser! {
+        pub(crate) fn postfix_expr[Input]()(Input) -> Expr
+        where [Input: Stream<Token = char>, RibParseError: Into<<Input::Error as ParseError<Input::Token, Input::Range, Input::Position>>::StreamError>,]
+        {
+            postfix_expr_()
+        }
+    }
+
     pub fn rib_expr_rest_<Input>() -> impl Parser<Input, Output = Vec<(BinaryOp, Expr)>>
     where
         Input: combine::Stream<Token = char>,
@@ -149,7 +176,7 @@ mod internal {
             <Input::Error as ParseError<Input::Token, Input::Range, Input::Position>>::StreamError,
         >,
     {
-        many((binary_op(), simple_expr()))
+        many((binary_op(), postfix_expr()))
     }
 
     parser! {
@@ -160,6 +187,25 @@ mod internal {
         }
     }
 
+    // The fields selected after `?.` are parsed as a regular field selection, such as `b.c` in `a?.b.c`.
+    // Each of them is selected optionally, which is the same as short-circuiting the rest of the selection
+    // if `a` is `none` or `err`: `a?.b.c` is `a?.b?.c`
+    pub(crate) fn optional_selector(base: Expr, selection: Expr) -> Result<Expr, RibParseError> {
+        match selection {
+            Expr::Identifier(variable_id, _) => Ok(Expr::optional_select_field(
+                base,
+                variable_id.name().as_str(),
+            )),
+            Expr::SelectField(inner, field, _) => Ok(Expr::optional_select_field(
+                optional_selector(base, *inner)?,
+                field.as_str(),
+            )),
+            _ => Err(RibParseError::Message(
+                "Expected a field name after `?.`".to_string(),
+            )),
+        }
+    }
+
     fn flag_or_record<Input>() -> impl Parser<Input, Output = Expr>
     where
         Input: combine::Stream<Token = char>,
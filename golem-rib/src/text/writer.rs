@@ -281,6 +281,16 @@ impl<W: Write> Writer<W> {
                 self.write_str(" || ")?;
                 self.write_expr(right)
             }
+            Expr::OptionalSelectField(expr, field, _) => {
+                self.write_expr(expr)?;
+                self.write_str("?.")?;
+                self.write_str(field)
+            }
+            Expr::Coalesce(expr, default, _) => {
+                self.write_expr(expr)?;
+                self.write_str(" ?? ")?;
+                self.write_expr(default)
+            }
         }
     }
 
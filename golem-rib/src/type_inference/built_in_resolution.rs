@@ -0,0 +1,133 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{Expr, FunctionTypeRegistry};
+
+// Calls to global functions having the name of a built-in function (example: `now()`)
+// are converted to built-in calls, unless the component exports a function of the same name.
+pub fn infer_built_in_functions(expr: &mut Expr, function_type_registry: &FunctionTypeRegistry) {
+    internal::convert_function_calls_to_built_in_calls(expr, function_type_registry);
+}
+
+mod internal {
+    use crate::call_type::CallType;
+    use crate::{
+        BuiltInFunction, DynamicParsedFunctionReference, Expr, FunctionTypeRegistry,
+        ParsedFunctionSite, RegistryKey,
+    };
+    use std::collections::VecDeque;
+
+    pub(crate) fn convert_function_calls_to_built_in_calls(
+        expr: &mut Expr,
+        function_type_registry: &FunctionTypeRegistry,
+    ) {
+        let mut queue = VecDeque::new();
+        queue.push_back(expr);
+
+        while let Some(expr) = queue.pop_back() {
+            match expr {
+                Expr::Call(CallType::Function(parsed_function_name), args, inferred_type) => {
+                    let built_in =
+                        match (&parsed_function_name.site, &parsed_function_name.function) {
+                            (
+                                ParsedFunctionSite::Global,
+                                DynamicParsedFunctionReference::Function { function },
+                            ) => BuiltInFunction::from_name(function).filter(|_| {
+                                function_type_registry
+                                    .lookup(&RegistryKey::FunctionName(function.clone()))
+                                    .is_none()
+                            }),
+                            _ => None,
+                        };
+
+                    match built_in {
+                        Some(built_in) => {
+                            *expr = Expr::Call(
+                                CallType::BuiltIn(built_in),
+                                args.clone(),
+                                inferred_type.clone(),
+                            );
+                            queue.push_back(expr);
+                        }
+                        None => expr.visit_children_mut_bottom_up(&mut queue),
+                    }
+                }
+                _ => expr.visit_children_mut_bottom_up(&mut queue),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod built_in_resolution_tests {
+    use test_r::test;
+
+    use crate::call_type::CallType;
+    use crate::{BuiltInFunction, Expr, FunctionTypeRegistry, InferredType};
+    use golem_wasm_ast::analysis::analysed_type::str;
+    use golem_wasm_ast::analysis::{AnalysedExport, AnalysedFunction, AnalysedFunctionResult};
+
+    #[test]
+    fn test_built_in_function_call() {
+        let mut expr = Expr::from_text(r#"format-timestamp(now())"#).unwrap();
+        expr.infer_types(&FunctionTypeRegistry::empty()).unwrap();
+
+        let Expr::Call(call_type, args, inferred_type) = &expr else {
+            panic!("expected a call, got {expr:?}");
+        };
+
+        assert_eq!(
+            call_type,
+            &CallType::BuiltIn(BuiltInFunction::FormatTimestamp)
+        );
+        assert_eq!(inferred_type, &InferredType::Str);
+        assert!(matches!(
+            args.as_slice(),
+            [Expr::Call(
+                CallType::BuiltIn(BuiltInFunction::Now),
+                _,
+                InferredType::U64
+            )]
+        ));
+    }
+
+    #[test]
+    fn test_exported_function_shadows_built_in_function() {
+        let exports = vec![AnalysedExport::Function(AnalysedFunction {
+            name: "now".to_string(),
+            parameters: vec![],
+            results: vec![AnalysedFunctionResult {
+                name: None,
+                typ: str(),
+            }],
+        })];
+
+        let mut expr = Expr::from_text(r#"now()"#).unwrap();
+        expr.infer_types(&FunctionTypeRegistry::from_export_metadata(&exports))
+            .unwrap();
+
+        assert!(matches!(
+            expr,
+            Expr::Call(CallType::Function(_), _, InferredType::Str)
+        ));
+    }
+
+    #[test]
+    fn test_built_in_function_argument_type_mismatch() {
+        let mut expr = Expr::from_text(r#"parse-timestamp(1)"#).unwrap();
+        let result = expr.infer_types(&FunctionTypeRegistry::empty());
+
+        assert!(result.is_err());
+    }
+}
@@ -153,6 +153,25 @@ mod internal {
                 )
                 .map_err(|e| e.to_string())
             }
+
+            CallType::BuiltIn(built_in) => {
+                let function_name = FunctionTypeInternal::Fqn(built_in.to_string());
+                let parameter_types = built_in.parameter_types();
+
+                if parameter_types.len() == args.len() {
+                    tag_argument_types(&function_name, args, &parameter_types)
+                        .map_err(|e| e.to_string())?;
+                    *inferred_type = built_in.return_type().into();
+                    Ok(())
+                } else {
+                    Err(FunctionArgsTypeInferenceError::ArgumentSizeMisMatch {
+                        function_type_internal: function_name,
+                        expected: parameter_types.len(),
+                        provided: args.len(),
+                    }
+                    .to_string())
+                }
+            }
         }
     }
 
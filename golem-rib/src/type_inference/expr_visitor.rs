@@ -73,6 +73,13 @@ pub fn visit_children_bottom_up_mut<'a>(expr: &'a mut Expr, queue: &mut VecDeque
             queue.push_back(&mut *exr);
         }
 
+        Expr::OptionalSelectField(expr, _, _) => queue.push_back(&mut *expr),
+
+        Expr::Coalesce(expr, default, _) => {
+            queue.push_back(&mut *expr);
+            queue.push_back(&mut *default)
+        }
+
         Expr::Literal(_, _) => {}
         Expr::Number(_, _, _) => {}
         Expr::Flags(_, _) => {}
@@ -150,6 +157,11 @@ pub fn visit_children_bottom_up<'a>(expr: &'a Expr, queue: &mut VecDeque<&'a Exp
         Expr::GetTag(expr, _) => {
             queue.push_back(expr);
         }
+        Expr::OptionalSelectField(expr, _, _) => queue.push_back(expr),
+        Expr::Coalesce(expr, default, _) => {
+            queue.push_back(expr);
+            queue.push_back(default);
+        }
 
         Expr::Literal(_, _) => {}
         Expr::Number(_, _, _) => {}
@@ -251,6 +263,11 @@ pub fn visit_children_mut_top_down<'a>(expr: &'a mut Expr, queue: &mut VecDeque<
         Expr::GetTag(expr, _) => {
             queue.push_front(&mut *expr);
         }
+        Expr::OptionalSelectField(expr, _, _) => queue.push_front(&mut *expr),
+        Expr::Coalesce(expr, default, _) => {
+            queue.push_front(&mut *expr);
+            queue.push_front(&mut *default)
+        }
 
         Expr::Unwrap(expr, _) => queue.push_front(&mut *expr),
         Expr::Literal(_, _) => {}
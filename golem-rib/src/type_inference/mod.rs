@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub use built_in_resolution::*;
 pub use call_arguments_inference::*;
 pub use enum_resolution::*;
 pub use expr_visitor::*;
@@ -19,6 +20,7 @@ pub use global_input_inference::*;
 pub use identifier_inference::*;
 pub use inference_fix_point::*;
 pub use name_binding::*;
+pub use numeric_literal_inference::*;
 pub use pattern_match_binding::*;
 pub use rib_input_type::*;
 pub(crate) use type_binding::*;
@@ -29,10 +31,12 @@ pub use type_reset::*;
 pub use type_unification::*;
 pub use variant_resolution::*;
 
+mod built_in_resolution;
 mod call_arguments_inference;
 mod expr_visitor;
 mod identifier_inference;
 mod name_binding;
+mod numeric_literal_inference;
 mod pattern_match_binding;
 mod rib_input_type;
 mod type_check;
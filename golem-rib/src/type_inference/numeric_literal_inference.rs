@@ -0,0 +1,254 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::Expr;
+
+// A let binding of a numeric literal without any type annotation
+// (example: `let x = 1;`) can be used at different numeric types, such as
+// passing `x` to a function expecting a u32 and to another one expecting a u64.
+// To allow this, every usage of such a variable used more than once is replaced
+// with the literal itself, so that each usage gets its own type.
+// This has to run after the local variables are given variable ids.
+pub fn inline_numeric_let_bindings(expr: &mut Expr) {
+    let numeric_let_bindings = internal::get_numeric_let_bindings(expr);
+
+    if !numeric_let_bindings.is_empty() {
+        internal::inline_identifiers(expr, &numeric_let_bindings);
+    }
+}
+
+// Numeric literals whose type couldn't be inferred even after the type inference
+// reached its fix point are given a default type. A literal compared against a value
+// of a known numeric type takes that type, otherwise integers default to u64
+// (s64 if negative) and numbers with a fractional part default to f64,
+// as long as the default is one of the types still possible for the literal.
+// Returns true if any literal got a default type.
+pub fn default_numeric_literals(expr: &mut Expr) -> bool {
+    let mut defaulted = internal::unify_compared_numeric_literals(expr);
+    defaulted |= internal::default_remaining_numeric_literals(expr);
+    defaulted
+}
+
+mod internal {
+    use crate::{Expr, InferredType, Number, VariableId};
+    use std::collections::{HashMap, VecDeque};
+
+    pub(crate) fn get_numeric_let_bindings(expr: &Expr) -> HashMap<VariableId, Expr> {
+        let mut numeric_literals = HashMap::new();
+        let mut usages: HashMap<VariableId, usize> = HashMap::new();
+
+        let mut queue = VecDeque::new();
+        queue.push_back(expr);
+
+        while let Some(expr) = queue.pop_back() {
+            match expr {
+                Expr::Let(variable_id, None, rhs, _) => {
+                    if let Expr::Number(_, None, _) = rhs.as_ref() {
+                        numeric_literals.insert(variable_id.clone(), rhs.as_ref().clone());
+                    }
+                    queue.push_back(rhs);
+                }
+                Expr::Identifier(variable_id, _) => {
+                    *usages.entry(variable_id.clone()).or_insert(0) += 1;
+                }
+                _ => expr.visit_children_bottom_up(&mut queue),
+            }
+        }
+
+        numeric_literals
+            .into_iter()
+            .filter(|(variable_id, _)| usages.get(variable_id).copied().unwrap_or(0) > 1)
+            .collect()
+    }
+
+    pub(crate) fn inline_identifiers(expr: &mut Expr, literals: &HashMap<VariableId, Expr>) {
+        let mut queue = VecDeque::new();
+        queue.push_back(expr);
+
+        while let Some(expr) = queue.pop_back() {
+            match expr {
+                Expr::Identifier(variable_id, _) => {
+                    if let Some(literal) = literals.get(variable_id) {
+                        *expr = literal.clone();
+                    }
+                }
+                _ => expr.visit_children_mut_bottom_up(&mut queue),
+            }
+        }
+    }
+
+    pub(crate) fn unify_compared_numeric_literals(expr: &mut Expr) -> bool {
+        let mut unified = false;
+        let mut queue = VecDeque::new();
+        queue.push_back(expr);
+
+        while let Some(expr) = queue.pop_back() {
+            match expr {
+                Expr::GreaterThan(lhs, rhs, _)
+                | Expr::GreaterThanOrEqualTo(lhs, rhs, _)
+                | Expr::LessThan(lhs, rhs, _)
+                | Expr::LessThanOrEqualTo(lhs, rhs, _)
+                | Expr::EqualTo(lhs, rhs, _) => {
+                    unified |= unify_with_operand(lhs, &rhs.inferred_type());
+                    unified |= unify_with_operand(rhs, &lhs.inferred_type());
+                    queue.push_back(lhs);
+                    queue.push_back(rhs);
+                }
+                _ => expr.visit_children_mut_bottom_up(&mut queue),
+            }
+        }
+
+        unified
+    }
+
+    pub(crate) fn default_remaining_numeric_literals(expr: &mut Expr) -> bool {
+        let mut defaulted = false;
+        let mut queue = VecDeque::new();
+        queue.push_back(expr);
+
+        while let Some(expr) = queue.pop_back() {
+            match expr {
+                Expr::Number(number, _, inferred_type) => {
+                    if let Some(candidates) = ambiguous_numeric_types(inferred_type) {
+                        if let Some(default) = default_numeric_type(number, &candidates) {
+                            *inferred_type = default;
+                            defaulted = true;
+                        }
+                    }
+                }
+                _ => expr.visit_children_mut_bottom_up(&mut queue),
+            }
+        }
+
+        defaulted
+    }
+
+    fn unify_with_operand(expr: &mut Expr, operand_type: &InferredType) -> bool {
+        if let Expr::Number(_, _, inferred_type) = expr {
+            if let Some(candidates) = ambiguous_numeric_types(inferred_type) {
+                if let Ok(operand_type) = operand_type.unify_types() {
+                    if candidates.contains(&operand_type) {
+                        *inferred_type = operand_type;
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    // The numeric types a literal can still have, if there are more than one
+    fn ambiguous_numeric_types(inferred_type: &InferredType) -> Option<Vec<InferredType>> {
+        match inferred_type.unify_types() {
+            Ok(InferredType::OneOf(types)) if types.iter().all(|typ| typ.is_number()) => {
+                Some(types)
+            }
+            _ => None,
+        }
+    }
+
+    fn default_numeric_type(number: &Number, candidates: &[InferredType]) -> Option<InferredType> {
+        let preferences = if number.value.fract() != 0.0 {
+            vec![InferredType::F64, InferredType::F32]
+        } else if number.value < 0.0 {
+            vec![
+                InferredType::S64,
+                InferredType::S32,
+                InferredType::S16,
+                InferredType::S8,
+                InferredType::F64,
+                InferredType::F32,
+            ]
+        } else {
+            vec![
+                InferredType::U64,
+                InferredType::S64,
+                InferredType::U32,
+                InferredType::S32,
+                InferredType::U16,
+                InferredType::S16,
+                InferredType::U8,
+                InferredType::S8,
+                InferredType::F64,
+                InferredType::F32,
+            ]
+        };
+
+        preferences
+            .into_iter()
+            .find(|preference| candidates.contains(preference))
+    }
+}
+
+#[cfg(test)]
+mod numeric_literal_inference_tests {
+    use test_r::test;
+
+    use crate::{compiler, Expr, FunctionTypeRegistry, InferredType};
+    use golem_wasm_ast::analysis::analysed_type::{u32, u64};
+    use golem_wasm_ast::analysis::{
+        AnalysedExport, AnalysedFunction, AnalysedFunctionParameter, AnalysedType,
+    };
+
+    fn function(name: &str, parameter_type: AnalysedType) -> AnalysedExport {
+        AnalysedExport::Function(AnalysedFunction {
+            name: name.to_string(),
+            parameters: vec![AnalysedFunctionParameter {
+                name: "param".to_string(),
+                typ: parameter_type,
+            }],
+            results: vec![],
+        })
+    }
+
+    #[test]
+    fn test_numeric_let_binding_used_at_multiple_types() {
+        let expr = Expr::from_text(
+            r#"
+              let x = 1;
+              foo(x);
+              bar(x)
+            "#,
+        )
+        .unwrap();
+
+        let exports = vec![function("foo", u32()), function("bar", u64())];
+
+        assert!(compiler::compile(&expr, &exports).is_ok());
+    }
+
+    #[test]
+    fn test_numeric_literal_defaults() {
+        let mut expr = Expr::from_text(
+            r#"
+              let x = 1;
+              let y = -1;
+              let z = 1.5;
+              { x: x, y: y, z: z }
+            "#,
+        )
+        .unwrap();
+
+        expr.infer_types(&FunctionTypeRegistry::empty()).unwrap();
+
+        assert_eq!(
+            expr.inferred_type(),
+            InferredType::Record(vec![
+                ("x".to_string(), InferredType::U64),
+                ("y".to_string(), InferredType::S64),
+                ("z".to_string(), InferredType::F64),
+            ])
+        );
+    }
+}
@@ -70,6 +70,8 @@ mod internal {
             | Expr::Unwrap(_, inferred_type)
             | Expr::Throw(_, inferred_type)
             | Expr::GetTag(_, inferred_type)
+            | Expr::OptionalSelectField(_, _, inferred_type)
+            | Expr::Coalesce(_, _, inferred_type)
             | Expr::And(_, _, inferred_type)
             | Expr::Or(_, _, inferred_type)
             | Expr::Call(_, _, inferred_type) => {
@@ -217,6 +217,24 @@ pub fn type_pull_up(expr: &Expr) -> Result<Expr, String> {
             Expr::GetTag(_, inferred_type) => {
                 internal::handle_get_tag(expr, inferred_type, &mut inferred_type_stack);
             }
+
+            Expr::OptionalSelectField(expr, field, current_inferred_type) => {
+                internal::handle_optional_select_field(
+                    expr,
+                    field,
+                    current_inferred_type,
+                    &mut inferred_type_stack,
+                )?;
+            }
+
+            Expr::Coalesce(expr, default, current_inferred_type) => {
+                internal::handle_coalesce(
+                    expr,
+                    default,
+                    current_inferred_type,
+                    &mut inferred_type_stack,
+                );
+            }
         }
     }
 
@@ -611,6 +629,15 @@ mod internal {
                 );
                 inferred_type_stack.push_front(new_call);
             }
+
+            CallType::BuiltIn(built_in) => {
+                let new_call = Expr::Call(
+                    CallType::BuiltIn(*built_in),
+                    new_arg_exprs,
+                    inferred_type.clone(),
+                );
+                inferred_type_stack.push_front(new_call);
+            }
         }
     }
 
@@ -640,6 +667,73 @@ mod internal {
         inferred_type_stack.push_front(new_get_tag);
     }
 
+    pub(crate) fn handle_optional_select_field(
+        original_selection_expr: &Expr,
+        field: &str,
+        current_inferred_type: &InferredType,
+        inferred_type_stack: &mut VecDeque<Expr>,
+    ) -> Result<(), String> {
+        let expr = inferred_type_stack
+            .pop_front()
+            .unwrap_or(original_selection_expr.clone());
+
+        let new_type = match expr.inferred_type() {
+            InferredType::Option(inner) if !inner.is_unknown() => {
+                let field_type = get_inferred_type_of_selected_field(field, &inner)?;
+                InferredType::Option(Box::new(field_type))
+            }
+            InferredType::Result {
+                ok: Some(ok),
+                error,
+            } if !ok.is_unknown() => {
+                let field_type = get_inferred_type_of_selected_field(field, &ok)?;
+                InferredType::Result {
+                    ok: Some(Box::new(field_type)),
+                    error,
+                }
+            }
+            _ => InferredType::Unknown,
+        };
+
+        let new_optional_select_field = Expr::OptionalSelectField(
+            Box::new(expr),
+            field.to_string(),
+            current_inferred_type.merge(new_type),
+        );
+
+        inferred_type_stack.push_front(new_optional_select_field);
+
+        Ok(())
+    }
+
+    pub(crate) fn handle_coalesce(
+        original_expr: &Expr,
+        original_default_expr: &Expr,
+        current_inferred_type: &InferredType,
+        inferred_type_stack: &mut VecDeque<Expr>,
+    ) {
+        let default_expr = inferred_type_stack
+            .pop_front()
+            .unwrap_or(original_default_expr.clone());
+        let expr = inferred_type_stack
+            .pop_front()
+            .unwrap_or(original_expr.clone());
+
+        let inner_type = match expr.inferred_type() {
+            InferredType::Option(inner) => *inner,
+            InferredType::Result { ok: Some(ok), .. } => *ok,
+            _ => InferredType::Unknown,
+        };
+
+        let new_type = current_inferred_type
+            .merge(inner_type)
+            .merge(default_expr.inferred_type());
+
+        let new_coalesce = Expr::Coalesce(Box::new(expr), Box::new(default_expr), new_type);
+
+        inferred_type_stack.push_front(new_coalesce);
+    }
+
     pub(crate) fn handle_let(
         original_variable_id: &VariableId,
         original_expr: &Expr,
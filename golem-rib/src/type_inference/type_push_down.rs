@@ -92,6 +92,17 @@ pub fn push_types_down(expr: &mut Expr) -> Result<(), String> {
                 internal::handle_call(call_type, expressions, inferred_type, &mut queue);
             }
 
+            Expr::OptionalSelectField(expr, field, inferred_type) => {
+                internal::handle_optional_select_field(expr, field, inferred_type);
+                queue.push_back(expr);
+            }
+
+            Expr::Coalesce(expr, default, inferred_type) => {
+                internal::handle_coalesce(expr, default, inferred_type);
+                queue.push_back(expr);
+                queue.push_back(default);
+            }
+
             _ => expr.visit_children_mut_bottom_up(&mut queue),
         }
     }
@@ -105,6 +116,7 @@ mod internal {
     use crate::type_refinement::TypeRefinement;
     use crate::{ArmPattern, Expr, InferredType};
     use std::collections::VecDeque;
+    use std::ops::Deref;
 
     pub(crate) fn handle_option(
         inner_expr: &mut Expr,
@@ -144,6 +156,63 @@ mod internal {
         Ok(())
     }
 
+    // `expr?.field` of type `option<t>` or `result<t, e>` implies `expr` is an option
+    // or result of a record having `field` of type `t`
+    pub(crate) fn handle_optional_select_field(
+        inner_expr: &mut Expr,
+        field: &str,
+        outer_inferred_type: &InferredType,
+    ) {
+        match outer_inferred_type {
+            InferredType::Option(field_type) => {
+                let record_type =
+                    InferredType::Record(vec![(field.to_string(), field_type.deref().clone())]);
+                inner_expr.add_infer_type_mut(InferredType::Option(Box::new(record_type)));
+            }
+            InferredType::Result {
+                ok: Some(field_type),
+                error,
+            } => {
+                let record_type =
+                    InferredType::Record(vec![(field.to_string(), field_type.deref().clone())]);
+                inner_expr.add_infer_type_mut(InferredType::Result {
+                    ok: Some(Box::new(record_type)),
+                    error: error.clone(),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    // The default of `expr ?? default` has the type of the whole expression, and so has
+    // the value inside `expr` if it is already known to be an option or a result
+    pub(crate) fn handle_coalesce(
+        inner_expr: &mut Expr,
+        default_expr: &mut Expr,
+        outer_inferred_type: &InferredType,
+    ) {
+        if outer_inferred_type.is_unknown() {
+            return;
+        }
+
+        default_expr.add_infer_type_mut(outer_inferred_type.clone());
+
+        match inner_expr.inferred_type() {
+            InferredType::Option(_) => {
+                inner_expr.add_infer_type_mut(InferredType::Option(Box::new(
+                    outer_inferred_type.clone(),
+                )));
+            }
+            InferredType::Result { error, .. } => {
+                inner_expr.add_infer_type_mut(InferredType::Result {
+                    ok: Some(Box::new(outer_inferred_type.clone())),
+                    error,
+                });
+            }
+            _ => {}
+        }
+    }
+
     pub(crate) fn handle_sequence<'a>(
         inner_expressions: &'a mut [Expr],
         outer_inferred_type: &InferredType,
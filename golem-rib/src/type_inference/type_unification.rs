@@ -321,6 +321,33 @@ pub fn unify_types(expr: &mut Expr) -> Result<(), Vec<String>> {
                 }
             }
 
+            Expr::OptionalSelectField(expr, _, inferred_type) => {
+                queue.push(expr);
+                let unified_inferred_type = inferred_type.unify_types_and_verify();
+
+                match unified_inferred_type {
+                    Ok(unified_type) => *inferred_type = unified_type,
+                    Err(e) => {
+                        errors.push(format!("Unable to resolve the type of {}", expr_str));
+                        errors.extend(e);
+                    }
+                }
+            }
+
+            Expr::Coalesce(expr, default, inferred_type) => {
+                queue.push(expr);
+                queue.push(default);
+                let unified_inferred_type = inferred_type.unify_types_and_verify();
+
+                match unified_inferred_type {
+                    Ok(unified_type) => *inferred_type = unified_type,
+                    Err(e) => {
+                        errors.push(format!("Unable to resolve the type of {}", expr_str));
+                        errors.extend(e);
+                    }
+                }
+            }
+
             Expr::GreaterThan(left, right, _) => {
                 queue.push(left);
                 queue.push(right);
@@ -1,5 +1,6 @@
 use std::fmt::{Display, Formatter};
 
+use golem_common::model::auth::{HasTokenScope, TokenScope};
 use serde::Deserialize;
 
 #[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
@@ -11,6 +12,12 @@ impl Display for EmptyAuthCtx {
     }
 }
 
+impl HasTokenScope for EmptyAuthCtx {
+    fn token_scope(&self) -> TokenScope {
+        TokenScope::unrestricted()
+    }
+}
+
 impl IntoIterator for EmptyAuthCtx {
     type Item = (String, String);
     type IntoIter = std::iter::Empty<Self::Item>;
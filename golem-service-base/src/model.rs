@@ -13,24 +13,34 @@
 // limitations under the License.
 
 use bincode::{Decode, Encode};
+use golem_common::model::component_compatibility::ComponentCompatibilityReport;
 use golem_common::model::component_metadata::ComponentMetadata;
+use golem_common::model::error::{ErrorCode, ProblemDetails};
 use golem_common::model::public_oplog::{OplogCursor, PublicOplogEntry};
+use golem_common::model::regions::CorruptedOplogRegion;
 use golem_common::model::{
-    ComponentId, ComponentType, ComponentVersion, PromiseId, ScanCursor, ShardId, Timestamp,
-    WorkerFilter, WorkerId, WorkerStatus,
+    ComponentId, ComponentType, ComponentVersion, IdempotencyKey, LastInvokedFunction, ProjectId,
+    PromiseId, ScanCursor, ShardId, Timestamp, WorkerFilter, WorkerId, WorkerStatus,
 };
 use golem_common::SafeDisplay;
 use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
+use poem_openapi::types::{Base64, ParseFromParameter, ParseResult};
 use poem_openapi::{Enum, NewType, Object, Union};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::str::FromStr;
 use std::time::SystemTime;
 use std::{collections::HashMap, fmt::Display, fmt::Formatter};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
 pub struct WorkerCreationRequest {
     pub name: String,
     pub args: Vec<String>,
     pub env: HashMap<String, String>,
+    /// Creates the worker with the version the alias points to instead of the latest version
+    pub component_version_alias: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
@@ -107,21 +117,70 @@ impl std::fmt::Display for VersionedComponentId {
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
 pub struct Empty {}
 
-pub fn validate_worker_name(name: &str) -> Result<(), &'static str> {
-    let length = name.len();
-    if !(1..=100).contains(&length) {
-        Err("Worker name must be between 1 and 100 characters")
-    } else if name.contains(' ') {
-        Err("Worker name must not contain spaces")
-    } else if !name
-        .chars()
-        .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
-    {
-        Err("Worker name must contain only alphanumeric characters, underscores, and dashes")
-    } else if name.starts_with('-') {
-        Err("Worker name must not start with a dash")
-    } else {
-        Ok(())
+pub fn validate_component_labels(labels: &BTreeMap<String, String>) -> Result<(), String> {
+    for (key, value) in labels {
+        if !(1..=100).contains(&key.len()) {
+            return Err(format!(
+                "Label key {key} must be between 1 and 100 characters"
+            ));
+        } else if key.contains('=') || key.contains(char::is_whitespace) {
+            return Err(format!(
+                "Label key {key} must not contain whitespace or '='"
+            ));
+        } else if value.len() > 256 {
+            return Err(format!(
+                "Value of label {key} must not be longer than 256 characters"
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Selects components by one of their labels, parsed from `key` or `key=value`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentLabelFilter {
+    pub key: String,
+    /// If not set, any component having the label matches
+    pub value: Option<String>,
+}
+
+impl ComponentLabelFilter {
+    pub fn matches(&self, labels: &BTreeMap<String, String>) -> bool {
+        match (labels.get(&self.key), &self.value) {
+            (Some(value), Some(expected)) => value == expected,
+            (Some(_), None) => true,
+            (None, _) => false,
+        }
+    }
+}
+
+impl FromStr for ComponentLabelFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (key, value) = match s.split_once('=') {
+            Some((key, value)) => (key.trim(), Some(value.trim().to_string())),
+            None => (s.trim(), None),
+        };
+        if key.is_empty() {
+            Err(format!(
+                "Invalid label filter: {s} - expected key or key=value"
+            ))
+        } else {
+            Ok(Self {
+                key: key.to_string(),
+                value,
+            })
+        }
+    }
+}
+
+impl Display for ComponentLabelFilter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.value {
+            Some(value) => write!(f, "{}={}", self.key, value),
+            None => write!(f, "{}", self.key),
+        }
     }
 }
 
@@ -958,201 +1017,1015 @@ impl From<crate::model::GolemErrorShardingNotReady>
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
-pub struct InvokeParameters {
-    pub params: Vec<TypeAnnotatedValue>,
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object, thiserror::Error)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+#[error("Too many pending invocations for worker {worker_id}: {pending_invocations}")]
+pub struct GolemErrorTooManyPendingInvocations {
+    pub worker_id: WorkerId,
+    pub pending_invocations: u64,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize, Object)]
-pub struct DeleteWorkerResponse {}
-
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize, Object)]
-pub struct InvokeResponse {}
+impl SafeDisplay for GolemErrorTooManyPendingInvocations {
+    fn to_safe_string(&self) -> String {
+        self.to_string()
+    }
+}
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize, Object)]
-pub struct InterruptResponse {}
+impl TryFrom<golem_api_grpc::proto::golem::worker::v1::TooManyPendingInvocations>
+    for GolemErrorTooManyPendingInvocations
+{
+    type Error = String;
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize, Object)]
-pub struct ResumeResponse {}
+    fn try_from(
+        value: golem_api_grpc::proto::golem::worker::v1::TooManyPendingInvocations,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self {
+            worker_id: value
+                .worker_id
+                .ok_or("Missing field: worker_id")?
+                .try_into()?,
+            pending_invocations: value.pending_invocations,
+        })
+    }
+}
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize, Object)]
-pub struct UpdateWorkerResponse {}
+impl From<GolemErrorTooManyPendingInvocations>
+    for golem_api_grpc::proto::golem::worker::v1::TooManyPendingInvocations
+{
+    fn from(value: GolemErrorTooManyPendingInvocations) -> Self {
+        Self {
+            worker_id: Some(value.worker_id.into()),
+            pending_invocations: value.pending_invocations,
+        }
+    }
+}
 
-#[derive(Debug, Clone, Serialize, Deserialize, Object)]
-pub struct GetOplogResponse {
-    pub entries: Vec<PublicOplogEntry>,
-    pub next: Option<OplogCursor>,
-    pub first_index_in_chunk: u64,
-    pub last_index: u64,
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object, thiserror::Error)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+#[error("Invocation of worker {worker_id} timed out after {timeout_millis} ms")]
+pub struct GolemErrorInvocationTimedOut {
+    pub worker_id: WorkerId,
+    pub timeout_millis: u64,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Enum)]
-pub enum WorkerUpdateMode {
-    Automatic,
-    Manual,
+impl SafeDisplay for GolemErrorInvocationTimedOut {
+    fn to_safe_string(&self) -> String {
+        self.to_string()
+    }
 }
 
-impl From<golem_api_grpc::proto::golem::worker::UpdateMode> for WorkerUpdateMode {
-    fn from(value: golem_api_grpc::proto::golem::worker::UpdateMode) -> Self {
-        match value {
-            golem_api_grpc::proto::golem::worker::UpdateMode::Automatic => {
-                WorkerUpdateMode::Automatic
-            }
-            golem_api_grpc::proto::golem::worker::UpdateMode::Manual => WorkerUpdateMode::Manual,
-        }
+impl TryFrom<golem_api_grpc::proto::golem::worker::v1::InvocationTimedOut>
+    for GolemErrorInvocationTimedOut
+{
+    type Error = String;
+
+    fn try_from(
+        value: golem_api_grpc::proto::golem::worker::v1::InvocationTimedOut,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self {
+            worker_id: value
+                .worker_id
+                .ok_or("Missing field: worker_id")?
+                .try_into()?,
+            timeout_millis: value.timeout_millis,
+        })
     }
 }
 
-impl From<WorkerUpdateMode> for golem_api_grpc::proto::golem::worker::UpdateMode {
-    fn from(value: WorkerUpdateMode) -> Self {
-        match value {
-            WorkerUpdateMode::Automatic => {
-                golem_api_grpc::proto::golem::worker::UpdateMode::Automatic
-            }
-            WorkerUpdateMode::Manual => golem_api_grpc::proto::golem::worker::UpdateMode::Manual,
+impl From<GolemErrorInvocationTimedOut>
+    for golem_api_grpc::proto::golem::worker::v1::InvocationTimedOut
+{
+    fn from(value: GolemErrorInvocationTimedOut) -> Self {
+        Self {
+            worker_id: Some(value.worker_id.into()),
+            timeout_millis: value.timeout_millis,
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object, thiserror::Error)]
 #[serde(rename_all = "camelCase")]
 #[oai(rename_all = "camelCase")]
-pub struct UpdateWorkerRequest {
-    pub mode: WorkerUpdateMode,
-    pub target_version: ComponentVersion,
+#[error(
+    "Worker executor is busy, already running {max_active_workers} active workers, retry later"
+)]
+pub struct GolemErrorExecutorBusy {
+    pub max_active_workers: u64,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
-pub struct WorkersMetadataRequest {
-    pub filter: Option<WorkerFilter>,
-    pub cursor: Option<ScanCursor>,
-    pub count: Option<u64>,
-    pub precise: Option<bool>,
+impl SafeDisplay for GolemErrorExecutorBusy {
+    fn to_safe_string(&self) -> String {
+        self.to_string()
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
-pub struct WorkersMetadataResponse {
-    pub workers: Vec<WorkerMetadata>,
-    pub cursor: Option<ScanCursor>,
+impl From<golem_api_grpc::proto::golem::worker::v1::ExecutorBusy> for GolemErrorExecutorBusy {
+    fn from(value: golem_api_grpc::proto::golem::worker::v1::ExecutorBusy) -> Self {
+        Self {
+            max_active_workers: value.max_active_workers,
+        }
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+impl From<GolemErrorExecutorBusy> for golem_api_grpc::proto::golem::worker::v1::ExecutorBusy {
+    fn from(value: GolemErrorExecutorBusy) -> Self {
+        Self {
+            max_active_workers: value.max_active_workers,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object, thiserror::Error)]
 #[serde(rename_all = "camelCase")]
 #[oai(rename_all = "camelCase")]
-pub struct WorkerMetadata {
+#[error("No invocation with idempotency key {idempotency_key} was found for worker {worker_id}")]
+pub struct GolemErrorInvocationNotFound {
     pub worker_id: WorkerId,
-    pub args: Vec<String>,
-    pub env: HashMap<String, String>,
-    pub status: WorkerStatus,
-    pub component_version: ComponentVersion,
-    pub retry_count: u64,
-    pub pending_invocation_count: u64,
-    pub updates: Vec<UpdateRecord>,
-    pub created_at: Timestamp,
-    pub last_error: Option<String>,
-    pub component_size: u64,
-    pub total_linear_memory_size: u64,
-    pub owned_resources: HashMap<u64, ResourceMetadata>,
+    pub idempotency_key: IdempotencyKey,
 }
 
-impl TryFrom<golem_api_grpc::proto::golem::worker::WorkerMetadata> for WorkerMetadata {
+impl SafeDisplay for GolemErrorInvocationNotFound {
+    fn to_safe_string(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl TryFrom<golem_api_grpc::proto::golem::worker::v1::InvocationNotFound>
+    for GolemErrorInvocationNotFound
+{
     type Error = String;
 
     fn try_from(
-        value: golem_api_grpc::proto::golem::worker::WorkerMetadata,
+        value: golem_api_grpc::proto::golem::worker::v1::InvocationNotFound,
     ) -> Result<Self, Self::Error> {
         Ok(Self {
-            worker_id: value.worker_id.ok_or("Missing worker_id")?.try_into()?,
-            args: value.args,
-            env: value.env,
-            status: value.status.try_into()?,
-            component_version: value.component_version,
-            retry_count: value.retry_count,
-            pending_invocation_count: value.pending_invocation_count,
-            updates: value
-                .updates
-                .into_iter()
-                .map(|update| update.try_into())
-                .collect::<Result<Vec<UpdateRecord>, String>>()?,
-            created_at: value.created_at.ok_or("Missing created_at")?.into(),
-            last_error: value.last_error,
-            component_size: value.component_size,
-            total_linear_memory_size: value.total_linear_memory_size,
-            owned_resources: value
-                .owned_resources
-                .into_iter()
-                .map(|(k, v)| v.try_into().map(|v| (k, v)))
-                .collect::<Result<HashMap<_, _>, _>>()?,
+            worker_id: value
+                .worker_id
+                .ok_or("Missing field: worker_id")?
+                .try_into()?,
+            idempotency_key: value
+                .idempotency_key
+                .ok_or("Missing field: idempotency_key")?
+                .into(),
         })
     }
 }
 
-impl From<WorkerMetadata> for golem_api_grpc::proto::golem::worker::WorkerMetadata {
-    fn from(value: WorkerMetadata) -> Self {
+impl From<GolemErrorInvocationNotFound>
+    for golem_api_grpc::proto::golem::worker::v1::InvocationNotFound
+{
+    fn from(value: GolemErrorInvocationNotFound) -> Self {
         Self {
             worker_id: Some(value.worker_id.into()),
-            account_id: Some(golem_api_grpc::proto::golem::common::AccountId {
-                name: "-1".to_string(),
-            }),
-            args: value.args,
-            env: value.env,
-            status: value.status.into(),
-            component_version: value.component_version,
-            retry_count: value.retry_count,
-            pending_invocation_count: value.pending_invocation_count,
-            updates: value.updates.iter().cloned().map(|u| u.into()).collect(),
-            created_at: Some(value.created_at.into()),
-            last_error: value.last_error,
-            component_size: value.component_size,
-            total_linear_memory_size: value.total_linear_memory_size,
-            owned_resources: value
-                .owned_resources
-                .into_iter()
-                .map(|(k, v)| (k, v.into()))
-                .collect(),
+            idempotency_key: Some(value.idempotency_key.into()),
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Union)]
-#[serde(rename_all = "camelCase")]
-#[oai(discriminator_name = "type", one_of = true, rename_all = "camelCase")]
-pub enum UpdateRecord {
-    PendingUpdate(PendingUpdate),
-    SuccessfulUpdate(SuccessfulUpdate),
-    FailedUpdate(FailedUpdate),
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+pub struct InvokeParameters {
+    pub params: Vec<TypeAnnotatedValue>,
+    /// Key-value pairs available to the worker during the invocation, such as the locale or the
+    /// tenant of the request
+    pub context: Option<HashMap<String, String>>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
-#[serde(rename_all = "camelCase")]
-#[oai(rename_all = "camelCase")]
-pub struct PendingUpdate {
-    timestamp: Timestamp,
-    target_version: ComponentVersion,
+/// Parameters of an invocation given as plain JSON values, converted to the parameter types of
+/// the invoked function by the worker service
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+pub struct InvokeJsonParameters {
+    pub params: Vec<serde_json::Value>,
+    /// Key-value pairs available to the worker during the invocation, such as the locale or the
+    /// tenant of the request
+    pub context: Option<HashMap<String, String>>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize, Object)]
+pub struct DeleteWorkerResponse {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize, Object)]
+pub struct InvokeResponse {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize, Object)]
+pub struct InterruptResponse {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize, Object)]
+pub struct ResumeResponse {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize, Object)]
+pub struct UpdateWorkerResponse {}
+
+/// The outcome of checking whether a manual update of a worker could load a snapshot of its current state
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize, Object)]
 #[serde(rename_all = "camelCase")]
 #[oai(rename_all = "camelCase")]
-pub struct SuccessfulUpdate {
-    timestamp: Timestamp,
-    target_version: ComponentVersion,
+pub struct DryRunUpdateWorkerResponse {
+    pub would_succeed: bool,
+    /// The reason why the update would fail
+    pub error: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize, Object)]
+pub struct PendingInvocationCountResponse {
+    pub pending_invocations: u64,
+}
+
+/// An entry of the key-value store shared by all the workers of an account
+#[derive(Debug, Clone, Object)]
+pub struct SharedKeyValueEntry {
+    pub value: Base64<Vec<u8>>,
+    /// Starts at 1 and is incremented by every update. Deleting the entry resets it.
+    pub version: u64,
+}
+
+#[derive(Debug, Clone, Object)]
 #[oai(rename_all = "camelCase")]
-pub struct FailedUpdate {
-    timestamp: Timestamp,
-    target_version: ComponentVersion,
-    details: Option<String>,
+pub struct SetSharedValueRequest {
+    pub value: Base64<Vec<u8>>,
+    /// If set, the value is only updated if the current version of the entry matches,
+    /// where version 0 expects the entry to not exist
+    pub expected_version: Option<u64>,
 }
 
-impl TryFrom<golem_api_grpc::proto::golem::worker::UpdateRecord> for UpdateRecord {
-    type Error = String;
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize, Object)]
+pub struct SetSharedValueResponse {
+    pub version: u64,
+}
 
-    fn try_from(
-        value: golem_api_grpc::proto::golem::worker::UpdateRecord,
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize, Object)]
+pub struct DeleteSharedValueResponse {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize, Object)]
+pub struct SharedKeysResponse {
+    pub keys: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize, Object)]
+pub struct TopicsResponse {
+    pub topics: Vec<String>,
+}
+
+/// A publish/subscribe topic shared by the workers of an account
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct TopicInfo {
+    pub name: String,
+    /// Sequence number of the last published message, 0 if nothing has been published yet
+    pub last_sequence: u64,
+    /// Number of messages kept because not all subscribers acknowledged them yet
+    pub retained_messages: u64,
+    pub subscriptions: Vec<TopicSubscriptionInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct TopicSubscriptionInfo {
+    pub worker_id: WorkerId,
+    pub handler: String,
+    /// Sequence number of the last message delivered to the subscriber
+    pub acknowledged_sequence: u64,
+    /// Number of published messages not delivered to the subscriber yet
+    pub lag: u64,
+}
+
+impl TryFrom<golem_api_grpc::proto::golem::workerexecutor::v1::Topic> for TopicInfo {
+    type Error = String;
+
+    fn try_from(
+        value: golem_api_grpc::proto::golem::workerexecutor::v1::Topic,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self {
+            name: value.name,
+            last_sequence: value.last_sequence,
+            retained_messages: value.retained_messages,
+            subscriptions: value
+                .subscriptions
+                .into_iter()
+                .map(|subscription| subscription.try_into())
+                .collect::<Result<Vec<_>, _>>()?,
+        })
+    }
+}
+
+impl TryFrom<golem_api_grpc::proto::golem::workerexecutor::v1::TopicSubscription>
+    for TopicSubscriptionInfo
+{
+    type Error = String;
+
+    fn try_from(
+        value: golem_api_grpc::proto::golem::workerexecutor::v1::TopicSubscription,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self {
+            worker_id: value.worker_id.ok_or("Missing worker_id")?.try_into()?,
+            handler: value.handler,
+            acknowledged_sequence: value.acknowledged_sequence,
+            lag: value.lag,
+        })
+    }
+}
+
+/// Resource usage of the workers of an account
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct AccountUsage {
+    /// Number of started invocations
+    pub invocations: u64,
+    /// Total linear memory of the workers in GB multiplied by the duration of their invocations
+    /// in seconds
+    pub memory_gb_seconds: f64,
+    /// Size of the entries added to the oplogs of the workers in bytes
+    pub oplog_bytes: u64,
+}
+
+impl AccountUsage {
+    pub fn add(&mut self, other: &AccountUsage) {
+        self.invocations += other.invocations;
+        self.memory_gb_seconds += other.memory_gb_seconds;
+        self.oplog_bytes += other.oplog_bytes;
+    }
+}
+
+/// The usage of an account in a billing period
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct AccountUsageRecord {
+    pub period_start: Timestamp,
+    pub period_end: Timestamp,
+    pub usage: AccountUsage,
+}
+
+impl TryFrom<golem_api_grpc::proto::golem::workerexecutor::v1::UsageRecord> for AccountUsageRecord {
+    type Error = String;
+
+    fn try_from(
+        value: golem_api_grpc::proto::golem::workerexecutor::v1::UsageRecord,
+    ) -> Result<Self, Self::Error> {
+        const GB: f64 = (1024 * 1024 * 1024) as f64;
+
+        Ok(Self {
+            period_start: value.period_start.ok_or("Missing period_start")?.into(),
+            period_end: value.period_end.ok_or("Missing period_end")?.into(),
+            usage: AccountUsage {
+                invocations: value.invocations,
+                memory_gb_seconds: value.memory_byte_seconds as f64 / GB,
+                oplog_bytes: value.oplog_bytes,
+            },
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct AccountUsageResponse {
+    /// The usage in the billing periods starting in the queried time range, in chronological order
+    pub records: Vec<AccountUsageRecord>,
+    /// The sum of the usage of the returned billing periods
+    pub total: AccountUsage,
+}
+
+impl AccountUsageResponse {
+    pub fn new(records: Vec<AccountUsageRecord>) -> Self {
+        let mut total = AccountUsage::default();
+        for record in &records {
+            total.add(&record.usage);
+        }
+        Self { records, total }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum ScheduledActionKind {
+    /// Completes a promise of the worker when its timeout expires
+    CompletePromise,
+    /// Moves the oplog of an idle worker to the next storage layer
+    ArchiveOplog,
+}
+
+impl From<golem_api_grpc::proto::golem::workerexecutor::v1::ScheduledActionKind>
+    for ScheduledActionKind
+{
+    fn from(value: golem_api_grpc::proto::golem::workerexecutor::v1::ScheduledActionKind) -> Self {
+        match value {
+            golem_api_grpc::proto::golem::workerexecutor::v1::ScheduledActionKind::CompletePromise => {
+                ScheduledActionKind::CompletePromise
+            }
+            golem_api_grpc::proto::golem::workerexecutor::v1::ScheduledActionKind::ArchiveOplog => {
+                ScheduledActionKind::ArchiveOplog
+            }
+        }
+    }
+}
+
+impl From<ScheduledActionKind>
+    for golem_api_grpc::proto::golem::workerexecutor::v1::ScheduledActionKind
+{
+    fn from(value: ScheduledActionKind) -> Self {
+        match value {
+            ScheduledActionKind::CompletePromise => {
+                golem_api_grpc::proto::golem::workerexecutor::v1::ScheduledActionKind::CompletePromise
+            }
+            ScheduledActionKind::ArchiveOplog => {
+                golem_api_grpc::proto::golem::workerexecutor::v1::ScheduledActionKind::ArchiveOplog
+            }
+        }
+    }
+}
+
+/// Selects the pending scheduled actions, every criteria which is set has to match
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScheduledActionFilter {
+    pub worker_id: Option<WorkerId>,
+    pub kind: Option<ScheduledActionKind>,
+    /// Only selects actions due at or after this time
+    pub due_after: Option<Timestamp>,
+    /// Only selects actions due at or before this time
+    pub due_before: Option<Timestamp>,
+}
+
+impl From<ScheduledActionFilter>
+    for golem_api_grpc::proto::golem::workerexecutor::v1::ScheduledActionFilter
+{
+    fn from(value: ScheduledActionFilter) -> Self {
+        Self {
+            worker_id: value.worker_id.map(|worker_id| worker_id.into()),
+            kind: value.kind.map(|kind| {
+                golem_api_grpc::proto::golem::workerexecutor::v1::ScheduledActionKind::from(kind)
+                    as i32
+            }),
+            due_after: value.due_after.map(|due| due.into()),
+            due_before: value.due_before.map(|due| due.into()),
+        }
+    }
+}
+
+/// An action waiting in the scheduler of the worker executors
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct PendingScheduledAction {
+    pub worker_id: WorkerId,
+    pub kind: ScheduledActionKind,
+    pub due: Timestamp,
+    /// The promise completed by the action, if it is a promise completion
+    pub promise_id: Option<PromiseId>,
+    /// The last index of the oplog when the archival was scheduled, if it is an oplog archival
+    pub last_oplog_index: Option<u64>,
+}
+
+impl TryFrom<golem_api_grpc::proto::golem::workerexecutor::v1::PendingScheduledAction>
+    for PendingScheduledAction
+{
+    type Error = String;
+
+    fn try_from(
+        value: golem_api_grpc::proto::golem::workerexecutor::v1::PendingScheduledAction,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self {
+            worker_id: value.worker_id.ok_or("Missing worker_id")?.try_into()?,
+            kind: golem_api_grpc::proto::golem::workerexecutor::v1::ScheduledActionKind::try_from(
+                value.kind,
+            )
+            .map_err(|_| format!("Unknown scheduled action kind: {}", value.kind))?
+            .into(),
+            due: value.due.ok_or("Missing due")?.into(),
+            promise_id: value
+                .promise_id
+                .map(|promise_id| promise_id.try_into())
+                .transpose()?,
+            last_oplog_index: value.last_oplog_index,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct PendingScheduledActionsResponse {
+    /// The actions in the order of their due time
+    pub actions: Vec<PendingScheduledAction>,
+    /// Cursor of the next page, not set if there are no more actions
+    pub cursor: Option<ScheduledActionCursor>,
+}
+
+/// Position of a page of the pending scheduled actions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+pub struct ScheduledActionCursor {
+    pub bucket: i64,
+    pub offset: u64,
+}
+
+impl ParseFromParameter for ScheduledActionCursor {
+    fn parse_from_parameter(value: &str) -> ParseResult<Self> {
+        let (bucket, offset) = value
+            .split_once('-')
+            .ok_or("Invalid scheduled action cursor")?;
+        let bucket = bucket
+            .parse()
+            .map_err(|_| "Invalid bucket in the scheduled action cursor")?;
+        let offset = offset
+            .parse()
+            .map_err(|_| "Invalid offset in the scheduled action cursor")?;
+        Ok(ScheduledActionCursor { bucket, offset })
+    }
+}
+
+impl Display for ScheduledActionCursor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.bucket, self.offset)
+    }
+}
+
+impl From<golem_api_grpc::proto::golem::workerexecutor::v1::ScheduledActionCursor>
+    for ScheduledActionCursor
+{
+    fn from(
+        value: golem_api_grpc::proto::golem::workerexecutor::v1::ScheduledActionCursor,
+    ) -> Self {
+        Self {
+            bucket: value.bucket,
+            offset: value.offset,
+        }
+    }
+}
+
+impl From<ScheduledActionCursor>
+    for golem_api_grpc::proto::golem::workerexecutor::v1::ScheduledActionCursor
+{
+    fn from(value: ScheduledActionCursor) -> Self {
+        Self {
+            bucket: value.bucket,
+            offset: value.offset,
+        }
+    }
+}
+
+/// A promise of a worker which was not completed yet
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct OutstandingPromise {
+    pub promise_id: PromiseId,
+    /// The index of the oplog entry which created the promise
+    pub oplog_index: u64,
+    /// Not set if the creating entry is no longer readable from the oplog
+    pub created_at: Option<Timestamp>,
+    pub age_seconds: Option<u64>,
+}
+
+impl TryFrom<golem_api_grpc::proto::golem::workerexecutor::v1::OutstandingPromise>
+    for OutstandingPromise
+{
+    type Error = String;
+
+    fn try_from(
+        value: golem_api_grpc::proto::golem::workerexecutor::v1::OutstandingPromise,
+    ) -> Result<Self, Self::Error> {
+        let promise_id: PromiseId = value.promise_id.ok_or("Missing promise_id")?.try_into()?;
+        Ok(Self {
+            oplog_index: u64::from(promise_id.oplog_idx),
+            promise_id,
+            created_at: value.created_at.map(|created_at| created_at.into()),
+            age_seconds: value.age_seconds,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct OutstandingPromisesResponse {
+    /// The promises in the order of their creation
+    pub promises: Vec<OutstandingPromise>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum WorkerSagaState {
+    /// The worker is performing the steps of the saga
+    Open,
+    /// The compensations of the saga are being invoked
+    Compensating,
+    /// One of the compensations failed, the rest of them were not invoked
+    CompensationFailed,
+}
+
+impl From<golem_api_grpc::proto::golem::workerexecutor::v1::WorkerSagaState> for WorkerSagaState {
+    fn from(value: golem_api_grpc::proto::golem::workerexecutor::v1::WorkerSagaState) -> Self {
+        match value {
+            golem_api_grpc::proto::golem::workerexecutor::v1::WorkerSagaState::Open => {
+                WorkerSagaState::Open
+            }
+            golem_api_grpc::proto::golem::workerexecutor::v1::WorkerSagaState::Compensating => {
+                WorkerSagaState::Compensating
+            }
+            golem_api_grpc::proto::golem::workerexecutor::v1::WorkerSagaState::CompensationFailed => {
+                WorkerSagaState::CompensationFailed
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct SagaCompensation {
+    pub target: WorkerId,
+    pub function_name: String,
+}
+
+impl TryFrom<golem_api_grpc::proto::golem::workerexecutor::v1::SagaCompensation>
+    for SagaCompensation
+{
+    type Error = String;
+
+    fn try_from(
+        value: golem_api_grpc::proto::golem::workerexecutor::v1::SagaCompensation,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self {
+            target: value.target.ok_or("Missing target")?.try_into()?,
+            function_name: value.function_name,
+        })
+    }
+}
+
+/// A saga of a worker which was not finished yet
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct WorkerSaga {
+    /// The oplog index of the worker when the saga was started
+    pub id: u64,
+    pub state: WorkerSagaState,
+    /// The error of the failed compensation, if the state is CompensationFailed
+    pub error: Option<String>,
+    /// The compensations in the order they were registered, invoked in reverse order
+    pub compensations: Vec<SagaCompensation>,
+}
+
+impl TryFrom<golem_api_grpc::proto::golem::workerexecutor::v1::WorkerSaga> for WorkerSaga {
+    type Error = String;
+
+    fn try_from(
+        value: golem_api_grpc::proto::golem::workerexecutor::v1::WorkerSaga,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: value.id,
+            state: golem_api_grpc::proto::golem::workerexecutor::v1::WorkerSagaState::try_from(
+                value.state,
+            )
+            .map_err(|_| format!("Unknown saga state: {}", value.state))?
+            .into(),
+            error: value.error,
+            compensations: value
+                .compensations
+                .into_iter()
+                .map(|compensation| compensation.try_into())
+                .collect::<Result<Vec<_>, _>>()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct WorkerSagasResponse {
+    /// The sagas in the order they were started
+    pub sagas: Vec<WorkerSaga>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Object)]
+pub struct GetOplogResponse {
+    pub entries: Vec<PublicOplogEntry>,
+    pub next: Option<OplogCursor>,
+    pub first_index_in_chunk: u64,
+    pub last_index: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum WorkerUpdateMode {
+    Automatic,
+    Manual,
+}
+
+impl From<golem_api_grpc::proto::golem::worker::UpdateMode> for WorkerUpdateMode {
+    fn from(value: golem_api_grpc::proto::golem::worker::UpdateMode) -> Self {
+        match value {
+            golem_api_grpc::proto::golem::worker::UpdateMode::Automatic => {
+                WorkerUpdateMode::Automatic
+            }
+            golem_api_grpc::proto::golem::worker::UpdateMode::Manual => WorkerUpdateMode::Manual,
+        }
+    }
+}
+
+impl From<WorkerUpdateMode> for golem_api_grpc::proto::golem::worker::UpdateMode {
+    fn from(value: WorkerUpdateMode) -> Self {
+        match value {
+            WorkerUpdateMode::Automatic => {
+                golem_api_grpc::proto::golem::worker::UpdateMode::Automatic
+            }
+            WorkerUpdateMode::Manual => golem_api_grpc::proto::golem::worker::UpdateMode::Manual,
+        }
+    }
+}
+
+/// Policy for updating the existing workers of a component when a new version is uploaded
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct ComponentUpdatePolicy {
+    /// Whether updates are enqueued for the existing workers when a new version is uploaded
+    pub enabled: bool,
+    pub mode: WorkerUpdateMode,
+    /// Only the workers matching the filter are updated, or all the workers if not set
+    pub filter: Option<WorkerFilter>,
+    /// Maximum number of workers being updated at the same time on each worker executor
+    pub max_concurrent_updates_per_executor: u32,
+    /// If set, updates are enqueued when the version alias is moved instead of when a new version
+    /// is uploaded, and only for the workers created through the alias
+    pub version_alias: Option<String>,
+}
+
+impl ComponentUpdatePolicy {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_concurrent_updates_per_executor == 0 {
+            Err(
+                "The maximum number of concurrent updates per executor must be at least 1"
+                    .to_string(),
+            )
+        } else if let Some(version_alias) = &self.version_alias {
+            validate_component_version_alias(version_alias)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Default for ComponentUpdatePolicy {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mode: WorkerUpdateMode::Automatic,
+            filter: None,
+            max_concurrent_updates_per_executor: 10,
+            version_alias: None,
+        }
+    }
+}
+
+impl TryFrom<golem_api_grpc::proto::golem::component::ComponentUpdatePolicy>
+    for ComponentUpdatePolicy
+{
+    type Error = String;
+
+    fn try_from(
+        value: golem_api_grpc::proto::golem::component::ComponentUpdatePolicy,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self {
+            enabled: value.enabled,
+            mode: value.mode().into(),
+            filter: value.filter.map(|filter| filter.try_into()).transpose()?,
+            max_concurrent_updates_per_executor: value.max_concurrent_updates_per_executor,
+            version_alias: value.version_alias,
+        })
+    }
+}
+
+impl From<ComponentUpdatePolicy>
+    for golem_api_grpc::proto::golem::component::ComponentUpdatePolicy
+{
+    fn from(value: ComponentUpdatePolicy) -> Self {
+        let mode: golem_api_grpc::proto::golem::worker::UpdateMode = value.mode.into();
+        Self {
+            enabled: value.enabled,
+            mode: mode as i32,
+            filter: value.filter.map(|filter| filter.into()),
+            max_concurrent_updates_per_executor: value.max_concurrent_updates_per_executor,
+            version_alias: value.version_alias,
+        }
+    }
+}
+
+/// Progress of updating the workers of a component to a given version
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct UpdateRolloutStatus {
+    pub target_version: ComponentVersion,
+    pub total_workers: u64,
+    /// Workers running the target version or a newer one
+    pub updated_workers: u64,
+    /// Workers with an update to the target version enqueued
+    pub pending_workers: u64,
+    /// Workers which failed to update to the target version
+    pub failed_workers: u64,
+    /// Workers running an older version without an update to the target version
+    pub outdated_workers: u64,
+    pub failures: Vec<UpdateRolloutFailure>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct UpdateRolloutFailure {
+    pub worker_name: String,
+    pub timestamp: Timestamp,
+    pub details: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct UpdateWorkerRequest {
+    pub mode: WorkerUpdateMode,
+    pub target_version: ComponentVersion,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct DryRunUpdateWorkerRequest {
+    pub target_version: ComponentVersion,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct WorkersMetadataRequest {
+    pub filter: Option<WorkerFilter>,
+    pub cursor: Option<ScanCursor>,
+    pub count: Option<u64>,
+    pub precise: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct WorkersMetadataResponse {
+    pub workers: Vec<WorkerMetadata>,
+    pub cursor: Option<ScanCursor>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct WorkersMetadataBulkRequest {
+    pub worker_ids: Vec<WorkerId>,
+}
+
+/// Metadata of one of the workers requested in bulk, or the reason it could not be fetched
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct WorkerMetadataBulkEntry {
+    pub worker_id: WorkerId,
+    pub metadata: Option<WorkerMetadata>,
+    pub error: Option<ProblemDetails>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct WorkersMetadataBulkResponse {
+    pub workers: Vec<WorkerMetadataBulkEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct WorkerMetadata {
+    pub worker_id: WorkerId,
+    pub args: Vec<String>,
+    pub env: HashMap<String, String>,
+    pub status: WorkerStatus,
+    pub component_version: ComponentVersion,
+    pub retry_count: u64,
+    pub pending_invocation_count: u64,
+    pub updates: Vec<UpdateRecord>,
+    pub created_at: Timestamp,
+    pub last_error: Option<String>,
+    pub component_size: u64,
+    pub total_linear_memory_size: u64,
+    pub owned_resources: HashMap<u64, ResourceMetadata>,
+    pub corrupted_oplog_regions: Vec<CorruptedOplogRegion>,
+    pub last_invoked_function: Option<LastInvokedFunction>,
+}
+
+impl TryFrom<golem_api_grpc::proto::golem::worker::WorkerMetadata> for WorkerMetadata {
+    type Error = String;
+
+    fn try_from(
+        value: golem_api_grpc::proto::golem::worker::WorkerMetadata,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self {
+            worker_id: value.worker_id.ok_or("Missing worker_id")?.try_into()?,
+            args: value.args,
+            env: value.env,
+            status: value.status.try_into()?,
+            component_version: value.component_version,
+            retry_count: value.retry_count,
+            pending_invocation_count: value.pending_invocation_count,
+            updates: value
+                .updates
+                .into_iter()
+                .map(|update| update.try_into())
+                .collect::<Result<Vec<UpdateRecord>, String>>()?,
+            created_at: value.created_at.ok_or("Missing created_at")?.into(),
+            last_error: value.last_error,
+            component_size: value.component_size,
+            total_linear_memory_size: value.total_linear_memory_size,
+            owned_resources: value
+                .owned_resources
+                .into_iter()
+                .map(|(k, v)| v.try_into().map(|v| (k, v)))
+                .collect::<Result<HashMap<_, _>, _>>()?,
+            corrupted_oplog_regions: value
+                .corrupted_oplog_regions
+                .into_iter()
+                .map(|region| region.try_into())
+                .collect::<Result<Vec<_>, _>>()?,
+            last_invoked_function: value
+                .last_invoked_function
+                .map(|last_invoked_function| last_invoked_function.try_into())
+                .transpose()?,
+        })
+    }
+}
+
+impl From<WorkerMetadata> for golem_api_grpc::proto::golem::worker::WorkerMetadata {
+    fn from(value: WorkerMetadata) -> Self {
+        Self {
+            worker_id: Some(value.worker_id.into()),
+            account_id: Some(golem_api_grpc::proto::golem::common::AccountId {
+                name: "-1".to_string(),
+            }),
+            args: value.args,
+            env: value.env,
+            status: value.status.into(),
+            component_version: value.component_version,
+            retry_count: value.retry_count,
+            pending_invocation_count: value.pending_invocation_count,
+            updates: value.updates.iter().cloned().map(|u| u.into()).collect(),
+            created_at: Some(value.created_at.into()),
+            last_error: value.last_error,
+            component_size: value.component_size,
+            total_linear_memory_size: value.total_linear_memory_size,
+            owned_resources: value
+                .owned_resources
+                .into_iter()
+                .map(|(k, v)| (k, v.into()))
+                .collect(),
+            corrupted_oplog_regions: value
+                .corrupted_oplog_regions
+                .into_iter()
+                .map(|region| region.into())
+                .collect(),
+            last_invoked_function: value
+                .last_invoked_function
+                .map(|last_invoked_function| last_invoked_function.into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Union)]
+#[serde(rename_all = "camelCase")]
+#[oai(discriminator_name = "type", one_of = true, rename_all = "camelCase")]
+pub enum UpdateRecord {
+    PendingUpdate(PendingUpdate),
+    SuccessfulUpdate(SuccessfulUpdate),
+    FailedUpdate(FailedUpdate),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct PendingUpdate {
+    pub timestamp: Timestamp,
+    pub target_version: ComponentVersion,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct SuccessfulUpdate {
+    pub timestamp: Timestamp,
+    pub target_version: ComponentVersion,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct FailedUpdate {
+    pub timestamp: Timestamp,
+    pub target_version: ComponentVersion,
+    pub details: Option<String>,
+}
+
+impl TryFrom<golem_api_grpc::proto::golem::worker::UpdateRecord> for UpdateRecord {
+    type Error = String;
+
+    fn try_from(
+        value: golem_api_grpc::proto::golem::worker::UpdateRecord,
     ) -> Result<Self, Self::Error> {
         match value.update.ok_or("Missing update field")? {
             golem_api_grpc::proto::golem::worker::update_record::Update::Failed(failed) => {
@@ -1283,6 +2156,21 @@ pub struct InvokeResult {
     pub result: TypeAnnotatedValue,
 }
 
+/// Result of an invocation as a plain JSON value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+pub struct InvokeJsonResult {
+    pub result: serde_json::Value,
+}
+
+/// Returned instead of the result of an invocation which did not finish before the deadline. The
+/// token can be used to resume awaiting the result, and stays valid after the worker restarts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct InvocationAwaitToken {
+    pub await_token: String,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Union, thiserror::Error)]
 #[oai(discriminator_name = "type", one_of = true)]
 #[serde(tag = "type")]
@@ -1333,6 +2221,14 @@ pub enum GolemError {
     InvalidAccount(GolemErrorInvalidAccount),
     #[error(transparent)]
     ShardingNotReady(GolemErrorShardingNotReady),
+    #[error(transparent)]
+    TooManyPendingInvocations(GolemErrorTooManyPendingInvocations),
+    #[error(transparent)]
+    InvocationTimedOut(GolemErrorInvocationTimedOut),
+    #[error(transparent)]
+    ExecutorBusy(GolemErrorExecutorBusy),
+    #[error(transparent)]
+    InvocationNotFound(GolemErrorInvocationNotFound),
 }
 
 impl SafeDisplay for GolemError {
@@ -1361,6 +2257,117 @@ impl SafeDisplay for GolemError {
             GolemError::Unknown(inner) => inner.to_safe_string(),
             GolemError::InvalidAccount(inner) => inner.to_safe_string(),
             GolemError::ShardingNotReady(inner) => inner.to_safe_string(),
+            GolemError::TooManyPendingInvocations(inner) => inner.to_safe_string(),
+            GolemError::InvocationTimedOut(inner) => inner.to_safe_string(),
+            GolemError::ExecutorBusy(inner) => inner.to_safe_string(),
+            GolemError::InvocationNotFound(inner) => inner.to_safe_string(),
+        }
+    }
+}
+
+impl GolemError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            GolemError::InvalidRequest(_)
+            | GolemError::ParamTypeMismatch(_)
+            | GolemError::NoValueInMessage(_)
+            | GolemError::ValueMismatch(_) => ErrorCode::InvalidRequest,
+            GolemError::InvalidAccount(_) => ErrorCode::Forbidden,
+            GolemError::WorkerNotFound(_)
+            | GolemError::PromiseNotFound(_)
+            | GolemError::InvocationNotFound(_) => ErrorCode::NotFound,
+            GolemError::WorkerAlreadyExists(_) => ErrorCode::AlreadyExists,
+            GolemError::PromiseDropped(_)
+            | GolemError::PromiseAlreadyCompleted(_)
+            | GolemError::PreviousInvocationFailed(_)
+            | GolemError::PreviousInvocationExited(_) => ErrorCode::Conflict,
+            GolemError::TooManyPendingInvocations(_) | GolemError::ExecutorBusy(_) => {
+                ErrorCode::TooManyRequests
+            }
+            GolemError::InvocationTimedOut(_) => ErrorCode::Timeout,
+            GolemError::InvalidShardId(_) | GolemError::ShardingNotReady(_) => {
+                ErrorCode::Unavailable
+            }
+            GolemError::Interrupted(_) => ErrorCode::Conflict,
+            GolemError::WorkerCreationFailed(_)
+            | GolemError::FailedToResumeWorker(_)
+            | GolemError::ComponentDownloadFailed(_)
+            | GolemError::ComponentParseFailed(_)
+            | GolemError::GetLatestVersionOfComponentFailed(_)
+            | GolemError::UnexpectedOplogEntry(_)
+            | GolemError::RuntimeError(_)
+            | GolemError::Unknown(_) => ErrorCode::Internal,
+        }
+    }
+
+    /// Returns true if the failed request can be retried without changes
+    pub fn is_retriable(&self) -> bool {
+        match self {
+            GolemError::Interrupted(inner) => inner.recover_immediately,
+            // Parsing the same component again fails the same way
+            GolemError::ComponentParseFailed(_) | GolemError::UnexpectedOplogEntry(_) => false,
+            _ => self.code().is_retriable(),
+        }
+    }
+
+    /// Gets the parameters of the error, identifying the entities it is about
+    pub fn params(&self) -> BTreeMap<String, String> {
+        let params: Vec<(&str, String)> = match self {
+            GolemError::WorkerAlreadyExists(inner) => {
+                vec![("workerId", inner.worker_id.to_string())]
+            }
+            GolemError::WorkerNotFound(inner) => vec![("workerId", inner.worker_id.to_string())],
+            GolemError::WorkerCreationFailed(inner) => {
+                vec![("workerId", inner.worker_id.to_string())]
+            }
+            GolemError::FailedToResumeWorker(inner) => {
+                vec![("workerId", inner.worker_id.to_string())]
+            }
+            GolemError::ComponentDownloadFailed(inner) => {
+                vec![("componentId", inner.component_id.to_string())]
+            }
+            GolemError::ComponentParseFailed(inner) => {
+                vec![("componentId", inner.component_id.to_string())]
+            }
+            GolemError::GetLatestVersionOfComponentFailed(inner) => {
+                vec![("componentId", inner.component_id.to_string())]
+            }
+            GolemError::PromiseNotFound(inner) => {
+                vec![("promiseId", inner.promise_id.to_string())]
+            }
+            GolemError::PromiseDropped(inner) => vec![("promiseId", inner.promise_id.to_string())],
+            GolemError::PromiseAlreadyCompleted(inner) => {
+                vec![("promiseId", inner.promise_id.to_string())]
+            }
+            GolemError::InvalidShardId(inner) => vec![("shardId", inner.shard_id.to_string())],
+            GolemError::TooManyPendingInvocations(inner) => vec![
+                ("workerId", inner.worker_id.to_string()),
+                ("pendingInvocations", inner.pending_invocations.to_string()),
+            ],
+            GolemError::InvocationTimedOut(inner) => vec![
+                ("workerId", inner.worker_id.to_string()),
+                ("timeoutMillis", inner.timeout_millis.to_string()),
+            ],
+            GolemError::ExecutorBusy(inner) => {
+                vec![("maxActiveWorkers", inner.max_active_workers.to_string())]
+            }
+            GolemError::InvocationNotFound(inner) => vec![
+                ("workerId", inner.worker_id.to_string()),
+                ("idempotencyKey", inner.idempotency_key.to_string()),
+            ],
+            _ => vec![],
+        };
+        params
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect()
+    }
+
+    pub fn to_problem_details(&self) -> ProblemDetails {
+        ProblemDetails {
+            params: self.params(),
+            ..ProblemDetails::new(self.code(), self.to_safe_string())
+                .with_retriable(self.is_retriable())
         }
     }
 }
@@ -1445,6 +2452,18 @@ impl TryFrom<golem_api_grpc::proto::golem::worker::v1::WorkerExecutionError> for
             Some(golem_api_grpc::proto::golem::worker::v1::worker_execution_error::Error::ShardingNotReady(err)) => {
                 Ok(GolemError::ShardingNotReady(err.into()))
             }
+            Some(golem_api_grpc::proto::golem::worker::v1::worker_execution_error::Error::TooManyPendingInvocations(err)) => {
+                Ok(GolemError::TooManyPendingInvocations(err.try_into()?))
+            }
+            Some(golem_api_grpc::proto::golem::worker::v1::worker_execution_error::Error::InvocationTimedOut(err)) => {
+                Ok(GolemError::InvocationTimedOut(err.try_into()?))
+            }
+            Some(golem_api_grpc::proto::golem::worker::v1::worker_execution_error::Error::ExecutorBusy(err)) => {
+                Ok(GolemError::ExecutorBusy(err.into()))
+            }
+            Some(golem_api_grpc::proto::golem::worker::v1::worker_execution_error::Error::InvocationNotFound(err)) => {
+                Ok(GolemError::InvocationNotFound(err.try_into()?))
+            }
             None => Err("Missing field: error".to_string()),
         }
     }
@@ -1530,6 +2549,18 @@ impl From<GolemError> for golem_api_grpc::proto::golem::worker::v1::worker_execu
             GolemError::ShardingNotReady(err) => {
                 golem_api_grpc::proto::golem::worker::v1::worker_execution_error::Error::ShardingNotReady(err.into())
             }
+            GolemError::TooManyPendingInvocations(err) => {
+                golem_api_grpc::proto::golem::worker::v1::worker_execution_error::Error::TooManyPendingInvocations(err.into())
+            }
+            GolemError::InvocationTimedOut(err) => {
+                golem_api_grpc::proto::golem::worker::v1::worker_execution_error::Error::InvocationTimedOut(err.into())
+            }
+            GolemError::ExecutorBusy(err) => {
+                golem_api_grpc::proto::golem::worker::v1::worker_execution_error::Error::ExecutorBusy(err.into())
+            }
+            GolemError::InvocationNotFound(err) => {
+                golem_api_grpc::proto::golem::worker::v1::worker_execution_error::Error::InvocationNotFound(err.into())
+            }
         }
     }
 }
@@ -1586,6 +2617,10 @@ pub struct Component {
     pub metadata: ComponentMetadata,
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
     pub component_type: Option<ComponentType>,
+    /// Compatibility of the exports with the previous version, returned when a new version is uploaded
+    pub compatibility_report: Option<ComponentCompatibilityReport>,
+    /// Content hash of the archive of initial files materialized into the workers' file system
+    pub initial_files_hash: Option<String>,
 }
 
 impl TryFrom<golem_api_grpc::proto::golem::component::Component> for Component {
@@ -1621,6 +2656,8 @@ impl TryFrom<golem_api_grpc::proto::golem::component::Component> for Component {
             } else {
                 None
             },
+            compatibility_report: value.compatibility_report.map(|report| report.into()),
+            initial_files_hash: value.initial_files_hash,
         })
     }
 }
@@ -1640,6 +2677,8 @@ impl From<Component> for golem_api_grpc::proto::golem::component::Component {
                 let c: golem_api_grpc::proto::golem::component::ComponentType = c.into();
                 c.into()
             }),
+            compatibility_report: value.compatibility_report.map(|report| report.into()),
+            initial_files_hash: value.initial_files_hash,
         }
     }
 }
@@ -1652,11 +2691,239 @@ impl Component {
         };
         Self {
             versioned_component_id: new_version.clone(),
+            compatibility_report: None,
             ..self
         }
     }
 }
 
+/// A component calling, or called by, another component through a generated wasm-rpc stub
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct ComponentDependency {
+    pub versioned_component_id: VersionedComponentId,
+    pub component_name: ComponentName,
+    /// Package exported by the called component and imported through its stub
+    pub package: String,
+}
+
+/// The direct dependencies between the latest version of a component and the latest versions of
+/// the other components
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct ComponentDependencyGraph {
+    pub versioned_component_id: VersionedComponentId,
+    /// Components called by the component
+    pub dependencies: Vec<ComponentDependency>,
+    /// Components calling the component, affected by breaking changes of its exports
+    pub dependents: Vec<ComponentDependency>,
+}
+
+/// A transformation registered in an account, applied to the WASM of every component uploaded to
+/// the account before storing it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct ComponentTransformationPlugin {
+    /// Name of the plugin, unique in the account
+    pub name: String,
+    /// The plugins are applied in ascending order of priority
+    pub priority: i32,
+    pub transformation: ComponentTransformation,
+}
+
+impl ComponentTransformationPlugin {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=100).contains(&self.name.len()) {
+            Err("Plugin name must be between 1 and 100 characters".to_string())
+        } else if self.name.contains(char::is_whitespace) || self.name.contains('/') {
+            Err("Plugin name must not contain whitespace or '/'".to_string())
+        } else {
+            self.transformation.validate()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Union)]
+#[serde(tag = "type", rename_all = "camelCase")]
+#[oai(discriminator_name = "type", one_of = true, rename_all = "camelCase")]
+pub enum ComponentTransformation {
+    StripCustomSections(StripCustomSections),
+    PlugComponent(PlugComponent),
+    Webhook(TransformationWebhook),
+}
+
+impl ComponentTransformation {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            ComponentTransformation::Webhook(webhook) => url::Url::parse(&webhook.url)
+                .map_err(|e| format!("Invalid webhook URL {}: {e}", webhook.url))
+                .and_then(|url| {
+                    if matches!(url.scheme(), "http" | "https") {
+                        Ok(())
+                    } else {
+                        Err(format!(
+                            "Webhook URL {} must use the http or https scheme",
+                            webhook.url
+                        ))
+                    }
+                }),
+            ComponentTransformation::StripCustomSections(_)
+            | ComponentTransformation::PlugComponent(_) => Ok(()),
+        }
+    }
+}
+
+/// Removes the custom sections (debug names, producers, etc.) from the top level of the WASM
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct StripCustomSections {
+    /// Names of the custom sections to keep
+    #[serde(default)]
+    #[oai(default)]
+    pub keep: Vec<String>,
+}
+
+/// Plugs the latest version of a stored component, for example an observability adapter, into the
+/// matching imports of the uploaded component
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct PlugComponent {
+    pub component_id: ComponentId,
+}
+
+/// Sends the WASM in a POST request to an external service, and uses the WASM returned in the
+/// response body instead
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct TransformationWebhook {
+    pub url: String,
+}
+
+/// A group of components, API definitions and API deployments of an account
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct Project {
+    pub project_id: ProjectId,
+    pub name: String,
+    pub description: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl TryFrom<golem_api_grpc::proto::golem::component::Project> for Project {
+    type Error = String;
+
+    fn try_from(
+        value: golem_api_grpc::proto::golem::component::Project,
+    ) -> Result<Self, Self::Error> {
+        let created_at = SystemTime::try_from(value.created_at.ok_or("Missing created_at")?)
+            .map_err(|_| "Failed to convert timestamp")?;
+        Ok(Self {
+            project_id: value.project_id.ok_or("Missing project_id")?.try_into()?,
+            name: value.name,
+            description: value.description,
+            created_at: created_at.into(),
+        })
+    }
+}
+
+impl From<Project> for golem_api_grpc::proto::golem::component::Project {
+    fn from(value: Project) -> Self {
+        Self {
+            project_id: Some(value.project_id.into()),
+            name: value.name,
+            description: value.description,
+            created_at: Some(prost_types::Timestamp::from(SystemTime::from(
+                value.created_at,
+            ))),
+        }
+    }
+}
+
+/// The properties of a project which can be set when creating or updating it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct ProjectData {
+    /// Name of the project, unique in the account
+    pub name: String,
+    #[serde(default)]
+    #[oai(default)]
+    pub description: String,
+}
+
+impl ProjectData {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=100).contains(&self.name.len()) {
+            Err("Project name must be between 1 and 100 characters".to_string())
+        } else if self.name.trim().is_empty() {
+            Err("Project name must not be blank".to_string())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// The project a component, API definition or API deployment belongs to, if any
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct ProjectAssignment {
+    pub project_id: Option<ProjectId>,
+}
+
+/// A named pointer, like `stable` or `canary`, to a version of a component
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct ComponentVersionAlias {
+    pub name: String,
+    pub version: ComponentVersion,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+pub fn validate_component_version_alias(name: &str) -> Result<(), String> {
+    if !(1..=100).contains(&name.len()) {
+        Err("Version alias must be between 1 and 100 characters".to_string())
+    } else if !name
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '_' || c == '-' || c == '.')
+    {
+        Err(format!(
+            "Version alias {name} must contain only alphanumeric characters, underscores, dashes and dots"
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// The version a version alias is moved to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct ComponentVersionAliasTarget {
+    pub version: ComponentVersion,
+}
+
+/// A change of a version alias, either moving it or removing it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct ComponentVersionAliasChange {
+    pub name: String,
+    /// The version the alias pointed to before the change, if it existed
+    pub previous_version: Option<ComponentVersion>,
+    /// The version the alias points to after the change, or nothing if it was removed
+    pub version: Option<ComponentVersion>,
+    pub changed_at: chrono::DateTime<chrono::Utc>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize, Object)]
 #[serde(rename_all = "camelCase")]
 #[oai(rename_all = "camelCase")]
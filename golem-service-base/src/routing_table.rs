@@ -97,6 +97,13 @@ pub struct RoutingTableConfig {
     port: u16,
     #[serde(with = "humantime_serde")]
     invalidation_min_delay: Duration,
+    /// Serve read-only worker operations from any executor instead of the shard owner
+    read_replicas: bool,
+    /// Maximum age of the routing table used for read-only worker operations
+    #[serde(with = "humantime_serde")]
+    read_max_staleness: Duration,
+    /// Availability zone of this service, read-only worker operations prefer executors of the same zone
+    zone: Option<String>,
 }
 
 impl RoutingTableConfig {
@@ -113,6 +120,9 @@ impl Default for RoutingTableConfig {
             host: "localhost".to_string(),
             port: 9002,
             invalidation_min_delay: Duration::from_millis(500),
+            read_replicas: false,
+            read_max_staleness: Duration::from_secs(5),
+            zone: None,
         }
     }
 }
@@ -122,6 +132,10 @@ pub trait RoutingTableService {
     async fn get_routing_table(&self) -> Result<RoutingTable, RoutingTableError>;
     // Returns false in case of skipped (throttled) invalidation
     async fn try_invalidate_routing_table(&self) -> bool;
+    // Returns the staleness bound for read routing, or None if reads go to the shard owner
+    fn read_max_staleness(&self) -> Option<Duration>;
+    // Returns the availability zone to prefer when routing reads
+    fn zone(&self) -> Option<&str>;
 }
 
 pub trait HasRoutingTableService {
@@ -221,6 +235,18 @@ impl RoutingTableService for RoutingTableServiceDefault {
         *last_invalidated_at = Some(Instant::now());
         true
     }
+
+    fn read_max_staleness(&self) -> Option<Duration> {
+        if self.config.read_replicas {
+            Some(self.config.read_max_staleness)
+        } else {
+            None
+        }
+    }
+
+    fn zone(&self) -> Option<&str> {
+        self.config.zone.as_deref()
+    }
 }
 
 pub struct RoutingTableServiceNoop {}
@@ -234,4 +260,12 @@ impl RoutingTableService for RoutingTableServiceNoop {
     async fn try_invalidate_routing_table(&self) -> bool {
         return false;
     }
+
+    fn read_max_staleness(&self) -> Option<Duration> {
+        None
+    }
+
+    fn zone(&self) -> Option<&str> {
+        None
+    }
 }
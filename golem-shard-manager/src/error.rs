@@ -39,6 +39,14 @@ pub enum ShardManagerError {
     SerializationError(String),
     #[error("Redis error {0}")]
     RedisError(fred::error::RedisError),
+    #[error("etcd error {0}")]
+    EtcdError(String),
+    #[error("Routing table has been updated by another shard manager")]
+    ConcurrentUpdate,
+    #[error("Unknown pod: {0}")]
+    UnknownPod(String),
+    #[error("Invalid pod weight: {0}")]
+    InvalidPodWeight(u32),
 }
 
 impl IsRetriableError for ShardManagerError {
@@ -52,6 +60,10 @@ impl IsRetriableError for ShardManagerError {
             ShardManagerError::WorkerExecutionError(_) => true, // TODO: can we define which ones are retryable?
             ShardManagerError::SerializationError(_) => false,
             ShardManagerError::RedisError(_) => false,
+            ShardManagerError::EtcdError(_) => false,
+            ShardManagerError::ConcurrentUpdate => false,
+            ShardManagerError::UnknownPod(_) => false,
+            ShardManagerError::InvalidPodWeight(_) => false,
         }
     }
 
@@ -96,6 +108,21 @@ impl From<ShardManagerError> for golem::shardmanager::v1::ShardManagerError {
             ShardManagerError::RedisError(err) => {
                 error(shard_manager_error::Error::Unknown, err.to_string())
             }
+            ShardManagerError::EtcdError(details) => {
+                error(shard_manager_error::Error::Unknown, details)
+            }
+            ShardManagerError::ConcurrentUpdate => error(
+                shard_manager_error::Error::Unknown,
+                "ConcurrentUpdate".to_string(),
+            ),
+            ShardManagerError::UnknownPod(pod) => error(
+                shard_manager_error::Error::InvalidRequest,
+                format!("UnknownPod: {pod}"),
+            ),
+            ShardManagerError::InvalidPodWeight(weight) => error(
+                shard_manager_error::Error::InvalidRequest,
+                format!("InvalidPodWeight: {weight}"),
+            ),
         }
     }
 }
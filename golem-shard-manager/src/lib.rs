@@ -29,7 +29,9 @@ use std::sync::Arc;
 use crate::error::ShardManagerTraceErrorKind;
 use crate::healthcheck::{get_unhealthy_pods, GrpcHealthCheck, HealthCheck};
 use crate::http_server::HttpServerImpl;
-use crate::shard_manager_config::{make_config_loader, HealthCheckK8sConfig, HealthCheckMode};
+use crate::shard_manager_config::{
+    make_config_loader, HealthCheckK8sConfig, HealthCheckMode, PersistenceConfig,
+};
 use error::ShardManagerError;
 use golem_api_grpc::proto;
 use golem_api_grpc::proto::golem;
@@ -40,7 +42,7 @@ use golem_api_grpc::proto::golem::shardmanager::v1::shard_manager_service_server
 use golem_common::recorded_grpc_api_request;
 use golem_common::tracing::init_tracing_with_default_env_filter;
 use model::{Pod, RoutingTable};
-use persistence::{PersistenceService, PersistenceServiceDefault};
+use persistence::{PersistenceService, PersistenceServiceDefault, PersistenceServiceEtcd};
 use prometheus::{default_registry, Registry};
 use shard_management::ShardManagement;
 use shard_manager_config::ShardManagerConfig;
@@ -72,6 +74,7 @@ impl ShardManagerServiceImpl {
             worker_executor_service,
             health_check.clone(),
             shard_manager_config.rebalance_threshold,
+            shard_manager_config.placement.clone(),
         )
         .await?;
 
@@ -101,9 +104,55 @@ impl ShardManagerServiceImpl {
     ) -> Result<(), ShardManagerError> {
         let source_ip = source_ip.ok_or(ShardManagerError::NoSourceIpForPod)?.ip();
 
+        let weight = request.weight;
+        if weight == Some(0) {
+            return Err(ShardManagerError::InvalidPodWeight(0));
+        }
+        let zone = request.zone.clone();
+
         let pod = Pod::from_register_request(source_ip, request)?;
         info!("Shard Manager received request to register pod: {}", pod);
-        self.shard_management.register_pod(pod).await;
+        self.shard_management.register_pod(pod, weight, zone).await;
+        Ok(())
+    }
+
+    async fn unregister_internal(
+        &self,
+        source_ip: Option<SocketAddr>,
+        request: golem::shardmanager::v1::UnregisterRequest,
+    ) -> Result<(), ShardManagerError> {
+        let source_ip = source_ip.ok_or(ShardManagerError::NoSourceIpForPod)?.ip();
+
+        let pod = Pod::from_unregister_request(source_ip, request);
+        info!("Shard Manager received request to unregister pod: {}", pod);
+        self.shard_management.unregister_pod(pod).await;
+        Ok(())
+    }
+
+    async fn set_pod_weight_internal(
+        &self,
+        request: golem::shardmanager::v1::SetPodWeightRequest,
+    ) -> Result<(), ShardManagerError> {
+        if request.weight == 0 {
+            return Err(ShardManagerError::InvalidPodWeight(request.weight));
+        }
+
+        let pod = self
+            .shard_management
+            .current_snapshot()
+            .await
+            .find_pod(&request.host, request.port as u16)
+            .ok_or(ShardManagerError::UnknownPod(format!(
+                "{}:{}",
+                request.host, request.port
+            )))?;
+        info!(
+            "Shard Manager received request to set the weight of pod {} to {}",
+            pod, request.weight
+        );
+        self.shard_management
+            .set_pod_weight(pod, request.weight)
+            .await;
         Ok(())
     }
 
@@ -206,6 +255,85 @@ impl ShardManagerService for ShardManagerServiceImpl {
             result: Some(result),
         }))
     }
+
+    async fn unregister(
+        &self,
+        request: tonic::Request<golem::shardmanager::v1::UnregisterRequest>,
+    ) -> Result<tonic::Response<golem::shardmanager::v1::UnregisterResponse>, tonic::Status> {
+        let source_ip = request.remote_addr();
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "unregister",
+            source_ip = source_ip.map(|ip| ip.to_string()),
+            host = &request.host,
+            port = &request.port.to_string(),
+        );
+
+        let response = self
+            .unregister_internal(source_ip, request)
+            .instrument(record.span.clone())
+            .await;
+
+        let result = match response {
+            Ok(_) => record.succeed(
+                golem::shardmanager::v1::unregister_response::Result::Success(
+                    golem::common::Empty {},
+                ),
+            ),
+            Err(error) => {
+                let error: golem::shardmanager::v1::ShardManagerError = error.into();
+                record.fail(
+                    golem::shardmanager::v1::unregister_response::Result::Failure(error.clone()),
+                    &ShardManagerTraceErrorKind(&error),
+                )
+            }
+        };
+
+        Ok(Response::new(golem::shardmanager::v1::UnregisterResponse {
+            result: Some(result),
+        }))
+    }
+
+    async fn set_pod_weight(
+        &self,
+        request: tonic::Request<golem::shardmanager::v1::SetPodWeightRequest>,
+    ) -> Result<tonic::Response<golem::shardmanager::v1::SetPodWeightResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "set_pod_weight",
+            host = &request.host,
+            port = &request.port.to_string(),
+            weight = &request.weight.to_string(),
+        );
+
+        let response = self
+            .set_pod_weight_internal(request)
+            .instrument(record.span.clone())
+            .await;
+
+        let result = match response {
+            Ok(_) => record.succeed(
+                golem::shardmanager::v1::set_pod_weight_response::Result::Success(
+                    golem::common::Empty {},
+                ),
+            ),
+            Err(error) => {
+                let error: golem::shardmanager::v1::ShardManagerError = error.into();
+                record.fail(
+                    golem::shardmanager::v1::set_pod_weight_response::Result::Failure(
+                        error.clone(),
+                    ),
+                    &ShardManagerTraceErrorKind(&error),
+                )
+            }
+        };
+
+        Ok(Response::new(
+            golem::shardmanager::v1::SetPodWeightResponse {
+                result: Some(result),
+            },
+        ))
+    }
 }
 
 pub fn server_main() -> Result<(), Box<dyn std::error::Error>> {
@@ -245,15 +373,27 @@ async fn async_main(
         registry,
     );
 
-    info!("Using Redis at {}", shard_manager_config.redis.url());
-    let pool = golem_common::redis::RedisPool::configured(&shard_manager_config.redis).await?;
-
     let shard_manager_config = Arc::new(shard_manager_config.clone());
 
-    let persistence_service = Arc::new(PersistenceServiceDefault::new(
-        &pool,
-        &shard_manager_config.number_of_shards,
-    ));
+    let persistence_service: Arc<dyn PersistenceService + Send + Sync> =
+        match &shard_manager_config.persistence {
+            PersistenceConfig::Redis(_) => {
+                info!("Using Redis at {}", shard_manager_config.redis.url());
+                let pool =
+                    golem_common::redis::RedisPool::configured(&shard_manager_config.redis).await?;
+                Arc::new(PersistenceServiceDefault::new(
+                    &pool,
+                    &shard_manager_config.number_of_shards,
+                ))
+            }
+            PersistenceConfig::Etcd(etcd) => {
+                info!("Using etcd at {}", etcd.endpoints.join(", "));
+                Arc::new(PersistenceServiceEtcd::new(
+                    etcd,
+                    &shard_manager_config.number_of_shards,
+                )?)
+            }
+        };
     let worker_executors = Arc::new(WorkerExecutorServiceDefault::new(
         shard_manager_config.worker_executors.clone(),
     ));
@@ -31,6 +31,10 @@ use golem_common::model::ShardId;
 use crate::error::ShardManagerError;
 use crate::rebalancing::Rebalance;
 
+/// Weight of pods which did not specify their capacity. Shards are distributed among pods
+/// proportionally to their weights.
+pub const DEFAULT_POD_WEIGHT: u32 = 1;
+
 #[derive(
     Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd, Serialize, Deserialize, Encode, Decode,
 )]
@@ -65,10 +69,28 @@ impl Pod {
             .expect("Failed to build URI")
     }
 
+    /// Checks if the pod is the one listening on the given port of the given host,
+    /// where the host can be either the registered host name or the pod's IP address
+    pub fn matches(&self, host: &str, port: u16) -> bool {
+        self.port == port && (self.host == host || self.ip.to_string() == host)
+    }
+
     pub fn address(&self) -> Result<vec::IntoIter<SocketAddr>, std::io::Error> {
         format!("{}:{}", self.ip, self.port).to_socket_addrs()
     }
 
+    pub fn from_unregister_request(
+        source_ip: IpAddr,
+        request: golem::shardmanager::v1::UnregisterRequest,
+    ) -> Self {
+        Pod {
+            host: request.host,
+            port: request.port as u16,
+            pod_name: request.pod_name,
+            ip: source_ip,
+        }
+    }
+
     pub fn from_register_request(
         source_ip: IpAddr,
         request: golem::shardmanager::v1::RegisterRequest,
@@ -113,6 +135,7 @@ impl From<Pod> for golem::shardmanager::Pod {
             host: value.ip.to_string(),
             port: value.port as u32,
             pod_name: value.pod_name,
+            zone: None,
         }
     }
 }
@@ -130,6 +153,10 @@ impl Display for Pod {
 pub struct RoutingTable {
     pub number_of_shards: usize,
     pub shard_assignments: BTreeMap<Pod, BTreeSet<ShardId>>,
+    /// Weights of the pods not having the default weight
+    pub pod_weights: BTreeMap<Pod, u32>,
+    /// Availability zones of the pods which reported one
+    pub pod_zones: BTreeMap<Pod, String>,
 }
 
 impl RoutingTable {
@@ -137,6 +164,8 @@ impl RoutingTable {
         Self {
             number_of_shards,
             shard_assignments: BTreeMap::new(),
+            pod_weights: BTreeMap::new(),
+            pod_zones: BTreeMap::new(),
         }
     }
 
@@ -199,6 +228,53 @@ impl RoutingTable {
 
     pub fn remove_pod(&mut self, pod: &Pod) {
         self.shard_assignments.remove(pod);
+        self.pod_weights.remove(pod);
+        self.pod_zones.remove(pod);
+    }
+
+    pub fn get_pod_weight(&self, pod: &Pod) -> u32 {
+        self.pod_weights
+            .get(pod)
+            .copied()
+            .unwrap_or(DEFAULT_POD_WEIGHT)
+    }
+
+    pub fn set_pod_weight(&mut self, pod: &Pod, weight: u32) {
+        if weight == DEFAULT_POD_WEIGHT {
+            self.pod_weights.remove(pod);
+        } else {
+            self.pod_weights.insert(pod.clone(), weight);
+        }
+    }
+
+    pub fn get_pod_zone(&self, pod: &Pod) -> Option<&str> {
+        self.pod_zones.get(pod).map(|zone| zone.as_str())
+    }
+
+    pub fn set_pod_zone(&mut self, pod: &Pod, zone: String) {
+        self.pod_zones.insert(pod.clone(), zone);
+    }
+
+    /// Gets the pods which are not in any of the given zones. If none of the pods are in these
+    /// zones, or no zones are given, no pods are excluded.
+    pub fn get_pods_outside_zones(&self, zones: &[String]) -> HashSet<Pod> {
+        let (inside, outside): (Vec<&Pod>, Vec<&Pod>) =
+            self.shard_assignments.keys().partition(|pod| {
+                self.get_pod_zone(pod)
+                    .is_some_and(|zone| zones.iter().any(|z| z == zone))
+            });
+        if inside.is_empty() {
+            HashSet::new()
+        } else {
+            outside.into_iter().cloned().collect()
+        }
+    }
+
+    pub fn find_pod(&self, host: &str, port: u16) -> Option<Pod> {
+        self.shard_assignments
+            .keys()
+            .find(|pod| pod.matches(host, port))
+            .cloned()
     }
 
     pub fn has_pod(&self, pod: &Pod) -> bool {
@@ -208,18 +284,23 @@ impl RoutingTable {
 
 impl From<RoutingTable> for golem::shardmanager::RoutingTable {
     fn from(routing_table: RoutingTable) -> golem::shardmanager::RoutingTable {
+        let pod_zones = routing_table.pod_zones;
         golem::shardmanager::RoutingTable {
             number_of_shards: routing_table.number_of_shards as u32,
             shard_assignments: routing_table
                 .shard_assignments
                 .into_iter()
                 .flat_map(|(pod, shard_ids)| {
+                    let pod = golem::shardmanager::Pod {
+                        zone: pod_zones.get(&pod).cloned(),
+                        ..pod.into()
+                    };
                     shard_ids
                         .into_iter()
                         .map(move |shard_id| (pod.clone(), shard_id))
                 })
                 .map(|(pod, shard_id)| golem::shardmanager::RoutingTableEntry {
-                    pod: Some(pod.into()),
+                    pod: Some(pod),
                     shard_id: Some(shard_id.into()),
                 })
                 .collect(),
@@ -394,6 +475,60 @@ impl ShardManagerState {
         RoutingTable {
             number_of_shards: self.number_of_shards,
             shard_assignments,
+            pod_weights: BTreeMap::new(),
+            pod_zones: BTreeMap::new(),
+        }
+    }
+}
+
+/// Persisted separately from `ShardManagerState` to keep the previously stored states readable
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Encode, Decode)]
+pub struct PodWeights {
+    pub weights: Vec<(Pod, u32)>,
+}
+
+impl PodWeights {
+    pub fn new(routing_table: &RoutingTable) -> Self {
+        PodWeights {
+            weights: routing_table
+                .pod_weights
+                .iter()
+                .map(|(pod, weight)| (pod.clone(), *weight))
+                .collect(),
+        }
+    }
+
+    pub fn apply(&self, routing_table: &mut RoutingTable) {
+        for (pod, weight) in &self.weights {
+            if routing_table.has_pod(pod) {
+                routing_table.set_pod_weight(pod, *weight);
+            }
+        }
+    }
+}
+
+/// Persisted separately from `ShardManagerState` to keep the previously stored states readable
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Encode, Decode)]
+pub struct PodZones {
+    pub zones: Vec<(Pod, String)>,
+}
+
+impl PodZones {
+    pub fn new(routing_table: &RoutingTable) -> Self {
+        PodZones {
+            zones: routing_table
+                .pod_zones
+                .iter()
+                .map(|(pod, zone)| (pod.clone(), zone.clone()))
+                .collect(),
+        }
+    }
+
+    pub fn apply(&self, routing_table: &mut RoutingTable) {
+        for (pod, zone) in &self.zones {
+            if routing_table.has_pod(pod) {
+                routing_table.set_pod_zone(pod, zone.clone());
+            }
         }
     }
 }
@@ -13,11 +13,17 @@
 // limitations under the License.
 
 use async_trait::async_trait;
+use base64::prelude::*;
 use bytes::Bytes;
 use golem_common::redis::RedisPool;
+use golem_common::serialization::{deserialize, serialize};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tracing::warn;
 
 use crate::error::ShardManagerError;
-use crate::model::{RoutingTable, ShardManagerState};
+use crate::model::{PodWeights, PodZones, RoutingTable, ShardManagerState};
+use crate::shard_manager_config::EtcdConfig;
 
 #[async_trait]
 pub trait PersistenceService {
@@ -40,6 +46,32 @@ impl PersistenceService for PersistenceServiceDefault {
             .serialize(&shard_manager_state)
             .map_err(ShardManagerError::SerializationError)?;
 
+        self.pool
+            .with("persistence", "write")
+            .set(key, value, None, None, false)
+            .await
+            .map_err(ShardManagerError::RedisError)?;
+
+        let pod_weights = PodWeights::new(routing_table);
+        let key = "shard:pod_weights";
+        let value = self
+            .pool
+            .serialize(&pod_weights)
+            .map_err(ShardManagerError::SerializationError)?;
+
+        self.pool
+            .with("persistence", "write")
+            .set(key, value, None, None, false)
+            .await
+            .map_err(ShardManagerError::RedisError)?;
+
+        let pod_zones = PodZones::new(routing_table);
+        let key = "shard:pod_zones";
+        let value = self
+            .pool
+            .serialize(&pod_zones)
+            .map_err(ShardManagerError::SerializationError)?;
+
         self.pool
             .with("persistence", "write")
             .set(key, value, None, None, false)
@@ -57,16 +89,52 @@ impl PersistenceService for PersistenceServiceDefault {
             .await
             .map_err(ShardManagerError::RedisError)?;
 
-        match value {
+        let mut routing_table = match value {
             Some(value) => {
                 let shard_manager_state: ShardManagerState = self
                     .pool
                     .deserialize(&value)
                     .map_err(ShardManagerError::SerializationError)?;
-                Ok(shard_manager_state.get_routing_table())
+                shard_manager_state.get_routing_table()
             }
-            None => Ok(RoutingTable::new(self.number_of_shards)),
+            None => RoutingTable::new(self.number_of_shards),
+        };
+
+        let key = "shard:pod_weights";
+
+        let value: Option<Bytes> = self
+            .pool
+            .with("persistence", "read")
+            .get(key)
+            .await
+            .map_err(ShardManagerError::RedisError)?;
+
+        if let Some(value) = value {
+            let pod_weights: PodWeights = self
+                .pool
+                .deserialize(&value)
+                .map_err(ShardManagerError::SerializationError)?;
+            pod_weights.apply(&mut routing_table);
         }
+
+        let key = "shard:pod_zones";
+
+        let value: Option<Bytes> = self
+            .pool
+            .with("persistence", "read")
+            .get(key)
+            .await
+            .map_err(ShardManagerError::RedisError)?;
+
+        if let Some(value) = value {
+            let pod_zones: PodZones = self
+                .pool
+                .deserialize(&value)
+                .map_err(ShardManagerError::SerializationError)?;
+            pod_zones.apply(&mut routing_table);
+        }
+
+        Ok(routing_table)
     }
 }
 
@@ -78,3 +146,422 @@ impl PersistenceServiceDefault {
         }
     }
 }
+
+/// Persistence of the routing table in etcd, using the JSON gateway of its v3 API.
+///
+/// The shard manager state, the pod weights and the pod zones are written and read in single transactions, so
+/// they are always consistent with each other, and reads are linearizable.
+///
+/// Writes only succeed if the stored state has not been changed since this shard manager last read or wrote
+/// it, so a shard manager which has been replaced by another one cannot overwrite its routing table.
+pub struct PersistenceServiceEtcd {
+    client: reqwest::Client,
+    endpoints: Vec<String>,
+    key_prefix: String,
+    number_of_shards: usize,
+    /// Modification revision of the stored shard manager state as last seen, 0 if it did not exist
+    revision: Mutex<i64>,
+}
+
+#[async_trait]
+impl PersistenceService for PersistenceServiceEtcd {
+    async fn write(&self, routing_table: &RoutingTable) -> Result<(), ShardManagerError> {
+        let shard_manager_state = serialize(&ShardManagerState::new(routing_table))
+            .map_err(ShardManagerError::SerializationError)?;
+        let pod_weights = serialize(&PodWeights::new(routing_table))
+            .map_err(ShardManagerError::SerializationError)?;
+        let pod_zones = serialize(&PodZones::new(routing_table))
+            .map_err(ShardManagerError::SerializationError)?;
+
+        let expected_revision = *self.revision.lock().unwrap();
+        let response = self
+            .txn(
+                vec![self.mod_revision_equals("shard:shard_manager_state", expected_revision)],
+                vec![
+                    self.put("shard:shard_manager_state", &shard_manager_state),
+                    self.put("shard:pod_weights", &pod_weights),
+                    self.put("shard:pod_zones", &pod_zones),
+                ],
+            )
+            .await?;
+
+        if response.succeeded {
+            // All keys written by the transaction get its revision
+            *self.revision.lock().unwrap() = response.revision()?;
+            Ok(())
+        } else {
+            Err(ShardManagerError::ConcurrentUpdate)
+        }
+    }
+
+    async fn read(&self) -> Result<RoutingTable, ShardManagerError> {
+        let response = self
+            .txn(
+                vec![],
+                vec![
+                    self.range("shard:shard_manager_state"),
+                    self.range("shard:pod_weights"),
+                    self.range("shard:pod_zones"),
+                ],
+            )
+            .await?;
+        *self.revision.lock().unwrap() = response.mod_revision(0)?;
+
+        let mut routing_table = match response.value(0)? {
+            Some(value) => {
+                let shard_manager_state: ShardManagerState =
+                    deserialize(&value).map_err(ShardManagerError::SerializationError)?;
+                shard_manager_state.get_routing_table()
+            }
+            None => RoutingTable::new(self.number_of_shards),
+        };
+
+        if let Some(value) = response.value(1)? {
+            let pod_weights: PodWeights =
+                deserialize(&value).map_err(ShardManagerError::SerializationError)?;
+            pod_weights.apply(&mut routing_table);
+        }
+
+        if let Some(value) = response.value(2)? {
+            let pod_zones: PodZones =
+                deserialize(&value).map_err(ShardManagerError::SerializationError)?;
+            pod_zones.apply(&mut routing_table);
+        }
+
+        Ok(routing_table)
+    }
+}
+
+impl PersistenceServiceEtcd {
+    pub fn new(config: &EtcdConfig, number_of_shards: &usize) -> Result<Self, ShardManagerError> {
+        let mut builder = reqwest::Client::builder().timeout(config.request_timeout);
+
+        if let Some(ca_cert) = &config.ca_cert {
+            let ca_cert = reqwest::Certificate::from_pem(&read_pem(ca_cert)?)
+                .map_err(|err| ShardManagerError::EtcdError(err.to_string()))?;
+            builder = builder.add_root_certificate(ca_cert);
+        }
+
+        match (&config.client_cert, &config.client_key) {
+            (Some(client_cert), Some(client_key)) => {
+                let identity = reqwest::Identity::from_pkcs8_pem(
+                    &read_pem(client_cert)?,
+                    &read_pem(client_key)?,
+                )
+                .map_err(|err| ShardManagerError::EtcdError(err.to_string()))?;
+                builder = builder.identity(identity);
+            }
+            (None, None) => {}
+            _ => {
+                return Err(ShardManagerError::EtcdError(
+                    "Both client_cert and client_key have to be configured".to_string(),
+                ))
+            }
+        }
+
+        Ok(Self {
+            client: builder
+                .build()
+                .map_err(|err| ShardManagerError::EtcdError(err.to_string()))?,
+            endpoints: config.endpoints.clone(),
+            key_prefix: config.key_prefix.clone(),
+            number_of_shards: *number_of_shards,
+            revision: Mutex::new(0),
+        })
+    }
+
+    fn key(&self, key: &str) -> String {
+        BASE64_STANDARD.encode(format!("{}{}", self.key_prefix, key))
+    }
+
+    fn put(&self, key: &str, value: &[u8]) -> EtcdRequestOp {
+        EtcdRequestOp::RequestPut {
+            key: self.key(key),
+            value: BASE64_STANDARD.encode(value),
+        }
+    }
+
+    fn range(&self, key: &str) -> EtcdRequestOp {
+        EtcdRequestOp::RequestRange { key: self.key(key) }
+    }
+
+    fn mod_revision_equals(&self, key: &str, revision: i64) -> EtcdCompare {
+        EtcdCompare {
+            target: EtcdCompareTarget::Mod,
+            key: self.key(key),
+            mod_revision: revision.to_string(),
+            result: EtcdCompareResult::Equal,
+        }
+    }
+
+    /// Executes the operations in a single transaction if all the comparisons hold, on the first endpoint
+    /// which responds
+    async fn txn(
+        &self,
+        compare: Vec<EtcdCompare>,
+        operations: Vec<EtcdRequestOp>,
+    ) -> Result<EtcdTxnResponse, ShardManagerError> {
+        let request = EtcdTxnRequest {
+            compare,
+            success: operations,
+        };
+
+        let mut last_error = "No etcd endpoints configured".to_string();
+        for endpoint in &self.endpoints {
+            let url = format!("{}/v3/kv/txn", endpoint.trim_end_matches('/'));
+            let response = self
+                .client
+                .post(&url)
+                .json(&request)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+            match response {
+                Ok(response) => {
+                    return response
+                        .json()
+                        .await
+                        .map_err(|err| ShardManagerError::EtcdError(err.to_string()));
+                }
+                Err(err) => {
+                    warn!("etcd transaction failed on {endpoint}: {err}");
+                    last_error = err.to_string();
+                }
+            }
+        }
+        Err(ShardManagerError::EtcdError(last_error))
+    }
+}
+
+fn read_pem(path: &std::path::Path) -> Result<Vec<u8>, ShardManagerError> {
+    std::fs::read(path).map_err(|err| {
+        ShardManagerError::EtcdError(format!("Failed to read {}: {err}", path.display()))
+    })
+}
+
+#[derive(Debug, Serialize)]
+struct EtcdTxnRequest {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    compare: Vec<EtcdCompare>,
+    success: Vec<EtcdRequestOp>,
+}
+
+#[derive(Debug, Serialize)]
+struct EtcdCompare {
+    target: EtcdCompareTarget,
+    key: String,
+    mod_revision: String,
+    result: EtcdCompareResult,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+enum EtcdCompareTarget {
+    Mod,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+enum EtcdCompareResult {
+    Equal,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum EtcdRequestOp {
+    RequestPut { key: String, value: String },
+    RequestRange { key: String },
+}
+
+// The JSON gateway omits fields with default values and encodes 64 bit integers as strings
+#[derive(Debug, Deserialize)]
+struct EtcdTxnResponse {
+    header: Option<EtcdResponseHeader>,
+    #[serde(default)]
+    succeeded: bool,
+    #[serde(default)]
+    responses: Vec<EtcdResponseOp>,
+}
+
+impl EtcdTxnResponse {
+    /// Gets the revision of the store after the transaction
+    fn revision(&self) -> Result<i64, ShardManagerError> {
+        parse_revision(
+            self.header
+                .as_ref()
+                .and_then(|header| header.revision.as_deref()),
+        )
+    }
+
+    fn key_value(&self, idx: usize) -> Option<&EtcdKeyValue> {
+        self.responses
+            .get(idx)
+            .and_then(|response| response.response_range.as_ref())
+            .and_then(|range| range.kvs.first())
+    }
+
+    /// Gets the modification revision of the key read by the range operation at the given index, 0 if the key
+    /// does not exist
+    fn mod_revision(&self, idx: usize) -> Result<i64, ShardManagerError> {
+        parse_revision(
+            self.key_value(idx)
+                .and_then(|kv| kv.mod_revision.as_deref()),
+        )
+    }
+
+    /// Gets the value read by the range operation at the given index, if the key exists
+    fn value(&self, idx: usize) -> Result<Option<Vec<u8>>, ShardManagerError> {
+        let value = self
+            .key_value(idx)
+            .map(|kv| kv.value.as_deref().unwrap_or_default());
+        value
+            .map(|value| {
+                BASE64_STANDARD
+                    .decode(value)
+                    .map_err(|err| ShardManagerError::EtcdError(err.to_string()))
+            })
+            .transpose()
+    }
+}
+
+fn parse_revision(revision: Option<&str>) -> Result<i64, ShardManagerError> {
+    revision
+        .map(|revision| {
+            revision
+                .parse()
+                .map_err(|_| ShardManagerError::EtcdError(format!("Invalid revision: {revision}")))
+        })
+        .unwrap_or(Ok(0))
+}
+
+#[derive(Debug, Deserialize)]
+struct EtcdResponseHeader {
+    revision: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EtcdResponseOp {
+    response_range: Option<EtcdRangeResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EtcdRangeResponse {
+    #[serde(default)]
+    kvs: Vec<EtcdKeyValue>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EtcdKeyValue {
+    mod_revision: Option<String>,
+    value: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::persistence::{
+        EtcdCompare, EtcdCompareResult, EtcdCompareTarget, EtcdRequestOp, EtcdTxnRequest,
+        EtcdTxnResponse,
+    };
+
+    #[test]
+    fn etcd_txn_request_uses_the_gateway_format() {
+        let request = EtcdTxnRequest {
+            compare: vec![],
+            success: vec![
+                EtcdRequestOp::RequestPut {
+                    key: "a2V5".to_string(),
+                    value: "dmFsdWU=".to_string(),
+                },
+                EtcdRequestOp::RequestRange {
+                    key: "a2V5".to_string(),
+                },
+            ],
+        };
+
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({
+                "success": [
+                    { "request_put": { "key": "a2V5", "value": "dmFsdWU=" } },
+                    { "request_range": { "key": "a2V5" } }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn etcd_txn_response_values_are_decoded() {
+        let response: EtcdTxnResponse = serde_json::from_value(serde_json::json!({
+            "header": { "revision": "7" },
+            "succeeded": true,
+            "responses": [
+                { "response_range": { "header": {}, "kvs": [{ "key": "a2V5", "value": "dmFsdWU=" }], "count": "1" } },
+                { "response_range": { "header": {} } }
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(response.value(0).unwrap(), Some(b"value".to_vec()));
+        assert_eq!(response.value(1).unwrap(), None);
+    }
+
+    #[test]
+    fn etcd_conditional_txn_request_compares_the_mod_revision() {
+        let request = EtcdTxnRequest {
+            compare: vec![EtcdCompare {
+                target: EtcdCompareTarget::Mod,
+                key: "a2V5".to_string(),
+                mod_revision: "7".to_string(),
+                result: EtcdCompareResult::Equal,
+            }],
+            success: vec![EtcdRequestOp::RequestPut {
+                key: "a2V5".to_string(),
+                value: "dmFsdWU=".to_string(),
+            }],
+        };
+
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({
+                "compare": [
+                    { "target": "MOD", "key": "a2V5", "mod_revision": "7", "result": "EQUAL" }
+                ],
+                "success": [
+                    { "request_put": { "key": "a2V5", "value": "dmFsdWU=" } }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn etcd_txn_response_revisions_are_decoded() {
+        let read: EtcdTxnResponse = serde_json::from_value(serde_json::json!({
+            "header": { "revision": "9" },
+            "succeeded": true,
+            "responses": [
+                { "response_range": { "kvs": [{ "key": "a2V5", "mod_revision": "7", "value": "dmFsdWU=" }], "count": "1" } },
+                { "response_range": {} }
+            ]
+        }))
+        .unwrap();
+        assert_eq!(read.mod_revision(0).unwrap(), 7);
+        assert_eq!(read.mod_revision(1).unwrap(), 0);
+
+        let written: EtcdTxnResponse = serde_json::from_value(serde_json::json!({
+            "header": { "revision": "10" },
+            "succeeded": true,
+            "responses": [{ "response_put": { "header": { "revision": "10" } } }]
+        }))
+        .unwrap();
+        assert!(written.succeeded);
+        assert_eq!(written.revision().unwrap(), 10);
+
+        let conflicting: EtcdTxnResponse = serde_json::from_value(serde_json::json!({
+            "header": { "revision": "11" }
+        }))
+        .unwrap();
+        assert!(!conflicting.succeeded);
+    }
+}
@@ -7,7 +7,8 @@ use tracing::trace;
 
 use golem_common::model::ShardId;
 
-use crate::model::{Assignments, Pod, RoutingTable, Unassignments};
+use crate::model::{Assignments, Pod, RoutingTable, RoutingTableEntry, Unassignments};
+use crate::shard_manager_config::PlacementConfig;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Rebalance {
@@ -25,14 +26,24 @@ impl Rebalance {
 
     /// Constructs a rebalance plan from the current state of the routing table.
     ///
+    /// Shards are distributed among the pods proportionally to their weights. The optimal
+    /// count (balanced state) of a pod is number_of_shards*pod_weight/total_weight, which is
+    /// number_of_shards/pod_count when all the pods have the same weight.
+    ///
     /// The `threshold` parameter is used to reduce the number of shard reassignments by
     /// allowing a given number of shards to be over or under the optimal count per pod.
     ///
-    /// The optimal count (balanced state) is number_of_shards/pod_count.
-    /// Threshold is a percentage of the optimal count, so for 10 pods with 1000 shards,
-    /// and a threshold of 10%, pods with shard count between 90 and 110 will be considered
-    /// balanced.
-    pub fn from_routing_table(routing_table: &RoutingTable, threshold: f64) -> Self {
+    /// Threshold is a percentage of the optimal count, so for 10 pods with the same weight,
+    /// 1000 shards and a threshold of 10%, pods with shard count between 90 and 110 will
+    /// be considered balanced.
+    ///
+    /// Pods excluded by the `placement` constraints get no shards, and the shards they already
+    /// have are moved to the other pods.
+    pub fn from_routing_table(
+        routing_table: &RoutingTable,
+        threshold: f64,
+        placement: &PlacementConfig,
+    ) -> Self {
         let mut assignments = Assignments::new();
         let mut unassignments = Unassignments::new();
         let pod_count = routing_table.get_pod_count();
@@ -50,21 +61,68 @@ impl Rebalance {
             .filter(|&(_idx, entry)| entry.shard_ids.is_empty())
             .map(|(idx, _entry)| idx)
             .collect();
-        let optimal_count = routing_table.number_of_shards / pod_count;
-        let upper_threshold = (optimal_count as f64 * (1.0 + threshold)).ceil() as usize;
-        let lower_threshold = (optimal_count as f64 * (1.0 - threshold)).floor() as usize;
+        let excluded_pods = routing_table.get_pods_outside_zones(&placement.zones);
+        let weights: Vec<usize> = routing_table_entries
+            .iter()
+            .map(|entry| {
+                if excluded_pods.contains(&entry.pod) {
+                    0
+                } else {
+                    routing_table.get_pod_weight(&entry.pod) as usize
+                }
+            })
+            .collect();
+        let total_weight: usize = weights.iter().sum();
+        let optimal_counts: Vec<usize> = weights
+            .iter()
+            .map(|weight| routing_table.number_of_shards * weight / total_weight)
+            .collect();
+        let upper_thresholds: Vec<usize> = optimal_counts
+            .iter()
+            .map(|optimal_count| (*optimal_count as f64 * (1.0 + threshold)).ceil() as usize)
+            .collect();
+        let lower_thresholds: Vec<usize> = optimal_counts
+            .iter()
+            .map(|optimal_count| (*optimal_count as f64 * (1.0 - threshold)).floor() as usize)
+            .collect();
+
+        // Shards of the excluded pods are reassigned together with the unassigned ones
+        let mut unassigned_shards = routing_table.get_unassigned_shards();
+        for entry in routing_table_entries
+            .iter_mut()
+            .filter(|entry| excluded_pods.contains(&entry.pod))
+        {
+            for shard in std::mem::take(&mut entry.shard_ids) {
+                trace!("Unassigning shard from excluded pod: {}", shard);
+                unassignments.unassign(entry.pod.clone(), shard);
+                unassigned_shards.insert(shard);
+            }
+        }
 
         // Distributing unassigned shards evenly
-        let unassigned_shards = routing_table.get_unassigned_shards();
         let mut unassigned_shards_iter = unassigned_shards.into_iter();
 
-        // First assign to and distribute among empty pods, until all of them reach the optimal count
+        // First assign to and distribute among empty pods, until all of them reach their optimal count
         if !initial_target_pods.is_empty() {
             let pod_count = initial_target_pods.len();
-            let last_pod_idx = pod_count - 1;
+            let below_optimal_count = |entries: &[RoutingTableEntry], idx: usize| {
+                entries[idx].shard_ids.len() < optimal_counts[idx]
+            };
 
             let mut idx = 0;
-            for shard in unassigned_shards_iter.by_ref() {
+            while initial_target_pods
+                .iter()
+                .any(|&target_idx| below_optimal_count(&routing_table_entries, target_idx))
+            {
+                let Some(shard) = unassigned_shards_iter.next() else {
+                    break;
+                };
+
+                // Skipping the pods which already reached their optimal count
+                while !below_optimal_count(&routing_table_entries, initial_target_pods[idx]) {
+                    idx = (idx + 1) % pod_count;
+                }
+
                 let target_idx = initial_target_pods[idx];
                 let routing_table_entry = &mut routing_table_entries[target_idx];
 
@@ -76,25 +134,23 @@ impl Rebalance {
                 assignments.assign(routing_table_entry.pod.clone(), shard);
                 routing_table_entry.shard_ids.insert(shard);
 
-                // If the last pod is at optimal count, then all pods are at optimal count
-                if idx == last_pod_idx && routing_table_entry.shard_ids.len() == optimal_count {
-                    break;
-                }
-
                 idx = (idx + 1) % pod_count;
             }
         }
 
-        // Now assign to and distribute among all pods
-        {
-            let mut idx = 0;
-            for shard in unassigned_shards_iter {
-                trace!("Assigning shard: {} to {}", shard, idx);
-                let routing_table_entry = &mut routing_table_entries[idx];
-                assignments.assign(routing_table_entry.pod.clone(), shard);
-                routing_table_entry.shard_ids.insert(shard);
-                idx = (idx + 1) % pod_count;
-            }
+        // Now assign each remaining shard to the pod having the fewest shards relative to its weight
+        for shard in unassigned_shards_iter {
+            let idx = (0..pod_count)
+                .filter(|&idx| weights[idx] > 0)
+                .min_by(|&a, &b| {
+                    (routing_table_entries[a].shard_ids.len() * weights[b])
+                        .cmp(&(routing_table_entries[b].shard_ids.len() * weights[a]))
+                })
+                .unwrap();
+            trace!("Assigning shard: {} to {}", shard, idx);
+            let routing_table_entry = &mut routing_table_entries[idx];
+            assignments.assign(routing_table_entry.pod.clone(), shard);
+            routing_table_entry.shard_ids.insert(shard);
         }
 
         if pod_count == 1 {
@@ -104,7 +160,7 @@ impl Rebalance {
             };
         };
 
-        // We redistribute shards from each entry having more than the optimal count
+        // We redistribute shards from each entry having more than its optimal count
         // to the last one until it becomes balanced, and repeat if we have more than one unbalanced entry.
         // We also apply a threshold to the optimal count, to reduce the number of shard reassignments.
         for target_idx in 0..routing_table_entries.len() {
@@ -117,11 +173,16 @@ impl Rebalance {
                 );
             }
 
+            let lower_threshold = lower_thresholds[target_idx];
             if routing_table_entries[target_idx].shard_ids.len() < lower_threshold {
                 trace!("Found a pod with too few shards: {}", target_idx);
 
                 loop {
-                    trace!("Target count: {}..{}", lower_threshold, upper_threshold);
+                    trace!(
+                        "Target count: {}..{}",
+                        lower_threshold,
+                        upper_thresholds[target_idx]
+                    );
                     let current_target_len = routing_table_entries[target_idx].shard_ids.len();
                     if current_target_len < lower_threshold {
                         // Finding a source pod which has more than enough shards
@@ -130,9 +191,11 @@ impl Rebalance {
                             .enumerate()
                             .filter(|(idx, entry)| {
                                 *idx != target_idx && // we need a different source
-                                    entry.shard_ids.len() > lower_threshold
+                                    entry.shard_ids.len() > lower_thresholds[*idx]
+                            })
+                            .max_by_key(|(idx, entry)| {
+                                entry.shard_ids.len() as isize - optimal_counts[*idx] as isize
                             })
-                            .max_by(|(_, a), (_, b)| a.shard_ids.len().cmp(&b.shard_ids.len()))
                         {
                             let shard_id = *routing_table_entries[source_idx]
                                 .shard_ids
@@ -256,6 +319,7 @@ mod tests {
 
     use crate::model::{Pod, RoutingTable};
     use crate::rebalancing::Rebalance;
+    use crate::shard_manager_config::PlacementConfig;
 
     struct TestConfig {
         number_of_shards: usize,
@@ -365,7 +429,8 @@ mod tests {
             initial_assignments: vec![],
         });
 
-        let rebalance = Rebalance::from_routing_table(&routing_table, 0.0);
+        let rebalance =
+            Rebalance::from_routing_table(&routing_table, 0.0, &PlacementConfig::default());
         assert!(rebalance.is_empty());
     }
 
@@ -378,7 +443,8 @@ mod tests {
             initial_assignments: vec![(0, vec![0, 1, 2, 3])],
         });
 
-        let rebalance = Rebalance::from_routing_table(&routing_table, 0.0);
+        let rebalance =
+            Rebalance::from_routing_table(&routing_table, 0.0, &PlacementConfig::default());
         assert!(rebalance.is_empty());
     }
 
@@ -391,7 +457,8 @@ mod tests {
             initial_assignments: vec![(0, vec![0, 3])],
         });
 
-        let rebalance = Rebalance::from_routing_table(&routing_table, 0.0);
+        let rebalance =
+            Rebalance::from_routing_table(&routing_table, 0.0, &PlacementConfig::default());
 
         assert!(rebalance.get_unassignments().is_empty());
         assert_assignments(&rebalance, vec![(0, vec![1, 2, 4, 5])]);
@@ -411,7 +478,8 @@ mod tests {
             ],
         });
 
-        let rebalance = Rebalance::from_routing_table(&routing_table, 0.0);
+        let rebalance =
+            Rebalance::from_routing_table(&routing_table, 0.0, &PlacementConfig::default());
         assert!(rebalance.is_empty());
     }
 
@@ -429,7 +497,8 @@ mod tests {
             ],
         });
 
-        let rebalance = Rebalance::from_routing_table(&routing_table, 0.0);
+        let rebalance =
+            Rebalance::from_routing_table(&routing_table, 0.0, &PlacementConfig::default());
         assert!(rebalance.get_unassignments().is_empty());
 
         assert_assignments(
@@ -456,7 +525,8 @@ mod tests {
             ],
         });
 
-        let rebalance = Rebalance::from_routing_table(&routing_table, 0.0);
+        let rebalance =
+            Rebalance::from_routing_table(&routing_table, 0.0, &PlacementConfig::default());
 
         assert_assignments(
             &rebalance,
@@ -492,7 +562,8 @@ mod tests {
             ],
         });
 
-        let rebalance = Rebalance::from_routing_table(&routing_table, 0.33);
+        let rebalance =
+            Rebalance::from_routing_table(&routing_table, 0.33, &PlacementConfig::default());
 
         assert_assignments(
             &rebalance,
@@ -515,6 +586,32 @@ mod tests {
         );
     }
 
+    #[test]
+    #[traced_test]
+    fn rebalance_one_new_pod_outside_placement_zones_with_threshold() {
+        let mut routing_table = new_routing_table(TestConfig {
+            number_of_shards: 9,
+            number_of_pods: 3,
+            initial_assignments: vec![
+                //
+                (0, vec![0, 1, 2, 3]),
+                (1, vec![4, 5, 6, 7, 8]),
+            ],
+        });
+        routing_table.set_pod_zone(&pod(0), "zone-a".to_string());
+        routing_table.set_pod_zone(&pod(1), "zone-a".to_string());
+        routing_table.set_pod_zone(&pod(2), "zone-b".to_string());
+
+        let rebalance = Rebalance::from_routing_table(
+            &routing_table,
+            0.33,
+            &PlacementConfig {
+                zones: vec!["zone-a".to_string()],
+            },
+        );
+        assert!(rebalance.is_empty());
+    }
+
     #[test]
     #[traced_test]
     fn rebalance_one_new_pod_after_removing_two() {
@@ -530,7 +627,8 @@ mod tests {
             ],
         });
 
-        let rebalance = Rebalance::from_routing_table(&routing_table, 0.0);
+        let rebalance =
+            Rebalance::from_routing_table(&routing_table, 0.0, &PlacementConfig::default());
 
         assert_assignments(
             &rebalance,
@@ -562,7 +660,8 @@ mod tests {
             initial_assignments: vec![(0, vec![0, 1, 2, 3, 4, 5, 6, 7, 8])],
         });
 
-        let rebalance = Rebalance::from_routing_table(&routing_table, 0.0);
+        let rebalance =
+            Rebalance::from_routing_table(&routing_table, 0.0, &PlacementConfig::default());
 
         assert_assignments(
             &rebalance,
@@ -601,7 +700,8 @@ mod tests {
             ],
         });
 
-        let rebalance = Rebalance::from_routing_table(&routing_table, 0.0);
+        let rebalance =
+            Rebalance::from_routing_table(&routing_table, 0.0, &PlacementConfig::default());
 
         assert_assignments(
             &rebalance,
@@ -637,7 +737,8 @@ mod tests {
             initial_assignments: vec![(0, vec![3, 4, 5])],
         });
 
-        let rebalance = Rebalance::from_routing_table(&routing_table, 0.0);
+        let rebalance =
+            Rebalance::from_routing_table(&routing_table, 0.0, &PlacementConfig::default());
 
         assert_assignments(
             &rebalance,
@@ -659,7 +760,8 @@ mod tests {
             initial_assignments: vec![],
         });
 
-        let rebalance = Rebalance::from_routing_table(&routing_table, 0.0);
+        let rebalance =
+            Rebalance::from_routing_table(&routing_table, 0.0, &PlacementConfig::default());
 
         assert_assignments(
             &rebalance,
@@ -684,7 +786,8 @@ mod tests {
             initial_assignments: vec![],
         });
 
-        let rebalance = Rebalance::from_routing_table(&routing_table, 0.0);
+        let rebalance =
+            Rebalance::from_routing_table(&routing_table, 0.0, &PlacementConfig::default());
 
         assert_assignments(
             &rebalance,
@@ -709,7 +812,8 @@ mod tests {
             initial_assignments: vec![(0, vec![0, 1])],
         });
 
-        let rebalance = Rebalance::from_routing_table(&routing_table, 0.0);
+        let rebalance =
+            Rebalance::from_routing_table(&routing_table, 0.0, &PlacementConfig::default());
 
         assert_assignments(
             &rebalance,
@@ -723,4 +827,137 @@ mod tests {
 
         assert_eq!(rebalance.unassignments.unassignments.len(), 0);
     }
+
+    #[test]
+    #[traced_test]
+    fn initial_assign_is_proportional_to_pod_weights() {
+        let mut routing_table = new_routing_table(TestConfig {
+            number_of_shards: 8,
+            number_of_pods: 2,
+            initial_assignments: vec![],
+        });
+        routing_table.set_pod_weight(&pod(1), 3);
+
+        let rebalance =
+            Rebalance::from_routing_table(&routing_table, 0.0, &PlacementConfig::default());
+
+        assert_assignments(
+            &rebalance,
+            vec![
+                //
+                (0, vec![0, 2]),
+                (1, vec![1, 3, 4, 5, 6, 7]),
+            ],
+        );
+
+        assert_eq!(rebalance.unassignments.unassignments.len(), 0);
+    }
+
+    #[test]
+    #[traced_test]
+    fn rebalance_after_increasing_pod_weight() {
+        let mut routing_table = new_routing_table(TestConfig {
+            number_of_shards: 8,
+            number_of_pods: 2,
+            initial_assignments: vec![
+                //
+                (0, vec![0, 1, 2, 3]),
+                (1, vec![4, 5, 6, 7]),
+            ],
+        });
+        routing_table.set_pod_weight(&pod(1), 3);
+
+        let rebalance =
+            Rebalance::from_routing_table(&routing_table, 0.0, &PlacementConfig::default());
+
+        assert_assignments(
+            &rebalance,
+            vec![
+                //
+                (0, vec![]),
+                (1, vec![0, 1]),
+            ],
+        );
+
+        assert_unassignments(
+            &rebalance,
+            vec![
+                //
+                (0, vec![0, 1]),
+                (1, vec![]),
+            ],
+        );
+    }
+
+    #[test]
+    #[traced_test]
+    fn rebalance_moves_shards_off_pods_outside_placement_zones() {
+        let mut routing_table = new_routing_table(TestConfig {
+            number_of_shards: 6,
+            number_of_pods: 3,
+            initial_assignments: vec![
+                //
+                (0, vec![0, 1]),
+                (1, vec![2, 3]),
+                (2, vec![4, 5]),
+            ],
+        });
+        routing_table.set_pod_zone(&pod(0), "zone-a".to_string());
+        routing_table.set_pod_zone(&pod(1), "zone-a".to_string());
+        routing_table.set_pod_zone(&pod(2), "zone-b".to_string());
+
+        let rebalance = Rebalance::from_routing_table(
+            &routing_table,
+            0.0,
+            &PlacementConfig {
+                zones: vec!["zone-a".to_string()],
+            },
+        );
+
+        assert_assignments(
+            &rebalance,
+            vec![
+                //
+                (0, vec![4]),
+                (1, vec![5]),
+                (2, vec![]),
+            ],
+        );
+
+        assert_unassignments(
+            &rebalance,
+            vec![
+                //
+                (2, vec![4, 5]),
+            ],
+        );
+    }
+
+    #[test]
+    #[traced_test]
+    fn placement_zones_are_ignored_without_pods_in_them() {
+        let mut routing_table = new_routing_table(TestConfig {
+            number_of_shards: 4,
+            number_of_pods: 2,
+            initial_assignments: vec![],
+        });
+        routing_table.set_pod_zone(&pod(0), "zone-b".to_string());
+
+        let rebalance = Rebalance::from_routing_table(
+            &routing_table,
+            0.0,
+            &PlacementConfig {
+                zones: vec!["zone-a".to_string()],
+            },
+        );
+
+        assert_assignments(
+            &rebalance,
+            vec![
+                //
+                (0, vec![0, 2]),
+                (1, vec![1, 3]),
+            ],
+        );
+    }
 }
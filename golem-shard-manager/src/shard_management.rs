@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use async_rwlock::RwLock;
@@ -26,6 +26,7 @@ use crate::healthcheck::{get_unhealthy_pods, HealthCheck};
 use crate::model::{Pod, RoutingTable};
 use crate::persistence::PersistenceService;
 use crate::rebalancing::Rebalance;
+use crate::shard_manager_config::PlacementConfig;
 use crate::worker_executor::{assign_shards, revoke_shards, WorkerExecutorService};
 
 #[derive(Clone)]
@@ -45,6 +46,7 @@ impl ShardManagement {
         worker_executors: Arc<dyn WorkerExecutorService + Send + Sync>,
         health_check: Arc<dyn HealthCheck + Send + Sync>,
         threshold: f64,
+        placement: PlacementConfig,
     ) -> Result<Self, ShardManagerError> {
         let routing_table = persistence_service.read().await.unwrap();
 
@@ -78,6 +80,7 @@ impl ShardManagement {
                     persistence_service,
                     worker_executors,
                     threshold,
+                    placement,
                 )
                 .await
             })))
@@ -93,10 +96,25 @@ impl ShardManagement {
         })
     }
 
-    /// Registers a new pod to be added
-    pub async fn register_pod(&self, pod: Pod) {
-        debug!(pod=%pod, "Registering pod");
-        self.updates.lock().await.add_new_pod(pod);
+    /// Registers a new pod to be added, optionally with the weight and the zone it reported
+    pub async fn register_pod(&self, pod: Pod, weight: Option<u32>, zone: Option<String>) {
+        debug!(pod=%pod, weight, zone = zone.as_deref(), "Registering pod");
+        let mut updates = self.updates.lock().await;
+        if let Some(weight) = weight {
+            updates.set_pod_weight(pod.clone(), weight);
+        }
+        if let Some(zone) = zone {
+            updates.set_pod_zone(pod.clone(), zone);
+        }
+        updates.add_new_pod(pod);
+        drop(updates);
+        self.change.notify_one();
+    }
+
+    /// Changes the weight of a pod, rebalancing the shards accordingly
+    pub async fn set_pod_weight(&self, pod: Pod, weight: u32) {
+        debug!(pod=%pod, weight, "Setting pod weight");
+        self.updates.lock().await.set_pod_weight(pod, weight);
         self.change.notify_one();
     }
 
@@ -119,15 +137,24 @@ impl ShardManagement {
         persistence_service: Arc<dyn PersistenceService + Send + Sync>,
         worker_executors: Arc<dyn WorkerExecutorService + Send + Sync>,
         threshold: f64,
+        placement: PlacementConfig,
     ) {
         loop {
             debug!("Shard management loop awaiting changes");
             change.notified().await;
 
-            let (new_pods, removed_pods) = updates.lock().await.reset();
+            let (new_pods, removed_pods, pod_weights, pod_zones) = updates.lock().await.reset();
             debug!(
                 new_pods = new_pods.iter().join(", "),
                 removed_pods = removed_pods.iter().join(", "),
+                pod_weights = pod_weights
+                    .iter()
+                    .map(|(pod, weight)| format!("{pod}: {weight}"))
+                    .join(", "),
+                pod_zones = pod_zones
+                    .iter()
+                    .map(|(pod, zone)| format!("{pod}: {zone}"))
+                    .join(", "),
                 "Shard management loop woken up",
             );
 
@@ -155,8 +182,21 @@ impl ShardManagement {
                         info!(pod= %pod, "Pod added");
                     }
                 }
+
+                for (pod, weight) in pod_weights {
+                    if current_routing_table.has_pod(&pod) {
+                        current_routing_table.set_pod_weight(&pod, weight);
+                        info!(pod= %pod, weight, "Pod weight set");
+                    }
+                }
+                for (pod, zone) in pod_zones {
+                    if current_routing_table.has_pod(&pod) {
+                        info!(pod= %pod, zone= %zone, "Pod zone set");
+                        current_routing_table.set_pod_zone(&pod, zone);
+                    }
+                }
                 let mut rebalance =
-                    Rebalance::from_routing_table(&current_routing_table, threshold);
+                    Rebalance::from_routing_table(&current_routing_table, threshold, &placement);
 
                 for pod in send_full_assignment {
                     let assignments = current_routing_table.get_shards(&pod).unwrap_or_default();
@@ -218,6 +258,8 @@ impl ShardManagement {
 struct ShardManagementChanges {
     new_pods: HashSet<Pod>,
     removed_pods: HashSet<Pod>,
+    pod_weights: HashMap<Pod, u32>,
+    pod_zones: HashMap<Pod, String>,
 }
 
 impl ShardManagementChanges {
@@ -225,6 +267,8 @@ impl ShardManagementChanges {
         ShardManagementChanges {
             new_pods,
             removed_pods,
+            pod_weights: HashMap::new(),
+            pod_zones: HashMap::new(),
         }
     }
 
@@ -235,15 +279,36 @@ impl ShardManagementChanges {
 
     pub fn remove_pod(&mut self, pod: Pod) {
         self.new_pods.remove(&pod);
+        self.pod_weights.remove(&pod);
+        self.pod_zones.remove(&pod);
         self.removed_pods.insert(pod);
     }
 
-    pub fn reset(&mut self) -> (HashSet<Pod>, HashSet<Pod>) {
+    pub fn set_pod_weight(&mut self, pod: Pod, weight: u32) {
+        self.pod_weights.insert(pod, weight);
+    }
+
+    pub fn set_pod_zone(&mut self, pod: Pod, zone: String) {
+        self.pod_zones.insert(pod, zone);
+    }
+
+    pub fn reset(
+        &mut self,
+    ) -> (
+        HashSet<Pod>,
+        HashSet<Pod>,
+        HashMap<Pod, u32>,
+        HashMap<Pod, String>,
+    ) {
         let new = self.new_pods.clone();
         let removed = self.removed_pods.clone();
+        let weights = self.pod_weights.clone();
+        let zones = self.pod_zones.clone();
         self.new_pods.clear();
         self.removed_pods.clear();
-        (new, removed)
+        self.pod_weights.clear();
+        self.pod_zones.clear();
+        (new, removed, weights, zones)
     }
 }
 
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
@@ -28,9 +28,11 @@ use crate::shard_manager_config::HealthCheckMode::K8s;
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ShardManagerConfig {
     pub tracing: TracingConfig,
+    pub persistence: PersistenceConfig,
     pub redis: RedisConfig,
     pub worker_executors: WorkerExecutorServiceConfig,
     pub health_check: HealthCheckConfig,
+    pub placement: PlacementConfig,
     pub http_port: u16,
     pub number_of_shards: usize,
     pub rebalance_threshold: f64,
@@ -40,9 +42,11 @@ impl Default for ShardManagerConfig {
     fn default() -> Self {
         Self {
             tracing: TracingConfig::local_dev("shard-manager"),
+            persistence: PersistenceConfig::default(),
             redis: RedisConfig::default(),
             worker_executors: WorkerExecutorServiceConfig::default(),
             health_check: HealthCheckConfig::default(),
+            placement: PlacementConfig::default(),
             http_port: 8081,
             number_of_shards: 1024,
             rebalance_threshold: 0.1,
@@ -52,18 +56,27 @@ impl Default for ShardManagerConfig {
 
 impl HasConfigExamples<ShardManagerConfig> for ShardManagerConfig {
     fn examples() -> Vec<ConfigExample<ShardManagerConfig>> {
-        vec![(
-            "with k8s healthcheck",
-            Self {
-                health_check: HealthCheckConfig {
-                    delay: Duration::from_secs(1),
-                    mode: K8s(HealthCheckK8sConfig {
-                        namespace: "namespace".to_string(),
-                    }),
+        vec![
+            (
+                "with k8s healthcheck",
+                Self {
+                    health_check: HealthCheckConfig {
+                        delay: Duration::from_secs(1),
+                        mode: K8s(HealthCheckK8sConfig {
+                            namespace: "namespace".to_string(),
+                        }),
+                    },
+                    ..Self::default()
                 },
-                ..Self::default()
-            },
-        )]
+            ),
+            (
+                "with etcd persistence",
+                Self {
+                    persistence: PersistenceConfig::Etcd(EtcdConfig::default()),
+                    ..Self::default()
+                },
+            ),
+        ]
     }
 }
 
@@ -124,6 +137,58 @@ pub struct HealthCheckK8sConfig {
     pub namespace: String,
 }
 
+/// Constraints on which pods the shards are assigned to
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PlacementConfig {
+    /// Shards are only assigned to pods registered with one of these availability zones, as long
+    /// as there is at least one such pod. Empty means no constraint.
+    pub zones: Vec<String>,
+}
+
+/// Where the routing table is stored. Redis uses the `redis` section of the configuration.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", content = "config")]
+pub enum PersistenceConfig {
+    Redis(Empty),
+    Etcd(EtcdConfig),
+}
+
+impl Default for PersistenceConfig {
+    fn default() -> Self {
+        Self::Redis(Empty {})
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EtcdConfig {
+    /// Client URLs of the etcd cluster members, tried in order until one of them responds. Use
+    /// `https` URLs to connect with TLS.
+    pub endpoints: Vec<String>,
+    pub key_prefix: String,
+    #[serde(with = "humantime_serde")]
+    pub request_timeout: Duration,
+    /// PEM file of the CA certificate the etcd server certificates are verified with, in addition
+    /// to the system roots
+    pub ca_cert: Option<PathBuf>,
+    /// PEM files of the client certificate and its PKCS #8 private key, for etcd clusters
+    /// requiring client certificate authentication
+    pub client_cert: Option<PathBuf>,
+    pub client_key: Option<PathBuf>,
+}
+
+impl Default for EtcdConfig {
+    fn default() -> Self {
+        Self {
+            endpoints: vec!["http://localhost:2379".to_string()],
+            key_prefix: "".to_string(),
+            request_timeout: Duration::from_secs(5),
+            ca_cert: None,
+            client_cert: None,
+            client_key: None,
+        }
+    }
+}
+
 pub fn make_config_loader() -> ConfigLoader<ShardManagerConfig> {
     ConfigLoader::new_with_examples(Path::new("config/shard-manager.toml"))
 }
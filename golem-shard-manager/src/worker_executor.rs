@@ -182,6 +182,7 @@ impl WorkerExecutorService for WorkerExecutorServiceDefault {
 impl WorkerExecutorServiceDefault {
     pub fn new(config: WorkerExecutorServiceConfig) -> Self {
         let client = MultiTargetGrpcClient::new(
+            "worker_executor",
             |channel| {
                 WorkerExecutorClient::new(channel)
                     .send_compressed(CompressionEncoding::Gzip)
@@ -72,6 +72,7 @@ pub trait ComponentService {
                 .get_components(GetComponentsRequest {
                     project_id: None,
                     component_name: Some(file_name.to_string()),
+                    labels: vec![],
                 })
                 .await
                 .expect("Failed to call get-components")
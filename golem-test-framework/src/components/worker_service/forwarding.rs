@@ -240,6 +240,7 @@ impl WorkerService for ForwardingWorkerService {
                     max_memory_per_worker: i64::MAX,
                 }),
                 context: request.context,
+                priority: request.priority,
             })
             .await?
             .into_inner();
@@ -290,6 +291,8 @@ impl WorkerService for ForwardingWorkerService {
                     max_memory_per_worker: i64::MAX,
                 }),
                 context: request.context,
+                priority: request.priority,
+                timeout_millis: request.timeout_millis,
             })
             .await?
             .into_inner();
@@ -350,6 +353,7 @@ impl WorkerService for ForwardingWorkerService {
                     available_fuel: i64::MAX,
                     max_memory_per_worker: i64::MAX,
                 }),
+                backfill_count: request.backfill_count,
             })
             .await?
             .into_inner())
@@ -503,6 +507,8 @@ impl WorkerService for ForwardingWorkerService {
                 from_oplog_index: request.from_oplog_index,
                 cursor: request.cursor,
                 count: request.count,
+                read_replica: false,
+                invocations_before: request.invocations_before,
             })
             .await?
             .into_inner();
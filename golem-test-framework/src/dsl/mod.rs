@@ -38,13 +38,14 @@ use golem_common::model::oplog::{
 use golem_common::model::public_oplog::PublicOplogEntry;
 use golem_common::model::regions::DeletedRegions;
 use golem_common::model::{
-    ComponentId, ComponentType, ComponentVersion, FailedUpdateRecord, IdempotencyKey, ScanCursor,
-    SuccessfulUpdateRecord, TargetWorkerId, WorkerFilter, WorkerId, WorkerMetadata,
-    WorkerResourceDescription, WorkerStatusRecord,
+    ComponentId, ComponentType, ComponentVersion, FailedUpdateRecord, IdempotencyKey,
+    InvocationPriority, ScanCursor, SuccessfulUpdateRecord, TargetWorkerId, WorkerFilter, WorkerId,
+    WorkerMetadata, WorkerResourceDescription, WorkerStatusRecord,
 };
 use golem_wasm_rpc::Value;
 use std::collections::HashMap;
 use std::path::Path;
+use std::time::Duration;
 use tokio::select;
 use tokio::sync::mpsc::UnboundedReceiver;
 use tokio::sync::oneshot::Sender;
@@ -133,6 +134,13 @@ pub trait TestDsl {
         function_name: &str,
         params: Vec<Value>,
     ) -> crate::Result<Result<Vec<Value>, Error>>;
+    async fn invoke_and_await_with_timeout(
+        &self,
+        worker_id: impl Into<TargetWorkerId> + Send + Sync,
+        function_name: &str,
+        params: Vec<Value>,
+        timeout: Duration,
+    ) -> crate::Result<Result<Vec<Value>, Error>>;
     async fn invoke_and_await_json(
         &self,
         worker_id: impl Into<TargetWorkerId> + Send + Sync,
@@ -267,6 +275,7 @@ impl<T: TestDependencies + Send + Sync> TestDsl for T {
                 name: name.to_string(),
                 args,
                 env,
+                component_version_alias: None,
             })
             .await?;
 
@@ -379,6 +388,7 @@ impl<T: TestDependencies + Send + Sync> TestDsl for T {
                     params: params.into_iter().map(|v| v.into()).collect(),
                 }),
                 context: None,
+                priority: InvocationPriority::Normal.into(),
             })
             .await?;
 
@@ -412,6 +422,7 @@ impl<T: TestDependencies + Send + Sync> TestDsl for T {
                     params: params.into_iter().map(|v| v.into()).collect(),
                 }),
                 context: None,
+                priority: InvocationPriority::Normal.into(),
             })
             .await?;
 
@@ -488,6 +499,48 @@ impl<T: TestDependencies + Send + Sync> TestDsl for T {
                     params: params.into_iter().map(|v| v.into()).collect(),
                 }),
                 context: None,
+                priority: InvocationPriority::Normal.into(),
+                timeout_millis: None,
+            })
+            .await?;
+
+        match invoke_response.result {
+            None => Err(anyhow!("No response from invoke_and_await")),
+            Some(invoke_and_await_response::Result::Success(response)) => Ok(Ok(response
+                .result
+                .into_iter()
+                .map(|v| v.try_into())
+                .collect::<Result<Vec<Value>, String>>()
+                .map_err(|err| anyhow!("Invocation result had unexpected format: {err}"))?)),
+            Some(invoke_and_await_response::Result::Error(WorkerError { error: Some(error) })) => {
+                Ok(Err(error))
+            }
+            Some(invoke_and_await_response::Result::Error(_)) => {
+                Err(anyhow!("Empty error response from invoke_and_await"))
+            }
+        }
+    }
+
+    async fn invoke_and_await_with_timeout(
+        &self,
+        worker_id: impl Into<TargetWorkerId> + Send + Sync,
+        function_name: &str,
+        params: Vec<Value>,
+        timeout: Duration,
+    ) -> crate::Result<Result<Vec<Value>, Error>> {
+        let target_worker_id: TargetWorkerId = worker_id.into();
+        let invoke_response = self
+            .worker_service()
+            .invoke_and_await(InvokeAndAwaitRequest {
+                worker_id: Some(target_worker_id.into()),
+                idempotency_key: Some(IdempotencyKey::fresh().into()),
+                function: function_name.to_string(),
+                invoke_parameters: Some(InvokeParameters {
+                    params: params.into_iter().map(|v| v.into()).collect(),
+                }),
+                context: None,
+                priority: InvocationPriority::Normal.into(),
+                timeout_millis: Some(timeout.as_millis() as u64),
             })
             .await?;
 
@@ -524,6 +577,8 @@ impl<T: TestDependencies + Send + Sync> TestDsl for T {
                 function: function_name.to_string(),
                 invoke_parameters: params,
                 context: None,
+                priority: InvocationPriority::Normal.into(),
+                timeout_millis: None,
             })
             .await?;
 
@@ -550,6 +605,7 @@ impl<T: TestDependencies + Send + Sync> TestDsl for T {
             let mut response = cloned_service
                 .connect_worker(ConnectWorkerRequest {
                     worker_id: Some(worker_id.clone().into()),
+                    backfill_count: 0,
                 })
                 .await
                 .expect("Failed to connect worker");
@@ -579,6 +635,7 @@ impl<T: TestDependencies + Send + Sync> TestDsl for T {
                 let mut response = cloned_service
                     .connect_worker(ConnectWorkerRequest {
                         worker_id: Some(worker_id.clone().into()),
+                        backfill_count: 0,
                     })
                     .await
                     .expect("Failed to connect worker");
@@ -625,6 +682,7 @@ impl<T: TestDependencies + Send + Sync> TestDsl for T {
             let mut response = cloned_service
                 .connect_worker(ConnectWorkerRequest {
                     worker_id: Some(worker_id.clone().into()),
+                    backfill_count: 0,
                 })
                 .await
                 .expect("Failed to connect to worker");
@@ -648,6 +706,7 @@ impl<T: TestDependencies + Send + Sync> TestDsl for T {
             let mut response = cloned_service
                 .connect_worker(ConnectWorkerRequest {
                     worker_id: Some(worker_id.clone().into()),
+                    backfill_count: 0,
                 })
                 .await
                 .expect("Failed to connect worker");
@@ -781,6 +840,7 @@ impl<T: TestDependencies + Send + Sync> TestDsl for T {
                     from_oplog_index: from.into(),
                     cursor: cursor.clone(),
                     count: 100,
+                    invocations_before: None,
                 })
                 .await?;
 
@@ -911,6 +971,7 @@ pub fn worker_error_message(error: &Error) -> String {
     match error {
         Error::BadRequest(errors) => errors.errors.join(", "),
         Error::Unauthorized(error) => error.error.clone(),
+        Error::Forbidden(error) => error.error.clone(),
         Error::LimitExceeded(error) => error.error.clone(),
         Error::NotFound(error) => error.error.clone(),
         Error::AlreadyExists(error) => error.error.clone(),
@@ -994,6 +1055,22 @@ pub fn worker_error_message(error: &Error) -> String {
                 worker_execution_error::Error::ShardingNotReady(_error) => {
                     "Sharing not ready".to_string()
                 }
+                worker_execution_error::Error::TooManyPendingInvocations(error) => format!(
+                    "Too many pending invocations for worker {:?}: {}",
+                    error.worker_id, error.pending_invocations
+                ),
+                worker_execution_error::Error::InvocationTimedOut(error) => format!(
+                    "Invocation of worker {:?} timed out after {} ms",
+                    error.worker_id, error.timeout_millis
+                ),
+                worker_execution_error::Error::ExecutorBusy(error) => format!(
+                    "Worker executor is busy with {} active workers",
+                    error.max_active_workers
+                ),
+                worker_execution_error::Error::InvocationNotFound(error) => format!(
+                    "No invocation {:?} was found for worker {:?}",
+                    error.idempotency_key, error.worker_id
+                ),
             },
         },
     }
@@ -1033,7 +1110,18 @@ pub fn to_worker_metadata(
                 oplog_idx: OplogIndex::default(),
                 status: metadata.status.try_into().expect("invalid status"),
                 overridden_retry_config: None, // not passed through gRPC
-                deleted_regions: DeletedRegions::new(),
+                deleted_regions: {
+                    let mut deleted_regions = DeletedRegions::new();
+                    for region in &metadata.corrupted_oplog_regions {
+                        deleted_regions.add_corrupted(
+                            region
+                                .clone()
+                                .try_into()
+                                .expect("invalid corrupted oplog region"),
+                        );
+                    }
+                    deleted_regions
+                },
                 pending_invocations: vec![],
                 pending_updates: metadata
                     .updates
@@ -1110,6 +1198,11 @@ pub fn to_worker_metadata(
                         )
                     })
                     .collect(),
+                last_invoked_function: metadata.last_invoked_function.clone().map(|f| {
+                    f.try_into()
+                        .expect("invalid last invoked function in worker metadata")
+                }),
+                changed_environment: None, // already applied to args and env
             },
             parent: None,
         },
@@ -1220,6 +1313,13 @@ pub trait TestDslUnsafe {
         function_name: &str,
         params: Vec<Value>,
     ) -> Result<Vec<Value>, Error>;
+    async fn invoke_and_await_with_timeout(
+        &self,
+        worker_id: impl Into<TargetWorkerId> + Send + Sync,
+        function_name: &str,
+        params: Vec<Value>,
+        timeout: Duration,
+    ) -> Result<Vec<Value>, Error>;
     async fn invoke_and_await_json(
         &self,
         worker_id: impl Into<TargetWorkerId> + Send + Sync,
@@ -1371,6 +1471,24 @@ impl<T: TestDsl + Sync> TestDslUnsafe for T {
             .expect("Failed to invoke function")
     }
 
+    async fn invoke_and_await_with_timeout(
+        &self,
+        worker_id: impl Into<TargetWorkerId> + Send + Sync,
+        function_name: &str,
+        params: Vec<Value>,
+        timeout: Duration,
+    ) -> Result<Vec<Value>, Error> {
+        <T as TestDsl>::invoke_and_await_with_timeout(
+            self,
+            worker_id,
+            function_name,
+            params,
+            timeout,
+        )
+        .await
+        .expect("Failed to invoke function")
+    }
+
     async fn invoke_and_await_json(
         &self,
         worker_id: impl Into<TargetWorkerId> + Send + Sync,
@@ -31,7 +31,10 @@ impl<Ctx: WorkerCtx> Host for DurableWorkerCtx<Ctx> {
             WrappedFunctionType::ReadLocal,
             "golem_environment::get_environment",
             (),
-            |ctx| Box::pin(async { Host::get_environment(&mut ctx.as_wasi_view()).await }),
+            |ctx| {
+                let env = ctx.state.env.clone();
+                Box::pin(async move { Ok(env) })
+            },
         )
         .await
     }
@@ -44,7 +47,10 @@ impl<Ctx: WorkerCtx> Host for DurableWorkerCtx<Ctx> {
             WrappedFunctionType::ReadLocal,
             "golem_environment::get_arguments",
             (),
-            |ctx| Box::pin(async { Host::get_arguments(&mut ctx.as_wasi_view()).await }),
+            |ctx| {
+                let args = ctx.state.args.clone();
+                Box::pin(async move { Ok(args) })
+            },
         )
         .await
     }
@@ -12,13 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use wasmtime::component::Resource;
+use wasmtime_wasi::{subscribe, Subscribe};
 
 use crate::durable_host::serialized::SerializableError;
 use crate::durable_host::{Durability, DurableWorkerCtx};
 use crate::metrics::wasm::record_host_function_call;
 use crate::services::oplog::CommitLevel;
+use crate::services::virtual_clock::VirtualClock;
 use crate::workerctx::WorkerCtx;
 use golem_common::model::oplog::WrappedFunctionType;
 use wasmtime_wasi::bindings::clocks::monotonic_clock::{Duration, Host, Instant, Pollable};
@@ -33,7 +37,14 @@ impl<Ctx: WorkerCtx> Host for DurableWorkerCtx<Ctx> {
             WrappedFunctionType::ReadLocal,
             "monotonic_clock::now",
             (),
-            |ctx| Box::pin(async { Host::now(&mut ctx.as_wasi_view()).await }),
+            |ctx| {
+                Box::pin(async {
+                    match ctx.state.virtual_clock().await? {
+                        Some(clock) => Ok(clock.now()),
+                        None => Host::now(&mut ctx.as_wasi_view()).await,
+                    }
+                })
+            },
         )
         .await
     }
@@ -54,7 +65,7 @@ impl<Ctx: WorkerCtx> Host for DurableWorkerCtx<Ctx> {
     async fn subscribe_instant(&mut self, when: Instant) -> anyhow::Result<Resource<Pollable>> {
         let _permit = self.begin_async_host_function().await?;
         record_host_function_call("clocks::monotonic_clock", "subscribe_instant");
-        Host::subscribe_instant(&mut self.as_wasi_view(), when).await
+        self.subscribe_instant_on_worker_clock(when).await
     }
 
     async fn subscribe_duration(&mut self, when: Duration) -> anyhow::Result<Resource<Pollable>> {
@@ -65,12 +76,48 @@ impl<Ctx: WorkerCtx> Host for DurableWorkerCtx<Ctx> {
             WrappedFunctionType::ReadLocal,
             "monotonic_clock::subscribe_duration",
             (),
-            |ctx| Box::pin(async { Host::now(&mut ctx.as_wasi_view()).await }),
+            |ctx| {
+                Box::pin(async {
+                    match ctx.state.virtual_clock().await? {
+                        Some(clock) => Ok(clock.now()),
+                        None => Host::now(&mut ctx.as_wasi_view()).await,
+                    }
+                })
+            },
         )
         .await?;
         self.state.oplog.commit(CommitLevel::DurableOnly).await;
         let when = now.saturating_add(when);
-        Host::subscribe_instant(&mut self.as_wasi_view(), when).await
+        self.subscribe_instant_on_worker_clock(when).await
+    }
+}
+
+impl<Ctx: WorkerCtx> DurableWorkerCtx<Ctx> {
+    /// Subscribes to an instant of the worker's clock, which only passes when the virtual time
+    /// is advanced if the worker's clock is virtualized
+    async fn subscribe_instant_on_worker_clock(
+        &mut self,
+        when: Instant,
+    ) -> anyhow::Result<Resource<Pollable>> {
+        match self.state.virtual_clock().await? {
+            Some(clock) => {
+                let deadline = self.table().push(VirtualDeadline { clock, when })?;
+                subscribe(self.table(), deadline)
+            }
+            None => Host::subscribe_instant(&mut self.as_wasi_view(), when).await,
+        }
+    }
+}
+
+struct VirtualDeadline {
+    clock: Arc<VirtualClock>,
+    when: Instant,
+}
+
+#[async_trait]
+impl Subscribe for VirtualDeadline {
+    async fn ready(&mut self) {
+        self.clock.wait_until(self.when).await
     }
 }
 
@@ -31,7 +31,20 @@ impl<Ctx: WorkerCtx> Host for DurableWorkerCtx<Ctx> {
             WrappedFunctionType::ReadLocal,
             "wall_clock::now",
             (),
-            |ctx| Box::pin(async { Host::now(&mut ctx.as_wasi_view()).await }),
+            |ctx| {
+                Box::pin(async {
+                    match ctx.state.virtual_clock().await? {
+                        Some(clock) => {
+                            let now = clock.now();
+                            Ok(Datetime {
+                                seconds: now / 1_000_000_000,
+                                nanoseconds: (now % 1_000_000_000) as u32,
+                            })
+                        }
+                        None => Host::now(&mut ctx.as_wasi_view()).await,
+                    }
+                })
+            },
         )
         .await
     }
@@ -362,12 +362,13 @@ impl<Ctx: WorkerCtx, SerializableInput, SerializableSuccess, SerializableErr>
 
             intermediate.and_then(|value| to_result(self, value))
         } else {
-            let (_, oplog_entry) = crate::get_oplog_entry!(
+            let (oplog_index, oplog_entry) = crate::get_oplog_entry!(
                 self.state.replay_state,
                 OplogEntry::ImportedFunctionInvoked,
                 OplogEntry::ImportedFunctionInvokedV1
             )?;
-            DurableWorkerCtx::<Ctx>::validate_oplog_entry(&oplog_entry, function_name)?;
+            self.validate_oplog_entry(oplog_index, &oplog_entry, function_name)
+                .await?;
 
             let oplog = self.state.oplog.clone();
             let result = from_serialized(self, oplog, &oplog_entry).await;
@@ -476,12 +477,13 @@ impl<Ctx: WorkerCtx, SerializableInput, SerializableSuccess, SerializableErr>
             }
             result
         } else {
-            let (_, oplog_entry) = crate::get_oplog_entry!(
+            let (oplog_index, oplog_entry) = crate::get_oplog_entry!(
                 self.state.replay_state,
                 OplogEntry::ImportedFunctionInvoked,
                 OplogEntry::ImportedFunctionInvokedV1
             )?;
-            DurableWorkerCtx::<Ctx>::validate_oplog_entry(&oplog_entry, function_name)?;
+            self.validate_oplog_entry(oplog_index, &oplog_entry, function_name)
+                .await?;
             let response: Result<SerializableSuccess, SerializableErr> =
                 DurableWorkerCtx::<Ctx>::default_load(self.state.oplog.clone(), &oplog_entry).await;
 
@@ -546,13 +548,22 @@ impl<Ctx: WorkerCtx> DurableWorkerCtx<Ctx> {
         SerializedErr: Encode + Debug + From<GolemError> + Into<Err> + Send + Sync,
     {
         if self.state.persistence_level != PersistenceLevel::PersistNothing {
+            // Batched remote writes belonging to an already open one are the parts of an outgoing
+            // http request, so their responses are response body chunks
+            let max_inline_response_size = match wrapped_function_type {
+                WrappedFunctionType::WriteRemoteBatched(Some(_)) => {
+                    self.state.http_durability.blob_storage_threshold
+                }
+                _ => None,
+            };
             self.state
                 .oplog
-                .add_imported_function_invoked(
+                .add_imported_function_invoked_with_response_limit(
                     function_name.to_string(),
                     &serializable_input,
                     &serializable_result,
                     wrapped_function_type.clone(),
+                    max_inline_response_size,
                 )
                 .await
                 .unwrap_or_else(|err| {
@@ -578,38 +589,31 @@ impl<Ctx: WorkerCtx> DurableWorkerCtx<Ctx> {
         Ok(())
     }
 
-    fn validate_oplog_entry(
+    async fn validate_oplog_entry(
+        &self,
+        oplog_index: OplogIndex,
         oplog_entry: &OplogEntry,
         expected_function_name: &str,
     ) -> Result<(), GolemError> {
-        if let OplogEntry::ImportedFunctionInvoked { function_name, .. } = oplog_entry {
-            if function_name != expected_function_name {
-                error!(
-                    "Unexpected imported function call entry in oplog: expected {}, got {}",
-                    expected_function_name, function_name
-                );
-                Err(GolemError::unexpected_oplog_entry(
-                    expected_function_name,
-                    function_name,
-                ))
-            } else {
-                Ok(())
-            }
-        } else if let OplogEntry::ImportedFunctionInvokedV1 { function_name, .. } = oplog_entry {
-            if function_name != expected_function_name {
+        match oplog_entry {
+            OplogEntry::ImportedFunctionInvoked { function_name, .. }
+            | OplogEntry::ImportedFunctionInvokedV1 { function_name, .. }
+                if function_name != expected_function_name =>
+            {
                 error!(
                     "Unexpected imported function call entry in oplog: expected {}, got {}",
                     expected_function_name, function_name
                 );
+                self.state
+                    .replay_state
+                    .record_divergence(oplog_index, function_name, expected_function_name)
+                    .await;
                 Err(GolemError::unexpected_oplog_entry(
                     expected_function_name,
                     function_name,
                 ))
-            } else {
-                Ok(())
             }
-        } else {
-            Ok(())
+            _ => Ok(()),
         }
     }
 }
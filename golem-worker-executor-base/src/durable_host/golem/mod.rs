@@ -13,6 +13,7 @@
 // limitations under the License.
 
 pub mod v11;
+pub mod v12;
 
 use anyhow::anyhow;
 use async_trait::async_trait;
@@ -23,7 +24,9 @@ use uuid::Uuid;
 use wasmtime::component::Resource;
 use wasmtime_wasi::WasiView;
 
-use crate::durable_host::serialized::SerializableError;
+use crate::durable_host::serialized::{
+    SerializableError, SerializableSelfMetadata, SerializableWorkerMetadata,
+};
 use crate::durable_host::wasm_rpc::UrnExtensions;
 use crate::durable_host::{Durability, DurableWorkerCtx};
 use crate::error::GolemError;
@@ -41,7 +44,8 @@ use crate::workerctx::{InvocationManagement, StatusManagement, WorkerCtx};
 use golem_common::model::oplog::{OplogEntry, OplogIndex, WrappedFunctionType};
 use golem_common::model::regions::OplogRegion;
 use golem_common::model::{
-    ComponentId, IdempotencyKey, OwnedWorkerId, PromiseId, ScanCursor, WorkerId,
+    ComponentId, IdempotencyKey, OwnedWorkerId, PromiseId, ScanCursor, WorkerFilter, WorkerId,
+    WorkerName,
 };
 
 #[async_trait]
@@ -80,10 +84,39 @@ impl<Ctx: WorkerCtx> HostGetWorkers for DurableWorkerCtx<Ctx> {
             })?;
 
         if let Some(cursor) = cursor {
-            let (new_cursor, workers) = self
-                .state
-                .get_workers(&component_id, filter, cursor, count, precise)
-                .await?;
+            let (new_cursor, workers) = Durability::<
+                Ctx,
+                (ComponentId, Option<WorkerFilter>, ScanCursor, u64, bool),
+                (Option<ScanCursor>, Vec<SerializableWorkerMetadata>),
+                SerializableError,
+            >::wrap(
+                self,
+                WrappedFunctionType::ReadRemote,
+                "golem::api::get-workers::get-next",
+                (
+                    component_id.clone(),
+                    filter.clone(),
+                    cursor.clone(),
+                    count,
+                    precise,
+                ),
+                |ctx| {
+                    Box::pin(async move {
+                        let (new_cursor, workers) = ctx
+                            .state
+                            .get_workers(&component_id, filter, cursor, count, precise)
+                            .await?;
+                        Ok((
+                            new_cursor,
+                            workers
+                                .into_iter()
+                                .map(SerializableWorkerMetadata::from)
+                                .collect(),
+                        ))
+                    })
+                },
+            )
+            .await?;
 
             let _ = self
                 .as_wasi_view()
@@ -132,6 +165,37 @@ impl GetWorkersEntry {
     }
 }
 
+impl<Ctx: WorkerCtx> DurableWorkerCtx<Ctx> {
+    /// Gets the worker's own metadata, recorded in the oplog so a replayed worker sees the same
+    /// snapshot. Shared by all the golem:api versions exposing get-self-metadata.
+    async fn get_durable_self_metadata(&mut self) -> Result<SerializableSelfMetadata, GolemError> {
+        Durability::<Ctx, (), SerializableSelfMetadata, SerializableError>::wrap(
+            self,
+            WrappedFunctionType::ReadLocal,
+            "golem::api::get-self-metadata",
+            (),
+            |ctx| {
+                Box::pin(async move {
+                    let metadata = ctx.public_state.worker().get_metadata().await?;
+                    let retry_config = ctx
+                        .state
+                        .overridden_retry_policy
+                        .clone()
+                        .unwrap_or_else(|| ctx.state.config.retry.clone());
+                    Ok(SerializableSelfMetadata {
+                        created_at: metadata.created_at,
+                        retry_config,
+                        pending_update_count: metadata.last_known_status.pending_updates.len()
+                            as u32,
+                        metadata: metadata.into(),
+                    })
+                })
+            },
+        )
+        .await
+    }
+}
+
 #[async_trait]
 impl<Ctx: WorkerCtx> golem::api0_2_0::host::Host for DurableWorkerCtx<Ctx> {
     async fn create_promise(&mut self) -> Result<golem::api0_2_0::host::PromiseId, anyhow::Error> {
@@ -279,6 +343,7 @@ impl<Ctx: WorkerCtx> golem::api0_2_0::host::Host for DurableWorkerCtx<Ctx> {
                 .oplog
                 .add_and_commit(OplogEntry::jump(jump))
                 .await;
+            self.state.restore_sagas().await;
 
             debug!("Interrupting live execution for jumping from {jump_source} to {jump_target}",);
             Err(InterruptKind::Jump.into())
@@ -361,6 +426,7 @@ impl<Ctx: WorkerCtx> golem::api0_2_0::host::Host for DurableWorkerCtx<Ctx> {
                         .oplog
                         .add_and_commit(OplogEntry::jump(deleted_region))
                         .await;
+                    self.state.restore_sagas().await;
                 }
             }
 
@@ -528,8 +594,8 @@ impl<Ctx: WorkerCtx> golem::api0_2_0::host::Host for DurableWorkerCtx<Ctx> {
     async fn get_self_metadata(&mut self) -> anyhow::Result<WorkerMetadata> {
         let _permit = self.begin_async_host_function().await?;
         record_host_function_call("golem::api", "get_self_metadata");
-        let metadata = self.public_state.worker().get_metadata().await?;
-        Ok(metadata.into())
+        let self_metadata = self.get_durable_self_metadata().await?;
+        Ok(self_metadata.metadata.into())
     }
 
     async fn get_worker_metadata(
@@ -540,24 +606,40 @@ impl<Ctx: WorkerCtx> golem::api0_2_0::host::Host for DurableWorkerCtx<Ctx> {
         record_host_function_call("golem::api", "get_worker_metadata");
         let worker_id: WorkerId = worker_id.into();
         let owned_worker_id = OwnedWorkerId::new(&self.owned_worker_id.account_id, &worker_id);
-        let metadata = self.state.worker_service.get(&owned_worker_id).await;
-
-        match metadata {
-            Some(metadata) => {
-                let last_known_status = Ctx::compute_latest_worker_status(
-                    &self.state,
-                    &owned_worker_id,
-                    &Some(metadata.clone()),
-                )
-                .await?;
-                let updated_metadata = golem_common::model::WorkerMetadata {
-                    last_known_status,
-                    ..metadata
-                };
-                Ok(Some(updated_metadata.into()))
-            }
-            None => Ok(None),
-        }
+        let metadata = Durability::<
+            Ctx,
+            WorkerId,
+            Option<SerializableWorkerMetadata>,
+            SerializableError,
+        >::wrap(
+            self,
+            WrappedFunctionType::ReadRemote,
+            "golem::api::get-worker-metadata",
+            worker_id,
+            |ctx| {
+                Box::pin(async move {
+                    let metadata = ctx.state.worker_service.get(&owned_worker_id).await;
+                    match metadata {
+                        Some(metadata) => {
+                            let last_known_status = Ctx::compute_latest_worker_status(
+                                &ctx.state,
+                                &owned_worker_id,
+                                &Some(metadata.clone()),
+                            )
+                            .await?;
+                            let updated_metadata = golem_common::model::WorkerMetadata {
+                                last_known_status,
+                                ..metadata
+                            };
+                            Ok(Some(updated_metadata.into()))
+                        }
+                        None => Ok(None),
+                    }
+                })
+            },
+        )
+        .await?;
+        Ok(metadata.map(|metadata| metadata.into()))
     }
 }
 
@@ -700,7 +782,7 @@ impl From<WorkerId> for golem::api0_2_0::host::WorkerId {
     fn from(worker_id: WorkerId) -> Self {
         golem::api0_2_0::host::WorkerId {
             component_id: worker_id.component_id.into(),
-            worker_name: worker_id.worker_name,
+            worker_name: worker_id.worker_name.into_string(),
         }
     }
 }
@@ -709,7 +791,7 @@ impl From<golem::api0_2_0::host::WorkerId> for WorkerId {
     fn from(host: golem::api0_2_0::host::WorkerId) -> Self {
         Self {
             component_id: host.component_id.into(),
-            worker_name: host.worker_name,
+            worker_name: WorkerName::from_existing(host.worker_name),
         }
     }
 }
@@ -936,12 +1018,18 @@ impl From<golem::api0_2_0::host::WorkerAnyFilter> for golem_common::model::Worke
 
 impl From<golem_common::model::WorkerMetadata> for WorkerMetadata {
     fn from(value: golem_common::model::WorkerMetadata) -> Self {
+        SerializableWorkerMetadata::from(value).into()
+    }
+}
+
+impl From<SerializableWorkerMetadata> for WorkerMetadata {
+    fn from(value: SerializableWorkerMetadata) -> Self {
         Self {
             worker_id: value.worker_id.into(),
             args: value.args,
             env: value.env,
-            status: value.last_known_status.status.into(),
-            component_version: value.last_known_status.component_version,
+            status: value.status.into(),
+            component_version: value.component_version,
             retry_count: 0,
         }
     }
@@ -468,7 +468,7 @@ impl From<WorkerId> for golem::api0_2_0::host::WorkerId {
     fn from(value: WorkerId) -> Self {
         golem::api0_2_0::host::WorkerId {
             component_id: value.component_id.into(),
-            worker_name: value.worker_name,
+            worker_name: value.worker_name.into_string(),
         }
     }
 }
@@ -477,7 +477,7 @@ impl From<golem::api0_2_0::host::WorkerId> for WorkerId {
     fn from(value: golem::api0_2_0::host::WorkerId) -> Self {
         WorkerId {
             component_id: value.component_id.into(),
-            worker_name: value.worker_name,
+            worker_name: golem_common::model::WorkerName::from_existing(value.worker_name),
         }
     }
 }
@@ -739,7 +739,7 @@ impl From<golem_common::model::WorkerId> for golem::api1_1_0_rc1::host::WorkerId
     fn from(worker_id: golem_common::model::WorkerId) -> Self {
         golem::api1_1_0_rc1::host::WorkerId {
             component_id: worker_id.component_id.into(),
-            worker_name: worker_id.worker_name,
+            worker_name: worker_id.worker_name.into_string(),
         }
     }
 }
@@ -748,7 +748,7 @@ impl From<golem::api1_1_0_rc1::host::WorkerId> for golem_common::model::WorkerId
     fn from(host: golem::api1_1_0_rc1::host::WorkerId) -> Self {
         Self {
             component_id: host.component_id.into(),
-            worker_name: host.worker_name,
+            worker_name: golem_common::model::WorkerName::from_existing(host.worker_name),
         }
     }
 }
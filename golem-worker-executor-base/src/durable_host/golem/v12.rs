@@ -0,0 +1,75 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::durable_host::serialized::SerializableSelfMetadata;
+use crate::durable_host::DurableWorkerCtx;
+use crate::metrics::wasm::record_host_function_call;
+use crate::preview2::api_v1_2::golem::api::self_metadata::{
+    Host, RetryPolicy, SelfMetadata, WorkerStatus,
+};
+use crate::workerctx::WorkerCtx;
+use async_trait::async_trait;
+
+#[async_trait]
+impl<Ctx: WorkerCtx> Host for DurableWorkerCtx<Ctx> {
+    async fn get_self_metadata(&mut self) -> anyhow::Result<SelfMetadata> {
+        let _permit = self.begin_async_host_function().await?;
+        record_host_function_call("golem::api::self_metadata", "get_self_metadata");
+        let self_metadata = self.get_durable_self_metadata().await?;
+        Ok(self_metadata.into())
+    }
+}
+
+#[async_trait]
+impl<Ctx: WorkerCtx> Host for &mut DurableWorkerCtx<Ctx> {
+    async fn get_self_metadata(&mut self) -> anyhow::Result<SelfMetadata> {
+        (*self).get_self_metadata().await
+    }
+}
+
+impl From<SerializableSelfMetadata> for SelfMetadata {
+    fn from(value: SerializableSelfMetadata) -> Self {
+        Self {
+            component_id: value.metadata.worker_id.component_id.to_string(),
+            worker_name: value.metadata.worker_id.worker_name,
+            args: value.metadata.args,
+            env: value.metadata.env,
+            created_at: value.created_at.to_millis(),
+            component_version: value.metadata.component_version,
+            status: value.metadata.status.into(),
+            retry_policy: RetryPolicy {
+                max_attempts: value.retry_config.max_attempts,
+                min_delay_millis: value.retry_config.min_delay.as_millis() as u64,
+                max_delay_millis: value.retry_config.max_delay.as_millis() as u64,
+                multiplier: value.retry_config.multiplier,
+                max_jitter_factor: value.retry_config.max_jitter_factor,
+            },
+            pending_update_count: value.pending_update_count,
+        }
+    }
+}
+
+impl From<golem_common::model::WorkerStatus> for WorkerStatus {
+    fn from(value: golem_common::model::WorkerStatus) -> Self {
+        match value {
+            golem_common::model::WorkerStatus::Running => WorkerStatus::Running,
+            golem_common::model::WorkerStatus::Idle => WorkerStatus::Idle,
+            golem_common::model::WorkerStatus::Suspended => WorkerStatus::Suspended,
+            golem_common::model::WorkerStatus::Interrupted => WorkerStatus::Interrupted,
+            golem_common::model::WorkerStatus::Retrying => WorkerStatus::Retrying,
+            golem_common::model::WorkerStatus::Failed => WorkerStatus::Failed,
+            golem_common::model::WorkerStatus::Exited => WorkerStatus::Exited,
+        }
+    }
+}
@@ -0,0 +1,317 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+use http_body_util::BodyExt;
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+use tracing::warn;
+use wasmtime_wasi::runtime::spawn;
+use wasmtime_wasi_http::bindings::http::types::{DnsErrorPayload, ErrorCode};
+use wasmtime_wasi_http::body::HyperOutgoingBody;
+use wasmtime_wasi_http::hyper_request_error;
+use wasmtime_wasi_http::io::TokioIo;
+use wasmtime_wasi_http::types::{IncomingResponse, OutgoingRequestConfig};
+
+use crate::services::golem_config::NetworkEgressPolicy;
+
+/// Gets the host part of a request's authority, without the port and the brackets around
+/// IPv6 addresses
+pub fn host_of_authority(authority: &str) -> &str {
+    if let Some(rest) = authority.strip_prefix('[') {
+        return rest.split_once(']').map(|(host, _)| host).unwrap_or(rest);
+    }
+    match authority.rsplit_once(':') {
+        Some((host, port)) if port.chars().all(|c| c.is_ascii_digit()) => host,
+        _ => authority,
+    }
+}
+
+/// Checks a host against the policy. On denial, the error contains the `deny` rule matching the
+/// host, or `None` if the host is denied because it matches none of the `allow` rules.
+pub fn check_host(policy: &NetworkEgressPolicy, host: &str) -> Result<(), Option<String>> {
+    let host = host.trim_end_matches('.');
+    if let Some(rule) = policy.deny.iter().find(|rule| matches_rule(rule, host)) {
+        return Err(Some(rule.clone()));
+    }
+    if !policy.allow.is_empty() && !policy.allow.iter().any(|rule| matches_rule(rule, host)) {
+        return Err(None);
+    }
+    Ok(())
+}
+
+/// Checks a resolved address of a request's host against the `deny` rules of the policy. The
+/// host name itself is checked by `check_host`, but it can resolve to any address, so the address
+/// connected to is checked too.
+pub fn check_address(policy: &NetworkEgressPolicy, address: IpAddr) -> Result<(), String> {
+    let address = address.to_canonical().to_string();
+    match policy.deny.iter().find(|rule| matches_rule(rule, &address)) {
+        Some(rule) => Err(rule.clone()),
+        None => Ok(()),
+    }
+}
+
+/// Resolves the authority of a request, and connects to the first of its addresses allowed by
+/// the policy. The connection is made to the checked address, so the name cannot be resolved to
+/// a different one between the check and the connection.
+async fn connect(
+    policy: &NetworkEgressPolicy,
+    authority: &str,
+    connect_timeout: std::time::Duration,
+) -> Result<TcpStream, ErrorCode> {
+    let addresses: Vec<SocketAddr> = tokio::net::lookup_host(authority)
+        .await
+        .map_err(|err| {
+            ErrorCode::DnsError(DnsErrorPayload {
+                rcode: Some(err.to_string()),
+                info_code: Some(0),
+            })
+        })?
+        .collect();
+
+    let mut last_error = None;
+    for address in addresses {
+        if let Err(rule) = check_address(policy, address.ip()) {
+            warn!("Outgoing HTTP request to {authority} resolved to {address}, denied by the network egress rule {rule}");
+            last_error.get_or_insert(ErrorCode::HttpRequestDenied);
+            continue;
+        }
+        match timeout(connect_timeout, TcpStream::connect(address)).await {
+            Ok(Ok(stream)) => return Ok(stream),
+            Ok(Err(_)) => last_error = Some(ErrorCode::ConnectionRefused),
+            Err(_) => last_error = Some(ErrorCode::ConnectionTimeout),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| {
+        ErrorCode::DnsError(DnsErrorPayload {
+            rcode: Some("address not available".to_string()),
+            info_code: Some(0),
+        })
+    }))
+}
+
+/// Sends an outgoing http request like `default_send_request_handler`, but only connects to
+/// addresses allowed by the network egress policy
+pub(crate) async fn send_request_handler(
+    mut request: hyper::Request<HyperOutgoingBody>,
+    OutgoingRequestConfig {
+        use_tls,
+        connect_timeout,
+        first_byte_timeout,
+        between_bytes_timeout,
+    }: OutgoingRequestConfig,
+    policy: Arc<NetworkEgressPolicy>,
+) -> Result<IncomingResponse, ErrorCode> {
+    let authority = match request.uri().authority() {
+        Some(authority) if authority.port().is_some() => authority.to_string(),
+        Some(authority) => format!("{}:{}", authority, if use_tls { 443 } else { 80 }),
+        None => return Err(ErrorCode::HttpRequestUriInvalid),
+    };
+    let tcp_stream = connect(&policy, &authority, connect_timeout).await?;
+
+    let (mut sender, worker) = if use_tls {
+        let root_cert_store = rustls::RootCertStore {
+            roots: webpki_roots::TLS_SERVER_ROOTS.into(),
+        };
+        let config = rustls::ClientConfig::builder()
+            .with_root_certificates(root_cert_store)
+            .with_no_client_auth();
+        let connector = tokio_rustls::TlsConnector::from(Arc::new(config));
+        let domain = rustls::pki_types::ServerName::try_from(host_of_authority(&authority))
+            .map_err(|_| {
+                ErrorCode::DnsError(DnsErrorPayload {
+                    rcode: Some("invalid dns name".to_string()),
+                    info_code: Some(0),
+                })
+            })?
+            .to_owned();
+        let stream = connector
+            .connect(domain, tcp_stream)
+            .await
+            .map_err(|_| ErrorCode::TlsProtocolError)?;
+        let (sender, conn) = timeout(
+            connect_timeout,
+            hyper::client::conn::http1::handshake(TokioIo::new(stream)),
+        )
+        .await
+        .map_err(|_| ErrorCode::ConnectionTimeout)?
+        .map_err(hyper_request_error)?;
+        let worker = spawn(async move {
+            if let Err(err) = conn.await {
+                warn!("Outgoing HTTP connection to {authority} failed: {err}");
+            }
+        });
+        (sender, worker)
+    } else {
+        let (sender, conn) = timeout(
+            connect_timeout,
+            hyper::client::conn::http1::handshake(TokioIo::new(tcp_stream)),
+        )
+        .await
+        .map_err(|_| ErrorCode::ConnectionTimeout)?
+        .map_err(hyper_request_error)?;
+        let worker = spawn(async move {
+            if let Err(err) = conn.await {
+                warn!("Outgoing HTTP connection to {authority} failed: {err}");
+            }
+        });
+        (sender, worker)
+    };
+
+    // The request is sent with a relative uri, like with the default handler
+    let path_and_query = request
+        .uri()
+        .path_and_query()
+        .map(|path_and_query| path_and_query.as_str())
+        .unwrap_or("/")
+        .to_string();
+    *request.uri_mut() = http::Uri::builder()
+        .path_and_query(path_and_query)
+        .build()
+        .map_err(|_| ErrorCode::HttpRequestUriInvalid)?;
+
+    let resp = timeout(first_byte_timeout, sender.send_request(request))
+        .await
+        .map_err(|_| ErrorCode::ConnectionReadTimeout)?
+        .map_err(hyper_request_error)?
+        .map(|body| body.map_err(hyper_request_error).boxed());
+
+    Ok(IncomingResponse {
+        resp,
+        worker: Some(worker),
+        between_bytes_timeout,
+    })
+}
+
+fn matches_rule(rule: &str, host: &str) -> bool {
+    let rule = rule.trim().trim_end_matches('.');
+    if let Some((network, prefix)) = rule.split_once('/') {
+        match (
+            network.parse::<IpAddr>(),
+            prefix.parse::<u32>(),
+            host.parse::<IpAddr>(),
+        ) {
+            (Ok(network), Ok(prefix), Ok(address)) => is_in_network(address, network, prefix),
+            _ => false,
+        }
+    } else if let Some(domain) = rule.strip_prefix("*.") {
+        host.len() > domain.len() + 1
+            && host.as_bytes()[host.len() - domain.len() - 1] == b'.'
+            && host[host.len() - domain.len()..].eq_ignore_ascii_case(domain)
+    } else if let Ok(rule_address) = rule.parse::<IpAddr>() {
+        host.parse::<IpAddr>() == Ok(rule_address)
+    } else {
+        rule.eq_ignore_ascii_case(host)
+    }
+}
+
+fn is_in_network(address: IpAddr, network: IpAddr, prefix: u32) -> bool {
+    match (address, network) {
+        (IpAddr::V4(address), IpAddr::V4(network)) if prefix <= 32 => {
+            let mask = u32::MAX.checked_shl(32 - prefix).unwrap_or(0);
+            u32::from(address) & mask == u32::from(network) & mask
+        }
+        (IpAddr::V6(address), IpAddr::V6(network)) if prefix <= 128 => {
+            let mask = u128::MAX.checked_shl(128 - prefix).unwrap_or(0);
+            u128::from(address) & mask == u128::from(network) & mask
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::net::IpAddr;
+
+    use crate::durable_host::http::egress::{check_address, check_host, host_of_authority};
+    use crate::services::golem_config::NetworkEgressPolicy;
+
+    fn policy(allow: &[&str], deny: &[&str]) -> NetworkEgressPolicy {
+        NetworkEgressPolicy {
+            allow: allow.iter().map(|rule| rule.to_string()).collect(),
+            deny: deny.iter().map(|rule| rule.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn host_of_authority_strips_ports_and_brackets() {
+        assert_eq!(host_of_authority("example.com"), "example.com");
+        assert_eq!(host_of_authority("example.com:8080"), "example.com");
+        assert_eq!(host_of_authority("10.0.0.1:80"), "10.0.0.1");
+        assert_eq!(host_of_authority("[::1]:8080"), "::1");
+        assert_eq!(host_of_authority("[::1]"), "::1");
+    }
+
+    #[test]
+    fn empty_policy_allows_everything() {
+        assert_eq!(check_host(&policy(&[], &[]), "example.com"), Ok(()));
+        assert_eq!(check_host(&policy(&[], &[]), "10.0.0.1"), Ok(()));
+    }
+
+    #[test]
+    fn deny_rules_take_precedence() {
+        let policy = policy(&["*.example.com"], &["internal.example.com"]);
+        assert_eq!(check_host(&policy, "api.example.com"), Ok(()));
+        assert_eq!(
+            check_host(&policy, "INTERNAL.example.com"),
+            Err(Some("internal.example.com".to_string()))
+        );
+    }
+
+    #[test]
+    fn wildcard_rules_match_subdomains_only() {
+        let policy = policy(&["*.example.com"], &[]);
+        assert_eq!(check_host(&policy, "a.b.example.com"), Ok(()));
+        assert_eq!(check_host(&policy, "example.com"), Err(None));
+        assert_eq!(check_host(&policy, "badexample.com"), Err(None));
+    }
+
+    #[test]
+    fn cidr_rules_match_addresses() {
+        let policy = policy(&[], &["10.0.0.0/8", "169.254.169.254", "fd00::/8"]);
+        assert_eq!(
+            check_host(&policy, "10.1.2.3"),
+            Err(Some("10.0.0.0/8".to_string()))
+        );
+        assert_eq!(
+            check_host(&policy, "169.254.169.254"),
+            Err(Some("169.254.169.254".to_string()))
+        );
+        assert_eq!(
+            check_host(&policy, "fd12::1"),
+            Err(Some("fd00::/8".to_string()))
+        );
+        assert_eq!(check_host(&policy, "11.0.0.1"), Ok(()));
+        assert_eq!(check_host(&policy, "example.com"), Ok(()));
+    }
+
+    #[test]
+    fn resolved_addresses_are_checked_against_deny_rules() {
+        let policy = policy(&["*.example.com"], &["10.0.0.0/8", "169.254.169.254"]);
+        let address = |address: &str| address.parse::<IpAddr>().unwrap();
+        assert_eq!(check_address(&policy, address("93.184.216.34")), Ok(()));
+        assert_eq!(
+            check_address(&policy, address("169.254.169.254")),
+            Err("169.254.169.254".to_string())
+        );
+        assert_eq!(
+            check_address(&policy, address("::ffff:10.1.2.3")),
+            Err("10.0.0.0/8".to_string())
+        );
+    }
+}
@@ -14,9 +14,25 @@
 
 use crate::durable_host::{DurableWorkerCtx, HttpRequestCloseOwner};
 use crate::error::GolemError;
+use crate::services::golem_config::NetworkEgressPolicy;
 use crate::workerctx::WorkerCtx;
+use anyhow::anyhow;
+use golem_common::config::RetryConfig;
 use golem_common::model::oplog::WrappedFunctionType;
-use tracing::warn;
+use golem_common::retries::get_delay;
+use http_body_util::{BodyExt, Full};
+use std::sync::Arc;
+use tracing::{debug, warn};
+use wasmtime_wasi::StreamError;
+use wasmtime_wasi_http::bindings::http::types::ErrorCode;
+use wasmtime_wasi_http::body::HyperOutgoingBody;
+use wasmtime_wasi_http::types::{
+    default_send_request, default_send_request_handler, HostFutureIncomingResponse,
+    IncomingResponse, OutgoingRequestConfig,
+};
+
+/// Allow and deny rules of the outgoing http requests
+pub mod egress;
 
 pub mod outgoing_http;
 
@@ -85,3 +101,178 @@ pub(crate) fn continue_http_request<Ctx: WorkerCtx>(
         warn!("No matching HTTP request is associated with resource handle. Handle: {}, open requests: {:?}", current_handle, ctx.state.open_http_requests);
     }
 }
+
+/// Counts the response body bytes read for the request associated with the given handle, and fails
+/// the read if it goes over the component's maximum recorded response size
+pub(crate) fn record_received_http_body_bytes<Ctx: WorkerCtx>(
+    ctx: &mut DurableWorkerCtx<Ctx>,
+    current_handle: u32,
+    len: usize,
+) -> Result<(), StreamError> {
+    if let Some(max_size) = ctx.state.http_durability.max_recorded_response_size {
+        if let Some(state) = ctx.state.open_http_requests.get_mut(&current_handle) {
+            state.received_bytes += len as u64;
+            if state.received_bytes > max_size as u64 {
+                return Err(StreamError::LastOperationFailed(anyhow!(
+                    "Response body of {} exceeds the maximum recorded response size of {max_size} bytes",
+                    state.request.uri
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Header marking a request as safe to send more than once, even if its method is not idempotent
+const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// A failed request may have been processed by the server, so only requests which can be sent
+/// again without repeating their side effects are retried: the ones with an idempotent method,
+/// or with an idempotency key chosen by the worker
+pub(crate) fn is_retryable_request<B>(request: &hyper::Request<B>) -> bool {
+    request.method().is_idempotent() || request.headers().contains_key(IDEMPOTENCY_KEY_HEADER)
+}
+
+/// The network egress policy only has to be applied to the connections if it has deny rules, as
+/// the addresses a host resolves to are only checked against those
+fn connection_policy(policy: &NetworkEgressPolicy) -> Option<Arc<NetworkEgressPolicy>> {
+    if policy.deny.is_empty() {
+        None
+    } else {
+        Some(Arc::new(policy.clone()))
+    }
+}
+
+async fn send_request_handler(
+    request: hyper::Request<HyperOutgoingBody>,
+    config: OutgoingRequestConfig,
+    policy: &Option<Arc<NetworkEgressPolicy>>,
+) -> Result<IncomingResponse, ErrorCode> {
+    match policy {
+        Some(policy) => egress::send_request_handler(request, config, policy.clone()).await,
+        None => default_send_request_handler(request, config).await,
+    }
+}
+
+/// Sends an outgoing http request, connecting only to the addresses allowed by the network
+/// egress policy
+pub(crate) fn send_request(
+    request: hyper::Request<HyperOutgoingBody>,
+    config: OutgoingRequestConfig,
+    policy: &NetworkEgressPolicy,
+) -> HostFutureIncomingResponse {
+    match connection_policy(policy) {
+        Some(policy) => {
+            let handle = wasmtime_wasi::runtime::spawn(async move {
+                Ok(egress::send_request_handler(request, config, policy).await)
+            });
+            HostFutureIncomingResponse::pending(handle)
+        }
+        None => default_send_request(request, config),
+    }
+}
+
+/// Sends an outgoing http request, retrying it on connection, DNS and timeout errors as long as the
+/// retry policy allows. The request body is buffered so it can be sent again.
+pub(crate) fn send_request_with_retries(
+    request: hyper::Request<HyperOutgoingBody>,
+    config: OutgoingRequestConfig,
+    retry_config: RetryConfig,
+    policy: &NetworkEgressPolicy,
+) -> HostFutureIncomingResponse {
+    let policy = connection_policy(policy);
+    let handle = wasmtime_wasi::runtime::spawn(async move {
+        let (parts, body) = request.into_parts();
+        let body = match body.collect().await {
+            Ok(collected) => collected.to_bytes(),
+            Err(error_code) => return Ok(Err(error_code)),
+        };
+
+        let mut attempts = 0;
+        loop {
+            let mut request = hyper::Request::new(
+                Full::new(body.clone())
+                    .map_err(|never| match never {})
+                    .boxed(),
+            );
+            *request.method_mut() = parts.method.clone();
+            *request.uri_mut() = parts.uri.clone();
+            *request.version_mut() = parts.version;
+            *request.headers_mut() = parts.headers.clone();
+
+            let config = OutgoingRequestConfig {
+                use_tls: config.use_tls,
+                connect_timeout: config.connect_timeout,
+                first_byte_timeout: config.first_byte_timeout,
+                between_bytes_timeout: config.between_bytes_timeout,
+            };
+
+            attempts += 1;
+            match send_request_handler(request, config, &policy).await {
+                Err(error_code) if is_transient_http_error(&error_code) => {
+                    match get_delay(&retry_config, attempts) {
+                        Some(delay) => {
+                            debug!(
+                                "Outgoing http request to {} failed with {error_code:?}, retrying in {delay:?}",
+                                parts.uri
+                            );
+                            tokio::time::sleep(delay).await;
+                        }
+                        None => break Ok(Err(error_code)),
+                    }
+                }
+                result => break Ok(result),
+            }
+        }
+    });
+    HostFutureIncomingResponse::pending(handle)
+}
+
+fn is_transient_http_error(error_code: &ErrorCode) -> bool {
+    matches!(
+        error_code,
+        ErrorCode::DnsTimeout
+            | ErrorCode::DnsError(_)
+            | ErrorCode::ConnectionRefused
+            | ErrorCode::ConnectionTerminated
+            | ErrorCode::ConnectionTimeout
+            | ErrorCode::ConnectionReadTimeout
+            | ErrorCode::ConnectionWriteTimeout
+            | ErrorCode::ConnectionLimitReached
+            | ErrorCode::HttpResponseTimeout
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use super::is_retryable_request;
+
+    fn request(method: hyper::Method, idempotency_key: Option<&str>) -> hyper::Request<()> {
+        let mut builder = hyper::Request::builder()
+            .method(method)
+            .uri("http://localhost/api");
+        if let Some(key) = idempotency_key {
+            builder = builder.header("Idempotency-Key", key);
+        }
+        builder.body(()).unwrap()
+    }
+
+    #[test]
+    fn idempotent_methods_are_retryable() {
+        assert!(is_retryable_request(&request(hyper::Method::GET, None)));
+        assert!(is_retryable_request(&request(hyper::Method::PUT, None)));
+        assert!(is_retryable_request(&request(hyper::Method::DELETE, None)));
+    }
+
+    #[test]
+    fn non_idempotent_methods_are_retryable_only_with_an_idempotency_key() {
+        assert!(!is_retryable_request(&request(hyper::Method::POST, None)));
+        assert!(!is_retryable_request(&request(hyper::Method::PATCH, None)));
+        assert!(is_retryable_request(&request(
+            hyper::Method::POST,
+            Some("f1c2a4e0-request-1")
+        )));
+    }
+}
@@ -21,8 +21,10 @@ use wasmtime_wasi_http::bindings::wasi::http::outgoing_handler::Host;
 use wasmtime_wasi_http::types::{HostFutureIncomingResponse, HostOutgoingRequest};
 use wasmtime_wasi_http::{HttpError, HttpResult};
 
-use golem_common::model::oplog::WrappedFunctionType;
+use golem_common::model::oplog::{OplogEntry, WrappedFunctionType};
+use tracing::warn;
 
+use crate::durable_host::http::egress::{check_host, host_of_authority};
 use crate::durable_host::http::serialized::SerializableHttpRequest;
 use crate::durable_host::{DurableWorkerCtx, HttpRequestCloseOwner, HttpRequestState};
 use crate::metrics::wasm::record_host_function_call;
@@ -41,6 +43,35 @@ impl<Ctx: WorkerCtx> Host for DurableWorkerCtx<Ctx> {
             .map_err(HttpError::trap)?;
         record_host_function_call("http::outgoing_handler", "handle");
 
+        // The policy may have changed since the oplog was recorded, so during replay the
+        // decisions recorded in live mode are followed instead of evaluating it again
+        let authority = self
+            .table()
+            .get(&request)?
+            .authority
+            .clone()
+            .unwrap_or_default();
+        let host = host_of_authority(&authority).to_string();
+        let denied = if self.state.is_live() {
+            match check_host(&self.state.network_egress, &host) {
+                Ok(()) => false,
+                Err(rule) => {
+                    self.state
+                        .oplog
+                        .add(OplogEntry::network_egress_denied(host.clone(), rule))
+                        .await;
+                    true
+                }
+            }
+        } else {
+            self.state.replay_state.take_denied_egress(&host).await
+        };
+        if denied {
+            self.table().delete(request)?;
+            warn!("Outgoing HTTP request to {host} denied by the network egress policy");
+            return Err(types::ErrorCode::HttpRequestDenied.into());
+        }
+
         // Durability is handled by the WasiHttpView send_request method and the follow-up calls to await/poll the response future
         let begin_index = self
             .state
@@ -89,6 +120,7 @@ impl<Ctx: WorkerCtx> Host for DurableWorkerCtx<Ctx> {
                         close_owner: HttpRequestCloseOwner::FutureIncomingResponseDrop,
                         root_handle: handle,
                         request,
+                        received_bytes: 0,
                     },
                 );
             }
@@ -0,0 +1,48 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use golem_common::model::oplog::WrappedFunctionType;
+
+use crate::durable_host::serialized::SerializableError;
+use crate::durable_host::{Durability, DurableWorkerCtx};
+use crate::metrics::wasm::record_host_function_call;
+use crate::preview2::invocation_context::golem::invocation_context::context::Host;
+use crate::workerctx::WorkerCtx;
+
+#[async_trait]
+impl<Ctx: WorkerCtx> Host for DurableWorkerCtx<Ctx> {
+    async fn get_invocation_context(&mut self) -> anyhow::Result<Vec<(String, String)>> {
+        let _permit = self.begin_async_host_function().await?;
+        record_host_function_call("golem::invocation_context", "get_invocation_context");
+        Durability::<Ctx, (), Vec<(String, String)>, SerializableError>::wrap(
+            self,
+            WrappedFunctionType::ReadLocal,
+            "golem invocation_context::get_invocation_context",
+            (),
+            |ctx| {
+                let context = ctx.state.invocation_context.clone().into_iter().collect();
+                Box::pin(async move { Ok(context) })
+            },
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl<Ctx: WorkerCtx> Host for &mut DurableWorkerCtx<Ctx> {
+    async fn get_invocation_context(&mut self) -> anyhow::Result<Vec<(String, String)>> {
+        (*self).get_invocation_context().await
+    }
+}
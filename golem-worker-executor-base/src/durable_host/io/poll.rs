@@ -22,6 +22,7 @@ use wasmtime_wasi::bindings::io::poll::{Host, HostPollable, Pollable};
 use crate::durable_host::serialized::SerializableError;
 use crate::durable_host::{Durability, DurableWorkerCtx, SuspendForSleep};
 use crate::metrics::wasm::record_host_function_call;
+use crate::services::HasWorker;
 use crate::workerctx::WorkerCtx;
 
 #[async_trait]
@@ -57,7 +58,16 @@ impl<Ctx: WorkerCtx> Host for DurableWorkerCtx<Ctx> {
             WrappedFunctionType::ReadLocal,
             "golem io::poll::poll",
             (),
-            |ctx| Box::pin(async move { Host::poll(&mut ctx.as_wasi_view(), in_).await }),
+            |ctx| {
+                Box::pin(async move {
+                    // Not holding the invocation's turn while blocked, for example waiting for
+                    // the result of an asynchronous RPC call
+                    let worker = ctx.public_state.worker();
+                    worker
+                        .without_invocation_permit(Host::poll(&mut ctx.as_wasi_view(), in_))
+                        .await
+                })
+            },
             |result| is_suspend_for_sleep(result).is_none(), // We must not persist the suspend signal
         )
         .await;
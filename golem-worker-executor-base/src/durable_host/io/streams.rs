@@ -18,7 +18,9 @@ use wasmtime::component::Resource;
 use wasmtime_wasi::{ResourceTable, StreamError};
 
 use crate::durable_host::http::serialized::SerializableHttpRequest;
-use crate::durable_host::http::{end_http_request, end_http_request_sync};
+use crate::durable_host::http::{
+    end_http_request, end_http_request_sync, record_received_http_body_bytes,
+};
 use crate::durable_host::io::{ManagedStdErr, ManagedStdOut};
 use crate::durable_host::serialized::SerializableStreamError;
 use crate::durable_host::{Durability, DurableWorkerCtx, HttpRequestCloseOwner};
@@ -54,7 +56,10 @@ impl<Ctx: WorkerCtx> HostInputStream for DurableWorkerCtx<Ctx> {
                     request,
                     |ctx| {
                         Box::pin(async move {
-                            HostInputStream::read(&mut ctx.as_wasi_view(), self_, len).await
+                            let chunk =
+                                HostInputStream::read(&mut ctx.as_wasi_view(), self_, len).await?;
+                            record_received_http_body_bytes(ctx, handle, chunk.len())?;
+                            Ok(chunk)
                         })
                     },
                 )
@@ -86,8 +91,11 @@ impl<Ctx: WorkerCtx> HostInputStream for DurableWorkerCtx<Ctx> {
                     request,
                     |ctx| {
                         Box::pin(async move {
-                            HostInputStream::blocking_read(&mut ctx.as_wasi_view(), self_, len)
-                                .await
+                            let chunk =
+                                HostInputStream::blocking_read(&mut ctx.as_wasi_view(), self_, len)
+                                    .await?;
+                            record_received_http_body_bytes(ctx, handle, chunk.len())?;
+                            Ok(chunk)
                         })
                     },
                 )
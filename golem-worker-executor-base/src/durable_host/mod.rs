@@ -15,21 +15,21 @@
 // WASI Host implementation for Golem, delegating to the core WASI implementation (wasmtime_wasi)
 // implementing the Golem specific instrumentation on top of it.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::error::Error;
 use std::fmt::{Debug, Display, Formatter};
 use std::ops::Add;
 use std::sync::{Arc, Mutex, RwLock, Weak};
 use std::time::{Duration, Instant};
 
-use crate::error::GolemError;
+use crate::error::{GolemError, WorkerMemoryLimitExceeded};
 use crate::invocation::{invoke_worker, InvokeResult};
 use crate::model::{
     CurrentResourceLimits, ExecutionStatus, InterruptKind, LastError, PersistenceLevel, TrapType,
-    WorkerConfig,
+    WorkerConfig, GOLEM_ENV_VARS,
 };
 use crate::services::blob_store::BlobStoreService;
-use crate::services::golem_config::GolemConfig;
+use crate::services::golem_config::{GolemConfig, HttpDurabilityPolicy, NetworkEgressPolicy};
 use crate::services::key_value::KeyValueService;
 use crate::services::promise::PromiseService;
 use crate::services::worker::WorkerService;
@@ -42,10 +42,13 @@ use crate::workerctx::{
 use anyhow::anyhow;
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use futures_util::{stream, StreamExt, TryStreamExt};
 use golem_common::config::RetryConfig;
+use golem_common::model::component_observability::ComponentObservability;
+use golem_common::model::initial_files::extract_archive;
 use golem_common::model::oplog::{
-    IndexedResourceKey, LogLevel, OplogEntry, OplogIndex, UpdateDescription, WorkerError,
-    WorkerResourceId, WrappedFunctionType,
+    IndexedResourceKey, LogLevel, OplogEntry, OplogIndex, OplogPayload, UpdateDescription,
+    WorkerError, WorkerResourceId, WrappedFunctionType,
 };
 use golem_common::model::regions::{DeletedRegions, OplogRegion};
 use golem_common::model::{
@@ -59,21 +62,28 @@ use golem_wasm_rpc::wasmtime::ResourceStore;
 use golem_wasm_rpc::{Uri, Value};
 use tempfile::TempDir;
 use tracing::{debug, info, span, warn, Instrument, Level};
-use wasmtime::component::{Instance, ResourceAny};
+use wasmtime::component::{Instance, Resource, ResourceAny};
 use wasmtime::{AsContext, AsContextMut};
-use wasmtime_wasi::{I32Exit, ResourceTable, Stderr, Stdout, WasiCtx, WasiView};
+use wasmtime_wasi::bindings::io::streams::{HostInputStream, InputStream};
+use wasmtime_wasi::{I32Exit, ResourceTable, Stderr, Stdout, StreamError, WasiCtx, WasiView};
 use wasmtime_wasi_http::body::HyperOutgoingBody;
-use wasmtime_wasi_http::types::{
-    default_send_request, HostFutureIncomingResponse, OutgoingRequestConfig,
-};
+use wasmtime_wasi_http::types::{HostFutureIncomingResponse, OutgoingRequestConfig};
 use wasmtime_wasi_http::{HttpResult, WasiHttpCtx, WasiHttpView};
 
 use crate::durable_host::io::{ManagedStdErr, ManagedStdIn, ManagedStdOut};
 use crate::durable_host::wasm_rpc::UrnExtensions;
+use crate::metrics::recovery::{
+    record_recovery_finished, record_recovery_started, record_worker_recovered,
+};
+use crate::metrics::snapshots::record_snapshot;
 use crate::metrics::wasm::{record_number_of_replayed_functions, record_resume_worker};
 use crate::services::oplog::{CommitLevel, Oplog, OplogOps, OplogService};
 use crate::services::rpc::Rpc;
+use crate::services::saga::{compensate_failed_worker, restore_sagas_from_oplog, SagaService};
 use crate::services::scheduler::SchedulerService;
+use crate::services::secrets::SecretService;
+use crate::services::topics::TopicService;
+use crate::services::virtual_clock::{VirtualClock, VirtualClockService};
 use crate::services::HasOplogService;
 use crate::wasi_host;
 use crate::worker::{calculate_last_known_status, is_worker_error_retriable};
@@ -84,25 +94,34 @@ mod clocks;
 mod filesystem;
 pub mod golem;
 pub mod http;
+mod invocation_context;
 pub mod io;
 pub mod keyvalue;
 mod logging;
 mod random;
+mod saga;
+mod secrets;
 pub mod serialized;
+mod shared_kv;
 mod sockets;
+mod topics;
 pub mod wasm_rpc;
+mod worker_blobs;
+mod worker_kv;
 
 mod durability;
-mod replay_state;
+pub(crate) mod replay_state;
 mod sync_helper;
 
 use crate::durable_host::http::serialized::SerializableHttpRequest;
+use crate::durable_host::http::{is_retryable_request, send_request, send_request_with_retries};
 use crate::durable_host::replay_state::ReplayState;
+use crate::durable_host::serialized::SerializableStreamError;
 use crate::durable_host::sync_helper::{SyncHelper, SyncHelperPermit};
 use crate::function_result_interpreter::interpret_function_results;
 use crate::services::component::{ComponentMetadata, ComponentService};
 use crate::services::worker_proxy::WorkerProxy;
-use crate::worker::{RetryDecision, Worker};
+use crate::worker::{RecoveryPriority, RetryDecision, RunningWorker, Worker};
 pub use durability::*;
 use golem_common::model::exports;
 use golem_common::retries::get_delay;
@@ -130,6 +149,10 @@ impl<Ctx: WorkerCtx> DurableWorkerCtx<Ctx> {
         >,
         key_value_service: Arc<dyn KeyValueService + Send + Sync>,
         blob_store_service: Arc<dyn BlobStoreService + Send + Sync>,
+        secret_service: Arc<dyn SecretService + Send + Sync>,
+        topic_service: Arc<dyn TopicService + Send + Sync>,
+        saga_service: Arc<dyn SagaService + Send + Sync>,
+        virtual_clock_service: Arc<dyn VirtualClockService + Send + Sync>,
         event_service: Arc<dyn WorkerEventService + Send + Sync>,
         oplog_service: Arc<dyn OplogService + Send + Sync>,
         oplog: Arc<dyn Oplog + Send + Sync>,
@@ -150,6 +173,28 @@ impl<Ctx: WorkerCtx> DurableWorkerCtx<Ctx> {
             temp_dir.path()
         );
 
+        if let Some(initial_files_hash) = &component_metadata.initial_files_hash {
+            let archive = component_service
+                .get_initial_files(
+                    &owned_worker_id.worker_id.component_id,
+                    component_metadata.version,
+                    initial_files_hash,
+                )
+                .await?;
+            let root = temp_dir.path().to_path_buf();
+            tokio::task::spawn_blocking(move || extract_archive(&archive, &root))
+                .await
+                .map_err(|e| GolemError::unknown(e.to_string()))?
+                .map_err(|e| {
+                    GolemError::runtime(format!("Failed to extract initial files: {e}"))
+                })?;
+            debug!(
+                "Extracted initial files {} into {:?}",
+                initial_files_hash,
+                temp_dir.path()
+            );
+        }
+
         debug!(
             "Worker {} initialized with deleted regions {}",
             owned_worker_id.worker_id, worker_config.deleted_regions
@@ -193,6 +238,10 @@ impl<Ctx: WorkerCtx> DurableWorkerCtx<Ctx> {
                 worker_enumeration_service,
                 key_value_service,
                 blob_store_service,
+                secret_service,
+                topic_service,
+                saga_service,
+                virtual_clock_service,
                 component_service,
                 config.clone(),
                 owned_worker_id.clone(),
@@ -202,6 +251,10 @@ impl<Ctx: WorkerCtx> DurableWorkerCtx<Ctx> {
                 last_oplog_index,
                 component_metadata,
                 worker_config.total_linear_memory_size,
+                worker_config.max_linear_memory,
+                worker_config.args.clone(),
+                worker_config.env.clone(),
+                worker_config.sandboxed,
             )
             .await,
             _temp_dir: temp_dir,
@@ -231,6 +284,16 @@ impl<Ctx: WorkerCtx> DurableWorkerCtx<Ctx> {
         &self.state.component_metadata
     }
 
+    pub fn component_observability(&self) -> ComponentObservability {
+        self.state
+            .component_service
+            .get_observability(&self.owned_worker_id.component_id())
+    }
+
+    pub fn invocation_fuel_budget(&self) -> Option<u64> {
+        self.state.invocation_fuel_budget
+    }
+
     pub fn is_exit(error: &anyhow::Error) -> Option<i32> {
         error
             .root_cause()
@@ -256,6 +319,11 @@ impl<Ctx: WorkerCtx> DurableWorkerCtx<Ctx> {
     }
 
     pub async fn update_worker_status(&self, f: impl FnOnce(&mut WorkerStatusRecord)) {
+        if self.state.sandboxed {
+            // Throwaway instances share the worker, but must not change its status
+            return;
+        }
+
         let mut status = self
             .execution_status
             .read()
@@ -298,9 +366,18 @@ impl<Ctx: WorkerCtx> DurableWorkerCtx<Ctx> {
     pub async fn increase_memory(&mut self, delta: u64) -> anyhow::Result<bool> {
         if self.state.is_replay() {
             // The increased amount was already recorded in live mode, so our worker
-            // was initialized with the correct amount of memory.
+            // was initialized with the correct amount of memory. The limit is not enforced
+            // here, as it may have been lowered since the growth was recorded.
             Ok(true)
         } else {
+            if let Some(limit) = self.state.max_linear_memory {
+                if self.state.total_linear_memory_size + delta > limit {
+                    // Failing the worker with a specific error instead of letting the memory growth
+                    // fail inside the component
+                    return Err(anyhow!(WorkerMemoryLimitExceeded { limit }));
+                }
+            }
+
             // In live mode we need to try to get more memory permits and if we can't,
             // we fail the worker, unload it from memory and schedule a retry.
             // let current_size = self.update_worker_status();
@@ -365,6 +442,12 @@ impl<Ctx: WorkerCtx> DurableWorkerCtx<Ctx> {
     }
 
     async fn emit_log_event(&self, event: WorkerEvent) {
+        // Log entries below the component's configured level are neither forwarded nor persisted
+        let forwarded = match &event {
+            WorkerEvent::Log { level, .. } => self.component_observability().forwards_log(level),
+            _ => true,
+        };
+
         if let Some(entry) = event.as_oplog_entry() {
             if let OplogEntry::Log {
                 level,
@@ -383,10 +466,12 @@ impl<Ctx: WorkerCtx> DurableWorkerCtx<Ctx> {
                     // If persistence is off, we always emit events
                     {
                         // Emit the event and write a special oplog entry
-                        self.public_state
-                            .event_service
-                            .emit_event(event.clone(), true);
-                        self.state.oplog.add(entry).await;
+                        if forwarded {
+                            self.public_state
+                                .event_service
+                                .emit_event(event.clone(), true);
+                            self.state.oplog.add(entry).await;
+                        }
                     } else if !self
                         .state
                         .replay_state
@@ -394,10 +479,12 @@ impl<Ctx: WorkerCtx> DurableWorkerCtx<Ctx> {
                         .await
                     {
                         // haven't seen this log before
-                        self.public_state
-                            .event_service
-                            .emit_event(event.clone(), true);
-                        self.state.oplog.add(entry).await;
+                        if forwarded {
+                            self.public_state
+                                .event_service
+                                .emit_event(event.clone(), true);
+                            self.state.oplog.add(entry).await;
+                        }
                     } else {
                         // we have persisted emitting this log before, so we mark it as non-live and
                         // remove the entry from the seen log set.
@@ -448,65 +535,11 @@ impl<Ctx: WorkerCtx + DurableWorkerCtxView<Ctx>> DurableWorkerCtx<Ctx> {
                             .await
                         {
                             Ok(Some(data)) => {
-                                let idempotency_key = IdempotencyKey::fresh();
-                                store
-                                    .as_context_mut()
-                                    .data_mut()
-                                    .durable_ctx_mut()
-                                    .set_current_idempotency_key(idempotency_key.clone())
-                                    .await;
-
-                                store
-                                    .as_context_mut()
-                                    .data_mut()
-                                    .begin_call_snapshotting_function();
-                                let load_result = invoke_worker(
-                                    "golem:api/load-snapshot@0.2.0.{load}".to_string(),
-                                    vec![Value::List(data.iter().map(|b| Value::U8(*b)).collect())],
-                                    store,
-                                    instance,
-                                )
-                                .await;
-                                store
-                                    .as_context_mut()
-                                    .data_mut()
-                                    .end_call_snapshotting_function();
-
-                                let failed = match load_result {
-                                    Err(error) => Some(format!(
-                                        "Manual update failed to load snapshot: {error}"
-                                    )),
-                                    Ok(InvokeResult::Failed { error, .. }) => {
-                                        let stderr = store
-                                            .as_context()
-                                            .data()
-                                            .get_public_state()
-                                            .event_service()
-                                            .get_last_invocation_errors();
-                                        let error = error.to_string(&stderr);
-                                        Some(format!(
-                                            "Manual update failed to load snapshot: {error}"
-                                        ))
-                                    }
-                                    Ok(InvokeResult::Succeeded { output, .. }) => {
-                                        if output.len() == 1 {
-                                            match &output[0] {
-                                                Value::Result(Err(Some(boxed_error_value))) => {
-                                                    match &**boxed_error_value {
-                                                        Value::String(error) =>
-                                                            Some(format!("Manual update failed to load snapshot: {error}")),
-                                                        _ =>
-                                                            Some("Unexpected result value from the snapshot load function".to_string())
-                                                    }
-                                                }
-                                                _ => None
-                                            }
-                                        } else {
-                                            Some("Unexpected result value from the snapshot load function".to_string())
-                                        }
-                                    }
-                                    _ => None,
-                                };
+                                let failed = Self::load_snapshot(instance, store, &data).await.map(
+                                    |error| {
+                                        format!("Manual update failed to load snapshot: {error}")
+                                    },
+                                );
 
                                 if let Some(error) = failed {
                                     store
@@ -597,6 +630,115 @@ impl<Ctx: WorkerCtx + DurableWorkerCtxView<Ctx>> DurableWorkerCtx<Ctx> {
             }
         }
     }
+
+    /// Calls the worker's `load-snapshot` export with the given snapshot, returning the reason of
+    /// the failure if it could not be loaded
+    async fn load_snapshot(
+        instance: &Instance,
+        store: &mut (impl AsContextMut<Data = Ctx> + Send),
+        data: &[u8],
+    ) -> Option<String> {
+        store
+            .as_context_mut()
+            .data_mut()
+            .durable_ctx_mut()
+            .set_current_idempotency_key(IdempotencyKey::fresh())
+            .await;
+
+        store
+            .as_context_mut()
+            .data_mut()
+            .begin_call_snapshotting_function();
+        let load_result = invoke_worker(
+            "golem:api/load-snapshot@0.2.0.{load}".to_string(),
+            vec![Value::List(data.iter().map(|b| Value::U8(*b)).collect())],
+            store,
+            instance,
+        )
+        .await;
+        store
+            .as_context_mut()
+            .data_mut()
+            .end_call_snapshotting_function();
+
+        match load_result {
+            Err(error) => Some(error.to_string()),
+            Ok(InvokeResult::Failed { error, .. }) => {
+                let stderr = store
+                    .as_context()
+                    .data()
+                    .get_public_state()
+                    .event_service()
+                    .get_last_invocation_errors();
+                Some(error.to_string(&stderr))
+            }
+            Ok(InvokeResult::Succeeded { output, .. }) => {
+                if output.len() == 1 {
+                    match &output[0] {
+                        Value::Result(Err(Some(boxed_error_value))) => match &**boxed_error_value {
+                            Value::String(error) => Some(error.clone()),
+                            _ => Some(
+                                "unexpected result value from the snapshot load function"
+                                    .to_string(),
+                            ),
+                        },
+                        _ => None,
+                    }
+                } else {
+                    Some("unexpected result value from the snapshot load function".to_string())
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Loads the latest usable snapshot of the worker, so its recovery only replays the oplog
+    /// entries written after it. If loading the snapshot fails, the worker has to be restarted
+    /// and recovered by replaying its whole oplog.
+    async fn restore_latest_snapshot(
+        instance: &Instance,
+        store: &mut (impl AsContextMut<Data = Ctx> + Send),
+    ) -> RetryDecision {
+        let ctx = store.as_context().data().durable_ctx();
+        let worker = ctx.public_state.worker();
+        if !ctx.state.config.snapshot.enabled
+            || !ctx.state.is_replay()
+            || worker.skips_snapshot_recovery()
+            || !worker.pending_updates().0.is_empty()
+        {
+            return RetryDecision::None;
+        }
+
+        let (snapshot_index, payload) = match ctx.state.find_latest_snapshot().await {
+            Some(snapshot) => snapshot,
+            None => return RetryDecision::None,
+        };
+        let data = match ctx.state.oplog.download_payload(&payload).await {
+            Ok(data) => data,
+            Err(error) => {
+                record_snapshot("load_failed");
+                warn!("Failed to download the snapshot at {snapshot_index}, replaying the whole oplog: {error}");
+                return RetryDecision::None;
+            }
+        };
+
+        debug!("Loading the snapshot at {snapshot_index}");
+        match Self::load_snapshot(instance, store, &data).await {
+            None => {
+                record_snapshot("loaded");
+                let state = &mut store.as_context_mut().data_mut().durable_ctx_mut().state;
+                state.replay_state.skip_to_snapshot(snapshot_index).await;
+                state.last_snapshot_index = snapshot_index;
+                RetryDecision::None
+            }
+            Some(error) => {
+                record_snapshot("load_failed");
+                warn!("Failed to load the snapshot at {snapshot_index}, restarting to replay the whole oplog: {error}");
+                worker.skip_snapshot_recovery();
+                RetryDecision::Immediate
+            }
+        }
+    }
 }
 
 #[async_trait]
@@ -609,6 +751,10 @@ impl<Ctx: WorkerCtx> InvocationManagement for DurableWorkerCtx<Ctx> {
         self.state.get_current_idempotency_key()
     }
 
+    async fn set_invocation_context(&mut self, context: BTreeMap<String, String>) {
+        self.state.invocation_context = context;
+    }
+
     fn is_live(&self) -> bool {
         self.state.is_live()
     }
@@ -713,6 +859,9 @@ impl<Ctx: WorkerCtx> StatusManagement for DurableWorkerCtx<Ctx> {
     }
 
     async fn store_worker_status(&self, status: WorkerStatus) {
+        if self.state.sandboxed {
+            return;
+        }
         self.update_worker_status(|s| s.status = status.clone())
             .await;
         if (status == WorkerStatus::Idle
@@ -745,6 +894,55 @@ impl<Ctx: WorkerCtx> StatusManagement for DurableWorkerCtx<Ctx> {
     }
 }
 
+/// Maximum number of bytes read at once from the input streams returned by invocations
+const RESULT_STREAM_CHUNK_SIZE: u64 = 64 * 1024;
+
+/// Name of the oplog entries recording the chunks of result streams
+pub(crate) const RESULT_STREAM_READ_FUNCTION: &str = "golem result_stream::read";
+
+/// Reads back the chunks of the result stream of the invocation completed at `completed_at`
+/// from the oplog, in the order they were produced. Used to resync callers which missed some of
+/// the chunks emitted while the invocation was running.
+pub async fn recorded_result_chunks(
+    oplog: &Arc<dyn Oplog + Send + Sync>,
+    idempotency_key: &IdempotencyKey,
+    completed_at: OplogIndex,
+) -> Result<Vec<Vec<u8>>, GolemError> {
+    let mut chunks = Vec::new();
+    let mut idx = completed_at;
+    loop {
+        let entry = oplog.read(idx).await;
+        match &entry {
+            OplogEntry::ExportedFunctionInvoked {
+                idempotency_key: key,
+                ..
+            } if key == idempotency_key => break,
+            OplogEntry::ImportedFunctionInvoked { function_name, .. }
+                if function_name == RESULT_STREAM_READ_FUNCTION =>
+            {
+                let chunk: Option<Result<Vec<u8>, SerializableStreamError>> = oplog
+                    .get_payload_of_entry(&entry)
+                    .await
+                    .map_err(GolemError::unknown)?;
+                // The failed read ending the stream is recorded too
+                if let Some(Ok(chunk)) = chunk {
+                    chunks.push(chunk);
+                }
+            }
+            _ => {}
+        }
+
+        if idx == OplogIndex::INITIAL {
+            return Err(GolemError::unknown(format!(
+                "The start of invocation {idempotency_key} is missing from the oplog"
+            )));
+        }
+        idx = idx.previous();
+    }
+    chunks.reverse();
+    Ok(chunks)
+}
+
 #[async_trait]
 impl<Ctx: WorkerCtx> InvocationHooks for DurableWorkerCtx<Ctx> {
     async fn on_exported_function_invoked(
@@ -752,6 +950,11 @@ impl<Ctx: WorkerCtx> InvocationHooks for DurableWorkerCtx<Ctx> {
         full_function_name: &str,
         function_input: &Vec<Value>,
     ) -> Result<(), GolemError> {
+        // Changes of the worker's arguments and environment variables are applied between
+        // invocations
+        let (args, env) = self.public_state.worker().environment();
+        self.state.set_environment(args, env);
+
         if self.state.snapshotting_mode.is_none() {
             let proto_function_input: Vec<golem_wasm_rpc::protobuf::Val> = function_input
                 .iter()
@@ -774,12 +977,25 @@ impl<Ctx: WorkerCtx> InvocationHooks for DurableWorkerCtx<Ctx> {
                         self.worker_id()
                     )
                 });
+            if !self.state.invocation_context.is_empty() {
+                self.state
+                    .oplog
+                    .add(OplogEntry::invocation_context(
+                        self.state.invocation_context.clone(),
+                    ))
+                    .await;
+            }
             self.state.oplog.commit(CommitLevel::Always).await;
         }
         Ok(())
     }
 
     async fn on_invocation_failure(&mut self, trap_type: &TrapType) -> RetryDecision {
+        // A failure after diverging from the recorded history is reported with the details of the
+        // divergence, and it is not retried as replaying it again would diverge the same way
+        let divergence = self.state.replay_state.take_divergence().await;
+        let trap_type = &trap_type.clone().with_divergence(divergence);
+
         let previous_tries = self.state.trailing_error_count().await;
         let default_retry_config = &self.state.config.retry;
         let retry_config = self
@@ -829,6 +1045,18 @@ impl<Ctx: WorkerCtx> InvocationHooks for DurableWorkerCtx<Ctx> {
         self.store_worker_status(updated_worker_status.clone())
             .await;
 
+        if updated_worker_status == WorkerStatus::Failed {
+            // The worker will not continue its open sagas, so they get compensated
+            compensate_failed_worker(
+                self.state.saga_service.clone(),
+                self.rpc(),
+                self.owned_worker_id.clone(),
+                self.state.args.clone(),
+                self.state.env.clone(),
+            )
+            .await;
+        }
+
         if store_result {
             // Giving up, associating the stored result with the current and upcoming invocations
             if let Some(idempotency_key) = self.state.get_current_idempotency_key() {
@@ -881,9 +1109,17 @@ impl<Ctx: WorkerCtx> InvocationHooks for DurableWorkerCtx<Ctx> {
                 .get_oplog_entry_exported_function_completed()
                 .await?;
 
-            if let Some(function_output) = response {
+            if let Some((oplog_index, function_output)) = response {
                 let is_diverged = function_output != output;
                 if is_diverged {
+                    self.state
+                        .replay_state
+                        .record_divergence(
+                            oplog_index,
+                            format!("{full_function_name} => {function_output:?}"),
+                            format!("{full_function_name} => {output:?}"),
+                        )
+                        .await;
                     return Err(GolemError::unexpected_oplog_entry(
                         format!("{full_function_name}({function_input:?}) => {function_output:?}"),
                         format!("{full_function_name}({function_input:?}) => {output:?}"),
@@ -892,6 +1128,9 @@ impl<Ctx: WorkerCtx> InvocationHooks for DurableWorkerCtx<Ctx> {
             }
         }
 
+        // Divergences which did not fail the invocation are not reported on later failures
+        let _ = self.state.replay_state.take_divergence().await;
+
         self.store_worker_status(WorkerStatus::Idle).await;
 
         debug!("Function {full_function_name} finished with {output:?}");
@@ -899,6 +1138,57 @@ impl<Ctx: WorkerCtx> InvocationHooks for DurableWorkerCtx<Ctx> {
         // Return indicating that it is done
         Ok(())
     }
+
+    async fn forward_result_stream(
+        &mut self,
+        stream: Resource<InputStream>,
+    ) -> Result<(), GolemError> {
+        let idempotency_key = self.get_current_idempotency_key().await.ok_or(anyhow!(
+            "No active invocation key is associated with the worker"
+        ))?;
+        let handle = stream.rep();
+
+        // Every chunk is a separate entry in the oplog, so the chunk boundaries are the same
+        // when the invocation is replayed
+        let result = loop {
+            let chunk = Durability::<Ctx, (), Vec<u8>, SerializableStreamError>::wrap(
+                self,
+                WrappedFunctionType::ReadLocal,
+                RESULT_STREAM_READ_FUNCTION,
+                (),
+                |ctx| {
+                    Box::pin(async move {
+                        HostInputStream::blocking_read(
+                            &mut ctx.as_wasi_view(),
+                            Resource::new_borrow(handle),
+                            RESULT_STREAM_CHUNK_SIZE,
+                        )
+                        .await
+                    })
+                },
+            )
+            .await;
+
+            match chunk {
+                Ok(bytes) => {
+                    self.public_state.event_service.emit_result_chunk(
+                        &idempotency_key,
+                        bytes,
+                        self.state.is_live(),
+                    );
+                }
+                Err(StreamError::Closed) => break Ok(()),
+                Err(err) => {
+                    break Err(GolemError::runtime(format!(
+                        "Failed to read the result stream: {err}"
+                    )))
+                }
+            }
+        };
+
+        HostInputStream::drop(&mut self.as_wasi_view(), stream)?;
+        result
+    }
 }
 
 #[async_trait]
@@ -1124,6 +1414,10 @@ impl<Ctx: WorkerCtx + DurableWorkerCtxView<Ctx>> ExternalOperations<Ctx> for Dur
 
             Ok(RetryDecision::None)
         } else {
+            if Self::restore_latest_snapshot(instance, store).await == RetryDecision::Immediate {
+                return Ok(RetryDecision::Immediate);
+            }
+
             // Handle the case when recovery immediately starts in a deleted region
             // (for example due to a manual update)
             store
@@ -1321,11 +1615,119 @@ impl<Ctx: WorkerCtx + DurableWorkerCtxView<Ctx>> ExternalOperations<Ctx> for Dur
         }
     }
 
+    async fn take_snapshot_if_due(
+        instance: &Instance,
+        store: &mut (impl AsContextMut<Data = Ctx> + Send),
+    ) -> Result<(), GolemError> {
+        let ctx = store.as_context().data().durable_ctx();
+        let config = &ctx.state.config.snapshot;
+        if !config.enabled || !ctx.state.is_live() {
+            return Ok(());
+        }
+
+        let current_index = ctx.state.oplog.current_oplog_index().await;
+        if u64::from(current_index) - u64::from(ctx.state.last_snapshot_index)
+            < config.oplog_interval
+        {
+            return Ok(());
+        }
+
+        let component_metadata = ctx.state.component_metadata.clone();
+        let exports_snapshotting = [
+            "golem:api/save-snapshot@0.2.0.{save}",
+            "golem:api/load-snapshot@0.2.0.{load}",
+        ]
+        .iter()
+        .all(|name| {
+            matches!(
+                exports::function_by_name(&component_metadata.exports, name),
+                Ok(Some(_))
+            )
+        });
+        // The state of a component instance can only be captured by the component itself, so
+        // the workers of components not exporting the snapshotting functions always replay
+        // their whole oplog
+        if !exports_snapshotting {
+            record_snapshot("unsupported");
+            return Ok(());
+        }
+        // Resources owned by the worker cannot be restored from a snapshot, as their handles
+        // are held by the callers
+        if !ctx.state.resources.is_empty() {
+            record_snapshot("owns_resources");
+            return Ok(());
+        }
+
+        store
+            .as_context_mut()
+            .data_mut()
+            .durable_ctx_mut()
+            .set_current_idempotency_key(IdempotencyKey::fresh())
+            .await;
+        store
+            .as_context_mut()
+            .data_mut()
+            .begin_call_snapshotting_function();
+        let result = invoke_worker(
+            "golem:api/save-snapshot@0.2.0.{save}".to_string(),
+            vec![],
+            store,
+            instance,
+        )
+        .await;
+        store
+            .as_context_mut()
+            .data_mut()
+            .end_call_snapshotting_function();
+
+        let data = match result {
+            Ok(InvokeResult::Succeeded { output, .. }) => {
+                RunningWorker::decode_snapshot_result(output)
+                    .ok_or_else(|| GolemError::runtime("Invalid snapshot result"))?
+            }
+            Ok(other) => {
+                record_snapshot("save_failed");
+                return Err(GolemError::runtime(format!(
+                    "Failed to save snapshot: {other:?}"
+                )));
+            }
+            Err(error) => {
+                record_snapshot("save_failed");
+                return Err(error);
+            }
+        };
+
+        let state = &mut store.as_context_mut().data_mut().durable_ctx_mut().state;
+        let payload = state
+            .oplog
+            .upload_payload(&data)
+            .await
+            .map_err(GolemError::runtime)?;
+        let snapshot_index = state
+            .oplog
+            .add_and_commit(OplogEntry::snapshot(component_metadata.version, payload))
+            .await;
+        state.last_snapshot_index = snapshot_index;
+        record_snapshot("saved");
+        debug!("Saved snapshot at {snapshot_index}");
+        Ok(())
+    }
+
+    async fn try_load_snapshot(
+        instance: &Instance,
+        store: &mut (impl AsContextMut<Data = Ctx> + Send),
+        data: &[u8],
+    ) -> Option<String> {
+        Self::load_snapshot(instance, store, data).await
+    }
+
     async fn record_last_known_limits<T: HasAll<Ctx> + Send + Sync>(
-        _this: &T,
-        _account_id: &AccountId,
-        _last_known_limits: &CurrentResourceLimits,
+        this: &T,
+        account_id: &AccountId,
+        last_known_limits: &CurrentResourceLimits,
     ) -> Result<(), GolemError> {
+        this.active_workers()
+            .set_account_linear_memory_limit(account_id, last_known_limits.max_memory as u64);
         Ok(())
     }
 
@@ -1341,52 +1743,102 @@ impl<Ctx: WorkerCtx + DurableWorkerCtxView<Ctx>> ExternalOperations<Ctx> for Dur
     ) -> Result<(), anyhow::Error> {
         info!("Recovering workers");
 
+        let start = Instant::now();
         let workers = this.worker_service().get_running_workers_in_shards().await;
 
         debug!("Recovering running workers: {:?}", workers);
 
+        let max_concurrent_recoveries = this
+            .config()
+            .active_workers
+            .max_concurrent_recoveries
+            .max(1);
+
+        let mut prioritized_workers = stream::iter(workers)
+            .map(|worker| async move {
+                let owned_worker_id = worker.owned_worker_id();
+                let actualized_metadata =
+                    calculate_last_known_status(this, &owned_worker_id, &Some(worker)).await?;
+                let last_activity =
+                    last_activity(this, &owned_worker_id, actualized_metadata.oplog_idx).await;
+                let priority = RecoveryPriority::new(&actualized_metadata, last_activity);
+                Ok::<_, GolemError>((owned_worker_id, actualized_metadata, priority))
+            })
+            .buffer_unordered(max_concurrent_recoveries)
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        // Workers with pending invocations and recent activity are resumed first, so user-visible
+        // work continues before idle workers are rehydrated
+        RecoveryPriority::sort_for_recovery(&mut prioritized_workers, |(_, _, priority)| priority);
+
+        record_recovery_started(prioritized_workers.len());
+
         let default_retry_config = &this.config().retry;
-        for worker in workers {
-            let owned_worker_id = worker.owned_worker_id();
-            let actualized_metadata =
-                calculate_last_known_status(this, &owned_worker_id, &Some(worker)).await?;
-            let last_error = Self::get_last_error_and_retry_count(this, &owned_worker_id).await;
-            let decision = Self::get_recovery_decision_on_startup(
-                actualized_metadata
-                    .overridden_retry_config
-                    .as_ref()
-                    .unwrap_or(default_retry_config),
-                &last_error,
-            );
+        stream::iter(prioritized_workers)
+            .map(
+                |(owned_worker_id, actualized_metadata, priority)| async move {
+                    let last_error =
+                        Self::get_last_error_and_retry_count(this, &owned_worker_id).await;
+                    let decision = Self::get_recovery_decision_on_startup(
+                        actualized_metadata
+                            .overridden_retry_config
+                            .as_ref()
+                            .unwrap_or(default_retry_config),
+                        &last_error,
+                    );
+
+                    if let Some(last_error) = last_error {
+                        debug!("Recovery decision after {last_error}: {decision:?}");
+                    }
 
-            if let Some(last_error) = last_error {
-                debug!("Recovery decision after {last_error}: {decision:?}");
-            }
+                    let result = match decision {
+                        RetryDecision::Immediate | RetryDecision::ReacquirePermits => {
+                            Worker::get_or_create_recovering(this, &owned_worker_id)
+                                .await
+                                .map(|_| "recovered")
+                        }
+                        RetryDecision::Delayed(_) => {
+                            panic!("Delayed recovery on startup is not supported currently")
+                        }
+                        RetryDecision::None => Ok("skipped"),
+                    };
+
+                    record_worker_recovered(
+                        priority.label(),
+                        *result.as_ref().unwrap_or(&"failed"),
+                    );
+                    result
+                },
+            )
+            .buffer_unordered(max_concurrent_recoveries)
+            .try_collect::<Vec<_>>()
+            .await?;
 
-            match decision {
-                RetryDecision::Immediate | RetryDecision::ReacquirePermits => {
-                    let _ = Worker::get_or_create_running(
-                        this,
-                        &owned_worker_id,
-                        None,
-                        None,
-                        None,
-                        None,
-                    )
-                    .await?;
-                }
-                RetryDecision::Delayed(_) => {
-                    panic!("Delayed recovery on startup is not supported currently")
-                }
-                RetryDecision::None => {}
-            }
-        }
+        record_recovery_finished(start.elapsed());
 
         info!("Finished recovering workers");
         Ok(())
     }
 }
 
+// Timestamp of the last oplog entry of a worker
+async fn last_activity<T: HasOplogService>(
+    this: &T,
+    owned_worker_id: &OwnedWorkerId,
+    last_oplog_idx: OplogIndex,
+) -> Option<Timestamp> {
+    if last_oplog_idx == OplogIndex::NONE {
+        None
+    } else {
+        this.oplog_service()
+            .read(owned_worker_id, last_oplog_idx, 1)
+            .await
+            .first_key_value()
+            .map(|(_, entry)| entry.timestamp())
+    }
+}
+
 async fn last_error_and_retry_count<T: HasOplogService + HasConfig>(
     this: &T,
     owned_worker_id: &OwnedWorkerId,
@@ -1522,6 +1974,8 @@ struct HttpRequestState {
     pub root_handle: u32,
     /// Information about the request to be included in the oplog
     pub request: SerializableHttpRequest,
+    /// Number of response body bytes read so far
+    pub received_bytes: u64,
 }
 
 pub struct PrivateDurableWorkerState {
@@ -1533,6 +1987,10 @@ pub struct PrivateDurableWorkerState {
     worker_enumeration_service: Arc<dyn worker_enumeration::WorkerEnumerationService + Send + Sync>,
     key_value_service: Arc<dyn KeyValueService + Send + Sync>,
     blob_store_service: Arc<dyn BlobStoreService + Send + Sync>,
+    secret_service: Arc<dyn SecretService + Send + Sync>,
+    topic_service: Arc<dyn TopicService + Send + Sync>,
+    saga_service: Arc<dyn SagaService + Send + Sync>,
+    virtual_clock_service: Arc<dyn VirtualClockService + Send + Sync>,
     component_service: Arc<dyn ComponentService + Send + Sync>,
     config: Arc<GolemConfig>,
     owned_worker_id: OwnedWorkerId,
@@ -1549,14 +2007,32 @@ pub struct PrivateDurableWorkerState {
 
     /// State of ongoing http requests, key is the resource id it is most recently associated with (one state object can belong to multiple resources, but just one at once)
     open_http_requests: HashMap<u32, HttpRequestState>,
+    /// Durability policy of the outgoing http requests, depending on the worker's component
+    http_durability: HttpDurabilityPolicy,
+    /// Allowed and denied destinations of the outgoing http requests, depending on the worker's component
+    network_egress: NetworkEgressPolicy,
 
     snapshotting_mode: Option<PersistenceLevel>,
+    /// Oplog index of the latest snapshot taken or loaded by this instance of the worker
+    last_snapshot_index: OplogIndex,
 
     indexed_resources: HashMap<IndexedResourceKey, WorkerResourceId>,
     component_metadata: ComponentMetadata,
 
     total_linear_memory_size: u64,
+    max_linear_memory: Option<u64>,
+    invocation_fuel_budget: Option<u64>,
     sync_helper: SyncHelper,
+
+    /// Arguments and environment variables returned to the worker by live calls
+    args: Vec<String>,
+    env: Vec<(String, String)>,
+
+    /// Context passed by the caller with the current invocation
+    invocation_context: BTreeMap<String, String>,
+
+    /// True for throwaway instances of the worker, see `WorkerConfig::sandboxed`
+    sandboxed: bool,
 }
 
 impl PrivateDurableWorkerState {
@@ -1571,6 +2047,10 @@ impl PrivateDurableWorkerState {
         >,
         key_value_service: Arc<dyn KeyValueService + Send + Sync>,
         blob_store_service: Arc<dyn BlobStoreService + Send + Sync>,
+        secret_service: Arc<dyn SecretService + Send + Sync>,
+        topic_service: Arc<dyn TopicService + Send + Sync>,
+        saga_service: Arc<dyn SagaService + Send + Sync>,
+        virtual_clock_service: Arc<dyn VirtualClockService + Send + Sync>,
         component_service: Arc<dyn ComponentService + Send + Sync>,
         config: Arc<GolemConfig>,
         owned_worker_id: OwnedWorkerId,
@@ -1580,6 +2060,10 @@ impl PrivateDurableWorkerState {
         last_oplog_index: OplogIndex,
         component_metadata: ComponentMetadata,
         total_linear_memory_size: u64,
+        max_linear_memory: Option<u64>,
+        args: Vec<String>,
+        env: Vec<(String, String)>,
+        sandboxed: bool,
     ) -> Self {
         let replay_state = ReplayState::new(
             owned_worker_id.clone(),
@@ -1589,6 +2073,15 @@ impl PrivateDurableWorkerState {
             last_oplog_index,
         )
         .await;
+        let http_durability = config
+            .http_durability
+            .policy_for(&owned_worker_id.worker_id.component_id);
+        let network_egress = config
+            .network_egress
+            .policy_for(&owned_worker_id.worker_id.component_id);
+        let invocation_fuel_budget = config
+            .limits
+            .invocation_fuel_budget(&owned_worker_id.worker_id.component_id);
         Self {
             oplog_service,
             oplog: oplog.clone(),
@@ -1598,6 +2091,10 @@ impl PrivateDurableWorkerState {
             worker_enumeration_service,
             key_value_service,
             blob_store_service,
+            secret_service,
+            topic_service,
+            saga_service,
+            virtual_clock_service,
             component_service,
             config,
             owned_worker_id,
@@ -1611,12 +2108,95 @@ impl PrivateDurableWorkerState {
             assume_idempotence: true,
             open_function_table: HashMap::new(),
             open_http_requests: HashMap::new(),
+            http_durability,
+            network_egress,
             snapshotting_mode: None,
+            last_snapshot_index: OplogIndex::NONE,
             indexed_resources: HashMap::new(),
             component_metadata,
             total_linear_memory_size,
+            max_linear_memory,
+            invocation_fuel_budget,
             sync_helper: SyncHelper::new(oplog.clone(), replay_state.clone()),
             replay_state,
+            args,
+            env,
+            invocation_context: BTreeMap::new(),
+            sandboxed,
+        }
+    }
+
+    /// Replaces the worker's arguments and environment variables, keeping the environment
+    /// variables set by Golem
+    fn set_environment(&mut self, args: Vec<String>, env: Vec<(String, String)>) {
+        let golem_env = self
+            .env
+            .iter()
+            .filter(|(key, _)| GOLEM_ENV_VARS.contains(&key.as_str()))
+            .cloned()
+            .collect::<Vec<_>>();
+        self.args = args;
+        self.env = env
+            .into_iter()
+            .filter(|(key, _)| !GOLEM_ENV_VARS.contains(&key.as_str()))
+            .chain(golem_env)
+            .collect();
+    }
+
+    /// Looks for the latest snapshot of the worker taken with the current component version.
+    /// The oplog is searched backwards, as snapshots may have been skipped for any number of
+    /// intervals while the worker owned resources. The search stops at the last update of the
+    /// worker, as the snapshots before it belong to another component version.
+    async fn find_latest_snapshot(&self) -> Option<(OplogIndex, OplogPayload)> {
+        const CHUNK_SIZE: u64 = 100;
+
+        let last_index = u64::from(self.replay_state.replay_target());
+        let lowest_index = u64::from(OplogIndex::INITIAL.next());
+        let mut end = last_index;
+        while end >= lowest_index {
+            let start = end.saturating_sub(CHUNK_SIZE - 1).max(lowest_index);
+            let entries = self
+                .oplog_service
+                .read(
+                    &self.owned_worker_id,
+                    OplogIndex::from_u64(start),
+                    end - start + 1,
+                )
+                .await;
+            for (idx, entry) in entries.into_iter().rev() {
+                if self.replay_state.is_in_deleted_region(idx).await {
+                    continue;
+                }
+                match entry {
+                    OplogEntry::Snapshot {
+                        component_version,
+                        payload,
+                        ..
+                    } if component_version == self.component_metadata.version => {
+                        return Some((idx, payload));
+                    }
+                    OplogEntry::SuccessfulUpdate { .. } => return None,
+                    _ => {}
+                }
+            }
+            end = start - 1;
+        }
+        None
+    }
+
+    /// Rebuilds the worker's sagas from its oplog after a jump, so they do not keep the effects
+    /// of the saga host function calls in the deleted region
+    async fn restore_sagas(&self) {
+        let deleted_regions = self.replay_state.deleted_regions().await;
+        if let Err(err) = restore_sagas_from_oplog(
+            &self.oplog_service,
+            &self.saga_service,
+            &self.owned_worker_id,
+            &deleted_regions,
+        )
+        .await
+        {
+            warn!("Failed to restore the sagas of the worker after a jump: {err}");
         }
     }
 
@@ -1624,6 +2204,18 @@ impl PrivateDurableWorkerState {
         &mut self,
         wrapped_function_type: &WrappedFunctionType,
     ) -> Result<OplogIndex, GolemError> {
+        if self.sandboxed
+            && self.is_live()
+            && matches!(
+                wrapped_function_type,
+                WrappedFunctionType::WriteRemote | WrappedFunctionType::WriteRemoteBatched(_)
+            )
+        {
+            return Err(GolemError::runtime(
+                "Throwaway instances of a worker cannot perform side effects",
+            ));
+        }
+
         if self.persistence_level != PersistenceLevel::PersistNothing
             && ((*wrapped_function_type == WrappedFunctionType::WriteRemote
                 && !self.assume_idempotence)
@@ -1684,6 +2276,7 @@ impl PrivateDurableWorkerState {
                         self.oplog
                             .add_and_commit(OplogEntry::jump(deleted_region))
                             .await;
+                        self.restore_sagas().await;
                     }
 
                     Ok(begin_index)
@@ -1774,6 +2367,13 @@ impl PrivateDurableWorkerState {
         !self.is_live()
     }
 
+    /// Gets the virtual clock of the worker, if its clock is virtualized in test mode
+    pub async fn virtual_clock(&self) -> Result<Option<Arc<VirtualClock>>, GolemError> {
+        self.virtual_clock_service
+            .get(&self.owned_worker_id.worker_id)
+            .await
+    }
+
     pub async fn sleep_until(&self, when: DateTime<Utc>) -> Result<(), GolemError> {
         let promise_id = self
             .promise_service
@@ -1976,7 +2576,18 @@ impl<'a, Ctx: WorkerCtx> WasiHttpView for DurableWorkerCtxWasiHttpView<'a, Ctx>
             // or poll the response future.
             Ok(HostFutureIncomingResponse::deferred(request, config))
         } else {
-            Ok(default_send_request(request, config))
+            let network_egress = &self.0.state.network_egress;
+            match &self.0.state.http_durability.retries {
+                Some(retry_config) if is_retryable_request(&request) => {
+                    Ok(send_request_with_retries(
+                        request,
+                        config,
+                        retry_config.clone(),
+                        network_egress,
+                    ))
+                }
+                _ => Ok(send_request(request, config, network_egress)),
+            }
         }
     }
 }
@@ -1996,8 +2607,17 @@ macro_rules! get_oplog_entry {
                 })+
                 entry if entry.is_hint() => {}
                 _ => {
+                    $private_state
+                        .record_divergence(
+                            oplog_index,
+                            $crate::durable_host::replay_state::ReplayState::side_effect_signature(
+                                &oplog_entry,
+                            ),
+                            stringify!($($cases),+),
+                        )
+                        .await;
                     break Err($crate::error::GolemError::unexpected_oplog_entry(
-                        stringify!($case),
+                        stringify!($($cases),+),
                         format!("{:?}", oplog_entry),
                     ));
                 }
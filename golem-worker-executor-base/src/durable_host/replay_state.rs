@@ -25,7 +25,18 @@ use std::hash::Hasher;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::sync::RwLock;
-use tracing::debug;
+use tracing::{debug, warn};
+
+/// A side effect attempted while replaying the oplog which does not match the one recorded in it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReplayDivergence {
+    /// Index of the oplog entry where the replay diverged
+    pub oplog_index: OplogIndex,
+    /// The side effect recorded in the oplog
+    pub expected: String,
+    /// The side effect the worker attempted instead
+    pub actual: String,
+}
 
 #[derive(Clone)]
 pub struct ReplayState {
@@ -45,6 +56,10 @@ struct InternalReplayState {
     pub next_deleted_region: Option<OplogRegion>,
     /// Hashes of log entries persisted since the last read non-hint oplog entry
     pub log_hashes: HashSet<(u64, u64)>,
+    /// Hosts of the outgoing http requests denied since the last read non-hint oplog entry
+    pub denied_egress_hosts: HashSet<String>,
+    /// The first divergence from the recorded history detected while replaying
+    pub divergence: Option<ReplayDivergence>,
 }
 
 impl ReplayState {
@@ -66,6 +81,8 @@ impl ReplayState {
                 deleted_regions,
                 next_deleted_region,
                 log_hashes: HashSet::new(),
+                denied_egress_hosts: HashSet::new(),
+                divergence: None,
             })),
             has_seen_logs: Arc::new(AtomicBool::new(false)),
         };
@@ -95,6 +112,23 @@ impl ReplayState {
         internal.deleted_regions.add(region);
     }
 
+    /// Skips the oplog entries up to the given snapshot, whose effects are restored by loading
+    /// it. The skipped region is an override, so it is not persisted in the worker's status.
+    pub async fn skip_to_snapshot(&mut self, snapshot_index: OplogIndex) {
+        let mut internal = self.internal.write().await;
+        internal
+            .deleted_regions
+            .set_override(DeletedRegions::from_regions(vec![
+                OplogRegion::from_index_range(OplogIndex::INITIAL.next()..=snapshot_index),
+            ]));
+        if snapshot_index > self.last_replayed_index.get() {
+            self.last_replayed_index.set(snapshot_index);
+        }
+        internal.next_deleted_region = internal
+            .deleted_regions
+            .find_next_deleted_region(self.last_replayed_index.get());
+    }
+
     pub async fn is_in_deleted_region(&self, oplog_index: OplogIndex) -> bool {
         let internal = self.internal.read().await;
         internal.deleted_regions.is_in_deleted_region(oplog_index)
@@ -119,6 +153,7 @@ impl ReplayState {
 
         // Skipping hint entries and recording log entries
         let mut logs = HashSet::new();
+        let mut denied_egress_hosts = HashSet::new();
         while self.is_replay() {
             let saved_replay_idx = self.last_replayed_index.get();
             let internal = self.internal.read().await;
@@ -140,6 +175,8 @@ impl ReplayState {
             {
                 let hash = Self::hash_log_entry(*level, context, message);
                 logs.insert(hash);
+            } else if let OplogEntry::NetworkEgressDenied { host, .. } = &entry {
+                denied_egress_hosts.insert(host.clone());
             }
         }
 
@@ -147,6 +184,7 @@ impl ReplayState {
             .store(!logs.is_empty(), Ordering::Relaxed);
         let mut internal = self.internal.write().await;
         internal.log_hashes = logs;
+        internal.denied_egress_hosts = denied_egress_hosts;
 
         (read_idx, entry)
     }
@@ -171,6 +209,62 @@ impl ReplayState {
             .store(!internal.log_hashes.is_empty(), Ordering::Relaxed);
     }
 
+    /// Returns true if an outgoing http request to the given host was denied by the network egress
+    /// policy since the last non-hint oplog entry, and forgets about the denial.
+    pub async fn take_denied_egress(&self, host: &str) -> bool {
+        let mut internal = self.internal.write().await;
+        internal.denied_egress_hosts.remove(host)
+    }
+
+    /// Records that the replay diverged from the recorded history at the given oplog index.
+    /// Only the first divergence is kept, as the following ones are usually caused by it.
+    pub async fn record_divergence(
+        &self,
+        oplog_index: OplogIndex,
+        expected: impl Into<String>,
+        actual: impl Into<String>,
+    ) {
+        let expected = expected.into();
+        let actual = actual.into();
+        warn!(
+            oplog_index = %oplog_index,
+            expected,
+            actual,
+            "Replay diverged from the recorded oplog"
+        );
+        let mut internal = self.internal.write().await;
+        if internal.divergence.is_none() {
+            internal.divergence = Some(ReplayDivergence {
+                oplog_index,
+                expected,
+                actual,
+            });
+        }
+    }
+
+    /// Takes the divergence detected since the last call, if any
+    pub async fn take_divergence(&self) -> Option<ReplayDivergence> {
+        let mut internal = self.internal.write().await;
+        internal.divergence.take()
+    }
+
+    /// Describes the side effect recorded by an oplog entry, for reporting replay divergences
+    pub fn side_effect_signature(entry: &OplogEntry) -> String {
+        match entry {
+            OplogEntry::ImportedFunctionInvoked { function_name, .. }
+            | OplogEntry::ImportedFunctionInvokedV1 { function_name, .. }
+            | OplogEntry::ExportedFunctionInvoked { function_name, .. } => function_name.clone(),
+            _ => {
+                let debug = format!("{entry:?}");
+                debug
+                    .split(|c: char| !c.is_alphanumeric())
+                    .next()
+                    .unwrap_or_default()
+                    .to_string()
+            }
+        }
+    }
+
     fn hash_log_entry(level: LogLevel, context: &str, message: &str) -> (u64, u64) {
         let mut hasher = MetroHash128::new();
         hasher.write_u8(level as u8);
@@ -215,9 +309,11 @@ impl ReplayState {
 
         const CHUNK_SIZE: u64 = 1024;
         while start < replay_target {
-            let entries = self
+            // Entries which cannot be decoded are skipped here, they are quarantined
+            // as deleted regions when the worker's status is calculated
+            let (entries, decode_failures) = self
                 .oplog_service
-                .read(&self.owned_worker_id, start, CHUNK_SIZE)
+                .read_range_checked(&self.owned_worker_id, start, start.range_end(CHUNK_SIZE))
                 .await;
             for (idx, entry) in &entries {
                 // TODO: handle deleted regions
@@ -227,7 +323,9 @@ impl ReplayState {
                     return None;
                 }
             }
-            start = start.range_end(entries.len() as u64).next();
+            start = start
+                .range_end((entries.len() + decode_failures.len()) as u64)
+                .next();
         }
 
         None
@@ -238,7 +336,7 @@ impl ReplayState {
     ) -> Result<Option<(String, Vec<Value>, IdempotencyKey)>, GolemError> {
         loop {
             if self.is_replay() {
-                let (_, oplog_entry) = self.get_oplog_entry().await;
+                let (oplog_index, oplog_entry) = self.get_oplog_entry().await;
                 match &oplog_entry {
                     OplogEntry::ExportedFunctionInvoked {
                         function_name,
@@ -266,6 +364,12 @@ impl ReplayState {
                     }
                     entry if entry.is_hint() => {}
                     _ => {
+                        self.record_divergence(
+                            oplog_index,
+                            Self::side_effect_signature(&oplog_entry),
+                            "ExportedFunctionInvoked",
+                        )
+                        .await;
                         break Err(GolemError::unexpected_oplog_entry(
                             "ExportedFunctionInvoked",
                             format!("{:?}", oplog_entry),
@@ -280,10 +384,10 @@ impl ReplayState {
 
     pub async fn get_oplog_entry_exported_function_completed(
         &mut self,
-    ) -> Result<Option<TypeAnnotatedValue>, GolemError> {
+    ) -> Result<Option<(OplogIndex, TypeAnnotatedValue)>, GolemError> {
         loop {
             if self.is_replay() {
-                let (_, oplog_entry) = self.get_oplog_entry().await;
+                let (oplog_index, oplog_entry) = self.get_oplog_entry().await;
                 match &oplog_entry {
                     OplogEntry::ExportedFunctionCompleted { .. } => {
                         let response: TypeAnnotatedValue = self
@@ -293,10 +397,16 @@ impl ReplayState {
                             .expect("failed to deserialize function response payload")
                             .unwrap();
 
-                        break Ok(Some(response));
+                        break Ok(Some((oplog_index, response)));
                     }
                     entry if entry.is_hint() => {}
                     _ => {
+                        self.record_divergence(
+                            oplog_index,
+                            Self::side_effect_signature(&oplog_entry),
+                            "ExportedFunctionCompleted",
+                        )
+                        .await;
                         break Err(GolemError::unexpected_oplog_entry(
                             "ExportedFunctionCompleted",
                             format!("{:?}", oplog_entry),
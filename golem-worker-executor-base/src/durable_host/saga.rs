@@ -0,0 +1,210 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use golem_common::model::oplog::WrappedFunctionType;
+use golem_common::model::{TargetWorkerId, WorkerId};
+use golem_wasm_rpc::golem::rpc::types::Uri;
+use golem_wasm_rpc::WitValue;
+use wasmtime_wasi::bindings::cli::environment::Host as EnvironmentHost;
+
+use crate::durable_host::serialized::SerializableError;
+use crate::durable_host::wasm_rpc::UrnExtensions;
+use crate::durable_host::{Durability, DurableWorkerCtx};
+use crate::error::GolemError;
+use crate::metrics::wasm::record_host_function_call;
+use crate::preview2::saga::golem::saga::saga::Host;
+use crate::services::saga::{run_compensations, Compensation};
+use crate::workerctx::WorkerCtx;
+
+#[async_trait]
+impl<Ctx: WorkerCtx> Host for DurableWorkerCtx<Ctx> {
+    async fn begin_saga(&mut self) -> anyhow::Result<Result<u64, String>> {
+        let _permit = self.begin_async_host_function().await?;
+        record_host_function_call("golem::saga", "begin_saga");
+        let owned_worker_id = self.owned_worker_id.clone();
+        // The oplog index identifies the saga uniquely within the worker, and it is the same
+        // when the worker gets replayed
+        let saga_id = u64::from(self.state.current_oplog_index().await);
+        let result = Durability::<Ctx, u64, u64, SerializableError>::wrap(
+            self,
+            WrappedFunctionType::WriteRemote,
+            "golem saga::begin_saga",
+            saga_id,
+            |ctx| {
+                Box::pin(async move {
+                    ctx.state
+                        .saga_service
+                        .begin(&owned_worker_id, saga_id)
+                        .await?;
+                    Ok::<u64, GolemError>(saga_id)
+                })
+            },
+        )
+        .await;
+        Ok(result.map_err(|err: GolemError| err.to_string()))
+    }
+
+    async fn add_compensation(
+        &mut self,
+        saga: u64,
+        target: Uri,
+        function_name: String,
+        function_params: Vec<WitValue>,
+    ) -> anyhow::Result<Result<(), String>> {
+        let _permit = self.begin_async_host_function().await?;
+        record_host_function_call("golem::saga", "add_compensation");
+        let target = match compensation_target(&target) {
+            Ok(target) if target == self.owned_worker_id.worker_id => {
+                return Ok(Err(
+                    "Compensations must invoke another worker than the saga's own".to_string(),
+                ));
+            }
+            Ok(target) => target,
+            Err(err) => return Ok(Err(err)),
+        };
+        let owned_worker_id = self.owned_worker_id.clone();
+        let result =
+            Durability::<Ctx, (u64, WorkerId, String, Vec<WitValue>), (), SerializableError>::wrap(
+                self,
+                WrappedFunctionType::WriteRemote,
+                "golem saga::add_compensation",
+                (
+                    saga,
+                    target.clone(),
+                    function_name.clone(),
+                    function_params.clone(),
+                ),
+                |ctx| {
+                    Box::pin(async move {
+                        ctx.state
+                            .saga_service
+                            .add_compensation(
+                                &owned_worker_id,
+                                saga,
+                                Compensation {
+                                    target,
+                                    function_name,
+                                    function_params,
+                                },
+                            )
+                            .await
+                    })
+                },
+            )
+            .await;
+        Ok(result.map_err(|err: GolemError| err.to_string()))
+    }
+
+    async fn complete_saga(&mut self, saga: u64) -> anyhow::Result<Result<(), String>> {
+        let _permit = self.begin_async_host_function().await?;
+        record_host_function_call("golem::saga", "complete_saga");
+        let owned_worker_id = self.owned_worker_id.clone();
+        let result = Durability::<Ctx, u64, (), SerializableError>::wrap(
+            self,
+            WrappedFunctionType::WriteRemote,
+            "golem saga::complete_saga",
+            saga,
+            |ctx| {
+                Box::pin(async move {
+                    ctx.state
+                        .saga_service
+                        .complete(&owned_worker_id, saga)
+                        .await
+                })
+            },
+        )
+        .await;
+        Ok(result.map_err(|err: GolemError| err.to_string()))
+    }
+
+    async fn abort_saga(&mut self, saga: u64) -> anyhow::Result<Result<(), String>> {
+        let args = self.get_arguments().await?;
+        let env = self.get_environment().await?;
+
+        let _permit = self.begin_async_host_function().await?;
+        record_host_function_call("golem::saga", "abort_saga");
+        let owned_worker_id = self.owned_worker_id.clone();
+        let result = Durability::<Ctx, u64, Result<(), String>, SerializableError>::wrap(
+            self,
+            WrappedFunctionType::WriteRemote,
+            "golem saga::abort_saga",
+            saga,
+            |ctx| {
+                Box::pin(async move {
+                    let saga_service = ctx.state.saga_service.clone();
+                    let saga = saga_service
+                        .start_compensation(&owned_worker_id, saga)
+                        .await?;
+                    let result =
+                        run_compensations(&ctx.rpc(), &owned_worker_id, &saga, &args, &env).await;
+                    saga_service
+                        .finish_compensation(&owned_worker_id, saga.id, result.clone())
+                        .await?;
+                    Ok::<Result<(), String>, GolemError>(result)
+                })
+            },
+        )
+        .await;
+        Ok(result.unwrap_or_else(|err| Err(err.to_string())))
+    }
+}
+
+#[async_trait]
+impl<Ctx: WorkerCtx> Host for &mut DurableWorkerCtx<Ctx> {
+    async fn begin_saga(&mut self) -> anyhow::Result<Result<u64, String>> {
+        (*self).begin_saga().await
+    }
+
+    async fn add_compensation(
+        &mut self,
+        saga: u64,
+        target: Uri,
+        function_name: String,
+        function_params: Vec<WitValue>,
+    ) -> anyhow::Result<Result<(), String>> {
+        (*self)
+            .add_compensation(saga, target, function_name, function_params)
+            .await
+    }
+
+    async fn complete_saga(&mut self, saga: u64) -> anyhow::Result<Result<(), String>> {
+        (*self).complete_saga(saga).await
+    }
+
+    async fn abort_saga(&mut self, saga: u64) -> anyhow::Result<Result<(), String>> {
+        (*self).abort_saga(saga).await
+    }
+}
+
+/// Compensations are only accepted for named target workers, so running them later invokes the
+/// same worker which performed the compensated step
+fn compensation_target(target: &Uri) -> Result<WorkerId, String> {
+    match target.parse_as_golem_urn() {
+        Some((
+            TargetWorkerId {
+                component_id,
+                worker_name: Some(worker_name),
+            },
+            None,
+        )) => Ok(WorkerId {
+            component_id,
+            worker_name,
+        }),
+        _ => Err(format!(
+            "Invalid URI: {}. Must be urn:worker:component-id/worker-name",
+            target.value
+        )),
+    }
+}
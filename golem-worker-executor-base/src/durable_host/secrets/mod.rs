@@ -0,0 +1,58 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use golem_common::model::oplog::WrappedFunctionType;
+
+use crate::durable_host::serialized::SerializableError;
+use crate::durable_host::{Durability, DurableWorkerCtx};
+use crate::metrics::wasm::record_host_function_call;
+use crate::preview2::secrets::golem::secrets::secrets::Host;
+use crate::workerctx::WorkerCtx;
+
+/// The value read is persisted in the oplog like the result of any other remote read, so a
+/// secret updated or deleted since then does not change the replayed behaviour of the worker.
+#[async_trait]
+impl<Ctx: WorkerCtx> Host for DurableWorkerCtx<Ctx> {
+    async fn get_secret(&mut self, name: String) -> anyhow::Result<Option<Vec<u8>>> {
+        let _permit = self.begin_async_host_function().await?;
+        record_host_function_call("golem::secrets", "get_secret");
+
+        let account_id = self.owned_worker_id.account_id();
+        let component_id = self.owned_worker_id.component_id();
+        let value = Durability::<Ctx, String, Option<Vec<u8>>, SerializableError>::wrap(
+            self,
+            WrappedFunctionType::ReadRemote,
+            "golem secrets::get_secret",
+            name.clone(),
+            |ctx| {
+                Box::pin(async move {
+                    ctx.state
+                        .secret_service
+                        .get(&account_id, &component_id, &name)
+                        .await
+                })
+            },
+        )
+        .await?;
+        Ok(value)
+    }
+}
+
+#[async_trait]
+impl<Ctx: WorkerCtx> Host for &mut DurableWorkerCtx<Ctx> {
+    async fn get_secret(&mut self, name: String) -> anyhow::Result<Option<Vec<u8>>> {
+        (*self).get_secret(name).await
+    }
+}
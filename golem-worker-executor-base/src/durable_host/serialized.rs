@@ -17,6 +17,8 @@ use crate::services::rpc::RpcError;
 use crate::services::worker_proxy::WorkerProxyError;
 use anyhow::anyhow;
 use bincode::{Decode, Encode};
+use golem_common::config::RetryConfig;
+use golem_common::model::{ComponentVersion, Timestamp, WorkerId, WorkerMetadata, WorkerStatus};
 use std::ops::Add;
 use std::time::{Duration, SystemTime};
 use wasmtime_wasi::bindings::sockets::ip_name_lookup::IpAddress;
@@ -532,6 +534,38 @@ pub struct SerializableFileTimes {
     pub data_modification_timestamp: Option<SerializableDateTime>,
 }
 
+/// The metadata of a worker as seen by the guest through golem:api, recorded so it does not
+/// change during replay
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct SerializableWorkerMetadata {
+    pub worker_id: WorkerId,
+    pub args: Vec<String>,
+    pub env: Vec<(String, String)>,
+    pub status: WorkerStatus,
+    pub component_version: ComponentVersion,
+}
+
+impl From<WorkerMetadata> for SerializableWorkerMetadata {
+    fn from(value: WorkerMetadata) -> Self {
+        Self {
+            worker_id: value.worker_id,
+            args: value.args,
+            env: value.env,
+            status: value.last_known_status.status,
+            component_version: value.last_known_status.component_version,
+        }
+    }
+}
+
+/// The worker's own metadata as seen by the guest, recorded so it does not change during replay
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct SerializableSelfMetadata {
+    pub metadata: SerializableWorkerMetadata,
+    pub created_at: Timestamp,
+    pub retry_config: RetryConfig,
+    pub pending_update_count: u32,
+}
+
 #[cfg(test)]
 mod tests {
     use test_r::test;
@@ -543,7 +577,9 @@ mod tests {
     use crate::error::GolemError;
     use crate::model::InterruptKind;
     use golem_common::model::oplog::OplogIndex;
-    use golem_common::model::{ComponentId, PromiseId, ShardId, WorkerId};
+    use golem_common::model::{
+        ComponentId, IdempotencyKey, PromiseId, ShardId, WorkerId, WorkerName,
+    };
     use proptest::collection::vec;
     use proptest::prelude::*;
     use proptest::strategy::LazyJust;
@@ -636,7 +672,7 @@ mod tests {
     fn workerid_strat() -> impl Strategy<Value = WorkerId> {
         (componentid_strat(), ".+").prop_map(|(component_id, worker_name)| WorkerId {
             component_id,
-            worker_name,
+            worker_name: WorkerName::from_existing(worker_name),
         })
     }
 
@@ -684,6 +720,10 @@ mod tests {
             ".*".prop_map(|details| GolemError::PreviousInvocationFailed { details }),
             Just(GolemError::PreviousInvocationExited),
             ".*".prop_map(|details| GolemError::Unknown { details }),
+            (workerid_strat(), any::<u64>()).prop_map(|(worker_id, pending_invocations)| GolemError::TooManyPendingInvocations { worker_id, pending_invocations }),
+            (workerid_strat(), any::<u64>()).prop_map(|(worker_id, timeout_millis)| GolemError::InvocationTimedOut { worker_id, timeout_millis }),
+            any::<u64>().prop_map(|max_active_workers| GolemError::ExecutorBusy { max_active_workers }),
+            (workerid_strat(), ".*").prop_map(|(worker_id, idempotency_key)| GolemError::InvocationNotFound { worker_id, idempotency_key: IdempotencyKey::new(idempotency_key) }),
         }
     }
 
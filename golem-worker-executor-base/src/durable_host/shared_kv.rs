@@ -0,0 +1,148 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use golem_common::model::oplog::WrappedFunctionType;
+
+use crate::durable_host::serialized::SerializableError;
+use crate::durable_host::{Durability, DurableWorkerCtx};
+use crate::metrics::wasm::record_host_function_call;
+use crate::preview2::shared_kv::golem::shared_kv::store::{Entry, Host};
+use crate::services::key_value::SharedKeyValueEntry;
+use crate::workerctx::WorkerCtx;
+
+#[async_trait]
+impl<Ctx: WorkerCtx> Host for DurableWorkerCtx<Ctx> {
+    async fn get(&mut self, key: String) -> anyhow::Result<Result<Option<Entry>, String>> {
+        let _permit = self.begin_async_host_function().await?;
+        record_host_function_call("golem::shared_kv", "get");
+        let account_id = self.owned_worker_id.account_id();
+        let result =
+            Durability::<Ctx, String, Option<SharedKeyValueEntry>, SerializableError>::wrap(
+                self,
+                WrappedFunctionType::ReadRemote,
+                "golem shared_kv::get",
+                key.clone(),
+                |ctx| ctx.state.key_value_service.get_shared(account_id, key),
+            )
+            .await;
+        Ok(result
+            .map(|entry| {
+                entry.map(|entry| Entry {
+                    value: entry.value,
+                    version: entry.version,
+                })
+            })
+            .map_err(|err| err.to_string()))
+    }
+
+    async fn compare_and_swap(
+        &mut self,
+        key: String,
+        expected_version: u64,
+        value: Vec<u8>,
+    ) -> anyhow::Result<Result<Option<u64>, String>> {
+        let _permit = self.begin_async_host_function().await?;
+        record_host_function_call("golem::shared_kv", "compare_and_swap");
+        let account_id = self.owned_worker_id.account_id();
+        let result = Durability::<Ctx, (String, u64, u64), Option<u64>, SerializableError>::wrap(
+            self,
+            WrappedFunctionType::WriteRemote,
+            "golem shared_kv::compare_and_swap",
+            (key.clone(), expected_version, value.len() as u64),
+            |ctx| {
+                ctx.state.key_value_service.compare_and_swap_shared(
+                    account_id,
+                    key,
+                    expected_version,
+                    value,
+                )
+            },
+        )
+        .await;
+        Ok(result.map_err(|err| err.to_string()))
+    }
+
+    async fn compare_and_delete(
+        &mut self,
+        key: String,
+        expected_version: u64,
+    ) -> anyhow::Result<Result<bool, String>> {
+        let _permit = self.begin_async_host_function().await?;
+        record_host_function_call("golem::shared_kv", "compare_and_delete");
+        let account_id = self.owned_worker_id.account_id();
+        let result = Durability::<Ctx, (String, u64), bool, SerializableError>::wrap(
+            self,
+            WrappedFunctionType::WriteRemote,
+            "golem shared_kv::compare_and_delete",
+            (key.clone(), expected_version),
+            |ctx| {
+                ctx.state.key_value_service.compare_and_delete_shared(
+                    account_id,
+                    key,
+                    expected_version,
+                )
+            },
+        )
+        .await;
+        Ok(result.map_err(|err| err.to_string()))
+    }
+
+    async fn list(&mut self, prefix: String) -> anyhow::Result<Result<Vec<String>, String>> {
+        let _permit = self.begin_async_host_function().await?;
+        record_host_function_call("golem::shared_kv", "list");
+        let account_id = self.owned_worker_id.account_id();
+        let result = Durability::<Ctx, String, Vec<String>, SerializableError>::wrap(
+            self,
+            WrappedFunctionType::ReadRemote,
+            "golem shared_kv::list",
+            prefix.clone(),
+            |ctx| {
+                ctx.state
+                    .key_value_service
+                    .get_shared_keys(account_id, prefix)
+            },
+        )
+        .await;
+        Ok(result.map_err(|err| err.to_string()))
+    }
+}
+
+#[async_trait]
+impl<Ctx: WorkerCtx> Host for &mut DurableWorkerCtx<Ctx> {
+    async fn get(&mut self, key: String) -> anyhow::Result<Result<Option<Entry>, String>> {
+        (*self).get(key).await
+    }
+
+    async fn compare_and_swap(
+        &mut self,
+        key: String,
+        expected_version: u64,
+        value: Vec<u8>,
+    ) -> anyhow::Result<Result<Option<u64>, String>> {
+        (*self).compare_and_swap(key, expected_version, value).await
+    }
+
+    async fn compare_and_delete(
+        &mut self,
+        key: String,
+        expected_version: u64,
+    ) -> anyhow::Result<Result<bool, String>> {
+        (*self).compare_and_delete(key, expected_version).await
+    }
+
+    async fn list(&mut self, prefix: String) -> anyhow::Result<Result<Vec<String>, String>> {
+        (*self).list(prefix).await
+    }
+}
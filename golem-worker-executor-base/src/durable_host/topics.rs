@@ -0,0 +1,158 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use golem_common::model::oplog::WrappedFunctionType;
+use tracing::warn;
+use wasmtime_wasi::bindings::cli::environment::Host as EnvironmentHost;
+
+use crate::durable_host::serialized::SerializableError;
+use crate::durable_host::{Durability, DurableWorkerCtx};
+use crate::error::GolemError;
+use crate::metrics::wasm::record_host_function_call;
+use crate::preview2::topics::golem::topics::pubsub::Host;
+use crate::services::topics::deliver_pending_messages;
+use crate::workerctx::WorkerCtx;
+
+#[async_trait]
+impl<Ctx: WorkerCtx> Host for DurableWorkerCtx<Ctx> {
+    async fn subscribe(
+        &mut self,
+        topic: String,
+        handler: String,
+    ) -> anyhow::Result<Result<(), String>> {
+        let _permit = self.begin_async_host_function().await?;
+        record_host_function_call("golem::topics", "subscribe");
+        let owned_worker_id = self.owned_worker_id.clone();
+        let result = Durability::<Ctx, (String, String), (), SerializableError>::wrap(
+            self,
+            WrappedFunctionType::WriteRemote,
+            "golem topics::subscribe",
+            (topic.clone(), handler.clone()),
+            |ctx| {
+                Box::pin(async move {
+                    ctx.state
+                        .topic_service
+                        .subscribe(
+                            &owned_worker_id.account_id,
+                            &topic,
+                            &owned_worker_id.worker_id,
+                            &handler,
+                        )
+                        .await
+                })
+            },
+        )
+        .await;
+        Ok(result.map_err(|err: GolemError| err.to_string()))
+    }
+
+    async fn unsubscribe(&mut self, topic: String) -> anyhow::Result<Result<(), String>> {
+        let _permit = self.begin_async_host_function().await?;
+        record_host_function_call("golem::topics", "unsubscribe");
+        let owned_worker_id = self.owned_worker_id.clone();
+        let result = Durability::<Ctx, String, (), SerializableError>::wrap(
+            self,
+            WrappedFunctionType::WriteRemote,
+            "golem topics::unsubscribe",
+            topic.clone(),
+            |ctx| {
+                Box::pin(async move {
+                    ctx.state
+                        .topic_service
+                        .unsubscribe(
+                            &owned_worker_id.account_id,
+                            &topic,
+                            &owned_worker_id.worker_id,
+                        )
+                        .await
+                })
+            },
+        )
+        .await;
+        Ok(result.map_err(|err: GolemError| err.to_string()))
+    }
+
+    async fn publish(
+        &mut self,
+        topic: String,
+        message: Vec<u8>,
+    ) -> anyhow::Result<Result<u64, String>> {
+        let args = self.get_arguments().await?;
+        let env = self.get_environment().await?;
+
+        let _permit = self.begin_async_host_function().await?;
+        record_host_function_call("golem::topics", "publish");
+        let owned_worker_id = self.owned_worker_id.clone();
+        let result = Durability::<Ctx, (String, u64), u64, SerializableError>::wrap(
+            self,
+            WrappedFunctionType::WriteRemote,
+            "golem topics::publish",
+            (topic.clone(), message.len() as u64),
+            |ctx| {
+                Box::pin(async move {
+                    let topic_service = ctx.state.topic_service.clone();
+                    let sequence = topic_service
+                        .publish(
+                            &owned_worker_id.account_id,
+                            &topic,
+                            &owned_worker_id.worker_id,
+                            message,
+                        )
+                        .await?;
+
+                    if let Err(err) = deliver_pending_messages(
+                        topic_service,
+                        ctx.rpc(),
+                        &owned_worker_id.account_id,
+                        &topic,
+                        &args,
+                        &env,
+                    )
+                    .await
+                    {
+                        warn!("Failed to deliver the pending messages of topic {topic}: {err}");
+                    }
+
+                    Ok::<u64, GolemError>(sequence)
+                })
+            },
+        )
+        .await;
+        Ok(result.map_err(|err| err.to_string()))
+    }
+}
+
+#[async_trait]
+impl<Ctx: WorkerCtx> Host for &mut DurableWorkerCtx<Ctx> {
+    async fn subscribe(
+        &mut self,
+        topic: String,
+        handler: String,
+    ) -> anyhow::Result<Result<(), String>> {
+        (*self).subscribe(topic, handler).await
+    }
+
+    async fn unsubscribe(&mut self, topic: String) -> anyhow::Result<Result<(), String>> {
+        (*self).unsubscribe(topic).await
+    }
+
+    async fn publish(
+        &mut self,
+        topic: String,
+        message: Vec<u8>,
+    ) -> anyhow::Result<Result<u64, String>> {
+        (*self).publish(topic, message).await
+    }
+}
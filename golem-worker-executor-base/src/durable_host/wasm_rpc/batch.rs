@@ -0,0 +1,275 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use futures::future::join_all;
+use golem_common::model::oplog::WrappedFunctionType;
+use golem_common::model::{IdempotencyKey, OwnedWorkerId, TargetWorkerId, WorkerId};
+use golem_wasm_rpc::golem::rpc::types::Uri;
+use tracing::error;
+use wasmtime_wasi::bindings::cli::environment::Host as EnvironmentHost;
+
+use crate::durable_host::serialized::SerializableError;
+use crate::durable_host::wasm_rpc::serialized::SerializableInvokeRequest;
+use crate::durable_host::wasm_rpc::{try_get_typed_parameters, UrnExtensions};
+use crate::durable_host::{Durability, DurableWorkerCtx};
+use crate::metrics::wasm::record_host_function_call;
+use crate::preview2::rpc_batch::golem::rpc_batch::batch::{Host, Invocation};
+use crate::services::rpc::RpcError;
+use crate::workerctx::{InvocationManagement, WorkerCtx};
+
+#[async_trait]
+impl<Ctx: WorkerCtx> Host for DurableWorkerCtx<Ctx> {
+    async fn enqueue(
+        &mut self,
+        invocations: Vec<Invocation>,
+    ) -> anyhow::Result<Vec<Result<(), golem_wasm_rpc::RpcError>>> {
+        record_host_function_call("golem::rpc::batch", "enqueue");
+        let args = self.get_arguments().await?;
+        let env = self.get_environment().await?;
+        let context = self.state.invocation_context.clone();
+
+        let _permit = self.begin_async_host_function().await?;
+
+        let current_idempotency_key = self
+            .get_current_idempotency_key()
+            .await
+            .unwrap_or(IdempotencyKey::fresh());
+        let oplog_index = self.state.current_oplog_index().await;
+
+        let count = invocations.len();
+        let mut requests = Vec::with_capacity(count);
+        let mut serializable_requests = Vec::with_capacity(count);
+        for (item_index, invocation) in invocations.into_iter().enumerate() {
+            match target_worker_id(&invocation.target) {
+                Ok(worker_id) => {
+                    let idempotency_key = IdempotencyKey::derived_batch_item(
+                        &current_idempotency_key,
+                        oplog_index,
+                        item_index,
+                    );
+                    serializable_requests.push(SerializableInvokeRequest {
+                        remote_worker_id: worker_id.clone(),
+                        idempotency_key: idempotency_key.clone(),
+                        function_name: invocation.function_name.clone(),
+                        function_params: try_get_typed_parameters(
+                            self.state.component_service.clone(),
+                            &worker_id.component_id,
+                            &invocation.function_name,
+                            &invocation.function_params,
+                        )
+                        .await,
+                    });
+                    requests.push(Ok((
+                        OwnedWorkerId::new(&self.owned_worker_id.account_id, &worker_id),
+                        idempotency_key,
+                        invocation.function_name,
+                        invocation.function_params,
+                    )));
+                }
+                Err(err) => requests.push(Err(err)),
+            }
+        }
+
+        let result = Durability::<
+            Ctx,
+            Vec<SerializableInvokeRequest>,
+            Vec<Result<(), RpcError>>,
+            SerializableError,
+        >::wrap(
+            self,
+            WrappedFunctionType::WriteRemote,
+            "golem::rpc::batch::enqueue",
+            serializable_requests,
+            |ctx| {
+                Box::pin(async move {
+                    let rpc = ctx.rpc();
+                    let self_worker_id = ctx.worker_id();
+                    let invocations =
+                        requests.into_iter().map(|request| {
+                            let rpc = rpc.clone();
+                            let self_worker_id = &self_worker_id;
+                            let args = &args;
+                            let env = &env;
+                            let context = &context;
+                            async move {
+                                let (
+                                    remote_worker_id,
+                                    idempotency_key,
+                                    function_name,
+                                    function_params,
+                                ) = request?;
+                                rpc.invoke(
+                                    &remote_worker_id,
+                                    Some(idempotency_key),
+                                    function_name,
+                                    function_params,
+                                    self_worker_id,
+                                    args,
+                                    env,
+                                    context,
+                                )
+                                .await
+                            }
+                        });
+                    Ok::<_, RpcError>(join_all(invocations).await)
+                })
+            },
+        )
+        .await;
+
+        Ok(batch_results(result, count))
+    }
+}
+
+/// Converts the result of the batch to one result per invocation. If the batch as a whole
+/// failed, every invocation gets the batch's error.
+fn batch_results(
+    result: Result<Vec<Result<(), RpcError>>, RpcError>,
+    count: usize,
+) -> Vec<Result<(), golem_wasm_rpc::RpcError>> {
+    match result {
+        Ok(results) => results
+            .into_iter()
+            .map(|result| {
+                result.map_err(|err| {
+                    error!("RPC error for batch item: {err}");
+                    err.into()
+                })
+            })
+            .collect(),
+        Err(err) => {
+            error!("RPC error for batch: {err}");
+            (0..count).map(|_| Err(err.clone().into())).collect()
+        }
+    }
+}
+
+#[async_trait]
+impl<Ctx: WorkerCtx> Host for &mut DurableWorkerCtx<Ctx> {
+    async fn enqueue(
+        &mut self,
+        invocations: Vec<Invocation>,
+    ) -> anyhow::Result<Vec<Result<(), golem_wasm_rpc::RpcError>>> {
+        (*self).enqueue(invocations).await
+    }
+}
+
+/// Batched invocations only accept named target workers, as generating a unique name for
+/// each target would require an additional oplog entry per invocation
+fn target_worker_id(target: &Uri) -> Result<WorkerId, RpcError> {
+    match target.parse_as_golem_urn() {
+        Some((
+            TargetWorkerId {
+                component_id,
+                worker_name: Some(worker_name),
+            },
+            None,
+        )) => Ok(WorkerId {
+            component_id,
+            worker_name,
+        }),
+        _ => Err(RpcError::ProtocolError {
+            details: format!(
+                "Invalid URI: {}. Must be urn:worker:component-id/worker-name",
+                target.value
+            ),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use golem_common::model::{ComponentId, WorkerId, WorkerName};
+    use golem_wasm_rpc::golem::rpc::types::Uri;
+
+    use crate::durable_host::wasm_rpc::UrnExtensions;
+    use crate::services::rpc::RpcError;
+
+    use super::{batch_results, target_worker_id};
+
+    #[test]
+    fn named_workers_are_valid_batch_targets() {
+        let worker_id = WorkerId {
+            component_id: ComponentId::new_v4(),
+            worker_name: WorkerName::from_existing("subscriber-1"),
+        };
+
+        let target = target_worker_id(&Uri::golem_urn(&worker_id, None));
+
+        assert_eq!(target, Ok(worker_id));
+    }
+
+    #[test]
+    fn nameless_workers_and_functions_are_not_valid_batch_targets() {
+        let component_id = ComponentId::new_v4();
+        let worker_id = WorkerId {
+            component_id: component_id.clone(),
+            worker_name: WorkerName::from_existing("subscriber-1"),
+        };
+
+        let nameless = Uri {
+            value: format!("urn:worker:{component_id}"),
+        };
+        let function = Uri::golem_urn(&worker_id, Some("golem:it/api.{notify}"));
+        let invalid = Uri {
+            value: "http://localhost/worker".to_string(),
+        };
+
+        for target in [nameless, function, invalid] {
+            assert!(matches!(
+                target_worker_id(&target),
+                Err(RpcError::ProtocolError { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn failed_batch_fails_every_invocation() {
+        let error = RpcError::Denied {
+            details: "limit exceeded".to_string(),
+        };
+
+        let results = batch_results(Err(error), 3);
+
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(|result| matches!(
+            result,
+            Err(golem_wasm_rpc::RpcError::Denied(details)) if details == "limit exceeded"
+        )));
+    }
+
+    #[test]
+    fn invocation_results_are_kept_in_order() {
+        let results = batch_results(
+            Ok(vec![
+                Ok(()),
+                Err(RpcError::NotFound {
+                    details: "missing".to_string(),
+                }),
+                Ok(()),
+            ]),
+            3,
+        );
+
+        assert!(results[0].is_ok());
+        assert!(matches!(
+            &results[1],
+            Err(golem_wasm_rpc::RpcError::NotFound(details)) if details == "missing"
+        ));
+        assert!(results[2].is_ok());
+    }
+}
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod batch;
 pub mod serialized;
 
 use crate::durable_host::serialized::SerializableError;
@@ -26,6 +27,7 @@ use crate::model::PersistenceLevel;
 use crate::services::component::ComponentService;
 use crate::services::oplog::{CommitLevel, OplogOps};
 use crate::services::rpc::{RpcDemand, RpcError};
+use crate::services::HasWorker;
 use crate::workerctx::{InvocationManagement, WorkerCtx};
 use anyhow::anyhow;
 use async_trait::async_trait;
@@ -41,6 +43,7 @@ use golem_wasm_rpc::{
     FutureInvokeResultEntry, HostWasmRpc, SubscribeAny, ValueAndType, WasmRpcEntry, WitValue,
 };
 use std::any::Any;
+use std::collections::BTreeMap;
 use std::str::FromStr;
 use std::sync::Arc;
 use tracing::{error, warn};
@@ -88,6 +91,7 @@ impl<Ctx: WorkerCtx> HostWasmRpc for DurableWorkerCtx<Ctx> {
         record_host_function_call("golem::rpc::wasm-rpc", "invoke-and-await");
         let args = self.get_arguments().await?;
         let env = self.get_environment().await?;
+        let context = self.state.invocation_context.clone();
 
         let _permit = self.begin_async_host_function().await?;
 
@@ -146,8 +150,12 @@ impl<Ctx: WorkerCtx> HostWasmRpc for DurableWorkerCtx<Ctx> {
             },
             |ctx| {
                 Box::pin(async move {
-                    ctx.rpc()
-                        .invoke_and_await(
+                    // Not holding the invocation's turn while waiting for the remote worker,
+                    // which may need a turn of its own to respond
+                    let worker = ctx.public_state.worker();
+                    let rpc = ctx.rpc();
+                    worker
+                        .without_invocation_permit(rpc.invoke_and_await(
                             &remote_worker_id,
                             Some(idempotency_key),
                             function_name,
@@ -155,7 +163,8 @@ impl<Ctx: WorkerCtx> HostWasmRpc for DurableWorkerCtx<Ctx> {
                             ctx.worker_id(),
                             &args,
                             &env,
-                        )
+                            &context,
+                        ))
                         .await
                 })
             },
@@ -210,6 +219,7 @@ impl<Ctx: WorkerCtx> HostWasmRpc for DurableWorkerCtx<Ctx> {
         record_host_function_call("golem::rpc::wasm-rpc", "invoke");
         let args = self.get_arguments().await?;
         let env = self.get_environment().await?;
+        let context = self.state.invocation_context.clone();
 
         let _permit = self.begin_async_host_function().await?;
 
@@ -262,8 +272,11 @@ impl<Ctx: WorkerCtx> HostWasmRpc for DurableWorkerCtx<Ctx> {
             },
             |ctx| {
                 Box::pin(async move {
-                    ctx.rpc()
-                        .invoke(
+                    // Enqueuing the invocation may have to wait for the remote worker to start
+                    let worker = ctx.public_state.worker();
+                    let rpc = ctx.rpc();
+                    worker
+                        .without_invocation_permit(rpc.invoke(
                             &remote_worker_id,
                             Some(idempotency_key),
                             function_name,
@@ -271,7 +284,8 @@ impl<Ctx: WorkerCtx> HostWasmRpc for DurableWorkerCtx<Ctx> {
                             ctx.worker_id(),
                             &args,
                             &env,
-                        )
+                            &context,
+                        ))
                         .await
                 })
             },
@@ -296,6 +310,7 @@ impl<Ctx: WorkerCtx> HostWasmRpc for DurableWorkerCtx<Ctx> {
         record_host_function_call("golem::rpc::wasm-rpc", "async-invoke-and-await");
         let args = self.get_arguments().await?;
         let env = self.get_environment().await?;
+        let context = self.state.invocation_context.clone();
 
         let _permit = self.begin_async_host_function().await?;
         let begin_index = self
@@ -359,6 +374,7 @@ impl<Ctx: WorkerCtx> HostWasmRpc for DurableWorkerCtx<Ctx> {
                         &worker_id,
                         &args,
                         &env,
+                        &context,
                     )
                     .await)
             });
@@ -374,6 +390,7 @@ impl<Ctx: WorkerCtx> HostWasmRpc for DurableWorkerCtx<Ctx> {
                     self_worker_id: worker_id,
                     args,
                     env,
+                    context,
                     function_name,
                     function_params,
                     idempotency_key,
@@ -435,6 +452,7 @@ enum FutureInvokeResultState {
         self_worker_id: WorkerId,
         args: Vec<String>,
         env: Vec<(String, String)>,
+        context: BTreeMap<String, String>,
         function_name: String,
         function_params: Vec<WitValue>,
         idempotency_key: IdempotencyKey,
@@ -546,6 +564,7 @@ impl<Ctx: WorkerCtx> HostFutureInvokeResult for DurableWorkerCtx<Ctx> {
                             self_worker_id,
                             args,
                             env,
+                            context,
                             function_name,
                             function_params,
                             idempotency_key,
@@ -562,6 +581,7 @@ impl<Ctx: WorkerCtx> HostFutureInvokeResult for DurableWorkerCtx<Ctx> {
                                 &self_worker_id,
                                 &args,
                                 &env,
+                                &context,
                             )
                             .await)
                     });
@@ -0,0 +1,132 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use golem_common::model::oplog::WrappedFunctionType;
+
+use crate::durable_host::serialized::SerializableError;
+use crate::durable_host::{Durability, DurableWorkerCtx};
+use crate::metrics::wasm::record_host_function_call;
+use crate::preview2::worker_blobs::golem::worker_blobs::store::Host;
+use crate::services::blob_store::blob_id_of;
+use crate::workerctx::WorkerCtx;
+
+#[async_trait]
+impl<Ctx: WorkerCtx> Host for DurableWorkerCtx<Ctx> {
+    async fn put(&mut self, data: Vec<u8>) -> anyhow::Result<Result<String, String>> {
+        let _permit = self.begin_async_host_function().await?;
+        record_host_function_call("golem::worker_blobs", "put");
+        let owned_worker_id = self.owned_worker_id.clone();
+        // Only the size of the data is recorded, the blob itself is already stored when replaying
+        let result = Durability::<Ctx, u64, String, SerializableError>::wrap(
+            self,
+            WrappedFunctionType::WriteRemote,
+            "golem worker_blobs::put",
+            data.len() as u64,
+            |ctx| {
+                ctx.state
+                    .blob_store_service
+                    .put_worker_blob(owned_worker_id, data)
+            },
+        )
+        .await;
+        Ok(result.map_err(|err| err.to_string()))
+    }
+
+    async fn get(&mut self, blob_id: String) -> anyhow::Result<Result<Option<Vec<u8>>, String>> {
+        let _permit = self.begin_async_host_function().await?;
+        record_host_function_call("golem::worker_blobs", "get");
+        let owned_worker_id = self.owned_worker_id.clone();
+        let replayed_owned_worker_id = owned_worker_id.clone();
+        let replayed_blob_id = blob_id.clone();
+        // Only the size of the blob is recorded, and it is read again from the storage when
+        // replaying. Reading the blob references it from the worker, so it is kept until the
+        // worker gets deleted, and the blob id is the hash of its contents, so the replayed
+        // data is verified to be the same as the data read originally.
+        let result = Durability::<Ctx, String, Option<u64>, SerializableError>::custom_wrap(
+            self,
+            WrappedFunctionType::ReadRemote,
+            "golem worker_blobs::get",
+            blob_id.clone(),
+            |ctx| {
+                ctx.state
+                    .blob_store_service
+                    .get_worker_blob(owned_worker_id, blob_id)
+            },
+            |_ctx, data: &Option<Vec<u8>>| Ok(data.as_ref().map(|data| data.len() as u64)),
+            move |ctx, size| {
+                Box::pin(async move {
+                    match size {
+                        Some(size) => {
+                            let data = ctx
+                                .state
+                                .blob_store_service
+                                .get_worker_blob(
+                                    replayed_owned_worker_id,
+                                    replayed_blob_id.clone(),
+                                )
+                                .await?
+                                .filter(|data| {
+                                    data.len() as u64 == size
+                                        && blob_id_of(data) == replayed_blob_id
+                                })
+                                .ok_or_else(|| {
+                                    anyhow!("Blob {replayed_blob_id} read by the worker is no longer available")
+                                })?;
+                            Ok(Some(data))
+                        }
+                        None => Ok(None),
+                    }
+                })
+            },
+        )
+        .await;
+        Ok(result.map_err(|err| err.to_string()))
+    }
+
+    async fn release(&mut self, blob_id: String) -> anyhow::Result<Result<(), String>> {
+        let _permit = self.begin_async_host_function().await?;
+        record_host_function_call("golem::worker_blobs", "release");
+        let owned_worker_id = self.owned_worker_id.clone();
+        let result = Durability::<Ctx, String, (), SerializableError>::wrap(
+            self,
+            WrappedFunctionType::WriteRemote,
+            "golem worker_blobs::release",
+            blob_id.clone(),
+            |ctx| {
+                ctx.state
+                    .blob_store_service
+                    .release_worker_blob(owned_worker_id, blob_id)
+            },
+        )
+        .await;
+        Ok(result.map_err(|err| err.to_string()))
+    }
+}
+
+#[async_trait]
+impl<Ctx: WorkerCtx> Host for &mut DurableWorkerCtx<Ctx> {
+    async fn put(&mut self, data: Vec<u8>) -> anyhow::Result<Result<String, String>> {
+        (*self).put(data).await
+    }
+
+    async fn get(&mut self, blob_id: String) -> anyhow::Result<Result<Option<Vec<u8>>, String>> {
+        (*self).get(blob_id).await
+    }
+
+    async fn release(&mut self, blob_id: String) -> anyhow::Result<Result<(), String>> {
+        (*self).release(blob_id).await
+    }
+}
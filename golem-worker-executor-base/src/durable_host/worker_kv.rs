@@ -0,0 +1,120 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use golem_common::model::oplog::WrappedFunctionType;
+
+use crate::durable_host::serialized::SerializableError;
+use crate::durable_host::{Durability, DurableWorkerCtx};
+use crate::metrics::wasm::record_host_function_call;
+use crate::preview2::worker_kv::golem::worker_kv::store::Host;
+use crate::workerctx::WorkerCtx;
+
+#[async_trait]
+impl<Ctx: WorkerCtx> Host for DurableWorkerCtx<Ctx> {
+    async fn get(&mut self, key: String) -> anyhow::Result<Result<Option<Vec<u8>>, String>> {
+        let _permit = self.begin_async_host_function().await?;
+        record_host_function_call("golem::worker_kv", "get");
+        let owned_worker_id = self.owned_worker_id.clone();
+        let result = Durability::<Ctx, String, Option<Vec<u8>>, SerializableError>::wrap(
+            self,
+            WrappedFunctionType::ReadRemote,
+            "golem worker_kv::get",
+            key.clone(),
+            |ctx| {
+                ctx.state
+                    .key_value_service
+                    .get_worker_value(owned_worker_id, key)
+            },
+        )
+        .await;
+        Ok(result.map_err(|err| err.to_string()))
+    }
+
+    async fn set(&mut self, key: String, value: Vec<u8>) -> anyhow::Result<Result<(), String>> {
+        let _permit = self.begin_async_host_function().await?;
+        record_host_function_call("golem::worker_kv", "set");
+        let owned_worker_id = self.owned_worker_id.clone();
+        let result = Durability::<Ctx, (String, u64), (), SerializableError>::wrap(
+            self,
+            WrappedFunctionType::WriteRemote,
+            "golem worker_kv::set",
+            (key.clone(), value.len() as u64),
+            |ctx| {
+                ctx.state
+                    .key_value_service
+                    .set_worker_value(owned_worker_id, key, value)
+            },
+        )
+        .await;
+        Ok(result.map_err(|err| err.to_string()))
+    }
+
+    async fn delete(&mut self, key: String) -> anyhow::Result<Result<(), String>> {
+        let _permit = self.begin_async_host_function().await?;
+        record_host_function_call("golem::worker_kv", "delete");
+        let owned_worker_id = self.owned_worker_id.clone();
+        let result = Durability::<Ctx, String, (), SerializableError>::wrap(
+            self,
+            WrappedFunctionType::WriteRemote,
+            "golem worker_kv::delete",
+            key.clone(),
+            |ctx| {
+                ctx.state
+                    .key_value_service
+                    .delete_worker_value(owned_worker_id, key)
+            },
+        )
+        .await;
+        Ok(result.map_err(|err| err.to_string()))
+    }
+
+    async fn list(&mut self, prefix: String) -> anyhow::Result<Result<Vec<String>, String>> {
+        let _permit = self.begin_async_host_function().await?;
+        record_host_function_call("golem::worker_kv", "list");
+        let owned_worker_id = self.owned_worker_id.clone();
+        let result = Durability::<Ctx, String, Vec<String>, SerializableError>::wrap(
+            self,
+            WrappedFunctionType::ReadRemote,
+            "golem worker_kv::list",
+            prefix.clone(),
+            |ctx| {
+                ctx.state
+                    .key_value_service
+                    .get_worker_keys(owned_worker_id, prefix)
+            },
+        )
+        .await;
+        Ok(result.map_err(|err| err.to_string()))
+    }
+}
+
+#[async_trait]
+impl<Ctx: WorkerCtx> Host for &mut DurableWorkerCtx<Ctx> {
+    async fn get(&mut self, key: String) -> anyhow::Result<Result<Option<Vec<u8>>, String>> {
+        (*self).get(key).await
+    }
+
+    async fn set(&mut self, key: String, value: Vec<u8>) -> anyhow::Result<Result<(), String>> {
+        (*self).set(key, value).await
+    }
+
+    async fn delete(&mut self, key: String) -> anyhow::Result<Result<(), String>> {
+        (*self).delete(key).await
+    }
+
+    async fn list(&mut self, prefix: String) -> anyhow::Result<Result<Vec<String>, String>> {
+        (*self).list(prefix).await
+    }
+}
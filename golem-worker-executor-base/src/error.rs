@@ -15,11 +15,12 @@
 use std::collections::HashSet;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
+use std::time::Duration;
 
 use bincode::{Decode, Encode};
 use golem_api_grpc::proto::golem;
 use golem_common::metrics::api::TraceErrorKind;
-use golem_common::model::{ComponentId, PromiseId, ShardId, WorkerId};
+use golem_common::model::{ComponentId, IdempotencyKey, PromiseId, ShardId, WorkerId};
 use golem_wasm_rpc::wasmtime::EncodingError;
 use tonic::Status;
 
@@ -97,6 +98,21 @@ pub enum GolemError {
         details: String,
     },
     ShardingNotReady,
+    TooManyPendingInvocations {
+        worker_id: WorkerId,
+        pending_invocations: u64,
+    },
+    InvocationTimedOut {
+        worker_id: WorkerId,
+        timeout_millis: u64,
+    },
+    ExecutorBusy {
+        max_active_workers: u64,
+    },
+    InvocationNotFound {
+        worker_id: WorkerId,
+        idempotency_key: IdempotencyKey,
+    },
 }
 
 impl GolemError {
@@ -165,6 +181,33 @@ impl GolemError {
             details: details.into(),
         }
     }
+
+    pub fn too_many_pending_invocations(worker_id: WorkerId, pending_invocations: u64) -> Self {
+        GolemError::TooManyPendingInvocations {
+            worker_id,
+            pending_invocations,
+        }
+    }
+
+    pub fn invocation_timed_out(worker_id: WorkerId, timeout: Duration) -> Self {
+        GolemError::InvocationTimedOut {
+            worker_id,
+            timeout_millis: timeout.as_millis() as u64,
+        }
+    }
+
+    pub fn executor_busy(max_active_workers: usize) -> Self {
+        GolemError::ExecutorBusy {
+            max_active_workers: max_active_workers as u64,
+        }
+    }
+
+    pub fn invocation_not_found(worker_id: WorkerId, idempotency_key: IdempotencyKey) -> Self {
+        GolemError::InvocationNotFound {
+            worker_id,
+            idempotency_key,
+        }
+    }
 }
 
 impl Display for GolemError {
@@ -262,6 +305,39 @@ impl Display for GolemError {
             GolemError::ShardingNotReady => {
                 write!(f, "Sharding not ready")
             }
+            GolemError::TooManyPendingInvocations {
+                worker_id,
+                pending_invocations,
+            } => {
+                write!(
+                    f,
+                    "Too many pending invocations for worker {worker_id}: {pending_invocations}"
+                )
+            }
+            GolemError::InvocationTimedOut {
+                worker_id,
+                timeout_millis,
+            } => {
+                write!(
+                    f,
+                    "Invocation of worker {worker_id} timed out after {timeout_millis} ms"
+                )
+            }
+            GolemError::ExecutorBusy { max_active_workers } => {
+                write!(
+                    f,
+                    "Worker executor is busy, already running {max_active_workers} active workers, retry later"
+                )
+            }
+            GolemError::InvocationNotFound {
+                worker_id,
+                idempotency_key,
+            } => {
+                write!(
+                    f,
+                    "No invocation with idempotency key {idempotency_key} was found for worker {worker_id}"
+                )
+            }
         }
     }
 }
@@ -294,6 +370,10 @@ impl Error for GolemError {
             GolemError::PreviousInvocationExited => "The previously invoked function exited",
             GolemError::Unknown { .. } => "Unknown error",
             GolemError::ShardingNotReady => "Sharding not ready",
+            GolemError::TooManyPendingInvocations { .. } => "Too many pending invocations",
+            GolemError::InvocationTimedOut { .. } => "Invocation timed out",
+            GolemError::ExecutorBusy { .. } => "Worker executor is busy",
+            GolemError::InvocationNotFound { .. } => "Invocation not found",
         }
     }
 }
@@ -326,6 +406,10 @@ impl TraceErrorKind for GolemError {
             GolemError::PreviousInvocationExited => "PreviousInvocationExited",
             GolemError::Unknown { .. } => "Unknown",
             GolemError::ShardingNotReady => "ShardingNotReady",
+            GolemError::TooManyPendingInvocations { .. } => "TooManyPendingInvocations",
+            GolemError::InvocationTimedOut { .. } => "InvocationTimedOut",
+            GolemError::ExecutorBusy { .. } => "ExecutorBusy",
+            GolemError::InvocationNotFound { .. } => "InvocationNotFound",
         }
     }
 }
@@ -367,6 +451,12 @@ impl From<GolemError> for Status {
                 Status::invalid_argument(format!("Value mismatch: {details}"))
             }
             GolemError::Unknown { details } => Status::unknown(details),
+            GolemError::TooManyPendingInvocations { .. } => {
+                Status::resource_exhausted(format!("{value}"))
+            }
+            GolemError::InvocationTimedOut { .. } => Status::deadline_exceeded(format!("{value}")),
+            GolemError::ExecutorBusy { .. } => Status::resource_exhausted(format!("{value}")),
+            GolemError::InvocationNotFound { .. } => Status::not_found(format!("{value}")),
             _ => Status::internal(format!("{value}")),
         }
     }
@@ -587,6 +677,52 @@ impl From<GolemError> for golem::worker::v1::WorkerExecutionError {
                     ),
                 ),
             },
+            GolemError::TooManyPendingInvocations {
+                worker_id,
+                pending_invocations,
+            } => golem::worker::v1::WorkerExecutionError {
+                error: Some(
+                    golem::worker::v1::worker_execution_error::Error::TooManyPendingInvocations(
+                        golem::worker::v1::TooManyPendingInvocations {
+                            worker_id: Some(worker_id.into()),
+                            pending_invocations,
+                        },
+                    ),
+                ),
+            },
+            GolemError::InvocationTimedOut {
+                worker_id,
+                timeout_millis,
+            } => golem::worker::v1::WorkerExecutionError {
+                error: Some(
+                    golem::worker::v1::worker_execution_error::Error::InvocationTimedOut(
+                        golem::worker::v1::InvocationTimedOut {
+                            worker_id: Some(worker_id.into()),
+                            timeout_millis,
+                        },
+                    ),
+                ),
+            },
+            GolemError::ExecutorBusy { max_active_workers } => {
+                golem::worker::v1::WorkerExecutionError {
+                    error: Some(golem::worker::v1::worker_execution_error::Error::ExecutorBusy(
+                        golem::worker::v1::ExecutorBusy { max_active_workers },
+                    )),
+                }
+            }
+            GolemError::InvocationNotFound {
+                worker_id,
+                idempotency_key,
+            } => golem::worker::v1::WorkerExecutionError {
+                error: Some(
+                    golem::worker::v1::worker_execution_error::Error::InvocationNotFound(
+                        golem::worker::v1::InvocationNotFound {
+                            worker_id: Some(worker_id.into()),
+                            idempotency_key: Some(idempotency_key.into()),
+                        },
+                    ),
+                ),
+            },
         }
     }
 }
@@ -755,6 +891,41 @@ impl TryFrom<golem::worker::v1::WorkerExecutionError> for GolemError {
             Some(golem::worker::v1::worker_execution_error::Error::ShardingNotReady(_)) => {
                 Ok(GolemError::ShardingNotReady)
             }
+            Some(golem::worker::v1::worker_execution_error::Error::TooManyPendingInvocations(
+                too_many_pending_invocations,
+            )) => Ok(GolemError::TooManyPendingInvocations {
+                worker_id: too_many_pending_invocations
+                    .worker_id
+                    .ok_or("Missing worker_id")?
+                    .try_into()?,
+                pending_invocations: too_many_pending_invocations.pending_invocations,
+            }),
+            Some(golem::worker::v1::worker_execution_error::Error::InvocationTimedOut(
+                invocation_timed_out,
+            )) => Ok(GolemError::InvocationTimedOut {
+                worker_id: invocation_timed_out
+                    .worker_id
+                    .ok_or("Missing worker_id")?
+                    .try_into()?,
+                timeout_millis: invocation_timed_out.timeout_millis,
+            }),
+            Some(golem::worker::v1::worker_execution_error::Error::ExecutorBusy(executor_busy)) => {
+                Ok(GolemError::ExecutorBusy {
+                    max_active_workers: executor_busy.max_active_workers,
+                })
+            }
+            Some(golem::worker::v1::worker_execution_error::Error::InvocationNotFound(
+                invocation_not_found,
+            )) => Ok(GolemError::InvocationNotFound {
+                worker_id: invocation_not_found
+                    .worker_id
+                    .ok_or("Missing worker_id")?
+                    .try_into()?,
+                idempotency_key: invocation_not_found
+                    .idempotency_key
+                    .ok_or("Missing idempotency_key")?
+                    .into(),
+            }),
         }
     }
 }
@@ -781,3 +952,35 @@ impl Display for WorkerOutOfMemory {
 }
 
 impl Error for WorkerOutOfMemory {}
+
+/// The worker tried to grow its linear memory over the limit set for its component or account
+#[derive(Debug, Clone, PartialOrd, PartialEq, Eq, Hash)]
+pub struct WorkerMemoryLimitExceeded {
+    pub limit: u64,
+}
+
+impl Display for WorkerMemoryLimitExceeded {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Worker exceeded its linear memory limit of {} bytes",
+            self.limit
+        )
+    }
+}
+
+impl Error for WorkerMemoryLimitExceeded {}
+
+/// An exported function call consumed more fuel than the budget set for its component
+#[derive(Debug, Clone, PartialOrd, PartialEq, Eq, Hash)]
+pub struct InvocationFuelBudgetExceeded {
+    pub budget: u64,
+}
+
+impl Display for InvocationFuelBudgetExceeded {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invocation exceeded its fuel budget of {}", self.budget)
+    }
+}
+
+impl Error for InvocationFuelBudgetExceeded {}
@@ -12,59 +12,105 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use futures_util::Stream;
+use futures::future::join_all;
+use futures_util::{FutureExt, Stream};
 use gethostname::gethostname;
 use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
 use golem_wasm_rpc::protobuf::Val;
 use std::cmp::min;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::{Debug, Display, Formatter};
 use std::marker::PhantomData;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
 use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc;
 use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_stream::wrappers::ReceiverStream;
 use tonic::{Request, Response, Status};
 use tracing::{debug, error, info, warn, Instrument};
 use uuid::Uuid;
 use wasmtime::Error;
 
+use crate::durable_host::recorded_result_chunks;
 use crate::error::*;
 use golem_api_grpc::proto::golem;
 use golem_api_grpc::proto::golem::common::ResourceLimits as GrpcResourceLimits;
 use golem_api_grpc::proto::golem::worker::{Cursor, ResourceMetadata, UpdateMode};
 use golem_api_grpc::proto::golem::workerexecutor::v1::worker_executor_server::WorkerExecutor;
 use golem_api_grpc::proto::golem::workerexecutor::v1::{
-    ConnectWorkerRequest, DeleteWorkerRequest, GetOplogRequest, GetOplogResponse,
-    GetRunningWorkersMetadataRequest, GetRunningWorkersMetadataResponse, GetWorkersMetadataRequest,
-    GetWorkersMetadataResponse, InvokeAndAwaitWorkerRequest, InvokeAndAwaitWorkerResponseTyped,
-    InvokeAndAwaitWorkerSuccess, UpdateWorkerRequest, UpdateWorkerResponse,
+    AccountUsageRecords, CancelScheduledActionsRequest, CancelScheduledActionsResponse,
+    ConnectWorkerRequest, DeleteOplogRegionRequest, DeleteOplogRegionResponse,
+    DeleteOplogRegionSuccess, DeleteSecretRequest, DeleteSecretResponse, DeleteSharedValueRequest,
+    DeleteSharedValueResponse, DeleteWorkerRequest, DrainPhase, DrainProgress, DrainRequest,
+    DrainResponse, DryRunUpdateWorkerRequest, DryRunUpdateWorkerResponse, ForkWorkerRequest,
+    ForkWorkerResponse, ForkWorkerSuccess, GetAccountUsageRequest, GetAccountUsageResponse,
+    GetComponentWorkerStatisticsRequest, GetComponentWorkerStatisticsResponse, GetOplogRequest,
+    GetOplogResponse, GetPendingInvocationCountRequest, GetPendingInvocationCountResponse,
+    GetRunningWorkersMetadataRequest, GetRunningWorkersMetadataResponse, GetSharedValueRequest,
+    GetSharedValueResponse, GetSharedValueSuccessResponse, GetTopicRequest, GetTopicResponse,
+    GetTopicSuccessResponse, GetWorkerFuelConsumptionRequest, GetWorkerFuelConsumptionResponse,
+    GetWorkerReplayDivergencesRequest, GetWorkerReplayDivergencesResponse, GetWorkerSagasRequest,
+    GetWorkerSagasResponse, GetWorkerStatusRequest, GetWorkerStatusResponse,
+    GetWorkersMetadataRequest, GetWorkersMetadataResponse, InvokeAndAwaitWorkerRequest,
+    InvokeAndAwaitWorkerResponseTyped, InvokeAndAwaitWorkerSuccess, ListPromisesRequest,
+    ListPromisesResponse, ListScheduledActionsRequest, ListScheduledActionsResponse,
+    ListSecretsRequest, ListSecretsResponse, ListSharedKeysRequest, ListSharedKeysResponse,
+    ListTopicsRequest, ListTopicsResponse, OutstandingPromise, OutstandingPromises,
+    PendingScheduledAction, PendingScheduledActions, PutSecretRequest, PutSecretResponse,
+    QueryWorkerAtOplogIndexRequest, QueryWorkerAtOplogIndexResponse,
+    QueryWorkerAtOplogIndexSuccess, RedecodeCorruptedOplogEntriesRequest,
+    RedecodeCorruptedOplogEntriesResponse, ReviveWorkerRequest, ReviveWorkerResponse,
+    ReviveWorkerSuccess, SagaCompensation, ScheduledActionCursor, ScheduledActionFilter,
+    ScheduledActionKind, SecretNames, SetSharedValueRequest, SetSharedValueResponse,
+    SetSharedValueSuccessResponse, SharedKeys, SharedValue, Topic, TopicNames, TopicSubscription,
+    UpdateComponentObservabilityRequest, UpdateComponentObservabilityResponse, UpdateDryRunResult,
+    UpdateWorkerEnvironmentRequest, UpdateWorkerEnvironmentResponse,
+    UpdateWorkerEnvironmentSuccess, UpdateWorkerRequest, UpdateWorkerResponse,
+    UpdateWorkerVirtualClockRequest, UpdateWorkerVirtualClockResponse, UsageRecord,
+    WorkerReplayDivergences, WorkerSaga, WorkerSagaState, WorkerSagas, WorkerVirtualClock,
 };
 use golem_common::grpc::{
     proto_account_id_string, proto_component_id_string, proto_idempotency_key_string,
     proto_promise_id_string, proto_target_worker_id_string, proto_worker_id_string,
 };
 use golem_common::metrics::api::record_new_grpc_api_active_stream;
-use golem_common::model::oplog::{OplogIndex, UpdateDescription};
+use golem_common::model::oplog::{OplogEntry, OplogIndex, UpdateDescription};
+use golem_common::model::public_worker_status::PublicWorkerStatusRecord;
+use golem_common::model::regions::OplogRegion;
+use golem_common::model::worker_statistics::ComponentWorkerStatistics;
 use golem_common::model::{
-    AccountId, ComponentId, ComponentType, IdempotencyKey, OwnedWorkerId, ScanCursor, ShardId,
-    TargetWorkerId, TimestampedWorkerInvocation, WorkerEvent, WorkerFilter, WorkerId,
-    WorkerInvocation, WorkerMetadata, WorkerStatus, WorkerStatusRecord,
+    AccountId, ComponentId, ComponentType, IdempotencyKey, InvocationPriority, OwnedWorkerId,
+    ScanCursor, ScheduledAction, ShardId, TargetWorkerId, Timestamp, TimestampedWorkerInvocation,
+    WorkerEvent, WorkerFilter, WorkerId, WorkerInvocation, WorkerMetadata, WorkerName,
+    WorkerStatus, WorkerStatusRecord,
 };
+use golem_common::serialization::try_deserialize_with_any_version;
 use golem_common::{model as common_model, recorded_grpc_api_request};
 
-use crate::model::public_oplog::{find_component_version_at, get_public_oplog_chunk};
-use crate::model::{InterruptKind, LastError};
+use crate::model::public_oplog::{
+    find_component_version_at, find_last_invocation_starts, get_public_oplog_chunk,
+};
+use crate::model::{InterruptKind, LastError, ReplayDivergenceReport, WorkerFuelConsumption};
 use crate::services::events::Event;
+use crate::services::golem_config::{WorkerStatusCacheConfig, WorkerStatusReadConsistency};
+use crate::services::oplog::CommitLevel;
+use crate::services::promise_gc;
+use crate::services::saga::{restore_sagas_from_oplog, Saga, SagaState};
+use crate::services::scheduler;
 use crate::services::worker_activator::{DefaultWorkerActivator, LazyWorkerActivator};
-use crate::services::worker_event::WorkerEventReceiver;
+use crate::services::worker_event::{read_log_events_from_oplog, WorkerEventReceiver};
 use crate::services::{
-    All, HasActiveWorkers, HasAll, HasComponentService, HasEvents, HasOplogService,
-    HasPromiseService, HasRunningWorkerEnumerationService, HasShardManagerService, HasShardService,
-    HasWorkerEnumerationService, HasWorkerService, UsesAllDeps,
+    All, HasActiveWorkers, HasAll, HasBlobStoreService, HasComponentService, HasConfig, HasEvents,
+    HasKeyValueService, HasOplog, HasOplogService, HasPromiseService,
+    HasRunningWorkerEnumerationService, HasSagaService, HasSchedulerService, HasSecretService,
+    HasShardManagerService, HasShardService, HasTopicService, HasUsageService,
+    HasVirtualClockService, HasWorkerEnumerationService, HasWorkerService, UsesAllDeps,
 };
-use crate::worker::Worker;
+use crate::time_travel;
+use crate::worker::{calculate_last_known_status, Worker};
 use crate::workerctx::WorkerCtx;
 
 pub enum GrpcError<E> {
@@ -132,6 +178,9 @@ pub struct WorkerExecutorImpl<
 > {
     /// Reference to all the initialized services
     services: Svcs,
+    /// Host and port this executor registered with the shard manager
+    host: String,
+    port: u16,
     ctx: PhantomData<Ctx>,
 }
 
@@ -141,6 +190,8 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
     fn clone(&self) -> Self {
         Self {
             services: self.services.clone(),
+            host: self.host.clone(),
+            port: self.port,
             ctx: PhantomData,
         }
     }
@@ -148,6 +199,18 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
 
 type ResponseResult<T> = Result<Response<T>, Status>;
 type ResponseStream = WorkerEventStream;
+type DrainResponseStream = ReceiverStream<Result<DrainResponse, Status>>;
+type InvokeAndStreamWorkerResponseStream =
+    ReceiverStream<Result<golem::workerexecutor::v1::InvokeAndStreamWorkerResponse, Status>>;
+
+/// How often the progress of draining the executor is checked and reported
+const DRAIN_PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Number of workers enumerated at once while computing the statistics of a component's workers
+const WORKER_STATISTICS_SCAN_PAGE_SIZE: u64 = 1000;
+
+/// Longest time a poll request can wait for the result of an invocation
+const MAX_POLL_DEADLINE: Duration = Duration::from_secs(300);
 
 impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync + 'static>
     WorkerExecutorImpl<Ctx, Svcs>
@@ -157,15 +220,17 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
         lazy_worker_activator: Arc<LazyWorkerActivator>,
         port: u16,
     ) -> Result<Self, Error> {
+        let host = gethostname().to_string_lossy().to_string();
+
         let worker_executor = WorkerExecutorImpl {
             services: services.clone(),
+            host: host.clone(),
+            port,
             ctx: PhantomData,
         };
         let worker_activator = Arc::new(DefaultWorkerActivator::new(services));
         lazy_worker_activator.set(worker_activator);
 
-        let host = gethostname().to_string_lossy().to_string();
-
         info!(host, port, "Registering worker executor");
 
         let shard_assignment = worker_executor
@@ -230,6 +295,30 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
         self.shard_service().check_worker(worker_id)
     }
 
+    /// Rebuilds the sagas of the worker from its oplog after its history got changed, so they
+    /// do not keep the effects of the skipped saga host function calls
+    async fn restore_sagas(&self, owned_worker_id: &OwnedWorkerId) {
+        let metadata = self.worker_service().get(owned_worker_id).await;
+        let result = match calculate_last_known_status(self, owned_worker_id, &metadata).await {
+            Ok(status) => {
+                restore_sagas_from_oplog(
+                    &self.oplog_service(),
+                    &self.saga_service(),
+                    owned_worker_id,
+                    &status.deleted_regions,
+                )
+                .await
+            }
+            Err(err) => Err(err),
+        };
+        if let Err(err) = result {
+            warn!(
+                worker_id = owned_worker_id.worker_id.to_string(),
+                "Failed to restore the sagas of the worker from its oplog: {err}"
+            );
+        }
+    }
+
     async fn create_worker_internal(
         &self,
         request: golem::workerexecutor::v1::CreateWorkerRequest,
@@ -403,6 +492,36 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
         }
 
         Ctx::on_worker_deleted(self, &worker_id).await?;
+        if let Err(err) = self
+            .key_value_service()
+            .delete_worker_values(owned_worker_id.clone())
+            .await
+        {
+            warn!("Failed to delete the key-value store of worker {worker_id}: {err}");
+        }
+        if let Err(err) = self
+            .blob_store_service()
+            .release_all_worker_blobs(owned_worker_id.clone())
+            .await
+        {
+            warn!("Failed to release the blobs of worker {worker_id}: {err}");
+        }
+        if let Err(err) = self
+            .topic_service()
+            .unsubscribe_all(&owned_worker_id.account_id, &worker_id)
+            .await
+        {
+            warn!("Failed to remove the topic subscriptions of worker {worker_id}: {err}");
+        }
+        if let Err(err) = self.saga_service().remove_all(&owned_worker_id).await {
+            warn!("Failed to remove the sagas of worker {worker_id}: {err}");
+        }
+        if let Err(err) = self.virtual_clock_service().reset(&worker_id).await {
+            warn!("Failed to remove the virtual clock of worker {worker_id}: {err}");
+        }
+        if let Err(err) = promise_gc::collect_deleted_worker_promises(self, &worker_id).await {
+            warn!("Failed to remove the promises of worker {worker_id}: {err}");
+        }
         self.worker_service().remove(&owned_worker_id).await;
         self.active_workers().remove(&worker_id);
 
@@ -593,12 +712,172 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
             .map_err(|msg| GolemError::ValueMismatch { details: msg })?;
 
         let values = worker
-            .invoke_and_await(idempotency_key, full_function_name, function_input)
+            .invoke_and_await(
+                idempotency_key,
+                full_function_name,
+                function_input,
+                request.invocation_priority(),
+                request.invocation_context(),
+                request.timeout(),
+            )
             .await?;
 
         Ok(values)
     }
 
+    /// Invokes the worker like `invoke_and_await_worker_internal_typed`, sending the chunks of the
+    /// input streams returned by the function as they are read, before the result
+    async fn invoke_and_stream_worker_internal(
+        &self,
+        mut request: InvokeAndAwaitWorkerRequest,
+        sender: &mpsc::Sender<
+            Result<golem::workerexecutor::v1::InvokeAndStreamWorkerResponse, Status>,
+        >,
+    ) -> Result<TypeAnnotatedValue, GolemError> {
+        // The chunks are matched to the invocation by its idempotency key
+        let idempotency_key = request
+            .idempotency_key()?
+            .unwrap_or(IdempotencyKey::fresh());
+        request.idempotency_key = Some(idempotency_key.clone().into());
+
+        // Subscribing before the invocation starts, so none of the chunks are missed
+        let worker = self.get_or_create(&request).await?;
+        let mut events = worker.event_service().receiver();
+
+        let invocation = self.invoke_and_await_worker_internal_typed(&request);
+        tokio::pin!(invocation);
+        let mut sent_chunks = 0;
+        let mut missed_chunks = false;
+        let result = loop {
+            tokio::select! {
+                biased;
+                event = events.recv(), if !missed_chunks => {
+                    match received_result_chunk(event, &idempotency_key) {
+                        ReceivedResultChunk::Chunk(bytes) => {
+                            send_result_chunk(bytes, sender).await;
+                            sent_chunks += 1;
+                        }
+                        ReceivedResultChunk::Other => {}
+                        ReceivedResultChunk::Missed => missed_chunks = true,
+                    }
+                }
+                result = &mut invocation => break result?,
+            }
+        };
+
+        // Chunks emitted right before the invocation finished may not have been received yet
+        while !missed_chunks {
+            let Some(event) = events.recv().now_or_never() else {
+                break;
+            };
+            match received_result_chunk(event, &idempotency_key) {
+                ReceivedResultChunk::Chunk(bytes) => {
+                    send_result_chunk(bytes, sender).await;
+                    sent_chunks += 1;
+                }
+                ReceivedResultChunk::Other => {}
+                ReceivedResultChunk::Missed => missed_chunks = true,
+            }
+        }
+
+        // The receiver lagged behind or got closed, so the chunks not sent yet are read back
+        // from the oplog instead of failing the successful invocation
+        if missed_chunks {
+            warn!("Resyncing the result stream of {idempotency_key} from the oplog");
+            let completed_at =
+                worker
+                    .invocation_result_index(&idempotency_key)
+                    .ok_or(GolemError::unknown(format!(
+                        "The result of invocation {idempotency_key} is missing"
+                    )))?;
+            let chunks =
+                recorded_result_chunks(&worker.oplog(), &idempotency_key, completed_at).await?;
+            for bytes in chunks.into_iter().skip(sent_chunks) {
+                send_result_chunk(bytes, sender).await;
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Invokes the worker waiting for the result until the request's deadline. Returns the
+    /// idempotency key of the invocation and its result if it finished in time.
+    async fn invoke_and_poll_worker_internal(
+        &self,
+        request: golem::workerexecutor::v1::InvokeAndPollWorkerRequest,
+    ) -> Result<(IdempotencyKey, Option<TypeAnnotatedValue>), GolemError> {
+        let deadline = poll_deadline(request.deadline_millis)?;
+        let mut invocation = request
+            .invocation
+            .ok_or(GolemError::invalid_request("invocation not found"))?;
+
+        // The idempotency key is the token for awaiting the result later
+        let idempotency_key = invocation
+            .idempotency_key()?
+            .unwrap_or(IdempotencyKey::fresh());
+        invocation.idempotency_key = Some(idempotency_key.clone().into());
+
+        let worker = self.get_or_create(&invocation).await?;
+
+        let function_input = invocation
+            .input()
+            .iter()
+            .map(|val| val.clone().try_into())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|msg| GolemError::ValueMismatch { details: msg })?;
+
+        let output = worker
+            .invoke_and_poll(
+                idempotency_key.clone(),
+                invocation.name(),
+                function_input,
+                invocation.invocation_priority(),
+                invocation.invocation_context(),
+                deadline,
+            )
+            .await?;
+
+        Ok((idempotency_key, output))
+    }
+
+    async fn await_worker_invocation_internal(
+        &self,
+        request: golem::workerexecutor::v1::AwaitWorkerInvocationRequest,
+    ) -> Result<(IdempotencyKey, Option<TypeAnnotatedValue>), GolemError> {
+        let deadline = poll_deadline(request.deadline_millis)?;
+        let worker_id = request
+            .worker_id
+            .ok_or(GolemError::invalid_request("worker_id not found"))?;
+        let worker_id: WorkerId = worker_id.try_into().map_err(GolemError::invalid_request)?;
+
+        let account_id = request
+            .account_id
+            .ok_or(GolemError::invalid_request("account_id not found"))?;
+        let account_id: AccountId = account_id.into();
+
+        let idempotency_key: IdempotencyKey = request
+            .idempotency_key
+            .ok_or(GolemError::invalid_request("idempotency_key not found"))?
+            .into();
+
+        let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+
+        self.ensure_worker_belongs_to_this_executor(&worker_id)?;
+
+        if self.worker_service().get(&owned_worker_id).await.is_none() {
+            return Err(GolemError::worker_not_found(worker_id));
+        }
+
+        // The worker has to be running for its pending invocations to make progress
+        let worker =
+            Worker::get_or_create_running(self, &owned_worker_id, None, None, None, None).await?;
+        let output = worker
+            .await_invocation_result(&idempotency_key, deadline)
+            .await?;
+
+        Ok((idempotency_key, output))
+    }
+
     async fn get_or_create<Req: GrpcInvokeRequest>(
         &self,
         request: &Req,
@@ -620,6 +899,7 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
         let worker_id = target_worker_id.into_worker_id(
             &current_assignment.shard_ids,
             current_assignment.number_of_shards,
+            None,
         );
 
         if unspecified_name {
@@ -673,7 +953,13 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
             .map_err(|msg| GolemError::ValueMismatch { details: msg })?;
 
         worker
-            .invoke(idempotency_key, full_function_name, function_input)
+            .invoke(
+                idempotency_key,
+                full_function_name,
+                function_input,
+                request.invocation_priority(),
+                request.invocation_context(),
+            )
             .await?;
 
         Ok(())
@@ -699,6 +985,7 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
                 }
             }
         }
+        self.worker_service().on_shards_revoked().await;
 
         Ok(())
     }
@@ -711,6 +998,10 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
 
         let shard_ids = proto_shard_ids.into_iter().map(ShardId::from).collect();
 
+        if self.shard_service().is_draining() {
+            return Err(GolemError::ShardingNotReady);
+        }
+
         self.shard_service().assign_shards(&shard_ids)?;
         Ctx::on_shard_assignment_changed(self).await?;
 
@@ -736,9 +1027,13 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
 
         let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
 
+        let consistency = match &self.config().worker_status_cache {
+            WorkerStatusCacheConfig::Disabled => WorkerStatusReadConsistency::Strong,
+            WorkerStatusCacheConfig::Enabled(config) => config.metadata_api_reads,
+        };
         let metadata = self
             .worker_service()
-            .get(&owned_worker_id)
+            .get_with_consistency(&owned_worker_id, consistency)
             .await
             .ok_or(GolemError::worker_not_found(worker_id.clone()))?;
 
@@ -755,6 +1050,38 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
         ))
     }
 
+    /// Gets the metadata of the requested workers of this executor concurrently. A failure only
+    /// affects its own worker, and workers of other executors fail with `InvalidShardId`.
+    async fn get_workers_metadata_bulk_internal(
+        &self,
+        request: golem::workerexecutor::v1::GetWorkersMetadataBulkRequest,
+    ) -> Result<Vec<Result<golem::worker::WorkerMetadata, GolemError>>, GolemError> {
+        let account_id = request
+            .account_id
+            .ok_or(GolemError::invalid_request("account_id not found"))?;
+
+        let results = join_all(request.worker_ids.into_iter().map(|worker_id| {
+            let account_id = account_id.clone();
+            async move {
+                let typed_worker_id: WorkerId = worker_id
+                    .clone()
+                    .try_into()
+                    .map_err(GolemError::invalid_request)?;
+                self.ensure_worker_belongs_to_this_executor(&typed_worker_id)?;
+                self.get_worker_metadata_internal(
+                    golem::workerexecutor::v1::GetWorkerMetadataRequest {
+                        worker_id: Some(worker_id),
+                        account_id: Some(account_id),
+                    },
+                )
+                .await
+            }
+        }))
+        .await;
+
+        Ok(results)
+    }
+
     async fn get_running_workers_metadata_internal(
         &self,
         request: GetRunningWorkersMetadataRequest,
@@ -841,6 +1168,53 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
         ))
     }
 
+    async fn get_component_worker_statistics_internal(
+        &self,
+        request: GetComponentWorkerStatisticsRequest,
+    ) -> Result<ComponentWorkerStatistics, GolemError> {
+        let component_id: ComponentId = request
+            .component_id
+            .and_then(|t| t.try_into().ok())
+            .ok_or(GolemError::invalid_request("Invalid component id"))?;
+
+        let account_id: AccountId = request
+            .account_id
+            .map(|t| t.into())
+            .ok_or(GolemError::invalid_request("Invalid account id"))?;
+
+        // Only the workers of this executor's shards are counted, so the statistics of all
+        // the executors can be summed without counting a worker twice
+        let mut statistics = ComponentWorkerStatistics::new(component_id.clone());
+        let mut cursor = Some(ScanCursor::default());
+        while let Some(current_cursor) = cursor {
+            let (next_cursor, workers) = self
+                .worker_enumeration_service()
+                .get_owned(
+                    &account_id,
+                    &component_id,
+                    None,
+                    current_cursor,
+                    WORKER_STATISTICS_SCAN_PAGE_SIZE,
+                    false,
+                )
+                .await?;
+
+            for worker in workers {
+                let status = if request.precise {
+                    let owned_worker_id = worker.owned_worker_id();
+                    calculate_last_known_status(self, &owned_worker_id, &Some(worker)).await?
+                } else {
+                    worker.last_known_status
+                };
+                statistics.add_worker(&status);
+            }
+
+            cursor = next_cursor;
+        }
+
+        Ok(statistics)
+    }
+
     async fn update_worker_internal(&self, request: UpdateWorkerRequest) -> Result<(), GolemError> {
         let worker_id = request
             .worker_id
@@ -989,90 +1363,604 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
         Ok(())
     }
 
-    async fn connect_worker_internal(
+    /// Checks whether a manual update of the worker would succeed, without changing the worker.
+    /// Returns the reason of the failure if it would not.
+    async fn dry_run_update_worker_internal(
         &self,
-        request: ConnectWorkerRequest,
-    ) -> ResponseResult<<Self as WorkerExecutor>::ConnectWorkerStream> {
-        let worker_id: WorkerId = request
+        request: DryRunUpdateWorkerRequest,
+    ) -> Result<Option<String>, GolemError> {
+        let worker_id = request
             .worker_id
-            .ok_or(GolemError::invalid_request("missing worker_id"))?
-            .try_into()
-            .map_err(GolemError::invalid_request)?;
-        let account_id: AccountId = request
+            .ok_or(GolemError::invalid_request("worker_id not found"))?;
+        let worker_id: WorkerId = worker_id.try_into().map_err(GolemError::invalid_request)?;
+
+        let account_id = request
             .account_id
-            .ok_or(GolemError::invalid_request("missing account_id"))?
-            .into();
+            .ok_or(GolemError::invalid_request("account_id not found"))?;
+        let account_id: AccountId = account_id.into();
         let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
 
-        self.ensure_worker_belongs_to_this_executor(&worker_id)?;
+        let metadata = self
+            .worker_service()
+            .get(&owned_worker_id)
+            .await
+            .ok_or(GolemError::worker_not_found(worker_id.clone()))?;
 
-        let metadata = self.worker_service().get(&owned_worker_id).await;
-        if metadata.is_some() {
-            let worker_status = self
-                .validate_worker_status(&owned_worker_id, &metadata)
-                .await
-                .map_err(|e| {
-                    error!("Failed to connect to worker {worker_id}: {:?}", e);
-                    Status::internal(format!("Error connecting to worker: {e}"))
-                })?;
+        if metadata.last_known_status.component_version == request.target_version {
+            return Err(GolemError::invalid_request(
+                "Worker is already at the target version",
+            ));
+        }
 
-            if worker_status.status != WorkerStatus::Interrupted {
-                let event_service =
-                    Worker::get_or_create_suspended(self, &owned_worker_id, None, None, None, None)
-                        .await?
-                        .event_service();
+        let component_metadata = self
+            .component_service()
+            .get_metadata(
+                &worker_id.component_id,
+                Some(metadata.last_known_status.component_version),
+            )
+            .await?;
+        if component_metadata.component_type == ComponentType::Ephemeral {
+            return Err(GolemError::invalid_request(
+                "Ephemeral workers cannot be updated",
+            ));
+        }
 
-                let receiver = event_service.receiver();
+        // An inactive worker is not loaded, the dry run only needs a detached handle of it
+        let worker = match self.active_workers().try_get(&worker_id) {
+            Some(worker) => worker,
+            None => Arc::new(Worker::new(self, owned_worker_id, None, None, None, None).await?),
+        };
+        Worker::dry_run_update(&worker, request.target_version).await
+    }
 
-                info!("Client connected");
-                record_new_grpc_api_active_stream();
+    async fn update_component_observability_internal(
+        &self,
+        request: UpdateComponentObservabilityRequest,
+    ) -> Result<(), GolemError> {
+        let component_id: ComponentId = request
+            .component_id
+            .and_then(|t| t.try_into().ok())
+            .ok_or(GolemError::invalid_request("Invalid component id"))?;
+        let observability = request
+            .observability
+            .ok_or(GolemError::invalid_request("observability not found"))?;
 
-                Ok(Response::new(WorkerEventStream::new(receiver)))
-            } else {
-                // We don't want 'connect' to resume interrupted workers
-                Err(GolemError::Interrupted {
-                    kind: InterruptKind::Interrupt,
-                }
-                .into())
-            }
-        } else {
-            Err(GolemError::WorkerNotFound { worker_id }.into())
+        // Running workers read the settings on each invocation and log entry,
+        // so overriding them here applies to all of them without a restart
+        self.component_service()
+            .set_observability(&component_id, observability.into());
+
+        Ok(())
+    }
+
+    fn secret_owner(
+        component_id: Option<golem::component::ComponentId>,
+        account_id: Option<golem::common::AccountId>,
+    ) -> Result<(AccountId, ComponentId), GolemError> {
+        let component_id: ComponentId = component_id
+            .and_then(|t| t.try_into().ok())
+            .ok_or(GolemError::invalid_request("Invalid component id"))?;
+        let account_id: AccountId = account_id
+            .ok_or(GolemError::invalid_request("account_id not found"))?
+            .into();
+        Ok((account_id, component_id))
+    }
+
+    async fn put_secret_internal(&self, request: PutSecretRequest) -> Result<(), GolemError> {
+        let (account_id, component_id) =
+            Self::secret_owner(request.component_id, request.account_id)?;
+        if request.name.is_empty() {
+            return Err(GolemError::invalid_request("Secret name must not be empty"));
         }
+        self.secret_service()
+            .put(&account_id, &component_id, &request.name, &request.value)
+            .await
     }
 
-    async fn get_oplog_internal(
-        &self,
-        request: GetOplogRequest,
-    ) -> Result<GetOplogResponse, GolemError> {
-        let worker_id = request
-            .worker_id
-            .ok_or(GolemError::invalid_request("worker_id not found"))?;
-        let worker_id: WorkerId = worker_id.try_into().map_err(GolemError::invalid_request)?;
+    async fn delete_secret_internal(&self, request: DeleteSecretRequest) -> Result<(), GolemError> {
+        let (account_id, component_id) =
+            Self::secret_owner(request.component_id, request.account_id)?;
+        self.secret_service()
+            .delete(&account_id, &component_id, &request.name)
+            .await
+    }
 
-        let account_id = request
-            .account_id
-            .ok_or(GolemError::invalid_request("account_id not found"))?;
-        let account_id: AccountId = account_id.into();
+    async fn list_secrets_internal(
+        &self,
+        request: ListSecretsRequest,
+    ) -> Result<Vec<String>, GolemError> {
+        let (account_id, component_id) =
+            Self::secret_owner(request.component_id, request.account_id)?;
+        self.secret_service()
+            .list_names(&account_id, &component_id)
+            .await
+    }
 
-        let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+    fn shared_kv_owner(
+        account_id: Option<golem::common::AccountId>,
+    ) -> Result<AccountId, GolemError> {
+        Ok(account_id
+            .ok_or(GolemError::invalid_request("account_id not found"))?
+            .into())
+    }
 
-        self.ensure_worker_belongs_to_this_executor(&worker_id)?;
+    async fn get_shared_value_internal(
+        &self,
+        request: GetSharedValueRequest,
+    ) -> Result<Option<SharedValue>, GolemError> {
+        let account_id = Self::shared_kv_owner(request.account_id)?;
+        let entry = self
+            .key_value_service()
+            .get_shared(account_id, request.key)
+            .await
+            .map_err(|err| GolemError::unknown(format!("Failed to get shared value: {err}")))?;
+        Ok(entry.map(|entry| SharedValue {
+            value: entry.value,
+            version: entry.version,
+        }))
+    }
 
-        let chunk = match request.cursor {
-            Some(cursor) => {
-                get_public_oplog_chunk(
-                    self.component_service(),
-                    self.oplog_service(),
-                    &owned_worker_id,
-                    cursor.current_component_version,
-                    OplogIndex::from_u64(cursor.next_oplog_index),
-                    min(request.count as usize, 100), // TODO: configurable maximum
-                )
-                .await
-                .map_err(GolemError::unknown)?
+    async fn set_shared_value_internal(
+        &self,
+        request: SetSharedValueRequest,
+    ) -> Result<Option<u64>, GolemError> {
+        let account_id = Self::shared_kv_owner(request.account_id)?;
+        if request.key.is_empty() {
+            return Err(GolemError::invalid_request("Key must not be empty"));
+        }
+        let result = match request.expected_version {
+            Some(expected_version) => {
+                self.key_value_service()
+                    .compare_and_swap_shared(
+                        account_id,
+                        request.key,
+                        expected_version,
+                        request.value,
+                    )
+                    .await
+            }
+            None => self
+                .key_value_service()
+                .set_shared(account_id, request.key, request.value)
+                .await
+                .map(Some),
+        };
+        result.map_err(|err| GolemError::unknown(format!("Failed to set shared value: {err}")))
+    }
+
+    async fn delete_shared_value_internal(
+        &self,
+        request: DeleteSharedValueRequest,
+    ) -> Result<bool, GolemError> {
+        let account_id = Self::shared_kv_owner(request.account_id)?;
+        let result = match request.expected_version {
+            Some(expected_version) => {
+                self.key_value_service()
+                    .compare_and_delete_shared(account_id, request.key, expected_version)
+                    .await
+            }
+            None => self
+                .key_value_service()
+                .delete_shared(account_id, request.key)
+                .await
+                .map(|_| true),
+        };
+        result.map_err(|err| GolemError::unknown(format!("Failed to delete shared value: {err}")))
+    }
+
+    async fn list_shared_keys_internal(
+        &self,
+        request: ListSharedKeysRequest,
+    ) -> Result<Vec<String>, GolemError> {
+        let account_id = Self::shared_kv_owner(request.account_id)?;
+        self.key_value_service()
+            .get_shared_keys(account_id, request.prefix)
+            .await
+            .map_err(|err| GolemError::unknown(format!("Failed to list shared keys: {err}")))
+    }
+
+    async fn list_topics_internal(
+        &self,
+        request: ListTopicsRequest,
+    ) -> Result<Vec<String>, GolemError> {
+        let account_id = Self::shared_kv_owner(request.account_id)?;
+        self.topic_service().list_topics(&account_id).await
+    }
+
+    async fn get_topic_internal(
+        &self,
+        request: GetTopicRequest,
+    ) -> Result<Option<Topic>, GolemError> {
+        let account_id = Self::shared_kv_owner(request.account_id)?;
+        let info = self
+            .topic_service()
+            .get_topic(&account_id, &request.topic)
+            .await?;
+        Ok(info.map(|info| Topic {
+            name: info.name.clone(),
+            last_sequence: info.last_sequence,
+            retained_messages: info.retained_messages,
+            subscriptions: info
+                .subscriptions
+                .iter()
+                .map(|subscription| TopicSubscription {
+                    worker_id: Some(subscription.worker_id.clone().into()),
+                    handler: subscription.handler.clone(),
+                    acknowledged_sequence: subscription.acknowledged,
+                    lag: info.lag(subscription),
+                })
+                .collect(),
+        }))
+    }
+
+    async fn get_account_usage_internal(
+        &self,
+        request: GetAccountUsageRequest,
+    ) -> Result<Vec<UsageRecord>, GolemError> {
+        let account_id = Self::shared_kv_owner(request.account_id)?;
+        let from = request
+            .from
+            .ok_or(GolemError::invalid_request("from not found"))?;
+        let to = request
+            .to
+            .ok_or(GolemError::invalid_request("to not found"))?;
+
+        let records = self
+            .usage_service()
+            .get_usage(&account_id, from.into(), to.into())
+            .await?;
+        Ok(records
+            .into_iter()
+            .map(|record| UsageRecord {
+                period_start: Some(record.period_start.into()),
+                period_end: Some(record.period_end.into()),
+                invocations: record.usage.invocations,
+                memory_byte_seconds: record.usage.memory_byte_seconds,
+                oplog_bytes: record.usage.oplog_bytes,
+            })
+            .collect())
+    }
+
+    fn scheduled_action_filter(
+        account_id: Option<golem::common::AccountId>,
+        filter: Option<ScheduledActionFilter>,
+    ) -> Result<scheduler::ScheduledActionFilter, GolemError> {
+        let account_id = Self::shared_kv_owner(account_id)?;
+        let filter = filter.unwrap_or_default();
+        let due_time = |timestamp: Option<Timestamp>| {
+            timestamp
+                .and_then(|timestamp| DateTime::from_timestamp_millis(timestamp.to_millis() as i64))
+        };
+        Ok(scheduler::ScheduledActionFilter {
+            account_id: Some(account_id),
+            worker_id: filter
+                .worker_id
+                .map(|worker_id| worker_id.try_into())
+                .transpose()
+                .map_err(GolemError::invalid_request)?,
+            kind: filter
+                .kind
+                .map(|kind| match ScheduledActionKind::try_from(kind) {
+                    Ok(ScheduledActionKind::CompletePromise) => {
+                        Ok(scheduler::ScheduledActionKind::CompletePromise)
+                    }
+                    Ok(ScheduledActionKind::ArchiveOplog) => {
+                        Ok(scheduler::ScheduledActionKind::ArchiveOplog)
+                    }
+                    Err(_) => Err(GolemError::invalid_request(format!(
+                        "Unknown scheduled action kind: {kind}"
+                    ))),
+                })
+                .transpose()?,
+            due_after: due_time(filter.due_after.map(Timestamp::from)),
+            due_before: due_time(filter.due_before.map(Timestamp::from)),
+        })
+    }
+
+    fn pending_scheduled_action(
+        pending: scheduler::PendingScheduledAction,
+    ) -> PendingScheduledAction {
+        let due = Timestamp::from(pending.due.timestamp_millis() as u64);
+        let kind = match scheduler::ScheduledActionKind::of(pending.action()) {
+            scheduler::ScheduledActionKind::CompletePromise => ScheduledActionKind::CompletePromise,
+            scheduler::ScheduledActionKind::ArchiveOplog => ScheduledActionKind::ArchiveOplog,
+        };
+        let (promise_id, last_oplog_index) = match &pending.id.action {
+            ScheduledAction::CompletePromise { promise_id, .. } => {
+                (Some(promise_id.clone().into()), None)
+            }
+            ScheduledAction::ArchiveOplog {
+                last_oplog_index, ..
+            } => (None, Some(u64::from(*last_oplog_index))),
+        };
+        PendingScheduledAction {
+            worker_id: Some(pending.action().owned_worker_id().worker_id.into()),
+            kind: kind as i32,
+            due: Some(due.into()),
+            promise_id,
+            last_oplog_index,
+        }
+    }
+
+    async fn list_scheduled_actions_internal(
+        &self,
+        request: ListScheduledActionsRequest,
+    ) -> Result<PendingScheduledActions, GolemError> {
+        if request.count == 0 {
+            return Err(GolemError::invalid_request(
+                "The count of scheduled actions must be positive",
+            ));
+        }
+        let filter = Self::scheduled_action_filter(request.account_id, request.filter)?;
+        let cursor = request
+            .cursor
+            .map(|cursor| scheduler::ScheduledActionCursor {
+                bucket: cursor.bucket,
+                offset: cursor.offset,
+            });
+        let (next_cursor, pending) = self
+            .scheduler_service()
+            .list(&filter, cursor, request.count as usize)
+            .await
+            .map_err(|err| {
+                GolemError::unknown(format!("Failed to list scheduled actions: {err}"))
+            })?;
+        Ok(PendingScheduledActions {
+            actions: pending
+                .into_iter()
+                .map(Self::pending_scheduled_action)
+                .collect(),
+            next_cursor: next_cursor.map(|cursor| ScheduledActionCursor {
+                bucket: cursor.bucket,
+                offset: cursor.offset,
+            }),
+        })
+    }
+
+    async fn cancel_scheduled_actions_internal(
+        &self,
+        request: CancelScheduledActionsRequest,
+    ) -> Result<Vec<PendingScheduledAction>, GolemError> {
+        let filter = Self::scheduled_action_filter(request.account_id, request.filter)?;
+        let cancelled = self
+            .scheduler_service()
+            .cancel_matching(&filter)
+            .await
+            .map_err(|err| {
+                GolemError::unknown(format!("Failed to cancel scheduled actions: {err}"))
+            })?;
+        info!("Cancelled {} scheduled actions", cancelled.len());
+        Ok(cancelled
+            .into_iter()
+            .map(Self::pending_scheduled_action)
+            .collect())
+    }
+
+    /// Lists the not yet completed promises of a worker, with the time of the oplog entry
+    /// which created them
+    async fn list_promises_internal(
+        &self,
+        request: ListPromisesRequest,
+    ) -> Result<Vec<OutstandingPromise>, GolemError> {
+        let worker_id = request
+            .worker_id
+            .ok_or(GolemError::invalid_request("worker_id not found"))?;
+        let worker_id: WorkerId = worker_id.try_into().map_err(GolemError::invalid_request)?;
+
+        let account_id = request
+            .account_id
+            .ok_or(GolemError::invalid_request("account_id not found"))?;
+        let account_id: AccountId = account_id.into();
+
+        let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+
+        self.ensure_worker_belongs_to_this_executor(&worker_id)?;
+
+        self.worker_service()
+            .get(&owned_worker_id)
+            .await
+            .ok_or(GolemError::worker_not_found(worker_id.clone()))?;
+
+        let now = Timestamp::now_utc().to_millis();
+        let mut promises = Vec::new();
+        for record in self.promise_service().list(&worker_id).await? {
+            if record.completed {
+                continue;
+            }
+            let created_at = self
+                .oplog_service()
+                .read(&owned_worker_id, record.promise_id.oplog_idx, 1)
+                .await
+                .remove(&record.promise_id.oplog_idx)
+                .map(|entry| entry.timestamp());
+            promises.push(OutstandingPromise {
+                promise_id: Some(record.promise_id.into()),
+                created_at: created_at.map(|timestamp| timestamp.into()),
+                age_seconds: created_at
+                    .map(|timestamp| now.saturating_sub(timestamp.to_millis()) / 1000),
+            });
+        }
+        Ok(promises)
+    }
+
+    /// Lists the sagas of a worker which are not finished yet, including the ones whose
+    /// compensation failed
+    async fn get_worker_sagas_internal(
+        &self,
+        request: GetWorkerSagasRequest,
+    ) -> Result<Vec<WorkerSaga>, GolemError> {
+        let worker_id = request
+            .worker_id
+            .ok_or(GolemError::invalid_request("worker_id not found"))?;
+        let worker_id: WorkerId = worker_id.try_into().map_err(GolemError::invalid_request)?;
+
+        let account_id = request
+            .account_id
+            .ok_or(GolemError::invalid_request("account_id not found"))?;
+        let account_id: AccountId = account_id.into();
+
+        let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+
+        self.ensure_worker_belongs_to_this_executor(&worker_id)?;
+
+        self.worker_service()
+            .get(&owned_worker_id)
+            .await
+            .ok_or(GolemError::worker_not_found(worker_id.clone()))?;
+
+        let sagas = self.saga_service().list(&owned_worker_id).await?;
+        Ok(sagas.into_iter().map(Self::worker_saga).collect())
+    }
+
+    fn worker_saga(saga: Saga) -> WorkerSaga {
+        let (state, error) = match saga.state {
+            SagaState::Open => (WorkerSagaState::Open, None),
+            SagaState::Compensating => (WorkerSagaState::Compensating, None),
+            SagaState::CompensationFailed { error } => {
+                (WorkerSagaState::CompensationFailed, Some(error))
+            }
+        };
+        WorkerSaga {
+            id: saga.id,
+            state: state as i32,
+            error,
+            compensations: saga
+                .compensations
+                .into_iter()
+                .map(|compensation| SagaCompensation {
+                    target: Some(compensation.target.into()),
+                    function_name: compensation.function_name,
+                })
+                .collect(),
+        }
+    }
+
+    async fn connect_worker_internal(
+        &self,
+        request: ConnectWorkerRequest,
+    ) -> ResponseResult<<Self as WorkerExecutor>::ConnectWorkerStream> {
+        let worker_id: WorkerId = request
+            .worker_id
+            .ok_or(GolemError::invalid_request("missing worker_id"))?
+            .try_into()
+            .map_err(GolemError::invalid_request)?;
+        let account_id: AccountId = request
+            .account_id
+            .ok_or(GolemError::invalid_request("missing account_id"))?
+            .into();
+        let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+
+        self.ensure_worker_belongs_to_this_executor(&worker_id)?;
+
+        let metadata = self.worker_service().get(&owned_worker_id).await;
+        if metadata.is_some() {
+            let worker_status = self
+                .validate_worker_status(&owned_worker_id, &metadata)
+                .await
+                .map_err(|e| {
+                    error!("Failed to connect to worker {worker_id}: {:?}", e);
+                    Status::internal(format!("Error connecting to worker: {e}"))
+                })?;
+
+            if worker_status.status != WorkerStatus::Interrupted {
+                let event_service =
+                    Worker::get_or_create_suspended(self, &owned_worker_id, None, None, None, None)
+                        .await?
+                        .event_service();
+
+                let mut receiver = event_service.receiver();
+
+                if request.backfill_count > 0 {
+                    let backfilled = read_log_events_from_oplog(
+                        self.oplog_service().as_ref(),
+                        &owned_worker_id,
+                        min(
+                            request.backfill_count as usize,
+                            self.config().limits.max_event_backfill_count,
+                        ),
+                        self.config().limits.max_event_backfill_scanned_entries,
+                    )
+                    .await;
+                    receiver = receiver.with_backfill(backfilled);
+                }
+
+                info!("Client connected");
+                record_new_grpc_api_active_stream();
+
+                Ok(Response::new(WorkerEventStream::new(receiver)))
+            } else {
+                // We don't want 'connect' to resume interrupted workers
+                Err(GolemError::Interrupted {
+                    kind: InterruptKind::Interrupt,
+                }
+                .into())
+            }
+        } else {
+            Err(GolemError::WorkerNotFound { worker_id }.into())
+        }
+    }
+
+    async fn get_oplog_internal(
+        &self,
+        request: GetOplogRequest,
+    ) -> Result<GetOplogResponse, GolemError> {
+        let worker_id = request
+            .worker_id
+            .ok_or(GolemError::invalid_request("worker_id not found"))?;
+        let worker_id: WorkerId = worker_id.try_into().map_err(GolemError::invalid_request)?;
+
+        let account_id = request
+            .account_id
+            .ok_or(GolemError::invalid_request("account_id not found"))?;
+        let account_id: AccountId = account_id.into();
+
+        let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+
+        if !request.read_replica {
+            self.ensure_worker_belongs_to_this_executor(&worker_id)?;
+        }
+
+        let chunk = match request.cursor {
+            Some(cursor) => {
+                get_public_oplog_chunk(
+                    self.component_service(),
+                    self.oplog_service(),
+                    &owned_worker_id,
+                    cursor.current_component_version,
+                    OplogIndex::from_u64(cursor.next_oplog_index),
+                    min(request.count as usize, 100), // TODO: configurable maximum
+                )
+                .await
+                .map_err(GolemError::unknown)?
             }
             None => {
-                let start = OplogIndex::from_u64(request.from_oplog_index);
+                let start = match request.invocations_before {
+                    Some(invocations_before) if invocations_before > 0 => {
+                        let before = if request.from_oplog_index == 0 {
+                            self.oplog_service()
+                                .get_last_index(&owned_worker_id)
+                                .await
+                                .next()
+                        } else {
+                            OplogIndex::from_u64(request.from_oplog_index)
+                        };
+                        let metadata = self.worker_service().get(&owned_worker_id).await;
+                        let worker_status =
+                            calculate_last_known_status(self, &owned_worker_id, &metadata).await?;
+                        find_last_invocation_starts(
+                            self.oplog_service(),
+                            &owned_worker_id,
+                            &worker_status.deleted_regions,
+                            before,
+                            invocations_before as usize,
+                        )
+                        .await?
+                        .first()
+                        .copied()
+                        .unwrap_or(OplogIndex::INITIAL)
+                    }
+                    _ => OplogIndex::from_u64(request.from_oplog_index),
+                };
                 let initial_component_version =
                     find_component_version_at(self.oplog_service(), &owned_worker_id, start)
                         .await?;
@@ -1118,642 +2006,2516 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
         })
     }
 
-    fn create_proto_metadata(
-        metadata: WorkerMetadata,
-        latest_status: WorkerStatusRecord,
-        last_error_and_retry_count: Option<LastError>,
-    ) -> golem::worker::WorkerMetadata {
-        let mut updates = Vec::new();
+    /// Tries to decode the entries of the worker's corrupted oplog regions with all the supported
+    /// serialization versions. This is only a diagnostic operation, the oplog is not modified.
+    async fn redecode_corrupted_oplog_entries_internal(
+        &self,
+        request: RedecodeCorruptedOplogEntriesRequest,
+    ) -> Result<Vec<golem::workerexecutor::v1::RedecodedOplogEntry>, GolemError> {
+        let worker_id = request
+            .worker_id
+            .ok_or(GolemError::invalid_request("worker_id not found"))?;
+        let worker_id: WorkerId = worker_id.try_into().map_err(GolemError::invalid_request)?;
 
-        for pending_invocation in &latest_status.pending_invocations {
-            if let TimestampedWorkerInvocation {
-                timestamp,
-                invocation: WorkerInvocation::ManualUpdate { target_version },
-            } = pending_invocation
-            {
-                updates.push(golem::worker::UpdateRecord {
-                    timestamp: Some((*timestamp).into()),
-                    target_version: *target_version,
-                    update: Some(golem::worker::update_record::Update::Pending(
-                        golem::worker::PendingUpdate {},
-                    )),
-                });
+        let account_id = request
+            .account_id
+            .ok_or(GolemError::invalid_request("account_id not found"))?;
+        let account_id: AccountId = account_id.into();
+
+        let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+
+        self.ensure_worker_belongs_to_this_executor(&worker_id)?;
+
+        let metadata = self
+            .worker_service()
+            .get(&owned_worker_id)
+            .await
+            .ok_or(GolemError::worker_not_found(worker_id.clone()))?;
+        let latest_status =
+            Ctx::compute_latest_worker_status(self, &owned_worker_id, &Some(metadata)).await?;
+
+        let mut result = Vec::new();
+        for corrupted in latest_status.deleted_regions.corrupted_regions() {
+            let raw_entries = self
+                .oplog_service()
+                .read_raw_range(
+                    &owned_worker_id,
+                    corrupted.region.start,
+                    corrupted.region.end,
+                )
+                .await;
+
+            for (oplog_index, raw_entry) in raw_entries {
+                let recorded_serialization_version = raw_entry.serialization_version() as u32;
+                let entry = match try_deserialize_with_any_version::<OplogEntry>(raw_entry.bytes())
+                {
+                    Ok((version, _)) => golem::workerexecutor::v1::RedecodedOplogEntry {
+                        oplog_index: oplog_index.into(),
+                        recorded_serialization_version,
+                        decoded_with_serialization_version: Some(version as u32),
+                        error: None,
+                    },
+                    Err(error) => golem::workerexecutor::v1::RedecodedOplogEntry {
+                        oplog_index: oplog_index.into(),
+                        recorded_serialization_version,
+                        decoded_with_serialization_version: None,
+                        error: Some(error),
+                    },
+                };
+                result.push(entry);
             }
         }
-        for pending_update in &latest_status.pending_updates {
-            updates.push(golem::worker::UpdateRecord {
-                timestamp: Some(pending_update.timestamp.into()),
-                target_version: *pending_update.description.target_version(),
-                update: Some(golem::worker::update_record::Update::Pending(
-                    golem::worker::PendingUpdate {},
-                )),
-            });
+
+        Ok(result)
+    }
+
+    async fn get_worker_status_internal(
+        &self,
+        request: GetWorkerStatusRequest,
+    ) -> Result<golem::worker::PublicWorkerStatusRecord, GolemError> {
+        let worker_id = request
+            .worker_id
+            .ok_or(GolemError::invalid_request("worker_id not found"))?;
+        let worker_id: WorkerId = worker_id.try_into().map_err(GolemError::invalid_request)?;
+
+        let account_id = request
+            .account_id
+            .ok_or(GolemError::invalid_request("account_id not found"))?;
+        let account_id: AccountId = account_id.into();
+
+        let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+
+        self.ensure_worker_belongs_to_this_executor(&worker_id)?;
+
+        let metadata = self
+            .worker_service()
+            .get(&owned_worker_id)
+            .await
+            .ok_or(GolemError::worker_not_found(worker_id.clone()))?;
+        let latest_status =
+            Ctx::compute_latest_worker_status(self, &owned_worker_id, &Some(metadata)).await?;
+
+        Ok(PublicWorkerStatusRecord::from(latest_status).into())
+    }
+
+    async fn get_pending_invocation_count_internal(
+        &self,
+        request: GetPendingInvocationCountRequest,
+    ) -> Result<u64, GolemError> {
+        let worker_id = request
+            .worker_id
+            .ok_or(GolemError::invalid_request("worker_id not found"))?;
+        let worker_id: WorkerId = worker_id.try_into().map_err(GolemError::invalid_request)?;
+
+        let account_id = request
+            .account_id
+            .ok_or(GolemError::invalid_request("account_id not found"))?;
+        let account_id: AccountId = account_id.into();
+
+        let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+
+        self.ensure_worker_belongs_to_this_executor(&worker_id)?;
+
+        // Active workers know their in-memory queue; otherwise fall back to the
+        // last known status without loading the worker
+        if let Some(worker) = self.active_workers().try_get(&worker_id) {
+            return Ok(worker.pending_invocation_count() as u64);
+        }
+
+        let metadata = self
+            .worker_service()
+            .get(&owned_worker_id)
+            .await
+            .ok_or(GolemError::worker_not_found(worker_id.clone()))?;
+
+        Ok(metadata.last_known_status.pending_invocations.len() as u64)
+    }
+
+    /// Aggregates the fuel consumed by the completed invocations of a worker by reading its
+    /// whole oplog, so the result does not depend on the worker being in memory
+    async fn get_worker_fuel_consumption_internal(
+        &self,
+        request: GetWorkerFuelConsumptionRequest,
+    ) -> Result<WorkerFuelConsumption, GolemError> {
+        let worker_id = request
+            .worker_id
+            .ok_or(GolemError::invalid_request("worker_id not found"))?;
+        let worker_id: WorkerId = worker_id.try_into().map_err(GolemError::invalid_request)?;
+
+        let account_id = request
+            .account_id
+            .ok_or(GolemError::invalid_request("account_id not found"))?;
+        let account_id: AccountId = account_id.into();
+
+        let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+
+        self.ensure_worker_belongs_to_this_executor(&worker_id)?;
+
+        if self.worker_service().get(&owned_worker_id).await.is_none() {
+            return Err(GolemError::worker_not_found(worker_id));
+        }
+
+        const CHUNK_SIZE: u64 = 1000;
+
+        let last_index = self.oplog_service().get_last_index(&owned_worker_id).await;
+        let mut consumption = WorkerFuelConsumption::default();
+        let mut start = OplogIndex::INITIAL;
+        while start <= last_index {
+            let entries = self
+                .oplog_service()
+                .read(&owned_worker_id, start, CHUNK_SIZE)
+                .await;
+            for entry in entries.values() {
+                consumption.add_entry(entry);
+            }
+            start = start.range_end(CHUNK_SIZE).next();
+        }
+
+        Ok(consumption)
+    }
+
+    /// Collects the failures of a worker caused by its replay diverging from the recorded history
+    /// by reading its whole oplog, in the order they happened
+    async fn get_worker_replay_divergences_internal(
+        &self,
+        request: GetWorkerReplayDivergencesRequest,
+    ) -> Result<Vec<ReplayDivergenceReport>, GolemError> {
+        let worker_id = request
+            .worker_id
+            .ok_or(GolemError::invalid_request("worker_id not found"))?;
+        let worker_id: WorkerId = worker_id.try_into().map_err(GolemError::invalid_request)?;
+
+        let account_id = request
+            .account_id
+            .ok_or(GolemError::invalid_request("account_id not found"))?;
+        let account_id: AccountId = account_id.into();
+
+        let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+
+        self.ensure_worker_belongs_to_this_executor(&worker_id)?;
+
+        if self.worker_service().get(&owned_worker_id).await.is_none() {
+            return Err(GolemError::worker_not_found(worker_id));
+        }
+
+        const CHUNK_SIZE: u64 = 1000;
+
+        let last_index = self.oplog_service().get_last_index(&owned_worker_id).await;
+        let mut divergences = Vec::new();
+        let mut start = OplogIndex::INITIAL;
+        while start <= last_index {
+            let entries = self
+                .oplog_service()
+                .read(&owned_worker_id, start, CHUNK_SIZE)
+                .await;
+            divergences.extend(
+                entries
+                    .iter()
+                    .filter_map(|(idx, entry)| ReplayDivergenceReport::from_entry(*idx, entry)),
+            );
+            start = start.range_end(CHUNK_SIZE).next();
+        }
+
+        Ok(divergences)
+    }
+
+    /// Restores the state of a worker as of an oplog index in a throwaway instance of the worker
+    /// and invokes a query function on it, see `Worker::query_as_of`. Returns the oplog index the
+    /// state was restored to and the result of the query.
+    async fn query_worker_at_oplog_index_internal(
+        &self,
+        request: QueryWorkerAtOplogIndexRequest,
+    ) -> Result<(OplogIndex, TypeAnnotatedValue), GolemError> {
+        let worker_id = request
+            .worker_id
+            .ok_or(GolemError::invalid_request("worker_id not found"))?;
+        let worker_id: WorkerId = worker_id.try_into().map_err(GolemError::invalid_request)?;
+
+        let account_id = request
+            .account_id
+            .ok_or(GolemError::invalid_request("account_id not found"))?;
+        let account_id: AccountId = account_id.into();
+
+        let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+
+        self.ensure_worker_belongs_to_this_executor(&worker_id)?;
+
+        let function_input = request
+            .input
+            .into_iter()
+            .map(|val| val.try_into())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|msg| GolemError::ValueMismatch { details: msg })?;
+
+        if self.worker_service().get(&owned_worker_id).await.is_none() {
+            return Err(GolemError::worker_not_found(worker_id));
+        }
+        let worker =
+            Worker::get_or_create_suspended(self, &owned_worker_id, None, None, None, None).await?;
+
+        let query = Worker::query_as_of(
+            &worker,
+            OplogIndex::from_u64(request.oplog_index),
+            request.name,
+            function_input,
+        );
+        match request.timeout_millis.map(Duration::from_millis) {
+            Some(timeout) => tokio::time::timeout(timeout, query)
+                .await
+                .map_err(|_| GolemError::invocation_timed_out(worker_id, timeout))?,
+            None => query.await,
+        }
+    }
+
+    /// Creates a new worker from the oplog of an existing one, continuing from the state the
+    /// source worker had at the given oplog index. Returns the oplog index the new worker
+    /// continues from.
+    async fn fork_worker_internal(
+        &self,
+        request: ForkWorkerRequest,
+    ) -> Result<OplogIndex, GolemError> {
+        let source_worker_id = request
+            .source_worker_id
+            .ok_or(GolemError::invalid_request("source_worker_id not found"))?;
+        let source_worker_id: WorkerId = source_worker_id
+            .try_into()
+            .map_err(GolemError::invalid_request)?;
+
+        let target_worker_id = request
+            .target_worker_id
+            .ok_or(GolemError::invalid_request("target_worker_id not found"))?;
+        let target_worker_id: WorkerId = target_worker_id
+            .try_into()
+            .map_err(GolemError::invalid_request)?;
+
+        let account_id = request
+            .account_id
+            .ok_or(GolemError::invalid_request("account_id not found"))?;
+        let account_id: AccountId = account_id.into();
+
+        if source_worker_id.component_id != target_worker_id.component_id {
+            return Err(GolemError::invalid_request(
+                "The forked worker must belong to the component of the source worker",
+            ));
+        }
+        WorkerName::validate(&target_worker_id.worker_name).map_err(GolemError::invalid_request)?;
+
+        self.ensure_worker_belongs_to_this_executor(&source_worker_id)?;
+        self.ensure_worker_belongs_to_this_executor(&target_worker_id)?;
+
+        let target = OwnedWorkerId::new(&account_id, &target_worker_id);
+        let oplog_index = time_travel::copy_worker(
+            self,
+            &OwnedWorkerId::new(&account_id, &source_worker_id),
+            &target,
+            OplogIndex::from_u64(request.oplog_index),
+            true,
+        )
+        .await?;
+        self.restore_sagas(&target).await;
+        info!(
+            "Forked worker {target_worker_id} from {source_worker_id} at oplog index {oplog_index}"
+        );
+
+        Ok(oplog_index)
+    }
+
+    async fn delete_oplog_region_internal(
+        &self,
+        request: DeleteOplogRegionRequest,
+    ) -> Result<OplogIndex, GolemError> {
+        let worker_id = request
+            .worker_id
+            .ok_or(GolemError::invalid_request("worker_id not found"))?;
+        let worker_id: WorkerId = worker_id.try_into().map_err(GolemError::invalid_request)?;
+
+        let account_id = request
+            .account_id
+            .ok_or(GolemError::invalid_request("account_id not found"))?;
+        let account_id: AccountId = account_id.into();
+
+        let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+
+        self.ensure_worker_belongs_to_this_executor(&worker_id)?;
+
+        if self.worker_service().get(&owned_worker_id).await.is_none() {
+            return Err(GolemError::worker_not_found(worker_id));
+        }
+        let worker =
+            Worker::get_or_create_suspended(self, &owned_worker_id, None, None, None, None).await?;
+
+        let region = OplogRegion::from_range(request.start..=request.end);
+        let oplog_index = Worker::delete_oplog_region(&worker, region.clone()).await?;
+        info!("Deleted oplog region {region} of worker {worker_id}");
+        self.restore_sagas(&owned_worker_id).await;
+
+        // The worker is recovered without the deleted region
+        self.active_workers().remove(&worker_id);
+        Worker::get_or_create_running(self, &owned_worker_id, None, None, None, None).await?;
+
+        Ok(oplog_index)
+    }
+
+    async fn revive_worker_internal(
+        &self,
+        request: ReviveWorkerRequest,
+    ) -> Result<OplogIndex, GolemError> {
+        let worker_id = request
+            .worker_id
+            .ok_or(GolemError::invalid_request("worker_id not found"))?;
+        let worker_id: WorkerId = worker_id.try_into().map_err(GolemError::invalid_request)?;
+
+        let account_id = request
+            .account_id
+            .ok_or(GolemError::invalid_request("account_id not found"))?;
+        let account_id: AccountId = account_id.into();
+
+        let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+
+        self.ensure_worker_belongs_to_this_executor(&worker_id)?;
+
+        if !request.confirm {
+            return Err(GolemError::invalid_request(
+                "Reviving a failed worker has to be confirmed",
+            ));
+        }
+
+        let metadata = self
+            .worker_service()
+            .get(&owned_worker_id)
+            .await
+            .ok_or(GolemError::worker_not_found(worker_id.clone()))?;
+        let worker_status =
+            Ctx::compute_latest_worker_status(self, &owned_worker_id, &Some(metadata)).await?;
+        if worker_status.status != WorkerStatus::Failed {
+            return Err(GolemError::invalid_request(format!(
+                "Only failed workers can be revived, the worker is {:?}",
+                worker_status.status
+            )));
+        }
+
+        if let Some(worker) = self.active_workers().try_get(&worker_id) {
+            worker.stop().await;
+            self.active_workers().remove(&worker_id);
+        }
+
+        let last_index = self.oplog_service().get_last_index(&owned_worker_id).await;
+        let trimmed_region = if request.trim_failed_invocation {
+            let mut entries = self
+                .oplog_service()
+                .read_range(&owned_worker_id, OplogIndex::INITIAL, last_index)
+                .await;
+            entries.retain(|idx, _| !worker_status.deleted_regions.is_in_deleted_region(*idx));
+            let restored_index = time_travel::last_index_between_invocations(&entries, last_index);
+            (restored_index < last_index)
+                .then(|| OplogRegion::from_index_range(restored_index.next()..=last_index))
+        } else {
+            None
+        };
+
+        let component_type = self
+            .component_service()
+            .get_metadata(
+                &worker_id.component_id,
+                Some(worker_status.component_version),
+            )
+            .await?
+            .component_type;
+        let oplog = self
+            .oplog_service()
+            .open(&owned_worker_id, last_index, component_type)
+            .await;
+        let oplog_index = oplog
+            .add_and_commit(OplogEntry::revive(trimmed_region.clone()))
+            .await;
+        match trimmed_region {
+            Some(region) => info!("Revived worker {worker_id}, trimming its oplog region {region}"),
+            None => info!("Revived worker {worker_id}"),
+        }
+
+        if !worker_status.pending_invocations.is_empty() {
+            Worker::get_or_create_running(self, &owned_worker_id, None, None, None, None).await?;
+        }
+
+        Ok(oplog_index)
+    }
+
+    async fn update_worker_environment_internal(
+        &self,
+        request: UpdateWorkerEnvironmentRequest,
+    ) -> Result<OplogIndex, GolemError> {
+        let worker_id = request
+            .worker_id
+            .ok_or(GolemError::invalid_request("worker_id not found"))?;
+        let worker_id: WorkerId = worker_id.try_into().map_err(GolemError::invalid_request)?;
+
+        let account_id = request
+            .account_id
+            .ok_or(GolemError::invalid_request("account_id not found"))?;
+        let account_id: AccountId = account_id.into();
+
+        let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+
+        self.ensure_worker_belongs_to_this_executor(&worker_id)?;
+
+        if self.worker_service().get(&owned_worker_id).await.is_none() {
+            return Err(GolemError::worker_not_found(worker_id));
+        }
+
+        let worker =
+            Worker::get_or_create_suspended(self, &owned_worker_id, None, None, None, None).await?;
+        let args = request.update_args.then_some(request.args);
+        let env = request.env.into_iter().collect();
+        let oplog_index = worker.update_environment(args, env).await?;
+        info!("Updated the environment of worker {worker_id}");
+
+        Ok(oplog_index)
+    }
+
+    /// Sets, advances or resets the virtual clock of a worker, returning its new virtual time in
+    /// milliseconds, or `None` if it was switched back to the real clock
+    async fn update_worker_virtual_clock_internal(
+        &self,
+        request: UpdateWorkerVirtualClockRequest,
+    ) -> Result<Option<u64>, GolemError> {
+        let worker_id = request
+            .worker_id
+            .ok_or(GolemError::invalid_request("worker_id not found"))?;
+        let worker_id: WorkerId = worker_id.try_into().map_err(GolemError::invalid_request)?;
+
+        let account_id = request
+            .account_id
+            .ok_or(GolemError::invalid_request("account_id not found"))?;
+        let account_id: AccountId = account_id.into();
+
+        let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+
+        self.ensure_worker_belongs_to_this_executor(&worker_id)?;
+
+        if self.worker_service().get(&owned_worker_id).await.is_none() {
+            return Err(GolemError::worker_not_found(worker_id));
+        }
+
+        let virtual_clock_service = self.virtual_clock_service();
+        let time = match request
+            .update
+            .ok_or(GolemError::invalid_request("update not found"))?
+        {
+            golem::workerexecutor::v1::update_worker_virtual_clock_request::Update::SetTimeMillis(
+                millis,
+            ) => Some(
+                virtual_clock_service
+                    .set_time(&worker_id, millis.saturating_mul(1_000_000))
+                    .await?,
+            ),
+            golem::workerexecutor::v1::update_worker_virtual_clock_request::Update::AdvanceMillis(
+                millis,
+            ) => Some(
+                virtual_clock_service
+                    .advance(&worker_id, Duration::from_millis(millis))
+                    .await?,
+            ),
+            golem::workerexecutor::v1::update_worker_virtual_clock_request::Update::Reset(_) => {
+                virtual_clock_service.reset(&worker_id).await?;
+                None
+            }
+        };
+
+        Ok(time.map(|nanos| nanos / 1_000_000))
+    }
+
+    fn create_proto_metadata(
+        metadata: WorkerMetadata,
+        latest_status: WorkerStatusRecord,
+        last_error_and_retry_count: Option<LastError>,
+    ) -> golem::worker::WorkerMetadata {
+        let mut updates = Vec::new();
+
+        for pending_invocation in &latest_status.pending_invocations {
+            if let TimestampedWorkerInvocation {
+                timestamp,
+                invocation: WorkerInvocation::ManualUpdate { target_version },
+            } = pending_invocation
+            {
+                updates.push(golem::worker::UpdateRecord {
+                    timestamp: Some((*timestamp).into()),
+                    target_version: *target_version,
+                    update: Some(golem::worker::update_record::Update::Pending(
+                        golem::worker::PendingUpdate {},
+                    )),
+                });
+            }
+        }
+        for pending_update in &latest_status.pending_updates {
+            updates.push(golem::worker::UpdateRecord {
+                timestamp: Some(pending_update.timestamp.into()),
+                target_version: *pending_update.description.target_version(),
+                update: Some(golem::worker::update_record::Update::Pending(
+                    golem::worker::PendingUpdate {},
+                )),
+            });
+        }
+        for successful_update in &latest_status.successful_updates {
+            updates.push(golem::worker::UpdateRecord {
+                timestamp: Some(successful_update.timestamp.into()),
+                target_version: successful_update.target_version,
+                update: Some(golem::worker::update_record::Update::Successful(
+                    golem::worker::SuccessfulUpdate {},
+                )),
+            });
+        }
+        for failed_update in &latest_status.failed_updates {
+            updates.push(golem::worker::UpdateRecord {
+                timestamp: Some(failed_update.timestamp.into()),
+                target_version: failed_update.target_version,
+                update: Some(golem::worker::update_record::Update::Failed(
+                    golem::worker::FailedUpdate {
+                        details: failed_update.details.clone(),
+                    },
+                )),
+            });
+        }
+        updates.sort_by_key(|record| {
+            record.timestamp.as_ref().unwrap().seconds * 1_000_000_000
+                + record.timestamp.as_ref().unwrap().nanos as i64
+        });
+
+        let mut owned_resources = HashMap::new();
+        for (resource_id, resource) in metadata.last_known_status.owned_resources {
+            owned_resources.insert(
+                resource_id.0,
+                ResourceMetadata {
+                    created_at: Some(resource.created_at.into()),
+                    indexed: resource.indexed_resource_key.map(|t| t.into()),
+                },
+            );
+        }
+
+        golem::worker::WorkerMetadata {
+            worker_id: Some(metadata.worker_id.into()),
+            args: metadata.args.clone(),
+            env: HashMap::from_iter(metadata.env.iter().cloned()),
+            account_id: Some(metadata.account_id.into()),
+            component_version: latest_status.component_version,
+            status: Into::<golem::worker::WorkerStatus>::into(latest_status.status).into(),
+            retry_count: last_error_and_retry_count
+                .as_ref()
+                .map(|last_error| last_error.retry_count)
+                .unwrap_or_default(),
+
+            pending_invocation_count: latest_status.pending_invocations.len() as u64,
+            updates,
+            created_at: Some(metadata.created_at.into()),
+            last_error: last_error_and_retry_count
+                .map(|last_error| last_error.error.to_string(&last_error.stderr)),
+            component_size: metadata.last_known_status.component_size,
+            total_linear_memory_size: metadata.last_known_status.total_linear_memory_size,
+            owned_resources,
+            corrupted_oplog_regions: latest_status
+                .deleted_regions
+                .corrupted_regions()
+                .cloned()
+                .map(|region| region.into())
+                .collect(),
+            last_invoked_function: latest_status
+                .last_invoked_function
+                .map(|last_invoked_function| last_invoked_function.into()),
+        }
+    }
+
+    /// Drains the executor for a restart: new invocations are rejected (with a retriable error)
+    /// while the in-flight ones get a chance to finish until the deadline. Workers still busy
+    /// after the deadline are suspended, so the executor taking over their shard recovers them.
+    /// Finally all workers are unloaded and the shards are released to the shard manager.
+    async fn drain_internal(
+        &self,
+        request: DrainRequest,
+        progress: &mpsc::Sender<Result<DrainResponse, Status>>,
+    ) -> Result<(), GolemError> {
+        if !self.shard_service().start_draining() {
+            return Err(GolemError::invalid_request(
+                "The worker executor is already draining",
+            ));
+        }
+
+        let deadline = request
+            .deadline_millis
+            .map(Duration::from_millis)
+            .unwrap_or(self.config().drain.deadline);
+        let start = Instant::now();
+
+        info!(deadline = ?deadline, "Draining worker executor");
+
+        let mut in_flight_workers = self.workers_with_in_flight_invocations().await;
+        while !in_flight_workers.is_empty() && start.elapsed() < deadline {
+            report_drain_progress(
+                progress,
+                DrainPhase::WaitingForInvocations,
+                in_flight_workers.len(),
+                0,
+                start,
+            )
+            .await;
+            tokio::time::sleep(min(
+                DRAIN_PROGRESS_INTERVAL,
+                deadline.saturating_sub(start.elapsed()),
+            ))
+            .await;
+            in_flight_workers = self.workers_with_in_flight_invocations().await;
+        }
+
+        let suspended_workers = in_flight_workers.len();
+        if suspended_workers > 0 {
+            warn!(
+                suspended_workers,
+                "Drain deadline reached, suspending workers with in-flight invocations"
+            );
+            report_drain_progress(
+                progress,
+                DrainPhase::SuspendingWorkers,
+                suspended_workers,
+                suspended_workers,
+                start,
+            )
+            .await;
+            for worker in in_flight_workers {
+                if let Some(mut await_interrupted) =
+                    worker.set_interrupting(InterruptKind::Suspend).await
+                {
+                    let _ = await_interrupted.recv().await;
+                }
+            }
+        }
+
+        report_drain_progress(
+            progress,
+            DrainPhase::ReleasingShards,
+            0,
+            suspended_workers,
+            start,
+        )
+        .await;
+
+        let workers = self.active_workers().iter().collect::<Vec<_>>();
+        for (worker_id, worker) in workers {
+            worker.stop().await;
+            worker.oplog().commit(CommitLevel::Immediate).await;
+            self.active_workers().remove(&worker_id);
+        }
+
+        let shard_assignment = self.shard_service().current_assignment()?;
+        self.shard_service()
+            .revoke_shards(&shard_assignment.shard_ids)?;
+        self.worker_service().on_shards_revoked().await;
+        self.shard_manager_service()
+            .unregister(self.host.clone(), self.port)
+            .await?;
+
+        info!(elapsed = ?start.elapsed(), suspended_workers, "Worker executor drained");
+        report_drain_progress(progress, DrainPhase::Drained, 0, suspended_workers, start).await;
+
+        Ok(())
+    }
+
+    async fn workers_with_in_flight_invocations(&self) -> Vec<Arc<Worker<Ctx>>> {
+        let mut result = Vec::new();
+        for (_, worker) in self.active_workers().iter() {
+            if worker.has_in_flight_invocations().await {
+                result.push(worker);
+            }
+        }
+        result
+    }
+}
+
+impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync + 'static> UsesAllDeps
+    for WorkerExecutorImpl<Ctx, Svcs>
+{
+    type Ctx = Ctx;
+
+    fn all(&self) -> &All<Ctx> {
+        self.services.all()
+    }
+}
+
+#[tonic::async_trait]
+impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync + 'static>
+    WorkerExecutor for WorkerExecutorImpl<Ctx, Svcs>
+{
+    async fn create_worker(
+        &self,
+        request: Request<golem::workerexecutor::v1::CreateWorkerRequest>,
+    ) -> Result<Response<golem::workerexecutor::v1::CreateWorkerResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "create_worker",
+            worker_id = proto_worker_id_string(&request.worker_id),
+            component_version = request.component_version,
+            account_id = proto_account_id_string(&request.account_id),
+        );
+
+        match self
+            .create_worker_internal(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(_) => record.succeed(Ok(Response::new(
+                golem::workerexecutor::v1::CreateWorkerResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::create_worker_response::Result::Success(
+                            golem::common::Empty {},
+                        ),
+                    ),
+                },
+            ))),
+            Err(err) => record.fail(
+                Ok(Response::new(
+                    golem::workerexecutor::v1::CreateWorkerResponse {
+                        result: Some(
+                            golem::workerexecutor::v1::create_worker_response::Result::Failure(
+                                err.clone().into(),
+                            ),
+                        ),
+                    },
+                )),
+                &err,
+            ),
+        }
+    }
+
+    async fn invoke_and_await_worker(
+        &self,
+        request: Request<InvokeAndAwaitWorkerRequest>,
+    ) -> Result<Response<golem::workerexecutor::v1::InvokeAndAwaitWorkerResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "invoke_and_await_worker",
+            worker_id = proto_target_worker_id_string(&request.worker_id),
+            idempotency_key = proto_idempotency_key_string(&request.idempotency_key),
+            account_id = proto_account_id_string(&request.account_id),
+        );
+
+        match self.invoke_and_await_worker_internal_proto(&request).instrument(record.span.clone()).await {
+            Ok(output) => {
+                let result = InvokeAndAwaitWorkerSuccess { output };
+
+                record.succeed(Ok(Response::new(
+                    golem::workerexecutor::v1::InvokeAndAwaitWorkerResponse {
+                        result: Some(
+                            golem::workerexecutor::v1::invoke_and_await_worker_response::Result::Success(result),
+                        ),
+                    },
+                )))
+            }
+            Err(err) => record.fail(
+                Ok(Response::new(
+                    golem::workerexecutor::v1::InvokeAndAwaitWorkerResponse {
+                        result: Some(
+                            golem::workerexecutor::v1::invoke_and_await_worker_response::Result::Failure(
+                                err.clone().into(),
+                            ),
+                        ),
+                    },
+                )),
+                &err,
+            ),
+        }
+    }
+
+    async fn invoke_and_await_worker_typed(
+        &self,
+        request: Request<InvokeAndAwaitWorkerRequest>,
+    ) -> Result<Response<InvokeAndAwaitWorkerResponseTyped>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "invoke_and_await_worker_json_typed",
+            worker_id = proto_target_worker_id_string(&request.worker_id),
+            idempotency_key = proto_idempotency_key_string(&request.idempotency_key),
+            account_id = proto_account_id_string(&request.account_id),
+        );
+
+        match self.invoke_and_await_worker_internal_typed(&request).instrument(record.span.clone()).await {
+            Ok(type_annotated_value) => {
+                let result = golem::workerexecutor::v1::InvokeAndAwaitWorkerSuccessTyped {
+                    output: Some(golem_wasm_rpc::protobuf::TypeAnnotatedValue {
+                        type_annotated_value: Some(type_annotated_value),
+                    })
+                };
+
+                record.succeed(Ok(Response::new(
+                    golem::workerexecutor::v1::InvokeAndAwaitWorkerResponseTyped {
+                        result: Some(
+                            golem::workerexecutor::v1::invoke_and_await_worker_response_typed::Result::Success(result),
+                        ),
+                    },
+                )))
+            }
+            Err(err) => record.fail(
+                Ok(Response::new(
+                    golem::workerexecutor::v1::InvokeAndAwaitWorkerResponseTyped {
+                        result: Some(
+                            golem::workerexecutor::v1::invoke_and_await_worker_response_typed::Result::Failure(
+                                err.clone().into(),
+                            ),
+                        ),
+                    },
+                )),
+                &err,
+            ),
+        }
+    }
+
+    async fn invoke_and_poll_worker(
+        &self,
+        request: Request<golem::workerexecutor::v1::InvokeAndPollWorkerRequest>,
+    ) -> Result<Response<golem::workerexecutor::v1::PollWorkerInvocationResponse>, Status> {
+        let request = request.into_inner();
+        let invocation = request.invocation.as_ref();
+        let record = recorded_grpc_api_request!(
+            "invoke_and_poll_worker",
+            worker_id =
+                proto_target_worker_id_string(&invocation.and_then(|i| i.worker_id.clone())),
+            idempotency_key =
+                proto_idempotency_key_string(&invocation.and_then(|i| i.idempotency_key.clone())),
+            account_id = proto_account_id_string(&invocation.and_then(|i| i.account_id.clone())),
+        );
+
+        match self
+            .invoke_and_poll_worker_internal(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok((idempotency_key, output)) => record.succeed(Ok(Response::new(
+                poll_worker_invocation_response(idempotency_key, output),
+            ))),
+            Err(err) => record.fail(
+                Ok(Response::new(
+                    golem::workerexecutor::v1::PollWorkerInvocationResponse {
+                        result: Some(
+                            golem::workerexecutor::v1::poll_worker_invocation_response::Result::Failure(
+                                err.clone().into(),
+                            ),
+                        ),
+                    },
+                )),
+                &err,
+            ),
+        }
+    }
+
+    async fn await_worker_invocation(
+        &self,
+        request: Request<golem::workerexecutor::v1::AwaitWorkerInvocationRequest>,
+    ) -> Result<Response<golem::workerexecutor::v1::PollWorkerInvocationResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "await_worker_invocation",
+            worker_id = proto_worker_id_string(&request.worker_id),
+            idempotency_key = proto_idempotency_key_string(&request.idempotency_key),
+            account_id = proto_account_id_string(&request.account_id),
+        );
+
+        match self
+            .await_worker_invocation_internal(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok((idempotency_key, output)) => record.succeed(Ok(Response::new(
+                poll_worker_invocation_response(idempotency_key, output),
+            ))),
+            Err(err) => record.fail(
+                Ok(Response::new(
+                    golem::workerexecutor::v1::PollWorkerInvocationResponse {
+                        result: Some(
+                            golem::workerexecutor::v1::poll_worker_invocation_response::Result::Failure(
+                                err.clone().into(),
+                            ),
+                        ),
+                    },
+                )),
+                &err,
+            ),
+        }
+    }
+
+    type InvokeAndStreamWorkerStream = InvokeAndStreamWorkerResponseStream;
+
+    async fn invoke_and_stream_worker(
+        &self,
+        request: Request<InvokeAndAwaitWorkerRequest>,
+    ) -> ResponseResult<Self::InvokeAndStreamWorkerStream> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "invoke_and_stream_worker",
+            worker_id = proto_target_worker_id_string(&request.worker_id),
+            idempotency_key = proto_idempotency_key_string(&request.idempotency_key),
+            account_id = proto_account_id_string(&request.account_id),
+        );
+
+        let (sender, receiver) = mpsc::channel(16);
+        let this = self.clone();
+        let span = record.span.clone();
+
+        tokio::spawn(
+            async move {
+                match this.invoke_and_stream_worker_internal(request, &sender).await {
+                    Ok(type_annotated_value) => {
+                        let result = golem::workerexecutor::v1::InvokeAndAwaitWorkerSuccessTyped {
+                            output: Some(golem_wasm_rpc::protobuf::TypeAnnotatedValue {
+                                type_annotated_value: Some(type_annotated_value),
+                            }),
+                        };
+                        let _ = sender
+                            .send(Ok(golem::workerexecutor::v1::InvokeAndStreamWorkerResponse {
+                                result: Some(
+                                    golem::workerexecutor::v1::invoke_and_stream_worker_response::Result::Done(result),
+                                ),
+                            }))
+                            .await;
+                        record.succeed(())
+                    }
+                    Err(err) => {
+                        let _ = sender
+                            .send(Ok(golem::workerexecutor::v1::InvokeAndStreamWorkerResponse {
+                                result: Some(
+                                    golem::workerexecutor::v1::invoke_and_stream_worker_response::Result::Failure(
+                                        err.clone().into(),
+                                    ),
+                                ),
+                            }))
+                            .await;
+                        record.fail((), &err)
+                    }
+                }
+            }
+            .instrument(span),
+        );
+
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+
+    async fn invoke_worker(
+        &self,
+        request: Request<golem::workerexecutor::v1::InvokeWorkerRequest>,
+    ) -> Result<Response<golem::workerexecutor::v1::InvokeWorkerResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "invoke_worker",
+            worker_id = proto_target_worker_id_string(&request.worker_id),
+            function = request.name,
+            account_id = proto_account_id_string(&request.account_id)
+        );
+
+        match self
+            .invoke_worker_internal(&request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(_) => record.succeed(Ok(Response::new(
+                golem::workerexecutor::v1::InvokeWorkerResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::invoke_worker_response::Result::Success(
+                            golem::common::Empty {},
+                        ),
+                    ),
+                },
+            ))),
+            Err(err) => record.fail(
+                Ok(Response::new(
+                    golem::workerexecutor::v1::InvokeWorkerResponse {
+                        result: Some(
+                            golem::workerexecutor::v1::invoke_worker_response::Result::Failure(
+                                err.clone().into(),
+                            ),
+                        ),
+                    },
+                )),
+                &err,
+            ),
+        }
+    }
+
+    type ConnectWorkerStream = ResponseStream;
+
+    async fn connect_worker(
+        &self,
+        request: Request<golem::workerexecutor::v1::ConnectWorkerRequest>,
+    ) -> ResponseResult<Self::ConnectWorkerStream> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "connect_worker",
+            worker_id = proto_worker_id_string(&request.worker_id),
+            account_id = proto_account_id_string(&request.account_id)
+        );
+
+        self.connect_worker_internal(request)
+            .instrument(record.span.clone())
+            .await
+    }
+
+    async fn delete_worker(
+        &self,
+        request: Request<golem::workerexecutor::v1::DeleteWorkerRequest>,
+    ) -> Result<Response<golem::workerexecutor::v1::DeleteWorkerResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "delete_worker",
+            worker_id = proto_worker_id_string(&request.worker_id)
+        );
+
+        match self
+            .delete_worker_internal(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(_) => record.succeed(Ok(Response::new(
+                golem::workerexecutor::v1::DeleteWorkerResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::delete_worker_response::Result::Success(
+                            golem::common::Empty {},
+                        ),
+                    ),
+                },
+            ))),
+            Err(err) => record.fail(
+                Ok(Response::new(
+                    golem::workerexecutor::v1::DeleteWorkerResponse {
+                        result: Some(
+                            golem::workerexecutor::v1::delete_worker_response::Result::Failure(
+                                err.clone().into(),
+                            ),
+                        ),
+                    },
+                )),
+                &err,
+            ),
+        }
+    }
+
+    async fn complete_promise(
+        &self,
+        request: Request<golem::workerexecutor::v1::CompletePromiseRequest>,
+    ) -> Result<Response<golem::workerexecutor::v1::CompletePromiseResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "complete_promise",
+            promise_id = proto_promise_id_string(&request.promise_id)
+        );
+
+        match self
+            .complete_promise_internal(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(success) => record.succeed(Ok(Response::new(
+                golem::workerexecutor::v1::CompletePromiseResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::complete_promise_response::Result::Success(
+                            success,
+                        ),
+                    ),
+                },
+            ))),
+            Err(err) => record.fail(
+                Ok(Response::new(
+                    golem::workerexecutor::v1::CompletePromiseResponse {
+                        result: Some(
+                            golem::workerexecutor::v1::complete_promise_response::Result::Failure(
+                                err.clone().into(),
+                            ),
+                        ),
+                    },
+                )),
+                &err,
+            ),
+        }
+    }
+
+    async fn interrupt_worker(
+        &self,
+        request: Request<golem::workerexecutor::v1::InterruptWorkerRequest>,
+    ) -> Result<Response<golem::workerexecutor::v1::InterruptWorkerResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "interrupt_worker",
+            worker_id = proto_worker_id_string(&request.worker_id),
+        );
+
+        match self
+            .interrupt_worker_internal(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(_) => record.succeed(Ok(Response::new(
+                golem::workerexecutor::v1::InterruptWorkerResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::interrupt_worker_response::Result::Success(
+                            golem::common::Empty {},
+                        ),
+                    ),
+                },
+            ))),
+            Err(err) => record.fail(
+                Ok(Response::new(
+                    golem::workerexecutor::v1::InterruptWorkerResponse {
+                        result: Some(
+                            golem::workerexecutor::v1::interrupt_worker_response::Result::Failure(
+                                err.clone().into(),
+                            ),
+                        ),
+                    },
+                )),
+                &err,
+            ),
+        }
+    }
+
+    async fn revoke_shards(
+        &self,
+        request: Request<golem::workerexecutor::v1::RevokeShardsRequest>,
+    ) -> Result<Response<golem::workerexecutor::v1::RevokeShardsResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!("revoke_shards",);
+
+        match self
+            .revoke_shards_internal(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(_) => record.succeed(Ok(Response::new(
+                golem::workerexecutor::v1::RevokeShardsResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::revoke_shards_response::Result::Success(
+                            golem::common::Empty {},
+                        ),
+                    ),
+                },
+            ))),
+            Err(err) => record.fail(
+                Ok(Response::new(
+                    golem::workerexecutor::v1::RevokeShardsResponse {
+                        result: Some(
+                            golem::workerexecutor::v1::revoke_shards_response::Result::Failure(
+                                err.clone().into(),
+                            ),
+                        ),
+                    },
+                )),
+                &err,
+            ),
+        }
+    }
+
+    async fn assign_shards(
+        &self,
+        request: Request<golem::workerexecutor::v1::AssignShardsRequest>,
+    ) -> Result<Response<golem::workerexecutor::v1::AssignShardsResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!("assign_shards",);
+
+        match self
+            .assign_shards_internal(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(_) => record.succeed(Ok(Response::new(
+                golem::workerexecutor::v1::AssignShardsResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::assign_shards_response::Result::Success(
+                            golem::common::Empty {},
+                        ),
+                    ),
+                },
+            ))),
+            Err(err) => record.fail(
+                Ok(Response::new(
+                    golem::workerexecutor::v1::AssignShardsResponse {
+                        result: Some(
+                            golem::workerexecutor::v1::assign_shards_response::Result::Failure(
+                                err.clone().into(),
+                            ),
+                        ),
+                    },
+                )),
+                &err,
+            ),
+        }
+    }
+
+    async fn get_worker_metadata(
+        &self,
+        request: Request<golem::workerexecutor::v1::GetWorkerMetadataRequest>,
+    ) -> Result<Response<golem::workerexecutor::v1::GetWorkerMetadataResponse>, Status> {
+        let request = request.into_inner();
+
+        let record = recorded_grpc_api_request!(
+            "get_worker_metadata",
+            worker_id = proto_worker_id_string(&request.worker_id)
+        );
+
+        let result = self
+            .get_worker_metadata_internal(request)
+            .instrument(record.span.clone())
+            .await;
+        match result {
+            Ok(result) => record.succeed(Ok(Response::new(
+                golem::workerexecutor::v1::GetWorkerMetadataResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::get_worker_metadata_response::Result::Success(
+                            result,
+                        ),
+                    ),
+                },
+            ))),
+            Err(err @ GolemError::WorkerNotFound { .. }) => record.succeed(Ok(Response::new(
+                golem::workerexecutor::v1::GetWorkerMetadataResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::get_worker_metadata_response::Result::Failure(
+                            err.clone().into(),
+                        ),
+                    ),
+                },
+            ))),
+            Err(err) => record.fail(
+                Ok(Response::new(
+                    golem::workerexecutor::v1::GetWorkerMetadataResponse {
+                        result: Some(
+                            golem::workerexecutor::v1::get_worker_metadata_response::Result::Failure(
+                                err.clone().into(),
+                            ),
+                        ),
+                    },
+                )),
+                &err,
+            ),
+        }
+    }
+
+    async fn get_workers_metadata_bulk(
+        &self,
+        request: Request<golem::workerexecutor::v1::GetWorkersMetadataBulkRequest>,
+    ) -> Result<Response<golem::workerexecutor::v1::GetWorkersMetadataBulkResponse>, Status> {
+        let request = request.into_inner();
+
+        let record = recorded_grpc_api_request!(
+            "get_workers_metadata_bulk",
+            worker_count = request.worker_ids.len()
+        );
+
+        let result = self
+            .get_workers_metadata_bulk_internal(request)
+            .instrument(record.span.clone())
+            .await;
+        match result {
+            Ok(results) => record.succeed(Ok(Response::new(
+                golem::workerexecutor::v1::GetWorkersMetadataBulkResponse {
+                    results: results
+                        .into_iter()
+                        .map(|result| golem::workerexecutor::v1::GetWorkerMetadataResponse {
+                            result: Some(match result {
+                                Ok(metadata) => golem::workerexecutor::v1::get_worker_metadata_response::Result::Success(metadata),
+                                Err(err) => golem::workerexecutor::v1::get_worker_metadata_response::Result::Failure(err.into()),
+                            }),
+                        })
+                        .collect(),
+                },
+            ))),
+            Err(err) => record.fail(Err(err.clone().into()), &err),
+        }
+    }
+
+    async fn resume_worker(
+        &self,
+        request: Request<golem::workerexecutor::v1::ResumeWorkerRequest>,
+    ) -> Result<Response<golem::workerexecutor::v1::ResumeWorkerResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "resume_worker",
+            worker_id = proto_worker_id_string(&request.worker_id),
+        );
+
+        match self
+            .resume_worker_internal(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(_) => record.succeed(Ok(Response::new(
+                golem::workerexecutor::v1::ResumeWorkerResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::resume_worker_response::Result::Success(
+                            golem::common::Empty {},
+                        ),
+                    ),
+                },
+            ))),
+            Err(err) => record.fail(
+                Ok(Response::new(
+                    golem::workerexecutor::v1::ResumeWorkerResponse {
+                        result: Some(
+                            golem::workerexecutor::v1::resume_worker_response::Result::Failure(
+                                err.clone().into(),
+                            ),
+                        ),
+                    },
+                )),
+                &err,
+            ),
+        }
+    }
+
+    async fn get_running_workers_metadata(
+        &self,
+        request: Request<GetRunningWorkersMetadataRequest>,
+    ) -> Result<Response<GetRunningWorkersMetadataResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "get_running_workers_metadata",
+            component_id = proto_component_id_string(&request.component_id),
+        );
+
+        let result = self
+            .get_running_workers_metadata_internal(request)
+            .instrument(record.span.clone())
+            .await;
+        match result {
+            Ok(workers) => record.succeed(Ok(Response::new(
+                golem::workerexecutor::v1::GetRunningWorkersMetadataResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::get_running_workers_metadata_response::Result::Success(
+                            golem::workerexecutor::v1::GetRunningWorkersMetadataSuccessResponse {
+                                workers
+                            }
+                        ),
+                    ),
+                },
+            ))),
+            Err(err) => record.fail(
+                Ok(Response::new(
+                    GetRunningWorkersMetadataResponse {
+                        result: Some(
+                            golem::workerexecutor::v1::get_running_workers_metadata_response::Result::Failure(
+                                err.clone().into(),
+                            ),
+                        ),
+                    },
+                )),
+                &err,
+            ),
+        }
+    }
+
+    async fn get_workers_metadata(
+        &self,
+        request: Request<GetWorkersMetadataRequest>,
+    ) -> Result<Response<GetWorkersMetadataResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "get_workers_metadata",
+            component_id = proto_component_id_string(&request.component_id),
+        );
+
+        let result = self
+            .get_workers_metadata_internal(request)
+            .instrument(record.span.clone())
+            .await;
+        match result {
+            Ok((cursor, workers)) => {
+                record.succeed(Ok(Response::new(GetWorkersMetadataResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::get_workers_metadata_response::Result::Success(
+                            golem::workerexecutor::v1::GetWorkersMetadataSuccessResponse {
+                                workers,
+                                cursor,
+                            },
+                        ),
+                    ),
+                })))
+            }
+            Err(err) => record.fail(
+                Ok(Response::new(GetWorkersMetadataResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::get_workers_metadata_response::Result::Failure(
+                            err.clone().into(),
+                        ),
+                    ),
+                })),
+                &err,
+            ),
         }
-        for successful_update in &latest_status.successful_updates {
-            updates.push(golem::worker::UpdateRecord {
-                timestamp: Some(successful_update.timestamp.into()),
-                target_version: successful_update.target_version,
-                update: Some(golem::worker::update_record::Update::Successful(
-                    golem::worker::SuccessfulUpdate {},
-                )),
-            });
+    }
+
+    async fn get_component_worker_statistics(
+        &self,
+        request: Request<GetComponentWorkerStatisticsRequest>,
+    ) -> Result<Response<GetComponentWorkerStatisticsResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "get_component_worker_statistics",
+            component_id = proto_component_id_string(&request.component_id),
+        );
+
+        let result = self
+            .get_component_worker_statistics_internal(request)
+            .instrument(record.span.clone())
+            .await;
+        match result {
+            Ok(statistics) => record.succeed(Ok(Response::new(
+                GetComponentWorkerStatisticsResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::get_component_worker_statistics_response::Result::Success(
+                            statistics.into(),
+                        ),
+                    ),
+                },
+            ))),
+            Err(err) => record.fail(
+                Ok(Response::new(GetComponentWorkerStatisticsResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::get_component_worker_statistics_response::Result::Failure(
+                            err.clone().into(),
+                        ),
+                    ),
+                })),
+                &err,
+            ),
         }
-        for failed_update in &latest_status.failed_updates {
-            updates.push(golem::worker::UpdateRecord {
-                timestamp: Some(failed_update.timestamp.into()),
-                target_version: failed_update.target_version,
-                update: Some(golem::worker::update_record::Update::Failed(
-                    golem::worker::FailedUpdate {
-                        details: failed_update.details.clone(),
-                    },
-                )),
-            });
+    }
+
+    async fn update_worker(
+        &self,
+        request: Request<UpdateWorkerRequest>,
+    ) -> Result<Response<UpdateWorkerResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "update_worker",
+            worker_id = proto_worker_id_string(&request.worker_id),
+            target_version = request.target_version,
+        );
+
+        match self
+            .update_worker_internal(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(_) => record.succeed(Ok(Response::new(UpdateWorkerResponse {
+                result: Some(
+                    golem::workerexecutor::v1::update_worker_response::Result::Success(
+                        golem::common::Empty {},
+                    ),
+                ),
+            }))),
+            Err(err) => record.fail(
+                Ok(Response::new(UpdateWorkerResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::update_worker_response::Result::Failure(
+                            err.clone().into(),
+                        ),
+                    ),
+                })),
+                &err,
+            ),
+        }
+    }
+
+    async fn dry_run_update_worker(
+        &self,
+        request: Request<DryRunUpdateWorkerRequest>,
+    ) -> Result<Response<DryRunUpdateWorkerResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "dry_run_update_worker",
+            worker_id = proto_worker_id_string(&request.worker_id),
+            target_version = request.target_version,
+        );
+
+        match self
+            .dry_run_update_worker_internal(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(error) => record.succeed(Ok(Response::new(DryRunUpdateWorkerResponse {
+                result: Some(
+                    golem::workerexecutor::v1::dry_run_update_worker_response::Result::Success(
+                        UpdateDryRunResult {
+                            would_succeed: error.is_none(),
+                            error,
+                        },
+                    ),
+                ),
+            }))),
+            Err(err) => record.fail(
+                Ok(Response::new(DryRunUpdateWorkerResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::dry_run_update_worker_response::Result::Failure(
+                            err.clone().into(),
+                        ),
+                    ),
+                })),
+                &err,
+            ),
+        }
+    }
+
+    async fn get_oplog(
+        &self,
+        request: Request<GetOplogRequest>,
+    ) -> Result<Response<GetOplogResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "get_oplog",
+            worker_id = proto_worker_id_string(&request.worker_id),
+        );
+
+        let result = self
+            .get_oplog_internal(request)
+            .instrument(record.span.clone())
+            .await;
+        match result {
+            Ok(response) => record.succeed(Ok(Response::new(response))),
+            Err(err) => record.fail(
+                Ok(Response::new(GetOplogResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::get_oplog_response::Result::Failure(
+                            err.clone().into(),
+                        ),
+                    ),
+                })),
+                &err,
+            ),
+        }
+    }
+
+    async fn redecode_corrupted_oplog_entries(
+        &self,
+        request: Request<RedecodeCorruptedOplogEntriesRequest>,
+    ) -> Result<Response<RedecodeCorruptedOplogEntriesResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "redecode_corrupted_oplog_entries",
+            worker_id = proto_worker_id_string(&request.worker_id),
+        );
+
+        let result = self
+            .redecode_corrupted_oplog_entries_internal(request)
+            .instrument(record.span.clone())
+            .await;
+        match result {
+            Ok(entries) => record.succeed(Ok(Response::new(
+                RedecodeCorruptedOplogEntriesResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::redecode_corrupted_oplog_entries_response::Result::Success(
+                            golem::workerexecutor::v1::RedecodeCorruptedOplogEntriesSuccessResponse {
+                                entries,
+                            },
+                        ),
+                    ),
+                },
+            ))),
+            Err(err) => record.fail(
+                Ok(Response::new(RedecodeCorruptedOplogEntriesResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::redecode_corrupted_oplog_entries_response::Result::Failure(
+                            err.clone().into(),
+                        ),
+                    ),
+                })),
+                &err,
+            ),
+        }
+    }
+
+    async fn update_component_observability(
+        &self,
+        request: Request<UpdateComponentObservabilityRequest>,
+    ) -> Result<Response<UpdateComponentObservabilityResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "update_component_observability",
+            component_id = proto_component_id_string(&request.component_id),
+        );
+
+        match self
+            .update_component_observability_internal(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(_) => record.succeed(Ok(Response::new(UpdateComponentObservabilityResponse {
+                result: Some(
+                    golem::workerexecutor::v1::update_component_observability_response::Result::Success(
+                        golem::common::Empty {},
+                    ),
+                ),
+            }))),
+            Err(err) => record.fail(
+                Ok(Response::new(UpdateComponentObservabilityResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::update_component_observability_response::Result::Failure(
+                            err.clone().into(),
+                        ),
+                    ),
+                })),
+                &err,
+            ),
+        }
+    }
+
+    async fn get_worker_status(
+        &self,
+        request: Request<GetWorkerStatusRequest>,
+    ) -> Result<Response<GetWorkerStatusResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "get_worker_status",
+            worker_id = proto_worker_id_string(&request.worker_id),
+        );
+
+        let result = self
+            .get_worker_status_internal(request)
+            .instrument(record.span.clone())
+            .await;
+        match result {
+            Ok(status) => record.succeed(Ok(Response::new(GetWorkerStatusResponse {
+                result: Some(
+                    golem::workerexecutor::v1::get_worker_status_response::Result::Success(status),
+                ),
+            }))),
+            Err(err @ GolemError::WorkerNotFound { .. }) => {
+                record.succeed(Ok(Response::new(GetWorkerStatusResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::get_worker_status_response::Result::Failure(
+                            err.clone().into(),
+                        ),
+                    ),
+                })))
+            }
+            Err(err) => record.fail(
+                Ok(Response::new(GetWorkerStatusResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::get_worker_status_response::Result::Failure(
+                            err.clone().into(),
+                        ),
+                    ),
+                })),
+                &err,
+            ),
+        }
+    }
+
+    async fn get_pending_invocation_count(
+        &self,
+        request: Request<GetPendingInvocationCountRequest>,
+    ) -> Result<Response<GetPendingInvocationCountResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "get_pending_invocation_count",
+            worker_id = proto_worker_id_string(&request.worker_id),
+        );
+
+        let result = self
+            .get_pending_invocation_count_internal(request)
+            .instrument(record.span.clone())
+            .await;
+        match result {
+            Ok(count) => record.succeed(Ok(Response::new(GetPendingInvocationCountResponse {
+                result: Some(
+                    golem::workerexecutor::v1::get_pending_invocation_count_response::Result::Success(count),
+                ),
+            }))),
+            Err(err @ GolemError::WorkerNotFound { .. }) => {
+                record.succeed(Ok(Response::new(GetPendingInvocationCountResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::get_pending_invocation_count_response::Result::Failure(
+                            err.clone().into(),
+                        ),
+                    ),
+                })))
+            }
+            Err(err) => record.fail(
+                Ok(Response::new(GetPendingInvocationCountResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::get_pending_invocation_count_response::Result::Failure(
+                            err.clone().into(),
+                        ),
+                    ),
+                })),
+                &err,
+            ),
         }
-        updates.sort_by_key(|record| {
-            record.timestamp.as_ref().unwrap().seconds * 1_000_000_000
-                + record.timestamp.as_ref().unwrap().nanos as i64
-        });
+    }
 
-        let mut owned_resources = HashMap::new();
-        for (resource_id, resource) in metadata.last_known_status.owned_resources {
-            owned_resources.insert(
-                resource_id.0,
-                ResourceMetadata {
-                    created_at: Some(resource.created_at.into()),
-                    indexed: resource.indexed_resource_key.map(|t| t.into()),
-                },
-            );
+    async fn get_worker_fuel_consumption(
+        &self,
+        request: Request<GetWorkerFuelConsumptionRequest>,
+    ) -> Result<Response<GetWorkerFuelConsumptionResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "get_worker_fuel_consumption",
+            worker_id = proto_worker_id_string(&request.worker_id),
+        );
+
+        let result = self
+            .get_worker_fuel_consumption_internal(request)
+            .instrument(record.span.clone())
+            .await;
+        match result {
+            Ok(consumption) => record.succeed(Ok(Response::new(GetWorkerFuelConsumptionResponse {
+                result: Some(
+                    golem::workerexecutor::v1::get_worker_fuel_consumption_response::Result::Success(consumption.into()),
+                ),
+            }))),
+            Err(err @ GolemError::WorkerNotFound { .. }) => {
+                record.succeed(Ok(Response::new(GetWorkerFuelConsumptionResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::get_worker_fuel_consumption_response::Result::Failure(
+                            err.clone().into(),
+                        ),
+                    ),
+                })))
+            }
+            Err(err) => record.fail(
+                Ok(Response::new(GetWorkerFuelConsumptionResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::get_worker_fuel_consumption_response::Result::Failure(
+                            err.clone().into(),
+                        ),
+                    ),
+                })),
+                &err,
+            ),
         }
+    }
 
-        golem::worker::WorkerMetadata {
-            worker_id: Some(metadata.worker_id.into()),
-            args: metadata.args.clone(),
-            env: HashMap::from_iter(metadata.env.iter().cloned()),
-            account_id: Some(metadata.account_id.into()),
-            component_version: latest_status.component_version,
-            status: Into::<golem::worker::WorkerStatus>::into(latest_status.status).into(),
-            retry_count: last_error_and_retry_count
-                .as_ref()
-                .map(|last_error| last_error.retry_count)
-                .unwrap_or_default(),
+    async fn get_worker_replay_divergences(
+        &self,
+        request: Request<GetWorkerReplayDivergencesRequest>,
+    ) -> Result<Response<GetWorkerReplayDivergencesResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "get_worker_replay_divergences",
+            worker_id = proto_worker_id_string(&request.worker_id),
+        );
 
-            pending_invocation_count: latest_status.pending_invocations.len() as u64,
-            updates,
-            created_at: Some(metadata.created_at.into()),
-            last_error: last_error_and_retry_count
-                .map(|last_error| last_error.error.to_string(&last_error.stderr)),
-            component_size: metadata.last_known_status.component_size,
-            total_linear_memory_size: metadata.last_known_status.total_linear_memory_size,
-            owned_resources,
+        let result = self
+            .get_worker_replay_divergences_internal(request)
+            .instrument(record.span.clone())
+            .await;
+        match result {
+            Ok(divergences) => record.succeed(Ok(Response::new(GetWorkerReplayDivergencesResponse {
+                result: Some(
+                    golem::workerexecutor::v1::get_worker_replay_divergences_response::Result::Success(
+                        WorkerReplayDivergences {
+                            divergences: divergences.into_iter().map(|d| d.into()).collect(),
+                        },
+                    ),
+                ),
+            }))),
+            Err(err @ GolemError::WorkerNotFound { .. }) => {
+                record.succeed(Ok(Response::new(GetWorkerReplayDivergencesResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::get_worker_replay_divergences_response::Result::Failure(
+                            err.clone().into(),
+                        ),
+                    ),
+                })))
+            }
+            Err(err) => record.fail(
+                Ok(Response::new(GetWorkerReplayDivergencesResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::get_worker_replay_divergences_response::Result::Failure(
+                            err.clone().into(),
+                        ),
+                    ),
+                })),
+                &err,
+            ),
         }
     }
-}
 
-impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync + 'static> UsesAllDeps
-    for WorkerExecutorImpl<Ctx, Svcs>
-{
-    type Ctx = Ctx;
+    async fn delete_oplog_region(
+        &self,
+        request: Request<DeleteOplogRegionRequest>,
+    ) -> Result<Response<DeleteOplogRegionResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "delete_oplog_region",
+            worker_id = proto_worker_id_string(&request.worker_id),
+            start = request.start,
+            end = request.end,
+        );
 
-    fn all(&self) -> &All<Ctx> {
-        self.services.all()
+        let result = self
+            .delete_oplog_region_internal(request)
+            .instrument(record.span.clone())
+            .await;
+        match result {
+            Ok(oplog_index) => record.succeed(Ok(Response::new(DeleteOplogRegionResponse {
+                result: Some(
+                    golem::workerexecutor::v1::delete_oplog_region_response::Result::Success(
+                        DeleteOplogRegionSuccess {
+                            oplog_index: oplog_index.into(),
+                        },
+                    ),
+                ),
+            }))),
+            Err(err) => record.fail(
+                Ok(Response::new(DeleteOplogRegionResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::delete_oplog_region_response::Result::Failure(
+                            err.clone().into(),
+                        ),
+                    ),
+                })),
+                &err,
+            ),
+        }
     }
-}
 
-#[tonic::async_trait]
-impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync + 'static>
-    WorkerExecutor for WorkerExecutorImpl<Ctx, Svcs>
-{
-    async fn create_worker(
+    async fn revive_worker(
         &self,
-        request: Request<golem::workerexecutor::v1::CreateWorkerRequest>,
-    ) -> Result<Response<golem::workerexecutor::v1::CreateWorkerResponse>, Status> {
+        request: Request<ReviveWorkerRequest>,
+    ) -> Result<Response<ReviveWorkerResponse>, Status> {
         let request = request.into_inner();
         let record = recorded_grpc_api_request!(
-            "create_worker",
+            "revive_worker",
             worker_id = proto_worker_id_string(&request.worker_id),
-            component_version = request.component_version,
-            account_id = proto_account_id_string(&request.account_id),
+            trim_failed_invocation = request.trim_failed_invocation,
         );
 
-        match self
-            .create_worker_internal(request)
+        let result = self
+            .revive_worker_internal(request)
             .instrument(record.span.clone())
-            .await
-        {
-            Ok(_) => record.succeed(Ok(Response::new(
-                golem::workerexecutor::v1::CreateWorkerResponse {
+            .await;
+        match result {
+            Ok(oplog_index) => record.succeed(Ok(Response::new(ReviveWorkerResponse {
+                result: Some(
+                    golem::workerexecutor::v1::revive_worker_response::Result::Success(
+                        ReviveWorkerSuccess {
+                            oplog_index: oplog_index.into(),
+                        },
+                    ),
+                ),
+            }))),
+            Err(err) => record.fail(
+                Ok(Response::new(ReviveWorkerResponse {
                     result: Some(
-                        golem::workerexecutor::v1::create_worker_response::Result::Success(
-                            golem::common::Empty {},
+                        golem::workerexecutor::v1::revive_worker_response::Result::Failure(
+                            err.clone().into(),
                         ),
                     ),
-                },
-            ))),
+                })),
+                &err,
+            ),
+        }
+    }
+
+    async fn update_worker_environment(
+        &self,
+        request: Request<UpdateWorkerEnvironmentRequest>,
+    ) -> Result<Response<UpdateWorkerEnvironmentResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "update_worker_environment",
+            worker_id = proto_worker_id_string(&request.worker_id),
+            update_args = request.update_args,
+        );
+
+        let result = self
+            .update_worker_environment_internal(request)
+            .instrument(record.span.clone())
+            .await;
+        match result {
+            Ok(oplog_index) => record.succeed(Ok(Response::new(UpdateWorkerEnvironmentResponse {
+                result: Some(
+                    golem::workerexecutor::v1::update_worker_environment_response::Result::Success(
+                        UpdateWorkerEnvironmentSuccess {
+                            oplog_index: oplog_index.into(),
+                        },
+                    ),
+                ),
+            }))),
             Err(err) => record.fail(
-                Ok(Response::new(
-                    golem::workerexecutor::v1::CreateWorkerResponse {
-                        result: Some(
-                            golem::workerexecutor::v1::create_worker_response::Result::Failure(
-                                err.clone().into(),
-                            ),
+                Ok(Response::new(UpdateWorkerEnvironmentResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::update_worker_environment_response::Result::Failure(
+                            err.clone().into(),
                         ),
-                    },
-                )),
+                    ),
+                })),
                 &err,
             ),
         }
     }
 
-    async fn invoke_and_await_worker(
+    async fn fork_worker(
         &self,
-        request: Request<InvokeAndAwaitWorkerRequest>,
-    ) -> Result<Response<golem::workerexecutor::v1::InvokeAndAwaitWorkerResponse>, Status> {
+        request: Request<ForkWorkerRequest>,
+    ) -> Result<Response<ForkWorkerResponse>, Status> {
         let request = request.into_inner();
         let record = recorded_grpc_api_request!(
-            "invoke_and_await_worker",
-            worker_id = proto_target_worker_id_string(&request.worker_id),
-            idempotency_key = proto_idempotency_key_string(&request.idempotency_key),
-            account_id = proto_account_id_string(&request.account_id),
+            "fork_worker",
+            source_worker_id = proto_worker_id_string(&request.source_worker_id),
+            target_worker_id = proto_worker_id_string(&request.target_worker_id),
+            oplog_index = request.oplog_index,
         );
 
-        match self.invoke_and_await_worker_internal_proto(&request).instrument(record.span.clone()).await {
-            Ok(output) => {
-                let result = InvokeAndAwaitWorkerSuccess { output };
+        let result = self
+            .fork_worker_internal(request)
+            .instrument(record.span.clone())
+            .await;
+        match result {
+            Ok(oplog_index) => record.succeed(Ok(Response::new(ForkWorkerResponse {
+                result: Some(
+                    golem::workerexecutor::v1::fork_worker_response::Result::Success(
+                        ForkWorkerSuccess {
+                            oplog_index: oplog_index.into(),
+                        },
+                    ),
+                ),
+            }))),
+            Err(err) => record.fail(
+                Ok(Response::new(ForkWorkerResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::fork_worker_response::Result::Failure(
+                            err.clone().into(),
+                        ),
+                    ),
+                })),
+                &err,
+            ),
+        }
+    }
 
-                record.succeed(Ok(Response::new(
-                    golem::workerexecutor::v1::InvokeAndAwaitWorkerResponse {
-                        result: Some(
-                            golem::workerexecutor::v1::invoke_and_await_worker_response::Result::Success(result),
+    async fn query_worker_at_oplog_index(
+        &self,
+        request: Request<QueryWorkerAtOplogIndexRequest>,
+    ) -> Result<Response<QueryWorkerAtOplogIndexResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "query_worker_at_oplog_index",
+            worker_id = proto_worker_id_string(&request.worker_id),
+            oplog_index = request.oplog_index,
+            function = request.name,
+        );
+
+        let result = self
+            .query_worker_at_oplog_index_internal(request)
+            .instrument(record.span.clone())
+            .await;
+        match result {
+            Ok((oplog_index, output)) => {
+                record.succeed(Ok(Response::new(QueryWorkerAtOplogIndexResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::query_worker_at_oplog_index_response::Result::Success(
+                            QueryWorkerAtOplogIndexSuccess {
+                                oplog_index: oplog_index.into(),
+                                output: Some(output),
+                            },
                         ),
-                    },
-                )))
+                    ),
+                })))
             }
             Err(err) => record.fail(
-                Ok(Response::new(
-                    golem::workerexecutor::v1::InvokeAndAwaitWorkerResponse {
-                        result: Some(
-                            golem::workerexecutor::v1::invoke_and_await_worker_response::Result::Failure(
-                                err.clone().into(),
-                            ),
+                Ok(Response::new(QueryWorkerAtOplogIndexResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::query_worker_at_oplog_index_response::Result::Failure(
+                            err.clone().into(),
+                        ),
+                    ),
+                })),
+                &err,
+            ),
+        }
+    }
+
+    async fn update_worker_virtual_clock(
+        &self,
+        request: Request<UpdateWorkerVirtualClockRequest>,
+    ) -> Result<Response<UpdateWorkerVirtualClockResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "update_worker_virtual_clock",
+            worker_id = proto_worker_id_string(&request.worker_id),
+        );
+
+        let result = self
+            .update_worker_virtual_clock_internal(request)
+            .instrument(record.span.clone())
+            .await;
+        match result {
+            Ok(time_millis) => record.succeed(Ok(Response::new(UpdateWorkerVirtualClockResponse {
+                result: Some(
+                    golem::workerexecutor::v1::update_worker_virtual_clock_response::Result::Success(
+                        WorkerVirtualClock { time_millis },
+                    ),
+                ),
+            }))),
+            Err(err) => record.fail(
+                Ok(Response::new(UpdateWorkerVirtualClockResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::update_worker_virtual_clock_response::Result::Failure(
+                            err.clone().into(),
                         ),
-                    },
-                )),
+                    ),
+                })),
                 &err,
             ),
         }
     }
 
-    async fn invoke_and_await_worker_typed(
+    type DrainStream = DrainResponseStream;
+
+    async fn drain(&self, request: Request<DrainRequest>) -> ResponseResult<Self::DrainStream> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!("drain",);
+
+        let (sender, receiver) = mpsc::channel(16);
+        let this = self.clone();
+        let span = record.span.clone();
+
+        // Draining keeps going even if the caller disconnects from the progress stream
+        tokio::spawn(
+            async move {
+                match this.drain_internal(request, &sender).await {
+                    Ok(()) => record.succeed(()),
+                    Err(err) => {
+                        let _ = sender
+                            .send(Ok(DrainResponse {
+                                result: Some(
+                                    golem::workerexecutor::v1::drain_response::Result::Failure(
+                                        err.clone().into(),
+                                    ),
+                                ),
+                            }))
+                            .await;
+                        record.fail((), &err)
+                    }
+                }
+            }
+            .instrument(span),
+        );
+
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+
+    async fn put_secret(
         &self,
-        request: Request<InvokeAndAwaitWorkerRequest>,
-    ) -> Result<Response<InvokeAndAwaitWorkerResponseTyped>, Status> {
+        request: Request<PutSecretRequest>,
+    ) -> Result<Response<PutSecretResponse>, Status> {
         let request = request.into_inner();
         let record = recorded_grpc_api_request!(
-            "invoke_and_await_worker_json_typed",
-            worker_id = proto_target_worker_id_string(&request.worker_id),
-            idempotency_key = proto_idempotency_key_string(&request.idempotency_key),
-            account_id = proto_account_id_string(&request.account_id),
+            "put_secret",
+            component_id = proto_component_id_string(&request.component_id),
+            secret_name = %request.name,
         );
 
-        match self.invoke_and_await_worker_internal_typed(&request).instrument(record.span.clone()).await {
-            Ok(type_annotated_value) => {
-                let result = golem::workerexecutor::v1::InvokeAndAwaitWorkerSuccessTyped {
-                    output: Some(golem_wasm_rpc::protobuf::TypeAnnotatedValue {
-                        type_annotated_value: Some(type_annotated_value),
-                    })
-                };
-
-                record.succeed(Ok(Response::new(
-                    golem::workerexecutor::v1::InvokeAndAwaitWorkerResponseTyped {
-                        result: Some(
-                            golem::workerexecutor::v1::invoke_and_await_worker_response_typed::Result::Success(result),
-                        ),
-                    },
-                )))
-            }
+        match self
+            .put_secret_internal(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(_) => record.succeed(Ok(Response::new(PutSecretResponse {
+                result: Some(
+                    golem::workerexecutor::v1::put_secret_response::Result::Success(
+                        golem::common::Empty {},
+                    ),
+                ),
+            }))),
             Err(err) => record.fail(
-                Ok(Response::new(
-                    golem::workerexecutor::v1::InvokeAndAwaitWorkerResponseTyped {
-                        result: Some(
-                            golem::workerexecutor::v1::invoke_and_await_worker_response_typed::Result::Failure(
-                                err.clone().into(),
-                            ),
+                Ok(Response::new(PutSecretResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::put_secret_response::Result::Failure(
+                            err.clone().into(),
                         ),
-                    },
-                )),
+                    ),
+                })),
                 &err,
             ),
         }
     }
 
-    async fn invoke_worker(
+    async fn delete_secret(
         &self,
-        request: Request<golem::workerexecutor::v1::InvokeWorkerRequest>,
-    ) -> Result<Response<golem::workerexecutor::v1::InvokeWorkerResponse>, Status> {
+        request: Request<DeleteSecretRequest>,
+    ) -> Result<Response<DeleteSecretResponse>, Status> {
         let request = request.into_inner();
         let record = recorded_grpc_api_request!(
-            "invoke_worker",
-            worker_id = proto_target_worker_id_string(&request.worker_id),
-            function = request.name,
-            account_id = proto_account_id_string(&request.account_id)
+            "delete_secret",
+            component_id = proto_component_id_string(&request.component_id),
+            secret_name = %request.name,
         );
 
         match self
-            .invoke_worker_internal(&request)
+            .delete_secret_internal(request)
             .instrument(record.span.clone())
             .await
         {
-            Ok(_) => record.succeed(Ok(Response::new(
-                golem::workerexecutor::v1::InvokeWorkerResponse {
-                    result: Some(
-                        golem::workerexecutor::v1::invoke_worker_response::Result::Success(
-                            golem::common::Empty {},
-                        ),
+            Ok(_) => record.succeed(Ok(Response::new(DeleteSecretResponse {
+                result: Some(
+                    golem::workerexecutor::v1::delete_secret_response::Result::Success(
+                        golem::common::Empty {},
                     ),
-                },
-            ))),
+                ),
+            }))),
             Err(err) => record.fail(
-                Ok(Response::new(
-                    golem::workerexecutor::v1::InvokeWorkerResponse {
-                        result: Some(
-                            golem::workerexecutor::v1::invoke_worker_response::Result::Failure(
-                                err.clone().into(),
-                            ),
+                Ok(Response::new(DeleteSecretResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::delete_secret_response::Result::Failure(
+                            err.clone().into(),
                         ),
-                    },
-                )),
+                    ),
+                })),
                 &err,
             ),
         }
     }
 
-    type ConnectWorkerStream = ResponseStream;
-
-    async fn connect_worker(
+    async fn list_secrets(
         &self,
-        request: Request<golem::workerexecutor::v1::ConnectWorkerRequest>,
-    ) -> ResponseResult<Self::ConnectWorkerStream> {
+        request: Request<ListSecretsRequest>,
+    ) -> Result<Response<ListSecretsResponse>, Status> {
         let request = request.into_inner();
         let record = recorded_grpc_api_request!(
-            "connect_worker",
-            worker_id = proto_worker_id_string(&request.worker_id),
-            account_id = proto_account_id_string(&request.account_id)
+            "list_secrets",
+            component_id = proto_component_id_string(&request.component_id),
         );
 
-        self.connect_worker_internal(request)
+        match self
+            .list_secrets_internal(request)
             .instrument(record.span.clone())
             .await
+        {
+            Ok(names) => record.succeed(Ok(Response::new(ListSecretsResponse {
+                result: Some(
+                    golem::workerexecutor::v1::list_secrets_response::Result::Success(
+                        SecretNames { names },
+                    ),
+                ),
+            }))),
+            Err(err) => record.fail(
+                Ok(Response::new(ListSecretsResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::list_secrets_response::Result::Failure(
+                            err.clone().into(),
+                        ),
+                    ),
+                })),
+                &err,
+            ),
+        }
     }
 
-    async fn delete_worker(
+    async fn get_shared_value(
         &self,
-        request: Request<golem::workerexecutor::v1::DeleteWorkerRequest>,
-    ) -> Result<Response<golem::workerexecutor::v1::DeleteWorkerResponse>, Status> {
+        request: Request<GetSharedValueRequest>,
+    ) -> Result<Response<GetSharedValueResponse>, Status> {
         let request = request.into_inner();
         let record = recorded_grpc_api_request!(
-            "delete_worker",
-            worker_id = proto_worker_id_string(&request.worker_id)
+            "get_shared_value",
+            account_id = proto_account_id_string(&request.account_id),
+            key = %request.key,
         );
 
         match self
-            .delete_worker_internal(request)
+            .get_shared_value_internal(request)
             .instrument(record.span.clone())
             .await
         {
-            Ok(_) => record.succeed(Ok(Response::new(
-                golem::workerexecutor::v1::DeleteWorkerResponse {
-                    result: Some(
-                        golem::workerexecutor::v1::delete_worker_response::Result::Success(
-                            golem::common::Empty {},
-                        ),
+            Ok(entry) => record.succeed(Ok(Response::new(GetSharedValueResponse {
+                result: Some(
+                    golem::workerexecutor::v1::get_shared_value_response::Result::Success(
+                        GetSharedValueSuccessResponse { entry },
                     ),
-                },
-            ))),
+                ),
+            }))),
             Err(err) => record.fail(
-                Ok(Response::new(
-                    golem::workerexecutor::v1::DeleteWorkerResponse {
-                        result: Some(
-                            golem::workerexecutor::v1::delete_worker_response::Result::Failure(
-                                err.clone().into(),
-                            ),
+                Ok(Response::new(GetSharedValueResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::get_shared_value_response::Result::Failure(
+                            err.clone().into(),
                         ),
-                    },
-                )),
+                    ),
+                })),
                 &err,
             ),
         }
     }
 
-    async fn complete_promise(
+    async fn set_shared_value(
         &self,
-        request: Request<golem::workerexecutor::v1::CompletePromiseRequest>,
-    ) -> Result<Response<golem::workerexecutor::v1::CompletePromiseResponse>, Status> {
+        request: Request<SetSharedValueRequest>,
+    ) -> Result<Response<SetSharedValueResponse>, Status> {
         let request = request.into_inner();
         let record = recorded_grpc_api_request!(
-            "complete_promise",
-            promise_id = proto_promise_id_string(&request.promise_id)
+            "set_shared_value",
+            account_id = proto_account_id_string(&request.account_id),
+            key = %request.key,
         );
 
         match self
-            .complete_promise_internal(request)
+            .set_shared_value_internal(request)
             .instrument(record.span.clone())
             .await
         {
-            Ok(success) => record.succeed(Ok(Response::new(
-                golem::workerexecutor::v1::CompletePromiseResponse {
-                    result: Some(
-                        golem::workerexecutor::v1::complete_promise_response::Result::Success(
-                            success,
-                        ),
+            Ok(version) => record.succeed(Ok(Response::new(SetSharedValueResponse {
+                result: Some(
+                    golem::workerexecutor::v1::set_shared_value_response::Result::Success(
+                        SetSharedValueSuccessResponse { version },
                     ),
-                },
-            ))),
+                ),
+            }))),
             Err(err) => record.fail(
-                Ok(Response::new(
-                    golem::workerexecutor::v1::CompletePromiseResponse {
-                        result: Some(
-                            golem::workerexecutor::v1::complete_promise_response::Result::Failure(
-                                err.clone().into(),
-                            ),
+                Ok(Response::new(SetSharedValueResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::set_shared_value_response::Result::Failure(
+                            err.clone().into(),
                         ),
-                    },
-                )),
+                    ),
+                })),
                 &err,
             ),
         }
     }
 
-    async fn interrupt_worker(
+    async fn delete_shared_value(
         &self,
-        request: Request<golem::workerexecutor::v1::InterruptWorkerRequest>,
-    ) -> Result<Response<golem::workerexecutor::v1::InterruptWorkerResponse>, Status> {
+        request: Request<DeleteSharedValueRequest>,
+    ) -> Result<Response<DeleteSharedValueResponse>, Status> {
         let request = request.into_inner();
         let record = recorded_grpc_api_request!(
-            "interrupt_worker",
-            worker_id = proto_worker_id_string(&request.worker_id),
+            "delete_shared_value",
+            account_id = proto_account_id_string(&request.account_id),
+            key = %request.key,
         );
 
         match self
-            .interrupt_worker_internal(request)
+            .delete_shared_value_internal(request)
             .instrument(record.span.clone())
             .await
         {
-            Ok(_) => record.succeed(Ok(Response::new(
-                golem::workerexecutor::v1::InterruptWorkerResponse {
-                    result: Some(
-                        golem::workerexecutor::v1::interrupt_worker_response::Result::Success(
-                            golem::common::Empty {},
-                        ),
+            Ok(deleted) => record.succeed(Ok(Response::new(DeleteSharedValueResponse {
+                result: Some(
+                    golem::workerexecutor::v1::delete_shared_value_response::Result::Success(
+                        deleted,
                     ),
-                },
-            ))),
+                ),
+            }))),
             Err(err) => record.fail(
-                Ok(Response::new(
-                    golem::workerexecutor::v1::InterruptWorkerResponse {
-                        result: Some(
-                            golem::workerexecutor::v1::interrupt_worker_response::Result::Failure(
-                                err.clone().into(),
-                            ),
+                Ok(Response::new(DeleteSharedValueResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::delete_shared_value_response::Result::Failure(
+                            err.clone().into(),
                         ),
-                    },
-                )),
+                    ),
+                })),
                 &err,
             ),
         }
     }
 
-    async fn revoke_shards(
+    async fn list_shared_keys(
         &self,
-        request: Request<golem::workerexecutor::v1::RevokeShardsRequest>,
-    ) -> Result<Response<golem::workerexecutor::v1::RevokeShardsResponse>, Status> {
+        request: Request<ListSharedKeysRequest>,
+    ) -> Result<Response<ListSharedKeysResponse>, Status> {
         let request = request.into_inner();
-        let record = recorded_grpc_api_request!("revoke_shards",);
+        let record = recorded_grpc_api_request!(
+            "list_shared_keys",
+            account_id = proto_account_id_string(&request.account_id),
+        );
 
         match self
-            .revoke_shards_internal(request)
+            .list_shared_keys_internal(request)
             .instrument(record.span.clone())
             .await
         {
-            Ok(_) => record.succeed(Ok(Response::new(
-                golem::workerexecutor::v1::RevokeShardsResponse {
-                    result: Some(
-                        golem::workerexecutor::v1::revoke_shards_response::Result::Success(
-                            golem::common::Empty {},
-                        ),
+            Ok(keys) => record.succeed(Ok(Response::new(ListSharedKeysResponse {
+                result: Some(
+                    golem::workerexecutor::v1::list_shared_keys_response::Result::Success(
+                        SharedKeys { keys },
                     ),
-                },
-            ))),
+                ),
+            }))),
             Err(err) => record.fail(
-                Ok(Response::new(
-                    golem::workerexecutor::v1::RevokeShardsResponse {
-                        result: Some(
-                            golem::workerexecutor::v1::revoke_shards_response::Result::Failure(
-                                err.clone().into(),
-                            ),
+                Ok(Response::new(ListSharedKeysResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::list_shared_keys_response::Result::Failure(
+                            err.clone().into(),
                         ),
-                    },
-                )),
+                    ),
+                })),
                 &err,
             ),
         }
     }
-
-    async fn assign_shards(
+    async fn list_topics(
         &self,
-        request: Request<golem::workerexecutor::v1::AssignShardsRequest>,
-    ) -> Result<Response<golem::workerexecutor::v1::AssignShardsResponse>, Status> {
+        request: Request<ListTopicsRequest>,
+    ) -> Result<Response<ListTopicsResponse>, Status> {
         let request = request.into_inner();
-        let record = recorded_grpc_api_request!("assign_shards",);
+        let record = recorded_grpc_api_request!(
+            "list_topics",
+            account_id = proto_account_id_string(&request.account_id),
+        );
 
         match self
-            .assign_shards_internal(request)
+            .list_topics_internal(request)
             .instrument(record.span.clone())
             .await
         {
-            Ok(_) => record.succeed(Ok(Response::new(
-                golem::workerexecutor::v1::AssignShardsResponse {
+            Ok(names) => record.succeed(Ok(Response::new(ListTopicsResponse {
+                result: Some(
+                    golem::workerexecutor::v1::list_topics_response::Result::Success(TopicNames {
+                        names,
+                    }),
+                ),
+            }))),
+            Err(err) => record.fail(
+                Ok(Response::new(ListTopicsResponse {
                     result: Some(
-                        golem::workerexecutor::v1::assign_shards_response::Result::Success(
-                            golem::common::Empty {},
+                        golem::workerexecutor::v1::list_topics_response::Result::Failure(
+                            err.clone().into(),
                         ),
                     ),
-                },
-            ))),
-            Err(err) => record.fail(
-                Ok(Response::new(
-                    golem::workerexecutor::v1::AssignShardsResponse {
-                        result: Some(
-                            golem::workerexecutor::v1::assign_shards_response::Result::Failure(
-                                err.clone().into(),
-                            ),
-                        ),
-                    },
-                )),
+                })),
                 &err,
             ),
         }
     }
 
-    async fn get_worker_metadata(
+    async fn get_topic(
         &self,
-        request: Request<golem::workerexecutor::v1::GetWorkerMetadataRequest>,
-    ) -> Result<Response<golem::workerexecutor::v1::GetWorkerMetadataResponse>, Status> {
+        request: Request<GetTopicRequest>,
+    ) -> Result<Response<GetTopicResponse>, Status> {
         let request = request.into_inner();
-
         let record = recorded_grpc_api_request!(
-            "get_worker_metadata",
-            worker_id = proto_worker_id_string(&request.worker_id)
+            "get_topic",
+            account_id = proto_account_id_string(&request.account_id),
+            topic = %request.topic,
         );
 
-        let result = self
-            .get_worker_metadata_internal(request)
+        match self
+            .get_topic_internal(request)
             .instrument(record.span.clone())
-            .await;
-        match result {
-            Ok(result) => record.succeed(Ok(Response::new(
-                golem::workerexecutor::v1::GetWorkerMetadataResponse {
-                    result: Some(
-                        golem::workerexecutor::v1::get_worker_metadata_response::Result::Success(
-                            result,
-                        ),
+            .await
+        {
+            Ok(topic) => record.succeed(Ok(Response::new(GetTopicResponse {
+                result: Some(
+                    golem::workerexecutor::v1::get_topic_response::Result::Success(
+                        GetTopicSuccessResponse { topic },
                     ),
-                },
-            ))),
-            Err(err @ GolemError::WorkerNotFound { .. }) => record.succeed(Ok(Response::new(
-                golem::workerexecutor::v1::GetWorkerMetadataResponse {
+                ),
+            }))),
+            Err(err) => record.fail(
+                Ok(Response::new(GetTopicResponse {
                     result: Some(
-                        golem::workerexecutor::v1::get_worker_metadata_response::Result::Failure(
+                        golem::workerexecutor::v1::get_topic_response::Result::Failure(
                             err.clone().into(),
                         ),
                     ),
-                },
-            ))),
-            Err(err) => record.fail(
-                Ok(Response::new(
-                    golem::workerexecutor::v1::GetWorkerMetadataResponse {
-                        result: Some(
-                            golem::workerexecutor::v1::get_worker_metadata_response::Result::Failure(
-                                err.clone().into(),
-                            ),
-                        ),
-                    },
-                )),
+                })),
                 &err,
             ),
         }
     }
 
-    async fn resume_worker(
+    async fn get_account_usage(
         &self,
-        request: Request<golem::workerexecutor::v1::ResumeWorkerRequest>,
-    ) -> Result<Response<golem::workerexecutor::v1::ResumeWorkerResponse>, Status> {
+        request: Request<GetAccountUsageRequest>,
+    ) -> Result<Response<GetAccountUsageResponse>, Status> {
         let request = request.into_inner();
         let record = recorded_grpc_api_request!(
-            "resume_worker",
-            worker_id = proto_worker_id_string(&request.worker_id),
+            "get_account_usage",
+            account_id = proto_account_id_string(&request.account_id),
         );
 
         match self
-            .resume_worker_internal(request)
+            .get_account_usage_internal(request)
             .instrument(record.span.clone())
             .await
         {
-            Ok(_) => record.succeed(Ok(Response::new(
-                golem::workerexecutor::v1::ResumeWorkerResponse {
-                    result: Some(
-                        golem::workerexecutor::v1::resume_worker_response::Result::Success(
-                            golem::common::Empty {},
-                        ),
+            Ok(records) => record.succeed(Ok(Response::new(GetAccountUsageResponse {
+                result: Some(
+                    golem::workerexecutor::v1::get_account_usage_response::Result::Success(
+                        AccountUsageRecords { records },
                     ),
-                },
-            ))),
+                ),
+            }))),
             Err(err) => record.fail(
-                Ok(Response::new(
-                    golem::workerexecutor::v1::ResumeWorkerResponse {
-                        result: Some(
-                            golem::workerexecutor::v1::resume_worker_response::Result::Failure(
-                                err.clone().into(),
-                            ),
+                Ok(Response::new(GetAccountUsageResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::get_account_usage_response::Result::Failure(
+                            err.clone().into(),
                         ),
-                    },
-                )),
+                    ),
+                })),
                 &err,
             ),
         }
     }
 
-    async fn get_running_workers_metadata(
+    async fn list_scheduled_actions(
         &self,
-        request: Request<GetRunningWorkersMetadataRequest>,
-    ) -> Result<Response<GetRunningWorkersMetadataResponse>, Status> {
+        request: Request<ListScheduledActionsRequest>,
+    ) -> Result<Response<ListScheduledActionsResponse>, Status> {
         let request = request.into_inner();
         let record = recorded_grpc_api_request!(
-            "get_running_workers_metadata",
-            component_id = proto_component_id_string(&request.component_id),
+            "list_scheduled_actions",
+            account_id = proto_account_id_string(&request.account_id),
         );
 
-        let result = self
-            .get_running_workers_metadata_internal(request)
+        match self
+            .list_scheduled_actions_internal(request)
             .instrument(record.span.clone())
-            .await;
-        match result {
-            Ok(workers) => record.succeed(Ok(Response::new(
-                golem::workerexecutor::v1::GetRunningWorkersMetadataResponse {
-                    result: Some(
-                        golem::workerexecutor::v1::get_running_workers_metadata_response::Result::Success(
-                            golem::workerexecutor::v1::GetRunningWorkersMetadataSuccessResponse {
-                                workers
-                            }
-                        ),
+            .await
+        {
+            Ok(actions) => record.succeed(Ok(Response::new(ListScheduledActionsResponse {
+                result: Some(
+                    golem::workerexecutor::v1::list_scheduled_actions_response::Result::Success(
+                        actions,
                     ),
-                },
-            ))),
+                ),
+            }))),
             Err(err) => record.fail(
-                Ok(Response::new(
-                    GetRunningWorkersMetadataResponse {
-                        result: Some(
-                            golem::workerexecutor::v1::get_running_workers_metadata_response::Result::Failure(
-                                err.clone().into(),
-                            ),
+                Ok(Response::new(ListScheduledActionsResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::list_scheduled_actions_response::Result::Failure(
+                            err.clone().into(),
                         ),
-                    },
-                )),
+                    ),
+                })),
                 &err,
             ),
         }
     }
 
-    async fn get_workers_metadata(
+    async fn cancel_scheduled_actions(
         &self,
-        request: Request<GetWorkersMetadataRequest>,
-    ) -> Result<Response<GetWorkersMetadataResponse>, Status> {
+        request: Request<CancelScheduledActionsRequest>,
+    ) -> Result<Response<CancelScheduledActionsResponse>, Status> {
         let request = request.into_inner();
         let record = recorded_grpc_api_request!(
-            "get_workers_metadata",
-            component_id = proto_component_id_string(&request.component_id),
+            "cancel_scheduled_actions",
+            account_id = proto_account_id_string(&request.account_id),
         );
 
-        let result = self
-            .get_workers_metadata_internal(request)
+        match self
+            .cancel_scheduled_actions_internal(request)
             .instrument(record.span.clone())
-            .await;
-        match result {
-            Ok((cursor, workers)) => {
-                record.succeed(Ok(Response::new(GetWorkersMetadataResponse {
-                    result: Some(
-                        golem::workerexecutor::v1::get_workers_metadata_response::Result::Success(
-                            golem::workerexecutor::v1::GetWorkersMetadataSuccessResponse {
-                                workers,
-                                cursor,
-                            },
-                        ),
+            .await
+        {
+            Ok(actions) => record.succeed(Ok(Response::new(CancelScheduledActionsResponse {
+                result: Some(
+                    golem::workerexecutor::v1::cancel_scheduled_actions_response::Result::Success(
+                        PendingScheduledActions {
+                            actions,
+                            next_cursor: None,
+                        },
                     ),
-                })))
-            }
+                ),
+            }))),
             Err(err) => record.fail(
-                Ok(Response::new(GetWorkersMetadataResponse {
+                Ok(Response::new(CancelScheduledActionsResponse {
                     result: Some(
-                        golem::workerexecutor::v1::get_workers_metadata_response::Result::Failure(
+                        golem::workerexecutor::v1::cancel_scheduled_actions_response::Result::Failure(
                             err.clone().into(),
                         ),
                     ),
@@ -1763,33 +4525,32 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
         }
     }
 
-    async fn update_worker(
+    async fn list_promises(
         &self,
-        request: Request<UpdateWorkerRequest>,
-    ) -> Result<Response<UpdateWorkerResponse>, Status> {
+        request: Request<ListPromisesRequest>,
+    ) -> Result<Response<ListPromisesResponse>, Status> {
         let request = request.into_inner();
         let record = recorded_grpc_api_request!(
-            "update_worker",
+            "list_promises",
             worker_id = proto_worker_id_string(&request.worker_id),
-            target_version = request.target_version,
         );
 
         match self
-            .update_worker_internal(request)
+            .list_promises_internal(request)
             .instrument(record.span.clone())
             .await
         {
-            Ok(_) => record.succeed(Ok(Response::new(UpdateWorkerResponse {
+            Ok(promises) => record.succeed(Ok(Response::new(ListPromisesResponse {
                 result: Some(
-                    golem::workerexecutor::v1::update_worker_response::Result::Success(
-                        golem::common::Empty {},
+                    golem::workerexecutor::v1::list_promises_response::Result::Success(
+                        OutstandingPromises { promises },
                     ),
                 ),
             }))),
             Err(err) => record.fail(
-                Ok(Response::new(UpdateWorkerResponse {
+                Ok(Response::new(ListPromisesResponse {
                     result: Some(
-                        golem::workerexecutor::v1::update_worker_response::Result::Failure(
+                        golem::workerexecutor::v1::list_promises_response::Result::Failure(
                             err.clone().into(),
                         ),
                     ),
@@ -1799,26 +4560,32 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
         }
     }
 
-    async fn get_oplog(
+    async fn get_worker_sagas(
         &self,
-        request: Request<GetOplogRequest>,
-    ) -> Result<Response<GetOplogResponse>, Status> {
+        request: Request<GetWorkerSagasRequest>,
+    ) -> Result<Response<GetWorkerSagasResponse>, Status> {
         let request = request.into_inner();
         let record = recorded_grpc_api_request!(
-            "get_oplog",
+            "get_worker_sagas",
             worker_id = proto_worker_id_string(&request.worker_id),
         );
 
-        let result = self
-            .get_oplog_internal(request)
+        match self
+            .get_worker_sagas_internal(request)
             .instrument(record.span.clone())
-            .await;
-        match result {
-            Ok(response) => record.succeed(Ok(Response::new(response))),
+            .await
+        {
+            Ok(sagas) => record.succeed(Ok(Response::new(GetWorkerSagasResponse {
+                result: Some(
+                    golem::workerexecutor::v1::get_worker_sagas_response::Result::Success(
+                        WorkerSagas { sagas },
+                    ),
+                ),
+            }))),
             Err(err) => record.fail(
-                Ok(Response::new(GetOplogResponse {
+                Ok(Response::new(GetWorkerSagasResponse {
                     result: Some(
-                        golem::workerexecutor::v1::get_oplog_response::Result::Failure(
+                        golem::workerexecutor::v1::get_worker_sagas_response::Result::Failure(
                             err.clone().into(),
                         ),
                     ),
@@ -1829,6 +4596,32 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
     }
 }
 
+async fn report_drain_progress(
+    progress: &mpsc::Sender<Result<DrainResponse, Status>>,
+    phase: DrainPhase,
+    in_flight_workers: usize,
+    suspended_workers: usize,
+    start: Instant,
+) {
+    debug!(
+        phase = phase.as_str_name(),
+        in_flight_workers, suspended_workers, "Drain progress"
+    );
+    // The caller may have stopped listening, which must not interrupt draining
+    let _ = progress
+        .send(Ok(DrainResponse {
+            result: Some(golem::workerexecutor::v1::drain_response::Result::Progress(
+                DrainProgress {
+                    phase: phase.into(),
+                    in_flight_workers: in_flight_workers as u64,
+                    suspended_workers: suspended_workers as u64,
+                    elapsed_millis: start.elapsed().as_millis() as u64,
+                },
+            )),
+        }))
+        .await;
+}
+
 trait GrpcInvokeRequest {
     fn account_id(&self) -> Result<AccountId, GolemError>;
     fn account_limits(&self) -> Option<GrpcResourceLimits>;
@@ -1839,6 +4632,9 @@ trait GrpcInvokeRequest {
     fn args(&self) -> Option<Vec<String>>;
     fn env(&self) -> Option<Vec<(String, String)>>;
     fn parent(&self) -> Option<WorkerId>;
+    fn invocation_priority(&self) -> InvocationPriority;
+    fn invocation_context(&self) -> BTreeMap<String, String>;
+    fn timeout(&self) -> Option<Duration>;
 }
 
 impl GrpcInvokeRequest for golem::workerexecutor::v1::InvokeWorkerRequest {
@@ -1891,6 +4687,21 @@ impl GrpcInvokeRequest for golem::workerexecutor::v1::InvokeWorkerRequest {
                 .and_then(|worker_id| worker_id.clone().try_into().ok())
         })
     }
+
+    fn invocation_priority(&self) -> InvocationPriority {
+        self.priority().into()
+    }
+
+    fn invocation_context(&self) -> BTreeMap<String, String> {
+        self.context
+            .as_ref()
+            .map(|ctx| ctx.values.clone().into_iter().collect())
+            .unwrap_or_default()
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        None
+    }
 }
 
 impl GrpcInvokeRequest for golem::workerexecutor::v1::InvokeAndAwaitWorkerRequest {
@@ -1943,6 +4754,21 @@ impl GrpcInvokeRequest for golem::workerexecutor::v1::InvokeAndAwaitWorkerReques
                 .and_then(|worker_id| worker_id.clone().try_into().ok())
         })
     }
+
+    fn invocation_priority(&self) -> InvocationPriority {
+        self.priority().into()
+    }
+
+    fn invocation_context(&self) -> BTreeMap<String, String> {
+        self.context
+            .as_ref()
+            .map(|ctx| ctx.values.clone().into_iter().collect())
+            .unwrap_or_default()
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout_millis.map(Duration::from_millis)
+    }
 }
 
 pub trait UriBackConversion {
@@ -1964,6 +4790,87 @@ pub fn authorised_grpc_request<T>(request: T, access_token: &Uuid) -> Request<T>
     req
 }
 
+/// Gets the deadline of a poll request, which has to be positive and at most `MAX_POLL_DEADLINE`
+fn poll_deadline(deadline_millis: u64) -> Result<Duration, GolemError> {
+    let deadline = Duration::from_millis(deadline_millis);
+    if deadline.is_zero() || deadline > MAX_POLL_DEADLINE {
+        Err(GolemError::invalid_request(format!(
+            "Invalid deadline_millis {deadline_millis}, it has to be between 1 and {}",
+            MAX_POLL_DEADLINE.as_millis()
+        )))
+    } else {
+        Ok(deadline)
+    }
+}
+
+/// Makes the response of polling an invocation, which is pending if it has no output yet
+fn poll_worker_invocation_response(
+    idempotency_key: IdempotencyKey,
+    output: Option<TypeAnnotatedValue>,
+) -> golem::workerexecutor::v1::PollWorkerInvocationResponse {
+    let result = match output {
+        Some(type_annotated_value) => {
+            golem::workerexecutor::v1::poll_worker_invocation_response::Result::Success(
+                golem::workerexecutor::v1::InvokeAndAwaitWorkerSuccessTyped {
+                    output: Some(golem_wasm_rpc::protobuf::TypeAnnotatedValue {
+                        type_annotated_value: Some(type_annotated_value),
+                    }),
+                },
+            )
+        }
+        None => golem::workerexecutor::v1::poll_worker_invocation_response::Result::Pending(
+            idempotency_key.into(),
+        ),
+    };
+    golem::workerexecutor::v1::PollWorkerInvocationResponse {
+        result: Some(result),
+    }
+}
+
+/// A worker event received while streaming the result of an invocation
+enum ReceivedResultChunk {
+    /// A chunk of the streamed result
+    Chunk(Vec<u8>),
+    /// Any other event, including chunks of other invocations
+    Other,
+    /// Some events were missed, because the receiver lagged behind or got closed
+    Missed,
+}
+
+fn received_result_chunk(
+    event: Result<WorkerEvent, RecvError>,
+    idempotency_key: &IdempotencyKey,
+) -> ReceivedResultChunk {
+    match event {
+        Ok(WorkerEvent::ResultChunk {
+            idempotency_key: key,
+            bytes,
+            ..
+        }) if key == *idempotency_key => ReceivedResultChunk::Chunk(bytes),
+        Ok(_) => ReceivedResultChunk::Other,
+        Err(RecvError::Closed) | Err(RecvError::Lagged(_)) => ReceivedResultChunk::Missed,
+    }
+}
+
+/// Sends a chunk of the result to the caller of a streamed invocation
+async fn send_result_chunk(
+    bytes: Vec<u8>,
+    sender: &mpsc::Sender<Result<golem::workerexecutor::v1::InvokeAndStreamWorkerResponse, Status>>,
+) {
+    // The invocation goes on even if the caller disconnected
+    let _ = sender
+        .send(Ok(
+            golem::workerexecutor::v1::InvokeAndStreamWorkerResponse {
+                result: Some(
+                    golem::workerexecutor::v1::invoke_and_stream_worker_response::Result::Chunk(
+                        bytes,
+                    ),
+                ),
+            },
+        ))
+        .await;
+}
+
 pub struct WorkerEventStream {
     inner: Pin<Box<dyn Stream<Item = Result<WorkerEvent, BroadcastStreamRecvError>> + Send>>,
 }
@@ -1987,24 +4894,78 @@ impl Stream for WorkerEventStream {
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         let WorkerEventStream { inner } = self.get_mut();
-        match inner.as_mut().poll_next(cx) {
-            Poll::Ready(Some(Ok(event))) => match &event {
-                WorkerEvent::Close => Poll::Ready(None),
-                WorkerEvent::StdOut { .. } => Poll::Ready(Some(Ok(event.try_into().unwrap()))),
-                WorkerEvent::StdErr { .. } => Poll::Ready(Some(Ok(event.try_into().unwrap()))),
-                WorkerEvent::Log { .. } => Poll::Ready(Some(Ok(event.try_into().unwrap()))),
-                WorkerEvent::InvocationStart { .. } => {
-                    Poll::Ready(Some(Ok(event.try_into().unwrap())))
-                }
-                WorkerEvent::InvocationFinished { .. } => {
-                    Poll::Ready(Some(Ok(event.try_into().unwrap())))
-                }
-            },
-            Poll::Ready(Some(Err(BroadcastStreamRecvError::Lagged(n)))) => Poll::Ready(Some(Err(
-                Status::data_loss(format!("Lagged by {} events", n)),
-            ))),
-            Poll::Ready(None) => Poll::Ready(None),
-            Poll::Pending => Poll::Pending,
+        loop {
+            return match inner.as_mut().poll_next(cx) {
+                Poll::Ready(Some(Ok(event))) => match &event {
+                    WorkerEvent::Close => Poll::Ready(None),
+                    WorkerEvent::StdOut { .. } => Poll::Ready(Some(Ok(event.try_into().unwrap()))),
+                    WorkerEvent::StdErr { .. } => Poll::Ready(Some(Ok(event.try_into().unwrap()))),
+                    WorkerEvent::Log { .. } => Poll::Ready(Some(Ok(event.try_into().unwrap()))),
+                    WorkerEvent::InvocationStart { .. } => {
+                        Poll::Ready(Some(Ok(event.try_into().unwrap())))
+                    }
+                    WorkerEvent::InvocationFinished { .. } => {
+                        Poll::Ready(Some(Ok(event.try_into().unwrap())))
+                    }
+                    // Result chunks are only sent to the caller of the streamed invocation
+                    WorkerEvent::ResultChunk { .. } => continue,
+                },
+                Poll::Ready(Some(Err(BroadcastStreamRecvError::Lagged(n)))) => Poll::Ready(Some(
+                    Err(Status::data_loss(format!("Lagged by {} events", n))),
+                )),
+                Poll::Ready(None) => Poll::Ready(None),
+                Poll::Pending => Poll::Pending,
+            };
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use golem_common::model::{IdempotencyKey, WorkerEvent};
+    use tokio::sync::broadcast::error::RecvError;
+
+    use crate::grpc::{
+        poll_deadline, received_result_chunk, ReceivedResultChunk, MAX_POLL_DEADLINE,
+    };
+
+    #[test]
+    fn only_chunks_of_the_invocation_are_forwarded() {
+        let idempotency_key = IdempotencyKey::fresh();
+
+        let own = received_result_chunk(
+            Ok(WorkerEvent::result_chunk(&idempotency_key, vec![1, 2])),
+            &idempotency_key,
+        );
+        let other = received_result_chunk(
+            Ok(WorkerEvent::result_chunk(&IdempotencyKey::fresh(), vec![3])),
+            &idempotency_key,
+        );
+
+        assert!(matches!(own, ReceivedResultChunk::Chunk(bytes) if bytes == vec![1, 2]));
+        assert!(matches!(other, ReceivedResultChunk::Other));
+    }
+
+    #[test]
+    fn lagged_or_closed_receivers_missed_chunks() {
+        let idempotency_key = IdempotencyKey::fresh();
+
+        assert!(matches!(
+            received_result_chunk(Err(RecvError::Lagged(3)), &idempotency_key),
+            ReceivedResultChunk::Missed
+        ));
+        assert!(matches!(
+            received_result_chunk(Err(RecvError::Closed), &idempotency_key),
+            ReceivedResultChunk::Missed
+        ));
+    }
+    #[test]
+    fn poll_deadlines_are_bounded() {
+        assert!(poll_deadline(0).is_err());
+        assert!(poll_deadline(1).is_ok());
+        assert!(poll_deadline(MAX_POLL_DEADLINE.as_millis() as u64).is_ok());
+        assert!(poll_deadline(MAX_POLL_DEADLINE.as_millis() as u64 + 1).is_err());
+    }
+}
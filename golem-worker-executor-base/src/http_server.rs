@@ -15,14 +15,19 @@
 use std::fmt::Display;
 use std::net::SocketAddr;
 
+use golem_common::model::ComponentId;
 use http_02::{Response, StatusCode};
 use prometheus::{Encoder, Registry, TextEncoder};
+use serde::Deserialize;
 use tokio::task::JoinHandle;
 use tracing::info;
 use warp::hyper::Body;
 use warp::Filter;
 
-/// The worker executor's HTTP interface provides Prometheus metrics and a healthcheck endpoint
+use crate::metrics::invocation_latency;
+
+/// The worker executor's HTTP interface provides Prometheus metrics, a healthcheck endpoint and
+/// optionally the recent invocation latency percentiles of the exported functions
 pub struct HttpServerImpl {
     handle: JoinHandle<()>,
 }
@@ -33,7 +38,19 @@ impl HttpServerImpl {
         registry: Registry,
         body_message: &'static str,
     ) -> HttpServerImpl {
-        let handle = tokio::spawn(server(addr, registry, body_message));
+        let handle = tokio::spawn(server(addr, registry, body_message, None));
+        HttpServerImpl { handle }
+    }
+
+    /// Also serves `GET /invocation-latencies`, if an access token is configured. Requests have to
+    /// send it as a bearer token in the `Authorization` header.
+    pub fn with_invocation_latencies(
+        addr: impl Into<SocketAddr> + Display + Send + 'static,
+        registry: Registry,
+        body_message: &'static str,
+        access_token: Option<String>,
+    ) -> HttpServerImpl {
+        let handle = tokio::spawn(server(addr, registry, body_message, access_token));
         HttpServerImpl { handle }
     }
 }
@@ -49,6 +66,7 @@ async fn server(
     addr: impl Into<SocketAddr> + Display + Send,
     registry: Registry,
     body_message: &'static str,
+    invocation_latencies_access_token: Option<String>,
 ) {
     let healthcheck = warp::path!("healthcheck").map(move || {
         Response::builder()
@@ -60,7 +78,48 @@ async fn server(
     let metrics = warp::path!("metrics").map(move || prometheus_metrics(registry.clone()));
 
     info!("Http server started on {addr}");
-    warp::serve(healthcheck.or(metrics)).run(addr).await;
+    match invocation_latencies_access_token {
+        Some(access_token) => {
+            let expected_authorization = format!("Bearer {access_token}");
+            let invocation_latencies = warp::path!("invocation-latencies")
+                .and(warp::header::optional::<String>("authorization"))
+                .and(warp::query::<InvocationLatencyQuery>())
+                .map(
+                    move |authorization: Option<String>, query: InvocationLatencyQuery| {
+                        if authorization.as_ref() == Some(&expected_authorization) {
+                            let body = serde_json::to_vec(&invocation_latency::percentiles(
+                                query.component_id.as_ref(),
+                            ))
+                            .unwrap();
+                            Response::builder()
+                                .header("Content-Type", "application/json")
+                                .body(Body::from(body))
+                                .unwrap()
+                        } else {
+                            Response::builder()
+                                .status(StatusCode::UNAUTHORIZED)
+                                .body(Body::empty())
+                                .unwrap()
+                        }
+                    },
+                );
+
+            warp::serve(healthcheck.or(metrics).or(invocation_latencies))
+                .run(addr)
+                .await;
+        }
+        None => {
+            warp::serve(healthcheck.or(metrics)).run(addr).await;
+        }
+    }
+}
+
+/// Query parameters of `GET /invocation-latencies`, optionally restricting the result to the
+/// functions of a single component
+#[derive(Debug, Deserialize)]
+struct InvocationLatencyQuery {
+    #[serde(rename = "component-id")]
+    component_id: Option<ComponentId>,
 }
 
 fn prometheus_metrics(registry: Registry) -> Response<Body> {
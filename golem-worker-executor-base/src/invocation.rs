@@ -12,17 +12,24 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::time::Instant;
+
+use anyhow::anyhow;
 use golem_common::model::oplog::{WorkerError, WorkerResourceId};
 use golem_common::model::WorkerStatus;
 use golem_wasm_rpc::wasmtime::{decode_param, encode_output, type_to_analysed_type};
 use golem_wasm_rpc::Value;
 use rib::{ParsedFunctionName, ParsedFunctionReference};
 use tracing::{debug, error};
-use wasmtime::component::{Func, Val};
-use wasmtime::{AsContextMut, StoreContextMut};
-
-use crate::error::GolemError;
-use crate::metrics::wasm::{record_invocation, record_invocation_consumption};
+use wasmtime::component::{Func, Resource, ResourceType, Type, Val};
+use wasmtime::{AsContextMut, StoreContextMut, Trap};
+use wasmtime_wasi::bindings::io::streams::InputStream;
+
+use crate::error::{GolemError, InvocationFuelBudgetExceeded};
+use crate::metrics::wasm::{
+    record_function_invocation_duration, record_invocation, record_invocation_consumption,
+    record_invocation_duration, record_invocation_fuel_budget_exceeded,
+};
 use crate::model::{InterruptKind, TrapType};
 use crate::workerctx::{PublicWorkerIo, WorkerCtx};
 
@@ -44,6 +51,16 @@ pub async fn invoke_worker<Ctx: WorkerCtx>(
 ) -> Result<InvokeResult, GolemError> {
     let mut store = store.as_context_mut();
     let was_live_before = store.data().is_live();
+    let metrics_enabled = store.data().component_observability().metrics_enabled;
+    let record_outcome = |outcome: &'static str| {
+        if metrics_enabled {
+            record_invocation(was_live_before, outcome);
+        }
+    };
+
+    let component_type = store.data().component_metadata().component_type;
+    let component_id = store.data().worker_id().component_id.clone();
+    let start = Instant::now();
 
     let result = invoke_or_fail(
         full_function_name.clone(),
@@ -55,40 +72,46 @@ pub async fn invoke_worker<Ctx: WorkerCtx>(
 
     debug!("Invocation resulted in {:?}", result);
 
+    if metrics_enabled && was_live_before {
+        let duration = start.elapsed();
+        record_invocation_duration(component_type, duration);
+        record_function_invocation_duration(&component_id, &full_function_name, duration);
+    }
+
     match &result {
         Err(_) => {
-            record_invocation(was_live_before, "failed");
+            record_outcome("failed");
             result
         }
         Ok(InvokeResult::Exited { .. }) => {
-            record_invocation(was_live_before, "exited");
+            record_outcome("exited");
             result
         }
         Ok(InvokeResult::Interrupted {
             interrupt_kind: InterruptKind::Interrupt,
             ..
         }) => {
-            record_invocation(was_live_before, "interrupted");
+            record_outcome("interrupted");
             result
         }
         Ok(InvokeResult::Interrupted {
             interrupt_kind: InterruptKind::Suspend,
             ..
         }) => {
-            record_invocation(was_live_before, "suspended");
+            record_outcome("suspended");
             result
         }
         Ok(InvokeResult::Interrupted { .. }) => {
-            record_invocation(was_live_before, "restarted"); // TODO: do we want to record this?
+            record_outcome("restarted"); // TODO: do we want to record this?
             result
         }
         Ok(InvokeResult::Failed { .. }) => {
-            record_invocation(was_live_before, "failed");
+            record_outcome("failed");
             result
         }
         Ok(InvokeResult::Succeeded { .. }) => {
             // this invocation finished and produced a result
-            record_invocation(was_live_before, "success");
+            record_outcome("success");
             result
         }
     }
@@ -357,6 +380,11 @@ async fn invoke<Ctx: WorkerCtx>(
                 let result_value = encode_output(val, typ, store.data_mut())
                     .await
                     .map_err(GolemError::from)?;
+                if is_input_stream(typ) {
+                    if let Value::Handle { resource_id, .. } = &result_value {
+                        forward_result_stream(&mut store, *resource_id).await?;
+                    }
+                }
                 output.push(result_value);
             }
 
@@ -366,6 +394,23 @@ async fn invoke<Ctx: WorkerCtx>(
     }
 }
 
+fn is_input_stream(typ: &Type) -> bool {
+    matches!(typ, Type::Own(resource_type) if *resource_type == ResourceType::host::<InputStream>())
+}
+
+/// Takes the input stream returned by an invocation out of the worker's resources and forwards
+/// its contents to the caller of the invocation
+async fn forward_result_stream<Ctx: WorkerCtx>(
+    store: &mut StoreContextMut<'_, Ctx>,
+    resource_id: u64,
+) -> Result<(), GolemError> {
+    if let Some(resource) = store.data_mut().get(resource_id).await {
+        let stream = Resource::<InputStream>::try_from_resource_any(resource, &mut *store)?;
+        store.data_mut().forward_result_stream(stream).await?;
+    }
+    Ok(())
+}
+
 async fn drop_resource<Ctx: WorkerCtx>(
     store: &mut impl AsContextMut<Data = Ctx>,
     parsed_function_name: &ParsedFunctionName,
@@ -442,6 +487,22 @@ async fn call_exported_function<Ctx: WorkerCtx>(
 
     store.data_mut().borrow_fuel().await?;
 
+    // With a fuel budget the call runs with the budget as the store's fuel level, so wasmtime
+    // traps deterministically when it is used up. The original level is restored afterwards,
+    // as the fuel management of the worker context relies on it.
+    // Calls being replayed are not budgeted, as the budget may have been lowered since they
+    // were originally executed and replaying them must not fail.
+    let fuel_level_before_call = store.get_fuel().unwrap_or(0);
+    let fuel_budget = if store.data().is_live() {
+        store.data().invocation_fuel_budget()
+    } else {
+        None
+    };
+    let budgeted_fuel_level = fuel_budget.map(|budget| budget.min(fuel_level_before_call));
+    if let Some(level) = budgeted_fuel_level {
+        store.set_fuel(level)?;
+    }
+
     let idempotency_key = store.data().get_current_idempotency_key().await;
     if let Some(idempotency_key) = &idempotency_key {
         store
@@ -467,8 +528,25 @@ async fn call_exported_function<Ctx: WorkerCtx>(
         result
     };
 
-    let current_fuel_level = store.get_fuel().unwrap_or(0);
-    let consumed_fuel_for_call = store
+    let mut current_fuel_level = store.get_fuel().unwrap_or(0);
+    let result = match (fuel_budget, budgeted_fuel_level) {
+        (Some(budget), Some(budgeted_level)) => {
+            current_fuel_level = fuel_level_before_call - (budgeted_level - current_fuel_level);
+            store.set_fuel(current_fuel_level)?;
+
+            result.map_err(|err| match err.root_cause().downcast_ref::<Trap>() {
+                Some(&Trap::OutOfFuel) => {
+                    record_invocation_fuel_budget_exceeded();
+                    anyhow!(InvocationFuelBudgetExceeded { budget })
+                }
+                _ => err,
+            })
+        }
+        _ => result,
+    };
+
+    let consumed_fuel_for_call = (fuel_level_before_call - current_fuel_level) as i64;
+    store
         .data_mut()
         .return_fuel(current_fuel_level as i64)
         .await?;
@@ -488,7 +566,9 @@ async fn call_exported_function<Ctx: WorkerCtx>(
             .emit_invocation_finished(raw_function_name, &idempotency_key, store.data().is_live());
     }
 
-    record_invocation_consumption(consumed_fuel_for_call);
+    if store.data().component_observability().metrics_enabled {
+        record_invocation_consumption(consumed_fuel_for_call);
+    }
     Ok((result.map(|_| results), consumed_fuel_for_call))
 }
 
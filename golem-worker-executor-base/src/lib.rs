@@ -21,9 +21,11 @@ pub mod function_result_interpreter;
 pub mod invocation;
 pub mod metrics;
 pub mod model;
+pub mod oplog_regions;
 pub mod preview2;
 pub mod services;
 pub mod storage;
+pub mod time_travel;
 pub mod wasi_host;
 pub mod worker;
 pub mod workerctx;
@@ -36,27 +38,40 @@ use crate::http_server::HttpServerImpl;
 use crate::services::active_workers::ActiveWorkers;
 use crate::services::blob_store::{BlobStoreService, DefaultBlobStoreService};
 use crate::services::component::ComponentService;
+use crate::services::encryption::{
+    DefaultEncryptionService, EncryptionService, LocalKeyManagementService,
+};
 use crate::services::events::Events;
 use crate::services::golem_config::{
-    BlobStorageConfig, GolemConfig, IndexedStorageConfig, KeyValueStorageConfig,
+    BlobStorageConfig, EncryptionConfig, GolemConfig, IndexedStorageConfig, KeyValueStorageConfig,
 };
 use crate::services::key_value::{DefaultKeyValueService, KeyValueService};
+use crate::services::log_export::LogExportService;
 use crate::services::oplog::{
-    BlobOplogArchiveService, CompressedOplogArchiveService, MultiLayerOplogService,
-    OplogArchiveService, OplogService, PrimaryOplogService,
+    BlobOplogArchiveService, CompressedLogArchiveService, CompressedOplogArchiveService,
+    LogRetentionPolicy, MultiLayerOplogService, OplogArchiveAgePolicy, OplogArchiveService,
+    OplogDurabilityPolicy, OplogService, PrimaryOplogService,
 };
 use crate::services::promise::{DefaultPromiseService, PromiseService};
+use crate::services::saga::{DefaultSagaService, SagaService};
 use crate::services::scheduler::{SchedulerService, SchedulerServiceDefault};
+use crate::services::secrets::{DefaultSecretService, SecretService};
 use crate::services::shard::{ShardService, ShardServiceDefault};
 use crate::services::shard_manager::ShardManagerService;
-use crate::services::worker::{DefaultWorkerService, WorkerService};
+use crate::services::topics::{DefaultTopicService, TopicService};
+use crate::services::usage::UsageService;
+use crate::services::virtual_clock::{DefaultVirtualClockService, VirtualClockService};
+use crate::services::worker::WorkerService;
 use crate::services::worker_activator::{LazyWorkerActivator, WorkerActivator};
 use crate::services::worker_enumeration::{
     DefaultWorkerEnumerationService, RunningWorkerEnumerationService,
     RunningWorkerEnumerationServiceDefault, WorkerEnumerationService,
 };
 use crate::services::worker_proxy::{RemoteWorkerProxy, WorkerProxy};
-use crate::services::{component, shard_manager, All};
+use crate::services::{
+    component, connectors, event_sinks, log_export, prewarm, promise_gc, saga, shard_manager,
+    topics, usage, worker, All,
+};
 use crate::storage::blob::s3::S3BlobStorage;
 use crate::storage::blob::BlobStorage;
 use crate::storage::indexed::redis::RedisIndexedStorage;
@@ -114,11 +129,17 @@ pub trait Bootstrap<Ctx: WorkerCtx> {
         shard_service: Arc<dyn ShardService + Send + Sync>,
         key_value_service: Arc<dyn KeyValueService + Send + Sync>,
         blob_store_service: Arc<dyn BlobStoreService + Send + Sync>,
+        secret_service: Arc<dyn SecretService + Send + Sync>,
+        topic_service: Arc<dyn TopicService + Send + Sync>,
+        saga_service: Arc<dyn SagaService + Send + Sync>,
+        virtual_clock_service: Arc<dyn VirtualClockService + Send + Sync>,
         worker_activator: Arc<dyn WorkerActivator + Send + Sync>,
         oplog_service: Arc<dyn OplogService + Send + Sync>,
         scheduler_service: Arc<dyn SchedulerService + Send + Sync>,
         worker_proxy: Arc<dyn WorkerProxy + Send + Sync>,
         events: Arc<Events>,
+        log_export_service: Arc<dyn LogExportService + Send + Sync>,
+        usage_service: Arc<dyn UsageService + Send + Sync>,
     ) -> anyhow::Result<All<Ctx>>;
 
     /// Can be overridden to customize the wasmtime configuration
@@ -168,10 +189,11 @@ pub trait Bootstrap<Ctx: WorkerCtx> {
             .build()
             .unwrap();
 
-        let http_server = HttpServerImpl::new(
+        let http_server = HttpServerImpl::with_invocation_latencies(
             golem_config.http_addr()?,
             prometheus_registry,
             "Worker executor is running",
+            golem_config.invocation_latencies_access_token.clone(),
         );
 
         let (redis, key_value_storage): (
@@ -248,6 +270,7 @@ pub trait Bootstrap<Ctx: WorkerCtx> {
             &golem_config.component_cache,
             &golem_config.compiled_component_service,
             blob_storage.clone(),
+            key_value_storage.clone(),
         )
         .await;
 
@@ -271,44 +294,91 @@ pub trait Bootstrap<Ctx: WorkerCtx> {
         }
         let oplog_archives = NEVec::from_vec(oplog_archives);
 
+        let encryption_service: Option<Arc<dyn EncryptionService + Send + Sync>> =
+            match &golem_config.encryption {
+                EncryptionConfig::Disabled => None,
+                EncryptionConfig::Local(config) => {
+                    info!("Using local key management for encryption at rest");
+                    let key_management_service =
+                        LocalKeyManagementService::new(config).map_err(|err| anyhow!(err))?;
+                    Some(Arc::new(DefaultEncryptionService::new(
+                        Arc::new(key_management_service),
+                        key_value_storage.clone(),
+                    )))
+                }
+            };
+
+        let mut primary_oplog_service = PrimaryOplogService::new(
+            indexed_storage.clone(),
+            blob_storage.clone(),
+            golem_config.oplog.max_operations_before_commit,
+            golem_config.oplog.max_payload_size,
+        )
+        .await
+        .with_durability_policy(OplogDurabilityPolicy::new(
+            golem_config.oplog.durability,
+            golem_config
+                .oplog
+                .component_durabilities
+                .iter()
+                .map(|durability| (durability.component_id.clone(), durability.durability))
+                .collect(),
+            golem_config.oplog.max_batch_size,
+            golem_config.oplog.batch_flush_interval,
+        ));
+        if let Some(encryption_service) = &encryption_service {
+            primary_oplog_service =
+                primary_oplog_service.with_encryption(encryption_service.clone());
+        }
+
         let oplog_service: Arc<dyn OplogService + Send + Sync> = match oplog_archives {
-            None => Arc::new(
-                PrimaryOplogService::new(
-                    indexed_storage.clone(),
-                    blob_storage.clone(),
-                    golem_config.oplog.max_operations_before_commit,
-                    golem_config.oplog.max_payload_size,
-                )
-                .await,
-            ),
+            None => Arc::new(primary_oplog_service),
             Some(oplog_archives) => {
-                let primary = Arc::new(
-                    PrimaryOplogService::new(
-                        indexed_storage.clone(),
-                        blob_storage.clone(),
-                        golem_config.oplog.max_operations_before_commit,
-                        golem_config.oplog.max_payload_size,
-                    )
-                    .await,
-                );
+                let primary = Arc::new(primary_oplog_service);
 
-                Arc::new(MultiLayerOplogService::new(
+                let mut multi_layer_oplog_service = MultiLayerOplogService::new(
                     primary,
                     oplog_archives,
                     golem_config.oplog.entry_count_limit,
                     golem_config.oplog.max_operations_before_commit_ephemeral,
-                ))
+                    OplogArchiveAgePolicy::new(
+                        golem_config.oplog.archive_age_limit,
+                        golem_config
+                            .oplog
+                            .component_archive_age_limits
+                            .iter()
+                            .map(|limit| (limit.component_id.clone(), limit.age_limit))
+                            .collect(),
+                        golem_config.oplog.archive_age_bucket,
+                    ),
+                );
+                if golem_config.ephemeral.fast_path {
+                    multi_layer_oplog_service =
+                        multi_layer_oplog_service.with_in_memory_ephemeral_oplogs();
+                }
+                if let Some(age_limit) = golem_config.oplog.log_retention_age_limit {
+                    multi_layer_oplog_service =
+                        multi_layer_oplog_service.with_log_retention(LogRetentionPolicy::new(
+                            age_limit,
+                            golem_config.oplog.log_retention_interval,
+                            Arc::new(CompressedLogArchiveService::new(indexed_storage.clone())),
+                        ));
+                }
+
+                Arc::new(multi_layer_oplog_service)
             }
         };
 
-        let worker_service = Arc::new(DefaultWorkerService::new(
+        let worker_service = worker::configured(
+            &golem_config.worker_status_cache,
             key_value_storage.clone(),
             shard_service.clone(),
             oplog_service.clone(),
-        ));
+        );
         let worker_enumeration_service = Arc::new(DefaultWorkerEnumerationService::new(
             worker_service.clone(),
             oplog_service.clone(),
+            shard_service.clone(),
             golem_config.clone(),
         ));
 
@@ -337,7 +407,23 @@ pub trait Bootstrap<Ctx: WorkerCtx> {
 
         let key_value_service = Arc::new(DefaultKeyValueService::new(key_value_storage.clone()));
 
-        let blob_store_service = Arc::new(DefaultBlobStoreService::new(blob_storage.clone()));
+        let blob_store_service = Arc::new(DefaultBlobStoreService::new(
+            blob_storage.clone(),
+            key_value_storage.clone(),
+        ));
+
+        let secret_service = Arc::new(DefaultSecretService::new(
+            key_value_storage.clone(),
+            encryption_service,
+        ));
+
+        let topic_service = Arc::new(DefaultTopicService::new(key_value_storage.clone()));
+        let saga_service = Arc::new(DefaultSagaService::new(key_value_storage.clone()));
+
+        let virtual_clock_service = Arc::new(DefaultVirtualClockService::new(
+            key_value_storage.clone(),
+            golem_config.virtual_clock.enabled,
+        ));
 
         let scheduler_service = SchedulerServiceDefault::new(
             key_value_storage.clone(),
@@ -362,6 +448,11 @@ pub trait Bootstrap<Ctx: WorkerCtx> {
             golem_config.limits.invocation_result_broadcast_capacity,
         ));
 
+        let log_export_service = log_export::configured(&golem_config.log_export);
+
+        let usage_service =
+            usage::configured(&golem_config.usage_metering, key_value_storage.clone());
+
         let services = self
             .create_services(
                 active_workers,
@@ -378,18 +469,35 @@ pub trait Bootstrap<Ctx: WorkerCtx> {
                 shard_service,
                 key_value_service,
                 blob_store_service,
+                secret_service,
+                topic_service,
+                saga_service,
+                virtual_clock_service,
                 lazy_worker_activator.clone(),
                 oplog_service,
                 scheduler_service,
                 worker_proxy,
                 events,
+                log_export_service,
+                usage_service,
             )
             .await?;
 
         let addr = golem_config.grpc_addr()?;
-        let worker_executor =
-            WorkerExecutorImpl::<Ctx, All<Ctx>>::new(services, lazy_worker_activator, addr.port())
-                .await?;
+        let worker_executor = WorkerExecutorImpl::<Ctx, All<Ctx>>::new(
+            services.clone(),
+            lazy_worker_activator,
+            addr.port(),
+        )
+        .await?;
+
+        promise_gc::start(services.clone());
+        connectors::kafka::start::<Ctx, _>(services.clone());
+        connectors::amqp::start::<Ctx, _>(services.clone());
+        event_sinks::start(services.clone(), key_value_storage.clone());
+        topics::start(services.clone());
+        saga::start(services.clone());
+        prewarm::start::<Ctx, _>(services);
 
         let service = WorkerExecutorServer::new(worker_executor)
             .accept_compressed(CompressionEncoding::Gzip)
@@ -106,6 +106,69 @@ pub mod events {
     }
 }
 
+pub mod log_export {
+    use lazy_static::lazy_static;
+    use prometheus::*;
+
+    lazy_static! {
+        static ref LOG_EXPORT_RECORDS_TOTAL: CounterVec = register_counter_vec!(
+            "log_export_records_total",
+            "Number of worker log records handled by the log export",
+            &["result"]
+        )
+        .unwrap();
+    }
+
+    pub fn record_exported_logs(count: usize) {
+        LOG_EXPORT_RECORDS_TOTAL
+            .with_label_values(&["exported"])
+            .inc_by(count as f64);
+    }
+
+    pub fn record_failed_logs(count: usize) {
+        LOG_EXPORT_RECORDS_TOTAL
+            .with_label_values(&["failed"])
+            .inc_by(count as f64);
+    }
+
+    pub fn record_dropped_log() {
+        LOG_EXPORT_RECORDS_TOTAL
+            .with_label_values(&["dropped"])
+            .inc();
+    }
+}
+
+pub mod usage {
+    use lazy_static::lazy_static;
+    use prometheus::*;
+
+    lazy_static! {
+        static ref USAGE_METERED_ENTRIES_DROPPED_TOTAL: Counter = register_counter!(
+            "usage_metered_entries_dropped_total",
+            "Number of oplog entries not metered because the usage aggregation has stopped"
+        )
+        .unwrap();
+        static ref USAGE_RECORDS_TOTAL: CounterVec = register_counter_vec!(
+            "usage_records_total",
+            "Number of aggregated usage records written to the storage",
+            &["result"]
+        )
+        .unwrap();
+    }
+
+    pub fn record_dropped_usage() {
+        USAGE_METERED_ENTRIES_DROPPED_TOTAL.inc();
+    }
+
+    pub fn record_stored_usage_record() {
+        USAGE_RECORDS_TOTAL.with_label_values(&["stored"]).inc();
+    }
+
+    pub fn record_failed_usage_record() {
+        USAGE_RECORDS_TOTAL.with_label_values(&["failed"]).inc();
+    }
+}
+
 pub mod workers {
     use lazy_static::lazy_static;
     use prometheus::*;
@@ -117,6 +180,18 @@ pub mod workers {
             &["api"]
         )
         .unwrap();
+        static ref WORKER_ADMISSION_TOTAL: CounterVec = register_counter_vec!(
+            "worker_admission_total",
+            "Number of worker starts checked against the limit of active workers",
+            &["outcome"]
+        )
+        .unwrap();
+        static ref WORKER_STATUS_WRITE_TOTAL: CounterVec = register_counter_vec!(
+            "worker_status_write_total",
+            "Number of worker status updates by the way they reached the key value storage",
+            &["mode"]
+        )
+        .unwrap();
     }
 
     pub fn record_worker_call(api_name: &'static str) {
@@ -124,6 +199,49 @@ pub mod workers {
             .with_label_values(&[api_name])
             .inc();
     }
+
+    /// Records the outcome of starting a worker when the active workers are limited: `admitted`,
+    /// `evicted` if an idle worker had to be unloaded for it, `delayed` if it had to wait for a
+    /// free slot, or `rejected`
+    pub fn record_worker_admission(outcome: &'static str) {
+        WORKER_ADMISSION_TOTAL.with_label_values(&[outcome]).inc();
+    }
+
+    /// Records a worker status update when the status records are cached: `write_through` if it
+    /// was stored immediately, `deferred` if it was queued, `coalesced` if it replaced a queued
+    /// update and `flushed` when a queued update gets stored
+    pub fn record_worker_status_write(mode: &'static str) {
+        WORKER_STATUS_WRITE_TOTAL.with_label_values(&[mode]).inc();
+    }
+}
+
+pub mod scheduling {
+    use std::time::Duration;
+
+    use lazy_static::lazy_static;
+    use prometheus::*;
+
+    lazy_static! {
+        static ref INVOCATION_QUEUE_WAIT_SECONDS: Histogram = register_histogram!(
+            "invocation_queue_wait_seconds",
+            "Time an invocation waited for its turn to be executed",
+            golem_common::metrics::DEFAULT_TIME_BUCKETS.to_vec()
+        )
+        .unwrap();
+        static ref INVOCATIONS_WAITING: IntGauge = register_int_gauge!(
+            "invocations_waiting",
+            "Number of invocations waiting for their turn to be executed"
+        )
+        .unwrap();
+    }
+
+    pub fn record_invocation_queue_wait(duration: Duration) {
+        INVOCATION_QUEUE_WAIT_SECONDS.observe(duration.as_secs_f64());
+    }
+
+    pub fn record_invocations_waiting(count: usize) {
+        INVOCATIONS_WAITING.set(count as i64);
+    }
 }
 
 pub mod promises {
@@ -138,6 +256,12 @@ pub mod promises {
             "Number of scheduled promise completions"
         )
         .unwrap();
+        static ref PROMISES_COLLECTED_TOTAL: CounterVec = register_counter_vec!(
+            "promises_collected_total",
+            "Number of promises of exited or failed workers completed or removed by the garbage collection",
+            &["action"]
+        )
+        .unwrap();
     }
 
     pub fn record_promise_created() {
@@ -147,6 +271,163 @@ pub mod promises {
     pub fn record_scheduled_promise_completed() {
         PROMISES_SCHEDULED_COMPLETE_TOTAL.inc();
     }
+
+    /// `action` is either `completed` or `removed`
+    pub fn record_promises_collected(action: &'static str, count: usize) {
+        PROMISES_COLLECTED_TOTAL
+            .with_label_values(&[action])
+            .inc_by(count as f64);
+    }
+}
+
+pub mod connectors {
+    use lazy_static::lazy_static;
+    use prometheus::*;
+
+    lazy_static! {
+        static ref CONNECTOR_MESSAGES_TOTAL: CounterVec = register_counter_vec!(
+            "connector_messages_total",
+            "Number of messages consumed by the event source connectors",
+            &["connector", "outcome"]
+        )
+        .unwrap();
+    }
+
+    /// `outcome` is either `invoked`, `dead_lettered`, `skipped` or `rejected`
+    pub fn record_connector_message(connector: &str, outcome: &'static str) {
+        CONNECTOR_MESSAGES_TOTAL
+            .with_label_values(&[connector, outcome])
+            .inc();
+    }
+}
+
+pub mod event_sinks {
+    use lazy_static::lazy_static;
+    use prometheus::*;
+
+    lazy_static! {
+        static ref EVENT_SINK_EVENTS_TOTAL: CounterVec = register_counter_vec!(
+            "event_sink_events_total",
+            "Number of invocation result events pushed to the event sinks",
+            &["sink", "outcome"]
+        )
+        .unwrap();
+    }
+
+    /// `outcome` is either `delivered` or `failed`
+    pub fn record_event_sink_event(sink: &str, outcome: &'static str) {
+        EVENT_SINK_EVENTS_TOTAL
+            .with_label_values(&[sink, outcome])
+            .inc();
+    }
+}
+
+pub mod scheduler {
+    use std::time::Duration;
+
+    use lazy_static::lazy_static;
+    use prometheus::*;
+
+    lazy_static! {
+        static ref SCHEDULED_ACTIONS_DUE: IntGaugeVec = register_int_gauge_vec!(
+            "scheduled_actions_due",
+            "Number of scheduled actions which were due in the last run of the scheduler, including the ones of other executors",
+            &["action"]
+        )
+        .unwrap();
+        static ref SCHEDULED_ACTIONS_PENDING: IntGaugeVec = register_int_gauge_vec!(
+            "scheduled_actions_pending",
+            "Number of pending scheduled actions of the workers of this executor, including the ones not due yet",
+            &["action"]
+        )
+        .unwrap();
+        static ref SCHEDULED_ACTION_LAG_SECONDS: HistogramVec = register_histogram_vec!(
+            "scheduled_action_lag_seconds",
+            "Time between the due time of a scheduled action and its execution",
+            &["action"],
+            golem_common::metrics::DEFAULT_TIME_BUCKETS.to_vec()
+        )
+        .unwrap();
+    }
+
+    pub fn record_scheduled_actions_due(action: &'static str, count: usize) {
+        SCHEDULED_ACTIONS_DUE
+            .with_label_values(&[action])
+            .set(count as i64);
+    }
+
+    pub fn record_scheduled_actions_pending(action: &'static str, count: usize) {
+        SCHEDULED_ACTIONS_PENDING
+            .with_label_values(&[action])
+            .set(count as i64);
+    }
+
+    pub fn record_scheduled_action_lag(action: &'static str, lag: Duration) {
+        SCHEDULED_ACTION_LAG_SECONDS
+            .with_label_values(&[action])
+            .observe(lag.as_secs_f64());
+    }
+}
+
+pub mod recovery {
+    use std::time::Duration;
+
+    use lazy_static::lazy_static;
+    use prometheus::*;
+
+    lazy_static! {
+        static ref RECOVERY_PENDING_WORKERS: IntGauge = register_int_gauge!(
+            "recovery_pending_workers",
+            "Number of workers waiting to be recovered"
+        )
+        .unwrap();
+        static ref RECOVERED_WORKERS_TOTAL: CounterVec = register_counter_vec!(
+            "recovered_workers_total",
+            "Number of workers processed by the recovery after startup or shard assignment",
+            &["priority", "outcome"]
+        )
+        .unwrap();
+        static ref RECOVERY_SECONDS: Histogram = register_histogram!(
+            "recovery_seconds",
+            "Time taken to recover the workers after startup or shard assignment",
+            golem_common::metrics::DEFAULT_TIME_BUCKETS.to_vec()
+        )
+        .unwrap();
+    }
+
+    pub fn record_recovery_started(pending_workers: usize) {
+        RECOVERY_PENDING_WORKERS.set(pending_workers as i64);
+    }
+
+    pub fn record_worker_recovered(priority: &'static str, outcome: &'static str) {
+        RECOVERY_PENDING_WORKERS.dec();
+        RECOVERED_WORKERS_TOTAL
+            .with_label_values(&[priority, outcome])
+            .inc();
+    }
+
+    pub fn record_recovery_finished(duration: Duration) {
+        RECOVERY_PENDING_WORKERS.set(0);
+        RECOVERY_SECONDS.observe(duration.as_secs_f64());
+    }
+}
+
+pub mod snapshots {
+    use lazy_static::lazy_static;
+    use prometheus::*;
+
+    lazy_static! {
+        static ref SNAPSHOTS_TOTAL: CounterVec = register_counter_vec!(
+            "snapshots_total",
+            "Number of periodic snapshots saved, skipped or loaded by the workers",
+            &["outcome"]
+        )
+        .unwrap();
+    }
+
+    pub fn record_snapshot(outcome: &'static str) {
+        SNAPSHOTS_TOTAL.with_label_values(&[outcome]).inc();
+    }
 }
 
 pub mod sharding {
@@ -171,6 +452,7 @@ pub mod wasm {
     use tracing::debug;
 
     use golem_common::metrics::api::TraceErrorKind;
+    use golem_common::model::{ComponentId, ComponentType};
 
     use crate::error::GolemError;
 
@@ -193,12 +475,38 @@ pub mod wasm {
             &["mode", "outcome"]
         )
         .unwrap();
+        static ref INVOCATION_SECONDS: HistogramVec = register_histogram_vec!(
+            "invocation_seconds",
+            "Time taken to execute an invocation",
+            &["component_type"],
+            golem_common::metrics::DEFAULT_TIME_BUCKETS.to_vec()
+        )
+        .unwrap();
+        static ref FUNCTION_INVOCATION_SECONDS: HistogramVec = register_histogram_vec!(
+            "function_invocation_seconds",
+            "Time taken to execute an invocation of an exported function",
+            &["component_id", "function"],
+            golem_common::metrics::DEFAULT_TIME_BUCKETS.to_vec()
+        )
+        .unwrap();
+        static ref CREATE_INSTANCE_SECONDS: HistogramVec = register_histogram_vec!(
+            "create_instance_seconds",
+            "Time taken to instantiate the component of a worker",
+            &["component_type"],
+            golem_common::metrics::DEFAULT_TIME_BUCKETS.to_vec()
+        )
+        .unwrap();
         static ref INVOCATION_CONSUMPTION_TOTAL: Histogram = register_histogram!(
             "invocation_consumption_total",
             "Amount of fuel consumed by an invocation",
             crate::metrics::FUEL_BUCKETS.to_vec()
         )
         .unwrap();
+        static ref INVOCATION_FUEL_BUDGET_EXCEEDED_TOTAL: Counter = register_counter!(
+            "invocation_fuel_budget_exceeded_total",
+            "Number of invocations cut off by running out of their fuel budget"
+        )
+        .unwrap();
         static ref ALLOCATED_MEMORY_BYTES: Histogram = register_histogram!(
             "allocated_memory_bytes",
             "Amount of memory allocated by a single memory.grow instruction",
@@ -258,10 +566,44 @@ pub mod wasm {
         INVOCATION_TOTAL.with_label_values(&[mode, outcome]).inc();
     }
 
+    pub fn record_invocation_duration(component_type: ComponentType, duration: Duration) {
+        INVOCATION_SECONDS
+            .with_label_values(&[component_type_label(component_type)])
+            .observe(duration.as_secs_f64());
+    }
+
+    pub fn record_function_invocation_duration(
+        component_id: &ComponentId,
+        function_name: &str,
+        duration: Duration,
+    ) {
+        FUNCTION_INVOCATION_SECONDS
+            .with_label_values(&[&component_id.to_string(), function_name])
+            .observe(duration.as_secs_f64());
+        crate::metrics::invocation_latency::record(component_id, function_name, duration);
+    }
+
+    pub fn record_create_instance(component_type: ComponentType, duration: Duration) {
+        CREATE_INSTANCE_SECONDS
+            .with_label_values(&[component_type_label(component_type)])
+            .observe(duration.as_secs_f64());
+    }
+
+    fn component_type_label(component_type: ComponentType) -> &'static str {
+        match component_type {
+            ComponentType::Durable => "durable",
+            ComponentType::Ephemeral => "ephemeral",
+        }
+    }
+
     pub fn record_invocation_consumption(fuel: i64) {
         INVOCATION_CONSUMPTION_TOTAL.observe(fuel as f64);
     }
 
+    pub fn record_invocation_fuel_budget_exceeded() {
+        INVOCATION_FUEL_BUDGET_EXCEEDED_TOTAL.inc();
+    }
+
     pub fn record_allocated_memory(amount: usize) {
         ALLOCATED_MEMORY_BYTES.observe(amount as f64);
     }
@@ -301,3 +643,258 @@ pub mod oplog {
             .observe(duration.as_secs_f64());
     }
 }
+
+/// Recent invocation durations of each exported function, kept in memory to report percentiles
+/// over a rolling window without depending on an external Prometheus server
+pub mod invocation_latency {
+    use std::collections::{HashMap, VecDeque};
+    use std::sync::Mutex;
+    use std::time::{Duration, Instant};
+
+    use golem_common::model::ComponentId;
+    use lazy_static::lazy_static;
+    use serde::Serialize;
+
+    /// Durations older than this are not considered in the percentiles
+    pub const ROLLING_WINDOW: Duration = Duration::from_secs(5 * 60);
+
+    /// Maximum number of durations kept per function within the window
+    const MAX_SAMPLES: usize = 1024;
+
+    lazy_static! {
+        static ref INVOCATION_LATENCIES: Mutex<RollingLatencies> =
+            Mutex::new(RollingLatencies::new(ROLLING_WINDOW, MAX_SAMPLES));
+    }
+
+    pub fn record(component_id: &ComponentId, function_name: &str, duration: Duration) {
+        INVOCATION_LATENCIES.lock().unwrap().record(
+            component_id,
+            function_name,
+            duration,
+            Instant::now(),
+        );
+    }
+
+    /// Percentiles of the invocation durations in the rolling window, for all the functions of
+    /// the given component or for every function if no component is given
+    pub fn percentiles(component_id: Option<&ComponentId>) -> Vec<FunctionLatency> {
+        INVOCATION_LATENCIES
+            .lock()
+            .unwrap()
+            .percentiles(component_id, Instant::now())
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct FunctionLatency {
+        pub component_id: ComponentId,
+        pub function_name: String,
+        pub count: usize,
+        pub p50_ms: f64,
+        pub p95_ms: f64,
+        pub p99_ms: f64,
+    }
+
+    /// Recording a duration only drops the expired samples of the same function, the functions
+    /// without recent invocations are swept at most once per window
+    pub(crate) struct RollingLatencies {
+        window: Duration,
+        max_samples: usize,
+        samples: HashMap<(ComponentId, String), VecDeque<(Instant, Duration)>>,
+        last_sweep: Option<Instant>,
+    }
+
+    impl RollingLatencies {
+        pub fn new(window: Duration, max_samples: usize) -> Self {
+            Self {
+                window,
+                max_samples,
+                samples: HashMap::new(),
+                last_sweep: None,
+            }
+        }
+
+        pub fn record(
+            &mut self,
+            component_id: &ComponentId,
+            function_name: &str,
+            duration: Duration,
+            now: Instant,
+        ) {
+            let samples = self
+                .samples
+                .entry((component_id.clone(), function_name.to_string()))
+                .or_default();
+            Self::drop_expired(samples, self.window, now);
+            if samples.len() == self.max_samples {
+                samples.pop_front();
+            }
+            samples.push_back((now, duration));
+
+            if self.last_sweep.map_or(true, |last_sweep| {
+                now.duration_since(last_sweep) >= self.window
+            }) {
+                self.evict(now);
+            }
+        }
+
+        pub fn percentiles(
+            &mut self,
+            component_id: Option<&ComponentId>,
+            now: Instant,
+        ) -> Vec<FunctionLatency> {
+            self.evict(now);
+
+            let mut result: Vec<_> = self
+                .samples
+                .iter()
+                .filter(|((id, _), _)| component_id.map_or(true, |component_id| id == component_id))
+                .map(|((component_id, function_name), samples)| {
+                    let mut durations: Vec<_> = samples.iter().map(|(_, d)| *d).collect();
+                    durations.sort();
+                    FunctionLatency {
+                        component_id: component_id.clone(),
+                        function_name: function_name.clone(),
+                        count: durations.len(),
+                        p50_ms: percentile_ms(&durations, 0.50),
+                        p95_ms: percentile_ms(&durations, 0.95),
+                        p99_ms: percentile_ms(&durations, 0.99),
+                    }
+                })
+                .collect();
+            result.sort_by(|a, b| {
+                (&a.component_id, &a.function_name).cmp(&(&b.component_id, &b.function_name))
+            });
+            result
+        }
+
+        fn evict(&mut self, now: Instant) {
+            let window = self.window;
+            self.samples.retain(|_, samples| {
+                Self::drop_expired(samples, window, now);
+                !samples.is_empty()
+            });
+            self.last_sweep = Some(now);
+        }
+
+        fn drop_expired(
+            samples: &mut VecDeque<(Instant, Duration)>,
+            window: Duration,
+            now: Instant,
+        ) {
+            while samples
+                .front()
+                .is_some_and(|(recorded_at, _)| now.duration_since(*recorded_at) > window)
+            {
+                samples.pop_front();
+            }
+        }
+    }
+
+    /// Nearest-rank percentile of the sorted, non-empty durations
+    fn percentile_ms(sorted: &[Duration], percentile: f64) -> f64 {
+        let rank = (percentile * sorted.len() as f64).ceil() as usize;
+        sorted[rank.clamp(1, sorted.len()) - 1].as_secs_f64() * 1000.0
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use test_r::test;
+
+        use std::time::{Duration, Instant};
+
+        use golem_common::model::ComponentId;
+
+        use super::RollingLatencies;
+
+        #[test]
+        fn percentiles_of_the_rolling_window() {
+            let component_id = ComponentId::new_v4();
+            let other_component_id = ComponentId::new_v4();
+            let mut latencies = RollingLatencies::new(Duration::from_secs(60), 1000);
+            let start = Instant::now();
+
+            latencies.record(&component_id, "f", Duration::from_secs(10), start);
+            for i in 1..=100 {
+                latencies.record(
+                    &component_id,
+                    "f",
+                    Duration::from_millis(i),
+                    start + Duration::from_secs(30),
+                );
+            }
+            latencies.record(
+                &other_component_id,
+                "g",
+                Duration::from_millis(5),
+                start + Duration::from_secs(30),
+            );
+
+            let result =
+                latencies.percentiles(Some(&component_id), start + Duration::from_secs(61));
+
+            assert_eq!(result.len(), 1);
+            assert_eq!(result[0].function_name, "f");
+            assert_eq!(result[0].count, 100);
+            assert_eq!(result[0].p50_ms, 50.0);
+            assert_eq!(result[0].p95_ms, 95.0);
+            assert_eq!(result[0].p99_ms, 99.0);
+
+            let result = latencies.percentiles(None, start + Duration::from_secs(91));
+            assert!(result.is_empty());
+        }
+
+        #[test]
+        fn oldest_samples_are_dropped_above_the_limit() {
+            let component_id = ComponentId::new_v4();
+            let mut latencies = RollingLatencies::new(Duration::from_secs(60), 10);
+            let now = Instant::now();
+
+            for i in 1..=20 {
+                latencies.record(&component_id, "f", Duration::from_millis(i), now);
+            }
+
+            let result = latencies.percentiles(None, now);
+            assert_eq!(result[0].count, 10);
+            assert_eq!(result[0].p50_ms, 15.0);
+        }
+
+        #[test]
+        fn idle_functions_are_swept_once_per_window() {
+            let component_id = ComponentId::new_v4();
+            let mut latencies = RollingLatencies::new(Duration::from_secs(60), 1000);
+            let start = Instant::now();
+
+            latencies.record(&component_id, "g", Duration::from_millis(1), start);
+            latencies.record(
+                &component_id,
+                "f",
+                Duration::from_millis(1),
+                start + Duration::from_secs(10),
+            );
+            latencies.record(
+                &component_id,
+                "g",
+                Duration::from_millis(1),
+                start + Duration::from_secs(65),
+            );
+            // f expired, but the last sweep was less than a window ago
+            latencies.record(
+                &component_id,
+                "g",
+                Duration::from_millis(1),
+                start + Duration::from_secs(100),
+            );
+            assert_eq!(latencies.samples.len(), 2);
+
+            latencies.record(
+                &component_id,
+                "g",
+                Duration::from_millis(1),
+                start + Duration::from_secs(130),
+            );
+            assert_eq!(latencies.samples.len(), 1);
+            assert_eq!(latencies.samples[&(component_id, "g".to_string())].len(), 3);
+        }
+    }
+}
@@ -23,13 +23,16 @@ use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
 use serde::{Deserialize, Serialize};
 use wasmtime::Trap;
 
-use golem_common::model::oplog::WorkerError;
+use golem_common::model::oplog::{OplogEntry, OplogIndex, WorkerError};
 use golem_common::model::regions::DeletedRegions;
 use golem_common::model::{
     ComponentType, ShardAssignment, ShardId, Timestamp, WorkerId, WorkerStatusRecord,
 };
 
-use crate::error::{GolemError, WorkerOutOfMemory};
+use crate::durable_host::replay_state::ReplayDivergence;
+use crate::error::{
+    GolemError, InvocationFuelBudgetExceeded, WorkerMemoryLimitExceeded, WorkerOutOfMemory,
+};
 use crate::workerctx::WorkerCtx;
 
 pub trait ShardAssignmentCheck {
@@ -79,8 +82,20 @@ pub struct WorkerConfig {
     pub env: Vec<(String, String)>,
     pub deleted_regions: DeletedRegions,
     pub total_linear_memory_size: u64,
+    /// The maximum total linear memory size the worker can grow to, if limited
+    pub max_linear_memory: Option<u64>,
+    /// Throwaway instances of a worker are sandboxed: they cannot perform side effects in live
+    /// mode, and they do not change the worker's status
+    pub sandboxed: bool,
 }
 
+/// Environment variables set by Golem for every worker, overriding the ones given to the worker
+pub const GOLEM_ENV_VARS: [&str; 3] = [
+    "GOLEM_WORKER_NAME",
+    "GOLEM_COMPONENT_ID",
+    "GOLEM_COMPONENT_VERSION",
+];
+
 impl WorkerConfig {
     pub fn new(
         worker_id: WorkerId,
@@ -89,16 +104,13 @@ impl WorkerConfig {
         mut worker_env: Vec<(String, String)>,
         deleted_regions: DeletedRegions,
         total_linear_memory_size: u64,
+        max_linear_memory: Option<u64>,
     ) -> WorkerConfig {
         let worker_name = worker_id.worker_name.clone();
         let component_id = worker_id.component_id;
         let component_version = component_version.to_string();
-        worker_env.retain(|(key, _)| {
-            key != "GOLEM_WORKER_NAME"
-                && key != "GOLEM_COMPONENT_ID"
-                && key != "GOLEM_COMPONENT_VERSION"
-        });
-        worker_env.push((String::from("GOLEM_WORKER_NAME"), worker_name));
+        worker_env.retain(|(key, _)| !GOLEM_ENV_VARS.contains(&key.as_str()));
+        worker_env.push((String::from("GOLEM_WORKER_NAME"), worker_name.into_string()));
         worker_env.push((String::from("GOLEM_COMPONENT_ID"), component_id.to_string()));
         worker_env.push((String::from("GOLEM_COMPONENT_VERSION"), component_version));
         WorkerConfig {
@@ -106,6 +118,15 @@ impl WorkerConfig {
             env: worker_env,
             deleted_regions,
             total_linear_memory_size,
+            max_linear_memory,
+            sandboxed: false,
+        }
+    }
+
+    pub fn sandboxed(self) -> WorkerConfig {
+        WorkerConfig {
+            sandboxed: true,
+            ..self
         }
     }
 }
@@ -226,7 +247,7 @@ impl ExecutionStatus {
 }
 
 /// Describes the various reasons a worker can run into a trap
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum TrapType {
     /// Interrupted through Golem (including user interrupts, suspends, jumps, etc.)
     Interrupt(InterruptKind),
@@ -246,11 +267,34 @@ impl TrapType {
                     Some(&Trap::StackOverflow) => TrapType::Error(WorkerError::StackOverflow),
                     _ => match error.root_cause().downcast_ref::<WorkerOutOfMemory>() {
                         Some(_) => TrapType::Error(WorkerError::OutOfMemory),
-                        None => match error.root_cause().downcast_ref::<GolemError>() {
-                            Some(GolemError::InvalidRequest { details }) => {
-                                TrapType::Error(WorkerError::InvalidRequest(details.clone()))
-                            }
-                            _ => TrapType::Error(WorkerError::Unknown(format!("{:#}", error))),
+                        None => match error
+                            .root_cause()
+                            .downcast_ref::<WorkerMemoryLimitExceeded>()
+                        {
+                            Some(exceeded) => TrapType::Error(WorkerError::MemoryLimitExceeded {
+                                limit: exceeded.limit,
+                            }),
+                            None => match error
+                                .root_cause()
+                                .downcast_ref::<InvocationFuelBudgetExceeded>()
+                            {
+                                Some(exceeded) => {
+                                    TrapType::Error(WorkerError::FuelBudgetExceeded {
+                                        budget: exceeded.budget,
+                                    })
+                                }
+                                None => match error.root_cause().downcast_ref::<GolemError>() {
+                                    Some(GolemError::InvalidRequest { details }) => {
+                                        TrapType::Error(WorkerError::InvalidRequest(
+                                            details.clone(),
+                                        ))
+                                    }
+                                    _ => TrapType::Error(WorkerError::Unknown(format!(
+                                        "{:#}",
+                                        error
+                                    ))),
+                                },
+                            },
                         },
                     },
                 },
@@ -258,6 +302,22 @@ impl TrapType {
         }
     }
 
+    /// A worker failing or exiting after its replay diverged from the recorded history is
+    /// reported with the details of the divergence, no matter how the failure surfaced in the
+    /// worker. Interrupts are not caused by the divergence, so they are kept.
+    pub fn with_divergence(self, divergence: Option<ReplayDivergence>) -> TrapType {
+        match (self, divergence) {
+            (TrapType::Error(_) | TrapType::Exit, Some(divergence)) => {
+                TrapType::Error(WorkerError::ReplayDiverged {
+                    oplog_index: divergence.oplog_index,
+                    expected: divergence.expected,
+                    actual: divergence.actual,
+                })
+            }
+            (trap_type, _) => trap_type,
+        }
+    }
+
     pub fn as_golem_error(&self, error_logs: &str) -> Option<GolemError> {
         match self {
             TrapType::Interrupt(InterruptKind::Interrupt) => {
@@ -342,13 +402,93 @@ pub enum LookupResult {
     Complete(Result<TypeAnnotatedValue, GolemError>),
 }
 
+/// Fuel consumed by the completed invocations of a worker, aggregated from its oplog
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WorkerFuelConsumption {
+    pub completed_invocations: u64,
+    pub total_consumed_fuel: u64,
+    pub max_consumed_fuel: u64,
+}
+
+impl WorkerFuelConsumption {
+    pub fn add_entry(&mut self, entry: &OplogEntry) {
+        if let OplogEntry::ExportedFunctionCompleted { consumed_fuel, .. } = entry {
+            let consumed_fuel = (*consumed_fuel).max(0) as u64;
+            self.completed_invocations += 1;
+            self.total_consumed_fuel += consumed_fuel;
+            self.max_consumed_fuel = self.max_consumed_fuel.max(consumed_fuel);
+        }
+    }
+}
+
+impl From<WorkerFuelConsumption>
+    for golem_api_grpc::proto::golem::workerexecutor::v1::WorkerFuelConsumption
+{
+    fn from(value: WorkerFuelConsumption) -> Self {
+        Self {
+            completed_invocations: value.completed_invocations,
+            total_consumed_fuel: value.total_consumed_fuel,
+            max_consumed_fuel: value.max_consumed_fuel,
+        }
+    }
+}
+
+/// A failure of a worker caused by replaying its oplog diverging from the recorded history
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReplayDivergenceReport {
+    /// Index of the error entry recording the failure
+    pub error_oplog_index: OplogIndex,
+    /// Index of the oplog entry where the replay diverged
+    pub oplog_index: OplogIndex,
+    /// The side effect recorded in the oplog
+    pub expected: String,
+    /// The side effect the worker attempted instead
+    pub actual: String,
+}
+
+impl ReplayDivergenceReport {
+    pub fn from_entry(entry_index: OplogIndex, entry: &OplogEntry) -> Option<Self> {
+        match entry {
+            OplogEntry::Error {
+                error:
+                    WorkerError::ReplayDiverged {
+                        oplog_index,
+                        expected,
+                        actual,
+                    },
+                ..
+            } => Some(Self {
+                error_oplog_index: entry_index,
+                oplog_index: *oplog_index,
+                expected: expected.clone(),
+                actual: actual.clone(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl From<ReplayDivergenceReport>
+    for golem_api_grpc::proto::golem::workerexecutor::v1::ReplayDivergence
+{
+    fn from(value: ReplayDivergenceReport) -> Self {
+        Self {
+            error_oplog_index: value.error_oplog_index.into(),
+            oplog_index: value.oplog_index.into(),
+            expected: value.expected,
+            actual: value.actual,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use test_r::test;
 
     use uuid::Uuid;
 
-    use golem_common::model::ComponentId;
+    use golem_common::model::oplog::OplogPayload;
+    use golem_common::model::{ComponentId, WorkerName};
 
     use super::*;
 
@@ -359,10 +499,95 @@ mod tests {
         let component_id = ComponentId(uuid);
         let worker_id = WorkerId {
             component_id,
-            worker_name: "instanceName".to_string(),
+            worker_name: WorkerName::from_existing("instanceName"),
         };
         let hash = ShardId::hash_worker_id(&worker_id);
         println!("hash: {:?}", hash);
         assert_eq!(hash, -6692039695739768661);
     }
+
+    #[test]
+    fn fuel_consumption_aggregates_completed_invocations() {
+        let completed = |consumed_fuel| OplogEntry::ExportedFunctionCompleted {
+            timestamp: Timestamp::now_utc(),
+            response: OplogPayload::Inline(vec![]),
+            consumed_fuel,
+        };
+
+        let mut consumption = WorkerFuelConsumption::default();
+        consumption.add_entry(&completed(100));
+        consumption.add_entry(&OplogEntry::suspend());
+        consumption.add_entry(&completed(250));
+        consumption.add_entry(&completed(50));
+
+        assert_eq!(
+            consumption,
+            WorkerFuelConsumption {
+                completed_invocations: 3,
+                total_consumed_fuel: 400,
+                max_consumed_fuel: 250,
+            }
+        );
+    }
+
+    #[test]
+    fn failures_after_a_replay_divergence_report_the_divergence() {
+        let divergence = || {
+            Some(ReplayDivergence {
+                oplog_index: OplogIndex::from_u64(5),
+                expected: "golem::api::generate_idempotency_key".to_string(),
+                actual: "wasi:clocks/wall-clock::now".to_string(),
+            })
+        };
+        let diverged = TrapType::Error(WorkerError::ReplayDiverged {
+            oplog_index: OplogIndex::from_u64(5),
+            expected: "golem::api::generate_idempotency_key".to_string(),
+            actual: "wasi:clocks/wall-clock::now".to_string(),
+        });
+
+        for trap_type in [
+            TrapType::Error(WorkerError::Unknown("panic".to_string())),
+            TrapType::Error(WorkerError::InvalidRequest("invalid".to_string())),
+            TrapType::Error(WorkerError::StackOverflow),
+            TrapType::Error(WorkerError::OutOfMemory),
+            TrapType::Exit,
+        ] {
+            assert_eq!(trap_type.with_divergence(divergence()), diverged);
+        }
+
+        assert_eq!(
+            TrapType::Interrupt(InterruptKind::Suspend).with_divergence(divergence()),
+            TrapType::Interrupt(InterruptKind::Suspend)
+        );
+        assert_eq!(
+            TrapType::Error(WorkerError::StackOverflow).with_divergence(None),
+            TrapType::Error(WorkerError::StackOverflow)
+        );
+    }
+
+    #[test]
+    fn replay_divergence_reports_are_read_from_error_entries() {
+        let diverged = OplogEntry::error(WorkerError::ReplayDiverged {
+            oplog_index: OplogIndex::from_u64(5),
+            expected: "golem::api::generate_idempotency_key".to_string(),
+            actual: "wasi:clocks/wall-clock::now".to_string(),
+        });
+
+        assert_eq!(
+            ReplayDivergenceReport::from_entry(OplogIndex::from_u64(7), &diverged),
+            Some(ReplayDivergenceReport {
+                error_oplog_index: OplogIndex::from_u64(7),
+                oplog_index: OplogIndex::from_u64(5),
+                expected: "golem::api::generate_idempotency_key".to_string(),
+                actual: "wasi:clocks/wall-clock::now".to_string(),
+            })
+        );
+        assert_eq!(
+            ReplayDivergenceReport::from_entry(
+                OplogIndex::from_u64(8),
+                &OplogEntry::error(WorkerError::StackOverflow)
+            ),
+            None
+        );
+    }
 }
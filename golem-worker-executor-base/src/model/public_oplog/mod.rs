@@ -21,7 +21,8 @@ use crate::durable_host::http::serialized::{
 };
 use crate::durable_host::serialized::{
     SerializableDateTime, SerializableError, SerializableFileTimes, SerializableIpAddress,
-    SerializableIpAddresses, SerializableStreamError,
+    SerializableIpAddresses, SerializableSelfMetadata, SerializableStreamError,
+    SerializableWorkerMetadata,
 };
 use crate::durable_host::wasm_rpc::serialized::{
     SerializableInvokeRequest, SerializableInvokeResult,
@@ -29,33 +30,39 @@ use crate::durable_host::wasm_rpc::serialized::{
 use crate::error::GolemError;
 use crate::model::InterruptKind;
 use crate::services::component::ComponentService;
-use crate::services::oplog::OplogService;
+use crate::services::oplog::{OplogMarker, OplogService};
 use crate::services::rpc::RpcError;
 use crate::services::worker_proxy::WorkerProxyError;
 use async_trait::async_trait;
 use bincode::Decode;
 use golem_api_grpc::proto::golem::worker::UpdateMode;
+use golem_common::model::component_observability::ComponentObservability;
 use golem_common::model::exports::{find_resource_site, function_by_name};
-use golem_common::model::oplog::{OplogEntry, OplogIndex, UpdateDescription};
+use golem_common::model::oplog::{OplogEntry, OplogEntryHeader, OplogIndex, UpdateDescription};
 use golem_common::model::public_oplog::{
-    ChangeRetryPolicyParameters, CreateParameters, DescribeResourceParameters, Empty,
-    EndRegionParameters, ErrorParameters, ExportedFunctionCompletedParameters,
-    ExportedFunctionInvokedParameters, ExportedFunctionParameters, FailedUpdateParameters,
-    GrowMemoryParameters, ImportedFunctionInvokedParameters, JumpParameters, LogParameters,
-    ManualUpdateParameters, PendingUpdateParameters, PendingWorkerInvocationParameters,
-    PublicOplogEntry, PublicUpdateDescription, PublicWorkerInvocation, ResourceParameters,
-    SnapshotBasedUpdateParameters, SuccessfulUpdateParameters, TimestampParameter,
+    ChangeEnvironmentParameters, ChangeRetryPolicyParameters, CreateParameters,
+    DescribeResourceParameters, Empty, EndRegionParameters, ErrorParameters,
+    ExportedFunctionCompletedParameters, ExportedFunctionInvokedParameters,
+    ExportedFunctionParameters, FailedUpdateParameters, ForkedParameters, GrowMemoryParameters,
+    ImportedFunctionInvokedParameters, InvocationContextParameters, JumpParameters, LogParameters,
+    ManualUpdateParameters, NetworkEgressDeniedParameters, PendingUpdateParameters,
+    PendingWorkerInvocationParameters, PublicOplogEntry, PublicUpdateDescription,
+    PublicWorkerInvocation, ResourceParameters, ReviveParameters, SnapshotBasedUpdateParameters,
+    SnapshotParameters, SuccessfulUpdateParameters, TimestampParameter,
 };
+use golem_common::model::regions::DeletedRegions;
 use golem_common::model::{
-    ComponentId, ComponentVersion, IdempotencyKey, OwnedWorkerId, PromiseId, ShardId, WorkerId,
-    WorkerInvocation,
+    ComponentId, ComponentVersion, IdempotencyKey, OwnedWorkerId, PromiseId, ScanCursor, ShardId,
+    WorkerFilter, WorkerId, WorkerInvocation,
 };
 use golem_common::serialization::try_deserialize as core_try_deserialize;
 use golem_wasm_ast::analysis::analysed_type::{
-    case, field, list, option, r#enum, record, result, result_err, str, tuple, u16, u32, u64, u8,
-    unit_case, variant,
+    case, f64, field, list, option, r#enum, record, result, result_err, str, tuple, u16, u32, u64,
+    u8, unit_case, variant,
+};
+use golem_wasm_ast::analysis::{
+    AnalysedFunctionParameter, AnalysedType, NameOptionTypePair, TypeVariant,
 };
-use golem_wasm_ast::analysis::{AnalysedType, NameOptionTypePair, TypeVariant};
 use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
 use golem_wasm_rpc::{
     type_annotated_value_from_str, IntoValue, IntoValueAndType, Value, ValueAndType, WitValue,
@@ -66,6 +73,9 @@ use std::net::IpAddr;
 use std::sync::Arc;
 use uuid::Uuid;
 
+/// Value shown in place of sensitive parameters in the public oplog
+pub const REDACTED_VALUE: &str = "<redacted>";
+
 pub struct PublicOplogChunk {
     pub entries: Vec<PublicOplogEntry>,
     pub next_oplog_index: OplogIndex,
@@ -83,7 +93,7 @@ pub async fn get_public_oplog_chunk(
     count: usize,
 ) -> Result<PublicOplogChunk, String> {
     let raw_entries = oplog_service
-        .read(owned_worker_id, initial_oplog_index, count as u64)
+        .read_with_logs(owned_worker_id, initial_oplog_index, count as u64)
         .await;
 
     let last_index = oplog_service.get_last_index(owned_worker_id).await;
@@ -123,32 +133,158 @@ pub async fn find_component_version_at(
     owned_worker_id: &OwnedWorkerId,
     start: OplogIndex,
 ) -> Result<ComponentVersion, GolemError> {
+    const PAGE_SIZE: u64 = 1000;
+
     let mut initial_component_version = 0;
     let last_oplog_index = oplog_service.get_last_index(owned_worker_id).await;
-    let mut current = OplogIndex::INITIAL;
-    while current < start && current <= last_oplog_index {
-        // NOTE: could be reading in pages for optimization
-        let entry = oplog_service
-            .read(owned_worker_id, current, 1)
+    // Looking at the entries before `start`
+    let last_index = u64::from(start)
+        .saturating_sub(1)
+        .min(u64::from(last_oplog_index));
+    if last_index >= u64::from(OplogIndex::INITIAL) {
+        if let Some(markers) = oplog_service
+            .read_markers(
+                owned_worker_id,
+                OplogIndex::INITIAL,
+                OplogIndex::from_u64(last_index),
+            )
             .await
-            .iter()
-            .next()
-            .map(|(_, v)| v.clone());
-
-        if let Some(OplogEntry::Create {
-            component_version, ..
-        }) = entry
         {
-            initial_component_version = component_version;
-        } else if let Some(OplogEntry::SuccessfulUpdate { target_version, .. }) = entry {
-            initial_component_version = target_version;
+            let component_version = markers
+                .into_values()
+                .filter_map(|marker| match marker {
+                    OplogMarker::ComponentVersion(component_version) => Some(component_version),
+                    _ => None,
+                })
+                .last();
+            return Ok(component_version.unwrap_or(initial_component_version));
+        }
+    }
+
+    let mut current = u64::from(OplogIndex::INITIAL);
+    while current <= last_index {
+        let page_end = (current + PAGE_SIZE - 1).min(last_index);
+        // Only the headers are decoded, except for the entries changing the component version
+        let raw_entries = oplog_service
+            .read_raw_range(
+                owned_worker_id,
+                OplogIndex::from_u64(current),
+                OplogIndex::from_u64(page_end),
+            )
+            .await;
+        for (idx, raw_entry) in raw_entries {
+            let header = raw_entry.header().map_err(|err| {
+                GolemError::unknown(format!(
+                    "Failed to decode oplog entry header at {idx}: {err}"
+                ))
+            })?;
+            if header.changes_component_version() {
+                match raw_entry.decode() {
+                    Ok(OplogEntry::Create {
+                        component_version, ..
+                    }) => initial_component_version = component_version,
+                    Ok(OplogEntry::SuccessfulUpdate { target_version, .. }) => {
+                        initial_component_version = target_version
+                    }
+                    Ok(_) => {}
+                    Err((_, err)) => {
+                        return Err(GolemError::unknown(format!(
+                            "Failed to decode oplog entry at {idx}: {err}"
+                        )))
+                    }
+                }
+            }
         }
-        current = current.next();
+        current = page_end + 1;
     }
 
     Ok(initial_component_version)
 }
 
+/// Finds the oplog indices of the `ExportedFunctionInvoked` entries of the last `count`
+/// invocations started before `before`, in ascending order.
+///
+/// Invocations started in the `deleted_regions` of the worker, for example the ones removed by a
+/// revert, are not counted.
+///
+/// Uses the marker index of the oplog if it has one, otherwise scans the entries backwards.
+pub async fn find_last_invocation_starts(
+    oplog_service: Arc<dyn OplogService + Send + Sync>,
+    owned_worker_id: &OwnedWorkerId,
+    deleted_regions: &DeletedRegions,
+    before: OplogIndex,
+    count: usize,
+) -> Result<Vec<OplogIndex>, GolemError> {
+    // The markers are sparse, so they can be read in much bigger pages than the entries
+    const MARKER_PAGE_SIZE: u64 = 100000;
+    const ENTRY_PAGE_SIZE: u64 = 1000;
+
+    let last_oplog_index = oplog_service.get_last_index(owned_worker_id).await;
+    let mut last = u64::from(before)
+        .saturating_sub(1)
+        .min(u64::from(last_oplog_index));
+    // Newest first
+    let mut starts = Vec::new();
+    while last >= u64::from(OplogIndex::INITIAL) && starts.len() < count {
+        let first = (last + 1)
+            .saturating_sub(MARKER_PAGE_SIZE)
+            .max(u64::from(OplogIndex::INITIAL));
+        let markers = oplog_service
+            .read_markers(
+                owned_worker_id,
+                OplogIndex::from_u64(first),
+                OplogIndex::from_u64(last),
+            )
+            .await;
+        let (first, page_starts) = match markers {
+            Some(markers) => (
+                first,
+                markers
+                    .into_iter()
+                    .filter(|(idx, marker)| {
+                        *marker == OplogMarker::InvocationStart
+                            && !deleted_regions.is_in_deleted_region(*idx)
+                    })
+                    .map(|(idx, _)| idx)
+                    .collect::<Vec<_>>(),
+            ),
+            None => {
+                let first = (last + 1)
+                    .saturating_sub(ENTRY_PAGE_SIZE)
+                    .max(u64::from(OplogIndex::INITIAL));
+                // Only the headers of the entries are decoded
+                let raw_entries = oplog_service
+                    .read_raw_range(
+                        owned_worker_id,
+                        OplogIndex::from_u64(first),
+                        OplogIndex::from_u64(last),
+                    )
+                    .await;
+                let mut page_starts = Vec::new();
+                for (idx, raw_entry) in raw_entries {
+                    let header = raw_entry.header().map_err(|err| {
+                        GolemError::unknown(format!(
+                            "Failed to decode oplog entry header at {idx}: {err}"
+                        ))
+                    })?;
+                    if header.variant == OplogEntryHeader::EXPORTED_FUNCTION_INVOKED
+                        && !deleted_regions.is_in_deleted_region(idx)
+                    {
+                        page_starts.push(idx);
+                    }
+                }
+                (first, page_starts)
+            }
+        };
+        let remaining = count - starts.len();
+        starts.extend(page_starts.into_iter().rev().take(remaining));
+        last = first - 1;
+    }
+    starts.reverse();
+
+    Ok(starts)
+}
+
 #[async_trait]
 pub trait PublicOplogEntryOps: Sized {
     async fn from_oplog_entry(
@@ -264,11 +400,15 @@ impl PublicOplogEntryOps for PublicOplogEntry {
                 let function = function_by_name(&metadata.exports, &function_name)?.ok_or(
                     format!("Exported function {function_name} not found in component {} version {component_version}", owned_worker_id.component_id())
                 )?;
+                let observability =
+                    components.get_observability(&owned_worker_id.worker_id.component_id);
                 let request = function
                     .parameters
                     .iter()
                     .zip(params)
-                    .map(|(param, value)| ValueAndType::new(value, param.typ.clone()))
+                    .map(|(param, value)| {
+                        redact_sensitive_parameter(&observability, &function_name, param, value)
+                    })
                     .collect();
 
                 Ok(PublicOplogEntry::ExportedFunctionInvoked(
@@ -370,6 +510,7 @@ impl PublicOplogEntryOps for PublicOplogEntry {
                         idempotency_key,
                         full_function_name,
                         function_input,
+                        ..
                     } => {
                         let metadata = components
                             .get_metadata(
@@ -389,13 +530,20 @@ impl PublicOplogEntryOps for PublicOplogEntry {
                         let mut params = None;
                         if let Some(function) = function {
                             if function.parameters.len() == function_input.len() {
+                                let observability = components
+                                    .get_observability(&owned_worker_id.worker_id.component_id);
                                 params = Some(
                                     function
                                         .parameters
                                         .iter()
                                         .zip(function_input)
                                         .map(|(param, value)| {
-                                            ValueAndType::new(value, param.typ.clone())
+                                            redact_sensitive_parameter(
+                                                &observability,
+                                                &full_function_name,
+                                                param,
+                                                value,
+                                            )
                                         })
                                         .collect(),
                                 );
@@ -547,10 +695,77 @@ impl PublicOplogEntryOps for PublicOplogEntry {
             OplogEntry::Restart { timestamp } => {
                 Ok(PublicOplogEntry::Restart(TimestampParameter { timestamp }))
             }
+            OplogEntry::Snapshot {
+                timestamp,
+                component_version,
+                ..
+            } => Ok(PublicOplogEntry::Snapshot(SnapshotParameters {
+                timestamp,
+                component_version,
+            })),
+            OplogEntry::NetworkEgressDenied {
+                timestamp,
+                host,
+                rule,
+            } => Ok(PublicOplogEntry::NetworkEgressDenied(
+                NetworkEgressDeniedParameters {
+                    timestamp,
+                    host,
+                    rule,
+                },
+            )),
+            OplogEntry::Revive {
+                timestamp,
+                trimmed_region,
+            } => Ok(PublicOplogEntry::Revive(ReviveParameters {
+                timestamp,
+                trimmed_region,
+            })),
+            OplogEntry::ChangeEnvironment {
+                timestamp,
+                args,
+                env,
+            } => Ok(PublicOplogEntry::ChangeEnvironment(
+                ChangeEnvironmentParameters {
+                    timestamp,
+                    args,
+                    env: env.into_iter().collect(),
+                },
+            )),
+            OplogEntry::InvocationContext { timestamp, context } => {
+                Ok(PublicOplogEntry::InvocationContext(
+                    InvocationContextParameters { timestamp, context },
+                ))
+            }
+            OplogEntry::Forked {
+                timestamp,
+                source_worker_id,
+                source_oplog_index,
+            } => Ok(PublicOplogEntry::Forked(ForkedParameters {
+                timestamp,
+                source_worker_id,
+                source_oplog_index,
+            })),
         }
     }
 }
 
+/// Masks the value of the exported function parameters marked as sensitive for the component.
+/// Only the public projection is affected, the raw oplog entries keep the original values
+/// for replay.
+fn redact_sensitive_parameter(
+    observability: &ComponentObservability,
+    function_name: &str,
+    parameter: &AnalysedFunctionParameter,
+    value: Value,
+) -> ValueAndType {
+    if observability.is_sensitive_parameter(function_name, &parameter.name) {
+        ValueAndType::new(Value::String(REDACTED_VALUE.to_string()), str())
+    } else {
+        ValueAndType::new(value, parameter.typ.clone())
+    }
+}
+
 fn try_deserialize<T: Decode>(data: &[u8]) -> Result<T, String> {
     core_try_deserialize(data)?.ok_or("Unexpected oplog payload, cannot deserialize".to_string())
 }
@@ -710,6 +925,31 @@ fn encode_host_function_request_as_value(
                 ]),
             ))
         }
+        "golem::api::get-workers::get-next" => {
+            let payload: (ComponentId, Option<WorkerFilter>, ScanCursor, u64, bool) =
+                try_deserialize(bytes)?;
+            Ok(ValueAndType::new(
+                Value::Record(vec![
+                    payload.0.into_value(),
+                    payload.1.map(|filter| filter.to_string()).into_value(),
+                    Value::String(payload.2.to_string()),
+                    payload.3.into_value(),
+                    payload.4.into_value(),
+                ]),
+                record(vec![
+                    field("component_id", ComponentId::get_type()),
+                    field("filter", option(str())),
+                    field("cursor", str()),
+                    field("count", u64()),
+                    field("precise", bool::get_type()),
+                ]),
+            ))
+        }
+        "golem::api::get-worker-metadata" => {
+            let payload: WorkerId = try_deserialize(bytes)?;
+            Ok(payload.into_value_and_type())
+        }
+        "golem::api::get-self-metadata" => no_payload(),
         "http::types::incoming_body_stream::skip" => {
             let payload: SerializableHttpRequest = try_deserialize(bytes)?;
             Ok(payload.into_value_and_type())
@@ -998,6 +1238,25 @@ fn encode_host_function_response_as_value(
             let payload: Result<(), SerializableError> = try_deserialize(bytes)?;
             Ok(payload.into_value_and_type())
         }
+        "golem::api::get-workers::get-next" => {
+            let payload: Result<
+                (Option<ScanCursor>, Vec<SerializableWorkerMetadata>),
+                SerializableError,
+            > = try_deserialize(bytes)?;
+            Ok(payload
+                .map(|(cursor, workers)| (cursor.map(|cursor| cursor.to_string()), workers))
+                .into_value_and_type())
+        }
+        "golem::api::get-worker-metadata" => {
+            let payload: Result<Option<SerializableWorkerMetadata>, SerializableError> =
+                try_deserialize(bytes)?;
+            Ok(payload.into_value_and_type())
+        }
+        "golem::api::get-self-metadata" => {
+            let payload: Result<SerializableSelfMetadata, SerializableError> =
+                try_deserialize(bytes)?;
+            Ok(payload.into_value_and_type())
+        }
         "http::types::incoming_body_stream::skip" => {
             let payload: Result<u64, SerializableStreamError> = try_deserialize(bytes)?;
             Ok(payload.into_value_and_type())
@@ -1759,6 +2018,42 @@ impl IntoValue for GolemError {
                     case_idx: 22,
                     case_value: None,
                 },
+                GolemError::TooManyPendingInvocations {
+                    worker_id,
+                    pending_invocations,
+                } => Value::Variant {
+                    case_idx: 23,
+                    case_value: Some(Box::new(Value::Record(vec![
+                        worker_id.into_value(),
+                        pending_invocations.into_value(),
+                    ]))),
+                },
+                GolemError::InvocationTimedOut {
+                    worker_id,
+                    timeout_millis,
+                } => Value::Variant {
+                    case_idx: 24,
+                    case_value: Some(Box::new(Value::Record(vec![
+                        worker_id.into_value(),
+                        timeout_millis.into_value(),
+                    ]))),
+                },
+                GolemError::ExecutorBusy { max_active_workers } => Value::Variant {
+                    case_idx: 25,
+                    case_value: Some(Box::new(Value::Record(vec![
+                        max_active_workers.into_value()
+                    ]))),
+                },
+                GolemError::InvocationNotFound {
+                    worker_id,
+                    idempotency_key,
+                } => Value::Variant {
+                    case_idx: 26,
+                    case_value: Some(Box::new(Value::Record(vec![
+                        worker_id.into_value(),
+                        idempotency_key.into_value(),
+                    ]))),
+                },
             }
         }
         into_value(self, true)
@@ -1852,6 +2147,31 @@ impl IntoValue for GolemError {
                 unit_case("PreviousInvocationExited"),
                 case("Unknown", record(vec![field("details", str())])),
                 unit_case("ShardingNotReady"),
+                case(
+                    "TooManyPendingInvocations",
+                    record(vec![
+                        field("worker_id", WorkerId::get_type()),
+                        field("pending_invocations", u64()),
+                    ]),
+                ),
+                case(
+                    "InvocationTimedOut",
+                    record(vec![
+                        field("worker_id", WorkerId::get_type()),
+                        field("timeout_millis", u64()),
+                    ]),
+                ),
+                case(
+                    "ExecutorBusy",
+                    record(vec![field("max_active_workers", u64())]),
+                ),
+                case(
+                    "InvocationNotFound",
+                    record(vec![
+                        field("worker_id", WorkerId::get_type()),
+                        field("idempotency_key", IdempotencyKey::get_type()),
+                    ]),
+                ),
             ])
         }
         get_type(true)
@@ -1900,6 +2220,10 @@ impl IntoValue for WorkerProxyError {
                 case_idx: 5,
                 case_value: Some(Box::new(error.into_value())),
             },
+            WorkerProxyError::Forbidden(error) => Value::Variant {
+                case_idx: 6,
+                case_value: Some(Box::new(error.into_value())),
+            },
         }
     }
 
@@ -1911,6 +2235,7 @@ impl IntoValue for WorkerProxyError {
             case("NotFound", str()),
             case("AlreadyExists", str()),
             case("InternalError", GolemError::get_type()),
+            case("Forbidden", str()),
         ])
     }
 }
@@ -1969,6 +2294,67 @@ impl IntoValue for SerializableIpAddress {
     }
 }
 
+impl IntoValue for SerializableWorkerMetadata {
+    fn into_value(self) -> Value {
+        Value::Record(vec![
+            self.worker_id.into_value(),
+            self.args.into_value(),
+            self.env.into_value(),
+            Value::String(self.status.to_string()),
+            self.component_version.into_value(),
+        ])
+    }
+
+    fn get_type() -> AnalysedType {
+        record(vec![
+            field("worker_id", WorkerId::get_type()),
+            field("args", list(str())),
+            field("env", list(tuple(vec![str(), str()]))),
+            field("status", str()),
+            field("component_version", u64()),
+        ])
+    }
+}
+
+impl IntoValue for SerializableSelfMetadata {
+    fn into_value(self) -> Value {
+        Value::Record(vec![
+            self.metadata.into_value(),
+            self.created_at.to_millis().into_value(),
+            Value::Record(vec![
+                self.retry_config.max_attempts.into_value(),
+                (self.retry_config.min_delay.as_millis() as u64).into_value(),
+                (self.retry_config.max_delay.as_millis() as u64).into_value(),
+                Value::F64(self.retry_config.multiplier),
+                Value::Option(
+                    self.retry_config
+                        .max_jitter_factor
+                        .map(|factor| Box::new(Value::F64(factor))),
+                ),
+            ]),
+            self.pending_update_count.into_value(),
+        ])
+    }
+
+    fn get_type() -> AnalysedType {
+        record(vec![
+            field("metadata", SerializableWorkerMetadata::get_type()),
+            field("created_at", u64()),
+            field(
+                "retry_config",
+                record(vec![
+                    field("max_attempts", u32()),
+                    field("min_delay_millis", u64()),
+                    field("max_delay_millis", u64()),
+                    field("multiplier", f64()),
+                    field("max_jitter_factor", option(f64())),
+                ]),
+            ),
+            field("pending_update_count", u32()),
+        ])
+    }
+}
+
 impl IntoValue for SerializableFileTimes {
     fn into_value(self) -> Value {
         Value::Record(vec![
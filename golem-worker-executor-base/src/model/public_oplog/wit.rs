@@ -16,13 +16,15 @@ use crate::model::public_oplog::{PublicOplogEntry, PublicUpdateDescription};
 use crate::preview2::golem::api1_1_0_rc1::oplog;
 use crate::preview2::wasi::clocks::wall_clock::Datetime;
 use golem_common::model::public_oplog::{
-    ChangeRetryPolicyParameters, CreateParameters, DescribeResourceParameters, EndRegionParameters,
-    ErrorParameters, ExportedFunctionCompletedParameters, ExportedFunctionInvokedParameters,
-    ExportedFunctionParameters, FailedUpdateParameters, GrowMemoryParameters,
-    ImportedFunctionInvokedParameters, JumpParameters, LogParameters, ManualUpdateParameters,
-    PendingUpdateParameters, PendingWorkerInvocationParameters, PublicRetryConfig,
-    PublicWorkerInvocation, PublicWrappedFunctionType, ResourceParameters,
-    SnapshotBasedUpdateParameters, SuccessfulUpdateParameters, TimestampParameter,
+    ChangeEnvironmentParameters, ChangeRetryPolicyParameters, CreateParameters,
+    DescribeResourceParameters, EndRegionParameters, ErrorParameters,
+    ExportedFunctionCompletedParameters, ExportedFunctionInvokedParameters,
+    ExportedFunctionParameters, FailedUpdateParameters, ForkedParameters, GrowMemoryParameters,
+    ImportedFunctionInvokedParameters, InvocationContextParameters, JumpParameters, LogParameters,
+    ManualUpdateParameters, NetworkEgressDeniedParameters, PendingUpdateParameters,
+    PendingWorkerInvocationParameters, PublicRetryConfig, PublicWorkerInvocation,
+    PublicWrappedFunctionType, ResourceParameters, ReviveParameters, SnapshotBasedUpdateParameters,
+    SnapshotParameters, SuccessfulUpdateParameters, TimestampParameter,
     WriteRemoteBatchedParameters,
 };
 use golem_common::model::Timestamp;
@@ -218,6 +220,51 @@ impl From<PublicOplogEntry> for oplog::OplogEntry {
             PublicOplogEntry::Restart(TimestampParameter { timestamp }) => {
                 Self::Restart(timestamp.into())
             }
+            // This version of the oplog interface has no snapshot entry, and snapshots have no
+            // effect visible to the worker
+            PublicOplogEntry::Snapshot(SnapshotParameters { timestamp, .. }) => {
+                Self::NoOp(timestamp.into())
+            }
+            // This version of the oplog interface has no network egress entry, so denials are
+            // presented as warnings
+            PublicOplogEntry::NetworkEgressDenied(NetworkEgressDeniedParameters {
+                timestamp,
+                host,
+                rule,
+            }) => Self::Log(oplog::LogParameters {
+                timestamp: timestamp.into(),
+                level: oplog::LogLevel::Warn,
+                context: "network-egress".to_string(),
+                message: match rule {
+                    Some(rule) => format!("Request to {host} denied by rule {rule}"),
+                    None => format!("Request to {host} denied as it is not allowed by any rule"),
+                },
+            }),
+            // This version of the oplog interface has no revive entry, the trimmed region is
+            // presented as a jump
+            PublicOplogEntry::Revive(ReviveParameters {
+                timestamp,
+                trimmed_region,
+            }) => match trimmed_region {
+                Some(region) => Self::Jump(oplog::JumpParameters {
+                    timestamp: timestamp.into(),
+                    start: region.start.into(),
+                    end: region.end.into(),
+                }),
+                None => Self::NoOp(timestamp.into()),
+            },
+            // This version of the oplog interface has no entry for changing the environment
+            PublicOplogEntry::ChangeEnvironment(ChangeEnvironmentParameters {
+                timestamp, ..
+            }) => Self::NoOp(timestamp.into()),
+            // This version of the oplog interface has no entry for the invocation context
+            PublicOplogEntry::InvocationContext(InvocationContextParameters {
+                timestamp, ..
+            }) => Self::NoOp(timestamp.into()),
+            // This version of the oplog interface has no entry for forking
+            PublicOplogEntry::Forked(ForkedParameters { timestamp, .. }) => {
+                Self::NoOp(timestamp.into())
+            }
         }
     }
 }
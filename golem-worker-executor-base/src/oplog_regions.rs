@@ -0,0 +1,270 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Deleting regions of a worker's oplog through the admin API, to recover workers from entries
+//! which cannot be replayed.
+//!
+//! The region is recorded by a `Jump` entry, the same way as the regions deleted by the workers
+//! themselves, so it is skipped by every later recovery of the worker.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use golem_common::model::oplog::{OplogEntry, OplogIndex};
+use golem_common::model::regions::OplogRegion;
+use golem_common::model::{OwnedWorkerId, TimestampedWorkerInvocation};
+
+use crate::error::GolemError;
+use crate::services::oplog::Oplog;
+use crate::services::{HasConfig, HasOplogService, HasWorkerService};
+use crate::worker::calculate_last_known_status;
+
+/// Deletes the `region` of the worker's oplog after validating that the worker can still be
+/// replayed without it, see [validate_deleted_region]. Returns the index of the written `Jump`
+/// entry.
+///
+/// Nothing else may write to the worker's `oplog` until the `Jump` entry is written, so the
+/// worker must be stopped and prevented from starting, see `Worker::delete_oplog_region`.
+pub async fn delete_oplog_region<T>(
+    deps: &T,
+    owned_worker_id: &OwnedWorkerId,
+    oplog: &Arc<dyn Oplog + Send + Sync>,
+    region: OplogRegion,
+) -> Result<OplogIndex, GolemError>
+where
+    T: HasOplogService + HasWorkerService + HasConfig,
+{
+    let metadata = deps
+        .worker_service()
+        .get(owned_worker_id)
+        .await
+        .ok_or(GolemError::worker_not_found(owned_worker_id.worker_id()))?;
+    let status = calculate_last_known_status(deps, owned_worker_id, &Some(metadata)).await?;
+
+    let last_index = oplog.current_oplog_index().await;
+    if region.start <= OplogIndex::INITIAL || region.start > region.end || region.end > last_index {
+        return Err(GolemError::invalid_request(format!(
+            "Oplog region {region} is not a valid region of the worker's oplog ({}..={last_index})",
+            OplogIndex::INITIAL.next()
+        )));
+    }
+
+    let mut entries = deps
+        .oplog_service()
+        .read_range(owned_worker_id, OplogIndex::INITIAL.next(), last_index)
+        .await;
+    entries.retain(|idx, _| !status.deleted_regions.is_in_deleted_region(*idx));
+
+    let problems = validate_deleted_region(&entries, &status.pending_invocations, &region);
+    if !problems.is_empty() {
+        return Err(GolemError::invalid_request(format!(
+            "Oplog region {region} cannot be deleted: {}",
+            problems.join("; ")
+        )));
+    }
+
+    Ok(oplog.add_and_commit(OplogEntry::jump(region)).await)
+}
+
+/// Checks that the worker can be replayed consistently without the oplog entries of `region`,
+/// given its oplog entries not deleted already. Returns the list of found problems.
+///
+/// Invocations, atomic regions and remote writes must be deleted completely or not at all,
+/// resources created in the region must not be used after it, updates cannot be deleted, and
+/// invocations enqueued in the region must not be pending anymore.
+pub fn validate_deleted_region(
+    entries: &BTreeMap<OplogIndex, OplogEntry>,
+    pending_invocations: &[TimestampedWorkerInvocation],
+    region: &OplogRegion,
+) -> Vec<String> {
+    let mut problems = Vec::new();
+    let mut current_invocation = None;
+    let mut created_resources = HashMap::new();
+
+    for (idx, entry) in entries {
+        let idx = *idx;
+        match entry {
+            OplogEntry::ExportedFunctionInvoked { .. } => current_invocation = Some(idx),
+            OplogEntry::ExportedFunctionCompleted { .. } => {
+                if let Some(begin) = current_invocation.take() {
+                    if overlaps_partially(region, begin, idx) {
+                        problems.push(format!(
+                            "the invocation between {begin} and {idx} is only partially in the region"
+                        ));
+                    }
+                }
+            }
+            OplogEntry::EndAtomicRegion { begin_index, .. } => {
+                if overlaps_partially(region, *begin_index, idx) {
+                    problems.push(format!(
+                        "the atomic region between {begin_index} and {idx} is only partially in the region"
+                    ));
+                }
+            }
+            OplogEntry::EndRemoteWrite { begin_index, .. } => {
+                if overlaps_partially(region, *begin_index, idx) {
+                    problems.push(format!(
+                        "the remote write between {begin_index} and {idx} is only partially in the region"
+                    ));
+                }
+            }
+            OplogEntry::CreateResource { id, .. } if region.contains(idx) => {
+                created_resources.insert(*id, idx);
+            }
+            OplogEntry::DropResource { id, .. } | OplogEntry::DescribeResource { id, .. }
+                if idx > region.end =>
+            {
+                if let Some(created) = created_resources.get(id) {
+                    problems.push(format!(
+                        "resource {id} created at {created} is used at {idx}, after the region"
+                    ));
+                }
+            }
+            OplogEntry::PendingUpdate { .. }
+            | OplogEntry::SuccessfulUpdate { .. }
+            | OplogEntry::FailedUpdate { .. }
+            | OplogEntry::Snapshot { .. }
+                if region.contains(idx) =>
+            {
+                problems.push(format!("the update entry at {idx} cannot be deleted"));
+            }
+            OplogEntry::PendingWorkerInvocation {
+                timestamp,
+                invocation,
+            } if region.contains(idx) => {
+                if pending_invocations.iter().any(|pending| {
+                    pending.timestamp == *timestamp && pending.invocation == *invocation
+                }) {
+                    problems.push(format!("the invocation enqueued at {idx} is still pending"));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // The unfinished invocation lasts until the end of the oplog
+    if let (Some(begin), Some(last)) = (current_invocation, entries.keys().next_back()) {
+        if overlaps_partially(region, begin, *last) {
+            problems.push(format!(
+                "the unfinished invocation started at {begin} is only partially in the region"
+            ));
+        }
+    }
+
+    problems
+}
+
+/// Returns true if the region contains some, but not all, of the entries between `start` and `end`
+fn overlaps_partially(region: &OplogRegion, start: OplogIndex, end: OplogIndex) -> bool {
+    let overlaps = start <= region.end && region.start <= end;
+    let covers = region.start <= start && end <= region.end;
+    overlaps && !covers
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::collections::BTreeMap;
+
+    use golem_common::model::oplog::{OplogEntry, OplogIndex, OplogPayload, WorkerResourceId};
+    use golem_common::model::regions::OplogRegion;
+    use golem_common::model::{IdempotencyKey, Timestamp};
+
+    use crate::oplog_regions::validate_deleted_region;
+
+    fn invoked() -> OplogEntry {
+        OplogEntry::ExportedFunctionInvoked {
+            timestamp: Timestamp::now_utc(),
+            function_name: "golem:it/api.{add-item}".to_string(),
+            request: OplogPayload::Inline(vec![]),
+            idempotency_key: IdempotencyKey::fresh(),
+        }
+    }
+
+    fn completed() -> OplogEntry {
+        OplogEntry::ExportedFunctionCompleted {
+            timestamp: Timestamp::now_utc(),
+            response: OplogPayload::Inline(vec![]),
+            consumed_fuel: 0,
+        }
+    }
+
+    fn entries(entries: Vec<OplogEntry>) -> BTreeMap<OplogIndex, OplogEntry> {
+        entries
+            .into_iter()
+            .enumerate()
+            .map(|(idx, entry)| (OplogIndex::from_u64(idx as u64 + 2), entry))
+            .collect()
+    }
+
+    fn region(start: u64, end: u64) -> OplogRegion {
+        OplogRegion::from_range(start..=end)
+    }
+
+    #[test]
+    fn whole_invocations_can_be_deleted() {
+        let entries = entries(vec![
+            invoked(),
+            completed(),
+            invoked(),
+            OplogEntry::nop(),
+            completed(),
+            invoked(),
+            completed(),
+        ]);
+        assert!(validate_deleted_region(&entries, &[], &region(4, 6)).is_empty());
+        assert!(validate_deleted_region(&entries, &[], &region(2, 8)).is_empty());
+    }
+
+    #[test]
+    fn partially_deleted_invocations_are_rejected() {
+        let entries = entries(vec![invoked(), OplogEntry::nop(), completed(), invoked()]);
+        assert_eq!(
+            validate_deleted_region(&entries, &[], &region(3, 4)),
+            vec!["the invocation between 2 and 4 is only partially in the region".to_string()]
+        );
+        assert_eq!(
+            validate_deleted_region(&entries, &[], &region(2, 4)),
+            Vec::<String>::new()
+        );
+
+        let entries = entries(vec![invoked(), OplogEntry::nop(), OplogEntry::nop()]);
+        assert_eq!(
+            validate_deleted_region(&entries, &[], &region(3, 3)),
+            vec![
+                "the unfinished invocation started at 2 is only partially in the region"
+                    .to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn resources_used_after_the_region_are_rejected() {
+        let id = WorkerResourceId(1);
+        let entries = entries(vec![
+            invoked(),
+            OplogEntry::create_resource(id),
+            completed(),
+            invoked(),
+            OplogEntry::drop_resource(id),
+            completed(),
+        ]);
+        assert_eq!(
+            validate_deleted_region(&entries, &[], &region(2, 4)),
+            vec!["resource 1 created at 3 is used at 6, after the region".to_string()]
+        );
+        assert!(validate_deleted_region(&entries, &[], &region(2, 7)).is_empty());
+    }
+}
@@ -17,6 +17,122 @@ use std::mem;
 
 include!(concat!(env!("OUT_DIR"), "/preview2_mod.rs"));
 
+pub mod api_v1_2 {
+    wasmtime::component::bindgen!({
+        path: "wit/api-v1.2",
+        interfaces: "
+          import golem:api/self-metadata@1.2.0;
+        ",
+        tracing: false,
+        async: true,
+        trappable_imports: true,
+    });
+}
+
+pub mod invocation_context {
+    wasmtime::component::bindgen!({
+        path: "wit/invocation-context",
+        interfaces: "
+          import golem:invocation-context/context@0.1.0;
+        ",
+        tracing: false,
+        async: true,
+        trappable_imports: true,
+    });
+}
+
+pub mod rpc_batch {
+    wasmtime::component::bindgen!({
+        path: "wit/rpc-batch",
+        interfaces: "
+          import golem:rpc-batch/batch@0.1.0;
+        ",
+        tracing: false,
+        async: true,
+        trappable_imports: true,
+        with: {
+            "wasi:io/poll": wasmtime_wasi::bindings::io::poll,
+            "golem:rpc/types": golem_wasm_rpc::golem::rpc::types,
+        },
+    });
+}
+
+pub mod saga {
+    wasmtime::component::bindgen!({
+        path: "wit/saga",
+        interfaces: "
+          import golem:saga/saga@0.1.0;
+        ",
+        tracing: false,
+        async: true,
+        trappable_imports: true,
+        with: {
+            "wasi:io/poll": wasmtime_wasi::bindings::io::poll,
+            "golem:rpc/types": golem_wasm_rpc::golem::rpc::types,
+        },
+    });
+}
+
+pub mod secrets {
+    wasmtime::component::bindgen!({
+        path: "wit/secrets",
+        interfaces: "
+          import golem:secrets/secrets@0.1.0;
+        ",
+        tracing: false,
+        async: true,
+        trappable_imports: true,
+    });
+}
+
+pub mod shared_kv {
+    wasmtime::component::bindgen!({
+        path: "wit/shared-kv",
+        interfaces: "
+          import golem:shared-kv/store@0.1.0;
+        ",
+        tracing: false,
+        async: true,
+        trappable_imports: true,
+    });
+}
+
+pub mod topics {
+    wasmtime::component::bindgen!({
+        path: "wit/topics",
+        interfaces: "
+          import golem:topics/pubsub@0.1.0;
+        ",
+        tracing: false,
+        async: true,
+        trappable_imports: true,
+    });
+}
+
+pub mod worker_blobs {
+    wasmtime::component::bindgen!({
+        path: "wit/worker-blobs",
+        interfaces: "
+          import golem:worker-blobs/store@0.1.0;
+        ",
+        tracing: false,
+        async: true,
+        trappable_imports: true,
+    });
+}
+
+pub mod worker_kv {
+    wasmtime::component::bindgen!({
+        path: "wit/worker-kv",
+        interfaces: "
+          import golem:worker-kv/store@0.1.0;
+        ",
+        tracing: false,
+        async: true,
+        trappable_imports: true,
+    });
+}
+
 pub type InputStream = wasmtime_wasi::InputStream;
 pub type OutputStream = wasmtime_wasi::OutputStream;
 
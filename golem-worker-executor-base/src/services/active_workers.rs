@@ -12,17 +12,25 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::{Mutex, OwnedSemaphorePermit, Semaphore, TryAcquireError};
 
+use dashmap::DashMap;
 use tracing::{debug, Instrument};
+use wasmtime::component::{Component, InstancePre, Linker};
 
 use golem_common::cache::{BackgroundEvictionMode, Cache, FullCacheEvictionMode, SimpleCache};
-use golem_common::model::{OwnedWorkerId, WorkerId};
+use golem_common::model::{
+    AccountId, ComponentId, ComponentType, ComponentVersion, OwnedWorkerId, WorkerId,
+};
 
 use crate::error::GolemError;
-use crate::services::golem_config::MemoryConfig;
+use crate::metrics::workers::record_worker_admission;
+use crate::services::golem_config::{EphemeralConfig, InvocationSchedulingConfig, MemoryConfig};
+use crate::services::invocation_scheduling::{FairShareScheduler, InvocationPermitSlot};
 use crate::services::HasAll;
 use crate::worker::Worker;
 use crate::workerctx::WorkerCtx;
@@ -31,12 +39,30 @@ use crate::workerctx::WorkerCtx;
 pub struct ActiveWorkers<Ctx: WorkerCtx> {
     workers: Cache<WorkerId, (), Arc<Worker<Ctx>>, GolemError>,
     worker_memory: Arc<Semaphore>,
+    /// `None` if the number of workers loaded in memory is not limited
+    active_worker_slots: Option<ActiveWorkerSlots>,
     priority_allocation_lock: Arc<Mutex<()>>,
     acquire_retry_delay: Duration,
+    /// Linked components of ephemeral workers, reused by every invocation. `None` if the
+    /// ephemeral fast path is disabled.
+    ephemeral_instances:
+        Option<Cache<(ComponentId, ComponentVersion), (), InstancePre<Ctx>, GolemError>>,
+    /// Linked components prepared ahead of the first invocation of their workers
+    prewarmed_instances: DashMap<ComponentId, (ComponentVersion, InstancePre<Ctx>)>,
+    default_linear_memory_limit: Option<u64>,
+    component_linear_memory_limits: HashMap<ComponentId, u64>,
+    /// Per worker memory limits of the accounts, as last reported with their invocations
+    account_linear_memory_limits: DashMap<AccountId, u64>,
+    /// `None` if the invocations are not scheduled and start as soon as they arrive
+    invocation_scheduler: Option<FairShareScheduler>,
 }
 
 impl<Ctx: WorkerCtx> ActiveWorkers<Ctx> {
-    pub fn new(memory_config: &MemoryConfig) -> Self {
+    pub fn new(
+        memory_config: &MemoryConfig,
+        ephemeral_config: &EphemeralConfig,
+        invocation_scheduling_config: &InvocationSchedulingConfig,
+    ) -> Self {
         let worker_memory_size = memory_config.worker_memory();
         Self {
             workers: Cache::new(
@@ -45,9 +71,34 @@ impl<Ctx: WorkerCtx> ActiveWorkers<Ctx> {
                 BackgroundEvictionMode::None,
                 "active_workers",
             ),
+            ephemeral_instances: if ephemeral_config.fast_path {
+                Some(Cache::new(
+                    Some(ephemeral_config.instance_pool_size),
+                    FullCacheEvictionMode::LeastRecentlyUsed(1),
+                    BackgroundEvictionMode::None,
+                    "ephemeral_instances",
+                ))
+            } else {
+                None
+            },
+            prewarmed_instances: DashMap::new(),
+            default_linear_memory_limit: memory_config.max_linear_memory_per_worker,
+            component_linear_memory_limits: memory_config
+                .component_linear_memory_limits
+                .iter()
+                .map(|limit| (limit.component_id.clone(), limit.max_linear_memory))
+                .collect(),
+            account_linear_memory_limits: DashMap::new(),
             worker_memory: Arc::new(Semaphore::new(worker_memory_size)),
+            active_worker_slots: memory_config.max_active_workers.map(ActiveWorkerSlots::new),
             acquire_retry_delay: memory_config.acquire_retry_delay,
             priority_allocation_lock: Arc::new(Mutex::new(())),
+            invocation_scheduler: match invocation_scheduling_config {
+                InvocationSchedulingConfig::Disabled => None,
+                InvocationSchedulingConfig::FairShare(config) => {
+                    Some(FairShareScheduler::new(config))
+                }
+            },
         }
     }
 
@@ -87,6 +138,123 @@ impl<Ctx: WorkerCtx> ActiveWorkers<Ctx> {
             .await
     }
 
+    /// Links the component of a worker with the host functions, preparing it for instantiation.
+    ///
+    /// For ephemeral components the result is pooled, so only the first invocation of a given
+    /// component version pays the cost of linking.
+    pub async fn instance_pre(
+        &self,
+        linker: Arc<Linker<Ctx>>,
+        component: &Component,
+        component_type: ComponentType,
+        component_version: ComponentVersion,
+        owned_worker_id: &OwnedWorkerId,
+    ) -> Result<InstancePre<Ctx>, GolemError> {
+        if let Some(prewarmed) = self
+            .prewarmed_instances
+            .get(&owned_worker_id.component_id())
+        {
+            let (version, instance_pre) = prewarmed.value();
+            if *version == component_version {
+                return Ok(instance_pre.clone());
+            }
+        }
+
+        let link = {
+            let component = component.clone();
+            let owned_worker_id = owned_worker_id.clone();
+            move || {
+                linker.instantiate_pre(&component).map_err(|e| {
+                    GolemError::worker_creation_failed(
+                        owned_worker_id.worker_id(),
+                        format!("Failed to pre-instantiate worker {owned_worker_id}: {e}"),
+                    )
+                })
+            }
+        };
+
+        match &self.ephemeral_instances {
+            Some(ephemeral_instances) if component_type == ComponentType::Ephemeral => {
+                let key = (owned_worker_id.component_id(), component_version);
+                ephemeral_instances
+                    .get_or_insert_simple(&key, || Box::pin(async move { link() }))
+                    .await
+            }
+            _ => link(),
+        }
+    }
+
+    /// Links a component version ahead of time, replacing the previously prepared version of
+    /// the same component
+    pub fn prewarm(
+        &self,
+        linker: &Linker<Ctx>,
+        component: &Component,
+        component_id: &ComponentId,
+        component_version: ComponentVersion,
+    ) -> Result<(), GolemError> {
+        let already_prepared = self
+            .prewarmed_instances
+            .get(component_id)
+            .is_some_and(|prewarmed| prewarmed.value().0 == component_version);
+        if !already_prepared {
+            let instance_pre = linker.instantiate_pre(component).map_err(|e| {
+                GolemError::runtime(format!(
+                    "Failed to pre-instantiate component {component_id}#{component_version}: {e}"
+                ))
+            })?;
+            self.prewarmed_instances
+                .insert(component_id.clone(), (component_version, instance_pre));
+        }
+        Ok(())
+    }
+
+    /// Drops the prepared instances of the components not listed
+    pub fn retain_prewarmed(&self, component_ids: &[ComponentId]) {
+        self.prewarmed_instances
+            .retain(|component_id, _| component_ids.contains(component_id));
+    }
+
+    /// Records the linear memory limit of all the workers of an account
+    pub fn set_account_linear_memory_limit(&self, account_id: &AccountId, limit: u64) {
+        self.account_linear_memory_limits
+            .insert(account_id.clone(), limit);
+    }
+
+    /// Gets the maximum linear memory size of a worker: the limit of its component (or the
+    /// default limit), lowered to the limit of its account if that is known and smaller
+    pub fn linear_memory_limit(
+        &self,
+        account_id: &AccountId,
+        component_id: &ComponentId,
+    ) -> Option<u64> {
+        let component_limit = self
+            .component_linear_memory_limits
+            .get(component_id)
+            .copied()
+            .or(self.default_linear_memory_limit);
+        let account_limit = self
+            .account_linear_memory_limits
+            .get(account_id)
+            .map(|limit| *limit);
+        match (component_limit, account_limit) {
+            (Some(component_limit), Some(account_limit)) => {
+                Some(component_limit.min(account_limit))
+            }
+            (component_limit, account_limit) => component_limit.or(account_limit),
+        }
+    }
+
+    /// Creates the slot holding the invocation permit of a worker, which never holds a permit if
+    /// invocations are not scheduled
+    pub fn invocation_permit_slot(&self, owned_worker_id: &OwnedWorkerId) -> InvocationPermitSlot {
+        InvocationPermitSlot::new(self.invocation_scheduler.clone(), owned_worker_id.clone())
+    }
+
+    pub fn try_get(&self, worker_id: &WorkerId) -> Option<Arc<Worker<Ctx>>> {
+        self.workers.try_get(worker_id)
+    }
+
     pub fn remove(&self, worker_id: &WorkerId) {
         self.workers.remove(worker_id);
     }
@@ -95,6 +263,28 @@ impl<Ctx: WorkerCtx> ActiveWorkers<Ctx> {
         self.workers.iter()
     }
 
+    /// Admits a worker to be loaded in memory. If the number of active workers is limited and
+    /// the limit is reached, the least recently used idle worker is unloaded to make room for it.
+    /// If none of them is idle, the start is either rejected with `GolemError::ExecutorBusy` or
+    /// waits for a free slot, depending on `admission`.
+    ///
+    /// The returned slot, `None` if the active workers are not limited, has to be held as long as
+    /// the worker is loaded.
+    pub async fn admit(
+        &self,
+        admission: Admission,
+    ) -> Result<Option<OwnedSemaphorePermit>, GolemError> {
+        match &self.active_worker_slots {
+            Some(active_worker_slots) => active_worker_slots
+                .admit(admission, self.acquire_retry_delay, || {
+                    self.try_unload_least_recently_used_idle_worker()
+                })
+                .await
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
     pub async fn acquire(&self, memory: u64) -> OwnedSemaphorePermit {
         let mem32: u32 = memory
             .try_into()
@@ -175,6 +365,28 @@ impl<Ctx: WorkerCtx> ActiveWorkers<Ctx> {
         }
     }
 
+    async fn try_unload_least_recently_used_idle_worker(&self) -> bool {
+        let mut idle_workers = Vec::new();
+        for (worker_id, worker) in self.workers.iter() {
+            if worker.is_currently_idle_but_running() {
+                let last_changed = worker.last_execution_state_change().await;
+                idle_workers.push((worker_id, worker, last_changed));
+            }
+        }
+
+        // Oldest first
+        idle_workers.sort_by_key(|(_worker_id, _worker, last_changed)| last_changed.to_millis());
+
+        for (worker_id, worker, _) in idle_workers {
+            debug!("Trying to stop {worker_id} to admit a new worker");
+            if worker.stop_if_idle().await {
+                debug!("Stopped {worker_id} to admit a new worker");
+                return true;
+            }
+        }
+        false
+    }
+
     async fn try_free_up_memory(&self, memory: u64) -> bool {
         let current_avail = self.worker_memory.available_permits();
         let needed = memory.saturating_sub(current_avail as u64);
@@ -222,3 +434,192 @@ impl<Ctx: WorkerCtx> ActiveWorkers<Ctx> {
         }
     }
 }
+
+/// How to admit a worker when the limit of active workers is reached and none of them is idle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Admission {
+    /// Rejecting the start with `GolemError::ExecutorBusy`, so the caller can retry on another
+    /// executor
+    RejectWhenBusy,
+    /// Waiting until a slot gets free. Used when a worker has to be recovered on this executor,
+    /// as there is nobody to retry a rejected start.
+    WaitWhenBusy,
+}
+
+/// Slots of the workers loaded in memory when their number is limited
+struct ActiveWorkerSlots {
+    max_active_workers: usize,
+    slots: Arc<Semaphore>,
+}
+
+impl ActiveWorkerSlots {
+    fn new(max_active_workers: usize) -> Self {
+        Self {
+            max_active_workers,
+            slots: Arc::new(Semaphore::new(max_active_workers)),
+        }
+    }
+
+    async fn admit<F, Fut>(
+        &self,
+        admission: Admission,
+        retry_delay: Duration,
+        mut try_unload_idle_worker: F,
+    ) -> Result<OwnedSemaphorePermit, GolemError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = bool>,
+    {
+        let mut evicted = false;
+        loop {
+            match self.slots.clone().try_acquire_owned() {
+                Ok(slot) => {
+                    record_worker_admission(if evicted { "evicted" } else { "admitted" });
+                    break Ok(slot);
+                }
+                Err(TryAcquireError::Closed) => {
+                    panic!("active worker slots semaphore has been closed")
+                }
+                Err(TryAcquireError::NoPermits) => {
+                    // Another worker may take the freed slot in the meantime, so retrying until
+                    // there are no idle workers left
+                    if try_unload_idle_worker().await {
+                        evicted = true;
+                    } else if admission == Admission::WaitWhenBusy {
+                        // Workers becoming idle do not release their slots, so checking for them
+                        // again after the delay
+                        if let Ok(slot) =
+                            tokio::time::timeout(retry_delay, self.slots.clone().acquire_owned())
+                                .await
+                        {
+                            record_worker_admission("delayed");
+                            break Ok(slot.expect("active worker slots semaphore has been closed"));
+                        }
+                    } else {
+                        record_worker_admission("rejected");
+                        break Err(GolemError::executor_busy(self.max_active_workers));
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use crate::error::GolemError;
+    use crate::services::active_workers::{ActiveWorkerSlots, Admission};
+
+    const RETRY_DELAY: Duration = Duration::from_millis(10);
+
+    #[test]
+    async fn workers_are_admitted_while_there_are_free_slots() {
+        let slots = ActiveWorkerSlots::new(2);
+
+        let first = slots
+            .admit(Admission::RejectWhenBusy, RETRY_DELAY, || async { false })
+            .await;
+        let second = slots
+            .admit(Admission::RejectWhenBusy, RETRY_DELAY, || async { false })
+            .await;
+
+        assert!(first.is_ok());
+        assert!(second.is_ok());
+    }
+
+    #[test]
+    async fn busy_executor_rejects_new_workers_without_idle_ones() {
+        let slots = ActiveWorkerSlots::new(1);
+        let _active = slots
+            .admit(Admission::RejectWhenBusy, RETRY_DELAY, || async { false })
+            .await
+            .unwrap();
+
+        let result = slots
+            .admit(Admission::RejectWhenBusy, RETRY_DELAY, || async { false })
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(GolemError::ExecutorBusy {
+                max_active_workers: 1
+            })
+        ));
+    }
+
+    #[test]
+    async fn idle_workers_are_unloaded_to_admit_new_ones() {
+        let slots = ActiveWorkerSlots::new(1);
+        let idle = Mutex::new(Some(
+            slots
+                .admit(Admission::RejectWhenBusy, RETRY_DELAY, || async { false })
+                .await
+                .unwrap(),
+        ));
+
+        let result = slots
+            .admit(Admission::RejectWhenBusy, RETRY_DELAY, || {
+                let unloaded = idle.lock().unwrap().take().is_some();
+                async move { unloaded }
+            })
+            .await;
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    async fn recovered_workers_wait_for_a_slot() {
+        let slots = Arc::new(ActiveWorkerSlots::new(1));
+        let active = slots
+            .admit(Admission::RejectWhenBusy, RETRY_DELAY, || async { false })
+            .await
+            .unwrap();
+
+        let recovery = {
+            let slots = slots.clone();
+            tokio::spawn(async move {
+                slots
+                    .admit(Admission::WaitWhenBusy, RETRY_DELAY, || async { false })
+                    .await
+                    .is_ok()
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!recovery.is_finished());
+
+        drop(active);
+        let admitted = tokio::time::timeout(Duration::from_secs(5), recovery).await;
+        assert!(matches!(admitted, Ok(Ok(true))));
+    }
+
+    #[test]
+    async fn recovered_workers_retry_unloading_idle_workers() {
+        let slots = ActiveWorkerSlots::new(1);
+        let idle = Mutex::new(Some(
+            slots
+                .admit(Admission::RejectWhenBusy, RETRY_DELAY, || async { false })
+                .await
+                .unwrap(),
+        ));
+        // The active worker only becomes idle after the first attempt
+        let became_idle = AtomicBool::new(false);
+
+        let result = tokio::time::timeout(
+            Duration::from_secs(5),
+            slots.admit(Admission::WaitWhenBusy, RETRY_DELAY, || {
+                let unloaded = became_idle.swap(true, Ordering::SeqCst)
+                    && idle.lock().unwrap().take().is_some();
+                async move { unloaded }
+            }),
+        )
+        .await;
+
+        assert!(matches!(result, Ok(Ok(_))));
+    }
+}
@@ -19,9 +19,13 @@ use anyhow::anyhow;
 use async_trait::async_trait;
 use bincode::{Decode, Encode};
 
-use golem_common::model::AccountId;
+use golem_common::model::{AccountId, OwnedWorkerId, WorkerId};
+use sha2::{Digest, Sha256};
 
 use crate::storage::blob::{BlobStorage, BlobStorageNamespace, ExistsResult};
+use crate::storage::keyvalue::{
+    KeyValueStorage, KeyValueStorageLabelledApi, KeyValueStorageNamespace,
+};
 
 /// Interface for storing blobs in a persistent storage.
 #[async_trait]
@@ -84,6 +88,14 @@ pub trait BlobStoreService {
         end: u64,
     ) -> anyhow::Result<Vec<u8>>;
 
+    /// Gets a blob stored by any worker of the account, and references it from the reading
+    /// worker, so it is kept as long as the reading worker's oplog refers to it
+    async fn get_worker_blob(
+        &self,
+        owned_worker_id: OwnedWorkerId,
+        blob_id: String,
+    ) -> anyhow::Result<Option<Vec<u8>>>;
+
     async fn has_object(
         &self,
         account_id: AccountId,
@@ -113,6 +125,26 @@ pub trait BlobStoreService {
         object_name: String,
     ) -> anyhow::Result<ObjectMetadata>;
 
+    /// Stores a blob in the account's blob storage and references it from the worker. Blobs are
+    /// content addressed, storing the same data again returns the same id.
+    async fn put_worker_blob(
+        &self,
+        owned_worker_id: OwnedWorkerId,
+        data: Vec<u8>,
+    ) -> anyhow::Result<String>;
+
+    /// Removes all the references of the worker, used when the worker and its oplog get deleted.
+    /// Blobs are deleted when no worker references them anymore.
+    async fn release_all_worker_blobs(&self, owned_worker_id: OwnedWorkerId) -> anyhow::Result<()>;
+
+    /// Marks the blob as no longer used by the worker. The worker's reference is kept until the
+    /// worker gets deleted, because replaying its oplog reads the blob again.
+    async fn release_worker_blob(
+        &self,
+        owned_worker_id: OwnedWorkerId,
+        blob_id: String,
+    ) -> anyhow::Result<()>;
+
     async fn write_data(
         &self,
         account_id: AccountId,
@@ -124,14 +156,122 @@ pub trait BlobStoreService {
 
 pub struct DefaultBlobStoreService {
     blob_storage: Arc<dyn BlobStorage + Send + Sync>,
+    key_value_storage: Arc<dyn KeyValueStorage + Send + Sync>,
 }
 
 impl DefaultBlobStoreService {
-    pub fn new(blob_storage: Arc<dyn BlobStorage + Send + Sync>) -> Self {
-        Self { blob_storage }
+    pub fn new(
+        blob_storage: Arc<dyn BlobStorage + Send + Sync>,
+        key_value_storage: Arc<dyn KeyValueStorage + Send + Sync>,
+    ) -> Self {
+        Self {
+            blob_storage,
+            key_value_storage,
+        }
+    }
+
+    fn blob_references_namespace(account_id: AccountId) -> KeyValueStorageNamespace {
+        KeyValueStorageNamespace::BlobReferences { account_id }
+    }
+
+    /// Key of the set of workers referencing a blob
+    fn blob_key(blob_id: &str) -> String {
+        format!("blob:{blob_id}")
+    }
+
+    /// Key of the set of blobs referenced by a worker
+    fn worker_key(worker_id: &WorkerId) -> String {
+        format!("worker:{}", worker_id.to_redis_key())
+    }
+
+    fn validate_blob_id(blob_id: &str) -> anyhow::Result<()> {
+        if blob_id.len() == 64 && blob_id.chars().all(|c| c.is_ascii_hexdigit()) {
+            Ok(())
+        } else {
+            Err(anyhow!("Invalid blob id: {blob_id}"))
+        }
+    }
+
+    async fn add_reference(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        blob_id: &str,
+    ) -> anyhow::Result<()> {
+        let namespace = Self::blob_references_namespace(owned_worker_id.account_id.clone());
+        self.key_value_storage
+            .with_entity("blob_store", "add_reference", "blob_reference")
+            .add_to_set(
+                namespace.clone(),
+                &Self::blob_key(blob_id),
+                &owned_worker_id.worker_id,
+            )
+            .await
+            .map_err(|err| anyhow!(err))?;
+        self.key_value_storage
+            .with_entity("blob_store", "add_reference", "blob_reference")
+            .add_to_set(
+                namespace,
+                &Self::worker_key(&owned_worker_id.worker_id),
+                &blob_id.to_string(),
+            )
+            .await
+            .map_err(|err| anyhow!(err))
+    }
+
+    /// Removes the worker's reference to the blob, and deletes the blob if no other worker
+    /// references it
+    async fn remove_reference(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        blob_id: &str,
+    ) -> anyhow::Result<()> {
+        let namespace = Self::blob_references_namespace(owned_worker_id.account_id.clone());
+
+        self.key_value_storage
+            .with_entity("blob_store", "remove_reference", "blob_reference")
+            .remove_from_set(
+                namespace.clone(),
+                &Self::worker_key(&owned_worker_id.worker_id),
+                &blob_id.to_string(),
+            )
+            .await
+            .map_err(|err| anyhow!(err))?;
+        self.key_value_storage
+            .with_entity("blob_store", "remove_reference", "blob_reference")
+            .remove_from_set(
+                namespace.clone(),
+                &Self::blob_key(blob_id),
+                &owned_worker_id.worker_id,
+            )
+            .await
+            .map_err(|err| anyhow!(err))?;
+
+        let remaining: Vec<WorkerId> = self
+            .key_value_storage
+            .with_entity("blob_store", "remove_reference", "blob_reference")
+            .members_of_set(namespace, &Self::blob_key(blob_id))
+            .await
+            .map_err(|err| anyhow!(err))?;
+        if remaining.is_empty() {
+            self.blob_storage
+                .delete(
+                    "blob_store",
+                    "remove_reference",
+                    BlobStorageNamespace::WorkerBlobs(owned_worker_id.account_id.clone()),
+                    Path::new(blob_id),
+                )
+                .await
+                .map_err(|err| anyhow!(err))?;
+        }
+        Ok(())
     }
 }
 
+/// Blobs are identified by the SHA-256 hash of their contents
+pub fn blob_id_of(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
 #[async_trait]
 impl BlobStoreService for DefaultBlobStoreService {
     async fn clear(&self, account_id: AccountId, container_name: String) -> anyhow::Result<()> {
@@ -304,6 +444,37 @@ impl BlobStoreService for DefaultBlobStoreService {
         }
     }
 
+    async fn get_worker_blob(
+        &self,
+        owned_worker_id: OwnedWorkerId,
+        blob_id: String,
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        Self::validate_blob_id(&blob_id)?;
+
+        // The reference is added before the read, so the blob cannot be deleted between the
+        // read and recording the read in the oplog
+        self.add_reference(&owned_worker_id, &blob_id).await?;
+
+        let data = self
+            .blob_storage
+            .get_raw(
+                "blob_store",
+                "get_worker_blob",
+                BlobStorageNamespace::WorkerBlobs(owned_worker_id.account_id.clone()),
+                Path::new(&blob_id),
+            )
+            .await
+            .map_err(|err| anyhow!(err))?;
+
+        match data {
+            Some(data) => Ok(Some(data.to_vec())),
+            None => {
+                self.remove_reference(&owned_worker_id, &blob_id).await?;
+                Ok(None)
+            }
+        }
+    }
+
     async fn has_object(
         &self,
         account_id: AccountId,
@@ -395,6 +566,55 @@ impl BlobStoreService for DefaultBlobStoreService {
         }
     }
 
+    async fn put_worker_blob(
+        &self,
+        owned_worker_id: OwnedWorkerId,
+        data: Vec<u8>,
+    ) -> anyhow::Result<String> {
+        let blob_id = blob_id_of(&data);
+
+        // The reference is added before the upload, so a concurrent deletion of the workers
+        // referencing the same blob does not delete it
+        self.add_reference(&owned_worker_id, &blob_id).await?;
+
+        self.blob_storage
+            .put_raw(
+                "blob_store",
+                "put_worker_blob",
+                BlobStorageNamespace::WorkerBlobs(owned_worker_id.account_id),
+                Path::new(&blob_id),
+                &data,
+            )
+            .await
+            .map_err(|err| anyhow!(err))?;
+
+        Ok(blob_id)
+    }
+
+    async fn release_all_worker_blobs(&self, owned_worker_id: OwnedWorkerId) -> anyhow::Result<()> {
+        let blob_ids: Vec<String> = self
+            .key_value_storage
+            .with_entity("blob_store", "release_all_worker_blobs", "blob_reference")
+            .members_of_set(
+                Self::blob_references_namespace(owned_worker_id.account_id.clone()),
+                &Self::worker_key(&owned_worker_id.worker_id),
+            )
+            .await
+            .map_err(|err| anyhow!(err))?;
+        for blob_id in blob_ids {
+            self.remove_reference(&owned_worker_id, &blob_id).await?;
+        }
+        Ok(())
+    }
+
+    async fn release_worker_blob(
+        &self,
+        _owned_worker_id: OwnedWorkerId,
+        blob_id: String,
+    ) -> anyhow::Result<()> {
+        Self::validate_blob_id(&blob_id)
+    }
+
     async fn write_data(
         &self,
         account_id: AccountId,
@@ -431,12 +651,14 @@ mod tests {
     use std::sync::Arc;
 
     use tempfile::TempDir;
+    use uuid::Uuid;
 
-    use golem_common::model::AccountId;
+    use golem_common::model::{AccountId, ComponentId, OwnedWorkerId, WorkerId, WorkerName};
 
     use crate::services::blob_store::{BlobStoreService, DefaultBlobStoreService};
     use crate::storage::blob::fs::FileSystemBlobStorage;
     use crate::storage::blob::memory::InMemoryBlobStorage;
+    use crate::storage::keyvalue::memory::InMemoryKeyValueStorage;
 
     async fn test_container_exists(blob_store: &impl BlobStoreService) {
         let account1 = AccountId {
@@ -604,65 +826,93 @@ mod tests {
         );
     }
 
-    fn in_memory_blob_store() -> impl BlobStoreService {
-        let blob_storage = Arc::new(InMemoryBlobStorage::new());
-        DefaultBlobStoreService::new(blob_storage)
-    }
-
-    async fn fs_blob_store(path: &Path) -> impl BlobStoreService {
-        let blob_storage = Arc::new(FileSystemBlobStorage::new(path).await.unwrap());
-        DefaultBlobStoreService::new(blob_storage)
-    }
-
-    #[test]
-    async fn test_container_exists_in_memory() {
-        let blob_store = in_memory_blob_store();
-        test_container_exists(&blob_store).await;
-    }
+    async fn test_worker_blobs_are_deleted_when_unreferenced(blob_store: &impl BlobStoreService) {
+        let account1 = AccountId {
+            value: "account1".to_string(),
+        };
+        let component_id = ComponentId(Uuid::new_v4());
+        let worker1 = OwnedWorkerId::new(
+            &account1,
+            &WorkerId {
+                component_id: component_id.clone(),
+                worker_name: WorkerName::from_existing("worker1"),
+            },
+        );
+        let worker2 = OwnedWorkerId::new(
+            &account1,
+            &WorkerId {
+                component_id,
+                worker_name: WorkerName::from_existing("worker2"),
+            },
+        );
 
-    #[test]
-    async fn test_container_exists_local() {
-        let tempdir = TempDir::new().unwrap();
-        let blob_store = fs_blob_store(tempdir.path()).await;
-        test_container_exists(&blob_store).await;
-    }
+        let data = vec![7u8; 4096];
+        let blob_id = blob_store
+            .put_worker_blob(worker1.clone(), data.clone())
+            .await
+            .unwrap();
 
-    #[test]
-    async fn test_container_delete_in_memory() {
-        let blob_store = in_memory_blob_store();
-        test_container_delete(&blob_store).await;
-    }
+        // Reading the blob references it from the reading worker too
+        assert_eq!(
+            blob_store
+                .get_worker_blob(worker2.clone(), blob_id.clone())
+                .await
+                .unwrap(),
+            Some(data.clone())
+        );
 
-    #[test]
-    async fn test_container_delete_local() {
-        let tempdir = TempDir::new().unwrap();
-        let blob_store = fs_blob_store(tempdir.path()).await;
-        test_container_delete(&blob_store).await;
-    }
+        // Releasing keeps the reference, as the worker's oplog still refers to the blob
+        blob_store
+            .release_worker_blob(worker1.clone(), blob_id.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            blob_store
+                .get_worker_blob(worker1.clone(), blob_id.clone())
+                .await
+                .unwrap(),
+            Some(data.clone())
+        );
 
-    #[test]
-    async fn test_container_has_write_read_has_in_memory() {
-        let blob_store = in_memory_blob_store();
-        test_container_has_write_read_has(&blob_store).await;
-    }
+        blob_store
+            .release_all_worker_blobs(worker1.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            blob_store
+                .get_worker_blob(worker2.clone(), blob_id.clone())
+                .await
+                .unwrap(),
+            Some(data)
+        );
 
-    #[test]
-    async fn test_container_has_write_read_has_local() {
-        let tempdir = TempDir::new().unwrap();
-        let blob_store = fs_blob_store(tempdir.path()).await;
-        test_container_has_write_read_has(&blob_store).await;
+        blob_store
+            .release_all_worker_blobs(worker2.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            blob_store
+                .get_worker_blob(worker1.clone(), blob_id)
+                .await
+                .unwrap(),
+            None
+        );
+        assert!(blob_store
+            .get_worker_blob(worker1, "../other".to_string())
+            .await
+            .is_err());
     }
 
     #[test]
-    async fn test_container_list_copy_move_list_in_memory() {
+    async fn test_worker_blobs_are_deleted_when_unreferenced_in_memory() {
         let blob_store = in_memory_blob_store();
-        test_container_list_copy_move_list(&blob_store).await;
+        test_worker_blobs_are_deleted_when_unreferenced(&blob_store).await;
     }
 
     #[test]
-    async fn test_container_list_copy_move_list_local() {
+    async fn test_worker_blobs_are_deleted_when_unreferenced_local() {
         let tempdir = TempDir::new().unwrap();
         let blob_store = fs_blob_store(tempdir.path()).await;
-        test_container_list_copy_move_list(&blob_store).await;
+        test_worker_blobs_are_deleted_when_unreferenced(&blob_store).await;
     }
 }
@@ -12,19 +12,26 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
+use sha2::{Digest, Sha256};
 use tokio::time::Instant;
-use tracing::debug;
+use tracing::{debug, warn};
 use wasmtime::component::Component;
 
-use golem_common::model::ComponentId;
+use golem_common::model::{ComponentId, Timestamp};
+use golem_common::serialization::{deserialize, serialize};
 
 use crate::error::GolemError;
-use crate::services::golem_config::CompiledComponentServiceConfig;
+use crate::services::golem_config::{
+    CompiledComponentServiceConfig, CompiledComponentServiceEnabledConfig,
+};
 use crate::storage::blob::{BlobStorage, BlobStorageNamespace};
+use crate::storage::keyvalue::{KeyValueStorage, KeyValueStorageNamespace};
 use crate::Engine;
 
 /// Service for storing compiled native binaries of WebAssembly components
@@ -42,22 +49,142 @@ pub trait CompiledComponentService {
         component_version: u64,
         component: &Component,
     ) -> Result<(), GolemError>;
+
+    /// Tries to take the lease for compiling the given component version, so executors sharing
+    /// the compilation cache do not all compile the same component at once. Returns `false` if
+    /// another executor holds the lease; the result should then be awaited with `wait_for`.
+    async fn try_lease(
+        &self,
+        _component_id: &ComponentId,
+        _component_version: u64,
+        _engine: &Engine,
+    ) -> Result<bool, GolemError> {
+        Ok(true)
+    }
+
+    /// Waits until the holder of the compilation lease uploads the compiled component. Returns
+    /// `None` if the lease is released or expires before the compiled component is available.
+    async fn wait_for(
+        &self,
+        _component_id: &ComponentId,
+        _component_version: u64,
+        _engine: &Engine,
+    ) -> Result<Option<Component>, GolemError> {
+        Ok(None)
+    }
 }
 
 pub struct DefaultCompiledComponentService {
     blob_storage: Arc<dyn BlobStorage + Send + Sync>,
+    lease: Option<CompilationLease>,
+}
+
+struct CompilationLease {
+    key_value_storage: Arc<dyn KeyValueStorage + Send + Sync>,
+    duration: Duration,
+    poll_interval: Duration,
 }
 
 impl DefaultCompiledComponentService {
     pub fn new(blob_storage: Arc<dyn BlobStorage + Send + Sync>) -> Self {
-        Self { blob_storage }
+        Self {
+            blob_storage,
+            lease: None,
+        }
+    }
+
+    /// Coordinates compilation with the other executors through the given key-value storage
+    pub fn with_compilation_lease(
+        mut self,
+        key_value_storage: Arc<dyn KeyValueStorage + Send + Sync>,
+        config: &CompiledComponentServiceEnabledConfig,
+    ) -> Self {
+        self.lease = Some(CompilationLease {
+            key_value_storage,
+            duration: config.compilation_lease,
+            poll_interval: config.poll_interval,
+        });
+        self
+    }
+
+    /// Compiled artifacts are only loadable by engines with a compatible configuration, so the
+    /// engine's compatibility hash is part of every key
+    fn engine_hash(engine: &Engine) -> String {
+        let mut hasher = Sha256Hasher(Sha256::new());
+        engine.precompile_compatibility_hash().hash(&mut hasher);
+        hex::encode(&hasher.0.finalize()[..8])
+    }
+
+    fn key(component_id: &ComponentId, component_version: u64, engine: &Engine) -> PathBuf {
+        Path::new(&component_id.to_string()).join(format!(
+            "{component_version}-{}.cwasm",
+            Self::engine_hash(engine)
+        ))
+    }
+
+    fn lease_key(component_id: &ComponentId, component_version: u64, engine: &Engine) -> String {
+        format!(
+            "{component_id}/{component_version}/{}",
+            Self::engine_hash(engine)
+        )
     }
 
-    fn key(component_id: &ComponentId, component_version: u64) -> PathBuf {
-        Path::new(&component_id.to_string()).join(format!("{component_version}.cwasm"))
+    /// Returns the expiry of the current holder's lease, in milliseconds since the epoch
+    async fn lease_expiry(
+        lease: &CompilationLease,
+        key: &str,
+    ) -> Result<Option<(u64, Vec<u8>)>, String> {
+        let raw = lease
+            .key_value_storage
+            .with_entity("compiled_component", "get_lease", "lease")
+            .get_raw(KeyValueStorageNamespace::CompilationLease, key)
+            .await?;
+        match raw {
+            Some(raw) => {
+                let expiry: u64 = deserialize(&raw)?;
+                Ok(Some((expiry, raw.to_vec())))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn release_lease(
+        &self,
+        component_id: &ComponentId,
+        component_version: u64,
+        engine: &Engine,
+    ) {
+        if let Some(lease) = &self.lease {
+            let key = Self::lease_key(component_id, component_version, engine);
+            if let Err(err) = lease
+                .key_value_storage
+                .with("compiled_component", "release_lease")
+                .del(KeyValueStorageNamespace::CompilationLease, &key)
+                .await
+            {
+                warn!("Failed to release compilation lease of {component_id}#{component_version}: {err}");
+            }
+        }
+    }
+}
+
+/// Feeds `Hash` implementations into SHA-256, which unlike `DefaultHasher` gives the same result
+/// on every executor
+struct Sha256Hasher(Sha256);
+
+impl Hasher for Sha256Hasher {
+    fn finish(&self) -> u64 {
+        let digest = self.0.clone().finalize();
+        u64::from_be_bytes(digest[..8].try_into().unwrap())
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.update(bytes);
     }
 }
 
+const DIGEST_LENGTH: usize = 32;
+
 #[async_trait]
 impl CompiledComponentService for DefaultCompiledComponentService {
     async fn get(
@@ -72,15 +199,28 @@ impl CompiledComponentService for DefaultCompiledComponentService {
                 "compiled_component",
                 "get",
                 BlobStorageNamespace::CompilationCache,
-                &Self::key(component_id, component_version),
+                &Self::key(component_id, component_version, engine),
             )
             .await
         {
             Ok(None) => Ok(None),
-            Ok(Some(bytes)) => {
+            Ok(Some(stored)) => {
+                // The compiled code is loaded without validation, so it must be exactly what
+                // was uploaded
+                if stored.len() < DIGEST_LENGTH
+                    || Sha256::digest(&stored[DIGEST_LENGTH..])[..] != stored[..DIGEST_LENGTH]
+                {
+                    warn!(
+                        "Ignoring corrupted precompiled image of {}#{}",
+                        component_id, component_version
+                    );
+                    return Ok(None);
+                }
+                let bytes = &stored[DIGEST_LENGTH..];
+
                 let start = Instant::now();
                 let component = unsafe {
-                    Component::deserialize(engine, &bytes).map_err(|err| {
+                    Component::deserialize(engine, bytes).map_err(|err| {
                         GolemError::component_download_failed(
                             component_id.clone(),
                             component_version,
@@ -113,16 +253,20 @@ impl CompiledComponentService for DefaultCompiledComponentService {
         component_version: u64,
         component: &Component,
     ) -> Result<(), GolemError> {
+        let engine = component.engine();
         let bytes = component
             .serialize()
             .expect("Could not serialize component");
-        self.blob_storage
+        let mut stored = Sha256::digest(&bytes).to_vec();
+        stored.extend_from_slice(&bytes);
+        let result = self
+            .blob_storage
             .put_raw(
                 "compiled_component",
                 "put",
                 BlobStorageNamespace::CompilationCache,
-                &Self::key(component_id, component_version),
-                &bytes,
+                &Self::key(component_id, component_version, engine),
+                &stored,
             )
             .await
             .map_err(|err| {
@@ -131,17 +275,109 @@ impl CompiledComponentService for DefaultCompiledComponentService {
                     component_version,
                     format!("Could not store compiled component: {err}"),
                 )
-            })
+            });
+        // Released even if the upload failed, so the waiting executors compile it themselves
+        self.release_lease(component_id, component_version, engine)
+            .await;
+        result
+    }
+
+    async fn try_lease(
+        &self,
+        component_id: &ComponentId,
+        component_version: u64,
+        engine: &Engine,
+    ) -> Result<bool, GolemError> {
+        match &self.lease {
+            Some(lease) => {
+                let key = Self::lease_key(component_id, component_version, engine);
+                let now = Timestamp::now_utc().to_millis();
+                let expiry = now + lease.duration.as_millis() as u64;
+                let acquired = lease
+                    .key_value_storage
+                    .with_entity("compiled_component", "try_lease", "lease")
+                    .set_if_not_exists(KeyValueStorageNamespace::CompilationLease, &key, &expiry)
+                    .await
+                    .map_err(GolemError::unknown)?;
+                if acquired {
+                    return Ok(true);
+                }
+
+                match Self::lease_expiry(lease, &key)
+                    .await
+                    .map_err(GolemError::unknown)?
+                {
+                    Some((current_expiry, _)) if current_expiry > now => Ok(false),
+                    current => {
+                        // The previous holder released the lease or gave up on it
+                        let new_value = serialize(&expiry).map_err(GolemError::unknown)?;
+                        lease
+                            .key_value_storage
+                            .with_entity("compiled_component", "try_lease", "lease")
+                            .compare_and_swap_raw(
+                                KeyValueStorageNamespace::CompilationLease,
+                                &key,
+                                current.as_ref().map(|(_, raw)| raw.as_slice()),
+                                Some(&new_value),
+                            )
+                            .await
+                            .map_err(GolemError::unknown)
+                    }
+                }
+            }
+            None => Ok(true),
+        }
+    }
+
+    async fn wait_for(
+        &self,
+        component_id: &ComponentId,
+        component_version: u64,
+        engine: &Engine,
+    ) -> Result<Option<Component>, GolemError> {
+        match &self.lease {
+            Some(lease) => {
+                let key = Self::lease_key(component_id, component_version, engine);
+                loop {
+                    tokio::time::sleep(lease.poll_interval).await;
+                    if let Some(component) =
+                        self.get(component_id, component_version, engine).await?
+                    {
+                        return Ok(Some(component));
+                    }
+                    match Self::lease_expiry(lease, &key)
+                        .await
+                        .map_err(GolemError::unknown)?
+                    {
+                        Some((expiry, _)) if expiry > Timestamp::now_utc().to_millis() => {}
+                        _ => {
+                            // The upload may have finished right before the lease was released
+                            return self.get(component_id, component_version, engine).await;
+                        }
+                    }
+                }
+            }
+            None => Ok(None),
+        }
     }
 }
 
+/// When a key-value storage is given, executors sharing it take a lease before compiling a
+/// component so that only one of them compiles it and the others download the result
 pub fn configured(
     config: &CompiledComponentServiceConfig,
     blob_storage: Arc<dyn BlobStorage + Send + Sync>,
+    key_value_storage: Option<Arc<dyn KeyValueStorage + Send + Sync>>,
 ) -> Arc<dyn CompiledComponentService + Send + Sync> {
     match config {
-        CompiledComponentServiceConfig::Enabled(_) => {
-            Arc::new(DefaultCompiledComponentService::new(blob_storage))
+        CompiledComponentServiceConfig::Enabled(config) => {
+            let service = DefaultCompiledComponentService::new(blob_storage);
+            match key_value_storage {
+                Some(key_value_storage) => {
+                    Arc::new(service.with_compilation_lease(key_value_storage, config))
+                }
+                None => Arc::new(service),
+            }
         }
         CompiledComponentServiceConfig::Disabled(_) => {
             Arc::new(CompiledComponentServiceDisabled::new())
@@ -183,3 +419,261 @@ impl CompiledComponentService for CompiledComponentServiceDisabled {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use golem_common::model::ComponentId;
+    use wasmtime::component::Component;
+    use wasmtime::{Config, Engine};
+
+    use crate::services::compiled_component::{
+        CompiledComponentService, DefaultCompiledComponentService,
+    };
+    use crate::services::golem_config::CompiledComponentServiceEnabledConfig;
+    use crate::storage::blob::memory::InMemoryBlobStorage;
+    use crate::storage::blob::{BlobStorage, BlobStorageNamespace};
+    use crate::storage::keyvalue::memory::InMemoryKeyValueStorage;
+    use crate::storage::keyvalue::KeyValueStorage;
+
+    /// Binary encoding of an empty component
+    const EMPTY_COMPONENT: &[u8] = &[0x00, 0x61, 0x73, 0x6d, 0x0d, 0x00, 0x01, 0x00];
+
+    fn engine() -> Engine {
+        let mut config = Config::new();
+        config.wasm_component_model(true);
+        Engine::new(&config).unwrap()
+    }
+
+    fn leased_service(
+        blob_storage: Arc<dyn BlobStorage + Send + Sync>,
+        key_value_storage: Arc<dyn KeyValueStorage + Send + Sync>,
+        compilation_lease: Duration,
+    ) -> DefaultCompiledComponentService {
+        DefaultCompiledComponentService::new(blob_storage).with_compilation_lease(
+            key_value_storage,
+            &CompiledComponentServiceEnabledConfig {
+                compilation_lease,
+                poll_interval: Duration::from_millis(10),
+            },
+        )
+    }
+
+    #[test]
+    async fn stored_components_can_be_loaded() {
+        let engine = engine();
+        let component = Component::from_binary(&engine, EMPTY_COMPONENT).unwrap();
+        let service = DefaultCompiledComponentService::new(Arc::new(InMemoryBlobStorage::new()));
+        let component_id = ComponentId::new_v4();
+
+        assert!(service
+            .get(&component_id, 0, &engine)
+            .await
+            .unwrap()
+            .is_none());
+
+        service.put(&component_id, 0, &component).await.unwrap();
+
+        assert!(service
+            .get(&component_id, 0, &engine)
+            .await
+            .unwrap()
+            .is_some());
+        assert!(service
+            .get(&component_id, 1, &engine)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    async fn corrupted_components_are_ignored() {
+        let engine = engine();
+        let component = Component::from_binary(&engine, EMPTY_COMPONENT).unwrap();
+        let blob_storage = Arc::new(InMemoryBlobStorage::new());
+        let service = DefaultCompiledComponentService::new(blob_storage.clone());
+        let component_id = ComponentId::new_v4();
+
+        service.put(&component_id, 0, &component).await.unwrap();
+
+        let key = DefaultCompiledComponentService::key(&component_id, 0, &engine);
+        let mut stored = blob_storage
+            .get_raw("test", "get", BlobStorageNamespace::CompilationCache, &key)
+            .await
+            .unwrap()
+            .unwrap()
+            .to_vec();
+        let last = stored.len() - 1;
+        stored[last] ^= 0xff;
+        blob_storage
+            .put_raw(
+                "test",
+                "put",
+                BlobStorageNamespace::CompilationCache,
+                &key,
+                &stored,
+            )
+            .await
+            .unwrap();
+        blob_storage
+            .put_raw(
+                "test",
+                "put",
+                BlobStorageNamespace::CompilationCache,
+                &DefaultCompiledComponentService::key(&component_id, 1, &engine),
+                &[1, 2, 3],
+            )
+            .await
+            .unwrap();
+
+        assert!(service
+            .get(&component_id, 0, &engine)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(service
+            .get(&component_id, 1, &engine)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    async fn only_one_executor_holds_the_compilation_lease() {
+        let engine = engine();
+        let component = Component::from_binary(&engine, EMPTY_COMPONENT).unwrap();
+        let blob_storage = Arc::new(InMemoryBlobStorage::new());
+        let key_value_storage = Arc::new(InMemoryKeyValueStorage::new());
+        let executor1 = leased_service(
+            blob_storage.clone(),
+            key_value_storage.clone(),
+            Duration::from_secs(60),
+        );
+        let executor2 = leased_service(
+            blob_storage.clone(),
+            key_value_storage.clone(),
+            Duration::from_secs(60),
+        );
+        let component_id = ComponentId::new_v4();
+
+        assert!(executor1
+            .try_lease(&component_id, 0, &engine)
+            .await
+            .unwrap());
+        assert!(!executor2
+            .try_lease(&component_id, 0, &engine)
+            .await
+            .unwrap());
+        // Other versions are compiled independently
+        assert!(executor2
+            .try_lease(&component_id, 1, &engine)
+            .await
+            .unwrap());
+
+        // Uploading the compiled component releases the lease
+        executor1.put(&component_id, 0, &component).await.unwrap();
+        assert!(executor2
+            .try_lease(&component_id, 0, &engine)
+            .await
+            .unwrap());
+    }
+
+    #[test]
+    async fn expired_compilation_lease_can_be_taken_over() {
+        let engine = engine();
+        let blob_storage = Arc::new(InMemoryBlobStorage::new());
+        let key_value_storage = Arc::new(InMemoryKeyValueStorage::new());
+        let executor1 = leased_service(
+            blob_storage.clone(),
+            key_value_storage.clone(),
+            Duration::ZERO,
+        );
+        let executor2 = leased_service(
+            blob_storage.clone(),
+            key_value_storage.clone(),
+            Duration::from_secs(60),
+        );
+        let component_id = ComponentId::new_v4();
+
+        assert!(executor1
+            .try_lease(&component_id, 0, &engine)
+            .await
+            .unwrap());
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        assert!(executor2
+            .try_lease(&component_id, 0, &engine)
+            .await
+            .unwrap());
+        // The lease taken over is not expired
+        assert!(!executor1
+            .try_lease(&component_id, 0, &engine)
+            .await
+            .unwrap());
+    }
+
+    #[test]
+    async fn waiting_executors_get_the_uploaded_component() {
+        let engine = engine();
+        let component = Component::from_binary(&engine, EMPTY_COMPONENT).unwrap();
+        let blob_storage = Arc::new(InMemoryBlobStorage::new());
+        let key_value_storage = Arc::new(InMemoryKeyValueStorage::new());
+        let executor1 = leased_service(
+            blob_storage.clone(),
+            key_value_storage.clone(),
+            Duration::from_secs(60),
+        );
+        let executor2 = leased_service(
+            blob_storage.clone(),
+            key_value_storage.clone(),
+            Duration::from_secs(60),
+        );
+        let component_id = ComponentId::new_v4();
+
+        assert!(executor1
+            .try_lease(&component_id, 0, &engine)
+            .await
+            .unwrap());
+
+        let (waited, uploaded) = tokio::join!(
+            executor2.wait_for(&component_id, 0, &engine),
+            executor1.put(&component_id, 0, &component)
+        );
+
+        uploaded.unwrap();
+        assert!(waited.unwrap().is_some());
+    }
+
+    #[test]
+    async fn waiting_stops_when_the_lease_is_released_without_upload() {
+        let engine = engine();
+        let blob_storage = Arc::new(InMemoryBlobStorage::new());
+        let key_value_storage = Arc::new(InMemoryKeyValueStorage::new());
+        let executor1 = leased_service(
+            blob_storage.clone(),
+            key_value_storage.clone(),
+            Duration::from_secs(60),
+        );
+        let executor2 = leased_service(
+            blob_storage.clone(),
+            key_value_storage.clone(),
+            Duration::from_secs(60),
+        );
+        let component_id = ComponentId::new_v4();
+
+        assert!(executor1
+            .try_lease(&component_id, 0, &engine)
+            .await
+            .unwrap());
+        executor1.release_lease(&component_id, 0, &engine).await;
+
+        assert!(executor2
+            .wait_for(&component_id, 0, &engine)
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
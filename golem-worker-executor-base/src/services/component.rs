@@ -25,7 +25,9 @@ use crate::services::golem_config::{
     CompiledComponentServiceConfig, ComponentCacheConfig, ComponentServiceConfig,
 };
 use crate::storage::blob::BlobStorage;
+use crate::storage::keyvalue::KeyValueStorage;
 use async_trait::async_trait;
+use dashmap::DashMap;
 use futures_util::TryStreamExt;
 use golem_api_grpc::proto::golem::component::v1::component_service_client::ComponentServiceClient;
 use golem_api_grpc::proto::golem::component::v1::{
@@ -38,6 +40,7 @@ use golem_common::client::{GrpcClient, GrpcClientConfig};
 use golem_common::config::RetryConfig;
 use golem_common::metrics::external_calls::record_external_call_response_size_bytes;
 use golem_common::model::component_metadata::RawComponentMetadata;
+use golem_common::model::component_observability::ComponentObservability;
 use golem_common::model::{ComponentId, ComponentType, ComponentVersion};
 use golem_common::retries::with_retries;
 use golem_wasm_ast::analysis::AnalysedExport;
@@ -58,6 +61,8 @@ pub struct ComponentMetadata {
     pub memories: Vec<LinearMemory>,
     pub exports: Vec<AnalysedExport>,
     pub component_type: ComponentType,
+    /// Content hash of the archive of initial files to be placed into the worker's file system
+    pub initial_files_hash: Option<String>,
 }
 
 /// Service for downloading a specific Golem component from the Golem Component API
@@ -75,6 +80,22 @@ pub trait ComponentService {
         component_id: &ComponentId,
         forced_version: Option<ComponentVersion>,
     ) -> Result<ComponentMetadata, GolemError>;
+
+    /// Gets the zip archive of the initial files of a component version. Archives are cached by
+    /// their content hash, as many versions typically share the same files.
+    async fn get_initial_files(
+        &self,
+        component_id: &ComponentId,
+        component_version: ComponentVersion,
+        initial_files_hash: &str,
+    ) -> Result<Arc<Vec<u8>>, GolemError>;
+
+    /// Gets the observability settings to be applied to the workers of a component.
+    /// Components never seen by this executor get the default settings.
+    fn get_observability(&self, component_id: &ComponentId) -> ComponentObservability;
+
+    /// Overrides the observability settings of a component, taking effect on all its workers
+    fn set_observability(&self, component_id: &ComponentId, observability: ComponentObservability);
 }
 
 pub async fn configured(
@@ -82,8 +103,10 @@ pub async fn configured(
     cache_config: &ComponentCacheConfig,
     compiled_config: &CompiledComponentServiceConfig,
     blob_storage: Arc<dyn BlobStorage + Send + Sync>,
+    key_value_storage: Arc<dyn KeyValueStorage + Send + Sync>,
 ) -> Arc<dyn ComponentService + Send + Sync> {
-    let compiled_component_service = compiled_component::configured(compiled_config, blob_storage);
+    let compiled_component_service =
+        compiled_component::configured(compiled_config, blob_storage, Some(key_value_storage));
     match config {
         ComponentServiceConfig::Grpc(config) => {
             info!("Using component API at {}", config.url());
@@ -95,6 +118,7 @@ pub async fn configured(
                     .expect("Access token must be an UUID"),
                 cache_config.max_capacity,
                 cache_config.max_metadata_capacity,
+                cache_config.max_initial_files_capacity,
                 cache_config.time_to_idle,
                 config.retries.clone(),
                 compiled_component_service,
@@ -111,6 +135,33 @@ pub async fn configured(
     }
 }
 
+/// If another executor sharing the compilation cache is already compiling the component, waits
+/// for its upload instead of compiling the same component again
+async fn await_compilation_by_other_executor(
+    compiled_component_service: &Arc<dyn CompiledComponentService + Send + Sync>,
+    component_id: &ComponentId,
+    component_version: ComponentVersion,
+    engine: &Engine,
+) -> Option<Component> {
+    let result = match compiled_component_service
+        .try_lease(component_id, component_version, engine)
+        .await
+    {
+        Ok(true) => Ok(None),
+        Ok(false) => {
+            debug!("Waiting for another executor to compile {component_id}#{component_version}");
+            compiled_component_service
+                .wait_for(component_id, component_version, engine)
+                .await
+        }
+        Err(err) => Err(err),
+    };
+    result.unwrap_or_else(|err| {
+        warn!("Failed to coordinate compilation of {component_id}#{component_version}: {err}");
+        None
+    })
+}
+
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 struct ComponentKey {
     component_id: ComponentId,
@@ -120,6 +171,8 @@ struct ComponentKey {
 pub struct ComponentServiceGrpc {
     component_cache: Cache<ComponentKey, (), Component, GolemError>,
     component_metadata_cache: Cache<ComponentKey, (), ComponentMetadata, GolemError>,
+    initial_files_cache: Cache<String, (), Arc<Vec<u8>>, GolemError>,
+    observability: Arc<DashMap<ComponentId, ComponentObservability>>,
     access_token: Uuid,
     retry_config: RetryConfig,
     compiled_component_service: Arc<dyn CompiledComponentService + Send + Sync>,
@@ -132,6 +185,7 @@ impl ComponentServiceGrpc {
         access_token: Uuid,
         max_capacity: usize,
         max_metadata_capacity: usize,
+        max_initial_files_capacity: usize,
         time_to_idle: Duration,
         retry_config: RetryConfig,
         compiled_component_service: Arc<dyn CompiledComponentService + Send + Sync>,
@@ -143,6 +197,11 @@ impl ComponentServiceGrpc {
                 max_metadata_capacity,
                 time_to_idle,
             ),
+            initial_files_cache: create_initial_files_cache(
+                max_initial_files_capacity,
+                time_to_idle,
+            ),
+            observability: Arc::new(DashMap::new()),
             access_token,
             retry_config: retry_config.clone(),
             compiled_component_service,
@@ -196,6 +255,18 @@ impl ComponentService for ComponentServiceGrpc {
                             None
                         }
                     };
+                    let component = match component {
+                        Some(component) => Some(component),
+                        None => {
+                            await_compilation_by_other_executor(
+                                &compiled_component_service,
+                                &component_id_clone,
+                                component_version,
+                                &engine,
+                            )
+                            .await
+                        }
+                    };
 
                     match component {
                         Some(component) => Ok(component),
@@ -266,6 +337,7 @@ impl ComponentService for ComponentServiceGrpc {
                 let access_token = self.access_token;
                 let retry_config = self.retry_config.clone();
                 let component_id = component_id.clone();
+                let observability = self.observability.clone();
                 self.component_metadata_cache
                     .get_or_insert_simple(
                         &ComponentKey {
@@ -274,21 +346,25 @@ impl ComponentService for ComponentServiceGrpc {
                         },
                         || {
                             Box::pin(async move {
-                                get_metadata_via_grpc(
+                                let (metadata, component_observability) = get_metadata_via_grpc(
                                     &client,
                                     &access_token,
                                     &retry_config,
                                     &component_id,
                                     forced_version,
                                 )
-                                .await
+                                .await?;
+                                if let Some(component_observability) = component_observability {
+                                    observability.insert(component_id, component_observability);
+                                }
+                                Ok(metadata)
                             })
                         },
                     )
                     .await
             }
             None => {
-                let metadata = get_metadata_via_grpc(
+                let (metadata, observability) = get_metadata_via_grpc(
                     &self.client,
                     &self.access_token,
                     &self.retry_config,
@@ -296,6 +372,9 @@ impl ComponentService for ComponentServiceGrpc {
                     None,
                 )
                 .await?;
+                if let Some(observability) = observability {
+                    self.set_observability(component_id, observability);
+                }
 
                 let metadata = self
                     .component_metadata_cache
@@ -312,6 +391,45 @@ impl ComponentService for ComponentServiceGrpc {
             }
         }
     }
+
+    async fn get_initial_files(
+        &self,
+        component_id: &ComponentId,
+        component_version: ComponentVersion,
+        initial_files_hash: &str,
+    ) -> Result<Arc<Vec<u8>>, GolemError> {
+        let client = self.client.clone();
+        let access_token = self.access_token;
+        let retry_config = self.retry_config.clone();
+        let component_id = component_id.clone();
+        self.initial_files_cache
+            .get_or_insert_simple(&initial_files_hash.to_string(), || {
+                Box::pin(async move {
+                    let bytes = download_initial_files_via_grpc(
+                        &client,
+                        &access_token,
+                        &retry_config,
+                        &component_id,
+                        component_version,
+                    )
+                    .await?;
+                    Ok(Arc::new(bytes))
+                })
+            })
+            .await
+    }
+
+    fn get_observability(&self, component_id: &ComponentId) -> ComponentObservability {
+        self.observability
+            .get(component_id)
+            .map(|entry| entry.value().clone())
+            .unwrap_or_default()
+    }
+
+    fn set_observability(&self, component_id: &ComponentId, observability: ComponentObservability) {
+        self.observability
+            .insert(component_id.clone(), observability);
+    }
 }
 
 async fn download_via_grpc(
@@ -372,13 +490,75 @@ async fn download_via_grpc(
     .map_err(|error| grpc_component_download_error(error, component_id, component_version))
 }
 
+async fn download_initial_files_via_grpc(
+    client: &GrpcClient<ComponentServiceClient<Channel>>,
+    access_token: &Uuid,
+    retry_config: &RetryConfig,
+    component_id: &ComponentId,
+    component_version: ComponentVersion,
+) -> Result<Vec<u8>, GolemError> {
+    with_retries(
+        "components",
+        "download_files",
+        Some(component_id.to_string()),
+        retry_config,
+        &(
+            client.clone(),
+            component_id.clone(),
+            access_token.to_owned(),
+        ),
+        |(client, component_id, access_token)| {
+            Box::pin(async move {
+                let response = client
+                    .call(move |client| {
+                        let request = authorised_grpc_request(
+                            DownloadComponentRequest {
+                                component_id: Some(component_id.clone().into()),
+                                version: Some(component_version),
+                            },
+                            access_token,
+                        );
+                        Box::pin(client.download_component_files(request))
+                    })
+                    .await?
+                    .into_inner();
+
+                let chunks = response.into_stream().try_collect::<Vec<_>>().await?;
+                let bytes = chunks
+                    .into_iter()
+                    .map(|chunk| match chunk.result {
+                        None => Err("Empty response".to_string().into()),
+                        Some(download_component_response::Result::SuccessChunk(chunk)) => Ok(chunk),
+                        Some(download_component_response::Result::Error(error)) => {
+                            Err(GrpcError::Domain(error))
+                        }
+                    })
+                    .collect::<Result<Vec<Vec<u8>>, GrpcError<ComponentError>>>()?;
+
+                let bytes: Vec<u8> = bytes.into_iter().flatten().collect();
+
+                record_external_call_response_size_bytes(
+                    "components",
+                    "download_files",
+                    bytes.len(),
+                );
+
+                Ok(bytes)
+            })
+        },
+        is_grpc_retriable::<ComponentError>,
+    )
+    .await
+    .map_err(|error| grpc_component_download_error(error, component_id, component_version))
+}
+
 async fn get_metadata_via_grpc(
     client: &GrpcClient<ComponentServiceClient<Channel>>,
     access_token: &Uuid,
     retry_config: &RetryConfig,
     component_id: &ComponentId,
     component_version: Option<ComponentVersion>,
-) -> Result<ComponentMetadata, GolemError> {
+) -> Result<(ComponentMetadata, Option<ComponentObservability>), GolemError> {
     let desc = format!("Getting component metadata of {component_id}");
     debug!("{}", &desc);
     with_retries(
@@ -421,13 +601,14 @@ async fn get_metadata_via_grpc(
                         .into_inner(),
                 };
                 let len = response.encoded_len();
-                let component = match response.result {
+                let (component, observability) = match response.result {
                     None => Err("Empty response".to_string().into()),
-                    Some(get_component_metadata_response::Result::Success(response)) => {
-                        Ok(response.component.ok_or(GrpcError::Unexpected(
+                    Some(get_component_metadata_response::Result::Success(response)) => Ok((
+                        response.component.ok_or(GrpcError::Unexpected(
                             "No component information in response".to_string(),
-                        ))?)
-                    }
+                        ))?,
+                        response.observability.map(ComponentObservability::from),
+                    )),
                     Some(get_component_metadata_response::Result::Error(error)) => {
                         Err(GrpcError::Domain(error))
                     }
@@ -460,11 +641,12 @@ async fn get_metadata_via_grpc(
                         .map_err(|_| {
                             GrpcError::Unexpected("Failed to get the exports".to_string())
                         })?,
+                    initial_files_hash: component.initial_files_hash,
                 };
 
                 record_external_call_response_size_bytes("components", "get_metadata", len);
 
-                Ok(result)
+                Ok((result, observability))
             })
         },
         is_grpc_retriable::<ComponentError>,
@@ -525,6 +707,21 @@ fn create_component_metadata_cache(
     )
 }
 
+fn create_initial_files_cache(
+    max_capacity: usize,
+    time_to_idle: Duration,
+) -> Cache<String, (), Arc<Vec<u8>>, GolemError> {
+    Cache::new(
+        Some(max_capacity),
+        FullCacheEvictionMode::LeastRecentlyUsed(1),
+        BackgroundEvictionMode::OlderThan {
+            ttl: time_to_idle,
+            period: Duration::from_secs(60),
+        },
+        "component_initial_files",
+    )
+}
+
 impl From<std::io::Error> for GolemError {
     fn from(value: std::io::Error) -> Self {
         GolemError::Unknown {
@@ -537,6 +734,7 @@ pub struct ComponentServiceLocalFileSystem {
     root: PathBuf,
     component_cache: Cache<ComponentKey, (), Component, GolemError>,
     component_metadata_cache: Cache<ComponentKey, (), ComponentMetadata, GolemError>,
+    observability: DashMap<ComponentId, ComponentObservability>,
     compiled_component_service: Arc<dyn CompiledComponentService + Send + Sync>,
 }
 
@@ -558,6 +756,7 @@ impl ComponentServiceLocalFileSystem {
                 max_metadata_capacity,
                 time_to_idle,
             ),
+            observability: DashMap::new(),
             compiled_component_service,
         }
     }
@@ -592,6 +791,18 @@ impl ComponentServiceLocalFileSystem {
                             None
                         }
                     };
+                    let component = match component {
+                        Some(component) => Some(component),
+                        None => {
+                            await_compilation_by_other_executor(
+                                &compiled_component_service,
+                                &component_id,
+                                component_version,
+                                &engine,
+                            )
+                            .await
+                        }
+                    };
 
                     match component {
                         Some(component) => Ok(component),
@@ -754,6 +965,7 @@ impl ComponentServiceLocalFileSystem {
             memories,
             exports,
             component_type: *component_type,
+            initial_files_hash: None,
         })
     }
 
@@ -838,4 +1050,30 @@ impl ComponentService for ComponentServiceLocalFileSystem {
             }
         }
     }
+
+    /// The local file system store has no initial files, its metadata never refers to any
+    async fn get_initial_files(
+        &self,
+        component_id: &ComponentId,
+        component_version: ComponentVersion,
+        _initial_files_hash: &str,
+    ) -> Result<Arc<Vec<u8>>, GolemError> {
+        Err(GolemError::ComponentDownloadFailed {
+            component_id: component_id.clone(),
+            component_version,
+            reason: "Initial files are not supported by the local component store".to_string(),
+        })
+    }
+
+    fn get_observability(&self, component_id: &ComponentId) -> ComponentObservability {
+        self.observability
+            .get(component_id)
+            .map(|entry| entry.value().clone())
+            .unwrap_or_default()
+    }
+
+    fn set_observability(&self, component_id: &ComponentId, observability: ComponentObservability) {
+        self.observability
+            .insert(component_id.clone(), observability);
+    }
 }
@@ -0,0 +1,543 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Display;
+use std::future::Future;
+use std::time::Duration;
+
+use futures::future::join_all;
+use futures::{TryStream, TryStreamExt};
+use golem_wasm_rpc::WitValue;
+use lapin::message::Delivery;
+use lapin::options::{BasicAckOptions, BasicConsumeOptions, BasicQosOptions, BasicRejectOptions};
+use lapin::types::FieldTable;
+use lapin::{Connection, ConnectionProperties, Consumer};
+use tokio::task::JoinHandle;
+use tracing::{info, warn, Instrument};
+use uuid::Uuid;
+
+use golem_common::config::RetryConfig;
+use golem_common::model::{IdempotencyKey, OwnedWorkerId, WorkerId};
+use golem_common::retries::get_delay;
+
+use crate::metrics::connectors::record_connector_message;
+use crate::services::connectors::{
+    convert_message, enqueue_invocation, function_parameter_types, retry_until_success,
+};
+use crate::services::golem_config::{
+    AmqpConnectorConfig, AmqpConnectorsConfig, AmqpConnectorsEnabledConfig, AmqpDeduplication,
+};
+use crate::services::HasAll;
+use crate::workerctx::WorkerCtx;
+
+/// Starts consuming the queues of the configured AMQP connectors, enqueueing an invocation of
+/// the connector's worker for each message.
+///
+/// A message is acknowledged only after its invocation was enqueued, and rejected without
+/// requeueing if it could not be converted to an invocation. At most `max_concurrency` messages
+/// of a queue are processed at the same time by an executor. When the connection to the broker
+/// is lost, the connector reconnects with exponential backoff, and the unacknowledged messages
+/// are redelivered by the broker. Each invocation has an idempotency key derived from the
+/// message, so redelivered messages do not invoke the worker twice, unless the connector's
+/// deduplication is turned off for messages without a `message_id`.
+///
+/// Returns `None` if the connectors are disabled.
+pub fn start<Ctx, T>(deps: T) -> Option<JoinHandle<()>>
+where
+    Ctx: WorkerCtx,
+    T: HasAll<Ctx> + Clone + Send + Sync + 'static,
+{
+    let config = match &deps.config().amqp_connectors {
+        AmqpConnectorsConfig::Enabled(config) if !config.connectors.is_empty() => config.clone(),
+        _ => return None,
+    };
+
+    info!("Starting {} AMQP connectors", config.connectors.len());
+    Some(tokio::spawn(
+        async move {
+            let connectors = config.connectors.iter().map(|connector| {
+                let deps = deps.clone();
+                let config = &config;
+                async move { run_connector(&deps, config, connector).await }
+            });
+            join_all(connectors).await;
+        }
+        .in_current_span(),
+    ))
+}
+
+async fn run_connector<Ctx, T>(
+    deps: &T,
+    config: &AmqpConnectorsEnabledConfig,
+    connector: &AmqpConnectorConfig,
+) where
+    Ctx: WorkerCtx,
+    T: HasAll<Ctx> + Clone + Send + Sync + 'static,
+{
+    // Consuming only after the executor got its shards, so the messages are not taken by
+    // executors which cannot enqueue invocations yet
+    while !deps.shard_service().is_ready() {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+
+    let mut backoff = ReconnectBackoff::new(&config.reconnect);
+    loop {
+        match connect(config, connector).await {
+            Ok((connection, consumer)) => {
+                info!(
+                    connector = connector.name,
+                    queue = connector.queue,
+                    "AMQP connector consuming"
+                );
+                backoff.connected();
+
+                let result =
+                    consume_concurrently(consumer, connector.max_concurrency, |delivery| {
+                        handle_delivery(deps, config, connector, delivery)
+                    })
+                    .await;
+                match result {
+                    Ok(()) => warn!(
+                        connector = connector.name,
+                        "The consumer was cancelled by the broker"
+                    ),
+                    Err(err) => warn!(
+                        connector = connector.name,
+                        "Lost the connection to the broker: {err}"
+                    ),
+                }
+                let _ = connection.close(0, "").await;
+            }
+            Err(err) => warn!(
+                connector = connector.name,
+                "Failed to connect to the broker: {err}"
+            ),
+        }
+        tokio::time::sleep(backoff.next_delay()).await;
+    }
+}
+
+/// Backoff of reconnecting to the broker. The delay grows exponentially from the minimal one,
+/// stays at the maximal one once the configured attempts are exhausted, and starts from the
+/// minimal one again after a connection was established.
+struct ReconnectBackoff<'a> {
+    config: &'a RetryConfig,
+    failures: u64,
+}
+
+impl<'a> ReconnectBackoff<'a> {
+    fn new(config: &'a RetryConfig) -> Self {
+        Self {
+            config,
+            failures: 0,
+        }
+    }
+
+    fn connected(&mut self) {
+        self.failures = 0;
+    }
+
+    /// The delay before the next attempt, after the connection failed or got lost
+    fn next_delay(&mut self) -> Duration {
+        self.failures += 1;
+        get_delay(self.config, self.failures).unwrap_or(self.config.max_delay)
+    }
+}
+
+/// The connection has to be kept open while the consumer is used
+async fn connect(
+    config: &AmqpConnectorsEnabledConfig,
+    connector: &AmqpConnectorConfig,
+) -> Result<(Connection, Consumer), lapin::Error> {
+    let connection = Connection::connect(&config.uri, ConnectionProperties::default()).await?;
+    let channel = connection.create_channel().await?;
+    channel
+        .basic_qos(connector.max_concurrency, BasicQosOptions::default())
+        .await?;
+    let consumer = channel
+        .basic_consume(
+            &connector.queue,
+            &format!("golem-{}-{}", connector.name, Uuid::new_v4()),
+            BasicConsumeOptions::default(),
+            FieldTable::default(),
+        )
+        .await?;
+    Ok((connection, consumer))
+}
+
+/// Handles the deliveries of the consumer, at most `max_concurrency` of them at the same time,
+/// 0 meaning no limit. Stops at the first error of the consumer or of the handler.
+async fn consume_concurrently<S, F, Fut>(
+    deliveries: S,
+    max_concurrency: u16,
+    handle: F,
+) -> Result<(), S::Error>
+where
+    S: TryStream,
+    F: FnMut(S::Ok) -> Fut,
+    Fut: Future<Output = Result<(), S::Error>>,
+{
+    let limit = (max_concurrency > 0).then_some(max_concurrency as usize);
+    deliveries.try_for_each_concurrent(limit, handle).await
+}
+
+async fn handle_delivery<Ctx, T>(
+    deps: &T,
+    config: &AmqpConnectorsEnabledConfig,
+    connector: &AmqpConnectorConfig,
+    delivery: Delivery,
+) -> Result<(), lapin::Error>
+where
+    Ctx: WorkerCtx,
+    T: HasAll<Ctx> + Clone + Send + Sync + 'static,
+{
+    let invocation = to_invocation(deps, config, connector, &delivery).await;
+    let idempotency_key = delivery_idempotency_key(
+        &connector.name,
+        &connector.queue,
+        connector.deduplication,
+        delivery
+            .properties
+            .message_id()
+            .as_ref()
+            .map(|message_id| message_id.as_str()),
+        delivery.routing_key.as_str(),
+        *delivery.properties.timestamp(),
+        &delivery.data,
+    );
+    let settlement = settle(
+        &config.retries,
+        &connector.name,
+        delivery.delivery_tag,
+        invocation,
+        |worker_id, function_params| {
+            let owned_worker_id = OwnedWorkerId::new(&connector.account_id, worker_id);
+            let idempotency_key = idempotency_key.clone();
+            let function_name = connector.function_name.clone();
+            async move {
+                enqueue_invocation(
+                    deps,
+                    &owned_worker_id,
+                    idempotency_key,
+                    function_name,
+                    function_params,
+                )
+                .await
+            }
+        },
+    )
+    .await;
+
+    match settlement {
+        Settlement::Ack => {
+            record_connector_message(&connector.name, "invoked");
+            delivery.ack(BasicAckOptions::default()).await
+        }
+        Settlement::Reject => {
+            record_connector_message(&connector.name, "rejected");
+            delivery.reject(BasicRejectOptions { requeue: false }).await
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Settlement {
+    Ack,
+    Reject,
+}
+
+/// Decides how a delivery is settled. The invocation of a message is enqueued with `enqueue`,
+/// retrying it until it succeeds, as the message would be lost by rejecting it. Messages which
+/// cannot be converted to an invocation are rejected.
+async fn settle<F, Fut, E>(
+    retries: &RetryConfig,
+    connector_name: &str,
+    delivery_tag: u64,
+    invocation: Result<(WorkerId, Vec<WitValue>), String>,
+    mut enqueue: F,
+) -> Settlement
+where
+    F: FnMut(&WorkerId, Vec<WitValue>) -> Fut,
+    Fut: Future<Output = Result<(), E>>,
+    E: Display,
+{
+    match invocation {
+        Ok((worker_id, function_params)) => {
+            retry_until_success(
+                retries,
+                || enqueue(&worker_id, function_params.clone()),
+                |err| {
+                    warn!(
+                        connector = connector_name,
+                        worker_id = worker_id.to_string(),
+                        "Failed to enqueue the invocation of message {delivery_tag}: {err}"
+                    )
+                },
+            )
+            .await;
+            Settlement::Ack
+        }
+        Err(error) => {
+            warn!(
+                connector = connector_name,
+                "Rejecting message {delivery_tag} which cannot be converted to an invocation: {error}"
+            );
+            Settlement::Reject
+        }
+    }
+}
+
+/// Converts the message to the target worker and the parameters of the invocation, the routing
+/// key being the key of the message
+async fn to_invocation<Ctx, T>(
+    deps: &T,
+    config: &AmqpConnectorsEnabledConfig,
+    connector: &AmqpConnectorConfig,
+    delivery: &Delivery,
+) -> Result<(WorkerId, Vec<WitValue>), String>
+where
+    Ctx: WorkerCtx,
+    T: HasAll<Ctx> + Send + Sync,
+{
+    let parameter_types = function_parameter_types(
+        deps,
+        &config.retries,
+        &connector.name,
+        &connector.component_id,
+        &connector.function_name,
+        connector.payload_format,
+    )
+    .await?;
+
+    convert_message(
+        &connector.component_id,
+        connector.worker_name.as_deref(),
+        connector.payload_format,
+        Some(delivery.routing_key.as_str().as_bytes()),
+        Some(&delivery.data),
+        &parameter_types,
+    )
+}
+
+/// The idempotency key of the invocation created from a message, identifying the message by its
+/// `message_id` property. Messages without one are identified by their content, unless
+/// deduplicating them is turned off, in which case they get a fresh key.
+fn delivery_idempotency_key(
+    connector_name: &str,
+    queue: &str,
+    deduplication: AmqpDeduplication,
+    message_id: Option<&str>,
+    routing_key: &str,
+    timestamp: Option<u64>,
+    payload: &[u8],
+) -> IdempotencyKey {
+    let name = match (message_id, deduplication) {
+        (Some(message_id), _) => {
+            format!("amqp-connector:{connector_name}:{queue}:{message_id}").into_bytes()
+        }
+        (None, AmqpDeduplication::Content) => {
+            let mut name = format!(
+                "amqp-connector:{connector_name}:{queue}:content:{}:{routing_key}:{timestamp:?}:",
+                routing_key.len()
+            )
+            .into_bytes();
+            name.extend_from_slice(payload);
+            name
+        }
+        (None, AmqpDeduplication::None) => return IdempotencyKey::fresh(),
+    };
+    IdempotencyKey::from_uuid(Uuid::new_v5(&Uuid::NAMESPACE_OID, &name))
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use golem_common::config::RetryConfig;
+    use golem_common::model::{ComponentId, WorkerId, WorkerName};
+
+    use crate::services::connectors::amqp::{
+        consume_concurrently, delivery_idempotency_key, settle, ReconnectBackoff, Settlement,
+    };
+    use crate::services::golem_config::AmqpDeduplication;
+
+    fn retries() -> RetryConfig {
+        RetryConfig {
+            max_attempts: 4,
+            min_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(5),
+            multiplier: 2.0,
+            max_jitter_factor: None,
+        }
+    }
+
+    #[test]
+    fn idempotency_key_depends_on_the_message_id() {
+        let key = |connector: &str, queue: &str, message_id: Option<&str>| {
+            delivery_idempotency_key(
+                connector,
+                queue,
+                AmqpDeduplication::Content,
+                message_id,
+                "worker-1",
+                None,
+                b"[]",
+            )
+        };
+        assert_eq!(
+            key("orders", "orders", Some("1")),
+            key("orders", "orders", Some("1"))
+        );
+        assert_ne!(
+            key("orders", "orders", Some("1")),
+            key("orders", "orders", Some("2"))
+        );
+        assert_ne!(
+            key("orders", "orders", Some("1")),
+            key("orders", "payments", Some("1"))
+        );
+        assert_ne!(
+            key("orders", "orders", Some("1")),
+            key("payments", "orders", Some("1"))
+        );
+    }
+
+    #[test]
+    fn idempotency_key_of_messages_without_id_depends_on_the_deduplication() {
+        let key = |deduplication, routing_key: &str, timestamp, payload: &[u8]| {
+            delivery_idempotency_key(
+                "orders",
+                "orders",
+                deduplication,
+                None,
+                routing_key,
+                timestamp,
+                payload,
+            )
+        };
+        let content = AmqpDeduplication::Content;
+        assert_eq!(
+            key(content, "worker-1", Some(1), b"[1]"),
+            key(content, "worker-1", Some(1), b"[1]")
+        );
+        assert_ne!(
+            key(content, "worker-1", Some(1), b"[1]"),
+            key(content, "worker-2", Some(1), b"[1]")
+        );
+        assert_ne!(
+            key(content, "worker-1", Some(1), b"[1]"),
+            key(content, "worker-1", Some(2), b"[1]")
+        );
+        assert_ne!(
+            key(content, "worker-1", Some(1), b"[1]"),
+            key(content, "worker-1", Some(1), b"[2]")
+        );
+        assert_ne!(
+            key(AmqpDeduplication::None, "worker-1", Some(1), b"[1]"),
+            key(AmqpDeduplication::None, "worker-1", Some(1), b"[1]")
+        );
+    }
+
+    #[test]
+    fn reconnect_backoff_grows_until_connected() {
+        let retries = retries();
+        let mut backoff = ReconnectBackoff::new(&retries);
+        let delays = (0..5).map(|_| backoff.next_delay()).collect::<Vec<_>>();
+        assert_eq!(
+            delays,
+            vec![1, 2, 4, 5, 5]
+                .into_iter()
+                .map(Duration::from_millis)
+                .collect::<Vec<_>>()
+        );
+
+        backoff.connected();
+        assert_eq!(backoff.next_delay(), Duration::from_millis(1));
+    }
+
+    #[test]
+    async fn unconvertible_messages_are_rejected() {
+        let enqueued = &AtomicUsize::new(0);
+        let settlement = settle(
+            &retries(),
+            "orders",
+            1,
+            Err("The payload is not valid JSON".to_string()),
+            move |_, _| async move {
+                enqueued.fetch_add(1, Ordering::SeqCst);
+                Ok::<(), String>(())
+            },
+        )
+        .await;
+
+        assert_eq!(settlement, Settlement::Reject);
+        assert_eq!(enqueued.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    async fn messages_are_acknowledged_once_enqueued() {
+        let worker_id = WorkerId {
+            component_id: ComponentId::new_v4(),
+            worker_name: WorkerName::from_existing("worker-1"),
+        };
+        let attempts = AtomicUsize::new(0);
+        // Failing for longer than the configured attempts, which does not lose the message
+        let settlement = settle(
+            &retries(),
+            "orders",
+            1,
+            Ok((worker_id.clone(), vec![])),
+            |target, _| {
+                assert_eq!(target, &worker_id);
+                let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    if attempt < 6 {
+                        Err("unavailable".to_string())
+                    } else {
+                        Ok(())
+                    }
+                }
+            },
+        )
+        .await;
+
+        assert_eq!(settlement, Settlement::Ack);
+        assert_eq!(attempts.load(Ordering::SeqCst), 7);
+    }
+
+    async fn max_in_flight(max_concurrency: u16) -> usize {
+        let in_flight = &AtomicUsize::new(0);
+        let max_in_flight = &AtomicUsize::new(0);
+        let deliveries = futures::stream::iter((0..10).map(Ok::<u32, String>));
+        consume_concurrently(deliveries, max_concurrency, move |_| async move {
+            let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            max_in_flight.fetch_max(current, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            in_flight.fetch_sub(1, Ordering::SeqCst);
+            Ok(())
+        })
+        .await
+        .unwrap();
+        max_in_flight.load(Ordering::SeqCst)
+    }
+
+    #[test]
+    async fn deliveries_are_handled_up_to_the_concurrency_limit() {
+        assert_eq!(max_in_flight(3).await, 3);
+        assert_eq!(max_in_flight(0).await, 10);
+    }
+}
@@ -0,0 +1,389 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use futures::future::join_all;
+use golem_wasm_rpc::WitValue;
+use rdkafka::config::ClientConfig;
+use rdkafka::consumer::{Consumer, StreamConsumer};
+use rdkafka::message::{BorrowedMessage, Header, OwnedHeaders};
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::util::Timeout;
+use rdkafka::Message;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn, Instrument};
+use uuid::Uuid;
+
+use golem_common::model::{AccountId, IdempotencyKey, OwnedWorkerId, WorkerId};
+use golem_common::retries::get_delay;
+
+use crate::metrics::connectors::record_connector_message;
+use crate::services::connectors::{
+    convert_message, enqueue_invocation, function_parameter_types, retry, retry_until_success,
+};
+use crate::services::golem_config::{
+    KafkaAccountConnectorsConfig, KafkaConnectorConfig, KafkaConnectorsConfig,
+    KafkaConnectorsEnabledConfig,
+};
+use crate::services::HasAll;
+use crate::workerctx::WorkerCtx;
+
+/// Starts consuming the topics of the Kafka connectors configured for the accounts, enqueueing
+/// an invocation of the connector's worker for each message.
+///
+/// The offset of a message is stored only after its invocation was enqueued, or after it was
+/// sent to the dead letter topic if it could not be converted to an invocation or its invocation
+/// could not be enqueued with the configured retries. Each invocation has an idempotency key
+/// derived from the position of the message, so messages consumed again after a rebalance or a
+/// restart do not invoke the worker twice.
+///
+/// Returns `None` if the connectors are disabled.
+pub fn start<Ctx, T>(deps: T) -> Option<JoinHandle<()>>
+where
+    Ctx: WorkerCtx,
+    T: HasAll<Ctx> + Clone + Send + Sync + 'static,
+{
+    let config = match &deps.config().kafka_connectors {
+        KafkaConnectorsConfig::Enabled(config)
+            if config
+                .accounts
+                .iter()
+                .any(|account| !account.connectors.is_empty()) =>
+        {
+            config.clone()
+        }
+        _ => return None,
+    };
+
+    info!(
+        "Starting the Kafka connectors of {} accounts",
+        config.accounts.len()
+    );
+    Some(tokio::spawn(
+        async move {
+            let deps = &deps;
+            let config = &config;
+            let connectors = config.accounts.iter().flat_map(|account| {
+                account.connectors.iter().map(move |connector| {
+                    let deps = deps.clone();
+                    async move {
+                        if let Err(err) = run_connector(&deps, config, account, connector).await {
+                            error!(
+                                account_id = account.account_id.to_string(),
+                                connector = connector.name,
+                                "Kafka connector stopped: {err}"
+                            );
+                        }
+                    }
+                })
+            });
+            join_all(connectors).await;
+        }
+        .in_current_span(),
+    ))
+}
+
+async fn run_connector<Ctx, T>(
+    deps: &T,
+    config: &KafkaConnectorsEnabledConfig,
+    account: &KafkaAccountConnectorsConfig,
+    connector: &KafkaConnectorConfig,
+) -> Result<(), String>
+where
+    Ctx: WorkerCtx,
+    T: HasAll<Ctx> + Clone + Send + Sync + 'static,
+{
+    let consumer: StreamConsumer = client_config(account)
+        .set(
+            "group.id",
+            format!(
+                "{}-{}-{}",
+                config.consumer_group_prefix, account.account_id, connector.name
+            ),
+        )
+        .set("enable.auto.commit", "true")
+        .set("enable.auto.offset.store", "false")
+        .set("auto.offset.reset", "earliest")
+        .create()
+        .map_err(|err| format!("Failed to create the consumer: {err}"))?;
+    let dead_letter_producer: Option<FutureProducer> = match &connector.dead_letter_topic {
+        Some(_) => Some(
+            client_config(account)
+                .create()
+                .map_err(|err| format!("Failed to create the dead letter producer: {err}"))?,
+        ),
+        None => None,
+    };
+
+    // Joining the consumer group only after the executor got its shards, so the partitions are
+    // not assigned to executors which cannot enqueue invocations yet
+    while !deps.shard_service().is_ready() {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+
+    consumer
+        .subscribe(&[&connector.topic])
+        .map_err(|err| format!("Failed to subscribe to {}: {err}", connector.topic))?;
+    info!(
+        connector = connector.name,
+        topic = connector.topic,
+        "Kafka connector subscribed"
+    );
+
+    let mut receive_failures = 0;
+    loop {
+        let message = match consumer.recv().await {
+            Ok(message) => {
+                receive_failures = 0;
+                message
+            }
+            Err(err) => {
+                warn!(
+                    connector = connector.name,
+                    "Failed to consume message: {err}"
+                );
+                receive_failures += 1;
+                let delay = get_delay(&config.retries, receive_failures)
+                    .unwrap_or(config.retries.max_delay);
+                tokio::time::sleep(delay).await;
+                continue;
+            }
+        };
+
+        let result = match to_invocation(deps, config, connector, &message).await {
+            Ok((worker_id, function_params)) => {
+                let owned_worker_id = OwnedWorkerId::new(&account.account_id, &worker_id);
+                let idempotency_key = message_idempotency_key(
+                    &account.account_id,
+                    &connector.name,
+                    message.topic(),
+                    message.partition(),
+                    message.offset(),
+                );
+                retry(
+                    &config.retries,
+                    || {
+                        enqueue_invocation(
+                            deps,
+                            &owned_worker_id,
+                            idempotency_key.clone(),
+                            connector.function_name.clone(),
+                            function_params.clone(),
+                        )
+                    },
+                    |err| {
+                        warn!(
+                            connector = connector.name,
+                            worker_id = worker_id.to_string(),
+                            "Failed to enqueue the invocation of message {}: {err}",
+                            message.offset()
+                        )
+                    },
+                )
+                .await
+                .map_err(|err| format!("Failed to enqueue the invocation: {err}"))
+            }
+            Err(error) => Err(error),
+        };
+
+        let outcome = match result {
+            Ok(()) => "invoked",
+            Err(error) => match (&dead_letter_producer, &connector.dead_letter_topic) {
+                (Some(producer), Some(dead_letter_topic)) => {
+                    retry_until_success(
+                        &config.retries,
+                        || {
+                            send_to_dead_letter_topic(
+                                producer,
+                                dead_letter_topic,
+                                connector,
+                                &message,
+                                &error,
+                            )
+                        },
+                        |err| {
+                            warn!(
+                                connector = connector.name,
+                                "Failed to send message {} to the dead letter topic: {err}",
+                                message.offset()
+                            )
+                        },
+                    )
+                    .await;
+                    "dead_lettered"
+                }
+                _ => {
+                    warn!(
+                        connector = connector.name,
+                        "Skipping message {} which cannot be invoked: {error}",
+                        message.offset()
+                    );
+                    "skipped"
+                }
+            },
+        };
+        record_connector_message(&connector.name, outcome);
+
+        // Fails if a rebalance revoked the partition meanwhile. Its new owner consumes the
+        // message again, and the idempotency key prevents invoking the worker twice.
+        if let Err(err) = consumer.store_offset_from_message(&message) {
+            warn!(
+                connector = connector.name,
+                "Failed to store the offset of message {}: {err}",
+                message.offset()
+            );
+        }
+    }
+}
+
+fn client_config(account: &KafkaAccountConnectorsConfig) -> ClientConfig {
+    let mut client_config = ClientConfig::new();
+    client_config.set("bootstrap.servers", &account.bootstrap_servers);
+    for (key, value) in &account.properties {
+        client_config.set(key, value);
+    }
+    client_config
+}
+
+/// Converts the message to the target worker and the parameters of the invocation
+async fn to_invocation<Ctx, T>(
+    deps: &T,
+    config: &KafkaConnectorsEnabledConfig,
+    connector: &KafkaConnectorConfig,
+    message: &BorrowedMessage<'_>,
+) -> Result<(WorkerId, Vec<WitValue>), String>
+where
+    Ctx: WorkerCtx,
+    T: HasAll<Ctx> + Send + Sync,
+{
+    let parameter_types = function_parameter_types(
+        deps,
+        &config.retries,
+        &connector.name,
+        &connector.component_id,
+        &connector.function_name,
+        connector.payload_format,
+    )
+    .await?;
+
+    convert_message(
+        &connector.component_id,
+        connector.worker_name.as_deref(),
+        connector.payload_format,
+        message.key(),
+        message.payload(),
+        &parameter_types,
+    )
+}
+
+async fn send_to_dead_letter_topic(
+    producer: &FutureProducer,
+    dead_letter_topic: &str,
+    connector: &KafkaConnectorConfig,
+    message: &BorrowedMessage<'_>,
+    error: &str,
+) -> Result<(), String> {
+    let partition = message.partition().to_string();
+    let offset = message.offset().to_string();
+    let headers = OwnedHeaders::new()
+        .insert(Header {
+            key: "golem-connector",
+            value: Some(connector.name.as_str()),
+        })
+        .insert(Header {
+            key: "golem-error",
+            value: Some(error),
+        })
+        .insert(Header {
+            key: "golem-source-topic",
+            value: Some(message.topic()),
+        })
+        .insert(Header {
+            key: "golem-source-partition",
+            value: Some(partition.as_str()),
+        })
+        .insert(Header {
+            key: "golem-source-offset",
+            value: Some(offset.as_str()),
+        });
+
+    let mut record = FutureRecord::<[u8], [u8]>::to(dead_letter_topic).headers(headers);
+    if let Some(key) = message.key() {
+        record = record.key(key);
+    }
+    if let Some(payload) = message.payload() {
+        record = record.payload(payload);
+    }
+
+    producer
+        .send(record, Timeout::Never)
+        .await
+        .map(|_| ())
+        .map_err(|(err, _)| err.to_string())
+}
+
+/// The idempotency key of the invocation created from a message, identifying the message by
+/// its position in the topic
+fn message_idempotency_key(
+    account_id: &AccountId,
+    connector_name: &str,
+    topic: &str,
+    partition: i32,
+    offset: i64,
+) -> IdempotencyKey {
+    let name =
+        format!("kafka-connector:{account_id}:{connector_name}:{topic}:{partition}:{offset}");
+    IdempotencyKey::from_uuid(Uuid::new_v5(&Uuid::NAMESPACE_OID, name.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use golem_common::model::AccountId;
+
+    use crate::services::connectors::kafka::message_idempotency_key;
+
+    #[test]
+    fn idempotency_key_depends_on_the_position_of_the_message() {
+        let account_id = AccountId {
+            value: "account".to_string(),
+        };
+        let other_account_id = AccountId {
+            value: "other".to_string(),
+        };
+        let key = message_idempotency_key(&account_id, "orders", "orders", 0, 10);
+        assert_eq!(
+            key,
+            message_idempotency_key(&account_id, "orders", "orders", 0, 10)
+        );
+        assert_ne!(
+            key,
+            message_idempotency_key(&account_id, "orders", "orders", 1, 10)
+        );
+        assert_ne!(
+            key,
+            message_idempotency_key(&account_id, "orders", "orders", 0, 11)
+        );
+        assert_ne!(
+            key,
+            message_idempotency_key(&account_id, "payments", "orders", 0, 10)
+        );
+        assert_ne!(
+            key,
+            message_idempotency_key(&other_account_id, "orders", "orders", 0, 10)
+        );
+    }
+}
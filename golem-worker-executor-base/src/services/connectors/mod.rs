@@ -0,0 +1,360 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Connectors consuming messages of external brokers and turning each of them into an
+//! invocation of a worker
+
+pub mod amqp;
+pub mod kafka;
+
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+
+use golem_wasm_ast::analysis::AnalysedType;
+use golem_wasm_rpc::json::TypeAnnotatedValueJsonExtensions;
+use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
+use golem_wasm_rpc::{Value, WitValue};
+use tracing::warn;
+
+use golem_common::config::RetryConfig;
+use golem_common::model::exports::function_by_name;
+use golem_common::model::{
+    ComponentId, IdempotencyKey, InvocationPriority, OwnedWorkerId, WorkerId, WorkerName,
+};
+use golem_common::retries::RetryState;
+
+use crate::error::GolemError;
+use crate::services::golem_config::ConnectorPayloadFormat;
+use crate::services::HasAll;
+use crate::worker::Worker;
+use crate::workerctx::WorkerCtx;
+
+/// Gets the parameter types of the invoked function, needed by the JSON payload format. Getting
+/// the component's metadata is retried, as it may fail for reasons unrelated to the message.
+async fn function_parameter_types<Ctx, T>(
+    deps: &T,
+    retries: &RetryConfig,
+    connector_name: &str,
+    component_id: &ComponentId,
+    function_name: &str,
+    payload_format: ConnectorPayloadFormat,
+) -> Result<Vec<AnalysedType>, String>
+where
+    Ctx: WorkerCtx,
+    T: HasAll<Ctx> + Send + Sync,
+{
+    match payload_format {
+        ConnectorPayloadFormat::Json => {
+            let metadata = retry(
+                retries,
+                || async {
+                    deps.component_service()
+                        .get_metadata(component_id, None)
+                        .await
+                },
+                |err| {
+                    warn!(
+                        connector = connector_name,
+                        "Failed to get the metadata of component {component_id}: {err}"
+                    )
+                },
+            )
+            .await
+            .map_err(|err| {
+                format!("Failed to get the metadata of component {component_id}: {err}")
+            })?;
+            let function = function_by_name(&metadata.exports, function_name)?
+                .ok_or_else(|| format!("Function {function_name} not found"))?;
+            Ok(function
+                .parameters
+                .into_iter()
+                .map(|parameter| parameter.typ)
+                .collect())
+        }
+        ConnectorPayloadFormat::Raw => Ok(Vec::new()),
+    }
+}
+
+/// Converts the key and the payload of a message to the target worker and the parameters of
+/// the invocation. The key is only used if the connector has no fixed worker name, and the
+/// parameter types are only used by the JSON payload format.
+fn convert_message(
+    component_id: &ComponentId,
+    worker_name: Option<&str>,
+    payload_format: ConnectorPayloadFormat,
+    key: Option<&[u8]>,
+    payload: Option<&[u8]>,
+    parameter_types: &[AnalysedType],
+) -> Result<(WorkerId, Vec<WitValue>), String> {
+    let worker_name = match worker_name {
+        Some(worker_name) => WorkerName::from_existing(worker_name),
+        None => {
+            let key = key.ok_or("The message has no key to be used as worker name")?;
+            let worker_name = std::str::from_utf8(key).map_err(|err| {
+                format!("The key of the message is not a valid worker name: {err}")
+            })?;
+            WorkerName::new(worker_name).map_err(|err| {
+                format!("The key of the message is not a valid worker name: {err}")
+            })?
+        }
+    };
+    let worker_id = WorkerId {
+        component_id: component_id.clone(),
+        worker_name,
+    };
+
+    let payload = payload.unwrap_or_default();
+    let function_params = match payload_format {
+        ConnectorPayloadFormat::Json => {
+            let json: serde_json::Value = serde_json::from_slice(payload)
+                .map_err(|err| format!("The payload is not valid JSON: {err}"))?;
+            let values = json
+                .as_array()
+                .ok_or("The payload must be a JSON array of the function's parameters")?;
+            if values.len() != parameter_types.len() {
+                return Err(format!(
+                    "Unexpected number of parameters: got {}, expected {}",
+                    values.len(),
+                    parameter_types.len()
+                ));
+            }
+            values
+                .iter()
+                .zip(parameter_types)
+                .map(|(value, typ)| {
+                    let typed =
+                        TypeAnnotatedValue::parse_with_type(value, typ).map_err(|errors| {
+                            format!("Failed to parse parameter: {}", errors.join(", "))
+                        })?;
+                    Value::try_from(typed).map(WitValue::from)
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        }
+        ConnectorPayloadFormat::Raw => vec![WitValue::from(Value::List(
+            payload.iter().map(|byte| Value::U8(*byte)).collect(),
+        ))],
+    };
+
+    Ok((worker_id, function_params))
+}
+
+/// Enqueues the invocation on the worker if it belongs to this executor, otherwise through the
+/// worker API
+async fn enqueue_invocation<Ctx, T>(
+    deps: &T,
+    owned_worker_id: &OwnedWorkerId,
+    idempotency_key: IdempotencyKey,
+    function_name: String,
+    function_params: Vec<WitValue>,
+) -> Result<(), GolemError>
+where
+    Ctx: WorkerCtx,
+    T: HasAll<Ctx> + Clone + Send + Sync + 'static,
+{
+    if deps
+        .shard_service()
+        .check_worker(&owned_worker_id.worker_id)
+        .is_ok()
+    {
+        let worker =
+            Worker::get_or_create_running(deps, owned_worker_id, None, None, None, None).await?;
+        worker
+            .invoke(
+                idempotency_key,
+                function_name,
+                function_params.into_iter().map(Value::from).collect(),
+                InvocationPriority::Normal,
+                BTreeMap::new(),
+            )
+            .await?;
+        Ok(())
+    } else {
+        deps.worker_proxy()
+            .invoke(
+                owned_worker_id,
+                Some(idempotency_key),
+                function_name,
+                function_params,
+                None,
+                Vec::new(),
+                HashMap::new(),
+                BTreeMap::new(),
+            )
+            .await
+            .map_err(|err| GolemError::runtime(err.to_string()))
+    }
+}
+
+/// Runs the action until it succeeds or the configured attempts are exhausted, backing off
+/// between the attempts. Returns the error of the last attempt.
+async fn retry<F, Fut, R, E>(
+    retries: &RetryConfig,
+    mut action: F,
+    on_failure: impl Fn(&E),
+) -> Result<R, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<R, E>>,
+{
+    let mut retry_state = RetryState::new(retries);
+    loop {
+        retry_state.start_attempt();
+        match action().await {
+            Ok(result) => break Ok(result),
+            Err(err) => {
+                on_failure(&err);
+                if !retry_state.failed_attempt().await {
+                    break Err(err);
+                }
+            }
+        }
+    }
+}
+
+/// Runs the action until it succeeds, backing off between the attempts as configured. Once the
+/// configured attempts are exhausted the backoff starts again, for actions the connector cannot
+/// give up on without losing the current message.
+async fn retry_until_success<F, Fut, R, E>(
+    retries: &RetryConfig,
+    mut action: F,
+    on_failure: impl Fn(&E),
+) -> R
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<R, E>>,
+{
+    loop {
+        if let Ok(result) = retry(retries, &mut action, &on_failure).await {
+            break result;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration;
+
+    use golem_common::config::RetryConfig;
+    use golem_common::model::{ComponentId, WorkerId, WorkerName};
+    use golem_wasm_ast::analysis::analysed_type::{str, u32};
+    use golem_wasm_ast::analysis::AnalysedType;
+    use golem_wasm_rpc::{Value, WitValue};
+
+    use crate::services::connectors::{convert_message, retry};
+    use crate::services::golem_config::ConnectorPayloadFormat;
+
+    fn values(params: Vec<WitValue>) -> Vec<Value> {
+        params.into_iter().map(Value::from).collect()
+    }
+
+    #[test]
+    fn json_payload_is_converted_to_the_function_parameters() {
+        let component_id = ComponentId::new_v4();
+        let (worker_id, params) = convert_message(
+            &component_id,
+            None,
+            ConnectorPayloadFormat::Json,
+            Some(b"customer-1"),
+            Some(br#"["book", 2]"#),
+            &[str(), u32()],
+        )
+        .unwrap();
+
+        assert_eq!(
+            worker_id,
+            WorkerId {
+                component_id,
+                worker_name: WorkerName::from_existing("customer-1"),
+            }
+        );
+        assert_eq!(
+            values(params),
+            vec![Value::String("book".to_string()), Value::U32(2)]
+        );
+    }
+
+    #[test]
+    fn raw_payload_is_passed_as_is_to_the_fixed_worker() {
+        let (worker_id, params) = convert_message(
+            &ComponentId::new_v4(),
+            Some("ingest"),
+            ConnectorPayloadFormat::Raw,
+            None,
+            Some(&[1, 2]),
+            &[],
+        )
+        .unwrap();
+
+        assert_eq!(worker_id.worker_name, "ingest");
+        assert_eq!(
+            values(params),
+            vec![Value::List(vec![Value::U8(1), Value::U8(2)])]
+        );
+    }
+
+    #[test]
+    fn unconvertible_messages_are_rejected() {
+        let component_id = ComponentId::new_v4();
+        let convert = |key: Option<&[u8]>, payload: &[u8], parameter_types: &[AnalysedType]| {
+            convert_message(
+                &component_id,
+                None,
+                ConnectorPayloadFormat::Json,
+                key,
+                Some(payload),
+                parameter_types,
+            )
+        };
+        assert!(convert(None, b"[]", &[]).is_err());
+        assert!(convert(Some(b""), b"[]", &[]).is_err());
+        assert!(convert(Some(b"w"), b"not json", &[]).is_err());
+        assert!(convert(Some(b"w"), br#"{"a": 1}"#, &[]).is_err());
+        assert!(convert(Some(b"w"), br#"["x"]"#, &[u32()]).is_err());
+        assert!(convert(Some(b"w"), b"[]", &[u32()]).is_err());
+        assert!(convert(Some(b"not a worker"), b"[]", &[]).is_err());
+        assert!(convert(Some(b"orders/1"), b"[]", &[]).is_err());
+    }
+
+    #[test]
+    async fn retries_are_bounded() {
+        let retries = RetryConfig {
+            max_attempts: 3,
+            min_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(1),
+            multiplier: 1.0,
+            max_jitter_factor: None,
+        };
+        let attempts = AtomicU32::new(0);
+        let failures = AtomicU32::new(0);
+
+        let result = retry(
+            &retries,
+            || async {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err::<(), _>("unavailable")
+            },
+            |_| {
+                failures.fetch_add(1, Ordering::SeqCst);
+            },
+        )
+        .await;
+
+        assert_eq!(result, Err("unavailable"));
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+        assert_eq!(failures.load(Ordering::SeqCst), 3);
+    }
+}
@@ -0,0 +1,406 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+use std::time::Duration;
+
+use aes_gcm::aead::{Aead, KeyInit, OsRng};
+use aes_gcm::{AeadCore, Aes256Gcm, Key, Nonce};
+use async_trait::async_trait;
+use bincode::{Decode, Encode};
+use tracing::debug;
+
+use golem_common::cache::{BackgroundEvictionMode, Cache, FullCacheEvictionMode, SimpleCache};
+use golem_common::model::AccountId;
+
+use crate::services::golem_config::LocalEncryptionConfig;
+use crate::storage::keyvalue::{
+    KeyValueStorage, KeyValueStorageLabelledApi, KeyValueStorageNamespace,
+};
+
+const NONCE_SIZE: usize = 12;
+
+/// A data key encrypted with one of the master keys of a `KeyManagementService`
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct WrappedDataKey {
+    pub master_key_id: String,
+    pub wrapped_key: Vec<u8>,
+}
+
+/// Pluggable key management service protecting the per-account data keys
+#[async_trait]
+pub trait KeyManagementService: Debug {
+    /// The id of the master key new data keys are wrapped with
+    fn current_master_key_id(&self) -> &str;
+
+    /// Wraps a data key with the current master key
+    async fn wrap_data_key(&self, data_key: &[u8]) -> Result<WrappedDataKey, String>;
+
+    /// Unwraps a data key with the master key it was wrapped with
+    async fn unwrap_data_key(&self, wrapped: &WrappedDataKey) -> Result<Vec<u8>, String>;
+}
+
+/// Key management service using AES-256-GCM master keys provided in the executor's configuration
+pub struct LocalKeyManagementService {
+    current_master_key_id: String,
+    master_keys: BTreeMap<String, Key<Aes256Gcm>>,
+}
+
+impl Debug for LocalKeyManagementService {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalKeyManagementService")
+            .field("current_master_key_id", &self.current_master_key_id)
+            .field(
+                "master_key_ids",
+                &self.master_keys.keys().collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl LocalKeyManagementService {
+    pub fn new(config: &LocalEncryptionConfig) -> Result<Self, String> {
+        let mut master_keys = BTreeMap::new();
+        for (id, key) in &config.master_keys {
+            let key = hex::decode(key).map_err(|err| format!("Invalid master key {id}: {err}"))?;
+            if key.len() != 32 {
+                return Err(format!("Master key {id} must be 256 bits long"));
+            }
+            master_keys.insert(id.clone(), *Key::<Aes256Gcm>::from_slice(&key));
+        }
+
+        if !master_keys.contains_key(&config.current_master_key) {
+            return Err(format!(
+                "Current master key {} is not configured",
+                config.current_master_key
+            ));
+        }
+
+        Ok(Self {
+            current_master_key_id: config.current_master_key.clone(),
+            master_keys,
+        })
+    }
+}
+
+#[async_trait]
+impl KeyManagementService for LocalKeyManagementService {
+    fn current_master_key_id(&self) -> &str {
+        &self.current_master_key_id
+    }
+
+    async fn wrap_data_key(&self, data_key: &[u8]) -> Result<WrappedDataKey, String> {
+        let master_key = &self.master_keys[&self.current_master_key_id];
+        Ok(WrappedDataKey {
+            master_key_id: self.current_master_key_id.clone(),
+            wrapped_key: seal(master_key, data_key)?,
+        })
+    }
+
+    async fn unwrap_data_key(&self, wrapped: &WrappedDataKey) -> Result<Vec<u8>, String> {
+        let master_key = self
+            .master_keys
+            .get(&wrapped.master_key_id)
+            .ok_or_else(|| format!("Unknown master key: {}", wrapped.master_key_id))?;
+        open(master_key, &wrapped.wrapped_key)
+    }
+}
+
+/// Envelope encryption of oplog payloads using per-account data keys
+#[async_trait]
+pub trait EncryptionService: Debug {
+    /// Encrypts the data with the account's data key, returning the nonce and the ciphertext
+    async fn encrypt(
+        &self,
+        account_id: &AccountId,
+        data: &[u8],
+    ) -> Result<(Vec<u8>, Vec<u8>), String>;
+
+    /// Decrypts a ciphertext produced by `encrypt`
+    async fn decrypt(
+        &self,
+        account_id: &AccountId,
+        nonce: &[u8],
+        data: &[u8],
+    ) -> Result<Vec<u8>, String>;
+}
+
+/// Encryption service storing the wrapped data keys in the key-value storage.
+///
+/// Data keys are generated on first use. A data key wrapped with a master key other than
+/// the current one gets re-wrapped the next time it is loaded, so rotating the master key
+/// does not require rewriting any stored payloads.
+pub struct DefaultEncryptionService {
+    key_management_service: Arc<dyn KeyManagementService + Send + Sync>,
+    key_value_storage: Arc<dyn KeyValueStorage + Send + Sync>,
+    data_keys: Cache<AccountId, (), Arc<Aes256Gcm>, String>,
+}
+
+impl Debug for DefaultEncryptionService {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DefaultEncryptionService")
+            .field("key_management_service", &self.key_management_service)
+            .finish()
+    }
+}
+
+impl DefaultEncryptionService {
+    pub fn new(
+        key_management_service: Arc<dyn KeyManagementService + Send + Sync>,
+        key_value_storage: Arc<dyn KeyValueStorage + Send + Sync>,
+    ) -> Self {
+        Self {
+            key_management_service,
+            key_value_storage,
+            data_keys: Cache::new(
+                Some(1024),
+                FullCacheEvictionMode::LeastRecentlyUsed(1),
+                BackgroundEvictionMode::OlderThan {
+                    ttl: Duration::from_secs(60 * 60),
+                    period: Duration::from_secs(60),
+                },
+                "data_keys",
+            ),
+        }
+    }
+
+    async fn data_key(&self, account_id: &AccountId) -> Result<Arc<Aes256Gcm>, String> {
+        let key_management_service = self.key_management_service.clone();
+        let key_value_storage = self.key_value_storage.clone();
+        let account_id = account_id.clone();
+        self.data_keys
+            .get_or_insert_simple(&account_id.clone(), || {
+                Box::pin(async move {
+                    let data_key =
+                        Self::load_or_create(key_management_service, key_value_storage, account_id)
+                            .await?;
+                    Ok(Arc::new(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(
+                        &data_key,
+                    ))))
+                })
+            })
+            .await
+    }
+
+    async fn load_or_create(
+        key_management_service: Arc<dyn KeyManagementService + Send + Sync>,
+        key_value_storage: Arc<dyn KeyValueStorage + Send + Sync>,
+        account_id: AccountId,
+    ) -> Result<Vec<u8>, String> {
+        let key = account_id.to_string();
+        let stored: Option<WrappedDataKey> = key_value_storage
+            .with_entity("encryption", "load_data_key", "data_key")
+            .get(KeyValueStorageNamespace::DataKey, &key)
+            .await?;
+
+        match stored {
+            Some(wrapped) => {
+                let data_key = key_management_service.unwrap_data_key(&wrapped).await?;
+                if wrapped.master_key_id != key_management_service.current_master_key_id() {
+                    debug!(
+                        account_id = account_id.to_string(),
+                        old_master_key = wrapped.master_key_id,
+                        "Re-wrapping data key with the current master key"
+                    );
+                    let rewrapped = key_management_service.wrap_data_key(&data_key).await?;
+                    key_value_storage
+                        .with_entity("encryption", "rewrap_data_key", "data_key")
+                        .set(KeyValueStorageNamespace::DataKey, &key, &rewrapped)
+                        .await?;
+                }
+                Ok(data_key)
+            }
+            None => {
+                let data_key = Aes256Gcm::generate_key(OsRng).to_vec();
+                let wrapped = key_management_service.wrap_data_key(&data_key).await?;
+                let created = key_value_storage
+                    .with_entity("encryption", "create_data_key", "data_key")
+                    .set_if_not_exists(KeyValueStorageNamespace::DataKey, &key, &wrapped)
+                    .await?;
+                if created {
+                    Ok(data_key)
+                } else {
+                    // Another executor created the account's data key concurrently
+                    let wrapped: WrappedDataKey = key_value_storage
+                        .with_entity("encryption", "load_data_key", "data_key")
+                        .get(KeyValueStorageNamespace::DataKey, &key)
+                        .await?
+                        .ok_or_else(|| format!("Data key of account {account_id} disappeared"))?;
+                    key_management_service.unwrap_data_key(&wrapped).await
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl EncryptionService for DefaultEncryptionService {
+    async fn encrypt(
+        &self,
+        account_id: &AccountId,
+        data: &[u8],
+    ) -> Result<(Vec<u8>, Vec<u8>), String> {
+        let cipher = self.data_key(account_id).await?;
+        let nonce = Aes256Gcm::generate_nonce(OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, data)
+            .map_err(|err| format!("Failed to encrypt oplog payload: {err}"))?;
+        Ok((nonce.to_vec(), ciphertext))
+    }
+
+    async fn decrypt(
+        &self,
+        account_id: &AccountId,
+        nonce: &[u8],
+        data: &[u8],
+    ) -> Result<Vec<u8>, String> {
+        if nonce.len() != NONCE_SIZE {
+            return Err(format!("Invalid oplog payload nonce size: {}", nonce.len()));
+        }
+        let cipher = self.data_key(account_id).await?;
+        cipher
+            .decrypt(Nonce::from_slice(nonce), data)
+            .map_err(|err| format!("Failed to decrypt oplog payload: {err}"))
+    }
+}
+
+fn seal(key: &Key<Aes256Gcm>, data: &[u8]) -> Result<Vec<u8>, String> {
+    let nonce = Aes256Gcm::generate_nonce(OsRng);
+    let mut result = nonce.to_vec();
+    result.extend(
+        Aes256Gcm::new(key)
+            .encrypt(&nonce, data)
+            .map_err(|err| format!("Failed to wrap data key: {err}"))?,
+    );
+    Ok(result)
+}
+
+fn open(key: &Key<Aes256Gcm>, data: &[u8]) -> Result<Vec<u8>, String> {
+    if data.len() < NONCE_SIZE {
+        return Err("Wrapped data key is too short".to_string());
+    }
+    let (nonce, ciphertext) = data.split_at(NONCE_SIZE);
+    Aes256Gcm::new(key)
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|err| format!("Failed to unwrap data key: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+
+    use golem_common::model::AccountId;
+
+    use crate::services::encryption::{
+        DefaultEncryptionService, EncryptionService, LocalKeyManagementService, WrappedDataKey,
+    };
+    use crate::services::golem_config::LocalEncryptionConfig;
+    use crate::storage::keyvalue::memory::InMemoryKeyValueStorage;
+    use crate::storage::keyvalue::{
+        KeyValueStorage, KeyValueStorageLabelledApi, KeyValueStorageNamespace,
+    };
+
+    fn config(current_master_key: &str) -> LocalEncryptionConfig {
+        LocalEncryptionConfig {
+            current_master_key: current_master_key.to_string(),
+            master_keys: BTreeMap::from([
+                ("key1".to_string(), "11".repeat(32)),
+                ("key2".to_string(), "22".repeat(32)),
+            ]),
+        }
+    }
+
+    fn service(
+        current_master_key: &str,
+        key_value_storage: Arc<dyn KeyValueStorage + Send + Sync>,
+    ) -> DefaultEncryptionService {
+        DefaultEncryptionService::new(
+            Arc::new(LocalKeyManagementService::new(&config(current_master_key)).unwrap()),
+            key_value_storage,
+        )
+    }
+
+    fn account(name: &str) -> AccountId {
+        AccountId {
+            value: name.to_string(),
+        }
+    }
+
+    #[test]
+    async fn encrypt_decrypt_roundtrip() {
+        let service = service("key1", Arc::new(InMemoryKeyValueStorage::new()));
+        let account = account("account1");
+
+        let (nonce, ciphertext) = service.encrypt(&account, b"hello world").await.unwrap();
+        assert_ne!(ciphertext, b"hello world".to_vec());
+
+        let plaintext = service
+            .decrypt(&account, &nonce, &ciphertext)
+            .await
+            .unwrap();
+        assert_eq!(plaintext, b"hello world".to_vec());
+    }
+
+    #[test]
+    async fn data_keys_are_account_scoped() {
+        let service = service("key1", Arc::new(InMemoryKeyValueStorage::new()));
+
+        let (nonce, ciphertext) = service
+            .encrypt(&account("account1"), b"secret")
+            .await
+            .unwrap();
+
+        assert!(service
+            .decrypt(&account("account2"), &nonce, &ciphertext)
+            .await
+            .is_err());
+    }
+
+    #[test]
+    async fn master_key_rotation_rewraps_data_keys() {
+        let key_value_storage: Arc<dyn KeyValueStorage + Send + Sync> =
+            Arc::new(InMemoryKeyValueStorage::new());
+        let account = account("account1");
+
+        let (nonce, ciphertext) = service("key1", key_value_storage.clone())
+            .encrypt(&account, b"secret")
+            .await
+            .unwrap();
+
+        let rotated = service("key2", key_value_storage.clone());
+        let plaintext = rotated
+            .decrypt(&account, &nonce, &ciphertext)
+            .await
+            .unwrap();
+        assert_eq!(plaintext, b"secret".to_vec());
+
+        let stored: WrappedDataKey = key_value_storage
+            .with_entity("test", "get", "data_key")
+            .get(KeyValueStorageNamespace::DataKey, &account.to_string())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.master_key_id, "key2");
+    }
+
+    #[test]
+    fn unknown_current_master_key_is_rejected() {
+        assert!(LocalKeyManagementService::new(&config("key3")).is_err());
+    }
+}
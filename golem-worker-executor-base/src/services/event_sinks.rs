@@ -0,0 +1,1014 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bincode::{Decode, Encode};
+use futures::future::join_all;
+use golem_wasm_rpc::json::TypeAnnotatedValueJsonExtensions;
+use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
+use rdkafka::config::ClientConfig;
+use rdkafka::message::{Header, OwnedHeaders};
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::util::Timeout;
+use serde_json::{json, Value};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn, Instrument};
+
+use golem_common::config::RetryConfig;
+use golem_common::model::oplog::{OplogEntry, OplogIndex, OplogPayload};
+use golem_common::model::regions::DeletedRegions;
+use golem_common::model::{
+    AccountId, IdempotencyKey, OwnedWorkerId, ScanCursor, Timestamp, WorkerId,
+};
+use golem_common::retries::RetryState;
+use golem_common::serialization::try_deserialize;
+
+use crate::metrics::event_sinks::record_event_sink_event;
+use crate::services::events::Event;
+use crate::services::golem_config::{
+    EventSinkConfig, EventSinkTargetConfig, EventSinksConfig, EventSinksEnabledConfig,
+};
+use crate::services::oplog::OplogService;
+use crate::services::worker::WorkerService;
+use crate::services::{HasConfig, HasEvents, HasOplogService, HasShardService, HasWorkerService};
+use crate::storage::keyvalue::{
+    KeyValueStorage, KeyValueStorageLabelledApi, KeyValueStorageNamespace,
+};
+use crate::worker::calculate_last_known_status;
+
+/// The CloudEvents `type` of the events of completed invocations
+pub const INVOCATION_COMPLETED_EVENT_TYPE: &str = "cloud.golem.invocation.completed";
+
+const CLOUD_EVENTS_CONTENT_TYPE: &str = "application/cloudevents+json";
+
+/// Number of workers scanned at once when looking for the workers of a component
+const WORKER_SCAN_PAGE_SIZE: u64 = 100;
+
+/// Number of oplog entries read at once when looking for completed invocations
+const OPLOG_PAGE_SIZE: u64 = 100;
+
+/// Periodically pushes the results of the completed invocations of the workers owned by the
+/// executor to the configured event sinks.
+///
+/// The results are taken from the `ExportedFunctionCompleted` entries of the oplogs, skipping
+/// the deleted regions of the oplogs. Results which were already delivered before their region
+/// got deleted, for example by a jump, are not retracted. Each sink stores a checkpoint per
+/// worker, which only moves past an invocation after its event was delivered, so every result
+/// is delivered at least once, in the order of the invocations of the worker. Consumers can
+/// deduplicate the events by their `source` and `id`.
+///
+/// Only the workers which completed invocations since they were last pushed are processed,
+/// which are recorded in a pending set per sink when the invocations complete. The workers of
+/// the selected components are all added to the sets once when the executor starts, and when
+/// completed invocations were missed.
+///
+/// Returns `None` if the event sinks are disabled.
+pub fn start<T>(
+    deps: T,
+    key_value_storage: Arc<dyn KeyValueStorage + Send + Sync>,
+) -> Option<JoinHandle<()>>
+where
+    T: HasConfig
+        + HasEvents
+        + HasOplogService
+        + HasShardService
+        + HasWorkerService
+        + Send
+        + Sync
+        + 'static,
+{
+    let config = match &deps.config().event_sinks {
+        EventSinksConfig::Enabled(config) if !config.sinks.is_empty() => config.clone(),
+        _ => return None,
+    };
+
+    let sinks: Vec<(EventSinkConfig, Arc<dyn EventSink + Send + Sync>)> = config
+        .sinks
+        .iter()
+        .filter_map(|sink| match configured(&sink.target, config.timeout) {
+            Ok(target) => Some((sink.clone(), target)),
+            Err(err) => {
+                error!(sink = sink.name, "Failed to create the event sink: {err}");
+                None
+            }
+        })
+        .collect();
+
+    info!(
+        "Pushing invocation results to {} event sinks every {:?}",
+        sinks.len(),
+        config.interval
+    );
+    let store = CheckpointStore::new(key_value_storage);
+    // Subscribing before the initial scan, so no completed invocation is missed
+    let mut subscription = deps.events().subscribe();
+    Some(tokio::spawn(
+        async move {
+            let scan_needed = AtomicBool::new(true);
+
+            let record_completions = async {
+                loop {
+                    match subscription.wait_for(completed_worker).await {
+                        Ok(worker_id) => {
+                            for (sink, _) in &sinks {
+                                if !sink.component_ids.contains(&worker_id.component_id) {
+                                    continue;
+                                }
+                                let owned_worker_id =
+                                    OwnedWorkerId::new(&sink.account_id, &worker_id);
+                                if let Err(err) =
+                                    store.add_pending(&sink.name, &owned_worker_id).await
+                                {
+                                    warn!(
+                                        sink = sink.name,
+                                        worker_id = worker_id.to_string(),
+                                        "Failed to record the completed invocation: {err}"
+                                    );
+                                    scan_needed.store(true, Ordering::Release);
+                                }
+                            }
+                        }
+                        Err(RecvError::Lagged(missed)) => {
+                            warn!("Missed {missed} worker events, scanning the selected workers");
+                            scan_needed.store(true, Ordering::Release);
+                        }
+                        Err(RecvError::Closed) => break,
+                    }
+                }
+            };
+
+            let push = async {
+                let mut interval = tokio::time::interval(config.interval);
+                loop {
+                    interval.tick().await;
+                    if !deps.shard_service().is_ready() {
+                        continue;
+                    }
+                    if scan_needed.swap(false, Ordering::AcqRel) {
+                        for (sink, _) in &sinks {
+                            if let Err(err) = add_owned_workers(&deps, &store, sink).await {
+                                warn!(sink = sink.name, "{err}");
+                                scan_needed.store(true, Ordering::Release);
+                            }
+                        }
+                    }
+                    join_all(sinks.iter().map(|(sink, target)| {
+                        push_results(&deps, &store, &config, sink, target.as_ref())
+                    }))
+                    .await;
+                }
+            };
+
+            tokio::join!(record_completions, push);
+        }
+        .in_current_span(),
+    ))
+}
+
+fn completed_worker(event: &Event) -> Option<WorkerId> {
+    match event {
+        Event::InvocationCompleted {
+            worker_id,
+            result: Ok(_),
+            ..
+        } => Some(worker_id.clone()),
+        _ => None,
+    }
+}
+
+fn configured(
+    target: &EventSinkTargetConfig,
+    timeout: Duration,
+) -> Result<Arc<dyn EventSink + Send + Sync>, String> {
+    match target {
+        EventSinkTargetConfig::Http { url, headers } => Ok(Arc::new(HttpEventSink {
+            client: reqwest::Client::builder()
+                .timeout(timeout)
+                .build()
+                .map_err(|err| format!("Failed to create the HTTP client: {err}"))?,
+            url: url.clone(),
+            headers: headers.clone(),
+        })),
+        EventSinkTargetConfig::Kafka {
+            bootstrap_servers,
+            topic,
+            properties,
+        } => {
+            let mut client_config = ClientConfig::new();
+            client_config.set("bootstrap.servers", bootstrap_servers);
+            client_config.set("message.timeout.ms", timeout.as_millis().to_string());
+            for (key, value) in properties {
+                client_config.set(key, value);
+            }
+            let producer = client_config
+                .create()
+                .map_err(|err| format!("Failed to create the producer: {err}"))?;
+            Ok(Arc::new(KafkaEventSink {
+                producer,
+                topic: topic.clone(),
+                timeout,
+            }))
+        }
+    }
+}
+
+/// Adds the selected components' workers owned by the executor to the pending workers of the
+/// sink
+async fn add_owned_workers<T>(
+    deps: &T,
+    store: &CheckpointStore,
+    sink: &EventSinkConfig,
+) -> Result<(), String>
+where
+    T: HasOplogService + HasShardService + Send + Sync,
+{
+    for component_id in &sink.component_ids {
+        let mut cursor = ScanCursor::default();
+        loop {
+            let (next_cursor, owned_worker_ids) = deps
+                .oplog_service()
+                .scan_for_component(
+                    &sink.account_id,
+                    component_id,
+                    cursor,
+                    WORKER_SCAN_PAGE_SIZE,
+                )
+                .await
+                .map_err(|err| {
+                    format!("Failed to scan the workers of component {component_id}: {err}")
+                })?;
+
+            for owned_worker_id in owned_worker_ids {
+                if deps
+                    .shard_service()
+                    .check_worker(&owned_worker_id.worker_id)
+                    .is_ok()
+                {
+                    store.add_pending(&sink.name, &owned_worker_id).await?;
+                }
+            }
+
+            if next_cursor.is_finished() {
+                break;
+            }
+            cursor = next_cursor;
+        }
+    }
+    Ok(())
+}
+
+/// Pushes the new results of the sink's pending workers owned by the executor
+async fn push_results<T>(
+    deps: &T,
+    store: &CheckpointStore,
+    config: &EventSinksEnabledConfig,
+    sink: &EventSinkConfig,
+    target: &(dyn EventSink + Send + Sync),
+) where
+    T: HasConfig + HasOplogService + HasShardService + HasWorkerService + Send + Sync,
+{
+    let worker_ids = match store.pending(&sink.name, &sink.account_id).await {
+        Ok(worker_ids) => worker_ids,
+        Err(err) => {
+            warn!(sink = sink.name, "Failed to get the pending workers: {err}");
+            return;
+        }
+    };
+
+    for worker_id in worker_ids {
+        // The results are pushed by the executor owning the worker, so a worker's checkpoint
+        // is only moved by a single executor at a time
+        if deps.shard_service().check_worker(&worker_id).is_err() {
+            continue;
+        }
+        let owned_worker_id = OwnedWorkerId::new(&sink.account_id, &worker_id);
+        let result = match push_worker_results(deps, store, config, sink, target, &owned_worker_id)
+            .await
+        {
+            Ok(processed) => complete_pending(deps, store, sink, &owned_worker_id, processed).await,
+            Err(err) => Err(err),
+        };
+        if let Err(err) = result {
+            warn!(
+                sink = sink.name,
+                worker_id = worker_id.to_string(),
+                "Failed to push the invocation results of the worker: {err}"
+            );
+        }
+    }
+}
+
+/// Removes a worker from the pending ones after its oplog was processed up to `processed`. If
+/// an invocation completed meanwhile, the worker is added back, as its completion may have
+/// been recorded before the removal.
+async fn complete_pending<T>(
+    deps: &T,
+    store: &CheckpointStore,
+    sink: &EventSinkConfig,
+    owned_worker_id: &OwnedWorkerId,
+    processed: OplogIndex,
+) -> Result<(), String>
+where
+    T: HasOplogService + Send + Sync,
+{
+    store.remove_pending(&sink.name, owned_worker_id).await?;
+    if deps.oplog_service().get_last_index(owned_worker_id).await > processed {
+        store.add_pending(&sink.name, owned_worker_id).await?;
+    }
+    Ok(())
+}
+
+/// Pushes the new results of a worker, returning the last oplog index processed
+async fn push_worker_results<T>(
+    deps: &T,
+    store: &CheckpointStore,
+    config: &EventSinksEnabledConfig,
+    sink: &EventSinkConfig,
+    target: &(dyn EventSink + Send + Sync),
+    owned_worker_id: &OwnedWorkerId,
+) -> Result<OplogIndex, String>
+where
+    T: HasConfig + HasOplogService + HasWorkerService + Send + Sync,
+{
+    let metadata = deps.worker_service().get(owned_worker_id).await;
+    let Some(created_at) = metadata.as_ref().map(|metadata| metadata.created_at) else {
+        // The worker got deleted, a worker created again with the same name records its
+        // completed invocations again
+        return Ok(deps.oplog_service().get_last_index(owned_worker_id).await);
+    };
+    let status = calculate_last_known_status(deps, owned_worker_id, &metadata)
+        .await
+        .map_err(|err| err.to_string())?;
+
+    push_oplog_results(
+        &deps.oplog_service(),
+        store,
+        &config.retries,
+        &sink.name,
+        target,
+        owned_worker_id,
+        created_at,
+        &status.deleted_regions,
+    )
+    .await
+}
+
+/// Pushes the results of the invocations completed after the sink's checkpoint of the worker.
+/// A worker deleted and created again with the same name starts a new oplog, which is detected
+/// by its creation time.
+#[allow(clippy::too_many_arguments)]
+async fn push_oplog_results(
+    oplog_service: &Arc<dyn OplogService + Send + Sync>,
+    checkpoints: &CheckpointStore,
+    retries: &RetryConfig,
+    sink_name: &str,
+    target: &(dyn EventSink + Send + Sync),
+    owned_worker_id: &OwnedWorkerId,
+    created_at: Timestamp,
+    deleted_regions: &DeletedRegions,
+) -> Result<OplogIndex, String> {
+    let last_index = oplog_service.get_last_index(owned_worker_id).await;
+    let mut checkpoint = checkpoints
+        .get(sink_name, owned_worker_id)
+        .await?
+        .filter(|checkpoint| checkpoint.created_at == created_at)
+        .map(|checkpoint| checkpoint.last_index)
+        .unwrap_or(OplogIndex::NONE);
+
+    let mut running: Option<(OplogIndex, String, IdempotencyKey)> = None;
+    let mut next = checkpoint.next();
+    while next <= last_index {
+        let count = OPLOG_PAGE_SIZE.min(u64::from(last_index) - u64::from(next) + 1);
+        let entries = oplog_service.read(owned_worker_id, next, count).await;
+        if entries.is_empty() {
+            break;
+        }
+
+        for (index, entry) in entries {
+            next = index.next();
+            if deleted_regions.is_in_deleted_region(index) {
+                continue;
+            }
+            match entry {
+                OplogEntry::ExportedFunctionInvoked {
+                    function_name,
+                    idempotency_key,
+                    ..
+                } => {
+                    running = Some((index, function_name, idempotency_key));
+                }
+                OplogEntry::ExportedFunctionCompleted {
+                    timestamp,
+                    response,
+                    consumed_fuel,
+                } => {
+                    let Some((_, function_name, idempotency_key)) = running.take() else {
+                        continue;
+                    };
+                    let invocation = CompletedInvocation {
+                        worker_id: owned_worker_id.worker_id.clone(),
+                        worker_created_at: created_at,
+                        oplog_index: index,
+                        timestamp,
+                        function_name,
+                        idempotency_key,
+                        result: invocation_result(oplog_service, owned_worker_id, &response)
+                            .await?,
+                        consumed_fuel,
+                    };
+                    if let Err(err) = deliver(target, retries, &invocation).await {
+                        record_event_sink_event(sink_name, "failed");
+                        return Err(format!(
+                            "Failed to deliver the result of invocation {}: {err}",
+                            invocation.idempotency_key
+                        ));
+                    }
+                    record_event_sink_event(sink_name, "delivered");
+
+                    checkpoint = index;
+                    checkpoints
+                        .set(sink_name, owned_worker_id, created_at, checkpoint)
+                        .await?;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // Moving the checkpoint past the entries without results too, but not past the start of an
+    // invocation which is still running
+    let processed = next.previous();
+    let last_checkpoint = match &running {
+        Some((invoked_index, _, _)) => invoked_index.previous(),
+        None => processed,
+    };
+    if last_checkpoint > checkpoint {
+        checkpoints
+            .set(sink_name, owned_worker_id, created_at, last_checkpoint)
+            .await?;
+    }
+    Ok(processed)
+}
+
+async fn invocation_result(
+    oplog_service: &Arc<dyn OplogService + Send + Sync>,
+    owned_worker_id: &OwnedWorkerId,
+    response: &OplogPayload,
+) -> Result<Value, String> {
+    let bytes = oplog_service
+        .download_payload(owned_worker_id, response)
+        .await?;
+    let result: Option<TypeAnnotatedValue> = try_deserialize(&bytes)?;
+    Ok(result
+        .map(|result| result.to_json_value())
+        .unwrap_or(Value::Null))
+}
+
+async fn deliver(
+    target: &(dyn EventSink + Send + Sync),
+    retries: &RetryConfig,
+    invocation: &CompletedInvocation,
+) -> Result<(), String> {
+    let mut retry_state = RetryState::new(retries);
+    loop {
+        retry_state.start_attempt();
+        match target.send(invocation).await {
+            Ok(()) => return Ok(()),
+            Err(err) => {
+                if !retry_state.failed_attempt().await {
+                    return Err(err);
+                }
+            }
+        }
+    }
+}
+
+/// An invocation completed by a worker, with its result in the JSON format of the invocation
+/// API
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompletedInvocation {
+    pub worker_id: WorkerId,
+    /// Distinguishes the worker from earlier ones deleted with the same name
+    pub worker_created_at: Timestamp,
+    /// The index of the `ExportedFunctionCompleted` entry
+    pub oplog_index: OplogIndex,
+    pub timestamp: Timestamp,
+    pub function_name: String,
+    pub idempotency_key: IdempotencyKey,
+    pub result: Value,
+    pub consumed_fuel: i64,
+}
+
+impl CompletedInvocation {
+    /// The event in the JSON format of CloudEvents. The creation time of the worker and the
+    /// oplog index identify the event within the worker, which is the source of the event.
+    pub fn cloud_event(&self) -> Value {
+        json!({
+            "specversion": "1.0",
+            "id": format!("{}-{}", self.worker_created_at.to_millis(), self.oplog_index),
+            "source": format!("urn:worker:{}", self.worker_id),
+            "type": INVOCATION_COMPLETED_EVENT_TYPE,
+            "subject": self.function_name,
+            "time": self.timestamp.to_string(),
+            "datacontenttype": "application/json",
+            "data": {
+                "componentId": self.worker_id.component_id.to_string(),
+                "workerName": self.worker_id.worker_name,
+                "functionName": self.function_name,
+                "idempotencyKey": self.idempotency_key.value,
+                "result": self.result,
+                "consumedFuel": self.consumed_fuel,
+            },
+        })
+    }
+}
+
+#[async_trait]
+pub trait EventSink {
+    async fn send(&self, invocation: &CompletedInvocation) -> Result<(), String>;
+}
+
+/// Posts each event in the structured content mode of the CloudEvents HTTP binding
+pub struct HttpEventSink {
+    client: reqwest::Client,
+    url: String,
+    headers: BTreeMap<String, String>,
+}
+
+#[async_trait]
+impl EventSink for HttpEventSink {
+    async fn send(&self, invocation: &CompletedInvocation) -> Result<(), String> {
+        let mut request = self
+            .client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, CLOUD_EVENTS_CONTENT_TYPE)
+            .body(invocation.cloud_event().to_string());
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        request
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+}
+
+/// Produces each event in the structured content mode of the CloudEvents Kafka binding, keyed
+/// by the worker id
+pub struct KafkaEventSink {
+    producer: FutureProducer,
+    topic: String,
+    timeout: Duration,
+}
+
+#[async_trait]
+impl EventSink for KafkaEventSink {
+    async fn send(&self, invocation: &CompletedInvocation) -> Result<(), String> {
+        let key = invocation.worker_id.to_string();
+        let payload = invocation.cloud_event().to_string();
+        let record = FutureRecord::to(&self.topic)
+            .key(key.as_str())
+            .payload(payload.as_str())
+            .headers(OwnedHeaders::new().insert(Header {
+                key: "content-type",
+                value: Some(CLOUD_EVENTS_CONTENT_TYPE),
+            }));
+        self.producer
+            .send(record, Timeout::After(self.timeout))
+            .await
+            .map(|_| ())
+            .map_err(|(err, _)| err.to_string())
+    }
+}
+
+/// The last oplog index of a worker processed by a sink
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+struct Checkpoint {
+    created_at: Timestamp,
+    last_index: OplogIndex,
+}
+
+struct CheckpointStore {
+    key_value_storage: Arc<dyn KeyValueStorage + Send + Sync>,
+}
+
+impl CheckpointStore {
+    fn new(key_value_storage: Arc<dyn KeyValueStorage + Send + Sync>) -> Self {
+        Self { key_value_storage }
+    }
+
+    fn namespace(owned_worker_id: &OwnedWorkerId) -> KeyValueStorageNamespace {
+        KeyValueStorageNamespace::EventSinks {
+            account_id: owned_worker_id.account_id.clone(),
+        }
+    }
+
+    fn pending_key(sink_name: &str) -> String {
+        format!("sink:{sink_name}:pending-workers")
+    }
+
+    fn key(sink_name: &str, owned_worker_id: &OwnedWorkerId) -> String {
+        format!(
+            "sink:{sink_name}:worker:{}",
+            owned_worker_id.worker_id.to_redis_key()
+        )
+    }
+
+    async fn get(
+        &self,
+        sink_name: &str,
+        owned_worker_id: &OwnedWorkerId,
+    ) -> Result<Option<Checkpoint>, String> {
+        self.key_value_storage
+            .with_entity("event_sinks", "get_checkpoint", "checkpoint")
+            .get(
+                Self::namespace(owned_worker_id),
+                &Self::key(sink_name, owned_worker_id),
+            )
+            .await
+    }
+
+    async fn set(
+        &self,
+        sink_name: &str,
+        owned_worker_id: &OwnedWorkerId,
+        created_at: Timestamp,
+        last_index: OplogIndex,
+    ) -> Result<(), String> {
+        self.key_value_storage
+            .with_entity("event_sinks", "set_checkpoint", "checkpoint")
+            .set(
+                Self::namespace(owned_worker_id),
+                &Self::key(sink_name, owned_worker_id),
+                &Checkpoint {
+                    created_at,
+                    last_index,
+                },
+            )
+            .await
+    }
+
+    async fn add_pending(
+        &self,
+        sink_name: &str,
+        owned_worker_id: &OwnedWorkerId,
+    ) -> Result<(), String> {
+        self.key_value_storage
+            .with_entity("event_sinks", "add_pending_worker", "worker_id")
+            .add_to_set(
+                Self::namespace(owned_worker_id),
+                &Self::pending_key(sink_name),
+                &owned_worker_id.worker_id,
+            )
+            .await
+    }
+
+    async fn remove_pending(
+        &self,
+        sink_name: &str,
+        owned_worker_id: &OwnedWorkerId,
+    ) -> Result<(), String> {
+        self.key_value_storage
+            .with_entity("event_sinks", "remove_pending_worker", "worker_id")
+            .remove_from_set(
+                Self::namespace(owned_worker_id),
+                &Self::pending_key(sink_name),
+                &owned_worker_id.worker_id,
+            )
+            .await
+    }
+
+    async fn pending(
+        &self,
+        sink_name: &str,
+        account_id: &AccountId,
+    ) -> Result<Vec<WorkerId>, String> {
+        self.key_value_storage
+            .with_entity("event_sinks", "get_pending_workers", "worker_id")
+            .members_of_set(
+                KeyValueStorageNamespace::EventSinks {
+                    account_id: account_id.clone(),
+                },
+                &Self::pending_key(sink_name),
+            )
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use async_trait::async_trait;
+    use golem_common::config::RetryConfig;
+    use golem_common::model::oplog::{OplogEntry, OplogIndex, OplogRegion};
+    use golem_common::model::regions::DeletedRegions;
+    use golem_common::model::{
+        AccountId, ComponentId, ComponentType, IdempotencyKey, OwnedWorkerId, Timestamp, WorkerId,
+        WorkerName,
+    };
+    use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
+    use serde_json::json;
+
+    use crate::services::event_sinks::{
+        push_oplog_results, CheckpointStore, CompletedInvocation, EventSink,
+        INVOCATION_COMPLETED_EVENT_TYPE,
+    };
+    use crate::services::oplog::{CommitLevel, Oplog, OplogOps, OplogService, PrimaryOplogService};
+    use crate::storage::blob::memory::InMemoryBlobStorage;
+    use crate::storage::indexed::memory::InMemoryIndexedStorage;
+    use crate::storage::keyvalue::memory::InMemoryKeyValueStorage;
+
+    #[test]
+    fn completed_invocation_is_encoded_as_cloud_event() {
+        let component_id = ComponentId::new_v4();
+        let invocation = CompletedInvocation {
+            worker_id: WorkerId {
+                component_id: component_id.clone(),
+                worker_name: WorkerName::from_existing("worker"),
+            },
+            worker_created_at: Timestamp::from(1_600_000_000_000u64),
+            oplog_index: OplogIndex::from_u64(12),
+            timestamp: Timestamp::from(1_700_000_000_000u64),
+            function_name: "golem:it/api.{add}".to_string(),
+            idempotency_key: IdempotencyKey::new("key".to_string()),
+            result: json!([42]),
+            consumed_fuel: 100,
+        };
+
+        let event = invocation.cloud_event();
+        assert_eq!(event["specversion"], "1.0");
+        assert_eq!(event["id"], "1600000000000-12");
+        assert_eq!(event["source"], format!("urn:worker:{component_id}/worker"));
+        assert_eq!(event["type"], INVOCATION_COMPLETED_EVENT_TYPE);
+        assert_eq!(event["subject"], "golem:it/api.{add}");
+        assert_eq!(event["time"], invocation.timestamp.to_string());
+        assert_eq!(
+            event["data"],
+            json!({
+                "componentId": component_id.to_string(),
+                "workerName": "worker",
+                "functionName": "golem:it/api.{add}",
+                "idempotencyKey": "key",
+                "result": [42],
+                "consumedFuel": 100,
+            })
+        );
+    }
+
+    /// Records the delivered events, failing the given number of deliveries first
+    #[derive(Default)]
+    struct RecordingSink {
+        failures: AtomicUsize,
+        delivered: Mutex<Vec<CompletedInvocation>>,
+    }
+
+    impl RecordingSink {
+        fn delivered(&self) -> Vec<(String, String)> {
+            self.delivered
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|invocation| {
+                    (
+                        invocation.idempotency_key.value.clone(),
+                        invocation.cloud_event()["id"].as_str().unwrap().to_string(),
+                    )
+                })
+                .collect()
+        }
+    }
+
+    #[async_trait]
+    impl EventSink for RecordingSink {
+        async fn send(&self, invocation: &CompletedInvocation) -> Result<(), String> {
+            let failures = self.failures.load(Ordering::SeqCst);
+            if failures > 0 {
+                self.failures.store(failures - 1, Ordering::SeqCst);
+                Err("unavailable".to_string())
+            } else {
+                self.delivered.lock().unwrap().push(invocation.clone());
+                Ok(())
+            }
+        }
+    }
+
+    struct TestWorker {
+        oplog_service: Arc<dyn OplogService + Send + Sync>,
+        checkpoints: CheckpointStore,
+        owned_worker_id: OwnedWorkerId,
+    }
+
+    impl TestWorker {
+        async fn new() -> Self {
+            let oplog_service: Arc<dyn OplogService + Send + Sync> = Arc::new(
+                PrimaryOplogService::new(
+                    Arc::new(InMemoryIndexedStorage::new()),
+                    Arc::new(InMemoryBlobStorage::new()),
+                    1,
+                    1024,
+                )
+                .await,
+            );
+            let account_id = AccountId {
+                value: "account".to_string(),
+            };
+            let worker_id = WorkerId {
+                component_id: ComponentId::new_v4(),
+                worker_name: WorkerName::from_existing("worker"),
+            };
+            Self {
+                oplog_service,
+                checkpoints: CheckpointStore::new(Arc::new(InMemoryKeyValueStorage::new())),
+                owned_worker_id: OwnedWorkerId::new(&account_id, &worker_id),
+            }
+        }
+
+        async fn create(&self) -> Arc<dyn Oplog + Send + Sync> {
+            self.oplog_service
+                .create(
+                    &self.owned_worker_id,
+                    OplogEntry::create(
+                        self.owned_worker_id.worker_id.clone(),
+                        0,
+                        vec![],
+                        vec![],
+                        self.owned_worker_id.account_id.clone(),
+                        None,
+                        0,
+                        0,
+                    ),
+                    ComponentType::Durable,
+                )
+                .await
+        }
+
+        async fn push(
+            &self,
+            sink: &RecordingSink,
+            created_at: u64,
+            deleted_regions: &DeletedRegions,
+        ) -> Result<OplogIndex, String> {
+            push_oplog_results(
+                &self.oplog_service,
+                &self.checkpoints,
+                &RetryConfig {
+                    max_attempts: 2,
+                    min_delay: Duration::from_millis(1),
+                    max_delay: Duration::from_millis(1),
+                    multiplier: 1.0,
+                    max_jitter_factor: None,
+                },
+                "sink",
+                sink,
+                &self.owned_worker_id,
+                Timestamp::from(created_at),
+                deleted_regions,
+            )
+            .await
+        }
+    }
+
+    async fn invoke(oplog: &Arc<dyn Oplog + Send + Sync>, key: &str) {
+        start(oplog, key).await;
+        complete(oplog).await;
+    }
+
+    async fn start(oplog: &Arc<dyn Oplog + Send + Sync>, key: &str) {
+        oplog
+            .add_exported_function_invoked(
+                "golem:it/api.{add}".to_string(),
+                &Vec::<u32>::new(),
+                IdempotencyKey::new(key.to_string()),
+            )
+            .await
+            .unwrap();
+        oplog.commit(CommitLevel::Always).await;
+    }
+
+    async fn complete(oplog: &Arc<dyn Oplog + Send + Sync>) {
+        oplog
+            .add_exported_function_completed(&TypeAnnotatedValue::U32(42), 0)
+            .await
+            .unwrap();
+        oplog.commit(CommitLevel::Always).await;
+    }
+
+    fn keys(delivered: &[(String, String)]) -> Vec<&str> {
+        delivered.iter().map(|(key, _)| key.as_str()).collect()
+    }
+
+    #[test]
+    async fn checkpoint_advances_past_the_delivered_results() {
+        let worker = TestWorker::new().await;
+        let oplog = worker.create().await;
+        let sink = RecordingSink::default();
+
+        invoke(&oplog, "k1").await;
+        start(&oplog, "k2").await;
+        let processed = worker.push(&sink, 1, &DeletedRegions::new()).await.unwrap();
+        assert_eq!(processed, oplog.current_oplog_index().await);
+        assert_eq!(keys(&sink.delivered()), vec!["k1"]);
+
+        // The running invocation is delivered once it completes, the earlier one only once
+        complete(&oplog).await;
+        invoke(&oplog, "k3").await;
+        worker.push(&sink, 1, &DeletedRegions::new()).await.unwrap();
+        worker.push(&sink, 1, &DeletedRegions::new()).await.unwrap();
+        let delivered = sink.delivered();
+        assert_eq!(keys(&delivered), vec!["k1", "k2", "k3"]);
+        assert_eq!(delivered[0].1, "1-3");
+    }
+
+    #[test]
+    async fn pushing_resumes_after_the_last_delivered_result() {
+        let worker = TestWorker::new().await;
+        let oplog = worker.create().await;
+        let sink = RecordingSink::default();
+
+        invoke(&oplog, "k1").await;
+        worker.push(&sink, 1, &DeletedRegions::new()).await.unwrap();
+        invoke(&oplog, "k2").await;
+        invoke(&oplog, "k3").await;
+
+        // Exhausting the retries of delivering the second result
+        sink.failures.store(2, Ordering::SeqCst);
+        assert!(worker.push(&sink, 1, &DeletedRegions::new()).await.is_err());
+        assert_eq!(keys(&sink.delivered()), vec!["k1"]);
+
+        worker.push(&sink, 1, &DeletedRegions::new()).await.unwrap();
+        assert_eq!(keys(&sink.delivered()), vec!["k1", "k2", "k3"]);
+    }
+
+    #[test]
+    async fn results_of_deleted_regions_are_not_delivered() {
+        let worker = TestWorker::new().await;
+        let oplog = worker.create().await;
+        let sink = RecordingSink::default();
+
+        invoke(&oplog, "k1").await;
+        let start_of_reverted = oplog.current_oplog_index().await.next();
+        invoke(&oplog, "k2").await;
+        let end_of_reverted = oplog.current_oplog_index().await;
+        oplog
+            .add(OplogEntry::jump(OplogRegion {
+                start: start_of_reverted,
+                end: end_of_reverted,
+            }))
+            .await;
+        invoke(&oplog, "k3").await;
+
+        let deleted_regions = DeletedRegions::from_regions(vec![OplogRegion {
+            start: start_of_reverted,
+            end: end_of_reverted,
+        }]);
+        worker.push(&sink, 1, &deleted_regions).await.unwrap();
+        assert_eq!(keys(&sink.delivered()), vec!["k1", "k3"]);
+    }
+
+    #[test]
+    async fn recreated_workers_are_delivered_from_the_start() {
+        let worker = TestWorker::new().await;
+        let oplog = worker.create().await;
+        let sink = RecordingSink::default();
+
+        invoke(&oplog, "k1").await;
+        invoke(&oplog, "k2").await;
+        worker.push(&sink, 1, &DeletedRegions::new()).await.unwrap();
+        drop(oplog);
+
+        worker.oplog_service.delete(&worker.owned_worker_id).await;
+        let oplog = worker.create().await;
+        invoke(&oplog, "k3").await;
+        worker.push(&sink, 2, &DeletedRegions::new()).await.unwrap();
+
+        // The recreated worker's result has the oplog index of the first one, but another id
+        let delivered = sink.delivered();
+        assert_eq!(keys(&delivered), vec!["k1", "k2", "k3"]);
+        assert_eq!(delivered[0].1, "1-3");
+        assert_eq!(delivered[2].1, "2-3");
+    }
+}
@@ -0,0 +1,419 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use golem_common::model::{AccountId, ComponentId, OwnedWorkerId};
+use tokio::sync::oneshot;
+
+use crate::metrics::scheduling::{record_invocation_queue_wait, record_invocations_waiting};
+use crate::services::golem_config::FairShareSchedulingConfig;
+
+/// Limits the number of invocations executed at the same time, globally and per account.
+///
+/// Invocations which cannot start right away are queued per account and per component, and
+/// whenever an invocation finishes the next one is taken from the account (and from the
+/// component of that account) that had its turn the longest time ago. This way an account or a
+/// component flooding the executor with invocations only delays its own invocations.
+#[derive(Clone)]
+pub struct FairShareScheduler {
+    state: Arc<Mutex<SchedulerState>>,
+}
+
+impl FairShareScheduler {
+    pub fn new(config: &FairShareSchedulingConfig) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(SchedulerState {
+                max_concurrent: config.max_concurrent_invocations.max(1),
+                max_concurrent_per_account: config.max_concurrent_invocations_per_account.max(1),
+                running: 0,
+                running_per_account: HashMap::new(),
+                turns: VecDeque::new(),
+                waiting: HashMap::new(),
+                waiting_count: 0,
+            })),
+        }
+    }
+
+    /// Waits until an invocation of the given worker can be executed. The invocation must be
+    /// executed while holding the returned permit.
+    pub async fn acquire(&self, owned_worker_id: &OwnedWorkerId) -> InvocationPermit {
+        let start = Instant::now();
+        let account_id = &owned_worker_id.account_id;
+
+        let receiver = {
+            let mut state = self.state.lock().unwrap();
+            if !state.waiting.contains_key(account_id) && state.can_start(account_id) {
+                state.start(account_id);
+                None
+            } else {
+                let (sender, receiver) = oneshot::channel();
+                state.enqueue(account_id, &owned_worker_id.worker_id.component_id, sender);
+                Self::dispatch(&self.state, &mut state);
+                Some(receiver)
+            }
+        };
+
+        let permit = match receiver {
+            None => InvocationPermit {
+                state: Some(self.state.clone()),
+                account_id: account_id.clone(),
+            },
+            Some(receiver) => receiver
+                .await
+                .expect("Fair share scheduler dropped a waiting invocation"),
+        };
+        record_invocation_queue_wait(start.elapsed());
+        permit
+    }
+
+    /// Starts the waiting invocations taking turns between the accounts, as long as there are
+    /// free slots
+    fn dispatch(this: &Arc<Mutex<SchedulerState>>, state: &mut SchedulerState) {
+        let mut skipped = 0;
+        while state.running < state.max_concurrent && skipped < state.turns.len() {
+            let account_id = state.turns.pop_front().unwrap();
+            if !state.can_start(&account_id) {
+                // The account reached its own limit, the others can still start invocations
+                state.turns.push_back(account_id);
+                skipped += 1;
+                continue;
+            }
+
+            let queue = state.waiting.get_mut(&account_id).unwrap();
+            let sender = queue.pop();
+            if queue.is_empty() {
+                state.waiting.remove(&account_id);
+            } else {
+                state.turns.push_back(account_id.clone());
+            }
+            let Some(sender) = sender else {
+                continue;
+            };
+            state.waiting_count -= 1;
+            skipped = 0;
+
+            state.start(&account_id);
+            let permit = InvocationPermit {
+                state: Some(this.clone()),
+                account_id: account_id.clone(),
+            };
+            if let Err(mut permit) = sender.send(permit) {
+                // The invocation is not waiting anymore
+                permit.state = None;
+                state.finish(&account_id);
+            }
+        }
+        record_invocations_waiting(state.waiting_count);
+    }
+}
+
+/// A slot for executing an invocation, released when dropped
+pub struct InvocationPermit {
+    state: Option<Arc<Mutex<SchedulerState>>>,
+    account_id: AccountId,
+}
+
+impl Drop for InvocationPermit {
+    fn drop(&mut self) {
+        if let Some(this) = self.state.take() {
+            let mut state = this.lock().unwrap();
+            state.finish(&self.account_id);
+            FairShareScheduler::dispatch(&this, &mut state);
+        }
+    }
+}
+
+/// The invocation permit of a single worker. The worker holds it while executing an invocation,
+/// and gives it up while the invocation waits for other workers, which may need a permit of
+/// their own to respond.
+pub struct InvocationPermitSlot {
+    scheduler: Option<FairShareScheduler>,
+    owned_worker_id: OwnedWorkerId,
+    permit: Mutex<Option<InvocationPermit>>,
+}
+
+impl InvocationPermitSlot {
+    /// `scheduler` is `None` if invocations are not scheduled, in which case the slot never
+    /// holds a permit
+    pub fn new(scheduler: Option<FairShareScheduler>, owned_worker_id: OwnedWorkerId) -> Self {
+        Self {
+            scheduler,
+            owned_worker_id,
+            permit: Mutex::new(None),
+        }
+    }
+
+    /// Waits for the turn of the worker's next invocation. The permit is released when the
+    /// returned guard is dropped, on every path the invocation finishes.
+    pub async fn acquire(&self) -> InvocationPermitGuard<'_> {
+        if let Some(scheduler) = &self.scheduler {
+            let permit = scheduler.acquire(&self.owned_worker_id).await;
+            *self.permit.lock().unwrap() = Some(permit);
+        }
+        InvocationPermitGuard { slot: self }
+    }
+
+    /// Releases the permit while awaiting `f`, and waits for a new turn before returning if the
+    /// permit was held. If `f` is cancelled, the invocation continues without a permit until the
+    /// guard is dropped.
+    pub async fn release_while<F: Future>(&self, f: F) -> F::Output {
+        let released = self.permit.lock().unwrap().take();
+        match (released, &self.scheduler) {
+            (Some(released), Some(scheduler)) => {
+                drop(released);
+                let result = f.await;
+                let permit = scheduler.acquire(&self.owned_worker_id).await;
+                *self.permit.lock().unwrap() = Some(permit);
+                result
+            }
+            _ => f.await,
+        }
+    }
+}
+
+/// Releases the permit of an `InvocationPermitSlot` when dropped
+pub struct InvocationPermitGuard<'a> {
+    slot: &'a InvocationPermitSlot,
+}
+
+impl Drop for InvocationPermitGuard<'_> {
+    fn drop(&mut self) {
+        let permit = self.slot.permit.lock().unwrap().take();
+        drop(permit);
+    }
+}
+
+struct SchedulerState {
+    max_concurrent: usize,
+    max_concurrent_per_account: usize,
+    running: usize,
+    running_per_account: HashMap<AccountId, usize>,
+    /// Accounts with waiting invocations, in the order they get their next turn
+    turns: VecDeque<AccountId>,
+    waiting: HashMap<AccountId, AccountQueue>,
+    waiting_count: usize,
+}
+
+impl SchedulerState {
+    fn can_start(&self, account_id: &AccountId) -> bool {
+        self.running < self.max_concurrent
+            && self
+                .running_per_account
+                .get(account_id)
+                .map_or(true, |running| *running < self.max_concurrent_per_account)
+    }
+
+    fn start(&mut self, account_id: &AccountId) {
+        self.running += 1;
+        *self
+            .running_per_account
+            .entry(account_id.clone())
+            .or_default() += 1;
+    }
+
+    fn finish(&mut self, account_id: &AccountId) {
+        self.running -= 1;
+        if let Some(running) = self.running_per_account.get_mut(account_id) {
+            *running -= 1;
+            if *running == 0 {
+                self.running_per_account.remove(account_id);
+            }
+        }
+    }
+
+    fn enqueue(
+        &mut self,
+        account_id: &AccountId,
+        component_id: &ComponentId,
+        sender: oneshot::Sender<InvocationPermit>,
+    ) {
+        if !self.waiting.contains_key(account_id) {
+            self.turns.push_back(account_id.clone());
+        }
+        self.waiting
+            .entry(account_id.clone())
+            .or_default()
+            .push(component_id, sender);
+        self.waiting_count += 1;
+    }
+}
+
+/// The waiting invocations of an account, taking turns between its components
+#[derive(Default)]
+struct AccountQueue {
+    turns: VecDeque<ComponentId>,
+    waiting: HashMap<ComponentId, VecDeque<oneshot::Sender<InvocationPermit>>>,
+}
+
+impl AccountQueue {
+    fn push(&mut self, component_id: &ComponentId, sender: oneshot::Sender<InvocationPermit>) {
+        let waiting = self.waiting.entry(component_id.clone()).or_default();
+        if waiting.is_empty() {
+            self.turns.push_back(component_id.clone());
+        }
+        waiting.push_back(sender);
+    }
+
+    fn pop(&mut self) -> Option<oneshot::Sender<InvocationPermit>> {
+        let component_id = self.turns.pop_front()?;
+        let waiting = self.waiting.get_mut(&component_id)?;
+        let sender = waiting.pop_front();
+        if waiting.is_empty() {
+            self.waiting.remove(&component_id);
+        } else {
+            self.turns.push_back(component_id);
+        }
+        sender
+    }
+
+    fn is_empty(&self) -> bool {
+        self.turns.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use golem_common::model::{AccountId, ComponentId, OwnedWorkerId, WorkerId, WorkerName};
+
+    use crate::services::golem_config::FairShareSchedulingConfig;
+    use crate::services::invocation_scheduling::{FairShareScheduler, InvocationPermitSlot};
+
+    fn worker(account: &str, component_id: &ComponentId) -> OwnedWorkerId {
+        OwnedWorkerId::new(
+            &AccountId {
+                value: account.to_string(),
+            },
+            &WorkerId {
+                component_id: component_id.clone(),
+                worker_name: WorkerName::from_existing("worker"),
+            },
+        )
+    }
+
+    #[test]
+    async fn waiting_invocations_take_turns_between_accounts() {
+        let scheduler = FairShareScheduler::new(&FairShareSchedulingConfig {
+            max_concurrent_invocations: 1,
+            max_concurrent_invocations_per_account: 1,
+        });
+        let component_id = ComponentId::new_v4();
+        let flooding = worker("flooding", &component_id);
+        let other = worker("other", &component_id);
+
+        let first = scheduler.acquire(&flooding).await;
+        let started = Arc::new(Mutex::new(Vec::new()));
+
+        let mut handles = Vec::new();
+        for (name, owned_worker_id) in [
+            ("flooding-1", &flooding),
+            ("flooding-2", &flooding),
+            ("flooding-3", &flooding),
+            ("other", &other),
+        ] {
+            let scheduler = scheduler.clone();
+            let started = started.clone();
+            let owned_worker_id = owned_worker_id.clone();
+            handles.push(tokio::spawn(async move {
+                let _permit = scheduler.acquire(&owned_worker_id).await;
+                started.lock().unwrap().push(name);
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }));
+            // Keeping the arrival order
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        drop(first);
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        assert_eq!(
+            *started.lock().unwrap(),
+            vec!["flooding-1", "other", "flooding-2", "flooding-3"]
+        );
+    }
+
+    #[test]
+    async fn accounts_are_limited_to_their_own_concurrency() {
+        let scheduler = FairShareScheduler::new(&FairShareSchedulingConfig {
+            max_concurrent_invocations: 3,
+            max_concurrent_invocations_per_account: 2,
+        });
+        let component_id = ComponentId::new_v4();
+        let flooding = worker("flooding", &component_id);
+        let other = worker("other", &component_id);
+
+        let _first = scheduler.acquire(&flooding).await;
+        let _second = scheduler.acquire(&flooding).await;
+
+        let third = tokio::time::timeout(Duration::from_millis(50), scheduler.acquire(&flooding));
+        assert!(third.await.is_err());
+
+        let other_permit =
+            tokio::time::timeout(Duration::from_millis(50), scheduler.acquire(&other)).await;
+        assert!(other_permit.is_ok());
+    }
+
+    #[test]
+    async fn waiting_invocations_do_not_hold_their_permits() {
+        let scheduler = FairShareScheduler::new(&FairShareSchedulingConfig {
+            max_concurrent_invocations: 2,
+            max_concurrent_invocations_per_account: 2,
+        });
+        let component_id = ComponentId::new_v4();
+        let callers: Vec<_> = (0..4)
+            .map(|i| {
+                InvocationPermitSlot::new(
+                    Some(scheduler.clone()),
+                    worker(&format!("caller-{i}"), &component_id),
+                )
+            })
+            .collect();
+        let callee = Arc::new(InvocationPermitSlot::new(
+            Some(scheduler.clone()),
+            worker("callee", &component_id),
+        ));
+
+        // More callers than the limit, all of them waiting for the same callee, which needs a
+        // permit of its own to respond
+        let calls = callers.iter().map(|caller| {
+            let callee = callee.clone();
+            async move {
+                let _permit = caller.acquire().await;
+                caller
+                    .release_while(async move {
+                        let _permit = callee.acquire().await;
+                    })
+                    .await;
+            }
+        });
+
+        let result =
+            tokio::time::timeout(Duration::from_secs(5), futures::future::join_all(calls)).await;
+        assert!(result.is_ok());
+
+        // Every permit got released
+        let _first = scheduler.acquire(&worker("other", &component_id)).await;
+        let _second = scheduler.acquire(&worker("other", &component_id)).await;
+    }
+}
@@ -16,9 +16,11 @@ use std::sync::Arc;
 
 use anyhow::anyhow;
 use async_trait::async_trait;
+use bincode::{Decode, Encode};
 use bytes::Bytes;
 
-use golem_common::model::AccountId;
+use golem_common::model::{AccountId, OwnedWorkerId};
+use golem_common::serialization::{deserialize, serialize};
 
 use crate::storage::keyvalue::{
     KeyValueStorage, KeyValueStorageLabelledApi, KeyValueStorageNamespace,
@@ -78,6 +80,88 @@ pub trait KeyValueService {
         bucket: String,
         key_values: Vec<(String, Vec<u8>)>,
     ) -> anyhow::Result<()>;
+
+    /// Gets a value from the key-value store private to the worker
+    async fn get_worker_value(
+        &self,
+        owned_worker_id: OwnedWorkerId,
+        key: String,
+    ) -> anyhow::Result<Option<Vec<u8>>>;
+
+    async fn set_worker_value(
+        &self,
+        owned_worker_id: OwnedWorkerId,
+        key: String,
+        value: Vec<u8>,
+    ) -> anyhow::Result<()>;
+
+    async fn delete_worker_value(
+        &self,
+        owned_worker_id: OwnedWorkerId,
+        key: String,
+    ) -> anyhow::Result<()>;
+
+    /// Lists the keys of the worker's store starting with the prefix, in lexicographic order
+    async fn get_worker_keys(
+        &self,
+        owned_worker_id: OwnedWorkerId,
+        prefix: String,
+    ) -> anyhow::Result<Vec<String>>;
+
+    /// Deletes the whole key-value store of the worker
+    async fn delete_worker_values(&self, owned_worker_id: OwnedWorkerId) -> anyhow::Result<()>;
+
+    /// Gets an entry of the key-value store shared by all the workers of the account
+    async fn get_shared(
+        &self,
+        account_id: AccountId,
+        key: String,
+    ) -> anyhow::Result<Option<SharedKeyValueEntry>>;
+
+    /// Sets the value of a shared entry if its current version is `expected_version`, where
+    /// version 0 expects the entry to not exist. Returns the new version of the entry, or
+    /// `None` if the expected version did not match.
+    async fn compare_and_swap_shared(
+        &self,
+        account_id: AccountId,
+        key: String,
+        expected_version: u64,
+        value: Vec<u8>,
+    ) -> anyhow::Result<Option<u64>>;
+
+    /// Sets the value of a shared entry regardless of its current version, returning the new version
+    async fn set_shared(
+        &self,
+        account_id: AccountId,
+        key: String,
+        value: Vec<u8>,
+    ) -> anyhow::Result<u64>;
+
+    /// Deletes a shared entry if its current version is `expected_version`. Returns whether
+    /// the entry was deleted.
+    async fn compare_and_delete_shared(
+        &self,
+        account_id: AccountId,
+        key: String,
+        expected_version: u64,
+    ) -> anyhow::Result<bool>;
+
+    async fn delete_shared(&self, account_id: AccountId, key: String) -> anyhow::Result<()>;
+
+    /// Lists the keys of the shared store starting with the prefix, in lexicographic order
+    async fn get_shared_keys(
+        &self,
+        account_id: AccountId,
+        prefix: String,
+    ) -> anyhow::Result<Vec<String>>;
+}
+
+/// An entry of the shared key-value store. The version starts at 1 and is incremented by every
+/// update, deleting the entry resets it.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct SharedKeyValueEntry {
+    pub value: Vec<u8>,
+    pub version: u64,
 }
 
 #[derive(Clone, Debug)]
@@ -89,6 +173,62 @@ impl DefaultKeyValueService {
     pub fn new(key_value_storage: Arc<dyn KeyValueStorage + Send + Sync>) -> Self {
         Self { key_value_storage }
     }
+
+    fn worker_namespace(owned_worker_id: OwnedWorkerId) -> KeyValueStorageNamespace {
+        KeyValueStorageNamespace::WorkerState {
+            account_id: owned_worker_id.account_id,
+            worker_id: owned_worker_id.worker_id,
+        }
+    }
+
+    fn shared_namespace(account_id: AccountId) -> KeyValueStorageNamespace {
+        KeyValueStorageNamespace::Shared { account_id }
+    }
+
+    /// Reads the raw stored entry together with its decoded form, so it can be used as the
+    /// expected value of a compare-and-swap
+    async fn get_shared_raw(
+        &self,
+        account_id: AccountId,
+        key: &str,
+    ) -> anyhow::Result<Option<(Bytes, SharedKeyValueEntry)>> {
+        let raw = self
+            .key_value_storage
+            .with_entity("key_value", "get_shared", "shared_entry")
+            .get_raw(Self::shared_namespace(account_id), key)
+            .await
+            .map_err(|err| anyhow!(err))?;
+        match raw {
+            Some(raw) => {
+                let entry: SharedKeyValueEntry = deserialize(&raw).map_err(|err| anyhow!(err))?;
+                Ok(Some((raw, entry)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn swap_shared(
+        &self,
+        account_id: AccountId,
+        key: &str,
+        current: Option<&[u8]>,
+        value: Option<SharedKeyValueEntry>,
+    ) -> anyhow::Result<bool> {
+        let serialized = value
+            .map(|entry| serialize(&entry))
+            .transpose()
+            .map_err(|err| anyhow!(err))?;
+        self.key_value_storage
+            .with_entity("key_value", "compare_and_swap_shared", "shared_entry")
+            .compare_and_swap_raw(
+                Self::shared_namespace(account_id),
+                key,
+                current,
+                serialized.as_deref(),
+            )
+            .await
+            .map_err(|err| anyhow!(err))
+    }
 }
 
 #[async_trait]
@@ -234,4 +374,326 @@ impl KeyValueService for DefaultKeyValueService {
             .map_err(|err| anyhow!(err))?;
         Ok(())
     }
+
+    async fn get_worker_value(
+        &self,
+        owned_worker_id: OwnedWorkerId,
+        key: String,
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        let value: Option<Vec<u8>> = self
+            .key_value_storage
+            .with_entity("key_value", "get_worker_value", "worker_state")
+            .get_raw(Self::worker_namespace(owned_worker_id), &key)
+            .await
+            .map_err(|err| anyhow!(err))?
+            .map(|bytes| bytes.to_vec());
+        Ok(value)
+    }
+
+    async fn set_worker_value(
+        &self,
+        owned_worker_id: OwnedWorkerId,
+        key: String,
+        value: Vec<u8>,
+    ) -> anyhow::Result<()> {
+        self.key_value_storage
+            .with_entity("key_value", "set_worker_value", "worker_state")
+            .set_raw(Self::worker_namespace(owned_worker_id), &key, &value)
+            .await
+            .map_err(|err| anyhow!(err))?;
+        Ok(())
+    }
+
+    async fn delete_worker_value(
+        &self,
+        owned_worker_id: OwnedWorkerId,
+        key: String,
+    ) -> anyhow::Result<()> {
+        self.key_value_storage
+            .with("key_value", "delete_worker_value")
+            .del(Self::worker_namespace(owned_worker_id), &key)
+            .await
+            .map_err(|err| anyhow!(err))?;
+        Ok(())
+    }
+
+    async fn get_worker_keys(
+        &self,
+        owned_worker_id: OwnedWorkerId,
+        prefix: String,
+    ) -> anyhow::Result<Vec<String>> {
+        let mut keys: Vec<String> = self
+            .key_value_storage
+            .with("key_value", "get_worker_keys")
+            .keys(Self::worker_namespace(owned_worker_id))
+            .await
+            .map_err(|err| anyhow!(err))?
+            .into_iter()
+            .filter(|key| key.starts_with(&prefix))
+            .collect();
+        keys.sort();
+        Ok(keys)
+    }
+
+    async fn delete_worker_values(&self, owned_worker_id: OwnedWorkerId) -> anyhow::Result<()> {
+        let namespace = Self::worker_namespace(owned_worker_id);
+        let keys = self
+            .key_value_storage
+            .with("key_value", "delete_worker_values")
+            .keys(namespace.clone())
+            .await
+            .map_err(|err| anyhow!(err))?;
+        if !keys.is_empty() {
+            self.key_value_storage
+                .with("key_value", "delete_worker_values")
+                .del_many(namespace, keys)
+                .await
+                .map_err(|err| anyhow!(err))?;
+        }
+        Ok(())
+    }
+
+    async fn get_shared(
+        &self,
+        account_id: AccountId,
+        key: String,
+    ) -> anyhow::Result<Option<SharedKeyValueEntry>> {
+        Ok(self
+            .get_shared_raw(account_id, &key)
+            .await?
+            .map(|(_, entry)| entry))
+    }
+
+    async fn compare_and_swap_shared(
+        &self,
+        account_id: AccountId,
+        key: String,
+        expected_version: u64,
+        value: Vec<u8>,
+    ) -> anyhow::Result<Option<u64>> {
+        let current = self.get_shared_raw(account_id.clone(), &key).await?;
+        let current_version = current.as_ref().map_or(0, |(_, entry)| entry.version);
+        if current_version != expected_version {
+            return Ok(None);
+        }
+
+        let version = current_version + 1;
+        // The storage level swap only succeeds if nobody changed the entry since it was read,
+        // otherwise its version no longer matches the expected one either
+        let swapped = self
+            .swap_shared(
+                account_id,
+                &key,
+                current.as_ref().map(|(raw, _)| raw.as_ref()),
+                Some(SharedKeyValueEntry { value, version }),
+            )
+            .await?;
+        Ok(swapped.then_some(version))
+    }
+
+    async fn set_shared(
+        &self,
+        account_id: AccountId,
+        key: String,
+        value: Vec<u8>,
+    ) -> anyhow::Result<u64> {
+        loop {
+            let current_version = self
+                .get_shared_raw(account_id.clone(), &key)
+                .await?
+                .map_or(0, |(_, entry)| entry.version);
+            if let Some(version) = self
+                .compare_and_swap_shared(
+                    account_id.clone(),
+                    key.clone(),
+                    current_version,
+                    value.clone(),
+                )
+                .await?
+            {
+                break Ok(version);
+            }
+        }
+    }
+
+    async fn compare_and_delete_shared(
+        &self,
+        account_id: AccountId,
+        key: String,
+        expected_version: u64,
+    ) -> anyhow::Result<bool> {
+        match self.get_shared_raw(account_id.clone(), &key).await? {
+            Some((raw, entry)) if entry.version == expected_version => {
+                self.swap_shared(account_id, &key, Some(raw.as_ref()), None)
+                    .await
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn delete_shared(&self, account_id: AccountId, key: String) -> anyhow::Result<()> {
+        self.key_value_storage
+            .with("key_value", "delete_shared")
+            .del(Self::shared_namespace(account_id), &key)
+            .await
+            .map_err(|err| anyhow!(err))?;
+        Ok(())
+    }
+
+    async fn get_shared_keys(
+        &self,
+        account_id: AccountId,
+        prefix: String,
+    ) -> anyhow::Result<Vec<String>> {
+        let mut keys: Vec<String> = self
+            .key_value_storage
+            .with("key_value", "get_shared_keys")
+            .keys(Self::shared_namespace(account_id))
+            .await
+            .map_err(|err| anyhow!(err))?
+            .into_iter()
+            .filter(|key| key.starts_with(&prefix))
+            .collect();
+        keys.sort();
+        Ok(keys)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::sync::Arc;
+
+    use golem_common::model::{AccountId, ComponentId, OwnedWorkerId, WorkerId, WorkerName};
+
+    use crate::services::key_value::{
+        DefaultKeyValueService, KeyValueService, SharedKeyValueEntry,
+    };
+    use crate::storage::keyvalue::memory::InMemoryKeyValueStorage;
+
+    fn owned_worker_id(worker_name: &str) -> OwnedWorkerId {
+        OwnedWorkerId {
+            account_id: AccountId {
+                value: "account1".to_string(),
+            },
+            worker_id: WorkerId {
+                component_id: ComponentId::new_v4(),
+                worker_name: WorkerName::from_existing(worker_name),
+            },
+        }
+    }
+
+    #[test]
+    async fn worker_values_are_private_to_the_worker() {
+        let service = DefaultKeyValueService::new(Arc::new(InMemoryKeyValueStorage::new()));
+        let worker1 = owned_worker_id("worker1");
+        let worker2 = owned_worker_id("worker2");
+
+        for key in ["config/b", "config/a", "state"] {
+            service
+                .set_worker_value(worker1.clone(), key.to_string(), key.as_bytes().to_vec())
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(
+            service
+                .get_worker_value(worker1.clone(), "state".to_string())
+                .await
+                .unwrap(),
+            Some(b"state".to_vec())
+        );
+        assert_eq!(
+            service
+                .get_worker_value(worker2.clone(), "state".to_string())
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            service
+                .get_worker_keys(worker1.clone(), "config/".to_string())
+                .await
+                .unwrap(),
+            vec!["config/a".to_string(), "config/b".to_string()]
+        );
+
+        service
+            .delete_worker_value(worker1.clone(), "config/a".to_string())
+            .await
+            .unwrap();
+        assert_eq!(
+            service
+                .get_worker_keys(worker1.clone(), "".to_string())
+                .await
+                .unwrap(),
+            vec!["config/b".to_string(), "state".to_string()]
+        );
+
+        service.delete_worker_values(worker1.clone()).await.unwrap();
+        assert!(service
+            .get_worker_keys(worker1, "".to_string())
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    async fn shared_values_are_updated_with_compare_and_swap() {
+        let service = DefaultKeyValueService::new(Arc::new(InMemoryKeyValueStorage::new()));
+        let account_id = AccountId {
+            value: "account1".to_string(),
+        };
+        let key = "counter".to_string();
+
+        let swap = |expected_version: u64, value: &[u8]| {
+            service.compare_and_swap_shared(
+                account_id.clone(),
+                key.clone(),
+                expected_version,
+                value.to_vec(),
+            )
+        };
+
+        assert_eq!(swap(1, b"a").await.unwrap(), None);
+        assert_eq!(swap(0, b"a").await.unwrap(), Some(1));
+        assert_eq!(swap(0, b"b").await.unwrap(), None);
+        assert_eq!(swap(1, b"b").await.unwrap(), Some(2));
+        assert_eq!(
+            service
+                .get_shared(account_id.clone(), key.clone())
+                .await
+                .unwrap(),
+            Some(SharedKeyValueEntry {
+                value: b"b".to_vec(),
+                version: 2,
+            })
+        );
+
+        assert_eq!(
+            service
+                .set_shared(account_id.clone(), key.clone(), b"c".to_vec())
+                .await
+                .unwrap(),
+            3
+        );
+        assert_eq!(
+            service
+                .get_shared_keys(account_id.clone(), "count".to_string())
+                .await
+                .unwrap(),
+            vec![key.clone()]
+        );
+
+        assert!(!service
+            .compare_and_delete_shared(account_id.clone(), key.clone(), 2)
+            .await
+            .unwrap());
+        assert!(service
+            .compare_and_delete_shared(account_id.clone(), key.clone(), 3)
+            .await
+            .unwrap());
+        assert_eq!(service.get_shared(account_id, key).await.unwrap(), None);
+    }
 }
@@ -0,0 +1,526 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use golem_common::config::RetryConfig;
+use golem_common::model::{LogLevel, OwnedWorkerId, Timestamp, WorkerEvent};
+use golem_common::retries::with_retries;
+use serde_json::{json, Value};
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::mpsc::{channel, Receiver, Sender};
+use tracing::{error, info, warn, Instrument};
+
+use crate::metrics::log_export::{record_dropped_log, record_exported_logs, record_failed_logs};
+use crate::services::golem_config::{LogExportConfig, LogExportSinkConfig};
+
+/// Forwards the stdout, stderr and log events of the workers to an external log sink
+pub trait LogExportService {
+    /// Queues the event for export if it is a stdout, stderr or log event. Never blocks the
+    /// caller; the event is dropped if the queue is full.
+    fn export(&self, owned_worker_id: &OwnedWorkerId, event: &WorkerEvent);
+}
+
+pub fn configured(config: &LogExportConfig) -> Arc<dyn LogExportService + Send + Sync> {
+    match config {
+        LogExportConfig::Disabled => Arc::new(LogExportServiceDisabled),
+        LogExportConfig::Otlp(config) => {
+            info!("Exporting worker logs to OTLP endpoint {}", config.url);
+            Arc::new(LogExportServiceDefault::new(
+                Arc::new(OtlpLogSink::new(config)),
+                config,
+            ))
+        }
+        LogExportConfig::Loki(config) => {
+            info!("Exporting worker logs to Loki endpoint {}", config.url);
+            Arc::new(LogExportServiceDefault::new(
+                Arc::new(LokiLogSink::new(config)),
+                config,
+            ))
+        }
+    }
+}
+
+pub struct LogExportServiceDisabled;
+
+impl LogExportService for LogExportServiceDisabled {
+    fn export(&self, _owned_worker_id: &OwnedWorkerId, _event: &WorkerEvent) {}
+}
+
+/// Queues the log records in a bounded channel, which is drained by a background task sending
+/// them to the sink in batches. The background task stops when the service is dropped, after
+/// sending the remaining records.
+pub struct LogExportServiceDefault {
+    sender: Sender<LogRecord>,
+    dropping: AtomicBool,
+}
+
+impl LogExportServiceDefault {
+    pub fn new(sink: Arc<dyn LogSink + Send + Sync>, config: &LogExportSinkConfig) -> Self {
+        let (sender, receiver) = channel(config.queue_capacity);
+        tokio::spawn(
+            Self::run(
+                receiver,
+                sink,
+                config.max_batch_size.max(1),
+                config.flush_interval,
+                config.retries.clone(),
+            )
+            .in_current_span(),
+        );
+        Self {
+            sender,
+            dropping: AtomicBool::new(false),
+        }
+    }
+
+    async fn run(
+        mut receiver: Receiver<LogRecord>,
+        sink: Arc<dyn LogSink + Send + Sync>,
+        max_batch_size: usize,
+        flush_interval: std::time::Duration,
+        retries: RetryConfig,
+    ) {
+        while let Some(first) = receiver.recv().await {
+            let mut batch = vec![first];
+            let deadline = tokio::time::Instant::now() + flush_interval;
+            while batch.len() < max_batch_size {
+                match tokio::time::timeout_at(deadline, receiver.recv()).await {
+                    Ok(Some(record)) => batch.push(record),
+                    Ok(None) | Err(_) => break,
+                }
+            }
+
+            Self::send(&sink, &retries, batch).await;
+        }
+    }
+
+    async fn send(
+        sink: &Arc<dyn LogSink + Send + Sync>,
+        retries: &RetryConfig,
+        batch: Vec<LogRecord>,
+    ) {
+        let count = batch.len();
+        let result = with_retries(
+            "log_export",
+            "send",
+            None,
+            retries,
+            &(sink.clone(), batch),
+            |(sink, batch)| Box::pin(async move { sink.send(batch).await }),
+            is_retriable,
+        )
+        .await;
+
+        match result {
+            Ok(()) => record_exported_logs(count),
+            Err(err) => {
+                record_failed_logs(count);
+                error!("Failed to export {count} worker log records: {err}");
+            }
+        }
+    }
+}
+
+impl LogExportService for LogExportServiceDefault {
+    fn export(&self, owned_worker_id: &OwnedWorkerId, event: &WorkerEvent) {
+        let Some(record) = LogRecord::from_event(owned_worker_id, event) else {
+            return;
+        };
+
+        match self.sender.try_send(record) {
+            Ok(()) => {
+                self.dropping.store(false, Ordering::Relaxed);
+            }
+            Err(TrySendError::Full(_)) => {
+                record_dropped_log();
+                if !self.dropping.swap(true, Ordering::Relaxed) {
+                    warn!(
+                        "Log export queue is full, dropping worker logs until the sink catches up"
+                    );
+                }
+            }
+            Err(TrySendError::Closed(_)) => {
+                record_dropped_log();
+            }
+        }
+    }
+}
+
+/// A worker log line, stdout and stderr chunks being exported with info and error level
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogRecord {
+    pub owned_worker_id: OwnedWorkerId,
+    pub timestamp: Timestamp,
+    pub stream: &'static str,
+    pub level: LogLevel,
+    pub context: String,
+    pub message: String,
+}
+
+impl LogRecord {
+    pub fn from_event(owned_worker_id: &OwnedWorkerId, event: &WorkerEvent) -> Option<Self> {
+        let (timestamp, stream, level, context, message) = match event {
+            WorkerEvent::StdOut { timestamp, bytes } => (
+                *timestamp,
+                "stdout",
+                LogLevel::Info,
+                String::new(),
+                String::from_utf8_lossy(bytes).to_string(),
+            ),
+            WorkerEvent::StdErr { timestamp, bytes } => (
+                *timestamp,
+                "stderr",
+                LogLevel::Error,
+                String::new(),
+                String::from_utf8_lossy(bytes).to_string(),
+            ),
+            WorkerEvent::Log {
+                timestamp,
+                level,
+                context,
+                message,
+            } => (
+                *timestamp,
+                "log",
+                level.clone(),
+                context.clone(),
+                message.clone(),
+            ),
+            _ => return None,
+        };
+
+        let message = message.trim_end_matches(['\r', '\n']).to_string();
+        if message.is_empty() {
+            None
+        } else {
+            Some(Self {
+                owned_worker_id: owned_worker_id.clone(),
+                timestamp,
+                stream,
+                level,
+                context,
+                message,
+            })
+        }
+    }
+
+    fn unix_nanos(&self) -> String {
+        (self.timestamp.to_millis() as u128 * 1_000_000).to_string()
+    }
+}
+
+#[async_trait]
+pub trait LogSink {
+    async fn send(&self, records: &[LogRecord]) -> Result<(), reqwest::Error>;
+}
+
+/// Sends the records as JSON encoded OTLP logs, with one resource per worker
+pub struct OtlpLogSink {
+    client: reqwest::Client,
+    url: String,
+    headers: BTreeMap<String, String>,
+}
+
+impl OtlpLogSink {
+    pub fn new(config: &LogExportSinkConfig) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: config.url.clone(),
+            headers: config.headers.clone(),
+        }
+    }
+
+    pub fn payload(records: &[LogRecord]) -> Value {
+        let mut by_worker: BTreeMap<String, (&OwnedWorkerId, Vec<Value>)> = BTreeMap::new();
+        for record in records {
+            let mut attributes = vec![string_attribute("golem.log.stream", record.stream)];
+            if !record.context.is_empty() {
+                attributes.push(string_attribute("golem.log.context", &record.context));
+            }
+
+            by_worker
+                .entry(record.owned_worker_id.to_string())
+                .or_insert_with(|| (&record.owned_worker_id, Vec::new()))
+                .1
+                .push(json!({
+                    "timeUnixNano": record.unix_nanos(),
+                    "severityNumber": otlp_severity_number(&record.level),
+                    "severityText": level_name(&record.level).to_uppercase(),
+                    "body": { "stringValue": record.message },
+                    "attributes": attributes,
+                }));
+        }
+
+        let resource_logs: Vec<Value> = by_worker
+            .into_values()
+            .map(|(owned_worker_id, log_records)| {
+                json!({
+                    "resource": {
+                        "attributes": [
+                            string_attribute("service.name", "golem-worker"),
+                            string_attribute("golem.account_id", &owned_worker_id.account_id.value),
+                            string_attribute(
+                                "golem.component_id",
+                                &owned_worker_id.worker_id.component_id.to_string(),
+                            ),
+                            string_attribute("golem.worker_name", &owned_worker_id.worker_id.worker_name),
+                        ]
+                    },
+                    "scopeLogs": [{
+                        "scope": { "name": "golem-worker-executor" },
+                        "logRecords": log_records,
+                    }],
+                })
+            })
+            .collect();
+
+        json!({ "resourceLogs": resource_logs })
+    }
+}
+
+#[async_trait]
+impl LogSink for OtlpLogSink {
+    async fn send(&self, records: &[LogRecord]) -> Result<(), reqwest::Error> {
+        post_json(
+            &self.client,
+            &self.url,
+            &self.headers,
+            &Self::payload(records),
+        )
+        .await
+    }
+}
+
+/// Sends the records to the Loki push API, with one stream per component and log level.
+///
+/// The worker name is not a label, as every worker would create a new stream in Loki. Instead each
+/// line is a JSON object containing the worker name, context and message, which can be queried
+/// with Loki's `json` parser.
+pub struct LokiLogSink {
+    client: reqwest::Client,
+    url: String,
+    headers: BTreeMap<String, String>,
+}
+
+impl LokiLogSink {
+    pub fn new(config: &LogExportSinkConfig) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: config.url.clone(),
+            headers: config.headers.clone(),
+        }
+    }
+
+    pub fn payload(records: &[LogRecord]) -> Value {
+        let mut streams: BTreeMap<BTreeMap<&'static str, String>, Vec<Value>> = BTreeMap::new();
+        for record in records {
+            let labels = BTreeMap::from([
+                (
+                    "account_id",
+                    record.owned_worker_id.account_id.value.clone(),
+                ),
+                (
+                    "component_id",
+                    record.owned_worker_id.worker_id.component_id.to_string(),
+                ),
+                ("stream", record.stream.to_string()),
+                ("level", level_name(&record.level).to_string()),
+            ]);
+            let mut line = json!({
+                "worker_name": record.owned_worker_id.worker_id.worker_name,
+                "message": record.message,
+            });
+            if !record.context.is_empty() {
+                line["context"] = json!(record.context);
+            }
+
+            streams
+                .entry(labels)
+                .or_default()
+                .push(json!([record.unix_nanos(), line.to_string()]));
+        }
+
+        let streams: Vec<Value> = streams
+            .into_iter()
+            .map(|(labels, values)| json!({ "stream": labels, "values": values }))
+            .collect();
+
+        json!({ "streams": streams })
+    }
+}
+
+#[async_trait]
+impl LogSink for LokiLogSink {
+    async fn send(&self, records: &[LogRecord]) -> Result<(), reqwest::Error> {
+        post_json(
+            &self.client,
+            &self.url,
+            &self.headers,
+            &Self::payload(records),
+        )
+        .await
+    }
+}
+
+async fn post_json(
+    client: &reqwest::Client,
+    url: &str,
+    headers: &BTreeMap<String, String>,
+    body: &Value,
+) -> Result<(), reqwest::Error> {
+    let mut request = client.post(url).json(body);
+    for (name, value) in headers {
+        request = request.header(name, value);
+    }
+    request.send().await?.error_for_status()?;
+    Ok(())
+}
+
+/// Only server errors and failures to reach the sink are retried, the sink would reject the same
+/// batch again on a client error
+fn is_retriable(err: &reqwest::Error) -> bool {
+    match err.status() {
+        Some(status) => status.is_server_error(),
+        None => !err.is_builder() && !err.is_decode(),
+    }
+}
+
+fn string_attribute(key: &str, value: &str) -> Value {
+    json!({ "key": key, "value": { "stringValue": value } })
+}
+
+fn level_name(level: &LogLevel) -> &'static str {
+    match level {
+        LogLevel::Trace => "trace",
+        LogLevel::Debug => "debug",
+        LogLevel::Info => "info",
+        LogLevel::Warn => "warn",
+        LogLevel::Error => "error",
+        LogLevel::Critical => "critical",
+    }
+}
+
+fn otlp_severity_number(level: &LogLevel) -> u8 {
+    match level {
+        LogLevel::Trace => 1,
+        LogLevel::Debug => 5,
+        LogLevel::Info => 9,
+        LogLevel::Warn => 13,
+        LogLevel::Error => 17,
+        LogLevel::Critical => 21,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use golem_common::model::{
+        AccountId, ComponentId, IdempotencyKey, LogLevel, OwnedWorkerId, WorkerEvent, WorkerId,
+        WorkerName,
+    };
+    use serde_json::{json, Value};
+
+    use crate::services::log_export::{LogRecord, LokiLogSink, OtlpLogSink};
+
+    fn owned_worker_id() -> OwnedWorkerId {
+        OwnedWorkerId::new(
+            &AccountId {
+                value: "account".to_string(),
+            },
+            &WorkerId {
+                component_id: ComponentId::new_v4(),
+                worker_name: WorkerName::from_existing("worker"),
+            },
+        )
+    }
+
+    fn records(owned_worker_id: &OwnedWorkerId) -> Vec<LogRecord> {
+        [
+            WorkerEvent::stdout(b"hello\n".to_vec()),
+            WorkerEvent::log(LogLevel::Warn, "ctx", "careful"),
+            WorkerEvent::invocation_start("f", &IdempotencyKey::fresh()),
+            WorkerEvent::stderr(b"\n".to_vec()),
+        ]
+        .iter()
+        .filter_map(|event| LogRecord::from_event(owned_worker_id, event))
+        .collect()
+    }
+
+    #[test]
+    fn only_non_empty_log_events_are_exported() {
+        let owned_worker_id = owned_worker_id();
+        let records = records(&owned_worker_id);
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].message, "hello");
+        assert_eq!(records[0].stream, "stdout");
+        assert_eq!(records[1].context, "ctx");
+        assert_eq!(records[1].level, LogLevel::Warn);
+    }
+
+    #[test]
+    fn otlp_payload_has_one_resource_per_worker() {
+        let owned_worker_id = owned_worker_id();
+        let payload = OtlpLogSink::payload(&records(&owned_worker_id));
+
+        let resource_logs = payload["resourceLogs"].as_array().unwrap();
+        assert_eq!(resource_logs.len(), 1);
+        assert!(resource_logs[0]["resource"]["attributes"]
+            .as_array()
+            .unwrap()
+            .contains(&json!({"key": "golem.worker_name", "value": {"stringValue": "worker"}})));
+
+        let log_records = resource_logs[0]["scopeLogs"][0]["logRecords"]
+            .as_array()
+            .unwrap();
+        assert_eq!(log_records.len(), 2);
+        assert_eq!(log_records[0]["severityNumber"], json!(9));
+        assert_eq!(log_records[1]["severityText"], json!("WARN"));
+        assert_eq!(log_records[1]["body"]["stringValue"], json!("careful"));
+    }
+
+    #[test]
+    fn loki_payload_has_one_stream_per_level() {
+        let owned_worker_id = owned_worker_id();
+        let payload = LokiLogSink::payload(&records(&owned_worker_id));
+
+        let streams = payload["streams"].as_array().unwrap();
+        assert_eq!(streams.len(), 2);
+        for stream in streams {
+            assert_eq!(stream["stream"]["account_id"], json!("account"));
+            assert_eq!(
+                stream["stream"]["component_id"],
+                json!(owned_worker_id.worker_id.component_id.to_string())
+            );
+            assert_eq!(stream["values"].as_array().unwrap().len(), 1);
+        }
+
+        for stream in streams {
+            assert!(stream["stream"].get("worker_name").is_none());
+        }
+
+        let lines: Vec<Value> = streams
+            .iter()
+            .map(|stream| serde_json::from_str(stream["values"][0][1].as_str().unwrap()).unwrap())
+            .collect();
+        assert!(lines.contains(&json!({"worker_name": "worker", "message": "hello"})));
+        assert!(lines
+            .contains(&json!({"worker_name": "worker", "context": "ctx", "message": "careful"})));
+    }
+}
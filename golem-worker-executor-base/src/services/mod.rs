@@ -23,15 +23,27 @@ pub mod active_workers;
 pub mod blob_store;
 pub mod compiled_component;
 pub mod component;
+pub mod connectors;
+pub mod encryption;
+pub mod event_sinks;
 pub mod events;
 pub mod golem_config;
+pub mod invocation_scheduling;
 pub mod key_value;
+pub mod log_export;
 pub mod oplog;
+pub mod prewarm;
 pub mod promise;
+pub mod promise_gc;
 pub mod rpc;
+pub mod saga;
 pub mod scheduler;
+pub mod secrets;
 pub mod shard;
 pub mod shard_manager;
+pub mod topics;
+pub mod usage;
+pub mod virtual_clock;
 pub mod worker;
 pub mod worker_activator;
 pub mod worker_enumeration;
@@ -94,6 +106,22 @@ pub trait HasBlobStoreService {
     fn blob_store_service(&self) -> Arc<dyn blob_store::BlobStoreService + Send + Sync>;
 }
 
+pub trait HasSecretService {
+    fn secret_service(&self) -> Arc<dyn secrets::SecretService + Send + Sync>;
+}
+
+pub trait HasTopicService {
+    fn topic_service(&self) -> Arc<dyn topics::TopicService + Send + Sync>;
+}
+
+pub trait HasSagaService {
+    fn saga_service(&self) -> Arc<dyn saga::SagaService + Send + Sync>;
+}
+
+pub trait HasVirtualClockService {
+    fn virtual_clock_service(&self) -> Arc<dyn virtual_clock::VirtualClockService + Send + Sync>;
+}
+
 pub trait HasOplogService {
     fn oplog_service(&self) -> Arc<dyn oplog::OplogService + Send + Sync>;
 }
@@ -130,6 +158,14 @@ pub trait HasEvents {
     fn events(&self) -> Arc<Events>;
 }
 
+pub trait HasLogExportService {
+    fn log_export_service(&self) -> Arc<dyn log_export::LogExportService + Send + Sync>;
+}
+
+pub trait HasUsageService {
+    fn usage_service(&self) -> Arc<dyn usage::UsageService + Send + Sync>;
+}
+
 /// HasAll is a shortcut for requiring all available service dependencies
 pub trait HasAll<Ctx: WorkerCtx>:
     HasActiveWorkers<Ctx>
@@ -142,12 +178,18 @@ pub trait HasAll<Ctx: WorkerCtx>:
     + HasWasmtimeEngine<Ctx>
     + HasKeyValueService
     + HasBlobStoreService
+    + HasSecretService
+    + HasTopicService
+    + HasSagaService
+    + HasVirtualClockService
     + HasOplogService
     + HasRpc
     + HasSchedulerService
     + HasWorkerActivator
     + HasWorkerProxy
     + HasEvents
+    + HasLogExportService
+    + HasUsageService
     + HasShardManagerService
     + HasShardService
     + HasExtraDeps<Ctx>
@@ -167,12 +209,18 @@ impl<
             + HasWasmtimeEngine<Ctx>
             + HasKeyValueService
             + HasBlobStoreService
+            + HasSecretService
+            + HasTopicService
+            + HasSagaService
+            + HasVirtualClockService
             + HasOplogService
             + HasRpc
             + HasSchedulerService
             + HasWorkerActivator
             + HasWorkerProxy
             + HasEvents
+            + HasLogExportService
+            + HasUsageService
             + HasShardManagerService
             + HasShardService
             + HasExtraDeps<Ctx>
@@ -199,12 +247,18 @@ pub struct All<Ctx: WorkerCtx> {
     shard_service: Arc<dyn shard::ShardService + Send + Sync>,
     key_value_service: Arc<dyn key_value::KeyValueService + Send + Sync>,
     blob_store_service: Arc<dyn blob_store::BlobStoreService + Send + Sync>,
+    secret_service: Arc<dyn secrets::SecretService + Send + Sync>,
+    topic_service: Arc<dyn topics::TopicService + Send + Sync>,
+    saga_service: Arc<dyn saga::SagaService + Send + Sync>,
+    virtual_clock_service: Arc<dyn virtual_clock::VirtualClockService + Send + Sync>,
     oplog_service: Arc<dyn oplog::OplogService + Send + Sync>,
     rpc: Arc<dyn rpc::Rpc + Send + Sync>,
     scheduler_service: Arc<dyn scheduler::SchedulerService + Send + Sync>,
     worker_activator: Arc<dyn WorkerActivator + Send + Sync>,
     worker_proxy: Arc<dyn worker_proxy::WorkerProxy + Send + Sync>,
     events: Arc<Events>,
+    log_export_service: Arc<dyn log_export::LogExportService + Send + Sync>,
+    usage_service: Arc<dyn usage::UsageService + Send + Sync>,
     extra_deps: Ctx::ExtraDeps,
 }
 
@@ -225,12 +279,18 @@ impl<Ctx: WorkerCtx> Clone for All<Ctx> {
             shard_service: self.shard_service.clone(),
             key_value_service: self.key_value_service.clone(),
             blob_store_service: self.blob_store_service.clone(),
+            secret_service: self.secret_service.clone(),
+            topic_service: self.topic_service.clone(),
+            saga_service: self.saga_service.clone(),
+            virtual_clock_service: self.virtual_clock_service.clone(),
             oplog_service: self.oplog_service.clone(),
             rpc: self.rpc.clone(),
             scheduler_service: self.scheduler_service.clone(),
             worker_activator: self.worker_activator.clone(),
             worker_proxy: self.worker_proxy.clone(),
             events: self.events.clone(),
+            log_export_service: self.log_export_service.clone(),
+            usage_service: self.usage_service.clone(),
             extra_deps: self.extra_deps.clone(),
         }
     }
@@ -257,12 +317,18 @@ impl<Ctx: WorkerCtx> All<Ctx> {
         shard_service: Arc<dyn shard::ShardService + Send + Sync>,
         key_value_service: Arc<dyn key_value::KeyValueService + Send + Sync>,
         blob_store_service: Arc<dyn blob_store::BlobStoreService + Send + Sync>,
+        secret_service: Arc<dyn secrets::SecretService + Send + Sync>,
+        topic_service: Arc<dyn topics::TopicService + Send + Sync>,
+        saga_service: Arc<dyn saga::SagaService + Send + Sync>,
+        virtual_clock_service: Arc<dyn virtual_clock::VirtualClockService + Send + Sync>,
         oplog_service: Arc<dyn oplog::OplogService + Send + Sync>,
         rpc: Arc<dyn rpc::Rpc + Send + Sync>,
         scheduler_service: Arc<dyn scheduler::SchedulerService + Send + Sync>,
         worker_activator: Arc<dyn WorkerActivator + Send + Sync>,
         worker_proxy: Arc<dyn worker_proxy::WorkerProxy + Send + Sync>,
         events: Arc<Events>,
+        log_export_service: Arc<dyn log_export::LogExportService + Send + Sync>,
+        usage_service: Arc<dyn usage::UsageService + Send + Sync>,
         extra_deps: Ctx::ExtraDeps,
     ) -> Self {
         Self {
@@ -280,12 +346,18 @@ impl<Ctx: WorkerCtx> All<Ctx> {
             shard_service,
             key_value_service,
             blob_store_service,
+            secret_service,
+            topic_service,
+            saga_service,
+            virtual_clock_service,
             oplog_service,
             rpc,
             scheduler_service,
             worker_activator,
             worker_proxy,
             events,
+            log_export_service,
+            usage_service,
             extra_deps,
         }
     }
@@ -306,12 +378,18 @@ impl<Ctx: WorkerCtx> All<Ctx> {
             this.shard_service(),
             this.key_value_service(),
             this.blob_store_service(),
+            this.secret_service(),
+            this.topic_service(),
+            this.saga_service(),
+            this.virtual_clock_service(),
             this.oplog_service(),
             this.rpc(),
             this.scheduler_service(),
             this.worker_activator(),
             this.worker_proxy(),
             this.events(),
+            this.log_export_service(),
+            this.usage_service(),
             this.extra_deps(),
         )
     }
@@ -415,6 +493,30 @@ impl<Ctx: WorkerCtx, T: UsesAllDeps<Ctx = Ctx>> HasBlobStoreService for T {
     }
 }
 
+impl<Ctx: WorkerCtx, T: UsesAllDeps<Ctx = Ctx>> HasSecretService for T {
+    fn secret_service(&self) -> Arc<dyn secrets::SecretService + Send + Sync> {
+        self.all().secret_service.clone()
+    }
+}
+
+impl<Ctx: WorkerCtx, T: UsesAllDeps<Ctx = Ctx>> HasTopicService for T {
+    fn topic_service(&self) -> Arc<dyn topics::TopicService + Send + Sync> {
+        self.all().topic_service.clone()
+    }
+}
+
+impl<Ctx: WorkerCtx, T: UsesAllDeps<Ctx = Ctx>> HasSagaService for T {
+    fn saga_service(&self) -> Arc<dyn saga::SagaService + Send + Sync> {
+        self.all().saga_service.clone()
+    }
+}
+
+impl<Ctx: WorkerCtx, T: UsesAllDeps<Ctx = Ctx>> HasVirtualClockService for T {
+    fn virtual_clock_service(&self) -> Arc<dyn virtual_clock::VirtualClockService + Send + Sync> {
+        self.all().virtual_clock_service.clone()
+    }
+}
+
 impl<Ctx: WorkerCtx, T: UsesAllDeps<Ctx = Ctx>> HasOplogService for T {
     fn oplog_service(&self) -> Arc<dyn oplog::OplogService + Send + Sync> {
         self.all().oplog_service.clone()
@@ -451,6 +553,18 @@ impl<Ctx: WorkerCtx, T: UsesAllDeps<Ctx = Ctx>> HasEvents for T {
     }
 }
 
+impl<Ctx: WorkerCtx, T: UsesAllDeps<Ctx = Ctx>> HasLogExportService for T {
+    fn log_export_service(&self) -> Arc<dyn log_export::LogExportService + Send + Sync> {
+        self.all().log_export_service.clone()
+    }
+}
+
+impl<Ctx: WorkerCtx, T: UsesAllDeps<Ctx = Ctx>> HasUsageService for T {
+    fn usage_service(&self) -> Arc<dyn usage::UsageService + Send + Sync> {
+        self.all().usage_service.clone()
+    }
+}
+
 impl<Ctx: WorkerCtx, T: UsesAllDeps<Ctx = Ctx>> HasExtraDeps<Ctx> for T {
     fn extra_deps(&self) -> Ctx::ExtraDeps {
         self.all().extra_deps.clone()
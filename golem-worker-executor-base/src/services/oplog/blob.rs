@@ -26,7 +26,9 @@ use crate::storage::blob::{
 use async_trait::async_trait;
 use evicting_cache_map::EvictingCacheMap;
 use golem_common::model::oplog::{OplogEntry, OplogIndex};
-use golem_common::model::{AccountId, ComponentId, OwnedWorkerId, ScanCursor, WorkerId};
+use golem_common::model::{
+    AccountId, ComponentId, OwnedWorkerId, ScanCursor, WorkerId, WorkerName,
+};
 use tokio::sync::RwLock;
 
 /// An oplog archive implementation that uses the configured blob storage to store compressed
@@ -71,7 +73,7 @@ impl OplogArchiveService for BlobOplogArchiveService {
                     component_id: owned_worker_id.component_id(),
                     level: self.level,
                 },
-                Path::new(&owned_worker_id.worker_name()),
+                Path::new(owned_worker_id.worker_name().as_str()),
             )
             .await
             .unwrap_or_else(|err| {
@@ -101,7 +103,7 @@ impl OplogArchiveService for BlobOplogArchiveService {
                     component_id: owned_worker_id.component_id(),
                     level: self.level,
                 },
-                Path::new(&owned_worker_id.worker_name()),
+                Path::new(owned_worker_id.worker_name().as_str()),
             )
             .await
             .map(|exists| exists == ExistsResult::Directory)
@@ -142,7 +144,7 @@ impl OplogArchiveService for BlobOplogArchiveService {
                         account_id: account_id.clone(),
                         worker_id: WorkerId {
                             component_id: component_id.clone(),
-                            worker_name: worker_name.to_string(),
+                            worker_name: WorkerName::from_existing(worker_name),
                         },
                     }
                 })
@@ -208,7 +210,7 @@ impl BlobOplogArchive {
                     component_id: owned_worker_id.component_id(),
                     level,
                 },
-                Path::new(&owned_worker_id.worker_name()),
+                Path::new(owned_worker_id.worker_name().as_str()),
             )
             .await
             .map(|exists| exists == ExistsResult::Directory)
@@ -228,7 +230,7 @@ impl BlobOplogArchive {
                         component_id: owned_worker_id.component_id(),
                         level,
                     },
-                    Path::new(&owned_worker_id.worker_name()),
+                    Path::new(owned_worker_id.worker_name().as_str()),
                 )
                 .await
                 .unwrap_or_else(|err| {
@@ -265,7 +267,7 @@ impl BlobOplogArchive {
                     component_id: owned_worker_id.component_id(),
                     level,
                 },
-                Path::new(&owned_worker_id.worker_name()),
+                Path::new(owned_worker_id.worker_name().as_str()),
             )
             .await
             .unwrap_or_else(|err| {
@@ -294,7 +296,7 @@ impl BlobOplogArchive {
 
     pub(crate) fn oplog_index_to_path(&self, idx: OplogIndex) -> PathBuf {
         let mut path = PathBuf::new();
-        path.push(self.owned_worker_id.worker_name());
+        path.push(self.owned_worker_id.worker_name().as_str());
         path.push(idx.to_string());
         path
     }
@@ -421,6 +423,75 @@ impl OplogArchive for BlobOplogArchive {
         }
     }
 
+    async fn replace(&self, replaced: Vec<(OplogIndex, OplogEntry)>) -> bool {
+        let mut replaced = replaced.into_iter().collect::<BTreeMap<_, _>>();
+        let entries = self.entries.write().await;
+        let ns = BlobStorageNamespace::CompressedOplog {
+            account_id: self.owned_worker_id.account_id(),
+            component_id: self.owned_worker_id.component_id(),
+            level: self.level,
+        };
+
+        while let Some(first_idx) = replaced.keys().next().copied() {
+            let Some((last_idx, path)) = entries.range(first_idx..).next() else {
+                break;
+            };
+
+            let chunk: CompressedOplogChunk = self
+                .blob_storage
+                .with("blob_oplog", "replace")
+                .get(ns.clone(), path)
+                .await
+                .and_then(|chunk| chunk.ok_or(format!("compressed chunk for {last_idx} not found")))
+                .unwrap_or_else(|err| {
+                    panic!(
+                        "failed to read compressed oplog chunk for worker {} in blob storage: {err}",
+                        self.owned_worker_id.worker_id
+                    )
+                });
+            let first_in_chunk = Into::<u64>::into(*last_idx) - chunk.count + 1;
+            let chunk_entries = chunk
+                .decompress()
+                .unwrap_or_else(|err| panic!("failed to decompress oplog chunk: {err}"))
+                .into_iter()
+                .enumerate()
+                .map(|(i, entry)| {
+                    let idx = OplogIndex::from_u64(first_in_chunk + i as u64);
+                    (idx, replaced.remove(&idx).unwrap_or(entry))
+                })
+                .collect::<Vec<_>>();
+            // Entries before this chunk are not in this archive
+            replaced.retain(|idx, _| idx > last_idx);
+
+            let compressed_chunk = CompressedOplogChunk::compress(
+                chunk_entries
+                    .iter()
+                    .map(|(_, entry)| entry.clone())
+                    .collect(),
+            )
+            .unwrap_or_else(|err| panic!("failed to compress oplog chunk: {err}"));
+            self.blob_storage
+                .with("blob_oplog", "replace")
+                .put(ns.clone(), path, &compressed_chunk)
+                .await
+                .unwrap_or_else(|err| {
+                    panic!(
+                        "failed to store compressed oplog chunk for worker {} in blob storage: {err}",
+                        self.owned_worker_id.worker_id
+                    )
+                });
+
+            let mut cache = self.cache.write().await;
+            for (idx, entry) in chunk_entries {
+                if cache.get(&idx).is_some() {
+                    cache.insert(idx, entry);
+                }
+            }
+        }
+
+        true
+    }
+
     async fn current_oplog_index(&self) -> OplogIndex {
         let entries = self.entries.read().await;
         entries
@@ -454,7 +525,7 @@ impl OplogArchive for BlobOplogArchive {
             .iter()
             .map(|idx| {
                 let mut path = PathBuf::new();
-                path.push(self.owned_worker_id.worker_name());
+                path.push(self.owned_worker_id.worker_name().as_str());
                 path.push(idx.to_string());
                 path
             })
@@ -489,7 +560,7 @@ impl OplogArchive for BlobOplogArchive {
                     component_id: self.owned_worker_id.component_id(),
                     level: self.level,
                 },
-                Path::new(&self.owned_worker_id.worker_name())).await.unwrap_or_else(|err| {
+                Path::new(self.owned_worker_id.worker_name().as_str())).await.unwrap_or_else(|err| {
                     panic!(
                         "failed to drop compressed oplog directory for worker {} in blob storage: {err}",
                         self.owned_worker_id.worker_id
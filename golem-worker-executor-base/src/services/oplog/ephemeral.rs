@@ -38,19 +38,35 @@ struct EphemeralOplogState {
     last_oplog_idx: OplogIndex,
     last_committed_idx: OplogIndex,
     max_operations_before_commit: u64,
+    /// If true, the entries are never written to the target archive, only kept in the buffer
+    in_memory: bool,
     target: Arc<dyn OplogArchive + Send + Sync>,
 }
 
 impl EphemeralOplogState {
     async fn add(&mut self, entry: OplogEntry) {
         self.buffer.push_back(entry);
-        if self.buffer.len() > self.max_operations_before_commit as usize {
+        if !self.in_memory && self.buffer.len() > self.max_operations_before_commit as usize {
             self.commit().await;
         }
         self.last_oplog_idx = self.last_oplog_idx.next();
     }
 
+    fn buffered(&self, oplog_index: OplogIndex) -> Option<&OplogEntry> {
+        if oplog_index > self.last_committed_idx {
+            let offset =
+                Into::<u64>::into(oplog_index) - Into::<u64>::into(self.last_committed_idx);
+            self.buffer.get(offset as usize - 1)
+        } else {
+            None
+        }
+    }
+
     async fn commit(&mut self) {
+        if self.in_memory {
+            return;
+        }
+
         let entries = self.buffer.drain(..).collect::<Vec<OplogEntry>>();
 
         let mut pairs = Vec::new();
@@ -69,6 +85,7 @@ impl EphemeralOplog {
         owned_worker_id: OwnedWorkerId,
         last_oplog_idx: OplogIndex,
         max_operations_before_commit: u64,
+        in_memory: bool,
         primary: Arc<dyn Oplog + Send + Sync>,
         target: Arc<dyn OplogArchive + Send + Sync>,
         close: Box<dyn FnOnce() + Send + Sync>,
@@ -82,6 +99,7 @@ impl EphemeralOplog {
                 last_oplog_idx,
                 last_committed_idx: last_oplog_idx,
                 max_operations_before_commit,
+                in_memory,
                 target,
             })),
             close_fn: Some(close),
@@ -150,6 +168,10 @@ impl Oplog for EphemeralOplog {
 
     async fn read(&self, oplog_index: OplogIndex) -> OplogEntry {
         record_oplog_call("read");
+        if let Some(entry) = self.state.lock().await.buffered(oplog_index) {
+            return entry.clone();
+        }
+
         let entries = self.target.read(oplog_index, 1).await;
         if let Some(entry) = entries.get(&oplog_index) {
             entry.clone()
@@ -163,7 +185,8 @@ impl Oplog for EphemeralOplog {
 
     async fn length(&self) -> u64 {
         record_oplog_call("length");
-        self.target.length().await
+        let buffered = self.state.lock().await.buffer.len() as u64;
+        self.target.length().await + buffered
     }
 
     async fn upload_payload(&self, data: &[u8]) -> Result<OplogPayload, String> {
@@ -171,6 +194,16 @@ impl Oplog for EphemeralOplog {
         self.primary.upload_payload(data).await
     }
 
+    async fn upload_payload_with_limit(
+        &self,
+        data: &[u8],
+        max_inline_size: usize,
+    ) -> Result<OplogPayload, String> {
+        self.primary
+            .upload_payload_with_limit(data, max_inline_size)
+            .await
+    }
+
     async fn download_payload(&self, payload: &OplogPayload) -> Result<Bytes, String> {
         // Downloading oplog payloads through the primary layer
         self.primary.download_payload(payload).await
@@ -0,0 +1,203 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bincode::{Decode, Encode};
+use golem_common::model::oplog::{OplogEntry, OplogIndex};
+use golem_common::model::{OwnedWorkerId, WorkerId};
+use golem_common::serialization::{deserialize, serialize};
+
+use crate::storage::indexed::{IndexedStorage, IndexedStorageLabelledApi, IndexedStorageNamespace};
+
+/// Storage of the log entries (stdout, stderr and log calls) moved out of the oplog by the log
+/// retention policy of `MultiLayerOplogService`.
+///
+/// The oplog keeps each moved entry without its context and message, so the oplog indices do not
+/// change and only the log query APIs have to look up the original entries here.
+#[async_trait]
+pub trait LogArchiveService: Debug {
+    /// Stores a chunk of log entries of a worker. The entries may be older than the already stored
+    /// ones, as the retention pass of the last oplog layer moves logs which were not old enough when
+    /// they got transferred. Entries already stored are ignored, so retrying a failed transfer does
+    /// not store them twice.
+    async fn append(&self, owned_worker_id: &OwnedWorkerId, entries: Vec<(OplogIndex, OplogEntry)>);
+
+    /// Reads the stored log entries of a worker with oplog index in the given inclusive range
+    async fn read_range(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        start_idx: OplogIndex,
+        last_idx: OplogIndex,
+    ) -> BTreeMap<OplogIndex, OplogEntry>;
+
+    /// Deletes all the stored log entries of a worker
+    async fn delete(&self, owned_worker_id: &OwnedWorkerId);
+}
+
+/// Log archive storing compressed chunks of log entries in the configured indexed storage, each
+/// chunk identified by the oplog index of its last entry.
+///
+/// As chunks can be appended out of order, their index ranges may overlap, so reading a range has
+/// to look at every chunk identified by an index after the range's start.
+#[derive(Debug)]
+pub struct CompressedLogArchiveService {
+    indexed_storage: Arc<dyn IndexedStorage + Send + Sync>,
+}
+
+impl CompressedLogArchiveService {
+    const ZSTD_LEVEL: i32 = 0;
+
+    pub fn new(indexed_storage: Arc<dyn IndexedStorage + Send + Sync>) -> Self {
+        Self { indexed_storage }
+    }
+
+    fn compressed_logs_key(worker_id: &WorkerId) -> String {
+        worker_id.to_redis_key()
+    }
+}
+
+#[async_trait]
+impl LogArchiveService for CompressedLogArchiveService {
+    async fn append(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        entries: Vec<(OplogIndex, OplogEntry)>,
+    ) {
+        let key = Self::compressed_logs_key(&owned_worker_id.worker_id);
+
+        let stored = match (entries.first(), entries.last()) {
+            (Some((first_idx, _)), Some((last_idx, _))) => {
+                self.read_range(owned_worker_id, *first_idx, *last_idx)
+                    .await
+            }
+            _ => BTreeMap::new(),
+        };
+
+        let entries: Vec<_> = entries
+            .into_iter()
+            .filter(|(idx, _)| !stored.contains_key(idx))
+            .collect();
+
+        if let Some((last_idx, _)) = entries.last() {
+            let last_idx = *last_idx;
+            let chunk = CompressedLogChunk::compress(entries)
+                .unwrap_or_else(|err| panic!("failed to compress log chunk: {err}"));
+
+            self.indexed_storage
+                .with_entity("compressed_logs", "append", "compressed_log_chunk")
+                .append(
+                    IndexedStorageNamespace::CompressedLogs,
+                    &key,
+                    last_idx.into(),
+                    &chunk,
+                )
+                .await
+                .unwrap_or_else(|err| {
+                    panic!("failed to append archived log chunk of worker {owned_worker_id} in indexed storage: {err}")
+                });
+        }
+    }
+
+    async fn read_range(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        start_idx: OplogIndex,
+        last_idx: OplogIndex,
+    ) -> BTreeMap<OplogIndex, OplogEntry> {
+        let key = Self::compressed_logs_key(&owned_worker_id.worker_id);
+        let storage = self.indexed_storage.with_entity(
+            "compressed_logs",
+            "read_range",
+            "compressed_log_chunk",
+        );
+
+        let last_stored_idx = storage
+            .last_id(IndexedStorageNamespace::CompressedLogs, &key)
+            .await
+            .unwrap_or_else(|err| {
+                panic!("failed to get the last archived log entry of worker {owned_worker_id} in indexed storage: {err}")
+            });
+
+        // Every chunk ending at or after the range's start may contain entries of the range
+        let chunks = match last_stored_idx {
+            Some(last_stored_idx) if last_stored_idx >= start_idx.into() => storage
+                .read::<CompressedLogChunk>(
+                    IndexedStorageNamespace::CompressedLogs,
+                    &key,
+                    start_idx.into(),
+                    last_stored_idx,
+                )
+                .await
+                .unwrap_or_else(|err| {
+                    panic!("failed to read archived logs of worker {owned_worker_id} in indexed storage: {err}")
+                }),
+            _ => Vec::new(),
+        };
+
+        let mut result = BTreeMap::new();
+        for (_, chunk) in chunks {
+            let entries = chunk
+                .decompress()
+                .unwrap_or_else(|err| panic!("failed to decompress log chunk: {err}"));
+            result.extend(
+                entries
+                    .into_iter()
+                    .filter(|(idx, _)| *idx >= start_idx && *idx <= last_idx),
+            );
+        }
+        result
+    }
+
+    async fn delete(&self, owned_worker_id: &OwnedWorkerId) {
+        self.indexed_storage
+            .with("compressed_logs", "delete")
+            .delete(
+                IndexedStorageNamespace::CompressedLogs,
+                &Self::compressed_logs_key(&owned_worker_id.worker_id),
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!("failed to drop archived logs of worker {owned_worker_id} in indexed storage: {err}")
+            });
+    }
+}
+
+/// A chunk of log entries with their oplog indices, which are not contiguous unlike in
+/// `CompressedOplogChunk`
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct CompressedLogChunk {
+    pub compressed_data: Vec<u8>,
+}
+
+impl CompressedLogChunk {
+    pub fn compress(entries: Vec<(OplogIndex, OplogEntry)>) -> Result<Self, String> {
+        let uncompressed_data =
+            serialize(&entries).map_err(|err| format!("failed to serialize log chunk: {err}"))?;
+        let compressed_data =
+            zstd::encode_all(&*uncompressed_data, CompressedLogArchiveService::ZSTD_LEVEL)
+                .map_err(|err| format!("failed to compress log chunk: {err}"))?;
+        Ok(Self { compressed_data })
+    }
+
+    pub fn decompress(&self) -> Result<Vec<(OplogIndex, OplogEntry)>, String> {
+        let uncompressed_data = zstd::decode_all(&*self.compressed_data)
+            .map_err(|err| format!("failed to decompress log chunk: {err}"))?;
+        deserialize(&uncompressed_data)
+            .map_err(|err| format!("failed to deserialize log chunk: {err}"))
+    }
+}
@@ -0,0 +1,48 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bincode::{Decode, Encode};
+use golem_common::model::oplog::OplogEntry;
+use golem_common::model::ComponentVersion;
+
+/// The oplog entries recorded in the marker index of an oplog.
+///
+/// The marker index is stored next to the oplog of durable workers and is appended together with
+/// the entries, so the invocation boundaries and component version changes can be found without
+/// reading all the entries in between.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode)]
+pub enum OplogMarker {
+    /// An `ExportedFunctionInvoked` entry
+    InvocationStart,
+    /// An `ExportedFunctionCompleted` entry
+    InvocationEnd,
+    /// A `Create` or `SuccessfulUpdate` entry, with the component version the worker uses from it
+    ComponentVersion(ComponentVersion),
+}
+
+impl OplogMarker {
+    pub fn of(entry: &OplogEntry) -> Option<Self> {
+        match entry {
+            OplogEntry::ExportedFunctionInvoked { .. } => Some(Self::InvocationStart),
+            OplogEntry::ExportedFunctionCompleted { .. } => Some(Self::InvocationEnd),
+            OplogEntry::Create {
+                component_version, ..
+            } => Some(Self::ComponentVersion(*component_version)),
+            OplogEntry::SuccessfulUpdate { target_version, .. } => {
+                Some(Self::ComponentVersion(*target_version))
+            }
+            _ => None,
+        }
+    }
+}
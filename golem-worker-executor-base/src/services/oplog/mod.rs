@@ -27,15 +27,21 @@ use bytes::Bytes;
 pub use compressed::{CompressedOplogArchive, CompressedOplogArchiveService, CompressedOplogChunk};
 use golem_common::cache::{BackgroundEvictionMode, Cache, FullCacheEvictionMode};
 use golem_common::model::oplog::{
-    OplogEntry, OplogIndex, OplogPayload, UpdateDescription, WrappedFunctionType,
+    OplogEntry, OplogEntryHeader, OplogIndex, OplogPayload, UpdateDescription, WrappedFunctionType,
 };
 use golem_common::model::{
     AccountId, ComponentId, ComponentType, ComponentVersion, IdempotencyKey, OwnedWorkerId,
     ScanCursor, Timestamp, WorkerId,
 };
-use golem_common::serialization::{serialize, try_deserialize};
-pub use multilayer::{MultiLayerOplog, MultiLayerOplogService, OplogArchiveService};
-pub use primary::PrimaryOplogService;
+use golem_common::serialization::{serialize, try_deserialize, SERIALIZATION_VERSION_V1};
+pub use logs::{CompressedLogArchiveService, LogArchiveService};
+pub use markers::OplogMarker;
+pub use multilayer::{
+    LogRetentionPolicy, MultiLayerOplog, MultiLayerOplogService, OplogArchiveAgePolicy,
+    OplogArchiveService,
+};
+pub use primary::{OplogDurabilityPolicy, PrimaryOplogService};
+pub use throwaway::ThrowawayOplog;
 use tracing::Instrument;
 
 use crate::error::GolemError;
@@ -43,12 +49,85 @@ use crate::error::GolemError;
 mod blob;
 mod compressed;
 mod ephemeral;
+mod logs;
+mod markers;
 mod multilayer;
 mod primary;
+mod throwaway;
 
 #[cfg(test)]
 mod tests;
 
+/// Describes a stored oplog entry which could not be decoded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OplogDecodeFailure {
+    pub oplog_index: OplogIndex,
+    /// The serialization version recorded in the entry's first byte
+    pub serialization_version: u8,
+    pub error: String,
+}
+
+/// Decodes a raw oplog entry without panicking on unsupported serialization versions.
+/// On failure returns the recorded serialization version and the error message.
+pub fn try_decode_oplog_entry(bytes: &[u8]) -> Result<OplogEntry, (u8, String)> {
+    let version = bytes.first().copied().unwrap_or_default();
+    if version == SERIALIZATION_VERSION_V1 {
+        Err((
+            version,
+            "Support for v1 serialization format has been dropped".to_string(),
+        ))
+    } else {
+        match try_deserialize(bytes) {
+            Ok(Some(entry)) => Ok(entry),
+            Ok(None) => Err((version, format!("Unknown serialization version: {version}"))),
+            Err(err) => Err((version, err)),
+        }
+    }
+}
+
+/// A stored oplog entry which is only decoded on demand
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawOplogEntry {
+    bytes: Bytes,
+}
+
+impl RawOplogEntry {
+    pub fn new(bytes: Bytes) -> Self {
+        Self { bytes }
+    }
+
+    /// Encodes an already decoded entry, for oplog layers not storing the entries one by one
+    pub fn encode(entry: &OplogEntry) -> Self {
+        Self::new(
+            serialize(entry).unwrap_or_else(|err| panic!("failed to serialize oplog entry: {err}")),
+        )
+    }
+
+    pub fn bytes(&self) -> &Bytes {
+        &self.bytes
+    }
+
+    pub fn into_bytes(self) -> Bytes {
+        self.bytes
+    }
+
+    /// The serialization version recorded in the entry's first byte
+    pub fn serialization_version(&self) -> u8 {
+        self.bytes.first().copied().unwrap_or_default()
+    }
+
+    /// Decodes only the variant and the timestamp of the entry
+    pub fn header(&self) -> Result<OplogEntryHeader, String> {
+        OplogEntryHeader::decode(&self.bytes)
+    }
+
+    /// Decodes the whole entry. On failure returns the recorded serialization version and the
+    /// error message.
+    pub fn decode(&self) -> Result<OplogEntry, (u8, String)> {
+        try_decode_oplog_entry(&self.bytes)
+    }
+}
+
 /// A top-level service for managing worker oplogs
 ///
 /// For write access an oplog has to be opened with the `open` function (or if it doesn't exist,
@@ -89,6 +168,19 @@ pub trait OplogService: Debug {
         n: u64,
     ) -> BTreeMap<OplogIndex, OplogEntry>;
 
+    /// Reads a section of the oplog like `read`, restoring the log entries moved out of the oplog
+    /// by a log retention policy. Used by the APIs querying the oplog, not by recovery.
+    ///
+    /// The default implementation is the same as `read`, as it has no separate log storage.
+    async fn read_with_logs(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        idx: OplogIndex,
+        n: u64,
+    ) -> BTreeMap<OplogIndex, OplogEntry> {
+        self.read(owned_worker_id, idx, n).await
+    }
+
     /// Reads an inclusive range of entries from the oplog
     async fn read_range(
         &self,
@@ -120,6 +212,54 @@ pub trait OplogService: Debug {
             .await
     }
 
+    /// Reads an inclusive range of entries from the oplog, reporting the entries which cannot be
+    /// decoded separately instead of failing the whole read.
+    ///
+    /// The default implementation is not able to detect decoding failures.
+    async fn read_range_checked(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        start_idx: OplogIndex,
+        last_idx: OplogIndex,
+    ) -> (BTreeMap<OplogIndex, OplogEntry>, Vec<OplogDecodeFailure>) {
+        (
+            self.read_range(owned_worker_id, start_idx, last_idx).await,
+            Vec::new(),
+        )
+    }
+
+    /// Reads an inclusive range of entries from the oplog without decoding them.
+    ///
+    /// Implementations storing the entries one by one return the stored bytes as they are, the
+    /// default implementation reads the decoded entries and encodes them again.
+    async fn read_raw_range(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        start_idx: OplogIndex,
+        last_idx: OplogIndex,
+    ) -> BTreeMap<OplogIndex, RawOplogEntry> {
+        self.read_range(owned_worker_id, start_idx, last_idx)
+            .await
+            .into_iter()
+            .map(|(idx, entry)| (idx, RawOplogEntry::encode(&entry)))
+            .collect()
+    }
+
+    /// Reads the markers of the invocation boundaries and component version changes in an
+    /// inclusive range of the oplog, without reading the entries.
+    ///
+    /// Returns `None` if the oplog has no complete marker index, for example because it was
+    /// created before the markers were recorded or entries were appended to it without markers,
+    /// in which case the entries have to be scanned.
+    async fn read_markers(
+        &self,
+        _owned_worker_id: &OwnedWorkerId,
+        _start_idx: OplogIndex,
+        _last_idx: OplogIndex,
+    ) -> Option<BTreeMap<OplogIndex, OplogMarker>> {
+        None
+    }
+
     /// Checks whether the oplog exists in the oplog, without opening it
     async fn exists(&self, owned_worker_id: &OwnedWorkerId) -> bool;
 
@@ -177,6 +317,12 @@ pub trait Oplog: Any + Debug {
     /// Returns the current oplog index
     async fn current_oplog_index(&self) -> OplogIndex;
 
+    /// Returns the index of the last entry already written to the underlying storage, which can be
+    /// lower than the current oplog index if commits are batched
+    async fn last_committed_oplog_index(&self) -> OplogIndex {
+        self.current_oplog_index().await
+    }
+
     /// Waits until indexed store writes all changes into at least `replicas` replicas (or the maximum
     /// available).
     /// Returns true if the maximum possible number of replicas is reached within the timeout,
@@ -199,6 +345,14 @@ pub trait Oplog: Any + Debug {
     /// Uploads a big oplog payload and returns a reference to it
     async fn upload_payload(&self, data: &[u8]) -> Result<OplogPayload, String>;
 
+    /// Uploads an oplog payload, storing it in blob storage if it is bigger than either
+    /// `max_inline_size` or the configured maximum payload size
+    async fn upload_payload_with_limit(
+        &self,
+        data: &[u8],
+        max_inline_size: usize,
+    ) -> Result<OplogPayload, String>;
+
     /// Downloads a big oplog payload by its reference
     async fn download_payload(&self, payload: &OplogPayload) -> Result<Bytes, String>;
 }
@@ -221,12 +375,41 @@ pub trait OplogOps: Oplog {
         request: &I,
         response: &O,
         wrapped_function_type: WrappedFunctionType,
+    ) -> Result<OplogEntry, String> {
+        self.add_imported_function_invoked_with_response_limit(
+            function_name,
+            request,
+            response,
+            wrapped_function_type,
+            None,
+        )
+        .await
+    }
+
+    /// Same as `add_imported_function_invoked`, but the response is stored in blob storage if it
+    /// is bigger than `max_inline_response_size`
+    async fn add_imported_function_invoked_with_response_limit<
+        I: Encode + Sync,
+        O: Encode + Sync,
+    >(
+        &self,
+        function_name: String,
+        request: &I,
+        response: &O,
+        wrapped_function_type: WrappedFunctionType,
+        max_inline_response_size: Option<usize>,
     ) -> Result<OplogEntry, String> {
         let serialized_request = serialize(request)?.to_vec();
         let serialized_response = serialize(response)?.to_vec();
 
         let request_payload: OplogPayload = self.upload_payload(&serialized_request).await?;
-        let response_payload = self.upload_payload(&serialized_response).await?;
+        let response_payload = match max_inline_response_size {
+            Some(max_inline_size) => {
+                self.upload_payload_with_limit(&serialized_response, max_inline_size)
+                    .await?
+            }
+            None => self.upload_payload(&serialized_response).await?,
+        };
         let entry = OplogEntry::ImportedFunctionInvoked {
             timestamp: Timestamp::now_utc(),
             function_name,
@@ -13,9 +13,9 @@
 // limitations under the License.
 
 use std::cmp::min;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::{Debug, Formatter};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use async_trait::async_trait;
@@ -27,14 +27,18 @@ use tracing::{debug, error, info, warn, Instrument};
 
 use crate::error::GolemError;
 use golem_common::model::oplog::{OplogEntry, OplogIndex, OplogPayload};
-use golem_common::model::{AccountId, ComponentId, ComponentType, OwnedWorkerId, ScanCursor};
+use golem_common::model::{
+    AccountId, ComponentId, ComponentType, OwnedWorkerId, ScanCursor, Timestamp,
+};
 
 use crate::services::oplog::ephemeral::EphemeralOplog;
+use crate::services::oplog::logs::LogArchiveService;
 use crate::services::oplog::multilayer::BackgroundTransferMessage::{
-    TransferFromLower, TransferFromPrimary,
+    ApplyLogRetention, TransferFromLower, TransferFromPrimary,
 };
 use crate::services::oplog::{
-    downcast_oplog, CommitLevel, OpenOplogs, Oplog, OplogConstructor, OplogService,
+    downcast_oplog, CommitLevel, OpenOplogs, Oplog, OplogConstructor, OplogDecodeFailure,
+    OplogMarker, OplogService, RawOplogEntry,
 };
 
 #[async_trait]
@@ -108,6 +112,198 @@ pub trait OplogArchive: Debug {
 
     /// Gets the last index in this oplog archive
     async fn get_last_index(&self) -> OplogIndex;
+
+    /// Replaces already archived entries with new ones, keeping their indices. Returns false if
+    /// the archive does not support rewriting its entries.
+    async fn replace(&self, _entries: Vec<(OplogIndex, OplogEntry)>) -> bool {
+        false
+    }
+}
+
+/// Archival of primary oplog entries based on their age, in addition to the entry count limit.
+///
+/// When the oldest entry in the primary oplog gets older than `limit + bucket`, all entries
+/// older than `limit` are moved to the next layer. This way busy workers with long histories
+/// keep their primary oplog small, while a transfer is triggered at most once per `bucket`.
+#[derive(Debug, Clone, Default)]
+pub struct OplogArchiveAgePolicy {
+    default_limit: Option<Duration>,
+    component_limits: HashMap<ComponentId, Duration>,
+    bucket: Duration,
+}
+
+impl OplogArchiveAgePolicy {
+    pub fn new(
+        default_limit: Option<Duration>,
+        component_limits: HashMap<ComponentId, Duration>,
+        bucket: Duration,
+    ) -> Self {
+        Self {
+            default_limit,
+            component_limits,
+            bucket,
+        }
+    }
+
+    /// No age based archival, entries are only moved by the entry count limit or explicit archiving
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    pub fn limit_for(&self, component_id: &ComponentId) -> Option<Duration> {
+        self.component_limits
+            .get(component_id)
+            .copied()
+            .or(self.default_limit)
+    }
+}
+
+/// Moves the log entries (stdout, stderr and log calls) older than `age_limit` out of the oplog
+/// into a separate log archive.
+///
+/// Log entries are moved when they are transferred between the oplog layers, and every open oplog
+/// runs a retention pass on its last layer every `interval`, as entries are never transferred from
+/// there. The pass can only strip the entries if the last layer supports `OplogArchive::replace`.
+///
+/// The oplog keeps the moved entries without their context and message, so recovery still sees
+/// the same oplog indices, while `OplogService::read_with_logs` restores them from the log archive.
+#[derive(Debug, Clone)]
+pub struct LogRetentionPolicy {
+    age_limit: Duration,
+    interval: Duration,
+    archive: Arc<dyn LogArchiveService + Send + Sync>,
+}
+
+impl LogRetentionPolicy {
+    const PASS_BATCH_SIZE: u64 = 1024;
+
+    pub fn new(
+        age_limit: Duration,
+        interval: Duration,
+        archive: Arc<dyn LogArchiveService + Send + Sync>,
+    ) -> Self {
+        Self {
+            age_limit,
+            interval,
+            archive,
+        }
+    }
+
+    fn cutoff(&self) -> Timestamp {
+        Timestamp::from(
+            Timestamp::now_utc()
+                .to_millis()
+                .saturating_sub(self.age_limit.as_millis() as u64),
+        )
+    }
+
+    /// Stores the log entries older than the age limit in the log archive, and returns the entries
+    /// with these log entries stripped
+    async fn apply(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        entries: Vec<(OplogIndex, OplogEntry)>,
+    ) -> Vec<(OplogIndex, OplogEntry)> {
+        let cutoff = self.cutoff();
+
+        let mut moved = Vec::new();
+        let entries = entries
+            .into_iter()
+            .map(|(idx, entry)| match strip_log(&entry, cutoff) {
+                Some(stripped) => {
+                    moved.push((idx, entry));
+                    (idx, stripped)
+                }
+                None => (idx, entry),
+            })
+            .collect();
+
+        self.archive_logs(owned_worker_id, moved).await;
+        entries
+    }
+
+    /// Moves the log entries older than the age limit out of an oplog archive layer, checking the
+    /// entries after `checked_up_to` only. Returns the index up to which all the entries are older
+    /// than the age limit, so the next pass can continue from there.
+    async fn apply_to_archive(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        archive: &Arc<dyn OplogArchive + Send + Sync>,
+        mut checked_up_to: OplogIndex,
+    ) -> OplogIndex {
+        let cutoff = self.cutoff();
+        let last_idx = archive.get_last_index().await;
+
+        while checked_up_to < last_idx {
+            let start_idx = checked_up_to.next();
+            let end_idx = min(start_idx.range_end(Self::PASS_BATCH_SIZE), last_idx);
+            let entries = archive.read_range(start_idx, end_idx).await;
+
+            let mut moved = Vec::new();
+            let mut stripped = Vec::new();
+            let mut reached_cutoff = false;
+            for (idx, entry) in entries {
+                if entry.timestamp() >= cutoff {
+                    reached_cutoff = true;
+                    break;
+                }
+                if let Some(stripped_entry) = strip_log(&entry, cutoff) {
+                    moved.push((idx, entry));
+                    stripped.push((idx, stripped_entry));
+                }
+                checked_up_to = idx;
+            }
+
+            if !stripped.is_empty() {
+                self.archive_logs(owned_worker_id, moved).await;
+                if !archive.replace(stripped).await {
+                    debug!("The last oplog layer does not support removing the old log entries");
+                    return last_idx;
+                }
+            }
+
+            if reached_cutoff {
+                break;
+            }
+            checked_up_to = end_idx;
+        }
+
+        checked_up_to
+    }
+
+    async fn archive_logs(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        moved: Vec<(OplogIndex, OplogEntry)>,
+    ) {
+        if !moved.is_empty() {
+            debug!(
+                "Moving {} log entries older than {:?} to the log archive",
+                moved.len(),
+                self.age_limit
+            );
+            self.archive.append(owned_worker_id, moved).await;
+        }
+    }
+}
+
+/// Returns the stripped version of a log entry if it is older than the cutoff and not stripped yet
+fn strip_log(entry: &OplogEntry, cutoff: Timestamp) -> Option<OplogEntry> {
+    match entry {
+        OplogEntry::Log {
+            timestamp, level, ..
+        } if *timestamp < cutoff && !is_stripped_log(entry) => Some(OplogEntry::Log {
+            timestamp: *timestamp,
+            level: *level,
+            context: String::new(),
+            message: String::new(),
+        }),
+        _ => None,
+    }
+}
+
+fn is_stripped_log(entry: &OplogEntry) -> bool {
+    matches!(entry, OplogEntry::Log { context, message, .. } if context.is_empty() && message.is_empty())
 }
 
 #[derive(Debug)]
@@ -119,6 +315,9 @@ pub struct MultiLayerOplogService {
 
     entry_count_limit: u64,
     max_operations_before_commit_ephemeral: u64,
+    in_memory_ephemeral_oplogs: bool,
+    archive_age_policy: OplogArchiveAgePolicy,
+    log_retention: Option<LogRetentionPolicy>,
 }
 
 impl MultiLayerOplogService {
@@ -127,6 +326,7 @@ impl MultiLayerOplogService {
         lower: NEVec<Arc<dyn OplogArchiveService + Send + Sync>>,
         entry_count_limit: u64,
         max_operations_before_commit_ephemeral: u64,
+        archive_age_policy: OplogArchiveAgePolicy,
     ) -> Self {
         Self {
             primary,
@@ -134,8 +334,25 @@ impl MultiLayerOplogService {
             oplogs: OpenOplogs::new("multi-layer oplog"),
             entry_count_limit,
             max_operations_before_commit_ephemeral,
+            in_memory_ephemeral_oplogs: false,
+            archive_age_policy,
+            log_retention: None,
         }
     }
+
+    /// Keeps the oplog entries of ephemeral workers in memory instead of writing them to the
+    /// last archive layer. Only the initial entry is stored, so the worker's metadata can still
+    /// be looked up while it is running.
+    pub fn with_in_memory_ephemeral_oplogs(mut self) -> Self {
+        self.in_memory_ephemeral_oplogs = true;
+        self
+    }
+
+    /// Moves the old log entries out of the oplog into a separate log archive
+    pub fn with_log_retention(mut self, log_retention: LogRetentionPolicy) -> Self {
+        self.log_retention = Some(log_retention);
+        self
+    }
 }
 
 impl Clone for MultiLayerOplogService {
@@ -146,6 +363,9 @@ impl Clone for MultiLayerOplogService {
             oplogs: self.oplogs.clone(),
             entry_count_limit: self.entry_count_limit,
             max_operations_before_commit_ephemeral: self.max_operations_before_commit_ephemeral,
+            in_memory_ephemeral_oplogs: self.in_memory_ephemeral_oplogs,
+            archive_age_policy: self.archive_age_policy.clone(),
+            log_retention: self.log_retention.clone(),
         }
     }
 }
@@ -229,6 +449,7 @@ impl OplogConstructor for CreateOplogConstructor {
                         self.owned_worker_id,
                         self.last_oplog_index,
                         self.service.max_operations_before_commit_ephemeral,
+                        self.service.in_memory_ephemeral_oplogs,
                         primary,
                         target,
                         close,
@@ -304,6 +525,9 @@ impl OplogService for MultiLayerOplogService {
         for layer in &self.lower {
             layer.delete(owned_worker_id).await
         }
+        if let Some(log_retention) = &self.log_retention {
+            log_retention.archive.delete(owned_worker_id).await
+        }
     }
 
     async fn read(
@@ -355,6 +579,109 @@ impl OplogService for MultiLayerOplogService {
         result
     }
 
+    async fn read_with_logs(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        idx: OplogIndex,
+        n: u64,
+    ) -> BTreeMap<OplogIndex, OplogEntry> {
+        let mut result = self.read(owned_worker_id, idx, n).await;
+
+        if let Some(log_retention) = &self.log_retention {
+            let stripped_range = result
+                .iter()
+                .filter(|(_, entry)| is_stripped_log(entry))
+                .map(|(idx, _)| *idx)
+                .fold(None, |range, idx| match range {
+                    None => Some((idx, idx)),
+                    Some((first, _)) => Some((first, idx)),
+                });
+
+            if let Some((first_idx, last_idx)) = stripped_range {
+                let logs = log_retention
+                    .archive
+                    .read_range(owned_worker_id, first_idx, last_idx)
+                    .await;
+                result.extend(logs);
+            }
+        }
+
+        result
+    }
+
+    async fn read_range_checked(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        start_idx: OplogIndex,
+        last_idx: OplogIndex,
+    ) -> (BTreeMap<OplogIndex, OplogEntry>, Vec<OplogDecodeFailure>) {
+        // Only the primary layer stores entries individually, lower layers are read as usual
+        let (mut result, failures) = self
+            .primary
+            .read_range_checked(owned_worker_id, start_idx, last_idx)
+            .await;
+
+        let first_in_primary = result
+            .keys()
+            .copied()
+            .chain(failures.iter().map(|failure| failure.oplog_index))
+            .min()
+            .unwrap_or(last_idx.next());
+
+        if first_in_primary > start_idx {
+            let lower = self
+                .read_range(owned_worker_id, start_idx, first_in_primary.previous())
+                .await;
+            result.extend(lower);
+        }
+
+        (result, failures)
+    }
+
+    async fn read_raw_range(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        start_idx: OplogIndex,
+        last_idx: OplogIndex,
+    ) -> BTreeMap<OplogIndex, RawOplogEntry> {
+        // Only the primary layer stores entries individually, entries of the lower layers are
+        // encoded again
+        let mut result = self
+            .primary
+            .read_raw_range(owned_worker_id, start_idx, last_idx)
+            .await;
+
+        let first_in_primary = result
+            .first_key_value()
+            .map(|(idx, _)| *idx)
+            .unwrap_or(last_idx.next());
+
+        if start_idx <= last_idx && first_in_primary > start_idx {
+            let lower = self
+                .read_range(owned_worker_id, start_idx, first_in_primary.previous())
+                .await;
+            result.extend(
+                lower
+                    .into_iter()
+                    .map(|(idx, entry)| (idx, RawOplogEntry::encode(&entry))),
+            );
+        }
+
+        result
+    }
+
+    async fn read_markers(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        start_idx: OplogIndex,
+        last_idx: OplogIndex,
+    ) -> Option<BTreeMap<OplogIndex, OplogMarker>> {
+        // The markers are kept by the primary layer even after the entries are moved down
+        self.primary
+            .read_markers(owned_worker_id, start_idx, last_idx)
+            .await
+    }
+
     async fn exists(&self, owned_worker_id: &OwnedWorkerId) -> bool {
         if self.primary.exists(owned_worker_id).await {
             return true;
@@ -449,6 +776,10 @@ pub struct MultiLayerOplog {
     transfer_fiber: Option<tokio::task::JoinHandle<()>>,
     transfer: UnboundedSender<BackgroundTransferMessage>,
     primary_length: AtomicU64,
+    // Age limit of the entries in the primary oplog, if age based archival is enabled for the component
+    archive_age_limit: Option<Duration>,
+    // Timestamp of the oldest entry in the primary oplog, or a lower bound of it after an age based transfer
+    oldest_primary_timestamp: Mutex<Option<Timestamp>>,
     close_fn: Option<Box<dyn FnOnce() + Send + Sync>>,
 }
 
@@ -494,6 +825,25 @@ impl MultiLayerOplog {
 
         let initial_primary_length = primary.length().await;
 
+        let archive_age_limit = multi_layer_oplog_service
+            .archive_age_policy
+            .limit_for(&owned_worker_id.worker_id.component_id);
+
+        let oldest_primary_timestamp = if archive_age_limit.is_some() && initial_primary_length > 0
+        {
+            let current_idx: u64 = primary.current_oplog_index().await.into();
+            let first_idx = OplogIndex::from_u64(current_idx + 1 - initial_primary_length);
+            multi_layer_oplog_service
+                .primary
+                .read(&owned_worker_id, first_idx, 1)
+                .await
+                .into_values()
+                .next()
+                .map(|entry| entry.timestamp())
+        } else {
+            None
+        };
+
         Self {
             owned_worker_id,
             primary,
@@ -502,10 +852,36 @@ impl MultiLayerOplog {
             transfer_fiber: Some(transfer_fiber),
             transfer: tx,
             primary_length: AtomicU64::new(initial_primary_length),
+            archive_age_limit,
+            oldest_primary_timestamp: Mutex::new(oldest_primary_timestamp),
             close_fn: Some(close),
         }
     }
 
+    /// Enqueues the transfer of the primary oplog entries older than the configured age limit,
+    /// if the oldest entry is already older than the age limit plus the bucket size
+    fn enqueue_age_based_transfer(&self, current_idx: OplogIndex) {
+        if let Some(age_limit) = self.archive_age_limit {
+            let bucket = self.multi_layer_oplog_service.archive_age_policy.bucket;
+            let now = Timestamp::now_utc().to_millis();
+            let cutoff = now.saturating_sub(age_limit.as_millis() as u64);
+
+            let mut oldest = self.oldest_primary_timestamp.lock().unwrap();
+            if let Some(oldest_timestamp) = *oldest {
+                if oldest_timestamp.to_millis() + (bucket.as_millis() as u64) < cutoff {
+                    debug!("Enqueuing transfer of oplog entries older than {age_limit:?} from the primary oplog to the next layer up to {current_idx}");
+                    let _ = self.transfer.send(TransferFromPrimary {
+                        last_transferred_idx: current_idx,
+                        older_than: Some(Timestamp::from(cutoff)),
+                        keep_alive: None,
+                    });
+                    // All the remaining entries are newer than the cutoff
+                    *oldest = Some(Timestamp::from(cutoff));
+                }
+            }
+        }
+    }
+
     async fn background_transfer(
         owned_worker_id: OwnedWorkerId,
         primary: Arc<dyn Oplog + Send + Sync>,
@@ -515,10 +891,40 @@ impl MultiLayerOplog {
     ) {
         // TODO: monitor queue length
 
-        while let Some(msg) = rx.recv().await {
+        // All the entries of the last layer up to this index are already older than the log
+        // retention age limit, and got checked by a previous retention pass
+        let mut retention_checked_up_to = OplogIndex::NONE;
+        let mut retention_interval =
+            multi_layer_oplog_service
+                .log_retention
+                .as_ref()
+                .map(|log_retention| {
+                    tokio::time::interval_at(
+                        tokio::time::Instant::now() + log_retention.interval,
+                        log_retention.interval,
+                    )
+                });
+
+        loop {
+            let msg = tokio::select! {
+                msg = rx.recv() => match msg {
+                    Some(msg) => msg,
+                    None => break,
+                },
+                _ = async {
+                    match &mut retention_interval {
+                        Some(interval) => {
+                            interval.tick().await;
+                        }
+                        None => std::future::pending::<()>().await,
+                    }
+                } => ApplyLogRetention { done: None },
+            };
+
             match msg {
                 TransferFromPrimary {
                     last_transferred_idx,
+                    older_than,
                     mut keep_alive,
                 } => {
                     info!("Transferring oplog entries up to index {last_transferred_idx} of the primary oplog to the next layer");
@@ -527,6 +933,7 @@ impl MultiLayerOplog {
                     let transfer = BackgroundTransferFromPrimary::new(
                         owned_worker_id.clone(),
                         last_transferred_idx,
+                        older_than,
                         multi_layer_oplog_service.clone(),
                         primary.clone(),
                         lower.clone(),
@@ -546,8 +953,10 @@ impl MultiLayerOplog {
                     debug!("Reading entries from oplog layer {source}");
 
                     let transfer = BackgroundTransferBetweenLowers::new(
+                        owned_worker_id.clone(),
                         source,
                         last_transferred_idx,
+                        multi_layer_oplog_service.log_retention.clone(),
                         lower.clone(),
                     );
                     let result = transfer.run().await;
@@ -557,6 +966,21 @@ impl MultiLayerOplog {
                     }
                     let _ = keep_alive.take();
                 }
+                ApplyLogRetention { done } => {
+                    if let Some(log_retention) = &multi_layer_oplog_service.log_retention {
+                        debug!("Applying the log retention policy on the last oplog layer");
+                        retention_checked_up_to = log_retention
+                            .apply_to_archive(
+                                &owned_worker_id,
+                                lower.last(),
+                                retention_checked_up_to,
+                            )
+                            .await;
+                    }
+                    if let Some(done) = done {
+                        let _ = done.send(());
+                    }
+                }
             }
         }
     }
@@ -566,15 +990,31 @@ impl MultiLayerOplog {
         Some(Self::archive(this).await)
     }
 
+    /// Runs a log retention pass on the last oplog layer after the already enqueued transfers
+    /// finished, without waiting for the next periodic pass. Returns `None` if the oplog is not
+    /// a multi-layer oplog.
+    pub async fn try_apply_log_retention(this: &Arc<dyn Oplog + Send + Sync>) -> Option<()> {
+        let this = downcast_oplog::<MultiLayerOplog>(this)?;
+        let (done, done_rx) = tokio::sync::oneshot::channel();
+        this.transfer
+            .send(ApplyLogRetention { done: Some(done) })
+            .expect("Failed to enqueue the log retention pass");
+        let _ = done_rx.await;
+        Some(())
+    }
+
     async fn archive(this: Arc<Self>) -> bool {
         if this.primary_length.get() > 0 {
             // transferring the whole primary oplog to the next layer
+            this.primary.commit(CommitLevel::Immediate).await;
             this.transfer
                 .send(TransferFromPrimary {
                     last_transferred_idx: this.primary.current_oplog_index().await,
+                    older_than: None,
                     keep_alive: Some(this.clone()),
                 })
                 .expect("Failed to enqueue transfer of primary oplog entries");
+            *this.oldest_primary_timestamp.lock().unwrap() = None;
 
             // If there are more layers to transfer from, return true
             this.lower.len().get() > 1
@@ -634,6 +1074,12 @@ impl Debug for MultiLayerOplog {
 #[async_trait]
 impl Oplog for MultiLayerOplog {
     async fn add(&self, entry: OplogEntry) {
+        if self.archive_age_limit.is_some() {
+            self.oldest_primary_timestamp
+                .lock()
+                .unwrap()
+                .get_or_insert(entry.timestamp());
+        }
         self.primary.add(entry).await;
         self.primary_length.inc_by(1);
     }
@@ -648,14 +1094,20 @@ impl Oplog for MultiLayerOplog {
         self.primary.commit(level).await;
         let count = self.primary_length.get();
         if count >= self.multi_layer_oplog_service.entry_count_limit {
-            let current_idx = self.primary.current_oplog_index().await;
+            // Only the entries already written to the primary oplog can be transferred
+            let current_idx = self.primary.last_committed_oplog_index().await;
             debug!("Enqueuing transfer of {count} oplog entries from the primary oplog to the next layer up to {current_idx}");
             let _ = self.transfer.send(TransferFromPrimary {
                 last_transferred_idx: current_idx,
+                older_than: None,
                 keep_alive: None,
             });
             // Resetting the counter, otherwise it would trigger additional transfers until the background process finishes
             self.primary_length.set(0);
+            *self.oldest_primary_timestamp.lock().unwrap() = None;
+        } else if self.archive_age_limit.is_some() {
+            let current_idx = self.primary.last_committed_oplog_index().await;
+            self.enqueue_age_based_transfer(current_idx);
         }
     }
 
@@ -663,6 +1115,10 @@ impl Oplog for MultiLayerOplog {
         self.primary.current_oplog_index().await
     }
 
+    async fn last_committed_oplog_index(&self) -> OplogIndex {
+        self.primary.last_committed_oplog_index().await
+    }
+
     async fn wait_for_replicas(&self, replicas: u8, timeout: Duration) -> bool {
         self.primary.wait_for_replicas(replicas, timeout).await
     }
@@ -688,6 +1144,16 @@ impl Oplog for MultiLayerOplog {
         self.primary.upload_payload(data).await
     }
 
+    async fn upload_payload_with_limit(
+        &self,
+        data: &[u8],
+        max_inline_size: usize,
+    ) -> Result<OplogPayload, String> {
+        self.primary
+            .upload_payload_with_limit(data, max_inline_size)
+            .await
+    }
+
     async fn download_payload(&self, payload: &OplogPayload) -> Result<Bytes, String> {
         self.primary.download_payload(payload).await
     }
@@ -697,6 +1163,8 @@ impl Oplog for MultiLayerOplog {
 enum BackgroundTransferMessage {
     TransferFromPrimary {
         last_transferred_idx: OplogIndex,
+        // If set, only the prefix of entries older than this timestamp is transferred
+        older_than: Option<Timestamp>,
         keep_alive: Option<Arc<dyn Oplog + Send + Sync>>,
     },
     TransferFromLower {
@@ -704,6 +1172,9 @@ enum BackgroundTransferMessage {
         last_transferred_idx: OplogIndex,
         keep_alive: Option<Arc<dyn Oplog + Send + Sync>>,
     },
+    ApplyLogRetention {
+        done: Option<tokio::sync::oneshot::Sender<()>>,
+    },
 }
 
 #[async_trait]
@@ -801,11 +1272,16 @@ impl OplogArchive for WrappedOplogArchive {
     async fn get_last_index(&self) -> OplogIndex {
         self.archive.get_last_index().await
     }
+
+    async fn replace(&self, entries: Vec<(OplogIndex, OplogEntry)>) -> bool {
+        self.archive.replace(entries).await
+    }
 }
 
 struct BackgroundTransferFromPrimary {
     owned_worker_id: OwnedWorkerId,
     last_transferred_idx: OplogIndex,
+    older_than: Option<Timestamp>,
     multi_layer_oplog_service: MultiLayerOplogService,
     primary: Arc<dyn Oplog + Send + Sync>,
     lower: NEVec<Arc<dyn OplogArchive + Send + Sync>>,
@@ -815,6 +1291,7 @@ impl BackgroundTransferFromPrimary {
     pub fn new(
         owned_worker_id: OwnedWorkerId,
         last_transferred_idx: OplogIndex,
+        older_than: Option<Timestamp>,
         multi_layer_oplog_service: MultiLayerOplogService,
         primary: Arc<dyn Oplog + Send + Sync>,
         lower: NEVec<Arc<dyn OplogArchive + Send + Sync>>,
@@ -822,6 +1299,7 @@ impl BackgroundTransferFromPrimary {
         Self {
             owned_worker_id,
             last_transferred_idx,
+            older_than,
             multi_layer_oplog_service,
             primary,
             lower,
@@ -832,15 +1310,49 @@ impl BackgroundTransferFromPrimary {
 #[async_trait]
 impl BackgroundTransfer for BackgroundTransferFromPrimary {
     async fn read_source(&self) -> Vec<(OplogIndex, OplogEntry)> {
-        self.multi_layer_oplog_service
-            .primary
-            .read_prefix(&self.owned_worker_id, self.last_transferred_idx)
-            .await
-            .into_iter()
-            .collect()
+        match &self.older_than {
+            Some(older_than) => {
+                // Only decoding the headers to find the entries older than the limit, the newer
+                // ones remain in the primary oplog
+                let raw_entries = self
+                    .multi_layer_oplog_service
+                    .primary
+                    .read_raw_range(
+                        &self.owned_worker_id,
+                        OplogIndex::INITIAL,
+                        self.last_transferred_idx,
+                    )
+                    .await;
+                let mut result = Vec::new();
+                for (idx, raw_entry) in raw_entries {
+                    let header = raw_entry.header().unwrap_or_else(|err| {
+                        panic!("failed to decode oplog entry header at {idx}: {err}")
+                    });
+                    if header.timestamp >= *older_than {
+                        break;
+                    }
+                    let entry = raw_entry.decode().unwrap_or_else(|(_, err)| {
+                        panic!("failed to decode oplog entry at {idx}: {err}")
+                    });
+                    result.push((idx, entry));
+                }
+                result
+            }
+            None => self
+                .multi_layer_oplog_service
+                .primary
+                .read_prefix(&self.owned_worker_id, self.last_transferred_idx)
+                .await
+                .into_iter()
+                .collect(),
+        }
     }
 
     async fn append_target(&self, entries: Vec<(OplogIndex, OplogEntry)>) {
+        let entries = match &self.multi_layer_oplog_service.log_retention {
+            Some(log_retention) => log_retention.apply(&self.owned_worker_id, entries).await,
+            None => entries,
+        };
         self.lower.head.append(entries).await
     }
 
@@ -850,22 +1362,28 @@ impl BackgroundTransfer for BackgroundTransferFromPrimary {
 }
 
 struct BackgroundTransferBetweenLowers {
+    owned_worker_id: OwnedWorkerId,
     last_transferred_idx: OplogIndex,
+    log_retention: Option<LogRetentionPolicy>,
     source_layer: Arc<dyn OplogArchive + Send + Sync>,
     target_layer: Arc<dyn OplogArchive + Send + Sync>,
 }
 
 impl BackgroundTransferBetweenLowers {
     pub fn new(
+        owned_worker_id: OwnedWorkerId,
         source: usize,
         last_transferred_idx: OplogIndex,
+        log_retention: Option<LogRetentionPolicy>,
         lower: NEVec<Arc<dyn OplogArchive + Send + Sync>>,
     ) -> Self {
         let source_layer = lower[source].clone();
         let target_layer = lower[source + 1].clone();
 
         Self {
+            owned_worker_id,
             last_transferred_idx,
+            log_retention,
             source_layer,
             target_layer,
         }
@@ -883,6 +1401,10 @@ impl BackgroundTransfer for BackgroundTransferBetweenLowers {
     }
 
     async fn append_target(&self, entries: Vec<(OplogIndex, OplogEntry)>) {
+        let entries = match &self.log_retention {
+            Some(log_retention) => log_retention.apply(&self.owned_worker_id, entries).await,
+            None => entries,
+        };
         self.target_layer.append(entries).await
     }
 
@@ -14,7 +14,12 @@
 
 use crate::error::GolemError;
 use crate::metrics::oplog::record_oplog_call;
-use crate::services::oplog::{CommitLevel, OpenOplogs, Oplog, OplogConstructor, OplogService};
+use crate::services::encryption::EncryptionService;
+use crate::services::golem_config::OplogDurability;
+use crate::services::oplog::{
+    CommitLevel, OpenOplogs, Oplog, OplogConstructor, OplogDecodeFailure, OplogMarker,
+    OplogService, RawOplogEntry,
+};
 use crate::storage::blob::{BlobStorage, BlobStorageNamespace};
 use crate::storage::indexed::{IndexedStorage, IndexedStorageLabelledApi, IndexedStorageNamespace};
 use async_mutex::Mutex;
@@ -22,14 +27,64 @@ use async_trait::async_trait;
 use bytes::Bytes;
 use golem_common::model::oplog::{OplogEntry, OplogIndex, OplogPayload, PayloadId};
 use golem_common::model::{
-    AccountId, ComponentId, ComponentType, OwnedWorkerId, ScanCursor, WorkerId,
+    AccountId, ComponentId, ComponentType, OwnedWorkerId, ScanCursor, WorkerId, WorkerName,
 };
-use std::collections::{BTreeMap, VecDeque};
+use golem_common::serialization::{deserialize, serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::fmt::{Debug, Formatter};
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
 use std::time::Duration;
-use tracing::error;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tracing::{error, warn, Instrument};
+
+/// Decides how the writes of each component's workers to the primary oplog are batched
+///
+/// With `OplogDurability::EveryEntry` the pending entries are written on every commit, before the
+/// worker continues. With `OplogDurability::Batched` only immediate commits write the pending
+/// entries, otherwise they are written when `max_batch_size` entries are pending or by a background
+/// flush every `flush_interval`.
+#[derive(Debug, Clone)]
+pub struct OplogDurabilityPolicy {
+    default_durability: OplogDurability,
+    component_durabilities: HashMap<ComponentId, OplogDurability>,
+    max_batch_size: usize,
+    flush_interval: Duration,
+}
+
+impl OplogDurabilityPolicy {
+    pub fn new(
+        default_durability: OplogDurability,
+        component_durabilities: HashMap<ComponentId, OplogDurability>,
+        max_batch_size: usize,
+        flush_interval: Duration,
+    ) -> Self {
+        Self {
+            default_durability,
+            component_durabilities,
+            max_batch_size,
+            flush_interval,
+        }
+    }
+
+    /// Every commit writes the pending entries for all components
+    pub fn every_entry() -> Self {
+        Self::new(
+            OplogDurability::EveryEntry,
+            HashMap::new(),
+            0,
+            Duration::ZERO,
+        )
+    }
+
+    pub fn durability_for(&self, component_id: &ComponentId) -> OplogDurability {
+        self.component_durabilities
+            .get(component_id)
+            .copied()
+            .unwrap_or(self.default_durability)
+    }
+}
 
 /// The primary oplog service implementation, suitable for direct use (top level of a multi-layered setup).
 ///
@@ -42,6 +97,8 @@ pub struct PrimaryOplogService {
     replicas: u8,
     max_operations_before_commit: u64,
     max_payload_size: usize,
+    encryption: Option<Arc<dyn EncryptionService + Send + Sync>>,
+    durability_policy: OplogDurabilityPolicy,
     oplogs: OpenOplogs,
 }
 
@@ -65,10 +122,24 @@ impl PrimaryOplogService {
             replicas,
             max_operations_before_commit,
             max_payload_size,
+            encryption: None,
+            durability_policy: OplogDurabilityPolicy::every_entry(),
             oplogs: OpenOplogs::new("primary oplog"),
         }
     }
 
+    /// Encrypts all oplog payloads with the owning account's data key
+    pub fn with_encryption(mut self, encryption: Arc<dyn EncryptionService + Send + Sync>) -> Self {
+        self.encryption = Some(encryption);
+        self
+    }
+
+    /// Batches the writes of the oplogs according to the given policy
+    pub fn with_durability_policy(mut self, durability_policy: OplogDurabilityPolicy) -> Self {
+        self.durability_policy = durability_policy;
+        self
+    }
+
     fn oplog_key(worker_id: &WorkerId) -> String {
         worker_id.to_redis_key()
     }
@@ -82,7 +153,7 @@ impl PrimaryOplogService {
         if key.starts_with(&redis_prefix) {
             let worker_name = &key[redis_prefix.len()..];
             WorkerId {
-                worker_name: worker_name.to_string(),
+                worker_name: WorkerName::from_existing(worker_name),
                 component_id: component_id.clone(),
             }
         } else {
@@ -91,6 +162,36 @@ impl PrimaryOplogService {
     }
 
     async fn upload_payload(
+        blob_storage: Arc<dyn BlobStorage + Send + Sync>,
+        encryption: Option<Arc<dyn EncryptionService + Send + Sync>>,
+        max_payload_size: usize,
+        owned_worker_id: &OwnedWorkerId,
+        data: &[u8],
+    ) -> Result<OplogPayload, String> {
+        match encryption {
+            Some(encryption) => {
+                let (nonce, ciphertext) = encryption
+                    .encrypt(&owned_worker_id.account_id, data)
+                    .await?;
+                let payload = Self::store_payload(
+                    blob_storage,
+                    max_payload_size,
+                    owned_worker_id,
+                    &ciphertext,
+                )
+                .await?;
+                Ok(OplogPayload::Encrypted {
+                    nonce,
+                    payload: Box::new(payload),
+                })
+            }
+            None => {
+                Self::store_payload(blob_storage, max_payload_size, owned_worker_id, data).await
+            }
+        }
+    }
+
+    async fn store_payload(
         blob_storage: Arc<dyn BlobStorage + Send + Sync>,
         max_payload_size: usize,
         owned_worker_id: &OwnedWorkerId,
@@ -123,6 +224,27 @@ impl PrimaryOplogService {
     }
 
     async fn download_payload(
+        blob_storage: Arc<dyn BlobStorage + Send + Sync>,
+        encryption: Option<Arc<dyn EncryptionService + Send + Sync>>,
+        owned_worker_id: &OwnedWorkerId,
+        payload: &OplogPayload,
+    ) -> Result<Bytes, String> {
+        match payload {
+            OplogPayload::Encrypted { nonce, payload } => {
+                let encryption = encryption.ok_or(format!(
+                    "Encrypted oplog payload found but encryption is not configured (worker: {owned_worker_id})"
+                ))?;
+                let ciphertext = Self::load_payload(blob_storage, owned_worker_id, payload).await?;
+                let data = encryption
+                    .decrypt(&owned_worker_id.account_id, nonce, &ciphertext)
+                    .await?;
+                Ok(Bytes::from(data))
+            }
+            _ => Self::load_payload(blob_storage, owned_worker_id, payload).await,
+        }
+    }
+
+    async fn load_payload(
         blob_storage: Arc<dyn BlobStorage + Send + Sync>,
         owned_worker_id: &OwnedWorkerId,
         payload: &OplogPayload,
@@ -146,6 +268,9 @@ impl PrimaryOplogService {
                     .await?
                     .ok_or(format!("Payload not found (worker: {owned_worker_id}, payload_id: {payload_id}, md5 hash: {md5_hash:02X?})"))
             }
+            OplogPayload::Encrypted { .. } => {
+                Err(format!("Nested encrypted oplog payload (worker: {owned_worker_id})"))
+            }
         }
     }
 }
@@ -174,15 +299,20 @@ impl OplogService for PrimaryOplogService {
             panic!("oplog for worker {owned_worker_id} already exists in indexed storage")
         }
 
-        self.indexed_storage
-            .with_entity("oplog", "create", "entry")
-            .append(IndexedStorageNamespace::OpLog, &key, 1, &initial_entry)
-            .await
-            .unwrap_or_else(|err| {
-                panic!(
-                    "failed to append initial oplog entry for worker {owned_worker_id} in indexed storage: {err}"
-                )
-            });
+        append_entry(
+            self.indexed_storage.as_ref(),
+            "create",
+            &key,
+            OplogIndex::INITIAL,
+            &initial_entry,
+            component_type == ComponentType::Durable,
+        )
+        .await
+        .unwrap_or_else(|err| {
+            panic!(
+                "failed to append initial oplog entry for worker {owned_worker_id} in indexed storage: {err}"
+            )
+        });
 
         self.open(owned_worker_id, OplogIndex::INITIAL, component_type)
             .await
@@ -192,7 +322,7 @@ impl OplogService for PrimaryOplogService {
         &self,
         owned_worker_id: &OwnedWorkerId,
         last_oplog_index: OplogIndex,
-        _component_type: ComponentType,
+        component_type: ComponentType,
     ) -> Arc<dyn Oplog + Send + Sync> {
         record_oplog_call("open");
 
@@ -204,9 +334,19 @@ impl OplogService for PrimaryOplogService {
                 CreateOplogConstructor::new(
                     self.indexed_storage.clone(),
                     self.blob_storage.clone(),
+                    self.encryption.clone(),
                     self.replicas,
                     self.max_operations_before_commit,
                     self.max_payload_size,
+                    OplogBatching {
+                        durability: self
+                            .durability_policy
+                            .durability_for(&owned_worker_id.worker_id.component_id),
+                        max_batch_size: self.durability_policy.max_batch_size,
+                        flush_interval: self.durability_policy.flush_interval,
+                    },
+                    // Ephemeral oplogs are never queried by invocations
+                    component_type == ComponentType::Durable,
                     key,
                     last_oplog_index,
                     owned_worker_id.clone(),
@@ -247,6 +387,19 @@ impl OplogService for PrimaryOplogService {
                     "failed to drop oplog for worker {owned_worker_id} in indexed storage: {err}"
                 )
             });
+
+        self.indexed_storage
+            .with("oplog", "delete")
+            .delete(
+                IndexedStorageNamespace::OplogMarkers,
+                &Self::oplog_key(&owned_worker_id.worker_id),
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!(
+                    "failed to drop oplog markers for worker {owned_worker_id} in indexed storage: {err}"
+                )
+            });
     }
 
     async fn read(
@@ -276,6 +429,112 @@ impl OplogService for PrimaryOplogService {
             .collect()
     }
 
+    async fn read_range_checked(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        start_idx: OplogIndex,
+        last_idx: OplogIndex,
+    ) -> (BTreeMap<OplogIndex, OplogEntry>, Vec<OplogDecodeFailure>) {
+        record_oplog_call("read_range_checked");
+
+        let mut entries = BTreeMap::new();
+        let mut failures = Vec::new();
+        for (idx, raw_entry) in self
+            .read_raw_range(owned_worker_id, start_idx, last_idx)
+            .await
+        {
+            match raw_entry.decode() {
+                Ok(entry) => {
+                    entries.insert(idx, entry);
+                }
+                Err((serialization_version, error)) => {
+                    failures.push(OplogDecodeFailure {
+                        oplog_index: idx,
+                        serialization_version,
+                        error,
+                    });
+                }
+            }
+        }
+        (entries, failures)
+    }
+
+    async fn read_raw_range(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        start_idx: OplogIndex,
+        last_idx: OplogIndex,
+    ) -> BTreeMap<OplogIndex, RawOplogEntry> {
+        record_oplog_call("read_raw_range");
+
+        self.indexed_storage
+            .with_entity("oplog", "read_raw", "entry")
+            .read_raw(
+                IndexedStorageNamespace::OpLog,
+                &Self::oplog_key(&owned_worker_id.worker_id),
+                start_idx.into(),
+                last_idx.into(),
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!(
+                    "failed to read oplog for worker {owned_worker_id} from indexed storage: {err}"
+                )
+            })
+            .into_iter()
+            .map(|(k, v)| (OplogIndex::from_u64(k), RawOplogEntry::new(v)))
+            .collect()
+    }
+
+    async fn read_markers(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        start_idx: OplogIndex,
+        last_idx: OplogIndex,
+    ) -> Option<BTreeMap<OplogIndex, OplogMarker>> {
+        record_oplog_call("read_markers");
+
+        let key = Self::oplog_key(&owned_worker_id.worker_id);
+        let storage = self
+            .indexed_storage
+            .with_entity("oplog", "read_markers", "marker");
+
+        // The marker of the `Create` entry is only missing if the markers are not recorded for
+        // the whole oplog. Otherwise the index is complete, as the markers are appended
+        // atomically with their entries, the missing ones are recorded when the oplog is opened
+        // and the index is dropped whenever the oplog is opened without recording them
+        let first_marker_idx = storage
+            .first_id(IndexedStorageNamespace::OplogMarkers, &key)
+            .await
+            .unwrap_or_else(|err| {
+                panic!(
+                    "failed to read oplog markers for worker {owned_worker_id} from indexed storage: {err}"
+                )
+            });
+        if first_marker_idx != Some(OplogIndex::INITIAL.into()) {
+            return None;
+        }
+
+        Some(
+            storage
+                .read(
+                    IndexedStorageNamespace::OplogMarkers,
+                    &key,
+                    start_idx.into(),
+                    last_idx.into(),
+                )
+                .await
+                .unwrap_or_else(|err| {
+                    panic!(
+                        "failed to read oplog markers for worker {owned_worker_id} from indexed storage: {err}"
+                    )
+                })
+                .into_iter()
+                .map(|(k, v): (u64, OplogMarker)| (OplogIndex::from_u64(k), v))
+                .collect(),
+        )
+    }
+
     async fn exists(&self, owned_worker_id: &OwnedWorkerId) -> bool {
         record_oplog_call("exists");
 
@@ -329,6 +588,7 @@ impl OplogService for PrimaryOplogService {
     ) -> Result<OplogPayload, String> {
         Self::upload_payload(
             self.blob_storage.clone(),
+            self.encryption.clone(),
             self.max_payload_size,
             owned_worker_id,
             data,
@@ -341,7 +601,13 @@ impl OplogService for PrimaryOplogService {
         owned_worker_id: &OwnedWorkerId,
         payload: &OplogPayload,
     ) -> Result<Bytes, String> {
-        Self::download_payload(self.blob_storage.clone(), owned_worker_id, payload).await
+        Self::download_payload(
+            self.blob_storage.clone(),
+            self.encryption.clone(),
+            owned_worker_id,
+            payload,
+        )
+        .await
     }
 }
 
@@ -349,9 +615,12 @@ impl OplogService for PrimaryOplogService {
 struct CreateOplogConstructor {
     indexed_storage: Arc<dyn IndexedStorage + Send + Sync>,
     blob_storage: Arc<dyn BlobStorage + Send + Sync>,
+    encryption: Option<Arc<dyn EncryptionService + Send + Sync>>,
     replicas: u8,
     max_operations_before_commit: u64,
     max_payload_size: usize,
+    batching: OplogBatching,
+    record_markers: bool,
     key: String,
     last_oplog_idx: OplogIndex,
     owned_worker_id: OwnedWorkerId,
@@ -361,9 +630,12 @@ impl CreateOplogConstructor {
     fn new(
         indexed_storage: Arc<dyn IndexedStorage + Send + Sync>,
         blob_storage: Arc<dyn BlobStorage + Send + Sync>,
+        encryption: Option<Arc<dyn EncryptionService + Send + Sync>>,
         replicas: u8,
         max_operations_before_commit: u64,
         max_payload_size: usize,
+        batching: OplogBatching,
+        record_markers: bool,
         key: String,
         last_oplog_idx: OplogIndex,
         owned_worker_id: OwnedWorkerId,
@@ -371,9 +643,12 @@ impl CreateOplogConstructor {
         Self {
             indexed_storage,
             blob_storage,
+            encryption,
             replicas,
             max_operations_before_commit,
             max_payload_size,
+            batching,
+            record_markers,
             key,
             last_oplog_idx,
             owned_worker_id,
@@ -381,18 +656,51 @@ impl CreateOplogConstructor {
     }
 }
 
+/// The batching settings of a single open oplog
+#[derive(Debug, Clone)]
+struct OplogBatching {
+    durability: OplogDurability,
+    max_batch_size: usize,
+    flush_interval: Duration,
+}
+
 #[async_trait]
 impl OplogConstructor for CreateOplogConstructor {
     async fn create_oplog(
         self,
         close: Box<dyn FnOnce() + Send + Sync>,
     ) -> Arc<dyn Oplog + Send + Sync> {
+        let markers_result = if self.record_markers {
+            complete_markers(
+                self.indexed_storage.as_ref(),
+                &self.key,
+                self.last_oplog_idx,
+            )
+            .await
+        } else {
+            // The entries appended from now on would have no markers, so the marker index is
+            // dropped and the oplog has to be scanned instead
+            self.indexed_storage
+                .with("oplog", "open")
+                .delete(IndexedStorageNamespace::OplogMarkers, &self.key)
+                .await
+        };
+        markers_result.unwrap_or_else(|err| {
+            panic!(
+                "failed to prepare oplog markers for {} in indexed storage: {err}",
+                self.key
+            )
+        });
+
         Arc::new(PrimaryOplog::new(
             self.indexed_storage,
             self.blob_storage,
+            self.encryption,
             self.replicas,
             self.max_operations_before_commit,
             self.max_payload_size,
+            self.batching,
+            self.record_markers,
             self.key,
             self.last_oplog_idx,
             self.owned_worker_id,
@@ -401,14 +709,31 @@ impl OplogConstructor for CreateOplogConstructor {
     }
 }
 
-struct PrimaryOplog {
+pub(super) struct PrimaryOplog {
     state: Arc<Mutex<PrimaryOplogState>>,
+    /// Index of the last entry written to the indexed storage
+    committed: watch::Receiver<OplogIndex>,
     key: String,
+    flush_fiber: Option<JoinHandle<()>>,
     close: Option<Box<dyn FnOnce() + Send + Sync>>,
 }
 
 impl Drop for PrimaryOplog {
     fn drop(&mut self) {
+        if let Some(flush_fiber) = self.flush_fiber.take() {
+            flush_fiber.abort();
+            // Batched entries must be committed before the oplog is dropped, writing them
+            // here would race with reopening the same oplog
+            if let Some(state) = self.state.try_lock() {
+                if !state.buffer.is_empty() {
+                    warn!(
+                        "Oplog {} dropped with {} uncommitted entries",
+                        self.key,
+                        state.buffer.len()
+                    );
+                }
+            }
+        }
         if let Some(close) = self.close.take() {
             close();
         }
@@ -419,43 +744,193 @@ impl PrimaryOplog {
     fn new(
         indexed_storage: Arc<dyn IndexedStorage + Send + Sync>,
         blob_storage: Arc<dyn BlobStorage + Send + Sync>,
+        encryption: Option<Arc<dyn EncryptionService + Send + Sync>>,
         replicas: u8,
         max_operations_before_commit: u64,
         max_payload_size: usize,
+        batching: OplogBatching,
+        record_markers: bool,
         key: String,
         last_oplog_idx: OplogIndex,
         owned_worker_id: OwnedWorkerId,
         close: Box<dyn FnOnce() + Send + Sync>,
     ) -> Self {
+        let (committed_sender, committed) = watch::channel(last_oplog_idx);
+        let state = Arc::new(Mutex::new(PrimaryOplogState {
+            indexed_storage,
+            blob_storage,
+            encryption,
+            replicas,
+            max_operations_before_commit: match batching.durability {
+                OplogDurability::EveryEntry => max_operations_before_commit,
+                OplogDurability::Batched => batching.max_batch_size as u64,
+            },
+            max_payload_size,
+            durability: batching.durability,
+            record_markers,
+            key: key.clone(),
+            buffer: VecDeque::new(),
+            last_committed_idx: last_oplog_idx,
+            committed: committed_sender,
+            last_oplog_idx,
+            owned_worker_id,
+        }));
+        let flush_fiber = match batching.durability {
+            OplogDurability::EveryEntry => None,
+            OplogDurability::Batched => Some(tokio::spawn(
+                Self::background_flush(Arc::downgrade(&state), batching.flush_interval)
+                    .in_current_span(),
+            )),
+        };
         Self {
-            state: Arc::new(Mutex::new(PrimaryOplogState {
-                indexed_storage,
-                blob_storage,
-                replicas,
-                max_operations_before_commit,
-                max_payload_size,
-                key: key.clone(),
-                buffer: VecDeque::new(),
-                last_committed_idx: last_oplog_idx,
-                last_oplog_idx,
-                owned_worker_id,
-            })),
+            state,
+            committed,
             key,
+            flush_fiber,
             close: Some(close),
         }
     }
+
+    /// Notifies about the entries written to the indexed storage, including the ones written
+    /// by the background flush of batched oplogs
+    pub(super) fn committed_updates(&self) -> watch::Receiver<OplogIndex> {
+        self.committed.clone()
+    }
+
+    /// Periodically writes the pending entries of a batched oplog
+    async fn background_flush(state: Weak<Mutex<PrimaryOplogState>>, flush_interval: Duration) {
+        let mut interval = tokio::time::interval(flush_interval.max(Duration::from_millis(1)));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            match state.upgrade() {
+                Some(state) => {
+                    let mut state = state.lock().await;
+                    if !state.buffer.is_empty() {
+                        state.commit().await;
+                    }
+                }
+                None => break,
+            }
+        }
+    }
+}
+
+/// Records the missing markers of the entries after the last marker, which were appended
+/// without markers, for example by an executor not recording them yet.
+///
+/// If these entries are no longer in the primary storage the marker index is dropped instead,
+/// so it is never used while it has gaps.
+async fn complete_markers(
+    indexed_storage: &(dyn IndexedStorage + Send + Sync),
+    key: &str,
+    last_oplog_idx: OplogIndex,
+) -> Result<(), String> {
+    let markers = indexed_storage.with_entity("oplog", "open", "marker");
+    // Oplogs without the marker of their initial entry have no marker index to complete
+    if markers
+        .first_id(IndexedStorageNamespace::OplogMarkers, key)
+        .await?
+        != Some(OplogIndex::INITIAL.into())
+    {
+        return Ok(());
+    }
+    let last_marker_idx = markers
+        .last_id(IndexedStorageNamespace::OplogMarkers, key)
+        .await?
+        .unwrap_or_default();
+    let last_oplog_idx: u64 = last_oplog_idx.into();
+    if last_marker_idx >= last_oplog_idx {
+        return Ok(());
+    }
+
+    let raw_entries = indexed_storage
+        .with_entity("oplog", "open", "entry")
+        .read_raw(
+            IndexedStorageNamespace::OpLog,
+            key,
+            last_marker_idx + 1,
+            last_oplog_idx,
+        )
+        .await?;
+    let entries = raw_entries
+        .iter()
+        .map(|(idx, bytes)| deserialize::<OplogEntry>(bytes).map(|entry| (*idx, entry)))
+        .collect::<Result<Vec<_>, _>>();
+    match entries {
+        Ok(entries) if entries.len() as u64 == last_oplog_idx - last_marker_idx => {
+            for (idx, entry) in entries {
+                if let Some(marker) = OplogMarker::of(&entry) {
+                    markers
+                        .append(IndexedStorageNamespace::OplogMarkers, key, idx, &marker)
+                        .await?;
+                }
+            }
+            Ok(())
+        }
+        _ => {
+            indexed_storage
+                .with("oplog", "open")
+                .delete(IndexedStorageNamespace::OplogMarkers, key)
+                .await
+        }
+    }
+}
+
+/// Appends an oplog entry together with its marker, if it has one and the markers are recorded,
+/// so the marker index never diverges from the entries
+async fn append_entry(
+    indexed_storage: &(dyn IndexedStorage + Send + Sync),
+    api_name: &'static str,
+    key: &str,
+    oplog_idx: OplogIndex,
+    entry: &OplogEntry,
+    record_markers: bool,
+) -> Result<(), String> {
+    let storage = indexed_storage.with_entity("oplog", api_name, "entry");
+    match OplogMarker::of(entry).filter(|_| record_markers) {
+        Some(marker) => {
+            storage
+                .append_many_raw(
+                    key,
+                    &[
+                        (
+                            IndexedStorageNamespace::OpLog,
+                            oplog_idx.into(),
+                            serialize(entry)?.to_vec(),
+                        ),
+                        (
+                            IndexedStorageNamespace::OplogMarkers,
+                            oplog_idx.into(),
+                            serialize(&marker)?.to_vec(),
+                        ),
+                    ],
+                )
+                .await
+        }
+        None => {
+            storage
+                .append(IndexedStorageNamespace::OpLog, key, oplog_idx.into(), entry)
+                .await
+        }
+    }
 }
 
 struct PrimaryOplogState {
     indexed_storage: Arc<dyn IndexedStorage + Send + Sync>,
     blob_storage: Arc<dyn BlobStorage + Send + Sync>,
+    encryption: Option<Arc<dyn EncryptionService + Send + Sync>>,
     replicas: u8,
     max_operations_before_commit: u64,
     max_payload_size: usize,
+    durability: OplogDurability,
+    /// Whether the markers of the entries are appended to the oplog's marker index
+    record_markers: bool,
     key: String,
     buffer: VecDeque<OplogEntry>,
     last_oplog_idx: OplogIndex,
     last_committed_idx: OplogIndex,
+    committed: watch::Sender<OplogIndex>,
     owned_worker_id: OwnedWorkerId,
 }
 
@@ -465,23 +940,24 @@ impl PrimaryOplogState {
 
         for entry in entries {
             let oplog_idx = self.last_committed_idx.next();
-            self.indexed_storage
-                .with_entity("oplog", "append", "entry")
-                .append(
-                    IndexedStorageNamespace::OpLog,
-                    &self.key,
-                    oplog_idx.into(),
-                    entry,
+            append_entry(
+                self.indexed_storage.as_ref(),
+                "append",
+                &self.key,
+                oplog_idx,
+                entry,
+                self.record_markers,
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!(
+                    "failed to append oplog entry for {} in indexed storage: {err}",
+                    self.key
                 )
-                .await
-                .unwrap_or_else(|err| {
-                    panic!(
-                        "failed to append oplog entry for {} in indexed storage: {err}",
-                        self.key
-                    )
-                });
+            });
             self.last_committed_idx = oplog_idx;
         }
+        self.committed.send_replace(self.last_committed_idx);
     }
 
     async fn add(&mut self, entry: OplogEntry) {
@@ -623,9 +1099,15 @@ impl Oplog for PrimaryOplog {
         }
     }
 
-    async fn commit(&self, _level: CommitLevel) {
+    async fn commit(&self, level: CommitLevel) {
         let mut state = self.state.lock().await;
-        state.commit().await
+        if state.durability == OplogDurability::EveryEntry || level == CommitLevel::Immediate {
+            state.commit().await
+        }
+    }
+
+    async fn last_committed_oplog_index(&self) -> OplogIndex {
+        *self.committed.borrow()
     }
 
     async fn current_oplog_index(&self) -> OplogIndex {
@@ -650,22 +1132,43 @@ impl Oplog for PrimaryOplog {
     }
 
     async fn upload_payload(&self, data: &[u8]) -> Result<OplogPayload, String> {
-        let (blob_storage, owned_worker_id, max_length) = {
+        self.upload_payload_with_limit(data, usize::MAX).await
+    }
+
+    async fn upload_payload_with_limit(
+        &self,
+        data: &[u8],
+        max_inline_size: usize,
+    ) -> Result<OplogPayload, String> {
+        let (blob_storage, encryption, owned_worker_id, max_length) = {
             let state = self.state.lock().await;
             (
                 state.blob_storage.clone(),
+                state.encryption.clone(),
                 state.owned_worker_id.clone(),
                 state.max_payload_size,
             )
         };
-        PrimaryOplogService::upload_payload(blob_storage, max_length, &owned_worker_id, data).await
+        PrimaryOplogService::upload_payload(
+            blob_storage,
+            encryption,
+            max_length.min(max_inline_size),
+            &owned_worker_id,
+            data,
+        )
+        .await
     }
 
     async fn download_payload(&self, payload: &OplogPayload) -> Result<Bytes, String> {
-        let (blob_storage, owned_worker_id) = {
+        let (blob_storage, encryption, owned_worker_id) = {
             let state = self.state.lock().await;
-            (state.blob_storage.clone(), state.owned_worker_id.clone())
+            (
+                state.blob_storage.clone(),
+                state.encryption.clone(),
+                state.owned_worker_id.clone(),
+            )
         };
-        PrimaryOplogService::download_payload(blob_storage, &owned_worker_id, payload).await
+        PrimaryOplogService::download_payload(blob_storage, encryption, &owned_worker_id, payload)
+            .await
     }
 }
@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::{BTreeMap, HashMap};
+
 use test_r::{test, test_dep};
 
 use assert2::check;
@@ -20,18 +22,23 @@ use tracing::{debug, info};
 use uuid::Uuid;
 
 use golem_common::config::RedisConfig;
-use golem_common::model::oplog::WorkerError;
-use golem_common::model::regions::OplogRegion;
-use golem_common::model::ComponentId;
+use golem_common::model::oplog::{LogLevel, OplogEntryHeader, WorkerError};
+use golem_common::model::regions::{DeletedRegions, DeletedRegionsBuilder, OplogRegion};
+use golem_common::model::{ComponentId, WorkerName};
 use golem_common::redis::RedisPool;
 use golem_common::tracing::{init_tracing, TracingConfig};
 
+use crate::durable_host::serialized::SerializableStreamError;
+use crate::durable_host::{recorded_result_chunks, RESULT_STREAM_READ_FUNCTION};
+use crate::services::encryption::{DefaultEncryptionService, LocalKeyManagementService};
+use crate::services::golem_config::{LocalEncryptionConfig, OplogDurability};
 use crate::services::oplog::compressed::CompressedOplogArchiveService;
 use crate::services::oplog::multilayer::OplogArchiveService;
 use crate::storage::blob::memory::InMemoryBlobStorage;
 use crate::storage::indexed::memory::InMemoryIndexedStorage;
 use crate::storage::indexed::redis::RedisIndexedStorage;
-use crate::storage::indexed::IndexedStorage;
+use crate::storage::indexed::{IndexedStorage, IndexedStorageLabelledApi, IndexedStorageNamespace};
+use crate::storage::keyvalue::memory::InMemoryKeyValueStorage;
 
 use super::*;
 
@@ -236,6 +243,53 @@ fn rounded(entry: OplogEntry) -> OplogEntry {
         OplogEntry::Restart { timestamp } => OplogEntry::Restart {
             timestamp: rounded_ts(timestamp),
         },
+        OplogEntry::Snapshot {
+            timestamp,
+            component_version,
+            payload,
+        } => OplogEntry::Snapshot {
+            timestamp: rounded_ts(timestamp),
+            component_version,
+            payload,
+        },
+        OplogEntry::NetworkEgressDenied {
+            timestamp,
+            host,
+            rule,
+        } => OplogEntry::NetworkEgressDenied {
+            timestamp: rounded_ts(timestamp),
+            host,
+            rule,
+        },
+        OplogEntry::Revive {
+            timestamp,
+            trimmed_region,
+        } => OplogEntry::Revive {
+            timestamp: rounded_ts(timestamp),
+            trimmed_region,
+        },
+        OplogEntry::ChangeEnvironment {
+            timestamp,
+            args,
+            env,
+        } => OplogEntry::ChangeEnvironment {
+            timestamp: rounded_ts(timestamp),
+            args,
+            env,
+        },
+        OplogEntry::InvocationContext { timestamp, context } => OplogEntry::InvocationContext {
+            timestamp: rounded_ts(timestamp),
+            context,
+        },
+        OplogEntry::Forked {
+            timestamp,
+            source_worker_id,
+            source_oplog_index,
+        } => OplogEntry::Forked {
+            timestamp: rounded_ts(timestamp),
+            source_worker_id,
+            source_oplog_index,
+        },
     }
 }
 
@@ -249,7 +303,7 @@ async fn open_add_and_read_back(_tracing: &Tracing) {
     };
     let worker_id = WorkerId {
         component_id: ComponentId(Uuid::new_v4()),
-        worker_name: "test".to_string(),
+        worker_name: WorkerName::from_existing("test"),
     };
     let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
     let last_oplog_index = oplog_service.get_last_index(&owned_worker_id).await;
@@ -287,6 +341,60 @@ async fn open_add_and_read_back(_tracing: &Tracing) {
     );
 }
 
+#[test]
+async fn batched_oplog_writes_on_immediate_commit_or_flush(_tracing: &Tracing) {
+    let indexed_storage = Arc::new(InMemoryIndexedStorage::new());
+    let blob_storage = Arc::new(InMemoryBlobStorage::new());
+    let component_id = ComponentId(Uuid::new_v4());
+    let oplog_service = PrimaryOplogService::new(indexed_storage, blob_storage, 1, 100)
+        .await
+        .with_durability_policy(OplogDurabilityPolicy::new(
+            OplogDurability::EveryEntry,
+            HashMap::from([(component_id.clone(), OplogDurability::Batched)]),
+            100,
+            Duration::from_millis(200),
+        ));
+    let account_id = AccountId {
+        value: "user1".to_string(),
+    };
+    let worker_id = WorkerId {
+        component_id,
+        worker_name: WorkerName::from_existing("test"),
+    };
+    let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+    let last_oplog_index = oplog_service.get_last_index(&owned_worker_id).await;
+    let oplog = oplog_service
+        .open(&owned_worker_id, last_oplog_index, ComponentType::Durable)
+        .await;
+
+    oplog.add(rounded(OplogEntry::suspend())).await;
+    oplog.add(rounded(OplogEntry::exited())).await;
+    oplog.commit(CommitLevel::Always).await;
+
+    check!(oplog.current_oplog_index().await == last_oplog_index.next().next());
+    check!(oplog.last_committed_oplog_index().await == last_oplog_index);
+    check!(oplog.length().await == 0);
+
+    oplog.commit(CommitLevel::Immediate).await;
+    check!(oplog.last_committed_oplog_index().await == last_oplog_index.next().next());
+    check!(oplog.length().await == 2);
+
+    let mut committed = downcast_oplog::<primary::PrimaryOplog>(&oplog)
+        .unwrap()
+        .committed_updates();
+    oplog.add(rounded(OplogEntry::suspend())).await;
+    oplog.commit(CommitLevel::DurableOnly).await;
+    check!(oplog.length().await == 2);
+
+    // Written by the background flush
+    let last_index = last_oplog_index.next().next().next();
+    committed
+        .wait_for(|committed| *committed == last_index)
+        .await
+        .unwrap();
+    check!(oplog.length().await == 3);
+}
+
 #[test]
 async fn open_add_and_read_back_ephemeral(_tracing: &Tracing) {
     let indexed_storage = Arc::new(InMemoryIndexedStorage::new());
@@ -304,6 +412,7 @@ async fn open_add_and_read_back_ephemeral(_tracing: &Tracing) {
         nev![secondary_layer.clone(), tertiary_layer.clone()],
         10,
         10,
+        OplogArchiveAgePolicy::disabled(),
     ));
 
     let account_id = AccountId {
@@ -311,7 +420,7 @@ async fn open_add_and_read_back_ephemeral(_tracing: &Tracing) {
     };
     let worker_id = WorkerId {
         component_id: ComponentId(Uuid::new_v4()),
-        worker_name: "test".to_string(),
+        worker_name: WorkerName::from_existing("test"),
     };
     let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
     let last_oplog_index = oplog_service.get_last_index(&owned_worker_id).await;
@@ -349,6 +458,519 @@ async fn open_add_and_read_back_ephemeral(_tracing: &Tracing) {
     );
 }
 
+#[test]
+async fn in_memory_ephemeral_oplog_is_not_archived(_tracing: &Tracing) {
+    let indexed_storage = Arc::new(InMemoryIndexedStorage::new());
+    let blob_storage = Arc::new(InMemoryBlobStorage::new());
+    let primary_oplog_service = Arc::new(
+        PrimaryOplogService::new(indexed_storage.clone(), blob_storage.clone(), 1, 100).await,
+    );
+    let secondary_layer: Arc<dyn OplogArchiveService + Send + Sync> = Arc::new(
+        CompressedOplogArchiveService::new(indexed_storage.clone(), 1),
+    );
+    let tertiary_layer: Arc<dyn OplogArchiveService + Send + Sync> =
+        Arc::new(BlobOplogArchiveService::new(blob_storage.clone(), 2));
+    let oplog_service = Arc::new(
+        MultiLayerOplogService::new(
+            primary_oplog_service.clone(),
+            nev![secondary_layer.clone(), tertiary_layer.clone()],
+            10,
+            1,
+            OplogArchiveAgePolicy::disabled(),
+        )
+        .with_in_memory_ephemeral_oplogs(),
+    );
+
+    let account_id = AccountId {
+        value: "user1".to_string(),
+    };
+    let worker_id = WorkerId {
+        component_id: ComponentId(Uuid::new_v4()),
+        worker_name: WorkerName::from_existing("test"),
+    };
+    let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+    let last_oplog_index = oplog_service.get_last_index(&owned_worker_id).await;
+    let oplog = oplog_service
+        .open(&owned_worker_id, last_oplog_index, ComponentType::Ephemeral)
+        .await;
+
+    let entry1 = rounded(OplogEntry::jump(OplogRegion {
+        start: OplogIndex::from_u64(5),
+        end: OplogIndex::from_u64(12),
+    }));
+    let entry2 = rounded(OplogEntry::suspend());
+    let entry3 = rounded(OplogEntry::exited());
+
+    let last_oplog_idx = oplog.current_oplog_index().await;
+    oplog.add(entry1.clone()).await;
+    oplog.add(entry2.clone()).await;
+    oplog.add(entry3.clone()).await;
+    oplog.commit(CommitLevel::Immediate).await;
+
+    let r1 = oplog.read(last_oplog_idx.next()).await;
+    let r3 = oplog.read(last_oplog_idx.next().next().next()).await;
+
+    assert_eq!(r1, entry1);
+    assert_eq!(r3, entry3);
+    assert_eq!(oplog.length().await, 3);
+    assert_eq!(
+        tertiary_layer.get_last_index(&owned_worker_id).await,
+        OplogIndex::NONE
+    );
+    assert!(oplog_service
+        .read(&owned_worker_id, OplogIndex::INITIAL, 10)
+        .await
+        .is_empty());
+}
+
+#[test]
+async fn read_range_checked_reports_undecodable_entries(_tracing: &Tracing) {
+    let indexed_storage = Arc::new(InMemoryIndexedStorage::new());
+    let blob_storage = Arc::new(InMemoryBlobStorage::new());
+    let oplog_service =
+        PrimaryOplogService::new(indexed_storage.clone(), blob_storage, 1, 100).await;
+    let account_id = AccountId {
+        value: "user1".to_string(),
+    };
+    let worker_id = WorkerId {
+        component_id: ComponentId(Uuid::new_v4()),
+        worker_name: WorkerName::from_existing("test"),
+    };
+    let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+    let last_oplog_index = oplog_service.get_last_index(&owned_worker_id).await;
+    let oplog = oplog_service
+        .open(&owned_worker_id, last_oplog_index, ComponentType::Durable)
+        .await;
+
+    let entry1 = rounded(OplogEntry::suspend());
+    let last_oplog_idx = oplog.current_oplog_index().await;
+    oplog.add(entry1.clone()).await;
+    oplog.commit(CommitLevel::Always).await;
+
+    let corrupted_idx = last_oplog_idx.next().next();
+    indexed_storage
+        .with_entity("oplog", "append", "entry")
+        .append_raw(
+            IndexedStorageNamespace::OpLog,
+            &worker_id.to_redis_key(),
+            corrupted_idx.into(),
+            &[17, 1, 2, 3],
+        )
+        .await
+        .unwrap();
+
+    let (entries, failures) = oplog_service
+        .read_range_checked(&owned_worker_id, last_oplog_idx.next(), corrupted_idx)
+        .await;
+
+    assert_eq!(entries.into_values().collect::<Vec<_>>(), vec![entry1]);
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].oplog_index, corrupted_idx);
+    assert_eq!(failures[0].serialization_version, 17);
+}
+
+#[test]
+async fn raw_entries_decode_headers_lazily(_tracing: &Tracing) {
+    let indexed_storage = Arc::new(InMemoryIndexedStorage::new());
+    let blob_storage = Arc::new(InMemoryBlobStorage::new());
+    let oplog_service = PrimaryOplogService::new(indexed_storage, blob_storage, 1, 100).await;
+    let account_id = AccountId {
+        value: "user1".to_string(),
+    };
+    let worker_id = WorkerId {
+        component_id: ComponentId(Uuid::new_v4()),
+        worker_name: WorkerName::from_existing("test"),
+    };
+    let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+    let last_oplog_index = oplog_service.get_last_index(&owned_worker_id).await;
+    let oplog = oplog_service
+        .open(&owned_worker_id, last_oplog_index, ComponentType::Durable)
+        .await;
+
+    let entry1 = rounded(OplogEntry::create(
+        worker_id.clone(),
+        1,
+        vec![],
+        vec![],
+        account_id.clone(),
+        None,
+        100,
+        200,
+    ));
+    let entry2 = rounded(OplogEntry::suspend());
+    let entry3 = rounded(OplogEntry::successful_update(2, 300));
+    oplog.add(entry1.clone()).await;
+    oplog.add(entry2.clone()).await;
+    oplog.add(entry3.clone()).await;
+    oplog.commit(CommitLevel::Always).await;
+
+    let raw_entries = oplog_service
+        .read_raw_range(
+            &owned_worker_id,
+            last_oplog_index.next(),
+            oplog.current_oplog_index().await,
+        )
+        .await
+        .into_values()
+        .collect::<Vec<_>>();
+
+    let headers = raw_entries
+        .iter()
+        .map(|raw_entry| raw_entry.header().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        headers,
+        vec![
+            OplogEntryHeader {
+                variant: OplogEntryHeader::CREATE,
+                timestamp: entry1.timestamp()
+            },
+            OplogEntryHeader {
+                variant: 4,
+                timestamp: entry2.timestamp()
+            },
+            OplogEntryHeader {
+                variant: OplogEntryHeader::SUCCESSFUL_UPDATE,
+                timestamp: entry3.timestamp()
+            },
+        ]
+    );
+    assert_eq!(
+        raw_entries
+            .iter()
+            .map(|raw_entry| raw_entry.decode().unwrap())
+            .collect::<Vec<_>>(),
+        vec![entry1.clone(), entry2, entry3.clone()]
+    );
+    assert_eq!(RawOplogEntry::encode(&entry1).header().unwrap(), headers[0]);
+    assert_eq!(RawOplogEntry::encode(&entry3).header().unwrap(), headers[2]);
+}
+
+#[test]
+async fn markers_of_invocation_boundaries(_tracing: &Tracing) {
+    let indexed_storage = Arc::new(InMemoryIndexedStorage::new());
+    let blob_storage = Arc::new(InMemoryBlobStorage::new());
+    let oplog_service: Arc<dyn OplogService + Send + Sync> =
+        Arc::new(PrimaryOplogService::new(indexed_storage, blob_storage, 1, 100).await);
+    let account_id = AccountId {
+        value: "user1".to_string(),
+    };
+    let worker_id = WorkerId {
+        component_id: ComponentId(Uuid::new_v4()),
+        worker_name: WorkerName::from_existing("test"),
+    };
+    let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+    let oplog = oplog_service
+        .create(
+            &owned_worker_id,
+            OplogEntry::create(
+                worker_id.clone(),
+                1,
+                vec![],
+                vec![],
+                account_id.clone(),
+                None,
+                100,
+                200,
+            ),
+            ComponentType::Durable,
+        )
+        .await;
+
+    let invoked = OplogEntry::ExportedFunctionInvoked {
+        timestamp: Timestamp::now_utc(),
+        function_name: "f".to_string(),
+        request: OplogPayload::Inline(vec![]),
+        idempotency_key: IdempotencyKey::fresh(),
+    };
+    let completed = OplogEntry::ExportedFunctionCompleted {
+        timestamp: Timestamp::now_utc(),
+        response: OplogPayload::Inline(vec![]),
+        consumed_fuel: 0,
+    };
+    oplog.add(invoked.clone()).await; // 2
+    oplog.add(completed.clone()).await; // 3
+    oplog.add(OplogEntry::successful_update(2, 300)).await; // 4
+    oplog.add(invoked.clone()).await; // 5
+    oplog.add(OplogEntry::suspend()).await; // 6
+    oplog.add(completed).await; // 7
+    oplog.add(invoked).await; // 8
+    oplog.commit(CommitLevel::Always).await;
+
+    let markers = oplog_service
+        .read_markers(
+            &owned_worker_id,
+            OplogIndex::INITIAL,
+            oplog.current_oplog_index().await,
+        )
+        .await;
+    check!(
+        markers
+            == Some(BTreeMap::from([
+                (OplogIndex::from_u64(1), OplogMarker::ComponentVersion(1)),
+                (OplogIndex::from_u64(2), OplogMarker::InvocationStart),
+                (OplogIndex::from_u64(3), OplogMarker::InvocationEnd),
+                (OplogIndex::from_u64(4), OplogMarker::ComponentVersion(2)),
+                (OplogIndex::from_u64(5), OplogMarker::InvocationStart),
+                (OplogIndex::from_u64(7), OplogMarker::InvocationEnd),
+                (OplogIndex::from_u64(8), OplogMarker::InvocationStart),
+            ]))
+    );
+
+    let starts = crate::model::public_oplog::find_last_invocation_starts(
+        oplog_service.clone(),
+        &owned_worker_id,
+        &DeletedRegions::new(),
+        OplogIndex::from_u64(8),
+        2,
+    )
+    .await
+    .unwrap();
+    check!(starts == vec![OplogIndex::from_u64(2), OplogIndex::from_u64(5)]);
+
+    // Invocations in deleted regions, for example reverted ones, are not counted
+    let deleted_regions = DeletedRegionsBuilder::from_regions(vec![OplogRegion::from_index_range(
+        OplogIndex::from_u64(5)..=OplogIndex::from_u64(7),
+    )])
+    .build();
+    let starts = crate::model::public_oplog::find_last_invocation_starts(
+        oplog_service.clone(),
+        &owned_worker_id,
+        &deleted_regions,
+        OplogIndex::from_u64(9),
+        2,
+    )
+    .await
+    .unwrap();
+    check!(starts == vec![OplogIndex::from_u64(2), OplogIndex::from_u64(8)]);
+
+    // Oplogs without the marker of their initial entry have to be scanned instead
+    let other_worker_id = WorkerId {
+        component_id: worker_id.component_id.clone(),
+        worker_name: WorkerName::from_existing("other"),
+    };
+    let other_owned_worker_id = OwnedWorkerId::new(&account_id, &other_worker_id);
+    let other_oplog = oplog_service
+        .open(
+            &other_owned_worker_id,
+            OplogIndex::NONE,
+            ComponentType::Durable,
+        )
+        .await;
+    other_oplog.add(OplogEntry::suspend()).await;
+    other_oplog.commit(CommitLevel::Always).await;
+    let other_markers = oplog_service
+        .read_markers(
+            &other_owned_worker_id,
+            OplogIndex::INITIAL,
+            OplogIndex::INITIAL,
+        )
+        .await;
+    check!(other_markers.is_none());
+}
+
+#[test]
+async fn markers_are_completed_or_dropped_on_open(_tracing: &Tracing) {
+    let indexed_storage = Arc::new(InMemoryIndexedStorage::new());
+    let blob_storage = Arc::new(InMemoryBlobStorage::new());
+    let oplog_service: Arc<dyn OplogService + Send + Sync> =
+        Arc::new(PrimaryOplogService::new(indexed_storage.clone(), blob_storage, 1, 100).await);
+    let account_id = AccountId {
+        value: "user1".to_string(),
+    };
+    let worker_id = WorkerId {
+        component_id: ComponentId(Uuid::new_v4()),
+        worker_name: WorkerName::from_existing("test"),
+    };
+    let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+    let oplog = oplog_service
+        .create(
+            &owned_worker_id,
+            OplogEntry::create(
+                worker_id.clone(),
+                1,
+                vec![],
+                vec![],
+                account_id.clone(),
+                None,
+                100,
+                200,
+            ),
+            ComponentType::Durable,
+        )
+        .await;
+    let invoked = OplogEntry::ExportedFunctionInvoked {
+        timestamp: Timestamp::now_utc(),
+        function_name: "f".to_string(),
+        request: OplogPayload::Inline(vec![]),
+        idempotency_key: IdempotencyKey::fresh(),
+    };
+    oplog.add(invoked.clone()).await; // 2
+    oplog.commit(CommitLevel::Always).await;
+    drop(oplog);
+
+    // Entries appended without their markers, like an executor not recording them would do
+    let entries = indexed_storage.with_entity("oplog", "append", "entry");
+    entries
+        .append(
+            IndexedStorageNamespace::OpLog,
+            &worker_id.to_redis_key(),
+            3,
+            &OplogEntry::suspend(),
+        )
+        .await
+        .unwrap();
+    entries
+        .append(
+            IndexedStorageNamespace::OpLog,
+            &worker_id.to_redis_key(),
+            4,
+            &invoked,
+        )
+        .await
+        .unwrap();
+
+    let oplog = oplog_service
+        .open(
+            &owned_worker_id,
+            OplogIndex::from_u64(4),
+            ComponentType::Durable,
+        )
+        .await;
+    let markers = oplog_service
+        .read_markers(
+            &owned_worker_id,
+            OplogIndex::INITIAL,
+            OplogIndex::from_u64(4),
+        )
+        .await;
+    check!(
+        markers
+            == Some(BTreeMap::from([
+                (OplogIndex::from_u64(1), OplogMarker::ComponentVersion(1)),
+                (OplogIndex::from_u64(2), OplogMarker::InvocationStart),
+                (OplogIndex::from_u64(4), OplogMarker::InvocationStart),
+            ]))
+    );
+    drop(oplog);
+
+    // Opening the oplog without recording the markers drops the index
+    let oplog = oplog_service
+        .open(
+            &owned_worker_id,
+            OplogIndex::from_u64(4),
+            ComponentType::Ephemeral,
+        )
+        .await;
+    oplog.add(invoked).await;
+    oplog.commit(CommitLevel::Always).await;
+    let markers = oplog_service
+        .read_markers(
+            &owned_worker_id,
+            OplogIndex::INITIAL,
+            OplogIndex::from_u64(5),
+        )
+        .await;
+    check!(markers.is_none());
+}
+
+#[test]
+async fn encrypted_payloads(_tracing: &Tracing) {
+    let indexed_storage = Arc::new(InMemoryIndexedStorage::new());
+    let blob_storage = Arc::new(InMemoryBlobStorage::new());
+    let key_management_service = LocalKeyManagementService::new(&LocalEncryptionConfig {
+        current_master_key: "key1".to_string(),
+        master_keys: BTreeMap::from([("key1".to_string(), "11".repeat(32))]),
+    })
+    .unwrap();
+    let encryption = Arc::new(DefaultEncryptionService::new(
+        Arc::new(key_management_service),
+        Arc::new(InMemoryKeyValueStorage::new()),
+    ));
+    let oplog_service = PrimaryOplogService::new(indexed_storage, blob_storage, 1, 100)
+        .await
+        .with_encryption(encryption);
+    let account_id = AccountId {
+        value: "user1".to_string(),
+    };
+    let worker_id = WorkerId {
+        component_id: ComponentId(Uuid::new_v4()),
+        worker_name: WorkerName::from_existing("test"),
+    };
+    let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+
+    let small_payload = vec![1u8; 10];
+    let large_payload = vec![2u8; 1000];
+
+    for data in [small_payload, large_payload] {
+        let payload = oplog_service
+            .upload_payload(&owned_worker_id, &data)
+            .await
+            .unwrap();
+        match &payload {
+            OplogPayload::Encrypted { payload, .. } => {
+                if let OplogPayload::Inline(ciphertext) = payload.as_ref() {
+                    check!(ciphertext != &data);
+                }
+            }
+            _ => panic!("Expected an encrypted payload, got {payload:?}"),
+        }
+
+        let downloaded = oplog_service
+            .download_payload(&owned_worker_id, &payload)
+            .await
+            .unwrap();
+        check!(downloaded.to_vec() == data);
+    }
+}
+
+#[test]
+async fn responses_over_the_limit_are_stored_externally(_tracing: &Tracing) {
+    let indexed_storage = Arc::new(InMemoryIndexedStorage::new());
+    let blob_storage = Arc::new(InMemoryBlobStorage::new());
+    let oplog_service = PrimaryOplogService::new(indexed_storage, blob_storage, 1, 1000).await;
+    let account_id = AccountId {
+        value: "user1".to_string(),
+    };
+    let worker_id = WorkerId {
+        component_id: ComponentId(Uuid::new_v4()),
+        worker_name: WorkerName::from_existing("test"),
+    };
+    let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+
+    let last_oplog_index = oplog_service.get_last_index(&owned_worker_id).await;
+    let oplog = oplog_service
+        .open(&owned_worker_id, last_oplog_index, ComponentType::Durable)
+        .await;
+
+    let response = vec![3u8; 100];
+    let entry = oplog
+        .add_imported_function_invoked_with_response_limit(
+            "f1".to_string(),
+            &"request".to_string(),
+            &response,
+            WrappedFunctionType::WriteRemoteBatched(Some(OplogIndex::INITIAL)),
+            Some(50),
+        )
+        .await
+        .unwrap();
+
+    match &entry {
+        OplogEntry::ImportedFunctionInvoked {
+            request,
+            response: response_payload,
+            ..
+        } => {
+            check!(matches!(request, OplogPayload::Inline(_)));
+            check!(matches!(response_payload, OplogPayload::External { .. }));
+        }
+        _ => panic!("Expected an imported function invoked entry, got {entry:?}"),
+    }
+
+    let stored: Option<Vec<u8>> = oplog.get_payload_of_entry(&entry).await.unwrap();
+    check!(stored == Some(response));
+}
+
 #[test]
 async fn entries_with_small_payload(_tracing: &Tracing) {
     let indexed_storage = Arc::new(InMemoryIndexedStorage::new());
@@ -359,7 +981,7 @@ async fn entries_with_small_payload(_tracing: &Tracing) {
     };
     let worker_id = WorkerId {
         component_id: ComponentId(Uuid::new_v4()),
-        worker_name: "test".to_string(),
+        worker_name: WorkerName::from_existing("test"),
     };
     let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
 
@@ -469,7 +1091,7 @@ async fn entries_with_large_payload(_tracing: &Tracing) {
     };
     let worker_id = WorkerId {
         component_id: ComponentId(Uuid::new_v4()),
-        worker_name: "test".to_string(),
+        worker_name: WorkerName::from_existing("test"),
     };
     let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
     let last_oplog_index = oplog_service.get_last_index(&owned_worker_id).await;
@@ -645,6 +1267,7 @@ async fn multilayer_transfers_entries_after_limit_reached(
         nev![secondary_layer.clone(), tertiary_layer.clone()],
         10,
         10,
+        OplogArchiveAgePolicy::disabled(),
     ));
 
     let account_id = AccountId {
@@ -652,7 +1275,7 @@ async fn multilayer_transfers_entries_after_limit_reached(
     };
     let worker_id = WorkerId {
         component_id: ComponentId(Uuid::new_v4()),
-        worker_name: "test".to_string(),
+        worker_name: WorkerName::from_existing("test"),
     };
     let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
 
@@ -746,13 +1369,14 @@ async fn read_from_archive_impl(use_blob: bool) {
         nev![secondary_layer.clone(), tertiary_layer.clone()],
         10,
         10,
+        OplogArchiveAgePolicy::disabled(),
     ));
     let account_id = AccountId {
         value: "user1".to_string(),
     };
     let worker_id = WorkerId {
         component_id: ComponentId(Uuid::new_v4()),
-        worker_name: "test".to_string(),
+        worker_name: WorkerName::from_existing("test"),
     };
     let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
 
@@ -867,13 +1491,14 @@ async fn write_after_archive_impl(use_blob: bool, reopen: Reopen) {
         nev![secondary_layer.clone(), tertiary_layer.clone()],
         10,
         10,
+        OplogArchiveAgePolicy::disabled(),
     ));
     let account_id = AccountId {
         value: "user1".to_string(),
     };
     let worker_id = WorkerId {
         component_id: ComponentId(Uuid::new_v4()),
-        worker_name: "test".to_string(),
+        worker_name: WorkerName::from_existing("test"),
     };
     let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
 
@@ -935,6 +1560,7 @@ async fn write_after_archive_impl(use_blob: bool, reopen: Reopen) {
             nev![secondary_layer.clone(), tertiary_layer.clone()],
             10,
             10,
+            OplogArchiveAgePolicy::disabled(),
         ));
         let last_oplog_index = oplog_service.get_last_index(&owned_worker_id).await;
         oplog_service
@@ -995,6 +1621,7 @@ async fn write_after_archive_impl(use_blob: bool, reopen: Reopen) {
             nev![secondary_layer.clone(), tertiary_layer.clone()],
             10,
             10,
+            OplogArchiveAgePolicy::disabled(),
         ));
         let last_oplog_index = oplog_service.get_last_index(&owned_worker_id).await;
         oplog_service
@@ -1098,13 +1725,14 @@ async fn empty_layer_gets_deleted_impl(use_blob: bool) {
         nev![secondary_layer.clone(), tertiary_layer.clone()],
         10,
         10,
+        OplogArchiveAgePolicy::disabled(),
     ));
     let account_id = AccountId {
         value: "user1".to_string(),
     };
     let worker_id = WorkerId {
         component_id: ComponentId(Uuid::new_v4()),
-        worker_name: "test".to_string(),
+        worker_name: WorkerName::from_existing("test"),
     };
     let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
 
@@ -1203,13 +1831,14 @@ async fn scheduled_archive_impl(use_blob: bool) {
         nev![secondary_layer.clone(), tertiary_layer.clone()],
         1000, // no transfer will occur by reaching limit in this test
         10,
+        OplogArchiveAgePolicy::disabled(),
     ));
     let account_id = AccountId {
         value: "user1".to_string(),
     };
     let worker_id = WorkerId {
         component_id: ComponentId(Uuid::new_v4()),
-        worker_name: "test".to_string(),
+        worker_name: WorkerName::from_existing("test"),
     };
     let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
 
@@ -1306,3 +1935,342 @@ async fn scheduled_archive_impl(use_blob: bool) {
 
     assert_eq!(last_oplog_index_2, last_oplog_index_3);
 }
+
+#[test]
+async fn age_based_archive(_tracing: &Tracing) {
+    let indexed_storage = Arc::new(InMemoryIndexedStorage::new());
+    let blob_storage = Arc::new(InMemoryBlobStorage::new());
+    let primary_oplog_service = Arc::new(
+        PrimaryOplogService::new(indexed_storage.clone(), blob_storage.clone(), 1, 100).await,
+    );
+    let secondary_layer: Arc<dyn OplogArchiveService + Send + Sync> = Arc::new(
+        CompressedOplogArchiveService::new(indexed_storage.clone(), 1),
+    );
+    let tertiary_layer: Arc<dyn OplogArchiveService + Send + Sync> =
+        Arc::new(BlobOplogArchiveService::new(blob_storage.clone(), 2));
+
+    let account_id = AccountId {
+        value: "user1".to_string(),
+    };
+    let worker_id = WorkerId {
+        component_id: ComponentId(Uuid::new_v4()),
+        worker_name: WorkerName::from_existing("test"),
+    };
+    let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+
+    let oplog_service = Arc::new(MultiLayerOplogService::new(
+        primary_oplog_service.clone(),
+        nev![secondary_layer.clone(), tertiary_layer.clone()],
+        1000, // no transfer will occur by reaching limit in this test
+        10,
+        OplogArchiveAgePolicy::new(
+            None,
+            HashMap::from([(worker_id.component_id.clone(), Duration::from_secs(60 * 60))]),
+            Duration::from_secs(60),
+        ),
+    ));
+
+    let now = Timestamp::now_utc();
+    let two_hours_ago = Timestamp::from(now.to_millis() - 2 * 60 * 60 * 1000);
+    let entries: Vec<OplogEntry> = (0..100)
+        .map(|i| {
+            rounded(OplogEntry::Error {
+                timestamp: if i < 40 { two_hours_ago } else { now },
+                error: WorkerError::Unknown(i.to_string()),
+            })
+        })
+        .collect();
+
+    let last_oplog_index = oplog_service.get_last_index(&owned_worker_id).await;
+    let oplog = oplog_service
+        .open(&owned_worker_id, last_oplog_index, ComponentType::Durable)
+        .await;
+    for entry in &entries {
+        oplog.add(entry.clone()).await;
+    }
+    oplog.commit(CommitLevel::Always).await;
+
+    tokio::time::sleep(Duration::from_secs(2)).await;
+
+    let primary_length = primary_oplog_service
+        .open(
+            &owned_worker_id,
+            primary_oplog_service.get_last_index(&owned_worker_id).await,
+            ComponentType::Durable,
+        )
+        .await
+        .length()
+        .await;
+    let secondary_length = secondary_layer.open(&owned_worker_id).await.length().await;
+
+    // Only the entries older than the age limit got moved to the next layer
+    assert_eq!(primary_length, 60);
+    assert_eq!(secondary_length, 1);
+
+    let all_entries = oplog_service
+        .read(&owned_worker_id, OplogIndex::INITIAL, 100)
+        .await;
+    assert_eq!(all_entries.into_values().collect::<Vec<_>>(), entries);
+}
+
+#[test]
+async fn old_logs_are_moved_to_log_archive(_tracing: &Tracing) {
+    let indexed_storage = Arc::new(InMemoryIndexedStorage::new());
+    let blob_storage = Arc::new(InMemoryBlobStorage::new());
+    let primary_oplog_service = Arc::new(
+        PrimaryOplogService::new(indexed_storage.clone(), blob_storage.clone(), 1, 100).await,
+    );
+    let secondary_layer: Arc<dyn OplogArchiveService + Send + Sync> = Arc::new(
+        CompressedOplogArchiveService::new(indexed_storage.clone(), 1),
+    );
+    let tertiary_layer: Arc<dyn OplogArchiveService + Send + Sync> =
+        Arc::new(BlobOplogArchiveService::new(blob_storage.clone(), 2));
+    let log_archive = Arc::new(CompressedLogArchiveService::new(indexed_storage.clone()));
+
+    let oplog_service = Arc::new(
+        MultiLayerOplogService::new(
+            primary_oplog_service.clone(),
+            nev![secondary_layer.clone(), tertiary_layer.clone()],
+            10,
+            10,
+            OplogArchiveAgePolicy::disabled(),
+        )
+        .with_log_retention(LogRetentionPolicy::new(
+            Duration::from_secs(60 * 60),
+            Duration::from_secs(60 * 60),
+            log_archive.clone(),
+        )),
+    );
+
+    let account_id = AccountId {
+        value: "user1".to_string(),
+    };
+    let worker_id = WorkerId {
+        component_id: ComponentId(Uuid::new_v4()),
+        worker_name: WorkerName::from_existing("test"),
+    };
+    let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+
+    let now = Timestamp::now_utc();
+    let two_hours_ago = Timestamp::from(now.to_millis() - 2 * 60 * 60 * 1000);
+    let entries: Vec<OplogEntry> = (0..20)
+        .map(|i| {
+            rounded(if i % 2 == 0 {
+                OplogEntry::Log {
+                    timestamp: if i < 10 { two_hours_ago } else { now },
+                    level: LogLevel::Stdout,
+                    context: "test".to_string(),
+                    message: format!("message {i}"),
+                }
+            } else {
+                OplogEntry::Error {
+                    timestamp: if i < 10 { two_hours_ago } else { now },
+                    error: WorkerError::Unknown(i.to_string()),
+                }
+            })
+        })
+        .collect();
+
+    let last_oplog_index = oplog_service.get_last_index(&owned_worker_id).await;
+    let oplog = oplog_service
+        .open(&owned_worker_id, last_oplog_index, ComponentType::Durable)
+        .await;
+    let initial_oplog_idx = oplog.current_oplog_index().await;
+    for entry in &entries {
+        oplog.add(entry.clone()).await;
+    }
+    oplog.commit(CommitLevel::Always).await;
+    let last_idx = oplog.current_oplog_index().await;
+
+    // Waits for the already enqueued transfer from the primary oplog
+    MultiLayerOplog::try_apply_log_retention(&oplog).await;
+
+    let first_idx = initial_oplog_idx.next();
+    let archived_logs = log_archive
+        .read_range(&owned_worker_id, first_idx, last_idx)
+        .await;
+
+    // Only the logs older than the age limit got moved to the log archive
+    check!(archived_logs.len() == 5);
+
+    let stripped_entries = oplog_service.read(&owned_worker_id, first_idx, 20).await;
+    let stripped_log_count = stripped_entries
+        .values()
+        .filter(|entry| {
+            matches!(entry, OplogEntry::Log { context, message, .. } if context.is_empty() && message.is_empty())
+        })
+        .count();
+    check!(stripped_entries.len() == 20);
+    check!(stripped_log_count == 5);
+
+    let restored_entries = oplog_service
+        .read_with_logs(&owned_worker_id, first_idx, 20)
+        .await;
+    assert_eq!(restored_entries.into_values().collect::<Vec<_>>(), entries);
+}
+
+#[test]
+async fn old_logs_in_the_last_layer_are_moved_by_the_retention_pass(_tracing: &Tracing) {
+    let indexed_storage = Arc::new(InMemoryIndexedStorage::new());
+    let blob_storage = Arc::new(InMemoryBlobStorage::new());
+    let primary_oplog_service = Arc::new(
+        PrimaryOplogService::new(indexed_storage.clone(), blob_storage.clone(), 1, 100).await,
+    );
+    let secondary_layer: Arc<dyn OplogArchiveService + Send + Sync> = Arc::new(
+        CompressedOplogArchiveService::new(indexed_storage.clone(), 1),
+    );
+    let tertiary_layer: Arc<dyn OplogArchiveService + Send + Sync> =
+        Arc::new(BlobOplogArchiveService::new(blob_storage.clone(), 2));
+    let log_archive = Arc::new(CompressedLogArchiveService::new(indexed_storage.clone()));
+
+    let oplog_service = Arc::new(
+        MultiLayerOplogService::new(
+            primary_oplog_service.clone(),
+            nev![secondary_layer.clone(), tertiary_layer.clone()],
+            10,
+            10,
+            OplogArchiveAgePolicy::disabled(),
+        )
+        .with_log_retention(LogRetentionPolicy::new(
+            Duration::from_secs(60 * 60),
+            Duration::from_secs(60 * 60),
+            log_archive.clone(),
+        )),
+    );
+
+    let account_id = AccountId {
+        value: "user1".to_string(),
+    };
+    let worker_id = WorkerId {
+        component_id: ComponentId(Uuid::new_v4()),
+        worker_name: WorkerName::from_existing("test"),
+    };
+    let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+
+    let now = Timestamp::now_utc();
+    let two_hours_ago = Timestamp::from(now.to_millis() - 2 * 60 * 60 * 1000);
+    let entries: Vec<(OplogIndex, OplogEntry)> = (1..=20)
+        .map(|i| {
+            let timestamp = if i <= 10 { two_hours_ago } else { now };
+            let entry = rounded(if i % 2 == 0 {
+                OplogEntry::Log {
+                    timestamp,
+                    level: LogLevel::Stdout,
+                    context: "test".to_string(),
+                    message: format!("message {i}"),
+                }
+            } else {
+                OplogEntry::Error {
+                    timestamp,
+                    error: WorkerError::Unknown(i.to_string()),
+                }
+            });
+            (OplogIndex::from_u64(i), entry)
+        })
+        .collect();
+
+    // The logs were not old enough yet when they got transferred to the last layer
+    let last_layer = tertiary_layer.open(&owned_worker_id).await;
+    last_layer.append(entries[0..10].to_vec()).await;
+    last_layer.append(entries[10..20].to_vec()).await;
+
+    let last_oplog_index = oplog_service.get_last_index(&owned_worker_id).await;
+    let oplog = oplog_service
+        .open(&owned_worker_id, last_oplog_index, ComponentType::Durable)
+        .await;
+
+    MultiLayerOplog::try_apply_log_retention(&oplog).await;
+    // A second pass does not move the same logs again
+    MultiLayerOplog::try_apply_log_retention(&oplog).await;
+
+    let first_idx = OplogIndex::from_u64(1);
+    let last_idx = OplogIndex::from_u64(20);
+    let archived_logs = log_archive
+        .read_range(&owned_worker_id, first_idx, last_idx)
+        .await;
+    check!(
+        archived_logs.into_iter().collect::<Vec<_>>()
+            == entries
+                .iter()
+                .filter(|(idx, entry)| {
+                    *idx <= OplogIndex::from_u64(10) && matches!(entry, OplogEntry::Log { .. })
+                })
+                .cloned()
+                .collect::<Vec<_>>()
+    );
+
+    let stripped_entries = tertiary_layer.read(&owned_worker_id, first_idx, 20).await;
+    let stripped_log_count = stripped_entries
+        .values()
+        .filter(|entry| {
+            matches!(entry, OplogEntry::Log { context, message, .. } if context.is_empty() && message.is_empty())
+        })
+        .count();
+    check!(stripped_entries.len() == 20);
+    check!(stripped_log_count == 5);
+
+    let restored_entries = oplog_service
+        .read_with_logs(&owned_worker_id, first_idx, 20)
+        .await;
+    assert_eq!(restored_entries.into_iter().collect::<Vec<_>>(), entries);
+}
+
+#[test]
+async fn recorded_result_chunks_are_read_back_in_order(_tracing: &Tracing) {
+    let indexed_storage = Arc::new(InMemoryIndexedStorage::new());
+    let blob_storage = Arc::new(InMemoryBlobStorage::new());
+    let oplog_service = PrimaryOplogService::new(indexed_storage, blob_storage, 1, 100).await;
+    let account_id = AccountId {
+        value: "user1".to_string(),
+    };
+    let worker_id = WorkerId {
+        component_id: ComponentId(Uuid::new_v4()),
+        worker_name: WorkerName::from_existing("test"),
+    };
+    let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+    let last_oplog_index = oplog_service.get_last_index(&owned_worker_id).await;
+    let oplog = oplog_service
+        .open(&owned_worker_id, last_oplog_index, ComponentType::Durable)
+        .await;
+
+    let add_chunk = |chunk: Result<Vec<u8>, SerializableStreamError>| {
+        let oplog = oplog.clone();
+        async move {
+            oplog
+                .add_imported_function_invoked(
+                    RESULT_STREAM_READ_FUNCTION.to_string(),
+                    &(),
+                    &chunk,
+                    WrappedFunctionType::ReadLocal,
+                )
+                .await
+                .unwrap();
+        }
+    };
+
+    // A previous streamed invocation, whose chunks must not be returned
+    oplog
+        .add_exported_function_invoked("f".to_string(), &(), IdempotencyKey::fresh())
+        .await
+        .unwrap();
+    add_chunk(Ok(vec![0])).await;
+    add_chunk(Err(SerializableStreamError::Closed)).await;
+    oplog.add_exported_function_completed(&(), 0).await.unwrap();
+
+    let idempotency_key = IdempotencyKey::fresh();
+    oplog
+        .add_exported_function_invoked("f".to_string(), &(), idempotency_key.clone())
+        .await
+        .unwrap();
+    add_chunk(Ok(vec![1, 2])).await;
+    add_chunk(Ok(vec![3])).await;
+    add_chunk(Err(SerializableStreamError::Closed)).await;
+    oplog.add_exported_function_completed(&(), 0).await.unwrap();
+    oplog.commit(CommitLevel::Always).await;
+    let completed_at = oplog.current_oplog_index().await;
+
+    let chunks = recorded_result_chunks(&oplog, &idempotency_key, completed_at)
+        .await
+        .unwrap();
+    check!(chunks == vec![vec![1, 2], vec![3]]);
+}
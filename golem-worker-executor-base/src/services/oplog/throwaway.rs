@@ -0,0 +1,114 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::services::oplog::{CommitLevel, Oplog};
+use async_mutex::Mutex;
+use async_trait::async_trait;
+use bytes::Bytes;
+use golem_common::model::oplog::{OplogEntry, OplogIndex, OplogPayload};
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// An oplog which is never persisted, used by throwaway instances of a worker.
+///
+/// The already existing entries and payloads are read from the worker's real oplog, while the
+/// new entries are only kept in memory and dropped together with the oplog.
+pub struct ThrowawayOplog {
+    base: Arc<dyn Oplog + Send + Sync>,
+    base_index: OplogIndex,
+    entries: Mutex<Vec<OplogEntry>>,
+}
+
+impl ThrowawayOplog {
+    pub async fn new(base: Arc<dyn Oplog + Send + Sync>) -> Self {
+        let base_index = base.current_oplog_index().await;
+        Self::at(base, base_index)
+    }
+
+    /// Creates a throwaway oplog continuing the worker's real oplog from `base_index`, ignoring
+    /// the entries following it
+    pub fn at(base: Arc<dyn Oplog + Send + Sync>, base_index: OplogIndex) -> Self {
+        Self {
+            base,
+            base_index,
+            entries: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl Debug for ThrowawayOplog {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ThrowawayOplog")
+            .field("base", &self.base)
+            .field("base_index", &self.base_index)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl Oplog for ThrowawayOplog {
+    async fn add(&self, entry: OplogEntry) {
+        self.entries.lock().await.push(entry);
+    }
+
+    async fn drop_prefix(&self, _last_dropped_id: OplogIndex) {}
+
+    async fn commit(&self, _level: CommitLevel) {}
+
+    async fn current_oplog_index(&self) -> OplogIndex {
+        let added = self.entries.lock().await.len() as u64;
+        OplogIndex::from_u64(u64::from(self.base_index) + added)
+    }
+
+    async fn wait_for_replicas(&self, _replicas: u8, _timeout: Duration) -> bool {
+        // Not supported
+        false
+    }
+
+    async fn read(&self, oplog_index: OplogIndex) -> OplogEntry {
+        if oplog_index > self.base_index {
+            let offset = u64::from(oplog_index) - u64::from(self.base_index);
+            self.entries.lock().await[offset as usize - 1].clone()
+        } else {
+            self.base.read(oplog_index).await
+        }
+    }
+
+    async fn length(&self) -> u64 {
+        let added = self.entries.lock().await.len() as u64;
+        self.base.length().await.min(u64::from(self.base_index)) + added
+    }
+
+    async fn upload_payload(&self, data: &[u8]) -> Result<OplogPayload, String> {
+        // Never uploading anything to the blob storage
+        Ok(OplogPayload::Inline(data.to_vec()))
+    }
+
+    async fn upload_payload_with_limit(
+        &self,
+        data: &[u8],
+        _max_inline_size: usize,
+    ) -> Result<OplogPayload, String> {
+        self.upload_payload(data).await
+    }
+
+    async fn download_payload(&self, payload: &OplogPayload) -> Result<Bytes, String> {
+        match payload {
+            OplogPayload::Inline(data) => Ok(Bytes::copy_from_slice(data)),
+            // Stored or encrypted by the base oplog
+            _ => self.base.download_payload(payload).await,
+        }
+    }
+}
@@ -0,0 +1,110 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use tokio::task::JoinHandle;
+use tracing::{debug, warn, Instrument};
+
+use golem_common::model::ComponentId;
+
+use crate::error::GolemError;
+use crate::services::{
+    HasActiveWorkers, HasComponentService, HasConfig, HasShardService, HasWasmtimeEngine,
+};
+use crate::workerctx::WorkerCtx;
+
+/// Periodically prepares the latest versions of the components configured in `prewarm`, so
+/// the first invocation of their new workers does not have to wait for compilation and linking.
+///
+/// Returns `None` if no component is configured.
+pub fn start<Ctx, T>(deps: T) -> Option<JoinHandle<()>>
+where
+    Ctx: WorkerCtx,
+    T: HasActiveWorkers<Ctx>
+        + HasComponentService
+        + HasConfig
+        + HasShardService
+        + HasWasmtimeEngine<Ctx>
+        + Send
+        + Sync
+        + 'static,
+{
+    let config = deps.config().prewarm.clone();
+    if config.components.is_empty() {
+        return None;
+    }
+
+    Some(tokio::spawn(
+        async move {
+            let mut interval = tokio::time::interval(config.refresh_interval);
+            loop {
+                interval.tick().await;
+                prewarm_components(&deps, &config.components).await;
+            }
+        }
+        .in_current_span(),
+    ))
+}
+
+async fn prewarm_components<Ctx, T>(deps: &T, component_ids: &[ComponentId])
+where
+    Ctx: WorkerCtx,
+    T: HasActiveWorkers<Ctx>
+        + HasComponentService
+        + HasShardService
+        + HasWasmtimeEngine<Ctx>
+        + Send
+        + Sync,
+{
+    // Only executors owning shards get new workers, the others don't need to keep anything in memory
+    let owns_shards = deps
+        .shard_service()
+        .try_get_current_assignment()
+        .is_some_and(|assignment| !assignment.shard_ids.is_empty());
+    if !owns_shards {
+        deps.active_workers().retain_prewarmed(&[]);
+        return;
+    }
+
+    for component_id in component_ids {
+        if let Err(err) = prewarm_component(deps, component_id).await {
+            warn!(
+                component_id = component_id.to_string(),
+                "Failed to prewarm component: {err}"
+            );
+        }
+    }
+}
+
+async fn prewarm_component<Ctx, T>(deps: &T, component_id: &ComponentId) -> Result<(), GolemError>
+where
+    Ctx: WorkerCtx,
+    T: HasActiveWorkers<Ctx> + HasComponentService + HasWasmtimeEngine<Ctx> + Send + Sync,
+{
+    let metadata = deps
+        .component_service()
+        .get_metadata(component_id, None)
+        .await?;
+    let (component, _) = deps
+        .component_service()
+        .get(&deps.engine(), component_id, metadata.version)
+        .await?;
+    deps.active_workers()
+        .prewarm(&deps.linker(), &component, component_id, metadata.version)?;
+    debug!(
+        component_id = component_id.to_string(),
+        component_version = metadata.version,
+        "Component prewarmed"
+    );
+    Ok(())
+}
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeSet;
 #[cfg(test)]
 use std::collections::HashSet;
 use std::ops::DerefMut;
@@ -22,9 +23,10 @@ use async_trait::async_trait;
 use bincode::{Decode, Encode};
 use dashmap::DashMap;
 use golem_common::model::oplog::OplogIndex;
-use golem_common::model::{PromiseId, WorkerId};
-use tokio::sync::oneshot;
-use tracing::debug;
+use golem_common::model::{ComponentId, PromiseId, WorkerId, WorkerName};
+use tokio::sync::{oneshot, OnceCell};
+use tracing::{debug, info};
+use uuid::Uuid;
 
 use crate::error::GolemError;
 use crate::metrics::promises::record_promise_created;
@@ -44,12 +46,33 @@ pub trait PromiseService {
     async fn complete(&self, promise_id: PromiseId, data: Vec<u8>) -> Result<bool, GolemError>;
 
     async fn delete(&self, promise_id: PromiseId);
+
+    /// Lists the workers which have stored promises. A worker stays listed until
+    /// `delete_all` gets called for it, even if its promises got deleted one by one.
+    async fn workers(&self) -> Result<Vec<WorkerId>, GolemError>;
+
+    /// Lists the promises of the worker which were created and not deleted yet, in the order
+    /// of their oplog index
+    async fn list(&self, worker_id: &WorkerId) -> Result<Vec<PromiseRecord>, GolemError>;
+
+    /// Deletes all the promises of the worker and removes it from the listed workers, returning
+    /// the number of deleted promises
+    async fn delete_all(&self, worker_id: &WorkerId) -> Result<usize, GolemError>;
+}
+
+/// A stored promise
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromiseRecord {
+    pub promise_id: PromiseId,
+    /// Whether the promise has been completed, but not deleted yet
+    pub completed: bool,
 }
 
 #[derive(Clone, Debug)]
 pub struct DefaultPromiseService {
     key_value_storage: Arc<dyn KeyValueStorage + Send + Sync>,
     promises: Arc<DashMap<PromiseId, PromiseState>>,
+    indexed: Arc<OnceCell<()>>,
 }
 
 impl DefaultPromiseService {
@@ -57,9 +80,95 @@ impl DefaultPromiseService {
         Self {
             key_value_storage,
             promises: Arc::new(DashMap::new()),
+            indexed: Arc::new(OnceCell::new()),
         }
     }
 
+    async fn add_to_index(&self, promise_id: &PromiseId) -> Result<(), String> {
+        // The promise is added to the worker's set first, so a listed worker always finds it
+        self.key_value_storage
+            .with_entity("promise", "create", "promise-index")
+            .add_to_set(
+                KeyValueStorageNamespace::Promise,
+                &get_worker_promises_redis_key(&promise_id.worker_id),
+                &promise_id.oplog_idx,
+            )
+            .await?;
+        self.key_value_storage
+            .with_entity("promise", "create", "promise-index")
+            .add_to_set(
+                KeyValueStorageNamespace::Promise,
+                PROMISE_WORKERS_REDIS_KEY,
+                &promise_id.worker_id,
+            )
+            .await
+    }
+
+    /// Promises created before the index existed are only stored in the promise hash, so they
+    /// get indexed once by scanning it. The scan is skipped after any executor completed it.
+    async fn ensure_indexed(&self) -> Result<(), GolemError> {
+        self.indexed
+            .get_or_try_init(|| async {
+                let storage = self.key_value_storage.with("promise", "index");
+                let indexed = storage
+                    .exists(KeyValueStorageNamespace::Promise, PROMISE_INDEXED_REDIS_KEY)
+                    .await
+                    .map_err(|err| {
+                        GolemError::unknown(format!("Failed to check the promise index: {err}"))
+                    })?;
+                if !indexed {
+                    let keys = storage
+                        .keys(KeyValueStorageNamespace::Promise)
+                        .await
+                        .map_err(|err| {
+                            GolemError::unknown(format!("Failed to list promises: {err}"))
+                        })?;
+                    let mut count = 0;
+                    for key in keys {
+                        if let Some((promise_id, false)) = parse_promise_redis_key(&key) {
+                            self.add_to_index(&promise_id).await.map_err(|err| {
+                                GolemError::unknown(format!("Failed to index promises: {err}"))
+                            })?;
+                            count += 1;
+                        }
+                    }
+                    self.key_value_storage
+                        .with_entity("promise", "index", "promise-index")
+                        .set(
+                            KeyValueStorageNamespace::Promise,
+                            PROMISE_INDEXED_REDIS_KEY,
+                            &true,
+                        )
+                        .await
+                        .map_err(|err| {
+                            GolemError::unknown(format!("Failed to store the promise index: {err}"))
+                        })?;
+                    info!("Indexed {count} existing promises");
+                }
+                Ok::<(), GolemError>(())
+            })
+            .await
+            .map(|_| ())
+    }
+
+    async fn indexed_oplog_indices(
+        &self,
+        worker_id: &WorkerId,
+    ) -> Result<BTreeSet<OplogIndex>, GolemError> {
+        self.ensure_indexed().await?;
+        Ok(self
+            .key_value_storage
+            .with_entity("promise", "list", "promise-index")
+            .members_of_set(
+                KeyValueStorageNamespace::Promise,
+                &get_worker_promises_redis_key(worker_id),
+            )
+            .await
+            .map_err(|err| GolemError::unknown(format!("Failed to list promises: {err}")))?
+            .into_iter()
+            .collect())
+    }
+
     fn insert_if_empty(&self, key: PromiseId, value: PromiseState) {
         loop {
             match self.promises.try_entry(key.clone()) {
@@ -108,6 +217,9 @@ impl PromiseService for DefaultPromiseService {
             )
             .await
             .unwrap_or_else(|err| panic!("failed to set promise {promise_id} in Redis: {err}"));
+        self.add_to_index(&promise_id)
+            .await
+            .unwrap_or_else(|err| panic!("failed to index promise {promise_id} in Redis: {err}"));
 
         record_promise_created();
         promise_id
@@ -246,9 +358,98 @@ impl PromiseService for DefaultPromiseService {
             .unwrap_or_else(|err| {
                 panic!("failed to delete promise {promise_id} from Redis: {err}")
             });
+        self.key_value_storage
+            .with_entity("promise", "delete", "promise-index")
+            .remove_from_set(
+                KeyValueStorageNamespace::Promise,
+                &get_worker_promises_redis_key(&promise_id.worker_id),
+                &promise_id.oplog_idx,
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!("failed to remove promise {promise_id} from the index in Redis: {err}")
+            });
+    }
+
+    async fn workers(&self) -> Result<Vec<WorkerId>, GolemError> {
+        self.ensure_indexed().await?;
+        self.key_value_storage
+            .with_entity("promise", "workers", "promise-index")
+            .members_of_set(KeyValueStorageNamespace::Promise, PROMISE_WORKERS_REDIS_KEY)
+            .await
+            .map_err(|err| {
+                GolemError::unknown(format!("Failed to list the workers with promises: {err}"))
+            })
+    }
+
+    async fn list(&self, worker_id: &WorkerId) -> Result<Vec<PromiseRecord>, GolemError> {
+        let oplog_indices = self.indexed_oplog_indices(worker_id).await?;
+        let storage = self.key_value_storage.with("promise", "list");
+        let mut promises = Vec::new();
+        for oplog_idx in oplog_indices {
+            let promise_id = PromiseId {
+                worker_id: worker_id.clone(),
+                oplog_idx,
+            };
+            // The index entry may outlive the promise if deleting it was interrupted
+            let exists = storage
+                .exists(
+                    KeyValueStorageNamespace::Promise,
+                    &get_promise_redis_key(&promise_id),
+                )
+                .await
+                .map_err(|err| GolemError::unknown(format!("Failed to list promises: {err}")))?;
+            if exists {
+                // The result of a completed promise is stored next to the promise
+                let completed = storage
+                    .exists(
+                        KeyValueStorageNamespace::Promise,
+                        &get_promise_result_redis_key(&promise_id),
+                    )
+                    .await
+                    .map_err(|err| {
+                        GolemError::unknown(format!("Failed to list promises: {err}"))
+                    })?;
+                promises.push(PromiseRecord {
+                    promise_id,
+                    completed,
+                });
+            }
+        }
+        Ok(promises)
+    }
+
+    async fn delete_all(&self, worker_id: &WorkerId) -> Result<usize, GolemError> {
+        let deleted = self.list(worker_id).await?.len();
+        // Deleting every indexed promise, including the already deleted ones, leaves the set
+        // of the worker empty
+        for oplog_idx in self.indexed_oplog_indices(worker_id).await? {
+            self.delete(PromiseId {
+                worker_id: worker_id.clone(),
+                oplog_idx,
+            })
+            .await;
+        }
+        self.key_value_storage
+            .with_entity("promise", "delete_all", "promise-index")
+            .remove_from_set(
+                KeyValueStorageNamespace::Promise,
+                PROMISE_WORKERS_REDIS_KEY,
+                worker_id,
+            )
+            .await
+            .map_err(|err| {
+                GolemError::unknown(format!(
+                    "Failed to remove worker {worker_id} from the promise index: {err}"
+                ))
+            })?;
+        Ok(deleted)
     }
 }
 
+const PROMISE_WORKERS_REDIS_KEY: &str = "index:workers";
+const PROMISE_INDEXED_REDIS_KEY: &str = "index:version";
+
 fn get_promise_redis_key(promise_id: &PromiseId) -> String {
     promise_id.to_redis_key()
 }
@@ -257,6 +458,30 @@ fn get_promise_result_redis_key(promise_id: &PromiseId) -> String {
     format!("{}:completed", promise_id.to_redis_key())
 }
 
+fn get_worker_promises_redis_key(worker_id: &WorkerId) -> String {
+    format!("index:worker:{}", worker_id.to_redis_key())
+}
+
+/// Parses the keys created by `get_promise_redis_key` and `get_promise_result_redis_key`,
+/// returning whether it is the key of the result
+fn parse_promise_redis_key(key: &str) -> Option<(PromiseId, bool)> {
+    let (key, is_result) = match key.strip_suffix(":completed") {
+        Some(key) => (key, true),
+        None => (key, false),
+    };
+    // Worker names may contain colons, but component ids and oplog indices can't
+    let (worker_key, oplog_idx) = key.rsplit_once(':')?;
+    let (component_id, worker_name) = worker_key.split_once(':')?;
+    let promise_id = PromiseId {
+        worker_id: WorkerId {
+            component_id: ComponentId(Uuid::parse_str(component_id).ok()?),
+            worker_name: WorkerName::from_existing(worker_name),
+        },
+        oplog_idx: OplogIndex::from_u64(oplog_idx.parse().ok()?),
+    };
+    Some((promise_id, is_result))
+}
+
 #[derive(Debug)]
 enum PromiseState {
     Pending(
@@ -320,4 +545,151 @@ impl PromiseService for PromiseServiceMock {
     async fn delete(&self, _promise_id: PromiseId) {
         unimplemented!()
     }
+
+    async fn workers(&self) -> Result<Vec<WorkerId>, GolemError> {
+        unimplemented!()
+    }
+
+    async fn list(&self, _worker_id: &WorkerId) -> Result<Vec<PromiseRecord>, GolemError> {
+        unimplemented!()
+    }
+
+    async fn delete_all(&self, _worker_id: &WorkerId) -> Result<usize, GolemError> {
+        unimplemented!()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::sync::Arc;
+
+    use golem_common::model::oplog::OplogIndex;
+    use golem_common::model::{ComponentId, WorkerId, WorkerName};
+    use uuid::Uuid;
+
+    use crate::services::promise::{
+        get_promise_redis_key, DefaultPromiseService, PromiseRecord, PromiseService,
+        RedisPromiseState,
+    };
+    use crate::storage::keyvalue::memory::InMemoryKeyValueStorage;
+    use crate::storage::keyvalue::{KeyValueStorageLabelledApi, KeyValueStorageNamespace};
+    use golem_common::model::PromiseId;
+
+    #[test]
+    pub async fn list_returns_the_stored_promises_of_the_worker() {
+        let component_id = ComponentId(Uuid::new_v4());
+        let w1 = WorkerId {
+            component_id: component_id.clone(),
+            worker_name: WorkerName::from_existing("w1"),
+        };
+        let w2 = WorkerId {
+            component_id: component_id.clone(),
+            worker_name: WorkerName::from_existing("w:2"),
+        };
+
+        let svc = DefaultPromiseService::new(Arc::new(InMemoryKeyValueStorage::new()));
+
+        let p1 = svc.create(&w1, OplogIndex::from_u64(12)).await;
+        let p2 = svc.create(&w1, OplogIndex::from_u64(3)).await;
+        let p3 = svc.create(&w1, OplogIndex::from_u64(20)).await;
+        let p4 = svc.create(&w2, OplogIndex::from_u64(5)).await;
+
+        svc.complete(p1.clone(), vec![1, 2, 3]).await.unwrap();
+        svc.delete(p3).await;
+
+        assert_eq!(
+            svc.list(&w1).await.unwrap(),
+            vec![
+                PromiseRecord {
+                    promise_id: p2,
+                    completed: false
+                },
+                PromiseRecord {
+                    promise_id: p1,
+                    completed: true
+                },
+            ]
+        );
+        assert_eq!(
+            svc.list(&w2).await.unwrap(),
+            vec![PromiseRecord {
+                promise_id: p4,
+                completed: false
+            }]
+        );
+    }
+
+    #[test]
+    pub async fn delete_all_removes_the_promises_and_the_worker() {
+        let component_id = ComponentId(Uuid::new_v4());
+        let w1 = WorkerId {
+            component_id: component_id.clone(),
+            worker_name: WorkerName::from_existing("w1"),
+        };
+        let w2 = WorkerId {
+            component_id: component_id.clone(),
+            worker_name: WorkerName::from_existing("w2"),
+        };
+
+        let svc = DefaultPromiseService::new(Arc::new(InMemoryKeyValueStorage::new()));
+
+        let p1 = svc.create(&w1, OplogIndex::from_u64(4)).await;
+        let p2 = svc.create(&w1, OplogIndex::from_u64(9)).await;
+        let p3 = svc.create(&w2, OplogIndex::from_u64(2)).await;
+        svc.complete(p1.clone(), vec![]).await.unwrap();
+        svc.delete(p2).await;
+
+        let mut workers = svc.workers().await.unwrap();
+        workers.sort_by_key(|worker_id| worker_id.worker_name.to_string());
+        assert_eq!(workers, vec![w1.clone(), w2.clone()]);
+
+        assert_eq!(svc.delete_all(&w1).await.unwrap(), 1);
+
+        assert_eq!(svc.workers().await.unwrap(), vec![w2.clone()]);
+        assert_eq!(svc.list(&w1).await.unwrap(), vec![]);
+        assert!(svc.poll(p1).await.is_err());
+        assert_eq!(
+            svc.list(&w2).await.unwrap(),
+            vec![PromiseRecord {
+                promise_id: p3,
+                completed: false
+            }]
+        );
+    }
+
+    #[test]
+    pub async fn promises_stored_before_the_index_get_listed() {
+        let worker_id = WorkerId {
+            component_id: ComponentId(Uuid::new_v4()),
+            worker_name: WorkerName::from_existing("w:1"),
+        };
+        let promise_id = PromiseId {
+            worker_id: worker_id.clone(),
+            oplog_idx: OplogIndex::from_u64(7),
+        };
+
+        let storage = Arc::new(InMemoryKeyValueStorage::new());
+        storage
+            .with_entity("promise", "create", "promise")
+            .set(
+                KeyValueStorageNamespace::Promise,
+                &get_promise_redis_key(&promise_id),
+                &RedisPromiseState::Pending,
+            )
+            .await
+            .unwrap();
+
+        let svc = DefaultPromiseService::new(storage.clone());
+
+        assert_eq!(svc.workers().await.unwrap(), vec![worker_id.clone()]);
+        assert_eq!(
+            svc.list(&worker_id).await.unwrap(),
+            vec![PromiseRecord {
+                promise_id,
+                completed: false
+            }]
+        );
+    }
 }
@@ -0,0 +1,136 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use tokio::task::JoinHandle;
+use tracing::{info, warn, Instrument};
+
+use golem_common::model::{WorkerId, WorkerStatus};
+
+use crate::error::GolemError;
+use crate::metrics::promises::record_promises_collected;
+use crate::services::golem_config::PromiseGcPolicy;
+use crate::services::{HasConfig, HasPromiseService, HasShardService, HasWorkerService};
+
+/// Periodically completes or removes the promises of the exited and failed workers owned by the
+/// executor, according to the `promise_gc` policy.
+///
+/// Returns `None` if the garbage collection is disabled.
+pub fn start<T>(deps: T) -> Option<JoinHandle<()>>
+where
+    T: HasConfig + HasPromiseService + HasShardService + HasWorkerService + Send + Sync + 'static,
+{
+    let config = deps.config().promise_gc.clone();
+    if config.policy == PromiseGcPolicy::Disabled {
+        return None;
+    }
+
+    info!(
+        "Collecting the promises of finished workers every {:?} with policy {:?}",
+        config.interval, config.policy
+    );
+    Some(tokio::spawn(
+        async move {
+            let mut interval = tokio::time::interval(config.interval);
+            loop {
+                interval.tick().await;
+                if let Err(err) = collect_promises(&deps, config.policy).await {
+                    warn!("Failed to collect the promises of finished workers: {err}");
+                }
+            }
+        }
+        .in_current_span(),
+    ))
+}
+
+async fn collect_promises<T>(deps: &T, policy: PromiseGcPolicy) -> Result<(), GolemError>
+where
+    T: HasPromiseService + HasShardService + HasWorkerService + Send + Sync,
+{
+    if !deps.shard_service().is_ready() {
+        return Ok(());
+    }
+
+    let mut completed = 0;
+    let mut removed = 0;
+    for worker_id in deps.promise_service().workers().await? {
+        // The promises are collected by the executor owning the worker, so they are not
+        // completed while the worker gets resumed somewhere else
+        if deps.shard_service().check_worker(&worker_id).is_err() {
+            continue;
+        }
+
+        // Workers without a stored status are ephemeral or not created yet, the promises of
+        // deleted workers are collected when deleting them
+        let is_finished = deps
+            .worker_service()
+            .get_status(&worker_id)
+            .await
+            .is_some_and(|status| {
+                matches!(status.status, WorkerStatus::Exited | WorkerStatus::Failed)
+            });
+        if !is_finished {
+            continue;
+        }
+
+        match policy {
+            PromiseGcPolicy::Disabled => {}
+            PromiseGcPolicy::Complete => {
+                for record in deps.promise_service().list(&worker_id).await? {
+                    if !record.completed {
+                        deps.promise_service()
+                            .complete(record.promise_id, vec![])
+                            .await?;
+                        completed += 1;
+                    }
+                }
+            }
+            PromiseGcPolicy::Remove => {
+                removed += deps.promise_service().delete_all(&worker_id).await?;
+            }
+        }
+    }
+
+    if completed > 0 || removed > 0 {
+        info!(
+            completed,
+            removed, "Collected the promises of finished workers"
+        );
+        record_promises_collected("completed", completed);
+        record_promises_collected("removed", removed);
+    }
+    Ok(())
+}
+
+/// Removes the promises of a deleted worker, unless the garbage collection is disabled
+pub async fn collect_deleted_worker_promises<T>(
+    deps: &T,
+    worker_id: &WorkerId,
+) -> Result<(), GolemError>
+where
+    T: HasConfig + HasPromiseService + Send + Sync,
+{
+    if deps.config().promise_gc.policy == PromiseGcPolicy::Disabled {
+        return Ok(());
+    }
+
+    let removed = deps.promise_service().delete_all(worker_id).await?;
+    if removed > 0 {
+        info!(
+            removed,
+            "Removed the promises of deleted worker {worker_id}"
+        );
+        record_promises_collected("removed", removed);
+    }
+    Ok(())
+}
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::{Display, Formatter};
 use std::sync::Arc;
 
@@ -23,19 +23,23 @@ use golem_wasm_rpc::WitValue;
 use tokio::runtime::Handle;
 use tracing::debug;
 
-use golem_common::model::{IdempotencyKey, OwnedWorkerId, TargetWorkerId, WorkerId};
+use golem_common::model::{
+    IdempotencyKey, InvocationPriority, OwnedWorkerId, TargetWorkerId, WorkerId,
+};
 
 use crate::error::GolemError;
 use crate::services::events::Events;
 use crate::services::shard::ShardService;
 use crate::services::worker_proxy::{WorkerProxy, WorkerProxyError};
 use crate::services::{
-    active_workers, blob_store, component, golem_config, key_value, oplog, promise, scheduler,
-    shard, shard_manager, worker, worker_activator, worker_enumeration, HasActiveWorkers,
-    HasBlobStoreService, HasComponentService, HasConfig, HasEvents, HasExtraDeps,
-    HasKeyValueService, HasOplogService, HasPromiseService, HasRpc,
-    HasRunningWorkerEnumerationService, HasSchedulerService, HasShardManagerService,
-    HasShardService, HasWasmtimeEngine, HasWorkerActivator, HasWorkerEnumerationService,
+    active_workers, blob_store, component, golem_config, key_value, log_export, oplog, promise,
+    saga, scheduler, secrets, shard, shard_manager, topics, usage, virtual_clock, worker,
+    worker_activator, worker_enumeration, HasActiveWorkers, HasBlobStoreService,
+    HasComponentService, HasConfig, HasEvents, HasExtraDeps, HasKeyValueService,
+    HasLogExportService, HasOplogService, HasPromiseService, HasRpc,
+    HasRunningWorkerEnumerationService, HasSagaService, HasSchedulerService, HasSecretService,
+    HasShardManagerService, HasShardService, HasTopicService, HasUsageService,
+    HasVirtualClockService, HasWasmtimeEngine, HasWorkerActivator, HasWorkerEnumerationService,
     HasWorkerProxy, HasWorkerService,
 };
 use crate::worker::Worker;
@@ -45,6 +49,8 @@ use crate::workerctx::WorkerCtx;
 pub trait Rpc {
     async fn create_demand(&self, owned_worker_id: &OwnedWorkerId) -> Box<dyn RpcDemand>;
 
+    /// Invokes a function of the target worker on behalf of the calling worker, passing on the
+    /// context of the caller's current invocation
     async fn invoke_and_await(
         &self,
         owned_worker_id: &OwnedWorkerId,
@@ -54,6 +60,7 @@ pub trait Rpc {
         self_worker_id: &WorkerId,
         self_args: &[String],
         self_env: &[(String, String)],
+        self_context: &BTreeMap<String, String>,
     ) -> Result<TypeAnnotatedValue, RpcError>;
 
     async fn invoke(
@@ -65,6 +72,7 @@ pub trait Rpc {
         self_worker_id: &WorkerId,
         self_args: &[String],
         self_env: &[(String, String)],
+        self_context: &BTreeMap<String, String>,
     ) -> Result<(), RpcError>;
 
     async fn generate_unique_local_worker_id(
@@ -142,6 +150,7 @@ impl From<WorkerProxyError> for RpcError {
             WorkerProxyError::NotFound(error) => RpcError::NotFound { details: error },
             WorkerProxyError::AlreadyExists(error) => RpcError::Denied { details: error },
             WorkerProxyError::InternalError(error) => error.into(),
+            WorkerProxyError::Forbidden(error) => RpcError::Denied { details: error },
         }
     }
 }
@@ -201,6 +210,7 @@ impl Rpc for RemoteInvocationRpc {
         self_worker_id: &WorkerId,
         self_args: &[String],
         self_env: &[(String, String)],
+        self_context: &BTreeMap<String, String>,
     ) -> Result<TypeAnnotatedValue, RpcError> {
         Ok(self
             .worker_proxy
@@ -212,6 +222,7 @@ impl Rpc for RemoteInvocationRpc {
                 self_worker_id.clone(),
                 self_args.to_vec(),
                 HashMap::from_iter(self_env.to_vec()),
+                self_context.clone(),
             )
             .await?)
     }
@@ -225,6 +236,7 @@ impl Rpc for RemoteInvocationRpc {
         self_worker_id: &WorkerId,
         self_args: &[String],
         self_env: &[(String, String)],
+        self_context: &BTreeMap<String, String>,
     ) -> Result<(), RpcError> {
         Ok(self
             .worker_proxy
@@ -233,9 +245,10 @@ impl Rpc for RemoteInvocationRpc {
                 idempotency_key,
                 function_name,
                 function_params,
-                self_worker_id.clone(),
+                Some(self_worker_id.clone()),
                 self_args.to_vec(),
                 HashMap::from_iter(self_env.to_vec()),
+                self_context.clone(),
             )
             .await?)
     }
@@ -248,10 +261,73 @@ impl Rpc for RemoteInvocationRpc {
         Ok(target_worker_id.into_worker_id(
             &current_assignment.shard_ids,
             current_assignment.number_of_shards,
+            None,
         ))
     }
 }
 
+/// Rpc implementation of the throwaway instances of workers, which cannot invoke other workers
+pub struct SandboxedRpc {
+    inner: Arc<dyn Rpc + Send + Sync>,
+}
+
+impl SandboxedRpc {
+    pub fn new(inner: Arc<dyn Rpc + Send + Sync>) -> Self {
+        Self { inner }
+    }
+
+    fn denied() -> RpcError {
+        RpcError::Denied {
+            details: "Throwaway instances of a worker cannot invoke other workers".to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl Rpc for SandboxedRpc {
+    async fn create_demand(&self, owned_worker_id: &OwnedWorkerId) -> Box<dyn RpcDemand> {
+        let demand = LoggingDemand::new(owned_worker_id.worker_id());
+        Box::new(demand)
+    }
+
+    async fn invoke_and_await(
+        &self,
+        _owned_worker_id: &OwnedWorkerId,
+        _idempotency_key: Option<IdempotencyKey>,
+        _function_name: String,
+        _function_params: Vec<WitValue>,
+        _self_worker_id: &WorkerId,
+        _self_args: &[String],
+        _self_env: &[(String, String)],
+        _self_context: &BTreeMap<String, String>,
+    ) -> Result<TypeAnnotatedValue, RpcError> {
+        Err(Self::denied())
+    }
+
+    async fn invoke(
+        &self,
+        _owned_worker_id: &OwnedWorkerId,
+        _idempotency_key: Option<IdempotencyKey>,
+        _function_name: String,
+        _function_params: Vec<WitValue>,
+        _self_worker_id: &WorkerId,
+        _self_args: &[String],
+        _self_env: &[(String, String)],
+        _self_context: &BTreeMap<String, String>,
+    ) -> Result<(), RpcError> {
+        Err(Self::denied())
+    }
+
+    async fn generate_unique_local_worker_id(
+        &self,
+        target_worker_id: TargetWorkerId,
+    ) -> Result<WorkerId, GolemError> {
+        self.inner
+            .generate_unique_local_worker_id(target_worker_id)
+            .await
+    }
+}
+
 pub struct DirectWorkerInvocationRpc<Ctx: WorkerCtx> {
     remote_rpc: Arc<RemoteInvocationRpc>,
     active_workers: Arc<active_workers::ActiveWorkers<Ctx>>,
@@ -269,10 +345,16 @@ pub struct DirectWorkerInvocationRpc<Ctx: WorkerCtx> {
     shard_service: Arc<dyn shard::ShardService + Send + Sync>,
     key_value_service: Arc<dyn key_value::KeyValueService + Send + Sync>,
     blob_store_service: Arc<dyn blob_store::BlobStoreService + Send + Sync>,
+    secret_service: Arc<dyn secrets::SecretService + Send + Sync>,
+    topic_service: Arc<dyn topics::TopicService + Send + Sync>,
+    saga_service: Arc<dyn saga::SagaService + Send + Sync>,
+    virtual_clock_service: Arc<dyn virtual_clock::VirtualClockService + Send + Sync>,
     oplog_service: Arc<dyn oplog::OplogService + Send + Sync>,
     scheduler_service: Arc<dyn scheduler::SchedulerService + Send + Sync>,
     worker_activator: Arc<dyn worker_activator::WorkerActivator + Send + Sync>,
     events: Arc<Events>,
+    log_export_service: Arc<dyn log_export::LogExportService + Send + Sync>,
+    usage_service: Arc<dyn usage::UsageService + Send + Sync>,
     extra_deps: Ctx::ExtraDeps,
 }
 
@@ -294,10 +376,16 @@ impl<Ctx: WorkerCtx> Clone for DirectWorkerInvocationRpc<Ctx> {
             shard_service: self.shard_service.clone(),
             key_value_service: self.key_value_service.clone(),
             blob_store_service: self.blob_store_service.clone(),
+            secret_service: self.secret_service.clone(),
+            topic_service: self.topic_service.clone(),
+            saga_service: self.saga_service.clone(),
+            virtual_clock_service: self.virtual_clock_service.clone(),
             oplog_service: self.oplog_service.clone(),
             scheduler_service: self.scheduler_service.clone(),
             worker_activator: self.worker_activator.clone(),
             events: self.events.clone(),
+            log_export_service: self.log_export_service.clone(),
+            usage_service: self.usage_service.clone(),
             extra_deps: self.extra_deps.clone(),
         }
     }
@@ -309,6 +397,18 @@ impl<Ctx: WorkerCtx> HasEvents for DirectWorkerInvocationRpc<Ctx> {
     }
 }
 
+impl<Ctx: WorkerCtx> HasLogExportService for DirectWorkerInvocationRpc<Ctx> {
+    fn log_export_service(&self) -> Arc<dyn log_export::LogExportService + Send + Sync> {
+        self.log_export_service.clone()
+    }
+}
+
+impl<Ctx: WorkerCtx> HasUsageService for DirectWorkerInvocationRpc<Ctx> {
+    fn usage_service(&self) -> Arc<dyn usage::UsageService + Send + Sync> {
+        self.usage_service.clone()
+    }
+}
+
 impl<Ctx: WorkerCtx> HasActiveWorkers<Ctx> for DirectWorkerInvocationRpc<Ctx> {
     fn active_workers(&self) -> Arc<active_workers::ActiveWorkers<Ctx>> {
         self.active_workers.clone()
@@ -381,6 +481,30 @@ impl<Ctx: WorkerCtx> HasBlobStoreService for DirectWorkerInvocationRpc<Ctx> {
     }
 }
 
+impl<Ctx: WorkerCtx> HasSecretService for DirectWorkerInvocationRpc<Ctx> {
+    fn secret_service(&self) -> Arc<dyn secrets::SecretService + Send + Sync> {
+        self.secret_service.clone()
+    }
+}
+
+impl<Ctx: WorkerCtx> HasTopicService for DirectWorkerInvocationRpc<Ctx> {
+    fn topic_service(&self) -> Arc<dyn topics::TopicService + Send + Sync> {
+        self.topic_service.clone()
+    }
+}
+
+impl<Ctx: WorkerCtx> HasSagaService for DirectWorkerInvocationRpc<Ctx> {
+    fn saga_service(&self) -> Arc<dyn saga::SagaService + Send + Sync> {
+        self.saga_service.clone()
+    }
+}
+
+impl<Ctx: WorkerCtx> HasVirtualClockService for DirectWorkerInvocationRpc<Ctx> {
+    fn virtual_clock_service(&self) -> Arc<dyn virtual_clock::VirtualClockService + Send + Sync> {
+        self.virtual_clock_service.clone()
+    }
+}
+
 impl<Ctx: WorkerCtx> HasSchedulerService for DirectWorkerInvocationRpc<Ctx> {
     fn scheduler_service(&self) -> Arc<dyn scheduler::SchedulerService + Send + Sync> {
         self.scheduler_service.clone()
@@ -450,10 +574,16 @@ impl<Ctx: WorkerCtx> DirectWorkerInvocationRpc<Ctx> {
         shard_manager_service: Arc<dyn shard_manager::ShardManagerService + Send + Sync>,
         key_value_service: Arc<dyn key_value::KeyValueService + Send + Sync>,
         blob_store_service: Arc<dyn blob_store::BlobStoreService + Send + Sync>,
+        secret_service: Arc<dyn secrets::SecretService + Send + Sync>,
+        topic_service: Arc<dyn topics::TopicService + Send + Sync>,
+        saga_service: Arc<dyn saga::SagaService + Send + Sync>,
+        virtual_clock_service: Arc<dyn virtual_clock::VirtualClockService + Send + Sync>,
         oplog_service: Arc<dyn oplog::OplogService + Send + Sync>,
         scheduler_service: Arc<dyn scheduler::SchedulerService + Send + Sync>,
         worker_activator: Arc<dyn worker_activator::WorkerActivator + Send + Sync>,
         events: Arc<Events>,
+        log_export_service: Arc<dyn log_export::LogExportService + Send + Sync>,
+        usage_service: Arc<dyn usage::UsageService + Send + Sync>,
         extra_deps: Ctx::ExtraDeps,
     ) -> Self {
         Self {
@@ -472,10 +602,16 @@ impl<Ctx: WorkerCtx> DirectWorkerInvocationRpc<Ctx> {
             shard_service,
             key_value_service,
             blob_store_service,
+            secret_service,
+            topic_service,
+            saga_service,
+            virtual_clock_service,
             oplog_service,
             scheduler_service,
             worker_activator,
             events,
+            log_export_service,
+            usage_service,
             extra_deps,
         }
     }
@@ -497,6 +633,7 @@ impl<Ctx: WorkerCtx> Rpc for DirectWorkerInvocationRpc<Ctx> {
         self_worker_id: &WorkerId,
         self_args: &[String],
         self_env: &[(String, String)],
+        self_context: &BTreeMap<String, String>,
     ) -> Result<TypeAnnotatedValue, RpcError> {
         let idempotency_key = idempotency_key.unwrap_or(IdempotencyKey::fresh());
 
@@ -523,7 +660,14 @@ impl<Ctx: WorkerCtx> Rpc for DirectWorkerInvocationRpc<Ctx> {
             .await?;
 
             let result_values = worker
-                .invoke_and_await(idempotency_key, function_name, input_values)
+                .invoke_and_await(
+                    idempotency_key,
+                    function_name,
+                    input_values,
+                    InvocationPriority::Normal,
+                    self_context.clone(),
+                    None,
+                )
                 .await?;
 
             Ok(result_values)
@@ -537,6 +681,7 @@ impl<Ctx: WorkerCtx> Rpc for DirectWorkerInvocationRpc<Ctx> {
                     self_worker_id,
                     self_args,
                     self_env,
+                    self_context,
                 )
                 .await
         }
@@ -551,6 +696,7 @@ impl<Ctx: WorkerCtx> Rpc for DirectWorkerInvocationRpc<Ctx> {
         self_worker_id: &WorkerId,
         self_args: &[String],
         self_env: &[(String, String)],
+        self_context: &BTreeMap<String, String>,
     ) -> Result<(), RpcError> {
         let idempotency_key = idempotency_key.unwrap_or(IdempotencyKey::fresh()); // TODO
 
@@ -577,7 +723,13 @@ impl<Ctx: WorkerCtx> Rpc for DirectWorkerInvocationRpc<Ctx> {
             .await?;
 
             worker
-                .invoke(idempotency_key, function_name, input_values)
+                .invoke(
+                    idempotency_key,
+                    function_name,
+                    input_values,
+                    InvocationPriority::Normal,
+                    self_context.clone(),
+                )
                 .await?;
             Ok(())
         } else {
@@ -590,6 +742,7 @@ impl<Ctx: WorkerCtx> Rpc for DirectWorkerInvocationRpc<Ctx> {
                     self_worker_id,
                     self_args,
                     self_env,
+                    self_context,
                 )
                 .await
         }
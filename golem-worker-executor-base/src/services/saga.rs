@@ -0,0 +1,1027 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bincode::{Decode, Encode};
+use golem_wasm_rpc::WitValue;
+use tokio::task::JoinHandle;
+use tracing::{info, warn, Instrument};
+use uuid::Uuid;
+
+use golem_common::model::oplog::{OplogEntry, OplogIndex};
+use golem_common::model::regions::DeletedRegions;
+use golem_common::model::{IdempotencyKey, OwnedWorkerId, Timestamp, WorkerId};
+use golem_common::serialization::{deserialize, serialize};
+
+use crate::durable_host::serialized::SerializableError;
+use crate::error::GolemError;
+use crate::services::oplog::OplogService;
+use crate::services::rpc::Rpc;
+use crate::services::{HasConfig, HasRpc, HasSagaService, HasShardService};
+use crate::storage::keyvalue::{
+    KeyValueStorage, KeyValueStorageLabelledApi, KeyValueStorageNamespace,
+};
+
+/// Service storing the sagas of the workers.
+///
+/// A saga is a list of compensation actions registered by a worker while it performs the steps
+/// of a transaction. The compensations are invocations of other workers, and they are run in
+/// reverse order when the worker aborts the saga or when the worker fails permanently.
+#[async_trait]
+pub trait SagaService {
+    /// Lists the sagas of the worker which are open, being compensated or failed to compensate
+    async fn list(&self, owned_worker_id: &OwnedWorkerId) -> Result<Vec<Saga>, GolemError>;
+
+    async fn begin(&self, owned_worker_id: &OwnedWorkerId, saga_id: u64) -> Result<(), GolemError>;
+
+    async fn add_compensation(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        saga_id: u64,
+        compensation: Compensation,
+    ) -> Result<(), GolemError>;
+
+    /// Drops the saga without running its compensations
+    async fn complete(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        saga_id: u64,
+    ) -> Result<(), GolemError>;
+
+    /// Marks the saga as being compensated and returns it
+    async fn start_compensation(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        saga_id: u64,
+    ) -> Result<Saga, GolemError>;
+
+    /// Removes the saga if all of its compensations succeeded, otherwise keeps it with the error
+    async fn finish_compensation(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        saga_id: u64,
+        result: Result<(), String>,
+    ) -> Result<(), GolemError>;
+
+    /// Replaces the sagas of the worker with the ones rebuilt from its oplog, used when the
+    /// history of the worker changes by a jump, a deleted oplog region or a fork. Sagas already
+    /// being compensated or failed to compensate keep their stored state.
+    async fn restore(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        sagas: Vec<Saga>,
+    ) -> Result<(), GolemError>;
+
+    /// Removes every saga of the worker, used when the worker gets deleted
+    async fn remove_all(&self, owned_worker_id: &OwnedWorkerId) -> Result<(), GolemError>;
+
+    /// Stores that the sagas of the failed worker have to be compensated, so compensating them
+    /// is resumed if it gets interrupted
+    async fn mark_failed(&self, failed: &FailedSagaWorker) -> Result<(), GolemError>;
+
+    /// Lists the failed workers of every account whose sagas may not be compensated yet
+    async fn failed_workers(&self) -> Result<Vec<FailedSagaWorker>, GolemError>;
+
+    /// Removes the worker from the failed workers once all of its sagas got compensated
+    async fn compensated(&self, owned_worker_id: &OwnedWorkerId) -> Result<(), GolemError>;
+}
+
+#[derive(Clone, Debug, Encode, Decode)]
+pub struct Saga {
+    /// The oplog index of the worker when the saga was started
+    pub id: u64,
+    pub state: SagaState,
+    /// The compensations in the order they were registered
+    pub compensations: Vec<Compensation>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub enum SagaState {
+    Open,
+    Compensating,
+    CompensationFailed { error: String },
+}
+
+#[derive(Clone, Debug, Encode, Decode)]
+pub struct Compensation {
+    pub target: WorkerId,
+    pub function_name: String,
+    pub function_params: Vec<WitValue>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct FailedSagaWorker {
+    pub owned_worker_id: OwnedWorkerId,
+    /// Arguments and environment of the worker, passed on to the compensation invocations
+    pub args: Vec<String>,
+    pub env: Vec<(String, String)>,
+    pub failed_at: Timestamp,
+}
+
+/// The idempotency key of running a compensation, making repeated runs of the same compensation
+/// a single invocation of the target
+pub fn compensation_idempotency_key(
+    worker_id: &WorkerId,
+    saga_id: u64,
+    index: usize,
+) -> IdempotencyKey {
+    let name = format!("saga:{worker_id}:{saga_id}:{index}");
+    IdempotencyKey::from_uuid(Uuid::new_v5(&Uuid::NAMESPACE_OID, name.as_bytes()))
+}
+
+/// Invokes the compensations of the saga one by one, the last registered one first, stopping
+/// at the first failing one
+pub async fn run_compensations(
+    rpc: &Arc<dyn Rpc + Send + Sync>,
+    owned_worker_id: &OwnedWorkerId,
+    saga: &Saga,
+    self_args: &[String],
+    self_env: &[(String, String)],
+) -> Result<(), String> {
+    for (index, compensation) in saga.compensations.iter().enumerate().rev() {
+        let target = OwnedWorkerId::new(&owned_worker_id.account_id, &compensation.target);
+        rpc.invoke_and_await(
+            &target,
+            Some(compensation_idempotency_key(
+                &owned_worker_id.worker_id,
+                saga.id,
+                index,
+            )),
+            compensation.function_name.clone(),
+            compensation.function_params.clone(),
+            &owned_worker_id.worker_id,
+            self_args,
+            self_env,
+            &BTreeMap::new(),
+        )
+        .await
+        .map_err(|err| {
+            format!(
+                "Compensation {} of {} on {} failed: {err}",
+                index, compensation.function_name, compensation.target
+            )
+        })?;
+    }
+    Ok(())
+}
+
+/// Compensates the sagas left open by a worker which failed permanently. The worker is stored
+/// as failed first, then the compensations run in the background, as they invoke other workers.
+/// If they get interrupted, they are resumed by the task started with [start].
+pub async fn compensate_failed_worker(
+    saga_service: Arc<dyn SagaService + Send + Sync>,
+    rpc: Arc<dyn Rpc + Send + Sync>,
+    owned_worker_id: OwnedWorkerId,
+    self_args: Vec<String>,
+    self_env: Vec<(String, String)>,
+) {
+    let failed = FailedSagaWorker {
+        owned_worker_id,
+        args: self_args,
+        env: self_env,
+        failed_at: Timestamp::now_utc(),
+    };
+    if let Err(err) = saga_service.mark_failed(&failed).await {
+        warn!("Failed to store the failed worker for compensating its sagas: {err}");
+    }
+    tokio::spawn(
+        async move {
+            if let Err(err) = compensate_sagas(&saga_service, &rpc, &failed).await {
+                warn!("Failed to compensate the sagas of the failed worker: {err}");
+            }
+        }
+        .in_current_span(),
+    );
+}
+
+/// Runs the compensations of every saga of the failed worker not compensated yet, and removes
+/// the worker from the failed workers if none of them got interrupted. Sagas whose
+/// compensation fails are kept in failed state, and they are not retried.
+async fn compensate_sagas(
+    saga_service: &Arc<dyn SagaService + Send + Sync>,
+    rpc: &Arc<dyn Rpc + Send + Sync>,
+    failed: &FailedSagaWorker,
+) -> Result<(), GolemError> {
+    let owned_worker_id = &failed.owned_worker_id;
+    for saga in saga_service.list(owned_worker_id).await? {
+        if matches!(saga.state, SagaState::CompensationFailed { .. }) {
+            continue;
+        }
+        let saga = saga_service
+            .start_compensation(owned_worker_id, saga.id)
+            .await?;
+        let result =
+            run_compensations(rpc, owned_worker_id, &saga, &failed.args, &failed.env).await;
+        match &result {
+            Ok(()) => info!(
+                saga_id = saga.id,
+                "Compensated the saga of the failed worker"
+            ),
+            Err(err) => warn!(
+                saga_id = saga.id,
+                "Failed to compensate the saga of the failed worker: {err}"
+            ),
+        }
+        saga_service
+            .finish_compensation(owned_worker_id, saga.id, result)
+            .await?;
+    }
+    saga_service.compensated(owned_worker_id).await
+}
+
+/// Starts resuming the compensations of the failed workers owned by this executor, which did
+/// not finish because the executor was stopped or the storage was unavailable
+pub fn start<T>(deps: T) -> Option<JoinHandle<()>>
+where
+    T: HasConfig + HasSagaService + HasRpc + HasShardService + Send + Sync + 'static,
+{
+    let interval = deps.config().sagas.compensation_interval;
+    info!("Resuming the compensations of failed workers' sagas every {interval:?}");
+    Some(tokio::spawn(
+        async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(err) = resume_compensations(&deps, interval.as_millis() as u64).await {
+                    warn!("Failed to resume the compensations of failed workers: {err}");
+                }
+            }
+        }
+        .in_current_span(),
+    ))
+}
+
+async fn resume_compensations<T>(deps: &T, min_age_millis: u64) -> Result<(), GolemError>
+where
+    T: HasSagaService + HasRpc + HasShardService + Send + Sync,
+{
+    if !deps.shard_service().is_ready() {
+        return Ok(());
+    }
+
+    let now = Timestamp::now_utc().to_millis();
+    for failed in deps.saga_service().failed_workers().await? {
+        // Workers which just failed are still being compensated by the task spawned on failure
+        if now.saturating_sub(failed.failed_at.to_millis()) < min_age_millis
+            || deps
+                .shard_service()
+                .check_worker(&failed.owned_worker_id.worker_id)
+                .is_err()
+        {
+            continue;
+        }
+
+        if let Err(err) = compensate_sagas(&deps.saga_service(), &deps.rpc(), &failed).await {
+            warn!(
+                worker_id = failed.owned_worker_id.worker_id.to_string(),
+                "Failed to resume the compensations of the failed worker: {err}"
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Rebuilds the sagas of the worker from the saga host function calls recorded in its oplog,
+/// skipping the deleted regions, and stores them in place of the current ones
+pub async fn restore_sagas_from_oplog(
+    oplog_service: &Arc<dyn OplogService + Send + Sync>,
+    saga_service: &Arc<dyn SagaService + Send + Sync>,
+    owned_worker_id: &OwnedWorkerId,
+    deleted_regions: &DeletedRegions,
+) -> Result<(), GolemError> {
+    const PAGE_SIZE: u64 = 1000;
+
+    let last_index = oplog_service.get_last_index(owned_worker_id).await;
+    let mut sagas = Vec::new();
+    let mut next = OplogIndex::INITIAL;
+    while next <= last_index {
+        let entries = oplog_service.read(owned_worker_id, next, PAGE_SIZE).await;
+        let Some(last_read) = entries.keys().next_back().copied() else {
+            break;
+        };
+        for (index, entry) in entries {
+            if deleted_regions.is_in_deleted_region(index) {
+                continue;
+            }
+            if let OplogEntry::ImportedFunctionInvoked {
+                function_name,
+                request,
+                response,
+                ..
+            } = entry
+            {
+                if function_name.starts_with("golem saga::") {
+                    let request = oplog_service
+                        .download_payload(owned_worker_id, &request)
+                        .await
+                        .map_err(GolemError::runtime)?;
+                    let response = oplog_service
+                        .download_payload(owned_worker_id, &response)
+                        .await
+                        .map_err(GolemError::runtime)?;
+                    apply_oplog_entry(&mut sagas, &function_name, &request, &response)
+                        .map_err(GolemError::runtime)?;
+                }
+            }
+        }
+        next = last_read.next();
+    }
+
+    saga_service.restore(owned_worker_id, sagas).await
+}
+
+/// Applies a recorded saga host function call to the sagas, the failed calls are ignored
+fn apply_oplog_entry(
+    sagas: &mut Vec<Saga>,
+    function_name: &str,
+    request: &[u8],
+    response: &[u8],
+) -> Result<(), String> {
+    match function_name {
+        "golem saga::begin_saga" => {
+            let response: Result<u64, SerializableError> = deserialize(response)?;
+            if let Ok(saga_id) = response {
+                if !sagas.iter().any(|saga| saga.id == saga_id) {
+                    sagas.push(Saga {
+                        id: saga_id,
+                        state: SagaState::Open,
+                        compensations: Vec::new(),
+                    });
+                }
+            }
+        }
+        "golem saga::add_compensation" => {
+            let (saga_id, target, function_name, function_params): (
+                u64,
+                WorkerId,
+                String,
+                Vec<WitValue>,
+            ) = deserialize(request)?;
+            let response: Result<(), SerializableError> = deserialize(response)?;
+            if response.is_ok() {
+                if let Some(saga) = sagas.iter_mut().find(|saga| saga.id == saga_id) {
+                    saga.compensations.push(Compensation {
+                        target,
+                        function_name,
+                        function_params,
+                    });
+                }
+            }
+        }
+        "golem saga::complete_saga" => {
+            let saga_id: u64 = deserialize(request)?;
+            let response: Result<(), SerializableError> = deserialize(response)?;
+            if response.is_ok() {
+                sagas.retain(|saga| saga.id != saga_id);
+            }
+        }
+        "golem saga::abort_saga" => {
+            let saga_id: u64 = deserialize(request)?;
+            let response: Result<Result<(), String>, SerializableError> = deserialize(response)?;
+            match response {
+                Ok(Ok(())) => sagas.retain(|saga| saga.id != saga_id),
+                Ok(Err(error)) => {
+                    if let Some(saga) = sagas.iter_mut().find(|saga| saga.id == saga_id) {
+                        saga.state = SagaState::CompensationFailed { error };
+                    }
+                }
+                Err(_) => {}
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Saga service storing all the sagas of a worker as a single entry of the key-value storage.
+/// The entry is updated with compare-and-swap, as the worker and the compensation of its sagas
+/// after a failure may change it concurrently.
+pub struct DefaultSagaService {
+    key_value_storage: Arc<dyn KeyValueStorage + Send + Sync>,
+}
+
+impl DefaultSagaService {
+    pub fn new(key_value_storage: Arc<dyn KeyValueStorage + Send + Sync>) -> Self {
+        Self { key_value_storage }
+    }
+
+    fn namespace(owned_worker_id: &OwnedWorkerId) -> KeyValueStorageNamespace {
+        KeyValueStorageNamespace::Sagas {
+            account_id: owned_worker_id.account_id.clone(),
+        }
+    }
+
+    fn sagas_key(owned_worker_id: &OwnedWorkerId) -> String {
+        format!("worker:{}:sagas", owned_worker_id.worker_id.to_redis_key())
+    }
+
+    fn failed_worker_key(owned_worker_id: &OwnedWorkerId) -> String {
+        format!(
+            "{}:{}",
+            owned_worker_id.account_id,
+            owned_worker_id.worker_id.to_redis_key()
+        )
+    }
+
+    /// Applies `f` to the stored sagas of the worker, retrying it if they got changed
+    /// concurrently. The entry is removed when no sagas are left.
+    async fn modify<R: Send>(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        mut f: impl FnMut(&mut Vec<Saga>) -> Result<R, GolemError> + Send,
+    ) -> Result<R, GolemError> {
+        let namespace = Self::namespace(owned_worker_id);
+        let key = Self::sagas_key(owned_worker_id);
+        let storage = self
+            .key_value_storage
+            .with_entity("saga", "modify", "sagas");
+
+        loop {
+            let current = storage
+                .get_raw(namespace.clone(), &key)
+                .await
+                .map_err(GolemError::runtime)?;
+            let mut sagas: Vec<Saga> = match &current {
+                Some(bytes) => deserialize(bytes).map_err(GolemError::runtime)?,
+                None => Vec::new(),
+            };
+            let result = f(&mut sagas)?;
+            let updated = if sagas.is_empty() {
+                None
+            } else {
+                Some(serialize(&sagas).map_err(GolemError::runtime)?)
+            };
+            if storage
+                .compare_and_swap_raw(
+                    namespace.clone(),
+                    &key,
+                    current.as_deref(),
+                    updated.as_deref(),
+                )
+                .await
+                .map_err(GolemError::runtime)?
+            {
+                return Ok(result);
+            }
+        }
+    }
+
+    async fn update<R: Send>(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        saga_id: u64,
+        mut f: impl FnMut(&mut Vec<Saga>, usize) -> Result<R, GolemError> + Send,
+    ) -> Result<R, GolemError> {
+        self.modify(owned_worker_id, |sagas| {
+            let index = sagas
+                .iter()
+                .position(|saga| saga.id == saga_id)
+                .ok_or_else(|| GolemError::invalid_request(format!("Unknown saga: {saga_id}")))?;
+            f(sagas, index)
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl SagaService for DefaultSagaService {
+    async fn list(&self, owned_worker_id: &OwnedWorkerId) -> Result<Vec<Saga>, GolemError> {
+        let sagas: Option<Vec<Saga>> = self
+            .key_value_storage
+            .with_entity("saga", "list", "sagas")
+            .get(
+                Self::namespace(owned_worker_id),
+                &Self::sagas_key(owned_worker_id),
+            )
+            .await
+            .map_err(GolemError::runtime)?;
+        Ok(sagas.unwrap_or_default())
+    }
+
+    async fn begin(&self, owned_worker_id: &OwnedWorkerId, saga_id: u64) -> Result<(), GolemError> {
+        self.modify(owned_worker_id, |sagas| {
+            if !sagas.iter().any(|saga| saga.id == saga_id) {
+                sagas.push(Saga {
+                    id: saga_id,
+                    state: SagaState::Open,
+                    compensations: Vec::new(),
+                });
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    async fn add_compensation(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        saga_id: u64,
+        compensation: Compensation,
+    ) -> Result<(), GolemError> {
+        self.update(owned_worker_id, saga_id, |sagas, index| {
+            let saga = &mut sagas[index];
+            if saga.state != SagaState::Open {
+                return Err(GolemError::invalid_request(format!(
+                    "Saga {saga_id} is no longer open"
+                )));
+            }
+            saga.compensations.push(compensation.clone());
+            Ok(())
+        })
+        .await
+    }
+
+    async fn complete(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        saga_id: u64,
+    ) -> Result<(), GolemError> {
+        self.update(owned_worker_id, saga_id, |sagas, index| {
+            if sagas[index].state != SagaState::Open {
+                return Err(GolemError::invalid_request(format!(
+                    "Saga {saga_id} is no longer open"
+                )));
+            }
+            sagas.remove(index);
+            Ok(())
+        })
+        .await
+    }
+
+    async fn start_compensation(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        saga_id: u64,
+    ) -> Result<Saga, GolemError> {
+        self.update(owned_worker_id, saga_id, |sagas, index| {
+            sagas[index].state = SagaState::Compensating;
+            Ok(sagas[index].clone())
+        })
+        .await
+    }
+
+    async fn finish_compensation(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        saga_id: u64,
+        result: Result<(), String>,
+    ) -> Result<(), GolemError> {
+        self.update(owned_worker_id, saga_id, |sagas, index| {
+            match &result {
+                Ok(()) => {
+                    sagas.remove(index);
+                }
+                Err(error) => {
+                    sagas[index].state = SagaState::CompensationFailed {
+                        error: error.clone(),
+                    };
+                }
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    async fn restore(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        sagas: Vec<Saga>,
+    ) -> Result<(), GolemError> {
+        self.modify(owned_worker_id, |stored| {
+            let restored = sagas
+                .iter()
+                .cloned()
+                .map(|mut saga| {
+                    if saga.state == SagaState::Open {
+                        if let Some(existing) = stored.iter().find(|s| s.id == saga.id) {
+                            saga.state = existing.state.clone();
+                        }
+                    }
+                    saga
+                })
+                .collect();
+            *stored = restored;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn remove_all(&self, owned_worker_id: &OwnedWorkerId) -> Result<(), GolemError> {
+        self.key_value_storage
+            .with("saga", "remove_all")
+            .del(
+                Self::namespace(owned_worker_id),
+                &Self::sagas_key(owned_worker_id),
+            )
+            .await
+            .map_err(GolemError::runtime)?;
+        self.compensated(owned_worker_id).await
+    }
+
+    async fn mark_failed(&self, failed: &FailedSagaWorker) -> Result<(), GolemError> {
+        self.key_value_storage
+            .with_entity("saga", "mark_failed", "failed_worker")
+            .set(
+                KeyValueStorageNamespace::PendingCompensations,
+                &Self::failed_worker_key(&failed.owned_worker_id),
+                failed,
+            )
+            .await
+            .map_err(GolemError::runtime)
+    }
+
+    async fn failed_workers(&self) -> Result<Vec<FailedSagaWorker>, GolemError> {
+        let keys = self
+            .key_value_storage
+            .with("saga", "failed_workers")
+            .keys(KeyValueStorageNamespace::PendingCompensations)
+            .await
+            .map_err(GolemError::runtime)?;
+        let failed: Vec<Option<FailedSagaWorker>> = self
+            .key_value_storage
+            .with_entity("saga", "failed_workers", "failed_worker")
+            .get_many(KeyValueStorageNamespace::PendingCompensations, keys)
+            .await
+            .map_err(GolemError::runtime)?;
+        Ok(failed.into_iter().flatten().collect())
+    }
+
+    async fn compensated(&self, owned_worker_id: &OwnedWorkerId) -> Result<(), GolemError> {
+        self.key_value_storage
+            .with("saga", "compensated")
+            .del(
+                KeyValueStorageNamespace::PendingCompensations,
+                &Self::failed_worker_key(owned_worker_id),
+            )
+            .await
+            .map_err(GolemError::runtime)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::collections::BTreeMap;
+    use std::sync::{Arc, Mutex};
+
+    use async_trait::async_trait;
+    use golem_common::model::{
+        AccountId, ComponentId, IdempotencyKey, OwnedWorkerId, TargetWorkerId, Timestamp, WorkerId,
+        WorkerName,
+    };
+    use golem_common::serialization::serialize;
+    use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
+    use golem_wasm_rpc::protobuf::TypedTuple;
+    use golem_wasm_rpc::WitValue;
+
+    use crate::durable_host::serialized::SerializableError;
+    use crate::error::GolemError;
+    use crate::services::rpc::{Rpc, RpcDemand, RpcError};
+    use crate::services::saga::{
+        apply_oplog_entry, compensate_sagas, run_compensations, Compensation, DefaultSagaService,
+        FailedSagaWorker, SagaService, SagaState,
+    };
+    use crate::storage::keyvalue::memory::InMemoryKeyValueStorage;
+
+    /// Records the invoked functions, failing the invocations of `failing`
+    struct RecordingRpc {
+        invoked: Mutex<Vec<String>>,
+        failing: Option<String>,
+    }
+
+    impl RecordingRpc {
+        fn new(failing: Option<&str>) -> Self {
+            Self {
+                invoked: Mutex::new(Vec::new()),
+                failing: failing.map(|name| name.to_string()),
+            }
+        }
+
+        fn invoked(&self) -> Vec<String> {
+            self.invoked.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait]
+    impl Rpc for RecordingRpc {
+        async fn create_demand(&self, _owned_worker_id: &OwnedWorkerId) -> Box<dyn RpcDemand> {
+            Box::new(())
+        }
+
+        async fn invoke_and_await(
+            &self,
+            _owned_worker_id: &OwnedWorkerId,
+            _idempotency_key: Option<IdempotencyKey>,
+            function_name: String,
+            _function_params: Vec<WitValue>,
+            _self_worker_id: &WorkerId,
+            _self_args: &[String],
+            _self_env: &[(String, String)],
+            _self_context: &BTreeMap<String, String>,
+        ) -> Result<TypeAnnotatedValue, RpcError> {
+            self.invoked.lock().unwrap().push(function_name.clone());
+            if self.failing.as_ref() == Some(&function_name) {
+                Err(RpcError::RemoteInternalError {
+                    details: format!("{function_name} failed"),
+                })
+            } else {
+                Ok(TypeAnnotatedValue::Tuple(TypedTuple {
+                    typ: vec![],
+                    value: vec![],
+                }))
+            }
+        }
+
+        async fn invoke(
+            &self,
+            _owned_worker_id: &OwnedWorkerId,
+            _idempotency_key: Option<IdempotencyKey>,
+            _function_name: String,
+            _function_params: Vec<WitValue>,
+            _self_worker_id: &WorkerId,
+            _self_args: &[String],
+            _self_env: &[(String, String)],
+            _self_context: &BTreeMap<String, String>,
+        ) -> Result<(), RpcError> {
+            unimplemented!()
+        }
+
+        async fn generate_unique_local_worker_id(
+            &self,
+            _target_worker_id: TargetWorkerId,
+        ) -> Result<WorkerId, GolemError> {
+            unimplemented!()
+        }
+    }
+
+    fn owned_worker_id(name: &str) -> OwnedWorkerId {
+        OwnedWorkerId::new(
+            &AccountId {
+                value: "account".to_string(),
+            },
+            &worker_id(name),
+        )
+    }
+
+    fn failed_worker(owned_worker_id: &OwnedWorkerId) -> FailedSagaWorker {
+        FailedSagaWorker {
+            owned_worker_id: owned_worker_id.clone(),
+            args: vec![],
+            env: vec![],
+            failed_at: Timestamp::now_utc(),
+        }
+    }
+
+    fn worker_id(name: &str) -> WorkerId {
+        WorkerId {
+            component_id: ComponentId::new_v4(),
+            worker_name: WorkerName::from_existing(name),
+        }
+    }
+
+    fn compensation(target: &WorkerId, function_name: &str) -> Compensation {
+        Compensation {
+            target: target.clone(),
+            function_name: function_name.to_string(),
+            function_params: vec![],
+        }
+    }
+
+    #[test]
+    pub async fn compensations_are_kept_until_the_saga_finishes() {
+        let service = DefaultSagaService::new(Arc::new(InMemoryKeyValueStorage::new()));
+        let owned_worker_id = OwnedWorkerId::new(
+            &AccountId {
+                value: "account".to_string(),
+            },
+            &worker_id("coordinator"),
+        );
+        let target = worker_id("inventory");
+
+        service.begin(&owned_worker_id, 10).await.unwrap();
+        service.begin(&owned_worker_id, 20).await.unwrap();
+        service
+            .add_compensation(&owned_worker_id, 10, compensation(&target, "release"))
+            .await
+            .unwrap();
+        service
+            .add_compensation(&owned_worker_id, 10, compensation(&target, "refund"))
+            .await
+            .unwrap();
+        service.complete(&owned_worker_id, 20).await.unwrap();
+
+        let saga = service
+            .start_compensation(&owned_worker_id, 10)
+            .await
+            .unwrap();
+        assert_eq!(saga.state, SagaState::Compensating);
+        assert_eq!(
+            saga.compensations
+                .iter()
+                .map(|c| c.function_name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["release", "refund"]
+        );
+        assert!(service
+            .add_compensation(&owned_worker_id, 10, compensation(&target, "late"))
+            .await
+            .is_err());
+
+        service
+            .finish_compensation(&owned_worker_id, 10, Err("refund failed".to_string()))
+            .await
+            .unwrap();
+        let sagas = service.list(&owned_worker_id).await.unwrap();
+        assert_eq!(sagas.len(), 1);
+        assert_eq!(
+            sagas[0].state,
+            SagaState::CompensationFailed {
+                error: "refund failed".to_string()
+            }
+        );
+
+        service.remove_all(&owned_worker_id).await.unwrap();
+        assert!(service.list(&owned_worker_id).await.unwrap().is_empty());
+    }
+
+    #[test]
+    pub async fn aborting_runs_the_compensations_in_reverse_until_one_fails() {
+        let service = DefaultSagaService::new(Arc::new(InMemoryKeyValueStorage::new()));
+        let owned_worker_id = owned_worker_id("coordinator");
+        let target = worker_id("inventory");
+
+        service.begin(&owned_worker_id, 10).await.unwrap();
+        for function_name in ["reserve-undo", "charge-undo", "ship-undo"] {
+            service
+                .add_compensation(&owned_worker_id, 10, compensation(&target, function_name))
+                .await
+                .unwrap();
+        }
+
+        let rpc = Arc::new(RecordingRpc::new(Some("charge-undo")));
+        let saga = service
+            .start_compensation(&owned_worker_id, 10)
+            .await
+            .unwrap();
+        let result = run_compensations(
+            &(rpc.clone() as Arc<dyn Rpc + Send + Sync>),
+            &owned_worker_id,
+            &saga,
+            &[],
+            &[],
+        )
+        .await;
+        assert!(result.is_err());
+        assert_eq!(rpc.invoked(), vec!["ship-undo", "charge-undo"]);
+
+        service
+            .finish_compensation(&owned_worker_id, 10, result)
+            .await
+            .unwrap();
+        let sagas = service.list(&owned_worker_id).await.unwrap();
+        assert_eq!(sagas.len(), 1);
+        assert!(matches!(
+            sagas[0].state,
+            SagaState::CompensationFailed { .. }
+        ));
+    }
+
+    #[test]
+    pub async fn sagas_of_failed_workers_are_compensated_until_finished() {
+        let service: Arc<dyn SagaService + Send + Sync> = Arc::new(DefaultSagaService::new(
+            Arc::new(InMemoryKeyValueStorage::new()),
+        ));
+        let owned_worker_id = owned_worker_id("coordinator");
+        let target = worker_id("inventory");
+
+        service.begin(&owned_worker_id, 10).await.unwrap();
+        service
+            .add_compensation(&owned_worker_id, 10, compensation(&target, "release"))
+            .await
+            .unwrap();
+        service.begin(&owned_worker_id, 20).await.unwrap();
+        service
+            .add_compensation(&owned_worker_id, 20, compensation(&target, "refund"))
+            .await
+            .unwrap();
+        // Interrupted while compensating, so it is compensated again
+        service
+            .start_compensation(&owned_worker_id, 20)
+            .await
+            .unwrap();
+
+        let failed = failed_worker(&owned_worker_id);
+        service.mark_failed(&failed).await.unwrap();
+        assert_eq!(
+            service.failed_workers().await.unwrap(),
+            vec![failed.clone()]
+        );
+
+        let rpc = Arc::new(RecordingRpc::new(Some("refund")));
+        compensate_sagas(
+            &service,
+            &(rpc.clone() as Arc<dyn Rpc + Send + Sync>),
+            &failed,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(rpc.invoked(), vec!["release", "refund"]);
+        let sagas = service.list(&owned_worker_id).await.unwrap();
+        assert_eq!(sagas.len(), 1);
+        assert_eq!(sagas[0].id, 20);
+        assert!(matches!(
+            sagas[0].state,
+            SagaState::CompensationFailed { .. }
+        ));
+        assert!(service.failed_workers().await.unwrap().is_empty());
+    }
+
+    #[test]
+    pub async fn sagas_are_restored_from_the_oplog_entries() {
+        let service = DefaultSagaService::new(Arc::new(InMemoryKeyValueStorage::new()));
+        let owned_worker_id = owned_worker_id("coordinator");
+        let target = worker_id("inventory");
+
+        let ok = |value: &Result<(), SerializableError>| serialize(value).unwrap();
+        let mut sagas = Vec::new();
+        for saga_id in [10u64, 20, 30] {
+            apply_oplog_entry(
+                &mut sagas,
+                "golem saga::begin_saga",
+                &serialize(&saga_id).unwrap(),
+                &serialize(&Ok::<u64, SerializableError>(saga_id)).unwrap(),
+            )
+            .unwrap();
+        }
+        apply_oplog_entry(
+            &mut sagas,
+            "golem saga::add_compensation",
+            &serialize(&(
+                10u64,
+                target.clone(),
+                "release".to_string(),
+                Vec::<WitValue>::new(),
+            ))
+            .unwrap(),
+            &ok(&Ok(())),
+        )
+        .unwrap();
+        apply_oplog_entry(
+            &mut sagas,
+            "golem saga::complete_saga",
+            &serialize(&20u64).unwrap(),
+            &ok(&Ok(())),
+        )
+        .unwrap();
+
+        apply_oplog_entry(
+            &mut sagas,
+            "golem saga::abort_saga",
+            &serialize(&30u64).unwrap(),
+            &serialize(&Ok::<Result<(), String>, SerializableError>(Err(
+                "refund failed".to_string(),
+            )))
+            .unwrap(),
+        )
+        .unwrap();
+
+        // Saga 40 was started in a deleted region of the oplog, and saga 10 is being compensated
+        service.begin(&owned_worker_id, 10).await.unwrap();
+        service.begin(&owned_worker_id, 40).await.unwrap();
+        service
+            .start_compensation(&owned_worker_id, 10)
+            .await
+            .unwrap();
+        service.restore(&owned_worker_id, sagas).await.unwrap();
+
+        let sagas = service.list(&owned_worker_id).await.unwrap();
+        assert_eq!(
+            sagas.iter().map(|saga| saga.id).collect::<Vec<_>>(),
+            vec![10, 30]
+        );
+        assert_eq!(sagas[0].state, SagaState::Compensating);
+        assert_eq!(sagas[0].compensations.len(), 1);
+        assert_eq!(
+            sagas[1].state,
+            SagaState::CompensationFailed {
+                error: "refund failed".to_string()
+            }
+        );
+    }
+}
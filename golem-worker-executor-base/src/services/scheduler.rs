@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::ops::Add;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
@@ -25,6 +25,9 @@ use tracing::{error, info, span, warn, Instrument, Level};
 
 use crate::metrics::oplog::record_scheduled_archive;
 use crate::metrics::promises::record_scheduled_promise_completed;
+use crate::metrics::scheduler::{
+    record_scheduled_action_lag, record_scheduled_actions_due, record_scheduled_actions_pending,
+};
 use crate::services::oplog::{MultiLayerOplog, OplogService};
 use crate::services::promise::PromiseService;
 use crate::services::shard::ShardService;
@@ -33,15 +36,109 @@ use crate::services::worker_activator::WorkerActivator;
 use crate::storage::keyvalue::{
     KeyValueStorage, KeyValueStorageLabelledApi, KeyValueStorageNamespace,
 };
-use golem_common::model::{ComponentType, ScheduleId, ScheduledAction};
+use golem_common::model::{AccountId, ComponentType, ScheduleId, ScheduledAction, WorkerId};
 
 #[async_trait]
 pub trait SchedulerService {
     async fn schedule(&self, time: DateTime<Utc>, action: ScheduledAction) -> ScheduleId;
 
     async fn cancel(&self, id: ScheduleId);
+
+    /// Lists a page of at most `count` pending actions of all the executors matching the filter,
+    /// ordered by their due time, starting at the cursor returned with the previous page.
+    /// No cursor is returned with the last page.
+    async fn list(
+        &self,
+        filter: &ScheduledActionFilter,
+        cursor: Option<ScheduledActionCursor>,
+        count: usize,
+    ) -> Result<(Option<ScheduledActionCursor>, Vec<PendingScheduledAction>), String>;
+
+    /// Cancels all the pending actions matching the filter and returns the cancelled ones
+    async fn cancel_matching(
+        &self,
+        filter: &ScheduledActionFilter,
+    ) -> Result<Vec<PendingScheduledAction>, String>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScheduledActionKind {
+    CompletePromise,
+    ArchiveOplog,
+}
+
+impl ScheduledActionKind {
+    pub fn of(action: &ScheduledAction) -> Self {
+        match action {
+            ScheduledAction::CompletePromise { .. } => ScheduledActionKind::CompletePromise,
+            ScheduledAction::ArchiveOplog { .. } => ScheduledActionKind::ArchiveOplog,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ScheduledActionKind::CompletePromise => "complete_promise",
+            ScheduledActionKind::ArchiveOplog => "archive_oplog",
+        }
+    }
+}
+
+/// An action waiting in the scheduler store
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingScheduledAction {
+    pub id: ScheduleId,
+    pub due: DateTime<Utc>,
+}
+
+impl PendingScheduledAction {
+    pub fn action(&self) -> &ScheduledAction {
+        &self.id.action
+    }
+}
+
+/// Position of a page of pending scheduled actions: the bucket it starts in, and the number of
+/// matching actions of the bucket returned by the previous pages. Actions cancelled or executed
+/// between two pages may make the next page skip some actions of the bucket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ScheduledActionCursor {
+    pub bucket: i64,
+    pub offset: u64,
+}
+
+/// Selects pending scheduled actions, every criteria which is set has to match
+#[derive(Debug, Clone, Default)]
+pub struct ScheduledActionFilter {
+    pub account_id: Option<AccountId>,
+    pub worker_id: Option<WorkerId>,
+    pub kind: Option<ScheduledActionKind>,
+    /// Only matches actions due at or after this time
+    pub due_after: Option<DateTime<Utc>>,
+    /// Only matches actions due at or before this time
+    pub due_before: Option<DateTime<Utc>>,
 }
 
+impl ScheduledActionFilter {
+    pub fn matches(&self, pending: &PendingScheduledAction) -> bool {
+        let owned_worker_id = pending.action().owned_worker_id();
+        self.account_id
+            .as_ref()
+            .map_or(true, |account_id| *account_id == owned_worker_id.account_id)
+            && self
+                .worker_id
+                .as_ref()
+                .map_or(true, |worker_id| *worker_id == owned_worker_id.worker_id)
+            && self.kind.map_or(true, |kind| {
+                kind == ScheduledActionKind::of(pending.action())
+            })
+            && self.due_after.map_or(true, |after| pending.due >= after)
+            && self.due_before.map_or(true, |before| pending.due <= before)
+    }
+}
+
+/// Pending actions of the workers of this executor are counted this often for the backlog gauge,
+/// as it requires reading all the buckets
+const BACKLOG_MEASUREMENT_INTERVAL: Duration = Duration::from_secs(300);
+
 #[derive(Clone)]
 pub struct SchedulerServiceDefault {
     key_value_storage: Arc<dyn KeyValueStorage + Send + Sync>,
@@ -76,6 +173,7 @@ impl SchedulerServiceDefault {
         let background_handle = {
             let svc = svc.clone();
             tokio::spawn(async move {
+                let mut last_backlog_measurement: Option<Instant> = None;
                 loop {
                     tokio::time::sleep(process_interval).await;
                     if svc.shard_service.is_ready() {
@@ -83,6 +181,14 @@ impl SchedulerServiceDefault {
                         if let Err(err) = r {
                             error!(err, "Error in scheduler background task");
                         }
+                        if last_backlog_measurement
+                            .map_or(true, |last| last.elapsed() >= BACKLOG_MEASUREMENT_INTERVAL)
+                        {
+                            last_backlog_measurement = Some(Instant::now());
+                            if let Err(err) = svc.measure_backlog(Utc::now()).await {
+                                error!(err, "Failed to measure the backlog of the scheduler");
+                            }
+                        }
                     } else {
                         warn!("Skipping schedule, shard service is not ready")
                     }
@@ -107,9 +213,15 @@ impl SchedulerServiceDefault {
             .get_sorted_set(KeyValueStorageNamespace::Schedule, &previous_hour_key)
             .await?;
 
-        let mut all: Vec<(&str, ScheduledAction)> = all_from_prev_hour
+        let mut all: Vec<(&str, DateTime<Utc>, ScheduledAction)> = all_from_prev_hour
             .into_iter()
-            .map(|(_score, action)| (previous_hour_key.as_str(), action))
+            .map(|(score, action)| {
+                (
+                    previous_hour_key.as_str(),
+                    Self::due_time(previous_hours_since_epoch, score),
+                    action,
+                )
+            })
             .collect();
 
         let all_from_this_hour: Vec<(f64, ScheduledAction)> = self
@@ -123,15 +235,28 @@ impl SchedulerServiceDefault {
             )
             .await?;
 
-        all.extend(
-            all_from_this_hour
-                .into_iter()
-                .map(|(_score, action)| (current_hour_key.as_str(), action)),
-        );
+        all.extend(all_from_this_hour.into_iter().map(|(score, action)| {
+            (
+                current_hour_key.as_str(),
+                Self::due_time(hours_since_epoch, score),
+                action,
+            )
+        }));
+
+        for kind in [
+            ScheduledActionKind::CompletePromise,
+            ScheduledActionKind::ArchiveOplog,
+        ] {
+            let due = all
+                .iter()
+                .filter(|(_, _, action)| ScheduledActionKind::of(action) == kind)
+                .count();
+            record_scheduled_actions_due(kind.label(), due);
+        }
 
-        let matching: Vec<(&str, ScheduledAction)> = all
+        let matching: Vec<(&str, DateTime<Utc>, ScheduledAction)> = all
             .into_iter()
-            .filter(|(_, action)| {
+            .filter(|(_, _, action)| {
                 self.shard_service
                     .check_worker(&action.owned_worker_id().worker_id)
                     .is_ok()
@@ -139,13 +264,18 @@ impl SchedulerServiceDefault {
             .collect::<Vec<_>>();
 
         let mut owned_worker_ids = HashSet::new();
-        for (key, action) in matching {
+        for (key, due, action) in matching {
             owned_worker_ids.insert(action.owned_worker_id().clone());
             self.key_value_storage
                 .with_entity("scheduler", "process", "scheduled_action")
                 .remove_from_sorted_set(KeyValueStorageNamespace::Schedule, key, &action)
                 .await?;
 
+            record_scheduled_action_lag(
+                ScheduledActionKind::of(&action).label(),
+                (now - due).to_std().unwrap_or_default(),
+            );
+
             match action {
                 ScheduledAction::CompletePromise { promise_id, .. } => {
                     self.promise_service
@@ -234,6 +364,187 @@ impl SchedulerServiceDefault {
     fn schedule_key_from_timestamp(timestamp: i64) -> String {
         format!("worker:schedule:{}", timestamp)
     }
+
+    /// Set of the hours since epoch which have a bucket of scheduled actions, used to find all
+    /// the pending actions without scanning the keys of the storage
+    const BUCKETS_KEY: &'static str = "worker:schedule:buckets";
+
+    fn due_time(hours_since_epoch: i64, remainder: f64) -> DateTime<Utc> {
+        DateTime::from_timestamp_millis(hours_since_epoch * Self::HOUR_IN_MILLIS + remainder as i64)
+            .unwrap_or_default()
+    }
+
+    /// The buckets which may contain actions matching the filter, in ascending order
+    async fn buckets(
+        &self,
+        filter: &ScheduledActionFilter,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<i64>, String> {
+        let mut buckets: Vec<i64> = self
+            .key_value_storage
+            .with_entity("scheduler", "list", "scheduled_action_bucket")
+            .members_of_set(KeyValueStorageNamespace::Schedule, Self::BUCKETS_KEY)
+            .await?;
+
+        // The buckets processed by the scheduler are always checked, as they may contain actions
+        // scheduled before the index of the buckets existed
+        let (hours_since_epoch, _) = Self::split_time(now);
+        buckets.extend([hours_since_epoch - 1, hours_since_epoch]);
+        buckets.sort();
+        buckets.dedup();
+
+        let first = filter.due_after.map(|after| Self::split_time(after).0);
+        let last = filter.due_before.map(|before| Self::split_time(before).0);
+        buckets.retain(|bucket| {
+            first.map_or(true, |first| *bucket >= first)
+                && last.map_or(true, |last| *bucket <= last)
+        });
+        Ok(buckets)
+    }
+
+    /// The actions of the bucket matching the filter, ordered by their due time
+    async fn bucket_actions(
+        &self,
+        bucket: i64,
+        filter: &ScheduledActionFilter,
+    ) -> Result<Vec<PendingScheduledAction>, String> {
+        let min = match filter.due_after.map(Self::split_time) {
+            Some((hours_since_epoch, remainder)) if hours_since_epoch == bucket => remainder,
+            _ => 0.0,
+        };
+        let max = match filter.due_before.map(Self::split_time) {
+            Some((hours_since_epoch, remainder)) if hours_since_epoch == bucket => remainder,
+            _ => Self::HOUR_IN_MILLIS as f64,
+        };
+        let actions: Vec<(f64, ScheduledAction)> = self
+            .key_value_storage
+            .with_entity("scheduler", "list", "scheduled_action")
+            .query_sorted_set(
+                KeyValueStorageNamespace::Schedule,
+                &Self::schedule_key_from_timestamp(bucket),
+                min,
+                max,
+            )
+            .await?;
+
+        let mut result: Vec<PendingScheduledAction> = actions
+            .into_iter()
+            .map(|(score, action)| PendingScheduledAction {
+                id: ScheduleId {
+                    timestamp: bucket,
+                    action,
+                },
+                due: Self::due_time(bucket, score),
+            })
+            .filter(|pending| filter.matches(pending))
+            .collect();
+        result.sort_by_key(|pending| pending.due);
+        Ok(result)
+    }
+
+    async fn pending(
+        &self,
+        filter: &ScheduledActionFilter,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<PendingScheduledAction>, String> {
+        let mut result = Vec::new();
+        for bucket in self.buckets(filter, now).await? {
+            result.extend(self.bucket_actions(bucket, filter).await?);
+        }
+        Ok(result)
+    }
+
+    async fn pending_page(
+        &self,
+        filter: &ScheduledActionFilter,
+        cursor: Option<ScheduledActionCursor>,
+        count: usize,
+        now: DateTime<Utc>,
+    ) -> Result<(Option<ScheduledActionCursor>, Vec<PendingScheduledAction>), String> {
+        let cursor = cursor.unwrap_or(ScheduledActionCursor {
+            bucket: i64::MIN,
+            offset: 0,
+        });
+        let buckets = self.buckets(filter, now).await?;
+        let buckets: Vec<i64> = buckets
+            .into_iter()
+            .filter(|bucket| *bucket >= cursor.bucket)
+            .collect();
+
+        let mut result = Vec::new();
+        for (idx, bucket) in buckets.iter().enumerate() {
+            let skipped = if *bucket == cursor.bucket {
+                cursor.offset as usize
+            } else {
+                0
+            };
+            let actions = self.bucket_actions(*bucket, filter).await?;
+            let remaining = actions.len().saturating_sub(skipped);
+            let taken = remaining.min(count - result.len());
+            result.extend(actions.into_iter().skip(skipped).take(taken));
+
+            if result.len() == count {
+                let next = if taken < remaining {
+                    Some(ScheduledActionCursor {
+                        bucket: *bucket,
+                        offset: (skipped + taken) as u64,
+                    })
+                } else {
+                    buckets.get(idx + 1).map(|bucket| ScheduledActionCursor {
+                        bucket: *bucket,
+                        offset: 0,
+                    })
+                };
+                return Ok((next, result));
+            }
+        }
+        Ok((None, result))
+    }
+
+    /// Records the number of pending actions of the workers of this executor, and drops the past
+    /// buckets which became empty from the index
+    async fn measure_backlog(&self, now: DateTime<Utc>) -> Result<(), String> {
+        let (hours_since_epoch, _) = Self::split_time(now);
+        let mut backlog = HashMap::from([
+            (ScheduledActionKind::CompletePromise, 0),
+            (ScheduledActionKind::ArchiveOplog, 0),
+        ]);
+
+        let filter = ScheduledActionFilter::default();
+        for bucket in self.buckets(&filter, now).await? {
+            let actions = self.bucket_actions(bucket, &filter).await?;
+
+            if actions.is_empty() && bucket < hours_since_epoch - 1 {
+                // Nothing is scheduled into a bucket which is not processed anymore
+                self.key_value_storage
+                    .with_entity("scheduler", "measure_backlog", "scheduled_action_bucket")
+                    .remove_from_set(
+                        KeyValueStorageNamespace::Schedule,
+                        Self::BUCKETS_KEY,
+                        &bucket,
+                    )
+                    .await?;
+            }
+
+            for pending in actions {
+                let owned_worker_id = pending.action().owned_worker_id();
+                if self
+                    .shard_service
+                    .check_worker(&owned_worker_id.worker_id)
+                    .is_ok()
+                {
+                    *backlog
+                        .entry(ScheduledActionKind::of(pending.action()))
+                        .or_default() += 1;
+                }
+            }
+        }
+
+        for (kind, count) in backlog {
+            record_scheduled_actions_pending(kind.label(), count);
+        }
+        Ok(())
+    }
 }
 
 impl Drop for SchedulerServiceDefault {
@@ -254,6 +565,20 @@ impl SchedulerService for SchedulerServiceDefault {
             action: action.clone(),
         };
 
+        self.key_value_storage
+            .with_entity("scheduler", "schedule", "scheduled_action_bucket")
+            .add_to_set(
+                KeyValueStorageNamespace::Schedule,
+                Self::BUCKETS_KEY,
+                &hours_since_epoch,
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!(
+                    "failed to register schedule bucket for action {action} in KV storage: {err}"
+                )
+            });
+
         self.key_value_storage
             .with_entity("scheduler", "schedule", "scheduled_action")
             .add_to_sorted_set(
@@ -286,6 +611,33 @@ impl SchedulerService for SchedulerServiceDefault {
                 )
             });
     }
+
+    async fn list(
+        &self,
+        filter: &ScheduledActionFilter,
+        cursor: Option<ScheduledActionCursor>,
+        count: usize,
+    ) -> Result<(Option<ScheduledActionCursor>, Vec<PendingScheduledAction>), String> {
+        self.pending_page(filter, cursor, count, Utc::now()).await
+    }
+
+    async fn cancel_matching(
+        &self,
+        filter: &ScheduledActionFilter,
+    ) -> Result<Vec<PendingScheduledAction>, String> {
+        let matching = self.pending(filter, Utc::now()).await?;
+        for pending in &matching {
+            self.key_value_storage
+                .with_entity("scheduler", "cancel_matching", "scheduled_action")
+                .remove_from_sorted_set(
+                    KeyValueStorageNamespace::Schedule,
+                    &Self::schedule_key(&pending.id),
+                    pending.action(),
+                )
+                .await?;
+        }
+        Ok(matching)
+    }
 }
 
 #[cfg(test)]
@@ -299,22 +651,25 @@ mod tests {
 
     use bincode::Encode;
 
-    use chrono::DateTime;
+    use chrono::{DateTime, Utc};
 
     use uuid::Uuid;
 
     use crate::services::oplog::{OplogService, PrimaryOplogService};
     use crate::services::promise::PromiseServiceMock;
-    use crate::services::scheduler::{SchedulerService, SchedulerServiceDefault};
+    use crate::services::scheduler::{
+        ScheduledActionFilter, ScheduledActionKind, SchedulerService, SchedulerServiceDefault,
+    };
     use crate::services::shard::{ShardService, ShardServiceDefault};
     use crate::services::worker::{DefaultWorkerService, WorkerService};
     use crate::services::worker_activator::{WorkerActivator, WorkerActivatorMock};
     use crate::storage::blob::memory::InMemoryBlobStorage;
     use crate::storage::indexed::memory::InMemoryIndexedStorage;
     use crate::storage::keyvalue::memory::InMemoryKeyValueStorage;
+    use crate::storage::keyvalue::{KeyValueStorage, KeyValueStorageNamespace};
     use golem_common::model::oplog::OplogIndex;
     use golem_common::model::{
-        AccountId, ComponentId, PromiseId, ScheduledAction, ShardId, WorkerId,
+        AccountId, ComponentId, PromiseId, ScheduledAction, ShardId, WorkerId, WorkerName,
     };
 
     fn serialized_bytes<T: Encode>(entry: &T) -> Vec<u8> {
@@ -363,11 +718,11 @@ mod tests {
         let c1: ComponentId = ComponentId(uuid);
         let i1: WorkerId = WorkerId {
             component_id: c1.clone(),
-            worker_name: "inst1".to_string(),
+            worker_name: WorkerName::from_existing("inst1"),
         };
         let i2: WorkerId = WorkerId {
             component_id: c1.clone(),
-            worker_name: "inst2".to_string(),
+            worker_name: WorkerName::from_existing("inst2"),
         };
 
         let account_id = AccountId {
@@ -480,11 +835,11 @@ mod tests {
         let c1: ComponentId = ComponentId(Uuid::new_v4());
         let i1: WorkerId = WorkerId {
             component_id: c1.clone(),
-            worker_name: "inst1".to_string(),
+            worker_name: WorkerName::from_existing("inst1"),
         };
         let i2: WorkerId = WorkerId {
             component_id: c1.clone(),
-            worker_name: "inst2".to_string(),
+            worker_name: WorkerName::from_existing("inst2"),
         };
 
         let account_id = AccountId {
@@ -577,16 +932,329 @@ mod tests {
         );
     }
 
+    #[test]
+    pub async fn list_and_cancel_matching() {
+        let c1: ComponentId = ComponentId(Uuid::new_v4());
+        let i1: WorkerId = WorkerId {
+            component_id: c1.clone(),
+            worker_name: WorkerName::from_existing("inst1"),
+        };
+        let i2: WorkerId = WorkerId {
+            component_id: c1.clone(),
+            worker_name: WorkerName::from_existing("inst2"),
+        };
+
+        let account_id = AccountId {
+            value: "test-account".to_string(),
+        };
+
+        let p1: PromiseId = PromiseId {
+            worker_id: i1.clone(),
+            oplog_idx: OplogIndex::from_u64(101),
+        };
+        let p2: PromiseId = PromiseId {
+            worker_id: i1.clone(),
+            oplog_idx: OplogIndex::from_u64(123),
+        };
+        let p3: PromiseId = PromiseId {
+            worker_id: i2.clone(),
+            oplog_idx: OplogIndex::from_u64(1000),
+        };
+
+        let kvs = Arc::new(InMemoryKeyValueStorage::new());
+
+        let shard_service = create_shard_service_mock();
+        let promise_service = create_promise_service_mock();
+        let worker_activator = create_worker_activator_mock();
+        let oplog_service = create_oplog_service_mock().await;
+        let worker_service =
+            create_worker_service_mock(kvs.clone(), shard_service.clone(), oplog_service.clone());
+
+        let svc = SchedulerServiceDefault::new(
+            kvs.clone(),
+            shard_service,
+            promise_service,
+            worker_activator,
+            oplog_service,
+            worker_service,
+            Duration::from_secs(1000), // not testing process() here
+        );
+
+        let s1 = svc
+            .schedule(
+                DateTime::from_str("2023-07-17T10:05:00Z").unwrap(),
+                ScheduledAction::CompletePromise {
+                    account_id: account_id.clone(),
+                    promise_id: p1.clone(),
+                },
+            )
+            .await;
+        let s2 = svc
+            .schedule(
+                DateTime::from_str("2023-07-17T09:59:00Z").unwrap(),
+                ScheduledAction::CompletePromise {
+                    account_id: account_id.clone(),
+                    promise_id: p2.clone(),
+                },
+            )
+            .await;
+        let s3 = svc
+            .schedule(
+                DateTime::from_str("2023-07-17T10:05:01Z").unwrap(),
+                ScheduledAction::CompletePromise {
+                    account_id: account_id.clone(),
+                    promise_id: p3.clone(),
+                },
+            )
+            .await;
+
+        let all = svc
+            .list(&ScheduledActionFilter::default(), None, 100)
+            .await
+            .unwrap()
+            .1;
+        assert_eq!(
+            all.iter().map(|pending| &pending.id).collect::<Vec<_>>(),
+            vec![&s2, &s1, &s3]
+        );
+        assert_eq!(
+            all[0].due,
+            DateTime::<chrono::Utc>::from_str("2023-07-17T09:59:00Z").unwrap()
+        );
+
+        let of_worker = svc
+            .list(
+                &ScheduledActionFilter {
+                    worker_id: Some(i1.clone()),
+                    ..Default::default()
+                },
+                None,
+                100,
+            )
+            .await
+            .unwrap()
+            .1;
+        assert_eq!(
+            of_worker
+                .iter()
+                .map(|pending| &pending.id)
+                .collect::<Vec<_>>(),
+            vec![&s2, &s1]
+        );
+
+        let due_before = svc
+            .list(
+                &ScheduledActionFilter {
+                    due_before: Some(DateTime::from_str("2023-07-17T10:05:00Z").unwrap()),
+                    ..Default::default()
+                },
+                None,
+                100,
+            )
+            .await
+            .unwrap()
+            .1;
+        assert_eq!(
+            due_before
+                .iter()
+                .map(|pending| &pending.id)
+                .collect::<Vec<_>>(),
+            vec![&s2, &s1]
+        );
+
+        let archives = svc
+            .list(
+                &ScheduledActionFilter {
+                    kind: Some(ScheduledActionKind::ArchiveOplog),
+                    ..Default::default()
+                },
+                None,
+                100,
+            )
+            .await
+            .unwrap()
+            .1;
+        assert!(archives.is_empty());
+
+        let cancelled = svc
+            .cancel_matching(&ScheduledActionFilter {
+                worker_id: Some(i2.clone()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            cancelled
+                .iter()
+                .map(|pending| &pending.id)
+                .collect::<Vec<_>>(),
+            vec![&s3]
+        );
+
+        let remaining = svc
+            .list(&ScheduledActionFilter::default(), None, 100)
+            .await
+            .unwrap()
+            .1;
+        assert_eq!(
+            remaining
+                .iter()
+                .map(|pending| &pending.id)
+                .collect::<Vec<_>>(),
+            vec![&s2, &s1]
+        );
+    }
+
+    #[test]
+    pub async fn list_pages_without_changing_the_buckets() {
+        let c1: ComponentId = ComponentId(Uuid::new_v4());
+        let i1: WorkerId = WorkerId {
+            component_id: c1.clone(),
+            worker_name: WorkerName::from_existing("inst1"),
+        };
+
+        let account_id = AccountId {
+            value: "test-account".to_string(),
+        };
+
+        let kvs = Arc::new(InMemoryKeyValueStorage::new());
+
+        let shard_service = create_shard_service_mock();
+        let promise_service = create_promise_service_mock();
+        let worker_activator = create_worker_activator_mock();
+        let oplog_service = create_oplog_service_mock().await;
+        let worker_service =
+            create_worker_service_mock(kvs.clone(), shard_service.clone(), oplog_service.clone());
+
+        let svc = SchedulerServiceDefault::new(
+            kvs.clone(),
+            shard_service,
+            promise_service,
+            worker_activator,
+            oplog_service,
+            worker_service,
+            Duration::from_secs(1000), // not testing process() here
+        );
+
+        let mut scheduled = Vec::new();
+        for (idx, due) in [
+            "2023-07-17T09:59:00Z",
+            "2023-07-17T10:05:00Z",
+            "2023-07-17T10:06:00Z",
+            "2023-07-17T12:00:00Z",
+        ]
+        .iter()
+        .enumerate()
+        {
+            scheduled.push(
+                svc.schedule(
+                    DateTime::from_str(due).unwrap(),
+                    ScheduledAction::CompletePromise {
+                        account_id: account_id.clone(),
+                        promise_id: PromiseId {
+                            worker_id: i1.clone(),
+                            oplog_idx: OplogIndex::from_u64(idx as u64),
+                        },
+                    },
+                )
+                .await,
+            );
+        }
+        svc.cancel(scheduled.remove(0)).await;
+
+        let buckets_before = kvs
+            .members_of_set(
+                "test",
+                "api",
+                "entity",
+                KeyValueStorageNamespace::Schedule,
+                SchedulerServiceDefault::BUCKETS_KEY,
+            )
+            .await
+            .unwrap();
+
+        let mut cursor = None;
+        let mut pages = Vec::new();
+        loop {
+            let (next, page) = svc
+                .list(&ScheduledActionFilter::default(), cursor, 1)
+                .await
+                .unwrap();
+            pages.push(
+                page.into_iter()
+                    .map(|pending| pending.id)
+                    .collect::<Vec<_>>(),
+            );
+            match next {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+        // The last page may be empty if the previous one ended at the end of a bucket
+        assert!(pages.iter().all(|page| page.len() <= 1));
+        assert_eq!(pages.concat(), scheduled);
+
+        let (next, not_future) = svc
+            .list(
+                &ScheduledActionFilter {
+                    due_before: Some(DateTime::from_str("2023-07-17T11:00:00Z").unwrap()),
+                    ..Default::default()
+                },
+                None,
+                100,
+            )
+            .await
+            .unwrap();
+        assert_eq!(next, None);
+        assert_eq!(
+            not_future
+                .into_iter()
+                .map(|pending| pending.id)
+                .collect::<Vec<_>>(),
+            vec![scheduled[0].clone(), scheduled[1].clone()]
+        );
+
+        let buckets_after = kvs
+            .members_of_set(
+                "test",
+                "api",
+                "entity",
+                KeyValueStorageNamespace::Schedule,
+                SchedulerServiceDefault::BUCKETS_KEY,
+            )
+            .await
+            .unwrap();
+        assert_eq!(buckets_before.len(), 3);
+        assert_eq!(
+            buckets_after.into_iter().collect::<HashSet<_>>(),
+            buckets_before.into_iter().collect::<HashSet<_>>()
+        );
+
+        svc.measure_backlog(Utc::now()).await.unwrap();
+
+        let buckets_measured = kvs
+            .members_of_set(
+                "test",
+                "api",
+                "entity",
+                KeyValueStorageNamespace::Schedule,
+                SchedulerServiceDefault::BUCKETS_KEY,
+            )
+            .await
+            .unwrap();
+        assert_eq!(buckets_measured.len(), 2);
+    }
+
     #[test]
     pub async fn process_current_hours_past_schedules() {
         let c1: ComponentId = ComponentId(Uuid::new_v4());
         let i1: WorkerId = WorkerId {
             component_id: c1.clone(),
-            worker_name: "inst1".to_string(),
+            worker_name: WorkerName::from_existing("inst1"),
         };
         let i2: WorkerId = WorkerId {
             component_id: c1.clone(),
-            worker_name: "inst2".to_string(),
+            worker_name: WorkerName::from_existing("inst2"),
         };
 
         let account_id = AccountId {
@@ -689,11 +1357,11 @@ mod tests {
         let c1: ComponentId = ComponentId(Uuid::new_v4());
         let i1: WorkerId = WorkerId {
             component_id: c1.clone(),
-            worker_name: "inst1".to_string(),
+            worker_name: WorkerName::from_existing("inst1"),
         };
         let i2: WorkerId = WorkerId {
             component_id: c1.clone(),
-            worker_name: "inst2".to_string(),
+            worker_name: WorkerName::from_existing("inst2"),
         };
 
         let account_id = AccountId {
@@ -790,11 +1458,11 @@ mod tests {
         let c1: ComponentId = ComponentId(Uuid::new_v4());
         let i1: WorkerId = WorkerId {
             component_id: c1.clone(),
-            worker_name: "inst1".to_string(),
+            worker_name: WorkerName::from_existing("inst1"),
         };
         let i2: WorkerId = WorkerId {
             component_id: c1.clone(),
-            worker_name: "inst2".to_string(),
+            worker_name: WorkerName::from_existing("inst2"),
         };
 
         let account_id = AccountId {
@@ -905,11 +1573,11 @@ mod tests {
         let c1: ComponentId = ComponentId(Uuid::new_v4());
         let i1: WorkerId = WorkerId {
             component_id: c1.clone(),
-            worker_name: "inst1".to_string(),
+            worker_name: WorkerName::from_existing("inst1"),
         };
         let i2: WorkerId = WorkerId {
             component_id: c1.clone(),
-            worker_name: "inst2".to_string(),
+            worker_name: WorkerName::from_existing("inst2"),
         };
 
         let account_id = AccountId {
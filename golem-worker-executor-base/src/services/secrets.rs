@@ -0,0 +1,303 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bincode::{Decode, Encode};
+
+use golem_common::model::{AccountId, ComponentId};
+
+use crate::error::GolemError;
+use crate::services::encryption::EncryptionService;
+use crate::storage::keyvalue::{
+    KeyValueStorage, KeyValueStorageLabelledApi, KeyValueStorageNamespace,
+};
+
+/// Service storing secrets available to the workers of a component.
+///
+/// Secrets are only ever returned to the running workers, they are never recorded in the
+/// oplog and are not part of the worker metadata.
+#[async_trait]
+pub trait SecretService {
+    async fn put(
+        &self,
+        account_id: &AccountId,
+        component_id: &ComponentId,
+        name: &str,
+        value: &[u8],
+    ) -> Result<(), GolemError>;
+
+    async fn get(
+        &self,
+        account_id: &AccountId,
+        component_id: &ComponentId,
+        name: &str,
+    ) -> Result<Option<Vec<u8>>, GolemError>;
+
+    async fn delete(
+        &self,
+        account_id: &AccountId,
+        component_id: &ComponentId,
+        name: &str,
+    ) -> Result<(), GolemError>;
+
+    /// Lists the names of the stored secrets, without their values
+    async fn list_names(
+        &self,
+        account_id: &AccountId,
+        component_id: &ComponentId,
+    ) -> Result<Vec<String>, GolemError>;
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+struct EncryptedSecret {
+    nonce: Vec<u8>,
+    ciphertext: Vec<u8>,
+}
+
+/// Secret service storing the secrets in the key-value storage, encrypted with the account's
+/// data key. Storing secrets is rejected if encryption is not configured for the executor.
+pub struct DefaultSecretService {
+    key_value_storage: Arc<dyn KeyValueStorage + Send + Sync>,
+    encryption: Option<Arc<dyn EncryptionService + Send + Sync>>,
+}
+
+impl DefaultSecretService {
+    pub fn new(
+        key_value_storage: Arc<dyn KeyValueStorage + Send + Sync>,
+        encryption: Option<Arc<dyn EncryptionService + Send + Sync>>,
+    ) -> Self {
+        Self {
+            key_value_storage,
+            encryption,
+        }
+    }
+
+    fn encryption(&self) -> Result<&Arc<dyn EncryptionService + Send + Sync>, GolemError> {
+        self.encryption.as_ref().ok_or(GolemError::runtime(
+            "Secrets are not available because encryption is not configured",
+        ))
+    }
+
+    fn namespace(account_id: &AccountId, component_id: &ComponentId) -> KeyValueStorageNamespace {
+        KeyValueStorageNamespace::Secret {
+            account_id: account_id.clone(),
+            component_id: component_id.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl SecretService for DefaultSecretService {
+    async fn put(
+        &self,
+        account_id: &AccountId,
+        component_id: &ComponentId,
+        name: &str,
+        value: &[u8],
+    ) -> Result<(), GolemError> {
+        let (nonce, ciphertext) = self
+            .encryption()?
+            .encrypt(account_id, value)
+            .await
+            .map_err(GolemError::runtime)?;
+        self.key_value_storage
+            .with_entity("secrets", "put", "secret")
+            .set(
+                Self::namespace(account_id, component_id),
+                name,
+                &EncryptedSecret { nonce, ciphertext },
+            )
+            .await
+            .map_err(GolemError::runtime)
+    }
+
+    async fn get(
+        &self,
+        account_id: &AccountId,
+        component_id: &ComponentId,
+        name: &str,
+    ) -> Result<Option<Vec<u8>>, GolemError> {
+        let stored: Option<EncryptedSecret> = self
+            .key_value_storage
+            .with_entity("secrets", "get", "secret")
+            .get(Self::namespace(account_id, component_id), name)
+            .await
+            .map_err(GolemError::runtime)?;
+        match stored {
+            Some(secret) => {
+                let value = self
+                    .encryption()?
+                    .decrypt(account_id, &secret.nonce, &secret.ciphertext)
+                    .await
+                    .map_err(GolemError::runtime)?;
+                Ok(Some(value))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn delete(
+        &self,
+        account_id: &AccountId,
+        component_id: &ComponentId,
+        name: &str,
+    ) -> Result<(), GolemError> {
+        self.key_value_storage
+            .with("secrets", "delete")
+            .del(Self::namespace(account_id, component_id), name)
+            .await
+            .map_err(GolemError::runtime)
+    }
+
+    async fn list_names(
+        &self,
+        account_id: &AccountId,
+        component_id: &ComponentId,
+    ) -> Result<Vec<String>, GolemError> {
+        let mut names = self
+            .key_value_storage
+            .with("secrets", "list_names")
+            .keys(Self::namespace(account_id, component_id))
+            .await
+            .map_err(GolemError::runtime)?;
+        names.sort();
+        Ok(names)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+
+    use golem_common::model::{AccountId, ComponentId};
+
+    use crate::services::encryption::{DefaultEncryptionService, LocalKeyManagementService};
+    use crate::services::golem_config::LocalEncryptionConfig;
+    use crate::services::secrets::{DefaultSecretService, SecretService};
+    use crate::storage::keyvalue::memory::InMemoryKeyValueStorage;
+    use crate::storage::keyvalue::{KeyValueStorage, KeyValueStorageNamespace};
+
+    fn encrypted_service(
+        key_value_storage: Arc<dyn KeyValueStorage + Send + Sync>,
+    ) -> DefaultSecretService {
+        let key_management_service = LocalKeyManagementService::new(&LocalEncryptionConfig {
+            current_master_key: "key1".to_string(),
+            master_keys: BTreeMap::from([("key1".to_string(), "11".repeat(32))]),
+        })
+        .unwrap();
+        DefaultSecretService::new(
+            key_value_storage.clone(),
+            Some(Arc::new(DefaultEncryptionService::new(
+                Arc::new(key_management_service),
+                key_value_storage,
+            ))),
+        )
+    }
+
+    #[test]
+    async fn put_get_delete() {
+        let service = encrypted_service(Arc::new(InMemoryKeyValueStorage::new()));
+        let account_id = AccountId {
+            value: "account1".to_string(),
+        };
+        let component_id = ComponentId::new_v4();
+
+        service
+            .put(&account_id, &component_id, "api-key", b"secret-value")
+            .await
+            .unwrap();
+        service
+            .put(&account_id, &component_id, "db-password", b"password")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            service
+                .get(&account_id, &component_id, "api-key")
+                .await
+                .unwrap(),
+            Some(b"secret-value".to_vec())
+        );
+        assert_eq!(
+            service
+                .list_names(&account_id, &component_id)
+                .await
+                .unwrap(),
+            vec!["api-key".to_string(), "db-password".to_string()]
+        );
+
+        service
+            .delete(&account_id, &component_id, "api-key")
+            .await
+            .unwrap();
+        assert_eq!(
+            service
+                .get(&account_id, &component_id, "api-key")
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    async fn secrets_are_encrypted_at_rest() {
+        let key_value_storage = Arc::new(InMemoryKeyValueStorage::new());
+        let service = encrypted_service(key_value_storage.clone());
+        let account_id = AccountId {
+            value: "account1".to_string(),
+        };
+        let component_id = ComponentId::new_v4();
+
+        service
+            .put(&account_id, &component_id, "api-key", b"secret-value")
+            .await
+            .unwrap();
+
+        let stored = key_value_storage
+            .get(
+                "test",
+                "get",
+                "secret",
+                KeyValueStorageNamespace::Secret {
+                    account_id: account_id.clone(),
+                    component_id: component_id.clone(),
+                },
+                "api-key",
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!stored
+            .windows(b"secret-value".len())
+            .any(|window| window == b"secret-value"));
+    }
+
+    #[test]
+    async fn secrets_require_encryption() {
+        let service = DefaultSecretService::new(Arc::new(InMemoryKeyValueStorage::new()), None);
+        let account_id = AccountId {
+            value: "account1".to_string(),
+        };
+
+        assert!(service
+            .put(&account_id, &ComponentId::new_v4(), "api-key", b"value")
+            .await
+            .is_err());
+    }
+}
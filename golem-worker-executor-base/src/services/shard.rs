@@ -14,6 +14,7 @@
 
 use std::collections::HashSet;
 use std::convert::identity;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, RwLock};
 
 use itertools::Itertools;
@@ -34,10 +35,15 @@ pub trait ShardService {
     fn revoke_shards(&self, shard_ids: &HashSet<ShardId>) -> Result<(), GolemError>;
     fn current_assignment(&self) -> Result<ShardAssignment, GolemError>;
     fn try_get_current_assignment(&self) -> Option<ShardAssignment>;
+    /// Marks the executor as draining; from this point no worker is accepted as belonging
+    /// to this executor. Returns false if it was already draining.
+    fn start_draining(&self) -> bool;
+    fn is_draining(&self) -> bool;
 }
 
 pub struct ShardServiceDefault {
     shard_assignment: Arc<RwLock<Option<ShardAssignment>>>,
+    draining: AtomicBool,
 }
 
 impl Default for ShardServiceDefault {
@@ -50,6 +56,7 @@ impl ShardServiceDefault {
     pub fn new() -> Self {
         Self {
             shard_assignment: Arc::new(RwLock::new(None)),
+            draining: AtomicBool::new(false),
         }
     }
 
@@ -99,6 +106,9 @@ impl ShardService for ShardServiceDefault {
     }
 
     fn check_worker(&self, worker_id: &WorkerId) -> Result<(), GolemError> {
+        if self.is_draining() {
+            return Err(GolemError::ShardingNotReady);
+        }
         self.with_read_shard_assignment(|shard_assignment: &ShardAssignment| {
             shard_assignment.check_worker(worker_id)
         })
@@ -150,6 +160,15 @@ impl ShardService for ShardServiceDefault {
     fn try_get_current_assignment(&self) -> Option<ShardAssignment> {
         self.shard_assignment.read().unwrap().clone()
     }
+
+    fn start_draining(&self) -> bool {
+        debug!("ShardService.start_draining");
+        !self.draining.swap(true, Ordering::AcqRel)
+    }
+
+    fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::Acquire)
+    }
 }
 
 fn sharding_not_ready_error() -> GolemError {
@@ -32,6 +32,7 @@ use crate::services::golem_config::{ShardManagerServiceConfig, ShardManagerServi
 #[async_trait]
 pub trait ShardManagerService {
     async fn register(&self, host: String, port: u16) -> Result<ShardAssignment, GolemError>;
+    async fn unregister(&self, host: String, port: u16) -> Result<(), GolemError>;
 }
 
 pub fn configured(
@@ -81,6 +82,8 @@ impl ShardManagerService for ShardManagerServiceGrpc {
             |(host, port)| {
                 let client = self.client.clone();
                 let pod_name = pod_name.clone();
+                let weight = self.config.weight;
+                let zone = self.config.zone.clone();
                 Box::pin(async move {
                     let response = client
                         .call(move |client| {
@@ -88,6 +91,8 @@ impl ShardManagerService for ShardManagerServiceGrpc {
                                 host: host.clone(),
                                 port: *port as i32,
                                 pod_name: pod_name.clone(),
+                                weight,
+                                zone: zone.clone(),
                             }))
                         })
                         .await
@@ -124,6 +129,58 @@ impl ShardManagerService for ShardManagerServiceGrpc {
         )
         .await
     }
+
+    async fn unregister(&self, host: String, port: u16) -> Result<(), GolemError> {
+        let pod_name = std::env::var_os("POD_NAME").map(|s| s.to_string_lossy().to_string());
+        with_retries(
+            "shard_manager",
+            "unregister",
+            Some(format!("{:?}", pod_name)),
+            &self.config.retries,
+            &(host, port),
+            |(host, port)| {
+                let client = self.client.clone();
+                let pod_name = pod_name.clone();
+                Box::pin(async move {
+                    let response = client
+                        .call(move |client| {
+                            Box::pin(client.unregister(shardmanager::v1::UnregisterRequest {
+                                host: host.clone(),
+                                port: *port as i32,
+                                pod_name: pod_name.clone(),
+                            }))
+                        })
+                        .await
+                        .map_err(|err| {
+                            GolemError::unknown(format!(
+                                "Unregistering from shard manager failed with {}",
+                                err
+                            ))
+                        })?;
+                    match response.into_inner() {
+                        shardmanager::v1::UnregisterResponse {
+                            result:
+                                Some(shardmanager::v1::unregister_response::Result::Success(_)),
+                        } => Ok(()),
+                        shardmanager::v1::UnregisterResponse {
+                            result:
+                                Some(shardmanager::v1::unregister_response::Result::Failure(
+                                    failure,
+                                )),
+                        } => Err(GolemError::unknown(format!(
+                            "Unregistering from shard manager failed with shard manager error {:?}",
+                            failure
+                        ))),
+                        shardmanager::v1::UnregisterResponse { .. } => Err(GolemError::unknown(
+                            "Unregistering from shard manager failed with unknown error",
+                        )),
+                    }
+                })
+            },
+            |_| true,
+        )
+        .await
+    }
 }
 
 pub struct ShardManagerServiceSingleShard {}
@@ -148,4 +205,8 @@ impl ShardManagerService for ShardManagerServiceSingleShard {
             HashSet::from_iter(vec![ShardId::new(0)]),
         ))
     }
+
+    async fn unregister(&self, _host: String, _port: u16) -> Result<(), GolemError> {
+        Ok(())
+    }
 }
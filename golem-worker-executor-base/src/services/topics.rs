@@ -0,0 +1,1109 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bincode::{Decode, Encode};
+use futures::future::join_all;
+use golem_wasm_rpc::{Value, WitValue};
+use tokio::task::JoinHandle;
+use tracing::{info, warn, Instrument};
+use uuid::Uuid;
+
+use golem_common::model::{AccountId, IdempotencyKey, OwnedWorkerId, WorkerId};
+use golem_common::serialization::{deserialize, serialize};
+
+use crate::error::GolemError;
+use crate::services::rpc::Rpc;
+use crate::services::{HasConfig, HasRpc, HasShardService, HasTopicService};
+use crate::storage::keyvalue::{
+    KeyValueStorage, KeyValueStorageLabelledApi, KeyValueStorageNamespace,
+};
+
+/// Service storing the named pub/sub topics of an account.
+///
+/// Published messages are kept until every subscriber of the topic has acknowledged them.
+/// Delivering the messages as invocations of the subscribers is done by the publishing worker
+/// and by the background delivery started with [start], this service only keeps track of the
+/// messages and of the delivery progress.
+#[async_trait]
+pub trait TopicService {
+    /// Subscribes the worker to the topic, delivering the messages published from now on as
+    /// invocations of `handler`. Subscribing again only changes the handler.
+    async fn subscribe(
+        &self,
+        account_id: &AccountId,
+        topic: &str,
+        worker_id: &WorkerId,
+        handler: &str,
+    ) -> Result<(), GolemError>;
+
+    async fn unsubscribe(
+        &self,
+        account_id: &AccountId,
+        topic: &str,
+        worker_id: &WorkerId,
+    ) -> Result<(), GolemError>;
+
+    /// Removes every subscription of the worker, used when the worker gets deleted
+    async fn unsubscribe_all(
+        &self,
+        account_id: &AccountId,
+        worker_id: &WorkerId,
+    ) -> Result<(), GolemError>;
+
+    /// Appends a message to the topic and returns its sequence number
+    async fn publish(
+        &self,
+        account_id: &AccountId,
+        topic: &str,
+        publisher: &WorkerId,
+        payload: Vec<u8>,
+    ) -> Result<u64, GolemError>;
+
+    async fn subscriptions(
+        &self,
+        account_id: &AccountId,
+        topic: &str,
+    ) -> Result<Vec<TopicSubscription>, GolemError>;
+
+    /// Returns the messages of the topic not yet acknowledged by the subscription, in order
+    async fn pending_messages(
+        &self,
+        account_id: &AccountId,
+        topic: &str,
+        subscription: &TopicSubscription,
+    ) -> Result<Vec<TopicMessage>, GolemError>;
+
+    /// Marks every message up to `sequence` as delivered to the worker
+    async fn acknowledge(
+        &self,
+        account_id: &AccountId,
+        topic: &str,
+        worker_id: &WorkerId,
+        sequence: u64,
+    ) -> Result<(), GolemError>;
+
+    /// Drops the messages acknowledged by every subscriber of the topic
+    async fn trim(&self, account_id: &AccountId, topic: &str) -> Result<(), GolemError>;
+
+    async fn list_topics(&self, account_id: &AccountId) -> Result<Vec<String>, GolemError>;
+
+    async fn get_topic(
+        &self,
+        account_id: &AccountId,
+        topic: &str,
+    ) -> Result<Option<TopicInfo>, GolemError>;
+
+    /// Lists the topics of every account which may have messages not delivered to all of their
+    /// subscribers yet
+    async fn pending_topics(&self) -> Result<Vec<PendingTopic>, GolemError>;
+
+    /// Removes the topic from the pending topics, unless some of its messages are still not
+    /// delivered to all of its subscribers
+    async fn delivered(&self, pending: &PendingTopic) -> Result<(), GolemError>;
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct TopicSubscription {
+    pub worker_id: WorkerId,
+    pub handler: String,
+    /// Sequence number of the last message delivered to the subscriber
+    pub acknowledged: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct TopicMessage {
+    pub sequence: u64,
+    pub publisher: WorkerId,
+    pub payload: Vec<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct PendingTopic {
+    pub account_id: AccountId,
+    pub topic: String,
+    /// The worker which published to the topic last. The pending messages are delivered in the
+    /// background by the executor owning this worker.
+    pub publisher: WorkerId,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TopicInfo {
+    pub name: String,
+    /// Sequence number of the last published message, 0 if nothing has been published yet
+    pub last_sequence: u64,
+    /// Number of messages stored because not all subscribers acknowledged them yet
+    pub retained_messages: u64,
+    pub subscriptions: Vec<TopicSubscription>,
+}
+
+impl TopicInfo {
+    /// Number of published messages not yet delivered to the subscription
+    pub fn lag(&self, subscription: &TopicSubscription) -> u64 {
+        self.last_sequence.saturating_sub(subscription.acknowledged)
+    }
+}
+
+/// The idempotency key of delivering a message to a subscriber, making repeated deliveries of
+/// the same message to the same worker a single invocation
+pub fn delivery_idempotency_key(
+    account_id: &AccountId,
+    topic: &str,
+    sequence: u64,
+    worker_id: &WorkerId,
+) -> IdempotencyKey {
+    let name = format!("topic:{account_id}:{topic}:{sequence}:{worker_id}");
+    IdempotencyKey::from_uuid(Uuid::new_v5(&Uuid::NAMESPACE_OID, name.as_bytes()))
+}
+
+/// Topic service storing the topics in the key-value storage
+pub struct DefaultTopicService {
+    key_value_storage: Arc<dyn KeyValueStorage + Send + Sync>,
+}
+
+impl DefaultTopicService {
+    pub fn new(key_value_storage: Arc<dyn KeyValueStorage + Send + Sync>) -> Self {
+        Self { key_value_storage }
+    }
+
+    fn namespace(account_id: &AccountId) -> KeyValueStorageNamespace {
+        KeyValueStorageNamespace::Topics {
+            account_id: account_id.clone(),
+        }
+    }
+
+    fn validate_topic(topic: &str) -> Result<(), GolemError> {
+        if topic.is_empty() {
+            Err(GolemError::invalid_request("Topic name must not be empty"))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn topics_key() -> &'static str {
+        "topics"
+    }
+
+    fn sequence_key(topic: &str) -> String {
+        format!("topic:{topic}:sequence")
+    }
+
+    fn message_key(topic: &str, sequence: u64) -> String {
+        format!("topic:{topic}:message:{sequence}")
+    }
+
+    fn pending_topic_key(account_id: &AccountId, topic: &str) -> String {
+        format!("{account_id}:{topic}")
+    }
+
+    fn subscribers_key(topic: &str) -> String {
+        format!("topic:{topic}:subscribers")
+    }
+
+    fn subscription_key(topic: &str, worker_id: &WorkerId) -> String {
+        format!("topic:{topic}:subscription:{}", worker_id.to_redis_key())
+    }
+
+    fn worker_topics_key(worker_id: &WorkerId) -> String {
+        format!("worker:{}:topics", worker_id.to_redis_key())
+    }
+
+    /// The stored sequence number is only a lower bound of the last published one, as it is
+    /// advanced after storing the message. It is always at least the sequence number of any
+    /// acknowledged message, so the slots of trimmed messages are never reused.
+    async fn sequence_hint(&self, account_id: &AccountId, topic: &str) -> Result<u64, GolemError> {
+        let sequence: Option<u64> = self
+            .key_value_storage
+            .with_entity("topics", "sequence_hint", "sequence")
+            .get(Self::namespace(account_id), &Self::sequence_key(topic))
+            .await
+            .map_err(GolemError::runtime)?;
+        Ok(sequence.unwrap_or(0))
+    }
+
+    async fn advance_sequence(
+        &self,
+        account_id: &AccountId,
+        topic: &str,
+        sequence: u64,
+    ) -> Result<(), GolemError> {
+        let storage = self
+            .key_value_storage
+            .with_entity("topics", "advance_sequence", "sequence");
+        let key = Self::sequence_key(topic);
+        loop {
+            let current = storage
+                .get_raw(Self::namespace(account_id), &key)
+                .await
+                .map_err(GolemError::runtime)?;
+            let hint: u64 = match &current {
+                Some(raw) => deserialize(raw).map_err(GolemError::runtime)?,
+                None => 0,
+            };
+            if hint >= sequence {
+                break Ok(());
+            }
+            let serialized = serialize(&sequence).map_err(GolemError::runtime)?;
+            if storage
+                .compare_and_swap_raw(
+                    Self::namespace(account_id),
+                    &key,
+                    current.as_deref(),
+                    Some(&serialized),
+                )
+                .await
+                .map_err(GolemError::runtime)?
+            {
+                break Ok(());
+            }
+        }
+    }
+
+    async fn last_sequence(&self, account_id: &AccountId, topic: &str) -> Result<u64, GolemError> {
+        let mut sequence = self.sequence_hint(account_id, topic).await?;
+        while self.message_exists(account_id, topic, sequence + 1).await? {
+            sequence += 1;
+        }
+        Ok(sequence)
+    }
+
+    async fn message_exists(
+        &self,
+        account_id: &AccountId,
+        topic: &str,
+        sequence: u64,
+    ) -> Result<bool, GolemError> {
+        self.key_value_storage
+            .with("topics", "message_exists")
+            .exists(
+                Self::namespace(account_id),
+                &Self::message_key(topic, sequence),
+            )
+            .await
+            .map_err(GolemError::runtime)
+    }
+
+    /// Reads the stored messages starting from `first`. The stored messages have consecutive
+    /// sequence numbers, so reading stops at the first missing one.
+    async fn messages_from(
+        &self,
+        account_id: &AccountId,
+        topic: &str,
+        first: u64,
+    ) -> Result<Vec<TopicMessage>, GolemError> {
+        const PAGE_SIZE: u64 = 100;
+
+        let storage = self
+            .key_value_storage
+            .with_entity("topics", "messages_from", "message");
+        let mut messages = Vec::new();
+        let mut next = first;
+        loop {
+            let keys = (next..next + PAGE_SIZE)
+                .map(|sequence| Self::message_key(topic, sequence))
+                .collect();
+            let page: Vec<Option<TopicMessage>> = storage
+                .get_many(Self::namespace(account_id), keys)
+                .await
+                .map_err(GolemError::runtime)?;
+            for message in page {
+                match message {
+                    Some(message) => messages.push(message),
+                    None => return Ok(messages),
+                }
+            }
+            next += PAGE_SIZE;
+        }
+    }
+
+    async fn mark_pending(
+        &self,
+        account_id: &AccountId,
+        topic: &str,
+        publisher: &WorkerId,
+    ) -> Result<(), GolemError> {
+        self.key_value_storage
+            .with_entity("topics", "mark_pending", "pending_topic")
+            .set(
+                KeyValueStorageNamespace::PendingTopics,
+                &Self::pending_topic_key(account_id, topic),
+                &PendingTopic {
+                    account_id: account_id.clone(),
+                    topic: topic.to_string(),
+                    publisher: publisher.clone(),
+                },
+            )
+            .await
+            .map_err(GolemError::runtime)
+    }
+
+    async fn get_subscription(
+        &self,
+        account_id: &AccountId,
+        topic: &str,
+        worker_id: &WorkerId,
+    ) -> Result<Option<TopicSubscription>, GolemError> {
+        self.key_value_storage
+            .with_entity("topics", "get_subscription", "subscription")
+            .get(
+                Self::namespace(account_id),
+                &Self::subscription_key(topic, worker_id),
+            )
+            .await
+            .map_err(GolemError::runtime)
+    }
+
+    async fn add_topic(&self, account_id: &AccountId, topic: &str) -> Result<(), GolemError> {
+        self.key_value_storage
+            .with_entity("topics", "add_topic", "topic")
+            .add_to_set(
+                Self::namespace(account_id),
+                Self::topics_key(),
+                &topic.to_string(),
+            )
+            .await
+            .map_err(GolemError::runtime)
+    }
+}
+
+#[async_trait]
+impl TopicService for DefaultTopicService {
+    async fn subscribe(
+        &self,
+        account_id: &AccountId,
+        topic: &str,
+        worker_id: &WorkerId,
+        handler: &str,
+    ) -> Result<(), GolemError> {
+        Self::validate_topic(topic)?;
+        if handler.is_empty() {
+            return Err(GolemError::invalid_request(
+                "Handler function name must not be empty",
+            ));
+        }
+
+        let acknowledged = match self.get_subscription(account_id, topic, worker_id).await? {
+            Some(existing) => existing.acknowledged,
+            None => self.last_sequence(account_id, topic).await?,
+        };
+
+        self.add_topic(account_id, topic).await?;
+        self.key_value_storage
+            .with_entity("topics", "subscribe", "subscription")
+            .set(
+                Self::namespace(account_id),
+                &Self::subscription_key(topic, worker_id),
+                &TopicSubscription {
+                    worker_id: worker_id.clone(),
+                    handler: handler.to_string(),
+                    acknowledged,
+                },
+            )
+            .await
+            .map_err(GolemError::runtime)?;
+        self.key_value_storage
+            .with_entity("topics", "subscribe", "worker_id")
+            .add_to_set(
+                Self::namespace(account_id),
+                &Self::subscribers_key(topic),
+                worker_id,
+            )
+            .await
+            .map_err(GolemError::runtime)?;
+        self.key_value_storage
+            .with_entity("topics", "subscribe", "topic")
+            .add_to_set(
+                Self::namespace(account_id),
+                &Self::worker_topics_key(worker_id),
+                &topic.to_string(),
+            )
+            .await
+            .map_err(GolemError::runtime)
+    }
+
+    async fn unsubscribe(
+        &self,
+        account_id: &AccountId,
+        topic: &str,
+        worker_id: &WorkerId,
+    ) -> Result<(), GolemError> {
+        Self::validate_topic(topic)?;
+
+        self.key_value_storage
+            .with_entity("topics", "unsubscribe", "worker_id")
+            .remove_from_set(
+                Self::namespace(account_id),
+                &Self::subscribers_key(topic),
+                worker_id,
+            )
+            .await
+            .map_err(GolemError::runtime)?;
+        self.key_value_storage
+            .with("topics", "unsubscribe")
+            .del(
+                Self::namespace(account_id),
+                &Self::subscription_key(topic, worker_id),
+            )
+            .await
+            .map_err(GolemError::runtime)?;
+        self.key_value_storage
+            .with_entity("topics", "unsubscribe", "topic")
+            .remove_from_set(
+                Self::namespace(account_id),
+                &Self::worker_topics_key(worker_id),
+                &topic.to_string(),
+            )
+            .await
+            .map_err(GolemError::runtime)?;
+
+        // The messages only kept for this subscriber are no longer needed
+        self.trim(account_id, topic).await
+    }
+
+    async fn unsubscribe_all(
+        &self,
+        account_id: &AccountId,
+        worker_id: &WorkerId,
+    ) -> Result<(), GolemError> {
+        let topics: Vec<String> = self
+            .key_value_storage
+            .with_entity("topics", "unsubscribe_all", "topic")
+            .members_of_set(
+                Self::namespace(account_id),
+                &Self::worker_topics_key(worker_id),
+            )
+            .await
+            .map_err(GolemError::runtime)?;
+        for topic in topics {
+            self.unsubscribe(account_id, &topic, worker_id).await?;
+        }
+        Ok(())
+    }
+
+    async fn publish(
+        &self,
+        account_id: &AccountId,
+        topic: &str,
+        publisher: &WorkerId,
+        payload: Vec<u8>,
+    ) -> Result<u64, GolemError> {
+        Self::validate_topic(topic)?;
+        self.add_topic(account_id, topic).await?;
+        self.mark_pending(account_id, topic, publisher).await?;
+
+        let storage = self
+            .key_value_storage
+            .with_entity("topics", "publish", "message");
+        let mut sequence = self.sequence_hint(account_id, topic).await? + 1;
+        loop {
+            // Storing the message reserves its sequence number, so there is no state in which
+            // the sequence number is taken but the message is missing
+            let message = TopicMessage {
+                sequence,
+                publisher: publisher.clone(),
+                payload: payload.clone(),
+            };
+            if !storage
+                .set_if_not_exists(
+                    Self::namespace(account_id),
+                    &Self::message_key(topic, sequence),
+                    &message,
+                )
+                .await
+                .map_err(GolemError::runtime)?
+            {
+                sequence += 1;
+                continue;
+            }
+
+            // The slot could have been freed by trimming after the hint was read, in which
+            // case the message would be treated as already acknowledged
+            let hint = self.sequence_hint(account_id, topic).await?;
+            if hint < sequence {
+                break;
+            }
+            self.key_value_storage
+                .with("topics", "publish")
+                .del(
+                    Self::namespace(account_id),
+                    &Self::message_key(topic, sequence),
+                )
+                .await
+                .map_err(GolemError::runtime)?;
+            sequence = hint + 1;
+        }
+
+        self.advance_sequence(account_id, topic, sequence).await?;
+        // Marking again after storing the message, in case the background delivery removed
+        // the topic from the pending ones in the meantime
+        self.mark_pending(account_id, topic, publisher).await?;
+        Ok(sequence)
+    }
+
+    async fn subscriptions(
+        &self,
+        account_id: &AccountId,
+        topic: &str,
+    ) -> Result<Vec<TopicSubscription>, GolemError> {
+        let worker_ids: Vec<WorkerId> = self
+            .key_value_storage
+            .with_entity("topics", "subscriptions", "worker_id")
+            .members_of_set(Self::namespace(account_id), &Self::subscribers_key(topic))
+            .await
+            .map_err(GolemError::runtime)?;
+
+        let mut subscriptions = Vec::with_capacity(worker_ids.len());
+        for worker_id in worker_ids {
+            if let Some(subscription) = self.get_subscription(account_id, topic, &worker_id).await?
+            {
+                subscriptions.push(subscription);
+            }
+        }
+        subscriptions.sort_by_key(|subscription| subscription.worker_id.to_string());
+        Ok(subscriptions)
+    }
+
+    async fn pending_messages(
+        &self,
+        account_id: &AccountId,
+        topic: &str,
+        subscription: &TopicSubscription,
+    ) -> Result<Vec<TopicMessage>, GolemError> {
+        self.messages_from(account_id, topic, subscription.acknowledged + 1)
+            .await
+    }
+
+    async fn acknowledge(
+        &self,
+        account_id: &AccountId,
+        topic: &str,
+        worker_id: &WorkerId,
+        sequence: u64,
+    ) -> Result<(), GolemError> {
+        self.advance_sequence(account_id, topic, sequence).await?;
+
+        let storage = self
+            .key_value_storage
+            .with_entity("topics", "acknowledge", "subscription");
+        let key = Self::subscription_key(topic, worker_id);
+        loop {
+            let current = storage
+                .get_raw(Self::namespace(account_id), &key)
+                .await
+                .map_err(GolemError::runtime)?;
+            // Unsubscribed in the meantime
+            let Some(current) = current else {
+                break Ok(());
+            };
+            let mut subscription: TopicSubscription =
+                deserialize(&current).map_err(GolemError::runtime)?;
+            if subscription.acknowledged >= sequence {
+                break Ok(());
+            }
+            subscription.acknowledged = sequence;
+            let serialized = serialize(&subscription).map_err(GolemError::runtime)?;
+            if storage
+                .compare_and_swap_raw(
+                    Self::namespace(account_id),
+                    &key,
+                    Some(&current),
+                    Some(&serialized),
+                )
+                .await
+                .map_err(GolemError::runtime)?
+            {
+                break Ok(());
+            }
+        }
+    }
+
+    async fn trim(&self, account_id: &AccountId, topic: &str) -> Result<(), GolemError> {
+        let subscriptions = self.subscriptions(account_id, topic).await?;
+        let delivered_to_all = match subscriptions
+            .iter()
+            .map(|subscription| subscription.acknowledged)
+            .min()
+        {
+            Some(acknowledged) => acknowledged,
+            None => self.last_sequence(account_id, topic).await?,
+        };
+        self.advance_sequence(account_id, topic, delivered_to_all)
+            .await?;
+
+        // Messages are trimmed in order, so the remaining ones are always consecutive
+        let storage = self.key_value_storage.with("topics", "trim");
+        let mut sequence = delivered_to_all;
+        while sequence > 0 && self.message_exists(account_id, topic, sequence).await? {
+            sequence -= 1;
+        }
+        for sequence in (sequence + 1)..=delivered_to_all {
+            storage
+                .del(
+                    Self::namespace(account_id),
+                    &Self::message_key(topic, sequence),
+                )
+                .await
+                .map_err(GolemError::runtime)?;
+        }
+        Ok(())
+    }
+
+    async fn list_topics(&self, account_id: &AccountId) -> Result<Vec<String>, GolemError> {
+        let mut topics: Vec<String> = self
+            .key_value_storage
+            .with_entity("topics", "list_topics", "topic")
+            .members_of_set(Self::namespace(account_id), Self::topics_key())
+            .await
+            .map_err(GolemError::runtime)?;
+        topics.sort();
+        Ok(topics)
+    }
+
+    async fn get_topic(
+        &self,
+        account_id: &AccountId,
+        topic: &str,
+    ) -> Result<Option<TopicInfo>, GolemError> {
+        if !self
+            .list_topics(account_id)
+            .await?
+            .iter()
+            .any(|t| t == topic)
+        {
+            return Ok(None);
+        }
+
+        let last_sequence = self.last_sequence(account_id, topic).await?;
+        let mut first_retained = last_sequence + 1;
+        while first_retained > 1
+            && self
+                .message_exists(account_id, topic, first_retained - 1)
+                .await?
+        {
+            first_retained -= 1;
+        }
+        let subscriptions = self.subscriptions(account_id, topic).await?;
+
+        Ok(Some(TopicInfo {
+            name: topic.to_string(),
+            last_sequence,
+            retained_messages: last_sequence + 1 - first_retained,
+            subscriptions,
+        }))
+    }
+
+    async fn pending_topics(&self) -> Result<Vec<PendingTopic>, GolemError> {
+        let keys = self
+            .key_value_storage
+            .with("topics", "pending_topics")
+            .keys(KeyValueStorageNamespace::PendingTopics)
+            .await
+            .map_err(GolemError::runtime)?;
+        let pending: Vec<Option<PendingTopic>> = self
+            .key_value_storage
+            .with_entity("topics", "pending_topics", "pending_topic")
+            .get_many(KeyValueStorageNamespace::PendingTopics, keys)
+            .await
+            .map_err(GolemError::runtime)?;
+        Ok(pending.into_iter().flatten().collect())
+    }
+
+    async fn delivered(&self, pending: &PendingTopic) -> Result<(), GolemError> {
+        self.key_value_storage
+            .with("topics", "delivered")
+            .del(
+                KeyValueStorageNamespace::PendingTopics,
+                &Self::pending_topic_key(&pending.account_id, &pending.topic),
+            )
+            .await
+            .map_err(GolemError::runtime)?;
+
+        // Checking after the removal, so a message published concurrently is either seen here
+        // or marks the topic as pending again by itself
+        let last_sequence = self
+            .last_sequence(&pending.account_id, &pending.topic)
+            .await?;
+        let subscriptions = self
+            .subscriptions(&pending.account_id, &pending.topic)
+            .await?;
+        if subscriptions
+            .iter()
+            .any(|subscription| subscription.acknowledged < last_sequence)
+        {
+            self.mark_pending(&pending.account_id, &pending.topic, &pending.publisher)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+/// Periodically delivers the messages of the pending topics whose last publisher is owned by
+/// the executor, so messages are delivered even if publishing them failed to deliver them and
+/// nothing else gets published to the topic.
+pub fn start<T>(deps: T) -> Option<JoinHandle<()>>
+where
+    T: HasConfig + HasTopicService + HasRpc + HasShardService + Send + Sync + 'static,
+{
+    let interval = deps.config().topics.delivery_interval;
+    info!("Delivering the pending messages of topics every {interval:?}");
+    Some(tokio::spawn(
+        async move {
+            let mut interval = tokio::time::interval(interval);
+            loop {
+                interval.tick().await;
+                if let Err(err) = deliver_pending_topics(&deps).await {
+                    warn!("Failed to deliver the pending messages of topics: {err}");
+                }
+            }
+        }
+        .in_current_span(),
+    ))
+}
+
+async fn deliver_pending_topics<T>(deps: &T) -> Result<(), GolemError>
+where
+    T: HasTopicService + HasRpc + HasShardService + Send + Sync,
+{
+    if !deps.shard_service().is_ready() {
+        return Ok(());
+    }
+
+    for pending in deps.topic_service().pending_topics().await? {
+        if deps
+            .shard_service()
+            .check_worker(&pending.publisher)
+            .is_err()
+        {
+            continue;
+        }
+
+        deliver_pending_messages(
+            deps.topic_service(),
+            deps.rpc(),
+            &pending.account_id,
+            &pending.topic,
+            &[],
+            &[],
+        )
+        .await?;
+        deps.topic_service().delivered(&pending).await?;
+    }
+    Ok(())
+}
+
+/// Delivers the messages of the topic not yet acknowledged by its subscribers, as invocations
+/// of their handler functions on behalf of the message's publisher. A message is only
+/// acknowledged after its invocation has been enqueued, so failed deliveries are retried.
+pub async fn deliver_pending_messages(
+    topic_service: Arc<dyn TopicService + Send + Sync>,
+    rpc: Arc<dyn Rpc + Send + Sync>,
+    account_id: &AccountId,
+    topic: &str,
+    args: &[String],
+    env: &[(String, String)],
+) -> Result<(), GolemError> {
+    let subscriptions = topic_service.subscriptions(account_id, topic).await?;
+    let deliveries = subscriptions.into_iter().map(|subscription| {
+        let topic_service = topic_service.clone();
+        let rpc = rpc.clone();
+        async move {
+            let pending = topic_service
+                .pending_messages(account_id, topic, &subscription)
+                .await?;
+            let target = OwnedWorkerId::new(account_id, &subscription.worker_id);
+            for message in pending {
+                let params: Vec<WitValue> = vec![
+                    Value::String(topic.to_string()).into(),
+                    Value::U64(message.sequence).into(),
+                    Value::List(message.payload.into_iter().map(Value::U8).collect()).into(),
+                ];
+                rpc.invoke(
+                    &target,
+                    Some(delivery_idempotency_key(
+                        account_id,
+                        topic,
+                        message.sequence,
+                        &subscription.worker_id,
+                    )),
+                    subscription.handler.clone(),
+                    params,
+                    &message.publisher,
+                    args,
+                    env,
+                    &BTreeMap::new(),
+                )
+                .await
+                .map_err(|err| {
+                    GolemError::runtime(format!(
+                        "Failed to deliver message {} to {}: {err}",
+                        message.sequence, subscription.worker_id
+                    ))
+                })?;
+                topic_service
+                    .acknowledge(account_id, topic, &subscription.worker_id, message.sequence)
+                    .await?;
+            }
+            Ok::<(), GolemError>(())
+        }
+    });
+
+    for result in join_all(deliveries).await {
+        if let Err(err) = result {
+            warn!("Failed to deliver messages of topic {topic}: {err}");
+        }
+    }
+
+    topic_service.trim(account_id, topic).await
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::sync::Arc;
+
+    use golem_common::model::{AccountId, ComponentId, WorkerId, WorkerName};
+
+    use futures::future::join_all;
+
+    use crate::services::topics::{DefaultTopicService, TopicService};
+    use crate::storage::keyvalue::memory::InMemoryKeyValueStorage;
+
+    fn worker_id(name: &str) -> WorkerId {
+        WorkerId {
+            component_id: ComponentId::new_v4(),
+            worker_name: WorkerName::from_existing(name),
+        }
+    }
+
+    #[test]
+    async fn messages_are_kept_until_acknowledged_by_all_subscribers() {
+        let service = DefaultTopicService::new(Arc::new(InMemoryKeyValueStorage::new()));
+        let account_id = AccountId {
+            value: "account1".to_string(),
+        };
+        let publisher = worker_id("publisher");
+        let worker1 = worker_id("worker1");
+        let worker2 = worker_id("worker2");
+
+        // Messages published before subscribing are not delivered
+        service
+            .publish(&account_id, "orders", &publisher, b"m0".to_vec())
+            .await
+            .unwrap();
+        service
+            .subscribe(&account_id, "orders", &worker1, "on-order")
+            .await
+            .unwrap();
+        service
+            .subscribe(&account_id, "orders", &worker2, "on-order")
+            .await
+            .unwrap();
+
+        let seq1 = service
+            .publish(&account_id, "orders", &publisher, b"m1".to_vec())
+            .await
+            .unwrap();
+        let seq2 = service
+            .publish(&account_id, "orders", &publisher, b"m2".to_vec())
+            .await
+            .unwrap();
+        assert_eq!((seq1, seq2), (2, 3));
+
+        let subscriptions = service.subscriptions(&account_id, "orders").await.unwrap();
+        assert_eq!(subscriptions.len(), 2);
+        let pending = service
+            .pending_messages(&account_id, "orders", &subscriptions[0])
+            .await
+            .unwrap();
+        assert_eq!(
+            pending
+                .iter()
+                .map(|message| message.payload.clone())
+                .collect::<Vec<_>>(),
+            vec![b"m1".to_vec(), b"m2".to_vec()]
+        );
+
+        service
+            .acknowledge(&account_id, "orders", &worker1, seq2)
+            .await
+            .unwrap();
+        service
+            .acknowledge(&account_id, "orders", &worker2, seq1)
+            .await
+            .unwrap();
+        service.trim(&account_id, "orders").await.unwrap();
+
+        let info = service
+            .get_topic(&account_id, "orders")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(info.last_sequence, 3);
+        assert_eq!(info.retained_messages, 1);
+        let lags = info
+            .subscriptions
+            .iter()
+            .map(|subscription| (subscription.worker_id.clone(), info.lag(subscription)))
+            .collect::<Vec<_>>();
+        assert!(lags.contains(&(worker1.clone(), 0)));
+        assert!(lags.contains(&(worker2.clone(), 1)));
+
+        service
+            .unsubscribe_all(&account_id, &worker2)
+            .await
+            .unwrap();
+        let info = service
+            .get_topic(&account_id, "orders")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(info.subscriptions.len(), 1);
+        assert_eq!(info.retained_messages, 0);
+        assert_eq!(
+            service.list_topics(&account_id).await.unwrap(),
+            vec!["orders".to_string()]
+        );
+    }
+
+    #[test]
+    async fn concurrent_publishes_get_distinct_sequence_numbers() {
+        let service = DefaultTopicService::new(Arc::new(InMemoryKeyValueStorage::new()));
+        let account_id = AccountId {
+            value: "account1".to_string(),
+        };
+        let publisher = worker_id("publisher");
+        let subscriber = worker_id("subscriber");
+        service
+            .subscribe(&account_id, "events", &subscriber, "on-event")
+            .await
+            .unwrap();
+
+        let publishes = (0..20u8).map(|i| {
+            let service = &service;
+            let account_id = &account_id;
+            let publisher = &publisher;
+            async move {
+                service
+                    .publish(account_id, "events", publisher, vec![i])
+                    .await
+                    .unwrap()
+            }
+        });
+        let mut sequences = join_all(publishes).await;
+        sequences.sort();
+        assert_eq!(sequences, (1..=20).collect::<Vec<u64>>());
+
+        let subscriptions = service.subscriptions(&account_id, "events").await.unwrap();
+        let pending = service
+            .pending_messages(&account_id, "events", &subscriptions[0])
+            .await
+            .unwrap();
+        assert_eq!(
+            pending
+                .iter()
+                .map(|message| message.sequence)
+                .collect::<Vec<_>>(),
+            (1..=20).collect::<Vec<u64>>()
+        );
+        assert!(pending.iter().all(|message| message.publisher == publisher));
+    }
+
+    #[test]
+    async fn sequence_numbers_of_trimmed_messages_are_not_reused() {
+        let service = DefaultTopicService::new(Arc::new(InMemoryKeyValueStorage::new()));
+        let account_id = AccountId {
+            value: "account1".to_string(),
+        };
+        let publisher = worker_id("publisher");
+        let subscriber = worker_id("subscriber");
+        service
+            .subscribe(&account_id, "events", &subscriber, "on-event")
+            .await
+            .unwrap();
+
+        let seq1 = service
+            .publish(&account_id, "events", &publisher, b"m1".to_vec())
+            .await
+            .unwrap();
+        let seq2 = service
+            .publish(&account_id, "events", &publisher, b"m2".to_vec())
+            .await
+            .unwrap();
+        service
+            .acknowledge(&account_id, "events", &subscriber, seq2)
+            .await
+            .unwrap();
+        service.trim(&account_id, "events").await.unwrap();
+
+        let info = service
+            .get_topic(&account_id, "events")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(info.retained_messages, 0);
+
+        let seq3 = service
+            .publish(&account_id, "events", &publisher, b"m3".to_vec())
+            .await
+            .unwrap();
+        assert_eq!((seq1, seq2, seq3), (1, 2, 3));
+
+        let subscriptions = service.subscriptions(&account_id, "events").await.unwrap();
+        let pending = service
+            .pending_messages(&account_id, "events", &subscriptions[0])
+            .await
+            .unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].payload, b"m3".to_vec());
+    }
+
+    #[test]
+    async fn topics_stay_pending_until_delivered_to_all_subscribers() {
+        let service = DefaultTopicService::new(Arc::new(InMemoryKeyValueStorage::new()));
+        let account_id = AccountId {
+            value: "account1".to_string(),
+        };
+        let publisher = worker_id("publisher");
+        let subscriber = worker_id("subscriber");
+        service
+            .subscribe(&account_id, "events", &subscriber, "on-event")
+            .await
+            .unwrap();
+
+        let sequence = service
+            .publish(&account_id, "events", &publisher, b"m1".to_vec())
+            .await
+            .unwrap();
+
+        let pending = service.pending_topics().await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].topic, "events");
+        assert_eq!(pending[0].publisher, publisher);
+
+        // Not acknowledged yet, so the topic remains pending
+        service.delivered(&pending[0]).await.unwrap();
+        assert_eq!(service.pending_topics().await.unwrap(), pending);
+
+        service
+            .acknowledge(&account_id, "events", &subscriber, sequence)
+            .await
+            .unwrap();
+        service.delivered(&pending[0]).await.unwrap();
+        assert!(service.pending_topics().await.unwrap().is_empty());
+    }
+}
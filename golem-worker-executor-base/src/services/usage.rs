@@ -0,0 +1,1019 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bincode::{Decode, Encode};
+use bytes::Bytes;
+use golem_common::model::oplog::{OplogEntry, OplogIndex, OplogPayload};
+use golem_common::model::{AccountId, OwnedWorkerId, Timestamp};
+use golem_common::serialization::{deserialize, serialize};
+use tokio::sync::mpsc::{channel, Receiver, Sender};
+use tokio::sync::oneshot;
+use tracing::{error, info, warn, Instrument};
+
+use crate::error::GolemError;
+use crate::metrics::usage::{
+    record_dropped_usage, record_failed_usage_record, record_stored_usage_record,
+};
+use crate::services::golem_config::{UsageMeteringConfig, UsageMeteringEnabledConfig};
+use crate::services::oplog::{CommitLevel, Oplog};
+use crate::storage::keyvalue::{
+    KeyValueStorage, KeyValueStorageLabelledApi, KeyValueStorageNamespace,
+};
+
+/// Meters the resource usage of the workers from the entries added to their oplogs, and stores
+/// it aggregated into billing records per account and billing period.
+///
+/// The records are stored in the shared key-value storage, so the usage of an account can be
+/// queried from any executor, including the usage metered by the others.
+///
+/// The usage is metered at least once: the position up to which the oplog of a worker has been
+/// metered is only stored together with the usage, and the entries after it are metered again
+/// when the worker is loaded, for example after a crash of the executor.
+#[async_trait]
+pub trait UsageService {
+    /// Wraps the oplog of a worker so the entries added to it get metered, after metering the
+    /// entries added since the last stored position of the worker. `memory_size` is the current
+    /// total linear memory size of the worker.
+    async fn metered_oplog(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        memory_size: u64,
+        oplog: Arc<dyn Oplog + Send + Sync>,
+    ) -> Arc<dyn Oplog + Send + Sync>;
+
+    /// Returns the usage of the account in the billing periods starting in the given time range
+    /// (`from` inclusive, `to` exclusive), in chronological order
+    async fn get_usage(
+        &self,
+        account_id: &AccountId,
+        from: Timestamp,
+        to: Timestamp,
+    ) -> Result<Vec<UsageRecord>, GolemError>;
+}
+
+pub fn configured(
+    config: &UsageMeteringConfig,
+    key_value_storage: Arc<dyn KeyValueStorage + Send + Sync>,
+) -> Arc<dyn UsageService + Send + Sync> {
+    match config {
+        UsageMeteringConfig::Disabled => Arc::new(DefaultUsageService::new(key_value_storage)),
+        UsageMeteringConfig::Enabled(config) => {
+            info!(
+                "Metering worker usage in billing periods of {:?}",
+                config.period
+            );
+            Arc::new(DefaultUsageService::new(key_value_storage).with_metering(config))
+        }
+    }
+}
+
+/// Resource usage of the workers of an account
+#[derive(Clone, Debug, Default, PartialEq, Eq, Encode, Decode)]
+pub struct Usage {
+    /// Number of started invocations
+    pub invocations: u64,
+    /// Total linear memory of the workers multiplied by the duration of their invocations
+    pub memory_byte_seconds: u64,
+    /// Size of the entries added to the oplogs of the workers
+    pub oplog_bytes: u64,
+}
+
+impl Usage {
+    pub fn add(&mut self, other: &Usage) {
+        self.invocations += other.invocations;
+        self.memory_byte_seconds += other.memory_byte_seconds;
+        self.oplog_bytes += other.oplog_bytes;
+    }
+}
+
+/// The usage of an account in a billing period
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UsageRecord {
+    pub period_start: Timestamp,
+    pub period_end: Timestamp,
+    pub usage: Usage,
+}
+
+/// The usage of an account in a billing period, stored under a single key per period which all
+/// the executors increment with compare-and-swap
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+struct StoredUsageRecord {
+    period_start: u64,
+    period_end: u64,
+    usage: Usage,
+}
+
+/// The last metered oplog entry of a worker, with the state of its meter after that entry
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+struct UsageCursor {
+    oplog_index: OplogIndex,
+    memory_size: u64,
+    invocation_started: Option<Timestamp>,
+}
+
+/// The usage of a worker caused by a single oplog entry, to be aggregated
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct MeteredUsage {
+    owned_worker_id: OwnedWorkerId,
+    cursor: UsageCursor,
+    /// The usage split by the times it belongs to
+    usage: Vec<(Timestamp, Usage)>,
+}
+
+enum UsageCommand {
+    Meter(MeteredUsage),
+    Flush(oneshot::Sender<()>),
+}
+
+pub struct DefaultUsageService {
+    key_value_storage: Arc<dyn KeyValueStorage + Send + Sync>,
+    queue: Option<Arc<UsageQueue>>,
+}
+
+impl DefaultUsageService {
+    const PERIODS_KEY: &'static str = "periods";
+
+    pub fn new(key_value_storage: Arc<dyn KeyValueStorage + Send + Sync>) -> Self {
+        Self {
+            key_value_storage,
+            queue: None,
+        }
+    }
+
+    /// Meters the oplogs of the workers, aggregating the usage in a background task which stops
+    /// when the service is dropped, after storing the remaining usage
+    pub fn with_metering(mut self, config: &UsageMeteringEnabledConfig) -> Self {
+        let (sender, receiver) = channel(config.queue_capacity.max(1));
+        let period = config.period.max(Duration::from_millis(1)).as_millis() as u64;
+        let unstored_cursors = Arc::new(Mutex::new(HashMap::new()));
+        tokio::spawn(
+            Self::run(
+                receiver,
+                self.key_value_storage.clone(),
+                period,
+                config.flush_interval,
+                unstored_cursors.clone(),
+            )
+            .in_current_span(),
+        );
+        self.queue = Some(Arc::new(UsageQueue {
+            sender,
+            period,
+            unstored_cursors,
+        }));
+        self
+    }
+
+    /// Stores the usage aggregated so far, returning when it has been stored or failed to be
+    /// stored. Does nothing if the metering is disabled.
+    pub async fn flush(&self) {
+        if let Some(queue) = &self.queue {
+            let (sender, receiver) = oneshot::channel();
+            if queue.sender.send(UsageCommand::Flush(sender)).await.is_ok() {
+                let _ = receiver.await;
+            }
+        }
+    }
+
+    fn namespace(account_id: &AccountId) -> KeyValueStorageNamespace {
+        KeyValueStorageNamespace::Usage {
+            account_id: account_id.clone(),
+        }
+    }
+
+    fn period_key(period_start: u64) -> String {
+        format!("period:{period_start}")
+    }
+
+    fn cursor_key(owned_worker_id: &OwnedWorkerId) -> String {
+        format!("cursor:{}", owned_worker_id.worker_id)
+    }
+
+    async fn run(
+        mut receiver: Receiver<UsageCommand>,
+        key_value_storage: Arc<dyn KeyValueStorage + Send + Sync>,
+        period: u64,
+        flush_interval: Duration,
+        unstored_cursors: Arc<Mutex<HashMap<OwnedWorkerId, UsageCursor>>>,
+    ) {
+        let mut aggregator = UsageAggregator {
+            key_value_storage,
+            period,
+            pending: HashMap::new(),
+            cursors: HashMap::new(),
+            unstored_cursors,
+        };
+        let mut flush = tokio::time::interval(flush_interval);
+        loop {
+            tokio::select! {
+                command = receiver.recv() => match command {
+                    Some(UsageCommand::Meter(metered)) => aggregator.add(metered),
+                    Some(UsageCommand::Flush(done)) => {
+                        aggregator.store().await;
+                        let _ = done.send(());
+                    }
+                    None => {
+                        aggregator.store().await;
+                        break;
+                    }
+                },
+                _ = flush.tick() => aggregator.store().await,
+            }
+        }
+    }
+
+    /// Reads the last stored position of a worker, unless this executor has metered entries of
+    /// the worker which are not stored yet
+    async fn get_cursor(
+        &self,
+        queue: &UsageQueue,
+        owned_worker_id: &OwnedWorkerId,
+    ) -> Result<Option<UsageCursor>, String> {
+        let unstored = queue
+            .unstored_cursors
+            .lock()
+            .unwrap()
+            .get(owned_worker_id)
+            .cloned();
+        match unstored {
+            Some(cursor) => Ok(Some(cursor)),
+            None => {
+                self.key_value_storage
+                    .with_entity("usage", "get_cursor", "usage_cursor")
+                    .get(
+                        Self::namespace(&owned_worker_id.account_id),
+                        &Self::cursor_key(owned_worker_id),
+                    )
+                    .await
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl UsageService for DefaultUsageService {
+    async fn metered_oplog(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        memory_size: u64,
+        oplog: Arc<dyn Oplog + Send + Sync>,
+    ) -> Arc<dyn Oplog + Send + Sync> {
+        let queue = match &self.queue {
+            Some(queue) => queue.clone(),
+            None => return oplog,
+        };
+
+        let last_index = oplog.current_oplog_index().await;
+        let meter = match self.get_cursor(&queue, owned_worker_id).await {
+            Ok(Some(cursor)) => {
+                // Metering again the entries added after the last stored position
+                let mut meter = WorkerMeter::from_cursor(&cursor, queue.period);
+                let mut oplog_index = cursor.oplog_index.next();
+                while oplog_index <= last_index {
+                    let entry = oplog.read(oplog_index).await;
+                    let usage = meter.add_entry(&entry, entry_size(&entry));
+                    queue
+                        .send(MeteredUsage {
+                            owned_worker_id: owned_worker_id.clone(),
+                            cursor: meter.cursor(oplog_index),
+                            usage,
+                        })
+                        .await;
+                    oplog_index = oplog_index.next();
+                }
+                meter
+            }
+            Ok(None) => {
+                // The worker has not been metered yet, the position is stored before any entry
+                // gets metered, so the first entries are not lost in a crash
+                let meter = WorkerMeter::new(memory_size, queue.period);
+                let result = self
+                    .key_value_storage
+                    .with_entity("usage", "metered_oplog", "usage_cursor")
+                    .set(
+                        Self::namespace(&owned_worker_id.account_id),
+                        &Self::cursor_key(owned_worker_id),
+                        &meter.cursor(last_index),
+                    )
+                    .await;
+                if let Err(err) = result {
+                    warn!(worker_id = %owned_worker_id.worker_id, "Failed to store the initial usage metering position: {err}");
+                }
+                meter
+            }
+            Err(err) => {
+                warn!(worker_id = %owned_worker_id.worker_id, "Failed to read the usage metering position, metering the new entries only: {err}");
+                WorkerMeter::new(memory_size, queue.period)
+            }
+        };
+
+        Arc::new(MeteredOplog {
+            owned_worker_id: owned_worker_id.clone(),
+            inner: oplog,
+            meter: Mutex::new(meter),
+            queue,
+        })
+    }
+
+    async fn get_usage(
+        &self,
+        account_id: &AccountId,
+        from: Timestamp,
+        to: Timestamp,
+    ) -> Result<Vec<UsageRecord>, GolemError> {
+        let from = from.to_millis();
+        let to = to.to_millis();
+        if from >= to {
+            return Ok(Vec::new());
+        }
+
+        let storage = self
+            .key_value_storage
+            .with_entity("usage", "get_usage", "usage_record");
+        let periods: Vec<(f64, u64)> = storage
+            .query_sorted_set(
+                Self::namespace(account_id),
+                Self::PERIODS_KEY,
+                from as f64,
+                to as f64,
+            )
+            .await
+            .map_err(GolemError::runtime)?;
+        let keys = periods
+            .into_iter()
+            .map(|(_, period_start)| period_start)
+            .filter(|period_start| *period_start >= from && *period_start < to)
+            .map(Self::period_key)
+            .collect::<Vec<_>>();
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let stored: Vec<Option<StoredUsageRecord>> = storage
+            .get_many(Self::namespace(account_id), keys)
+            .await
+            .map_err(GolemError::runtime)?;
+
+        let mut periods: BTreeMap<(u64, u64), Usage> = BTreeMap::new();
+        for record in stored.into_iter().flatten() {
+            periods
+                .entry((record.period_start, record.period_end))
+                .or_default()
+                .add(&record.usage);
+        }
+
+        Ok(periods
+            .into_iter()
+            .map(|((period_start, period_end), usage)| UsageRecord {
+                period_start: Timestamp::from(period_start),
+                period_end: Timestamp::from(period_end),
+                usage,
+            })
+            .collect())
+    }
+}
+
+/// Aggregates the metered usage in memory until it gets stored
+struct UsageAggregator {
+    key_value_storage: Arc<dyn KeyValueStorage + Send + Sync>,
+    period: u64,
+    pending: HashMap<(AccountId, u64), Usage>,
+    /// Positions of the workers whose usage is pending
+    cursors: HashMap<OwnedWorkerId, UsageCursor>,
+    /// Positions of the workers metered by this executor which are not stored yet, shared with
+    /// the service so reloaded workers do not get metered twice
+    unstored_cursors: Arc<Mutex<HashMap<OwnedWorkerId, UsageCursor>>>,
+}
+
+impl UsageAggregator {
+    fn add(&mut self, metered: MeteredUsage) {
+        for (timestamp, usage) in &metered.usage {
+            let timestamp = timestamp.to_millis();
+            let period_start = timestamp - timestamp % self.period;
+            self.pending
+                .entry((metered.owned_worker_id.account_id.clone(), period_start))
+                .or_default()
+                .add(usage);
+        }
+        self.cursors.insert(metered.owned_worker_id, metered.cursor);
+    }
+
+    /// Stores the pending usage, keeping the records which failed to be stored to retry them at
+    /// the next flush. The positions of the workers are only stored once all the pending usage
+    /// got stored.
+    async fn store(&mut self) {
+        let keys = self.pending.keys().cloned().collect::<Vec<_>>();
+        for (account_id, period_start) in keys {
+            let usage = self.pending[&(account_id.clone(), period_start)].clone();
+            match self.add_usage(&account_id, period_start, &usage).await {
+                Ok(()) => {
+                    record_stored_usage_record();
+                    self.pending.remove(&(account_id, period_start));
+                }
+                Err(err) => {
+                    record_failed_usage_record();
+                    error!(
+                        account_id = %account_id,
+                        "Failed to store the usage of billing period starting at {}, retrying at the next flush: {err}",
+                        Timestamp::from(period_start)
+                    );
+                }
+            }
+        }
+
+        if self.pending.is_empty() {
+            let cursors = std::mem::take(&mut self.cursors);
+            for (owned_worker_id, cursor) in cursors {
+                let result = self
+                    .key_value_storage
+                    .with_entity("usage", "store", "usage_cursor")
+                    .set(
+                        DefaultUsageService::namespace(&owned_worker_id.account_id),
+                        &DefaultUsageService::cursor_key(&owned_worker_id),
+                        &cursor,
+                    )
+                    .await;
+                match result {
+                    Ok(()) => {
+                        let mut unstored = self.unstored_cursors.lock().unwrap();
+                        if unstored.get(&owned_worker_id) == Some(&cursor) {
+                            unstored.remove(&owned_worker_id);
+                        }
+                    }
+                    Err(err) => {
+                        // Keeping the unstored position, the usage is metered again if the
+                        // executor crashes
+                        warn!(worker_id = %owned_worker_id.worker_id, "Failed to store the usage metering position: {err}");
+                        self.cursors.entry(owned_worker_id).or_insert(cursor);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Adds the usage to the stored record of the billing period
+    async fn add_usage(
+        &self,
+        account_id: &AccountId,
+        period_start: u64,
+        usage: &Usage,
+    ) -> Result<(), String> {
+        let namespace = DefaultUsageService::namespace(account_id);
+        let key = DefaultUsageService::period_key(period_start);
+        let storage = self
+            .key_value_storage
+            .with_entity("usage", "store", "usage_record");
+
+        // The period is indexed first, so a stored record is always found by the queries
+        storage
+            .add_to_sorted_set(
+                namespace.clone(),
+                DefaultUsageService::PERIODS_KEY,
+                period_start as f64,
+                &period_start,
+            )
+            .await?;
+
+        loop {
+            let current = storage.get_raw(namespace.clone(), &key).await?;
+            let mut record = match &current {
+                Some(bytes) => deserialize::<StoredUsageRecord>(bytes)?,
+                None => StoredUsageRecord {
+                    period_start,
+                    period_end: period_start + self.period,
+                    usage: Usage::default(),
+                },
+            };
+            record.usage.add(usage);
+            let updated = serialize(&record)?;
+            if storage
+                .compare_and_swap_raw(namespace.clone(), &key, current.as_deref(), Some(&updated))
+                .await?
+            {
+                return Ok(());
+            }
+        }
+    }
+}
+
+struct UsageQueue {
+    sender: Sender<UsageCommand>,
+    period: u64,
+    unstored_cursors: Arc<Mutex<HashMap<OwnedWorkerId, UsageCursor>>>,
+}
+
+impl UsageQueue {
+    /// Waits for free space in the queue if the aggregation is behind, so no usage gets lost
+    async fn send(&self, metered: MeteredUsage) {
+        self.unstored_cursors
+            .lock()
+            .unwrap()
+            .insert(metered.owned_worker_id.clone(), metered.cursor.clone());
+        if self
+            .sender
+            .send(UsageCommand::Meter(metered))
+            .await
+            .is_err()
+        {
+            // Only happens when the executor is shutting down, the entries are metered again
+            // when the worker gets loaded next time
+            record_dropped_usage();
+        }
+    }
+}
+
+fn entry_size(entry: &OplogEntry) -> u64 {
+    serialize(entry)
+        .map(|bytes| bytes.len() as u64)
+        .unwrap_or_default()
+}
+
+/// Calculates the usage caused by the entries added to the oplog of a worker
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct WorkerMeter {
+    memory_size: u64,
+    /// Start of the invocation in progress
+    invocation_started: Option<Timestamp>,
+    /// Length of the billing periods in milliseconds
+    period: u64,
+}
+
+impl WorkerMeter {
+    fn new(memory_size: u64, period: u64) -> Self {
+        Self {
+            memory_size,
+            invocation_started: None,
+            period,
+        }
+    }
+
+    fn from_cursor(cursor: &UsageCursor, period: u64) -> Self {
+        Self {
+            memory_size: cursor.memory_size,
+            invocation_started: cursor.invocation_started,
+            period,
+        }
+    }
+
+    fn cursor(&self, oplog_index: OplogIndex) -> UsageCursor {
+        UsageCursor {
+            oplog_index,
+            memory_size: self.memory_size,
+            invocation_started: self.invocation_started,
+        }
+    }
+
+    /// Returns the usage caused by an entry, split by the times it belongs to
+    fn add_entry(&mut self, entry: &OplogEntry, size: u64) -> Vec<(Timestamp, Usage)> {
+        let mut usage = Usage {
+            oplog_bytes: size,
+            ..Default::default()
+        };
+        let mut memory_usage = Vec::new();
+        match entry {
+            OplogEntry::ExportedFunctionInvoked { timestamp, .. } => {
+                usage.invocations = 1;
+                self.invocation_started = Some(*timestamp);
+            }
+            OplogEntry::GrowMemory { delta, .. } => {
+                self.memory_size += delta;
+            }
+            OplogEntry::ExportedFunctionCompleted { timestamp, .. }
+            | OplogEntry::Error { timestamp, .. }
+            | OplogEntry::Interrupted { timestamp }
+            | OplogEntry::Exited { timestamp }
+            | OplogEntry::Suspend { timestamp } => {
+                if let Some(started) = self.invocation_started.take() {
+                    memory_usage = self.memory_usage(started.to_millis(), timestamp.to_millis());
+                }
+            }
+            _ => {}
+        }
+        let mut result = vec![(entry.timestamp(), usage)];
+        result.extend(memory_usage);
+        result
+    }
+
+    /// The memory usage between `from` and `to`, split at the boundaries of the billing periods
+    fn memory_usage(&self, from: u64, to: u64) -> Vec<(Timestamp, Usage)> {
+        let byte_seconds = |millis: u64| (self.memory_size as u128 * millis as u128 / 1000) as u64;
+        let mut result = Vec::new();
+        let mut start = from;
+        while start < to {
+            let end = (start - start % self.period + self.period).min(to);
+            result.push((
+                Timestamp::from(start),
+                Usage {
+                    memory_byte_seconds: byte_seconds(end - from) - byte_seconds(start - from),
+                    ..Default::default()
+                },
+            ));
+            start = end;
+        }
+        result
+    }
+}
+
+/// Oplog metering the entries added to it before passing them to the wrapped oplog
+struct MeteredOplog {
+    owned_worker_id: OwnedWorkerId,
+    inner: Arc<dyn Oplog + Send + Sync>,
+    meter: Mutex<WorkerMeter>,
+    queue: Arc<UsageQueue>,
+}
+
+impl Debug for MeteredOplog {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MeteredOplog")
+            .field("owned_worker_id", &self.owned_worker_id)
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl Oplog for MeteredOplog {
+    async fn add(&self, entry: OplogEntry) {
+        let size = entry_size(&entry);
+        let usage = self.meter.lock().unwrap().add_entry(&entry, size);
+        self.inner.add(entry).await;
+
+        let oplog_index = self.inner.current_oplog_index().await;
+        let cursor = self.meter.lock().unwrap().cursor(oplog_index);
+        self.queue
+            .send(MeteredUsage {
+                owned_worker_id: self.owned_worker_id.clone(),
+                cursor,
+                usage,
+            })
+            .await
+    }
+
+    async fn drop_prefix(&self, last_dropped_id: OplogIndex) {
+        self.inner.drop_prefix(last_dropped_id).await
+    }
+
+    async fn commit(&self, level: CommitLevel) {
+        self.inner.commit(level).await
+    }
+
+    async fn current_oplog_index(&self) -> OplogIndex {
+        self.inner.current_oplog_index().await
+    }
+
+    async fn last_committed_oplog_index(&self) -> OplogIndex {
+        self.inner.last_committed_oplog_index().await
+    }
+
+    async fn wait_for_replicas(&self, replicas: u8, timeout: Duration) -> bool {
+        self.inner.wait_for_replicas(replicas, timeout).await
+    }
+
+    async fn read(&self, oplog_index: OplogIndex) -> OplogEntry {
+        self.inner.read(oplog_index).await
+    }
+
+    async fn length(&self) -> u64 {
+        self.inner.length().await
+    }
+
+    async fn upload_payload(&self, data: &[u8]) -> Result<OplogPayload, String> {
+        self.inner.upload_payload(data).await
+    }
+
+    async fn upload_payload_with_limit(
+        &self,
+        data: &[u8],
+        max_inline_size: usize,
+    ) -> Result<OplogPayload, String> {
+        self.inner
+            .upload_payload_with_limit(data, max_inline_size)
+            .await
+    }
+
+    async fn download_payload(&self, payload: &OplogPayload) -> Result<Bytes, String> {
+        self.inner.download_payload(payload).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use async_trait::async_trait;
+    use bytes::Bytes;
+    use golem_common::model::oplog::{OplogEntry, OplogIndex, OplogPayload, WorkerError};
+    use golem_common::model::{
+        AccountId, ComponentId, IdempotencyKey, OwnedWorkerId, Timestamp, WorkerId, WorkerName,
+    };
+
+    use crate::services::golem_config::UsageMeteringEnabledConfig;
+    use crate::services::oplog::{CommitLevel, Oplog};
+    use crate::services::usage::{
+        DefaultUsageService, MeteredUsage, Usage, UsageCursor, UsageRecord, UsageService,
+        WorkerMeter,
+    };
+    use crate::storage::keyvalue::memory::InMemoryKeyValueStorage;
+    use crate::storage::keyvalue::KeyValueStorage;
+
+    const HOUR: u64 = 60 * 60 * 1000;
+
+    fn invoked(timestamp: u64) -> OplogEntry {
+        OplogEntry::ExportedFunctionInvoked {
+            timestamp: Timestamp::from(timestamp),
+            function_name: "f".to_string(),
+            request: OplogPayload::Inline(vec![]),
+            idempotency_key: IdempotencyKey::fresh(),
+        }
+    }
+
+    fn completed(timestamp: u64) -> OplogEntry {
+        OplogEntry::ExportedFunctionCompleted {
+            timestamp: Timestamp::from(timestamp),
+            response: OplogPayload::Inline(vec![]),
+            consumed_fuel: 0,
+        }
+    }
+
+    fn total(usage: Vec<(Timestamp, Usage)>) -> Usage {
+        let mut result = Usage::default();
+        for (_, usage) in usage {
+            result.add(&usage);
+        }
+        result
+    }
+
+    fn owned_worker_id() -> OwnedWorkerId {
+        OwnedWorkerId {
+            account_id: AccountId {
+                value: "account1".to_string(),
+            },
+            worker_id: WorkerId {
+                component_id: ComponentId::new_v4(),
+                worker_name: WorkerName::from_existing("worker1"),
+            },
+        }
+    }
+
+    fn metering_service(
+        key_value_storage: Arc<dyn KeyValueStorage + Send + Sync>,
+        period: Duration,
+    ) -> DefaultUsageService {
+        // Only flushing explicitly
+        DefaultUsageService::new(key_value_storage).with_metering(&UsageMeteringEnabledConfig {
+            period,
+            flush_interval: Duration::from_secs(60 * 60),
+            queue_capacity: 1,
+        })
+    }
+
+    /// Oplog keeping its entries in memory
+    #[derive(Debug, Default)]
+    struct TestOplog {
+        entries: Mutex<Vec<OplogEntry>>,
+    }
+
+    #[async_trait]
+    impl Oplog for TestOplog {
+        async fn add(&self, entry: OplogEntry) {
+            self.entries.lock().unwrap().push(entry);
+        }
+
+        async fn drop_prefix(&self, _last_dropped_id: OplogIndex) {}
+
+        async fn commit(&self, _level: CommitLevel) {}
+
+        async fn current_oplog_index(&self) -> OplogIndex {
+            OplogIndex::from_u64(self.entries.lock().unwrap().len() as u64)
+        }
+
+        async fn wait_for_replicas(&self, _replicas: u8, _timeout: Duration) -> bool {
+            false
+        }
+
+        async fn read(&self, oplog_index: OplogIndex) -> OplogEntry {
+            self.entries.lock().unwrap()[u64::from(oplog_index) as usize - 1].clone()
+        }
+
+        async fn length(&self) -> u64 {
+            self.entries.lock().unwrap().len() as u64
+        }
+
+        async fn upload_payload(&self, data: &[u8]) -> Result<OplogPayload, String> {
+            Ok(OplogPayload::Inline(data.to_vec()))
+        }
+
+        async fn upload_payload_with_limit(
+            &self,
+            data: &[u8],
+            _max_inline_size: usize,
+        ) -> Result<OplogPayload, String> {
+            self.upload_payload(data).await
+        }
+
+        async fn download_payload(&self, payload: &OplogPayload) -> Result<Bytes, String> {
+            match payload {
+                OplogPayload::Inline(data) => Ok(Bytes::copy_from_slice(data)),
+                _ => Err("not supported".to_string()),
+            }
+        }
+    }
+
+    #[test]
+    fn memory_is_metered_for_the_duration_of_invocations() {
+        let mut meter = WorkerMeter::new(1000, HOUR);
+
+        assert_eq!(
+            total(meter.add_entry(&invoked(10_000), 10)),
+            Usage {
+                invocations: 1,
+                memory_byte_seconds: 0,
+                oplog_bytes: 10,
+            }
+        );
+        meter.add_entry(
+            &OplogEntry::GrowMemory {
+                timestamp: Timestamp::from(10_500),
+                delta: 1000,
+            },
+            5,
+        );
+        assert_eq!(
+            total(meter.add_entry(&completed(12_000), 20)),
+            Usage {
+                invocations: 0,
+                memory_byte_seconds: 4000,
+                oplog_bytes: 20,
+            }
+        );
+
+        // Failed invocations are metered until the failure
+        meter.add_entry(&invoked(20_000), 10);
+        assert_eq!(
+            total(meter.add_entry(
+                &OplogEntry::Error {
+                    timestamp: Timestamp::from(21_000),
+                    error: WorkerError::OutOfMemory,
+                },
+                3,
+            ))
+            .memory_byte_seconds,
+            2000
+        );
+
+        // Nothing is in progress after the failure
+        assert_eq!(
+            total(meter.add_entry(&completed(30_000), 1)).memory_byte_seconds,
+            0
+        );
+    }
+
+    #[test]
+    fn memory_usage_is_split_at_period_boundaries() {
+        let mut meter = WorkerMeter::new(1000, 60_000);
+
+        meter.add_entry(&invoked(50_000), 10);
+        let usage = meter.add_entry(&completed(130_000), 20);
+
+        let memory = usage
+            .into_iter()
+            .filter(|(_, usage)| usage.memory_byte_seconds > 0)
+            .map(|(timestamp, usage)| (timestamp.to_millis(), usage.memory_byte_seconds))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            memory,
+            vec![(50_000, 10_000), (60_000, 60_000), (120_000, 10_000)]
+        );
+    }
+
+    #[test]
+    async fn usage_is_aggregated_per_billing_period() {
+        let service = metering_service(
+            Arc::new(InMemoryKeyValueStorage::new()),
+            Duration::from_secs(60),
+        );
+        let owned_worker_id = owned_worker_id();
+
+        let queue = service.queue.clone().unwrap();
+        for (timestamp, invocations) in [(1_000, 1), (59_000, 2), (61_000, 3), (125_000, 4)] {
+            queue
+                .send(MeteredUsage {
+                    owned_worker_id: owned_worker_id.clone(),
+                    cursor: UsageCursor {
+                        oplog_index: OplogIndex::from_u64(invocations),
+                        memory_size: 0,
+                        invocation_started: None,
+                    },
+                    usage: vec![(
+                        Timestamp::from(timestamp),
+                        Usage {
+                            invocations,
+                            memory_byte_seconds: 10,
+                            oplog_bytes: 100,
+                        },
+                    )],
+                })
+                .await;
+        }
+        service.flush().await;
+
+        let records = service
+            .get_usage(
+                &owned_worker_id.account_id,
+                Timestamp::from(0),
+                Timestamp::from(120_000),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            records,
+            vec![
+                UsageRecord {
+                    period_start: Timestamp::from(0),
+                    period_end: Timestamp::from(60_000),
+                    usage: Usage {
+                        invocations: 3,
+                        memory_byte_seconds: 20,
+                        oplog_bytes: 200,
+                    },
+                },
+                UsageRecord {
+                    period_start: Timestamp::from(60_000),
+                    period_end: Timestamp::from(120_000),
+                    usage: Usage {
+                        invocations: 3,
+                        memory_byte_seconds: 10,
+                        oplog_bytes: 100,
+                    },
+                },
+            ]
+        );
+    }
+
+    #[test]
+    async fn unstored_usage_is_metered_again_after_a_crash() {
+        let key_value_storage: Arc<dyn KeyValueStorage + Send + Sync> =
+            Arc::new(InMemoryKeyValueStorage::new());
+        let owned_worker_id = owned_worker_id();
+        let account_id = owned_worker_id.account_id.clone();
+        let oplog = Arc::new(TestOplog::default());
+
+        // The first executor stores the usage of the first invocation, and crashes before
+        // storing the usage of the second one
+        let first = metering_service(key_value_storage.clone(), Duration::from_secs(60));
+        let metered = first
+            .metered_oplog(&owned_worker_id, 1000, oplog.clone())
+            .await;
+        metered.add(invoked(1_000)).await;
+        metered.add(completed(2_000)).await;
+        first.flush().await;
+        metered.add(invoked(3_000)).await;
+        metered.add(completed(5_000)).await;
+
+        // The second executor meters the entries added after the stored position
+        let second = metering_service(key_value_storage.clone(), Duration::from_secs(60));
+        let _metered = second
+            .metered_oplog(&owned_worker_id, 1000, oplog.clone())
+            .await;
+        second.flush().await;
+
+        let records = second
+            .get_usage(&account_id, Timestamp::from(0), Timestamp::from(60_000))
+            .await
+            .unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].usage.invocations, 2);
+        assert_eq!(records[0].usage.memory_byte_seconds, 3000);
+
+        // Loading the worker again does not meter anything twice
+        let third = metering_service(key_value_storage, Duration::from_secs(60));
+        let _metered = third.metered_oplog(&owned_worker_id, 1000, oplog).await;
+        third.flush().await;
+
+        let records = third
+            .get_usage(&account_id, Timestamp::from(0), Timestamp::from(60_000))
+            .await
+            .unwrap();
+        assert_eq!(records[0].usage.invocations, 2);
+    }
+}
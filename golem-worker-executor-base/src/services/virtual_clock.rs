@@ -0,0 +1,252 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use async_trait::async_trait;
+use tokio::sync::{watch, Mutex};
+
+use golem_common::model::WorkerId;
+
+use crate::error::GolemError;
+use crate::storage::keyvalue::{
+    KeyValueStorage, KeyValueStorageLabelledApi, KeyValueStorageNamespace,
+};
+
+/// Clock of a worker whose time only changes when it is set or advanced through the admin API.
+///
+/// Both the wall clock and the monotonic clock of the worker return the virtual time, in
+/// nanoseconds since the unix epoch, and sleeps end when the virtual time reaches their deadline.
+pub struct VirtualClock {
+    now: watch::Sender<u64>,
+}
+
+impl VirtualClock {
+    pub fn new(now: u64) -> Self {
+        Self {
+            now: watch::Sender::new(now),
+        }
+    }
+
+    pub fn now(&self) -> u64 {
+        *self.now.borrow()
+    }
+
+    /// Waits until the virtual time reaches `deadline`
+    pub async fn wait_until(&self, deadline: u64) {
+        let mut receiver = self.now.subscribe();
+        // The sender is owned by self, so it cannot be dropped while waiting
+        let _ = receiver.wait_for(|now| *now >= deadline).await;
+    }
+
+    fn set(&self, now: u64) {
+        self.now.send_replace(now);
+    }
+}
+
+/// Service managing the virtual clocks of the workers, available when the executor runs with
+/// `virtual_clock.enabled`, for deterministically testing sleeping and time dependent workers.
+#[async_trait]
+pub trait VirtualClockService {
+    /// Gets the virtual clock of the worker, or `None` if it uses the real clock
+    async fn get(&self, worker_id: &WorkerId) -> Result<Option<Arc<VirtualClock>>, GolemError>;
+
+    /// Sets the virtual time of the worker in nanoseconds since the unix epoch, virtualizing its
+    /// clock if it was using the real one. A virtual clock cannot be set back in time.
+    async fn set_time(&self, worker_id: &WorkerId, now: u64) -> Result<u64, GolemError>;
+
+    /// Advances the virtual time of the worker, starting from the current real time if it was
+    /// using the real clock. Returns the new virtual time.
+    async fn advance(&self, worker_id: &WorkerId, duration: Duration) -> Result<u64, GolemError>;
+
+    /// Switches the worker back to the real clock
+    async fn reset(&self, worker_id: &WorkerId) -> Result<(), GolemError>;
+}
+
+/// Virtual clock service storing the virtual time of the workers in the key-value storage, and
+/// keeping the clocks of the workers already looked up in memory
+pub struct DefaultVirtualClockService {
+    key_value_storage: Arc<dyn KeyValueStorage + Send + Sync>,
+    enabled: bool,
+    clocks: Mutex<HashMap<WorkerId, Option<Arc<VirtualClock>>>>,
+}
+
+impl DefaultVirtualClockService {
+    pub fn new(key_value_storage: Arc<dyn KeyValueStorage + Send + Sync>, enabled: bool) -> Self {
+        Self {
+            key_value_storage,
+            enabled,
+            clocks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn virtual_time_key(worker_id: &WorkerId) -> String {
+        format!("worker:virtual_time:{}", worker_id.to_redis_key())
+    }
+
+    fn ensure_enabled(&self) -> Result<(), GolemError> {
+        if self.enabled {
+            Ok(())
+        } else {
+            Err(GolemError::invalid_request(
+                "Virtual clocks are only available when the executor runs with virtual_clock.enabled",
+            ))
+        }
+    }
+
+    fn real_now() -> u64 {
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|duration| duration.as_nanos() as u64)
+            .unwrap_or(0)
+    }
+
+    async fn load(&self, worker_id: &WorkerId) -> Result<Option<Arc<VirtualClock>>, GolemError> {
+        let now: Option<u64> = self
+            .key_value_storage
+            .with_entity("virtual_clock", "get", "virtual_time")
+            .get(
+                KeyValueStorageNamespace::Worker,
+                &Self::virtual_time_key(worker_id),
+            )
+            .await
+            .map_err(GolemError::runtime)?;
+        Ok(now.map(|now| Arc::new(VirtualClock::new(now))))
+    }
+
+    /// Persists and applies the new virtual time computed from the current one, which is `None`
+    /// when the worker uses the real clock
+    async fn update(
+        &self,
+        worker_id: &WorkerId,
+        f: impl FnOnce(Option<u64>) -> Result<u64, GolemError> + Send,
+    ) -> Result<u64, GolemError> {
+        self.ensure_enabled()?;
+
+        let mut clocks = self.clocks.lock().await;
+        let clock = match clocks.get(worker_id) {
+            Some(clock) => clock.clone(),
+            None => self.load(worker_id).await?,
+        };
+        let now = f(clock.as_ref().map(|clock| clock.now()))?;
+
+        self.key_value_storage
+            .with_entity("virtual_clock", "set", "virtual_time")
+            .set(
+                KeyValueStorageNamespace::Worker,
+                &Self::virtual_time_key(worker_id),
+                &now,
+            )
+            .await
+            .map_err(GolemError::runtime)?;
+
+        match clock {
+            Some(clock) => {
+                clock.set(now);
+                clocks.insert(worker_id.clone(), Some(clock));
+            }
+            None => {
+                clocks.insert(worker_id.clone(), Some(Arc::new(VirtualClock::new(now))));
+            }
+        }
+        Ok(now)
+    }
+}
+
+#[async_trait]
+impl VirtualClockService for DefaultVirtualClockService {
+    async fn get(&self, worker_id: &WorkerId) -> Result<Option<Arc<VirtualClock>>, GolemError> {
+        if !self.enabled {
+            return Ok(None);
+        }
+
+        let mut clocks = self.clocks.lock().await;
+        match clocks.get(worker_id) {
+            Some(clock) => Ok(clock.clone()),
+            None => {
+                let clock = self.load(worker_id).await?;
+                clocks.insert(worker_id.clone(), clock.clone());
+                Ok(clock)
+            }
+        }
+    }
+
+    async fn set_time(&self, worker_id: &WorkerId, now: u64) -> Result<u64, GolemError> {
+        self.update(worker_id, |current| match current {
+            Some(current) if now < current => Err(GolemError::invalid_request(format!(
+                "The virtual clock cannot be set back in time, its current time is {current}"
+            ))),
+            _ => Ok(now),
+        })
+        .await
+    }
+
+    async fn advance(&self, worker_id: &WorkerId, duration: Duration) -> Result<u64, GolemError> {
+        self.update(worker_id, |current| {
+            Ok(current
+                .unwrap_or_else(Self::real_now)
+                .saturating_add(duration.as_nanos() as u64))
+        })
+        .await
+    }
+
+    async fn reset(&self, worker_id: &WorkerId) -> Result<(), GolemError> {
+        let mut clocks = self.clocks.lock().await;
+        self.key_value_storage
+            .with("virtual_clock", "reset")
+            .del(
+                KeyValueStorageNamespace::Worker,
+                &Self::virtual_time_key(worker_id),
+            )
+            .await
+            .map_err(GolemError::runtime)?;
+
+        // Sleeps of the worker waiting for the virtual time are ended by moving the dropped clock
+        // past every deadline
+        if let Some(Some(clock)) = clocks.remove(worker_id) {
+            clock.set(u64::MAX);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use crate::services::virtual_clock::VirtualClock;
+
+    #[test]
+    async fn wait_until_ends_when_the_deadline_is_reached() {
+        let clock = Arc::new(VirtualClock::new(100));
+        let waiting_clock = clock.clone();
+        let waiter = tokio::spawn(async move { waiting_clock.wait_until(200).await });
+
+        clock.set(150);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiter.is_finished());
+
+        clock.set(200);
+        tokio::time::timeout(Duration::from_secs(5), waiter)
+            .await
+            .expect("the sleep did not end")
+            .unwrap();
+        assert_eq!(clock.now(), 200);
+    }
+}
@@ -12,19 +12,30 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use async_trait::async_trait;
+use dashmap::DashMap;
+use golem_common::cache::{BackgroundEvictionMode, Cache, FullCacheEvictionMode, SimpleCache};
 use golem_common::model::oplog::{OplogEntry, OplogIndex};
+use golem_common::model::regions::CorruptedOplogRegion;
 use golem_common::model::{
     ComponentType, OwnedWorkerId, ShardId, Timestamp, WorkerId, WorkerMetadata, WorkerStatus,
     WorkerStatusRecord,
 };
-use tracing::{debug, warn};
+use golem_common::serialization::try_deserialize;
+use tokio::runtime::Handle;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn, Instrument};
 
 use crate::error::GolemError;
-use crate::metrics::workers::record_worker_call;
+use crate::metrics::workers::{record_worker_call, record_worker_status_write};
 
+use crate::services::golem_config::{
+    WorkerStatusCacheConfig, WorkerStatusCacheEnabledConfig, WorkerStatusReadConsistency,
+};
 use crate::services::oplog::OplogService;
 use crate::services::shard::ShardService;
 use crate::storage::keyvalue::{
@@ -42,6 +53,20 @@ pub trait WorkerService {
 
     async fn get(&self, owned_worker_id: &OwnedWorkerId) -> Option<WorkerMetadata>;
 
+    /// Gets the metadata of the worker with the given read consistency, which only makes a
+    /// difference if the status records are cached
+    async fn get_with_consistency(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        _consistency: WorkerStatusReadConsistency,
+    ) -> Option<WorkerMetadata> {
+        self.get(owned_worker_id).await
+    }
+
+    /// Gets the last status record of the worker, which unlike `get` does not need the account
+    /// owning the worker. Returns `None` if the worker has no status record.
+    async fn get_status(&self, worker_id: &WorkerId) -> Option<WorkerStatusRecord>;
+
     async fn get_running_workers_in_shards(&self) -> Vec<WorkerMetadata>;
 
     async fn remove(&self, owned_worker_id: &OwnedWorkerId);
@@ -54,6 +79,37 @@ pub trait WorkerService {
         status_value: &WorkerStatusRecord,
         component_type: ComponentType,
     );
+
+    /// Called after shards got revoked from this executor, before they are assigned to another
+    /// one
+    async fn on_shards_revoked(&self) {}
+}
+
+pub fn configured(
+    config: &WorkerStatusCacheConfig,
+    key_value_storage: Arc<dyn KeyValueStorage + Send + Sync>,
+    shard_service: Arc<dyn ShardService + Send + Sync>,
+    oplog_service: Arc<dyn OplogService + Send + Sync>,
+) -> Arc<dyn WorkerService + Send + Sync> {
+    let worker_service = Arc::new(DefaultWorkerService::new(
+        key_value_storage,
+        shard_service.clone(),
+        oplog_service,
+    ));
+    match config {
+        WorkerStatusCacheConfig::Disabled => worker_service,
+        WorkerStatusCacheConfig::Enabled(config) => {
+            info!(
+                "Caching the status of at most {} workers, storing the updates every {:?}",
+                config.max_capacity, config.flush_interval
+            );
+            Arc::new(CachedWorkerService::new(
+                worker_service,
+                shard_service,
+                config,
+            ))
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -99,10 +155,47 @@ impl DefaultWorkerService {
         workers
     }
 
+    /// Reads the stored status record of a worker. The status record is only a cache of
+    /// information computable from the oplog, so a record which cannot be decoded (for example
+    /// because it was stored in an older format) is treated as missing and gets recomputed.
+    async fn read_status(
+        &self,
+        api_name: &'static str,
+        worker_id: &WorkerId,
+    ) -> Option<WorkerStatusRecord> {
+        let bytes = self
+            .key_value_storage
+            .with_entity("worker", api_name, "worker_status")
+            .get_raw(
+                KeyValueStorageNamespace::Worker,
+                &Self::status_key(worker_id),
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!("failed to get worker status for {worker_id} from KV storage: {err}")
+            })?;
+        match try_deserialize(&bytes) {
+            Ok(status) => status,
+            Err(err) => {
+                warn!(
+                    worker_id = worker_id.to_string(),
+                    "Ignoring stored worker status which cannot be decoded: {err}"
+                );
+                None
+            }
+        }
+    }
+
     fn status_key(worker_id: &WorkerId) -> String {
         format!("worker:status:{}", worker_id.to_redis_key())
     }
 
+    /// Corruption markers of the deleted regions are not part of the status record's encoding,
+    /// so they are stored separately
+    fn corrupted_regions_key(worker_id: &WorkerId) -> String {
+        format!("worker:corrupted_regions:{}", worker_id.to_redis_key())
+    }
+
     fn running_in_shard_key(shard_id: &ShardId) -> String {
         format!("worker:running_in_shard:{shard_id}")
     }
@@ -211,22 +304,36 @@ impl WorkerService for DefaultWorkerService {
                     },
                 };
 
-                let status_value: Option<WorkerStatusRecord> = self
-                    .key_value_storage
-                    .with_entity("worker", "get", "worker_status")
-                    .get(
-                        KeyValueStorageNamespace::Worker,
-                        &Self::status_key(&owned_worker_id.worker_id),
-                    )
-                    .await
-                    .unwrap_or_else(|err| {
-                        panic!("failed to get worker status for {owned_worker_id} from KV storage: {err}")
-                    });
+                let status_value = self.read_status("get", &owned_worker_id.worker_id).await;
+
+                if let Some(mut status) = status_value {
+                    if !status.deleted_regions.is_empty() {
+                        let corrupted_regions: Option<Vec<CorruptedOplogRegion>> = self
+                            .key_value_storage
+                            .with_entity("worker", "get", "corrupted_regions")
+                            .get(
+                                KeyValueStorageNamespace::Worker,
+                                &Self::corrupted_regions_key(&owned_worker_id.worker_id),
+                            )
+                            .await
+                            .unwrap_or_else(|err| {
+                                panic!("failed to get corrupted oplog regions for {owned_worker_id} from KV storage: {err}")
+                            });
+                        if let Some(corrupted_regions) = corrupted_regions {
+                            status
+                                .deleted_regions
+                                .set_corrupted_regions(corrupted_regions);
+                        }
+                    }
 
-                if let Some(status) = status_value {
                     details.last_known_status = status;
                 }
 
+                if let Some((args, env)) = details.last_known_status.changed_environment.clone() {
+                    details.args = args;
+                    details.env = env;
+                }
+
                 Some(details)
             }
             Some((_, entry)) => {
@@ -261,6 +368,12 @@ impl WorkerService for DefaultWorkerService {
         }
     }
 
+    async fn get_status(&self, worker_id: &WorkerId) -> Option<WorkerStatusRecord> {
+        record_worker_call("get_status");
+
+        self.read_status("get_status", worker_id).await
+    }
+
     async fn get_running_workers_in_shards(&self) -> Vec<WorkerMetadata> {
         let shard_assignment = self.shard_service.try_get_current_assignment();
         let mut result: Vec<WorkerMetadata> = vec![];
@@ -314,6 +427,17 @@ impl WorkerService for DefaultWorkerService {
             .unwrap_or_else(|err| {
                 panic!("failed to remove worker status in the KV storage: {err}")
             });
+
+        self.key_value_storage
+            .with("worker", "remove")
+            .del(
+                KeyValueStorageNamespace::Worker,
+                &Self::corrupted_regions_key(&owned_worker_id.worker_id),
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!("failed to remove corrupted oplog regions in the KV storage: {err}")
+            });
     }
 
     async fn update_status(
@@ -336,6 +460,38 @@ impl WorkerService for DefaultWorkerService {
                 .await
                 .unwrap_or_else(|err| panic!("failed to set worker status in KV storage: {err}"));
 
+            if status_value.deleted_regions.has_corrupted_regions() {
+                let corrupted_regions = status_value
+                    .deleted_regions
+                    .corrupted_regions()
+                    .cloned()
+                    .collect::<Vec<_>>();
+                self.key_value_storage
+                    .with_entity("worker", "update_status", "corrupted_regions")
+                    .set(
+                        KeyValueStorageNamespace::Worker,
+                        &Self::corrupted_regions_key(&owned_worker_id.worker_id),
+                        &corrupted_regions,
+                    )
+                    .await
+                    .unwrap_or_else(|err| {
+                        panic!("failed to set corrupted oplog regions in KV storage: {err}")
+                    });
+            } else {
+                // The corrupted regions were repaired or the oplog was rewritten, so the
+                // previously stored markers must not be restored on the next read
+                self.key_value_storage
+                    .with("worker", "update_status")
+                    .del(
+                        KeyValueStorageNamespace::Worker,
+                        &Self::corrupted_regions_key(&owned_worker_id.worker_id),
+                    )
+                    .await
+                    .unwrap_or_else(|err| {
+                        panic!("failed to remove corrupted oplog regions in KV storage: {err}")
+                    });
+            }
+
             let shard_assignment = self
                 .shard_service
                 .current_assignment()
@@ -375,3 +531,818 @@ impl WorkerService for DefaultWorkerService {
         }
     }
 }
+
+/// Keeps the metadata of the recently used workers in memory, and stores their status updates
+/// through the wrapped service in batches instead of on every change.
+///
+/// Deferring the status updates is safe because the status records are only checkpoints of the
+/// status computed from the oplog, so an update lost in a crash gets recomputed on recovery. The
+/// exception is the set of running workers per shard, used to find the workers to recover, so
+/// updates marking a worker as running are stored immediately.
+///
+/// Only the workers of the shards assigned to the executor are cached. When shards get revoked,
+/// the pending updates of their workers are stored before the new owner takes them over, and the
+/// later updates are stored immediately.
+pub struct CachedWorkerService {
+    inner: Arc<dyn WorkerService + Send + Sync>,
+    shard_service: Arc<dyn ShardService + Send + Sync>,
+    cache: WorkerMetadataCache,
+    pending: Arc<Mutex<HashMap<OwnedWorkerId, PendingStatusUpdate>>>,
+    store_locks: StoreLocks,
+    flush_handle: JoinHandle<()>,
+}
+
+type WorkerMetadataCache =
+    Cache<OwnedWorkerId, (), Option<Arc<Mutex<CachedWorkerMetadata>>>, GolemError>;
+
+struct CachedWorkerMetadata {
+    metadata: WorkerMetadata,
+    /// Whether the stored status of the worker is `Running`
+    stored_running: bool,
+}
+
+#[derive(Clone, PartialEq)]
+struct PendingStatusUpdate {
+    status: WorkerStatusRecord,
+    component_type: ComponentType,
+}
+
+/// Per worker locks serializing storing the pending update of a worker with the updates
+/// bypassing it, so an older pending update never overwrites a newer one
+#[derive(Clone, Default)]
+struct StoreLocks {
+    locks: Arc<DashMap<OwnedWorkerId, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+impl StoreLocks {
+    async fn lock(&self, owned_worker_id: &OwnedWorkerId) -> StoreGuard {
+        let lock = self
+            .locks
+            .entry(owned_worker_id.clone())
+            .or_default()
+            .clone();
+        let guard = lock.lock_owned().await;
+        StoreGuard {
+            locks: self.clone(),
+            owned_worker_id: owned_worker_id.clone(),
+            guard: Some(guard),
+        }
+    }
+}
+
+struct StoreGuard {
+    locks: StoreLocks,
+    owned_worker_id: OwnedWorkerId,
+    guard: Option<tokio::sync::OwnedMutexGuard<()>>,
+}
+
+impl Drop for StoreGuard {
+    fn drop(&mut self) {
+        drop(self.guard.take());
+        // The lock of a worker is only kept while somebody is holding or waiting for it
+        self.locks
+            .locks
+            .remove_if(&self.owned_worker_id, |_, lock| {
+                Arc::strong_count(lock) == 1
+            });
+    }
+}
+
+impl CachedWorkerService {
+    pub fn new(
+        inner: Arc<dyn WorkerService + Send + Sync>,
+        shard_service: Arc<dyn ShardService + Send + Sync>,
+        config: &WorkerStatusCacheEnabledConfig,
+    ) -> Self {
+        let cache = Cache::new(
+            Some(config.max_capacity),
+            FullCacheEvictionMode::LeastRecentlyUsed(1),
+            BackgroundEvictionMode::None,
+            "worker_status",
+        );
+        let pending = Arc::new(Mutex::new(HashMap::new()));
+        let store_locks = StoreLocks::default();
+        let flush_handle = tokio::spawn(
+            Self::flush_periodically(
+                inner.clone(),
+                cache.clone(),
+                pending.clone(),
+                store_locks.clone(),
+                config.flush_interval,
+            )
+            .in_current_span(),
+        );
+        Self {
+            inner,
+            shard_service,
+            cache,
+            pending,
+            store_locks,
+            flush_handle,
+        }
+    }
+
+    async fn flush_periodically(
+        inner: Arc<dyn WorkerService + Send + Sync>,
+        cache: WorkerMetadataCache,
+        pending: Arc<Mutex<HashMap<OwnedWorkerId, PendingStatusUpdate>>>,
+        store_locks: StoreLocks,
+        flush_interval: Duration,
+    ) {
+        let mut flush = tokio::time::interval(flush_interval);
+        // The first tick completes immediately
+        flush.tick().await;
+        loop {
+            flush.tick().await;
+            Self::store_pending_updates(&inner, &cache, &pending, &store_locks, |_| true).await;
+        }
+    }
+
+    /// Stores the pending status updates of the workers selected by `filter`
+    async fn store_pending_updates(
+        inner: &Arc<dyn WorkerService + Send + Sync>,
+        cache: &WorkerMetadataCache,
+        pending: &Mutex<HashMap<OwnedWorkerId, PendingStatusUpdate>>,
+        store_locks: &StoreLocks,
+        filter: impl Fn(&OwnedWorkerId) -> bool,
+    ) {
+        let owned_worker_ids = pending
+            .lock()
+            .unwrap()
+            .keys()
+            .filter(|owned_worker_id| filter(owned_worker_id))
+            .cloned()
+            .collect::<Vec<_>>();
+        for owned_worker_id in owned_worker_ids {
+            let _guard = store_locks.lock(&owned_worker_id).await;
+            Self::store_pending_update(inner, cache, pending, &owned_worker_id).await;
+        }
+    }
+
+    /// Stores the pending status update of the worker if there is one. Must be called while
+    /// holding the store lock of the worker.
+    async fn store_pending_update(
+        inner: &Arc<dyn WorkerService + Send + Sync>,
+        cache: &WorkerMetadataCache,
+        pending: &Mutex<HashMap<OwnedWorkerId, PendingStatusUpdate>>,
+        owned_worker_id: &OwnedWorkerId,
+    ) {
+        let update = pending.lock().unwrap().get(owned_worker_id).cloned();
+        if let Some(update) = update {
+            inner
+                .update_status(owned_worker_id, &update.status, update.component_type)
+                .await;
+            Self::mark_stored(cache, owned_worker_id, &update.status);
+            record_worker_status_write("flushed");
+
+            // The update is kept pending until it is stored, so concurrent reads missing the
+            // cache never see an older status, but a newer update must not be dropped
+            let mut pending = pending.lock().unwrap();
+            if pending.get(owned_worker_id) == Some(&update) {
+                pending.remove(owned_worker_id);
+            }
+        }
+    }
+
+    fn mark_stored(
+        cache: &WorkerMetadataCache,
+        owned_worker_id: &OwnedWorkerId,
+        status: &WorkerStatusRecord,
+    ) {
+        if let Some(Some(cached)) = cache.try_get(owned_worker_id) {
+            cached.lock().unwrap().stored_running = status.status == WorkerStatus::Running;
+        }
+    }
+
+    fn forget(&self, owned_worker_id: &OwnedWorkerId) {
+        self.pending.lock().unwrap().remove(owned_worker_id);
+        self.cache.remove(owned_worker_id);
+    }
+
+    /// Whether the worker belongs to the shards assigned to this executor
+    fn is_owned(shard_service: &Arc<dyn ShardService + Send + Sync>, worker_id: &WorkerId) -> bool {
+        shard_service.check_worker(worker_id).is_ok()
+    }
+}
+
+impl Drop for CachedWorkerService {
+    fn drop(&mut self) {
+        self.flush_handle.abort();
+
+        // The updates still pending are stored by a last flush outliving the service
+        if let Ok(handle) = Handle::try_current() {
+            let inner = self.inner.clone();
+            let cache = self.cache.clone();
+            let pending = self.pending.clone();
+            let store_locks = self.store_locks.clone();
+            handle.spawn(
+                async move {
+                    Self::store_pending_updates(&inner, &cache, &pending, &store_locks, |_| true)
+                        .await
+                }
+                .in_current_span(),
+            );
+        }
+    }
+}
+
+#[async_trait]
+impl WorkerService for CachedWorkerService {
+    async fn add(
+        &self,
+        worker_metadata: &WorkerMetadata,
+        component_type: ComponentType,
+    ) -> Result<(), GolemError> {
+        let owned_worker_id = worker_metadata.owned_worker_id();
+        let _guard = self.store_locks.lock(&owned_worker_id).await;
+        self.forget(&owned_worker_id);
+        self.inner.add(worker_metadata, component_type).await
+    }
+
+    async fn get(&self, owned_worker_id: &OwnedWorkerId) -> Option<WorkerMetadata> {
+        if !Self::is_owned(&self.shard_service, &owned_worker_id.worker_id) {
+            // The status of the workers owned by other executors is not cached, as it is
+            // changed by them
+            return self.inner.get(owned_worker_id).await;
+        }
+
+        let inner = self.inner.clone();
+        let pending = self.pending.clone();
+        let key = owned_worker_id.clone();
+        let cached = self
+            .cache
+            .get_or_insert_simple(owned_worker_id, || {
+                Box::pin(async move {
+                    let metadata = inner.get(&key).await.map(|mut metadata| {
+                        let stored_running =
+                            metadata.last_known_status.status == WorkerStatus::Running;
+                        if let Some(update) = pending.lock().unwrap().get(&key) {
+                            metadata.last_known_status = update.status.clone();
+                        }
+                        Arc::new(Mutex::new(CachedWorkerMetadata {
+                            metadata,
+                            stored_running,
+                        }))
+                    });
+                    Ok::<_, GolemError>(metadata)
+                })
+            })
+            .await;
+
+        match cached {
+            Ok(Some(cached)) => {
+                let metadata = cached.lock().unwrap().metadata.clone();
+                Some(metadata)
+            }
+            _ => {
+                // Missing workers are not cached, as they may get created by another executor
+                // owning the worker's shard later
+                self.cache.remove(owned_worker_id);
+                None
+            }
+        }
+    }
+
+    async fn get_with_consistency(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        consistency: WorkerStatusReadConsistency,
+    ) -> Option<WorkerMetadata> {
+        match consistency {
+            WorkerStatusReadConsistency::Cached => self.get(owned_worker_id).await,
+            WorkerStatusReadConsistency::Strong => {
+                {
+                    let _guard = self.store_locks.lock(owned_worker_id).await;
+                    Self::store_pending_update(
+                        &self.inner,
+                        &self.cache,
+                        &self.pending,
+                        owned_worker_id,
+                    )
+                    .await;
+                }
+                self.inner.get(owned_worker_id).await
+            }
+        }
+    }
+
+    async fn get_status(&self, worker_id: &WorkerId) -> Option<WorkerStatusRecord> {
+        let pending = self
+            .pending
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(owned_worker_id, _)| owned_worker_id.worker_id == *worker_id)
+            .map(|(_, update)| update.status.clone());
+        match pending {
+            Some(status) => Some(status),
+            None => self.inner.get_status(worker_id).await,
+        }
+    }
+
+    async fn get_running_workers_in_shards(&self) -> Vec<WorkerMetadata> {
+        let mut workers = self.inner.get_running_workers_in_shards().await;
+        let pending = self.pending.lock().unwrap();
+        for worker in &mut workers {
+            let owned_worker_id = OwnedWorkerId::new(&worker.account_id, &worker.worker_id);
+            if let Some(update) = pending.get(&owned_worker_id) {
+                worker.last_known_status = update.status.clone();
+            }
+        }
+        workers
+    }
+
+    async fn remove(&self, owned_worker_id: &OwnedWorkerId) {
+        let _guard = self.store_locks.lock(owned_worker_id).await;
+        self.forget(owned_worker_id);
+        self.inner.remove(owned_worker_id).await;
+    }
+
+    async fn remove_cached_status(&self, owned_worker_id: &OwnedWorkerId) {
+        let _guard = self.store_locks.lock(owned_worker_id).await;
+        self.forget(owned_worker_id);
+        self.inner.remove_cached_status(owned_worker_id).await;
+    }
+
+    async fn update_status(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        status_value: &WorkerStatusRecord,
+        component_type: ComponentType,
+    ) {
+        if component_type == ComponentType::Ephemeral {
+            // The status of ephemeral workers is never stored
+            self.inner
+                .update_status(owned_worker_id, status_value, component_type)
+                .await;
+            return;
+        }
+
+        let stored_running = match self.cache.try_get(owned_worker_id) {
+            Some(Some(cached)) => {
+                let mut cached = cached.lock().unwrap();
+                if let Some((args, env)) = status_value.changed_environment.clone() {
+                    cached.metadata.args = args;
+                    cached.metadata.env = env;
+                }
+                cached.metadata.last_known_status = status_value.clone();
+                cached.stored_running
+            }
+            _ => false,
+        };
+
+        // The updates of workers not owned anymore are not deferred, so they cannot overwrite
+        // the status stored by the new owner later
+        if (status_value.status == WorkerStatus::Running && !stored_running)
+            || !Self::is_owned(&self.shard_service, &owned_worker_id.worker_id)
+        {
+            let _guard = self.store_locks.lock(owned_worker_id).await;
+            self.pending.lock().unwrap().remove(owned_worker_id);
+            self.inner
+                .update_status(owned_worker_id, status_value, component_type)
+                .await;
+            Self::mark_stored(&self.cache, owned_worker_id, status_value);
+            record_worker_status_write("write_through");
+        } else {
+            let previous = self.pending.lock().unwrap().insert(
+                owned_worker_id.clone(),
+                PendingStatusUpdate {
+                    status: status_value.clone(),
+                    component_type,
+                },
+            );
+            record_worker_status_write(if previous.is_some() {
+                "coalesced"
+            } else {
+                "deferred"
+            });
+        }
+    }
+
+    async fn on_shards_revoked(&self) {
+        let shard_service = &self.shard_service;
+        Self::store_pending_updates(
+            &self.inner,
+            &self.cache,
+            &self.pending,
+            &self.store_locks,
+            |owned_worker_id| !Self::is_owned(shard_service, &owned_worker_id.worker_id),
+        )
+        .await;
+
+        let revoked = self
+            .cache
+            .iter()
+            .map(|(owned_worker_id, _)| owned_worker_id)
+            .filter(|owned_worker_id| !Self::is_owned(shard_service, &owned_worker_id.worker_id))
+            .collect::<Vec<_>>();
+        for owned_worker_id in revoked {
+            self.cache.remove(&owned_worker_id);
+        }
+    }
+}
+
+/// Worker service of the throwaway instances of workers, reading the metadata of the workers
+/// through the wrapped service but ignoring all the changes
+pub struct ReadOnlyWorkerService {
+    inner: Arc<dyn WorkerService + Send + Sync>,
+}
+
+impl ReadOnlyWorkerService {
+    pub fn new(inner: Arc<dyn WorkerService + Send + Sync>) -> Self {
+        Self { inner }
+    }
+}
+
+#[async_trait]
+impl WorkerService for ReadOnlyWorkerService {
+    async fn add(
+        &self,
+        _worker_metadata: &WorkerMetadata,
+        _component_type: ComponentType,
+    ) -> Result<(), GolemError> {
+        Ok(())
+    }
+
+    async fn get(&self, owned_worker_id: &OwnedWorkerId) -> Option<WorkerMetadata> {
+        self.inner.get(owned_worker_id).await
+    }
+
+    async fn get_with_consistency(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        consistency: WorkerStatusReadConsistency,
+    ) -> Option<WorkerMetadata> {
+        self.inner
+            .get_with_consistency(owned_worker_id, consistency)
+            .await
+    }
+
+    async fn get_status(&self, worker_id: &WorkerId) -> Option<WorkerStatusRecord> {
+        self.inner.get_status(worker_id).await
+    }
+
+    async fn get_running_workers_in_shards(&self) -> Vec<WorkerMetadata> {
+        self.inner.get_running_workers_in_shards().await
+    }
+
+    async fn remove(&self, _owned_worker_id: &OwnedWorkerId) {}
+
+    async fn remove_cached_status(&self, _owned_worker_id: &OwnedWorkerId) {}
+
+    async fn update_status(
+        &self,
+        _owned_worker_id: &OwnedWorkerId,
+        _status_value: &WorkerStatusRecord,
+        _component_type: ComponentType,
+    ) {
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::collections::HashSet;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use golem_common::model::oplog::OplogIndex;
+    use golem_common::model::regions::{CorruptedOplogRegion, DeletedRegionsBuilder, OplogRegion};
+    use golem_common::model::{
+        AccountId, ComponentId, ComponentType, LastInvokedFunction, OwnedWorkerId, ShardId,
+        Timestamp, WorkerId, WorkerMetadata, WorkerName, WorkerStatus, WorkerStatusRecord,
+    };
+    use uuid::Uuid;
+
+    use crate::services::golem_config::{
+        WorkerStatusCacheEnabledConfig, WorkerStatusReadConsistency,
+    };
+    use crate::services::oplog::PrimaryOplogService;
+    use crate::services::shard::{ShardService, ShardServiceDefault};
+    use crate::services::worker::{
+        CachedWorkerService, DefaultWorkerService, StoreLocks, WorkerService,
+    };
+    use crate::storage::blob::memory::InMemoryBlobStorage;
+    use crate::storage::indexed::memory::InMemoryIndexedStorage;
+    use crate::storage::keyvalue::memory::InMemoryKeyValueStorage;
+    use crate::storage::keyvalue::{KeyValueStorageLabelledApi, KeyValueStorageNamespace};
+
+    async fn cached_worker_service(
+        shard_service: Arc<ShardServiceDefault>,
+    ) -> (Arc<dyn WorkerService + Send + Sync>, CachedWorkerService) {
+        let oplog_service = Arc::new(
+            PrimaryOplogService::new(
+                Arc::new(InMemoryIndexedStorage::new()),
+                Arc::new(InMemoryBlobStorage::new()),
+                1,
+                1024,
+            )
+            .await,
+        );
+        let inner: Arc<dyn WorkerService + Send + Sync> = Arc::new(DefaultWorkerService::new(
+            Arc::new(InMemoryKeyValueStorage::new()),
+            shard_service.clone(),
+            oplog_service,
+        ));
+        let service = CachedWorkerService::new(
+            inner.clone(),
+            shard_service,
+            &WorkerStatusCacheEnabledConfig {
+                flush_interval: Duration::from_secs(3600),
+                ..WorkerStatusCacheEnabledConfig::default()
+            },
+        );
+        (inner, service)
+    }
+
+    /// Adds a worker and defers an update of its status to `Idle`, after storing it as running
+    async fn add_with_deferred_update(
+        service: &CachedWorkerService,
+        inner: &Arc<dyn WorkerService + Send + Sync>,
+    ) -> (OwnedWorkerId, WorkerStatusRecord, WorkerStatusRecord) {
+        let metadata = WorkerMetadata::default(
+            WorkerId {
+                component_id: ComponentId(Uuid::new_v4()),
+                worker_name: WorkerName::from_existing("worker-1"),
+            },
+            AccountId {
+                value: "test-account".to_string(),
+            },
+        );
+        let owned_worker_id = metadata.owned_worker_id();
+        service
+            .add(&metadata, ComponentType::Durable)
+            .await
+            .unwrap();
+
+        let running = WorkerStatusRecord {
+            status: WorkerStatus::Running,
+            oplog_idx: OplogIndex::from_u64(2),
+            ..WorkerStatusRecord::default()
+        };
+        service
+            .update_status(&owned_worker_id, &running, ComponentType::Durable)
+            .await;
+        let stored = inner.get(&owned_worker_id).await.unwrap();
+        assert_eq!(stored.last_known_status, running);
+
+        let idle = WorkerStatusRecord {
+            status: WorkerStatus::Idle,
+            oplog_idx: OplogIndex::from_u64(3),
+            ..WorkerStatusRecord::default()
+        };
+        service
+            .update_status(&owned_worker_id, &idle, ComponentType::Durable)
+            .await;
+        (owned_worker_id, running, idle)
+    }
+
+    #[test]
+    pub async fn status_updates_are_deferred_until_read_strongly() {
+        let shard_service = Arc::new(ShardServiceDefault::new());
+        shard_service.register(1, &HashSet::from_iter(vec![ShardId::new(0)]));
+        let (inner, service) = cached_worker_service(shard_service).await;
+        let (owned_worker_id, running, idle) = add_with_deferred_update(&service, &inner).await;
+
+        let cached = service.get(&owned_worker_id).await.unwrap();
+        let stored = inner.get(&owned_worker_id).await.unwrap();
+        assert_eq!(cached.last_known_status, idle);
+        assert_eq!(stored.last_known_status, running);
+
+        let strong = service
+            .get_with_consistency(&owned_worker_id, WorkerStatusReadConsistency::Strong)
+            .await
+            .unwrap();
+        let stored = inner.get(&owned_worker_id).await.unwrap();
+        assert_eq!(strong.last_known_status, idle);
+        assert_eq!(stored.last_known_status, idle);
+    }
+
+    #[test]
+    pub async fn status_updates_of_revoked_shards_are_not_deferred() {
+        let shard_service = Arc::new(ShardServiceDefault::new());
+        shard_service.register(1, &HashSet::from_iter(vec![ShardId::new(0)]));
+        let (inner, service) = cached_worker_service(shard_service.clone()).await;
+        let (owned_worker_id, running, idle) = add_with_deferred_update(&service, &inner).await;
+        let stored = inner.get(&owned_worker_id).await.unwrap();
+        assert_eq!(stored.last_known_status, running);
+
+        shard_service
+            .revoke_shards(&HashSet::from_iter(vec![ShardId::new(0)]))
+            .unwrap();
+        service.on_shards_revoked().await;
+        let stored = inner.get(&owned_worker_id).await.unwrap();
+        assert_eq!(stored.last_known_status, idle);
+
+        let failed = WorkerStatusRecord {
+            status: WorkerStatus::Failed,
+            oplog_idx: OplogIndex::from_u64(4),
+            ..WorkerStatusRecord::default()
+        };
+        service
+            .update_status(&owned_worker_id, &failed, ComponentType::Durable)
+            .await;
+        let stored = inner.get(&owned_worker_id).await.unwrap();
+        assert_eq!(stored.last_known_status, failed);
+
+        // The new owner's changes are not hidden by the cache
+        inner
+            .update_status(&owned_worker_id, &running, ComponentType::Durable)
+            .await;
+        let read = service.get(&owned_worker_id).await.unwrap();
+        assert_eq!(read.last_known_status, running);
+    }
+
+    #[test]
+    pub async fn pending_status_updates_are_stored_when_dropped() {
+        let shard_service = Arc::new(ShardServiceDefault::new());
+        shard_service.register(1, &HashSet::from_iter(vec![ShardId::new(0)]));
+        let (inner, service) = cached_worker_service(shard_service).await;
+        let (owned_worker_id, _, idle) = add_with_deferred_update(&service, &inner).await;
+
+        drop(service);
+
+        let mut stored = inner.get(&owned_worker_id).await.unwrap();
+        for _ in 0..100 {
+            if stored.last_known_status == idle {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            stored = inner.get(&owned_worker_id).await.unwrap();
+        }
+        assert_eq!(stored.last_known_status, idle);
+    }
+
+    #[test]
+    pub async fn store_locks_are_per_worker() {
+        let locks = StoreLocks::default();
+        let owned_worker_id1 = OwnedWorkerId::new(
+            &AccountId {
+                value: "test-account".to_string(),
+            },
+            &WorkerId {
+                component_id: ComponentId(Uuid::new_v4()),
+                worker_name: WorkerName::from_existing("worker-1"),
+            },
+        );
+        let owned_worker_id2 = OwnedWorkerId::new(
+            &owned_worker_id1.account_id,
+            &WorkerId {
+                component_id: owned_worker_id1.worker_id.component_id.clone(),
+                worker_name: WorkerName::from_existing("worker-2"),
+            },
+        );
+
+        let guard1 = locks.lock(&owned_worker_id1).await;
+        let guard2 = tokio::time::timeout(Duration::from_secs(1), locks.lock(&owned_worker_id2))
+            .await
+            .expect("The lock of another worker must not wait");
+        let blocked =
+            tokio::time::timeout(Duration::from_millis(50), locks.lock(&owned_worker_id1)).await;
+        assert!(blocked.is_err());
+
+        drop(guard1);
+        drop(guard2);
+        assert!(locks.locks.is_empty());
+    }
+
+    #[test]
+    pub async fn corrupted_regions_are_cleared_when_repaired_or_removed() {
+        let shard_service = Arc::new(ShardServiceDefault::new());
+        shard_service.register(1, &HashSet::from_iter(vec![ShardId::new(0)]));
+        let oplog_service = Arc::new(
+            PrimaryOplogService::new(
+                Arc::new(InMemoryIndexedStorage::new()),
+                Arc::new(InMemoryBlobStorage::new()),
+                1,
+                1024,
+            )
+            .await,
+        );
+        let key_value_storage = Arc::new(InMemoryKeyValueStorage::new());
+        let service =
+            DefaultWorkerService::new(key_value_storage.clone(), shard_service, oplog_service);
+
+        let metadata = WorkerMetadata::default(
+            WorkerId {
+                component_id: ComponentId(Uuid::new_v4()),
+                worker_name: WorkerName::from_existing("worker-1"),
+            },
+            AccountId {
+                value: "test-account".to_string(),
+            },
+        );
+        let owned_worker_id = metadata.owned_worker_id();
+        let corrupted_regions_key =
+            DefaultWorkerService::corrupted_regions_key(&owned_worker_id.worker_id);
+        service
+            .add(&metadata, ComponentType::Durable)
+            .await
+            .unwrap();
+
+        let mut deleted_regions = DeletedRegionsBuilder::new();
+        deleted_regions.add_corrupted(CorruptedOplogRegion {
+            region: OplogRegion::from_index_range(
+                OplogIndex::from_u64(2)..=OplogIndex::from_u64(3),
+            ),
+            serialization_version: 1,
+            details: "unknown variant".to_string(),
+        });
+        let corrupted = WorkerStatusRecord {
+            deleted_regions: deleted_regions.build(),
+            oplog_idx: OplogIndex::from_u64(3),
+            ..WorkerStatusRecord::default()
+        };
+        service
+            .update_status(&owned_worker_id, &corrupted, ComponentType::Durable)
+            .await;
+        let stored = service.get(&owned_worker_id).await.unwrap();
+        assert!(stored
+            .last_known_status
+            .deleted_regions
+            .has_corrupted_regions());
+
+        let repaired = WorkerStatusRecord {
+            oplog_idx: OplogIndex::from_u64(4),
+            ..WorkerStatusRecord::default()
+        };
+        service
+            .update_status(&owned_worker_id, &repaired, ComponentType::Durable)
+            .await;
+        let stored = service.get(&owned_worker_id).await.unwrap();
+        assert!(!stored
+            .last_known_status
+            .deleted_regions
+            .has_corrupted_regions());
+        assert!(!key_value_storage
+            .with("worker", "test")
+            .exists(KeyValueStorageNamespace::Worker, &corrupted_regions_key)
+            .await
+            .unwrap());
+
+        service
+            .update_status(&owned_worker_id, &corrupted, ComponentType::Durable)
+            .await;
+        service.remove(&owned_worker_id).await;
+        assert!(!key_value_storage
+            .with("worker", "test")
+            .exists(KeyValueStorageNamespace::Worker, &corrupted_regions_key)
+            .await
+            .unwrap());
+    }
+
+    #[test]
+    pub async fn undecodable_status_records_are_ignored() {
+        let shard_service = Arc::new(ShardServiceDefault::new());
+        shard_service.register(1, &HashSet::from_iter(vec![ShardId::new(0)]));
+        let oplog_service = Arc::new(
+            PrimaryOplogService::new(
+                Arc::new(InMemoryIndexedStorage::new()),
+                Arc::new(InMemoryBlobStorage::new()),
+                1,
+                1024,
+            )
+            .await,
+        );
+        let key_value_storage = Arc::new(InMemoryKeyValueStorage::new());
+        let service =
+            DefaultWorkerService::new(key_value_storage.clone(), shard_service, oplog_service);
+
+        let worker_id = WorkerId {
+            component_id: ComponentId(Uuid::new_v4()),
+            worker_name: WorkerName::from_existing("worker-1"),
+        };
+        let status = WorkerStatusRecord {
+            status: WorkerStatus::Running,
+            last_invoked_function: Some(LastInvokedFunction {
+                function_name: "golem:it/api.{f}".to_string(),
+                timestamp: Timestamp::from(1724701938466),
+            }),
+            oplog_idx: OplogIndex::from_u64(2),
+            ..WorkerStatusRecord::default()
+        };
+        key_value_storage
+            .with("worker", "test")
+            .set(
+                KeyValueStorageNamespace::Worker,
+                &DefaultWorkerService::status_key(&worker_id),
+                &status,
+            )
+            .await
+            .unwrap();
+        assert_eq!(service.get_status(&worker_id).await, Some(status));
+
+        // A truncated record, like the ones stored in an older format, is recomputed instead
+        key_value_storage
+            .with("worker", "test")
+            .set_raw(
+                KeyValueStorageNamespace::Worker,
+                &DefaultWorkerService::status_key(&worker_id),
+                &[2, 1],
+            )
+            .await
+            .unwrap();
+        assert_eq!(service.get_status(&worker_id).await, None);
+    }
+}
@@ -2,13 +2,16 @@ use crate::error::GolemError;
 use crate::services::active_workers::ActiveWorkers;
 use crate::services::golem_config::GolemConfig;
 use crate::services::oplog::OplogService;
+use crate::services::shard::ShardService;
 use crate::services::worker::WorkerService;
 use crate::services::{HasConfig, HasOplogService, HasWorkerService};
 use crate::worker::calculate_last_known_status;
 use crate::workerctx::WorkerCtx;
 use async_trait::async_trait;
+use futures::{stream, Stream, StreamExt, TryStreamExt};
 use golem_common::model::{
-    AccountId, ComponentId, ScanCursor, WorkerFilter, WorkerMetadata, WorkerStatus,
+    AccountId, ComponentId, OwnedWorkerId, ScanCursor, ShardAssignment, ShardId, WorkerFilter,
+    WorkerMetadata, WorkerStatus,
 };
 use std::sync::Arc;
 use tracing::info;
@@ -78,12 +81,31 @@ pub trait WorkerEnumerationService {
         count: u64,
         precise: bool,
     ) -> Result<(Option<ScanCursor>, Vec<WorkerMetadata>), GolemError>;
+
+    /// Enumerates the workers like `get`, but only those belonging to the shards assigned to
+    /// this executor. The metadata of the other workers is not read at all.
+    async fn get_owned(
+        &self,
+        account_id: &AccountId,
+        component_id: &ComponentId,
+        filter: Option<WorkerFilter>,
+        cursor: ScanCursor,
+        count: u64,
+        precise: bool,
+    ) -> Result<(Option<ScanCursor>, Vec<WorkerMetadata>), GolemError>;
 }
 
+/// Enumerates the workers of a component by scanning the oplogs.
+///
+/// The scan runs ahead of the worker metadata reads: up to `max_concurrent_pages` scanned pages
+/// are processed concurrently, and the metadata of the workers of each page is read
+/// concurrently too. The pages are consumed in the order of the scan, so the returned cursor
+/// continues exactly after the last returned page, whichever shards its workers belong to.
 #[derive(Clone)]
 pub struct DefaultWorkerEnumerationService {
     worker_service: Arc<dyn WorkerService + Send + Sync>,
     oplog_service: Arc<dyn OplogService + Send + Sync>,
+    shard_service: Arc<dyn ShardService + Send + Sync>,
     golem_config: Arc<GolemConfig>,
 }
 
@@ -91,62 +113,95 @@ impl DefaultWorkerEnumerationService {
     pub fn new(
         worker_service: Arc<dyn WorkerService + Send + Sync>,
         oplog_service: Arc<dyn OplogService + Send + Sync>,
+        shard_service: Arc<dyn ShardService + Send + Sync>,
         golem_config: Arc<GolemConfig>,
     ) -> Self {
         Self {
             worker_service,
             oplog_service,
+            shard_service,
             golem_config,
         }
     }
 
-    async fn get_internal(
+    async fn get_worker(
         &self,
-        account_id: &AccountId,
-        component_id: &ComponentId,
-        filter: Option<WorkerFilter>,
-        cursor: ScanCursor,
-        count: u64,
+        owned_worker_id: OwnedWorkerId,
+        filter: &Option<WorkerFilter>,
         precise: bool,
-    ) -> Result<(Option<ScanCursor>, Vec<WorkerMetadata>), GolemError> {
-        let mut new_cursor: Option<ScanCursor> = None;
-        let mut workers: Vec<WorkerMetadata> = vec![];
+    ) -> Result<Option<WorkerMetadata>, GolemError> {
+        let worker_metadata = self.worker_service.get(&owned_worker_id).await;
 
-        let (new_scan_cursor, keys) = self
-            .oplog_service
-            .scan_for_component(account_id, component_id, cursor, count)
-            .await?;
-
-        for owned_worker_id in keys {
-            let worker_metadata = self.worker_service.get(&owned_worker_id).await;
-
-            if let Some(worker_metadata) = worker_metadata {
-                let metadata = if precise {
-                    let last_known_status = calculate_last_known_status(
-                        self,
-                        &owned_worker_id,
-                        &Some(worker_metadata.clone()),
-                    )
-                    .await?;
-                    WorkerMetadata {
-                        last_known_status,
-                        ..worker_metadata
-                    }
-                } else {
-                    worker_metadata
-                };
-
-                if filter.clone().map_or(true, |f| f.matches(&metadata)) {
-                    workers.push(metadata);
+        if let Some(worker_metadata) = worker_metadata {
+            let metadata = if precise {
+                let last_known_status = calculate_last_known_status(
+                    self,
+                    &owned_worker_id,
+                    &Some(worker_metadata.clone()),
+                )
+                .await?;
+                WorkerMetadata {
+                    last_known_status,
+                    ..worker_metadata
                 }
+            } else {
+                worker_metadata
+            };
+
+            if filter.clone().map_or(true, |f| f.matches(&metadata)) {
+                return Ok(Some(metadata));
             }
         }
+        Ok(None)
+    }
 
-        if !new_scan_cursor.is_finished() {
-            new_cursor = Some(new_scan_cursor);
-        }
+    /// Reads the metadata of the workers of a scanned page concurrently, keeping the order of
+    /// the scan
+    async fn get_page(
+        &self,
+        keys: Vec<OwnedWorkerId>,
+        filter: &Option<WorkerFilter>,
+        precise: bool,
+    ) -> Result<Vec<WorkerMetadata>, GolemError> {
+        let max_concurrent_reads = self
+            .golem_config
+            .worker_enumeration
+            .max_concurrent_reads
+            .max(1);
 
-        Ok((new_cursor, workers))
+        Ok(stream::iter(keys)
+            .map(|owned_worker_id| self.get_worker(owned_worker_id, filter, precise))
+            .buffered(max_concurrent_reads)
+            .try_collect::<Vec<_>>()
+            .await?
+            .into_iter()
+            .flatten()
+            .collect())
+    }
+
+    /// Scans the keys of the workers of a component page by page, starting from `cursor`.
+    ///
+    /// Each page is returned with the cursor continuing after it, or `None` if it is the last one.
+    fn scan<'a>(
+        &'a self,
+        account_id: &'a AccountId,
+        component_id: &'a ComponentId,
+        cursor: ScanCursor,
+        count: u64,
+    ) -> impl Stream<Item = Result<(Option<ScanCursor>, Vec<OwnedWorkerId>), GolemError>> + 'a {
+        stream::try_unfold(Some(cursor), move |cursor| async move {
+            match cursor {
+                Some(cursor) => {
+                    let (next_cursor, keys) = self
+                        .oplog_service
+                        .scan_for_component(account_id, component_id, cursor, count)
+                        .await?;
+                    let next_cursor = (!next_cursor.is_finished()).then_some(next_cursor);
+                    Ok::<_, GolemError>(Some(((next_cursor.clone(), keys), next_cursor)))
+                }
+                None => Ok(None),
+            }
+        })
     }
 }
 
@@ -189,28 +244,269 @@ impl WorkerEnumerationService for DefaultWorkerEnumerationService {
             count,
             precise
         );
+        self.get_pages(
+            account_id,
+            component_id,
+            filter,
+            cursor,
+            count,
+            precise,
+            false,
+        )
+        .await
+    }
+
+    async fn get_owned(
+        &self,
+        account_id: &AccountId,
+        component_id: &ComponentId,
+        filter: Option<WorkerFilter>,
+        cursor: ScanCursor,
+        count: u64,
+        precise: bool,
+    ) -> Result<(Option<ScanCursor>, Vec<WorkerMetadata>), GolemError> {
+        info!(
+            "Get owned workers - filter: {}, cursor: {}, count: {}, precise: {}",
+            filter
+                .clone()
+                .map(|f| f.to_string())
+                .unwrap_or("N/A".to_string()),
+            cursor,
+            count,
+            precise
+        );
+        self.get_pages(
+            account_id,
+            component_id,
+            filter,
+            cursor,
+            count,
+            precise,
+            true,
+        )
+        .await
+    }
+}
+
+impl DefaultWorkerEnumerationService {
+    /// Reads pages of the scan until `count` workers are found or the scan is finished
+    #[allow(clippy::too_many_arguments)]
+    async fn get_pages(
+        &self,
+        account_id: &AccountId,
+        component_id: &ComponentId,
+        filter: Option<WorkerFilter>,
+        cursor: ScanCursor,
+        count: u64,
+        precise: bool,
+        owned_only: bool,
+    ) -> Result<(Option<ScanCursor>, Vec<WorkerMetadata>), GolemError> {
+        let assignment = if owned_only {
+            Some(self.shard_service.current_assignment()?)
+        } else {
+            None
+        };
+        let max_concurrent_pages = self
+            .golem_config
+            .worker_enumeration
+            .max_concurrent_pages
+            .max(1);
+
+        // The pages scanned ahead are processed concurrently, but yielded in the order of the scan
+        let mut pages = self
+            .scan(account_id, component_id, cursor.clone(), count)
+            .map_ok(|(next_cursor, keys)| {
+                let keys = match &assignment {
+                    Some(assignment) => keys_in_assignment(keys, assignment),
+                    None => keys,
+                };
+                let filter = &filter;
+                async move {
+                    let workers = self.get_page(keys, filter, precise).await?;
+                    Ok::<_, GolemError>((next_cursor, workers))
+                }
+            })
+            .try_buffered(max_concurrent_pages);
+
         let mut new_cursor: Option<ScanCursor> = Some(cursor);
         let mut workers: Vec<WorkerMetadata> = vec![];
 
-        while new_cursor.is_some() && (workers.len() as u64) < count {
-            let new_count = count - (workers.len() as u64);
-
-            let (next_cursor, workers_page) = self
-                .get_internal(
-                    account_id,
-                    component_id,
-                    filter.clone(),
-                    new_cursor.unwrap_or_default(),
-                    new_count,
-                    precise,
-                )
-                .await?;
+        while (workers.len() as u64) < count {
+            match pages.try_next().await? {
+                Some((next_cursor, workers_page)) => {
+                    workers.extend(workers_page);
+                    new_cursor = next_cursor;
+                }
+                None => break,
+            }
+        }
+
+        Ok((new_cursor, workers))
+    }
+}
 
-            workers.extend(workers_page);
+/// Keeps the scanned keys of the workers belonging to the shards of the assignment
+fn keys_in_assignment(
+    keys: Vec<OwnedWorkerId>,
+    assignment: &ShardAssignment,
+) -> Vec<OwnedWorkerId> {
+    keys.into_iter()
+        .filter(|owned_worker_id| {
+            assignment.shard_ids.contains(&ShardId::from_worker_id(
+                &owned_worker_id.worker_id,
+                assignment.number_of_shards,
+            ))
+        })
+        .collect()
+}
 
-            new_cursor = next_cursor;
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use super::{keys_in_assignment, DefaultWorkerEnumerationService, WorkerEnumerationService};
+    use crate::services::golem_config::GolemConfig;
+    use crate::services::oplog::PrimaryOplogService;
+    use crate::services::shard::{ShardService, ShardServiceDefault};
+    use crate::services::worker::{DefaultWorkerService, WorkerService};
+    use crate::storage::blob::memory::InMemoryBlobStorage;
+    use crate::storage::indexed::memory::InMemoryIndexedStorage;
+    use crate::storage::keyvalue::memory::InMemoryKeyValueStorage;
+    use golem_common::model::{
+        AccountId, ComponentId, ComponentType, OwnedWorkerId, ScanCursor, ShardAssignment, ShardId,
+        WorkerId, WorkerMetadata, WorkerName,
+    };
+    use std::collections::HashSet;
+    use std::sync::Arc;
+
+    fn owned_worker_id(component_id: &ComponentId, name: &str) -> OwnedWorkerId {
+        OwnedWorkerId {
+            account_id: AccountId {
+                value: "test-account".to_string(),
+            },
+            worker_id: WorkerId {
+                component_id: component_id.clone(),
+                worker_name: WorkerName::from_existing(name),
+            },
         }
+    }
 
-        Ok((new_cursor, workers))
+    #[test]
+    fn only_keys_of_assigned_shards_are_kept() {
+        let component_id = ComponentId::new_v4();
+        let keys = (0..100)
+            .map(|i| owned_worker_id(&component_id, &format!("worker-{i}")))
+            .collect::<Vec<_>>();
+        let assignment = ShardAssignment::new(4, HashSet::from([ShardId::new(1), ShardId::new(3)]));
+
+        let owned = keys_in_assignment(keys.clone(), &assignment);
+
+        let expected = keys
+            .into_iter()
+            .filter(|key| {
+                let shard_id = ShardId::from_worker_id(&key.worker_id, 4);
+                shard_id == ShardId::new(1) || shard_id == ShardId::new(3)
+            })
+            .collect::<Vec<_>>();
+        assert!(!owned.is_empty());
+        assert_eq!(owned, expected);
+    }
+
+    /// Enumerates all the pages of the workers of a component
+    async fn enumerate_all(
+        service: &DefaultWorkerEnumerationService,
+        account_id: &AccountId,
+        component_id: &ComponentId,
+        count: u64,
+        owned_only: bool,
+    ) -> Vec<WorkerId> {
+        let mut result = Vec::new();
+        let mut cursor = Some(ScanCursor::default());
+        while let Some(current_cursor) = cursor {
+            let (next_cursor, workers) = if owned_only {
+                service
+                    .get_owned(account_id, component_id, None, current_cursor, count, false)
+                    .await
+                    .unwrap()
+            } else {
+                service
+                    .get(account_id, component_id, None, current_cursor, count, false)
+                    .await
+                    .unwrap()
+            };
+            result.extend(workers.into_iter().map(|worker| worker.worker_id));
+            cursor = next_cursor;
+        }
+        result
+    }
+
+    #[test]
+    async fn enumerates_workers_across_shards() {
+        let shard_service = Arc::new(ShardServiceDefault::new());
+        shard_service.register(4, &HashSet::from([ShardId::new(1), ShardId::new(3)]));
+        let oplog_service = Arc::new(
+            PrimaryOplogService::new(
+                Arc::new(InMemoryIndexedStorage::new()),
+                Arc::new(InMemoryBlobStorage::new()),
+                1,
+                1024,
+            )
+            .await,
+        );
+        let worker_service = Arc::new(DefaultWorkerService::new(
+            Arc::new(InMemoryKeyValueStorage::new()),
+            shard_service.clone(),
+            oplog_service.clone(),
+        ));
+        let service = DefaultWorkerEnumerationService::new(
+            worker_service.clone(),
+            oplog_service,
+            shard_service,
+            Arc::new(GolemConfig::default()),
+        );
+
+        let account_id = AccountId {
+            value: "test-account".to_string(),
+        };
+        let component_id = ComponentId::new_v4();
+        let mut worker_ids = Vec::new();
+        for i in 0..50 {
+            let worker_id = WorkerId {
+                component_id: component_id.clone(),
+                worker_name: WorkerName::from_existing(format!("worker-{i}")),
+            };
+            worker_service
+                .add(
+                    &WorkerMetadata::default(worker_id.clone(), account_id.clone()),
+                    ComponentType::Durable,
+                )
+                .await
+                .unwrap();
+            worker_ids.push(worker_id);
+        }
+        let shards = worker_ids
+            .iter()
+            .map(|worker_id| ShardId::from_worker_id(worker_id, 4))
+            .collect::<HashSet<_>>();
+        assert_eq!(shards.len(), 4);
+
+        // Every worker is enumerated exactly once, whichever shard and page it is in
+        let mut all = enumerate_all(&service, &account_id, &component_id, 7, false).await;
+        all.sort_by_key(|worker_id| worker_id.worker_name.clone());
+        let mut expected = worker_ids.clone();
+        expected.sort_by_key(|worker_id| worker_id.worker_name.clone());
+        assert_eq!(all, expected);
+
+        let mut owned = enumerate_all(&service, &account_id, &component_id, 7, true).await;
+        owned.sort_by_key(|worker_id| worker_id.worker_name.clone());
+        let expected_owned = expected
+            .into_iter()
+            .filter(|worker_id| {
+                let shard_id = ShardId::from_worker_id(worker_id, 4);
+                shard_id == ShardId::new(1) || shard_id == ShardId::new(3)
+            })
+            .collect::<Vec<_>>();
+        assert!(!expected_owned.is_empty());
+        assert_eq!(owned, expected_owned);
     }
 }
@@ -13,8 +13,11 @@
 // limitations under the License.
 
 use crate::metrics::events::{record_broadcast_event, record_event};
-use futures_util::{stream, StreamExt};
-use golem_common::model::{IdempotencyKey, LogLevel, WorkerEvent};
+use crate::services::log_export::LogExportService;
+use crate::services::oplog::OplogService;
+use futures_util::{future, stream, StreamExt};
+use golem_common::model::oplog::OplogIndex;
+use golem_common::model::{IdempotencyKey, LogLevel, OwnedWorkerId, Timestamp, WorkerEvent};
 use ringbuf::storage::Heap;
 use ringbuf::traits::{Consumer, Producer, Split};
 use ringbuf::*;
@@ -77,6 +80,10 @@ pub trait WorkerEventService {
             is_live,
         )
     }
+
+    fn emit_result_chunk(&self, idempotency_key: &IdempotencyKey, bytes: Vec<u8>, is_live: bool) {
+        self.emit_event(WorkerEvent::result_chunk(idempotency_key, bytes), is_live)
+    }
 }
 
 #[derive(Clone)]
@@ -88,22 +95,65 @@ struct WorkerEventEntry {
 pub struct WorkerEventReceiver {
     history: Vec<WorkerEventEntry>,
     receiver: Receiver<WorkerEvent>,
+    backfilled: Vec<WorkerEvent>,
 }
 
 impl WorkerEventReceiver {
+    /// Starts the receiver with the given events reconstructed from the oplog, followed by the
+    /// events of the in-memory history which are not older than the backfilled ones.
+    ///
+    /// The oplog is read after subscribing, so an event emitted in between can be both backfilled
+    /// and received live. Such live events are skipped.
+    pub fn with_backfill(self, backfilled: Vec<WorkerEvent>) -> Self {
+        let last_backfilled = backfilled.last().and_then(event_timestamp);
+        let newer_history = self.history.into_iter().filter(|entry| {
+            entry.is_live
+                && !backfilled.contains(&entry.event)
+                && match (event_timestamp(&entry.event), last_backfilled) {
+                    (Some(timestamp), Some(last_backfilled)) => timestamp >= last_backfilled,
+                    _ => true,
+                }
+        });
+
+        let history = backfilled
+            .iter()
+            .cloned()
+            .map(|event| WorkerEventEntry {
+                event,
+                is_live: true,
+            })
+            .chain(newer_history)
+            .collect();
+
+        Self {
+            history,
+            receiver: self.receiver,
+            backfilled,
+        }
+    }
+
     pub async fn recv(&mut self) -> Result<WorkerEvent, RecvError> {
         loop {
             let popped = self.history.pop();
             match popped {
                 Some(entry) if entry.is_live => break Ok(entry.event),
                 Some(_) => continue,
-                None => break self.receiver.recv().await,
+                None => {
+                    let event = self.receiver.recv().await?;
+                    if !take_backfilled(&mut self.backfilled, &event) {
+                        break Ok(event);
+                    }
+                }
             }
         }
     }
 
     pub fn to_stream(self) -> impl Stream<Item = Result<WorkerEvent, BroadcastStreamRecvError>> {
-        let Self { history, receiver } = self;
+        let Self {
+            history,
+            receiver,
+            mut backfilled,
+        } = self;
         stream::iter(history.into_iter().filter_map(
             |WorkerEventEntry { event, is_live }| {
                 if is_live {
@@ -113,14 +163,96 @@ impl WorkerEventReceiver {
                 }
             },
         ))
-        .chain(BroadcastStream::new(receiver))
+        .chain(BroadcastStream::new(receiver).filter(move |event| {
+            let duplicate = match event {
+                Ok(event) => take_backfilled(&mut backfilled, event),
+                Err(_) => false,
+            };
+            future::ready(!duplicate)
+        }))
+    }
+}
+
+/// Removes the event from the backfilled events if it is one of them
+fn take_backfilled(backfilled: &mut Vec<WorkerEvent>, event: &WorkerEvent) -> bool {
+    match backfilled.iter().position(|backfilled| backfilled == event) {
+        Some(idx) => {
+            backfilled.remove(idx);
+            true
+        }
+        None => false,
     }
 }
 
+fn event_timestamp(event: &WorkerEvent) -> Option<Timestamp> {
+    match event {
+        WorkerEvent::StdOut { timestamp, .. }
+        | WorkerEvent::StdErr { timestamp, .. }
+        | WorkerEvent::Log { timestamp, .. }
+        | WorkerEvent::InvocationStart { timestamp, .. }
+        | WorkerEvent::InvocationFinished { timestamp, .. }
+        | WorkerEvent::ResultChunk { timestamp, .. } => Some(*timestamp),
+        WorkerEvent::Close => None,
+    }
+}
+
+/// Reconstructs the last `count` stdout, stderr and log events of a worker from its oplog, in the
+/// order they were emitted. Only the entries already committed to the oplog are considered, and at
+/// most the last `max_scanned_entries` of them are read, so fewer events are returned if the
+/// worker did not emit enough of them recently.
+pub async fn read_log_events_from_oplog(
+    oplog_service: &(dyn OplogService + Send + Sync),
+    owned_worker_id: &OwnedWorkerId,
+    count: usize,
+    max_scanned_entries: u64,
+) -> Vec<WorkerEvent> {
+    const CHUNK_SIZE: u64 = 100;
+
+    let mut events = Vec::new();
+    let mut last_idx = oplog_service.get_last_index(owned_worker_id).await;
+    let mut scanned_entries = 0;
+
+    while events.len() < count
+        && scanned_entries < max_scanned_entries
+        && last_idx >= OplogIndex::INITIAL
+    {
+        let chunk_size = CHUNK_SIZE.min(max_scanned_entries - scanned_entries);
+        let first_idx = OplogIndex::from_u64(
+            Into::<u64>::into(last_idx)
+                .saturating_sub(chunk_size - 1)
+                .max(OplogIndex::INITIAL.into()),
+        );
+        scanned_entries += Into::<u64>::into(last_idx) - Into::<u64>::into(first_idx) + 1;
+
+        let chunk = oplog_service
+            .read_with_logs(
+                owned_worker_id,
+                first_idx,
+                Into::<u64>::into(last_idx) - Into::<u64>::into(first_idx) + 1,
+            )
+            .await;
+
+        let remaining = count - events.len();
+        events.extend(
+            chunk
+                .values()
+                .rev()
+                .filter_map(WorkerEvent::from_oplog_entry)
+                .take(remaining),
+        );
+
+        last_idx = first_idx.previous();
+    }
+
+    events.reverse();
+    events
+}
+
 pub struct WorkerEventServiceDefault {
     sender: Sender<WorkerEvent>,
     ring_prod: Arc<Mutex<<SharedRb<Heap<WorkerEventEntry>> as Split>::Prod>>,
     ring_cons: Arc<Mutex<<SharedRb<Heap<WorkerEventEntry>> as Split>::Cons>>,
+    log_export: Option<(OwnedWorkerId, Arc<dyn LogExportService + Send + Sync>)>,
 }
 
 impl WorkerEventServiceDefault {
@@ -131,8 +263,20 @@ impl WorkerEventServiceDefault {
             sender: tx,
             ring_prod: Arc::new(Mutex::new(ring_prod)),
             ring_cons: Arc::new(Mutex::new(ring_cons)),
+            log_export: None,
         }
     }
+
+    /// Forwards the live stdout, stderr and log events of the worker to the log export service.
+    /// Events emitted while replaying the oplog are not exported again.
+    pub fn with_log_export(
+        mut self,
+        owned_worker_id: OwnedWorkerId,
+        log_export_service: Arc<dyn LogExportService + Send + Sync>,
+    ) -> Self {
+        self.log_export = Some((owned_worker_id, log_export_service));
+        self
+    }
 }
 
 impl Drop for WorkerEventServiceDefault {
@@ -146,6 +290,10 @@ impl WorkerEventService for WorkerEventServiceDefault {
         if is_live {
             record_event(label(&event));
 
+            if let Some((owned_worker_id, log_export_service)) = &self.log_export {
+                log_export_service.export(owned_worker_id, &event);
+            }
+
             if self.sender.receiver_count() > 0 {
                 record_broadcast_event(label(&event));
 
@@ -153,6 +301,12 @@ impl WorkerEventService for WorkerEventServiceDefault {
             }
         }
 
+        // Result chunks are only forwarded to the live subscribers, keeping them out of the
+        // history of the worker's output
+        if matches!(event, WorkerEvent::ResultChunk { .. }) {
+            return;
+        }
+
         let entry = WorkerEventEntry { event, is_live };
         let mut ring_prod = self.ring_prod.lock().unwrap();
         while ring_prod.try_push(entry.clone()).is_err() {
@@ -165,7 +319,11 @@ impl WorkerEventService for WorkerEventServiceDefault {
         let receiver = self.sender.subscribe();
         let ring_cons = self.ring_cons.lock().unwrap();
         let history = ring_cons.iter().cloned().collect();
-        WorkerEventReceiver { history, receiver }
+        WorkerEventReceiver {
+            history,
+            receiver,
+            backfilled: Vec::new(),
+        }
     }
 
     fn get_last_invocation_errors(&self) -> String {
@@ -193,6 +351,7 @@ fn label(event: &WorkerEvent) -> &'static str {
         WorkerEvent::Log { .. } => "log",
         WorkerEvent::InvocationStart { .. } => "invocation_start",
         WorkerEvent::InvocationFinished { .. } => "invocation_finished",
+        WorkerEvent::ResultChunk { .. } => "result_chunk",
         WorkerEvent::Close => "close",
     }
 }
@@ -201,13 +360,22 @@ fn label(event: &WorkerEvent) -> &'static str {
 mod tests {
     use test_r::{non_flaky, test};
 
+    use futures_util::{future, StreamExt};
     use std::sync::Arc;
     use tokio::sync::broadcast::error::RecvError;
     use tokio::sync::Mutex;
 
+    use crate::services::oplog::{CommitLevel, OplogService, PrimaryOplogService};
     use crate::services::worker_event::{
-        WorkerEvent, WorkerEventService, WorkerEventServiceDefault,
+        read_log_events_from_oplog, WorkerEvent, WorkerEventService, WorkerEventServiceDefault,
     };
+    use crate::storage::blob::memory::InMemoryBlobStorage;
+    use crate::storage::indexed::memory::InMemoryIndexedStorage;
+    use golem_common::model::oplog::{LogLevel, OplogEntry, OplogIndex};
+    use golem_common::model::{
+        AccountId, ComponentId, ComponentType, OwnedWorkerId, WorkerId, WorkerName,
+    };
+    use uuid::Uuid;
 
     #[test]
     #[non_flaky(10)]
@@ -290,6 +458,84 @@ mod tests {
         )
     }
 
+    #[test]
+    pub async fn backfill_scans_a_limited_number_of_oplog_entries() {
+        let oplog_service = PrimaryOplogService::new(
+            Arc::new(InMemoryIndexedStorage::new()),
+            Arc::new(InMemoryBlobStorage::new()),
+            1,
+            1024,
+        )
+        .await;
+        let owned_worker_id = OwnedWorkerId::new(
+            &AccountId {
+                value: "test-account".to_string(),
+            },
+            &WorkerId {
+                component_id: ComponentId(Uuid::new_v4()),
+                worker_name: WorkerName::from_existing("worker-1"),
+            },
+        );
+        let oplog = oplog_service
+            .open(&owned_worker_id, OplogIndex::NONE, ComponentType::Durable)
+            .await;
+        for i in 0..5 {
+            oplog
+                .add(OplogEntry::log(
+                    LogLevel::Info,
+                    "test".to_string(),
+                    format!("message {i}"),
+                ))
+                .await;
+        }
+        for _ in 0..150 {
+            oplog.add(OplogEntry::suspend()).await;
+        }
+        oplog.commit(CommitLevel::Always).await;
+
+        let within_limit =
+            read_log_events_from_oplog(&oplog_service, &owned_worker_id, 10, 155).await;
+        let beyond_limit =
+            read_log_events_from_oplog(&oplog_service, &owned_worker_id, 10, 152).await;
+
+        assert_eq!(
+            within_limit
+                .iter()
+                .map(|event| match event {
+                    WorkerEvent::Log { message, .. } => message.clone(),
+                    other => panic!("unexpected event {other:?}"),
+                })
+                .collect::<Vec<_>>(),
+            (0..5).map(|i| format!("message {i}")).collect::<Vec<_>>()
+        );
+        assert_eq!(beyond_limit.len(), 2);
+    }
+
+    #[test]
+    pub async fn backfilled_events_are_not_repeated() {
+        let svc = WorkerEventServiceDefault::new(4, 16);
+        let events: Vec<WorkerEvent> = (1..5u8).map(|b| WorkerEvent::stdout(vec![b])).collect();
+
+        svc.emit_event(events[0].clone(), true);
+        svc.emit_event(events[1].clone(), true);
+
+        // The third event is emitted live after subscribing, but it was already read from the oplog
+        let receiver = svc.receiver().with_backfill(events[0..3].to_vec());
+        svc.emit_event(events[2].clone(), true);
+        svc.emit_event(events[3].clone(), true);
+
+        drop(svc);
+
+        let received: Vec<WorkerEvent> = receiver
+            .to_stream()
+            .map(|event| event.unwrap())
+            .take_while(|event| future::ready(*event != WorkerEvent::Close))
+            .collect()
+            .await;
+
+        assert_eq!(received, events);
+    }
+
     #[test]
     #[non_flaky(10)]
     pub async fn both_subscriber_gets_events_large() {
@@ -24,11 +24,13 @@ use golem_api_grpc::proto::golem::worker::v1::{
 };
 use golem_api_grpc::proto::golem::worker::{InvocationContext, InvokeParameters, UpdateMode};
 use golem_common::client::GrpcClient;
-use golem_common::model::{ComponentVersion, IdempotencyKey, OwnedWorkerId, WorkerId};
+use golem_common::model::{
+    ComponentVersion, IdempotencyKey, InvocationPriority, OwnedWorkerId, WorkerId,
+};
 use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
 use golem_wasm_rpc::{Value, WitValue};
 use http::Uri;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::error::Error;
 use std::fmt::{Display, Formatter};
 use tonic::codec::CompressionEncoding;
@@ -47,17 +49,21 @@ pub trait WorkerProxy {
         caller_worker_id: WorkerId,
         caller_args: Vec<String>,
         caller_env: HashMap<String, String>,
+        caller_context: BTreeMap<String, String>,
     ) -> Result<TypeAnnotatedValue, WorkerProxyError>;
 
+    /// Enqueues the invocation without awaiting its result. The caller is not set for invocations
+    /// which do not originate from a worker.
     async fn invoke(
         &self,
         owned_worker_id: &OwnedWorkerId,
         idempotency_key: Option<IdempotencyKey>,
         function_name: String,
         function_params: Vec<WitValue>,
-        caller_worker_id: WorkerId,
+        caller_worker_id: Option<WorkerId>,
         caller_args: Vec<String>,
         caller_env: HashMap<String, String>,
+        caller_context: BTreeMap<String, String>,
     ) -> Result<(), WorkerProxyError>;
 
     async fn update(
@@ -76,6 +82,7 @@ pub enum WorkerProxyError {
     NotFound(String),
     AlreadyExists(String),
     InternalError(GolemError),
+    Forbidden(String),
 }
 
 impl Error for WorkerProxyError {}
@@ -89,6 +96,7 @@ impl Display for WorkerProxyError {
             WorkerProxyError::NotFound(error) => write!(f, "Not found: {error}"),
             WorkerProxyError::AlreadyExists(error) => write!(f, "Already exists: {error}"),
             WorkerProxyError::InternalError(error) => write!(f, "Internal error: {error}"),
+            WorkerProxyError::Forbidden(error) => write!(f, "Forbidden: {error}"),
         }
     }
 }
@@ -117,6 +125,7 @@ impl From<WorkerError> for WorkerProxyError {
             Some(worker_error::Error::Unauthorized(body)) => {
                 WorkerProxyError::Unauthorized(body.error)
             }
+            Some(worker_error::Error::Forbidden(body)) => WorkerProxyError::Forbidden(body.error),
             Some(worker_error::Error::LimitExceeded(body)) => {
                 WorkerProxyError::LimitExceeded(body.error)
             }
@@ -175,6 +184,7 @@ impl WorkerProxy for RemoteWorkerProxy {
         caller_worker_id: WorkerId,
         caller_args: Vec<String>,
         caller_env: HashMap<String, String>,
+        caller_context: BTreeMap<String, String>,
     ) -> Result<TypeAnnotatedValue, WorkerProxyError> {
         debug!(
             "Invoking remote worker function {function_name} with parameters {function_params:?}"
@@ -204,7 +214,10 @@ impl WorkerProxy for RemoteWorkerProxy {
                             parent: Some(caller_worker_id.clone().into()),
                             args: caller_args.clone(),
                             env: caller_env.clone(),
+                            values: HashMap::from_iter(caller_context.clone()),
                         }),
+                        priority: InvocationPriority::Normal.into(),
+                        timeout_millis: None,
                     },
                     &self.access_token,
                 )))
@@ -240,9 +253,10 @@ impl WorkerProxy for RemoteWorkerProxy {
         idempotency_key: Option<IdempotencyKey>,
         function_name: String,
         function_params: Vec<WitValue>,
-        caller_worker_id: WorkerId,
+        caller_worker_id: Option<WorkerId>,
         caller_args: Vec<String>,
         caller_env: HashMap<String, String>,
+        caller_context: BTreeMap<String, String>,
     ) -> Result<(), WorkerProxyError> {
         debug!("Invoking remote worker function {function_name} with parameters {function_params:?} without awaiting for the result");
 
@@ -267,10 +281,12 @@ impl WorkerProxy for RemoteWorkerProxy {
                         function: function_name.clone(),
                         invoke_parameters: invoke_parameters.clone(),
                         context: Some(InvocationContext {
-                            parent: Some(caller_worker_id.clone().into()),
+                            parent: caller_worker_id.clone().map(|id| id.into()),
                             args: caller_args.clone(),
                             env: caller_env.clone(),
+                            values: HashMap::from_iter(caller_context.clone()),
                         }),
+                        priority: InvocationPriority::Normal.into(),
                     },
                     &self.access_token,
                 )))
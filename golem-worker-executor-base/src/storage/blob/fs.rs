@@ -64,6 +64,10 @@ impl FileSystemBlobStorage {
                 result.push("custom_data");
                 result.push(account_id.to_string());
             }
+            BlobStorageNamespace::WorkerBlobs(account_id) => {
+                result.push("worker_blobs");
+                result.push(account_id.to_string());
+            }
             BlobStorageNamespace::OplogPayload {
                 account_id,
                 worker_id,
@@ -328,6 +328,7 @@ impl<'a, S: BlobStorage + ?Sized + Sync> LabelledBlobStorage<'a, S> {
 pub enum BlobStorageNamespace {
     CompilationCache,
     CustomStorage(AccountId),
+    WorkerBlobs(AccountId),
     OplogPayload {
         account_id: AccountId,
         worker_id: WorkerId,
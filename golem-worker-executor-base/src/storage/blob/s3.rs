@@ -67,6 +67,7 @@ impl S3BlobStorage {
         match namespace {
             BlobStorageNamespace::CompilationCache => &self.config.compilation_cache_bucket,
             BlobStorageNamespace::CustomStorage(_account_id) => &self.config.custom_data_bucket,
+            BlobStorageNamespace::WorkerBlobs(_account_id) => &self.config.custom_data_bucket,
             BlobStorageNamespace::OplogPayload { .. } => &self.config.oplog_payload_bucket,
             BlobStorageNamespace::CompressedOplog { level, .. } => {
                 &self.config.compressed_oplog_buckets[*level]
@@ -89,6 +90,16 @@ impl S3BlobStorage {
                         .to_path_buf()
                 }
             }
+            BlobStorageNamespace::WorkerBlobs(account_id) => {
+                // Stored next to the custom storage containers of the account, so a distinct
+                // prefix is needed
+                let blobs_path = Path::new("worker_blobs").join(account_id.to_string());
+                if self.config.object_prefix.is_empty() {
+                    blobs_path
+                } else {
+                    Path::new(&self.config.object_prefix).join(blobs_path)
+                }
+            }
             BlobStorageNamespace::OplogPayload {
                 account_id,
                 worker_id,
@@ -37,6 +37,9 @@ impl SqliteBlobStorage {
             BlobStorageNamespace::CustomStorage(account_id) => {
                 format!("custom_data-{}", account_id.value)
             }
+            BlobStorageNamespace::WorkerBlobs(account_id) => {
+                format!("worker_blobs-{}", account_id.value)
+            }
             BlobStorageNamespace::OplogPayload {
                 account_id,
                 worker_id,
@@ -135,6 +135,31 @@ impl IndexedStorage for InMemoryIndexedStorage {
         }
     }
 
+    async fn append_many(
+        &self,
+        _svc_name: &'static str,
+        _api_name: &'static str,
+        _entity_name: &'static str,
+        key: &str,
+        entries: &[(IndexedStorageNamespace, u64, Vec<u8>)],
+    ) -> Result<(), String> {
+        let exists = entries.iter().any(|(namespace, id, _)| {
+            self.data
+                .get(&Self::composite_key(namespace.clone(), key))
+                .is_some_and(|entry| entry.contains_key(id))
+        });
+        if exists {
+            return Err("Key already exists".to_string());
+        }
+        for (namespace, id, value) in entries {
+            self.data
+                .entry(Self::composite_key(namespace.clone(), key))
+                .or_default()
+                .insert(*id, value.clone());
+        }
+        Ok(())
+    }
+
     async fn length(
         &self,
         _svc_name: &'static str,
@@ -86,6 +86,17 @@ pub trait IndexedStorage: Debug {
         value: &[u8],
     ) -> Result<(), String>;
 
+    /// Appends entries to the indexes of the given key in multiple namespaces atomically, so
+    /// either all or none of them are stored
+    async fn append_many(
+        &self,
+        svc_name: &'static str,
+        api_name: &'static str,
+        entity_name: &'static str,
+        key: &str,
+        entries: &[(IndexedStorageNamespace, u64, Vec<u8>)],
+    ) -> Result<(), String>;
+
     /// Gets the number of entries in the index of the given key
     async fn length(
         &self,
@@ -343,6 +354,17 @@ impl<'a, S: ?Sized + IndexedStorage> LabelledEntityIndexedStorage<'a, S> {
             .await
     }
 
+    /// Appends entries to the indexes of the given key in multiple namespaces atomically
+    pub async fn append_many_raw(
+        &self,
+        key: &str,
+        entries: &[(IndexedStorageNamespace, u64, Vec<u8>)],
+    ) -> Result<(), String> {
+        self.storage
+            .append_many(self.svc_name, self.api_name, self.entity_name, key, entries)
+            .await
+    }
+
     /// Reads a closed range of entries from the index of the given key, deserializing each entry
     pub async fn read<V: Decode>(
         &self,
@@ -553,4 +575,6 @@ impl<'a, S: ?Sized + IndexedStorage> LabelledEntityIndexedStorage<'a, S> {
 pub enum IndexedStorageNamespace {
     OpLog,
     CompressedOpLog { level: usize },
+    CompressedLogs,
+    OplogMarkers,
 }
@@ -37,6 +37,8 @@ impl RedisIndexedStorage {
             IndexedStorageNamespace::CompressedOpLog { level } => {
                 format!("worker:c{level}-oplog:{key}")
             }
+            IndexedStorageNamespace::CompressedLogs => format!("worker:logs:{key}"),
+            IndexedStorageNamespace::OplogMarkers => format!("worker:oplog-markers:{key}"),
         }
     }
 
@@ -182,6 +184,42 @@ impl IndexedStorage for RedisIndexedStorage {
         Ok(())
     }
 
+    async fn append_many(
+        &self,
+        svc_name: &'static str,
+        api_name: &'static str,
+        entity_name: &'static str,
+        key: &str,
+        entries: &[(IndexedStorageNamespace, u64, Vec<u8>)],
+    ) -> Result<(), String> {
+        for (_, _, value) in entries {
+            record_redis_serialized_size(svc_name, entity_name, value.len());
+        }
+
+        let _: Vec<String> = self
+            .redis
+            .with(svc_name, api_name)
+            .transaction(|trx| async move {
+                for (namespace, id, value) in entries {
+                    trx.xadd(
+                        Self::composite_key(namespace.clone(), key),
+                        false,
+                        None,
+                        id.to_string(),
+                        (
+                            RedisKey::from(Self::KEY),
+                            RedisValue::Bytes(Bytes::copy_from_slice(value)),
+                        ),
+                    )
+                    .await?;
+                }
+                Ok(trx)
+            })
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
     async fn length(
         &self,
         svc_name: &'static str,
@@ -37,6 +37,8 @@ impl SqliteIndexedStorage {
             IndexedStorageNamespace::CompressedOpLog { level } => {
                 format!("worker-c{level}-oplog")
             }
+            IndexedStorageNamespace::CompressedLogs => "worker-logs".to_string(),
+            IndexedStorageNamespace::OplogMarkers => "worker-oplog-markers".to_string(),
         }
     }
 }
@@ -108,6 +110,25 @@ impl IndexedStorage for SqliteIndexedStorage {
             .await
     }
 
+    async fn append_many(
+        &self,
+        svc_name: &'static str,
+        api_name: &'static str,
+        _entity_name: &'static str,
+        key: &str,
+        entries: &[(IndexedStorageNamespace, u64, Vec<u8>)],
+    ) -> Result<(), String> {
+        let entries = entries
+            .iter()
+            .map(|(namespace, id, value)| (Self::to_string(namespace), *id, value.as_slice()))
+            .collect::<Vec<_>>();
+        self.pool
+            .with(svc_name, api_name)
+            .append_many(key, &entries)
+            .map_err(|e| e.to_string())
+            .await
+    }
+
     async fn length(
         &self,
         svc_name: &'static str,
@@ -106,6 +106,45 @@ impl KeyValueStorage for InMemoryKeyValueStorage {
         }
     }
 
+    async fn compare_and_swap(
+        &self,
+        _svc_name: &'static str,
+        _api_name: &'static str,
+        _entity_name: &'static str,
+        namespace: KeyValueStorageNamespace,
+        key: &str,
+        expected: Option<&[u8]>,
+        value: Option<&[u8]>,
+    ) -> Result<bool, String> {
+        match self.kvs.entry(Self::composite_key(&namespace, key)) {
+            Entry::Occupied(mut entry) => {
+                if expected != Some(entry.get().as_slice()) {
+                    Ok(false)
+                } else {
+                    match value {
+                        Some(value) => {
+                            entry.insert(value.to_vec());
+                        }
+                        None => {
+                            entry.remove();
+                        }
+                    }
+                    Ok(true)
+                }
+            }
+            Entry::Vacant(entry) => {
+                if expected.is_some() {
+                    Ok(false)
+                } else {
+                    if let Some(value) = value {
+                        entry.insert(value.to_vec());
+                    }
+                    Ok(true)
+                }
+            }
+        }
+    }
+
     async fn get(
         &self,
         _svc_name: &'static str,
@@ -19,7 +19,7 @@ pub mod sqlite;
 use async_trait::async_trait;
 use bincode::{Decode, Encode};
 use bytes::Bytes;
-use golem_common::model::AccountId;
+use golem_common::model::{AccountId, ComponentId, WorkerId};
 use golem_common::serialization::{deserialize, serialize};
 use std::fmt::Debug;
 
@@ -54,6 +54,20 @@ pub trait KeyValueStorage: Debug {
         value: &[u8],
     ) -> Result<bool, String>;
 
+    /// Atomically replaces the value of `key` if its current value is `expected`, where `None`
+    /// means that the key does not exist. A `None` value deletes the key. Returns whether the
+    /// value was replaced.
+    async fn compare_and_swap(
+        &self,
+        svc_name: &'static str,
+        api_name: &'static str,
+        entity_name: &'static str,
+        namespace: KeyValueStorageNamespace,
+        key: &str,
+        expected: Option<&[u8]>,
+        value: Option<&[u8]>,
+    ) -> Result<bool, String>;
+
     async fn get(
         &self,
         svc_name: &'static str,
@@ -326,6 +340,26 @@ impl<'a, S: ?Sized + KeyValueStorage> LabelledEntityKeyValueStorage<'a, S> {
             .await
     }
 
+    pub async fn compare_and_swap_raw(
+        &self,
+        namespace: KeyValueStorageNamespace,
+        key: &str,
+        expected: Option<&[u8]>,
+        value: Option<&[u8]>,
+    ) -> Result<bool, String> {
+        self.storage
+            .compare_and_swap(
+                self.svc_name,
+                self.api_name,
+                self.entity_name,
+                namespace,
+                key,
+                expected,
+                value,
+            )
+            .await
+    }
+
     pub async fn set_many<V: Encode>(
         &self,
         namespace: KeyValueStorageNamespace,
@@ -605,8 +639,38 @@ pub enum KeyValueStorageNamespace {
     Worker,
     Promise,
     Schedule,
+    DataKey,
+    Secret {
+        account_id: AccountId,
+        component_id: ComponentId,
+    },
     UserDefined {
         account_id: AccountId,
         bucket: String,
     },
+    WorkerState {
+        account_id: AccountId,
+        worker_id: WorkerId,
+    },
+    Shared {
+        account_id: AccountId,
+    },
+    BlobReferences {
+        account_id: AccountId,
+    },
+    Topics {
+        account_id: AccountId,
+    },
+    PendingTopics,
+    PendingCompensations,
+    Sagas {
+        account_id: AccountId,
+    },
+    EventSinks {
+        account_id: AccountId,
+    },
+    Usage {
+        account_id: AccountId,
+    },
+    CompilationLease,
 }
@@ -22,6 +22,23 @@ use tracing::debug;
 
 use crate::storage::keyvalue::{KeyValueStorage, KeyValueStorageNamespace};
 
+const COMPARE_AND_SWAP_SCRIPT: &str = r#"
+local current = redis.call('HGET', KEYS[1], ARGV[1])
+if ARGV[2] == '1' then
+    if current ~= ARGV[3] then
+        return 0
+    end
+elseif current then
+    return 0
+end
+if ARGV[4] == '1' then
+    redis.call('HSET', KEYS[1], ARGV[1], ARGV[5])
+else
+    redis.call('HDEL', KEYS[1], ARGV[1])
+end
+return 1
+"#;
+
 #[derive(Debug)]
 pub struct RedisKeyValueStorage {
     redis: RedisPool,
@@ -37,9 +54,36 @@ impl RedisKeyValueStorage {
             KeyValueStorageNamespace::Worker => None,
             KeyValueStorageNamespace::Promise => Some("promises".to_string()),
             KeyValueStorageNamespace::Schedule => None,
+            KeyValueStorageNamespace::DataKey => Some("data-keys".to_string()),
+            KeyValueStorageNamespace::Secret {
+                account_id,
+                component_id,
+            } => Some(format!("secrets:{account_id}:{component_id}")),
             KeyValueStorageNamespace::UserDefined { account_id, bucket } => {
                 Some(format!("user-defined:{account_id}:{bucket}"))
             }
+            KeyValueStorageNamespace::WorkerState {
+                account_id,
+                worker_id,
+            } => Some(format!(
+                "worker-state:{account_id}:{}",
+                worker_id.to_redis_key()
+            )),
+            KeyValueStorageNamespace::Shared { account_id } => Some(format!("shared:{account_id}")),
+            KeyValueStorageNamespace::BlobReferences { account_id } => {
+                Some(format!("blob-refs:{account_id}"))
+            }
+            KeyValueStorageNamespace::Topics { account_id } => Some(format!("topics:{account_id}")),
+            KeyValueStorageNamespace::PendingTopics => Some("pending-topics".to_string()),
+            KeyValueStorageNamespace::PendingCompensations => {
+                Some("pending-compensations".to_string())
+            }
+            KeyValueStorageNamespace::Sagas { account_id } => Some(format!("sagas:{account_id}")),
+            KeyValueStorageNamespace::EventSinks { account_id } => {
+                Some(format!("event-sinks:{account_id}"))
+            }
+            KeyValueStorageNamespace::Usage { account_id } => Some(format!("usage:{account_id}")),
+            KeyValueStorageNamespace::CompilationLease => Some("compilation-leases".to_string()),
         }
     }
 }
@@ -139,6 +183,45 @@ impl KeyValueStorage for RedisKeyValueStorage {
         }
     }
 
+    async fn compare_and_swap(
+        &self,
+        svc_name: &'static str,
+        api_name: &'static str,
+        entity_name: &'static str,
+        namespace: KeyValueStorageNamespace,
+        key: &str,
+        expected: Option<&[u8]>,
+        value: Option<&[u8]>,
+    ) -> Result<bool, String> {
+        if let Some(value) = value {
+            record_redis_serialized_size(svc_name, entity_name, value.len());
+        }
+
+        let ns = Self::use_hash(&namespace).ok_or_else(|| {
+            format!("compare_and_swap is not supported in namespace {namespace:?}")
+        })?;
+
+        // Arguments: field, whether a current value is expected, the expected value,
+        // whether the field should be set (or deleted), the new value
+        let flag = |present: bool| Bytes::from_static(if present { b"1" } else { b"0" });
+        let args: Vec<Bytes> = vec![
+            Bytes::copy_from_slice(key.as_bytes()),
+            flag(expected.is_some()),
+            Bytes::copy_from_slice(expected.unwrap_or_default()),
+            flag(value.is_some()),
+            Bytes::copy_from_slice(value.unwrap_or_default()),
+        ];
+        let result: i64 = self
+            .redis
+            .with(svc_name, api_name)
+            .eval(COMPARE_AND_SWAP_SCRIPT, vec![ns], args)
+            .await
+            .map_err(|redis_err| redis_err.to_string())?;
+
+        debug!("compare_and_swap result: {:?}", result);
+        Ok(result == 1)
+    }
+
     async fn get(
         &self,
         svc_name: &'static str,
@@ -84,6 +84,23 @@ impl KeyValueStorage for SqliteKeyValueStorage {
             .map_err(|e| e.to_string())
     }
 
+    async fn compare_and_swap(
+        &self,
+        svc_name: &'static str,
+        api_name: &'static str,
+        _entity_name: &'static str,
+        namespace: KeyValueStorageNamespace,
+        key: &str,
+        expected: Option<&[u8]>,
+        value: Option<&[u8]>,
+    ) -> Result<bool, String> {
+        self.pool
+            .with(svc_name, api_name)
+            .compare_and_swap(&Self::to_string(&namespace), key, expected, value)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
     async fn get(
         &self,
         svc_name: &'static str,
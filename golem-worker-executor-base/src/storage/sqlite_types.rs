@@ -331,6 +331,55 @@ impl SqliteLabelledApi {
             .map(|_| existing.is_none())
     }
 
+    pub async fn compare_and_swap(
+        &self,
+        namespace: &str,
+        key: &str,
+        expected: Option<&[u8]>,
+        value: Option<&[u8]>,
+    ) -> Result<bool, Error> {
+        let query = match (expected, value) {
+            (None, None) => {
+                sqlx::query("SELECT 1 FROM kv_storage WHERE key = ? AND namespace = ?;")
+                    .bind(key)
+                    .bind(namespace)
+            }
+            (None, Some(value)) => sqlx::query(
+                "INSERT OR IGNORE INTO kv_storage (key, value, namespace) VALUES (?, ?, ?);",
+            )
+            .bind(key)
+            .bind(value)
+            .bind(namespace),
+            (Some(expected), Some(value)) => sqlx::query(
+                "UPDATE kv_storage SET value = ? WHERE key = ? AND namespace = ? AND value = ?;",
+            )
+            .bind(value)
+            .bind(key)
+            .bind(namespace)
+            .bind(expected),
+            (Some(expected), None) => {
+                sqlx::query("DELETE FROM kv_storage WHERE key = ? AND namespace = ? AND value = ?;")
+                    .bind(key)
+                    .bind(namespace)
+                    .bind(expected)
+            }
+        };
+
+        let start = Instant::now();
+        match (expected, value) {
+            (None, None) => self
+                .record(
+                    start,
+                    "compare_and_swap",
+                    query.fetch_optional(&self.pool).await,
+                )
+                .map(|existing| existing.is_none()),
+            _ => self
+                .record(start, "compare_and_swap", query.execute(&self.pool).await)
+                .map(|result| result.rows_affected() == 1),
+        }
+    }
+
     pub async fn get(&self, namespace: &str, key: &str) -> Result<Option<Bytes>, Error> {
         let query = sqlx::query_as("SELECT value FROM kv_storage WHERE key = ? AND namespace = ?;")
             .bind(key)
@@ -625,6 +674,31 @@ impl SqliteLabelledApi {
             .map(|_| ())
     }
 
+    pub async fn append_many(
+        &self,
+        key: &str,
+        entries: &[(String, u64, &[u8])],
+    ) -> Result<(), Error> {
+        let mut tx = self.pool.begin().await?;
+        let start = Instant::now();
+
+        for (namespace, id, value) in entries {
+            sqlx::query(
+                r#"
+                INSERT INTO index_storage (namespace, key, id, value) VALUES (?,?,?,?);
+                "#,
+            )
+            .bind(namespace.as_str())
+            .bind(key)
+            .bind(sqlx::types::Json(*id))
+            .bind(*value)
+            .execute(&mut *tx)
+            .await?;
+        }
+        let result = tx.commit().await;
+        self.record(start, "append_many", result)
+    }
+
     pub async fn length(&self, namespace: &str, key: &str) -> Result<u64, Error> {
         let query = sqlx::query_as::<_, (i64,)>(
             "SELECT COUNT(*) FROM index_storage WHERE namespace = ? AND key = ?;",
@@ -0,0 +1,431 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Restoring a worker to its state as of a given oplog index.
+//!
+//! A copy of the worker gets the prefix of the original worker's oplog, so its recovery replays
+//! the recorded host calls without performing their side effects, and it can be continued without
+//! affecting the original worker. Queries use throwaway instances of the original worker
+//! instead, see `Worker::query_as_of`.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use golem_common::model::oplog::{OplogEntry, OplogIndex, OplogPayload, UpdateDescription};
+use golem_common::model::regions::OplogRegion;
+use golem_common::model::{ComponentType, OwnedWorkerId, WorkerMetadata, WorkerStatusRecord};
+
+use crate::error::GolemError;
+use crate::services::oplog::{CommitLevel, Oplog, OplogService};
+use crate::services::{HasComponentService, HasConfig, HasOplogService, HasWorkerService};
+use crate::worker::{calculate_last_known_status, calculate_status_from_entries};
+
+/// Creates the `target` worker as a copy of the `source` worker, restored to the state the source
+/// had at `oplog_index`. Returns the oplog index the state was restored to, see
+/// [last_index_between_invocations].
+///
+/// With `keep_later_entries` the entries following the restored point are copied too, masked by
+/// a deleted region, so the history of the source stays visible in the copy's oplog. Invocations
+/// and updates pending at the restored point are not executed by the copy.
+///
+/// The copy's oplog ends with a `Forked` entry recording the source worker and the restored
+/// index. The copy has no parent, as it was not created by the source worker.
+pub async fn copy_worker<T>(
+    deps: &T,
+    source: &OwnedWorkerId,
+    target: &OwnedWorkerId,
+    oplog_index: OplogIndex,
+    keep_later_entries: bool,
+) -> Result<OplogIndex, GolemError>
+where
+    T: HasOplogService + HasWorkerService + HasComponentService + HasConfig,
+{
+    let metadata = deps
+        .worker_service()
+        .get(source)
+        .await
+        .ok_or(GolemError::worker_not_found(source.worker_id()))?;
+    if metadata.account_id != source.account_id() {
+        // Not revealing that the worker exists in another account
+        return Err(GolemError::worker_not_found(source.worker_id()));
+    }
+    if deps.worker_service().get(target).await.is_some() {
+        return Err(GolemError::worker_already_exists(target.worker_id()));
+    }
+
+    let last_index = check_oplog_index(deps, source, oplog_index).await?;
+
+    let mut entries = deps
+        .oplog_service()
+        .read_range(
+            source,
+            OplogIndex::INITIAL,
+            if keep_later_entries {
+                last_index
+            } else {
+                oplog_index
+            },
+        )
+        .await;
+    let restored_index = last_index_between_invocations(&entries, oplog_index);
+
+    let Some(OplogEntry::Create {
+        component_version,
+        component_size,
+        initial_total_linear_memory_size,
+        ..
+    }) = entries.remove(&OplogIndex::INITIAL)
+    else {
+        return Err(GolemError::runtime(format!(
+            "The oplog of worker {source} does not start with a create entry"
+        )));
+    };
+    let component_type = deps
+        .component_service()
+        .get_metadata(&source.component_id(), Some(component_version))
+        .await?
+        .component_type;
+    if component_type == ComponentType::Ephemeral {
+        return Err(GolemError::invalid_request(
+            "Ephemeral workers do not keep their state between invocations",
+        ));
+    }
+
+    let target_metadata = WorkerMetadata {
+        worker_id: target.worker_id(),
+        account_id: target.account_id(),
+        parent: None,
+        last_known_status: WorkerStatusRecord {
+            component_version,
+            component_size,
+            total_linear_memory_size: initial_total_linear_memory_size,
+            ..WorkerStatusRecord::default()
+        },
+        ..metadata
+    };
+    deps.worker_service()
+        .add(&target_metadata, component_type)
+        .await?;
+
+    let later_entries = entries.split_off(&restored_index.next());
+    let target_oplog = deps
+        .oplog_service()
+        .open(target, OplogIndex::INITIAL, component_type)
+        .await;
+    let oplog_service = deps.oplog_service();
+    copy_oplog_entries(&oplog_service, source, target, &target_oplog, entries).await?;
+
+    let mut status =
+        calculate_last_known_status(deps, target, &deps.worker_service().get(target).await).await?;
+    status.pending_invocations.clear();
+    status.pending_updates.clear();
+    if status.deleted_regions.is_overridden() {
+        status.deleted_regions.drop_override();
+    }
+
+    if keep_later_entries && !later_entries.is_empty() {
+        copy_oplog_entries(&oplog_service, source, target, &target_oplog, later_entries).await?;
+        let jump_index = target_oplog.current_oplog_index().await.next();
+        let jump = OplogRegion {
+            start: restored_index.next(),
+            end: jump_index, // masking the jump entry too
+        };
+        target_oplog
+            .add_and_commit(OplogEntry::jump(jump.clone()))
+            .await;
+        status.deleted_regions.add(jump);
+    }
+
+    target_oplog
+        .add_and_commit(OplogEntry::forked(source.worker_id(), restored_index))
+        .await;
+    status.oplog_idx = target_oplog.current_oplog_index().await;
+
+    deps.worker_service()
+        .update_status(target, &status, component_type)
+        .await;
+
+    Ok(restored_index)
+}
+
+/// Calculates the status the worker had at the last oplog index not after `oplog_index` where it
+/// was not in the middle of an invocation, see [last_index_between_invocations]. Returns that
+/// oplog index with the status. Invocations and updates pending at that point are dropped.
+pub async fn status_as_of<T>(
+    deps: &T,
+    owned_worker_id: &OwnedWorkerId,
+    oplog_index: OplogIndex,
+) -> Result<(OplogIndex, WorkerStatusRecord), GolemError>
+where
+    T: HasOplogService + HasConfig,
+{
+    check_oplog_index(deps, owned_worker_id, oplog_index).await?;
+
+    let (mut entries, mut decode_failures) = deps
+        .oplog_service()
+        .read_range_checked(owned_worker_id, OplogIndex::INITIAL, oplog_index)
+        .await;
+    let restored_index = last_index_between_invocations(&entries, oplog_index);
+    let _ = entries.split_off(&restored_index.next());
+    decode_failures.retain(|failure| failure.oplog_index <= restored_index);
+
+    let Some(OplogEntry::Create {
+        component_version,
+        component_size,
+        initial_total_linear_memory_size,
+        ..
+    }) = entries.get(&OplogIndex::INITIAL)
+    else {
+        return Err(GolemError::runtime(format!(
+            "The oplog of worker {owned_worker_id} does not start with a create entry"
+        )));
+    };
+    let initial_status = WorkerStatusRecord {
+        component_version: *component_version,
+        component_size: *component_size,
+        total_linear_memory_size: *initial_total_linear_memory_size,
+        ..WorkerStatusRecord::default()
+    };
+
+    let mut status = calculate_status_from_entries(
+        initial_status,
+        &deps.config().retry,
+        restored_index,
+        &entries,
+        &decode_failures,
+    );
+    status.pending_invocations.clear();
+    status.pending_updates.clear();
+    if status.deleted_regions.is_overridden() {
+        status.deleted_regions.drop_override();
+    }
+
+    Ok((restored_index, status))
+}
+
+/// Checks that `oplog_index` is in the range of the worker's oplog, returning its last index
+async fn check_oplog_index<T: HasOplogService>(
+    deps: &T,
+    owned_worker_id: &OwnedWorkerId,
+    oplog_index: OplogIndex,
+) -> Result<OplogIndex, GolemError> {
+    let last_index = deps.oplog_service().get_last_index(owned_worker_id).await;
+    if oplog_index < OplogIndex::INITIAL || oplog_index > last_index {
+        Err(GolemError::invalid_request(format!(
+            "Oplog index {oplog_index} is out of the range of the worker's oplog ({}..={last_index})",
+            OplogIndex::INITIAL
+        )))
+    } else {
+        Ok(last_index)
+    }
+}
+
+/// Gets the last oplog index not after `oplog_index` where the worker is not in the middle of an
+/// invocation, given the oplog entries up to `oplog_index`.
+///
+/// A copy of the worker ending in the middle of an invocation would continue running it live on
+/// recovery, so the state is restored to the point before the unfinished invocation started.
+pub fn last_index_between_invocations(
+    entries: &BTreeMap<OplogIndex, OplogEntry>,
+    oplog_index: OplogIndex,
+) -> OplogIndex {
+    let mut unfinished_invocation = None;
+    for (idx, entry) in entries.range(..=oplog_index) {
+        match entry {
+            OplogEntry::ExportedFunctionInvoked { .. } => unfinished_invocation = Some(*idx),
+            OplogEntry::ExportedFunctionCompleted { .. } => unfinished_invocation = None,
+            _ => {}
+        }
+    }
+    match unfinished_invocation {
+        Some(invoked_idx) => invoked_idx.previous(),
+        None => oplog_index,
+    }
+}
+
+/// Appends the given entries of the `source` worker's oplog to the `target` oplog, uploading
+/// their external payloads again for the `target` worker
+async fn copy_oplog_entries(
+    oplog_service: &Arc<dyn OplogService + Send + Sync>,
+    source: &OwnedWorkerId,
+    target: &OwnedWorkerId,
+    target_oplog: &Arc<dyn Oplog + Send + Sync>,
+    entries: BTreeMap<OplogIndex, OplogEntry>,
+) -> Result<(), GolemError> {
+    for (_, entry) in entries {
+        let entry = copy_entry_payloads(oplog_service, source, target, entry)
+            .await
+            .map_err(|err| GolemError::runtime(format!("Failed to copy oplog payload: {err}")))?;
+        target_oplog.add(entry).await;
+    }
+    target_oplog.commit(CommitLevel::Always).await;
+    Ok(())
+}
+
+async fn copy_entry_payloads(
+    oplog_service: &Arc<dyn OplogService + Send + Sync>,
+    source: &OwnedWorkerId,
+    target: &OwnedWorkerId,
+    entry: OplogEntry,
+) -> Result<OplogEntry, String> {
+    let copy = |payload| copy_payload(oplog_service, source, target, payload);
+    Ok(match entry {
+        OplogEntry::ImportedFunctionInvokedV1 {
+            timestamp,
+            function_name,
+            response,
+            wrapped_function_type,
+        } => OplogEntry::ImportedFunctionInvokedV1 {
+            timestamp,
+            function_name,
+            response: copy(response).await?,
+            wrapped_function_type,
+        },
+        OplogEntry::ImportedFunctionInvoked {
+            timestamp,
+            function_name,
+            request,
+            response,
+            wrapped_function_type,
+        } => OplogEntry::ImportedFunctionInvoked {
+            timestamp,
+            function_name,
+            request: copy(request).await?,
+            response: copy(response).await?,
+            wrapped_function_type,
+        },
+        OplogEntry::ExportedFunctionInvoked {
+            timestamp,
+            function_name,
+            request,
+            idempotency_key,
+        } => OplogEntry::ExportedFunctionInvoked {
+            timestamp,
+            function_name,
+            request: copy(request).await?,
+            idempotency_key,
+        },
+        OplogEntry::ExportedFunctionCompleted {
+            timestamp,
+            response,
+            consumed_fuel,
+        } => OplogEntry::ExportedFunctionCompleted {
+            timestamp,
+            response: copy(response).await?,
+            consumed_fuel,
+        },
+        OplogEntry::Snapshot {
+            timestamp,
+            component_version,
+            payload,
+        } => OplogEntry::Snapshot {
+            timestamp,
+            component_version,
+            payload: copy(payload).await?,
+        },
+        OplogEntry::PendingUpdate {
+            timestamp,
+            description:
+                UpdateDescription::SnapshotBased {
+                    target_version,
+                    payload,
+                },
+        } => OplogEntry::PendingUpdate {
+            timestamp,
+            description: UpdateDescription::SnapshotBased {
+                target_version,
+                payload: copy(payload).await?,
+            },
+        },
+        entry => entry,
+    })
+}
+
+async fn copy_payload(
+    oplog_service: &Arc<dyn OplogService + Send + Sync>,
+    source: &OwnedWorkerId,
+    target: &OwnedWorkerId,
+    payload: OplogPayload,
+) -> Result<OplogPayload, String> {
+    match payload {
+        OplogPayload::Inline(_) => Ok(payload),
+        _ => {
+            let data = oplog_service.download_payload(source, &payload).await?;
+            oplog_service.upload_payload(target, &data).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::collections::BTreeMap;
+
+    use golem_common::model::oplog::{OplogEntry, OplogIndex, OplogPayload};
+    use golem_common::model::{IdempotencyKey, Timestamp};
+
+    use crate::time_travel::last_index_between_invocations;
+
+    fn invoked() -> OplogEntry {
+        OplogEntry::ExportedFunctionInvoked {
+            timestamp: Timestamp::now_utc(),
+            function_name: "golem:it/api.{add-item}".to_string(),
+            request: OplogPayload::Inline(vec![]),
+            idempotency_key: IdempotencyKey::fresh(),
+        }
+    }
+
+    fn completed() -> OplogEntry {
+        OplogEntry::ExportedFunctionCompleted {
+            timestamp: Timestamp::now_utc(),
+            response: OplogPayload::Inline(vec![]),
+            consumed_fuel: 0,
+        }
+    }
+
+    fn entries(entries: Vec<OplogEntry>) -> BTreeMap<OplogIndex, OplogEntry> {
+        entries
+            .into_iter()
+            .enumerate()
+            .map(|(idx, entry)| (OplogIndex::from_u64(idx as u64 + 2), entry))
+            .collect()
+    }
+
+    #[test]
+    fn index_between_invocations_is_kept() {
+        let entries = entries(vec![invoked(), OplogEntry::nop(), completed(), invoked()]);
+        assert_eq!(
+            last_index_between_invocations(&entries, OplogIndex::from_u64(4)),
+            OplogIndex::from_u64(4)
+        );
+        assert_eq!(
+            last_index_between_invocations(&entries, OplogIndex::from_u64(1)),
+            OplogIndex::from_u64(1)
+        );
+    }
+
+    #[test]
+    fn index_in_unfinished_invocation_is_moved_before_it() {
+        let entries = entries(vec![invoked(), completed(), invoked(), OplogEntry::nop()]);
+        assert_eq!(
+            last_index_between_invocations(&entries, OplogIndex::from_u64(5)),
+            OplogIndex::from_u64(3)
+        );
+        assert_eq!(
+            last_index_between_invocations(&entries, OplogIndex::from_u64(2)),
+            OplogIndex::from_u64(1)
+        );
+    }
+}
@@ -80,6 +80,20 @@ where
     crate::preview2::wasi::keyvalue::types::add_to_linker_get_host(&mut linker, get)?;
     crate::preview2::wasi::keyvalue::wasi_keyvalue_error::add_to_linker_get_host(&mut linker, get)?;
     crate::preview2::wasi::logging::logging::add_to_linker_get_host(&mut linker, get)?;
+    crate::preview2::invocation_context::golem::invocation_context::context::add_to_linker_get_host(
+        &mut linker,
+        get,
+    )?;
+    crate::preview2::secrets::golem::secrets::secrets::add_to_linker_get_host(&mut linker, get)?;
+    crate::preview2::saga::golem::saga::saga::add_to_linker_get_host(&mut linker, get)?;
+    crate::preview2::rpc_batch::golem::rpc_batch::batch::add_to_linker_get_host(&mut linker, get)?;
+    crate::preview2::worker_kv::golem::worker_kv::store::add_to_linker_get_host(&mut linker, get)?;
+    crate::preview2::shared_kv::golem::shared_kv::store::add_to_linker_get_host(&mut linker, get)?;
+    crate::preview2::topics::golem::topics::pubsub::add_to_linker_get_host(&mut linker, get)?;
+    crate::preview2::worker_blobs::golem::worker_blobs::store::add_to_linker_get_host(
+        &mut linker,
+        get,
+    )?;
 
     Ok(linker)
 }
@@ -13,27 +13,44 @@
 // limitations under the License.
 
 use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::future::Future;
 use std::mem;
 use std::ops::DerefMut;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, RwLock};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use crate::durable_host::recover_stderr_logs;
 use crate::error::{GolemError, WorkerOutOfMemory};
 use crate::function_result_interpreter::interpret_function_results;
 use crate::invocation::{invoke_worker, InvokeResult};
+use crate::metrics::wasm::record_create_instance;
 use crate::model::{ExecutionStatus, InterruptKind, LookupResult, TrapType, WorkerConfig};
+use crate::oplog_regions;
+use crate::services::active_workers::Admission;
+use crate::services::blob_store::DefaultBlobStoreService;
 use crate::services::component::ComponentMetadata;
 use crate::services::events::Event;
-use crate::services::oplog::{CommitLevel, Oplog, OplogOps};
+use crate::services::invocation_scheduling::{InvocationPermitGuard, InvocationPermitSlot};
+use crate::services::key_value::DefaultKeyValueService;
+use crate::services::oplog::{CommitLevel, Oplog, OplogDecodeFailure, OplogOps, ThrowawayOplog};
+use crate::services::promise::DefaultPromiseService;
+use crate::services::rpc::SandboxedRpc;
+use crate::services::saga::DefaultSagaService;
+use crate::services::topics::DefaultTopicService;
+use crate::services::worker::ReadOnlyWorkerService;
 use crate::services::worker_event::{WorkerEventService, WorkerEventServiceDefault};
 use crate::services::{
     All, HasActiveWorkers, HasAll, HasBlobStoreService, HasComponentService, HasConfig, HasEvents,
-    HasExtraDeps, HasKeyValueService, HasOplog, HasOplogService, HasPromiseService, HasRpc,
-    HasSchedulerService, HasWasmtimeEngine, HasWorker, HasWorkerEnumerationService, HasWorkerProxy,
-    HasWorkerService, UsesAllDeps,
+    HasExtraDeps, HasKeyValueService, HasLogExportService, HasOplog, HasOplogService,
+    HasPromiseService, HasRpc, HasSagaService, HasSchedulerService, HasSecretService,
+    HasTopicService, HasUsageService, HasVirtualClockService, HasWasmtimeEngine, HasWorker,
+    HasWorkerEnumerationService, HasWorkerProxy, HasWorkerService, UsesAllDeps,
 };
+use crate::storage::blob::memory::InMemoryBlobStorage;
+use crate::storage::keyvalue::memory::InMemoryKeyValueStorage;
+use crate::storage::keyvalue::KeyValueStorage;
+use crate::time_travel;
 use crate::workerctx::{PublicWorkerIo, WorkerCtx};
 use anyhow::anyhow;
 use golem_common::config::RetryConfig;
@@ -41,12 +58,15 @@ use golem_common::model::oplog::{
     OplogEntry, OplogIndex, TimestampedUpdateDescription, UpdateDescription, WorkerError,
     WorkerResourceId,
 };
-use golem_common::model::regions::{DeletedRegions, DeletedRegionsBuilder, OplogRegion};
+use golem_common::model::regions::{
+    CorruptedOplogRegion, DeletedRegions, DeletedRegionsBuilder, OplogRegion,
+};
 use golem_common::model::{exports, ComponentType};
 use golem_common::model::{
-    ComponentVersion, FailedUpdateRecord, IdempotencyKey, OwnedWorkerId, SuccessfulUpdateRecord,
-    Timestamp, TimestampedWorkerInvocation, WorkerId, WorkerInvocation, WorkerMetadata,
-    WorkerResourceDescription, WorkerStatus, WorkerStatusRecord,
+    ComponentVersion, FailedUpdateRecord, IdempotencyKey, InvocationPriority, LastInvokedFunction,
+    OwnedWorkerId, SuccessfulUpdateRecord, Timestamp, TimestampedWorkerInvocation, WorkerId,
+    WorkerInvocation, WorkerMetadata, WorkerName, WorkerResourceDescription, WorkerStatus,
+    WorkerStatusRecord,
 };
 use golem_common::retries::get_delay;
 use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
@@ -54,9 +74,9 @@ use golem_wasm_rpc::Value;
 use tokio::sync::broadcast::error::RecvError;
 use tokio::sync::broadcast::Receiver;
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
-use tokio::sync::{Mutex, MutexGuard, OwnedSemaphorePermit};
+use tokio::sync::{Mutex, OwnedSemaphorePermit};
 use tokio::task::JoinHandle;
-use tracing::{debug, error, info, span, warn, Instrument, Level};
+use tracing::{debug, error, info, span, warn, Instrument, Level, Span};
 use wasmtime::component::Instance;
 use wasmtime::{AsContext, Store, UpdateDeadline};
 
@@ -85,11 +105,19 @@ pub struct Worker<Ctx: WorkerCtx> {
     invocation_results: Arc<RwLock<HashMap<IdempotencyKey, InvocationResult>>>,
     execution_status: Arc<RwLock<ExecutionStatus>>,
     initial_worker_metadata: WorkerMetadata,
+    /// The worker's current arguments and environment variables, see [Worker::update_environment]
+    environment: RwLock<(Vec<String>, Vec<(String, String)>)>,
     stopping: AtomicBool,
+    /// Set when loading the worker's latest snapshot failed, so it is recovered by a full replay
+    skip_snapshot_recovery: AtomicBool,
     worker_estimate_coefficient: f64,
+    max_pending_invocations: Option<usize>,
 
     instance: Arc<Mutex<WorkerInstance>>,
     oom_retry_config: RetryConfig,
+    /// Held while executing an invocation if invocations are scheduled, see
+    /// `ActiveWorkers::invocation_permit_slot`
+    invocation_permit: InvocationPermitSlot,
 }
 
 impl<Ctx: WorkerCtx> HasOplog for Worker<Ctx> {
@@ -156,6 +184,22 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
         Ok(worker)
     }
 
+    /// Gets or creates a worker and makes sure it is running, waiting for a free slot instead of
+    /// failing if the executor is busy. Used for workers that have to be recovered on this
+    /// executor, as nobody retries them elsewhere.
+    pub async fn get_or_create_recovering<T>(
+        deps: &T,
+        owned_worker_id: &OwnedWorkerId,
+    ) -> Result<Arc<Self>, GolemError>
+    where
+        T: HasAll<Ctx> + Send + Sync + Clone + 'static,
+    {
+        let worker =
+            Self::get_or_create_suspended(deps, owned_worker_id, None, None, None, None).await?;
+        Self::start_if_needed_internal(worker.clone(), 0, Admission::WaitWhenBusy).await?;
+        Ok(worker)
+    }
+
     pub async fn new<T: HasAll<Ctx>>(
         deps: &T,
         owned_worker_id: OwnedWorkerId,
@@ -189,8 +233,16 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
                 initial_component_metadata.component_type,
             )
             .await;
+        let oplog = deps
+            .usage_service()
+            .metered_oplog(
+                &owned_worker_id,
+                worker_metadata.last_known_status.total_linear_memory_size,
+                oplog,
+            )
+            .await;
 
-        let initial_pending_invocations = worker_metadata
+        let mut initial_pending_invocations = worker_metadata
             .last_known_status
             .pending_invocations
             .clone();
@@ -203,9 +255,9 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
         let initial_invocation_results =
             worker_metadata.last_known_status.invocation_results.clone();
 
-        let queue = Arc::new(RwLock::new(VecDeque::from_iter(
-            initial_pending_invocations.iter().cloned(),
-        )));
+        // The pending invocations are recorded in their arrival order, the sort is stable
+        initial_pending_invocations.sort_by_key(|invocation| invocation.invocation.priority());
+        let queue = Arc::new(RwLock::new(VecDeque::from(initial_pending_invocations)));
         let pending_updates = Arc::new(RwLock::new(VecDeque::from_iter(
             initial_pending_updates.iter().cloned(),
         )));
@@ -229,13 +281,22 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
 
         let stopping = AtomicBool::new(false);
 
+        let event_service = Arc::new(
+            WorkerEventServiceDefault::new(
+                deps.config().limits.event_broadcast_capacity,
+                deps.config().limits.event_history_size,
+            )
+            .with_log_export(owned_worker_id.clone(), deps.log_export_service()),
+        );
+
+        let invocation_permit = deps
+            .active_workers()
+            .invocation_permit_slot(&owned_worker_id);
+
         Ok(Worker {
             owned_worker_id,
             oplog,
-            event_service: Arc::new(WorkerEventServiceDefault::new(
-                deps.config().limits.event_broadcast_capacity,
-                deps.config().limits.event_history_size,
-            )),
+            event_service,
             deps: All::from_other(deps),
             queue,
             pending_updates,
@@ -243,9 +304,19 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
             instance,
             execution_status,
             stopping,
+            skip_snapshot_recovery: AtomicBool::new(false),
+            environment: RwLock::new(
+                worker_metadata
+                    .last_known_status
+                    .changed_environment
+                    .clone()
+                    .unwrap_or_else(|| (worker_metadata.args.clone(), worker_metadata.env.clone())),
+            ),
             initial_worker_metadata: worker_metadata,
             worker_estimate_coefficient: deps.config().memory.worker_estimate_coefficient,
+            max_pending_invocations: deps.config().limits.max_pending_invocations,
             oom_retry_config: deps.config().memory.oom_retry_config.clone(),
+            invocation_permit,
         })
     }
 
@@ -253,21 +324,44 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
         &self.oom_retry_config
     }
 
+    /// Waits for the turn of the worker's next invocation, which lasts until the returned guard
+    /// is dropped
+    pub(crate) async fn acquire_invocation_permit(&self) -> InvocationPermitGuard<'_> {
+        self.invocation_permit.acquire().await
+    }
+
+    /// Gives up the turn of the current invocation while awaiting `f`, letting other invocations
+    /// start while it is waiting for other workers
+    pub(crate) async fn without_invocation_permit<F: Future>(&self, f: F) -> F::Output {
+        self.invocation_permit.release_while(f).await
+    }
+
+    pub fn skips_snapshot_recovery(&self) -> bool {
+        self.skip_snapshot_recovery.load(Ordering::Acquire)
+    }
+
+    pub fn skip_snapshot_recovery(&self) {
+        self.skip_snapshot_recovery.store(true, Ordering::Release);
+    }
+
     pub async fn start_if_needed(this: Arc<Worker<Ctx>>) -> Result<bool, GolemError> {
-        Self::start_if_needed_internal(this, 0).await
+        Self::start_if_needed_internal(this, 0, Admission::RejectWhenBusy).await
     }
 
     async fn start_if_needed_internal(
         this: Arc<Worker<Ctx>>,
         oom_retry_count: u64,
+        admission: Admission,
     ) -> Result<bool, GolemError> {
         let mut instance = this.instance.lock().await;
         if instance.is_unloaded() {
+            let active_worker_slot = this.active_workers().admit(admission).await?;
             this.mark_as_loading();
             *instance = WorkerInstance::WaitingForPermit(WaitingWorker::new(
                 this.clone(),
                 this.memory_requirement().await?,
                 oom_retry_count,
+                active_worker_slot,
             ));
             Ok(true)
         } else {
@@ -280,6 +374,7 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
         this: Arc<Worker<Ctx>>,
         permit: OwnedSemaphorePermit,
         oom_retry_count: u64,
+        active_worker_slot: Option<OwnedSemaphorePermit>,
     ) {
         let mut instance = this.instance.lock().await;
         *instance = WorkerInstance::Running(RunningWorker::new(
@@ -290,6 +385,7 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
             this.execution_status.clone(),
             permit,
             oom_retry_count,
+            active_worker_slot,
         ));
     }
 
@@ -317,13 +413,13 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
     /// - the invocation queue is empty, so it cannot get into `ExecutionStatus::Running`, as there is nothing to run
     /// - nothing can be added to the invocation queue because we are holding the `instance` lock
     ///
-    /// By passing the running lock to `stop_internal_running` it is never released and the stop eventually
+    /// By keeping the lock while calling `stop_internal_running` it is never released and the stop eventually
     /// drops the `RunningWorker` instance.
     ///
     /// The `stopping` flag is only used to prevent re-entrance of the stopping sequence in case the invocation loop
     /// triggers a stop (in case of a failure - by the way it should not happen here because the worker is idle).
     pub async fn stop_if_idle(&self) -> bool {
-        let instance_guard = self.instance.lock().await;
+        let mut instance_guard = self.instance.lock().await;
         match &*instance_guard {
             WorkerInstance::Running(running) => {
                 if is_running_worker_idle(running) {
@@ -334,7 +430,7 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
                         Ordering::Relaxed,
                     ) == Ok(false)
                     {
-                        self.stop_internal_running(instance_guard, false, None)
+                        self.stop_internal_running(&mut instance_guard, false, None)
                             .await;
                         true
                     } else {
@@ -349,6 +445,48 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
         }
     }
 
+    /// Deletes a region of the worker's oplog, see `oplog_regions::delete_oplog_region`. Returns
+    /// the index of the written `Jump` entry.
+    ///
+    /// The worker is stopped if it is loaded but idle, and the `instance` lock is held until the
+    /// `Jump` entry is written, so the worker cannot be started and write to its oplog while the
+    /// region is validated. Fails if the worker is processing invocations.
+    pub async fn delete_oplog_region(
+        this: &Arc<Worker<Ctx>>,
+        region: OplogRegion,
+    ) -> Result<OplogIndex, GolemError> {
+        let mut instance_guard = this.instance.lock().await;
+        match &*instance_guard {
+            WorkerInstance::Running(running) if is_running_worker_idle(running) => {
+                if this
+                    .stopping
+                    .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+                    != Ok(false)
+                {
+                    return Err(GolemError::invalid_request(
+                        "The worker is being stopped, deleting a region of its oplog has to be retried",
+                    ));
+                }
+                this.stop_internal_running(&mut instance_guard, false, None)
+                    .await;
+            }
+            WorkerInstance::Unloaded => {}
+            WorkerInstance::Running(_) | WorkerInstance::WaitingForPermit(_) => {
+                return Err(GolemError::invalid_request(
+                    "The worker is processing invocations, it has to be interrupted before deleting a region of its oplog",
+                ));
+            }
+        }
+
+        oplog_regions::delete_oplog_region(
+            this.as_ref(),
+            &this.owned_worker_id,
+            &this.oplog(),
+            region,
+        )
+        .await
+    }
+
     pub fn event_service(&self) -> Arc<dyn WorkerEventService + Send + Sync> {
         self.event_service.clone()
     }
@@ -390,13 +528,53 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
             .unwrap()
             .last_known_status()
             .clone();
+        let (args, env) = self.environment();
         let result = self.initial_worker_metadata.clone();
         Ok(WorkerMetadata {
+            args,
+            env,
             last_known_status: updated_status,
             ..result
         })
     }
 
+    pub fn environment(&self) -> (Vec<String>, Vec<(String, String)>) {
+        self.environment.read().unwrap().clone()
+    }
+
+    /// Changes the worker's environment variables, and its arguments if given. The running
+    /// instance of the worker gets the new values from its next invocation, and later instances
+    /// are created with them. Returns the index of the oplog entry recording the change.
+    pub async fn update_environment(
+        &self,
+        args: Option<Vec<String>>,
+        env: Vec<(String, String)>,
+    ) -> Result<OplogIndex, GolemError> {
+        if self.execution_status.read().unwrap().component_type() == ComponentType::Ephemeral {
+            return Err(GolemError::invalid_request(
+                "The environment of ephemeral workers cannot be changed",
+            ));
+        }
+
+        let args = args.unwrap_or_else(|| self.environment().0);
+        let oplog_index = self
+            .oplog
+            .add_and_commit(OplogEntry::change_environment(args.clone(), env.clone()))
+            .await;
+        *self.environment.write().unwrap() = (args, env);
+
+        // The status record calculated from the new entry keeps the change in the worker's metadata
+        self.update_metadata().await?;
+        let status = self
+            .execution_status
+            .read()
+            .unwrap()
+            .last_known_status()
+            .clone();
+        self.update_status(status).await;
+        Ok(oplog_index)
+    }
+
     /// Marks the worker as interrupting - this should eventually make the worker interrupted.
     /// There are several interruption modes but not all of them are supported by all worker
     /// executor implementations.
@@ -445,6 +623,8 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
         idempotency_key: IdempotencyKey,
         full_function_name: String,
         function_input: Vec<Value>,
+        priority: InvocationPriority,
+        context: BTreeMap<String, String>,
     ) -> Result<Option<Result<TypeAnnotatedValue, GolemError>>, GolemError> {
         let output = self.lookup_invocation_result(&idempotency_key).await;
 
@@ -453,9 +633,27 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
             LookupResult::Interrupted => Err(InterruptKind::Interrupt.into()),
             LookupResult::Pending => Ok(None),
             LookupResult::New => {
+                // Only new invocations are rejected when the queue is full, so retries of
+                // already accepted ones keep getting their results
+                if let Some(max_pending_invocations) = self.max_pending_invocations {
+                    let pending_invocations = self.pending_invocation_count();
+                    if pending_invocations >= max_pending_invocations {
+                        return Err(GolemError::too_many_pending_invocations(
+                            self.owned_worker_id.worker_id(),
+                            pending_invocations as u64,
+                        ));
+                    }
+                }
+
                 // Invoke the function in the background
-                self.enqueue(idempotency_key, full_function_name, function_input)
-                    .await;
+                self.enqueue(
+                    idempotency_key,
+                    full_function_name,
+                    function_input,
+                    priority,
+                    context,
+                )
+                .await;
                 Ok(None)
             }
         }
@@ -466,9 +664,18 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
         idempotency_key: IdempotencyKey,
         full_function_name: String,
         function_input: Vec<Value>,
+        priority: InvocationPriority,
+        context: BTreeMap<String, String>,
+        timeout: Option<Duration>,
     ) -> Result<TypeAnnotatedValue, GolemError> {
         match self
-            .invoke(idempotency_key.clone(), full_function_name, function_input)
+            .invoke(
+                idempotency_key.clone(),
+                full_function_name,
+                function_input,
+                priority,
+                context,
+            )
             .await?
         {
             Some(Ok(output)) => Ok(output),
@@ -476,23 +683,136 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
             None => {
                 debug!("Waiting for idempotency key to complete",);
 
-                let result = self.wait_for_invocation_result(&idempotency_key).await;
+                let result = match timeout {
+                    Some(timeout) => match tokio::time::timeout(
+                        timeout,
+                        self.wait_for_invocation_result(&idempotency_key),
+                    )
+                    .await
+                    {
+                        Ok(result) => result,
+                        Err(_) => {
+                            return self.invocation_timed_out(&idempotency_key, timeout).await
+                        }
+                    },
+                    None => self.wait_for_invocation_result(&idempotency_key).await,
+                };
 
                 debug!("Idempotency key lookup result: {:?}", result);
-                match result {
-                    Ok(LookupResult::Complete(Ok(output))) => Ok(output),
-                    Ok(LookupResult::Complete(Err(err))) => Err(err),
-                    Ok(LookupResult::Interrupted) => Err(InterruptKind::Interrupt.into()),
-                    Ok(LookupResult::Pending) => Err(GolemError::unknown(
-                        "Unexpected pending result after invoke",
-                    )),
-                    Ok(LookupResult::New) => Err(GolemError::unknown(
-                        "Unexpected missing result after invoke",
-                    )),
-                    Err(recv_error) => Err(GolemError::unknown(format!(
-                        "Failed waiting for invocation result: {recv_error}"
-                    ))),
+                invocation_output(result)
+            }
+        }
+    }
+
+    /// Invokes the worker like `invoke_and_await`, but only waits for the result until the
+    /// `deadline`. Returns `None` if the invocation did not finish by then, in which case it keeps
+    /// running and its result can be awaited later with `await_invocation_result`.
+    pub async fn invoke_and_poll(
+        &self,
+        idempotency_key: IdempotencyKey,
+        full_function_name: String,
+        function_input: Vec<Value>,
+        priority: InvocationPriority,
+        context: BTreeMap<String, String>,
+        deadline: Duration,
+    ) -> Result<Option<TypeAnnotatedValue>, GolemError> {
+        match self
+            .invoke(
+                idempotency_key.clone(),
+                full_function_name,
+                function_input,
+                priority,
+                context,
+            )
+            .await?
+        {
+            Some(result) => result.map(Some),
+            None => {
+                self.wait_for_invocation_result_until(&idempotency_key, deadline)
+                    .await
+            }
+        }
+    }
+
+    /// Waits for the result of an earlier invocation of the worker until the `deadline`, returning
+    /// `None` if it did not finish by then. The results are looked up by the idempotency key of
+    /// the invocation, so they are available after the worker was restarted too.
+    pub async fn await_invocation_result(
+        &self,
+        idempotency_key: &IdempotencyKey,
+        deadline: Duration,
+    ) -> Result<Option<TypeAnnotatedValue>, GolemError> {
+        if let LookupResult::New = self.lookup_invocation_result(idempotency_key).await {
+            // The running invocation is neither pending nor has a result yet
+            let is_running = self
+                .execution_status
+                .read()
+                .unwrap()
+                .last_known_status()
+                .current_idempotency_key
+                .as_ref()
+                == Some(idempotency_key);
+            if !is_running {
+                return Err(GolemError::invocation_not_found(
+                    self.owned_worker_id.worker_id(),
+                    idempotency_key.clone(),
+                ));
+            }
+        }
+
+        self.wait_for_invocation_result_until(idempotency_key, deadline)
+            .await
+    }
+
+    async fn wait_for_invocation_result_until(
+        &self,
+        idempotency_key: &IdempotencyKey,
+        deadline: Duration,
+    ) -> Result<Option<TypeAnnotatedValue>, GolemError> {
+        match tokio::time::timeout(deadline, self.wait_for_invocation_result(idempotency_key)).await
+        {
+            Ok(result) => invocation_output(result).map(Some),
+            Err(_) => {
+                debug!(
+                    idempotency_key = idempotency_key.to_string(),
+                    "Invocation did not finish in {deadline:?}"
+                );
+                Ok(None)
+            }
+        }
+    }
+
+    /// Handles an invoke-and-await call that did not get its result in time.
+    ///
+    /// If the invocation is running, the worker gets interrupted so the invocation does not keep
+    /// running without anyone waiting for it. Invocations which have not started yet are left in
+    /// the queue, as interrupting the worker would affect the invocation currently running.
+    async fn invocation_timed_out(
+        &self,
+        idempotency_key: &IdempotencyKey,
+        timeout: Duration,
+    ) -> Result<TypeAnnotatedValue, GolemError> {
+        match self.lookup_invocation_result(idempotency_key).await {
+            LookupResult::Complete(result) => result,
+            LookupResult::Interrupted => Err(InterruptKind::Interrupt.into()),
+            LookupResult::Pending | LookupResult::New => {
+                let is_queued = self
+                    .queue
+                    .read()
+                    .unwrap()
+                    .iter()
+                    .any(|queued| queued.invocation.is_idempotency_key(idempotency_key));
+                if !is_queued {
+                    warn!(
+                        idempotency_key = idempotency_key.to_string(),
+                        "Invocation timed out after {timeout:?}, interrupting the worker"
+                    );
+                    self.set_interrupting(InterruptKind::Interrupt).await;
                 }
+                Err(GolemError::invocation_timed_out(
+                    self.owned_worker_id.worker_id(),
+                    timeout,
+                ))
             }
         }
     }
@@ -535,10 +855,7 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
                     timestamp: entry.timestamp(),
                     invocation,
                 };
-                self.queue
-                    .write()
-                    .unwrap()
-                    .push_back(timestamped_invocation);
+                enqueue_by_priority(&mut self.queue.write().unwrap(), timestamped_invocation);
                 self.oplog.add_and_commit(entry).await;
                 self.update_metadata()
                     .await
@@ -547,10 +864,310 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
         }
     }
 
+    /// Checks whether a manual update of the worker to the given version could load the worker's
+    /// state, without changing the worker's oplog or status.
+    ///
+    /// Like a real manual update, the worker's current state is saved into a snapshot, which is
+    /// then loaded into an instance of the target version. Both are done in throwaway instances
+    /// writing into a [`ThrowawayOplog`]. Returns the reason why the update would fail, or `None`
+    /// if it would succeed.
+    pub async fn dry_run_update(
+        this: &Arc<Worker<Ctx>>,
+        target_version: ComponentVersion,
+    ) -> Result<Option<String>, GolemError> {
+        let worker_metadata = this.get_metadata().await?;
+        let current_version = worker_metadata.last_known_status.component_version;
+
+        let current_index = this.oplog.current_oplog_index().await;
+        let (instance, mut store) =
+            match Self::restore_throwaway_instance(this, current_index).await {
+                Ok((_, instance, store)) => (instance, store),
+                Err(error) => {
+                    return Ok(Some(format!(
+                    "Failed to restore the worker's state with version {current_version}: {error}"
+                )))
+                }
+            };
+
+        debug!(
+            "Saving a snapshot of the worker in a throwaway instance of version {current_version}"
+        );
+        store
+            .data_mut()
+            .set_current_idempotency_key(IdempotencyKey::fresh())
+            .await;
+        store.data_mut().begin_call_snapshotting_function();
+        let result = invoke_worker(
+            "golem:api/save-snapshot@0.2.0.{save}".to_string(),
+            vec![],
+            &mut store,
+            &instance,
+        )
+        .await;
+        store.data_mut().end_call_snapshotting_function();
+        drop(store);
+
+        let data = match result {
+            Ok(InvokeResult::Succeeded { output, .. }) => {
+                match RunningWorker::decode_snapshot_result(output) {
+                    Some(data) => data,
+                    None => {
+                        return Ok(Some(
+                            "Failed to save a snapshot: invalid snapshot result".to_string(),
+                        ))
+                    }
+                }
+            }
+            Ok(other) => return Ok(Some(format!("Failed to save a snapshot: {other:?}"))),
+            Err(error) => return Ok(Some(format!("Failed to save a snapshot: {error}"))),
+        };
+
+        let oplog: Arc<dyn Oplog + Send + Sync> =
+            Arc::new(ThrowawayOplog::new(this.oplog.clone()).await);
+        let last_oplog_index = oplog.current_oplog_index().await;
+        // Like in a real manual update, the whole history is skipped so the instance starts live
+        let mut deleted_regions = DeletedRegionsBuilder::new();
+        deleted_regions.add(OplogRegion::from_index_range(
+            OplogIndex::INITIAL.next()..=last_oplog_index,
+        ));
+        let worker_config = WorkerConfig::new(
+            worker_metadata.worker_id.clone(),
+            current_version,
+            worker_metadata.args.clone(),
+            worker_metadata.env.clone(),
+            deleted_regions.build(),
+            worker_metadata.last_known_status.total_linear_memory_size,
+            this.active_workers().linear_memory_limit(
+                &worker_metadata.account_id,
+                &worker_metadata.worker_id.component_id,
+            ),
+        );
+
+        let (instance, mut store) = match Self::create_throwaway_instance(
+            this,
+            target_version,
+            oplog,
+            worker_metadata.last_known_status.clone(),
+            worker_config,
+        )
+        .await?
+        {
+            Ok(result) => result,
+            Err(error) => {
+                return Ok(Some(format!(
+                    "Failed to instantiate version {target_version}: {error}"
+                )))
+            }
+        };
+
+        debug!("Loading the snapshot into a throwaway instance of version {target_version}");
+        Ok(Ctx::try_load_snapshot(&instance, &mut store, &data)
+            .await
+            .map(|error| format!("Failed to load the snapshot: {error}")))
+    }
+
+    /// Restores the state the worker had at the given oplog index in a throwaway instance of the
+    /// worker, and invokes a read-only query function on it. An index in the middle of an
+    /// invocation is moved to before the invocation. Returns the oplog index the state was
+    /// restored to, and the result of the query.
+    ///
+    /// The throwaway instance replays the worker's oplog up to the restored index from a
+    /// [`ThrowawayOplog`], so it is never visible as a worker. It is sandboxed, so the query cannot
+    /// perform remote side effects or change the worker's status.
+    pub async fn query_as_of(
+        this: &Arc<Worker<Ctx>>,
+        oplog_index: OplogIndex,
+        function_name: String,
+        function_input: Vec<Value>,
+    ) -> Result<(OplogIndex, TypeAnnotatedValue), GolemError> {
+        let (restored_index, instance, mut store) =
+            Self::restore_throwaway_instance(this, oplog_index).await?;
+
+        store
+            .data_mut()
+            .set_current_idempotency_key(IdempotencyKey::fresh())
+            .await;
+        let output =
+            match invoke_worker(function_name.clone(), function_input, &mut store, &instance)
+                .await?
+            {
+                InvokeResult::Succeeded { output, .. } => output,
+                InvokeResult::Failed { error, .. } => {
+                    return Err(GolemError::runtime(error.to_string("")))
+                }
+                InvokeResult::Exited { .. } => {
+                    return Err(GolemError::runtime("The query function exited"))
+                }
+                InvokeResult::Interrupted { interrupt_kind, .. } => {
+                    return Err(GolemError::runtime(format!(
+                        "The query function was interrupted: {interrupt_kind}"
+                    )))
+                }
+            };
+
+        let function_results =
+            exports::function_by_name(&store.data().component_metadata().exports, &function_name)
+                .map_err(GolemError::invalid_request)?
+                .ok_or_else(|| {
+                    GolemError::invalid_request(format!("Function {function_name} not found"))
+                })?
+                .results
+                .into_iter()
+                .collect();
+        let result = interpret_function_results(output, function_results).map_err(|e| {
+            GolemError::ValueMismatch {
+                details: e.join(", "),
+            }
+        })?;
+
+        Ok((restored_index, result))
+    }
+
+    /// Restores the state the worker had at the given oplog index in a throwaway instance of the
+    /// worker, see [`Worker::query_as_of`]. Returns the oplog index the state was restored to,
+    /// with the instance.
+    async fn restore_throwaway_instance(
+        this: &Arc<Worker<Ctx>>,
+        oplog_index: OplogIndex,
+    ) -> Result<(OplogIndex, Instance, Store<Ctx>), GolemError> {
+        let worker_metadata = this.get_metadata().await?;
+        let (restored_index, status) =
+            time_travel::status_as_of(this.as_ref(), &this.owned_worker_id, oplog_index).await?;
+        let component_version = status.component_version;
+
+        let oplog: Arc<dyn Oplog + Send + Sync> =
+            Arc::new(ThrowawayOplog::at(this.oplog.clone(), restored_index));
+        let worker_config = WorkerConfig::new(
+            worker_metadata.worker_id.clone(),
+            component_version,
+            worker_metadata.args.clone(),
+            worker_metadata.env.clone(),
+            status.deleted_regions.clone(),
+            status.total_linear_memory_size,
+            this.active_workers().linear_memory_limit(
+                &worker_metadata.account_id,
+                &worker_metadata.worker_id.component_id,
+            ),
+        );
+        let (instance, mut store) =
+            Self::create_throwaway_instance(this, component_version, oplog, status, worker_config)
+                .await?
+                .map_err(|error| {
+                    GolemError::runtime(format!(
+                        "Failed to instantiate version {component_version}: {error}"
+                    ))
+                })?;
+        if store.data().component_metadata().component_type == ComponentType::Ephemeral {
+            return Err(GolemError::invalid_request(
+                "Ephemeral workers do not keep their state between invocations",
+            ));
+        }
+
+        debug!("Restoring the worker as of oplog index {restored_index} in a throwaway instance");
+        let decision =
+            Ctx::prepare_instance(&worker_metadata.worker_id, &instance, &mut store).await?;
+        if decision != RetryDecision::None {
+            return Err(GolemError::runtime(format!(
+                "Failed to restore the worker as of oplog index {restored_index}"
+            )));
+        }
+
+        Ok((restored_index, instance, store))
+    }
+
+    /// Creates a sandboxed throwaway instance of the worker using the given version of its
+    /// component, and writing into the given throwaway oplog. See `WorkerConfig::sandboxed`.
+    /// The instance only reads the worker's metadata, and it cannot invoke other workers.
+    /// Returns the reason as the inner error if the component could not be instantiated.
+    async fn create_throwaway_instance(
+        this: &Arc<Worker<Ctx>>,
+        component_version: ComponentVersion,
+        oplog: Arc<dyn Oplog + Send + Sync>,
+        last_known_status: WorkerStatusRecord,
+        worker_config: WorkerConfig,
+    ) -> Result<Result<(Instance, Store<Ctx>), String>, GolemError> {
+        let component_id = this.owned_worker_id.component_id();
+        let (component, component_metadata) = this
+            .component_service()
+            .get(&this.engine(), &component_id, component_version)
+            .await?;
+        let component_type = component_metadata.component_type;
+
+        let event_service = Arc::new(WorkerEventServiceDefault::new(
+            this.config().limits.event_broadcast_capacity,
+            this.config().limits.event_history_size,
+        ));
+        let execution_status = Arc::new(RwLock::new(ExecutionStatus::Suspended {
+            last_known_status,
+            component_type,
+            timestamp: Timestamp::now_utc(),
+        }));
+
+        // The services with a state get their own in-memory storage, so nothing the instance
+        // writes in live mode is visible outside of it
+        let key_value_storage: Arc<dyn KeyValueStorage + Send + Sync> =
+            Arc::new(InMemoryKeyValueStorage::new());
+        let context = Ctx::create(
+            this.owned_worker_id.clone(),
+            component_metadata,
+            Arc::new(DefaultPromiseService::new(key_value_storage.clone())),
+            Arc::new(ReadOnlyWorkerService::new(this.worker_service())),
+            this.worker_enumeration_service(),
+            Arc::new(DefaultKeyValueService::new(key_value_storage.clone())),
+            Arc::new(DefaultBlobStoreService::new(
+                Arc::new(InMemoryBlobStorage::new()),
+                key_value_storage.clone(),
+            )),
+            this.secret_service(),
+            Arc::new(DefaultTopicService::new(key_value_storage.clone())),
+            Arc::new(DefaultSagaService::new(key_value_storage)),
+            this.virtual_clock_service(),
+            event_service,
+            this.active_workers(),
+            this.oplog_service(),
+            oplog,
+            Arc::downgrade(this),
+            this.scheduler_service(),
+            Arc::new(SandboxedRpc::new(this.rpc())),
+            this.worker_proxy(),
+            this.component_service(),
+            this.extra_deps(),
+            this.config(),
+            worker_config.sandboxed(),
+            execution_status,
+        )
+        .await?;
+
+        let mut store = Store::new(&this.engine(), context);
+        store.set_epoch_deadline(this.config().limits.epoch_ticks);
+        store.epoch_deadline_callback(|_| Ok(UpdateDeadline::Yield(1)));
+        store.set_fuel(i64::MAX as u64)?;
+        store.limiter_async(|ctx| ctx.resource_limiter());
+
+        let instance_pre = this
+            .active_workers()
+            .instance_pre(
+                this.linker(),
+                &component,
+                component_type,
+                component_version,
+                &this.owned_worker_id,
+            )
+            .await?;
+        match instance_pre.instantiate_async(&mut store).await {
+            Ok(instance) => Ok(Ok((instance, store))),
+            Err(error) => Ok(Err(error.to_string())),
+        }
+    }
+
     pub fn pending_invocations(&self) -> Vec<TimestampedWorkerInvocation> {
         self.queue.read().unwrap().iter().cloned().collect()
     }
 
+    pub fn pending_invocation_count(&self) -> usize {
+        self.queue.read().unwrap().len()
+    }
+
     pub fn pending_updates(&self) -> (VecDeque<TimestampedUpdateDescription>, DeletedRegions) {
         let pending_updates = self.pending_updates.read().unwrap().clone();
         let mut deleted_regions = DeletedRegionsBuilder::new();
@@ -650,10 +1267,15 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
 
     pub async fn update_status(&self, status_value: WorkerStatusRecord) {
         // Need to make sure the oplog is committed, because the updated status stores the current
-        // last oplog index as reference.
-        self.oplog().commit(CommitLevel::DurableOnly).await;
-        // Storing the status in the key-value storage
+        // last oplog index as reference. Durable workers may be batching their oplog writes, so
+        // their commit must not be deferred.
         let component_type = self.execution_status.read().unwrap().component_type();
+        let commit_level = match component_type {
+            ComponentType::Durable => CommitLevel::Immediate,
+            ComponentType::Ephemeral => CommitLevel::DurableOnly,
+        };
+        self.oplog().commit(commit_level).await;
+        // Storing the status in the key-value storage
         self.worker_service()
             .update_status(&self.owned_worker_id, &status_value, component_type)
             .await;
@@ -707,6 +1329,16 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
         }
     }
 
+    /// Returns true if the worker is loaded and is either performing an invocation or has
+    /// pending invocations waiting to be processed.
+    pub async fn has_in_flight_invocations(&self) -> bool {
+        match &*self.instance.lock().await {
+            WorkerInstance::Running(running) => !is_running_worker_idle(running),
+            WorkerInstance::WaitingForPermit(_) => true,
+            WorkerInstance::Unloaded => false,
+        }
+    }
+
     /// Gets the timestamp of the last time the execution status changed
     pub async fn last_execution_state_change(&self) -> Timestamp {
         self.execution_status.read().unwrap().timestamp()
@@ -733,11 +1365,19 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
         idempotency_key: IdempotencyKey,
         full_function_name: String,
         function_input: Vec<Value>,
+        priority: InvocationPriority,
+        context: BTreeMap<String, String>,
     ) {
         match &*self.instance.lock().await {
             WorkerInstance::Running(running) => {
                 running
-                    .enqueue(idempotency_key, full_function_name, function_input)
+                    .enqueue(
+                        idempotency_key,
+                        full_function_name,
+                        function_input,
+                        priority,
+                        context,
+                    )
                     .await;
             }
             WorkerInstance::Unloaded | WorkerInstance::WaitingForPermit(_) => {
@@ -746,16 +1386,15 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
                     idempotency_key,
                     full_function_name,
                     function_input,
+                    priority,
+                    context,
                 };
                 let entry = OplogEntry::pending_worker_invocation(invocation.clone());
                 let timestamped_invocation = TimestampedWorkerInvocation {
                     timestamp: entry.timestamp(),
                     invocation,
                 };
-                self.queue
-                    .write()
-                    .unwrap()
-                    .push_back(timestamped_invocation);
+                enqueue_by_priority(&mut self.queue.write().unwrap(), timestamped_invocation);
                 self.oplog.add_and_commit(entry).await;
                 self.update_metadata()
                     .await
@@ -801,6 +1440,15 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
         }
     }
 
+    /// Gets the index of the oplog entry completing the invocation, if it has already finished
+    pub fn invocation_result_index(&self, key: &IdempotencyKey) -> Option<OplogIndex> {
+        self.invocation_results
+            .read()
+            .unwrap()
+            .get(key)
+            .map(|result| result.oplog_idx())
+    }
+
     async fn lookup_invocation_result(&self, key: &IdempotencyKey) -> LookupResult {
         let maybe_result = self.invocation_results.read().unwrap().get(key).cloned();
         if let Some(mut result) = maybe_result {
@@ -869,9 +1517,9 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
             .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
             == Ok(false)
         {
-            let instance = self.instance.lock().await;
+            let mut instance = self.instance.lock().await;
             self.stop_internal_running(
-                instance,
+                &mut instance,
                 called_from_invocation_loop,
                 fail_pending_invocations,
             )
@@ -879,9 +1527,9 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
         }
     }
 
-    async fn stop_internal_running<'a>(
+    async fn stop_internal_running(
         &self,
-        mut instance: MutexGuard<'a, WorkerInstance>,
+        instance: &mut WorkerInstance,
         called_from_invocation_loop: bool,
         fail_pending_invocations: Option<GolemError>,
     ) {
@@ -918,6 +1566,10 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
                 let run_loop_handle = running.stop(); // this drops `running`
                 run_loop_handle.await.expect("Worker run loop failed");
             }
+
+            // Writing the entries of batched oplogs before the worker gets dropped, as
+            // dropping the oplog does not write them
+            self.oplog().commit(CommitLevel::Immediate).await;
         } else {
             debug!("Worker was already stopped");
         }
@@ -934,7 +1586,7 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
         if let Some(delay) = delay {
             tokio::time::sleep(delay).await;
         }
-        Self::start_if_needed_internal(this, oom_retry_count).await
+        Self::start_if_needed_internal(this, oom_retry_count, Admission::WaitWhenBusy).await
     }
 
     async fn get_or_create_worker_metadata<
@@ -949,6 +1601,11 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
     ) -> Result<WorkerMetadata, GolemError> {
         match this.worker_service().get(owned_worker_id).await {
             None => {
+                // Existing workers keep their names, but new ones must follow the same rules
+                // no matter whether they are created through the APIs, RPC or by an invocation
+                WorkerName::validate(&owned_worker_id.worker_id.worker_name)
+                    .map_err(GolemError::invalid_request)?;
+
                 let component_id = owned_worker_id.component_id();
                 let component_metadata = this
                     .component_service()
@@ -1029,6 +1686,7 @@ impl WaitingWorker {
         parent: Arc<Worker<Ctx>>,
         memory_requirement: u64,
         oom_retry_count: u64,
+        active_worker_slot: Option<OwnedSemaphorePermit>,
     ) -> Self {
         let span = span!(
             Level::INFO,
@@ -1038,7 +1696,8 @@ impl WaitingWorker {
         let handle = tokio::task::spawn(
             async move {
                 let permit = parent.active_workers().acquire(memory_requirement).await;
-                Worker::start_with_permit(parent, permit, oom_retry_count).await;
+                Worker::start_with_permit(parent, permit, oom_retry_count, active_worker_slot)
+                    .await;
             }
             .instrument(span),
         );
@@ -1065,10 +1724,14 @@ struct RunningWorker {
     oplog: Arc<dyn Oplog + Send + Sync>,
 
     permit: OwnedSemaphorePermit,
+    /// Slot of the worker among the active workers of the executor, released when unloaded
+    #[allow(dead_code)]
+    active_worker_slot: Option<OwnedSemaphorePermit>,
     waiting_for_command: Arc<AtomicBool>,
 }
 
 impl RunningWorker {
+    #[allow(clippy::too_many_arguments)]
     pub fn new<Ctx: WorkerCtx>(
         owned_worker_id: OwnedWorkerId,
         queue: Arc<RwLock<VecDeque<TimestampedWorkerInvocation>>>,
@@ -1077,6 +1740,7 @@ impl RunningWorker {
         execution_status: Arc<RwLock<ExecutionStatus>>,
         permit: OwnedSemaphorePermit,
         oom_retry_count: u64,
+        active_worker_slot: Option<OwnedSemaphorePermit>,
     ) -> Self {
         let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
 
@@ -1115,6 +1779,7 @@ impl RunningWorker {
             oplog,
             execution_status,
             permit,
+            active_worker_slot,
             waiting_for_command,
         }
     }
@@ -1132,11 +1797,15 @@ impl RunningWorker {
         idempotency_key: IdempotencyKey,
         full_function_name: String,
         function_input: Vec<Value>,
+        priority: InvocationPriority,
+        context: BTreeMap<String, String>,
     ) {
         let invocation = WorkerInvocation::ExportedFunction {
             idempotency_key,
             full_function_name,
             function_input,
+            priority,
+            context,
         };
         self.enqueue_worker_invocation(invocation).await;
     }
@@ -1157,10 +1826,7 @@ impl RunningWorker {
             // The worker is currently busy, so we write the pending worker invocation to the oplog
             self.oplog.add_and_commit(entry).await;
         }
-        self.queue
-            .write()
-            .unwrap()
-            .push_back(timestamped_invocation);
+        enqueue_by_priority(&mut self.queue.write().unwrap(), timestamped_invocation);
         self.sender.send(WorkerCommand::Invocation).unwrap()
     }
 
@@ -1171,6 +1837,7 @@ impl RunningWorker {
     async fn create_instance<Ctx: WorkerCtx>(
         parent: Arc<Worker<Ctx>>,
     ) -> Result<(Instance, async_mutex::Mutex<Store<Ctx>>), GolemError> {
+        let start = Instant::now();
         let component_id = parent.owned_worker_id.component_id();
         let worker_metadata = parent.get_metadata().await?;
 
@@ -1197,6 +1864,7 @@ impl RunningWorker {
             .component_service()
             .get(&parent.engine(), &component_id, component_version)
             .await?;
+        let component_type = component_metadata.component_type;
 
         let context = Ctx::create(
             OwnedWorkerId::new(&worker_metadata.account_id, &worker_metadata.worker_id),
@@ -1206,6 +1874,10 @@ impl RunningWorker {
             parent.worker_enumeration_service(),
             parent.key_value_service(),
             parent.blob_store_service(),
+            parent.secret_service(),
+            parent.topic_service(),
+            parent.saga_service(),
+            parent.virtual_clock_service(),
             parent.event_service.clone(),
             parent.active_workers(),
             parent.oplog_service(),
@@ -1224,6 +1896,10 @@ impl RunningWorker {
                 worker_metadata.env.clone(),
                 worker_metadata.last_known_status.deleted_regions.clone(),
                 worker_metadata.last_known_status.total_linear_memory_size,
+                parent.active_workers().linear_memory_limit(
+                    &worker_metadata.account_id,
+                    &worker_metadata.worker_id.component_id,
+                ),
             ),
             parent.execution_status.clone(),
         )
@@ -1250,15 +1926,16 @@ impl RunningWorker {
 
         store.limiter_async(|ctx| ctx.resource_limiter());
 
-        let instance_pre = parent.linker().instantiate_pre(&component).map_err(|e| {
-            GolemError::worker_creation_failed(
-                parent.owned_worker_id.worker_id(),
-                format!(
-                    "Failed to pre-instantiate worker {}: {e}",
-                    parent.owned_worker_id
-                ),
+        let instance_pre = parent
+            .active_workers()
+            .instance_pre(
+                parent.linker(),
+                &component,
+                component_type,
+                component_version,
+                &parent.owned_worker_id,
             )
-        })?;
+            .await?;
 
         let instance = instance_pre
             .instantiate_async(&mut store)
@@ -1272,6 +1949,8 @@ impl RunningWorker {
                     ),
                 )
             })?;
+        record_create_instance(component_type, start.elapsed());
+
         let store = async_mutex::Mutex::new(store);
         Ok((instance, store))
     }
@@ -1352,6 +2031,8 @@ impl RunningWorker {
                     waiting_for_command.store(false, Ordering::Release);
                     match cmd {
                         WorkerCommand::Invocation => {
+                            let _invocation_permit = parent.acquire_invocation_permit().await;
+
                             let message = active
                                 .write()
                                 .unwrap()
@@ -1366,19 +2047,35 @@ impl RunningWorker {
                                     idempotency_key: invocation_key,
                                     full_function_name,
                                     function_input,
+                                    context,
+                                    ..
                                 } => {
-                                    let span = span!(
-                                        Level::INFO,
-                                        "invocation",
-                                        worker_id = owned_worker_id.worker_id.to_string(),
-                                        idempotency_key = invocation_key.to_string(),
-                                        function = full_function_name
-                                    );
+                                    let observability = store.data().component_observability();
+                                    let span = if observability.sample_trace() {
+                                        span!(
+                                            Level::INFO,
+                                            "invocation",
+                                            worker_id = owned_worker_id.worker_id.to_string(),
+                                            idempotency_key = invocation_key.to_string(),
+                                            function = full_function_name
+                                        )
+                                    } else {
+                                        Span::none()
+                                    };
+                                    let capture_payload = observability.sample_payload_capture();
                                     let do_break = async {
+                                        if capture_payload {
+                                            info!(
+                                                function = full_function_name,
+                                                "Invocation input: {:?}", function_input
+                                            );
+                                        }
+
                                         store
                                             .data_mut()
                                             .set_current_idempotency_key(invocation_key)
                                             .await;
+                                        store.data_mut().set_invocation_context(context).await;
 
                                         if let Some(idempotency_key) =
                                             &store.data().get_current_idempotency_key().await
@@ -1408,6 +2105,13 @@ impl RunningWorker {
                                                 output,
                                                 consumed_fuel,
                                             }) => {
+                                                if capture_payload {
+                                                    info!(
+                                                        function = full_function_name,
+                                                        "Invocation output: {:?}", output
+                                                    );
+                                                }
+
                                                 let component_metadata =
                                                     store.as_context().data().component_metadata();
 
@@ -1454,6 +2158,14 @@ impl RunningWorker {
                                                                         RetryDecision::None;
                                                                     true // stop after the invocation
                                                                 } else {
+                                                                    if let Err(err) =
+                                                                        Ctx::take_snapshot_if_due(
+                                                                            &instance, store,
+                                                                        )
+                                                                        .await
+                                                                    {
+                                                                        warn!("Failed to take a snapshot of the worker: {err}");
+                                                                    }
                                                                     false // continue processing the queue
                                                                 }
                                                             }
@@ -1716,7 +2428,7 @@ impl RunningWorker {
     }
 
     /// Attempts to interpret the save snapshot result as a byte vector
-    fn decode_snapshot_result(values: Vec<Value>) -> Option<Vec<u8>> {
+    pub(crate) fn decode_snapshot_result(values: Vec<Value>) -> Option<Vec<u8>> {
         if values.len() == 1 {
             if let Value::List(bytes) = &values[0] {
                 let mut result = Vec::new();
@@ -1803,6 +2515,40 @@ pub enum RetryDecision {
     ReacquirePermits,
 }
 
+/// Order in which workers are recovered when the executor starts or gets shards assigned.
+/// Workers having pending work come first, followed by the most recently active ones.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub struct RecoveryPriority {
+    pub has_pending_work: bool,
+    pub last_activity: Option<Timestamp>,
+}
+
+impl RecoveryPriority {
+    pub fn new(status: &WorkerStatusRecord, last_activity: Option<Timestamp>) -> Self {
+        let has_pending_work = status.status == WorkerStatus::Running
+            || !status.pending_invocations.is_empty()
+            || !status.pending_updates.is_empty();
+
+        Self {
+            has_pending_work,
+            last_activity,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        if self.has_pending_work {
+            "pending"
+        } else {
+            "idle"
+        }
+    }
+
+    /// Sorts the workers to be recovered so the ones to be recovered first come first
+    pub fn sort_for_recovery<T>(workers: &mut [T], priority: impl Fn(&T) -> &RecoveryPriority) {
+        workers.sort_by(|a, b| priority(b).cmp(priority(a)));
+    }
+}
+
 #[derive(Clone, Debug)]
 enum WorkerCommand {
     Invocation,
@@ -1843,88 +2589,119 @@ where
     if last_known.oplog_idx == last_oplog_index {
         Ok(last_known)
     } else {
-        let new_entries: BTreeMap<OplogIndex, OplogEntry> = this
+        let (new_entries, decode_failures): (
+            BTreeMap<OplogIndex, OplogEntry>,
+            Vec<OplogDecodeFailure>,
+        ) = this
             .oplog_service()
-            .read_range(
+            .read_range_checked(
                 owned_worker_id,
                 last_known.oplog_idx.next(),
                 last_oplog_index,
             )
             .await;
 
-        let overridden_retry_config = calculate_overridden_retry_policy(
-            last_known.overridden_retry_config.clone(),
-            &new_entries,
-        );
-        let status = calculate_latest_worker_status(
-            &last_known.status,
+        for failure in &decode_failures {
+            error!(
+                worker_id = owned_worker_id.to_string(),
+                oplog_index = failure.oplog_index.to_string(),
+                serialization_version = failure.serialization_version,
+                "Failed to decode oplog entry, quarantining it as a corrupted region: {}",
+                failure.error
+            );
+        }
+
+        Ok(calculate_status_from_entries(
+            last_known,
             &this.config().retry,
-            last_known.overridden_retry_config.clone(),
+            last_oplog_index,
             &new_entries,
-        );
-
-        let mut initial_deleted_regions = last_known.deleted_regions;
-        if initial_deleted_regions.is_overridden() {
-            initial_deleted_regions.drop_override();
-        }
+            &decode_failures,
+        ))
+    }
+}
 
-        let mut deleted_regions = calculate_deleted_regions(initial_deleted_regions, &new_entries);
-        let pending_invocations =
-            calculate_pending_invocations(last_known.pending_invocations, &new_entries);
-        let (
-            pending_updates,
-            failed_updates,
-            successful_updates,
-            component_version,
-            component_size,
-        ) = calculate_update_fields(
+/// Applies the oplog entries following `last_known` up to `last_oplog_index` to the status record
+pub(crate) fn calculate_status_from_entries(
+    last_known: WorkerStatusRecord,
+    default_retry_policy: &RetryConfig,
+    last_oplog_index: OplogIndex,
+    new_entries: &BTreeMap<OplogIndex, OplogEntry>,
+    decode_failures: &[OplogDecodeFailure],
+) -> WorkerStatusRecord {
+    let overridden_retry_config =
+        calculate_overridden_retry_policy(last_known.overridden_retry_config.clone(), new_entries);
+    let status = calculate_latest_worker_status(
+        &last_known.status,
+        default_retry_policy,
+        last_known.overridden_retry_config.clone(),
+        new_entries,
+    );
+
+    let mut initial_deleted_regions = last_known.deleted_regions;
+    if initial_deleted_regions.is_overridden() {
+        initial_deleted_regions.drop_override();
+    }
+
+    let mut deleted_regions =
+        calculate_deleted_regions(initial_deleted_regions, new_entries, decode_failures);
+    let pending_invocations =
+        calculate_pending_invocations(last_known.pending_invocations, new_entries);
+    let (pending_updates, failed_updates, successful_updates, component_version, component_size) =
+        calculate_update_fields(
             last_known.pending_updates,
             last_known.failed_updates,
             last_known.successful_updates,
             last_known.component_version,
             last_known.component_size,
-            &new_entries,
+            new_entries,
         );
 
-        if let Some(TimestampedUpdateDescription {
-            oplog_index,
-            description: UpdateDescription::SnapshotBased { .. },
-            ..
-        }) = pending_updates.front()
-        {
-            deleted_regions.set_override(DeletedRegions::from_regions(vec![
-                OplogRegion::from_index_range(OplogIndex::INITIAL.next()..=*oplog_index),
-            ]));
-        }
+    if let Some(TimestampedUpdateDescription {
+        oplog_index,
+        description: UpdateDescription::SnapshotBased { .. },
+        ..
+    }) = pending_updates.front()
+    {
+        deleted_regions.set_override(DeletedRegions::from_regions(vec![
+            OplogRegion::from_index_range(OplogIndex::INITIAL.next()..=*oplog_index),
+        ]));
+    }
 
-        let (invocation_results, current_idempotency_key) = calculate_invocation_results(
-            last_known.invocation_results,
-            last_known.current_idempotency_key,
-            &new_entries,
-        );
+    let (invocation_results, current_idempotency_key) = calculate_invocation_results(
+        last_known.invocation_results,
+        last_known.current_idempotency_key,
+        new_entries,
+    );
 
-        let total_linear_memory_size =
-            calculate_total_linear_memory_size(last_known.total_linear_memory_size, &new_entries);
+    let total_linear_memory_size =
+        calculate_total_linear_memory_size(last_known.total_linear_memory_size, new_entries);
 
-        let owned_resources = calculate_owned_resources(last_known.owned_resources, &new_entries);
+    let owned_resources = calculate_owned_resources(last_known.owned_resources, new_entries);
 
-        let result = WorkerStatusRecord {
-            oplog_idx: last_oplog_index,
-            status,
-            overridden_retry_config,
-            pending_invocations,
-            deleted_regions,
-            pending_updates,
-            failed_updates,
-            successful_updates,
-            invocation_results,
-            current_idempotency_key,
-            component_version,
-            component_size,
-            owned_resources,
-            total_linear_memory_size,
-        };
-        Ok(result)
+    let last_invoked_function =
+        calculate_last_invoked_function(last_known.last_invoked_function, new_entries);
+
+    let changed_environment =
+        calculate_changed_environment(last_known.changed_environment, new_entries);
+
+    WorkerStatusRecord {
+        oplog_idx: last_oplog_index,
+        status,
+        overridden_retry_config,
+        pending_invocations,
+        deleted_regions,
+        pending_updates,
+        failed_updates,
+        successful_updates,
+        invocation_results,
+        current_idempotency_key,
+        component_version,
+        component_size,
+        owned_resources,
+        total_linear_memory_size,
+        last_invoked_function,
+        changed_environment,
     }
 }
 
@@ -2022,6 +2799,14 @@ fn calculate_latest_worker_status(
             OplogEntry::Restart { .. } => {
                 result = WorkerStatus::Idle;
             }
+            OplogEntry::Snapshot { .. } => {}
+            OplogEntry::NetworkEgressDenied { .. } => {}
+            OplogEntry::Revive { .. } => {
+                result = WorkerStatus::Idle;
+            }
+            OplogEntry::ChangeEnvironment { .. } => {}
+            OplogEntry::InvocationContext { .. } => {}
+            OplogEntry::Forked { .. } => {}
         }
     }
     result
@@ -2030,16 +2815,51 @@ fn calculate_latest_worker_status(
 fn calculate_deleted_regions(
     initial: DeletedRegions,
     entries: &BTreeMap<OplogIndex, OplogEntry>,
+    decode_failures: &[OplogDecodeFailure],
 ) -> DeletedRegions {
-    let mut builder = DeletedRegionsBuilder::from_regions(initial.into_regions());
-    for entry in entries.values() {
-        if let OplogEntry::Jump { jump, .. } = entry {
-            builder.add(jump.clone());
+    let mut builder = DeletedRegionsBuilder::from_deleted_regions(initial);
+    for (idx, entry) in entries {
+        match entry {
+            OplogEntry::Jump { jump, .. } => {
+                builder.add(jump.clone());
+                // Jumps deleting a region before them are skipped on replay too
+                builder.add(OplogRegion::from_index_range(*idx..=*idx));
+            }
+            OplogEntry::Revive { trimmed_region, .. } => {
+                if let Some(trimmed_region) = trimmed_region {
+                    builder.add(trimmed_region.clone());
+                }
+                builder.add(OplogRegion::from_index_range(*idx..=*idx));
+            }
+            _ => {}
         }
     }
+    for corrupted in calculate_corrupted_regions(decode_failures) {
+        builder.add_corrupted(corrupted);
+    }
     builder.build()
 }
 
+/// Groups consecutive oplog entries which could not be decoded into corrupted regions
+fn calculate_corrupted_regions(
+    decode_failures: &[OplogDecodeFailure],
+) -> Vec<CorruptedOplogRegion> {
+    let mut result: Vec<CorruptedOplogRegion> = Vec::new();
+    for failure in decode_failures {
+        match result.last_mut() {
+            Some(last) if last.region.end.next() == failure.oplog_index => {
+                last.region.end = failure.oplog_index;
+            }
+            _ => result.push(CorruptedOplogRegion {
+                region: OplogRegion::from_index_range(failure.oplog_index..=failure.oplog_index),
+                serialization_version: failure.serialization_version,
+                details: failure.error.clone(),
+            }),
+        }
+    }
+    result
+}
+
 fn calculate_overridden_retry_policy(
     initial: Option<RetryConfig>,
     entries: &BTreeMap<OplogIndex, OplogEntry>,
@@ -2053,6 +2873,40 @@ fn calculate_overridden_retry_policy(
     result
 }
 
+/// Gets the output of an invocation from the result of waiting for it
+fn invocation_output(
+    result: Result<LookupResult, RecvError>,
+) -> Result<TypeAnnotatedValue, GolemError> {
+    match result {
+        Ok(LookupResult::Complete(Ok(output))) => Ok(output),
+        Ok(LookupResult::Complete(Err(err))) => Err(err),
+        Ok(LookupResult::Interrupted) => Err(InterruptKind::Interrupt.into()),
+        Ok(LookupResult::Pending) => Err(GolemError::unknown(
+            "Unexpected pending result after invoke",
+        )),
+        Ok(LookupResult::New) => Err(GolemError::unknown(
+            "Unexpected missing result after invoke",
+        )),
+        Err(recv_error) => Err(GolemError::unknown(format!(
+            "Failed waiting for invocation result: {recv_error}"
+        ))),
+    }
+}
+
+/// Adds an invocation to the queue behind all the invocations with the same or higher priority,
+/// so the queue stays ordered by priority and by arrival within each priority.
+fn enqueue_by_priority(
+    queue: &mut VecDeque<TimestampedWorkerInvocation>,
+    invocation: TimestampedWorkerInvocation,
+) {
+    let priority = invocation.invocation.priority();
+    let position = queue
+        .iter()
+        .position(|queued| queued.invocation.priority() > priority)
+        .unwrap_or(queue.len());
+    queue.insert(position, invocation);
+}
+
 fn calculate_pending_invocations(
     initial: Vec<TimestampedWorkerInvocation>,
     entries: &BTreeMap<OplogIndex, OplogEntry>,
@@ -2230,6 +3084,40 @@ fn calculate_total_linear_memory_size(
     result
 }
 
+fn calculate_last_invoked_function(
+    initial: Option<LastInvokedFunction>,
+    entries: &BTreeMap<OplogIndex, OplogEntry>,
+) -> Option<LastInvokedFunction> {
+    let mut result = initial;
+    for entry in entries.values() {
+        if let OplogEntry::ExportedFunctionInvoked {
+            timestamp,
+            function_name,
+            ..
+        } = entry
+        {
+            result = Some(LastInvokedFunction {
+                function_name: function_name.clone(),
+                timestamp: *timestamp,
+            });
+        }
+    }
+    result
+}
+
+fn calculate_changed_environment(
+    initial: Option<(Vec<String>, Vec<(String, String)>)>,
+    entries: &BTreeMap<OplogIndex, OplogEntry>,
+) -> Option<(Vec<String>, Vec<(String, String)>)> {
+    let mut result = initial;
+    for entry in entries.values() {
+        if let OplogEntry::ChangeEnvironment { args, env, .. } = entry {
+            result = Some((args.clone(), env.clone()));
+        }
+    }
+    result
+}
+
 fn calculate_owned_resources(
     initial: HashMap<WorkerResourceId, WorkerResourceDescription>,
     entries: &BTreeMap<OplogIndex, OplogEntry>,
@@ -2274,9 +3162,104 @@ pub fn is_worker_error_retriable(
         WorkerError::InvalidRequest(_) => false,
         WorkerError::StackOverflow => false,
         WorkerError::OutOfMemory => true,
+        WorkerError::MemoryLimitExceeded { .. } => false,
+        WorkerError::FuelBudgetExceeded { .. } => false,
+        WorkerError::ReplayDiverged { .. } => false,
     }
 }
 
 fn is_running_worker_idle(running: &RunningWorker) -> bool {
     running.waiting_for_command.load(Ordering::Acquire) && running.queue.read().unwrap().is_empty()
 }
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use golem_common::model::oplog::{OplogIndex, TimestampedUpdateDescription, UpdateDescription};
+    use golem_common::model::{
+        Timestamp, TimestampedWorkerInvocation, WorkerInvocation, WorkerStatus, WorkerStatusRecord,
+    };
+
+    use crate::worker::RecoveryPriority;
+
+    fn timestamp(millis: u64) -> Option<Timestamp> {
+        Some(Timestamp::from(millis))
+    }
+
+    #[test]
+    fn workers_with_pending_work_are_recovered_first() {
+        let idle = WorkerStatusRecord {
+            status: WorkerStatus::Idle,
+            ..WorkerStatusRecord::default()
+        };
+        let running = WorkerStatusRecord {
+            status: WorkerStatus::Running,
+            ..WorkerStatusRecord::default()
+        };
+        let with_pending_invocation = WorkerStatusRecord {
+            pending_invocations: vec![TimestampedWorkerInvocation {
+                timestamp: Timestamp::from(1),
+                invocation: WorkerInvocation::ManualUpdate { target_version: 1 },
+            }],
+            ..idle.clone()
+        };
+        let with_pending_update = WorkerStatusRecord {
+            pending_updates: vec![TimestampedUpdateDescription {
+                timestamp: Timestamp::from(1),
+                oplog_index: OplogIndex::from_u64(2),
+                description: UpdateDescription::Automatic { target_version: 1 },
+            }]
+            .into(),
+            ..idle.clone()
+        };
+
+        assert!(!RecoveryPriority::new(&idle, timestamp(1000)).has_pending_work);
+        assert!(RecoveryPriority::new(&running, None).has_pending_work);
+        assert!(RecoveryPriority::new(&with_pending_invocation, None).has_pending_work);
+        assert!(RecoveryPriority::new(&with_pending_update, None).has_pending_work);
+
+        let mut workers = vec![
+            (
+                "recently-active-idle",
+                RecoveryPriority::new(&idle, timestamp(1000)),
+            ),
+            (
+                "long-running",
+                RecoveryPriority::new(&running, timestamp(10)),
+            ),
+            ("never-active-idle", RecoveryPriority::new(&idle, None)),
+            (
+                "recently-invoked",
+                RecoveryPriority::new(&with_pending_invocation, timestamp(500)),
+            ),
+            ("long-idle", RecoveryPriority::new(&idle, timestamp(100))),
+        ];
+        RecoveryPriority::sort_for_recovery(&mut workers, |(_, priority)| priority);
+
+        assert_eq!(
+            workers.iter().map(|(name, _)| *name).collect::<Vec<_>>(),
+            vec![
+                "recently-invoked",
+                "long-running",
+                "recently-active-idle",
+                "long-idle",
+                "never-active-idle",
+            ]
+        );
+    }
+
+    #[test]
+    fn priority_labels_distinguish_pending_and_idle_workers() {
+        let idle = WorkerStatusRecord {
+            status: WorkerStatus::Idle,
+            ..WorkerStatusRecord::default()
+        };
+        let running = WorkerStatusRecord {
+            status: WorkerStatus::Running,
+            ..WorkerStatusRecord::default()
+        };
+        assert_eq!(RecoveryPriority::new(&idle, None).label(), "idle");
+        assert_eq!(RecoveryPriority::new(&running, None).label(), "pending");
+    }
+}
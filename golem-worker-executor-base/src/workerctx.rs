@@ -12,14 +12,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
 use std::sync::{Arc, RwLock, Weak};
 
 use async_trait::async_trait;
 use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
 use golem_wasm_rpc::wasmtime::ResourceStore;
 use golem_wasm_rpc::Value;
+use wasmtime::component::Resource;
 use wasmtime::{AsContextMut, ResourceLimiterAsync};
+use wasmtime_wasi::bindings::io::streams::InputStream;
 
+use golem_common::model::component_observability::ComponentObservability;
 use golem_common::model::oplog::WorkerResourceId;
 use golem_common::model::{
     AccountId, ComponentVersion, IdempotencyKey, OwnedWorkerId, WorkerId, WorkerMetadata,
@@ -38,7 +42,11 @@ use crate::services::key_value::KeyValueService;
 use crate::services::oplog::{Oplog, OplogService};
 use crate::services::promise::PromiseService;
 use crate::services::rpc::Rpc;
+use crate::services::saga::SagaService;
 use crate::services::scheduler::SchedulerService;
+use crate::services::secrets::SecretService;
+use crate::services::topics::TopicService;
+use crate::services::virtual_clock::VirtualClockService;
 use crate::services::worker::WorkerService;
 use crate::services::worker_event::WorkerEventService;
 use crate::services::worker_proxy::WorkerProxy;
@@ -79,6 +87,10 @@ pub trait WorkerCtx:
     /// - `worker_service`: The service for managing workers
     /// - `key_value_service`: The service for storing key-value pairs
     /// - `blob_store_service`: The service for storing arbitrary blobs
+    /// - `secret_service`: The service for reading the secrets of the worker's component
+    /// - `topic_service`: The service for publishing to and subscribing to topics
+    /// - `saga_service`: The service storing the sagas of the worker and their compensations
+    /// - `virtual_clock_service`: The service for getting the virtual clock of the worker in test mode
     /// - `event_service`: The service for publishing worker events
     /// - `active_workers`: The service for managing active workers
     /// - `oplog_service`: The service for reading and writing the oplog
@@ -101,6 +113,10 @@ pub trait WorkerCtx:
         >,
         key_value_service: Arc<dyn KeyValueService + Send + Sync>,
         blob_store_service: Arc<dyn BlobStoreService + Send + Sync>,
+        secret_service: Arc<dyn SecretService + Send + Sync>,
+        topic_service: Arc<dyn TopicService + Send + Sync>,
+        saga_service: Arc<dyn SagaService + Send + Sync>,
+        virtual_clock_service: Arc<dyn VirtualClockService + Send + Sync>,
         event_service: Arc<dyn WorkerEventService + Send + Sync>,
         active_workers: Arc<ActiveWorkers<Self>>,
         oplog_service: Arc<dyn OplogService + Send + Sync>,
@@ -130,6 +146,13 @@ pub trait WorkerCtx:
 
     fn component_metadata(&self) -> &ComponentMetadata;
 
+    /// Get the observability settings currently applied to the worker's component
+    fn component_observability(&self) -> ComponentObservability;
+
+    /// Get the maximum amount of fuel a single exported function call of the worker can consume,
+    /// if limited
+    fn invocation_fuel_budget(&self) -> Option<u64>;
+
     /// The WASI exit API can use a special error to exit from the WASM execution. As this depends
     /// on the actual WASI implementation installed by the worker context, this function is used to
     ///determine if an error is an exit error and if so, what the exit code is.
@@ -154,6 +177,10 @@ pub trait WorkerCtx:
 /// more, and once the invocation is finished, returns the remaining amount. The implementation is
 /// supposed to track the amount of borrowed fuel and compare that with the actual fuel levels
 ///passed to these functions.
+///
+/// The only exception is the invocation fuel budget (see `WorkerCtx::invocation_fuel_budget`): while
+/// an exported function with a budget runs, the store's fuel level is lowered to the budget, and it
+/// is restored before `return_fuel` is called.
 #[async_trait]
 pub trait FuelManagement {
     /// Check if the worker is out of fuel
@@ -193,6 +220,9 @@ pub trait InvocationManagement {
     /// Gets the invocation key associated with the current invocation of the worker.
     async fn get_current_idempotency_key(&self) -> Option<IdempotencyKey>;
 
+    /// Sets the context passed by the caller with the current invocation of the worker.
+    async fn set_invocation_context(&mut self, context: BTreeMap<String, String>);
+
     /// Returns whether we are in live mode where we are executing new calls.
     fn is_live(&self) -> bool;
 
@@ -262,6 +292,17 @@ pub trait InvocationHooks {
         consumed_fuel: i64,
         output: TypeAnnotatedValue,
     ) -> Result<(), GolemError>;
+
+    /// Called when a worker invocation returned an input stream, before the invocation is
+    /// considered finished. Reads the stream until its end, forwarding its chunks to the caller
+    /// of the invocation, and drops it.
+    ///
+    /// The chunks are recorded in the oplog so the stream is not read again when the invocation
+    /// is replayed.
+    async fn forward_result_stream(
+        &mut self,
+        stream: Resource<InputStream>,
+    ) -> Result<(), GolemError>;
 }
 
 #[async_trait]
@@ -343,6 +384,22 @@ pub trait ExternalOperations<Ctx: WorkerCtx> {
         store: &mut (impl AsContextMut<Data = Ctx> + Send),
     ) -> Result<RetryDecision, GolemError>;
 
+    /// Called after an invocation of a durable worker succeeded, to save a snapshot of the
+    /// worker's state if one is due. Snapshots let later recoveries skip replaying the oplog
+    /// entries preceding them.
+    async fn take_snapshot_if_due(
+        instance: &wasmtime::component::Instance,
+        store: &mut (impl AsContextMut<Data = Ctx> + Send),
+    ) -> Result<(), GolemError>;
+
+    /// Loads a snapshot of the worker into the instance using its `load-snapshot` export, without
+    /// preparing the instance first. Returns the reason of the failure if it could not be loaded.
+    async fn try_load_snapshot(
+        instance: &wasmtime::component::Instance,
+        store: &mut (impl AsContextMut<Data = Ctx> + Send),
+        data: &[u8],
+    ) -> Option<String>;
+
     /// Records the last known resource limits of a worker without activating it
     async fn record_last_known_limits<T: HasAll<Ctx> + Send + Sync>(
         this: &T,
@@ -14,7 +14,7 @@
 
 use test_r::{inherit_test_dep, test};
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::env;
 use std::io::Write;
 use std::net::SocketAddr;
@@ -27,19 +27,33 @@ use assert2::check;
 use http_02::{Response, StatusCode};
 use redis::Commands;
 
-use golem_api_grpc::proto::golem::worker::v1::{worker_execution_error, ComponentParseFailed};
-use golem_api_grpc::proto::golem::worker::LogEvent;
-use golem_api_grpc::proto::golem::workerexecutor::v1::CompletePromiseRequest;
+use golem_api_grpc::proto::golem::worker::v1::{
+    worker_execution_error, ComponentParseFailed, InvalidRequest,
+};
+use golem_api_grpc::proto::golem::worker::{InvocationContext, LogEvent};
+use golem_api_grpc::proto::golem::workerexecutor::v1::{
+    delete_oplog_region_response, drain_response, fork_worker_response,
+    get_worker_fuel_consumption_response, get_worker_metadata_response,
+    invoke_and_await_worker_response, query_worker_at_oplog_index_response, revive_worker_response,
+    update_worker_environment_response, CompletePromiseRequest, DeleteOplogRegionRequest,
+    DrainPhase, DrainRequest, ForkWorkerRequest, GetWorkerFuelConsumptionRequest,
+    GetWorkersMetadataBulkRequest, InvokeAndAwaitWorkerRequest, QueryWorkerAtOplogIndexRequest,
+    ReviveWorkerRequest, UpdateWorkerEnvironmentRequest,
+};
 use golem_common::model::{
-    AccountId, ComponentId, FilterComparator, IdempotencyKey, PromiseId, ScanCursor,
-    StringFilterComparator, TargetWorkerId, Timestamp, WorkerFilter, WorkerId, WorkerMetadata,
-    WorkerResourceDescription, WorkerStatus,
+    AccountId, ComponentId, FilterComparator, IdempotencyKey, InvocationPriority, PromiseId,
+    ScanCursor, StringFilterComparator, TargetWorkerId, Timestamp, WorkerFilter, WorkerId,
+    WorkerMetadata, WorkerName, WorkerResourceDescription, WorkerStatus,
 };
 use golem_wasm_rpc::Value;
 
 use crate::common::{start, TestContext, TestWorkerExecutor};
 use crate::{LastUniqueId, Tracing, WorkerExecutorTestDependencies};
 use golem_common::model::oplog::{IndexedResourceKey, OplogIndex, WorkerResourceId};
+use golem_common::model::public_oplog::{
+    CreateParameters, ForkedParameters, ImportedFunctionInvokedParameters,
+    InvocationContextParameters, PublicOplogEntry,
+};
 use golem_test_framework::config::TestDependencies;
 use golem_test_framework::dsl::{
     drain_connection, is_worker_execution_error, stdout_event_matching, stdout_events,
@@ -91,6 +105,35 @@ async fn interruption(
     check!(worker_error_message(&result.err().unwrap()).contains("Interrupted via the Golem API"));
 }
 
+#[test]
+#[tracing::instrument]
+async fn invocation_timeout(
+    last_unique_id: &LastUniqueId,
+    deps: &WorkerExecutorTestDependencies,
+    _tracing: &Tracing,
+) {
+    let context = TestContext::new(last_unique_id);
+    let executor = start(deps, &context).await.unwrap();
+
+    let component_id = executor.store_component("interruption").await;
+    let worker_id = executor
+        .start_worker(&component_id, "invocation-timeout-1")
+        .await;
+
+    let result = executor
+        .invoke_and_await_with_timeout(&worker_id, "run", vec![], Duration::from_secs(2))
+        .await;
+
+    drop(executor);
+
+    println!(
+        "result: {:?}",
+        result.as_ref().map_err(worker_error_message)
+    );
+    check!(result.is_err());
+    check!(worker_error_message(&result.err().unwrap()).contains("timed out"));
+}
+
 #[test]
 #[tracing::instrument]
 async fn simulated_crash(
@@ -254,7 +297,7 @@ async fn dynamic_worker_creation(
     let component_id = executor.store_component("environment-service").await;
     let worker_id = WorkerId {
         component_id: component_id.clone(),
-        worker_name: "dynamic-worker-creation-1".to_string(),
+        worker_name: WorkerName::from_existing("dynamic-worker-creation-1"),
     };
 
     let args = executor
@@ -340,101 +383,823 @@ async fn dynamic_worker_creation_without_name(
         .await
         .unwrap();
 
-    drop(executor);
+    drop(executor);
+
+    let env1 = get_env_result(env1);
+    let env2 = get_env_result(env2);
+
+    check!(env1.contains_key("GOLEM_WORKER_NAME"));
+    check!(env1.get("GOLEM_COMPONENT_ID") == Some(&component_id.to_string()));
+    check!(env1.get("GOLEM_COMPONENT_VERSION") == Some(&"0".to_string()));
+    check!(env2.contains_key("GOLEM_WORKER_NAME"));
+    check!(env2.get("GOLEM_COMPONENT_ID") == Some(&component_id.to_string()));
+    check!(env2.get("GOLEM_COMPONENT_VERSION") == Some(&"0".to_string()));
+    check!(env1.get("GOLEM_WORKER_NAME") != env2.get("GOLEM_WORKER_NAME"));
+}
+
+#[test]
+#[tracing::instrument]
+async fn ephemeral_worker_creation_without_name(
+    last_unique_id: &LastUniqueId,
+    deps: &WorkerExecutorTestDependencies,
+) {
+    let context = TestContext::new(last_unique_id);
+    let executor = start(deps, &context).await.unwrap();
+
+    let component_id = executor
+        .store_ephemeral_component("environment-service")
+        .await;
+    let worker_id = TargetWorkerId {
+        component_id: component_id.clone(),
+        worker_name: None,
+    };
+
+    let env1 = executor
+        .invoke_and_await(worker_id.clone(), "golem:it/api.{get-environment}", vec![])
+        .await
+        .unwrap();
+    let env2 = executor
+        .invoke_and_await(worker_id.clone(), "golem:it/api.{get-environment}", vec![])
+        .await
+        .unwrap();
+
+    drop(executor);
+
+    let env1 = get_env_result(env1);
+    let env2 = get_env_result(env2);
+
+    check!(env1.contains_key("GOLEM_WORKER_NAME"));
+    check!(env1.get("GOLEM_COMPONENT_ID") == Some(&component_id.to_string()));
+    check!(env1.get("GOLEM_COMPONENT_VERSION") == Some(&"0".to_string()));
+    check!(env2.contains_key("GOLEM_WORKER_NAME"));
+    check!(env2.get("GOLEM_COMPONENT_ID") == Some(&component_id.to_string()));
+    check!(env2.get("GOLEM_COMPONENT_VERSION") == Some(&"0".to_string()));
+    check!(env1.get("GOLEM_WORKER_NAME") != env2.get("GOLEM_WORKER_NAME"));
+}
+
+#[test]
+#[tracing::instrument]
+async fn ephemeral_worker_creation_with_name_is_not_persistent(
+    last_unique_id: &LastUniqueId,
+    deps: &WorkerExecutorTestDependencies,
+) {
+    let context = TestContext::new(last_unique_id);
+    let executor = start(deps, &context).await.unwrap();
+
+    let component_id = executor.store_ephemeral_component("counters").await;
+    let worker_id = TargetWorkerId {
+        component_id: component_id.clone(),
+        worker_name: Some(WorkerName::from_existing("test")),
+    };
+
+    let _ = executor
+        .invoke_and_await(
+            worker_id.clone(),
+            "rpc:counters/api.{inc-global-by}",
+            vec![Value::U64(2)],
+        )
+        .await
+        .unwrap();
+
+    let result = executor
+        .invoke_and_await(
+            worker_id.clone(),
+            "rpc:counters/api.{get-global-value}",
+            vec![],
+        )
+        .await
+        .unwrap();
+
+    drop(executor);
+
+    check!(result == vec![Value::U64(0)]);
+}
+
+#[test]
+#[tracing::instrument]
+async fn promise(
+    last_unique_id: &LastUniqueId,
+    deps: &WorkerExecutorTestDependencies,
+    _tracing: &Tracing,
+) {
+    let context = TestContext::new(last_unique_id);
+    let executor = start(deps, &context).await.unwrap();
+
+    let component_id = executor.store_component("promise").await;
+    let worker_id = executor.start_worker(&component_id, "promise-1").await;
+
+    let executor_clone = executor.clone();
+    let worker_id_clone = worker_id.clone();
+    let fiber = tokio::spawn(async move {
+        executor_clone
+            .invoke_and_await(&worker_id_clone, "run", vec![])
+            .await
+    });
+
+    sleep(Duration::from_secs(10)).await;
+
+    executor
+        .client()
+        .await
+        .expect("Failed to get client")
+        .complete_promise(CompletePromiseRequest {
+            promise_id: Some(
+                PromiseId {
+                    worker_id: worker_id.clone(),
+                    oplog_idx: OplogIndex::from_u64(3),
+                }
+                .into(),
+            ),
+            data: vec![42],
+            account_id: Some(
+                AccountId {
+                    value: "test-account".to_string(),
+                }
+                .into(),
+            ),
+        })
+        .await
+        .unwrap();
+
+    let result = fiber.await.unwrap();
+
+    drop(executor);
+
+    check!(result == Ok(vec![Value::List(vec![Value::U8(42)])]));
+}
+
+#[test]
+#[tracing::instrument]
+async fn get_workers_metadata_bulk(
+    last_unique_id: &LastUniqueId,
+    deps: &WorkerExecutorTestDependencies,
+    _tracing: &Tracing,
+) {
+    let context = TestContext::new(last_unique_id);
+    let executor = start(deps, &context).await.unwrap();
+
+    let component_id = executor.store_component("shopping-cart").await;
+    let worker_id1 = executor.start_worker(&component_id, "bulk-1").await;
+    let worker_id2 = executor.start_worker(&component_id, "bulk-2").await;
+    let missing_worker_id = WorkerId {
+        component_id: component_id.clone(),
+        worker_name: WorkerName::from_existing("bulk-missing"),
+    };
+
+    let response = executor
+        .client()
+        .await
+        .expect("Failed to get client")
+        .get_workers_metadata_bulk(GetWorkersMetadataBulkRequest {
+            worker_ids: vec![
+                worker_id2.clone().into(),
+                missing_worker_id.into(),
+                worker_id1.clone().into(),
+            ],
+            account_id: Some(
+                AccountId {
+                    value: "test-account".to_string(),
+                }
+                .into(),
+            ),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+
+    drop(executor);
+
+    let results = response
+        .results
+        .into_iter()
+        .map(|response| response.result.unwrap())
+        .collect::<Vec<_>>();
+
+    check!(results.len() == 3);
+    check!(matches!(
+        &results[0],
+        get_worker_metadata_response::Result::Success(metadata)
+            if metadata.worker_id == Some(worker_id2.into())
+    ));
+    check!(matches!(
+        &results[1],
+        get_worker_metadata_response::Result::Failure(err)
+            if matches!(err.error, Some(worker_execution_error::Error::WorkerNotFound(_)))
+    ));
+    check!(matches!(
+        &results[2],
+        get_worker_metadata_response::Result::Success(metadata)
+            if metadata.worker_id == Some(worker_id1.into())
+    ));
+}
+
+#[test]
+#[tracing::instrument]
+async fn drain(
+    last_unique_id: &LastUniqueId,
+    deps: &WorkerExecutorTestDependencies,
+    _tracing: &Tracing,
+) {
+    let context = TestContext::new(last_unique_id);
+    let executor = start(deps, &context).await.unwrap();
+
+    let component_id = executor.store_component("shopping-cart").await;
+    let worker_id = executor.start_worker(&component_id, "drain-1").await;
+
+    let _ = executor
+        .invoke_and_await(
+            &worker_id,
+            "golem:it/api.{initialize-cart}",
+            vec![Value::String("test-user-1".to_string())],
+        )
+        .await;
+
+    let mut progress = executor
+        .client()
+        .await
+        .expect("Failed to get client")
+        .drain(DrainRequest {
+            deadline_millis: Some(10000),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+
+    let mut phases = Vec::new();
+    while let Some(response) = progress.message().await.unwrap() {
+        match response.result {
+            Some(drain_response::Result::Progress(progress)) => phases.push(progress.phase()),
+            other => panic!("Unexpected drain response: {other:?}"),
+        }
+    }
+
+    let result = executor
+        .invoke_and_await(&worker_id, "golem:it/api.{get-cart-contents}", vec![])
+        .await;
+
+    drop(executor);
+
+    check!(phases.last() == Some(&DrainPhase::Drained));
+    check!(result.is_err());
+}
+
+#[test]
+#[tracing::instrument]
+async fn worker_fuel_consumption(
+    last_unique_id: &LastUniqueId,
+    deps: &WorkerExecutorTestDependencies,
+    _tracing: &Tracing,
+) {
+    let context = TestContext::new(last_unique_id);
+    let executor = start(deps, &context).await.unwrap();
+
+    let component_id = executor.store_component("shopping-cart").await;
+    let worker_id = executor.start_worker(&component_id, "fuel-1").await;
+
+    let _ = executor
+        .invoke_and_await(
+            &worker_id,
+            "golem:it/api.{initialize-cart}",
+            vec![Value::String("test-user-1".to_string())],
+        )
+        .await;
+    let _ = executor
+        .invoke_and_await(&worker_id, "golem:it/api.{get-cart-contents}", vec![])
+        .await;
+
+    let response = executor
+        .client()
+        .await
+        .expect("Failed to get client")
+        .get_worker_fuel_consumption(GetWorkerFuelConsumptionRequest {
+            worker_id: Some(worker_id.clone().into()),
+            account_id: Some(
+                AccountId {
+                    value: "test-account".to_string(),
+                }
+                .into(),
+            ),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+
+    drop(executor);
+
+    let Some(get_worker_fuel_consumption_response::Result::Success(consumption)) = response.result
+    else {
+        panic!("Unexpected fuel consumption response: {response:?}");
+    };
+    check!(consumption.completed_invocations == 2);
+    check!(consumption.total_consumed_fuel > 0);
+    check!(consumption.max_consumed_fuel <= consumption.total_consumed_fuel);
+}
+
+/// Items for the cart of the shopping cart component
+fn cart_items(count: u32) -> Vec<Value> {
+    (0..count)
+        .map(|idx| {
+            Value::Record(vec![
+                Value::String(format!("G100{idx}")),
+                Value::String(format!("Golem T-Shirt {idx}")),
+                Value::F32(100.0),
+                Value::U32(idx + 1),
+            ])
+        })
+        .collect()
+}
+
+/// Starts a shopping cart worker, initializes its cart and adds the items to it one by one.
+/// Returns the worker's id and oplog.
+async fn start_shopping_cart(
+    executor: &TestWorkerExecutor,
+    component_id: &ComponentId,
+    name: &str,
+    items: &[Value],
+) -> (WorkerId, Vec<PublicOplogEntry>) {
+    let worker_id = executor.start_worker(component_id, name).await;
+
+    let _ = executor
+        .invoke_and_await(
+            &worker_id,
+            "golem:it/api.{initialize-cart}",
+            vec![Value::String("test-user-1".to_string())],
+        )
+        .await;
+    for item in items {
+        let _ = executor
+            .invoke_and_await(&worker_id, "golem:it/api.{add-item}", vec![item.clone()])
+            .await;
+    }
+
+    let oplog = executor.get_oplog(&worker_id, OplogIndex::INITIAL).await;
+    (worker_id, oplog)
+}
+
+/// Gets the oplog index of the `n`th (counting from 0) oplog entry matching `f`
+fn nth_oplog_index(oplog: &[PublicOplogEntry], n: usize, f: fn(&PublicOplogEntry) -> bool) -> u64 {
+    oplog
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| f(entry))
+        .nth(n)
+        .map(|(idx, _)| idx as u64 + 1)
+        .expect("oplog entry not found")
+}
+
+#[test]
+#[tracing::instrument]
+async fn query_worker_at_oplog_index(
+    last_unique_id: &LastUniqueId,
+    deps: &WorkerExecutorTestDependencies,
+    _tracing: &Tracing,
+) {
+    let context = TestContext::new(last_unique_id);
+    let executor = start(deps, &context).await.unwrap();
+
+    let component_id = executor.store_component("shopping-cart").await;
+    let items = cart_items(2);
+    let (worker_id, oplog) =
+        start_shopping_cart(&executor, &component_id, "time-travel-1", &items).await;
+    let first_item_completed = nth_oplog_index(&oplog, 1, |entry| {
+        matches!(entry, PublicOplogEntry::ExportedFunctionCompleted(_))
+    });
+    let second_item_invoked = nth_oplog_index(&oplog, 2, |entry| {
+        matches!(entry, PublicOplogEntry::ExportedFunctionInvoked(_))
+    });
+
+    let query = |oplog_index: u64| {
+        let executor = &executor;
+        let worker_id = &worker_id;
+        async move {
+            executor
+                .client()
+                .await
+                .expect("Failed to get client")
+                .query_worker_at_oplog_index(QueryWorkerAtOplogIndexRequest {
+                    worker_id: Some(worker_id.clone().into()),
+                    account_id: Some(
+                        AccountId {
+                            value: "test-account".to_string(),
+                        }
+                        .into(),
+                    ),
+                    oplog_index,
+                    name: "golem:it/api.{get-cart-contents}".to_string(),
+                    input: vec![],
+                    timeout_millis: None,
+                })
+                .await
+                .unwrap()
+                .into_inner()
+        }
+    };
+
+    let at_first_item = query(first_item_completed).await;
+    // In the middle of the second add-item invocation the state is restored to before it
+    let during_second_item = query(second_item_invoked).await;
+
+    // The queries run in a sandboxed instance and leave the worker's oplog untouched
+    let oplog_after_queries = executor.get_oplog(&worker_id, OplogIndex::INITIAL).await;
+
+    let contents = executor
+        .invoke_and_await(&worker_id, "golem:it/api.{get-cart-contents}", vec![])
+        .await
+        .unwrap();
+
+    drop(executor);
+
+    for (response, expected_index) in [
+        (at_first_item, first_item_completed),
+        (during_second_item, second_item_invoked - 1),
+    ] {
+        let Some(query_worker_at_oplog_index_response::Result::Success(success)) = response.result
+        else {
+            panic!("Unexpected query response: {response:?}");
+        };
+        check!(success.oplog_index == expected_index);
+        let output = Value::try_from(success.output.unwrap()).unwrap();
+        check!(output == Value::Tuple(vec![Value::List(vec![items[0].clone()])]));
+    }
+    check!(oplog_after_queries.len() == oplog.len());
+    check!(matches!(&contents[..], [Value::List(items)] if items.len() == 2));
+}
+
+#[test]
+#[tracing::instrument]
+async fn fork_worker(
+    last_unique_id: &LastUniqueId,
+    deps: &WorkerExecutorTestDependencies,
+    _tracing: &Tracing,
+) {
+    let context = TestContext::new(last_unique_id);
+    let executor = start(deps, &context).await.unwrap();
+
+    let component_id = executor.store_component("shopping-cart").await;
+    let items = cart_items(3);
+    let (worker_id, oplog) =
+        start_shopping_cart(&executor, &component_id, "fork-1", &items[..2]).await;
+    let forked_worker_id = WorkerId {
+        component_id: component_id.clone(),
+        worker_name: WorkerName::from_existing("fork-1-forked"),
+    };
+    let first_item_completed = nth_oplog_index(&oplog, 1, |entry| {
+        matches!(entry, PublicOplogEntry::ExportedFunctionCompleted(_))
+    });
+
+    let response = executor
+        .client()
+        .await
+        .expect("Failed to get client")
+        .fork_worker(ForkWorkerRequest {
+            source_worker_id: Some(worker_id.clone().into()),
+            target_worker_id: Some(forked_worker_id.clone().into()),
+            account_id: Some(
+                AccountId {
+                    value: "test-account".to_string(),
+                }
+                .into(),
+            ),
+            oplog_index: first_item_completed,
+        })
+        .await
+        .unwrap()
+        .into_inner();
+
+    let _ = executor
+        .invoke_and_await(
+            &forked_worker_id,
+            "golem:it/api.{add-item}",
+            vec![items[2].clone()],
+        )
+        .await;
+    let forked_contents = executor
+        .invoke_and_await(
+            &forked_worker_id,
+            "golem:it/api.{get-cart-contents}",
+            vec![],
+        )
+        .await
+        .unwrap();
+    let contents = executor
+        .invoke_and_await(&worker_id, "golem:it/api.{get-cart-contents}", vec![])
+        .await
+        .unwrap();
+    let forked_oplog = executor
+        .get_oplog(&forked_worker_id, OplogIndex::INITIAL)
+        .await;
+
+    let other_account_response = executor
+        .client()
+        .await
+        .expect("Failed to get client")
+        .fork_worker(ForkWorkerRequest {
+            source_worker_id: Some(worker_id.clone().into()),
+            target_worker_id: Some(
+                WorkerId {
+                    component_id: component_id.clone(),
+                    worker_name: WorkerName::from_existing("fork-1-other-account"),
+                }
+                .into(),
+            ),
+            account_id: Some(
+                AccountId {
+                    value: "other-account".to_string(),
+                }
+                .into(),
+            ),
+            oplog_index: first_item_completed,
+        })
+        .await
+        .unwrap()
+        .into_inner();
+
+    drop(executor);
+
+    let Some(fork_worker_response::Result::Success(success)) = response.result else {
+        panic!("Unexpected fork response: {response:?}");
+    };
+    check!(success.oplog_index == first_item_completed);
+    check!(forked_contents == vec![Value::List(vec![items[0].clone(), items[2].clone()])]);
+    check!(contents == vec![Value::List(vec![items[0].clone(), items[1].clone()])]);
+
+    check!(matches!(
+        &forked_oplog[0],
+        PublicOplogEntry::Create(CreateParameters { parent: None, .. })
+    ));
+    check!(forked_oplog.iter().any(|entry| matches!(
+        entry,
+        PublicOplogEntry::Forked(ForkedParameters { source_worker_id, source_oplog_index, .. })
+            if *source_worker_id == worker_id
+                && *source_oplog_index == OplogIndex::from_u64(first_item_completed)
+    )));
+    check!(matches!(
+        other_account_response.result,
+        Some(fork_worker_response::Result::Failure(_))
+    ));
+}
+
+#[test]
+#[tracing::instrument]
+async fn delete_oplog_region(
+    last_unique_id: &LastUniqueId,
+    deps: &WorkerExecutorTestDependencies,
+    _tracing: &Tracing,
+) {
+    let context = TestContext::new(last_unique_id);
+    let executor = start(deps, &context).await.unwrap();
+
+    let component_id = executor.store_component("shopping-cart").await;
+    let items = cart_items(3);
+    let (worker_id, oplog) =
+        start_shopping_cart(&executor, &component_id, "delete-region-1", &items).await;
+    let second_item_invoked = nth_oplog_index(&oplog, 2, |entry| {
+        matches!(entry, PublicOplogEntry::ExportedFunctionInvoked(_))
+    });
+    let second_item_completed = nth_oplog_index(&oplog, 2, |entry| {
+        matches!(entry, PublicOplogEntry::ExportedFunctionCompleted(_))
+    });
+
+    let delete = |start: u64, end: u64| {
+        let executor = &executor;
+        let worker_id = &worker_id;
+        async move {
+            executor
+                .client()
+                .await
+                .expect("Failed to get client")
+                .delete_oplog_region(DeleteOplogRegionRequest {
+                    worker_id: Some(worker_id.clone().into()),
+                    account_id: Some(
+                        AccountId {
+                            value: "test-account".to_string(),
+                        }
+                        .into(),
+                    ),
+                    start,
+                    end,
+                })
+                .await
+                .unwrap()
+                .into_inner()
+        }
+    };
+
+    // Deleting only the end of an invocation is rejected
+    let partial = delete(second_item_invoked + 1, second_item_completed).await;
+    let whole = delete(second_item_invoked, second_item_completed).await;
+
+    let contents = executor
+        .invoke_and_await(&worker_id, "golem:it/api.{get-cart-contents}", vec![])
+        .await
+        .unwrap();
+
+    drop(executor);
+
+    check!(matches!(
+        partial.result,
+        Some(delete_oplog_region_response::Result::Failure(_))
+    ));
+    check!(matches!(
+        whole.result,
+        Some(delete_oplog_region_response::Result::Success(_))
+    ));
+    check!(contents == vec![Value::List(vec![items[0].clone(), items[2].clone()])]);
+}
+
+#[test]
+#[tracing::instrument]
+async fn revive_failed_worker(
+    last_unique_id: &LastUniqueId,
+    deps: &WorkerExecutorTestDependencies,
+    _tracing: &Tracing,
+) {
+    let context = TestContext::new(last_unique_id);
+    let executor = start(deps, &context).await.unwrap();
+
+    let component_id = executor.store_component("failing-component").await;
+    let worker_id = executor.start_worker(&component_id, "revive-1").await;
+
+    let _ = executor
+        .invoke_and_await(&worker_id, "golem:component/api.{add}", vec![Value::U64(5)])
+        .await;
+    let failed = executor
+        .invoke_and_await(
+            &worker_id,
+            "golem:component/api.{add}",
+            vec![Value::U64(50)],
+        )
+        .await;
+    let (failed_metadata, _) = executor.get_worker_metadata(&worker_id).await.unwrap();
+
+    let revive = |confirm: bool| {
+        let executor = &executor;
+        let worker_id = &worker_id;
+        async move {
+            executor
+                .client()
+                .await
+                .expect("Failed to get client")
+                .revive_worker(ReviveWorkerRequest {
+                    worker_id: Some(worker_id.clone().into()),
+                    account_id: Some(
+                        AccountId {
+                            value: "test-account".to_string(),
+                        }
+                        .into(),
+                    ),
+                    trim_failed_invocation: true,
+                    confirm,
+                })
+                .await
+                .unwrap()
+                .into_inner()
+        }
+    };
+
+    let unconfirmed = revive(false).await;
+    let revived = revive(true).await;
+    let (revived_metadata, _) = executor.get_worker_metadata(&worker_id).await.unwrap();
 
-    let env1 = get_env_result(env1);
-    let env2 = get_env_result(env2);
+    let result = executor
+        .invoke_and_await(&worker_id, "golem:component/api.{get}", vec![])
+        .await;
 
-    check!(env1.contains_key("GOLEM_WORKER_NAME"));
-    check!(env1.get("GOLEM_COMPONENT_ID") == Some(&component_id.to_string()));
-    check!(env1.get("GOLEM_COMPONENT_VERSION") == Some(&"0".to_string()));
-    check!(env2.contains_key("GOLEM_WORKER_NAME"));
-    check!(env2.get("GOLEM_COMPONENT_ID") == Some(&component_id.to_string()));
-    check!(env2.get("GOLEM_COMPONENT_VERSION") == Some(&"0".to_string()));
-    check!(env1.get("GOLEM_WORKER_NAME") != env2.get("GOLEM_WORKER_NAME"));
+    drop(executor);
+
+    check!(failed.is_err());
+    check!(failed_metadata.last_known_status.status == WorkerStatus::Failed);
+    check!(matches!(
+        unconfirmed.result,
+        Some(revive_worker_response::Result::Failure(_))
+    ));
+    check!(matches!(
+        revived.result,
+        Some(revive_worker_response::Result::Success(_))
+    ));
+    check!(revived_metadata.last_known_status.status == WorkerStatus::Idle);
+    check!(result == Ok(vec![Value::U64(5)]));
 }
 
 #[test]
 #[tracing::instrument]
-async fn ephemeral_worker_creation_without_name(
+async fn update_environment_of_running_worker(
     last_unique_id: &LastUniqueId,
     deps: &WorkerExecutorTestDependencies,
+    _tracing: &Tracing,
 ) {
     let context = TestContext::new(last_unique_id);
     let executor = start(deps, &context).await.unwrap();
 
-    let component_id = executor
-        .store_ephemeral_component("environment-service")
+    let component_id = executor.store_component("environment-service").await;
+    let worker_id = executor
+        .start_worker(&component_id, "update-environment-1")
         .await;
-    let worker_id = TargetWorkerId {
-        component_id: component_id.clone(),
-        worker_name: None,
-    };
 
     let env1 = executor
-        .invoke_and_await(worker_id.clone(), "golem:it/api.{get-environment}", vec![])
+        .invoke_and_await(&worker_id, "golem:it/api.{get-environment}", vec![])
         .await
         .unwrap();
+
+    let updated = executor
+        .client()
+        .await
+        .expect("Failed to get client")
+        .update_worker_environment(UpdateWorkerEnvironmentRequest {
+            worker_id: Some(worker_id.clone().into()),
+            account_id: Some(
+                AccountId {
+                    value: "test-account".to_string(),
+                }
+                .into(),
+            ),
+            env: HashMap::from_iter(vec![("TEST_ENV".to_string(), "updated".to_string())]),
+            args: vec![],
+            update_args: false,
+        })
+        .await
+        .unwrap()
+        .into_inner();
+
     let env2 = executor
-        .invoke_and_await(worker_id.clone(), "golem:it/api.{get-environment}", vec![])
+        .invoke_and_await(&worker_id, "golem:it/api.{get-environment}", vec![])
         .await
         .unwrap();
+    let (metadata, _) = executor.get_worker_metadata(&worker_id).await.unwrap();
 
     drop(executor);
 
     let env1 = get_env_result(env1);
     let env2 = get_env_result(env2);
 
-    check!(env1.contains_key("GOLEM_WORKER_NAME"));
-    check!(env1.get("GOLEM_COMPONENT_ID") == Some(&component_id.to_string()));
-    check!(env1.get("GOLEM_COMPONENT_VERSION") == Some(&"0".to_string()));
-    check!(env2.contains_key("GOLEM_WORKER_NAME"));
-    check!(env2.get("GOLEM_COMPONENT_ID") == Some(&component_id.to_string()));
-    check!(env2.get("GOLEM_COMPONENT_VERSION") == Some(&"0".to_string()));
-    check!(env1.get("GOLEM_WORKER_NAME") != env2.get("GOLEM_WORKER_NAME"));
+    check!(matches!(
+        updated.result,
+        Some(update_worker_environment_response::Result::Success(_))
+    ));
+    check!(!env1.contains_key("TEST_ENV"));
+    check!(env2.get("TEST_ENV") == Some(&"updated".to_string()));
+    check!(env2.get("GOLEM_WORKER_NAME") == Some(&"update-environment-1".to_string()));
+    check!(metadata
+        .env
+        .contains(&("TEST_ENV".to_string(), "updated".to_string())));
 }
 
 #[test]
 #[tracing::instrument]
-async fn ephemeral_worker_creation_with_name_is_not_persistent(
+async fn updated_environment_survives_executor_restart(
     last_unique_id: &LastUniqueId,
     deps: &WorkerExecutorTestDependencies,
+    _tracing: &Tracing,
 ) {
     let context = TestContext::new(last_unique_id);
     let executor = start(deps, &context).await.unwrap();
 
-    let component_id = executor.store_ephemeral_component("counters").await;
-    let worker_id = TargetWorkerId {
-        component_id: component_id.clone(),
-        worker_name: Some("test".to_string()),
-    };
+    let component_id = executor.store_component("environment-service").await;
+    let worker_id = executor
+        .start_worker(&component_id, "update-environment-2")
+        .await;
 
     let _ = executor
-        .invoke_and_await(
-            worker_id.clone(),
-            "rpc:counters/api.{inc-global-by}",
-            vec![Value::U64(2)],
-        )
+        .client()
+        .await
+        .expect("Failed to get client")
+        .update_worker_environment(UpdateWorkerEnvironmentRequest {
+            worker_id: Some(worker_id.clone().into()),
+            account_id: Some(
+                AccountId {
+                    value: "test-account".to_string(),
+                }
+                .into(),
+            ),
+            env: HashMap::from_iter(vec![("TEST_ENV".to_string(), "updated".to_string())]),
+            args: vec![],
+            update_args: false,
+        })
         .await
         .unwrap();
 
-    let result = executor
-        .invoke_and_await(
-            worker_id.clone(),
-            "rpc:counters/api.{get-global-value}",
-            vec![],
-        )
+    drop(executor);
+    let executor = start(deps, &context).await.unwrap();
+
+    let (metadata, _) = executor.get_worker_metadata(&worker_id).await.unwrap();
+    let env = executor
+        .invoke_and_await(&worker_id, "golem:it/api.{get-environment}", vec![])
         .await
         .unwrap();
 
     drop(executor);
 
-    check!(result == vec![Value::U64(0)]);
+    let env = get_env_result(env);
+
+    check!(metadata
+        .env
+        .contains(&("TEST_ENV".to_string(), "updated".to_string())));
+    check!(env.get("TEST_ENV") == Some(&"updated".to_string()));
 }
 
 #[test]
 #[tracing::instrument]
-async fn promise(
+async fn invocation_context_is_recorded(
     last_unique_id: &LastUniqueId,
     deps: &WorkerExecutorTestDependencies,
     _tracing: &Tracing,
@@ -442,47 +1207,65 @@ async fn promise(
     let context = TestContext::new(last_unique_id);
     let executor = start(deps, &context).await.unwrap();
 
-    let component_id = executor.store_component("promise").await;
-    let worker_id = executor.start_worker(&component_id, "promise-1").await;
-
-    let executor_clone = executor.clone();
-    let worker_id_clone = worker_id.clone();
-    let fiber = tokio::spawn(async move {
-        executor_clone
-            .invoke_and_await(&worker_id_clone, "run", vec![])
-            .await
-    });
-
-    sleep(Duration::from_secs(10)).await;
+    let component_id = executor.store_component("environment-service").await;
+    let worker_id = executor
+        .start_worker(&component_id, "invocation-context-1")
+        .await;
 
-    executor
+    let response = executor
         .client()
         .await
         .expect("Failed to get client")
-        .complete_promise(CompletePromiseRequest {
-            promise_id: Some(
-                PromiseId {
-                    worker_id: worker_id.clone(),
-                    oplog_idx: OplogIndex::from_u64(3),
-                }
-                .into(),
-            ),
-            data: vec![42],
+        .invoke_and_await_worker(InvokeAndAwaitWorkerRequest {
+            worker_id: Some(worker_id.clone().into_target_worker_id().into()),
+            name: "golem:it/api.{get-environment}".to_string(),
+            input: vec![],
+            idempotency_key: Some(IdempotencyKey::fresh().into()),
             account_id: Some(
                 AccountId {
                     value: "test-account".to_string(),
                 }
                 .into(),
             ),
+            account_limits: None,
+            context: Some(InvocationContext {
+                parent: None,
+                args: vec![],
+                env: HashMap::new(),
+                values: HashMap::from_iter(vec![
+                    ("locale".to_string(), "en-US".to_string()),
+                    ("tenant".to_string(), "acme".to_string()),
+                ]),
+            }),
+            priority: InvocationPriority::Normal.into(),
+            timeout_millis: None,
         })
         .await
-        .unwrap();
+        .unwrap()
+        .into_inner();
 
-    let result = fiber.await.unwrap();
+    let oplog = executor.get_oplog(&worker_id, OplogIndex::INITIAL).await;
 
     drop(executor);
 
-    check!(result == Ok(vec![Value::List(vec![Value::U8(42)])]));
+    let recorded_context = oplog.iter().find_map(|entry| match entry {
+        PublicOplogEntry::InvocationContext(InvocationContextParameters { context, .. }) => {
+            Some(context.clone())
+        }
+        _ => None,
+    });
+
+    check!(matches!(
+        response.result,
+        Some(invoke_and_await_worker_response::Result::Success(_))
+    ));
+    check!(
+        recorded_context
+            == Some(BTreeMap::from([
+                ("locale".to_string(), "en-US".to_string()),
+                ("tenant".to_string(), "acme".to_string()),
+            ]))
+    );
 }
 
 #[test]
@@ -625,7 +1408,7 @@ async fn get_metadata_from_worker(
 
         Value::Record(vec![
             component_id_val,
-            Value::String(worker_id.worker_name.clone()),
+            Value::String(worker_id.worker_name.to_string()),
         ])
     }
 
@@ -691,6 +1474,185 @@ async fn get_metadata_from_worker(
     drop(executor);
 }
 
+#[test]
+#[tracing::instrument]
+async fn get_workers_from_worker_is_replayed_after_restart(
+    last_unique_id: &LastUniqueId,
+    deps: &WorkerExecutorTestDependencies,
+) {
+    let context = TestContext::new(last_unique_id);
+    let executor = start(deps, &context).await.unwrap();
+
+    let component_id = executor.store_component("runtime-service").await;
+
+    let worker_id1 = executor
+        .start_worker(&component_id, "runtime-service-replay-1")
+        .await;
+
+    let component_id_val = {
+        let (high, low) = component_id.0.as_u64_pair();
+        Value::Record(vec![Value::Record(vec![Value::U64(high), Value::U64(low)])])
+    };
+
+    let result1 = executor
+        .invoke_and_await(
+            worker_id1.clone(),
+            "golem:it/api.{get-workers}",
+            vec![
+                component_id_val.clone(),
+                Value::Option(None),
+                Value::Bool(true),
+            ],
+        )
+        .await
+        .unwrap();
+
+    let _worker_id2 = executor
+        .start_worker(&component_id, "runtime-service-replay-2")
+        .await;
+
+    drop(executor);
+    let executor = start(deps, &context).await.unwrap();
+
+    // Restarting replays the first invocation, which must see the recorded single worker
+    let result2 = executor
+        .invoke_and_await(
+            worker_id1.clone(),
+            "golem:it/api.{get-workers}",
+            vec![component_id_val, Value::Option(None), Value::Bool(true)],
+        )
+        .await
+        .unwrap();
+
+    let oplog = executor.get_oplog(&worker_id1, OplogIndex::INITIAL).await;
+
+    drop(executor);
+
+    let recorded_pages = oplog
+        .iter()
+        .filter(|entry| {
+            matches!(
+                entry,
+                PublicOplogEntry::ImportedFunctionInvoked(ImportedFunctionInvokedParameters {
+                    function_name,
+                    ..
+                }) if function_name == "golem::api::get-workers::get-next"
+            )
+        })
+        .count();
+
+    check!(matches!(result1.first(), Some(Value::List(list)) if list.len() == 1));
+    check!(matches!(result2.first(), Some(Value::List(list)) if list.len() == 2));
+    check!(recorded_pages >= 2);
+}
+
+#[test]
+#[tracing::instrument]
+async fn get_self_metadata_returns_the_args_and_env_of_the_worker(
+    last_unique_id: &LastUniqueId,
+    deps: &WorkerExecutorTestDependencies,
+) {
+    let context = TestContext::new(last_unique_id);
+    let executor = start(deps, &context).await.unwrap();
+
+    let component_id = executor.store_component("runtime-service").await;
+
+    let mut env = HashMap::new();
+    env.insert("TEST_ENV".to_string(), "test-value".to_string());
+    let worker_id = executor
+        .start_worker_with(
+            &component_id,
+            "runtime-service-3",
+            vec!["test-arg".to_string()],
+            env,
+        )
+        .await;
+
+    let result = executor
+        .invoke_and_await(&worker_id, "golem:it/api.{get-self-metadata}", vec![])
+        .await
+        .unwrap();
+
+    drop(executor);
+
+    match result.first() {
+        Some(Value::Record(values)) if values.len() == 6 => {
+            check!(values[1] == Value::List(vec![Value::String("test-arg".to_string())]));
+            check!(match &values[2] {
+                Value::List(items) => items.contains(&Value::Tuple(vec![
+                    Value::String("TEST_ENV".to_string()),
+                    Value::String("test-value".to_string()),
+                ])),
+                _ => false,
+            });
+            check!(values[4] == Value::U64(0));
+        }
+        _ => {
+            check!(false);
+        }
+    }
+}
+
+#[test]
+#[tracing::instrument]
+async fn get_self_metadata_is_replayed_after_restart(
+    last_unique_id: &LastUniqueId,
+    deps: &WorkerExecutorTestDependencies,
+) {
+    let context = TestContext::new(last_unique_id);
+    let executor = start(deps, &context).await.unwrap();
+
+    let component_id = executor.store_component("runtime-service").await;
+    let worker_id = executor
+        .start_worker_with(
+            &component_id,
+            "runtime-service-4",
+            vec!["test-arg".to_string()],
+            HashMap::new(),
+        )
+        .await;
+
+    let result1 = executor
+        .invoke_and_await(&worker_id, "golem:it/api.{get-self-metadata}", vec![])
+        .await
+        .unwrap();
+
+    drop(executor);
+    let executor = start(deps, &context).await.unwrap();
+
+    // Restarting replays the first invocation from the recorded snapshot
+    let result2 = executor
+        .invoke_and_await(&worker_id, "golem:it/api.{get-self-metadata}", vec![])
+        .await
+        .unwrap();
+
+    let oplog = executor.get_oplog(&worker_id, OplogIndex::INITIAL).await;
+
+    drop(executor);
+
+    let recorded_calls = oplog
+        .iter()
+        .filter(|entry| {
+            matches!(
+                entry,
+                PublicOplogEntry::ImportedFunctionInvoked(ImportedFunctionInvokedParameters {
+                    function_name,
+                    ..
+                }) if function_name == "golem::api::get-self-metadata"
+            )
+        })
+        .count();
+
+    let args = |result: &Vec<Value>| match result.first() {
+        Some(Value::Record(values)) if values.len() == 6 => Some(values[1].clone()),
+        _ => None,
+    };
+
+    check!(recorded_calls == 2);
+    check!(args(&result1) == Some(Value::List(vec![Value::String("test-arg".to_string())])));
+    check!(args(&result2) == args(&result1));
+}
+
 #[test]
 #[tracing::instrument]
 async fn invoking_with_same_idempotency_key_is_idempotent(
@@ -1000,7 +1962,7 @@ async fn delete_worker(
             &worker_id.component_id,
             Some(WorkerFilter::new_name(
                 StringFilterComparator::Equal,
-                worker_id.worker_name.clone(),
+                worker_id.worker_name.to_string(),
             )),
             ScanCursor::default(),
             10,
@@ -1073,7 +2035,7 @@ async fn get_workers(
             &component_id,
             Some(WorkerFilter::new_name(
                 StringFilterComparator::Equal,
-                worker_id.worker_name.clone(),
+                worker_id.worker_name.to_string(),
             )),
             1,
             &mut executor,
@@ -1465,6 +2427,31 @@ async fn recreating_a_worker_after_it_got_deleted_with_a_different_version(
     );
 }
 
+#[test]
+#[tracing::instrument]
+async fn creating_a_worker_with_an_invalid_name_fails(
+    last_unique_id: &LastUniqueId,
+    deps: &WorkerExecutorTestDependencies,
+) {
+    let context = TestContext::new(last_unique_id);
+    let executor = start(deps, &context).await.unwrap();
+
+    let component_id = executor.store_component("write-stdout").await;
+    let result = executor
+        .try_start_worker(&component_id, "invalid:name")
+        .await;
+
+    check!(result.is_err());
+    check!(is_worker_execution_error(
+        &result.err().unwrap(),
+        &worker_execution_error::Error::InvalidRequest(InvalidRequest {
+            details:
+                "Worker name must contain only alphanumeric characters, underscores, and dashes"
+                    .to_string()
+        })
+    ));
+}
+
 #[test]
 #[tracing::instrument]
 async fn trying_to_use_an_old_wasm_provides_good_error_message(
@@ -1715,7 +2702,7 @@ async fn long_running_poll_loop_interrupting_and_resuming_by_second_invocation(
             &worker_id.component_id,
             Some(WorkerFilter::new_name(
                 StringFilterComparator::Equal,
-                worker_id.worker_name.clone(),
+                worker_id.worker_name.to_string(),
             )),
         )
         .await;
@@ -1730,7 +2717,7 @@ async fn long_running_poll_loop_interrupting_and_resuming_by_second_invocation(
             &worker_id.component_id,
             Some(WorkerFilter::new_name(
                 StringFilterComparator::Equal,
-                worker_id.worker_name.clone(),
+                worker_id.worker_name.to_string(),
             )),
         )
         .await;
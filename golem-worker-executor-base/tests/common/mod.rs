@@ -7,6 +7,7 @@ use prometheus::Registry;
 
 use crate::{LastUniqueId, WorkerExecutorPerTestDependencies, WorkerExecutorTestDependencies};
 use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::Ordering;
 use std::sync::{Arc, RwLock, Weak};
@@ -21,8 +22,8 @@ use golem_worker_executor_base::error::GolemError;
 use golem_worker_executor_base::services::golem_config::{
     BlobStorageConfig, CompiledComponentServiceConfig, CompiledComponentServiceEnabledConfig,
     ComponentServiceConfig, ComponentServiceLocalConfig, GolemConfig, IndexedStorageConfig,
-    KeyValueStorageConfig, LocalFileSystemBlobStorageConfig, MemoryConfig,
-    ShardManagerServiceConfig, WorkerServiceGrpcConfig,
+    KeyValueStorageConfig, LocalFileSystemBlobStorageConfig, ShardManagerServiceConfig,
+    WorkerServiceGrpcConfig,
 };
 
 use golem_worker_executor_base::durable_host::{
@@ -35,11 +36,17 @@ use golem_worker_executor_base::services::active_workers::ActiveWorkers;
 use golem_worker_executor_base::services::blob_store::BlobStoreService;
 use golem_worker_executor_base::services::component::{ComponentMetadata, ComponentService};
 use golem_worker_executor_base::services::key_value::KeyValueService;
+use golem_worker_executor_base::services::log_export::LogExportService;
 use golem_worker_executor_base::services::oplog::{Oplog, OplogService};
 use golem_worker_executor_base::services::promise::PromiseService;
+use golem_worker_executor_base::services::saga::SagaService;
 use golem_worker_executor_base::services::scheduler::SchedulerService;
+use golem_worker_executor_base::services::secrets::SecretService;
 use golem_worker_executor_base::services::shard::ShardService;
 use golem_worker_executor_base::services::shard_manager::ShardManagerService;
+use golem_worker_executor_base::services::topics::TopicService;
+use golem_worker_executor_base::services::usage::UsageService;
+use golem_worker_executor_base::services::virtual_clock::VirtualClockService;
 use golem_worker_executor_base::services::worker::WorkerService;
 use golem_worker_executor_base::services::worker_activator::WorkerActivator;
 use golem_worker_executor_base::services::worker_event::WorkerEventService;
@@ -63,6 +70,7 @@ use golem_api_grpc::proto::golem::workerexecutor::v1::{
     GetRunningWorkersMetadataRequest, GetRunningWorkersMetadataSuccessResponse,
     GetWorkersMetadataRequest, GetWorkersMetadataSuccessResponse,
 };
+use golem_common::model::component_observability::ComponentObservability;
 use golem_common::model::oplog::WorkerResourceId;
 use golem_test_framework::components::component_compilation_service::ComponentCompilationService;
 use golem_test_framework::components::rdb::Rdb;
@@ -72,6 +80,7 @@ use golem_test_framework::components::shard_manager::ShardManager;
 use golem_test_framework::components::worker_executor_cluster::WorkerExecutorCluster;
 use golem_test_framework::config::TestDependencies;
 use golem_test_framework::dsl::to_worker_metadata;
+use golem_worker_executor_base::preview2::api_v1_2;
 use golem_worker_executor_base::preview2::golem;
 use golem_worker_executor_base::preview2::golem::api1_1_0_rc1;
 use golem_worker_executor_base::services::events::Events;
@@ -85,8 +94,9 @@ use golem_worker_executor_base::services::worker_proxy::WorkerProxy;
 use golem_worker_executor_base::worker::{RetryDecision, Worker};
 use tonic::transport::Channel;
 use tracing::{debug, error, info};
-use wasmtime::component::{Instance, Linker, ResourceAny};
+use wasmtime::component::{Instance, Linker, Resource, ResourceAny};
 use wasmtime::{AsContextMut, Engine, ResourceLimiterAsync};
+use wasmtime_wasi::bindings::io::streams::InputStream;
 
 pub struct TestWorkerExecutor {
     handle: Option<JoinHandle<Result<(), String>>>,
@@ -282,6 +292,17 @@ pub async fn start_limited(
     deps: &WorkerExecutorTestDependencies,
     context: &TestContext,
     system_memory_override: Option<u64>,
+) -> anyhow::Result<TestWorkerExecutor> {
+    start_customized(deps, context, |config| {
+        config.memory.system_memory_override = system_memory_override;
+    })
+    .await
+}
+
+pub async fn start_customized(
+    deps: &WorkerExecutorTestDependencies,
+    context: &TestContext,
+    customize: impl FnOnce(&mut GolemConfig),
 ) -> anyhow::Result<TestWorkerExecutor> {
     let redis = deps.redis();
     let redis_monitor = deps.redis_monitor();
@@ -290,7 +311,7 @@ pub async fn start_limited(
     println!("Using Redis on port {}", redis.public_port());
 
     let prometheus = golem_worker_executor_base::metrics::register_all();
-    let config = GolemConfig {
+    let mut config = GolemConfig {
         key_value_storage: KeyValueStorageConfig::Redis(RedisConfig {
             port: redis.public_port(),
             key_prefix: context.redis_prefix(),
@@ -306,7 +327,7 @@ pub async fn start_limited(
             root: Path::new("data/components").to_path_buf(),
         }),
         compiled_component_service: CompiledComponentServiceConfig::Enabled(
-            CompiledComponentServiceEnabledConfig {},
+            CompiledComponentServiceEnabledConfig::default(),
         ),
         shard_manager_service: ShardManagerServiceConfig::SingleShard,
         public_worker_api: WorkerServiceGrpcConfig {
@@ -314,12 +335,9 @@ pub async fn start_limited(
             port: context.grpc_port(),
             access_token: "03494299-B515-4427-8C37-4C1C915679B7".to_string(),
         },
-        memory: MemoryConfig {
-            system_memory_override,
-            ..Default::default()
-        },
         ..Default::default()
     };
+    customize(&mut config);
 
     let handle = Handle::current();
 
@@ -458,6 +476,21 @@ impl ExternalOperations<TestWorkerCtx> for TestWorkerCtx {
         DurableWorkerCtx::<TestWorkerCtx>::prepare_instance(worker_id, instance, store).await
     }
 
+    async fn take_snapshot_if_due(
+        instance: &Instance,
+        store: &mut (impl AsContextMut<Data = TestWorkerCtx> + Send),
+    ) -> Result<(), GolemError> {
+        DurableWorkerCtx::<TestWorkerCtx>::take_snapshot_if_due(instance, store).await
+    }
+
+    async fn try_load_snapshot(
+        instance: &Instance,
+        store: &mut (impl AsContextMut<Data = TestWorkerCtx> + Send),
+        data: &[u8],
+    ) -> Option<String> {
+        DurableWorkerCtx::<TestWorkerCtx>::try_load_snapshot(instance, store, data).await
+    }
+
     async fn record_last_known_limits<T: HasAll<TestWorkerCtx> + Send + Sync>(
         this: &T,
         account_id: &AccountId,
@@ -495,6 +528,10 @@ impl InvocationManagement for TestWorkerCtx {
         self.durable_ctx.get_current_idempotency_key().await
     }
 
+    async fn set_invocation_context(&mut self, context: BTreeMap<String, String>) {
+        self.durable_ctx.set_invocation_context(context).await
+    }
+
     fn is_live(&self) -> bool {
         self.durable_ctx.is_live()
     }
@@ -562,6 +599,13 @@ impl InvocationHooks for TestWorkerCtx {
             .on_invocation_success(full_function_name, function_input, consumed_fuel, output)
             .await
     }
+
+    async fn forward_result_stream(
+        &mut self,
+        stream: Resource<InputStream>,
+    ) -> Result<(), GolemError> {
+        self.durable_ctx.forward_result_stream(stream).await
+    }
 }
 
 #[async_trait]
@@ -628,6 +672,10 @@ impl WorkerCtx for TestWorkerCtx {
         worker_enumeration_service: Arc<dyn WorkerEnumerationService + Send + Sync>,
         key_value_service: Arc<dyn KeyValueService + Send + Sync>,
         blob_store_service: Arc<dyn BlobStoreService + Send + Sync>,
+        secret_service: Arc<dyn SecretService + Send + Sync>,
+        topic_service: Arc<dyn TopicService + Send + Sync>,
+        saga_service: Arc<dyn SagaService + Send + Sync>,
+        virtual_clock_service: Arc<dyn VirtualClockService + Send + Sync>,
         event_service: Arc<dyn WorkerEventService + Send + Sync>,
         _active_workers: Arc<ActiveWorkers<TestWorkerCtx>>,
         oplog_service: Arc<dyn OplogService + Send + Sync>,
@@ -650,6 +698,10 @@ impl WorkerCtx for TestWorkerCtx {
             worker_enumeration_service,
             key_value_service,
             blob_store_service,
+            secret_service,
+            topic_service,
+            saga_service,
+            virtual_clock_service,
             event_service,
             oplog_service,
             oplog,
@@ -682,6 +734,14 @@ impl WorkerCtx for TestWorkerCtx {
         self.durable_ctx.component_metadata()
     }
 
+    fn component_observability(&self) -> ComponentObservability {
+        self.durable_ctx.component_observability()
+    }
+
+    fn invocation_fuel_budget(&self) -> Option<u64> {
+        self.durable_ctx.invocation_fuel_budget()
+    }
+
     fn is_exit(error: &Error) -> Option<i32> {
         DurableWorkerCtx::<TestWorkerCtx>::is_exit(error)
     }
@@ -741,7 +801,11 @@ impl Bootstrap<TestWorkerCtx> for ServerBootstrap {
         &self,
         golem_config: &GolemConfig,
     ) -> Arc<ActiveWorkers<TestWorkerCtx>> {
-        Arc::new(ActiveWorkers::<TestWorkerCtx>::new(&golem_config.memory))
+        Arc::new(ActiveWorkers::<TestWorkerCtx>::new(
+            &golem_config.memory,
+            &golem_config.ephemeral,
+            &golem_config.invocation_scheduling,
+        ))
     }
 
     async fn create_services(
@@ -760,11 +824,17 @@ impl Bootstrap<TestWorkerCtx> for ServerBootstrap {
         shard_service: Arc<dyn ShardService + Send + Sync>,
         key_value_service: Arc<dyn KeyValueService + Send + Sync>,
         blob_store_service: Arc<dyn BlobStoreService + Send + Sync>,
+        secret_service: Arc<dyn SecretService + Send + Sync>,
+        topic_service: Arc<dyn TopicService + Send + Sync>,
+        saga_service: Arc<dyn SagaService + Send + Sync>,
+        virtual_clock_service: Arc<dyn VirtualClockService + Send + Sync>,
         worker_activator: Arc<dyn WorkerActivator + Send + Sync>,
         oplog_service: Arc<dyn OplogService + Send + Sync>,
         scheduler_service: Arc<dyn SchedulerService + Send + Sync>,
         worker_proxy: Arc<dyn WorkerProxy + Send + Sync>,
         events: Arc<Events>,
+        log_export_service: Arc<dyn LogExportService + Send + Sync>,
+        usage_service: Arc<dyn UsageService + Send + Sync>,
     ) -> anyhow::Result<All<TestWorkerCtx>> {
         let rpc = Arc::new(DirectWorkerInvocationRpc::new(
             Arc::new(RemoteInvocationRpc::new(
@@ -785,10 +855,16 @@ impl Bootstrap<TestWorkerCtx> for ServerBootstrap {
             shard_manager_service.clone(),
             key_value_service.clone(),
             blob_store_service.clone(),
+            secret_service.clone(),
+            topic_service.clone(),
+            saga_service.clone(),
+            virtual_clock_service.clone(),
             oplog_service.clone(),
             scheduler_service.clone(),
             worker_activator.clone(),
             events.clone(),
+            log_export_service.clone(),
+            usage_service.clone(),
             (),
         ));
         Ok(All::new(
@@ -806,12 +882,18 @@ impl Bootstrap<TestWorkerCtx> for ServerBootstrap {
             shard_service,
             key_value_service,
             blob_store_service,
+            secret_service,
+            topic_service,
+            saga_service,
+            virtual_clock_service,
             oplog_service,
             rpc,
             scheduler_service,
             worker_activator,
             worker_proxy,
             events.clone(),
+            log_export_service,
+            usage_service,
             (),
         ))
     }
@@ -820,6 +902,7 @@ impl Bootstrap<TestWorkerCtx> for ServerBootstrap {
         let mut linker = create_linker(engine, get_durable_ctx)?;
         api0_2_0::host::add_to_linker_get_host(&mut linker, get_durable_ctx)?;
         api1_1_0_rc1::host::add_to_linker_get_host(&mut linker, get_durable_ctx)?;
+        api_v1_2::golem::api::self_metadata::add_to_linker_get_host(&mut linker, get_durable_ctx)?;
         golem_wasm_rpc::golem::rpc::types::add_to_linker_get_host(&mut linker, get_durable_ctx)?;
         Ok(linker)
     }
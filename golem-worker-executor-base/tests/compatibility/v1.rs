@@ -31,9 +31,10 @@ use golem_common::model::oplog::{
 };
 use golem_common::model::regions::{DeletedRegions, OplogRegion};
 use golem_common::model::{
-    AccountId, ComponentId, FailedUpdateRecord, IdempotencyKey, OwnedWorkerId, PromiseId,
-    ScheduledAction, ShardId, SuccessfulUpdateRecord, Timestamp, TimestampedWorkerInvocation,
-    WorkerId, WorkerInvocation, WorkerResourceDescription, WorkerStatus, WorkerStatusRecord,
+    AccountId, ComponentId, FailedUpdateRecord, IdempotencyKey, InvocationPriority, OwnedWorkerId,
+    PromiseId, ScheduledAction, ShardId, SuccessfulUpdateRecord, Timestamp,
+    TimestampedWorkerInvocation, WorkerId, WorkerInvocation, WorkerName, WorkerResourceDescription,
+    WorkerStatus, WorkerStatusRecord,
 };
 use golem_common::serialization::{deserialize, serialize};
 use golem_wasm_ast::analysis::{
@@ -59,7 +60,7 @@ use golem_worker_executor_base::services::blob_store;
 use golem_worker_executor_base::services::promise::RedisPromiseState;
 use golem_worker_executor_base::services::rpc::RpcError;
 use golem_worker_executor_base::services::worker_proxy::WorkerProxyError;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::Debug;
 use std::io::Write;
 use std::path::Path;
@@ -271,6 +272,8 @@ pub fn timestamped_worker_invocation() {
             },
             full_function_name: "function-name".to_string(),
             function_input: vec![Value::Bool(true)],
+            priority: InvocationPriority::Normal,
+            context: BTreeMap::new(),
         },
     };
     let twi2 = TimestampedWorkerInvocation {
@@ -401,9 +404,14 @@ pub fn oplog_payload() {
         payload_id: PayloadId(Uuid::parse_str("4B29BF7C-13F6-4E37-AC03-830B81EAD478").unwrap()),
         md5_hash: vec![1, 2, 3, 4],
     };
+    let op3 = OplogPayload::Encrypted {
+        nonce: vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11],
+        payload: Box::new(OplogPayload::Inline(vec![0, 1, 2, 3, 4])),
+    };
     let mut mint = Mint::new("tests/goldenfiles");
     backward_compatible("oplog_payload_inline", &mut mint, op1);
     backward_compatible("oplog_payload_external", &mut mint, op2);
+    backward_compatible("oplog_payload_encrypted", &mut mint, op3);
 }
 
 #[test]
@@ -448,6 +456,8 @@ pub fn worker_status_record() {
             },
         )]),
         oplog_idx: OplogIndex::from_u64(10000),
+        last_invoked_function: None,
+        changed_environment: None,
     };
 
     let wsr2 = WorkerStatusRecord {
@@ -484,6 +494,8 @@ pub fn worker_status_record() {
             },
         )]),
         oplog_idx: OplogIndex::from_u64(10000),
+        last_invoked_function: None,
+        changed_environment: None,
     };
 
     let mut mint = Mint::new("tests/goldenfiles");
@@ -534,7 +546,7 @@ pub fn worker_id() {
             component_id: ComponentId(
                 Uuid::parse_str("4B29BF7C-13F6-4E37-AC03-830B81EAD478").unwrap(),
             ),
-            worker_name: "worker_name".to_string(),
+            worker_name: WorkerName::from_existing("worker_name"),
         },
     );
 }
@@ -546,7 +558,7 @@ pub fn promise_id() {
             component_id: ComponentId(
                 Uuid::parse_str("4B29BF7C-13F6-4E37-AC03-830B81EAD478").unwrap(),
             ),
-            worker_name: "worker_name".to_string(),
+            worker_name: WorkerName::from_existing("worker_name"),
         },
         oplog_idx: OplogIndex::from_u64(100),
     };
@@ -566,7 +578,7 @@ pub fn scheduled_action() {
                 component_id: ComponentId(
                     Uuid::parse_str("4B29BF7C-13F6-4E37-AC03-830B81EAD478").unwrap(),
                 ),
-                worker_name: "worker_name".to_string(),
+                worker_name: WorkerName::from_existing("worker_name"),
             },
             oplog_idx: OplogIndex::from_u64(100),
         },
@@ -580,7 +592,7 @@ pub fn scheduled_action() {
                 component_id: ComponentId(
                     Uuid::parse_str("4B29BF7C-13F6-4E37-AC03-830B81EAD478").unwrap(),
                 ),
-                worker_name: "worker_name".to_string(),
+                worker_name: WorkerName::from_existing("worker_name"),
             },
         },
         last_oplog_index: OplogIndex::from_u64(100),
@@ -662,7 +674,7 @@ pub fn oplog_entry() {
             component_id: ComponentId(
                 Uuid::parse_str("4B29BF7C-13F6-4E37-AC03-830B81EAD478").unwrap(),
             ),
-            worker_name: "worker_name".to_string(),
+            worker_name: WorkerName::from_existing("worker_name"),
         },
         component_version: 0,
         args: vec!["hello".to_string(), "world".to_string()],
@@ -683,7 +695,7 @@ pub fn oplog_entry() {
             component_id: ComponentId(
                 Uuid::parse_str("4B29BF7C-13F6-4E37-AC03-830B81EAD478").unwrap(),
             ),
-            worker_name: "worker_name".to_string(),
+            worker_name: WorkerName::from_existing("worker_name"),
         },
         component_version: 0,
         args: vec!["hello".to_string(), "world".to_string()],
@@ -698,7 +710,7 @@ pub fn oplog_entry() {
             component_id: ComponentId(
                 Uuid::parse_str("90BB3957-2C4E-4711-A488-902B7018100F").unwrap(),
             ),
-            worker_name: "parent_worker_name".to_string(),
+            worker_name: WorkerName::from_existing("parent_worker_name"),
         }),
         component_size: 100_000_000,
         initial_total_linear_memory_size: 100_000_000,
@@ -786,6 +798,8 @@ pub fn oplog_entry() {
             },
             full_function_name: "function-name".to_string(),
             function_input: vec![Value::Bool(true)],
+            priority: InvocationPriority::Normal,
+            context: BTreeMap::new(),
         },
     };
 
@@ -910,7 +924,7 @@ pub fn shard_id() {
 pub fn golem_error() {
     let wid = WorkerId {
         component_id: ComponentId(Uuid::parse_str("4B29BF7C-13F6-4E37-AC03-830B81EAD478").unwrap()),
-        worker_name: "worker_name".to_string(),
+        worker_name: WorkerName::from_existing("worker_name"),
     };
     let pid = PromiseId {
         worker_id: wid.clone(),
@@ -17,13 +17,23 @@ use test_r::{inherit_test_dep, test};
 use crate::{common, LastUniqueId, Tracing, WorkerExecutorTestDependencies};
 use assert2::check;
 use async_mutex::Mutex;
+use golem_api_grpc::proto::golem::workerexecutor::v1::{
+    dry_run_update_worker_response, DryRunUpdateWorkerRequest, UpdateDryRunResult,
+};
+use golem_common::model::oplog::OplogIndex;
+use golem_common::model::public_oplog::PublicOplogEntry;
+use golem_common::model::{AccountId, ComponentVersion, WorkerId};
 use golem_test_framework::dsl::TestDslUnsafe;
 use golem_wasm_rpc::Value;
+use golem_worker_executor_base::services::golem_config::{
+    GolemConfig, PrewarmConfig, SnapshotConfig,
+};
 use http_02::{Response, StatusCode};
 use log::info;
 use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::spawn;
 use tokio::task::JoinHandle;
 use tonic::transport::Body;
@@ -715,3 +725,209 @@ async fn manual_update_on_idle_with_failing_load(
     check!(metadata.last_known_status.failed_updates.len() == 1);
     check!(metadata.last_known_status.successful_updates.is_empty());
 }
+
+async fn dry_run_update(
+    executor: &common::TestWorkerExecutor,
+    worker_id: &WorkerId,
+    target_version: ComponentVersion,
+) -> UpdateDryRunResult {
+    let response = executor
+        .client()
+        .await
+        .expect("Failed to get client")
+        .dry_run_update_worker(DryRunUpdateWorkerRequest {
+            worker_id: Some(worker_id.clone().into()),
+            target_version,
+            account_id: Some(
+                AccountId {
+                    value: "test-account".to_string(),
+                }
+                .into(),
+            ),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+
+    match response.result {
+        Some(dry_run_update_worker_response::Result::Success(result)) => result,
+        other => panic!("Unexpected dry run response: {other:?}"),
+    }
+}
+
+#[test]
+#[tracing::instrument]
+async fn manual_update_dry_run(
+    last_unique_id: &LastUniqueId,
+    deps: &WorkerExecutorTestDependencies,
+    _tracing: &Tracing,
+) {
+    let context = common::TestContext::new(last_unique_id);
+    let executor = common::start(deps, &context).await.unwrap();
+
+    let component_id = executor.store_unique_component("update-test-v3").await;
+    let worker_id = executor
+        .start_worker(&component_id, "manual_update_dry_run")
+        .await;
+
+    let _ = executor
+        .invoke_and_await(
+            &worker_id,
+            "golem:component/api.{set}",
+            vec![Value::U64(11)],
+        )
+        .await
+        .unwrap();
+    let before_dry_run = executor
+        .invoke_and_await(&worker_id, "golem:component/api.{get}", vec![])
+        .await
+        .unwrap();
+
+    let compatible_version = executor
+        .update_component(&component_id, "update-test-v3")
+        .await;
+    let failing_version = executor
+        .update_component(&component_id, "update-test-v4")
+        .await;
+
+    let compatible = dry_run_update(&executor, &worker_id, compatible_version).await;
+    let failing = dry_run_update(&executor, &worker_id, failing_version).await;
+
+    let after_dry_run = executor
+        .invoke_and_await(&worker_id, "golem:component/api.{get}", vec![])
+        .await
+        .unwrap();
+    let (metadata, _) = executor.get_worker_metadata(&worker_id).await.unwrap();
+
+    drop(executor);
+
+    // Explanation: the dry runs save a fresh snapshot of the worker, which can be loaded by
+    // another v3 version, but v4's load function always fails. Neither dry run changes the worker.
+    check!(compatible.would_succeed);
+    check!(compatible.error.is_none());
+    check!(!failing.would_succeed);
+    check!(failing.error.is_some());
+    check!(before_dry_run == vec![Value::U64(11)]);
+    check!(after_dry_run == before_dry_run);
+    check!(metadata.last_known_status.component_version == 0);
+    check!(metadata.last_known_status.pending_updates.is_empty());
+    check!(metadata.last_known_status.failed_updates.is_empty());
+    check!(metadata.last_known_status.successful_updates.is_empty());
+}
+
+#[test]
+#[tracing::instrument]
+async fn prewarmed_component_follows_updates(
+    last_unique_id: &LastUniqueId,
+    deps: &WorkerExecutorTestDependencies,
+    _tracing: &Tracing,
+) {
+    let context = common::TestContext::new(last_unique_id);
+    let component_id = {
+        let executor = common::start(deps, &context).await.unwrap();
+        executor.store_unique_component("update-test-v1").await
+    };
+    let executor = common::start_customized(deps, &context, |config| {
+        config.prewarm = PrewarmConfig {
+            components: vec![component_id.clone()],
+            refresh_interval: Duration::from_millis(100),
+        };
+    })
+    .await
+    .unwrap();
+
+    let worker_id1 = executor
+        .start_worker(&component_id, "prewarmed_component_follows_updates_1")
+        .await;
+    let f3_v1 = executor
+        .invoke_and_await(&worker_id1, "golem:component/api.{f3}", vec![])
+        .await;
+
+    let target_version = executor
+        .update_component(&component_id, "update-test-v2")
+        .await;
+
+    let worker_id2 = executor
+        .start_worker(&component_id, "prewarmed_component_follows_updates_2")
+        .await;
+    let f4_v2 = executor
+        .invoke_and_await(&worker_id2, "golem:component/api.{f4}", vec![])
+        .await;
+
+    // Restarting the first worker must not pick up the prewarmed instance of the new version
+    executor.interrupt(&worker_id1).await;
+    let f4_v1 = executor
+        .invoke_and_await(&worker_id1, "golem:component/api.{f4}", vec![])
+        .await;
+    let (metadata1, _) = executor.get_worker_metadata(&worker_id1).await.unwrap();
+    let (metadata2, _) = executor.get_worker_metadata(&worker_id2).await.unwrap();
+
+    drop(executor);
+
+    check!(f3_v1.is_ok());
+    check!(f4_v2 == Ok(vec![Value::U64(11)]));
+    check!(f4_v1.is_err());
+    check!(metadata1.last_known_status.component_version == 0);
+    check!(metadata2.last_known_status.component_version == target_version);
+}
+
+#[test]
+#[tracing::instrument]
+async fn recover_from_snapshot(
+    last_unique_id: &LastUniqueId,
+    deps: &WorkerExecutorTestDependencies,
+    _tracing: &Tracing,
+) {
+    let context = common::TestContext::new(last_unique_id);
+    let customize = |config: &mut GolemConfig| {
+        config.snapshot = SnapshotConfig {
+            enabled: true,
+            oplog_interval: 1,
+        };
+    };
+    let executor = common::start_customized(deps, &context, customize)
+        .await
+        .unwrap();
+
+    let component_id = executor.store_unique_component("update-test-v3").await;
+    let worker_id = executor
+        .start_worker(&component_id, "recover_from_snapshot")
+        .await;
+
+    let _ = executor
+        .invoke_and_await(
+            &worker_id,
+            "golem:component/api.{set}",
+            vec![Value::U64(11)],
+        )
+        .await
+        .unwrap();
+    // The snapshot is saved before this invocation starts, so its entries are replayed after
+    // loading the snapshot
+    let _ = executor
+        .invoke_and_await(
+            &worker_id,
+            "golem:component/api.{set}",
+            vec![Value::U64(22)],
+        )
+        .await
+        .unwrap();
+    let oplog = executor.get_oplog(&worker_id, OplogIndex::INITIAL).await;
+
+    drop(executor);
+    let executor = common::start_customized(deps, &context, customize)
+        .await
+        .unwrap();
+
+    let after_recovery = executor
+        .invoke_and_await(&worker_id, "golem:component/api.{get}", vec![])
+        .await
+        .unwrap();
+
+    drop(executor);
+
+    check!(oplog
+        .iter()
+        .any(|entry| matches!(entry, PublicOplogEntry::Snapshot(_))));
+    check!(after_recovery == vec![Value::U64(22)]);
+}
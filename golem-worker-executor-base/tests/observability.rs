@@ -18,7 +18,7 @@ use crate::common::{start, TestContext};
 use crate::{LastUniqueId, Tracing, WorkerExecutorTestDependencies};
 use golem_common::model::oplog::OplogIndex;
 use golem_common::model::public_oplog::{ExportedFunctionInvokedParameters, PublicOplogEntry};
-use golem_common::model::{IdempotencyKey, WorkerId};
+use golem_common::model::{IdempotencyKey, WorkerId, WorkerName};
 use golem_test_framework::dsl::TestDslUnsafe;
 
 inherit_test_dep!(WorkerExecutorTestDependencies);
@@ -39,7 +39,7 @@ async fn get_oplog_1(
 
     let worker_id = WorkerId {
         component_id,
-        worker_name: "getoplog1".to_string(),
+        worker_name: WorkerName::from_existing("getoplog1"),
     };
 
     let idempotency_key1 = IdempotencyKey::fresh();
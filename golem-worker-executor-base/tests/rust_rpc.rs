@@ -17,9 +17,16 @@ use test_r::{inherit_test_dep, test};
 use crate::common::{start, TestContext};
 use crate::{LastUniqueId, Tracing, WorkerExecutorTestDependencies};
 use assert2::check;
+use golem_api_grpc::proto::golem::worker::InvocationContext;
+use golem_api_grpc::proto::golem::workerexecutor::v1::{
+    invoke_and_await_worker_response, InvokeAndAwaitWorkerRequest,
+};
+use golem_common::model::oplog::OplogIndex;
+use golem_common::model::public_oplog::{InvocationContextParameters, PublicOplogEntry};
+use golem_common::model::{AccountId, IdempotencyKey, InvocationPriority, ScanCursor};
 use golem_test_framework::dsl::{worker_error_message, TestDslUnsafe};
 use golem_wasm_rpc::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::time::SystemTime;
 use tracing::{debug, info};
 
@@ -96,6 +103,99 @@ async fn auction_example_1(
     );
 }
 
+#[test]
+#[tracing::instrument]
+async fn invocation_context_is_propagated_through_rpc(
+    last_unique_id: &LastUniqueId,
+    deps: &WorkerExecutorTestDependencies,
+    _tracing: &Tracing,
+) {
+    let context = TestContext::new(last_unique_id);
+    let executor = start(deps, &context).await.unwrap();
+
+    let registry_component_id = executor.store_component("auction_registry_composed").await;
+    let auction_component_id = executor.store_component("auction").await;
+
+    let mut env = HashMap::new();
+    env.insert(
+        "AUCTION_COMPONENT_ID".to_string(),
+        auction_component_id.to_string(),
+    );
+    let registry_worker_id = executor
+        .start_worker_with(
+            &registry_component_id,
+            "auction-registry-context",
+            vec![],
+            env,
+        )
+        .await;
+
+    let expiration = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let input: Vec<Value> = vec![
+        Value::String("test-auction".to_string()),
+        Value::String("this is a test".to_string()),
+        Value::F32(100.0),
+        Value::U64(expiration + 600),
+    ];
+    let response = executor
+        .client()
+        .await
+        .expect("Failed to get client")
+        .invoke_and_await_worker(InvokeAndAwaitWorkerRequest {
+            worker_id: Some(registry_worker_id.clone().into_target_worker_id().into()),
+            name: "auction:registry/api.{create-auction}".to_string(),
+            input: input.into_iter().map(|value| value.into()).collect(),
+            idempotency_key: Some(IdempotencyKey::fresh().into()),
+            account_id: Some(
+                AccountId {
+                    value: "test-account".to_string(),
+                }
+                .into(),
+            ),
+            account_limits: None,
+            context: Some(InvocationContext {
+                parent: None,
+                args: vec![],
+                env: HashMap::new(),
+                values: HashMap::from_iter(vec![("tenant".to_string(), "acme".to_string())]),
+            }),
+            priority: InvocationPriority::Normal.into(),
+            timeout_millis: None,
+        })
+        .await
+        .unwrap()
+        .into_inner();
+
+    let (_, auction_workers) = executor
+        .get_workers_metadata(&auction_component_id, None, ScanCursor::default(), 10, true)
+        .await;
+    let auction_worker_id = auction_workers
+        .first()
+        .map(|(metadata, _)| metadata.worker_id.clone())
+        .expect("the registry did not create an auction worker");
+    let oplog = executor
+        .get_oplog(&auction_worker_id, OplogIndex::INITIAL)
+        .await;
+
+    drop(executor);
+
+    let recorded_context = oplog.iter().find_map(|entry| match entry {
+        PublicOplogEntry::InvocationContext(InvocationContextParameters { context, .. }) => {
+            Some(context.clone())
+        }
+        _ => None,
+    });
+
+    check!(matches!(
+        response.result,
+        Some(invoke_and_await_worker_response::Result::Success(_))
+    ));
+    check!(recorded_context == Some(BTreeMap::from([("tenant".to_string(), "acme".to_string())])));
+}
+
 #[test]
 #[tracing::instrument]
 async fn auction_example_2(
@@ -14,14 +14,17 @@
 
 use test_r::{inherit_test_dep, test, timeout};
 
-use crate::common::{start, start_limited, TestContext, TestWorkerExecutor};
+use crate::common::{start, start_customized, start_limited, TestContext, TestWorkerExecutor};
 use crate::{LastUniqueId, Tracing, WorkerExecutorTestDependencies};
 use assert2::check;
 use futures_util::stream::FuturesUnordered;
 use futures_util::StreamExt;
-use golem_common::model::ComponentId;
-use golem_test_framework::dsl::TestDslUnsafe;
+use golem_common::model::{ComponentId, IdempotencyKey, WorkerStatus};
+use golem_test_framework::dsl::{worker_error_message, TestDslUnsafe};
 use golem_wasm_rpc::Value;
+use golem_worker_executor_base::services::golem_config::{
+    ComponentInvocationFuelBudget, ComponentLinearMemoryLimit,
+};
 use std::future::Future;
 use std::time::Duration;
 use tokio::spawn;
@@ -308,3 +311,163 @@ async fn dynamic_large_memory_allocation(
         check!(results[i][0] == Value::U64(0));
     }
 }
+
+#[test]
+#[timeout(30000)]
+#[tracing::instrument]
+async fn dynamic_memory_allocation_over_the_component_limit(
+    last_unique_id: &LastUniqueId,
+    deps: &WorkerExecutorTestDependencies,
+    _tracing: &Tracing,
+) {
+    let context = TestContext::new(last_unique_id);
+    let component_id = {
+        let executor = start(deps, &context).await.unwrap();
+        executor.store_component("large-dynamic-memory").await
+    };
+    let executor = start_customized(deps, &context, |config| {
+        config.memory.component_linear_memory_limits = vec![ComponentLinearMemoryLimit {
+            component_id: component_id.clone(),
+            max_linear_memory: 64 * 1024 * 1024,
+        }];
+    })
+    .await
+    .unwrap();
+
+    let worker_id = executor
+        .start_worker(&component_id, "large-dynamic-memory-limited")
+        .await;
+    let result = executor.invoke_and_await(&worker_id, "run", vec![]).await;
+
+    drop(executor);
+
+    check!(result.is_err());
+    check!(worker_error_message(&result.err().unwrap()).contains("linear memory limit"));
+}
+
+#[test]
+#[timeout(60000)]
+#[tracing::instrument]
+async fn replaying_memory_growth_over_the_component_limit(
+    last_unique_id: &LastUniqueId,
+    deps: &WorkerExecutorTestDependencies,
+    _tracing: &Tracing,
+) {
+    let context = TestContext::new(last_unique_id);
+    let idempotency_key = IdempotencyKey::fresh();
+    let (component_id, worker_id, result1) = {
+        let executor = start(deps, &context).await.unwrap();
+        let component_id = executor.store_component("large-dynamic-memory").await;
+        let worker_id = executor
+            .start_worker(&component_id, "large-dynamic-memory-replayed")
+            .await;
+        let result = executor
+            .invoke_and_await_with_key(&worker_id, &idempotency_key, "run", vec![])
+            .await
+            .unwrap();
+        (component_id, worker_id, result)
+    };
+
+    let executor = start_customized(deps, &context, |config| {
+        config.memory.component_linear_memory_limits = vec![ComponentLinearMemoryLimit {
+            component_id: component_id.clone(),
+            max_linear_memory: 64 * 1024 * 1024,
+        }];
+    })
+    .await
+    .unwrap();
+
+    // Repeating the invocation recovers the worker, replaying the memory growth which was
+    // recorded before the limit was lowered
+    let result2 = executor
+        .invoke_and_await_with_key(&worker_id, &idempotency_key, "run", vec![])
+        .await
+        .unwrap();
+    let (metadata, _) = executor.get_worker_metadata(&worker_id).await.unwrap();
+
+    drop(executor);
+
+    check!(result1 == vec![Value::U64(0)]);
+    check!(result2 == vec![Value::U64(0)]);
+    check!(metadata.last_known_status.status != WorkerStatus::Failed);
+}
+
+#[test]
+#[timeout(30000)]
+#[tracing::instrument]
+async fn invocation_over_the_component_fuel_budget(
+    last_unique_id: &LastUniqueId,
+    deps: &WorkerExecutorTestDependencies,
+    _tracing: &Tracing,
+) {
+    let context = TestContext::new(last_unique_id);
+    let component_id = {
+        let executor = start(deps, &context).await.unwrap();
+        executor.store_component("large-dynamic-memory").await
+    };
+    let executor = start_customized(deps, &context, |config| {
+        config.limits.component_invocation_fuel_budgets = vec![ComponentInvocationFuelBudget {
+            component_id: component_id.clone(),
+            fuel: 1000,
+        }];
+    })
+    .await
+    .unwrap();
+
+    let worker_id = executor
+        .start_worker(&component_id, "large-dynamic-memory-fuel-budget")
+        .await;
+    let result = executor.invoke_and_await(&worker_id, "run", vec![]).await;
+
+    drop(executor);
+
+    check!(result.is_err());
+    check!(worker_error_message(&result.err().unwrap()).contains("fuel budget"));
+}
+
+#[test]
+#[timeout(60000)]
+#[tracing::instrument]
+async fn replaying_invocation_over_the_component_fuel_budget(
+    last_unique_id: &LastUniqueId,
+    deps: &WorkerExecutorTestDependencies,
+    _tracing: &Tracing,
+) {
+    let context = TestContext::new(last_unique_id);
+    let idempotency_key = IdempotencyKey::fresh();
+    let (component_id, worker_id, result1) = {
+        let executor = start(deps, &context).await.unwrap();
+        let component_id = executor.store_component("large-dynamic-memory").await;
+        let worker_id = executor
+            .start_worker(&component_id, "large-dynamic-memory-fuel-replayed")
+            .await;
+        let result = executor
+            .invoke_and_await_with_key(&worker_id, &idempotency_key, "run", vec![])
+            .await
+            .unwrap();
+        (component_id, worker_id, result)
+    };
+
+    let executor = start_customized(deps, &context, |config| {
+        config.limits.component_invocation_fuel_budgets = vec![ComponentInvocationFuelBudget {
+            component_id: component_id.clone(),
+            fuel: 1000,
+        }];
+    })
+    .await
+    .unwrap();
+
+    // Repeating the invocation recovers the worker, replaying the call which was executed
+    // before the budget was introduced
+    let result2 = executor
+        .invoke_and_await_with_key(&worker_id, &idempotency_key, "run", vec![])
+        .await
+        .unwrap();
+    let (metadata, _) = executor.get_worker_metadata(&worker_id).await.unwrap();
+
+    drop(executor);
+
+    check!(result1 == vec![Value::U64(0)]);
+    check!(result2 == vec![Value::U64(0)]);
+    check!(metadata.last_known_status.status != WorkerStatus::Failed);
+}
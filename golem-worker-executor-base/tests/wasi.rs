@@ -20,10 +20,16 @@ use std::sync::atomic::AtomicU8;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, SystemTime};
 
-use crate::common::{start, TestContext};
+use crate::common::{start, start_customized, TestContext, TestWorkerExecutor};
 use crate::{LastUniqueId, Tracing, WorkerExecutorTestDependencies};
 use assert2::{assert, check};
-use golem_common::model::{IdempotencyKey, WorkerStatus};
+use golem_api_grpc::proto::golem::workerexecutor::v1::{
+    update_worker_virtual_clock_request, update_worker_virtual_clock_response,
+    UpdateWorkerVirtualClockRequest,
+};
+use golem_common::model::oplog::OplogIndex;
+use golem_common::model::public_oplog::{NetworkEgressDeniedParameters, PublicOplogEntry};
+use golem_common::model::{AccountId, IdempotencyKey, WorkerId, WorkerStatus};
 use golem_test_framework::dsl::{
     drain_connection, stderr_events, stdout_events, worker_error_message, TestDslUnsafe,
 };
@@ -161,6 +167,97 @@ async fn clocks(
     check!(odt_diff < 5.0);
 }
 
+#[test]
+#[tracing::instrument]
+async fn virtual_clocks(
+    last_unique_id: &LastUniqueId,
+    deps: &WorkerExecutorTestDependencies,
+    _tracing: &Tracing,
+) {
+    let context = TestContext::new(last_unique_id);
+    let executor = start_customized(deps, &context, |config| {
+        config.virtual_clock.enabled = true;
+    })
+    .await
+    .unwrap();
+
+    let component_id = executor.store_component("clocks").await;
+    let worker_id = executor
+        .start_worker(&component_id, "clocks-virtual-1")
+        .await;
+
+    let time = update_virtual_clock(
+        &executor,
+        &worker_id,
+        update_worker_virtual_clock_request::Update::SetTimeMillis(1_000_000_000_000),
+    )
+    .await;
+    check!(time == Some(1_000_000_000_000));
+
+    let executor_clone = executor.clone();
+    let worker_id_clone = worker_id.clone();
+    let invocation = spawn(async move {
+        executor_clone
+            .invoke_and_await(&worker_id_clone, "run", vec![])
+            .await
+            .unwrap()
+    });
+
+    // The worker sleeps for two seconds of virtual time, which only pass when the clock is advanced
+    tokio::time::sleep(Duration::from_secs(3)).await;
+    check!(!invocation.is_finished());
+
+    let time = update_virtual_clock(
+        &executor,
+        &worker_id,
+        update_worker_virtual_clock_request::Update::AdvanceMillis(2000),
+    )
+    .await;
+    check!(time == Some(1_000_000_002_000));
+
+    let result = invocation.await.unwrap();
+
+    drop(executor);
+
+    check!(
+        result
+            == vec![Value::Tuple(vec![
+                Value::F64(1_000_000_000.0),
+                Value::F64(2.0),
+                Value::String("2001-09-09T01:46:40Z".to_string())
+            ])]
+    );
+}
+
+async fn update_virtual_clock(
+    executor: &TestWorkerExecutor,
+    worker_id: &WorkerId,
+    update: update_worker_virtual_clock_request::Update,
+) -> Option<u64> {
+    let response = executor
+        .client()
+        .await
+        .expect("Failed to get client")
+        .update_worker_virtual_clock(UpdateWorkerVirtualClockRequest {
+            worker_id: Some(worker_id.clone().into()),
+            account_id: Some(
+                AccountId {
+                    value: "test-account".to_string(),
+                }
+                .into(),
+            ),
+            update: Some(update),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+
+    let Some(update_worker_virtual_clock_response::Result::Success(clock)) = response.result else {
+        panic!("Unexpected virtual clock response: {response:?}");
+    };
+    clock.time_millis
+}
+
 #[test]
 #[tracing::instrument]
 async fn file_write_read_delete(
@@ -426,6 +523,79 @@ async fn http_client(
     );
 }
 
+#[test]
+#[tracing::instrument]
+async fn http_client_denied_by_network_egress_policy(
+    last_unique_id: &LastUniqueId,
+    deps: &WorkerExecutorTestDependencies,
+    _tracing: &Tracing,
+) {
+    let context = TestContext::new(last_unique_id);
+    let executor = start_customized(deps, &context, |config| {
+        config.network_egress.default.deny = vec!["localhost".to_string()];
+    })
+    .await
+    .unwrap();
+
+    let component_id = executor.store_component("http-client").await;
+    let mut env = HashMap::new();
+    env.insert("PORT".to_string(), context.host_http_port().to_string());
+
+    let worker_id = executor
+        .start_worker_with(&component_id, "http-client-egress-1", vec![], env)
+        .await;
+    let rx = executor.capture_output(&worker_id).await;
+
+    let result = executor
+        .invoke_and_await(&worker_id, "golem:it/api.{run}", vec![])
+        .await;
+    let oplog = executor.get_oplog(&worker_id, OplogIndex::INITIAL).await;
+
+    drop(executor);
+    drop(rx);
+
+    check!(result.is_err());
+    check!(oplog.iter().any(|entry| matches!(
+        entry,
+        PublicOplogEntry::NetworkEgressDenied(NetworkEgressDeniedParameters { host, rule, .. })
+            if host == "localhost" && rule.as_deref() == Some("localhost")
+    )));
+}
+
+#[test]
+#[tracing::instrument]
+async fn http_client_resolving_to_address_denied_by_network_egress_policy(
+    last_unique_id: &LastUniqueId,
+    deps: &WorkerExecutorTestDependencies,
+    _tracing: &Tracing,
+) {
+    let context = TestContext::new(last_unique_id);
+    let executor = start_customized(deps, &context, |config| {
+        config.network_egress.default.deny = vec!["127.0.0.0/8".to_string(), "::1".to_string()];
+    })
+    .await
+    .unwrap();
+
+    let component_id = executor.store_component("http-client").await;
+    let mut env = HashMap::new();
+    env.insert("PORT".to_string(), context.host_http_port().to_string());
+
+    let worker_id = executor
+        .start_worker_with(&component_id, "http-client-egress-2", vec![], env)
+        .await;
+    let rx = executor.capture_output(&worker_id).await;
+
+    // The host name itself is allowed, but it resolves to a denied address
+    let result = executor
+        .invoke_and_await(&worker_id, "golem:it/api.{run}", vec![])
+        .await;
+
+    drop(executor);
+    drop(rx);
+
+    check!(result.is_err());
+}
+
 #[test]
 #[tracing::instrument]
 async fn http_client_using_reqwest(
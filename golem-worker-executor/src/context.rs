@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
 use std::sync::{Arc, RwLock, Weak};
 
 use anyhow::Error;
@@ -19,9 +20,11 @@ use async_trait::async_trait;
 use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
 use golem_wasm_rpc::wasmtime::ResourceStore;
 use golem_wasm_rpc::{Uri, Value};
-use wasmtime::component::{Instance, ResourceAny};
+use wasmtime::component::{Instance, Resource, ResourceAny};
 use wasmtime::{AsContextMut, ResourceLimiterAsync};
+use wasmtime_wasi::bindings::io::streams::InputStream;
 
+use golem_common::model::component_observability::ComponentObservability;
 use golem_common::model::oplog::WorkerResourceId;
 use golem_common::model::{
     AccountId, ComponentVersion, IdempotencyKey, OwnedWorkerId, WorkerId, WorkerMetadata,
@@ -42,7 +45,11 @@ use golem_worker_executor_base::services::key_value::KeyValueService;
 use golem_worker_executor_base::services::oplog::{Oplog, OplogService};
 use golem_worker_executor_base::services::promise::PromiseService;
 use golem_worker_executor_base::services::rpc::Rpc;
+use golem_worker_executor_base::services::saga::SagaService;
 use golem_worker_executor_base::services::scheduler::SchedulerService;
+use golem_worker_executor_base::services::secrets::SecretService;
+use golem_worker_executor_base::services::topics::TopicService;
+use golem_worker_executor_base::services::virtual_clock::VirtualClockService;
 use golem_worker_executor_base::services::worker::WorkerService;
 use golem_worker_executor_base::services::worker_event::WorkerEventService;
 use golem_worker_executor_base::services::worker_proxy::WorkerProxy;
@@ -115,6 +122,21 @@ impl ExternalOperations<Context> for Context {
         DurableWorkerCtx::<Context>::prepare_instance(worker_id, instance, store).await
     }
 
+    async fn take_snapshot_if_due(
+        instance: &Instance,
+        store: &mut (impl AsContextMut<Data = Context> + Send),
+    ) -> Result<(), GolemError> {
+        DurableWorkerCtx::<Context>::take_snapshot_if_due(instance, store).await
+    }
+
+    async fn try_load_snapshot(
+        instance: &Instance,
+        store: &mut (impl AsContextMut<Data = Context> + Send),
+        data: &[u8],
+    ) -> Option<String> {
+        DurableWorkerCtx::<Context>::try_load_snapshot(instance, store, data).await
+    }
+
     async fn record_last_known_limits<T: HasAll<Context> + Send + Sync>(
         this: &T,
         account_id: &AccountId,
@@ -150,6 +172,10 @@ impl InvocationManagement for Context {
         self.durable_ctx.get_current_idempotency_key().await
     }
 
+    async fn set_invocation_context(&mut self, context: BTreeMap<String, String>) {
+        self.durable_ctx.set_invocation_context(context).await
+    }
+
     fn is_live(&self) -> bool {
         self.durable_ctx.is_live()
     }
@@ -217,6 +243,13 @@ impl InvocationHooks for Context {
             .on_invocation_success(full_function_name, function_input, consumed_fuel, output)
             .await
     }
+
+    async fn forward_result_stream(
+        &mut self,
+        stream: Resource<InputStream>,
+    ) -> Result<(), GolemError> {
+        self.durable_ctx.forward_result_stream(stream).await
+    }
 }
 
 #[async_trait]
@@ -292,6 +325,10 @@ impl WorkerCtx for Context {
         >,
         key_value_service: Arc<dyn KeyValueService + Send + Sync>,
         blob_store_service: Arc<dyn BlobStoreService + Send + Sync>,
+        secret_service: Arc<dyn SecretService + Send + Sync>,
+        topic_service: Arc<dyn TopicService + Send + Sync>,
+        saga_service: Arc<dyn SagaService + Send + Sync>,
+        virtual_clock_service: Arc<dyn VirtualClockService + Send + Sync>,
         event_service: Arc<dyn WorkerEventService + Send + Sync>,
         _active_workers: Arc<ActiveWorkers<Context>>,
         oplog_service: Arc<dyn OplogService + Send + Sync>,
@@ -314,6 +351,10 @@ impl WorkerCtx for Context {
             worker_enumeration_service,
             key_value_service,
             blob_store_service,
+            secret_service,
+            topic_service,
+            saga_service,
+            virtual_clock_service,
             event_service,
             oplog_service,
             oplog,
@@ -348,6 +389,14 @@ impl WorkerCtx for Context {
         self.durable_ctx.component_metadata()
     }
 
+    fn component_observability(&self) -> ComponentObservability {
+        self.durable_ctx.component_observability()
+    }
+
+    fn invocation_fuel_budget(&self) -> Option<u64> {
+        self.durable_ctx.invocation_fuel_budget()
+    }
+
     fn is_exit(error: &Error) -> Option<i32> {
         DurableWorkerCtx::<Context>::is_exit(error)
     }
@@ -21,6 +21,7 @@ use crate::context::Context;
 use crate::services::AdditionalDeps;
 use async_trait::async_trait;
 use golem_worker_executor_base::durable_host::DurableWorkerCtx;
+use golem_worker_executor_base::preview2::api_v1_2;
 use golem_worker_executor_base::preview2::golem::{api0_2_0, api1_1_0_rc1};
 use golem_worker_executor_base::services::active_workers::ActiveWorkers;
 use golem_worker_executor_base::services::blob_store::BlobStoreService;
@@ -28,12 +29,18 @@ use golem_worker_executor_base::services::component::ComponentService;
 use golem_worker_executor_base::services::events::Events;
 use golem_worker_executor_base::services::golem_config::GolemConfig;
 use golem_worker_executor_base::services::key_value::KeyValueService;
+use golem_worker_executor_base::services::log_export::LogExportService;
 use golem_worker_executor_base::services::oplog::OplogService;
 use golem_worker_executor_base::services::promise::PromiseService;
 use golem_worker_executor_base::services::rpc::{DirectWorkerInvocationRpc, RemoteInvocationRpc};
+use golem_worker_executor_base::services::saga::SagaService;
 use golem_worker_executor_base::services::scheduler::SchedulerService;
+use golem_worker_executor_base::services::secrets::SecretService;
 use golem_worker_executor_base::services::shard::ShardService;
 use golem_worker_executor_base::services::shard_manager::ShardManagerService;
+use golem_worker_executor_base::services::topics::TopicService;
+use golem_worker_executor_base::services::usage::UsageService;
+use golem_worker_executor_base::services::virtual_clock::VirtualClockService;
 use golem_worker_executor_base::services::worker::WorkerService;
 use golem_worker_executor_base::services::worker_activator::WorkerActivator;
 use golem_worker_executor_base::services::worker_enumeration::{
@@ -57,7 +64,11 @@ struct ServerBootstrap {}
 #[async_trait]
 impl Bootstrap<Context> for ServerBootstrap {
     fn create_active_workers(&self, golem_config: &GolemConfig) -> Arc<ActiveWorkers<Context>> {
-        Arc::new(ActiveWorkers::<Context>::new(&golem_config.memory))
+        Arc::new(ActiveWorkers::<Context>::new(
+            &golem_config.memory,
+            &golem_config.ephemeral,
+            &golem_config.invocation_scheduling,
+        ))
     }
 
     async fn create_services(
@@ -76,11 +87,17 @@ impl Bootstrap<Context> for ServerBootstrap {
         shard_service: Arc<dyn ShardService + Send + Sync>,
         key_value_service: Arc<dyn KeyValueService + Send + Sync>,
         blob_store_service: Arc<dyn BlobStoreService + Send + Sync>,
+        secret_service: Arc<dyn SecretService + Send + Sync>,
+        topic_service: Arc<dyn TopicService + Send + Sync>,
+        saga_service: Arc<dyn SagaService + Send + Sync>,
+        virtual_clock_service: Arc<dyn VirtualClockService + Send + Sync>,
         worker_activator: Arc<dyn WorkerActivator + Send + Sync>,
         oplog_service: Arc<dyn OplogService + Send + Sync>,
         scheduler_service: Arc<dyn SchedulerService + Send + Sync>,
         worker_proxy: Arc<dyn WorkerProxy + Send + Sync>,
         events: Arc<Events>,
+        log_export_service: Arc<dyn LogExportService + Send + Sync>,
+        usage_service: Arc<dyn UsageService + Send + Sync>,
     ) -> anyhow::Result<All<Context>> {
         let additional_deps = AdditionalDeps {};
 
@@ -103,10 +120,16 @@ impl Bootstrap<Context> for ServerBootstrap {
             shard_manager_service.clone(),
             key_value_service.clone(),
             blob_store_service.clone(),
+            secret_service.clone(),
+            topic_service.clone(),
+            saga_service.clone(),
+            virtual_clock_service.clone(),
             oplog_service.clone(),
             scheduler_service.clone(),
             worker_activator.clone(),
             events.clone(),
+            log_export_service.clone(),
+            usage_service.clone(),
             additional_deps.clone(),
         ));
 
@@ -125,12 +148,18 @@ impl Bootstrap<Context> for ServerBootstrap {
             shard_service,
             key_value_service,
             blob_store_service,
+            secret_service,
+            topic_service,
+            saga_service,
+            virtual_clock_service,
             oplog_service,
             rpc,
             scheduler_service,
             worker_activator.clone(),
             worker_proxy.clone(),
             events.clone(),
+            log_export_service,
+            usage_service,
             additional_deps,
         ))
     }
@@ -139,6 +168,7 @@ impl Bootstrap<Context> for ServerBootstrap {
         let mut linker = create_linker(engine, get_durable_ctx)?;
         api0_2_0::host::add_to_linker_get_host(&mut linker, get_durable_ctx)?;
         api1_1_0_rc1::host::add_to_linker_get_host(&mut linker, get_durable_ctx)?;
+        api_v1_2::golem::api::self_metadata::add_to_linker_get_host(&mut linker, get_durable_ctx)?;
         golem_wasm_rpc::golem::rpc::types::add_to_linker_get_host(&mut linker, get_durable_ctx)?;
         Ok(linker)
     }
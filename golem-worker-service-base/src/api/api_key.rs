@@ -0,0 +1,74 @@
+use poem_openapi::*;
+use serde::{Deserialize, Serialize};
+
+use crate::api_definition::ApiSite;
+use crate::service::api_key::ApiKeyId;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct ApiKeyRequest {
+    pub site: ApiSite,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct ApiKey {
+    pub id: ApiKeyId,
+    pub site: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub revoked_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// A newly created API key. The key itself is not stored and cannot be retrieved again
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct ApiKeyWithSecret {
+    pub id: ApiKeyId,
+    pub site: String,
+    pub key: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct ApiKeyUsage {
+    pub id: ApiKeyId,
+    pub request_count: u64,
+    pub last_used_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl<N> From<crate::service::api_key::ApiKey<N>> for ApiKey {
+    fn from(value: crate::service::api_key::ApiKey<N>) -> Self {
+        Self {
+            id: value.id,
+            site: value.site.0,
+            created_at: value.created_at,
+            revoked_at: value.revoked_at,
+        }
+    }
+}
+
+impl<N> From<crate::service::api_key::ApiKeyWithSecret<N>> for ApiKeyWithSecret {
+    fn from(value: crate::service::api_key::ApiKeyWithSecret<N>) -> Self {
+        Self {
+            id: value.api_key.id,
+            site: value.api_key.site.0,
+            key: value.secret,
+            created_at: value.api_key.created_at,
+        }
+    }
+}
+
+impl From<crate::service::api_key::ApiKeyUsage> for ApiKeyUsage {
+    fn from(value: crate::service::api_key::ApiKeyUsage) -> Self {
+        Self {
+            id: value.id,
+            request_count: value.request_count,
+            last_used_at: value.last_used_at,
+        }
+    }
+}
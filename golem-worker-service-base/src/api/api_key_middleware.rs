@@ -0,0 +1,345 @@
+use std::fmt::Display;
+use std::sync::Arc;
+
+use hyper::header::{ACCESS_CONTROL_REQUEST_METHOD, HOST, ORIGIN};
+use poem::http::{HeaderMap, Method, StatusCode};
+use poem::{Body, Endpoint, IntoResponse, Middleware, Request, Response};
+use tracing::{error, info};
+
+use crate::api_definition::http::CompiledHttpApiDefinition;
+use crate::api_definition::ApiSiteString;
+use crate::http::{ApiInputPath, InputHttpRequest, InputHttpRequestBody};
+use crate::service::api_definition_lookup::ApiDefinitionsLookup;
+use crate::service::api_key::{ApiKeyError, ApiKeyService};
+
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+type ApiDefinitionsLookupService =
+    Arc<dyn ApiDefinitionsLookup<InputHttpRequest, CompiledHttpApiDefinition> + Sync + Send>;
+
+// Guards custom http requests of sites having API keys, rejecting requests
+// without a valid `X-Api-Key` header before they reach any worker.
+// CORS preflight requests never carry the key, so they are let through to be answered
+// according to the CORS policy of the route, and the rejections carry the CORS headers
+// of the route so browsers let the caller read them.
+pub struct ApiKeyMiddleware<Namespace> {
+    api_key_service: Arc<dyn ApiKeyService<Namespace> + Sync + Send>,
+    api_definition_lookup_service: ApiDefinitionsLookupService,
+}
+
+impl<Namespace> ApiKeyMiddleware<Namespace> {
+    pub fn new(
+        api_key_service: Arc<dyn ApiKeyService<Namespace> + Sync + Send>,
+        api_definition_lookup_service: ApiDefinitionsLookupService,
+    ) -> Self {
+        Self {
+            api_key_service,
+            api_definition_lookup_service,
+        }
+    }
+}
+
+impl<E, Namespace> Middleware<E> for ApiKeyMiddleware<Namespace>
+where
+    E: Endpoint,
+    Namespace: Display + Send + Sync + 'static,
+{
+    type Output = ApiKeyEndpoint<E, Namespace>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        ApiKeyEndpoint {
+            inner: ep,
+            api_key_service: self.api_key_service.clone(),
+            api_definition_lookup_service: self.api_definition_lookup_service.clone(),
+        }
+    }
+}
+
+pub struct ApiKeyEndpoint<E, Namespace> {
+    inner: E,
+    api_key_service: Arc<dyn ApiKeyService<Namespace> + Sync + Send>,
+    api_definition_lookup_service: ApiDefinitionsLookupService,
+}
+
+impl<E, Namespace> ApiKeyEndpoint<E, Namespace> {
+    async fn rejection(&self, req: &Request, status: StatusCode) -> Response {
+        let mut response = Response::builder().status(status).finish();
+        if let Some(cors_headers) = self.cors_headers(req).await {
+            response.headers_mut().extend(cors_headers);
+        }
+        response
+    }
+
+    // Response headers of the CORS policy of the route matching a cross-origin request
+    async fn cors_headers(&self, req: &Request) -> Option<HeaderMap> {
+        let origin = req.headers().get(ORIGIN)?.to_str().ok()?;
+
+        let input_http_request = InputHttpRequest {
+            input_path: ApiInputPath {
+                base_path: req.uri().path().to_string(),
+                query_path: req.uri().query().map(|x| x.to_string()),
+            },
+            headers: req.headers().clone(),
+            req_method: req.method().clone(),
+            req_body: InputHttpRequestBody::Json(serde_json::Value::Null),
+        };
+
+        let api_definitions = self
+            .api_definition_lookup_service
+            .get(input_http_request.clone())
+            .await
+            .ok()?;
+
+        input_http_request
+            .resolve_cors(api_definitions, &input_http_request.req_method)
+            .and_then(|cors| cors.response_headers(origin))
+    }
+}
+
+fn is_cors_preflight(req: &Request) -> bool {
+    req.method() == Method::OPTIONS && req.headers().contains_key(ACCESS_CONTROL_REQUEST_METHOD)
+}
+
+impl<E, Namespace> Endpoint for ApiKeyEndpoint<E, Namespace>
+where
+    E: Endpoint,
+    Namespace: Display + Send + Sync + 'static,
+{
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> poem::Result<Self::Output> {
+        // Requests without a host are rejected by the wrapped endpoint
+        let site = match req.headers().get(HOST).and_then(|h| h.to_str().ok()) {
+            Some(host) => ApiSiteString(host.to_string()),
+            None => return self.inner.call(req).await.map(IntoResponse::into_response),
+        };
+
+        if is_cors_preflight(&req) {
+            return self.inner.call(req).await.map(IntoResponse::into_response);
+        }
+
+        let api_key = req
+            .headers()
+            .get(API_KEY_HEADER)
+            .and_then(|h| h.to_str().ok())
+            .map(|h| h.to_string());
+
+        match self
+            .api_key_service
+            .authorize(&site, api_key.as_deref())
+            .await
+        {
+            Ok(()) => self.inner.call(req).await.map(IntoResponse::into_response),
+            Err(err @ ApiKeyError::MissingApiKey) => {
+                info!("API request host: {} - unauthorized: {}", site, err);
+                Ok(self.rejection(&req, StatusCode::UNAUTHORIZED).await)
+            }
+            Err(err @ ApiKeyError::InvalidApiKey) => {
+                info!("API request host: {} - forbidden: {}", site, err);
+                Ok(self.rejection(&req, StatusCode::FORBIDDEN).await)
+            }
+            Err(err) => {
+                error!("API request host: {} - error: {}", site, err);
+                Ok(Response::builder()
+                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .body(Body::from_string("Internal error".to_string())))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use async_trait::async_trait;
+    use http::header::{ACCESS_CONTROL_ALLOW_ORIGIN, ACCESS_CONTROL_REQUEST_METHOD, HOST, ORIGIN};
+    use http::StatusCode;
+    use poem::test::TestClient;
+    use poem::{handler, EndpointExt, Route};
+
+    use crate::api::api_key_middleware::{ApiKeyMiddleware, API_KEY_HEADER};
+    use crate::api_definition::http::{
+        CompiledHttpApiDefinition, ComponentMetadataDictionary, HttpApiCors, HttpApiDefinition,
+    };
+    use crate::api_definition::ApiSiteString;
+    use crate::http::InputHttpRequest;
+    use crate::service::api_definition_lookup::{ApiDefinitionLookupError, ApiDefinitionsLookup};
+    use crate::service::api_key::{
+        ApiKey, ApiKeyError, ApiKeyId, ApiKeyService, ApiKeyUsage, ApiKeyWithSecret,
+    };
+
+    const ALLOWED_ORIGIN: &str = "https://app.example.com";
+
+    struct TestApiKeyService;
+
+    #[async_trait]
+    impl ApiKeyService<String> for TestApiKeyService {
+        async fn create(
+            &self,
+            _namespace: &String,
+            _site: &ApiSiteString,
+        ) -> Result<ApiKeyWithSecret<String>, ApiKeyError<String>> {
+            unimplemented!()
+        }
+
+        async fn revoke(
+            &self,
+            _namespace: &String,
+            _id: &ApiKeyId,
+        ) -> Result<(), ApiKeyError<String>> {
+            unimplemented!()
+        }
+
+        async fn get_all(
+            &self,
+            _namespace: &String,
+            _site: &ApiSiteString,
+        ) -> Result<Vec<ApiKey<String>>, ApiKeyError<String>> {
+            unimplemented!()
+        }
+
+        async fn get_usage(
+            &self,
+            _namespace: &String,
+            _id: &ApiKeyId,
+        ) -> Result<ApiKeyUsage, ApiKeyError<String>> {
+            unimplemented!()
+        }
+
+        async fn authorize(
+            &self,
+            _site: &ApiSiteString,
+            secret: Option<&str>,
+        ) -> Result<(), ApiKeyError<String>> {
+            match secret {
+                Some("secret") => Ok(()),
+                Some(_) => Err(ApiKeyError::InvalidApiKey),
+                None => Err(ApiKeyError::MissingApiKey),
+            }
+        }
+    }
+
+    struct TestApiDefinitionsLookup(CompiledHttpApiDefinition);
+
+    #[async_trait]
+    impl ApiDefinitionsLookup<InputHttpRequest, CompiledHttpApiDefinition>
+        for TestApiDefinitionsLookup
+    {
+        async fn get(
+            &self,
+            _input: InputHttpRequest,
+        ) -> Result<Vec<CompiledHttpApiDefinition>, ApiDefinitionLookupError> {
+            Ok(vec![self.0.clone()])
+        }
+    }
+
+    fn api_definition() -> CompiledHttpApiDefinition {
+        let yaml_string = r#"
+          id: users-api
+          version: 0.0.1
+          createdAt: 2024-08-21T07:42:15.696Z
+          routes:
+          - method: Get
+            path: users/{user-id}
+            binding:
+              componentId:
+                version: 0
+                componentId: '15d70aa5-2e23-4ee3-b65c-4e1d702836a3'
+              workerName: '"users"'
+              response: '${ {status: 200} }'
+        "#;
+        let mut definition: HttpApiDefinition = serde_yaml::from_str(yaml_string).unwrap();
+        definition.cors = Some(HttpApiCors {
+            allow_origins: vec![ALLOWED_ORIGIN.to_string()],
+            allow_methods: vec![],
+            allow_headers: vec![API_KEY_HEADER.to_string()],
+            max_age: None,
+        });
+        CompiledHttpApiDefinition::from_http_api_definition(
+            &definition,
+            &ComponentMetadataDictionary {
+                metadata: HashMap::new(),
+            },
+        )
+        .unwrap()
+    }
+
+    #[handler]
+    fn inner() -> &'static str {
+        "inner"
+    }
+
+    fn client() -> TestClient<Route> {
+        TestClient::new(Route::new().nest(
+            "/",
+            inner.with(ApiKeyMiddleware::new(
+                Arc::new(TestApiKeyService),
+                Arc::new(TestApiDefinitionsLookup(api_definition())),
+            )),
+        ))
+    }
+
+    #[test]
+    async fn preflight_requests_do_not_need_the_api_key() {
+        let response = client()
+            .options("/users/1")
+            .header(HOST, "api.example.com")
+            .header(ORIGIN, ALLOWED_ORIGIN)
+            .header(ACCESS_CONTROL_REQUEST_METHOD, "GET")
+            .send()
+            .await;
+
+        response.assert_status_is_ok();
+        response.assert_text("inner").await;
+    }
+
+    #[test]
+    async fn rejections_carry_the_cors_headers_of_the_route() {
+        let client = client();
+
+        let response = client
+            .get("/users/1")
+            .header(HOST, "api.example.com")
+            .header(ORIGIN, ALLOWED_ORIGIN)
+            .send()
+            .await;
+        response.assert_status(StatusCode::UNAUTHORIZED);
+        response.assert_header(ACCESS_CONTROL_ALLOW_ORIGIN, ALLOWED_ORIGIN);
+
+        let response = client
+            .get("/users/1")
+            .header(HOST, "api.example.com")
+            .header(ORIGIN, ALLOWED_ORIGIN)
+            .header(API_KEY_HEADER, "wrong")
+            .send()
+            .await;
+        response.assert_status(StatusCode::FORBIDDEN);
+        response.assert_header(ACCESS_CONTROL_ALLOW_ORIGIN, ALLOWED_ORIGIN);
+
+        let response = client
+            .get("/users/1")
+            .header(HOST, "api.example.com")
+            .header(ORIGIN, "https://other.example.com")
+            .send()
+            .await;
+        response.assert_status(StatusCode::UNAUTHORIZED);
+        response.assert_header_is_not_exist(ACCESS_CONTROL_ALLOW_ORIGIN);
+    }
+
+    #[test]
+    async fn requests_with_a_valid_api_key_reach_the_endpoint() {
+        let response = client()
+            .get("/users/1")
+            .header(HOST, "api.example.com")
+            .header(API_KEY_HEADER, "secret")
+            .send()
+            .await;
+
+        response.assert_status_is_ok();
+        response.assert_text("inner").await;
+    }
+}
@@ -110,6 +110,7 @@ impl<'a> TraceErrorKind for WorkerTraceErrorKind<'a> {
             Some(error) => match error {
                 worker::v1::worker_error::Error::BadRequest(_) => "BadRequest",
                 worker::v1::worker_error::Error::Unauthorized(_) => "Unauthorized",
+                worker::v1::worker_error::Error::Forbidden(_) => "Forbidden",
                 worker::v1::worker_error::Error::LimitExceeded(_) => "LimitExceeded",
                 worker::v1::worker_error::Error::NotFound(_) => "NotFound",
                 worker::v1::worker_error::Error::AlreadyExists(_) => "AlreadyExists",
@@ -150,7 +151,11 @@ mod conversion {
     use crate::service::api_definition::ApiDefinitionError as ApiDefinitionServiceError;
     use crate::service::api_definition_validator::ValidationErrors;
     use crate::service::api_deployment::ApiDeploymentError;
+    use crate::service::api_key::ApiKeyError;
+    use crate::service::api_project::ApiProjectError;
+    use crate::service::component::ComponentServiceError;
     use crate::service::http::http_api_definition_validator::RouteValidationError;
+    use crate::service::rib_evaluation::RibEvaluationServiceError;
     use golem_api_grpc::proto::golem::common::ErrorsBody;
     use golem_api_grpc::proto::golem::{
         apidefinition,
@@ -214,6 +219,55 @@ mod conversion {
         }
     }
 
+    impl<Namespace: Display> From<ApiKeyError<Namespace>> for ApiEndpointError {
+        fn from(error: ApiKeyError<Namespace>) -> Self {
+            match error {
+                ApiKeyError::ApiDeploymentNotFound(_, _) => ApiEndpointError::not_found(error),
+                ApiKeyError::ApiKeyNotFound(_) => ApiEndpointError::not_found(error),
+                ApiKeyError::MissingApiKey => ApiEndpointError::unauthorized(error),
+                ApiKeyError::InvalidApiKey => ApiEndpointError::forbidden(error),
+                ApiKeyError::InternalRepoError(_) => ApiEndpointError::internal(error),
+                ApiKeyError::InternalConversionError { .. } => ApiEndpointError::internal(error),
+            }
+        }
+    }
+
+    impl<Namespace: Display> From<ApiProjectError<Namespace>> for ApiEndpointError {
+        fn from(error: ApiProjectError<Namespace>) -> Self {
+            match &error {
+                ApiProjectError::ProjectNotFound(_) => ApiEndpointError::not_found(error),
+                ApiProjectError::ApiDefinitionNotFound(_) => ApiEndpointError::not_found(error),
+                ApiProjectError::ApiDeploymentNotFound(_, _) => ApiEndpointError::not_found(error),
+                ApiProjectError::ApiDefinitionNotInProject(_, _) => {
+                    ApiEndpointError::not_found(error)
+                }
+                ApiProjectError::ApiDeploymentNotInProject(_, _) => {
+                    ApiEndpointError::not_found(error)
+                }
+                ApiProjectError::Component(component_error) => match component_error {
+                    ComponentServiceError::Unauthorized(_) => ApiEndpointError::unauthorized(error),
+                    ComponentServiceError::Forbidden(_) => ApiEndpointError::forbidden(error),
+                    _ => ApiEndpointError::internal(error),
+                },
+                ApiProjectError::InternalRepoError(_) => ApiEndpointError::internal(error),
+            }
+        }
+    }
+
+    impl From<RibEvaluationServiceError> for ApiEndpointError {
+        fn from(error: RibEvaluationServiceError) -> Self {
+            match &error {
+                RibEvaluationServiceError::Component(component_error) => match component_error {
+                    ComponentServiceError::NotFound(_) => ApiEndpointError::not_found(error),
+                    ComponentServiceError::Unauthorized(_) => ApiEndpointError::unauthorized(error),
+                    ComponentServiceError::Forbidden(_) => ApiEndpointError::forbidden(error),
+                    ComponentServiceError::BadRequest(_) => ApiEndpointError::bad_request(error),
+                    _ => ApiEndpointError::internal(error),
+                },
+            }
+        }
+    }
+
     impl From<ValidationErrors<RouteValidationError>> for ApiEndpointError {
         fn from(error: ValidationErrors<RouteValidationError>) -> Self {
             let error = WorkerServiceErrorsBody::Validation(ValidationErrorsBody {
@@ -1,18 +1,26 @@
 use std::future::Future;
 use std::sync::Arc;
+use std::time::Duration;
 
 use crate::api_definition::http::CompiledHttpApiDefinition;
+use crate::app_config::MultipartConfig;
 use crate::worker_service_rib_interpreter::{DefaultRibInterpreter, WorkerServiceRibInterpreter};
 use futures_util::FutureExt;
-use hyper::header::HOST;
-use poem::http::StatusCode;
+use golem_common::model::error::{ErrorCode, ProblemDetails};
+use hyper::header::{CACHE_CONTROL, CONTENT_TYPE, HOST, ORIGIN};
+use poem::http::{HeaderMap, HeaderName, HeaderValue, StatusCode};
 use poem::{Body, Endpoint, Request, Response};
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
-use crate::http::{ApiInputPath, InputHttpRequest};
+use crate::http::multipart::{is_multipart_form_data, read_multipart_body, MultipartError};
+use crate::http::{ApiInputPath, InputHttpRequest, InputHttpRequestBody};
 use crate::service::api_definition_lookup::ApiDefinitionsLookup;
 
-use crate::worker_binding::RequestToWorkerBindingResolver;
+use crate::service::http::http_response_cache::{
+    CachedHttpResponse, HttpResponseCache, HttpResponseCacheKey,
+};
+use crate::service::http::jwt_validator::JwtValidator;
+use crate::worker_binding::{RequestToWorkerBindingResolver, WorkerBindingResolutionError};
 use crate::worker_bridge_execution::WorkerRequestExecutor;
 
 // Executes custom request with the help of worker_request_executor and definition_service
@@ -22,6 +30,9 @@ pub struct CustomHttpRequestApi {
     pub worker_service_rib_interpreter: Arc<dyn WorkerServiceRibInterpreter + Sync + Send>,
     pub api_definition_lookup_service:
         Arc<dyn ApiDefinitionsLookup<InputHttpRequest, CompiledHttpApiDefinition> + Sync + Send>,
+    pub jwt_validator: Arc<dyn JwtValidator + Sync + Send>,
+    pub response_cache: Arc<dyn HttpResponseCache + Sync + Send>,
+    pub multipart_config: MultipartConfig,
 }
 
 impl CustomHttpRequestApi {
@@ -30,6 +41,9 @@ impl CustomHttpRequestApi {
         api_definition_lookup_service: Arc<
             dyn ApiDefinitionsLookup<InputHttpRequest, CompiledHttpApiDefinition> + Sync + Send,
         >,
+        jwt_validator: Arc<dyn JwtValidator + Sync + Send>,
+        response_cache: Arc<dyn HttpResponseCache + Sync + Send>,
+        multipart_config: MultipartConfig,
     ) -> Self {
         let evaluator = Arc::new(DefaultRibInterpreter::from_worker_request_executor(
             worker_request_executor_service.clone(),
@@ -38,6 +52,9 @@ impl CustomHttpRequestApi {
         Self {
             worker_service_rib_interpreter: evaluator,
             api_definition_lookup_service,
+            jwt_validator,
+            response_cache,
+            multipart_config,
         }
     }
 
@@ -49,36 +66,26 @@ impl CustomHttpRequestApi {
         let host = match headers.get(HOST).and_then(|h| h.to_str().ok()) {
             Some(host) => host.to_string(),
             None => {
-                return Response::builder()
-                    .status(StatusCode::BAD_REQUEST)
-                    .body(Body::from_string("Missing host".to_string()));
+                return ProblemDetails::new(ErrorCode::InvalidRequest, "Missing host")
+                    .to_http_response();
             }
         };
 
         info!("API request host: {}", host);
 
-        let json_request_body: serde_json::Value = if body.is_empty() {
-            serde_json::Value::Null
-        } else {
-            match body.into_json().await {
-                Ok(json_request_body) => json_request_body,
-                Err(err) => {
-                    error!("API request host: {} - error: {}", host, err);
-                    return Response::builder()
-                        .status(StatusCode::BAD_REQUEST)
-                        .body(Body::from_string("Request body parse error".to_string()));
-                }
-            }
-        };
+        let origin = headers
+            .get(ORIGIN)
+            .and_then(|h| h.to_str().ok())
+            .map(|h| h.to_string());
 
-        let input_http_request = InputHttpRequest {
+        let mut input_http_request = InputHttpRequest {
             input_path: ApiInputPath {
                 base_path: uri.path().to_string(),
                 query_path: uri.query().map(|x| x.to_string()),
             },
             headers,
             req_method: req_parts.method,
-            req_body: json_request_body,
+            req_body: InputHttpRequestBody::Json(serde_json::Value::Null),
         };
 
         let possible_api_definitions = match self
@@ -92,14 +99,124 @@ impl CustomHttpRequestApi {
                     "API request host: {} - error: {}",
                     host, api_defs_lookup_error
                 );
-                return Response::builder()
-                    .status(StatusCode::INTERNAL_SERVER_ERROR)
-                    .body(Body::from_string("Internal error".to_string()));
+                return ProblemDetails::new(ErrorCode::Internal, "Internal error")
+                    .to_http_response();
             }
         };
 
-        match input_http_request
-            .resolve_worker_binding(possible_api_definitions)
+        let multipart_content_type = input_http_request
+            .headers
+            .get(CONTENT_TYPE)
+            .and_then(|h| h.to_str().ok())
+            .filter(|content_type| is_multipart_form_data(content_type))
+            .map(|content_type| content_type.to_string());
+
+        // Bodies of routes bound in the raw mode are given to the worker as they are, without
+        // being parsed
+        let binding_mode =
+            input_http_request.resolve_binding_mode(possible_api_definitions.clone());
+
+        let request_body = if binding_mode.is_raw() {
+            match body.into_vec().await {
+                Ok(bytes) => InputHttpRequestBody::Raw(bytes),
+                Err(err) => {
+                    error!("API request host: {} - error: {}", host, err);
+                    return ProblemDetails::new(
+                        ErrorCode::InvalidRequest,
+                        "Request body read error",
+                    )
+                    .to_http_response();
+                }
+            }
+        } else if body.is_empty() {
+            InputHttpRequestBody::Json(serde_json::Value::Null)
+        } else if let Some(content_type) = multipart_content_type {
+            match read_multipart_body(&content_type, body, &self.multipart_config).await {
+                Ok(parts) => InputHttpRequestBody::Multipart(parts),
+                Err(err @ MultipartError::TooLarge(_)) => {
+                    info!("API request host: {} - error: {}", host, err);
+                    return ProblemDetails::new(ErrorCode::PayloadTooLarge, err.to_string())
+                        .to_http_response();
+                }
+                Err(err) => {
+                    error!("API request host: {} - error: {}", host, err);
+                    return ProblemDetails::new(
+                        ErrorCode::InvalidRequest,
+                        "Request body parse error",
+                    )
+                    .to_http_response();
+                }
+            }
+        } else {
+            match body.into_json().await {
+                Ok(json_request_body) => InputHttpRequestBody::Json(json_request_body),
+                Err(err) => {
+                    error!("API request host: {} - error: {}", host, err);
+                    return ProblemDetails::new(
+                        ErrorCode::InvalidRequest,
+                        "Request body parse error",
+                    )
+                    .to_http_response();
+                }
+            }
+        };
+
+        input_http_request.req_body = request_body;
+
+        // Cross-origin requests are answered according to the CORS policy of the matched route.
+        // Allowed preflight requests are answered by the gateway, without reaching any worker,
+        // while the other ones are routed like any other request
+        let cors_headers = match &origin {
+            Some(origin) => match input_http_request.cors_preflight_method() {
+                Some(requested_method) => {
+                    let preflight_headers = input_http_request
+                        .resolve_cors(possible_api_definitions.clone(), &requested_method)
+                        .and_then(|cors| cors.preflight_headers(origin, &requested_method));
+
+                    if let Some(preflight_headers) = preflight_headers {
+                        let mut response =
+                            Response::builder().status(StatusCode::NO_CONTENT).finish();
+                        response.headers_mut().extend(preflight_headers);
+                        return response;
+                    }
+
+                    None
+                }
+                None => input_http_request
+                    .resolve_cors(
+                        possible_api_definitions.clone(),
+                        &input_http_request.req_method,
+                    )
+                    .and_then(|cors| cors.response_headers(origin)),
+            },
+            None => None,
+        };
+
+        // Responses of cacheable routes are served from the cache until they expire, unless the
+        // request asks for a fresh one with `Cache-Control: no-cache` or `no-store`
+        let cache = input_http_request.resolve_cache(possible_api_definitions.clone());
+        let cache_control = CacheControl::from_headers(&input_http_request.headers);
+
+        if let Some((_, key)) = &cache {
+            if !cache_control.bypass_lookup {
+                match self.response_cache.get(key).await {
+                    Ok(Some(cached)) => {
+                        let mut response = cached_to_response(cached, CACHE_HIT);
+                        if let Some(cors_headers) = cors_headers {
+                            response.headers_mut().extend(cors_headers);
+                        }
+                        return response;
+                    }
+                    Ok(None) => {}
+                    Err(err) => {
+                        warn!("API request host: {} - response cache error: {}", host, err)
+                    }
+                }
+            }
+        }
+
+        let mut response = match input_http_request
+            .resolve_worker_binding(possible_api_definitions, &self.jwt_validator)
             .await
         {
             Ok(resolved_worker_binding) => {
@@ -108,6 +225,22 @@ impl CustomHttpRequestApi {
                     .await
             }
 
+            Err(WorkerBindingResolutionError::Unauthorized(msg)) => {
+                info!("API request host: {} - unauthorized: {}", host, msg);
+
+                ProblemDetails::new(ErrorCode::Unauthorized, "Unauthorized").to_http_response()
+            }
+
+            Err(WorkerBindingResolutionError::AuthenticationUnavailable(msg)) => {
+                warn!(
+                    "API request host: {} - authentication unavailable: {}",
+                    host, msg
+                );
+
+                ProblemDetails::new(ErrorCode::Unavailable, "Authentication unavailable")
+                    .to_http_response()
+            }
+
             Err(msg) => {
                 error!("Failed to resolve the API definition; error: {}", msg);
 
@@ -115,8 +248,110 @@ impl CustomHttpRequestApi {
                     .status(StatusCode::METHOD_NOT_ALLOWED)
                     .finish()
             }
+        };
+
+        if let Some((route_cache, key)) = cache {
+            if cache_control.bypass_store {
+                response
+                    .headers_mut()
+                    .insert(X_GOLEM_CACHE, HeaderValue::from_static(CACHE_BYPASS));
+            } else if response.status().is_success() {
+                response = self.store_response(key, route_cache.ttl(), response).await;
+            }
         }
+
+        if let Some(cors_headers) = cors_headers {
+            response.headers_mut().extend(cors_headers);
+        }
+
+        response
     }
+
+    async fn store_response(
+        &self,
+        key: HttpResponseCacheKey,
+        ttl: Duration,
+        response: Response,
+    ) -> Response {
+        let (parts, body) = response.into_parts();
+
+        let body = match body.into_vec().await {
+            Ok(body) => body,
+            Err(err) => {
+                error!("Failed to read the response to be cached; error: {}", err);
+                return Response::builder()
+                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .finish();
+            }
+        };
+
+        let cached = CachedHttpResponse {
+            status: parts.status.as_u16(),
+            headers: parts
+                .headers
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.as_bytes().to_vec()))
+                .collect(),
+            body,
+        };
+
+        if let Err(err) = self.response_cache.put(&key, &cached, ttl).await {
+            warn!("Failed to cache the response of {:?}; error: {}", key, err);
+        }
+
+        cached_to_response(cached, CACHE_MISS)
+    }
+}
+
+const X_GOLEM_CACHE: &str = "x-golem-cache";
+const CACHE_HIT: &str = "hit";
+const CACHE_MISS: &str = "miss";
+const CACHE_BYPASS: &str = "bypass";
+
+struct CacheControl {
+    bypass_lookup: bool,
+    bypass_store: bool,
+}
+
+impl CacheControl {
+    fn from_headers(headers: &HeaderMap) -> Self {
+        let directives: Vec<String> = headers
+            .get_all(CACHE_CONTROL)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(|directive| directive.trim().to_lowercase())
+            .collect();
+
+        let no_store = directives.iter().any(|d| d == "no-store");
+        let no_cache = directives.iter().any(|d| d == "no-cache");
+
+        CacheControl {
+            bypass_lookup: no_cache || no_store,
+            bypass_store: no_store,
+        }
+    }
+}
+
+fn cached_to_response(cached: CachedHttpResponse, cache_status: &'static str) -> Response {
+    let mut response = Response::builder()
+        .status(StatusCode::from_u16(cached.status).unwrap_or(StatusCode::OK))
+        .body(Body::from_vec(cached.body));
+
+    for (name, value) in cached.headers {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_bytes(&value),
+        ) {
+            response.headers_mut().append(name, value);
+        }
+    }
+
+    response
+        .headers_mut()
+        .insert(X_GOLEM_CACHE, HeaderValue::from_static(cache_status));
+
+    response
 }
 
 impl Endpoint for CustomHttpRequestApi {
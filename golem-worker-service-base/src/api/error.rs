@@ -1,6 +1,7 @@
 use crate::service::component::ComponentServiceError;
 use crate::service::worker::WorkerServiceError;
 use golem_common::metrics::api::TraceErrorKind;
+use golem_common::model::error::ErrorCode;
 use golem_common::SafeDisplay;
 use golem_service_base::model::*;
 use poem_openapi::payload::Json;
@@ -25,8 +26,14 @@ pub enum WorkerApiBaseError {
     NotFound(Json<ErrorBody>),
     #[oai(status = 409)]
     AlreadyExists(Json<ErrorBody>),
+    #[oai(status = 429)]
+    TooManyRequests(Json<GolemErrorBody>),
     #[oai(status = 500)]
     InternalError(Json<GolemErrorBody>),
+    #[oai(status = 503)]
+    ServiceUnavailable(Json<GolemErrorBody>),
+    #[oai(status = 504)]
+    GatewayTimeout(Json<GolemErrorBody>),
 }
 
 impl TraceErrorKind for WorkerApiBaseError {
@@ -37,7 +44,10 @@ impl TraceErrorKind for WorkerApiBaseError {
             WorkerApiBaseError::AlreadyExists(_) => "AlreadyExists",
             WorkerApiBaseError::Forbidden(_) => "Forbidden",
             WorkerApiBaseError::Unauthorized(_) => "Unauthorized",
+            WorkerApiBaseError::TooManyRequests(_) => "TooManyRequests",
             WorkerApiBaseError::InternalError(_) => "InternalError",
+            WorkerApiBaseError::ServiceUnavailable(_) => "ServiceUnavailable",
+            WorkerApiBaseError::GatewayTimeout(_) => "GatewayTimeout",
         }
     }
 }
@@ -85,21 +95,64 @@ impl From<WorkerServiceError> for WorkerApiBaseError {
             ServiceError::TypeChecker(_) => WorkerApiBaseError::BadRequest(Json(ErrorsBody {
                 errors: vec![error.to_safe_string()],
             })),
+            ServiceError::InvalidParameters(errors) => {
+                WorkerApiBaseError::BadRequest(Json(ErrorsBody { errors }))
+            }
             ServiceError::VersionedComponentIdNotFound(_)
             | ServiceError::ComponentNotFound(_)
             | ServiceError::AccountIdNotFound(_)
             | ServiceError::WorkerNotFound(_) => WorkerApiBaseError::NotFound(Json(ErrorBody {
                 error: error.to_safe_string(),
             })),
-            ServiceError::Golem(golem_error) => {
-                WorkerApiBaseError::InternalError(Json(GolemErrorBody { golem_error }))
-            }
+            ServiceError::Forbidden(_) => WorkerApiBaseError::Forbidden(Json(ErrorBody {
+                error: error.to_safe_string(),
+            })),
+            ServiceError::Golem(golem_error) => golem_error.into(),
             ServiceError::Component(error) => error.into(),
             ServiceError::InternalCallError(_) => internal(error.to_safe_string()),
         }
     }
 }
 
+// Errors of the worker executors are returned with the status of their error code, keeping the
+// structured error where the response has a place for it
+impl From<GolemError> for WorkerApiBaseError {
+    fn from(golem_error: GolemError) -> Self {
+        match golem_error.code() {
+            ErrorCode::InvalidRequest => WorkerApiBaseError::BadRequest(Json(ErrorsBody {
+                errors: vec![golem_error.to_safe_string()],
+            })),
+            ErrorCode::Unauthorized => WorkerApiBaseError::Unauthorized(Json(ErrorBody {
+                error: golem_error.to_safe_string(),
+            })),
+            ErrorCode::Forbidden => WorkerApiBaseError::Forbidden(Json(ErrorBody {
+                error: golem_error.to_safe_string(),
+            })),
+            ErrorCode::NotFound => WorkerApiBaseError::NotFound(Json(ErrorBody {
+                error: golem_error.to_safe_string(),
+            })),
+            // Conflicts share the 409 response of already existing entities
+            ErrorCode::AlreadyExists | ErrorCode::Conflict => {
+                WorkerApiBaseError::AlreadyExists(Json(ErrorBody {
+                    error: golem_error.to_safe_string(),
+                }))
+            }
+            ErrorCode::TooManyRequests => {
+                WorkerApiBaseError::TooManyRequests(Json(GolemErrorBody { golem_error }))
+            }
+            ErrorCode::Timeout => {
+                WorkerApiBaseError::GatewayTimeout(Json(GolemErrorBody { golem_error }))
+            }
+            ErrorCode::Unavailable => {
+                WorkerApiBaseError::ServiceUnavailable(Json(GolemErrorBody { golem_error }))
+            }
+            ErrorCode::PayloadTooLarge | ErrorCode::Internal => {
+                WorkerApiBaseError::InternalError(Json(GolemErrorBody { golem_error }))
+            }
+        }
+    }
+}
+
 impl From<ComponentServiceError> for WorkerApiBaseError {
     fn from(value: ComponentServiceError) -> Self {
         match value {
@@ -141,3 +194,71 @@ impl From<ComponentServiceError> for WorkerApiBaseError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use golem_common::model::{ComponentId, IdempotencyKey, WorkerId, WorkerName};
+    use golem_service_base::model::{
+        GolemError, GolemErrorInterrupted, GolemErrorInvocationNotFound,
+        GolemErrorInvocationTimedOut, GolemErrorShardingNotReady,
+    };
+    use uuid::Uuid;
+
+    use crate::api::error::WorkerApiBaseError;
+
+    #[test]
+    fn timed_out_invocations_are_gateway_timeouts() {
+        let error = GolemError::InvocationTimedOut(GolemErrorInvocationTimedOut {
+            worker_id: WorkerId {
+                component_id: ComponentId(Uuid::new_v4()),
+                worker_name: WorkerName::from_existing("worker-1"),
+            },
+            timeout_millis: 1000,
+        });
+
+        assert!(matches!(
+            WorkerApiBaseError::from(error),
+            WorkerApiBaseError::GatewayTimeout(_)
+        ));
+    }
+
+    #[test]
+    fn unknown_invocations_are_not_found() {
+        let error = GolemError::InvocationNotFound(GolemErrorInvocationNotFound {
+            worker_id: WorkerId {
+                component_id: ComponentId(Uuid::new_v4()),
+                worker_name: WorkerName::from_existing("worker-1"),
+            },
+            idempotency_key: IdempotencyKey::fresh(),
+        });
+
+        assert!(matches!(
+            WorkerApiBaseError::from(error),
+            WorkerApiBaseError::NotFound(_)
+        ));
+    }
+
+    #[test]
+    fn unavailable_executors_are_service_unavailable() {
+        let error = GolemError::ShardingNotReady(GolemErrorShardingNotReady {});
+
+        assert!(matches!(
+            WorkerApiBaseError::from(error),
+            WorkerApiBaseError::ServiceUnavailable(_)
+        ));
+    }
+
+    #[test]
+    fn conflicts_are_conflict_responses() {
+        let error = GolemError::Interrupted(GolemErrorInterrupted {
+            recover_immediately: false,
+        });
+
+        assert!(matches!(
+            WorkerApiBaseError::from(error),
+            WorkerApiBaseError::AlreadyExists(_)
+        ));
+    }
+}
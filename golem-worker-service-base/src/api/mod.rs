@@ -1,12 +1,18 @@
+pub use api_key::*;
+pub use api_key_middleware::*;
 pub use common::*;
 pub use custom_http_request_api::*;
 pub use error::*;
 pub use healthcheck::*;
 pub use register_api_definition_api::*;
+pub use rib_evaluation::*;
 
 // Components and request data that can be reused for implementing server API endpoints
+mod api_key;
+mod api_key_middleware;
 mod common;
 mod custom_http_request_api;
 mod error;
 mod healthcheck;
 mod register_api_definition_api;
+mod rib_evaluation;
@@ -6,10 +6,11 @@ use std::result::Result;
 use std::time::SystemTime;
 
 use crate::api_definition::http::{
-    AllPathPatterns, CompiledHttpApiDefinition, CompiledRoute, MethodPattern,
+    AllPathPatterns, CompiledHttpApiDefinition, CompiledRoute, HttpApiAuth, HttpApiCors,
+    HttpRouteCache, MethodPattern,
 };
 use crate::api_definition::{ApiDefinitionId, ApiSite, ApiVersion};
-use crate::worker_binding::CompiledGolemWorkerBinding;
+use crate::worker_binding::{CompiledGolemWorkerBinding, WorkerBindingMode};
 use rib::{Expr, RibInputTypeInfo};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
@@ -49,6 +50,8 @@ pub struct HttpApiDefinitionRequest {
     pub routes: Vec<Route>,
     #[serde(default)]
     pub draft: bool,
+    pub auth: Option<HttpApiAuth>,
+    pub cors: Option<HttpApiCors>,
 }
 
 // Mostly this data structures that represents the actual incoming request
@@ -63,6 +66,8 @@ pub struct HttpApiDefinition {
     pub routes: Vec<Route>,
     #[serde(default)]
     pub draft: bool,
+    pub auth: Option<HttpApiAuth>,
+    pub cors: Option<HttpApiCors>,
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
@@ -76,6 +81,8 @@ pub struct HttpApiDefinitionWithTypeInfo {
     pub routes: Vec<RouteWithTypeInfo>,
     #[serde(default)]
     pub draft: bool,
+    pub auth: Option<HttpApiAuth>,
+    pub cors: Option<HttpApiCors>,
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
@@ -88,6 +95,8 @@ impl From<CompiledHttpApiDefinition> for HttpApiDefinitionWithTypeInfo {
             version: value.version,
             routes,
             draft: value.draft,
+            auth: value.auth,
+            cors: value.cors,
             created_at: Some(value.created_at),
         }
     }
@@ -98,6 +107,7 @@ pub struct Route {
     pub method: MethodPattern,
     pub path: String,
     pub binding: GolemWorkerBinding,
+    pub cache: Option<HttpRouteCache>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
@@ -105,6 +115,7 @@ pub struct RouteWithTypeInfo {
     pub method: MethodPattern,
     pub path: String,
     pub binding: GolemWorkerBindingWithTypeInfo,
+    pub cache: Option<HttpRouteCache>,
 }
 
 impl From<CompiledRoute> for RouteWithTypeInfo {
@@ -116,6 +127,7 @@ impl From<CompiledRoute> for RouteWithTypeInfo {
             method,
             path,
             binding,
+            cache: value.cache,
         }
     }
 }
@@ -128,6 +140,9 @@ pub struct GolemWorkerBinding {
     pub worker_name: String,
     pub idempotency_key: Option<String>,
     pub response: String,
+    #[serde(default)]
+    #[oai(default)]
+    pub mode: WorkerBindingMode,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
@@ -141,6 +156,7 @@ pub struct GolemWorkerBindingWithTypeInfo {
     pub response_mapping_input: Option<RibInputTypeInfo>,
     pub worker_name_input: Option<RibInputTypeInfo>,
     pub idempotency_key_input: Option<RibInputTypeInfo>,
+    pub mode: WorkerBindingMode,
 }
 
 impl From<CompiledGolemWorkerBinding> for GolemWorkerBindingWithTypeInfo {
@@ -162,6 +178,7 @@ impl From<CompiledGolemWorkerBinding> for GolemWorkerBindingWithTypeInfo {
             idempotency_key_input: value
                 .idempotency_key_compiled
                 .map(|idempotency_key_compiled| idempotency_key_compiled.rib_input),
+            mode: value.mode,
         }
     }
 }
@@ -202,6 +219,8 @@ impl TryFrom<crate::api_definition::http::HttpApiDefinition> for HttpApiDefiniti
             version: value.version,
             routes,
             draft: value.draft,
+            auth: value.auth,
+            cors: value.cors,
             created_at: Some(value.created_at),
         })
     }
@@ -225,6 +244,8 @@ impl TryInto<crate::api_definition::http::HttpApiDefinitionRequest> for HttpApiD
             version: self.version,
             routes,
             draft: self.draft,
+            auth: self.auth,
+            cors: self.cors,
         })
     }
 }
@@ -240,6 +261,7 @@ impl TryFrom<crate::api_definition::http::Route> for Route {
             method: value.method,
             path,
             binding,
+            cache: value.cache,
         })
     }
 }
@@ -255,6 +277,7 @@ impl TryInto<crate::api_definition::http::Route> for Route {
             method: self.method,
             path,
             binding,
+            cache: self.cache,
         })
     }
 }
@@ -278,6 +301,7 @@ impl TryFrom<crate::worker_binding::GolemWorkerBinding> for GolemWorkerBinding {
             worker_name: worker_id,
             idempotency_key,
             response,
+            mode: value.mode,
         })
     }
 }
@@ -305,6 +329,7 @@ impl TryInto<crate::worker_binding::GolemWorkerBinding> for GolemWorkerBinding {
             worker_name,
             idempotency_key,
             response,
+            mode: self.mode,
         })
     }
 }
@@ -323,7 +348,11 @@ impl TryFrom<crate::api_definition::http::HttpApiDefinition> for grpc_apidefinit
 
         let id = value.id.0;
 
-        let definition = grpc_apidefinition::HttpApiDefinition { routes };
+        let definition = grpc_apidefinition::HttpApiDefinition {
+            routes,
+            auth: value.auth.map(|auth| auth.into()),
+            cors: value.cors.map(|cors| cors.into()),
+        };
 
         let created_at = prost_types::Timestamp::from(SystemTime::from(value.created_at));
 
@@ -345,12 +374,15 @@ impl TryFrom<grpc_apidefinition::ApiDefinition> for crate::api_definition::http:
     type Error = String;
 
     fn try_from(value: grpc_apidefinition::ApiDefinition) -> Result<Self, Self::Error> {
-        let routes = match value.definition.ok_or("definition is missing")? {
-            grpc_apidefinition::api_definition::Definition::Http(http) => http
-                .routes
-                .into_iter()
-                .map(crate::api_definition::http::Route::try_from)
-                .collect::<Result<Vec<crate::api_definition::http::Route>, String>>()?,
+        let (routes, auth, cors) = match value.definition.ok_or("definition is missing")? {
+            grpc_apidefinition::api_definition::Definition::Http(http) => (
+                http.routes
+                    .into_iter()
+                    .map(crate::api_definition::http::Route::try_from)
+                    .collect::<Result<Vec<crate::api_definition::http::Route>, String>>()?,
+                http.auth.map(HttpApiAuth::try_from).transpose()?,
+                http.cors.map(HttpApiCors::try_from).transpose()?,
+            ),
         };
 
         let id = value.id.ok_or("Api Definition ID is missing")?;
@@ -364,6 +396,8 @@ impl TryFrom<grpc_apidefinition::ApiDefinition> for crate::api_definition::http:
             version: ApiVersion(value.version),
             routes,
             draft: value.draft,
+            auth,
+            cors,
             created_at: created_at.into(),
         };
 
@@ -377,12 +411,15 @@ impl TryFrom<grpc_apidefinition::v1::ApiDefinitionRequest>
     type Error = String;
 
     fn try_from(value: grpc_apidefinition::v1::ApiDefinitionRequest) -> Result<Self, Self::Error> {
-        let routes = match value.definition.ok_or("definition is missing")? {
-            grpc_apidefinition::v1::api_definition_request::Definition::Http(http) => http
-                .routes
-                .into_iter()
-                .map(crate::api_definition::http::Route::try_from)
-                .collect::<Result<Vec<crate::api_definition::http::Route>, String>>()?,
+        let (routes, auth, cors) = match value.definition.ok_or("definition is missing")? {
+            grpc_apidefinition::v1::api_definition_request::Definition::Http(http) => (
+                http.routes
+                    .into_iter()
+                    .map(crate::api_definition::http::Route::try_from)
+                    .collect::<Result<Vec<crate::api_definition::http::Route>, String>>()?,
+                http.auth.map(HttpApiAuth::try_from).transpose()?,
+                http.cors.map(HttpApiCors::try_from).transpose()?,
+            ),
         };
 
         let id = value.id.ok_or("Api Definition ID is missing")?;
@@ -392,6 +429,8 @@ impl TryFrom<grpc_apidefinition::v1::ApiDefinitionRequest>
             version: ApiVersion(value.version),
             routes,
             draft: value.draft,
+            auth,
+            cors,
         };
 
         Ok(result)
@@ -410,6 +449,7 @@ impl TryFrom<crate::api_definition::http::Route> for grpc_apidefinition::HttpRou
             method: method as i32,
             path,
             binding: Some(binding),
+            cache: value.cache.map(|cache| cache.into()),
         };
 
         Ok(result)
@@ -427,6 +467,7 @@ impl TryFrom<CompiledRoute> for golem_api_grpc::proto::golem::apidefinition::Com
             method,
             path,
             binding: Some(binding),
+            cache: value.cache.map(|cache| cache.into()),
         })
     }
 }
@@ -444,6 +485,7 @@ impl TryFrom<golem_api_grpc::proto::golem::apidefinition::CompiledHttpRoute> for
             method,
             path,
             binding,
+            cache: value.cache.map(HttpRouteCache::from),
         })
     }
 }
@@ -477,6 +519,7 @@ impl TryFrom<grpc_apidefinition::HttpRoute> for crate::api_definition::http::Rou
             method,
             path,
             binding,
+            cache: value.cache.map(HttpRouteCache::from),
         };
 
         Ok(result)
@@ -493,11 +536,14 @@ impl TryFrom<crate::worker_binding::GolemWorkerBinding> for grpc_apidefinition::
 
         let idempotency_key = value.idempotency_key.map(|key| key.into());
 
+        let mode: grpc_apidefinition::WorkerBindingMode = value.mode.into();
+
         let result = grpc_apidefinition::WorkerBinding {
             component: Some(value.component_id.into()),
             worker_name,
             idempotency_key,
             response,
+            mode: mode as i32,
         };
 
         Ok(result)
@@ -526,11 +572,14 @@ impl TryFrom<grpc_apidefinition::WorkerBinding> for crate::worker_binding::Golem
             None
         };
 
+        let mode = value.mode().into();
+
         let result = crate::worker_binding::GolemWorkerBinding {
             component_id,
             worker_name,
             idempotency_key,
             response,
+            mode,
         };
 
         Ok(result)
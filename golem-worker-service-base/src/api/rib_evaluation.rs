@@ -0,0 +1,105 @@
+use golem_common::model::ComponentId;
+use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
+use poem_openapi::*;
+use rib::{RibEvaluationError, RibInterpreterResult};
+use serde::{Deserialize, Serialize};
+
+use crate::service::rib_evaluation::RibEvaluationComponent;
+
+/// A Rib script to evaluate with sample input
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct RibEvaluationRequest {
+    pub rib: String,
+    /// Values of the global variables used by the script, such as `{"request": {"body": {}}}`
+    pub input: Option<serde_json::Value>,
+    /// Component whose exported functions can be called by the script
+    pub component_id: Option<ComponentId>,
+    /// Version of the component, the latest one if not given
+    pub component_version: Option<u64>,
+    /// Worker of the component the function calls of the script are invoked on.
+    /// Calling a function fails if not given.
+    pub worker_name: Option<String>,
+}
+
+impl RibEvaluationRequest {
+    pub fn component(&self) -> Option<RibEvaluationComponent> {
+        self.component_id
+            .clone()
+            .map(|component_id| RibEvaluationComponent {
+                component_id,
+                component_version: self.component_version,
+                worker_name: self.worker_name.clone(),
+            })
+    }
+}
+
+/// Either the typed result of the evaluated script, which is missing if the script has no result,
+/// or the reason it could not be evaluated
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct RibEvaluationResponse {
+    pub result: Option<TypeAnnotatedValue>,
+    pub error: Option<RibEvaluationErrorBody>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum RibEvaluationErrorKind {
+    Syntax,
+    Compilation,
+    Input,
+    Runtime,
+}
+
+/// Syntax errors have the line and column (both starting from 1) where parsing failed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct RibEvaluationErrorBody {
+    pub kind: RibEvaluationErrorKind,
+    pub message: String,
+    pub line: Option<i32>,
+    pub column: Option<i32>,
+}
+
+impl From<Result<RibInterpreterResult, RibEvaluationError>> for RibEvaluationResponse {
+    fn from(value: Result<RibInterpreterResult, RibEvaluationError>) -> Self {
+        match value {
+            Ok(result) => Self {
+                result: result.get_val(),
+                error: None,
+            },
+            Err(error) => Self {
+                result: None,
+                error: Some(error.into()),
+            },
+        }
+    }
+}
+
+impl From<RibEvaluationError> for RibEvaluationErrorBody {
+    fn from(value: RibEvaluationError) -> Self {
+        let (kind, message) = match value {
+            RibEvaluationError::Syntax(error) => {
+                return Self {
+                    kind: RibEvaluationErrorKind::Syntax,
+                    message: error.message,
+                    line: Some(error.line),
+                    column: Some(error.column),
+                }
+            }
+            RibEvaluationError::Compilation(error) => (RibEvaluationErrorKind::Compilation, error),
+            RibEvaluationError::Input(error) => (RibEvaluationErrorKind::Input, error),
+            RibEvaluationError::Runtime(error) => (RibEvaluationErrorKind::Runtime, error),
+        };
+
+        Self {
+            kind,
+            message,
+            line: None,
+            column: None,
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use golem_api_grpc::proto::golem::apidefinition as grpc_apidefinition;
+use poem_openapi::Object;
+use serde::{Deserialize, Serialize};
+
+/// Authentication required by every route of an HTTP API definition, checked before any worker is invoked
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct HttpApiAuth {
+    pub jwt: JwtAuth,
+}
+
+/// Bearer JWT validation, exposing the verified claims to Rib as `request.auth.claims`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct JwtAuth {
+    /// Expected value of the `iss` claim
+    pub issuer: String,
+    /// Accepted values of the `aud` claim. When empty, the audience is not checked
+    #[serde(default)]
+    #[oai(default)]
+    pub audience: Vec<String>,
+    /// URL of the JSON Web Key Set used to verify token signatures
+    pub jwks_url: String,
+}
+
+impl From<HttpApiAuth> for grpc_apidefinition::HttpApiAuth {
+    fn from(value: HttpApiAuth) -> Self {
+        grpc_apidefinition::HttpApiAuth {
+            jwt: Some(value.jwt.into()),
+        }
+    }
+}
+
+impl TryFrom<grpc_apidefinition::HttpApiAuth> for HttpApiAuth {
+    type Error = String;
+
+    fn try_from(value: grpc_apidefinition::HttpApiAuth) -> Result<Self, Self::Error> {
+        Ok(HttpApiAuth {
+            jwt: value.jwt.ok_or("jwt auth is missing")?.into(),
+        })
+    }
+}
+
+impl From<JwtAuth> for grpc_apidefinition::JwtAuth {
+    fn from(value: JwtAuth) -> Self {
+        grpc_apidefinition::JwtAuth {
+            issuer: value.issuer,
+            audience: value.audience,
+            jwks_url: value.jwks_url,
+        }
+    }
+}
+
+impl From<grpc_apidefinition::JwtAuth> for JwtAuth {
+    fn from(value: grpc_apidefinition::JwtAuth) -> Self {
+        JwtAuth {
+            issuer: value.issuer,
+            audience: value.audience,
+            jwks_url: value.jwks_url,
+        }
+    }
+}
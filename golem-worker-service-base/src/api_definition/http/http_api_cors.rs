@@ -0,0 +1,195 @@
+use crate::api_definition::http::MethodPattern;
+use golem_api_grpc::proto::golem::apidefinition as grpc_apidefinition;
+use http::header::{
+    ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_METHODS, ACCESS_CONTROL_ALLOW_ORIGIN,
+    ACCESS_CONTROL_MAX_AGE, VARY,
+};
+use http::{HeaderMap, HeaderValue, Method};
+use poem_openapi::Object;
+use serde::{Deserialize, Serialize};
+
+/// CORS policy of an HTTP API definition, applied by the gateway to the preflight and actual requests of its routes
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct HttpApiCors {
+    /// Origins allowed to call the API, or `*` to allow any origin
+    pub allow_origins: Vec<String>,
+    /// Methods allowed in cross-origin requests. When empty, the methods of the defined routes are allowed
+    #[serde(default)]
+    #[oai(default)]
+    pub allow_methods: Vec<MethodPattern>,
+    /// Request headers allowed in cross-origin requests, or `*` to allow any header
+    #[serde(default)]
+    #[oai(default)]
+    pub allow_headers: Vec<String>,
+    /// Number of seconds browsers can cache the answer to a preflight request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age: Option<u64>,
+}
+
+impl HttpApiCors {
+    // Headers answering a preflight request asking for the given method,
+    // or None if the origin or the method is not allowed
+    pub fn preflight_headers(&self, origin: &str, method: &Method) -> Option<HeaderMap> {
+        let allows_method = self.allow_methods.is_empty()
+            || self
+                .allow_methods
+                .iter()
+                .any(|allowed| Method::from(allowed.clone()) == *method);
+
+        if !allows_method {
+            return None;
+        }
+
+        let mut headers = self.response_headers(origin)?;
+
+        let allow_methods = if self.allow_methods.is_empty() {
+            method.to_string()
+        } else {
+            self.allow_methods
+                .iter()
+                .map(|allowed| Method::from(allowed.clone()).to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        headers.insert(
+            ACCESS_CONTROL_ALLOW_METHODS,
+            HeaderValue::from_str(&allow_methods).ok()?,
+        );
+
+        if !self.allow_headers.is_empty() {
+            headers.insert(
+                ACCESS_CONTROL_ALLOW_HEADERS,
+                HeaderValue::from_str(&self.allow_headers.join(", ")).ok()?,
+            );
+        }
+
+        if let Some(max_age) = self.max_age {
+            headers.insert(ACCESS_CONTROL_MAX_AGE, HeaderValue::from(max_age));
+        }
+
+        Some(headers)
+    }
+
+    // Headers attached to the response of a cross-origin request,
+    // or None if the origin is not allowed
+    pub fn response_headers(&self, origin: &str) -> Option<HeaderMap> {
+        let mut headers = HeaderMap::new();
+
+        if self.allow_origins.iter().any(|allowed| allowed == "*") {
+            headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, HeaderValue::from_static("*"));
+        } else if self.allow_origins.iter().any(|allowed| allowed == origin) {
+            headers.insert(
+                ACCESS_CONTROL_ALLOW_ORIGIN,
+                HeaderValue::from_str(origin).ok()?,
+            );
+            // The response depends on the origin, so it must not be cached for other ones
+            headers.insert(VARY, HeaderValue::from_static("Origin"));
+        } else {
+            return None;
+        }
+
+        Some(headers)
+    }
+}
+
+impl From<HttpApiCors> for grpc_apidefinition::HttpApiCors {
+    fn from(value: HttpApiCors) -> Self {
+        grpc_apidefinition::HttpApiCors {
+            allow_origins: value.allow_origins,
+            allow_methods: value
+                .allow_methods
+                .into_iter()
+                .map(|method| grpc_apidefinition::HttpMethod::from(method) as i32)
+                .collect(),
+            allow_headers: value.allow_headers,
+            max_age: value.max_age,
+        }
+    }
+}
+
+impl TryFrom<grpc_apidefinition::HttpApiCors> for HttpApiCors {
+    type Error = String;
+
+    fn try_from(value: grpc_apidefinition::HttpApiCors) -> Result<Self, Self::Error> {
+        let allow_methods = value
+            .allow_methods
+            .into_iter()
+            .map(|method| MethodPattern::try_from(method).map_err(|e| e.to_string()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(HttpApiCors {
+            allow_origins: value.allow_origins,
+            allow_methods,
+            allow_headers: value.allow_headers,
+            max_age: value.max_age,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::api_definition::http::{HttpApiCors, MethodPattern};
+    use http::header::{
+        ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_METHODS, ACCESS_CONTROL_ALLOW_ORIGIN,
+        ACCESS_CONTROL_MAX_AGE, VARY,
+    };
+    use http::Method;
+
+    fn cors(allow_origins: Vec<&str>, allow_methods: Vec<MethodPattern>) -> HttpApiCors {
+        HttpApiCors {
+            allow_origins: allow_origins.into_iter().map(|s| s.to_string()).collect(),
+            allow_methods,
+            allow_headers: vec!["content-type".to_string(), "authorization".to_string()],
+            max_age: Some(600),
+        }
+    }
+
+    #[test]
+    fn preflight_of_allowed_origin() {
+        let cors = cors(vec!["https://app.example.com"], vec![]);
+
+        let headers = cors
+            .preflight_headers("https://app.example.com", &Method::PUT)
+            .unwrap();
+
+        assert_eq!(
+            headers.get(ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(),
+            "https://app.example.com"
+        );
+        assert_eq!(headers.get(VARY).unwrap(), "Origin");
+        assert_eq!(headers.get(ACCESS_CONTROL_ALLOW_METHODS).unwrap(), "PUT");
+        assert_eq!(
+            headers.get(ACCESS_CONTROL_ALLOW_HEADERS).unwrap(),
+            "content-type, authorization"
+        );
+        assert_eq!(headers.get(ACCESS_CONTROL_MAX_AGE).unwrap(), "600");
+
+        assert!(cors
+            .preflight_headers("https://other.example.com", &Method::PUT)
+            .is_none());
+    }
+
+    #[test]
+    fn preflight_of_restricted_methods() {
+        let cors = cors(vec!["*"], vec![MethodPattern::Get, MethodPattern::Post]);
+
+        let headers = cors
+            .preflight_headers("https://app.example.com", &Method::POST)
+            .unwrap();
+
+        assert_eq!(headers.get(ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(), "*");
+        assert!(headers.get(VARY).is_none());
+        assert_eq!(
+            headers.get(ACCESS_CONTROL_ALLOW_METHODS).unwrap(),
+            "GET, POST"
+        );
+
+        assert!(cors
+            .preflight_headers("https://app.example.com", &Method::DELETE)
+            .is_none());
+    }
+}
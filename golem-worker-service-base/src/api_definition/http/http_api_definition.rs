@@ -11,6 +11,7 @@ use poem_openapi::Enum;
 use serde::{Deserialize, Serialize, Serializer};
 use serde_json::Value;
 
+use crate::api_definition::http::{HttpApiAuth, HttpApiCors, HttpRouteCache};
 use crate::api_definition::{ApiDefinitionId, ApiVersion, HasGolemWorkerBindings};
 use crate::parser::path_pattern_parser::PathPatternParser;
 use crate::parser::{GolemParser, ParseError};
@@ -25,6 +26,10 @@ pub struct HttpApiDefinitionRequest {
     pub routes: Vec<Route>,
     #[serde(default)]
     pub draft: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<HttpApiAuth>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cors: Option<HttpApiCors>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -35,6 +40,10 @@ pub struct HttpApiDefinition {
     pub routes: Vec<Route>,
     #[serde(default)]
     pub draft: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<HttpApiAuth>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cors: Option<HttpApiCors>,
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
@@ -48,6 +57,8 @@ impl HttpApiDefinition {
             version: request.version,
             routes: request.routes,
             draft: request.draft,
+            auth: request.auth,
+            cors: request.cors,
             created_at,
         }
     }
@@ -60,6 +71,8 @@ impl From<HttpApiDefinition> for HttpApiDefinitionRequest {
             version: value.version,
             routes: value.routes,
             draft: value.draft,
+            auth: value.auth,
+            cors: value.cors,
         }
     }
 }
@@ -75,6 +88,8 @@ impl From<CompiledHttpApiDefinition> for HttpApiDefinition {
                 .map(Route::from)
                 .collect(),
             draft: compiled_http_api_definition.draft,
+            auth: compiled_http_api_definition.auth,
+            cors: compiled_http_api_definition.cors,
             created_at: compiled_http_api_definition.created_at,
         }
     }
@@ -91,6 +106,8 @@ pub struct CompiledHttpApiDefinition {
     pub version: ApiVersion,
     pub routes: Vec<CompiledRoute>,
     pub draft: bool,
+    pub auth: Option<HttpApiAuth>,
+    pub cors: Option<HttpApiCors>,
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
@@ -111,6 +128,8 @@ impl CompiledHttpApiDefinition {
             version: http_api_definition.version.clone(),
             routes: compiled_routes,
             draft: http_api_definition.draft,
+            auth: http_api_definition.auth.clone(),
+            cors: http_api_definition.cors.clone(),
             created_at: http_api_definition.created_at,
         })
     }
@@ -351,6 +370,8 @@ pub struct Route {
     pub method: MethodPattern,
     pub path: AllPathPatterns,
     pub binding: GolemWorkerBinding,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache: Option<HttpRouteCache>,
 }
 
 #[derive(Debug, Clone, PartialEq, Encode, Decode)]
@@ -358,6 +379,7 @@ pub struct CompiledRoute {
     pub method: MethodPattern,
     pub path: AllPathPatterns,
     pub binding: CompiledGolemWorkerBinding,
+    pub cache: Option<HttpRouteCache>,
 }
 
 #[derive(Debug)]
@@ -405,6 +427,7 @@ impl CompiledRoute {
             method: route.method.clone(),
             path: route.path.clone(),
             binding,
+            cache: route.cache.clone(),
         })
     }
 }
@@ -415,6 +438,7 @@ impl From<CompiledRoute> for Route {
             method: compiled_route.method,
             path: compiled_route.path,
             binding: compiled_route.binding.into(),
+            cache: compiled_route.cache,
         }
     }
 }
@@ -713,4 +737,37 @@ mod tests {
             "${ let result = golem:it/api.{do-something}(\"foo\"); {status: if result.user == \"admin\" then 401 else 200 } }",
         );
     }
+
+    #[test]
+    fn test_api_spec_with_auth_proto_conversion() {
+        let yaml_string = r#"
+          id: users-api
+          version: 0.0.1
+          createdAt: 2024-08-21T07:42:15.696Z
+          auth:
+            jwt:
+              issuer: https://issuer.example.com/
+              audience:
+              - users-api
+              jwksUrl: https://issuer.example.com/.well-known/jwks.json
+          routes:
+          - method: Get
+            path: foo/{user-id}
+            binding:
+              componentId:
+                version: 0
+                componentId: '15d70aa5-2e23-4ee3-b65c-4e1d702836a3'
+              workerName: 'request.auth.claims.sub'
+              response: '${ {status: 200} }'
+        "#;
+
+        let original: HttpApiDefinition = serde_yaml::from_str(yaml_string).unwrap();
+        let auth = original.auth.clone().unwrap();
+        assert_eq!(auth.jwt.issuer, "https://issuer.example.com/");
+        assert_eq!(auth.jwt.audience, vec!["users-api".to_string()]);
+
+        let proto: grpc_apidefinition::ApiDefinition = original.clone().try_into().unwrap();
+        let decoded: HttpApiDefinition = proto.try_into().unwrap();
+        assert_eq!(original, decoded);
+    }
 }
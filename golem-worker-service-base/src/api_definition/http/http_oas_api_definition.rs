@@ -23,6 +23,8 @@ pub fn get_api_definition(openapi: OpenAPI) -> Result<HttpApiDefinitionRequest,
         version: api_definition_version,
         routes,
         draft: true,
+        auth: None,
+        cors: None,
     })
 }
 
@@ -75,8 +77,8 @@ impl ParseFromJSON for JsonOpenApiDefinition {
 }
 
 mod internal {
-    use crate::api_definition::http::{AllPathPatterns, MethodPattern, Route};
-    use crate::worker_binding::{GolemWorkerBinding, ResponseMapping};
+    use crate::api_definition::http::{AllPathPatterns, HttpRouteCache, MethodPattern, Route};
+    use crate::worker_binding::{GolemWorkerBinding, ResponseMapping, WorkerBindingMode};
     use golem_common::model::ComponentId;
     use openapiv3::{OpenAPI, PathItem, Paths, ReferenceOr};
     use rib::Expr;
@@ -158,12 +160,14 @@ mod internal {
             component_id: get_component_id(worker_bridge_info)?,
             idempotency_key: get_idempotency_key(worker_bridge_info)?,
             response: get_response_mapping(worker_bridge_info)?,
+            mode: get_mode(worker_bridge_info)?,
         };
 
         Ok(Route {
             path: path_pattern.clone(),
             method,
             binding,
+            cache: get_cache(worker_bridge_info)?,
         })
     }
 
@@ -233,6 +237,31 @@ mod internal {
         }
     }
 
+    pub(crate) fn get_mode(worker_bridge_info: &Value) -> Result<WorkerBindingMode, String> {
+        match worker_bridge_info.get("mode") {
+            Some(mode) => match mode
+                .as_str()
+                .map(|mode| mode.to_ascii_lowercase())
+                .as_deref()
+            {
+                Some("json") => Ok(WorkerBindingMode::Json),
+                Some("raw") => Ok(WorkerBindingMode::Raw),
+                _ => Err("mode is neither json nor raw".to_string()),
+            },
+            None => Ok(WorkerBindingMode::Json),
+        }
+    }
+
+    pub(crate) fn get_cache(worker_bridge_info: &Value) -> Result<Option<HttpRouteCache>, String> {
+        worker_bridge_info
+            .get("cache")
+            .map(|cache| {
+                serde_json::from_value(cache.clone())
+                    .map_err(|err| format!("Invalid cache policy: {err}"))
+            })
+            .transpose()
+    }
+
     pub(crate) fn get_path_pattern(path: &str) -> Result<AllPathPatterns, String> {
         AllPathPatterns::parse(path).map_err(|err| err.to_string())
     }
@@ -244,7 +273,7 @@ mod tests {
 
     use super::*;
     use crate::api_definition::http::{AllPathPatterns, MethodPattern, Route};
-    use crate::worker_binding::{GolemWorkerBinding, ResponseMapping};
+    use crate::worker_binding::{GolemWorkerBinding, ResponseMapping, WorkerBindingMode};
     use golem_common::model::ComponentId;
     use openapiv3::PathItem;
     use rib::Expr;
@@ -308,8 +337,10 @@ mod tests {
                         ]
                         .into_iter()
                         .collect()
-                    ))
-                }
+                    )),
+                    mode: WorkerBindingMode::Json,
+                },
+                cache: None,
             })
         );
     }
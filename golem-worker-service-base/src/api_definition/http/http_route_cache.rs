@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use bincode::{Decode, Encode};
+use golem_api_grpc::proto::golem::apidefinition as grpc_apidefinition;
+use http::HeaderMap;
+use poem_openapi::Object;
+use serde::{Deserialize, Serialize};
+
+/// Caching of the responses of a GET route, served by the gateway without invoking the worker until they expire
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Encode, Decode, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct HttpRouteCache {
+    /// Number of seconds a cached response is served for
+    pub ttl_seconds: u64,
+    /// Request headers whose values select between the cached responses of the route
+    #[serde(default)]
+    #[oai(default)]
+    pub vary_on_headers: Vec<String>,
+    /// Query parameters whose values select between the cached responses of the route
+    #[serde(default)]
+    #[oai(default)]
+    pub vary_on_query_params: Vec<String>,
+}
+
+impl HttpRouteCache {
+    pub fn ttl(&self) -> Duration {
+        Duration::from_secs(self.ttl_seconds)
+    }
+
+    // Identifies the response of a request within its API definition. Headers and query
+    // parameters not listed in the policy are ignored, so requests only differing in them
+    // share the cached response.
+    pub fn request_key(
+        &self,
+        host: &str,
+        path: &str,
+        headers: &HeaderMap,
+        query_params: &HashMap<String, String>,
+    ) -> String {
+        let mut key = format!("{host}{path}");
+
+        for name in &self.vary_on_query_params {
+            let value = query_params.get(name).map(|v| v.as_str()).unwrap_or("");
+            key.push_str(&format!("&{name}={value}"));
+        }
+
+        for name in &self.vary_on_headers {
+            let value = headers
+                .get(name.as_str())
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or("");
+            key.push_str(&format!("|{}={value}", name.to_lowercase()));
+        }
+
+        key
+    }
+}
+
+impl From<HttpRouteCache> for grpc_apidefinition::HttpRouteCache {
+    fn from(value: HttpRouteCache) -> Self {
+        grpc_apidefinition::HttpRouteCache {
+            ttl_seconds: value.ttl_seconds,
+            vary_on_headers: value.vary_on_headers,
+            vary_on_query_params: value.vary_on_query_params,
+        }
+    }
+}
+
+impl From<grpc_apidefinition::HttpRouteCache> for HttpRouteCache {
+    fn from(value: grpc_apidefinition::HttpRouteCache) -> Self {
+        HttpRouteCache {
+            ttl_seconds: value.ttl_seconds,
+            vary_on_headers: value.vary_on_headers,
+            vary_on_query_params: value.vary_on_query_params,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::api_definition::http::HttpRouteCache;
+    use http::{HeaderMap, HeaderValue};
+    use std::collections::HashMap;
+
+    #[test]
+    fn request_key_only_varies_on_configured_values() {
+        let cache = HttpRouteCache {
+            ttl_seconds: 60,
+            vary_on_headers: vec!["Accept-Language".to_string()],
+            vary_on_query_params: vec!["page".to_string()],
+        };
+
+        let key = |language: &str, page: &str, other: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert("accept-language", HeaderValue::from_str(language).unwrap());
+            headers.insert("x-request-id", HeaderValue::from_str(other).unwrap());
+            let query_params = HashMap::from_iter(vec![
+                ("page".to_string(), page.to_string()),
+                ("sort".to_string(), other.to_string()),
+            ]);
+            cache.request_key("api.example.com", "/items", &headers, &query_params)
+        };
+
+        assert_eq!(
+            key("en", "1", "a"),
+            "api.example.com/items&page=1|accept-language=en"
+        );
+        assert_eq!(key("en", "1", "a"), key("en", "1", "b"));
+        assert_ne!(key("en", "1", "a"), key("de", "1", "a"));
+        assert_ne!(key("en", "1", "a"), key("en", "2", "a"));
+    }
+}
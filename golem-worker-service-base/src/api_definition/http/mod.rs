@@ -1,5 +1,11 @@
+pub use http_api_auth::*;
+pub use http_api_cors::*;
 pub use http_api_definition::*;
 pub use http_oas_api_definition::*;
+pub use http_route_cache::*;
 
+mod http_api_auth;
+mod http_api_cors;
 mod http_api_definition;
 mod http_oas_api_definition;
+mod http_route_cache;
@@ -1,3 +1,4 @@
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 use std::time::Duration;
 
@@ -7,7 +8,8 @@ use url::Url;
 use uuid::Uuid;
 
 use golem_common::config::{ConfigExample, HasConfigExamples, RetryConfig};
-use golem_common::config::{DbConfig, DbSqliteConfig};
+use golem_common::config::{DbConfig, DbSqliteConfig, RedisConfig};
+use golem_common::model::{ComponentId, TargetWorkerId};
 use golem_common::tracing::TracingConfig;
 use golem_service_base::routing_table::RoutingTableConfig;
 
@@ -25,6 +27,10 @@ pub struct WorkerServiceBaseConfig {
     pub worker_grpc_port: u16,
     pub routing_table: RoutingTableConfig,
     pub worker_executor_retries: RetryConfig,
+    pub worker_executor_pool: WorkerExecutorPoolConfig,
+    pub worker_placement: WorkerPlacementConfig,
+    pub response_cache: HttpResponseCacheConfig,
+    pub multipart: MultipartConfig,
 }
 
 impl WorkerServiceBaseConfig {
@@ -54,6 +60,10 @@ impl Default for WorkerServiceBaseConfig {
                 multiplier: 10.0,
                 max_jitter_factor: Some(0.15),
             },
+            worker_executor_pool: WorkerExecutorPoolConfig::default(),
+            worker_placement: WorkerPlacementConfig::default(),
+            response_cache: HttpResponseCacheConfig::default(),
+            multipart: MultipartConfig::default(),
         }
     }
 }
@@ -105,3 +115,166 @@ impl Default for ComponentServiceConfig {
         }
     }
 }
+
+// Placement of the workers created by invocations without a worker name
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WorkerPlacement {
+    // The worker lands on one of the shards owned by the executor receiving the invocation
+    #[default]
+    Local,
+    // The worker lands on any shard of the cluster, spreading the generated workers
+    // across all the executors
+    Spread,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct WorkerPlacementConfig {
+    pub default: WorkerPlacement,
+    // Overrides of the default placement, by component ID
+    pub components: HashMap<ComponentId, WorkerPlacement>,
+}
+
+impl WorkerPlacementConfig {
+    pub fn placement_of(&self, component_id: &ComponentId) -> WorkerPlacement {
+        self.components
+            .get(component_id)
+            .copied()
+            .unwrap_or(self.default)
+    }
+
+    // Generates the name of the workers targeted without one by components spreading their
+    // workers, so the new worker can land on any shard of the cluster. Other targets are kept.
+    pub fn place(&self, worker_id: &TargetWorkerId) -> TargetWorkerId {
+        match self.placement_of(&worker_id.component_id) {
+            WorkerPlacement::Spread if worker_id.worker_name.is_none() => worker_id
+                .clone()
+                .into_worker_id(&HashSet::new(), 0, None)
+                .into_target_worker_id(),
+            _ => worker_id.clone(),
+        }
+    }
+}
+
+// Storage of the cached responses of the API definition routes having a cache policy
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(tag = "type", content = "config")]
+pub enum HttpResponseCacheConfig {
+    #[default]
+    Disabled,
+    Redis(RedisConfig),
+}
+
+// Limits applied while reading the parts of multipart/form-data requests to API definitions.
+// Requests exceeding them are rejected as soon as the limit is reached, without reading the rest
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MultipartConfig {
+    // Maximum size of a single part in bytes
+    pub max_part_size: u64,
+    // Maximum size of the whole request body in bytes
+    pub max_total_size: u64,
+    // Overrides of the maximum part size, by part name
+    pub parts: HashMap<String, u64>,
+}
+
+impl Default for MultipartConfig {
+    fn default() -> Self {
+        Self {
+            max_part_size: 10 * 1024 * 1024,
+            max_total_size: 50 * 1024 * 1024,
+            parts: HashMap::new(),
+        }
+    }
+}
+
+// Health checking of the pooled worker executor connections. Connections of executors
+// failing the check, or no longer present in the routing table, are dropped from the pool
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WorkerExecutorPoolConfig {
+    #[serde(with = "humantime_serde")]
+    pub health_check_interval: Duration,
+    #[serde(with = "humantime_serde")]
+    pub health_check_timeout: Duration,
+}
+
+impl Default for WorkerExecutorPoolConfig {
+    fn default() -> Self {
+        Self {
+            health_check_interval: Duration::from_secs(30),
+            health_check_timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::collections::HashMap;
+
+    use golem_common::model::{ComponentId, TargetWorkerId, WorkerName};
+    use uuid::Uuid;
+
+    use crate::app_config::{WorkerPlacement, WorkerPlacementConfig};
+
+    fn nameless(component_id: &ComponentId) -> TargetWorkerId {
+        TargetWorkerId {
+            component_id: component_id.clone(),
+            worker_name: None,
+        }
+    }
+
+    #[test]
+    fn component_overrides_take_precedence_over_the_default_placement() {
+        let spread = ComponentId(Uuid::new_v4());
+        let other = ComponentId(Uuid::new_v4());
+        let config = WorkerPlacementConfig {
+            default: WorkerPlacement::Local,
+            components: HashMap::from([(spread.clone(), WorkerPlacement::Spread)]),
+        };
+
+        assert_eq!(config.placement_of(&spread), WorkerPlacement::Spread);
+        assert_eq!(config.placement_of(&other), WorkerPlacement::Local);
+    }
+
+    #[test]
+    fn nameless_workers_of_local_components_are_left_to_the_executor() {
+        let component_id = ComponentId(Uuid::new_v4());
+        let config = WorkerPlacementConfig::default();
+
+        let placed = config.place(&nameless(&component_id));
+
+        assert_eq!(placed, nameless(&component_id));
+    }
+
+    #[test]
+    fn nameless_workers_of_spread_components_get_a_unique_generated_name() {
+        let component_id = ComponentId(Uuid::new_v4());
+        let config = WorkerPlacementConfig {
+            default: WorkerPlacement::Local,
+            components: HashMap::from([(component_id.clone(), WorkerPlacement::Spread)]),
+        };
+
+        let first = config.place(&nameless(&component_id));
+        let second = config.place(&nameless(&component_id));
+
+        assert_eq!(first.component_id, component_id);
+        assert!(first.worker_name.is_some());
+        assert!(second.worker_name.is_some());
+        assert_ne!(first.worker_name, second.worker_name);
+    }
+
+    #[test]
+    fn named_workers_are_never_renamed() {
+        let component_id = ComponentId(Uuid::new_v4());
+        let config = WorkerPlacementConfig {
+            default: WorkerPlacement::Spread,
+            components: HashMap::new(),
+        };
+        let target = TargetWorkerId {
+            component_id,
+            worker_name: Some(WorkerName::from_existing("worker-1")),
+        };
+
+        assert_eq!(config.place(&target), target);
+    }
+}
@@ -1,6 +1,12 @@
 use std::collections::HashMap;
 
+use crate::api_definition::http::{CompiledHttpApiDefinition, HttpApiCors, HttpRouteCache};
 use crate::api_definition::ApiSiteString;
+use crate::http::multipart::MultipartPart;
+use crate::http::router::RouterPattern;
+use crate::service::http::http_response_cache::HttpResponseCacheKey;
+use crate::worker_binding::WorkerBindingMode;
+use hyper::http::header::ACCESS_CONTROL_REQUEST_METHOD;
 use hyper::http::{HeaderMap, Method};
 use serde_json::Value;
 
@@ -9,7 +15,17 @@ pub struct InputHttpRequest {
     pub input_path: ApiInputPath,
     pub headers: HeaderMap,
     pub req_method: Method,
-    pub req_body: Value,
+    pub req_body: InputHttpRequestBody,
+}
+
+// Body of a request, as read by the gateway depending on the binding mode of the matched route
+// and the content type of the request
+#[derive(Clone, Debug, PartialEq)]
+pub enum InputHttpRequestBody {
+    Json(Value),
+    Multipart(Vec<MultipartPart>),
+    // Bytes of a request to a route bound in the raw mode, which are given to Rib unparsed
+    Raw(Vec<u8>),
 }
 
 impl InputHttpRequest {
@@ -19,6 +35,81 @@ impl InputHttpRequest {
             .and_then(|host| host.to_str().ok())
             .map(|host_str| ApiSiteString(host_str.to_string()))
     }
+
+    // The method a CORS preflight request asks for, if the request is a preflight
+    pub fn cors_preflight_method(&self) -> Option<Method> {
+        if self.req_method != Method::OPTIONS {
+            return None;
+        }
+
+        self.headers
+            .get(ACCESS_CONTROL_REQUEST_METHOD)
+            .and_then(|method| Method::from_bytes(method.as_bytes()).ok())
+    }
+
+    // The CORS policy of the route matching the path of the request with the given method
+    pub fn resolve_cors(
+        &self,
+        api_definitions: Vec<CompiledHttpApiDefinition>,
+        method: &Method,
+    ) -> Option<HttpApiCors> {
+        let router = router::build(api_definitions);
+        let path: Vec<&str> = RouterPattern::split(&self.input_path.base_path).collect();
+
+        router
+            .check_path(method, &path)
+            .and_then(|entry| entry.cors.clone())
+    }
+
+    // The binding mode of the route matching the request, deciding how its body is read
+    pub fn resolve_binding_mode(
+        &self,
+        api_definitions: Vec<CompiledHttpApiDefinition>,
+    ) -> WorkerBindingMode {
+        let router = router::build(api_definitions);
+        let path: Vec<&str> = RouterPattern::split(&self.input_path.base_path).collect();
+
+        router
+            .check_path(&self.req_method, &path)
+            .map(|entry| entry.binding.mode)
+            .unwrap_or_default()
+    }
+
+    // The cache policy of the GET route matching the request, with the key of its response
+    pub fn resolve_cache(
+        &self,
+        api_definitions: Vec<CompiledHttpApiDefinition>,
+    ) -> Option<(HttpRouteCache, HttpResponseCacheKey)> {
+        if self.req_method != Method::GET {
+            return None;
+        }
+
+        let router = router::build(api_definitions);
+        let path: Vec<&str> = RouterPattern::split(&self.input_path.base_path).collect();
+        let entry = router.check_path(&Method::GET, &path)?;
+
+        // Responses of authenticated routes depend on the caller, so they are never cached
+        if entry.auth.is_some() {
+            return None;
+        }
+
+        let cache = entry.cache.clone()?;
+
+        let host = self.get_host().map(|host| host.0).unwrap_or_default();
+        let query_params = self.input_path.query_components().unwrap_or_default();
+        let key = HttpResponseCacheKey {
+            api_definition_id: entry.api_definition_id.clone(),
+            version: entry.api_version.clone(),
+            request: cache.request_key(
+                &host,
+                &self.input_path.base_path,
+                &self.headers,
+                &query_params,
+            ),
+        };
+
+        Some((cache, key))
+    }
 }
 
 #[derive(Clone)]
@@ -28,20 +119,34 @@ pub struct ApiInputPath {
 }
 
 impl ApiInputPath {
-    // Return the value of each query variable in a HashMap
+    // Return the value of each query variable in a HashMap. If a query variable is
+    // repeated, only its last value is returned
     pub fn query_components(&self) -> Option<HashMap<String, String>> {
+        self.query_values().map(|query_values| {
+            query_values
+                .into_iter()
+                .filter_map(|(key, mut values)| values.pop().map(|value| (key, value)))
+                .collect()
+        })
+    }
+
+    // Return all the values of each query variable in a HashMap, in the order they appear
+    pub fn query_values(&self) -> Option<HashMap<String, Vec<String>>> {
         if let Some(query_path) = self.query_path.clone() {
-            let mut query_components: HashMap<String, String> = HashMap::new();
+            let mut query_values: HashMap<String, Vec<String>> = HashMap::new();
             let query_parts = query_path.split('&').map(|x| x.trim());
 
             for part in query_parts {
                 let key_value: Vec<&str> = part.split('=').map(|x| x.trim()).collect();
 
                 if let (Some(key), Some(value)) = (key_value.first(), key_value.get(1)) {
-                    query_components.insert(key.to_string(), value.to_string());
+                    query_values
+                        .entry(key.to_string())
+                        .or_default()
+                        .push(value.to_string());
                 }
             }
-            Some(query_components)
+            Some(query_values)
         } else {
             None
         }
@@ -49,7 +154,10 @@ impl ApiInputPath {
 }
 
 pub mod router {
-    use crate::api_definition::http::CompiledRoute;
+    use crate::api_definition::http::{
+        CompiledHttpApiDefinition, HttpApiAuth, HttpApiCors, HttpRouteCache,
+    };
+    use crate::api_definition::{ApiDefinitionId, ApiVersion};
     use crate::worker_binding::CompiledGolemWorkerBinding;
     use crate::{
         api_definition::http::{PathPattern, QueryInfo, VarInfo},
@@ -62,15 +170,39 @@ pub mod router {
         pub path_params: Vec<(VarInfo, usize)>,
         pub query_params: Vec<QueryInfo>,
         pub binding: CompiledGolemWorkerBinding,
+        // auth of the api definition the route belongs to
+        pub auth: Option<HttpApiAuth>,
+        // cors policy of the api definition the route belongs to
+        pub cors: Option<HttpApiCors>,
+        pub cache: Option<HttpRouteCache>,
+        pub api_definition_id: ApiDefinitionId,
+        pub api_version: ApiVersion,
     }
 
-    pub fn build(routes: Vec<CompiledRoute>) -> Router<RouteEntry> {
+    pub fn build(definitions: Vec<CompiledHttpApiDefinition>) -> Router<RouteEntry> {
         let mut router = Router::new();
 
-        for route in routes {
+        let routes = definitions.into_iter().flat_map(|definition| {
+            let auth = definition.auth;
+            let cors = definition.cors;
+            let id = definition.id;
+            let version = definition.version;
+            definition.routes.into_iter().map(move |route| {
+                (
+                    route,
+                    auth.clone(),
+                    cors.clone(),
+                    id.clone(),
+                    version.clone(),
+                )
+            })
+        });
+
+        for (route, auth, cors, api_definition_id, api_version) in routes {
             let method = route.method.into();
             let path = route.path;
             let binding = route.binding;
+            let cache = route.cache;
 
             let path_params = path
                 .path_patterns
@@ -86,6 +218,11 @@ pub mod router {
                 path_params,
                 query_params: path.query_params,
                 binding,
+                auth,
+                cors,
+                cache,
+                api_definition_id,
+                api_version,
             };
 
             let path: Vec<RouterPattern> = path
@@ -106,13 +243,16 @@ mod tests {
     use test_r::test;
 
     use crate::api_definition::http::{
-        CompiledHttpApiDefinition, ComponentMetadataDictionary, HttpApiDefinition,
+        CompiledHttpApiDefinition, ComponentMetadataDictionary, HttpApiAuth, HttpApiCors,
+        HttpApiDefinition, JwtAuth,
     };
     use crate::getter::Getter;
     use crate::http::http_request::{ApiInputPath, InputHttpRequest};
     use crate::path::Path;
+    use crate::service::http::jwt_validator::{JwtValidationError, JwtValidator};
     use crate::worker_binding::{
         RequestDetails, RequestToWorkerBindingResolver, RibInputTypeMismatch,
+        WorkerBindingResolutionError,
     };
     use crate::worker_bridge_execution::to_response::ToResponse;
     use crate::worker_bridge_execution::{
@@ -250,6 +390,28 @@ mod tests {
         required
     }
 
+    struct TestJwtValidator {}
+
+    #[async_trait]
+    impl JwtValidator for TestJwtValidator {
+        // Accepts only the token "valid-token", with a fixed subject claim. The token
+        // "unavailable-jwks" simulates a JWKS endpoint that cannot be reached.
+        async fn validate(&self, auth: &JwtAuth, token: &str) -> Result<Value, JwtValidationError> {
+            match token {
+                "valid-token" => Ok(serde_json::json!({ "sub": "alice" })),
+                "unavailable-jwks" => Err(JwtValidationError::JwksUnavailable {
+                    url: auth.jwks_url.clone(),
+                    error: "connection refused".to_string(),
+                }),
+                _ => Err(JwtValidationError::InvalidToken("invalid".to_string())),
+            }
+        }
+    }
+
+    fn get_test_jwt_validator() -> Arc<dyn JwtValidator + Sync + Send> {
+        Arc::new(TestJwtValidator {})
+    }
+
     fn get_test_evaluator() -> Arc<dyn WorkerServiceRibInterpreter + Sync + Send> {
         Arc::new(DefaultRibInterpreter::from_worker_request_executor(
             Arc::new(TestWorkerRequestExecutor {}),
@@ -362,7 +524,7 @@ mod tests {
                 .unwrap();
 
         let resolved_route = api_request
-            .resolve_worker_binding(vec![compiled])
+            .resolve_worker_binding(vec![compiled], &get_test_jwt_validator())
             .await
             .unwrap();
 
@@ -506,6 +668,63 @@ mod tests {
         assert_eq!(result, expected);
     }
 
+    #[test]
+    async fn test_worker_request_resolution_with_repeated_query_params() {
+        let empty_headers = HeaderMap::new();
+        let api_request = get_api_request(
+            "foo/1",
+            Some("tag=jon&tag=kim"),
+            &empty_headers,
+            serde_json::Value::Null,
+        );
+
+        let expression = r#"let tags: list<str> = request.path.tag; let response = golem:it/api.{get-cart-contents}(tags[0], tags[1]); response"#;
+
+        let api_specification: HttpApiDefinition = get_api_spec(
+            "foo/{user-id}?{tag}",
+            "${let x: u64 = request.path.user-id; \"shopping-cart-${x}\"}",
+            expression,
+        );
+
+        let test_response = execute(&api_request, &api_specification).await;
+
+        let expected = Value::Array(vec![
+            Value::String("jon".to_string()),
+            Value::String("kim".to_string()),
+        ]);
+
+        assert_eq!(test_response.function_params, expected);
+    }
+
+    #[test]
+    async fn test_worker_request_resolution_with_repeated_query_params_as_single_value() {
+        let empty_headers = HeaderMap::new();
+        let api_request = get_api_request(
+            "foo/1",
+            Some("token-id=jon&token-id=kim"),
+            &empty_headers,
+            serde_json::Value::Null,
+        );
+
+        let expression = r#"let response = golem:it/api.{get-cart-contents}(request.path.token-id, request.path.token-id); response"#;
+
+        let api_specification: HttpApiDefinition = get_api_spec(
+            "foo/{user-id}?{token-id}",
+            "${let x: u64 = request.path.user-id; \"shopping-cart-${x}\"}",
+            expression,
+        );
+
+        let test_response = execute(&api_request, &api_specification).await;
+
+        // Definitions requiring a single value get the last one, as before
+        let expected = Value::Array(vec![
+            Value::String("kim".to_string()),
+            Value::String("kim".to_string()),
+        ]);
+
+        assert_eq!(test_response.function_params, expected);
+    }
+
     #[test]
     async fn test_worker_request_cond_expr_resolution() {
         let empty_headers = HeaderMap::new();
@@ -866,7 +1085,7 @@ mod tests {
             .unwrap();
 
             let resolved_route = api_request
-                .resolve_worker_binding(vec![compiled_api_spec])
+                .resolve_worker_binding(vec![compiled_api_spec], &get_test_jwt_validator())
                 .await;
 
             let result = resolved_route.map(|x| x.worker_detail);
@@ -907,7 +1126,7 @@ mod tests {
             .unwrap();
 
             let resolved_route = api_request
-                .resolve_worker_binding(vec![compiled_api_spec])
+                .resolve_worker_binding(vec![compiled_api_spec], &get_test_jwt_validator())
                 .await
                 .unwrap();
 
@@ -926,6 +1145,123 @@ mod tests {
         test_key(&headers, Some(IdempotencyKey::new("bar".to_string()))).await;
     }
 
+    #[test]
+    async fn test_worker_binding_with_jwt_auth() {
+        async fn resolve(header_map: &HeaderMap) -> Result<String, WorkerBindingResolutionError> {
+            let api_request = get_api_request("/getcartcontent/1", None, header_map, Value::Null);
+
+            let expression = r#"
+            let response = golem:it/api.{get-cart-contents}("foo", "bar");
+            response
+            "#;
+
+            let mut api_specification: HttpApiDefinition = get_api_spec(
+                "getcartcontent/{cart-id}",
+                "${let sub: str = request.auth.claims.sub; \"shopping-cart-${sub}\"}",
+                expression,
+            );
+
+            api_specification.auth = Some(HttpApiAuth {
+                jwt: JwtAuth {
+                    issuer: "https://issuer.example.com/".to_string(),
+                    audience: vec![],
+                    jwks_url: "https://issuer.example.com/.well-known/jwks.json".to_string(),
+                },
+            });
+
+            let compiled_api_spec = CompiledHttpApiDefinition::from_http_api_definition(
+                &api_specification,
+                &get_metadata(),
+            )
+            .unwrap();
+
+            api_request
+                .resolve_worker_binding(vec![compiled_api_spec], &get_test_jwt_validator())
+                .await
+                .map(|resolved| resolved.worker_detail.worker_name)
+        }
+
+        let result = resolve(&HeaderMap::new()).await;
+        assert!(matches!(
+            result,
+            Err(WorkerBindingResolutionError::Unauthorized(_))
+        ));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "Authorization",
+            HeaderValue::from_str("Bearer invalid-token").unwrap(),
+        );
+        let result = resolve(&headers).await;
+        assert!(matches!(
+            result,
+            Err(WorkerBindingResolutionError::Unauthorized(_))
+        ));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "Authorization",
+            HeaderValue::from_str("Bearer unavailable-jwks").unwrap(),
+        );
+        let result = resolve(&headers).await;
+        assert!(matches!(
+            result,
+            Err(WorkerBindingResolutionError::AuthenticationUnavailable(_))
+        ));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "Authorization",
+            HeaderValue::from_str("Bearer valid-token").unwrap(),
+        );
+        let result = resolve(&headers).await;
+        assert_eq!(result.unwrap(), "shopping-cart-alice".to_string());
+    }
+
+    #[test]
+    async fn test_cors_of_preflight_request() {
+        let mut api_specification: HttpApiDefinition = get_api_spec(
+            "getcartcontent/{cart-id}",
+            "${let x: u64 = request.path.cart-id; \"shopping-cart-${x}\"}",
+            "let response = golem:it/api.{get-cart-contents}(\"foo\", \"bar\"); response",
+        );
+
+        let cors = HttpApiCors {
+            allow_origins: vec!["https://app.example.com".to_string()],
+            allow_methods: vec![],
+            allow_headers: vec![],
+            max_age: None,
+        };
+        api_specification.cors = Some(cors.clone());
+
+        let compiled_api_spec = CompiledHttpApiDefinition::from_http_api_definition(
+            &api_specification,
+            &get_metadata(),
+        )
+        .unwrap();
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "Access-Control-Request-Method",
+            HeaderValue::from_str("GET").unwrap(),
+        );
+        let mut api_request = get_api_request("/getcartcontent/1", None, &headers, Value::Null);
+        assert_eq!(api_request.cors_preflight_method(), None);
+
+        api_request.req_method = Method::OPTIONS;
+        let requested_method = api_request.cors_preflight_method().unwrap();
+        assert_eq!(requested_method, Method::GET);
+
+        assert_eq!(
+            api_request.resolve_cors(vec![compiled_api_spec.clone()], &requested_method),
+            Some(cors)
+        );
+        assert_eq!(
+            api_request.resolve_cors(vec![compiled_api_spec], &Method::DELETE),
+            None
+        );
+    }
+
     fn get_api_request(
         base_path: &str,
         query_path: Option<&str>,
@@ -939,7 +1275,7 @@ mod tests {
             },
             headers: headers.clone(),
             req_method: Method::GET,
-            req_body,
+            req_body: InputHttpRequestBody::Json(req_body),
         }
     }
 
@@ -1,5 +1,6 @@
 pub use http_request::*;
 
 pub mod http_request;
+pub mod multipart;
 
 pub mod router;
@@ -0,0 +1,154 @@
+use multer::{Constraints, Multipart, SizeLimit};
+use poem::Body;
+
+use crate::app_config::MultipartConfig;
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum MultipartError {
+    #[error("Invalid multipart request: {0}")]
+    Invalid(String),
+    #[error("Multipart request is too large: {0}")]
+    TooLarge(String),
+}
+
+impl From<multer::Error> for MultipartError {
+    fn from(value: multer::Error) -> Self {
+        match value {
+            multer::Error::FieldSizeExceeded { .. } | multer::Error::StreamSizeExceeded { .. } => {
+                MultipartError::TooLarge(value.to_string())
+            }
+            _ => MultipartError::Invalid(value.to_string()),
+        }
+    }
+}
+
+// A part of a multipart/form-data request
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MultipartPart {
+    pub name: String,
+    pub content_type: String,
+    pub bytes: Vec<u8>,
+}
+
+pub fn is_multipart_form_data(content_type: &str) -> bool {
+    content_type
+        .split(';')
+        .next()
+        .map(|mime| mime.trim().eq_ignore_ascii_case("multipart/form-data"))
+        .unwrap_or(false)
+}
+
+// Reads the parts of a multipart/form-data body, which are given to Rib as a list of
+// `{name, content-type, bytes}` records in `request.body`.
+// The body is read part by part as it arrives, so requests exceeding the size limits
+// are rejected as soon as the limit is reached, without reading the rest of them.
+pub async fn read_multipart_body(
+    content_type: &str,
+    body: Body,
+    config: &MultipartConfig,
+) -> Result<Vec<MultipartPart>, MultipartError> {
+    let boundary = multer::parse_boundary(content_type)?;
+
+    let mut size_limit = SizeLimit::new()
+        .whole_stream(config.max_total_size)
+        .per_field(config.max_part_size);
+    for (name, limit) in &config.parts {
+        size_limit = size_limit.for_field(name.clone(), *limit);
+    }
+
+    let mut multipart = Multipart::with_constraints(
+        body.into_bytes_stream(),
+        boundary,
+        Constraints::new().size_limit(size_limit),
+    );
+
+    let mut parts = vec![];
+
+    while let Some(mut field) = multipart.next_field().await? {
+        let name = field.name().unwrap_or_default().to_string();
+        // Parts without a content type are plain text according to RFC 7578
+        let content_type = field
+            .content_type()
+            .map(|mime| mime.to_string())
+            .unwrap_or_else(|| "text/plain".to_string());
+
+        let mut bytes = vec![];
+        while let Some(chunk) = field.chunk().await? {
+            bytes.extend_from_slice(&chunk);
+        }
+
+        parts.push(MultipartPart {
+            name,
+            content_type,
+            bytes,
+        });
+    }
+
+    Ok(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::app_config::MultipartConfig;
+    use crate::http::multipart::{read_multipart_body, MultipartError, MultipartPart};
+    use poem::Body;
+    use std::collections::HashMap;
+
+    const CONTENT_TYPE: &str = "multipart/form-data; boundary=X-BOUNDARY";
+
+    fn body() -> Body {
+        Body::from_string(
+            [
+                "--X-BOUNDARY",
+                "Content-Disposition: form-data; name=\"title\"",
+                "",
+                "hi",
+                "--X-BOUNDARY",
+                "Content-Disposition: form-data; name=\"file\"; filename=\"a.bin\"",
+                "Content-Type: application/octet-stream",
+                "",
+                "abc",
+                "--X-BOUNDARY--",
+                "",
+            ]
+            .join("\r\n"),
+        )
+    }
+
+    #[test]
+    async fn parts_are_read_with_their_content_type() {
+        let result = read_multipart_body(CONTENT_TYPE, body(), &MultipartConfig::default())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result,
+            vec![
+                MultipartPart {
+                    name: "title".to_string(),
+                    content_type: "text/plain".to_string(),
+                    bytes: b"hi".to_vec(),
+                },
+                MultipartPart {
+                    name: "file".to_string(),
+                    content_type: "application/octet-stream".to_string(),
+                    bytes: b"abc".to_vec(),
+                }
+            ]
+        );
+    }
+
+    #[test]
+    async fn parts_exceeding_their_limit_are_rejected() {
+        let config = MultipartConfig {
+            parts: HashMap::from_iter(vec![("file".to_string(), 2)]),
+            ..MultipartConfig::default()
+        };
+
+        let result = read_multipart_body(CONTENT_TYPE, body(), &config).await;
+
+        assert!(matches!(result, Err(MultipartError::TooLarge(_))));
+    }
+}
@@ -0,0 +1,240 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use conditional_trait_gen::{trait_gen, when};
+use golem_service_base::repo::RepoError;
+use sqlx::{Database, Pool};
+use std::ops::Deref;
+use std::sync::Arc;
+
+#[derive(sqlx::FromRow, Debug, Clone)]
+pub struct ApiKeyRecord {
+    pub id: String,
+    pub namespace: String,
+    pub site: String,
+    pub key_hash: String,
+    pub request_count: i64,
+    pub last_used_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub revoked_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[async_trait]
+pub trait ApiKeyRepo {
+    async fn create(&self, api_key: &ApiKeyRecord) -> Result<(), RepoError>;
+
+    async fn get(&self, namespace: &str, id: &str) -> Result<Option<ApiKeyRecord>, RepoError>;
+
+    async fn get_all(&self, namespace: &str, site: &str) -> Result<Vec<ApiKeyRecord>, RepoError>;
+
+    async fn get_active_by_site(&self, site: &str) -> Result<Vec<ApiKeyRecord>, RepoError>;
+
+    async fn revoke(
+        &self,
+        namespace: &str,
+        id: &str,
+        revoked_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<bool, RepoError>;
+
+    async fn record_usage(
+        &self,
+        id: &str,
+        used_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), RepoError>;
+}
+
+pub struct DbApiKeyRepo<DB: Database> {
+    db_pool: Arc<Pool<DB>>,
+}
+
+impl<DB: Database> DbApiKeyRepo<DB> {
+    pub fn new(db_pool: Arc<Pool<DB>>) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[trait_gen(sqlx::Postgres -> sqlx::Postgres, sqlx::Sqlite)]
+#[async_trait]
+impl ApiKeyRepo for DbApiKeyRepo<sqlx::Postgres> {
+    async fn create(&self, api_key: &ApiKeyRecord) -> Result<(), RepoError> {
+        sqlx::query(
+            r#"
+              INSERT INTO api_keys
+                (id, namespace, site, key_hash, request_count, created_at)
+              VALUES
+                ($1, $2, $3, $4, $5, $6)
+               "#,
+        )
+        .bind(api_key.id.clone())
+        .bind(api_key.namespace.clone())
+        .bind(api_key.site.clone())
+        .bind(api_key.key_hash.clone())
+        .bind(api_key.request_count)
+        .bind(api_key.created_at)
+        .execute(self.db_pool.deref())
+        .await?;
+
+        Ok(())
+    }
+
+    #[when(sqlx::Postgres -> get)]
+    async fn get_postgres(
+        &self,
+        namespace: &str,
+        id: &str,
+    ) -> Result<Option<ApiKeyRecord>, RepoError> {
+        sqlx::query_as::<_, ApiKeyRecord>(
+            r#"
+                SELECT id, namespace, site, key_hash, request_count, last_used_at::timestamptz, created_at::timestamptz, revoked_at::timestamptz
+                FROM api_keys
+                WHERE namespace = $1 AND id = $2
+                "#,
+        )
+        .bind(namespace)
+        .bind(id)
+        .fetch_optional(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+
+    #[when(sqlx::Sqlite -> get)]
+    async fn get_sqlite(
+        &self,
+        namespace: &str,
+        id: &str,
+    ) -> Result<Option<ApiKeyRecord>, RepoError> {
+        sqlx::query_as::<_, ApiKeyRecord>(
+            r#"
+                SELECT id, namespace, site, key_hash, request_count, last_used_at, created_at, revoked_at
+                FROM api_keys
+                WHERE namespace = $1 AND id = $2
+                "#,
+        )
+        .bind(namespace)
+        .bind(id)
+        .fetch_optional(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+
+    #[when(sqlx::Postgres -> get_all)]
+    async fn get_all_postgres(
+        &self,
+        namespace: &str,
+        site: &str,
+    ) -> Result<Vec<ApiKeyRecord>, RepoError> {
+        sqlx::query_as::<_, ApiKeyRecord>(
+            r#"
+                SELECT id, namespace, site, key_hash, request_count, last_used_at::timestamptz, created_at::timestamptz, revoked_at::timestamptz
+                FROM api_keys
+                WHERE namespace = $1 AND site = $2
+                ORDER BY created_at
+                "#,
+        )
+        .bind(namespace)
+        .bind(site)
+        .fetch_all(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+
+    #[when(sqlx::Sqlite -> get_all)]
+    async fn get_all_sqlite(
+        &self,
+        namespace: &str,
+        site: &str,
+    ) -> Result<Vec<ApiKeyRecord>, RepoError> {
+        sqlx::query_as::<_, ApiKeyRecord>(
+            r#"
+                SELECT id, namespace, site, key_hash, request_count, last_used_at, created_at, revoked_at
+                FROM api_keys
+                WHERE namespace = $1 AND site = $2
+                ORDER BY created_at
+                "#,
+        )
+        .bind(namespace)
+        .bind(site)
+        .fetch_all(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+
+    #[when(sqlx::Postgres -> get_active_by_site)]
+    async fn get_active_by_site_postgres(
+        &self,
+        site: &str,
+    ) -> Result<Vec<ApiKeyRecord>, RepoError> {
+        sqlx::query_as::<_, ApiKeyRecord>(
+            r#"
+                SELECT id, namespace, site, key_hash, request_count, last_used_at::timestamptz, created_at::timestamptz, revoked_at::timestamptz
+                FROM api_keys
+                WHERE site = $1 AND revoked_at IS NULL
+                "#,
+        )
+        .bind(site)
+        .fetch_all(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+
+    #[when(sqlx::Sqlite -> get_active_by_site)]
+    async fn get_active_by_site_sqlite(&self, site: &str) -> Result<Vec<ApiKeyRecord>, RepoError> {
+        sqlx::query_as::<_, ApiKeyRecord>(
+            r#"
+                SELECT id, namespace, site, key_hash, request_count, last_used_at, created_at, revoked_at
+                FROM api_keys
+                WHERE site = $1 AND revoked_at IS NULL
+                "#,
+        )
+        .bind(site)
+        .fetch_all(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+
+    async fn revoke(
+        &self,
+        namespace: &str,
+        id: &str,
+        revoked_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<bool, RepoError> {
+        let result = sqlx::query(
+            "UPDATE api_keys SET revoked_at = $3 WHERE namespace = $1 AND id = $2 AND revoked_at IS NULL",
+        )
+        .bind(namespace)
+        .bind(id)
+        .bind(revoked_at)
+        .execute(self.db_pool.deref())
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn record_usage(
+        &self,
+        id: &str,
+        used_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), RepoError> {
+        sqlx::query(
+            "UPDATE api_keys SET request_count = request_count + 1, last_used_at = $2 WHERE id = $1",
+        )
+        .bind(id)
+        .bind(used_at)
+        .execute(self.db_pool.deref())
+        .await?;
+
+        Ok(())
+    }
+}
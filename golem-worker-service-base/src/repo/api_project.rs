@@ -0,0 +1,221 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use conditional_trait_gen::trait_gen;
+use golem_service_base::repo::RepoError;
+use sqlx::{Database, Pool};
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// Stores which project the API definitions and API deployments belong to.
+/// An API definition (with all its versions) or an API deployment belongs to at most one project.
+#[async_trait]
+pub trait ApiProjectRepo {
+    async fn set_definition_project(
+        &self,
+        namespace: &str,
+        definition_id: &str,
+        project_id: &str,
+    ) -> Result<(), RepoError>;
+
+    /// Returns whether the API definition belonged to the project
+    async fn remove_definition_project(
+        &self,
+        namespace: &str,
+        definition_id: &str,
+        project_id: &str,
+    ) -> Result<bool, RepoError>;
+
+    async fn get_definition_ids(
+        &self,
+        namespace: &str,
+        project_id: &str,
+    ) -> Result<Vec<String>, RepoError>;
+
+    async fn set_deployment_project(
+        &self,
+        namespace: &str,
+        site: &str,
+        project_id: &str,
+    ) -> Result<(), RepoError>;
+
+    /// Returns whether the API deployment belonged to the project
+    async fn remove_deployment_project(
+        &self,
+        namespace: &str,
+        site: &str,
+        project_id: &str,
+    ) -> Result<bool, RepoError>;
+
+    async fn get_deployment_sites(
+        &self,
+        namespace: &str,
+        project_id: &str,
+    ) -> Result<Vec<String>, RepoError>;
+
+    /// Removes all API definitions and API deployments from a deleted project
+    async fn delete_project(&self, namespace: &str, project_id: &str) -> Result<(), RepoError>;
+}
+
+pub struct DbApiProjectRepo<DB: Database> {
+    db_pool: Arc<Pool<DB>>,
+}
+
+impl<DB: Database> DbApiProjectRepo<DB> {
+    pub fn new(db_pool: Arc<Pool<DB>>) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[trait_gen(sqlx::Postgres -> sqlx::Postgres, sqlx::Sqlite)]
+#[async_trait]
+impl ApiProjectRepo for DbApiProjectRepo<sqlx::Postgres> {
+    async fn set_definition_project(
+        &self,
+        namespace: &str,
+        definition_id: &str,
+        project_id: &str,
+    ) -> Result<(), RepoError> {
+        sqlx::query(
+            r#"
+              INSERT INTO api_definition_projects
+                (namespace, definition_id, project_id)
+              VALUES
+                ($1, $2, $3)
+              ON CONFLICT (namespace, definition_id) DO UPDATE
+              SET project_id = $3
+               "#,
+        )
+        .bind(namespace)
+        .bind(definition_id)
+        .bind(project_id)
+        .execute(self.db_pool.deref())
+        .await?;
+
+        Ok(())
+    }
+
+    async fn remove_definition_project(
+        &self,
+        namespace: &str,
+        definition_id: &str,
+        project_id: &str,
+    ) -> Result<bool, RepoError> {
+        let result = sqlx::query(
+            "DELETE FROM api_definition_projects WHERE namespace = $1 AND definition_id = $2 AND project_id = $3",
+        )
+        .bind(namespace)
+        .bind(definition_id)
+        .bind(project_id)
+        .execute(self.db_pool.deref())
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_definition_ids(
+        &self,
+        namespace: &str,
+        project_id: &str,
+    ) -> Result<Vec<String>, RepoError> {
+        let result: Vec<(String,)> = sqlx::query_as(
+            "SELECT definition_id FROM api_definition_projects WHERE namespace = $1 AND project_id = $2 ORDER BY definition_id",
+        )
+        .bind(namespace)
+        .bind(project_id)
+        .fetch_all(self.db_pool.deref())
+        .await?;
+
+        Ok(result.into_iter().map(|(id,)| id).collect())
+    }
+
+    async fn set_deployment_project(
+        &self,
+        namespace: &str,
+        site: &str,
+        project_id: &str,
+    ) -> Result<(), RepoError> {
+        sqlx::query(
+            r#"
+              INSERT INTO api_deployment_projects
+                (namespace, site, project_id)
+              VALUES
+                ($1, $2, $3)
+              ON CONFLICT (namespace, site) DO UPDATE
+              SET project_id = $3
+               "#,
+        )
+        .bind(namespace)
+        .bind(site)
+        .bind(project_id)
+        .execute(self.db_pool.deref())
+        .await?;
+
+        Ok(())
+    }
+
+    async fn remove_deployment_project(
+        &self,
+        namespace: &str,
+        site: &str,
+        project_id: &str,
+    ) -> Result<bool, RepoError> {
+        let result = sqlx::query(
+            "DELETE FROM api_deployment_projects WHERE namespace = $1 AND site = $2 AND project_id = $3",
+        )
+        .bind(namespace)
+        .bind(site)
+        .bind(project_id)
+        .execute(self.db_pool.deref())
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_deployment_sites(
+        &self,
+        namespace: &str,
+        project_id: &str,
+    ) -> Result<Vec<String>, RepoError> {
+        let result: Vec<(String,)> = sqlx::query_as(
+            "SELECT site FROM api_deployment_projects WHERE namespace = $1 AND project_id = $2 ORDER BY site",
+        )
+        .bind(namespace)
+        .bind(project_id)
+        .fetch_all(self.db_pool.deref())
+        .await?;
+
+        Ok(result.into_iter().map(|(site,)| site).collect())
+    }
+
+    async fn delete_project(&self, namespace: &str, project_id: &str) -> Result<(), RepoError> {
+        let mut transaction = self.db_pool.begin().await?;
+
+        sqlx::query("DELETE FROM api_definition_projects WHERE namespace = $1 AND project_id = $2")
+            .bind(namespace)
+            .bind(project_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        sqlx::query("DELETE FROM api_deployment_projects WHERE namespace = $1 AND project_id = $2")
+            .bind(namespace)
+            .bind(project_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        transaction.commit().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,93 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use conditional_trait_gen::trait_gen;
+use golem_service_base::repo::RepoError;
+use sqlx::{Database, Pool};
+use std::ops::Deref;
+use std::sync::Arc;
+
+#[derive(sqlx::FromRow, Debug, Clone, PartialEq)]
+pub struct InvocationAttemptRecord {
+    pub idempotency_key: String,
+    pub component_id: String,
+    // Empty if the executor chooses the name of the worker
+    pub worker_name: Option<String>,
+    pub function_name: String,
+}
+
+#[async_trait]
+pub trait InvocationAttemptRepo {
+    // Stores the attempt unless one with the same idempotency key already exists, in which
+    // case the existing one is returned and the new one is dropped
+    async fn create_or_get(
+        &self,
+        attempt: &InvocationAttemptRecord,
+        created_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Option<InvocationAttemptRecord>, RepoError>;
+}
+
+pub struct DbInvocationAttemptRepo<DB: Database> {
+    db_pool: Arc<Pool<DB>>,
+}
+
+impl<DB: Database> DbInvocationAttemptRepo<DB> {
+    pub fn new(db_pool: Arc<Pool<DB>>) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[trait_gen(sqlx::Postgres -> sqlx::Postgres, sqlx::Sqlite)]
+#[async_trait]
+impl InvocationAttemptRepo for DbInvocationAttemptRepo<sqlx::Postgres> {
+    async fn create_or_get(
+        &self,
+        attempt: &InvocationAttemptRecord,
+        created_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Option<InvocationAttemptRecord>, RepoError> {
+        let result = sqlx::query(
+            r#"
+              INSERT INTO invocation_attempts
+                (idempotency_key, component_id, worker_name, function_name, created_at)
+              VALUES
+                ($1, $2, $3, $4, $5)
+              ON CONFLICT (idempotency_key) DO NOTHING
+               "#,
+        )
+        .bind(attempt.idempotency_key.clone())
+        .bind(attempt.component_id.clone())
+        .bind(attempt.worker_name.clone())
+        .bind(attempt.function_name.clone())
+        .bind(created_at)
+        .execute(self.db_pool.deref())
+        .await?;
+
+        if result.rows_affected() > 0 {
+            Ok(None)
+        } else {
+            sqlx::query_as::<_, InvocationAttemptRecord>(
+                r#"
+                SELECT idempotency_key, component_id, worker_name, function_name
+                FROM invocation_attempts
+                WHERE idempotency_key = $1
+                "#,
+            )
+            .bind(attempt.idempotency_key.clone())
+            .fetch_optional(self.db_pool.deref())
+            .await
+            .map_err(|e| e.into())
+        }
+    }
+}
@@ -14,3 +14,7 @@
 
 pub mod api_definition;
 pub mod api_deployment;
+pub mod api_key;
+pub mod api_project;
+pub mod invocation_attempt;
+pub mod update_rollout;
@@ -0,0 +1,113 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use conditional_trait_gen::trait_gen;
+use golem_service_base::repo::RepoError;
+use sqlx::{Database, Pool};
+use std::ops::Deref;
+use std::sync::Arc;
+
+#[derive(sqlx::FromRow, Debug, Clone, PartialEq)]
+pub struct UpdateRolloutRecord {
+    pub component_id: String,
+    pub target_version: i64,
+    // JSON serialized ComponentUpdatePolicy
+    pub policy: String,
+    pub account_id: Option<String>,
+}
+
+#[async_trait]
+pub trait UpdateRolloutRepo {
+    // Stores the rollout as unfinished, restarting it if it was already stored
+    async fn create(
+        &self,
+        rollout: &UpdateRolloutRecord,
+        created_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), RepoError>;
+
+    async fn finish(&self, component_id: &str, target_version: i64) -> Result<(), RepoError>;
+
+    async fn get_unfinished(&self) -> Result<Vec<UpdateRolloutRecord>, RepoError>;
+}
+
+pub struct DbUpdateRolloutRepo<DB: Database> {
+    db_pool: Arc<Pool<DB>>,
+}
+
+impl<DB: Database> DbUpdateRolloutRepo<DB> {
+    pub fn new(db_pool: Arc<Pool<DB>>) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[trait_gen(sqlx::Postgres -> sqlx::Postgres, sqlx::Sqlite)]
+#[async_trait]
+impl UpdateRolloutRepo for DbUpdateRolloutRepo<sqlx::Postgres> {
+    async fn create(
+        &self,
+        rollout: &UpdateRolloutRecord,
+        created_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), RepoError> {
+        sqlx::query(
+            r#"
+              INSERT INTO update_rollouts
+                (component_id, target_version, policy, account_id, finished, created_at)
+              VALUES
+                ($1, $2, $3, $4, false, $5)
+              ON CONFLICT (component_id, target_version) DO UPDATE
+              SET policy = $3, account_id = $4, finished = false, created_at = $5
+               "#,
+        )
+        .bind(rollout.component_id.clone())
+        .bind(rollout.target_version)
+        .bind(rollout.policy.clone())
+        .bind(rollout.account_id.clone())
+        .bind(created_at)
+        .execute(self.db_pool.deref())
+        .await?;
+
+        Ok(())
+    }
+
+    async fn finish(&self, component_id: &str, target_version: i64) -> Result<(), RepoError> {
+        sqlx::query(
+            r#"
+              UPDATE update_rollouts
+              SET finished = true
+              WHERE component_id = $1 AND target_version = $2
+               "#,
+        )
+        .bind(component_id)
+        .bind(target_version)
+        .execute(self.db_pool.deref())
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_unfinished(&self) -> Result<Vec<UpdateRolloutRecord>, RepoError> {
+        sqlx::query_as::<_, UpdateRolloutRecord>(
+            r#"
+                SELECT component_id, target_version, policy, account_id
+                FROM update_rollouts
+                WHERE finished = false
+                ORDER BY created_at
+                "#,
+        )
+        .fetch_all(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+}
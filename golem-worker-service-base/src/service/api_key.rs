@@ -0,0 +1,371 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::{Debug, Display};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use golem_common::SafeDisplay;
+use golem_service_base::repo::RepoError;
+use poem_openapi::NewType;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::api_definition::ApiSiteString;
+use crate::repo::api_deployment::ApiDeploymentRepo;
+use crate::repo::api_key::{ApiKeyRecord, ApiKeyRepo};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, NewType)]
+pub struct ApiKeyId(pub Uuid);
+
+impl ApiKeyId {
+    pub fn new_v4() -> Self {
+        ApiKeyId(Uuid::new_v4())
+    }
+}
+
+impl Display for ApiKeyId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiKey<Namespace> {
+    pub id: ApiKeyId,
+    pub namespace: Namespace,
+    pub site: ApiSiteString,
+    pub created_at: chrono::DateTime<Utc>,
+    pub revoked_at: Option<chrono::DateTime<Utc>>,
+}
+
+// A freshly minted key. The secret is only known at creation time,
+// as the repository keeps nothing but its hash.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiKeyWithSecret<Namespace> {
+    pub api_key: ApiKey<Namespace>,
+    pub secret: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiKeyUsage {
+    pub id: ApiKeyId,
+    pub request_count: u64,
+    pub last_used_at: Option<chrono::DateTime<Utc>>,
+}
+
+#[async_trait]
+pub trait ApiKeyService<Namespace> {
+    async fn create(
+        &self,
+        namespace: &Namespace,
+        site: &ApiSiteString,
+    ) -> Result<ApiKeyWithSecret<Namespace>, ApiKeyError<Namespace>>;
+
+    async fn revoke(
+        &self,
+        namespace: &Namespace,
+        id: &ApiKeyId,
+    ) -> Result<(), ApiKeyError<Namespace>>;
+
+    async fn get_all(
+        &self,
+        namespace: &Namespace,
+        site: &ApiSiteString,
+    ) -> Result<Vec<ApiKey<Namespace>>, ApiKeyError<Namespace>>;
+
+    async fn get_usage(
+        &self,
+        namespace: &Namespace,
+        id: &ApiKeyId,
+    ) -> Result<ApiKeyUsage, ApiKeyError<Namespace>>;
+
+    // Checks the key presented to a site and records its usage.
+    // Sites without any active key are open and accept requests without one.
+    async fn authorize(
+        &self,
+        site: &ApiSiteString,
+        secret: Option<&str>,
+    ) -> Result<(), ApiKeyError<Namespace>>;
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ApiKeyError<Namespace> {
+    #[error("API deployment not found: {1}")]
+    ApiDeploymentNotFound(Namespace, ApiSiteString),
+    #[error("API key not found: {0}")]
+    ApiKeyNotFound(ApiKeyId),
+    #[error("API key is missing")]
+    MissingApiKey,
+    #[error("API key is invalid")]
+    InvalidApiKey,
+    #[error("Internal repository error: {0}")]
+    InternalRepoError(RepoError),
+    #[error("Internal error: failed to convert {what}: {error}")]
+    InternalConversionError { what: String, error: String },
+}
+
+impl<T> ApiKeyError<T> {
+    pub fn conversion_error(what: impl AsRef<str>, error: String) -> Self {
+        Self::InternalConversionError {
+            what: what.as_ref().to_string(),
+            error,
+        }
+    }
+}
+
+impl<Namespace> From<RepoError> for ApiKeyError<Namespace> {
+    fn from(error: RepoError) -> Self {
+        ApiKeyError::InternalRepoError(error)
+    }
+}
+
+impl<Namespace: Display> SafeDisplay for ApiKeyError<Namespace> {
+    fn to_safe_string(&self) -> String {
+        match self {
+            ApiKeyError::ApiDeploymentNotFound(_, _) => self.to_string(),
+            ApiKeyError::ApiKeyNotFound(_) => self.to_string(),
+            ApiKeyError::MissingApiKey => self.to_string(),
+            ApiKeyError::InvalidApiKey => self.to_string(),
+            ApiKeyError::InternalRepoError(inner) => inner.to_safe_string(),
+            ApiKeyError::InternalConversionError { .. } => self.to_string(),
+        }
+    }
+}
+
+pub struct ApiKeyServiceDefault {
+    pub api_key_repo: Arc<dyn ApiKeyRepo + Sync + Send>,
+    pub deployment_repo: Arc<dyn ApiDeploymentRepo + Sync + Send>,
+}
+
+impl ApiKeyServiceDefault {
+    pub fn new(
+        api_key_repo: Arc<dyn ApiKeyRepo + Sync + Send>,
+        deployment_repo: Arc<dyn ApiDeploymentRepo + Sync + Send>,
+    ) -> Self {
+        Self {
+            api_key_repo,
+            deployment_repo,
+        }
+    }
+
+    async fn get_record<Namespace: Display>(
+        &self,
+        namespace: &Namespace,
+        id: &ApiKeyId,
+    ) -> Result<ApiKeyRecord, ApiKeyError<Namespace>> {
+        self.api_key_repo
+            .get(namespace.to_string().as_str(), id.to_string().as_str())
+            .await?
+            .ok_or(ApiKeyError::ApiKeyNotFound(id.clone()))
+    }
+}
+
+#[async_trait]
+impl<Namespace> ApiKeyService<Namespace> for ApiKeyServiceDefault
+where
+    Namespace: Display + TryFrom<String> + Eq + Clone + Send + Sync,
+    <Namespace as TryFrom<String>>::Error: Display + Debug + Send + Sync + 'static,
+{
+    async fn create(
+        &self,
+        namespace: &Namespace,
+        site: &ApiSiteString,
+    ) -> Result<ApiKeyWithSecret<Namespace>, ApiKeyError<Namespace>> {
+        info!(namespace = %namespace, "Create API key for site {}", site);
+
+        let deployments = self.deployment_repo.get_by_site(site.0.as_str()).await?;
+
+        if !deployments
+            .iter()
+            .any(|deployment| deployment.namespace == namespace.to_string())
+        {
+            return Err(ApiKeyError::ApiDeploymentNotFound(
+                namespace.clone(),
+                site.clone(),
+            ));
+        }
+
+        let secret = generate_secret();
+
+        let record = ApiKeyRecord {
+            id: ApiKeyId::new_v4().to_string(),
+            namespace: namespace.to_string(),
+            site: site.to_string(),
+            key_hash: hash_secret(&secret),
+            request_count: 0,
+            last_used_at: None,
+            created_at: Utc::now(),
+            revoked_at: None,
+        };
+
+        self.api_key_repo.create(&record).await?;
+
+        Ok(ApiKeyWithSecret {
+            api_key: record
+                .try_into()
+                .map_err(|e| ApiKeyError::conversion_error("API key record", e))?,
+            secret,
+        })
+    }
+
+    async fn revoke(
+        &self,
+        namespace: &Namespace,
+        id: &ApiKeyId,
+    ) -> Result<(), ApiKeyError<Namespace>> {
+        info!(namespace = %namespace, "Revoke API key {}", id);
+
+        // Fails on unknown keys, while revoking an already revoked key is a no-op
+        self.get_record(namespace, id).await?;
+
+        self.api_key_repo
+            .revoke(
+                namespace.to_string().as_str(),
+                id.to_string().as_str(),
+                Utc::now(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_all(
+        &self,
+        namespace: &Namespace,
+        site: &ApiSiteString,
+    ) -> Result<Vec<ApiKey<Namespace>>, ApiKeyError<Namespace>> {
+        info!(namespace = %namespace, "Get API keys for site {}", site);
+
+        let records = self
+            .api_key_repo
+            .get_all(namespace.to_string().as_str(), site.0.as_str())
+            .await?;
+
+        records
+            .into_iter()
+            .map(|record| {
+                record
+                    .try_into()
+                    .map_err(|e| ApiKeyError::conversion_error("API key record", e))
+            })
+            .collect()
+    }
+
+    async fn get_usage(
+        &self,
+        namespace: &Namespace,
+        id: &ApiKeyId,
+    ) -> Result<ApiKeyUsage, ApiKeyError<Namespace>> {
+        let record = self.get_record(namespace, id).await?;
+
+        Ok(ApiKeyUsage {
+            id: id.clone(),
+            request_count: record.request_count.max(0) as u64,
+            last_used_at: record.last_used_at,
+        })
+    }
+
+    async fn authorize(
+        &self,
+        site: &ApiSiteString,
+        secret: Option<&str>,
+    ) -> Result<(), ApiKeyError<Namespace>> {
+        let active_keys = self
+            .api_key_repo
+            .get_active_by_site(site.0.as_str())
+            .await?;
+
+        if active_keys.is_empty() {
+            return Ok(());
+        }
+
+        let secret = secret.ok_or(ApiKeyError::MissingApiKey)?;
+        let key_hash = hash_secret(secret);
+
+        let record = active_keys
+            .iter()
+            .find(|record| record.key_hash == key_hash)
+            .ok_or(ApiKeyError::InvalidApiKey)?;
+
+        // Failing to count a request should not reject an otherwise valid one
+        if let Err(err) = self
+            .api_key_repo
+            .record_usage(record.id.as_str(), Utc::now())
+            .await
+        {
+            error!("Failed to record usage of API key {}: {}", record.id, err);
+        }
+
+        Ok(())
+    }
+}
+
+impl<Namespace> TryFrom<ApiKeyRecord> for ApiKey<Namespace>
+where
+    Namespace: TryFrom<String>,
+    <Namespace as TryFrom<String>>::Error: Display,
+{
+    type Error = String;
+
+    fn try_from(value: ApiKeyRecord) -> Result<Self, Self::Error> {
+        let id = Uuid::parse_str(value.id.as_str()).map_err(|e| e.to_string())?;
+        let namespace = Namespace::try_from(value.namespace).map_err(|e| e.to_string())?;
+
+        Ok(ApiKey {
+            id: ApiKeyId(id),
+            namespace,
+            site: ApiSiteString(value.site),
+            created_at: value.created_at,
+            revoked_at: value.revoked_at,
+        })
+    }
+}
+
+fn generate_secret() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    to_hex(&bytes)
+}
+
+fn hash_secret(secret: &str) -> String {
+    to_hex(Sha256::digest(secret.as_bytes()).as_slice())
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use super::{generate_secret, hash_secret};
+
+    #[test]
+    fn generated_secrets_are_unique_and_hashed_deterministically() {
+        let secret1 = generate_secret();
+        let secret2 = generate_secret();
+
+        assert_eq!(secret1.len(), 64);
+        assert_ne!(secret1, secret2);
+        assert_eq!(hash_secret(&secret1), hash_secret(&secret1));
+        assert_ne!(hash_secret(&secret1), hash_secret(&secret2));
+        assert_ne!(hash_secret(&secret1), secret1);
+    }
+}
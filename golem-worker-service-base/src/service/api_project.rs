@@ -0,0 +1,368 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Display;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use golem_common::model::ProjectId;
+use golem_common::SafeDisplay;
+use golem_service_base::repo::RepoError;
+use tracing::info;
+
+use crate::api_definition::{ApiDefinitionId, ApiSiteString};
+use crate::repo::api_definition::ApiDefinitionRepo;
+use crate::repo::api_deployment::ApiDeploymentRepo;
+use crate::repo::api_project::ApiProjectRepo;
+use crate::service::component::{ComponentService, ComponentServiceError};
+
+// Groups API definitions and API deployments into the projects managed by the component service.
+// Components are assigned to projects by the component service itself.
+#[async_trait]
+pub trait ApiProjectService<AuthCtx, Namespace> {
+    async fn assign_definition(
+        &self,
+        namespace: &Namespace,
+        project_id: &ProjectId,
+        definition_id: &ApiDefinitionId,
+        auth_ctx: &AuthCtx,
+    ) -> Result<(), ApiProjectError<Namespace>>;
+
+    async fn unassign_definition(
+        &self,
+        namespace: &Namespace,
+        project_id: &ProjectId,
+        definition_id: &ApiDefinitionId,
+    ) -> Result<(), ApiProjectError<Namespace>>;
+
+    async fn get_definition_ids(
+        &self,
+        namespace: &Namespace,
+        project_id: &ProjectId,
+        auth_ctx: &AuthCtx,
+    ) -> Result<Vec<ApiDefinitionId>, ApiProjectError<Namespace>>;
+
+    async fn assign_deployment(
+        &self,
+        namespace: &Namespace,
+        project_id: &ProjectId,
+        site: &ApiSiteString,
+        auth_ctx: &AuthCtx,
+    ) -> Result<(), ApiProjectError<Namespace>>;
+
+    async fn unassign_deployment(
+        &self,
+        namespace: &Namespace,
+        project_id: &ProjectId,
+        site: &ApiSiteString,
+    ) -> Result<(), ApiProjectError<Namespace>>;
+
+    async fn get_deployment_sites(
+        &self,
+        namespace: &Namespace,
+        project_id: &ProjectId,
+        auth_ctx: &AuthCtx,
+    ) -> Result<Vec<ApiSiteString>, ApiProjectError<Namespace>>;
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ApiProjectError<Namespace> {
+    #[error("Project not found: {0}")]
+    ProjectNotFound(ProjectId),
+    #[error("API definition not found: {0}")]
+    ApiDefinitionNotFound(ApiDefinitionId),
+    #[error("API deployment not found: {1}")]
+    ApiDeploymentNotFound(Namespace, ApiSiteString),
+    #[error("API definition {0} does not belong to project {1}")]
+    ApiDefinitionNotInProject(ApiDefinitionId, ProjectId),
+    #[error("API deployment {0} does not belong to project {1}")]
+    ApiDeploymentNotInProject(ApiSiteString, ProjectId),
+    #[error(transparent)]
+    Component(ComponentServiceError),
+    #[error("Internal repository error: {0}")]
+    InternalRepoError(RepoError),
+}
+
+impl<Namespace> From<RepoError> for ApiProjectError<Namespace> {
+    fn from(error: RepoError) -> Self {
+        ApiProjectError::InternalRepoError(error)
+    }
+}
+
+impl<Namespace: Display> SafeDisplay for ApiProjectError<Namespace> {
+    fn to_safe_string(&self) -> String {
+        match self {
+            ApiProjectError::ProjectNotFound(_) => self.to_string(),
+            ApiProjectError::ApiDefinitionNotFound(_) => self.to_string(),
+            ApiProjectError::ApiDeploymentNotFound(_, _) => self.to_string(),
+            ApiProjectError::ApiDefinitionNotInProject(_, _) => self.to_string(),
+            ApiProjectError::ApiDeploymentNotInProject(_, _) => self.to_string(),
+            ApiProjectError::Component(inner) => inner.to_safe_string(),
+            ApiProjectError::InternalRepoError(inner) => inner.to_safe_string(),
+        }
+    }
+}
+
+pub struct ApiProjectServiceDefault<AuthCtx> {
+    pub component_service: Arc<dyn ComponentService<AuthCtx> + Send + Sync>,
+    pub project_repo: Arc<dyn ApiProjectRepo + Sync + Send>,
+    pub definition_repo: Arc<dyn ApiDefinitionRepo + Sync + Send>,
+    pub deployment_repo: Arc<dyn ApiDeploymentRepo + Sync + Send>,
+}
+
+impl<AuthCtx> ApiProjectServiceDefault<AuthCtx> {
+    pub fn new(
+        component_service: Arc<dyn ComponentService<AuthCtx> + Send + Sync>,
+        project_repo: Arc<dyn ApiProjectRepo + Sync + Send>,
+        definition_repo: Arc<dyn ApiDefinitionRepo + Sync + Send>,
+        deployment_repo: Arc<dyn ApiDeploymentRepo + Sync + Send>,
+    ) -> Self {
+        Self {
+            component_service,
+            project_repo,
+            definition_repo,
+            deployment_repo,
+        }
+    }
+
+    /// Projects are deleted by the component service, which has its own database,
+    /// so the assignments of a project are removed here once it is found to be gone.
+    async fn check_project<Namespace>(
+        &self,
+        namespace: &str,
+        project_id: &ProjectId,
+        auth_ctx: &AuthCtx,
+    ) -> Result<(), ApiProjectError<Namespace>> {
+        match self
+            .component_service
+            .get_project(project_id, auth_ctx)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(ComponentServiceError::NotFound(_)) => {
+                self.project_repo
+                    .delete_project(namespace, &project_id.to_string())
+                    .await?;
+                Err(ApiProjectError::ProjectNotFound(project_id.clone()))
+            }
+            Err(error) => Err(ApiProjectError::Component(error)),
+        }
+    }
+
+    async fn definition_exists(
+        &self,
+        namespace: &str,
+        definition_id: &str,
+    ) -> Result<bool, RepoError> {
+        let versions = self
+            .definition_repo
+            .get_all_versions(namespace, definition_id)
+            .await?;
+        Ok(!versions.is_empty())
+    }
+
+    async fn deployment_exists(&self, namespace: &str, site: &str) -> Result<bool, RepoError> {
+        let deployments = self.deployment_repo.get_by_site(site).await?;
+        Ok(deployments
+            .iter()
+            .any(|deployment| deployment.namespace == namespace))
+    }
+}
+
+#[async_trait]
+impl<AuthCtx, Namespace> ApiProjectService<AuthCtx, Namespace> for ApiProjectServiceDefault<AuthCtx>
+where
+    AuthCtx: Send + Sync,
+    Namespace: Display + Clone + Send + Sync,
+{
+    async fn assign_definition(
+        &self,
+        namespace: &Namespace,
+        project_id: &ProjectId,
+        definition_id: &ApiDefinitionId,
+        auth_ctx: &AuthCtx,
+    ) -> Result<(), ApiProjectError<Namespace>> {
+        info!(namespace = %namespace, project_id = %project_id, "Assign API definition {}", definition_id);
+
+        self.check_project(&namespace.to_string(), project_id, auth_ctx)
+            .await?;
+
+        let namespace = namespace.to_string();
+        if !self
+            .definition_exists(namespace.as_str(), definition_id.0.as_str())
+            .await?
+        {
+            return Err(ApiProjectError::ApiDefinitionNotFound(
+                definition_id.clone(),
+            ));
+        }
+
+        self.project_repo
+            .set_definition_project(
+                namespace.as_str(),
+                definition_id.0.as_str(),
+                project_id.to_string().as_str(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn unassign_definition(
+        &self,
+        namespace: &Namespace,
+        project_id: &ProjectId,
+        definition_id: &ApiDefinitionId,
+    ) -> Result<(), ApiProjectError<Namespace>> {
+        info!(namespace = %namespace, project_id = %project_id, "Unassign API definition {}", definition_id);
+
+        let removed = self
+            .project_repo
+            .remove_definition_project(
+                namespace.to_string().as_str(),
+                definition_id.0.as_str(),
+                project_id.to_string().as_str(),
+            )
+            .await?;
+
+        if removed {
+            Ok(())
+        } else {
+            Err(ApiProjectError::ApiDefinitionNotInProject(
+                definition_id.clone(),
+                project_id.clone(),
+            ))
+        }
+    }
+
+    async fn get_definition_ids(
+        &self,
+        namespace: &Namespace,
+        project_id: &ProjectId,
+        auth_ctx: &AuthCtx,
+    ) -> Result<Vec<ApiDefinitionId>, ApiProjectError<Namespace>> {
+        info!(namespace = %namespace, project_id = %project_id, "Get API definitions of project");
+
+        self.check_project(&namespace.to_string(), project_id, auth_ctx)
+            .await?;
+
+        let namespace = namespace.to_string();
+        let ids = self
+            .project_repo
+            .get_definition_ids(namespace.as_str(), project_id.to_string().as_str())
+            .await?;
+
+        // The assignments of deleted API definitions are kept, they apply again if the API
+        // definition gets recreated
+        let mut result = Vec::new();
+        for id in ids {
+            if self
+                .definition_exists(namespace.as_str(), id.as_str())
+                .await?
+            {
+                result.push(ApiDefinitionId(id));
+            }
+        }
+        Ok(result)
+    }
+
+    async fn assign_deployment(
+        &self,
+        namespace: &Namespace,
+        project_id: &ProjectId,
+        site: &ApiSiteString,
+        auth_ctx: &AuthCtx,
+    ) -> Result<(), ApiProjectError<Namespace>> {
+        info!(namespace = %namespace, project_id = %project_id, "Assign API deployment {}", site);
+
+        self.check_project(&namespace.to_string(), project_id, auth_ctx)
+            .await?;
+
+        if !self
+            .deployment_exists(namespace.to_string().as_str(), site.0.as_str())
+            .await?
+        {
+            return Err(ApiProjectError::ApiDeploymentNotFound(
+                namespace.clone(),
+                site.clone(),
+            ));
+        }
+
+        self.project_repo
+            .set_deployment_project(
+                namespace.to_string().as_str(),
+                site.0.as_str(),
+                project_id.to_string().as_str(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn unassign_deployment(
+        &self,
+        namespace: &Namespace,
+        project_id: &ProjectId,
+        site: &ApiSiteString,
+    ) -> Result<(), ApiProjectError<Namespace>> {
+        info!(namespace = %namespace, project_id = %project_id, "Unassign API deployment {}", site);
+
+        let removed = self
+            .project_repo
+            .remove_deployment_project(
+                namespace.to_string().as_str(),
+                site.0.as_str(),
+                project_id.to_string().as_str(),
+            )
+            .await?;
+
+        if removed {
+            Ok(())
+        } else {
+            Err(ApiProjectError::ApiDeploymentNotInProject(
+                site.clone(),
+                project_id.clone(),
+            ))
+        }
+    }
+
+    async fn get_deployment_sites(
+        &self,
+        namespace: &Namespace,
+        project_id: &ProjectId,
+        auth_ctx: &AuthCtx,
+    ) -> Result<Vec<ApiSiteString>, ApiProjectError<Namespace>> {
+        info!(namespace = %namespace, project_id = %project_id, "Get API deployments of project");
+
+        self.check_project(&namespace.to_string(), project_id, auth_ctx)
+            .await?;
+
+        let namespace = namespace.to_string();
+        let sites = self
+            .project_repo
+            .get_deployment_sites(namespace.as_str(), project_id.to_string().as_str())
+            .await?;
+
+        let mut result = Vec::new();
+        for site in sites {
+            if self
+                .deployment_exists(namespace.as_str(), site.as_str())
+                .await?
+            {
+                result.push(ApiSiteString(site));
+            }
+        }
+        Ok(result)
+    }
+}
@@ -5,14 +5,19 @@ use tonic::transport::Channel;
 
 use golem_api_grpc::proto::golem::component::v1::component_service_client::ComponentServiceClient;
 use golem_api_grpc::proto::golem::component::v1::{
-    get_component_metadata_response, GetComponentMetadataResponse, GetLatestComponentRequest,
-    GetVersionedComponentRequest,
+    get_component_metadata_response, get_component_observability_response,
+    get_component_update_policy_response, get_project_response,
+    update_component_observability_response, update_component_update_policy_response,
+    GetComponentByAliasRequest, GetComponentMetadataResponse, GetComponentRequest,
+    GetLatestComponentRequest, GetProjectRequest, GetVersionedComponentRequest,
+    UpdateComponentObservabilityRequest, UpdateComponentUpdatePolicyRequest,
 };
 use golem_common::client::{GrpcClient, GrpcClientConfig};
 use golem_common::config::RetryConfig;
-use golem_common::model::ComponentId;
+use golem_common::model::component_observability::ComponentObservability;
+use golem_common::model::{ComponentId, ProjectId};
 use golem_common::retries::with_retries;
-use golem_service_base::model::Component;
+use golem_service_base::model::{Component, ComponentUpdatePolicy, Project};
 
 use crate::service::component::ComponentServiceError;
 use crate::service::with_metadata;
@@ -34,6 +39,46 @@ pub trait ComponentService<AuthCtx> {
         component_id: &ComponentId,
         auth_ctx: &AuthCtx,
     ) -> ComponentResult<Component>;
+
+    /// Gets the component version the version alias points to
+    async fn get_by_version_alias(
+        &self,
+        component_id: &ComponentId,
+        alias: &str,
+        auth_ctx: &AuthCtx,
+    ) -> ComponentResult<Component>;
+
+    async fn get_observability(
+        &self,
+        component_id: &ComponentId,
+        auth_ctx: &AuthCtx,
+    ) -> ComponentResult<ComponentObservability>;
+
+    async fn update_observability(
+        &self,
+        component_id: &ComponentId,
+        observability: ComponentObservability,
+        auth_ctx: &AuthCtx,
+    ) -> ComponentResult<ComponentObservability>;
+
+    async fn get_update_policy(
+        &self,
+        component_id: &ComponentId,
+        auth_ctx: &AuthCtx,
+    ) -> ComponentResult<ComponentUpdatePolicy>;
+
+    async fn update_update_policy(
+        &self,
+        component_id: &ComponentId,
+        policy: ComponentUpdatePolicy,
+        auth_ctx: &AuthCtx,
+    ) -> ComponentResult<ComponentUpdatePolicy>;
+
+    async fn get_project(
+        &self,
+        project_id: &ProjectId,
+        auth_ctx: &AuthCtx,
+    ) -> ComponentResult<Project>;
 }
 
 #[derive(Clone)]
@@ -176,4 +221,283 @@ where
 
         Ok(value)
     }
+
+    async fn get_by_version_alias(
+        &self,
+        component_id: &ComponentId,
+        alias: &str,
+        metadata: &AuthCtx,
+    ) -> ComponentResult<Component> {
+        let value = with_retries(
+            "component",
+            "get_by_version_alias",
+            Some(format!("{component_id}@{alias}")),
+            &self.retry_config,
+            &(
+                self.client.clone(),
+                component_id.clone(),
+                alias.to_string(),
+                metadata.clone(),
+            ),
+            |(client, id, alias, metadata)| {
+                Box::pin(async move {
+                    let response = client
+                        .call(move |client| {
+                            let request = GetComponentByAliasRequest {
+                                component_id: Some(id.clone().into()),
+                                version_alias: alias.clone(),
+                            };
+                            let request = with_metadata(request, metadata.clone());
+
+                            Box::pin(client.get_component_metadata_by_alias(request))
+                        })
+                        .await?
+                        .into_inner();
+
+                    Self::process_metadata_response(response)
+                })
+            },
+            Self::is_retriable,
+        )
+        .await?;
+
+        Ok(value)
+    }
+
+    async fn get_observability(
+        &self,
+        component_id: &ComponentId,
+        metadata: &AuthCtx,
+    ) -> ComponentResult<ComponentObservability> {
+        let value = with_retries(
+            "component",
+            "get_observability",
+            Some(component_id.to_string()),
+            &self.retry_config,
+            &(self.client.clone(), component_id.clone(), metadata.clone()),
+            |(client, id, metadata)| {
+                Box::pin(async move {
+                    let response = client
+                        .call(move |client| {
+                            let request = GetComponentRequest {
+                                component_id: Some(id.clone().into()),
+                            };
+                            let request = with_metadata(request, metadata.clone());
+
+                            Box::pin(client.get_component_observability(request))
+                        })
+                        .await?
+                        .into_inner();
+
+                    match response.result {
+                        None => Err(ComponentServiceError::Internal(
+                            "Empty response".to_string(),
+                        )),
+                        Some(get_component_observability_response::Result::Success(
+                            observability,
+                        )) => Ok(observability.into()),
+                        Some(get_component_observability_response::Result::Error(error)) => {
+                            Err(error.into())
+                        }
+                    }
+                })
+            },
+            Self::is_retriable,
+        )
+        .await?;
+
+        Ok(value)
+    }
+
+    async fn update_observability(
+        &self,
+        component_id: &ComponentId,
+        observability: ComponentObservability,
+        metadata: &AuthCtx,
+    ) -> ComponentResult<ComponentObservability> {
+        let value = with_retries(
+            "component",
+            "update_observability",
+            Some(component_id.to_string()),
+            &self.retry_config,
+            &(
+                self.client.clone(),
+                component_id.clone(),
+                observability,
+                metadata.clone(),
+            ),
+            |(client, id, observability, metadata)| {
+                Box::pin(async move {
+                    let response = client
+                        .call(move |client| {
+                            let request = UpdateComponentObservabilityRequest {
+                                component_id: Some(id.clone().into()),
+                                observability: Some(observability.clone().into()),
+                            };
+                            let request = with_metadata(request, metadata.clone());
+
+                            Box::pin(client.update_component_observability(request))
+                        })
+                        .await?
+                        .into_inner();
+
+                    match response.result {
+                        None => Err(ComponentServiceError::Internal(
+                            "Empty response".to_string(),
+                        )),
+                        Some(update_component_observability_response::Result::Success(
+                            observability,
+                        )) => Ok(observability.into()),
+                        Some(update_component_observability_response::Result::Error(error)) => {
+                            Err(error.into())
+                        }
+                    }
+                })
+            },
+            Self::is_retriable,
+        )
+        .await?;
+
+        Ok(value)
+    }
+
+    async fn get_update_policy(
+        &self,
+        component_id: &ComponentId,
+        metadata: &AuthCtx,
+    ) -> ComponentResult<ComponentUpdatePolicy> {
+        let value = with_retries(
+            "component",
+            "get_update_policy",
+            Some(component_id.to_string()),
+            &self.retry_config,
+            &(self.client.clone(), component_id.clone(), metadata.clone()),
+            |(client, id, metadata)| {
+                Box::pin(async move {
+                    let response = client
+                        .call(move |client| {
+                            let request = GetComponentRequest {
+                                component_id: Some(id.clone().into()),
+                            };
+                            let request = with_metadata(request, metadata.clone());
+
+                            Box::pin(client.get_component_update_policy(request))
+                        })
+                        .await?
+                        .into_inner();
+
+                    match response.result {
+                        None => Err(ComponentServiceError::Internal(
+                            "Empty response".to_string(),
+                        )),
+                        Some(get_component_update_policy_response::Result::Success(policy)) => {
+                            policy.try_into().map_err(ComponentServiceError::Internal)
+                        }
+                        Some(get_component_update_policy_response::Result::Error(error)) => {
+                            Err(error.into())
+                        }
+                    }
+                })
+            },
+            Self::is_retriable,
+        )
+        .await?;
+
+        Ok(value)
+    }
+
+    async fn update_update_policy(
+        &self,
+        component_id: &ComponentId,
+        policy: ComponentUpdatePolicy,
+        metadata: &AuthCtx,
+    ) -> ComponentResult<ComponentUpdatePolicy> {
+        let value = with_retries(
+            "component",
+            "update_update_policy",
+            Some(component_id.to_string()),
+            &self.retry_config,
+            &(
+                self.client.clone(),
+                component_id.clone(),
+                policy,
+                metadata.clone(),
+            ),
+            |(client, id, policy, metadata)| {
+                Box::pin(async move {
+                    let response = client
+                        .call(move |client| {
+                            let request = UpdateComponentUpdatePolicyRequest {
+                                component_id: Some(id.clone().into()),
+                                policy: Some(policy.clone().into()),
+                            };
+                            let request = with_metadata(request, metadata.clone());
+
+                            Box::pin(client.update_component_update_policy(request))
+                        })
+                        .await?
+                        .into_inner();
+
+                    match response.result {
+                        None => Err(ComponentServiceError::Internal(
+                            "Empty response".to_string(),
+                        )),
+                        Some(update_component_update_policy_response::Result::Success(policy)) => {
+                            policy.try_into().map_err(ComponentServiceError::Internal)
+                        }
+                        Some(update_component_update_policy_response::Result::Error(error)) => {
+                            Err(error.into())
+                        }
+                    }
+                })
+            },
+            Self::is_retriable,
+        )
+        .await?;
+
+        Ok(value)
+    }
+
+    async fn get_project(
+        &self,
+        project_id: &ProjectId,
+        metadata: &AuthCtx,
+    ) -> ComponentResult<Project> {
+        let value = with_retries(
+            "component",
+            "get_project",
+            Some(project_id.to_string()),
+            &self.retry_config,
+            &(self.client.clone(), project_id.clone(), metadata.clone()),
+            |(client, id, metadata)| {
+                Box::pin(async move {
+                    let response = client
+                        .call(move |client| {
+                            let request = GetProjectRequest {
+                                project_id: Some(id.clone().into()),
+                            };
+                            let request = with_metadata(request, metadata.clone());
+
+                            Box::pin(client.get_project(request))
+                        })
+                        .await?
+                        .into_inner();
+
+                    match response.result {
+                        None => Err(ComponentServiceError::Internal(
+                            "Empty response".to_string(),
+                        )),
+                        Some(get_project_response::Result::Success(project)) => {
+                            project.try_into().map_err(ComponentServiceError::Internal)
+                        }
+                        Some(get_project_response::Result::Error(error)) => Err(error.into()),
+                    }
+                })
+            },
+            Self::is_retriable,
+        )
+        .await?;
+
+        Ok(value)
+    }
 }
@@ -4,6 +4,7 @@ use golem_api_grpc::proto::golem::worker::v1::{
     worker_error, worker_execution_error, UnknownError, WorkerError as GrpcWorkerError,
     WorkerExecutionError,
 };
+use golem_common::model::error::ErrorCode;
 use golem_common::SafeDisplay;
 
 // The dependents of golem-worker-service-base is expected
@@ -43,6 +44,23 @@ impl SafeDisplay for ComponentServiceError {
     }
 }
 
+impl ComponentServiceError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            ComponentServiceError::Unauthorized(_) => ErrorCode::Unauthorized,
+            ComponentServiceError::Forbidden(_) => ErrorCode::Forbidden,
+            ComponentServiceError::NotFound(_) => ErrorCode::NotFound,
+            ComponentServiceError::BadRequest(_) => ErrorCode::InvalidRequest,
+            ComponentServiceError::AlreadyExists(_) => ErrorCode::AlreadyExists,
+            ComponentServiceError::Internal(_) => ErrorCode::Internal,
+            ComponentServiceError::FailedGrpcStatus(status) => {
+                ErrorCode::from_grpc_code(status.code())
+            }
+            ComponentServiceError::FailedTransport(_) => ErrorCode::Unavailable,
+        }
+    }
+}
+
 impl From<Status> for ComponentServiceError {
     fn from(status: Status) -> Self {
         ComponentServiceError::FailedGrpcStatus(status)
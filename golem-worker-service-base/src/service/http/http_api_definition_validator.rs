@@ -57,7 +57,8 @@ impl ApiDefinitionValidatorService<HttpApiDefinition, RouteValidationError>
         api: &HttpApiDefinition,
         _components: &[Component],
     ) -> Result<(), ValidationErrors<RouteValidationError>> {
-        let errors = unique_routes(api.routes.as_slice());
+        let mut errors = unique_routes(api.routes.as_slice());
+        errors.extend(cacheable_routes(api.routes.as_slice()));
 
         if errors.is_empty() {
             Ok(())
@@ -99,13 +100,35 @@ fn unique_routes(routes: &[Route]) -> Vec<RouteValidationError> {
     errors
 }
 
+// Only the responses of GET routes can be cached, as serving them from the cache
+// must not skip any side effect of the worker invocation
+fn cacheable_routes(routes: &[Route]) -> Vec<RouteValidationError> {
+    routes
+        .iter()
+        .filter_map(|route| {
+            let cache = route.cache.as_ref()?;
+            let detail = if !route.method.is_get() {
+                "Only the responses of GET routes can be cached"
+            } else if cache.ttl_seconds == 0 {
+                "The TTL of the cached responses must be positive"
+            } else {
+                return None;
+            };
+            Some(RouteValidationError::from_route(
+                route.clone(),
+                detail.to_string(),
+            ))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use test_r::test;
 
     use crate::api_definition::http::{MethodPattern, Route};
     use crate::service::http::http_api_definition_validator::unique_routes;
-    use crate::worker_binding::ResponseMapping;
+    use crate::worker_binding::{ResponseMapping, WorkerBindingMode};
     use golem_common::model::ComponentId;
     use golem_service_base::model::VersionedComponentId;
     use rib::Expr;
@@ -124,7 +147,9 @@ mod tests {
                     worker_name: Expr::identifier("request"),
                     idempotency_key: None,
                     response: ResponseMapping(Expr::literal("sample")),
+                    mode: WorkerBindingMode::Json,
                 },
+                cache: None,
             }
         }
 
@@ -0,0 +1,218 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bincode::{Decode, Encode};
+use bytes::Bytes;
+use fred::types::Expiration;
+use golem_common::redis::RedisPool;
+use golem_common::SafeDisplay;
+
+use crate::api_definition::{ApiDefinitionId, ApiVersion};
+
+// Stores the responses of cacheable routes of custom http requests, so read-heavy traffic
+// is served by the gateway without invoking the workers
+#[async_trait]
+pub trait HttpResponseCache {
+    async fn get(
+        &self,
+        key: &HttpResponseCacheKey,
+    ) -> Result<Option<CachedHttpResponse>, HttpResponseCacheError>;
+
+    async fn put(
+        &self,
+        key: &HttpResponseCacheKey,
+        response: &CachedHttpResponse,
+        ttl: Duration,
+    ) -> Result<(), HttpResponseCacheError>;
+
+    // Removes all the cached responses of an API definition, returning their number
+    async fn purge(
+        &self,
+        api_definition_id: &ApiDefinitionId,
+        version: &ApiVersion,
+    ) -> Result<u64, HttpResponseCacheError>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HttpResponseCacheKey {
+    pub api_definition_id: ApiDefinitionId,
+    pub version: ApiVersion,
+    // Identifies the response within the API definition
+    pub request: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct CachedHttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, Vec<u8>)>,
+    pub body: Vec<u8>,
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum HttpResponseCacheError {
+    #[error("Response cache is unavailable: {0}")]
+    Unavailable(String),
+    #[error("Invalid cached response: {0}")]
+    InvalidEntry(String),
+}
+
+impl SafeDisplay for HttpResponseCacheError {
+    fn to_safe_string(&self) -> String {
+        match self {
+            HttpResponseCacheError::Unavailable(_) => "Response cache is unavailable".to_string(),
+            HttpResponseCacheError::InvalidEntry(_) => self.to_string(),
+        }
+    }
+}
+
+// Used when no response cache is configured, caching nothing
+pub struct DisabledHttpResponseCache;
+
+#[async_trait]
+impl HttpResponseCache for DisabledHttpResponseCache {
+    async fn get(
+        &self,
+        _key: &HttpResponseCacheKey,
+    ) -> Result<Option<CachedHttpResponse>, HttpResponseCacheError> {
+        Ok(None)
+    }
+
+    async fn put(
+        &self,
+        _key: &HttpResponseCacheKey,
+        _response: &CachedHttpResponse,
+        _ttl: Duration,
+    ) -> Result<(), HttpResponseCacheError> {
+        Ok(())
+    }
+
+    async fn purge(
+        &self,
+        _api_definition_id: &ApiDefinitionId,
+        _version: &ApiVersion,
+    ) -> Result<u64, HttpResponseCacheError> {
+        Ok(0)
+    }
+}
+
+pub struct RedisHttpResponseCache {
+    redis: RedisPool,
+}
+
+impl RedisHttpResponseCache {
+    const PURGE_SCAN_COUNT: u64 = 1000;
+
+    pub fn new(redis: RedisPool) -> Self {
+        Self { redis }
+    }
+
+    fn definition_prefix(api_definition_id: &ApiDefinitionId, version: &ApiVersion) -> String {
+        format!("http-response-cache:{api_definition_id}:{version}:")
+    }
+
+    fn key(key: &HttpResponseCacheKey) -> String {
+        format!(
+            "{}{}",
+            Self::definition_prefix(&key.api_definition_id, &key.version),
+            key.request
+        )
+    }
+
+    // Escapes the glob special characters of a key prefix used in a SCAN pattern
+    fn escape_pattern(prefix: &str) -> String {
+        let mut pattern = String::with_capacity(prefix.len());
+        for c in prefix.chars() {
+            if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+                pattern.push('\\');
+            }
+            pattern.push(c);
+        }
+        pattern
+    }
+}
+
+#[async_trait]
+impl HttpResponseCache for RedisHttpResponseCache {
+    async fn get(
+        &self,
+        key: &HttpResponseCacheKey,
+    ) -> Result<Option<CachedHttpResponse>, HttpResponseCacheError> {
+        let value: Option<Bytes> = self
+            .redis
+            .with("http_response_cache", "get")
+            .get(Self::key(key))
+            .await
+            .map_err(|err| HttpResponseCacheError::Unavailable(err.to_string()))?;
+
+        value
+            .map(|bytes| {
+                self.redis
+                    .deserialize(&bytes)
+                    .map_err(HttpResponseCacheError::InvalidEntry)
+            })
+            .transpose()
+    }
+
+    async fn put(
+        &self,
+        key: &HttpResponseCacheKey,
+        response: &CachedHttpResponse,
+        ttl: Duration,
+    ) -> Result<(), HttpResponseCacheError> {
+        let value = self
+            .redis
+            .serialize(response)
+            .map_err(HttpResponseCacheError::InvalidEntry)?;
+
+        self.redis
+            .with("http_response_cache", "put")
+            .set(
+                Self::key(key),
+                value,
+                Some(Expiration::EX(ttl.as_secs().max(1) as i64)),
+                None,
+                false,
+            )
+            .await
+            .map_err(|err| HttpResponseCacheError::Unavailable(err.to_string()))
+    }
+
+    async fn purge(
+        &self,
+        api_definition_id: &ApiDefinitionId,
+        version: &ApiVersion,
+    ) -> Result<u64, HttpResponseCacheError> {
+        let pattern = format!(
+            "{}*",
+            Self::escape_pattern(&Self::definition_prefix(api_definition_id, version))
+        );
+
+        let mut purged = 0;
+        let mut cursor = 0;
+        loop {
+            let (next_cursor, keys) = self
+                .redis
+                .with("http_response_cache", "purge")
+                .scan(&pattern, cursor, Self::PURGE_SCAN_COUNT)
+                .await
+                .map_err(|err| HttpResponseCacheError::Unavailable(err.to_string()))?;
+
+            if !keys.is_empty() {
+                purged += keys.len() as u64;
+                let _: u64 = self
+                    .redis
+                    .with("http_response_cache", "purge")
+                    .del_many(keys)
+                    .await
+                    .map_err(|err| HttpResponseCacheError::Unavailable(err.to_string()))?;
+            }
+
+            if next_cursor == 0 {
+                break;
+            }
+            cursor = next_cursor;
+        }
+
+        Ok(purged)
+    }
+}
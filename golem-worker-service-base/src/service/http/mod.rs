@@ -1 +1,3 @@
 pub mod http_api_definition_validator;
+pub mod http_response_cache;
+pub mod jwt_validator;
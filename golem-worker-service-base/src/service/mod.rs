@@ -16,7 +16,10 @@ pub mod api_definition;
 pub mod api_definition_lookup;
 pub mod api_definition_validator;
 pub mod api_deployment;
+pub mod api_key;
+pub mod api_project;
 pub mod component;
+pub mod rib_evaluation;
 pub mod worker;
 
 pub mod http;
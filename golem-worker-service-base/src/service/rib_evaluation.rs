@@ -0,0 +1,345 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures_util::FutureExt;
+use golem_common::model::ComponentId;
+use golem_common::SafeDisplay;
+use golem_wasm_rpc::json::TypeAnnotatedValueJsonExtensions;
+use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
+use rib::{RibEvaluationError, RibFunctionInvoke, RibInputTypeInfo, RibInterpreterResult};
+use tracing::{debug, info, info_span, Instrument};
+
+use crate::service::component::{ComponentService, ComponentServiceError};
+use crate::worker_bridge_execution::{WorkerRequest, WorkerRequestExecutor};
+
+// The component whose exports are used to type check the function calls of an evaluated script.
+// The latest version is used if no version is given.
+// The function calls are invoked on the given worker of the component, or on the one selected
+// by `use-worker` in the script.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RibEvaluationComponent {
+    pub component_id: ComponentId,
+    pub component_version: Option<u64>,
+    pub worker_name: Option<String>,
+}
+
+// Evaluates Rib scripts against sample input, so bindings can be tried out
+// without deploying an API definition.
+// Function calls are type checked against the exports of the given component,
+// and invoked on the given worker. Without a worker, calling a function fails at runtime.
+#[async_trait]
+pub trait RibEvaluationService<AuthCtx> {
+    async fn evaluate(
+        &self,
+        rib: &str,
+        input: &serde_json::Value,
+        component: Option<RibEvaluationComponent>,
+        auth_ctx: &AuthCtx,
+    ) -> Result<Result<RibInterpreterResult, RibEvaluationError>, RibEvaluationServiceError>;
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RibEvaluationServiceError {
+    #[error(transparent)]
+    Component(#[from] ComponentServiceError),
+}
+
+impl SafeDisplay for RibEvaluationServiceError {
+    fn to_safe_string(&self) -> String {
+        match self {
+            RibEvaluationServiceError::Component(inner) => inner.to_safe_string(),
+        }
+    }
+}
+
+pub struct RibEvaluationServiceDefault<AuthCtx> {
+    component_service: Arc<dyn ComponentService<AuthCtx> + Sync + Send>,
+    worker_request_executor: Arc<dyn WorkerRequestExecutor + Sync + Send>,
+}
+
+impl<AuthCtx> RibEvaluationServiceDefault<AuthCtx> {
+    pub fn new(
+        component_service: Arc<dyn ComponentService<AuthCtx> + Sync + Send>,
+        worker_request_executor: Arc<dyn WorkerRequestExecutor + Sync + Send>,
+    ) -> Self {
+        Self {
+            component_service,
+            worker_request_executor,
+        }
+    }
+}
+
+#[async_trait]
+impl<AuthCtx> RibEvaluationService<AuthCtx> for RibEvaluationServiceDefault<AuthCtx>
+where
+    AuthCtx: Send + Sync,
+{
+    async fn evaluate(
+        &self,
+        rib: &str,
+        input: &serde_json::Value,
+        component: Option<RibEvaluationComponent>,
+        auth_ctx: &AuthCtx,
+    ) -> Result<Result<RibInterpreterResult, RibEvaluationError>, RibEvaluationServiceError> {
+        let span = info_span!(
+            "evaluate_rib",
+            component_id = component
+                .as_ref()
+                .map(|component| component.component_id.to_string()),
+            worker_name = component
+                .as_ref()
+                .and_then(|component| component.worker_name.clone()),
+        );
+
+        let exports = match &component {
+            Some(RibEvaluationComponent {
+                component_id,
+                component_version: Some(version),
+                ..
+            }) => {
+                self.component_service
+                    .get_by_version(component_id, *version, auth_ctx)
+                    .instrument(span.clone())
+                    .await?
+                    .metadata
+                    .exports
+            }
+            Some(RibEvaluationComponent {
+                component_id,
+                component_version: None,
+                ..
+            }) => {
+                self.component_service
+                    .get_latest(component_id, auth_ctx)
+                    .instrument(span.clone())
+                    .await?
+                    .metadata
+                    .exports
+            }
+            None => vec![],
+        };
+
+        let result = rib::evaluate(
+            rib,
+            &exports,
+            |input_types| rib_input_from_json(input, input_types),
+            function_invoke(self.worker_request_executor.clone(), component),
+        )
+        .instrument(span.clone())
+        .await;
+
+        if let Err(error) = &result {
+            span.in_scope(|| debug!("Evaluating Rib script failed: {error}"));
+        }
+
+        Ok(result)
+    }
+}
+
+// Invokes the function calls of an evaluated script on the worker of the component,
+// or on the one selected by `use-worker`
+fn function_invoke(
+    executor: Arc<dyn WorkerRequestExecutor + Sync + Send>,
+    component: Option<RibEvaluationComponent>,
+) -> RibFunctionInvoke {
+    Arc::new(move |target_worker, function_name, function_params| {
+        let executor = executor.clone();
+        let target = component.clone().and_then(|component| {
+            target_worker
+                .or(component.worker_name)
+                .map(|worker_name| (component.component_id, worker_name))
+        });
+
+        async move {
+            let Some((component_id, worker_name)) = target else {
+                return Err(format!(
+                    "Function {function_name} cannot be invoked without a worker name"
+                ));
+            };
+
+            info!(
+                component_id = component_id.to_string(),
+                worker_name, function_name, "Invoking function of evaluated Rib script"
+            );
+
+            executor
+                .execute(WorkerRequest {
+                    component_id,
+                    worker_name,
+                    function_name,
+                    function_params,
+                    idempotency_key: None,
+                })
+                .await
+                .map(|response| response.result)
+                .map_err(|err| err.to_string())
+        }
+        .boxed()
+    })
+}
+
+// The sample input is a JSON object with a field for each global variable used by the script,
+// such as `{"request": {"path": {"user-id": "jon"}}}`
+fn rib_input_from_json(
+    input: &serde_json::Value,
+    input_types: &RibInputTypeInfo,
+) -> Result<HashMap<String, TypeAnnotatedValue>, String> {
+    let mut rib_input = HashMap::new();
+    let mut errors = vec![];
+
+    for (name, typ) in &input_types.types {
+        match input.get(name) {
+            Some(value) => match TypeAnnotatedValue::parse_with_type(value, typ) {
+                Ok(value) => {
+                    rib_input.insert(name.clone(), value);
+                }
+                Err(errs) => errors.push(format!("{name}: {}", errs.join(", "))),
+            },
+            None => errors.push(format!("{name}: missing from the input")),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(rib_input)
+    } else {
+        errors.sort();
+        Err(errors.join("; "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use async_trait::async_trait;
+    use golem_common::model::ComponentId;
+    use golem_wasm_ast::analysis::analysed_type::{field, record, str};
+    use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
+    use rib::RibInputTypeInfo;
+    use serde_json::json;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use uuid::Uuid;
+
+    use crate::service::rib_evaluation::{
+        function_invoke, rib_input_from_json, RibEvaluationComponent,
+    };
+    use crate::worker_bridge_execution::{
+        WorkerRequest, WorkerRequestExecutor, WorkerRequestExecutorError, WorkerResponse,
+    };
+
+    #[derive(Default)]
+    struct RecordingWorkerRequestExecutor {
+        requests: Mutex<Vec<WorkerRequest>>,
+    }
+
+    #[async_trait]
+    impl WorkerRequestExecutor for RecordingWorkerRequestExecutor {
+        async fn execute(
+            &self,
+            resolved_worker_request: WorkerRequest,
+        ) -> Result<WorkerResponse, WorkerRequestExecutorError> {
+            let result = TypeAnnotatedValue::Str(resolved_worker_request.worker_name.clone());
+            self.requests.lock().unwrap().push(resolved_worker_request);
+            Ok(WorkerResponse::new(result))
+        }
+    }
+
+    fn input_types() -> RibInputTypeInfo {
+        RibInputTypeInfo {
+            types: HashMap::from([(
+                "request".to_string(),
+                record(vec![field("path", record(vec![field("user", str())]))]),
+            )]),
+        }
+    }
+
+    #[test]
+    fn json_input_is_converted_using_the_input_types() {
+        let rib_input = rib_input_from_json(
+            &json!({"request": {"path": {"user": "jon"}}, "unused": 1}),
+            &input_types(),
+        )
+        .unwrap();
+
+        assert_eq!(rib_input.len(), 1);
+        assert!(matches!(
+            rib_input.get("request"),
+            Some(TypeAnnotatedValue::Record(_))
+        ));
+    }
+
+    #[test]
+    fn missing_input_is_reported() {
+        let result = rib_input_from_json(&json!({}), &input_types());
+
+        assert_eq!(result, Err("request: missing from the input".to_string()));
+    }
+
+    #[test]
+    async fn function_calls_are_invoked_on_the_selected_worker() {
+        let executor = Arc::new(RecordingWorkerRequestExecutor::default());
+        let component_id = ComponentId(Uuid::new_v4());
+        let invoke = function_invoke(
+            executor.clone(),
+            Some(RibEvaluationComponent {
+                component_id: component_id.clone(),
+                component_version: None,
+                worker_name: Some("worker-1".to_string()),
+            }),
+        );
+
+        let first = invoke(None, "foo".to_string(), vec![]).await;
+        let second = invoke(Some("worker-2".to_string()), "bar".to_string(), vec![]).await;
+
+        assert_eq!(first, Ok(TypeAnnotatedValue::Str("worker-1".to_string())));
+        assert_eq!(second, Ok(TypeAnnotatedValue::Str("worker-2".to_string())));
+        let requests = executor.requests.lock().unwrap();
+        assert_eq!(
+            requests
+                .iter()
+                .map(|request| (request.worker_name.as_str(), request.function_name.as_str()))
+                .collect::<Vec<_>>(),
+            vec![("worker-1", "foo"), ("worker-2", "bar")]
+        );
+        assert!(requests
+            .iter()
+            .all(|request| request.component_id == component_id));
+    }
+
+    #[test]
+    async fn function_calls_fail_without_a_worker() {
+        let executor = Arc::new(RecordingWorkerRequestExecutor::default());
+        let invoke = function_invoke(
+            executor.clone(),
+            Some(RibEvaluationComponent {
+                component_id: ComponentId(Uuid::new_v4()),
+                component_version: None,
+                worker_name: None,
+            }),
+        );
+
+        let result = invoke(None, "foo".to_string(), vec![]).await;
+
+        assert_eq!(
+            result,
+            Err("Function foo cannot be invoked without a worker name".to_string())
+        );
+        assert!(executor.requests.lock().unwrap().is_empty());
+    }
+}
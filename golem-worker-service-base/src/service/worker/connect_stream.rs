@@ -24,17 +24,23 @@ use tonic::{Status, Streaming};
 use tracing::{error, Instrument};
 
 use golem_api_grpc::proto::golem::worker::LogEvent;
+use golem_api_grpc::proto::golem::workerexecutor::v1::InvokeAndStreamWorkerResponse;
 use golem_common::metrics::api::{
     record_closed_grpc_api_active_stream, record_new_grpc_api_active_stream,
 };
 
-pub struct ConnectWorkerStream {
-    receiver: mpsc::Receiver<Result<LogEvent, Status>>,
+/// Stream of the messages of a worker executor, which is Send and Sync unlike `Streaming`
+pub struct WorkerStream<T> {
+    receiver: mpsc::Receiver<Result<T, Status>>,
     cancel: CancellationToken,
 }
 
-impl ConnectWorkerStream {
-    pub fn new(streaming: Streaming<LogEvent>) -> Self {
+pub type ConnectWorkerStream = WorkerStream<LogEvent>;
+
+pub type InvokeAndStreamWorkerStream = WorkerStream<InvokeAndStreamWorkerResponse>;
+
+impl<T: Send + 'static> WorkerStream<T> {
+    pub fn new(streaming: Streaming<T>) -> Self {
         // Create a channel which is Send and Sync.
         // Streaming is not Sync.
         let (sender, receiver) = mpsc::channel(32);
@@ -78,18 +84,18 @@ impl ConnectWorkerStream {
     }
 }
 
-impl Stream for ConnectWorkerStream {
-    type Item = Result<LogEvent, Status>;
+impl<T> Stream for WorkerStream<T> {
+    type Item = Result<T, Status>;
 
     fn poll_next(
         mut self: Pin<&mut Self>,
         cx: &mut Context<'_>,
-    ) -> Poll<Option<Result<LogEvent, Status>>> {
+    ) -> Poll<Option<Result<T, Status>>> {
         self.receiver.poll_recv(cx)
     }
 }
 
-impl Drop for ConnectWorkerStream {
+impl<T> Drop for WorkerStream<T> {
     fn drop(&mut self) {
         self.cancel.cancel();
     }
@@ -12,12 +12,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
+use std::time::Duration;
 use std::{collections::HashMap, sync::Arc};
 
 use async_trait::async_trait;
-use golem_wasm_ast::analysis::AnalysedFunctionResult;
+use futures::future::join_all;
+use golem_wasm_ast::analysis::{AnalysedExport, AnalysedFunctionResult};
 use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
 use golem_wasm_rpc::protobuf::Val as ProtoVal;
+use poem_openapi::types::Base64;
 use tonic::transport::Channel;
 use tonic::Code;
 use tracing::{error, info};
@@ -27,19 +31,28 @@ use golem_api_grpc::proto::golem::worker::{InvocationContext, InvokeResult};
 use golem_api_grpc::proto::golem::workerexecutor;
 use golem_api_grpc::proto::golem::workerexecutor::v1::worker_executor_client::WorkerExecutorClient;
 use golem_api_grpc::proto::golem::workerexecutor::v1::{
-    CompletePromiseRequest, ConnectWorkerRequest, CreateWorkerRequest, InterruptWorkerRequest,
-    InvokeAndAwaitWorkerRequest, ResumeWorkerRequest, UpdateWorkerRequest,
+    AwaitWorkerInvocationRequest, CompletePromiseRequest, ConnectWorkerRequest,
+    CreateWorkerRequest, DryRunUpdateWorkerRequest, InterruptWorkerRequest,
+    InvokeAndAwaitWorkerRequest, InvokeAndPollWorkerRequest, ResumeWorkerRequest,
+    UpdateComponentObservabilityRequest, UpdateWorkerRequest,
 };
 use golem_common::client::MultiTargetGrpcClient;
 use golem_common::config::RetryConfig;
+use golem_common::model::auth::{HasTokenScope, WorkerAction};
+use golem_common::model::component_observability::ComponentObservability;
 use golem_common::model::oplog::OplogIndex;
 use golem_common::model::public_oplog::OplogCursor;
+use golem_common::model::public_worker_status::PublicWorkerStatusRecord;
+use golem_common::model::worker_statistics::ComponentWorkerStatistics;
 use golem_common::model::{
-    AccountId, ComponentId, ComponentVersion, FilterComparator, IdempotencyKey, PromiseId,
-    ScanCursor, TargetWorkerId, WorkerFilter, WorkerId, WorkerStatus,
+    AccountId, ComponentId, ComponentVersion, FilterComparator, IdempotencyKey, InvocationPriority,
+    Pod, PromiseId, ScanCursor, TargetWorkerId, Timestamp, WorkerFilter, WorkerId,
+    WorkerPlacementHint, WorkerStatus,
 };
 use golem_service_base::model::{
-    GetOplogResponse, GolemErrorUnknown, ResourceLimits, WorkerMetadata,
+    AccountUsageRecord, GetOplogResponse, GolemErrorUnknown, OutstandingPromise,
+    PendingScheduledAction, ResourceLimits, ScheduledActionCursor, ScheduledActionFilter,
+    SharedKeyValueEntry, TopicInfo, WorkerMetadata, WorkerSaga,
 };
 use golem_service_base::routing_table::HasRoutingTableService;
 use golem_service_base::{
@@ -47,15 +60,26 @@ use golem_service_base::{
     routing_table::RoutingTableService,
 };
 
+use crate::app_config::WorkerPlacementConfig;
 use crate::service::component::ComponentService;
 
 use super::{
-    AllExecutors, CallWorkerExecutorError, ConnectWorkerStream, HasWorkerExecutorClients,
-    RandomExecutor, ResponseMapResult, RoutingLogic, WorkerServiceError,
+    convert_json_parameters, convert_wave_parameters, AllExecutors, CallWorkerExecutorError,
+    ConnectWorkerStream, HasWorkerExecutorClients, InvocationAttempt, InvocationAttempts,
+    InvokeAndStreamWorkerStream, RandomExecutor, ReadReplica, ResponseMapResult, RoutingLogic,
+    WorkerServiceError,
 };
 
 pub type WorkerResult<T> = Result<T, WorkerServiceError>;
 
+/// Result of waiting for an invocation until a deadline
+#[derive(Debug, Clone)]
+pub enum InvocationPollResult {
+    Completed(TypeAnnotatedValue),
+    /// The invocation is still running, its result can be awaited with its idempotency key
+    Pending(IdempotencyKey),
+}
+
 #[async_trait]
 pub trait WorkerService<AuthCtx> {
     async fn create(
@@ -68,9 +92,12 @@ pub trait WorkerService<AuthCtx> {
         auth_ctx: &AuthCtx,
     ) -> WorkerResult<WorkerId>;
 
+    /// Connects to the event stream of the worker, first replaying the last `backfill_count`
+    /// stdout, stderr and log events reconstructed from the oplog
     async fn connect(
         &self,
         worker_id: &WorkerId,
+        backfill_count: u64,
         metadata: WorkerRequestMetadata,
         auth_ctx: &AuthCtx,
     ) -> WorkerResult<ConnectWorkerStream>;
@@ -87,6 +114,18 @@ pub trait WorkerService<AuthCtx> {
         params: Vec<TypeAnnotatedValue>,
     ) -> WorkerResult<Vec<ProtoVal>>;
 
+    /// Generates the name of a new worker satisfying the placement hint, if the worker name is
+    /// not specified, and creates the worker with the latest version of its component, recording
+    /// the hint in its environment. Named workers are returned unchanged, as their name decides
+    /// their shard.
+    async fn place_worker_with_hint(
+        &self,
+        worker_id: &TargetWorkerId,
+        placement_hint: &WorkerPlacementHint,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<TargetWorkerId>;
+
     /// Validates the provided list of `TypeAnnotatedValue` parameters, and then
     /// invokes the worker and waits its results, returning it as a `TypeAnnotatedValue`.
     async fn validate_and_invoke_and_await_typed(
@@ -96,7 +135,10 @@ pub trait WorkerService<AuthCtx> {
         function_name: String,
         params: Vec<TypeAnnotatedValue>,
         invocation_context: Option<InvocationContext>,
+        priority: InvocationPriority,
+        timeout: Option<Duration>,
         metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
     ) -> WorkerResult<TypeAnnotatedValue> {
         let params = self.validate_typed_parameters(params)?;
         self.invoke_and_await_typed(
@@ -105,7 +147,69 @@ pub trait WorkerService<AuthCtx> {
             function_name,
             params,
             invocation_context,
+            priority,
+            timeout,
+            metadata,
+            auth_ctx,
+        )
+        .await
+    }
+
+    /// Converts the plain JSON parameters to `TypeAnnotatedValue`s using the parameter types of
+    /// the function exported by the worker's component.
+    async fn resolve_json_parameters(
+        &self,
+        worker_id: &TargetWorkerId,
+        function_name: &str,
+        params: Vec<serde_json::Value>,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Vec<TypeAnnotatedValue>>;
+
+    /// Parses the WAVE parameters, one per line, using the parameter types of the function
+    /// exported by the worker's component.
+    async fn resolve_wave_parameters(
+        &self,
+        worker_id: &TargetWorkerId,
+        function_name: &str,
+        params: &str,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Vec<TypeAnnotatedValue>>;
+
+    /// Converts the plain JSON parameters to the types of the invoked function, and then
+    /// invokes the worker and waits its results, returning it as a `TypeAnnotatedValue`.
+    async fn invoke_and_await_json(
+        &self,
+        worker_id: &TargetWorkerId,
+        idempotency_key: Option<IdempotencyKey>,
+        function_name: String,
+        params: Vec<serde_json::Value>,
+        invocation_context: Option<InvocationContext>,
+        priority: InvocationPriority,
+        timeout: Option<Duration>,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<TypeAnnotatedValue> {
+        let params = self
+            .resolve_json_parameters(
+                worker_id,
+                &function_name,
+                params,
+                metadata.clone(),
+                auth_ctx,
+            )
+            .await?;
+        self.validate_and_invoke_and_await_typed(
+            worker_id,
+            idempotency_key,
+            function_name,
+            params,
+            invocation_context,
+            priority,
+            timeout,
             metadata,
+            auth_ctx,
         )
         .await
     }
@@ -119,9 +223,54 @@ pub trait WorkerService<AuthCtx> {
         function_name: String,
         params: Vec<ProtoVal>,
         invocation_context: Option<InvocationContext>,
+        priority: InvocationPriority,
+        timeout: Option<Duration>,
         metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
     ) -> WorkerResult<TypeAnnotatedValue>;
 
+    /// Invokes a worker using raw `Val` parameter values and waits for its results until the
+    /// `deadline`. If the invocation does not finish in time it keeps running, and its result can
+    /// be awaited with `await_invocation`.
+    async fn invoke_and_poll(
+        &self,
+        worker_id: &TargetWorkerId,
+        idempotency_key: Option<IdempotencyKey>,
+        function_name: String,
+        params: Vec<ProtoVal>,
+        invocation_context: Option<InvocationContext>,
+        priority: InvocationPriority,
+        deadline: Duration,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<InvocationPollResult>;
+
+    /// Waits for the result of an earlier invocation of the worker with the given idempotency
+    /// key until the `deadline`
+    async fn await_invocation(
+        &self,
+        worker_id: &WorkerId,
+        idempotency_key: &IdempotencyKey,
+        deadline: Duration,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<InvocationPollResult>;
+
+    /// Invokes a worker using raw `Val` parameter values, streaming the chunks of the input
+    /// streams returned by the function as they are read, followed by its result
+    async fn invoke_and_stream(
+        &self,
+        worker_id: &TargetWorkerId,
+        idempotency_key: Option<IdempotencyKey>,
+        function_name: String,
+        params: Vec<ProtoVal>,
+        invocation_context: Option<InvocationContext>,
+        priority: InvocationPriority,
+        timeout: Option<Duration>,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<InvokeAndStreamWorkerStream>;
+
     /// Invokes a worker using raw `Val` parameter values and awaits its results returning
     /// a `Val` values (without type information)
     async fn invoke_and_await(
@@ -131,7 +280,10 @@ pub trait WorkerService<AuthCtx> {
         function_name: String,
         params: Vec<ProtoVal>,
         invocation_context: Option<InvocationContext>,
+        priority: InvocationPriority,
+        timeout: Option<Duration>,
         metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
     ) -> WorkerResult<InvokeResult>;
 
     /// Validates the provided list of `TypeAnnotatedValue` parameters, and then enqueues
@@ -143,7 +295,9 @@ pub trait WorkerService<AuthCtx> {
         function_name: String,
         params: Vec<TypeAnnotatedValue>,
         invocation_context: Option<InvocationContext>,
+        priority: InvocationPriority,
         metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
     ) -> WorkerResult<()> {
         let params = self.validate_typed_parameters(params)?;
         self.invoke(
@@ -152,7 +306,9 @@ pub trait WorkerService<AuthCtx> {
             function_name,
             params,
             invocation_context,
+            priority,
             metadata,
+            auth_ctx,
         )
         .await
     }
@@ -166,7 +322,9 @@ pub trait WorkerService<AuthCtx> {
         function_name: String,
         params: Vec<ProtoVal>,
         invocation_context: Option<InvocationContext>,
+        priority: InvocationPriority,
         metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
     ) -> WorkerResult<()>;
 
     async fn complete_promise(
@@ -193,6 +351,27 @@ pub trait WorkerService<AuthCtx> {
         auth_ctx: &AuthCtx,
     ) -> WorkerResult<WorkerMetadata>;
 
+    /// Gets the metadata of multiple workers, sending a single request to each executor owning
+    /// some of them, in parallel. The results are in the order of `worker_ids`, and a failure
+    /// only affects its own worker.
+    async fn get_metadata_bulk(
+        &self,
+        worker_ids: &[WorkerId],
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> Vec<WorkerResult<WorkerMetadata>>;
+
+    /// Gets the number of workers of a component by status, their pending invocations and linear
+    /// memory, in total and per component version. Each executor aggregates the workers of its
+    /// own shards, and their results are merged.
+    async fn get_component_worker_statistics(
+        &self,
+        component_id: &ComponentId,
+        precise: bool,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<ComponentWorkerStatistics>;
+
     async fn find_metadata(
         &self,
         component_id: &ComponentId,
@@ -220,6 +399,17 @@ pub trait WorkerService<AuthCtx> {
         auth_ctx: &AuthCtx,
     ) -> WorkerResult<()>;
 
+    /// Checks whether a manual update of the worker to the target version could load a snapshot
+    /// of the worker's current state, without changing the worker. Returns the reason why the
+    /// update would fail, or `None` if it would succeed.
+    async fn dry_run_update(
+        &self,
+        worker_id: &WorkerId,
+        target_version: ComponentVersion,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Option<String>>;
+
     async fn get_component_for_worker(
         &self,
         worker_id: &WorkerId,
@@ -233,9 +423,132 @@ pub trait WorkerService<AuthCtx> {
         from_oplog_index: OplogIndex,
         cursor: Option<OplogCursor>,
         count: u64,
+        invocations_before: Option<u64>,
         metadata: WorkerRequestMetadata,
         auth_ctx: &AuthCtx,
     ) -> Result<GetOplogResponse, WorkerServiceError>;
+
+    async fn get_status(
+        &self,
+        worker_id: &WorkerId,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> Result<PublicWorkerStatusRecord, WorkerServiceError>;
+
+    /// Gets the number of invocations waiting in the worker's queue.
+    async fn get_pending_invocation_count(
+        &self,
+        worker_id: &WorkerId,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<u64>;
+
+    async fn get_component_observability(
+        &self,
+        component_id: &ComponentId,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<ComponentObservability>;
+
+    /// Stores the observability settings of a component and pushes them to all the worker
+    /// executors, so they are applied to the running workers of the component as well.
+    async fn update_component_observability(
+        &self,
+        component_id: &ComponentId,
+        observability: ComponentObservability,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<ComponentObservability>;
+
+    /// Gets an entry of the key-value store shared by all the workers of the account
+    async fn get_shared_value(
+        &self,
+        key: &str,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Option<SharedKeyValueEntry>>;
+
+    /// Sets the value of a shared entry, only if its current version is `expected_version`
+    /// when given. Returns the new version of the entry, or `None` if the version did not match.
+    async fn set_shared_value(
+        &self,
+        key: &str,
+        value: Vec<u8>,
+        expected_version: Option<u64>,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Option<u64>>;
+
+    /// Deletes a shared entry, only if its current version is `expected_version` when given.
+    /// Returns whether the entry was deleted.
+    async fn delete_shared_value(
+        &self,
+        key: &str,
+        expected_version: Option<u64>,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<bool>;
+
+    async fn list_shared_keys(
+        &self,
+        prefix: &str,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Vec<String>>;
+
+    async fn list_topics(
+        &self,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Vec<String>>;
+
+    async fn get_topic(
+        &self,
+        topic: &str,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Option<TopicInfo>>;
+
+    async fn get_account_usage(
+        &self,
+        from: Timestamp,
+        to: Timestamp,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Vec<AccountUsageRecord>>;
+
+    /// Lists a page of at most `count` pending scheduled actions matching the filter, starting
+    /// at the cursor returned with the previous page
+    async fn list_scheduled_actions(
+        &self,
+        filter: ScheduledActionFilter,
+        cursor: Option<ScheduledActionCursor>,
+        count: u64,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<(Option<ScheduledActionCursor>, Vec<PendingScheduledAction>)>;
+
+    /// Cancels the pending scheduled actions matching the filter and returns them
+    async fn cancel_scheduled_actions(
+        &self,
+        filter: ScheduledActionFilter,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Vec<PendingScheduledAction>>;
+
+    /// Lists the promises of the worker which were not completed yet
+    async fn list_promises(
+        &self,
+        worker_id: &WorkerId,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Vec<OutstandingPromise>>;
+
+    /// Lists the sagas of the worker which were neither completed nor compensated yet
+    async fn get_worker_sagas(
+        &self,
+        worker_id: &WorkerId,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Vec<WorkerSaga>>;
 }
 
 pub struct TypedResult {
@@ -258,6 +571,8 @@ pub struct WorkerServiceDefault<AuthCtx> {
     worker_executor_retries: RetryConfig,
     component_service: Arc<dyn ComponentService<AuthCtx> + Send + Sync>,
     routing_table_service: Arc<dyn RoutingTableService + Send + Sync>,
+    worker_placement: WorkerPlacementConfig,
+    invocation_attempts: Arc<InvocationAttempts>,
 }
 
 impl<AuthCtx> WorkerServiceDefault<AuthCtx> {
@@ -266,13 +581,45 @@ impl<AuthCtx> WorkerServiceDefault<AuthCtx> {
         worker_executor_retries: RetryConfig,
         component_service: Arc<dyn ComponentService<AuthCtx> + Send + Sync>,
         routing_table_service: Arc<dyn RoutingTableService + Send + Sync>,
+        worker_placement: WorkerPlacementConfig,
+        invocation_attempts: Arc<InvocationAttempts>,
     ) -> Self {
         Self {
             worker_executor_clients,
             worker_executor_retries,
             component_service,
             routing_table_service,
+            worker_placement,
+            invocation_attempts,
+        }
+    }
+
+    // Invocations without a worker name are forwarded to a random executor, which creates the
+    // worker in one of its own shards. For components spreading their workers, the name is
+    // generated here instead, so the new worker can land on any shard of the cluster.
+    fn place_worker(&self, worker_id: &TargetWorkerId) -> TargetWorkerId {
+        let placed = self.worker_placement.place(worker_id);
+        if placed.worker_name != worker_id.worker_name {
+            info!("Generated new unique worker id {placed}");
         }
+        placed
+    }
+
+    // Invocations are retried when the connection to the executor fails, which may happen
+    // after the executor already started them. The key is decided before the first attempt,
+    // so every retry carries the same one, and the executor returns the result of the
+    // already started invocation instead of executing it again. Keys given by the client are
+    // persisted with the placed worker, so repeated requests are handled the same way.
+    async fn start_invocation(
+        &self,
+        worker_id: &TargetWorkerId,
+        idempotency_key: Option<IdempotencyKey>,
+        function_name: &str,
+    ) -> WorkerResult<InvocationAttempt> {
+        let placed_worker_id = self.place_worker(worker_id);
+        self.invocation_attempts
+            .start(worker_id, placed_worker_id, idempotency_key, function_name)
+            .await
     }
 }
 
@@ -295,7 +642,7 @@ impl<AuthCtx> HasWorkerExecutorClients for WorkerServiceDefault<AuthCtx> {
 #[async_trait]
 impl<AuthCtx> WorkerService<AuthCtx> for WorkerServiceDefault<AuthCtx>
 where
-    AuthCtx: Send + Sync,
+    AuthCtx: HasTokenScope + Send + Sync,
 {
     async fn create(
         &self,
@@ -304,8 +651,10 @@ where
         arguments: Vec<String>,
         environment_variables: HashMap<String, String>,
         metadata: WorkerRequestMetadata,
-        _auth_ctx: &AuthCtx,
+        auth_ctx: &AuthCtx,
     ) -> WorkerResult<WorkerId> {
+        Self::authorize_worker(auth_ctx, WorkerAction::Invoke, worker_id)?;
+
         let worker_id_clone = worker_id.clone();
         self.call_worker_executor(
             worker_id.clone(),
@@ -340,9 +689,12 @@ where
     async fn connect(
         &self,
         worker_id: &WorkerId,
+        backfill_count: u64,
         metadata: WorkerRequestMetadata,
-        _auth_ctx: &AuthCtx,
+        auth_ctx: &AuthCtx,
     ) -> WorkerResult<ConnectWorkerStream> {
+        Self::authorize_worker(auth_ctx, WorkerAction::Connect, worker_id)?;
+
         let worker_id = worker_id.clone();
         let worker_id_err: WorkerId = worker_id.clone();
         let stream = self
@@ -355,6 +707,7 @@ where
                         account_id: metadata.account_id.clone().map(|id| id.into()),
 
                         account_limits: metadata.limits.clone().map(|id| id.into()),
+                        backfill_count,
                     }))
                 },
                 |response| Ok(ConnectWorkerStream::new(response.into_inner())),
@@ -376,8 +729,10 @@ where
         &self,
         worker_id: &WorkerId,
         metadata: WorkerRequestMetadata,
-        _auth_ctx: &AuthCtx,
+        auth_ctx: &AuthCtx,
     ) -> WorkerResult<()> {
+        Self::authorize_worker(auth_ctx, WorkerAction::Delete, worker_id)?;
+
         let worker_id = worker_id.clone();
         self.call_worker_executor(
             worker_id.clone(),
@@ -422,6 +777,80 @@ where
         Ok(result)
     }
 
+    async fn place_worker_with_hint(
+        &self,
+        worker_id: &TargetWorkerId,
+        placement_hint: &WorkerPlacementHint,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<TargetWorkerId> {
+        if worker_id.worker_name.is_some() {
+            return Ok(worker_id.clone());
+        }
+
+        let routing_table = self
+            .routing_table_service
+            .get_routing_table()
+            .await
+            .map_err(|err| {
+                WorkerServiceError::InternalCallError(
+                    CallWorkerExecutorError::FailedToGetRoutingTable(err),
+                )
+            })?;
+        let worker_id = worker_id.clone().into_worker_id(
+            &HashSet::new(),
+            routing_table.number_of_shards.value,
+            Some(placement_hint),
+        );
+        info!("Generated new unique worker id {worker_id} with placement hint {placement_hint}");
+
+        let component = self
+            .component_service
+            .get_latest(&worker_id.component_id, auth_ctx)
+            .await?;
+        self.create(
+            &worker_id,
+            component.versioned_component_id.version,
+            vec![],
+            HashMap::from([(
+                WorkerPlacementHint::ENV_VAR.to_string(),
+                placement_hint.to_string(),
+            )]),
+            metadata,
+            auth_ctx,
+        )
+        .await?;
+        Ok(worker_id.into_target_worker_id())
+    }
+
+    async fn resolve_json_parameters(
+        &self,
+        worker_id: &TargetWorkerId,
+        function_name: &str,
+        params: Vec<serde_json::Value>,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Vec<TypeAnnotatedValue>> {
+        let exports = self
+            .invoked_component_exports(worker_id, metadata, auth_ctx)
+            .await?;
+        convert_json_parameters(&exports, function_name, params)
+    }
+
+    async fn resolve_wave_parameters(
+        &self,
+        worker_id: &TargetWorkerId,
+        function_name: &str,
+        params: &str,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Vec<TypeAnnotatedValue>> {
+        let exports = self
+            .invoked_component_exports(worker_id, metadata, auth_ctx)
+            .await?;
+        convert_wave_parameters(&exports, function_name, params)
+    }
+
     async fn invoke_and_await_typed(
         &self,
         worker_id: &TargetWorkerId,
@@ -429,9 +858,20 @@ where
         function_name: String,
         params: Vec<ProtoVal>,
         invocation_context: Option<InvocationContext>,
+        priority: InvocationPriority,
+        timeout: Option<Duration>,
         metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
     ) -> WorkerResult<TypeAnnotatedValue> {
-        let worker_id = worker_id.clone();
+        Self::authorize_target(auth_ctx, WorkerAction::Invoke, worker_id)?;
+
+        let InvocationAttempt {
+            worker_id,
+            idempotency_key,
+            ..
+        } = self
+            .start_invocation(worker_id, idempotency_key, &function_name)
+            .await?;
         let worker_id_clone = worker_id.clone();
         let function_name_clone = function_name.clone();
 
@@ -444,10 +884,12 @@ where
                         worker_id: Some(worker_id_clone.clone().into()),
                         name: function_name.clone(),
                         input: params.clone(),
-                        idempotency_key: idempotency_key.clone().map(|v| v.into()),
+                        idempotency_key: Some(idempotency_key.clone().into()),
                         account_id: metadata.account_id.clone().map(|id| id.into()),
                         account_limits: metadata.limits.clone().map(|id| id.into()),
                         context: invocation_context.clone(),
+                        priority: priority.into(),
+                        timeout_millis: timeout.map(|timeout| timeout.as_millis() as u64),
                     }
                 )
                 )
@@ -484,76 +926,240 @@ where
         Ok(invoke_response)
     }
 
-    async fn invoke_and_await(
+    async fn invoke_and_poll(
         &self,
         worker_id: &TargetWorkerId,
         idempotency_key: Option<IdempotencyKey>,
         function_name: String,
         params: Vec<ProtoVal>,
         invocation_context: Option<InvocationContext>,
+        priority: InvocationPriority,
+        deadline: Duration,
         metadata: WorkerRequestMetadata,
-    ) -> WorkerResult<InvokeResult> {
-        let worker_id = worker_id.clone();
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<InvocationPollResult> {
+        Self::authorize_target(auth_ctx, WorkerAction::Invoke, worker_id)?;
+
+        let InvocationAttempt {
+            worker_id,
+            idempotency_key,
+            ..
+        } = self
+            .start_invocation(worker_id, idempotency_key, &function_name)
+            .await?;
         let worker_id_clone = worker_id.clone();
 
-        let invoke_response = self.call_worker_executor(
+        self.call_worker_executor(
             worker_id.clone(),
             move |worker_executor_client| {
-                info!("Invoke and await function");
-                Box::pin(worker_executor_client.invoke_and_await_worker(
-                    workerexecutor::v1::InvokeAndAwaitWorkerRequest {
-                        worker_id: Some(worker_id_clone.clone().into()),
-                        name: function_name.clone(),
-                        input: params.clone(),
-                        idempotency_key: idempotency_key.clone().map(|k| k.into()),
-                        account_id: metadata.account_id.clone().map(|id| id.into()),
-                        account_limits: metadata.limits.clone().map(|id| id.into()),
-                        context: invocation_context.clone(),
-                    }
-                )
-                )
-            },
-            move |response| {
-                match response.into_inner() {
-                    workerexecutor::v1::InvokeAndAwaitWorkerResponse {
-                        result:
-                        Some(workerexecutor::v1::invoke_and_await_worker_response::Result::Success(
-                                 workerexecutor::v1::InvokeAndAwaitWorkerSuccess {
-                                     output,
-                                 },
-                             )),
-                    } => {
-                        Ok(InvokeResult { result: output })
-                    }
-                    workerexecutor::v1::InvokeAndAwaitWorkerResponse {
-                        result:
-                        Some(workerexecutor::v1::invoke_and_await_worker_response::Result::Failure(err)),
-                    } => {
-                        error!("Invoked function error: {err:?}");
-                        Err(err.into())
-                    }
-                    workerexecutor::v1::InvokeAndAwaitWorkerResponse { .. } => {
-                        error!("Invoked function failed with empty response");
-                        Err("Empty response".into())
-                    }
-                }
+                info!(
+                    "Invoking function on {}: {}",
+                    worker_id_clone, function_name
+                );
+                Box::pin(worker_executor_client.invoke_and_poll_worker(
+                    InvokeAndPollWorkerRequest {
+                        invocation: Some(InvokeAndAwaitWorkerRequest {
+                            worker_id: Some(worker_id_clone.clone().into()),
+                            name: function_name.clone(),
+                            input: params.clone(),
+                            idempotency_key: Some(idempotency_key.clone().into()),
+                            account_id: metadata.account_id.clone().map(|id| id.into()),
+                            account_limits: metadata.limits.clone().map(|id| id.into()),
+                            context: invocation_context.clone(),
+                            priority: priority.into(),
+                            timeout_millis: None,
+                        }),
+                        deadline_millis: deadline.as_millis() as u64,
+                    },
+                ))
             },
+            |response| poll_result(response.into_inner()),
             WorkerServiceError::InternalCallError,
-        ).await?;
-
-        Ok(invoke_response)
+        )
+        .await
     }
 
-    async fn invoke(
+    async fn await_invocation(
+        &self,
+        worker_id: &WorkerId,
+        idempotency_key: &IdempotencyKey,
+        deadline: Duration,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<InvocationPollResult> {
+        Self::authorize_worker(auth_ctx, WorkerAction::Invoke, worker_id)?;
+
+        let worker_id = worker_id.clone();
+        let idempotency_key = idempotency_key.clone();
+        self.call_worker_executor(
+            worker_id.clone(),
+            move |worker_executor_client| {
+                info!("Awaiting invocation {} of {}", idempotency_key, worker_id);
+                Box::pin(worker_executor_client.await_worker_invocation(
+                    AwaitWorkerInvocationRequest {
+                        worker_id: Some(worker_id.clone().into()),
+                        idempotency_key: Some(idempotency_key.clone().into()),
+                        account_id: metadata.account_id.clone().map(|id| id.into()),
+                        deadline_millis: deadline.as_millis() as u64,
+                    },
+                ))
+            },
+            |response| poll_result(response.into_inner()),
+            WorkerServiceError::InternalCallError,
+        )
+        .await
+    }
+
+    async fn invoke_and_stream(
+        &self,
+        worker_id: &TargetWorkerId,
+        idempotency_key: Option<IdempotencyKey>,
+        function_name: String,
+        params: Vec<ProtoVal>,
+        invocation_context: Option<InvocationContext>,
+        priority: InvocationPriority,
+        timeout: Option<Duration>,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<InvokeAndStreamWorkerStream> {
+        Self::authorize_target(auth_ctx, WorkerAction::Invoke, worker_id)?;
+
+        let InvocationAttempt {
+            worker_id,
+            idempotency_key,
+            ..
+        } = self
+            .start_invocation(worker_id, idempotency_key, &function_name)
+            .await?;
+        let worker_id_clone = worker_id.clone();
+
+        let stream = self
+            .call_worker_executor(
+                worker_id.clone(),
+                move |worker_executor_client| {
+                    info!(
+                        "Invoking function on {} with streamed result: {}",
+                        worker_id_clone, function_name
+                    );
+                    Box::pin(worker_executor_client.invoke_and_stream_worker(
+                        InvokeAndAwaitWorkerRequest {
+                            worker_id: Some(worker_id_clone.clone().into()),
+                            name: function_name.clone(),
+                            input: params.clone(),
+                            idempotency_key: Some(idempotency_key.clone().into()),
+                            account_id: metadata.account_id.clone().map(|id| id.into()),
+                            account_limits: metadata.limits.clone().map(|id| id.into()),
+                            context: invocation_context.clone(),
+                            priority: priority.into(),
+                            timeout_millis: timeout.map(|timeout| timeout.as_millis() as u64),
+                        },
+                    ))
+                },
+                |response| Ok(InvokeAndStreamWorkerStream::new(response.into_inner())),
+                WorkerServiceError::InternalCallError,
+            )
+            .await?;
+
+        Ok(stream)
+    }
+
+    async fn invoke_and_await(
+        &self,
+        worker_id: &TargetWorkerId,
+        idempotency_key: Option<IdempotencyKey>,
+        function_name: String,
+        params: Vec<ProtoVal>,
+        invocation_context: Option<InvocationContext>,
+        priority: InvocationPriority,
+        timeout: Option<Duration>,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<InvokeResult> {
+        Self::authorize_target(auth_ctx, WorkerAction::Invoke, worker_id)?;
+
+        let InvocationAttempt {
+            worker_id,
+            idempotency_key,
+            ..
+        } = self
+            .start_invocation(worker_id, idempotency_key, &function_name)
+            .await?;
+        let worker_id_clone = worker_id.clone();
+
+        let invoke_response = self.call_worker_executor(
+            worker_id.clone(),
+            move |worker_executor_client| {
+                info!("Invoke and await function");
+                Box::pin(worker_executor_client.invoke_and_await_worker(
+                    workerexecutor::v1::InvokeAndAwaitWorkerRequest {
+                        worker_id: Some(worker_id_clone.clone().into()),
+                        name: function_name.clone(),
+                        input: params.clone(),
+                        idempotency_key: Some(idempotency_key.clone().into()),
+                        account_id: metadata.account_id.clone().map(|id| id.into()),
+                        account_limits: metadata.limits.clone().map(|id| id.into()),
+                        context: invocation_context.clone(),
+                        priority: priority.into(),
+                        timeout_millis: timeout.map(|timeout| timeout.as_millis() as u64),
+                    }
+                )
+                )
+            },
+            move |response| {
+                match response.into_inner() {
+                    workerexecutor::v1::InvokeAndAwaitWorkerResponse {
+                        result:
+                        Some(workerexecutor::v1::invoke_and_await_worker_response::Result::Success(
+                                 workerexecutor::v1::InvokeAndAwaitWorkerSuccess {
+                                     output,
+                                 },
+                             )),
+                    } => {
+                        Ok(InvokeResult { result: output })
+                    }
+                    workerexecutor::v1::InvokeAndAwaitWorkerResponse {
+                        result:
+                        Some(workerexecutor::v1::invoke_and_await_worker_response::Result::Failure(err)),
+                    } => {
+                        error!("Invoked function error: {err:?}");
+                        Err(err.into())
+                    }
+                    workerexecutor::v1::InvokeAndAwaitWorkerResponse { .. } => {
+                        error!("Invoked function failed with empty response");
+                        Err("Empty response".into())
+                    }
+                }
+            },
+            WorkerServiceError::InternalCallError,
+        ).await?;
+
+        Ok(invoke_response)
+    }
+
+    async fn invoke(
         &self,
         worker_id: &TargetWorkerId,
         idempotency_key: Option<IdempotencyKey>,
         function_name: String,
         params: Vec<ProtoVal>,
         invocation_context: Option<InvocationContext>,
+        priority: InvocationPriority,
         metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
     ) -> WorkerResult<()> {
-        let worker_id = worker_id.clone();
+        Self::authorize_target(auth_ctx, WorkerAction::Invoke, worker_id)?;
+
+        let InvocationAttempt {
+            worker_id,
+            idempotency_key,
+            repeated,
+        } = self
+            .start_invocation(worker_id, idempotency_key, &function_name)
+            .await?;
+        if repeated {
+            info!("Invocation {idempotency_key} of {worker_id} is already enqueued");
+            return Ok(());
+        }
         self.call_worker_executor(
             worker_id.clone(),
             move |worker_executor_client| {
@@ -562,12 +1168,13 @@ where
                 Box::pin(worker_executor_client.invoke_worker(
                     workerexecutor::v1::InvokeWorkerRequest {
                         worker_id: Some(worker_id.into()),
-                        idempotency_key: idempotency_key.clone().map(|k| k.into()),
+                        idempotency_key: Some(idempotency_key.clone().into()),
                         name: function_name.clone(),
                         input: params.clone(),
                         account_id: metadata.account_id.clone().map(|id| id.into()),
                         account_limits: metadata.limits.clone().map(|id| id.into()),
                         context: invocation_context.clone(),
+                        priority: priority.into(),
                     },
                 ))
             },
@@ -595,8 +1202,10 @@ where
         oplog_id: u64,
         data: Vec<u8>,
         metadata: WorkerRequestMetadata,
-        _auth_ctx: &AuthCtx,
+        auth_ctx: &AuthCtx,
     ) -> WorkerResult<bool> {
+        Self::authorize_worker(auth_ctx, WorkerAction::Invoke, worker_id)?;
+
         let promise_id = PromiseId {
             worker_id: worker_id.clone(),
             oplog_idx: OplogIndex::from_u64(oplog_id),
@@ -648,8 +1257,10 @@ where
         worker_id: &WorkerId,
         recover_immediately: bool,
         metadata: WorkerRequestMetadata,
-        _auth_ctx: &AuthCtx,
+        auth_ctx: &AuthCtx,
     ) -> WorkerResult<()> {
+        Self::authorize_worker(auth_ctx, WorkerAction::Update, worker_id)?;
+
         let worker_id = worker_id.clone();
         self.call_worker_executor(
             worker_id.clone(),
@@ -685,11 +1296,13 @@ where
         &self,
         worker_id: &WorkerId,
         metadata: WorkerRequestMetadata,
-        _auth_ctx: &AuthCtx,
+        auth_ctx: &AuthCtx,
     ) -> WorkerResult<WorkerMetadata> {
+        Self::authorize_worker(auth_ctx, WorkerAction::ReadMetadata, worker_id)?;
+
         let worker_id = worker_id.clone();
         let metadata = self.call_worker_executor(
-            worker_id.clone(),
+            ReadReplica(worker_id.clone()),
             move |worker_executor_client| {
                 let worker_id = worker_id.clone();
                 info!("Get metadata");
@@ -726,6 +1339,143 @@ where
         Ok(metadata)
     }
 
+    async fn get_metadata_bulk(
+        &self,
+        worker_ids: &[WorkerId],
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> Vec<WorkerResult<WorkerMetadata>> {
+        let mut results: Vec<Option<WorkerResult<WorkerMetadata>>> =
+            worker_ids.iter().map(|_| None).collect();
+
+        // Each executor gets a single request for all the requested workers it owns
+        let routing_table = self.routing_table_service.get_routing_table().await.ok();
+        let mut batches: HashMap<Pod, Vec<usize>> = HashMap::new();
+        for (idx, worker_id) in worker_ids.iter().enumerate() {
+            if let Err(error) =
+                Self::authorize_worker(auth_ctx, WorkerAction::ReadMetadata, worker_id)
+            {
+                results[idx] = Some(Err(error));
+            } else if let Some(pod) = routing_table
+                .as_ref()
+                .and_then(|routing_table| routing_table.lookup(worker_id))
+            {
+                batches.entry(pod.clone()).or_default().push(idx);
+            }
+        }
+
+        let batch_results = join_all(batches.into_values().map(|indices| {
+            let batch = indices.iter().map(|idx| worker_ids[*idx].clone()).collect();
+            let metadata = metadata.clone();
+            async move { (indices, self.get_metadata_batch(batch, metadata).await) }
+        }))
+        .await;
+        for (indices, batch_result) in batch_results {
+            if let Ok(batch_result) = batch_result {
+                for (idx, result) in indices.into_iter().zip(batch_result) {
+                    if !is_misrouted(&result) {
+                        results[idx] = Some(result);
+                    }
+                }
+            }
+        }
+
+        // Workers without a result from a batch, because their executor could not be reached or
+        // no longer owns them, are requested one by one, retrying like single requests
+        let missing = results
+            .iter()
+            .enumerate()
+            .filter(|(_, result)| result.is_none())
+            .map(|(idx, _)| idx)
+            .collect::<Vec<_>>();
+        let fallback_results = join_all(missing.into_iter().map(|idx| {
+            let metadata = metadata.clone();
+            async move {
+                (
+                    idx,
+                    self.get_metadata(&worker_ids[idx], metadata, auth_ctx)
+                        .await,
+                )
+            }
+        }))
+        .await;
+        for (idx, result) in fallback_results {
+            results[idx] = Some(result);
+        }
+
+        results
+            .into_iter()
+            .map(|result| result.expect("Every worker has a result"))
+            .collect()
+    }
+
+    async fn get_component_worker_statistics(
+        &self,
+        component_id: &ComponentId,
+        precise: bool,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<ComponentWorkerStatistics> {
+        Self::authorize_component(auth_ctx, WorkerAction::ReadMetadata, component_id)?;
+
+        let executor_component_id = component_id.clone();
+        let statistics = self
+            .call_worker_executor(
+                AllExecutors,
+                move |worker_executor_client| {
+                    info!("Get component worker statistics");
+                    Box::pin(worker_executor_client.get_component_worker_statistics(
+                        workerexecutor::v1::GetComponentWorkerStatisticsRequest {
+                            component_id: Some(executor_component_id.clone().into()),
+                            account_id: metadata.account_id.clone().map(|id| id.into()),
+                            precise,
+                        },
+                    ))
+                },
+                |responses| {
+                    responses
+                        .into_iter()
+                        .map(|response| match response.into_inner() {
+                            workerexecutor::v1::GetComponentWorkerStatisticsResponse {
+                                result:
+                                    Some(
+                                        workerexecutor::v1::get_component_worker_statistics_response::Result::Success(
+                                            statistics,
+                                        ),
+                                    ),
+                            } => ComponentWorkerStatistics::try_from(statistics).map_err(|err| {
+                                GolemError::Unknown(GolemErrorUnknown {
+                                    details: format!(
+                                        "Unexpected worker statistics in response: {err}"
+                                    ),
+                                })
+                                .into()
+                            }),
+                            workerexecutor::v1::GetComponentWorkerStatisticsResponse {
+                                result:
+                                    Some(
+                                        workerexecutor::v1::get_component_worker_statistics_response::Result::Failure(
+                                            err,
+                                        ),
+                                    ),
+                            } => Err(err.into()),
+                            workerexecutor::v1::GetComponentWorkerStatisticsResponse { .. } => {
+                                Err("Empty response".into())
+                            }
+                        })
+                        .collect::<Result<Vec<_>, ResponseMapResult>>()
+                },
+                WorkerServiceError::InternalCallError,
+            )
+            .await?;
+
+        let mut result = ComponentWorkerStatistics::new(component_id.clone());
+        for executor_statistics in statistics.into_iter().flatten() {
+            result.merge(executor_statistics);
+        }
+        Ok(result)
+    }
+
     async fn find_metadata(
         &self,
         component_id: &ComponentId,
@@ -736,6 +1486,8 @@ where
         metadata: WorkerRequestMetadata,
         auth_ctx: &AuthCtx,
     ) -> WorkerResult<(Option<ScanCursor>, Vec<WorkerMetadata>)> {
+        Self::authorize_component(auth_ctx, WorkerAction::ReadMetadata, component_id)?;
+
         info!("Find metadata");
         if filter.as_ref().is_some_and(is_filter_with_running_status) {
             let result = self
@@ -761,8 +1513,10 @@ where
         &self,
         worker_id: &WorkerId,
         metadata: WorkerRequestMetadata,
-        _auth_ctx: &AuthCtx,
+        auth_ctx: &AuthCtx,
     ) -> WorkerResult<()> {
+        Self::authorize_worker(auth_ctx, WorkerAction::Update, worker_id)?;
+
         let worker_id = worker_id.clone();
         self.call_worker_executor(
             worker_id.clone(),
@@ -794,8 +1548,10 @@ where
         update_mode: UpdateMode,
         target_version: ComponentVersion,
         metadata: WorkerRequestMetadata,
-        _auth_ctx: &AuthCtx,
+        auth_ctx: &AuthCtx,
     ) -> WorkerResult<()> {
+        Self::authorize_worker(auth_ctx, WorkerAction::Update, worker_id)?;
+
         let worker_id = worker_id.clone();
         self.call_worker_executor(
             worker_id.clone(),
@@ -824,12 +1580,61 @@ where
         Ok(())
     }
 
+    async fn dry_run_update(
+        &self,
+        worker_id: &WorkerId,
+        target_version: ComponentVersion,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Option<String>> {
+        Self::authorize_worker(auth_ctx, WorkerAction::Update, worker_id)?;
+
+        let worker_id = worker_id.clone();
+        let error = self
+            .call_worker_executor(
+                worker_id.clone(),
+                move |worker_executor_client| {
+                    info!("Dry run update worker");
+                    let worker_id = worker_id.clone();
+                    Box::pin(worker_executor_client.dry_run_update_worker(
+                        DryRunUpdateWorkerRequest {
+                            worker_id: Some(worker_id.into()),
+                            target_version,
+                            account_id: metadata.account_id.clone().map(|id| id.into()),
+                        },
+                    ))
+                },
+                |response| match response.into_inner() {
+                    workerexecutor::v1::DryRunUpdateWorkerResponse {
+                        result:
+                            Some(workerexecutor::v1::dry_run_update_worker_response::Result::Success(
+                                result,
+                            )),
+                    } => Ok(result.error),
+                    workerexecutor::v1::DryRunUpdateWorkerResponse {
+                        result:
+                            Some(workerexecutor::v1::dry_run_update_worker_response::Result::Failure(
+                                err,
+                            )),
+                    } => Err(err.into()),
+                    workerexecutor::v1::DryRunUpdateWorkerResponse { .. } => {
+                        Err("Empty response".into())
+                    }
+                },
+                WorkerServiceError::InternalCallError,
+            )
+            .await?;
+        Ok(error)
+    }
+
     async fn get_component_for_worker(
         &self,
         worker_id: &WorkerId,
         metadata: WorkerRequestMetadata,
         auth_ctx: &AuthCtx,
     ) -> Result<Component, WorkerServiceError> {
+        Self::authorize_worker(auth_ctx, WorkerAction::ReadMetadata, worker_id)?;
+
         self.try_get_component_for_worker(worker_id, metadata, auth_ctx)
             .await
     }
@@ -840,12 +1645,16 @@ where
         from_oplog_index: OplogIndex,
         cursor: Option<OplogCursor>,
         count: u64,
+        invocations_before: Option<u64>,
         metadata: WorkerRequestMetadata,
-        _auth_ctx: &AuthCtx,
+        auth_ctx: &AuthCtx,
     ) -> Result<GetOplogResponse, WorkerServiceError> {
+        Self::authorize_worker(auth_ctx, WorkerAction::ReadOplog, worker_id)?;
+
         let worker_id = worker_id.clone();
+        let read_replica = self.routing_table_service.read_max_staleness().is_some();
         self.call_worker_executor(
-            worker_id.clone(),
+            ReadReplica(worker_id.clone()),
             move |worker_executor_client| {
                 info!("Get oplog");
                 let worker_id = worker_id.clone();
@@ -856,6 +1665,8 @@ where
                         cursor: cursor.clone().map(|c| c.into()),
                         count,
                         account_id: metadata.account_id.clone().map(|id| id.into()),
+                        read_replica,
+                        invocations_before,
                     }),
                 )
             },
@@ -893,54 +1704,836 @@ where
         )
         .await
     }
-}
 
-impl<AuthCtx> WorkerServiceDefault<AuthCtx>
-where
-    AuthCtx: Send + Sync,
-{
-    async fn try_get_component_for_worker(
+    async fn get_status(
         &self,
         worker_id: &WorkerId,
-        request_metadata: WorkerRequestMetadata,
+        metadata: WorkerRequestMetadata,
         auth_ctx: &AuthCtx,
-    ) -> Result<Component, WorkerServiceError> {
-        match self
-            .get_metadata(worker_id, request_metadata, auth_ctx)
-            .await
-        {
-            Ok(metadata) => {
-                let component_version = metadata.component_version;
-                let component_details = self
-                    .component_service
-                    .get_by_version(&worker_id.component_id, component_version, auth_ctx)
-                    .await?;
-
-                Ok(component_details)
-            }
-            Err(WorkerServiceError::WorkerNotFound(_)) => Ok(self
-                .component_service
-                .get_latest(&worker_id.component_id, auth_ctx)
-                .await?),
-            Err(WorkerServiceError::Golem(GolemError::WorkerNotFound(_))) => Ok(self
-                .component_service
-                .get_latest(&worker_id.component_id, auth_ctx)
-                .await?),
-            Err(other) => Err(other),
-        }
-    }
+    ) -> Result<PublicWorkerStatusRecord, WorkerServiceError> {
+        Self::authorize_worker(auth_ctx, WorkerAction::ReadMetadata, worker_id)?;
 
-    async fn find_running_metadata_internal(
-        &self,
-        component_id: &ComponentId,
-        filter: Option<WorkerFilter>,
-        _auth_ctx: &AuthCtx,
-    ) -> WorkerResult<Vec<WorkerMetadata>> {
-        let component_id = component_id.clone();
-        let result = self.call_worker_executor(
-            AllExecutors,
-            move |worker_executor_client| {
-                let component_id: golem_api_grpc::proto::golem::component::ComponentId =
+        let worker_id = worker_id.clone();
+        self.call_worker_executor(
+            worker_id.clone(),
+            move |worker_executor_client| {
+                let worker_id = worker_id.clone();
+                Box::pin(
+                    worker_executor_client.get_worker_status(
+                        workerexecutor::v1::GetWorkerStatusRequest {
+                            worker_id: Some(worker_id.into()),
+                            account_id: metadata.account_id.clone().map(|id| id.into()),
+                        },
+                    ),
+                )
+            },
+            |response| match response.into_inner() {
+                workerexecutor::v1::GetWorkerStatusResponse {
+                    result:
+                        Some(workerexecutor::v1::get_worker_status_response::Result::Success(status)),
+                } => status.try_into().map_err(|err| {
+                    GolemError::Unknown(GolemErrorUnknown {
+                        details: format!("Unexpected worker status: {err}"),
+                    })
+                    .into()
+                }),
+                workerexecutor::v1::GetWorkerStatusResponse {
+                    result:
+                        Some(workerexecutor::v1::get_worker_status_response::Result::Failure(err)),
+                } => Err(err.into()),
+                workerexecutor::v1::GetWorkerStatusResponse { .. } => {
+                    Err("Empty response".into())
+                }
+            },
+            WorkerServiceError::InternalCallError,
+        )
+        .await
+    }
+
+    async fn get_pending_invocation_count(
+        &self,
+        worker_id: &WorkerId,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<u64> {
+        Self::authorize_worker(auth_ctx, WorkerAction::ReadMetadata, worker_id)?;
+
+        let worker_id = worker_id.clone();
+        self.call_worker_executor(
+            worker_id.clone(),
+            move |worker_executor_client| {
+                let worker_id = worker_id.clone();
+                Box::pin(worker_executor_client.get_pending_invocation_count(
+                    workerexecutor::v1::GetPendingInvocationCountRequest {
+                        worker_id: Some(worker_id.into()),
+                        account_id: metadata.account_id.clone().map(|id| id.into()),
+                    },
+                ))
+            },
+            |response| match response.into_inner() {
+                workerexecutor::v1::GetPendingInvocationCountResponse {
+                    result:
+                        Some(workerexecutor::v1::get_pending_invocation_count_response::Result::Success(
+                            count,
+                        )),
+                } => Ok(count),
+                workerexecutor::v1::GetPendingInvocationCountResponse {
+                    result:
+                        Some(workerexecutor::v1::get_pending_invocation_count_response::Result::Failure(
+                            err,
+                        )),
+                } => Err(err.into()),
+                workerexecutor::v1::GetPendingInvocationCountResponse { .. } => {
+                    Err("Empty response".into())
+                }
+            },
+            WorkerServiceError::InternalCallError,
+        )
+        .await
+    }
+
+    async fn get_component_observability(
+        &self,
+        component_id: &ComponentId,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<ComponentObservability> {
+        Self::authorize_component(auth_ctx, WorkerAction::ReadMetadata, component_id)?;
+
+        Ok(self
+            .component_service
+            .get_observability(component_id, auth_ctx)
+            .await?)
+    }
+
+    async fn update_component_observability(
+        &self,
+        component_id: &ComponentId,
+        observability: ComponentObservability,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<ComponentObservability> {
+        Self::authorize_component(auth_ctx, WorkerAction::Update, component_id)?;
+
+        let observability = self
+            .component_service
+            .update_observability(component_id, observability, auth_ctx)
+            .await?;
+
+        let component_id = component_id.clone();
+        let pushed = observability.clone();
+        self.call_worker_executor(
+            AllExecutors,
+            move |worker_executor_client| {
+                info!("Update component observability");
+                Box::pin(worker_executor_client.update_component_observability(
+                    UpdateComponentObservabilityRequest {
+                        component_id: Some(component_id.clone().into()),
+                        observability: Some(pushed.clone().into()),
+                    },
+                ))
+            },
+            |responses| {
+                responses
+                    .into_iter()
+                    .map(|response| match response.into_inner() {
+                        workerexecutor::v1::UpdateComponentObservabilityResponse {
+                            result:
+                                Some(
+                                    workerexecutor::v1::update_component_observability_response::Result::Success(_),
+                                ),
+                        } => Ok(()),
+                        workerexecutor::v1::UpdateComponentObservabilityResponse {
+                            result:
+                                Some(
+                                    workerexecutor::v1::update_component_observability_response::Result::Failure(err),
+                                ),
+                        } => Err(err.into()),
+                        workerexecutor::v1::UpdateComponentObservabilityResponse { .. } => {
+                            Err("Empty response".into())
+                        }
+                    })
+                    .collect::<Result<Vec<_>, ResponseMapResult>>()
+            },
+            WorkerServiceError::InternalCallError,
+        )
+        .await?;
+
+        Ok(observability)
+    }
+
+    async fn get_shared_value(
+        &self,
+        key: &str,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Option<SharedKeyValueEntry>> {
+        Self::authorize_all_workers(auth_ctx, WorkerAction::ReadMetadata)?;
+
+        let key = key.to_string();
+        self.call_worker_executor(
+            RandomExecutor,
+            move |worker_executor_client| {
+                Box::pin(worker_executor_client.get_shared_value(
+                    workerexecutor::v1::GetSharedValueRequest {
+                        account_id: metadata.account_id.clone().map(|id| id.into()),
+                        key: key.clone(),
+                    },
+                ))
+            },
+            |response| match response.into_inner() {
+                workerexecutor::v1::GetSharedValueResponse {
+                    result:
+                        Some(workerexecutor::v1::get_shared_value_response::Result::Success(
+                            workerexecutor::v1::GetSharedValueSuccessResponse { entry },
+                        )),
+                } => Ok(entry.map(|entry| SharedKeyValueEntry {
+                    value: Base64(entry.value),
+                    version: entry.version,
+                })),
+                workerexecutor::v1::GetSharedValueResponse {
+                    result:
+                        Some(workerexecutor::v1::get_shared_value_response::Result::Failure(err)),
+                } => Err(err.into()),
+                workerexecutor::v1::GetSharedValueResponse { .. } => Err("Empty response".into()),
+            },
+            WorkerServiceError::InternalCallError,
+        )
+        .await
+    }
+
+    async fn set_shared_value(
+        &self,
+        key: &str,
+        value: Vec<u8>,
+        expected_version: Option<u64>,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Option<u64>> {
+        Self::authorize_all_workers(auth_ctx, WorkerAction::Update)?;
+
+        let key = key.to_string();
+        self.call_worker_executor(
+            RandomExecutor,
+            move |worker_executor_client| {
+                Box::pin(worker_executor_client.set_shared_value(
+                    workerexecutor::v1::SetSharedValueRequest {
+                        account_id: metadata.account_id.clone().map(|id| id.into()),
+                        key: key.clone(),
+                        value: value.clone(),
+                        expected_version,
+                    },
+                ))
+            },
+            |response| match response.into_inner() {
+                workerexecutor::v1::SetSharedValueResponse {
+                    result:
+                        Some(workerexecutor::v1::set_shared_value_response::Result::Success(
+                            workerexecutor::v1::SetSharedValueSuccessResponse { version },
+                        )),
+                } => Ok(version),
+                workerexecutor::v1::SetSharedValueResponse {
+                    result:
+                        Some(workerexecutor::v1::set_shared_value_response::Result::Failure(err)),
+                } => Err(err.into()),
+                workerexecutor::v1::SetSharedValueResponse { .. } => Err("Empty response".into()),
+            },
+            WorkerServiceError::InternalCallError,
+        )
+        .await
+    }
+
+    async fn delete_shared_value(
+        &self,
+        key: &str,
+        expected_version: Option<u64>,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<bool> {
+        Self::authorize_all_workers(auth_ctx, WorkerAction::Update)?;
+
+        let key = key.to_string();
+        self.call_worker_executor(
+            RandomExecutor,
+            move |worker_executor_client| {
+                Box::pin(
+                    worker_executor_client.delete_shared_value(
+                        workerexecutor::v1::DeleteSharedValueRequest {
+                            account_id: metadata.account_id.clone().map(|id| id.into()),
+                            key: key.clone(),
+                            expected_version,
+                        },
+                    ),
+                )
+            },
+            |response| match response.into_inner() {
+                workerexecutor::v1::DeleteSharedValueResponse {
+                    result:
+                        Some(workerexecutor::v1::delete_shared_value_response::Result::Success(
+                            deleted,
+                        )),
+                } => Ok(deleted),
+                workerexecutor::v1::DeleteSharedValueResponse {
+                    result:
+                        Some(workerexecutor::v1::delete_shared_value_response::Result::Failure(
+                            err,
+                        )),
+                } => Err(err.into()),
+                workerexecutor::v1::DeleteSharedValueResponse { .. } => {
+                    Err("Empty response".into())
+                }
+            },
+            WorkerServiceError::InternalCallError,
+        )
+        .await
+    }
+
+    async fn list_shared_keys(
+        &self,
+        prefix: &str,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Vec<String>> {
+        Self::authorize_all_workers(auth_ctx, WorkerAction::ReadMetadata)?;
+
+        let prefix = prefix.to_string();
+        self.call_worker_executor(
+            RandomExecutor,
+            move |worker_executor_client| {
+                Box::pin(worker_executor_client.list_shared_keys(
+                    workerexecutor::v1::ListSharedKeysRequest {
+                        account_id: metadata.account_id.clone().map(|id| id.into()),
+                        prefix: prefix.clone(),
+                    },
+                ))
+            },
+            |response| match response.into_inner() {
+                workerexecutor::v1::ListSharedKeysResponse {
+                    result:
+                        Some(workerexecutor::v1::list_shared_keys_response::Result::Success(
+                            workerexecutor::v1::SharedKeys { keys },
+                        )),
+                } => Ok(keys),
+                workerexecutor::v1::ListSharedKeysResponse {
+                    result:
+                        Some(workerexecutor::v1::list_shared_keys_response::Result::Failure(err)),
+                } => Err(err.into()),
+                workerexecutor::v1::ListSharedKeysResponse { .. } => Err("Empty response".into()),
+            },
+            WorkerServiceError::InternalCallError,
+        )
+        .await
+    }
+
+    async fn list_topics(
+        &self,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Vec<String>> {
+        Self::authorize_all_workers(auth_ctx, WorkerAction::ReadMetadata)?;
+
+        self.call_worker_executor(
+            RandomExecutor,
+            move |worker_executor_client| {
+                Box::pin(worker_executor_client.list_topics(
+                    workerexecutor::v1::ListTopicsRequest {
+                        account_id: metadata.account_id.clone().map(|id| id.into()),
+                    },
+                ))
+            },
+            |response| match response.into_inner() {
+                workerexecutor::v1::ListTopicsResponse {
+                    result:
+                        Some(workerexecutor::v1::list_topics_response::Result::Success(
+                            workerexecutor::v1::TopicNames { names },
+                        )),
+                } => Ok(names),
+                workerexecutor::v1::ListTopicsResponse {
+                    result: Some(workerexecutor::v1::list_topics_response::Result::Failure(err)),
+                } => Err(err.into()),
+                workerexecutor::v1::ListTopicsResponse { .. } => Err("Empty response".into()),
+            },
+            WorkerServiceError::InternalCallError,
+        )
+        .await
+    }
+
+    async fn get_topic(
+        &self,
+        topic: &str,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Option<TopicInfo>> {
+        Self::authorize_all_workers(auth_ctx, WorkerAction::ReadMetadata)?;
+
+        let topic = topic.to_string();
+        self.call_worker_executor(
+            RandomExecutor,
+            move |worker_executor_client| {
+                Box::pin(
+                    worker_executor_client.get_topic(workerexecutor::v1::GetTopicRequest {
+                        account_id: metadata.account_id.clone().map(|id| id.into()),
+                        topic: topic.clone(),
+                    }),
+                )
+            },
+            |response| match response.into_inner() {
+                workerexecutor::v1::GetTopicResponse {
+                    result:
+                        Some(workerexecutor::v1::get_topic_response::Result::Success(
+                            workerexecutor::v1::GetTopicSuccessResponse { topic },
+                        )),
+                } => topic
+                    .map(|topic| topic.try_into())
+                    .transpose()
+                    .map_err(|err| {
+                        GolemError::Unknown(GolemErrorUnknown {
+                            details: format!("Unexpected topic: {err}"),
+                        })
+                        .into()
+                    }),
+                workerexecutor::v1::GetTopicResponse {
+                    result: Some(workerexecutor::v1::get_topic_response::Result::Failure(err)),
+                } => Err(err.into()),
+                workerexecutor::v1::GetTopicResponse { .. } => Err("Empty response".into()),
+            },
+            WorkerServiceError::InternalCallError,
+        )
+        .await
+    }
+
+    async fn get_account_usage(
+        &self,
+        from: Timestamp,
+        to: Timestamp,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Vec<AccountUsageRecord>> {
+        Self::authorize_all_workers(auth_ctx, WorkerAction::ReadMetadata)?;
+
+        self.call_worker_executor(
+            RandomExecutor,
+            move |worker_executor_client| {
+                Box::pin(worker_executor_client.get_account_usage(
+                    workerexecutor::v1::GetAccountUsageRequest {
+                        account_id: metadata.account_id.clone().map(|id| id.into()),
+                        from: Some(from.into()),
+                        to: Some(to.into()),
+                    },
+                ))
+            },
+            |response| match response.into_inner() {
+                workerexecutor::v1::GetAccountUsageResponse {
+                    result:
+                        Some(workerexecutor::v1::get_account_usage_response::Result::Success(
+                            workerexecutor::v1::AccountUsageRecords { records },
+                        )),
+                } => records
+                    .into_iter()
+                    .map(|record| record.try_into())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|err| {
+                        GolemError::Unknown(GolemErrorUnknown {
+                            details: format!("Unexpected usage record: {err}"),
+                        })
+                        .into()
+                    }),
+                workerexecutor::v1::GetAccountUsageResponse {
+                    result:
+                        Some(workerexecutor::v1::get_account_usage_response::Result::Failure(err)),
+                } => Err(err.into()),
+                workerexecutor::v1::GetAccountUsageResponse { .. } => Err("Empty response".into()),
+            },
+            WorkerServiceError::InternalCallError,
+        )
+        .await
+    }
+
+    async fn list_scheduled_actions(
+        &self,
+        filter: ScheduledActionFilter,
+        cursor: Option<ScheduledActionCursor>,
+        count: u64,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<(Option<ScheduledActionCursor>, Vec<PendingScheduledAction>)> {
+        Self::authorize_all_workers(auth_ctx, WorkerAction::ReadMetadata)?;
+
+        self.call_worker_executor(
+            RandomExecutor,
+            move |worker_executor_client| {
+                Box::pin(worker_executor_client.list_scheduled_actions(
+                    workerexecutor::v1::ListScheduledActionsRequest {
+                        account_id: metadata.account_id.clone().map(|id| id.into()),
+                        filter: Some(filter.clone().into()),
+                        cursor: cursor.clone().map(|cursor| cursor.into()),
+                        count,
+                    },
+                ))
+            },
+            |response| match response.into_inner() {
+                workerexecutor::v1::ListScheduledActionsResponse {
+                    result:
+                        Some(workerexecutor::v1::list_scheduled_actions_response::Result::Success(
+                            workerexecutor::v1::PendingScheduledActions {
+                                actions,
+                                next_cursor,
+                            },
+                        )),
+                } => Ok((
+                    next_cursor.map(|cursor| cursor.into()),
+                    Self::pending_scheduled_actions(actions)?,
+                )),
+                workerexecutor::v1::ListScheduledActionsResponse {
+                    result:
+                        Some(workerexecutor::v1::list_scheduled_actions_response::Result::Failure(
+                            err,
+                        )),
+                } => Err(err.into()),
+                workerexecutor::v1::ListScheduledActionsResponse { .. } => {
+                    Err("Empty response".into())
+                }
+            },
+            WorkerServiceError::InternalCallError,
+        )
+        .await
+    }
+
+    async fn cancel_scheduled_actions(
+        &self,
+        filter: ScheduledActionFilter,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Vec<PendingScheduledAction>> {
+        Self::authorize_all_workers(auth_ctx, WorkerAction::Update)?;
+
+        self.call_worker_executor(
+            RandomExecutor,
+            move |worker_executor_client| {
+                Box::pin(worker_executor_client.cancel_scheduled_actions(
+                    workerexecutor::v1::CancelScheduledActionsRequest {
+                        account_id: metadata.account_id.clone().map(|id| id.into()),
+                        filter: Some(filter.clone().into()),
+                    },
+                ))
+            },
+            |response| match response.into_inner() {
+                workerexecutor::v1::CancelScheduledActionsResponse {
+                    result:
+                        Some(workerexecutor::v1::cancel_scheduled_actions_response::Result::Success(
+                            workerexecutor::v1::PendingScheduledActions { actions, .. },
+                        )),
+                } => Self::pending_scheduled_actions(actions),
+                workerexecutor::v1::CancelScheduledActionsResponse {
+                    result:
+                        Some(workerexecutor::v1::cancel_scheduled_actions_response::Result::Failure(
+                            err,
+                        )),
+                } => Err(err.into()),
+                workerexecutor::v1::CancelScheduledActionsResponse { .. } => {
+                    Err("Empty response".into())
+                }
+            },
+            WorkerServiceError::InternalCallError,
+        )
+        .await
+    }
+
+    async fn list_promises(
+        &self,
+        worker_id: &WorkerId,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Vec<OutstandingPromise>> {
+        Self::authorize_worker(auth_ctx, WorkerAction::ReadMetadata, worker_id)?;
+
+        let worker_id = worker_id.clone();
+        self.call_worker_executor(
+            worker_id.clone(),
+            move |worker_executor_client| {
+                let worker_id = worker_id.clone();
+                Box::pin(worker_executor_client.list_promises(
+                    workerexecutor::v1::ListPromisesRequest {
+                        worker_id: Some(worker_id.into()),
+                        account_id: metadata.account_id.clone().map(|id| id.into()),
+                    },
+                ))
+            },
+            |response| match response.into_inner() {
+                workerexecutor::v1::ListPromisesResponse {
+                    result:
+                        Some(workerexecutor::v1::list_promises_response::Result::Success(
+                            workerexecutor::v1::OutstandingPromises { promises },
+                        )),
+                } => promises
+                    .into_iter()
+                    .map(|promise| promise.try_into())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|err| {
+                        GolemError::Unknown(GolemErrorUnknown {
+                            details: format!("Unexpected promise: {err}"),
+                        })
+                        .into()
+                    }),
+                workerexecutor::v1::ListPromisesResponse {
+                    result: Some(workerexecutor::v1::list_promises_response::Result::Failure(err)),
+                } => Err(err.into()),
+                workerexecutor::v1::ListPromisesResponse { .. } => Err("Empty response".into()),
+            },
+            WorkerServiceError::InternalCallError,
+        )
+        .await
+    }
+
+    async fn get_worker_sagas(
+        &self,
+        worker_id: &WorkerId,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Vec<WorkerSaga>> {
+        Self::authorize_worker(auth_ctx, WorkerAction::ReadMetadata, worker_id)?;
+
+        let worker_id = worker_id.clone();
+        self.call_worker_executor(
+            worker_id.clone(),
+            move |worker_executor_client| {
+                let worker_id = worker_id.clone();
+                Box::pin(worker_executor_client.get_worker_sagas(
+                    workerexecutor::v1::GetWorkerSagasRequest {
+                        worker_id: Some(worker_id.into()),
+                        account_id: metadata.account_id.clone().map(|id| id.into()),
+                    },
+                ))
+            },
+            |response| match response.into_inner() {
+                workerexecutor::v1::GetWorkerSagasResponse {
+                    result:
+                        Some(workerexecutor::v1::get_worker_sagas_response::Result::Success(
+                            workerexecutor::v1::WorkerSagas { sagas },
+                        )),
+                } => sagas
+                    .into_iter()
+                    .map(|saga| saga.try_into())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|err| {
+                        GolemError::Unknown(GolemErrorUnknown {
+                            details: format!("Unexpected saga: {err}"),
+                        })
+                        .into()
+                    }),
+                workerexecutor::v1::GetWorkerSagasResponse {
+                    result:
+                        Some(workerexecutor::v1::get_worker_sagas_response::Result::Failure(err)),
+                } => Err(err.into()),
+                workerexecutor::v1::GetWorkerSagasResponse { .. } => Err("Empty response".into()),
+            },
+            WorkerServiceError::InternalCallError,
+        )
+        .await
+    }
+}
+
+impl<AuthCtx> WorkerServiceDefault<AuthCtx>
+where
+    AuthCtx: HasTokenScope + Send + Sync,
+{
+    fn authorize_worker(
+        auth_ctx: &AuthCtx,
+        action: WorkerAction,
+        worker_id: &WorkerId,
+    ) -> WorkerResult<()> {
+        if auth_ctx.token_scope().allows_worker(action, worker_id) {
+            Ok(())
+        } else {
+            Err(WorkerServiceError::Forbidden(format!(
+                "{action} is not permitted on worker {worker_id}"
+            )))
+        }
+    }
+
+    fn pending_scheduled_actions(
+        actions: Vec<workerexecutor::v1::PendingScheduledAction>,
+    ) -> Result<Vec<PendingScheduledAction>, ResponseMapResult> {
+        actions
+            .into_iter()
+            .map(|action| action.try_into())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| {
+                GolemError::Unknown(GolemErrorUnknown {
+                    details: format!("Unexpected scheduled action: {err}"),
+                })
+                .into()
+            })
+    }
+
+    fn authorize_all_workers(auth_ctx: &AuthCtx, action: WorkerAction) -> WorkerResult<()> {
+        if auth_ctx.token_scope().allows_all_workers(action) {
+            Ok(())
+        } else {
+            Err(WorkerServiceError::Forbidden(format!(
+                "{action} is not permitted on the shared state of all workers"
+            )))
+        }
+    }
+
+    fn authorize_target(
+        auth_ctx: &AuthCtx,
+        action: WorkerAction,
+        worker_id: &TargetWorkerId,
+    ) -> WorkerResult<()> {
+        if auth_ctx.token_scope().allows_target(action, worker_id) {
+            Ok(())
+        } else {
+            Err(WorkerServiceError::Forbidden(format!(
+                "{action} is not permitted on worker {worker_id}"
+            )))
+        }
+    }
+
+    pub(crate) fn authorize_component(
+        auth_ctx: &AuthCtx,
+        action: WorkerAction,
+        component_id: &ComponentId,
+    ) -> WorkerResult<()> {
+        if auth_ctx
+            .token_scope()
+            .allows_component(action, component_id)
+        {
+            Ok(())
+        } else {
+            Err(WorkerServiceError::Forbidden(format!(
+                "{action} is not permitted on the workers of component {component_id}"
+            )))
+        }
+    }
+
+    async fn try_get_component_for_worker(
+        &self,
+        worker_id: &WorkerId,
+        request_metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> Result<Component, WorkerServiceError> {
+        match self
+            .get_metadata(worker_id, request_metadata, auth_ctx)
+            .await
+        {
+            Ok(metadata) => {
+                let component_version = metadata.component_version;
+                let component_details = self
+                    .component_service
+                    .get_by_version(&worker_id.component_id, component_version, auth_ctx)
+                    .await?;
+
+                Ok(component_details)
+            }
+            Err(WorkerServiceError::WorkerNotFound(_)) => Ok(self
+                .component_service
+                .get_latest(&worker_id.component_id, auth_ctx)
+                .await?),
+            Err(WorkerServiceError::Golem(GolemError::WorkerNotFound(_))) => Ok(self
+                .component_service
+                .get_latest(&worker_id.component_id, auth_ctx)
+                .await?),
+            Err(other) => Err(other),
+        }
+    }
+
+    /// Gets the component of the targeted worker, or the latest version of the component if the
+    /// worker does not exist yet or is going to get a generated name
+    async fn try_get_component_for_target(
+        &self,
+        worker_id: &TargetWorkerId,
+        request_metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> Result<Component, WorkerServiceError> {
+        match &worker_id.worker_name {
+            Some(worker_name) => {
+                let worker_id = WorkerId {
+                    component_id: worker_id.component_id.clone(),
+                    worker_name: worker_name.clone(),
+                };
+                self.try_get_component_for_worker(&worker_id, request_metadata, auth_ctx)
+                    .await
+            }
+            None => Ok(self
+                .component_service
+                .get_latest(&worker_id.component_id, auth_ctx)
+                .await?),
+        }
+    }
+
+    /// Gets the metadata of workers owned by the same executor with a single request to it. The
+    /// results are in the order of `worker_ids`.
+    async fn get_metadata_batch(
+        &self,
+        worker_ids: Vec<WorkerId>,
+        metadata: WorkerRequestMetadata,
+    ) -> WorkerResult<Vec<WorkerResult<WorkerMetadata>>> {
+        let Some(first_worker_id) = worker_ids.first().cloned() else {
+            return Ok(vec![]);
+        };
+
+        self.call_worker_executor(
+            first_worker_id,
+            move |worker_executor_client| {
+                info!("Get metadata of {} workers", worker_ids.len());
+                Box::pin(worker_executor_client.get_workers_metadata_bulk(
+                    workerexecutor::v1::GetWorkersMetadataBulkRequest {
+                        worker_ids: worker_ids.iter().cloned().map(|id| id.into()).collect(),
+                        account_id: metadata.account_id.clone().map(|id| id.into()),
+                    },
+                ))
+            },
+            |response| {
+                Ok(response
+                    .into_inner()
+                    .results
+                    .into_iter()
+                    .map(|response| match response.result {
+                        Some(
+                            workerexecutor::v1::get_worker_metadata_response::Result::Success(
+                                metadata,
+                            ),
+                        ) => metadata.try_into().map_err(WorkerServiceError::Internal),
+                        Some(
+                            workerexecutor::v1::get_worker_metadata_response::Result::Failure(err),
+                        ) => Err(GolemError::try_from(err)
+                            .map(WorkerServiceError::Golem)
+                            .unwrap_or_else(WorkerServiceError::Internal)),
+                        None => Err(WorkerServiceError::Internal("Empty response".to_string())),
+                    })
+                    .collect())
+            },
+            WorkerServiceError::InternalCallError,
+        )
+        .await
+    }
+
+    /// Gets the exports of the component invoked through the target, after checking that the
+    /// caller may invoke it
+    async fn invoked_component_exports(
+        &self,
+        worker_id: &TargetWorkerId,
+        request_metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> Result<Vec<AnalysedExport>, WorkerServiceError> {
+        Self::authorize_target(auth_ctx, WorkerAction::Invoke, worker_id)?;
+
+        let component = self
+            .try_get_component_for_target(worker_id, request_metadata, auth_ctx)
+            .await?;
+        Ok(component.metadata.exports)
+    }
+
+    async fn find_running_metadata_internal(
+        &self,
+        component_id: &ComponentId,
+        filter: Option<WorkerFilter>,
+        _auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Vec<WorkerMetadata>> {
+        let component_id = component_id.clone();
+        let result = self.call_worker_executor(
+            AllExecutors,
+            move |worker_executor_client| {
+                let component_id: golem_api_grpc::proto::golem::component::ComponentId =
                     component_id.clone().into();
 
                 Box::pin(
@@ -1052,6 +2645,38 @@ where
     }
 }
 
+/// Whether a worker's request failed because it was sent to an executor not owning the worker
+fn is_misrouted<T>(result: &WorkerResult<T>) -> bool {
+    matches!(
+        result,
+        Err(WorkerServiceError::Golem(
+            GolemError::InvalidShardId(_) | GolemError::ShardingNotReady(_)
+        ))
+    )
+}
+
+fn poll_result(
+    response: workerexecutor::v1::PollWorkerInvocationResponse,
+) -> Result<InvocationPollResult, ResponseMapResult> {
+    match response.result {
+        Some(workerexecutor::v1::poll_worker_invocation_response::Result::Success(
+            workerexecutor::v1::InvokeAndAwaitWorkerSuccessTyped {
+                output: Some(output),
+            },
+        )) => output
+            .type_annotated_value
+            .map(InvocationPollResult::Completed)
+            .ok_or("Empty response".into()),
+        Some(workerexecutor::v1::poll_worker_invocation_response::Result::Pending(
+            idempotency_key,
+        )) => Ok(InvocationPollResult::Pending(idempotency_key.into())),
+        Some(workerexecutor::v1::poll_worker_invocation_response::Result::Failure(err)) => {
+            Err(err.into())
+        }
+        _ => Err("Empty response".into()),
+    }
+}
+
 fn is_filter_with_running_status(filter: &WorkerFilter) -> bool {
     match filter {
         WorkerFilter::Status(f)
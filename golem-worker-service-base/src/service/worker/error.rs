@@ -15,6 +15,7 @@
 use golem_api_grpc::proto::golem::worker::v1::{
     worker_error, worker_execution_error, UnknownError, WorkerError as GrpcWorkerError,
 };
+use golem_common::model::error::{ErrorCode, ProblemDetails};
 use golem_common::model::{AccountId, ComponentId, WorkerId};
 use golem_common::SafeDisplay;
 use golem_service_base::model::{GolemError, VersionedComponentId};
@@ -28,6 +29,8 @@ pub enum WorkerServiceError {
     Component(#[from] ComponentServiceError),
     #[error("Type checker error: {0}")]
     TypeChecker(String),
+    #[error("Invalid parameters: {}", .0.join("; "))]
+    InvalidParameters(Vec<String>),
     #[error("Component not found: {0}")]
     VersionedComponentIdNotFound(VersionedComponentId),
     #[error("Component not found: {0}")]
@@ -36,6 +39,8 @@ pub enum WorkerServiceError {
     AccountIdNotFound(AccountId),
     #[error("Worker not found: {0}")]
     WorkerNotFound(WorkerId),
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
     #[error("Internal error: {0}")]
     Internal(String),
     #[error(transparent)]
@@ -49,10 +54,12 @@ impl SafeDisplay for WorkerServiceError {
         match self {
             WorkerServiceError::Component(inner) => inner.to_safe_string(),
             WorkerServiceError::TypeChecker(_) => self.to_string(),
+            WorkerServiceError::InvalidParameters(_) => self.to_string(),
             WorkerServiceError::VersionedComponentIdNotFound(_) => self.to_string(),
             WorkerServiceError::ComponentNotFound(_) => self.to_string(),
             WorkerServiceError::AccountIdNotFound(_) => self.to_string(),
             WorkerServiceError::WorkerNotFound(_) => self.to_string(),
+            WorkerServiceError::Forbidden(_) => self.to_string(),
             WorkerServiceError::Internal(_) => self.to_string(),
             WorkerServiceError::Golem(inner) => inner.to_safe_string(),
             WorkerServiceError::InternalCallError(inner) => inner.to_safe_string(),
@@ -60,6 +67,33 @@ impl SafeDisplay for WorkerServiceError {
     }
 }
 
+impl WorkerServiceError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            WorkerServiceError::Component(inner) => inner.code(),
+            WorkerServiceError::TypeChecker(_) | WorkerServiceError::InvalidParameters(_) => {
+                ErrorCode::InvalidRequest
+            }
+            WorkerServiceError::VersionedComponentIdNotFound(_)
+            | WorkerServiceError::ComponentNotFound(_)
+            | WorkerServiceError::AccountIdNotFound(_)
+            | WorkerServiceError::WorkerNotFound(_) => ErrorCode::NotFound,
+            WorkerServiceError::Forbidden(_) => ErrorCode::Forbidden,
+            WorkerServiceError::Internal(_) | WorkerServiceError::InternalCallError(_) => {
+                ErrorCode::Internal
+            }
+            WorkerServiceError::Golem(inner) => inner.code(),
+        }
+    }
+
+    pub fn to_problem_details(&self) -> ProblemDetails {
+        match self {
+            WorkerServiceError::Golem(inner) => inner.to_problem_details(),
+            _ => ProblemDetails::new(self.code(), self.to_safe_string()),
+        }
+    }
+}
+
 impl From<WorkerServiceError> for GrpcWorkerError {
     fn from(error: WorkerServiceError) -> Self {
         GrpcWorkerError {
@@ -80,6 +114,9 @@ impl From<WorkerServiceError> for worker_error::Error {
             | WorkerServiceError::WorkerNotFound(_)) => worker_error::Error::NotFound(ErrorBody {
                 error: error.to_safe_string(),
             }),
+            WorkerServiceError::Forbidden(_) => worker_error::Error::Forbidden(ErrorBody {
+                error: error.to_safe_string(),
+            }),
             WorkerServiceError::Internal(_) => {
                 worker_error::Error::InternalError(WorkerExecutionError {
                     error: Some(worker_execution_error::Error::Unknown(UnknownError {
@@ -97,6 +134,9 @@ impl From<WorkerServiceError> for worker_error::Error {
             WorkerServiceError::TypeChecker(error) => worker_error::Error::BadRequest(ErrorsBody {
                 errors: vec![error],
             }),
+            WorkerServiceError::InvalidParameters(errors) => {
+                worker_error::Error::BadRequest(ErrorsBody { errors })
+            }
             WorkerServiceError::Component(component) => component.into(),
             WorkerServiceError::Golem(worker_execution_error) => {
                 worker_error::Error::InternalError(worker_execution_error.into())
@@ -110,3 +150,18 @@ impl From<GolemError> for WorkerServiceError {
         WorkerServiceError::Golem(value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use super::*;
+
+    #[test]
+    fn forbidden_worker_actions_are_not_reported_as_unauthorized() {
+        let error: worker_error::Error =
+            WorkerServiceError::Forbidden("missing permission".to_string()).into();
+
+        assert!(matches!(error, worker_error::Error::Forbidden(_)));
+    }
+}
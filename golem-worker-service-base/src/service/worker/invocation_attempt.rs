@@ -0,0 +1,274 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use golem_common::model::{IdempotencyKey, TargetWorkerId, WorkerName};
+use tracing::info;
+
+use crate::repo::invocation_attempt::{InvocationAttemptRecord, InvocationAttemptRepo};
+use crate::service::worker::{WorkerResult, WorkerServiceError};
+
+// An invocation about to be forwarded to an executor
+#[derive(Clone, Debug, PartialEq)]
+pub struct InvocationAttempt {
+    pub worker_id: TargetWorkerId,
+    pub idempotency_key: IdempotencyKey,
+    // The invocation was already forwarded by an earlier request with the same idempotency key
+    pub repeated: bool,
+}
+
+// Attempts of invocations with an idempotency key given by the client are persisted before
+// they are forwarded to an executor. A request repeated with the same key, such as a client
+// retrying after a lost response, is sent to the same worker, even if the worker service
+// generated its name, and is not enqueued again.
+// Invocations without a key get a fresh one, which is only reused by the retries of the
+// worker service itself, so they are not persisted.
+pub struct InvocationAttempts {
+    repo: Arc<dyn InvocationAttemptRepo + Send + Sync>,
+}
+
+impl InvocationAttempts {
+    pub fn new(repo: Arc<dyn InvocationAttemptRepo + Send + Sync>) -> Self {
+        Self { repo }
+    }
+
+    // The worker may be placed by the worker service, generating a name for a target without
+    // one. Repeated requests are sent to the worker placed by the first one
+    pub async fn start(
+        &self,
+        requested_worker_id: &TargetWorkerId,
+        worker_id: TargetWorkerId,
+        idempotency_key: Option<IdempotencyKey>,
+        function_name: &str,
+    ) -> WorkerResult<InvocationAttempt> {
+        let idempotency_key = match idempotency_key {
+            Some(idempotency_key) => idempotency_key,
+            None => {
+                return Ok(InvocationAttempt {
+                    worker_id,
+                    idempotency_key: IdempotencyKey::fresh(),
+                    repeated: false,
+                })
+            }
+        };
+
+        let record = InvocationAttemptRecord {
+            idempotency_key: idempotency_key.value.clone(),
+            component_id: worker_id.component_id.to_string(),
+            worker_name: worker_id.worker_name.clone().map(WorkerName::into_string),
+            function_name: function_name.to_string(),
+        };
+
+        let existing = self
+            .repo
+            .create_or_get(&record, chrono::Utc::now())
+            .await
+            .map_err(|err| WorkerServiceError::Internal(err.to_string()))?;
+
+        match existing {
+            None => Ok(InvocationAttempt {
+                worker_id,
+                idempotency_key,
+                repeated: false,
+            }),
+            Some(existing)
+                if existing.component_id == record.component_id
+                    && existing.function_name == record.function_name
+                    && (requested_worker_id.worker_name.is_none()
+                        || existing.worker_name.as_deref()
+                            == requested_worker_id.worker_name.as_deref()) =>
+            {
+                info!("Invocation with idempotency key {idempotency_key} was already started");
+                Ok(InvocationAttempt {
+                    worker_id: TargetWorkerId {
+                        component_id: worker_id.component_id,
+                        worker_name: existing.worker_name.map(WorkerName::from_existing),
+                    },
+                    idempotency_key,
+                    repeated: true,
+                })
+            }
+            Some(_) => Err(WorkerServiceError::InvalidParameters(vec![format!(
+                "Idempotency key {idempotency_key} was already used for a different invocation"
+            )])),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    use async_trait::async_trait;
+    use golem_common::model::{ComponentId, IdempotencyKey, TargetWorkerId, WorkerName};
+    use golem_service_base::repo::RepoError;
+    use uuid::Uuid;
+
+    use crate::repo::invocation_attempt::{InvocationAttemptRecord, InvocationAttemptRepo};
+    use crate::service::worker::invocation_attempt::InvocationAttempts;
+    use crate::service::worker::WorkerServiceError;
+
+    #[derive(Default)]
+    struct TestInvocationAttemptRepo {
+        attempts: Mutex<HashMap<String, InvocationAttemptRecord>>,
+    }
+
+    #[async_trait]
+    impl InvocationAttemptRepo for TestInvocationAttemptRepo {
+        async fn create_or_get(
+            &self,
+            attempt: &InvocationAttemptRecord,
+            _created_at: chrono::DateTime<chrono::Utc>,
+        ) -> Result<Option<InvocationAttemptRecord>, RepoError> {
+            let mut attempts = self.attempts.lock().unwrap();
+            match attempts.get(&attempt.idempotency_key) {
+                Some(existing) => Ok(Some(existing.clone())),
+                None => {
+                    attempts.insert(attempt.idempotency_key.clone(), attempt.clone());
+                    Ok(None)
+                }
+            }
+        }
+    }
+
+    fn attempts() -> InvocationAttempts {
+        InvocationAttempts::new(Arc::new(TestInvocationAttemptRepo::default()))
+    }
+
+    fn target(component_id: &ComponentId, worker_name: Option<&str>) -> TargetWorkerId {
+        TargetWorkerId {
+            component_id: component_id.clone(),
+            worker_name: worker_name.map(WorkerName::from_existing),
+        }
+    }
+
+    #[test]
+    async fn repeated_request_is_invoked_once() {
+        let attempts = attempts();
+        let component_id = ComponentId(Uuid::new_v4());
+        let key = IdempotencyKey::fresh();
+        let mut invocations = 0;
+
+        for _ in 0..2 {
+            let attempt = attempts
+                .start(
+                    &target(&component_id, Some("w1")),
+                    target(&component_id, Some("w1")),
+                    Some(key.clone()),
+                    "f",
+                )
+                .await
+                .unwrap();
+            assert_eq!(attempt.idempotency_key, key);
+            if !attempt.repeated {
+                invocations += 1;
+            }
+        }
+
+        assert_eq!(invocations, 1);
+    }
+
+    #[test]
+    async fn repeated_request_targets_the_generated_worker_of_the_first_one() {
+        let attempts = attempts();
+        let component_id = ComponentId(Uuid::new_v4());
+        let key = IdempotencyKey::fresh();
+
+        let first = attempts
+            .start(
+                &target(&component_id, None),
+                target(&component_id, Some("generated-1")),
+                Some(key.clone()),
+                "f",
+            )
+            .await
+            .unwrap();
+        let second = attempts
+            .start(
+                &target(&component_id, None),
+                target(&component_id, Some("generated-2")),
+                Some(key.clone()),
+                "f",
+            )
+            .await
+            .unwrap();
+
+        assert!(!first.repeated);
+        assert!(second.repeated);
+        assert_eq!(second.worker_id, target(&component_id, Some("generated-1")));
+    }
+
+    #[test]
+    async fn requests_without_a_key_are_always_invoked() {
+        let attempts = attempts();
+        let component_id = ComponentId(Uuid::new_v4());
+
+        let first = attempts
+            .start(
+                &target(&component_id, Some("w1")),
+                target(&component_id, Some("w1")),
+                None,
+                "f",
+            )
+            .await
+            .unwrap();
+        let second = attempts
+            .start(
+                &target(&component_id, Some("w1")),
+                target(&component_id, Some("w1")),
+                None,
+                "f",
+            )
+            .await
+            .unwrap();
+
+        assert!(!first.repeated);
+        assert!(!second.repeated);
+        assert_ne!(first.idempotency_key, second.idempotency_key);
+    }
+
+    #[test]
+    async fn key_reused_for_a_different_invocation_is_rejected() {
+        let attempts = attempts();
+        let component_id = ComponentId(Uuid::new_v4());
+        let key = IdempotencyKey::fresh();
+
+        attempts
+            .start(
+                &target(&component_id, Some("w1")),
+                target(&component_id, Some("w1")),
+                Some(key.clone()),
+                "f",
+            )
+            .await
+            .unwrap();
+        let result = attempts
+            .start(
+                &target(&component_id, Some("w1")),
+                target(&component_id, Some("w1")),
+                Some(key.clone()),
+                "g",
+            )
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(WorkerServiceError::InvalidParameters(_))
+        ));
+    }
+}
@@ -0,0 +1,88 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use golem_wasm_ast::analysis::AnalysedExport;
+use golem_wasm_rpc::json::TypeAnnotatedValueJsonExtensions;
+use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
+
+use super::parameters::convert_parameters;
+use super::WorkerResult;
+
+/// Converts the plain JSON parameters of an invocation to `TypeAnnotatedValue`s, using the
+/// parameter types of the function exported by the component.
+///
+/// Every parameter is converted even if an earlier one failed, so the returned
+/// `InvalidParameters` error lists all the mismatching parameters.
+pub fn convert_json_parameters(
+    exports: &Vec<AnalysedExport>,
+    function_name: &str,
+    params: Vec<serde_json::Value>,
+) -> WorkerResult<Vec<TypeAnnotatedValue>> {
+    convert_parameters(exports, function_name, params, |param, typ| {
+        TypeAnnotatedValue::parse_with_type(param, typ).map_err(|errs| errs.join(", "))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use serde_json::json;
+
+    use crate::service::worker::json_parameters::convert_json_parameters;
+    use crate::service::worker::parameters::add_item_exports as exports;
+    use crate::service::worker::WorkerServiceError;
+
+    #[test]
+    fn json_parameters_are_converted() {
+        let values = convert_json_parameters(
+            &exports(),
+            "golem:it/api.{add-item}",
+            vec![json!("cart"), json!({"id": 1, "tags": ["a", "b"]})],
+        )
+        .unwrap();
+        assert_eq!(values.len(), 2);
+    }
+
+    #[test]
+    fn mismatching_parameters_are_reported_one_by_one() {
+        let result = convert_json_parameters(
+            &exports(),
+            "golem:it/api.{add-item}",
+            vec![json!(1), json!({"id": "x", "tags": []})],
+        );
+        let Err(WorkerServiceError::InvalidParameters(errors)) = result else {
+            panic!("expected invalid parameters, got {result:?}");
+        };
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].starts_with("Parameter 1 (name): "));
+        assert!(errors[1].starts_with("Parameter 2 (item): "));
+    }
+
+    #[test]
+    fn unexpected_number_of_parameters_is_rejected() {
+        let result = convert_json_parameters(&exports(), "golem:it/api.{add-item}", vec![]);
+        assert!(matches!(
+            result,
+            Err(WorkerServiceError::InvalidParameters(errors))
+                if errors == vec!["Unexpected number of parameters for golem:it/api.{add-item}: got 0, expected 2".to_string()]
+        ));
+    }
+
+    #[test]
+    fn unknown_functions_are_rejected() {
+        let result = convert_json_parameters(&exports(), "golem:it/api.{remove-item}", vec![]);
+        assert!(matches!(result, Err(WorkerServiceError::TypeChecker(_))));
+    }
+}
@@ -16,10 +16,19 @@ pub use connect_proxy::*;
 pub use connect_stream::*;
 pub use default::*;
 pub use error::*;
+pub use invocation_attempt::*;
+pub use json_parameters::*;
 pub use routing_logic::*;
+pub use update_rollout::*;
+pub use wave_parameters::*;
 
 mod connect_proxy;
 mod connect_stream;
 mod default;
 mod error;
+mod invocation_attempt;
+mod json_parameters;
+mod parameters;
 mod routing_logic;
+mod update_rollout;
+mod wave_parameters;
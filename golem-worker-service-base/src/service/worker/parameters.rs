@@ -0,0 +1,114 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Conversion of invocation parameters given in a textual format, shared by the JSON and the
+//! WAVE parameter formats.
+
+use golem_common::model::exports::function_by_name;
+use golem_wasm_ast::analysis::{AnalysedExport, AnalysedFunctionParameter, AnalysedType};
+use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
+use rib::ParsedFunctionName;
+
+use super::{WorkerResult, WorkerServiceError};
+
+/// Converts the parameters of an invocation with `parse`, using the parameter types of the
+/// function exported by the component.
+///
+/// Every parameter is converted even if an earlier one failed, so the returned
+/// `InvalidParameters` error lists all the mismatching parameters.
+pub(crate) fn convert_parameters<P>(
+    exports: &Vec<AnalysedExport>,
+    function_name: &str,
+    params: Vec<P>,
+    parse: impl Fn(&P, &AnalysedType) -> Result<TypeAnnotatedValue, String>,
+) -> WorkerResult<Vec<TypeAnnotatedValue>> {
+    let parameters = function_parameters(exports, function_name)?;
+
+    if parameters.len() != params.len() {
+        return Err(WorkerServiceError::InvalidParameters(vec![format!(
+            "Unexpected number of parameters for {function_name}: got {}, expected {}",
+            params.len(),
+            parameters.len()
+        )]));
+    }
+
+    let mut values = Vec::new();
+    let mut errors = Vec::new();
+    for (index, (param, parameter)) in params.iter().zip(parameters).enumerate() {
+        match parse(param, &parameter.typ) {
+            Ok(value) => values.push(value),
+            Err(err) => errors.push(format!(
+                "Parameter {} ({}): {err}",
+                index + 1,
+                parameter.name
+            )),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(values)
+    } else {
+        Err(WorkerServiceError::InvalidParameters(errors))
+    }
+}
+
+/// Gets the parameters of the exported function which are passed by the caller. The resource
+/// handle of indexed resource methods is not passed, as it is resolved by the executor.
+fn function_parameters(
+    exports: &Vec<AnalysedExport>,
+    function_name: &str,
+) -> WorkerResult<Vec<AnalysedFunctionParameter>> {
+    let parsed = ParsedFunctionName::parse(function_name).map_err(|err| {
+        WorkerServiceError::TypeChecker(format!("Invalid function name {function_name}: {err}"))
+    })?;
+    let function = function_by_name(exports, function_name)
+        .map_err(WorkerServiceError::TypeChecker)?
+        .ok_or_else(|| {
+            WorkerServiceError::TypeChecker(format!(
+                "Function {function_name} is not exported by the component"
+            ))
+        })?;
+
+    if parsed.function().is_indexed_resource() {
+        Ok(function.parameters.into_iter().skip(1).collect())
+    } else {
+        Ok(function.parameters)
+    }
+}
+
+/// Exports of a component with a single `golem:it/api.{add-item}` function, taking a string
+/// and a record parameter
+#[cfg(test)]
+pub(crate) fn add_item_exports() -> Vec<AnalysedExport> {
+    use golem_wasm_ast::analysis::analysed_type::{field, list, record, str, u32};
+    use golem_wasm_ast::analysis::{AnalysedFunction, AnalysedInstance};
+
+    vec![AnalysedExport::Instance(AnalysedInstance {
+        name: "golem:it/api".to_string(),
+        functions: vec![AnalysedFunction {
+            name: "add-item".to_string(),
+            parameters: vec![
+                AnalysedFunctionParameter {
+                    name: "name".to_string(),
+                    typ: str(),
+                },
+                AnalysedFunctionParameter {
+                    name: "item".to_string(),
+                    typ: record(vec![field("id", u32()), field("tags", list(str()))]),
+                },
+            ],
+            results: vec![],
+        }],
+    })]
+}
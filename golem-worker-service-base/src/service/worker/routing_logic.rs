@@ -69,6 +69,7 @@ pub trait CallOnExecutor<Out: Send + 'static> {
     async fn call_on_worker_executor<F>(
         &self,
         context: &(impl HasRoutingTableService + HasWorkerExecutorClients + Send + Sync),
+        busy_executors: &HashSet<Pod>,
         f: F,
     ) -> Result<(Option<Self::ResultOut>, Option<Pod>), CallWorkerExecutorErrorWithContext>
     where
@@ -82,6 +83,13 @@ pub trait CallOnExecutor<Out: Send + 'static> {
             + 'static;
 
     fn tracing_kind(&self) -> &'static str;
+
+    /// Whether the call can be served by any executor, in which case it is retried on another
+    /// one if the chosen executor is too busy to start the worker. These calls avoid the
+    /// executors in `busy_executors` when there are others to choose from.
+    fn can_use_any_executor(&self) -> bool {
+        false
+    }
 }
 
 #[async_trait]
@@ -91,6 +99,7 @@ impl<Out: Send + 'static> CallOnExecutor<Out> for WorkerId {
     async fn call_on_worker_executor<F>(
         &self,
         context: &(impl HasRoutingTableService + HasWorkerExecutorClients + Send + Sync),
+        _busy_executors: &HashSet<Pod>,
         f: F,
     ) -> Result<(Option<Self::ResultOut>, Option<Pod>), CallWorkerExecutorErrorWithContext>
     where
@@ -141,6 +150,7 @@ impl<Out: Send + 'static> CallOnExecutor<Out> for TargetWorkerId {
     async fn call_on_worker_executor<F>(
         &self,
         context: &(impl HasRoutingTableService + HasWorkerExecutorClients + Send + Sync),
+        busy_executors: &HashSet<Pod>,
         f: F,
     ) -> Result<(Option<Self::ResultOut>, Option<Pod>), CallWorkerExecutorErrorWithContext>
     where
@@ -155,11 +165,15 @@ impl<Out: Send + 'static> CallOnExecutor<Out> for TargetWorkerId {
     {
         if let Some(worker_id) = self.clone().try_into_worker_id() {
             // The TargetWorkerId had a worker name so we know which shard we need to call it on
-            worker_id.call_on_worker_executor(context, f).await
+            worker_id
+                .call_on_worker_executor(context, busy_executors, f)
+                .await
         } else {
             // The TargetWorkerId did not have a worker name specified so we can forward the call to a random
             // executor
-            RandomExecutor.call_on_worker_executor(context, f).await
+            RandomExecutor
+                .call_on_worker_executor(context, busy_executors, f)
+                .await
         }
     }
 
@@ -170,6 +184,96 @@ impl<Out: Send + 'static> CallOnExecutor<Out> for TargetWorkerId {
             "WorkerId"
         }
     }
+
+    fn can_use_any_executor(&self) -> bool {
+        self.worker_name.is_none()
+    }
+}
+
+/// Routes a read-only worker operation to a read replica when read routing is enabled.
+///
+/// Any executor can serve these operations from storage, so they are spread across the
+/// non-owner pods of a routing table no older than the configured staleness bound. Falls back
+/// to the shard owner if read routing is disabled or no fresh routing table is available.
+pub struct ReadReplica(pub WorkerId);
+
+#[async_trait]
+impl<Out: Send + 'static> CallOnExecutor<Out> for ReadReplica {
+    type ResultOut = Out;
+
+    async fn call_on_worker_executor<F>(
+        &self,
+        context: &(impl HasRoutingTableService + HasWorkerExecutorClients + Send + Sync),
+        busy_executors: &HashSet<Pod>,
+        f: F,
+    ) -> Result<(Option<Self::ResultOut>, Option<Pod>), CallWorkerExecutorErrorWithContext>
+    where
+        F: for<'a> Fn(
+                &'a mut WorkerExecutorClient<Channel>,
+            )
+                -> Pin<Box<dyn Future<Output = Result<Out, Status>> + 'a + Send>>
+            + Send
+            + Sync
+            + Clone
+            + 'static,
+    {
+        let Some(max_staleness) = context.routing_table_service().read_max_staleness() else {
+            return self
+                .0
+                .call_on_worker_executor(context, busy_executors, f)
+                .await;
+        };
+
+        let mut routing_table = context
+            .routing_table_service()
+            .get_routing_table()
+            .await
+            .map_err(CallWorkerExecutorErrorWithContext::failed_to_get_routing_table)?;
+
+        if routing_table.age() > max_staleness
+            && context
+                .routing_table_service()
+                .try_invalidate_routing_table()
+                .await
+        {
+            routing_table = context
+                .routing_table_service()
+                .get_routing_table()
+                .await
+                .map_err(CallWorkerExecutorErrorWithContext::failed_to_get_routing_table)?;
+        }
+
+        let pod = match context.routing_table_service().zone() {
+            Some(zone) => routing_table.lookup_read_in_zone(&self.0, max_staleness, zone),
+            None => routing_table.lookup_read(&self.0, max_staleness),
+        };
+        match pod {
+            None => {
+                self.0
+                    .call_on_worker_executor(context, busy_executors, f)
+                    .await
+            }
+            Some(pod) => Ok((
+                Some(
+                    context
+                        .worker_executor_clients()
+                        .call(pod.uri_02(), f)
+                        .await
+                        .map_err(|err| {
+                            CallWorkerExecutorErrorWithContext::failed_to_connect_to_pod(
+                                err,
+                                pod.clone(),
+                            )
+                        })?,
+                ),
+                Some(pod.clone()),
+            )),
+        }
+    }
+
+    fn tracing_kind(&self) -> &'static str {
+        "ReadReplica"
+    }
 }
 
 pub struct RandomExecutor;
@@ -181,6 +285,7 @@ impl<Out: Send + 'static> CallOnExecutor<Out> for RandomExecutor {
     async fn call_on_worker_executor<F>(
         &self,
         context: &(impl HasRoutingTableService + HasWorkerExecutorClients + Send + Sync),
+        busy_executors: &HashSet<Pod>,
         f: F,
     ) -> Result<(Option<Self::ResultOut>, Option<Pod>), CallWorkerExecutorErrorWithContext>
     where
@@ -199,7 +304,7 @@ impl<Out: Send + 'static> CallOnExecutor<Out> for RandomExecutor {
             .await
             .map_err(CallWorkerExecutorErrorWithContext::failed_to_get_routing_table)?;
 
-        match routing_table.random() {
+        match routing_table.random_except(busy_executors) {
             None => Ok((None, None)),
             Some(pod) => Ok((
                 Some(
@@ -222,6 +327,10 @@ impl<Out: Send + 'static> CallOnExecutor<Out> for RandomExecutor {
     fn tracing_kind(&self) -> &'static str {
         "RandomExecutor"
     }
+
+    fn can_use_any_executor(&self) -> bool {
+        true
+    }
 }
 
 pub struct AllExecutors;
@@ -233,6 +342,7 @@ impl<Out: Send + 'static> CallOnExecutor<Out> for AllExecutors {
     async fn call_on_worker_executor<F>(
         &self,
         context: &(impl HasRoutingTableService + HasWorkerExecutorClients + Send + Sync),
+        _busy_executors: &HashSet<Pod>,
         f: F,
     ) -> Result<(Option<Self::ResultOut>, Option<Pod>), CallWorkerExecutorErrorWithContext>
     where
@@ -301,6 +411,8 @@ pub enum ResponseMapResult {
         shard_ids: HashSet<ShardId>,
     },
     ShardingNotReady,
+    /// The executor has too many active workers to start another one
+    ExecutorBusy(WorkerServiceError),
     Other(WorkerServiceError),
 }
 
@@ -315,6 +427,7 @@ impl From<GolemError> for ResponseMapResult {
                 shard_ids,
             },
             GolemError::ShardingNotReady(_) => ResponseMapResult::ShardingNotReady,
+            error @ GolemError::ExecutorBusy(_) => ResponseMapResult::ExecutorBusy(error.into()),
             other => ResponseMapResult::Other(other.into()),
         }
     }
@@ -369,11 +482,12 @@ impl<T: HasRoutingTableService + HasWorkerExecutorClients + Send + Sync> Routing
         H: Fn(CallWorkerExecutorError) -> WorkerServiceError + Send + Sync,
     {
         let mut retry = RetryState::new(self.worker_executor_retry_config());
+        let mut busy_executors = HashSet::new();
         loop {
             let span = retry.start_attempt(Target::tracing_kind(&target));
 
             let worker_result = target
-                .call_on_worker_executor(self, remote_call.clone())
+                .call_on_worker_executor(self, &busy_executors, remote_call.clone())
                 .await;
 
             let result = async {
@@ -391,6 +505,14 @@ impl<T: HasRoutingTableService + HasWorkerExecutorClients + Send + Sync> Routing
                             Err(error @ ResponseMapResult::ShardingNotReady) => {
                                 retry.retry(self, &error, &pod).await
                             }
+                            Err(ResponseMapResult::ExecutorBusy(error)) => {
+                                if target.can_use_any_executor() {
+                                    busy_executors.extend(pod.clone());
+                                    retry.retry_on_other_executor(error, &pod).await
+                                } else {
+                                    retry.non_retryable_error(error, &pod)
+                                }
+                            }
                             Err(ResponseMapResult::Other(error)) => {
                                 retry.non_retryable_error(error, &pod)
                             }
@@ -495,7 +617,7 @@ impl<'a> RetryState<'a> {
         RetrySpan::new(executor_kind, self.attempt)
     }
 
-    async fn retry<T: HasRoutingTableService, U>(
+    async fn retry<T: HasRoutingTableService + HasWorkerExecutorClients, U>(
         &mut self,
         context: &T,
         error: &impl Debug,
@@ -506,6 +628,16 @@ impl<'a> RetryState<'a> {
             .try_invalidate_routing_table()
             .await;
 
+        if invalidated {
+            // Dropping the pooled connections of executors which are not part of the new routing table
+            if let Ok(routing_table) = context.routing_table_service().get_routing_table().await {
+                let endpoints = routing_table.all().iter().map(|pod| pod.uri_02()).collect();
+                context
+                    .worker_executor_clients()
+                    .retain_endpoints(&endpoints);
+            }
+        }
+
         match get_delay(self.retry_config, self.retry_attempt) {
             Some(delay) => {
                 info!(
@@ -533,6 +665,29 @@ impl<'a> RetryState<'a> {
         }
     }
 
+    /// Retries a call which can be served by any executor after the chosen one turned out to be
+    /// busy. The routing table is still valid, so it is not invalidated, and the call fails with
+    /// the busy error once the retry attempts are exhausted.
+    async fn retry_on_other_executor<U>(
+        &mut self,
+        error: WorkerServiceError,
+        pod: &Option<Pod>,
+    ) -> Result<Option<U>, WorkerServiceError> {
+        match get_delay(self.retry_config, self.retry_attempt) {
+            Some(delay) => {
+                info!(
+                    error = error.to_string(),
+                    pod = format_pod(pod),
+                    delay_ms = delay.as_millis(),
+                    "Call on executor - executor busy - retry"
+                );
+                sleep(delay).await;
+                Ok(None)
+            }
+            None => self.non_retryable_error(error, pod),
+        }
+    }
+
     fn non_retryable_error<T>(
         &self,
         error: WorkerServiceError,
@@ -0,0 +1,589 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use futures::future::join_all;
+use futures::{stream, StreamExt};
+use golem_common::model::auth::{HasTokenScope, WorkerAction};
+use golem_common::model::{
+    AccountId, ComponentId, ComponentVersion, FilterComparator, ScanCursor, WorkerFilter, WorkerId,
+};
+use golem_service_base::model::{
+    ComponentUpdatePolicy, FailedUpdate, UpdateRecord, UpdateRolloutFailure, UpdateRolloutStatus,
+    WorkerMetadata, WorkerUpdateMode,
+};
+use golem_service_base::routing_table::RoutingTableService;
+use tracing::{error, info, warn, Instrument};
+
+use crate::repo::update_rollout::{UpdateRolloutRecord, UpdateRolloutRepo};
+use crate::service::component::ComponentService;
+
+use super::{
+    WorkerRequestMetadata, WorkerResult, WorkerService, WorkerServiceDefault, WorkerServiceError,
+};
+
+const ROLLOUT_PAGE_SIZE: u64 = 100;
+const UPDATE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Time after which a worker still having a pending update no longer holds a slot of its executor
+const UPDATE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Updates the existing workers of a component to a newly uploaded version, as configured
+/// by the component's update policy
+#[async_trait]
+pub trait UpdateRolloutService<AuthCtx> {
+    async fn get_update_policy(
+        &self,
+        component_id: &ComponentId,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<ComponentUpdatePolicy>;
+
+    async fn update_update_policy(
+        &self,
+        component_id: &ComponentId,
+        policy: ComponentUpdatePolicy,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<ComponentUpdatePolicy>;
+
+    /// Enqueues the updates of the outdated workers matching the policy's filter in the
+    /// background, keeping at most the policy's limit of updates in progress on each executor.
+    /// The rollout is stored until it finishes, so it is resumed if the service restarts.
+    async fn start_rollout(
+        &self,
+        component_id: &ComponentId,
+        target_version: ComponentVersion,
+        policy: ComponentUpdatePolicy,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<()>;
+
+    /// Aggregates the update records of all the workers of the component
+    async fn get_rollout_status(
+        &self,
+        component_id: &ComponentId,
+        target_version: ComponentVersion,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<UpdateRolloutStatus>;
+
+    /// Restarts the stored rollouts which did not finish before the service stopped
+    async fn resume_rollouts(&self, auth_ctx: &AuthCtx) -> WorkerResult<()>;
+}
+
+pub struct UpdateRolloutServiceDefault<AuthCtx> {
+    worker_service: Arc<dyn WorkerService<AuthCtx> + Send + Sync>,
+    component_service: Arc<dyn ComponentService<AuthCtx> + Send + Sync>,
+    routing_table_service: Arc<dyn RoutingTableService + Send + Sync>,
+    update_rollout_repo: Arc<dyn UpdateRolloutRepo + Send + Sync>,
+}
+
+impl<AuthCtx> UpdateRolloutServiceDefault<AuthCtx> {
+    pub fn new(
+        worker_service: Arc<dyn WorkerService<AuthCtx> + Send + Sync>,
+        component_service: Arc<dyn ComponentService<AuthCtx> + Send + Sync>,
+        routing_table_service: Arc<dyn RoutingTableService + Send + Sync>,
+        update_rollout_repo: Arc<dyn UpdateRolloutRepo + Send + Sync>,
+    ) -> Self {
+        Self {
+            worker_service,
+            component_service,
+            routing_table_service,
+            update_rollout_repo,
+        }
+    }
+}
+
+impl<AuthCtx> UpdateRolloutServiceDefault<AuthCtx>
+where
+    AuthCtx: HasTokenScope + Send + Sync,
+{
+    async fn find_workers(
+        &self,
+        component_id: &ComponentId,
+        filter: Option<WorkerFilter>,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Vec<WorkerMetadata>> {
+        let mut workers = Vec::new();
+        let mut cursor = Some(ScanCursor::default());
+        while let Some(current) = cursor {
+            let (next, page) = self
+                .worker_service
+                .find_metadata(
+                    component_id,
+                    filter.clone(),
+                    current,
+                    ROLLOUT_PAGE_SIZE,
+                    true,
+                    metadata.clone(),
+                    auth_ctx,
+                )
+                .await?;
+            workers.extend(page);
+            cursor = next;
+        }
+        Ok(workers)
+    }
+
+    /// Workers which already have a pending or failed update to the target version are skipped,
+    /// so resuming an interrupted rollout does not enqueue their updates again
+    async fn run_rollout(
+        &self,
+        component_id: &ComponentId,
+        target_version: ComponentVersion,
+        policy: ComponentUpdatePolicy,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<()>
+    where
+        AuthCtx: Clone + 'static,
+    {
+        let outdated = WorkerFilter::new_version(FilterComparator::Less, target_version);
+        let filter = match policy.filter {
+            Some(filter) => outdated.and(filter),
+            None => outdated,
+        };
+        let workers = self
+            .find_workers(component_id, Some(filter), metadata.clone(), auth_ctx)
+            .await?;
+
+        // Workers are grouped by the executor owning their shard, so the limit of concurrent
+        // updates applies to each executor separately
+        let routing_table = self.routing_table_service.get_routing_table().await.ok();
+        let mut workers_by_executor = HashMap::new();
+        for worker in workers {
+            if !matches!(
+                worker_update_state(&worker, target_version),
+                WorkerUpdateState::Outdated
+            ) {
+                continue;
+            }
+            let pod = routing_table
+                .as_ref()
+                .and_then(|routing_table| routing_table.lookup(&worker.worker_id))
+                .cloned();
+            workers_by_executor
+                .entry(pod)
+                .or_insert_with(Vec::new)
+                .push(worker.worker_id);
+        }
+
+        info!(
+            component_id = component_id.to_string(),
+            target_version,
+            executors = workers_by_executor.len(),
+            "Starting worker update rollout"
+        );
+
+        let max_concurrent_updates = policy.max_concurrent_updates_per_executor.max(1) as usize;
+        let mode = policy.mode;
+        let worker_service = self.worker_service.clone();
+        let update_rollout_repo = self.update_rollout_repo.clone();
+        let auth_ctx = auth_ctx.clone();
+        let component_id = component_id.clone();
+        tokio::spawn(
+            async move {
+                let rollouts = workers_by_executor.into_values().map(|workers| {
+                    let worker_service = worker_service.clone();
+                    let metadata = metadata.clone();
+                    let auth_ctx = auth_ctx.clone();
+                    let mode = mode.clone();
+                    stream::iter(workers).for_each_concurrent(
+                        max_concurrent_updates,
+                        move |worker_id| {
+                            let worker_service = worker_service.clone();
+                            let metadata = metadata.clone();
+                            let auth_ctx = auth_ctx.clone();
+                            let mode = mode.clone();
+                            async move {
+                                update_worker(
+                                    worker_service,
+                                    worker_id,
+                                    mode,
+                                    target_version,
+                                    metadata,
+                                    auth_ctx,
+                                )
+                                .await
+                            }
+                        },
+                    )
+                });
+                join_all(rollouts).await;
+
+                if let Err(err) = update_rollout_repo
+                    .finish(&component_id.to_string(), target_version as i64)
+                    .await
+                {
+                    error!(
+                        component_id = component_id.to_string(),
+                        target_version, "Failed to mark the worker update rollout finished: {err}"
+                    );
+                }
+                info!(
+                    component_id = component_id.to_string(),
+                    target_version, "Finished worker update rollout"
+                );
+            }
+            .in_current_span(),
+        );
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<AuthCtx> UpdateRolloutService<AuthCtx> for UpdateRolloutServiceDefault<AuthCtx>
+where
+    AuthCtx: HasTokenScope + Clone + Send + Sync + 'static,
+{
+    async fn get_update_policy(
+        &self,
+        component_id: &ComponentId,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<ComponentUpdatePolicy> {
+        WorkerServiceDefault::<AuthCtx>::authorize_component(
+            auth_ctx,
+            WorkerAction::ReadMetadata,
+            component_id,
+        )?;
+
+        Ok(self
+            .component_service
+            .get_update_policy(component_id, auth_ctx)
+            .await?)
+    }
+
+    async fn update_update_policy(
+        &self,
+        component_id: &ComponentId,
+        policy: ComponentUpdatePolicy,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<ComponentUpdatePolicy> {
+        WorkerServiceDefault::<AuthCtx>::authorize_component(
+            auth_ctx,
+            WorkerAction::Update,
+            component_id,
+        )?;
+
+        Ok(self
+            .component_service
+            .update_update_policy(component_id, policy, auth_ctx)
+            .await?)
+    }
+
+    async fn start_rollout(
+        &self,
+        component_id: &ComponentId,
+        target_version: ComponentVersion,
+        policy: ComponentUpdatePolicy,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<()> {
+        WorkerServiceDefault::<AuthCtx>::authorize_component(
+            auth_ctx,
+            WorkerAction::Update,
+            component_id,
+        )?;
+
+        let record = UpdateRolloutRecord {
+            component_id: component_id.to_string(),
+            target_version: target_version as i64,
+            policy: serde_json::to_string(&policy)
+                .map_err(|err| WorkerServiceError::Internal(err.to_string()))?,
+            account_id: metadata
+                .account_id
+                .as_ref()
+                .map(|account_id| account_id.value.clone()),
+        };
+        self.update_rollout_repo
+            .create(&record, chrono::Utc::now())
+            .await
+            .map_err(|err| WorkerServiceError::Internal(err.to_string()))?;
+
+        self.run_rollout(component_id, target_version, policy, metadata, auth_ctx)
+            .await
+    }
+
+    async fn get_rollout_status(
+        &self,
+        component_id: &ComponentId,
+        target_version: ComponentVersion,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<UpdateRolloutStatus> {
+        let workers = self
+            .find_workers(component_id, None, metadata, auth_ctx)
+            .await?;
+        Ok(rollout_status(target_version, &workers))
+    }
+
+    async fn resume_rollouts(&self, auth_ctx: &AuthCtx) -> WorkerResult<()> {
+        let records = self
+            .update_rollout_repo
+            .get_unfinished()
+            .await
+            .map_err(|err| WorkerServiceError::Internal(err.to_string()))?;
+
+        for record in records {
+            let component_id = ComponentId::try_from(record.component_id.as_str())
+                .map_err(WorkerServiceError::Internal)?;
+            let policy: ComponentUpdatePolicy = serde_json::from_str(&record.policy)
+                .map_err(|err| WorkerServiceError::Internal(err.to_string()))?;
+            let metadata = WorkerRequestMetadata {
+                account_id: record.account_id.map(|value| AccountId { value }),
+                limits: None,
+            };
+
+            info!(
+                component_id = component_id.to_string(),
+                target_version = record.target_version,
+                "Resuming worker update rollout"
+            );
+            self.run_rollout(
+                &component_id,
+                record.target_version as ComponentVersion,
+                policy,
+                metadata,
+                auth_ctx,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+}
+
+/// Enqueues the update of a single worker and waits until it is applied or fails
+async fn update_worker<AuthCtx>(
+    worker_service: Arc<dyn WorkerService<AuthCtx> + Send + Sync>,
+    worker_id: WorkerId,
+    mode: WorkerUpdateMode,
+    target_version: ComponentVersion,
+    metadata: WorkerRequestMetadata,
+    auth_ctx: AuthCtx,
+) {
+    if let Err(error) = worker_service
+        .update(
+            &worker_id,
+            mode.into(),
+            target_version,
+            metadata.clone(),
+            &auth_ctx,
+        )
+        .await
+    {
+        warn!(
+            worker_id = worker_id.to_string(),
+            "Failed to enqueue worker update: {error}"
+        );
+        return;
+    }
+
+    let started_at = Instant::now();
+    while started_at.elapsed() < UPDATE_TIMEOUT {
+        tokio::time::sleep(UPDATE_POLL_INTERVAL).await;
+        match worker_service
+            .get_metadata(&worker_id, metadata.clone(), &auth_ctx)
+            .await
+        {
+            Ok(worker) => {
+                if !matches!(
+                    worker_update_state(&worker, target_version),
+                    WorkerUpdateState::Pending
+                ) {
+                    return;
+                }
+            }
+            Err(error) => {
+                warn!(
+                    worker_id = worker_id.to_string(),
+                    "Failed to get the state of the worker update: {error}"
+                );
+                return;
+            }
+        }
+    }
+    warn!(
+        worker_id = worker_id.to_string(),
+        "Worker update is still pending after {UPDATE_TIMEOUT:?}"
+    );
+}
+
+enum WorkerUpdateState<'a> {
+    Updated,
+    Pending,
+    Failed(&'a FailedUpdate),
+    Outdated,
+}
+
+fn worker_update_state(
+    worker: &WorkerMetadata,
+    target_version: ComponentVersion,
+) -> WorkerUpdateState<'_> {
+    if worker.component_version >= target_version {
+        return WorkerUpdateState::Updated;
+    }
+
+    let mut failed = None;
+    for update in &worker.updates {
+        match update {
+            UpdateRecord::PendingUpdate(pending) if pending.target_version == target_version => {
+                return WorkerUpdateState::Pending;
+            }
+            UpdateRecord::FailedUpdate(failure) if failure.target_version == target_version => {
+                failed = Some(failure);
+            }
+            _ => {}
+        }
+    }
+    match failed {
+        Some(failure) => WorkerUpdateState::Failed(failure),
+        None => WorkerUpdateState::Outdated,
+    }
+}
+
+fn rollout_status(
+    target_version: ComponentVersion,
+    workers: &[WorkerMetadata],
+) -> UpdateRolloutStatus {
+    let mut status = UpdateRolloutStatus {
+        target_version,
+        total_workers: workers.len() as u64,
+        updated_workers: 0,
+        pending_workers: 0,
+        failed_workers: 0,
+        outdated_workers: 0,
+        failures: Vec::new(),
+    };
+    for worker in workers {
+        match worker_update_state(worker, target_version) {
+            WorkerUpdateState::Updated => status.updated_workers += 1,
+            WorkerUpdateState::Pending => status.pending_workers += 1,
+            WorkerUpdateState::Failed(failure) => {
+                status.failed_workers += 1;
+                status.failures.push(UpdateRolloutFailure {
+                    worker_name: worker.worker_id.worker_name.to_string(),
+                    timestamp: failure.timestamp,
+                    details: failure.details.clone(),
+                });
+            }
+            WorkerUpdateState::Outdated => status.outdated_workers += 1,
+        }
+    }
+    status
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::collections::HashMap;
+
+    use golem_common::model::{ComponentId, Timestamp, WorkerId, WorkerName, WorkerStatus};
+    use golem_service_base::model::{
+        FailedUpdate, PendingUpdate, SuccessfulUpdate, UpdateRecord, UpdateRolloutFailure,
+        WorkerMetadata,
+    };
+
+    use super::rollout_status;
+
+    fn worker(name: &str, component_version: u64, updates: Vec<UpdateRecord>) -> WorkerMetadata {
+        WorkerMetadata {
+            worker_id: WorkerId {
+                component_id: ComponentId::new_v4(),
+                worker_name: WorkerName::from_existing(name),
+            },
+            args: vec![],
+            env: HashMap::new(),
+            status: WorkerStatus::Idle,
+            component_version,
+            retry_count: 0,
+            pending_invocation_count: 0,
+            updates,
+            created_at: Timestamp::now_utc(),
+            last_error: None,
+            component_size: 0,
+            total_linear_memory_size: 0,
+            owned_resources: HashMap::new(),
+            corrupted_oplog_regions: vec![],
+            last_invoked_function: None,
+        }
+    }
+
+    #[test]
+    fn aggregates_the_update_records_of_the_workers() {
+        let timestamp = Timestamp::now_utc();
+        let workers = vec![
+            worker(
+                "updated",
+                2,
+                vec![UpdateRecord::SuccessfulUpdate(SuccessfulUpdate {
+                    timestamp,
+                    target_version: 2,
+                })],
+            ),
+            worker(
+                "pending",
+                1,
+                vec![
+                    UpdateRecord::FailedUpdate(FailedUpdate {
+                        timestamp,
+                        target_version: 2,
+                        details: None,
+                    }),
+                    UpdateRecord::PendingUpdate(PendingUpdate {
+                        timestamp,
+                        target_version: 2,
+                    }),
+                ],
+            ),
+            worker(
+                "failed",
+                1,
+                vec![UpdateRecord::FailedUpdate(FailedUpdate {
+                    timestamp,
+                    target_version: 2,
+                    details: Some("missing export".to_string()),
+                })],
+            ),
+            worker(
+                "outdated",
+                0,
+                vec![UpdateRecord::SuccessfulUpdate(SuccessfulUpdate {
+                    timestamp,
+                    target_version: 1,
+                })],
+            ),
+        ];
+
+        let status = rollout_status(2, &workers);
+
+        assert_eq!(status.total_workers, 4);
+        assert_eq!(status.updated_workers, 1);
+        assert_eq!(status.pending_workers, 1);
+        assert_eq!(status.failed_workers, 1);
+        assert_eq!(status.outdated_workers, 1);
+        assert_eq!(
+            status.failures,
+            vec![UpdateRolloutFailure {
+                worker_name: WorkerName::from_existing("failed"),
+                timestamp,
+                details: Some("missing export".to_string()),
+            }]
+        );
+    }
+}
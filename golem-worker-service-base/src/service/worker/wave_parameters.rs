@@ -0,0 +1,117 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Parameters and results of invocations in the WebAssembly Value Encoding (WAVE), with one
+//! value per line, the same way they are passed to the CLI.
+
+use golem_wasm_ast::analysis::AnalysedExport;
+use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
+use golem_wasm_rpc::protobuf::TypedTuple;
+use golem_wasm_rpc::{type_annotated_value_from_str, type_annotated_value_to_string};
+
+use super::parameters::convert_parameters;
+use super::{WorkerResult, WorkerServiceError};
+
+/// Parses the WAVE parameters of an invocation, one per non-empty line, using the parameter
+/// types of the function exported by the component.
+///
+/// Like [super::convert_json_parameters], every mismatching parameter is listed in the returned
+/// `InvalidParameters` error.
+pub fn convert_wave_parameters(
+    exports: &Vec<AnalysedExport>,
+    function_name: &str,
+    params: &str,
+) -> WorkerResult<Vec<TypeAnnotatedValue>> {
+    let params: Vec<&str> = params
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+    convert_parameters(exports, function_name, params, |param, typ| {
+        type_annotated_value_from_str(typ, param).map_err(|err| err.to_string())
+    })
+}
+
+/// Formats the results of an invocation in WAVE, one result per line
+pub fn wave_results(result: &TypeAnnotatedValue) -> WorkerResult<String> {
+    let results = match result {
+        TypeAnnotatedValue::Tuple(TypedTuple { value, .. }) => value
+            .iter()
+            .filter_map(|value| value.type_annotated_value.as_ref())
+            .collect(),
+        result => vec![result],
+    };
+
+    let lines = results
+        .into_iter()
+        .map(|result| {
+            type_annotated_value_to_string(result).map_err(|err| {
+                WorkerServiceError::Internal(format!("Failed to format the result as WAVE: {err}"))
+            })
+        })
+        .collect::<WorkerResult<Vec<_>>>()?;
+    Ok(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
+    use golem_wasm_rpc::protobuf::TypedTuple;
+
+    use crate::service::worker::parameters::add_item_exports as exports;
+    use crate::service::worker::wave_parameters::{convert_wave_parameters, wave_results};
+    use crate::service::worker::WorkerServiceError;
+
+    #[test]
+    fn wave_parameters_are_converted_and_formatted_back() {
+        let values = convert_wave_parameters(
+            &exports(),
+            "golem:it/api.{add-item}",
+            "\"cart\"\n{id: 1, tags: [\"a\", \"b\"]}\n",
+        )
+        .unwrap();
+        assert_eq!(values.len(), 2);
+
+        let result = TypeAnnotatedValue::Tuple(TypedTuple {
+            typ: vec![],
+            value: values
+                .into_iter()
+                .map(|value| golem_wasm_rpc::protobuf::TypeAnnotatedValue {
+                    type_annotated_value: Some(value),
+                })
+                .collect(),
+        });
+        assert_eq!(
+            wave_results(&result).unwrap(),
+            "\"cart\"\n{id: 1, tags: [\"a\", \"b\"]}"
+        );
+    }
+
+    #[test]
+    fn mismatching_wave_parameters_are_reported_one_by_one() {
+        let result = convert_wave_parameters(
+            &exports(),
+            "golem:it/api.{add-item}",
+            "1\n{id: \"x\", tags: []}",
+        );
+        let Err(WorkerServiceError::InvalidParameters(errors)) = result else {
+            panic!("expected invalid parameters, got {result:?}");
+        };
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].starts_with("Parameter 1 (name): "));
+        assert!(errors[1].starts_with("Parameter 2 (item): "));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::worker_binding::{GolemWorkerBinding, ResponseMapping};
+use crate::worker_binding::{GolemWorkerBinding, ResponseMapping, WorkerBindingMode};
 use crate::worker_service_rib_compiler::{DefaultRibCompiler, WorkerServiceRibCompiler};
 use bincode::{Decode, Encode};
 use golem_service_base::model::VersionedComponentId;
@@ -11,6 +11,7 @@ pub struct CompiledGolemWorkerBinding {
     pub worker_name_compiled: WorkerNameCompiled,
     pub idempotency_key_compiled: Option<IdempotencyKeyCompiled>,
     pub response_compiled: ResponseMappingCompiled,
+    pub mode: WorkerBindingMode,
 }
 
 impl CompiledGolemWorkerBinding {
@@ -39,6 +40,7 @@ impl CompiledGolemWorkerBinding {
             worker_name_compiled,
             idempotency_key_compiled,
             response_compiled,
+            mode: golem_worker_binding.mode,
         })
     }
 }
@@ -178,11 +180,14 @@ impl TryFrom<golem_api_grpc::proto::golem::apidefinition::CompiledWorkerBinding>
             rib_input: response_input,
         };
 
+        let mode = value.mode().into();
+
         Ok(CompiledGolemWorkerBinding {
             component_id,
             worker_name_compiled,
             idempotency_key_compiled,
             response_compiled,
+            mode,
         })
     }
 }
@@ -211,6 +216,8 @@ impl TryFrom<CompiledGolemWorkerBinding>
         let response = Some(value.response_compiled.response_rib_expr.into());
         let compiled_response_expr = Some(value.response_compiled.compiled_response.into());
         let response_rib_input = Some(value.response_compiled.rib_input.into());
+        let mode: golem_api_grpc::proto::golem::apidefinition::WorkerBindingMode =
+            value.mode.into();
 
         Ok(
             golem_api_grpc::proto::golem::apidefinition::CompiledWorkerBinding {
@@ -224,6 +231,7 @@ impl TryFrom<CompiledGolemWorkerBinding>
                 response,
                 compiled_response_expr,
                 response_rib_input,
+                mode: mode as i32,
             },
         )
     }
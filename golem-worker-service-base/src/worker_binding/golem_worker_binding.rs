@@ -1,4 +1,6 @@
 use bincode::{Decode, Encode};
+use golem_api_grpc::proto::golem::apidefinition as grpc_apidefinition;
+use poem_openapi::Enum;
 use serde::{Deserialize, Serialize};
 
 use crate::worker_binding::CompiledGolemWorkerBinding;
@@ -12,6 +14,45 @@ pub struct GolemWorkerBinding {
     pub worker_name: Expr,
     pub idempotency_key: Option<Expr>,
     pub response: ResponseMapping,
+    #[serde(default)]
+    pub mode: WorkerBindingMode,
+}
+
+// How the body of a request is given to the worker, and how the result is sent back.
+// In the `Raw` mode the request body is given to Rib as `list<u8>` without being parsed,
+// and the bytes returned by the worker are sent back as they are, without content negotiation,
+// which allows serving images, PDFs and other binary content from workers.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, Encode, Decode, Enum,
+)]
+pub enum WorkerBindingMode {
+    #[default]
+    Json,
+    Raw,
+}
+
+impl WorkerBindingMode {
+    pub fn is_raw(&self) -> bool {
+        matches!(self, WorkerBindingMode::Raw)
+    }
+}
+
+impl From<WorkerBindingMode> for grpc_apidefinition::WorkerBindingMode {
+    fn from(value: WorkerBindingMode) -> Self {
+        match value {
+            WorkerBindingMode::Json => grpc_apidefinition::WorkerBindingMode::Json,
+            WorkerBindingMode::Raw => grpc_apidefinition::WorkerBindingMode::Raw,
+        }
+    }
+}
+
+impl From<grpc_apidefinition::WorkerBindingMode> for WorkerBindingMode {
+    fn from(value: grpc_apidefinition::WorkerBindingMode) -> Self {
+        match value {
+            grpc_apidefinition::WorkerBindingMode::Json => WorkerBindingMode::Json,
+            grpc_apidefinition::WorkerBindingMode::Raw => WorkerBindingMode::Raw,
+        }
+    }
 }
 
 // ResponseMapping will consist of actual logic such as invoking worker functions
@@ -29,6 +70,7 @@ impl From<CompiledGolemWorkerBinding> for GolemWorkerBinding {
                 .idempotency_key_compiled
                 .map(|idempotency_key_compiled| idempotency_key_compiled.idempotency_key),
             response: ResponseMapping(worker_binding.response_compiled.response_rib_expr),
+            mode: worker_binding.mode,
         }
     }
 }
@@ -1,5 +1,8 @@
 use crate::api_definition::http::{QueryInfo, VarInfo};
+use crate::http::http_request::InputHttpRequestBody;
+use crate::worker_binding::WorkerBindingMode;
 
+use golem_wasm_ast::analysis::AnalysedType;
 use http::HeaderMap;
 use serde_json::Value;
 use std::collections::HashMap;
@@ -11,9 +14,9 @@ pub enum RequestDetails {
 impl RequestDetails {
     pub fn from(
         path_params: &HashMap<VarInfo, &str>,
-        query_variable_values: &HashMap<String, String>,
+        query_variable_values: &HashMap<String, Vec<String>>,
         query_variable_names: &[QueryInfo],
-        request_body: &Value,
+        request_body: &InputHttpRequestBody,
         headers: &HeaderMap,
     ) -> Result<Self, Vec<String>> {
         Ok(Self::Http(HttpRequestDetails::from_input_http_request(
@@ -25,40 +28,71 @@ impl RequestDetails {
         )?))
     }
 
-    pub fn as_json(&self) -> Value {
+    pub fn with_auth_claims(self, claims: Value) -> Self {
         match self {
             RequestDetails::Http(http_request_details) => {
-                let typed_path_values = http_request_details.request_path_values.clone().0;
-                let typed_query_values = http_request_details.request_query_values.clone().0;
-
-                let mut path_values = serde_json::Map::new();
-
-                for field in typed_path_values.fields.iter() {
-                    path_values.insert(field.name.clone(), field.value.clone());
-                }
-
-                for field in typed_query_values.fields.iter() {
-                    path_values.insert(field.name.clone(), field.value.clone());
-                }
-
-                let merged_request_path_and_query = Value::Object(path_values);
-
-                let mut header_records = serde_json::Map::new();
-
-                for field in http_request_details.request_header_values.0.fields.iter() {
-                    header_records.insert(field.name.clone(), field.value.clone());
-                }
+                RequestDetails::Http(HttpRequestDetails {
+                    auth_claims: Some(claims),
+                    ..http_request_details
+                })
+            }
+        }
+    }
 
-                let header_value = Value::Object(header_records);
+    pub fn with_binding_mode(self, binding_mode: WorkerBindingMode) -> Self {
+        match self {
+            RequestDetails::Http(http_request_details) => {
+                RequestDetails::Http(HttpRequestDetails {
+                    binding_mode,
+                    ..http_request_details
+                })
+            }
+        }
+    }
 
-                Value::Object(serde_json::Map::from_iter(vec![
+    // Repeated query parameters and headers are given as a list of all their values when the
+    // Rib expression requires a list for them. Otherwise only their last value is given, which
+    // keeps existing definitions working as before.
+    pub fn as_json(&self, request_type: Option<&AnalysedType>) -> Value {
+        match self {
+            RequestDetails::Http(http_request_details) => {
+                let typed_path_values = &http_request_details.request_path_values.0;
+                let typed_query_values = &http_request_details.request_query_values.0;
+
+                let merged_request_path_and_query = Value::Object(internal::json_record(
+                    typed_path_values
+                        .fields
+                        .iter()
+                        .chain(typed_query_values.fields.iter()),
+                    internal::field_type(request_type, "path"),
+                ));
+
+                let header_value = Value::Object(internal::json_record(
+                    http_request_details.request_header_values.0.fields.iter(),
+                    internal::field_type(request_type, "headers"),
+                ));
+
+                let body_value = http_request_details
+                    .request_body
+                    .as_json(internal::field_type(request_type, "body"));
+
+                let mut request = serde_json::Map::from_iter(vec![
                     ("path".to_string(), merged_request_path_and_query),
-                    (
-                        "body".to_string(),
-                        http_request_details.request_body.0.clone(),
-                    ),
+                    ("body".to_string(), body_value),
                     ("headers".to_string(), header_value),
-                ]))
+                ]);
+
+                if let Some(claims) = &http_request_details.auth_claims {
+                    request.insert(
+                        "auth".to_string(),
+                        Value::Object(serde_json::Map::from_iter(vec![(
+                            "claims".to_string(),
+                            claims.clone(),
+                        )])),
+                    );
+                }
+
+                Value::Object(request)
             }
         }
     }
@@ -70,15 +104,21 @@ pub struct HttpRequestDetails {
     pub request_body: RequestBody,
     pub request_query_values: RequestQueryValues,
     pub request_header_values: RequestHeaderValues,
+    // Verified JWT claims, present only if the api definition requires authentication
+    pub auth_claims: Option<Value>,
+    // Mode of the binding of the matched route, deciding how the response is sent back
+    pub binding_mode: WorkerBindingMode,
 }
 
 impl HttpRequestDetails {
     pub fn empty() -> HttpRequestDetails {
         HttpRequestDetails {
             request_path_values: RequestPathValues(JsonKeyValues::default()),
-            request_body: RequestBody(Value::Null),
+            request_body: RequestBody(InputHttpRequestBody::Json(Value::Null)),
             request_query_values: RequestQueryValues(JsonKeyValues::default()),
             request_header_values: RequestHeaderValues(JsonKeyValues::default()),
+            auth_claims: None,
+            binding_mode: WorkerBindingMode::default(),
         }
     }
 
@@ -87,18 +127,18 @@ impl HttpRequestDetails {
             .0
             .fields
             .iter()
-            .find(|field| field.name == http::header::ACCEPT.to_string())
+            .rfind(|field| field.name == http::header::ACCEPT.to_string())
             .and_then(|field| field.value.as_str().map(|x| x.to_string()))
     }
 
     fn from_input_http_request(
         path_params: &HashMap<VarInfo, &str>,
-        query_variable_values: &HashMap<String, String>,
+        query_variable_values: &HashMap<String, Vec<String>>,
         query_variable_names: &[QueryInfo],
-        request_body: &Value,
+        request_body: &InputHttpRequestBody,
         headers: &HeaderMap,
     ) -> Result<Self, Vec<String>> {
-        let request_body = RequestBody::from(request_body)?;
+        let request_body = RequestBody(request_body.clone());
         let path_params = RequestPathValues::from(path_params);
         let query_params = RequestQueryValues::from(query_variable_values, query_variable_names)?;
         let header_params = RequestHeaderValues::from(headers)?;
@@ -108,6 +148,8 @@ impl HttpRequestDetails {
             request_body,
             request_query_values: query_params,
             request_header_values: header_params,
+            auth_claims: None,
+            binding_mode: WorkerBindingMode::default(),
         })
     }
 }
@@ -136,7 +178,7 @@ pub struct RequestQueryValues(pub JsonKeyValues);
 
 impl RequestQueryValues {
     fn from(
-        query_key_values: &HashMap<String, String>,
+        query_key_values: &HashMap<String, Vec<String>>,
         query_keys: &[QueryInfo],
     ) -> Result<RequestQueryValues, Vec<String>> {
         let mut unavailable_query_variables: Vec<String> = vec![];
@@ -144,11 +186,16 @@ impl RequestQueryValues {
 
         for spec_query_variable in query_keys.iter() {
             let key = &spec_query_variable.key_name;
-            if let Some(query_value) = query_key_values.get(key) {
-                let typed_value = internal::refine_json_str_value(query_value);
-                query_variable_map.push(key.clone(), typed_value);
-            } else {
-                unavailable_query_variables.push(spec_query_variable.to_string());
+            match query_key_values.get(key) {
+                Some(query_values) if !query_values.is_empty() => {
+                    for query_value in query_values {
+                        let typed_value = internal::refine_json_str_value(query_value);
+                        query_variable_map.push(key.clone(), typed_value);
+                    }
+                }
+                _ => {
+                    unavailable_query_variables.push(spec_query_variable.to_string());
+                }
             }
         }
 
@@ -179,11 +226,43 @@ impl RequestHeaderValues {
 }
 
 #[derive(Debug, Clone)]
-pub struct RequestBody(Value);
+pub struct RequestBody(InputHttpRequestBody);
 
 impl RequestBody {
-    fn from(request_body: &Value) -> Result<RequestBody, Vec<String>> {
-        Ok(RequestBody(request_body.clone()))
+    // Bytes are given to Rib as a `list<u8>`, unless the Rib expression uses them as a string,
+    // in which case bodies holding valid UTF-8 are given as text
+    fn as_json(&self, body_type: Option<&AnalysedType>) -> Value {
+        match &self.0 {
+            InputHttpRequestBody::Json(value) => value.clone(),
+            InputHttpRequestBody::Multipart(parts) => {
+                let part_type = match body_type {
+                    Some(AnalysedType::List(list)) => Some(list.inner.as_ref()),
+                    _ => None,
+                };
+                Value::Array(
+                    parts
+                        .iter()
+                        .map(|part| {
+                            Value::Object(serde_json::Map::from_iter(vec![
+                                ("name".to_string(), Value::String(part.name.clone())),
+                                (
+                                    "content-type".to_string(),
+                                    Value::String(part.content_type.clone()),
+                                ),
+                                (
+                                    "bytes".to_string(),
+                                    internal::json_bytes(
+                                        &part.bytes,
+                                        internal::field_type(part_type, "bytes"),
+                                    ),
+                                ),
+                            ]))
+                        })
+                        .collect(),
+                )
+            }
+            InputHttpRequestBody::Raw(bytes) => internal::json_bytes(bytes, body_type),
+        }
     }
 }
 
@@ -205,9 +284,60 @@ pub struct JsonKeyValue {
 }
 
 mod internal {
+    use crate::worker_binding::request_details::JsonKeyValue;
+    use golem_wasm_ast::analysis::AnalysedType;
     use rib::{CoercedNumericValue, LiteralValue};
     use serde_json::Value;
 
+    // Type of a field of a record type, if the type is known
+    pub(crate) fn field_type<'a>(
+        record_type: Option<&'a AnalysedType>,
+        name: &str,
+    ) -> Option<&'a AnalysedType> {
+        match record_type {
+            Some(AnalysedType::Record(record)) => record
+                .fields
+                .iter()
+                .find(|field| field.name == name)
+                .map(|field| &field.typ),
+            _ => None,
+        }
+    }
+
+    // Builds a JSON object of the key values, where a key that is present multiple times
+    // becomes a list if the record type requires one, or keeps only its last value otherwise
+    pub(crate) fn json_record<'a>(
+        fields: impl Iterator<Item = &'a JsonKeyValue>,
+        record_type: Option<&AnalysedType>,
+    ) -> serde_json::Map<String, Value> {
+        let mut grouped: Vec<(String, Vec<Value>)> = vec![];
+
+        for field in fields {
+            match grouped.iter_mut().find(|(name, _)| *name == field.name) {
+                Some((_, values)) => values.push(field.value.clone()),
+                None => grouped.push((field.name.clone(), vec![field.value.clone()])),
+            }
+        }
+
+        grouped
+            .into_iter()
+            .filter_map(|(name, mut values)| {
+                let value = match field_type(record_type, &name) {
+                    Some(AnalysedType::List(_)) => Value::Array(values),
+                    _ => values.pop()?,
+                };
+                Some((name, value))
+            })
+            .collect()
+    }
+
+    pub(crate) fn json_bytes(bytes: &[u8], typ: Option<&AnalysedType>) -> Value {
+        match (typ, std::str::from_utf8(bytes)) {
+            (Some(AnalysedType::Str(_)), Ok(text)) => Value::String(text.to_string()),
+            _ => Value::Array(bytes.iter().map(|byte| Value::from(*byte)).collect()),
+        }
+    }
+
     pub(crate) fn refine_json_str_value(value: impl AsRef<str>) -> Value {
         let primitive = LiteralValue::from(value.as_ref().to_string());
         match primitive {
@@ -227,3 +357,65 @@ mod internal {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::http::http_request::InputHttpRequestBody;
+    use crate::http::multipart::MultipartPart;
+    use crate::worker_binding::request_details::RequestBody;
+    use golem_wasm_ast::analysis::analysed_type::{field, list, record, str, u8};
+    use serde_json::json;
+
+    fn part() -> MultipartPart {
+        MultipartPart {
+            name: "file".to_string(),
+            content_type: "text/plain".to_string(),
+            bytes: b"hi".to_vec(),
+        }
+    }
+
+    #[test]
+    fn raw_body_is_given_as_bytes() {
+        let body = RequestBody(InputHttpRequestBody::Raw(b"hi".to_vec()));
+
+        assert_eq!(body.as_json(Some(&list(u8()))), json!([104, 105]));
+        assert_eq!(body.as_json(None), json!([104, 105]));
+    }
+
+    #[test]
+    fn raw_body_is_given_as_text_when_used_as_a_string() {
+        let body = RequestBody(InputHttpRequestBody::Raw(b"hi".to_vec()));
+        let invalid_utf8 = RequestBody(InputHttpRequestBody::Raw(vec![0xff, 0xfe]));
+
+        assert_eq!(body.as_json(Some(&str())), json!("hi"));
+        assert_eq!(invalid_utf8.as_json(Some(&str())), json!([255, 254]));
+    }
+
+    #[test]
+    fn multipart_body_is_given_as_part_records() {
+        let body = RequestBody(InputHttpRequestBody::Multipart(vec![part()]));
+        let text_parts = list(record(vec![
+            field("name", str()),
+            field("content-type", str()),
+            field("bytes", str()),
+        ]));
+
+        assert_eq!(
+            body.as_json(None),
+            json!([{ "name": "file", "content-type": "text/plain", "bytes": [104, 105] }])
+        );
+        assert_eq!(
+            body.as_json(Some(&text_parts)),
+            json!([{ "name": "file", "content-type": "text/plain", "bytes": "hi" }])
+        );
+    }
+
+    #[test]
+    fn json_body_is_given_as_it_is() {
+        let body = RequestBody(InputHttpRequestBody::Json(json!({ "a": [1, 2] })));
+
+        assert_eq!(body.as_json(Some(&str())), json!({ "a": [1, 2] }));
+    }
+}
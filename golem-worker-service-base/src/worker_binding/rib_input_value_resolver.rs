@@ -51,10 +51,9 @@ impl RibInputValueResolver for RequestDetails {
     ) -> Result<RibInputValue, RibInputTypeMismatch> {
         let request_type_info = required_types.types.get("request");
 
-        let rib_input_with_request_content = &self.as_json();
-
         match request_type_info {
             Some(request_type) => {
+                let rib_input_with_request_content = &self.as_json(Some(request_type));
                 let input = TypeAnnotatedValue::parse_with_type(rib_input_with_request_content, request_type)
                         .map_err(|err| RibInputTypeMismatch(format!("Input request details don't match the requirements for rib expression to execute: {}. Requirements. {:?}", err.join(", "), request_type)))?;
 
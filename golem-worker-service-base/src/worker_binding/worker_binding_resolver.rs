@@ -2,11 +2,13 @@ use crate::api_definition::http::{CompiledHttpApiDefinition, VarInfo};
 use crate::http::http_request::router;
 use crate::http::router::RouterPattern;
 use crate::http::InputHttpRequest;
+use crate::service::http::jwt_validator::{JwtValidationError, JwtValidator};
 use crate::worker_service_rib_interpreter::EvaluationError;
 use crate::worker_service_rib_interpreter::WorkerServiceRibInterpreter;
 use async_trait::async_trait;
 use golem_common::model::IdempotencyKey;
 use golem_service_base::model::VersionedComponentId;
+use http::header::AUTHORIZATION;
 use rib::RibInterpreterResult;
 use serde_json::Value;
 use std::collections::HashMap;
@@ -25,21 +27,38 @@ pub trait RequestToWorkerBindingResolver<ApiDefinition> {
     async fn resolve_worker_binding(
         &self,
         api_definitions: Vec<ApiDefinition>,
+        jwt_validator: &Arc<dyn JwtValidator + Sync + Send>,
     ) -> Result<ResolvedWorkerBindingFromRequest, WorkerBindingResolutionError>;
 }
 
 #[derive(Debug)]
-pub struct WorkerBindingResolutionError(pub String);
+pub enum WorkerBindingResolutionError {
+    // The matched route requires authentication, and the request failed to authenticate
+    Unauthorized(String),
+    // The request could not be authenticated because the signing keys could not be fetched
+    AuthenticationUnavailable(String),
+    Other(String),
+}
 
 impl<A: AsRef<str>> From<A> for WorkerBindingResolutionError {
     fn from(message: A) -> Self {
-        WorkerBindingResolutionError(message.as_ref().to_string())
+        WorkerBindingResolutionError::Other(message.as_ref().to_string())
     }
 }
 
 impl Display for WorkerBindingResolutionError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Worker binding resolution error: {}", self.0)
+        match self {
+            WorkerBindingResolutionError::Unauthorized(message) => {
+                write!(f, "Unauthorized request: {}", message)
+            }
+            WorkerBindingResolutionError::AuthenticationUnavailable(message) => {
+                write!(f, "Authentication unavailable: {}", message)
+            }
+            WorkerBindingResolutionError::Other(message) => {
+                write!(f, "Worker binding resolution error: {}", message)
+            }
+        }
     }
 }
 
@@ -125,16 +144,12 @@ impl RequestToWorkerBindingResolver<CompiledHttpApiDefinition> for InputHttpRequ
     async fn resolve_worker_binding(
         &self,
         compiled_api_definitions: Vec<CompiledHttpApiDefinition>,
+        jwt_validator: &Arc<dyn JwtValidator + Sync + Send>,
     ) -> Result<ResolvedWorkerBindingFromRequest, WorkerBindingResolutionError> {
-        let compiled_routes = compiled_api_definitions
-            .iter()
-            .flat_map(|x| x.routes.clone())
-            .collect::<Vec<_>>();
-
         let api_request = self;
-        let router = router::build(compiled_routes);
+        let router = router::build(compiled_api_definitions);
         let path: Vec<&str> = RouterPattern::split(&api_request.input_path.base_path).collect();
-        let request_query_variables = self.input_path.query_components().unwrap_or_default();
+        let request_query_variables = self.input_path.query_values().unwrap_or_default();
         let request_body = &self.req_body;
         let headers = &self.headers;
 
@@ -142,6 +157,8 @@ impl RequestToWorkerBindingResolver<CompiledHttpApiDefinition> for InputHttpRequ
             path_params,
             query_params,
             binding,
+            auth,
+            ..
         } = router
             .check_path(&api_request.req_method, &path)
             .ok_or("Failed to resolve route")?;
@@ -160,7 +177,37 @@ impl RequestToWorkerBindingResolver<CompiledHttpApiDefinition> for InputHttpRequ
             request_body,
             headers,
         )
-        .map_err(|err| format!("Failed to fetch input request details {}", err.join(", ")))?;
+        .map_err(|err| format!("Failed to fetch input request details {}", err.join(", ")))?
+        .with_binding_mode(binding.mode);
+
+        // Authentication happens before anything is evaluated, so that unauthenticated
+        // requests never reach a worker
+        let http_request_details = match auth {
+            Some(auth) => {
+                let token = headers
+                    .get(AUTHORIZATION)
+                    .and_then(|h| h.to_str().ok())
+                    .and_then(|value| value.strip_prefix("Bearer "))
+                    .ok_or(WorkerBindingResolutionError::Unauthorized(
+                        "Missing bearer token".to_string(),
+                    ))?;
+
+                let claims = jwt_validator
+                    .validate(&auth.jwt, token.trim())
+                    .await
+                    .map_err(|err| match err {
+                        JwtValidationError::JwksUnavailable { .. } => {
+                            WorkerBindingResolutionError::AuthenticationUnavailable(err.to_string())
+                        }
+                        JwtValidationError::InvalidToken(_) => {
+                            WorkerBindingResolutionError::Unauthorized(err.to_string())
+                        }
+                    })?;
+
+                http_request_details.with_auth_claims(claims)
+            }
+            None => http_request_details,
+        };
 
         let resolve_rib_input = http_request_details
             .resolve_rib_input_value(&binding.worker_name_compiled.rib_input_type_info)
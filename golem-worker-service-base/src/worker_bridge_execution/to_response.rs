@@ -60,7 +60,9 @@ mod internal {
     use crate::path::Path;
     use golem_wasm_rpc::json::TypeAnnotatedValueJsonExtensions;
     use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
-    use golem_wasm_rpc::protobuf::TypedRecord;
+    use golem_wasm_rpc::protobuf::{TypedList, TypedRecord};
+    use http::header::CONTENT_TYPE;
+    use http::HeaderValue;
     use poem::web::headers::ContentType;
     use poem::{Body, IntoResponse, ResponseParts};
     use rib::{GetLiteralValue, LiteralValue, RibInterpreterResult};
@@ -114,7 +116,14 @@ mod internal {
             let status = &self.status;
             let evaluation_result = &self.body;
 
+            let binding_mode = match request_details {
+                RequestDetails::Http(http) => http.binding_mode,
+            };
+
             match headers {
+                Ok(response_headers) if binding_mode.is_raw() => {
+                    self.to_raw_http_response(response_headers)
+                }
                 Ok(response_headers) => {
                     let response_content_type =
                         get_content_type_from_response_headers(&response_headers);
@@ -161,6 +170,61 @@ mod internal {
                     ))),
             }
         }
+
+        // The bytes returned by the worker are sent back as they are, skipping the content
+        // negotiation. Without a content type given by the response mapping they are sent as
+        // application/octet-stream. A response body that is not made of bytes is an error of
+        // the worker or of the response mapping, not of the request, so it fails with 500
+        fn to_raw_http_response(&self, mut response_headers: HeaderMap) -> poem::Response {
+            let body = match &self.body {
+                Some(type_annotated_value) => match get_raw_bytes(type_annotated_value) {
+                    Ok(bytes) => {
+                        if !response_headers.contains_key(CONTENT_TYPE) {
+                            response_headers.insert(
+                                CONTENT_TYPE,
+                                HeaderValue::from_static("application/octet-stream"),
+                            );
+                        }
+                        Body::from_vec(bytes)
+                    }
+                    Err(err) => {
+                        return poem::Response::builder()
+                            .status(StatusCode::INTERNAL_SERVER_ERROR)
+                            .body(Body::from_string(err))
+                    }
+                },
+                None => Body::empty(),
+            };
+
+            let parts = ResponseParts {
+                status: self.status,
+                version: Default::default(),
+                headers: response_headers,
+                extensions: Default::default(),
+            };
+
+            poem::Response::from_parts(parts, body)
+        }
+    }
+
+    fn get_raw_bytes(type_annotated_value: &TypeAnnotatedValue) -> Result<Vec<u8>, String> {
+        let not_bytes = || {
+            format!(
+                "Response body of a raw binding is not a list<u8>. It is resolved to {}",
+                type_annotated_value.to_json_value()
+            )
+        };
+
+        match type_annotated_value {
+            TypeAnnotatedValue::List(TypedList { values, .. }) => values
+                .iter()
+                .map(|value| match &value.type_annotated_value {
+                    Some(TypeAnnotatedValue::U8(byte)) => Ok(*byte as u8),
+                    _ => Err(not_bytes()),
+                })
+                .collect(),
+            _ => Err(not_bytes()),
+        }
     }
 
     fn get_content_type_from_response_headers(response_headers: &HeaderMap) -> Option<ContentType> {
@@ -252,8 +316,10 @@ mod test {
     use golem_wasm_rpc::protobuf::Type;
     use golem_wasm_rpc::protobuf::{NameTypePair, NameValuePair, TypedRecord};
 
-    use crate::worker_binding::{HttpRequestDetails, RequestDetails};
+    use crate::worker_binding::{HttpRequestDetails, RequestDetails, WorkerBindingMode};
     use crate::worker_bridge_execution::to_response::ToResponse;
+    use golem_wasm_ast::analysis::analysed_type::u8;
+    use golem_wasm_rpc::protobuf::TypedList;
     use http::header::CONTENT_TYPE;
     use http::StatusCode;
     use rib::RibInterpreterResult;
@@ -351,6 +417,66 @@ mod test {
         assert_eq!(status, expected_status);
     }
 
+    #[test]
+    async fn test_evaluation_result_to_raw_response() {
+        let bytes = vec![0x89u8, b'P', b'N', b'G'];
+        let body = TypeAnnotatedValue::List(TypedList {
+            values: bytes
+                .iter()
+                .map(|byte| golem_wasm_rpc::protobuf::TypeAnnotatedValue {
+                    type_annotated_value: Some(TypeAnnotatedValue::U8(*byte as u32)),
+                })
+                .collect(),
+            typ: Some((&u8()).into()),
+        });
+
+        let record = create_record(vec![
+            (
+                "headers".to_string(),
+                create_record(vec![(
+                    "Content-Type".to_string(),
+                    TypeAnnotatedValue::Str("image/png".to_string()),
+                )]),
+            ),
+            ("body".to_string(), body.clone()),
+        ]);
+
+        let request_details = RequestDetails::Http(HttpRequestDetails::empty())
+            .with_binding_mode(WorkerBindingMode::Raw);
+
+        let http_response: poem::Response =
+            RibInterpreterResult::Val(record).to_response(&request_details);
+        let (response_parts, response_body) = http_response.into_parts();
+
+        assert_eq!(response_parts.status, StatusCode::OK);
+        assert_eq!(
+            response_parts.headers.get(CONTENT_TYPE).unwrap(),
+            "image/png"
+        );
+        assert_eq!(response_body.into_vec().await.unwrap(), bytes);
+
+        // Without a content type in the response mapping, and regardless of the Accept header
+        let http_response: poem::Response =
+            RibInterpreterResult::Val(body).to_response(&request_details);
+
+        assert_eq!(
+            http_response.headers().get(CONTENT_TYPE).unwrap(),
+            "application/octet-stream"
+        );
+    }
+
+    #[test]
+    async fn test_raw_response_not_made_of_bytes_is_a_server_error() {
+        let request_details = RequestDetails::Http(HttpRequestDetails::empty())
+            .with_binding_mode(WorkerBindingMode::Raw);
+
+        let http_response: poem::Response =
+            RibInterpreterResult::Val(TypeAnnotatedValue::Str("not bytes".to_string()))
+                .to_response(&request_details);
+
+        assert_eq!(http_response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
     #[test]
     fn test_get_response_headers_from_typed_value() {
         let header_map = create_record(vec![
@@ -1,6 +1,7 @@
 use async_trait::async_trait;
 use futures_util::FutureExt;
 use std::fmt::Display;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
 use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
@@ -17,7 +18,9 @@ use crate::worker_bridge_execution::{WorkerRequest, WorkerRequestExecutor};
 #[async_trait]
 pub trait WorkerServiceRibInterpreter {
     // Evaluate a Rib byte against a specific worker.
-    // RibByteCode may have actual function calls.
+    // RibByteCode may have actual function calls, which are invoked one after the other,
+    // in the order they appear in the script. Calls following a `use-worker` call target
+    // the selected worker of the same component instead of the given one.
     async fn evaluate(
         &self,
         worker_name: &str,
@@ -73,12 +76,23 @@ impl WorkerServiceRibInterpreter for DefaultRibInterpreter {
         let component_id = component_id.clone();
         let idempotency_key = idempotency_key.clone();
 
+        // Every call of a script having multiple worker function calls needs its own
+        // idempotency key, otherwise the executor would return the result of the first
+        // call for all the others
+        let invocation_index = AtomicUsize::new(0);
+
         let worker_invoke_function: RibFunctionInvoke = Arc::new(
-            move |function_name: String, parameters: Vec<TypeAnnotatedValue>| {
-                let worker_name = worker_name.to_string();
+            move |target_worker: Option<String>,
+                  function_name: String,
+                  parameters: Vec<TypeAnnotatedValue>| {
+                let worker_name = target_worker.unwrap_or_else(|| worker_name.clone());
                 let component_id = component_id.clone();
-                let worker_name = worker_name.clone();
-                let idempotency_key = idempotency_key.clone();
+                let idempotency_key = match invocation_index.fetch_add(1, Ordering::SeqCst) {
+                    0 => idempotency_key.clone(),
+                    index => idempotency_key
+                        .as_ref()
+                        .map(|key| IdempotencyKey::derived_invocation(key, index)),
+                };
                 let executor = executor.clone();
 
                 async move {
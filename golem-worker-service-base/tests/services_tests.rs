@@ -2,10 +2,11 @@ use test_r::test;
 
 use async_trait::async_trait;
 use golem_common::config::{DbPostgresConfig, DbSqliteConfig};
-use golem_common::model::ComponentId;
+use golem_common::model::component_observability::ComponentObservability;
+use golem_common::model::{ComponentId, ProjectId};
 use golem_service_base::auth::{DefaultNamespace, EmptyAuthCtx};
 use golem_service_base::db;
-use golem_service_base::model::Component;
+use golem_service_base::model::{Component, ComponentUpdatePolicy, Project};
 use golem_wasm_ast::analysis::{
     AnalysedExport, AnalysedFunction, AnalysedFunctionParameter, AnalysedFunctionResult,
     AnalysedInstance,
@@ -15,7 +16,9 @@ use golem_worker_service_base::api_definition::http::HttpApiDefinitionRequest;
 use golem_worker_service_base::api_definition::{
     ApiDefinitionId, ApiDeploymentRequest, ApiSite, ApiSiteString, ApiVersion,
 };
-use golem_worker_service_base::repo::{api_definition, api_deployment};
+use golem_worker_service_base::repo::{
+    api_definition, api_deployment, api_key, api_project, invocation_attempt, update_rollout,
+};
 use golem_worker_service_base::service::api_definition::{
     ApiDefinitionError, ApiDefinitionIdWithVersion, ApiDefinitionService,
     ApiDefinitionServiceDefault,
@@ -23,6 +26,12 @@ use golem_worker_service_base::service::api_definition::{
 use golem_worker_service_base::service::api_deployment::{
     ApiDeploymentError, ApiDeploymentService, ApiDeploymentServiceDefault,
 };
+use golem_worker_service_base::service::api_key::{
+    ApiKeyError, ApiKeyService, ApiKeyServiceDefault,
+};
+use golem_worker_service_base::service::api_project::{
+    ApiProjectError, ApiProjectService, ApiProjectServiceDefault,
+};
 use golem_worker_service_base::service::component::{ComponentResult, ComponentService};
 use golem_worker_service_base::service::http::http_api_definition_validator::{
     HttpApiDefinitionValidator, RouteValidationError,
@@ -30,6 +39,7 @@ use golem_worker_service_base::service::http::http_api_definition_validator::{
 
 use chrono::Utc;
 use golem_wasm_ast::analysis::analysed_type::str;
+use std::collections::BTreeMap;
 use std::sync::Arc;
 use testcontainers::runners::AsyncRunner;
 use testcontainers::{ContainerAsync, ImageExt};
@@ -96,7 +106,27 @@ pub async fn test_with_postgres_db() {
         api_deployment::DbApiDeploymentRepo::new(db_pool.clone().into()),
     );
 
-    test_services(api_definition_repo, api_deployment_repo).await;
+    let api_key_repo: Arc<dyn api_key::ApiKeyRepo + Sync + Send> =
+        Arc::new(api_key::DbApiKeyRepo::new(db_pool.clone().into()));
+    let api_project_repo: Arc<dyn api_project::ApiProjectRepo + Sync + Send> =
+        Arc::new(api_project::DbApiProjectRepo::new(db_pool.clone().into()));
+    let invocation_attempt_repo: Arc<dyn invocation_attempt::InvocationAttemptRepo + Sync + Send> =
+        Arc::new(invocation_attempt::DbInvocationAttemptRepo::new(
+            db_pool.clone().into(),
+        ));
+    let update_rollout_repo: Arc<dyn update_rollout::UpdateRolloutRepo + Sync + Send> = Arc::new(
+        update_rollout::DbUpdateRolloutRepo::new(db_pool.clone().into()),
+    );
+
+    test_services(
+        api_definition_repo,
+        api_deployment_repo,
+        api_key_repo,
+        api_project_repo,
+    )
+    .await;
+    test_invocation_attempts(invocation_attempt_repo).await;
+    test_update_rollouts(update_rollout_repo).await;
 }
 
 #[test]
@@ -120,7 +150,27 @@ pub async fn test_with_sqlite_db() {
         api_deployment::DbApiDeploymentRepo::new(db_pool.clone().into()),
     );
 
-    test_services(api_definition_repo, api_deployment_repo).await;
+    let api_key_repo: Arc<dyn api_key::ApiKeyRepo + Sync + Send> =
+        Arc::new(api_key::DbApiKeyRepo::new(db_pool.clone().into()));
+    let api_project_repo: Arc<dyn api_project::ApiProjectRepo + Sync + Send> =
+        Arc::new(api_project::DbApiProjectRepo::new(db_pool.clone().into()));
+    let invocation_attempt_repo: Arc<dyn invocation_attempt::InvocationAttemptRepo + Sync + Send> =
+        Arc::new(invocation_attempt::DbInvocationAttemptRepo::new(
+            db_pool.clone().into(),
+        ));
+    let update_rollout_repo: Arc<dyn update_rollout::UpdateRolloutRepo + Sync + Send> = Arc::new(
+        update_rollout::DbUpdateRolloutRepo::new(db_pool.clone().into()),
+    );
+
+    test_services(
+        api_definition_repo,
+        api_deployment_repo,
+        api_key_repo,
+        api_project_repo,
+    )
+    .await;
+    test_invocation_attempts(invocation_attempt_repo).await;
+    test_update_rollouts(update_rollout_repo).await;
 }
 
 struct TestComponentService;
@@ -143,9 +193,15 @@ impl TestComponentService {
                 exports: Self::get_metadata(),
                 producers: vec![],
                 memories: vec![],
+                labels: BTreeMap::new(),
+                rpc_dependencies: vec![],
+                imports: vec![],
+                plugs: vec![],
             },
             created_at: Some(Utc::now()),
             component_type: None,
+            compatibility_report: None,
+            initial_files_hash: None,
         }
     }
 
@@ -187,11 +243,68 @@ impl<AuthCtx> ComponentService<AuthCtx> for TestComponentService {
     ) -> ComponentResult<Component> {
         Ok(Self::test_component())
     }
+
+    async fn get_by_version_alias(
+        &self,
+        _component_id: &ComponentId,
+        _alias: &str,
+        _auth_ctx: &AuthCtx,
+    ) -> ComponentResult<Component> {
+        Ok(Self::test_component())
+    }
+
+    async fn get_observability(
+        &self,
+        _component_id: &ComponentId,
+        _auth_ctx: &AuthCtx,
+    ) -> ComponentResult<ComponentObservability> {
+        Ok(ComponentObservability::default())
+    }
+
+    async fn update_observability(
+        &self,
+        _component_id: &ComponentId,
+        observability: ComponentObservability,
+        _auth_ctx: &AuthCtx,
+    ) -> ComponentResult<ComponentObservability> {
+        Ok(observability)
+    }
+
+    async fn get_update_policy(
+        &self,
+        _component_id: &ComponentId,
+        _auth_ctx: &AuthCtx,
+    ) -> ComponentResult<ComponentUpdatePolicy> {
+        Ok(ComponentUpdatePolicy::default())
+    }
+
+    async fn update_update_policy(
+        &self,
+        _component_id: &ComponentId,
+        policy: ComponentUpdatePolicy,
+        _auth_ctx: &AuthCtx,
+    ) -> ComponentResult<ComponentUpdatePolicy> {
+        Ok(policy)
+    }
+    async fn get_project(
+        &self,
+        project_id: &ProjectId,
+        _auth_ctx: &AuthCtx,
+    ) -> ComponentResult<Project> {
+        Ok(Project {
+            project_id: project_id.clone(),
+            name: "test".to_string(),
+            description: "".to_string(),
+            created_at: Utc::now(),
+        })
+    }
 }
 
 async fn test_services(
     api_definition_repo: Arc<dyn api_definition::ApiDefinitionRepo + Sync + Send>,
     api_deployment_repo: Arc<dyn api_deployment::ApiDeploymentRepo + Sync + Send>,
+    api_key_repo: Arc<dyn api_key::ApiKeyRepo + Sync + Send>,
+    api_project_repo: Arc<dyn api_project::ApiProjectRepo + Sync + Send>,
 ) {
     let component_service: Arc<dyn ComponentService<EmptyAuthCtx> + Sync + Send> =
         Arc::new(TestComponentService {});
@@ -219,6 +332,106 @@ async fn test_services(
     test_delete_non_existing(definition_service.clone()).await;
     test_deployment(definition_service.clone(), deployment_service.clone()).await;
     test_deployment_conflict(definition_service.clone(), deployment_service.clone()).await;
+
+    let api_key_service: Arc<dyn ApiKeyService<DefaultNamespace> + Sync + Send> = Arc::new(
+        ApiKeyServiceDefault::new(api_key_repo.clone(), api_deployment_repo.clone()),
+    );
+
+    test_api_keys(
+        definition_service.clone(),
+        deployment_service.clone(),
+        api_key_service.clone(),
+    )
+    .await;
+
+    let api_project_service: Arc<
+        dyn ApiProjectService<EmptyAuthCtx, DefaultNamespace> + Sync + Send,
+    > = Arc::new(ApiProjectServiceDefault::new(
+        component_service.clone(),
+        api_project_repo.clone(),
+        api_definition_repo.clone(),
+        api_deployment_repo.clone(),
+    ));
+
+    test_api_projects(
+        definition_service.clone(),
+        deployment_service.clone(),
+        api_project_service.clone(),
+        api_project_repo.clone(),
+    )
+    .await;
+}
+
+async fn test_invocation_attempts(
+    invocation_attempt_repo: Arc<dyn invocation_attempt::InvocationAttemptRepo + Sync + Send>,
+) {
+    let attempt = invocation_attempt::InvocationAttemptRecord {
+        idempotency_key: Uuid::new_v4().to_string(),
+        component_id: Uuid::new_v4().to_string(),
+        worker_name: Some("worker-1".to_string()),
+        function_name: "golem:it/api.{f}".to_string(),
+    };
+    let repeated = invocation_attempt::InvocationAttemptRecord {
+        worker_name: Some("worker-2".to_string()),
+        ..attempt.clone()
+    };
+
+    let first = invocation_attempt_repo
+        .create_or_get(&attempt, Utc::now())
+        .await
+        .unwrap();
+    let second = invocation_attempt_repo
+        .create_or_get(&repeated, Utc::now())
+        .await
+        .unwrap();
+
+    assert_eq!(first, None);
+    assert_eq!(second, Some(attempt));
+}
+
+async fn test_update_rollouts(
+    update_rollout_repo: Arc<dyn update_rollout::UpdateRolloutRepo + Sync + Send>,
+) {
+    let policy = ComponentUpdatePolicy {
+        enabled: true,
+        ..ComponentUpdatePolicy::default()
+    };
+    let rollout = update_rollout::UpdateRolloutRecord {
+        component_id: Uuid::new_v4().to_string(),
+        target_version: 1,
+        policy: serde_json::to_string(&policy).unwrap(),
+        account_id: None,
+    };
+    let next_rollout = update_rollout::UpdateRolloutRecord {
+        target_version: 2,
+        ..rollout.clone()
+    };
+
+    update_rollout_repo
+        .create(&rollout, Utc::now())
+        .await
+        .unwrap();
+    update_rollout_repo
+        .create(&next_rollout, Utc::now())
+        .await
+        .unwrap();
+    update_rollout_repo
+        .finish(&rollout.component_id, rollout.target_version)
+        .await
+        .unwrap();
+
+    let unfinished = update_rollout_repo.get_unfinished().await.unwrap();
+    assert!(!unfinished.contains(&rollout));
+    assert!(unfinished.contains(&next_rollout));
+
+    // Starting a finished rollout again makes it unfinished
+    update_rollout_repo
+        .create(&rollout, Utc::now())
+        .await
+        .unwrap();
+
+    let unfinished = update_rollout_repo.get_unfinished().await.unwrap();
+    assert!(unfinished.contains(&rollout));
 }
 
 async fn test_deployment(
@@ -537,6 +750,207 @@ async fn test_deployment_conflict(
     );
 }
 
+async fn test_api_keys(
+    definition_service: Arc<
+        dyn ApiDefinitionService<EmptyAuthCtx, DefaultNamespace, RouteValidationError>
+            + Sync
+            + Send,
+    >,
+    deployment_service: Arc<dyn ApiDeploymentService<DefaultNamespace> + Sync + Send>,
+    api_key_service: Arc<dyn ApiKeyService<DefaultNamespace> + Sync + Send>,
+) {
+    let namespace = DefaultNamespace::default();
+    let site = ApiSiteString("test-keys.com".to_string());
+
+    let def = get_api_definition(
+        &Uuid::new_v4().to_string(),
+        "0.0.1",
+        "/api/get1",
+        "\"worker1\"",
+        "${ {body: golem:it/api.{get-cart-contents}(\"foo\")} }",
+        false,
+    );
+
+    let result = api_key_service.create(&namespace, &site).await;
+    assert!(matches!(
+        result,
+        Err(ApiKeyError::ApiDeploymentNotFound(_, _))
+    ));
+
+    definition_service
+        .create(&def, &namespace, &EmptyAuthCtx::default())
+        .await
+        .unwrap();
+    let deployment = get_api_deployment("test-keys.com", None, vec![&def.id.0]);
+    deployment_service.deploy(&deployment).await.unwrap();
+
+    // Sites are open until they get their first key
+    assert!(api_key_service.authorize(&site, None).await.is_ok());
+
+    let key1 = api_key_service.create(&namespace, &site).await.unwrap();
+    let key2 = api_key_service.create(&namespace, &site).await.unwrap();
+    assert_ne!(key1.secret, key2.secret);
+
+    assert!(matches!(
+        api_key_service.authorize(&site, None).await,
+        Err(ApiKeyError::MissingApiKey)
+    ));
+    assert!(matches!(
+        api_key_service.authorize(&site, Some("invalid")).await,
+        Err(ApiKeyError::InvalidApiKey)
+    ));
+    assert!(api_key_service
+        .authorize(&site, Some(&key1.secret))
+        .await
+        .is_ok());
+    assert!(api_key_service
+        .authorize(&site, Some(&key1.secret))
+        .await
+        .is_ok());
+    assert!(api_key_service
+        .authorize(&site, Some(&key2.secret))
+        .await
+        .is_ok());
+
+    let usage1 = api_key_service
+        .get_usage(&namespace, &key1.api_key.id)
+        .await
+        .unwrap();
+    assert_eq!(usage1.request_count, 2);
+    assert!(usage1.last_used_at.is_some());
+
+    api_key_service
+        .revoke(&namespace, &key1.api_key.id)
+        .await
+        .unwrap();
+    assert!(matches!(
+        api_key_service.authorize(&site, Some(&key1.secret)).await,
+        Err(ApiKeyError::InvalidApiKey)
+    ));
+    assert!(api_key_service
+        .authorize(&site, Some(&key2.secret))
+        .await
+        .is_ok());
+
+    let keys = api_key_service.get_all(&namespace, &site).await.unwrap();
+    assert_eq!(keys.len(), 2);
+    assert!(keys
+        .iter()
+        .any(|key| key.id == key1.api_key.id && key.revoked_at.is_some()));
+    assert!(keys
+        .iter()
+        .any(|key| key.id == key2.api_key.id && key.revoked_at.is_none()));
+}
+
+async fn test_api_projects(
+    definition_service: Arc<
+        dyn ApiDefinitionService<EmptyAuthCtx, DefaultNamespace, RouteValidationError>
+            + Sync
+            + Send,
+    >,
+    deployment_service: Arc<dyn ApiDeploymentService<DefaultNamespace> + Sync + Send>,
+    api_project_service: Arc<dyn ApiProjectService<EmptyAuthCtx, DefaultNamespace> + Sync + Send>,
+    api_project_repo: Arc<dyn api_project::ApiProjectRepo + Sync + Send>,
+) {
+    let namespace = DefaultNamespace::default();
+    let auth_ctx = EmptyAuthCtx::default();
+    let project1 = ProjectId::new_v4();
+    let project2 = ProjectId::new_v4();
+    let site = ApiSiteString("test-projects.com".to_string());
+
+    let def = get_api_definition(
+        &Uuid::new_v4().to_string(),
+        "0.0.1",
+        "/api/get1",
+        "\"worker1\"",
+        "${ {body: golem:it/api.{get-cart-contents}(\"foo\")} }",
+        false,
+    );
+
+    let result = api_project_service
+        .assign_definition(&namespace, &project1, &def.id, &auth_ctx)
+        .await;
+    assert!(matches!(
+        result,
+        Err(ApiProjectError::ApiDefinitionNotFound(_))
+    ));
+
+    definition_service
+        .create(&def, &namespace, &auth_ctx)
+        .await
+        .unwrap();
+    let deployment = get_api_deployment("test-projects.com", None, vec![&def.id.0]);
+    deployment_service.deploy(&deployment).await.unwrap();
+
+    api_project_service
+        .assign_definition(&namespace, &project1, &def.id, &auth_ctx)
+        .await
+        .unwrap();
+    api_project_service
+        .assign_deployment(&namespace, &project1, &site, &auth_ctx)
+        .await
+        .unwrap();
+
+    let definition_ids = api_project_service
+        .get_definition_ids(&namespace, &project1, &auth_ctx)
+        .await
+        .unwrap();
+    assert_eq!(definition_ids, vec![def.id.clone()]);
+    let sites = api_project_service
+        .get_deployment_sites(&namespace, &project1, &auth_ctx)
+        .await
+        .unwrap();
+    assert_eq!(sites, vec![site.clone()]);
+
+    // Assigning to another project moves the API definition
+    api_project_service
+        .assign_definition(&namespace, &project2, &def.id, &auth_ctx)
+        .await
+        .unwrap();
+    let definition_ids = api_project_service
+        .get_definition_ids(&namespace, &project1, &auth_ctx)
+        .await
+        .unwrap();
+    assert!(definition_ids.is_empty());
+
+    let result = api_project_service
+        .unassign_definition(&namespace, &project1, &def.id)
+        .await;
+    assert!(matches!(
+        result,
+        Err(ApiProjectError::ApiDefinitionNotInProject(_, _))
+    ));
+    api_project_service
+        .unassign_deployment(&namespace, &project1, &site)
+        .await
+        .unwrap();
+    let sites = api_project_service
+        .get_deployment_sites(&namespace, &project1, &auth_ctx)
+        .await
+        .unwrap();
+    assert!(sites.is_empty());
+
+    // Deleting a project removes all its assignments
+    api_project_service
+        .assign_deployment(&namespace, &project2, &site, &auth_ctx)
+        .await
+        .unwrap();
+    api_project_repo
+        .delete_project(&namespace.to_string(), &project2.to_string())
+        .await
+        .unwrap();
+    let definition_ids = api_project_service
+        .get_definition_ids(&namespace, &project2, &auth_ctx)
+        .await
+        .unwrap();
+    assert!(definition_ids.is_empty());
+    let sites = api_project_service
+        .get_deployment_sites(&namespace, &project2, &auth_ctx)
+        .await
+        .unwrap();
+    assert!(sites.is_empty());
+}
+
 async fn test_definition_crud(
     definition_service: Arc<
         dyn ApiDefinitionService<EmptyAuthCtx, DefaultNamespace, RouteValidationError>
@@ -0,0 +1,15 @@
+CREATE TABLE api_keys
+(
+    id            text      NOT NULL,
+    namespace     text      NOT NULL,
+    site          text      NOT NULL,
+    key_hash      text      NOT NULL,
+    request_count bigint    NOT NULL DEFAULT 0,
+    last_used_at  timestamp,
+    created_at    timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    revoked_at    timestamp,
+    PRIMARY KEY (id),
+    UNIQUE (key_hash)
+);
+
+CREATE INDEX api_keys_site_idx ON api_keys (site);
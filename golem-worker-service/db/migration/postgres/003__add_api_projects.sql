@@ -0,0 +1,19 @@
+CREATE TABLE api_definition_projects
+(
+    namespace     text NOT NULL,
+    definition_id text NOT NULL,
+    project_id    text NOT NULL,
+    PRIMARY KEY (namespace, definition_id)
+);
+
+CREATE INDEX api_definition_projects_project_id_idx ON api_definition_projects (namespace, project_id);
+
+CREATE TABLE api_deployment_projects
+(
+    namespace  text NOT NULL,
+    site       text NOT NULL,
+    project_id text NOT NULL,
+    PRIMARY KEY (namespace, site)
+);
+
+CREATE INDEX api_deployment_projects_project_id_idx ON api_deployment_projects (namespace, project_id);
@@ -0,0 +1,10 @@
+CREATE TABLE update_rollouts
+(
+    component_id   text    NOT NULL,
+    target_version bigint  NOT NULL,
+    policy         text    NOT NULL,
+    account_id     text,
+    finished       boolean NOT NULL,
+    created_at     timestamp without time zone DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    PRIMARY KEY (component_id, target_version)
+);
@@ -0,0 +1,15 @@
+CREATE TABLE api_keys
+(
+    id            text    NOT NULL,
+    namespace     text    NOT NULL,
+    site          text    NOT NULL,
+    key_hash      text    NOT NULL,
+    request_count integer NOT NULL DEFAULT 0,
+    last_used_at  timestamp without time zone,
+    created_at    timestamp without time zone DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    revoked_at    timestamp without time zone,
+    PRIMARY KEY (id),
+    UNIQUE (key_hash)
+);
+
+CREATE INDEX api_keys_site_idx ON api_keys (site);
@@ -0,0 +1,9 @@
+CREATE TABLE invocation_attempts
+(
+    idempotency_key text NOT NULL,
+    component_id    text NOT NULL,
+    worker_name     text,
+    function_name   text NOT NULL,
+    created_at      timestamp without time zone DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    PRIMARY KEY (idempotency_key)
+);
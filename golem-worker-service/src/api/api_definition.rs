@@ -14,6 +14,7 @@ use golem_worker_service_base::api_definition::http::JsonOpenApiDefinition;
 use golem_worker_service_base::api_definition::{ApiDefinitionId, ApiVersion};
 use golem_worker_service_base::service::api_definition::ApiDefinitionService;
 use golem_worker_service_base::service::http::http_api_definition_validator::RouteValidationError;
+use golem_worker_service_base::service::http::http_response_cache::HttpResponseCache;
 use poem_openapi::param::{Path, Query};
 use poem_openapi::payload::Json;
 use poem_openapi::*;
@@ -25,6 +26,7 @@ pub struct RegisterApiDefinitionApi {
             + Sync
             + Send,
     >,
+    response_cache: Arc<dyn HttpResponseCache + Sync + Send>,
 }
 
 #[OpenApi(prefix_path = "/v1/api/definitions", tag = ApiTags::ApiDefinition)]
@@ -35,8 +37,12 @@ impl RegisterApiDefinitionApi {
                 + Sync
                 + Send,
         >,
+        response_cache: Arc<dyn HttpResponseCache + Sync + Send>,
     ) -> Self {
-        Self { definition_service }
+        Self {
+            definition_service,
+            response_cache,
+        }
     }
 
     /// Upload an OpenAPI definition
@@ -237,6 +243,39 @@ impl RegisterApiDefinitionApi {
         record.result(response)
     }
 
+    /// Purge the cached responses of an API definition
+    ///
+    /// Removes all the responses cached by the gateway for the routes of an API definition,
+    /// so the following requests are served by invoking the workers again.
+    #[oai(
+        path = "/:id/:version/cache",
+        method = "delete",
+        operation_id = "purge_definition_cache"
+    )]
+    async fn purge_cache(
+        &self,
+        id: Path<ApiDefinitionId>,
+        version: Path<ApiVersion>,
+    ) -> Result<Json<String>, ApiEndpointError> {
+        let record = recorded_http_api_request!(
+            "purge_definition_cache",
+            api_definition_id = id.0.to_string(),
+            version = version.0.to_string()
+        );
+
+        let response = {
+            let purged = self
+                .response_cache
+                .purge(&id.0, &version.0)
+                .instrument(record.span.clone())
+                .await
+                .map_err(ApiEndpointError::internal)?;
+
+            Ok(Json(format!("Purged {purged} cached responses")))
+        };
+        record.result(response)
+    }
+
     /// Get or list API definitions
     ///
     /// If `api_definition_id` is specified, returns a single API definition.
@@ -308,14 +347,16 @@ mod test {
     use crate::service::component::ComponentService;
     use async_trait::async_trait;
     use golem_common::config::DbSqliteConfig;
-    use golem_common::model::ComponentId;
+    use golem_common::model::component_observability::ComponentObservability;
+    use golem_common::model::{ComponentId, ProjectId};
     use golem_service_base::db;
-    use golem_service_base::model::Component;
+    use golem_service_base::model::{Component, ComponentUpdatePolicy, Project};
     use golem_worker_service_base::repo::api_definition::{ApiDefinitionRepo, DbApiDefinitionRepo};
     use golem_worker_service_base::repo::api_deployment;
     use golem_worker_service_base::service::api_definition::ApiDefinitionServiceDefault;
     use golem_worker_service_base::service::component::ComponentResult;
     use golem_worker_service_base::service::http::http_api_definition_validator::HttpApiDefinitionValidator;
+    use golem_worker_service_base::service::http::http_response_cache::DisabledHttpResponseCache;
     use http::StatusCode;
     use poem::test::TestClient;
     use std::marker::PhantomData;
@@ -362,6 +403,56 @@ mod test {
         ) -> ComponentResult<Component> {
             unimplemented!()
         }
+
+        async fn get_by_version_alias(
+            &self,
+            _component_id: &ComponentId,
+            _alias: &str,
+            _auth_ctx: &EmptyAuthCtx,
+        ) -> ComponentResult<Component> {
+            unimplemented!()
+        }
+
+        async fn get_observability(
+            &self,
+            _component_id: &ComponentId,
+            _auth_ctx: &EmptyAuthCtx,
+        ) -> ComponentResult<ComponentObservability> {
+            unimplemented!()
+        }
+
+        async fn update_observability(
+            &self,
+            _component_id: &ComponentId,
+            _observability: ComponentObservability,
+            _auth_ctx: &EmptyAuthCtx,
+        ) -> ComponentResult<ComponentObservability> {
+            unimplemented!()
+        }
+
+        async fn get_update_policy(
+            &self,
+            _component_id: &ComponentId,
+            _auth_ctx: &EmptyAuthCtx,
+        ) -> ComponentResult<ComponentUpdatePolicy> {
+            unimplemented!()
+        }
+
+        async fn update_update_policy(
+            &self,
+            _component_id: &ComponentId,
+            _policy: ComponentUpdatePolicy,
+            _auth_ctx: &EmptyAuthCtx,
+        ) -> ComponentResult<ComponentUpdatePolicy> {
+            unimplemented!()
+        }
+        async fn get_project(
+            &self,
+            _project_id: &ProjectId,
+            _auth_ctx: &EmptyAuthCtx,
+        ) -> ComponentResult<Project> {
+            unimplemented!()
+        }
     }
 
     async fn make_route<'c>() -> (poem::Route, SqliteDb<'c>) {
@@ -392,7 +483,10 @@ mod test {
             Arc::new(HttpApiDefinitionValidator {}),
         );
 
-        let endpoint = RegisterApiDefinitionApi::new(Arc::new(definition_service));
+        let endpoint = RegisterApiDefinitionApi::new(
+            Arc::new(definition_service),
+            Arc::new(DisabledHttpResponseCache),
+        );
 
         (
             poem::Route::new().nest("", OpenApiService::new(endpoint, "test", "1.0")),
@@ -411,6 +505,8 @@ mod test {
                 version: ApiVersion("1.0".to_string()),
                 routes: vec![],
                 draft: false,
+                auth: None,
+                cors: None,
             };
 
         let response = client
@@ -441,6 +537,8 @@ mod test {
                 version: ApiVersion("42.0".to_string()),
                 routes: vec![],
                 draft: false,
+                auth: None,
+                cors: None,
             };
 
         let response = client
@@ -466,6 +564,8 @@ mod test {
                 version: ApiVersion("1.0".to_string()),
                 routes: vec![],
                 draft: false,
+                auth: None,
+                cors: None,
             };
         let response = client
             .post("/v1/api/definitions")
@@ -480,6 +580,8 @@ mod test {
                 version: ApiVersion("2.0".to_string()),
                 routes: vec![],
                 draft: false,
+                auth: None,
+                cors: None,
             };
         let response = client
             .post("/v1/api/definitions")
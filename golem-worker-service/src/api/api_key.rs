@@ -0,0 +1,115 @@
+use std::sync::Arc;
+
+use golem_common::recorded_http_api_request;
+use golem_service_base::api_tags::ApiTags;
+use golem_service_base::auth::DefaultNamespace;
+use golem_worker_service_base::api::ApiEndpointError;
+use golem_worker_service_base::api::{ApiKey, ApiKeyRequest, ApiKeyUsage, ApiKeyWithSecret};
+use golem_worker_service_base::api_definition::ApiSiteString;
+use golem_worker_service_base::service::api_key::{ApiKeyId, ApiKeyService};
+use poem_openapi::param::{Path, Query};
+use poem_openapi::payload::Json;
+use poem_openapi::*;
+use tracing::Instrument;
+
+pub struct ApiKeyApi {
+    api_key_service: Arc<dyn ApiKeyService<DefaultNamespace> + Sync + Send>,
+}
+
+#[OpenApi(prefix_path = "/v1/api/keys", tag = ApiTags::ApiKey)]
+impl ApiKeyApi {
+    pub fn new(api_key_service: Arc<dyn ApiKeyService<DefaultNamespace> + Sync + Send>) -> Self {
+        Self { api_key_service }
+    }
+
+    /// Create an API key
+    ///
+    /// Creates a new API key for a deployed site. Once a site has an active API key,
+    /// requests to it are only accepted with a valid key in the `X-Api-Key` header.
+    /// The key is only returned by this call.
+    #[oai(path = "/", method = "post", operation_id = "create_api_key")]
+    async fn create(
+        &self,
+        payload: Json<ApiKeyRequest>,
+    ) -> Result<Json<ApiKeyWithSecret>, ApiEndpointError> {
+        let record =
+            recorded_http_api_request!("create_api_key", site = payload.0.site.to_string());
+        let response = {
+            let api_key = self
+                .api_key_service
+                .create(
+                    &DefaultNamespace::default(),
+                    &ApiSiteString::from(&payload.site),
+                )
+                .instrument(record.span.clone())
+                .await?;
+
+            Ok(Json(api_key.into()))
+        };
+
+        record.result(response)
+    }
+
+    /// List the API keys of a site
+    ///
+    /// Lists both active and revoked API keys of the site.
+    #[oai(path = "/", method = "get", operation_id = "list_api_keys")]
+    async fn list(&self, site: Query<String>) -> Result<Json<Vec<ApiKey>>, ApiEndpointError> {
+        let record = recorded_http_api_request!("list_api_keys", site = site.0);
+        let response = {
+            let values = self
+                .api_key_service
+                .get_all(&DefaultNamespace::default(), &ApiSiteString(site.0))
+                .instrument(record.span.clone())
+                .await?;
+
+            Ok(Json(values.into_iter().map(|v| v.into()).collect()))
+        };
+
+        record.result(response)
+    }
+
+    /// Revoke an API key
+    ///
+    /// Requests using a revoked key are rejected from then on.
+    #[oai(path = "/:key_id", method = "delete", operation_id = "revoke_api_key")]
+    async fn revoke(&self, key_id: Path<ApiKeyId>) -> Result<Json<String>, ApiEndpointError> {
+        let record = recorded_http_api_request!("revoke_api_key", key_id = key_id.0.to_string());
+        let response = {
+            self.api_key_service
+                .revoke(&DefaultNamespace::default(), &key_id.0)
+                .instrument(record.span.clone())
+                .await?;
+
+            Ok(Json("API key revoked".to_string()))
+        };
+
+        record.result(response)
+    }
+
+    /// Get the usage of an API key
+    ///
+    /// Returns the number of requests accepted with the key, and when it was last used.
+    #[oai(
+        path = "/:key_id/usage",
+        method = "get",
+        operation_id = "get_api_key_usage"
+    )]
+    async fn get_usage(
+        &self,
+        key_id: Path<ApiKeyId>,
+    ) -> Result<Json<ApiKeyUsage>, ApiEndpointError> {
+        let record = recorded_http_api_request!("get_api_key_usage", key_id = key_id.0.to_string());
+        let response = {
+            let usage = self
+                .api_key_service
+                .get_usage(&DefaultNamespace::default(), &key_id.0)
+                .instrument(record.span.clone())
+                .await?;
+
+            Ok(Json(usage.into()))
+        };
+
+        record.result(response)
+    }
+}
@@ -0,0 +1,269 @@
+use std::sync::Arc;
+
+use golem_common::model::ProjectId;
+use golem_common::recorded_http_api_request;
+use golem_service_base::api_tags::ApiTags;
+use golem_service_base::auth::{DefaultNamespace, EmptyAuthCtx};
+use golem_worker_service_base::api::ApiDeployment;
+use golem_worker_service_base::api::ApiEndpointError;
+use golem_worker_service_base::api::HttpApiDefinitionWithTypeInfo;
+use golem_worker_service_base::api_definition::{ApiDefinitionId, ApiSiteString};
+use golem_worker_service_base::service::api_definition::ApiDefinitionService;
+use golem_worker_service_base::service::api_deployment::ApiDeploymentService;
+use golem_worker_service_base::service::api_project::ApiProjectService;
+use golem_worker_service_base::service::http::http_api_definition_validator::RouteValidationError;
+use poem_openapi::param::Path;
+use poem_openapi::payload::Json;
+use poem_openapi::*;
+use tracing::Instrument;
+
+pub struct ApiProjectApi {
+    project_service: Arc<dyn ApiProjectService<EmptyAuthCtx, DefaultNamespace> + Sync + Send>,
+    definition_service: Arc<
+        dyn ApiDefinitionService<EmptyAuthCtx, DefaultNamespace, RouteValidationError>
+            + Sync
+            + Send,
+    >,
+    deployment_service: Arc<dyn ApiDeploymentService<DefaultNamespace> + Sync + Send>,
+}
+
+#[OpenApi(prefix_path = "/v1/api/projects", tag = ApiTags::Project)]
+impl ApiProjectApi {
+    pub fn new(
+        project_service: Arc<dyn ApiProjectService<EmptyAuthCtx, DefaultNamespace> + Sync + Send>,
+        definition_service: Arc<
+            dyn ApiDefinitionService<EmptyAuthCtx, DefaultNamespace, RouteValidationError>
+                + Sync
+                + Send,
+        >,
+        deployment_service: Arc<dyn ApiDeploymentService<DefaultNamespace> + Sync + Send>,
+    ) -> Self {
+        Self {
+            project_service,
+            definition_service,
+            deployment_service,
+        }
+    }
+
+    /// List the API definitions of a project
+    ///
+    /// Returns all the versions of the API definitions assigned to the project.
+    #[oai(
+        path = "/:project_id/definitions",
+        method = "get",
+        operation_id = "list_project_definitions"
+    )]
+    async fn list_definitions(
+        &self,
+        project_id: Path<ProjectId>,
+    ) -> Result<Json<Vec<HttpApiDefinitionWithTypeInfo>>, ApiEndpointError> {
+        let record = recorded_http_api_request!(
+            "list_project_definitions",
+            project_id = project_id.0.to_string()
+        );
+        let response = {
+            let ids = self
+                .project_service
+                .get_definition_ids(
+                    &DefaultNamespace::default(),
+                    &project_id.0,
+                    &EmptyAuthCtx::default(),
+                )
+                .instrument(record.span.clone())
+                .await?;
+
+            let mut values = Vec::new();
+            for id in ids {
+                let definitions = self
+                    .definition_service
+                    .get_all_versions(&id, &DefaultNamespace::default(), &EmptyAuthCtx::default())
+                    .instrument(record.span.clone())
+                    .await?;
+                values.extend(
+                    definitions
+                        .into_iter()
+                        .map(HttpApiDefinitionWithTypeInfo::from),
+                );
+            }
+
+            Ok(Json(values))
+        };
+
+        record.result(response)
+    }
+
+    /// Assign an API definition to a project
+    ///
+    /// All versions of the API definition belong to the project. An API definition belongs to at
+    /// most one project, so it is moved if it was assigned to another project before.
+    #[oai(
+        path = "/:project_id/definitions/:id",
+        method = "put",
+        operation_id = "assign_project_definition"
+    )]
+    async fn assign_definition(
+        &self,
+        project_id: Path<ProjectId>,
+        id: Path<ApiDefinitionId>,
+    ) -> Result<Json<String>, ApiEndpointError> {
+        let record = recorded_http_api_request!(
+            "assign_project_definition",
+            project_id = project_id.0.to_string(),
+            api_definition_id = id.0.to_string()
+        );
+        let response = {
+            self.project_service
+                .assign_definition(
+                    &DefaultNamespace::default(),
+                    &project_id.0,
+                    &id.0,
+                    &EmptyAuthCtx::default(),
+                )
+                .instrument(record.span.clone())
+                .await?;
+
+            Ok(Json("API definition assigned to project".to_string()))
+        };
+
+        record.result(response)
+    }
+
+    /// Remove an API definition from a project
+    #[oai(
+        path = "/:project_id/definitions/:id",
+        method = "delete",
+        operation_id = "unassign_project_definition"
+    )]
+    async fn unassign_definition(
+        &self,
+        project_id: Path<ProjectId>,
+        id: Path<ApiDefinitionId>,
+    ) -> Result<Json<String>, ApiEndpointError> {
+        let record = recorded_http_api_request!(
+            "unassign_project_definition",
+            project_id = project_id.0.to_string(),
+            api_definition_id = id.0.to_string()
+        );
+        let response = {
+            self.project_service
+                .unassign_definition(&DefaultNamespace::default(), &project_id.0, &id.0)
+                .instrument(record.span.clone())
+                .await?;
+
+            Ok(Json("API definition removed from project".to_string()))
+        };
+
+        record.result(response)
+    }
+
+    /// List the API deployments of a project
+    #[oai(
+        path = "/:project_id/deployments",
+        method = "get",
+        operation_id = "list_project_deployments"
+    )]
+    async fn list_deployments(
+        &self,
+        project_id: Path<ProjectId>,
+    ) -> Result<Json<Vec<ApiDeployment>>, ApiEndpointError> {
+        let record = recorded_http_api_request!(
+            "list_project_deployments",
+            project_id = project_id.0.to_string()
+        );
+        let response = {
+            let sites = self
+                .project_service
+                .get_deployment_sites(
+                    &DefaultNamespace::default(),
+                    &project_id.0,
+                    &EmptyAuthCtx::default(),
+                )
+                .instrument(record.span.clone())
+                .await?;
+
+            let mut values = Vec::new();
+            for site in sites {
+                if let Some(deployment) = self
+                    .deployment_service
+                    .get_by_site(&site)
+                    .instrument(record.span.clone())
+                    .await?
+                {
+                    values.push(deployment.into());
+                }
+            }
+
+            Ok(Json(values))
+        };
+
+        record.result(response)
+    }
+
+    /// Assign an API deployment to a project
+    ///
+    /// An API deployment belongs to at most one project, so it is moved if it was assigned to
+    /// another project before.
+    #[oai(
+        path = "/:project_id/deployments/:site",
+        method = "put",
+        operation_id = "assign_project_deployment"
+    )]
+    async fn assign_deployment(
+        &self,
+        project_id: Path<ProjectId>,
+        site: Path<String>,
+    ) -> Result<Json<String>, ApiEndpointError> {
+        let record = recorded_http_api_request!(
+            "assign_project_deployment",
+            project_id = project_id.0.to_string(),
+            site = site.0
+        );
+        let response = {
+            self.project_service
+                .assign_deployment(
+                    &DefaultNamespace::default(),
+                    &project_id.0,
+                    &ApiSiteString(site.0),
+                    &EmptyAuthCtx::default(),
+                )
+                .instrument(record.span.clone())
+                .await?;
+
+            Ok(Json("API deployment assigned to project".to_string()))
+        };
+
+        record.result(response)
+    }
+
+    /// Remove an API deployment from a project
+    #[oai(
+        path = "/:project_id/deployments/:site",
+        method = "delete",
+        operation_id = "unassign_project_deployment"
+    )]
+    async fn unassign_deployment(
+        &self,
+        project_id: Path<ProjectId>,
+        site: Path<String>,
+    ) -> Result<Json<String>, ApiEndpointError> {
+        let record = recorded_http_api_request!(
+            "unassign_project_deployment",
+            project_id = project_id.0.to_string(),
+            site = site.0
+        );
+        let response = {
+            self.project_service
+                .unassign_deployment(
+                    &DefaultNamespace::default(),
+                    &project_id.0,
+                    &ApiSiteString(site.0),
+                )
+                .instrument(record.span.clone())
+                .await?;
+
+            Ok(Json("API deployment removed from project".to_string()))
+        };
+
+        record.result(response)
+    }
+}
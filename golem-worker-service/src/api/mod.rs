@@ -1,12 +1,21 @@
 pub mod api_definition;
 pub mod api_deployment;
+pub mod api_key;
+pub mod api_project;
+pub mod rib;
+pub mod scheduled_actions;
+pub mod shared_kv;
+pub mod topics;
+pub mod usage;
 pub mod worker;
 pub mod worker_connect;
 
 use crate::api::worker::WorkerApi;
 use crate::service::Services;
+use golem_worker_service_base::api::ApiKeyMiddleware;
 use golem_worker_service_base::api::CustomHttpRequestApi;
 use golem_worker_service_base::api::HealthcheckApi;
+use golem_worker_service_base::app_config::MultipartConfig;
 use poem::endpoint::PrometheusExporter;
 use poem::{get, EndpointExt, Route};
 use poem_openapi::OpenApiService;
@@ -16,8 +25,15 @@ use std::sync::Arc;
 
 type ApiServices = (
     WorkerApi,
+    shared_kv::SharedKeyValueApi,
+    topics::TopicsApi,
+    usage::UsageApi,
+    scheduled_actions::ScheduledActionsApi,
     api_definition::RegisterApiDefinitionApi,
     api_deployment::ApiDeploymentApi,
+    api_key::ApiKeyApi,
+    api_project::ApiProjectApi,
+    rib::RibApi,
     HealthcheckApi,
 );
 
@@ -41,13 +57,22 @@ pub fn combined_routes(prometheus_registry: Arc<Registry>, services: &Services)
         )
 }
 
-pub fn custom_request_route(services: Services) -> Route {
+pub fn custom_request_route(services: Services, multipart_config: MultipartConfig) -> Route {
     let custom_request_executor = CustomHttpRequestApi::new(
         services.worker_to_http_service,
-        services.http_definition_lookup_service,
+        services.http_definition_lookup_service.clone(),
+        services.jwt_validator,
+        services.response_cache,
+        multipart_config,
     );
 
-    Route::new().nest("/", custom_request_executor)
+    Route::new().nest(
+        "/",
+        custom_request_executor.with(ApiKeyMiddleware::new(
+            services.api_key_service,
+            services.http_definition_lookup_service,
+        )),
+    )
 }
 
 pub fn make_open_api_service(services: &Services) -> OpenApiService<ApiServices, ()> {
@@ -56,9 +81,32 @@ pub fn make_open_api_service(services: &Services) -> OpenApiService<ApiServices,
             worker::WorkerApi {
                 component_service: services.component_service.clone(),
                 worker_service: services.worker_service.clone(),
+                update_rollout_service: services.update_rollout_service.clone(),
+            },
+            shared_kv::SharedKeyValueApi {
+                worker_service: services.worker_service.clone(),
+            },
+            topics::TopicsApi {
+                worker_service: services.worker_service.clone(),
+            },
+            usage::UsageApi {
+                worker_service: services.worker_service.clone(),
+            },
+            scheduled_actions::ScheduledActionsApi {
+                worker_service: services.worker_service.clone(),
             },
-            api_definition::RegisterApiDefinitionApi::new(services.definition_service.clone()),
+            api_definition::RegisterApiDefinitionApi::new(
+                services.definition_service.clone(),
+                services.response_cache.clone(),
+            ),
             api_deployment::ApiDeploymentApi::new(services.deployment_service.clone()),
+            api_key::ApiKeyApi::new(services.api_key_service.clone()),
+            api_project::ApiProjectApi::new(
+                services.api_project_service.clone(),
+                services.definition_service.clone(),
+                services.deployment_service.clone(),
+            ),
+            rib::RibApi::new(services.rib_evaluation_service.clone()),
             HealthcheckApi,
         ),
         "Golem API",
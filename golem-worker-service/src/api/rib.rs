@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use golem_common::recorded_http_api_request;
+use golem_service_base::api_tags::ApiTags;
+use golem_service_base::auth::EmptyAuthCtx;
+use golem_worker_service_base::api::ApiEndpointError;
+use golem_worker_service_base::api::{RibEvaluationRequest, RibEvaluationResponse};
+use golem_worker_service_base::service::rib_evaluation::RibEvaluationService;
+use poem_openapi::payload::Json;
+use poem_openapi::*;
+use tracing::Instrument;
+
+pub struct RibApi {
+    rib_evaluation_service: Arc<dyn RibEvaluationService<EmptyAuthCtx> + Sync + Send>,
+}
+
+#[OpenApi(prefix_path = "/v1/rib", tag = ApiTags::ApiDefinition)]
+impl RibApi {
+    pub fn new(
+        rib_evaluation_service: Arc<dyn RibEvaluationService<EmptyAuthCtx> + Sync + Send>,
+    ) -> Self {
+        Self {
+            rib_evaluation_service,
+        }
+    }
+
+    /// Evaluate a Rib script
+    ///
+    /// Evaluates a Rib script with sample input, such as a sample `request`, to try out the
+    /// expressions of API definition bindings without deploying them. Calls to the functions of
+    /// the given component are type checked, and invoked on the given worker.
+    /// Syntax, compilation and runtime errors are returned in the `error` field of the response.
+    #[oai(path = "/evaluate", method = "post", operation_id = "evaluate_rib")]
+    async fn evaluate(
+        &self,
+        payload: Json<RibEvaluationRequest>,
+    ) -> Result<Json<RibEvaluationResponse>, ApiEndpointError> {
+        let record = recorded_http_api_request!(
+            "evaluate_rib",
+            component_id = payload.0.component_id.as_ref().map(|id| id.to_string()),
+            worker_name = payload.0.worker_name.clone(),
+        );
+
+        let response = {
+            let input = payload.0.input.clone().unwrap_or_default();
+
+            let result = self
+                .rib_evaluation_service
+                .evaluate(
+                    &payload.0.rib,
+                    &input,
+                    payload.0.component(),
+                    &EmptyAuthCtx::default(),
+                )
+                .instrument(record.span.clone())
+                .await?;
+
+            Ok(Json(result.into()))
+        };
+
+        record.result(response)
+    }
+}
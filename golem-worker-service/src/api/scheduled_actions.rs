@@ -0,0 +1,132 @@
+use crate::empty_worker_metadata;
+use crate::service::worker::WorkerService;
+use golem_common::model::{Timestamp, WorkerId};
+use golem_common::recorded_http_api_request;
+use golem_service_base::api_tags::ApiTags;
+use golem_service_base::auth::EmptyAuthCtx;
+use golem_service_base::model::*;
+use golem_worker_service_base::api::WorkerApiBaseError;
+use poem_openapi::param::Query;
+use poem_openapi::payload::Json;
+use poem_openapi::*;
+use std::str::FromStr;
+use tracing::Instrument;
+
+pub struct ScheduledActionsApi {
+    pub worker_service: WorkerService,
+}
+
+type Result<T> = std::result::Result<T, WorkerApiBaseError>;
+
+#[OpenApi(prefix_path = "/v1/scheduled-actions", tag = ApiTags::Worker)]
+impl ScheduledActionsApi {
+    /// List the pending scheduled actions
+    ///
+    /// Returns the promise completions and oplog archivals waiting in the scheduler of the worker executors, in the order of their due time. Every given filter has to match.
+    /// The result is paged: pass the returned `cursor` to get the next page, an empty cursor means there are no more actions.
+    #[oai(path = "/", method = "get", operation_id = "list_scheduled_actions")]
+    async fn list_scheduled_actions(
+        &self,
+        /// Only the actions of this worker, given as URN or id
+        #[oai(name = "worker-id")]
+        worker_id: Query<Option<String>>,
+        /// Only the actions of this kind
+        kind: Query<Option<ScheduledActionKind>>,
+        /// Only the actions due at or after this time
+        #[oai(name = "due-after")]
+        due_after: Query<Option<Timestamp>>,
+        /// Only the actions due at or before this time
+        #[oai(name = "due-before")]
+        due_before: Query<Option<Timestamp>>,
+        /// Cursor returned by the previous page
+        cursor: Query<Option<ScheduledActionCursor>>,
+        /// Maximum number of actions to return, defaults to 100
+        count: Query<Option<u64>>,
+    ) -> Result<Json<PendingScheduledActionsResponse>> {
+        let record = recorded_http_api_request!("list_scheduled_actions",);
+
+        let response = match make_filter(worker_id.0, kind.0, due_after.0, due_before.0) {
+            Ok(filter) => self
+                .worker_service
+                .list_scheduled_actions(
+                    filter,
+                    cursor.0,
+                    count.0.unwrap_or(100),
+                    empty_worker_metadata(),
+                    &EmptyAuthCtx::default(),
+                )
+                .instrument(record.span.clone())
+                .await
+                .map_err(|e| e.into())
+                .map(|(cursor, actions)| Json(PendingScheduledActionsResponse { actions, cursor })),
+            Err(err) => Err(err),
+        };
+
+        record.result(response)
+    }
+
+    /// Cancel pending scheduled actions
+    ///
+    /// Removes the pending scheduled actions matching all the given filters from the scheduler and returns them. A cancelled promise completion leaves the promise incomplete.
+    #[oai(
+        path = "/",
+        method = "delete",
+        operation_id = "cancel_scheduled_actions"
+    )]
+    async fn cancel_scheduled_actions(
+        &self,
+        /// Only the actions of this worker, given as URN or id
+        #[oai(name = "worker-id")]
+        worker_id: Query<Option<String>>,
+        /// Only the actions of this kind
+        kind: Query<Option<ScheduledActionKind>>,
+        /// Only the actions due at or after this time
+        #[oai(name = "due-after")]
+        due_after: Query<Option<Timestamp>>,
+        /// Only the actions due at or before this time
+        #[oai(name = "due-before")]
+        due_before: Query<Option<Timestamp>>,
+    ) -> Result<Json<PendingScheduledActionsResponse>> {
+        let record = recorded_http_api_request!("cancel_scheduled_actions",);
+
+        let response = match make_filter(worker_id.0, kind.0, due_after.0, due_before.0) {
+            Ok(filter) => self
+                .worker_service
+                .cancel_scheduled_actions(filter, empty_worker_metadata(), &EmptyAuthCtx::default())
+                .instrument(record.span.clone())
+                .await
+                .map_err(|e| e.into())
+                .map(|actions| {
+                    Json(PendingScheduledActionsResponse {
+                        actions,
+                        cursor: None,
+                    })
+                }),
+            Err(err) => Err(err),
+        };
+
+        record.result(response)
+    }
+}
+
+fn make_filter(
+    worker_id: Option<String>,
+    kind: Option<ScheduledActionKind>,
+    due_after: Option<Timestamp>,
+    due_before: Option<Timestamp>,
+) -> Result<ScheduledActionFilter> {
+    let worker_id = worker_id
+        .map(|worker_id| WorkerId::from_str(&worker_id))
+        .transpose()
+        .map_err(|err| {
+            WorkerApiBaseError::BadRequest(Json(ErrorsBody {
+                errors: vec![format!("Invalid worker id: {err}")],
+            }))
+        })?;
+    Ok(ScheduledActionFilter {
+        worker_id,
+        kind,
+        due_after,
+        due_before,
+    })
+}
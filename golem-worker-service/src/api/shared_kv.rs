@@ -0,0 +1,146 @@
+use crate::empty_worker_metadata;
+use crate::service::worker::WorkerService;
+use golem_common::recorded_http_api_request;
+use golem_service_base::api_tags::ApiTags;
+use golem_service_base::auth::EmptyAuthCtx;
+use golem_service_base::model::*;
+use golem_worker_service_base::api::WorkerApiBaseError;
+use poem_openapi::param::{Path, Query};
+use poem_openapi::payload::Json;
+use poem_openapi::*;
+use tracing::Instrument;
+
+pub struct SharedKeyValueApi {
+    pub worker_service: WorkerService,
+}
+
+type Result<T> = std::result::Result<T, WorkerApiBaseError>;
+
+#[OpenApi(prefix_path = "/v1/shared-kv", tag = ApiTags::Worker)]
+impl SharedKeyValueApi {
+    /// List the keys of the shared key-value store
+    ///
+    /// Returns the keys of the key-value store shared by all the workers of the account, in lexicographic order.
+    #[oai(path = "/", method = "get", operation_id = "list_shared_keys")]
+    async fn list_shared_keys(
+        &self,
+        /// Only return the keys starting with this prefix
+        prefix: Query<Option<String>>,
+    ) -> Result<Json<SharedKeysResponse>> {
+        let prefix = prefix.0.unwrap_or_default();
+        let record = recorded_http_api_request!("list_shared_keys", prefix = prefix);
+
+        let response = self
+            .worker_service
+            .list_shared_keys(&prefix, empty_worker_metadata(), &EmptyAuthCtx::default())
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|keys| Json(SharedKeysResponse { keys }));
+
+        record.result(response)
+    }
+
+    /// Get an entry of the shared key-value store
+    ///
+    /// Returns the value of the key together with its current version.
+    #[oai(path = "/:key", method = "get", operation_id = "get_shared_value")]
+    async fn get_shared_value(&self, key: Path<String>) -> Result<Json<SharedKeyValueEntry>> {
+        let record = recorded_http_api_request!("get_shared_value", key = key.0);
+
+        let response = self
+            .worker_service
+            .get_shared_value(&key.0, empty_worker_metadata(), &EmptyAuthCtx::default())
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .and_then(|entry| match entry {
+                Some(entry) => Ok(Json(entry)),
+                None => Err(WorkerApiBaseError::NotFound(Json(ErrorBody {
+                    error: format!("Shared key {} not found", key.0),
+                }))),
+            });
+
+        record.result(response)
+    }
+
+    /// Set an entry of the shared key-value store
+    ///
+    /// Sets the value of the key and returns its new version. If `expectedVersion` is given, the value is only updated if it matches the current version of the entry, otherwise the request fails with a conflict. Version 0 expects the key to not exist yet.
+    #[oai(path = "/:key", method = "put", operation_id = "set_shared_value")]
+    async fn set_shared_value(
+        &self,
+        key: Path<String>,
+        request: Json<SetSharedValueRequest>,
+    ) -> Result<Json<SetSharedValueResponse>> {
+        let record = recorded_http_api_request!("set_shared_value", key = key.0);
+
+        let SetSharedValueRequest {
+            value,
+            expected_version,
+        } = request.0;
+        let response = self
+            .worker_service
+            .set_shared_value(
+                &key.0,
+                value.0,
+                expected_version,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .and_then(|version| match version {
+                Some(version) => Ok(Json(SetSharedValueResponse { version })),
+                None => Err(version_mismatch(&key.0)),
+            });
+
+        record.result(response)
+    }
+
+    /// Delete an entry of the shared key-value store
+    ///
+    /// Deletes the key, doing nothing if it does not exist. If `expected_version` is given, the key is only deleted if it matches the current version of the entry, otherwise the request fails with a conflict.
+    #[oai(
+        path = "/:key",
+        method = "delete",
+        operation_id = "delete_shared_value"
+    )]
+    async fn delete_shared_value(
+        &self,
+        key: Path<String>,
+        expected_version: Query<Option<u64>>,
+    ) -> Result<Json<DeleteSharedValueResponse>> {
+        let record = recorded_http_api_request!("delete_shared_value", key = key.0);
+
+        let response = self
+            .worker_service
+            .delete_shared_value(
+                &key.0,
+                expected_version.0,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .and_then(|deleted| {
+                if deleted {
+                    Ok(Json(DeleteSharedValueResponse {}))
+                } else {
+                    Err(version_mismatch(&key.0))
+                }
+            });
+
+        record.result(response)
+    }
+}
+
+fn version_mismatch(key: &str) -> WorkerApiBaseError {
+    WorkerApiBaseError::AlreadyExists(Json(ErrorBody {
+        error: format!(
+            "The current version of shared key {key} does not match the expected version"
+        ),
+    }))
+}
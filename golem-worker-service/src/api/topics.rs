@@ -0,0 +1,61 @@
+use crate::empty_worker_metadata;
+use crate::service::worker::WorkerService;
+use golem_common::recorded_http_api_request;
+use golem_service_base::api_tags::ApiTags;
+use golem_service_base::auth::EmptyAuthCtx;
+use golem_service_base::model::*;
+use golem_worker_service_base::api::WorkerApiBaseError;
+use poem_openapi::param::Path;
+use poem_openapi::payload::Json;
+use poem_openapi::*;
+use tracing::Instrument;
+
+pub struct TopicsApi {
+    pub worker_service: WorkerService,
+}
+
+type Result<T> = std::result::Result<T, WorkerApiBaseError>;
+
+#[OpenApi(prefix_path = "/v1/topics", tag = ApiTags::Worker)]
+impl TopicsApi {
+    /// List the publish/subscribe topics
+    ///
+    /// Returns the names of the topics workers of the account published to or subscribed to, in lexicographic order.
+    #[oai(path = "/", method = "get", operation_id = "list_topics")]
+    async fn list_topics(&self) -> Result<Json<TopicsResponse>> {
+        let record = recorded_http_api_request!("list_topics",);
+
+        let response = self
+            .worker_service
+            .list_topics(empty_worker_metadata(), &EmptyAuthCtx::default())
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|topics| Json(TopicsResponse { topics }));
+
+        record.result(response)
+    }
+
+    /// Get a publish/subscribe topic
+    ///
+    /// Returns the sequence number of the last published message, the number of retained messages and the subscribers of the topic with how far behind each of them is.
+    #[oai(path = "/:topic", method = "get", operation_id = "get_topic")]
+    async fn get_topic(&self, topic: Path<String>) -> Result<Json<TopicInfo>> {
+        let record = recorded_http_api_request!("get_topic", topic = topic.0);
+
+        let response = self
+            .worker_service
+            .get_topic(&topic.0, empty_worker_metadata(), &EmptyAuthCtx::default())
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .and_then(|info| match info {
+                Some(info) => Ok(Json(info)),
+                None => Err(WorkerApiBaseError::NotFound(Json(ErrorBody {
+                    error: format!("Topic {} not found", topic.0),
+                }))),
+            });
+
+        record.result(response)
+    }
+}
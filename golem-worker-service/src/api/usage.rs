@@ -0,0 +1,54 @@
+use crate::empty_worker_metadata;
+use crate::service::worker::WorkerService;
+use golem_common::model::Timestamp;
+use golem_common::recorded_http_api_request;
+use golem_service_base::api_tags::ApiTags;
+use golem_service_base::auth::EmptyAuthCtx;
+use golem_service_base::model::*;
+use golem_worker_service_base::api::WorkerApiBaseError;
+use poem_openapi::param::Query;
+use poem_openapi::payload::Json;
+use poem_openapi::*;
+use tracing::Instrument;
+
+pub struct UsageApi {
+    pub worker_service: WorkerService,
+}
+
+type Result<T> = std::result::Result<T, WorkerApiBaseError>;
+
+#[OpenApi(prefix_path = "/v1/usage", tag = ApiTags::Worker)]
+impl UsageApi {
+    /// Get the usage of the account
+    ///
+    /// Returns the invocations, memory and oplog usage of the workers of the account in each billing period starting in the given time range, and their sum.
+    #[oai(path = "/", method = "get", operation_id = "get_account_usage")]
+    async fn get_account_usage(
+        &self,
+        /// Start of the time range, inclusive
+        from: Query<Timestamp>,
+        /// End of the time range, exclusive
+        to: Query<Timestamp>,
+    ) -> Result<Json<AccountUsageResponse>> {
+        let record = recorded_http_api_request!(
+            "get_account_usage",
+            from = from.0.to_string(),
+            to = to.0.to_string()
+        );
+
+        let response = self
+            .worker_service
+            .get_account_usage(
+                from.0,
+                to.0,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|records| Json(AccountUsageResponse::new(records)));
+
+        record.result(response)
+    }
+}
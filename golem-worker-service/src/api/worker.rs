@@ -1,30 +1,87 @@
-use crate::empty_worker_metadata;
-use crate::service::{component::ComponentService, worker::WorkerService};
+use crate::service::{
+    component::ComponentService,
+    worker::{UpdateRolloutService, WorkerService},
+};
+use crate::{empty_worker_metadata, pin_to_version_alias};
+use futures::StreamExt;
+use golem_api_grpc::proto::golem::worker::InvocationContext;
+use golem_api_grpc::proto::golem::workerexecutor::v1::{
+    invoke_and_stream_worker_response, InvokeAndStreamWorkerResponse,
+};
 use golem_common::model::{
-    ComponentId, IdempotencyKey, ScanCursor, TargetWorkerId, WorkerFilter, WorkerId,
+    ComponentId, IdempotencyKey, InvocationPriority, ScanCursor, TargetWorkerId, WorkerFilter,
+    WorkerId, WorkerName, WorkerPlacementHint,
 };
 use golem_common::recorded_http_api_request;
 use golem_service_base::api_tags::ApiTags;
 use golem_service_base::auth::EmptyAuthCtx;
 use golem_service_base::model::*;
+use golem_wasm_rpc::json::TypeAnnotatedValueJsonExtensions;
+use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
 use golem_worker_service_base::api::WorkerApiBaseError;
+use golem_worker_service_base::service::worker::{wave_results, InvocationPollResult};
+use poem::Body;
 use poem_openapi::param::{Header, Path, Query};
-use poem_openapi::payload::Json;
+use poem_openapi::payload::{Binary, Json, PlainText};
 use poem_openapi::*;
+use std::collections::HashMap;
 use std::str::FromStr;
+use std::time::Duration;
 use tap::TapFallible;
 
+use golem_common::model::component_observability::ComponentObservability;
 use golem_common::model::oplog::OplogIndex;
 use golem_common::model::public_oplog::OplogCursor;
+use golem_common::model::public_worker_status::PublicWorkerStatusRecord;
+use golem_common::model::worker_statistics::ComponentWorkerStatistics;
 use tracing::Instrument;
 
 pub struct WorkerApi {
     pub component_service: ComponentService,
     pub worker_service: WorkerService,
+    pub update_rollout_service: UpdateRolloutService,
 }
 
 type Result<T> = std::result::Result<T, WorkerApiBaseError>;
 
+/// Parameters of an invocation, either as JSON or in WAVE
+#[derive(ApiRequest, Debug)]
+pub enum InvokeRequestBody {
+    /// Parameters as type annotated JSON values, with an optional invocation context
+    Json(Json<InvokeParameters>),
+    /// Parameters in WAVE, one per line
+    Wave(PlainText<String>),
+}
+
+/// Results of an invocation, in WAVE if requested by the `Accept` header
+#[derive(ApiResponse, Debug)]
+pub enum InvokeAndAwaitResponse {
+    #[oai(status = 200)]
+    Json(Json<InvokeResult>),
+    #[oai(status = 200)]
+    Wave(PlainText<String>),
+}
+
+/// Result of an invocation awaited until a deadline
+#[derive(ApiResponse, Debug)]
+pub enum InvokeAndPollResponse {
+    /// The invocation finished before the deadline
+    #[oai(status = 200)]
+    Completed(Json<InvokeResult>),
+    /// The invocation is still running, its result can be awaited with the returned token
+    #[oai(status = 202)]
+    Pending(Json<InvocationAwaitToken>),
+}
+
+/// How long the result of an invocation is waited for if the request does not specify a deadline
+const DEFAULT_POLL_DEADLINE: Duration = Duration::from_secs(30);
+
+/// Longest deadline a request can ask for, beyond which the await token has to be used
+const MAX_POLL_DEADLINE: Duration = Duration::from_secs(300);
+
+/// Maximum number of workers whose metadata can be requested in one bulk request
+const MAX_BULK_METADATA_WORKERS: usize = 100;
+
 #[OpenApi(prefix_path = "/v1/components", tag = ApiTags::Worker)]
 impl WorkerApi {
     /// Launch a new worker.
@@ -35,6 +92,7 @@ impl WorkerApi {
     /// - `name` is the name of the created worker. This has to be unique, but only for a given component
     /// - `args` is a list of strings which appear as command line arguments for the worker
     /// - `env` is a list of key-value pairs (represented by arrays) which appear as environment variables for the worker
+    /// - `componentVersionAlias` optionally selects the component version by one of its version aliases, instead of using the latest version. The alias is recorded in the `GOLEM_COMPONENT_VERSION_ALIAS` environment variable of the worker
     #[oai(
         path = "/:component_id/workers",
         method = "post",
@@ -53,29 +111,44 @@ impl WorkerApi {
 
         let response = {
             let component_id = component_id.0;
-            let latest_component = self
-                .component_service
-                .get_latest(&component_id, &EmptyAuthCtx::default())
-                .instrument(record.span.clone())
-                .await
-                .tap_err(|error| tracing::error!("Error getting latest component: {:?}", error))
-                .map_err(|error| {
-                    WorkerApiBaseError::NotFound(Json(ErrorBody {
-                        error: format!(
-                            "Couldn't retrieve the component: {}. error: {}",
-                            &component_id, error
-                        ),
-                    }))
-                })?;
-
-            let WorkerCreationRequest { name, args, env } = request.0;
-
-            let worker_id = make_worker_id(component_id, name)?;
+            let WorkerCreationRequest {
+                name,
+                args,
+                mut env,
+                component_version_alias,
+            } = request.0;
+            pin_to_version_alias(&mut env, &component_version_alias);
+
+            let component = match &component_version_alias {
+                Some(alias) => {
+                    self.component_service
+                        .get_by_version_alias(&component_id, alias, &EmptyAuthCtx::default())
+                        .instrument(record.span.clone())
+                        .await
+                }
+                None => {
+                    self.component_service
+                        .get_latest(&component_id, &EmptyAuthCtx::default())
+                        .instrument(record.span.clone())
+                        .await
+                }
+            }
+            .tap_err(|error| tracing::error!("Error getting component: {:?}", error))
+            .map_err(|error| {
+                WorkerApiBaseError::NotFound(Json(ErrorBody {
+                    error: format!(
+                        "Couldn't retrieve the component: {}. error: {}",
+                        &component_id, error
+                    ),
+                }))
+            })?;
+
+            let worker_id = make_new_worker_id(component_id, name)?;
             let worker_id = self
                 .worker_service
                 .create(
                     &worker_id,
-                    latest_component.versioned_component_id.version,
+                    component.versioned_component_id.version,
                     args,
                     env,
                     empty_worker_metadata(),
@@ -85,7 +158,7 @@ impl WorkerApi {
                 .await?;
             Ok(Json(WorkerCreationResponse {
                 worker_id,
-                component_version: latest_component.versioned_component_id.version,
+                component_version: component.versioned_component_id.version,
             }))
         };
 
@@ -136,10 +209,26 @@ impl WorkerApi {
         &self,
         component_id: Path<ComponentId>,
         #[oai(name = "Idempotency-Key")] idempotency_key: Header<Option<IdempotencyKey>>,
+        request: &poem::Request,
         function: Query<String>,
-        params: Json<InvokeParameters>,
-    ) -> Result<Json<InvokeResult>> {
+        /// Priority of the invocation in the worker's queue - if not specified, the invocation has normal priority.
+        priority: Query<Option<InvocationPriority>>,
+        /// Maximum time to wait for the result in milliseconds - if exceeded, the running invocation is interrupted
+        /// and an `InvocationTimedOut` error is returned. If not specified, the call waits until the invocation completes.
+        timeout_millis: Query<Option<u64>>,
+        /// URN or id of a worker to place the new worker together with, in the same shard, so both always run on the same executor.
+        /// Ignored if the new worker cannot be placed there.
+        affinity: Query<Option<String>>,
+        /// Comma separated URNs or ids of workers to place the new worker apart from, in a shard not used by any of them.
+        /// Ignored if the new worker cannot be placed so.
+        anti_affinity: Query<Option<String>>,
+        /// Requests the results in WAVE, one per line, with `text/plain`
+        #[oai(name = "Accept")]
+        accept: Header<Option<String>>,
+        params: InvokeRequestBody,
+    ) -> Result<InvokeAndAwaitResponse> {
         let worker_id = make_target_worker_id(component_id.0, None)?;
+        let placement_hint = make_placement_hint(affinity.0, anti_affinity.0)?;
 
         let record = recorded_http_api_request!(
             "invoke_and_await_function_without_name",
@@ -148,63 +237,412 @@ impl WorkerApi {
             function = function.0
         );
 
+        let response = async {
+            let worker_id = self.place_new_worker(worker_id, placement_hint).await?;
+            let (params, context) = self
+                .resolve_invoke_parameters(&worker_id, &function.0, params)
+                .await?;
+            let result = self
+                .worker_service
+                .validate_and_invoke_and_await_typed(
+                    &worker_id,
+                    request_idempotency_key(
+                        idempotency_key.0,
+                        request,
+                        &worker_id.component_id,
+                        &function.0,
+                    ),
+                    function.0,
+                    params,
+                    context,
+                    priority.0.unwrap_or_default(),
+                    timeout_millis.0.map(Duration::from_millis),
+                    empty_worker_metadata(),
+                    &EmptyAuthCtx::default(),
+                )
+                .await?;
+            make_invoke_and_await_response(result, accept.0.as_deref())
+        }
+        .instrument(record.span.clone())
+        .await;
+
+        record.result(response)
+    }
+
+    /// Invoke a function with plain JSON parameters and await its resolution on a new worker with a random generated name
+    ///
+    /// Supply the parameters in the request body as plain JSON values, without type annotations.
+    /// They are converted to the parameter types of the function, and the result is returned as
+    /// plain JSON too. Each parameter not matching its type is reported in the errors.
+    #[oai(
+        path = "/:component_id/invoke-and-await-json",
+        method = "post",
+        operation_id = "invoke_and_await_function_json_without_name"
+    )]
+    async fn invoke_and_await_function_json_without_name(
+        &self,
+        component_id: Path<ComponentId>,
+        #[oai(name = "Idempotency-Key")] idempotency_key: Header<Option<IdempotencyKey>>,
+        request: &poem::Request,
+        function: Query<String>,
+        /// Priority of the invocation in the worker's queue - if not specified, the invocation has normal priority.
+        priority: Query<Option<InvocationPriority>>,
+        /// Maximum time to wait for the result in milliseconds - if exceeded, the running invocation is interrupted
+        /// and an `InvocationTimedOut` error is returned. If not specified, the call waits until the invocation completes.
+        timeout_millis: Query<Option<u64>>,
+        /// URN or id of a worker to place the new worker together with, in the same shard, so both always run on the same executor.
+        /// Ignored if the new worker cannot be placed there.
+        affinity: Query<Option<String>>,
+        /// Comma separated URNs or ids of workers to place the new worker apart from, in a shard not used by any of them.
+        /// Ignored if the new worker cannot be placed so.
+        anti_affinity: Query<Option<String>>,
+        params: Json<InvokeJsonParameters>,
+    ) -> Result<Json<InvokeJsonResult>> {
+        let worker_id = make_target_worker_id(component_id.0, None)?;
+        let placement_hint = make_placement_hint(affinity.0, anti_affinity.0)?;
+
+        let record = recorded_http_api_request!(
+            "invoke_and_await_function_json_without_name",
+            worker_id = worker_id.to_string(),
+            idempotency_key = idempotency_key.0.as_ref().map(|v| v.value.clone()),
+            function = function.0
+        );
+
+        let response: Result<Json<InvokeJsonResult>> = async {
+            let worker_id = self.place_new_worker(worker_id, placement_hint).await?;
+            let result = self
+                .worker_service
+                .invoke_and_await_json(
+                    &worker_id,
+                    request_idempotency_key(
+                        idempotency_key.0,
+                        request,
+                        &worker_id.component_id,
+                        &function.0,
+                    ),
+                    function.0,
+                    params.0.params,
+                    make_invocation_context(params.0.context),
+                    priority.0.unwrap_or_default(),
+                    timeout_millis.0.map(Duration::from_millis),
+                    empty_worker_metadata(),
+                    &EmptyAuthCtx::default(),
+                )
+                .await?;
+            Ok(Json(InvokeJsonResult {
+                result: result.to_json_value(),
+            }))
+        }
+        .instrument(record.span.clone())
+        .await;
+
+        record.result(response)
+    }
+
+    /// Invoke a function and await its resolution
+    ///
+    /// Supply the parameters in the request body as JSON.
+    #[oai(
+        path = "/:component_id/workers/:worker_name/invoke-and-await",
+        method = "post",
+        operation_id = "invoke_and_await_function"
+    )]
+    async fn invoke_and_await_function(
+        &self,
+        component_id: Path<ComponentId>,
+        worker_name: Path<String>,
+        #[oai(name = "Idempotency-Key")] idempotency_key: Header<Option<IdempotencyKey>>,
+        request: &poem::Request,
+        function: Query<String>,
+        /// Priority of the invocation in the worker's queue - if not specified, the invocation has normal priority.
+        priority: Query<Option<InvocationPriority>>,
+        /// Maximum time to wait for the result in milliseconds - if exceeded, the running invocation is interrupted
+        /// and an `InvocationTimedOut` error is returned. If not specified, the call waits until the invocation completes.
+        timeout_millis: Query<Option<u64>>,
+        /// Requests the results in WAVE, one per line, with `text/plain`
+        #[oai(name = "Accept")]
+        accept: Header<Option<String>>,
+        params: InvokeRequestBody,
+    ) -> Result<InvokeAndAwaitResponse> {
+        let worker_id = make_target_worker_id(component_id.0, Some(worker_name.0))?;
+
+        let record = recorded_http_api_request!(
+            "invoke_and_await_function",
+            worker_id = worker_id.to_string(),
+            idempotency_key = idempotency_key.0.as_ref().map(|v| v.value.clone()),
+            function = function.0
+        );
+
+        let response = async {
+            let (params, context) = self
+                .resolve_invoke_parameters(&worker_id, &function.0, params)
+                .await?;
+            let result = self
+                .worker_service
+                .validate_and_invoke_and_await_typed(
+                    &worker_id,
+                    request_idempotency_key(
+                        idempotency_key.0,
+                        request,
+                        &worker_id.component_id,
+                        &function.0,
+                    ),
+                    function.0,
+                    params,
+                    context,
+                    priority.0.unwrap_or_default(),
+                    timeout_millis.0.map(Duration::from_millis),
+                    empty_worker_metadata(),
+                    &EmptyAuthCtx::default(),
+                )
+                .await?;
+            make_invoke_and_await_response(result, accept.0.as_deref())
+        }
+        .instrument(record.span.clone())
+        .await;
+
+        record.result(response)
+    }
+
+    /// Invoke a function with plain JSON parameters and await its resolution
+    ///
+    /// Supply the parameters in the request body as plain JSON values, without type annotations.
+    /// They are converted to the parameter types of the function, and the result is returned as
+    /// plain JSON too. Each parameter not matching its type is reported in the errors.
+    #[oai(
+        path = "/:component_id/workers/:worker_name/invoke-and-await-json",
+        method = "post",
+        operation_id = "invoke_and_await_function_json"
+    )]
+    async fn invoke_and_await_function_json(
+        &self,
+        component_id: Path<ComponentId>,
+        worker_name: Path<String>,
+        #[oai(name = "Idempotency-Key")] idempotency_key: Header<Option<IdempotencyKey>>,
+        request: &poem::Request,
+        function: Query<String>,
+        /// Priority of the invocation in the worker's queue - if not specified, the invocation has normal priority.
+        priority: Query<Option<InvocationPriority>>,
+        /// Maximum time to wait for the result in milliseconds - if exceeded, the running invocation is interrupted
+        /// and an `InvocationTimedOut` error is returned. If not specified, the call waits until the invocation completes.
+        timeout_millis: Query<Option<u64>>,
+        params: Json<InvokeJsonParameters>,
+    ) -> Result<Json<InvokeJsonResult>> {
+        let worker_id = make_target_worker_id(component_id.0, Some(worker_name.0))?;
+
+        let record = recorded_http_api_request!(
+            "invoke_and_await_function_json",
+            worker_id = worker_id.to_string(),
+            idempotency_key = idempotency_key.0.as_ref().map(|v| v.value.clone()),
+            function = function.0
+        );
+
         let response = self
             .worker_service
-            .validate_and_invoke_and_await_typed(
+            .invoke_and_await_json(
                 &worker_id,
-                idempotency_key.0,
+                request_idempotency_key(
+                    idempotency_key.0,
+                    request,
+                    &worker_id.component_id,
+                    &function.0,
+                ),
                 function.0,
                 params.0.params,
-                None,
+                make_invocation_context(params.0.context),
+                priority.0.unwrap_or_default(),
+                timeout_millis.0.map(Duration::from_millis),
                 empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
             )
             .instrument(record.span.clone())
             .await
             .map_err(|e| e.into())
-            .map(|result| Json(InvokeResult { result }));
+            .map(|result| {
+                Json(InvokeJsonResult {
+                    result: result.to_json_value(),
+                })
+            });
 
         record.result(response)
     }
 
-    /// Invoke a function and await its resolution
+    /// Invoke a function and stream its result
     ///
-    /// Supply the parameters in the request body as JSON.
+    /// Supply the parameters in the request body as JSON or in WAVE. The contents of the input
+    /// streams returned by the function are sent in the response body as they are read, instead of
+    /// waiting for the whole result. If the invocation fails after the response started, the
+    /// response body is terminated with an error.
     #[oai(
-        path = "/:component_id/workers/:worker_name/invoke-and-await",
+        path = "/:component_id/workers/:worker_name/invoke-and-stream",
         method = "post",
-        operation_id = "invoke_and_await_function"
+        operation_id = "invoke_and_stream_function"
     )]
-    async fn invoke_and_await_function(
+    async fn invoke_and_stream_function(
         &self,
         component_id: Path<ComponentId>,
         worker_name: Path<String>,
         #[oai(name = "Idempotency-Key")] idempotency_key: Header<Option<IdempotencyKey>>,
+        request: &poem::Request,
         function: Query<String>,
-        params: Json<InvokeParameters>,
-    ) -> Result<Json<InvokeResult>> {
+        /// Priority of the invocation in the worker's queue - if not specified, the invocation has normal priority.
+        priority: Query<Option<InvocationPriority>>,
+        /// Maximum time to wait for the result in milliseconds - if exceeded, the running invocation is interrupted
+        /// and an `InvocationTimedOut` error is returned. If not specified, the call waits until the invocation completes.
+        timeout_millis: Query<Option<u64>>,
+        params: InvokeRequestBody,
+    ) -> Result<Binary<Body>> {
         let worker_id = make_target_worker_id(component_id.0, Some(worker_name.0))?;
 
         let record = recorded_http_api_request!(
-            "invoke_and_await_function",
+            "invoke_and_stream_function",
+            worker_id = worker_id.to_string(),
+            idempotency_key = idempotency_key.0.as_ref().map(|v| v.value.clone()),
+            function = function.0
+        );
+
+        let response: Result<Binary<Body>> = async {
+            let (params, context) = self
+                .resolve_invoke_parameters(&worker_id, &function.0, params)
+                .await?;
+            let params = self.worker_service.validate_typed_parameters(params)?;
+            let stream = self
+                .worker_service
+                .invoke_and_stream(
+                    &worker_id,
+                    request_idempotency_key(
+                        idempotency_key.0,
+                        request,
+                        &worker_id.component_id,
+                        &function.0,
+                    ),
+                    function.0,
+                    params,
+                    context,
+                    priority.0.unwrap_or_default(),
+                    timeout_millis.0.map(Duration::from_millis),
+                    empty_worker_metadata(),
+                    &EmptyAuthCtx::default(),
+                )
+                .await?;
+            Ok(Binary(Body::from_bytes_stream(stream.filter_map(
+                |message| async move { result_stream_chunk(message) },
+            ))))
+        }
+        .instrument(record.span.clone())
+        .await;
+
+        record.result(response)
+    }
+
+    /// Invoke a function and await its resolution until a deadline
+    ///
+    /// Supply the parameters in the request body as JSON or in WAVE. If the invocation does not
+    /// finish before the deadline, an await token is returned immediately instead of the result,
+    /// and the invocation keeps running. The result can be awaited again with the token, which
+    /// makes long invocations robust to client disconnects and load balancer timeouts.
+    #[oai(
+        path = "/:component_id/workers/:worker_name/invoke-and-poll",
+        method = "post",
+        operation_id = "invoke_and_poll_function"
+    )]
+    async fn invoke_and_poll_function(
+        &self,
+        component_id: Path<ComponentId>,
+        worker_name: Path<String>,
+        #[oai(name = "Idempotency-Key")] idempotency_key: Header<Option<IdempotencyKey>>,
+        request: &poem::Request,
+        function: Query<String>,
+        /// Priority of the invocation in the worker's queue - if not specified, the invocation has normal priority.
+        priority: Query<Option<InvocationPriority>>,
+        /// Maximum time to wait for the result in milliseconds before returning an await token - defaults to 30 seconds, at most 5 minutes.
+        deadline_millis: Query<Option<u64>>,
+        params: InvokeRequestBody,
+    ) -> Result<InvokeAndPollResponse> {
+        let worker_id = make_target_worker_id(component_id.0, Some(worker_name.0))?;
+        let deadline = make_poll_deadline(deadline_millis.0)?;
+
+        let record = recorded_http_api_request!(
+            "invoke_and_poll_function",
             worker_id = worker_id.to_string(),
             idempotency_key = idempotency_key.0.as_ref().map(|v| v.value.clone()),
             function = function.0
         );
 
+        let response: Result<InvokeAndPollResponse> = async {
+            let (params, context) = self
+                .resolve_invoke_parameters(&worker_id, &function.0, params)
+                .await?;
+            let params = self.worker_service.validate_typed_parameters(params)?;
+            let result = self
+                .worker_service
+                .invoke_and_poll(
+                    &worker_id,
+                    request_idempotency_key(
+                        idempotency_key.0,
+                        request,
+                        &worker_id.component_id,
+                        &function.0,
+                    ),
+                    function.0,
+                    params,
+                    context,
+                    priority.0.unwrap_or_default(),
+                    deadline,
+                    empty_worker_metadata(),
+                    &EmptyAuthCtx::default(),
+                )
+                .await?;
+            Ok(make_invoke_and_poll_response(result))
+        }
+        .instrument(record.span.clone())
+        .await;
+
+        record.result(response)
+    }
+
+    /// Resume awaiting the result of an invocation
+    ///
+    /// Awaits the result of an invocation started with `invoke-and-poll` using the await token it
+    /// returned. If the invocation still does not finish before the deadline, the same token is
+    /// returned again.
+    #[oai(
+        path = "/:component_id/workers/:worker_name/invocations/:await_token",
+        method = "get",
+        operation_id = "await_invocation"
+    )]
+    async fn await_invocation(
+        &self,
+        component_id: Path<ComponentId>,
+        worker_name: Path<String>,
+        await_token: Path<String>,
+        /// Maximum time to wait for the result in milliseconds before returning the await token again - defaults to 30 seconds, at most 5 minutes.
+        deadline_millis: Query<Option<u64>>,
+    ) -> Result<InvokeAndPollResponse> {
+        let worker_id = make_worker_id(component_id.0, worker_name.0)?;
+        let deadline = make_poll_deadline(deadline_millis.0)?;
+
+        let record = recorded_http_api_request!(
+            "await_invocation",
+            worker_id = worker_id.to_string(),
+            idempotency_key = await_token.0.clone()
+        );
+
+        // The await token is the idempotency key of the invocation
         let response = self
             .worker_service
-            .validate_and_invoke_and_await_typed(
+            .await_invocation(
                 &worker_id,
-                idempotency_key.0,
-                function.0,
-                params.0.params,
-                None,
+                &IdempotencyKey::new(await_token.0),
+                deadline,
                 empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
             )
             .instrument(record.span.clone())
             .await
             .map_err(|e| e.into())
-            .map(|result| Json(InvokeResult { result }));
+            .map(make_invoke_and_poll_response);
+
         record.result(response)
     }
 
@@ -221,10 +659,20 @@ impl WorkerApi {
         &self,
         component_id: Path<ComponentId>,
         #[oai(name = "Idempotency-Key")] idempotency_key: Header<Option<IdempotencyKey>>,
+        request: &poem::Request,
         function: Query<String>,
-        params: Json<InvokeParameters>,
+        /// Priority of the invocation in the worker's queue - if not specified, the invocation has normal priority.
+        priority: Query<Option<InvocationPriority>>,
+        /// URN or id of a worker to place the new worker together with, in the same shard, so both always run on the same executor.
+        /// Ignored if the new worker cannot be placed there.
+        affinity: Query<Option<String>>,
+        /// Comma separated URNs or ids of workers to place the new worker apart from, in a shard not used by any of them.
+        /// Ignored if the new worker cannot be placed so.
+        anti_affinity: Query<Option<String>>,
+        params: InvokeRequestBody,
     ) -> Result<Json<InvokeResponse>> {
         let worker_id = make_target_worker_id(component_id.0, None)?;
+        let placement_hint = make_placement_hint(affinity.0, anti_affinity.0)?;
 
         let record = recorded_http_api_request!(
             "invoke_function_without_name",
@@ -233,20 +681,32 @@ impl WorkerApi {
             function = function.0
         );
 
-        let response = self
-            .worker_service
-            .validate_and_invoke(
-                &worker_id,
-                idempotency_key.0,
-                function.0,
-                params.0.params,
-                None,
-                empty_worker_metadata(),
-            )
-            .instrument(record.span.clone())
-            .await
-            .map_err(|e| e.into())
-            .map(|_| Json(InvokeResponse {}));
+        let response: Result<Json<InvokeResponse>> = async {
+            let worker_id = self.place_new_worker(worker_id, placement_hint).await?;
+            let (params, context) = self
+                .resolve_invoke_parameters(&worker_id, &function.0, params)
+                .await?;
+            self.worker_service
+                .validate_and_invoke(
+                    &worker_id,
+                    request_idempotency_key(
+                        idempotency_key.0,
+                        request,
+                        &worker_id.component_id,
+                        &function.0,
+                    ),
+                    function.0,
+                    params,
+                    context,
+                    priority.0.unwrap_or_default(),
+                    empty_worker_metadata(),
+                    &EmptyAuthCtx::default(),
+                )
+                .await?;
+            Ok(Json(InvokeResponse {}))
+        }
+        .instrument(record.span.clone())
+        .await;
 
         record.result(response)
     }
@@ -264,8 +724,11 @@ impl WorkerApi {
         component_id: Path<ComponentId>,
         worker_name: Path<String>,
         #[oai(name = "Idempotency-Key")] idempotency_key: Header<Option<IdempotencyKey>>,
+        request: &poem::Request,
         function: Query<String>,
-        params: Json<InvokeParameters>,
+        /// Priority of the invocation in the worker's queue - if not specified, the invocation has normal priority.
+        priority: Query<Option<InvocationPriority>>,
+        params: InvokeRequestBody,
     ) -> Result<Json<InvokeResponse>> {
         let worker_id = make_target_worker_id(component_id.0, Some(worker_name.0))?;
 
@@ -276,20 +739,31 @@ impl WorkerApi {
             function = function.0
         );
 
-        let response = self
-            .worker_service
-            .validate_and_invoke(
-                &worker_id,
-                idempotency_key.0,
-                function.0,
-                params.0.params,
-                None,
-                empty_worker_metadata(),
-            )
-            .instrument(record.span.clone())
-            .await
-            .map_err(|e| e.into())
-            .map(|_| Json(InvokeResponse {}));
+        let response: Result<Json<InvokeResponse>> = async {
+            let (params, context) = self
+                .resolve_invoke_parameters(&worker_id, &function.0, params)
+                .await?;
+            self.worker_service
+                .validate_and_invoke(
+                    &worker_id,
+                    request_idempotency_key(
+                        idempotency_key.0,
+                        request,
+                        &worker_id.component_id,
+                        &function.0,
+                    ),
+                    function.0,
+                    params,
+                    context,
+                    priority.0.unwrap_or_default(),
+                    empty_worker_metadata(),
+                    &EmptyAuthCtx::default(),
+                )
+                .await?;
+            Ok(Json(InvokeResponse {}))
+        }
+        .instrument(record.span.clone())
+        .await;
 
         record.result(response)
     }
@@ -548,6 +1022,115 @@ impl WorkerApi {
         record.result(response)
     }
 
+    /// Get metadata of a list of workers
+    ///
+    /// Returns the metadata of up to 100 workers, possibly of different components, in the order
+    /// they were requested. Each entry has either the worker's `metadata`, or the `error` it could
+    /// not be fetched with, so a missing worker does not fail the whole request.
+    #[oai(
+        path = "/workers/metadata",
+        method = "post",
+        operation_id = "get_workers_metadata_bulk"
+    )]
+    async fn get_workers_metadata_bulk(
+        &self,
+        params: Json<WorkersMetadataBulkRequest>,
+    ) -> Result<Json<WorkersMetadataBulkResponse>> {
+        let record = recorded_http_api_request!(
+            "get_workers_metadata_bulk",
+            worker_count = params.worker_ids.len()
+        );
+
+        let response = async {
+            if params.0.worker_ids.len() > MAX_BULK_METADATA_WORKERS {
+                return Err(WorkerApiBaseError::BadRequest(Json(ErrorsBody {
+                    errors: vec![format!(
+                        "At most {MAX_BULK_METADATA_WORKERS} workers can be requested at once, got {}",
+                        params.0.worker_ids.len()
+                    )],
+                })));
+            }
+            let worker_ids = params
+                .0
+                .worker_ids
+                .into_iter()
+                .map(|worker_id| {
+                    make_worker_id(worker_id.component_id, worker_id.worker_name.into_string())
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            let results = self
+                .worker_service
+                .get_metadata_bulk(
+                    &worker_ids,
+                    empty_worker_metadata(),
+                    &EmptyAuthCtx::default(),
+                )
+                .await;
+
+            let workers = worker_ids
+                .into_iter()
+                .zip(results)
+                .map(|(worker_id, result)| match result {
+                    Ok(metadata) => WorkerMetadataBulkEntry {
+                        worker_id,
+                        metadata: Some(metadata),
+                        error: None,
+                    },
+                    Err(error) => WorkerMetadataBulkEntry {
+                        worker_id,
+                        metadata: None,
+                        error: Some(error.to_problem_details()),
+                    },
+                })
+                .collect();
+            Ok(Json(WorkersMetadataBulkResponse { workers }))
+        }
+        .instrument(record.span.clone())
+        .await;
+
+        record.result(response)
+    }
+
+    /// Get statistics of the workers of a component
+    ///
+    /// Returns the number of workers of the component in each status, the number of their pending
+    /// invocations and their total linear memory size, in total and per component version, without
+    /// enumerating the workers.
+    ///
+    /// The statistics use the last recorded status of the workers, unless `precise` is set, in
+    /// which case the status of each worker is recalculated from its oplog.
+    #[oai(
+        path = "/:component_id/worker-statistics",
+        method = "get",
+        operation_id = "get_component_worker_statistics"
+    )]
+    async fn get_component_worker_statistics(
+        &self,
+        component_id: Path<ComponentId>,
+        precise: Query<Option<bool>>,
+    ) -> Result<Json<ComponentWorkerStatistics>> {
+        let record = recorded_http_api_request!(
+            "get_component_worker_statistics",
+            component_id = component_id.0.to_string()
+        );
+
+        let response = self
+            .worker_service
+            .get_component_worker_statistics(
+                &component_id.0,
+                precise.0.unwrap_or(false),
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(Json);
+
+        record.result(response)
+    }
+
     /// Resume a worker
     #[oai(
         path = "/:component_id/workers/:worker_name/resume",
@@ -610,19 +1193,63 @@ impl WorkerApi {
         record.result(response)
     }
 
-    /// Get the oplog of a worker
+    /// Check whether a manual update of a worker would succeed
+    ///
+    /// Saves a snapshot of the worker's current state and loads it into a throwaway instance of the
+    /// target component version, without changing the worker's oplog or state.
     #[oai(
-        path = "/:component_id/workers/:worker_name/oplog",
-        method = "get",
-        operation_id = "get_oplog"
+        path = "/:component_id/workers/:worker_name/update/dry-run",
+        method = "post",
+        operation_id = "dry_run_update_worker"
     )]
-    async fn get_oplog(
+    async fn dry_run_update_worker(
         &self,
         component_id: Path<ComponentId>,
         worker_name: Path<String>,
-        from: Query<u64>,
-        count: Query<u64>,
+        params: Json<DryRunUpdateWorkerRequest>,
+    ) -> Result<Json<DryRunUpdateWorkerResponse>> {
+        let worker_id = make_worker_id(component_id.0, worker_name.0)?;
+
+        let record =
+            recorded_http_api_request!("dry_run_update_worker", worker_id = worker_id.to_string());
+
+        let response = self
+            .worker_service
+            .dry_run_update(
+                &worker_id,
+                params.target_version,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|error| {
+                Json(DryRunUpdateWorkerResponse {
+                    would_succeed: error.is_none(),
+                    error,
+                })
+            });
+
+        record.result(response)
+    }
+
+    /// Get the oplog of a worker
+    #[oai(
+        path = "/:component_id/workers/:worker_name/oplog",
+        method = "get",
+        operation_id = "get_oplog"
+    )]
+    async fn get_oplog(
+        &self,
+        component_id: Path<ComponentId>,
+        worker_name: Path<String>,
+        from: Query<u64>,
+        count: Query<u64>,
         cursor: Query<Option<OplogCursor>>,
+        /// If specified without a cursor, the chunk starts at the beginning of this many invocations before `from` (or
+        /// before the end of the oplog if `from` is 0), for example to get the last few invocations of the worker.
+        invocations_before: Query<Option<u64>>,
     ) -> Result<Json<GetOplogResponse>> {
         let worker_id = make_worker_id(component_id.0, worker_name.0)?;
 
@@ -635,6 +1262,299 @@ impl WorkerApi {
                 OplogIndex::from_u64(from.0),
                 cursor.0,
                 count.0,
+                invocations_before.0,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(Json);
+
+        record.result(response)
+    }
+
+    /// Get the status record of a worker
+    ///
+    /// Returns the status of the worker as last recorded by its executor, including its pending and finished updates, the deleted regions of its oplog and its owned resources.
+    /// The record has a versioned schema, its `schemaVersion` field is incremented whenever a field is removed or its meaning changes.
+    #[oai(
+        path = "/:component_id/workers/:worker_name/status",
+        method = "get",
+        operation_id = "get_worker_status"
+    )]
+    async fn get_worker_status(
+        &self,
+        component_id: Path<ComponentId>,
+        worker_name: Path<String>,
+    ) -> Result<Json<PublicWorkerStatusRecord>> {
+        let worker_id = make_worker_id(component_id.0, worker_name.0)?;
+
+        let record =
+            recorded_http_api_request!("get_worker_status", worker_id = worker_id.to_string());
+
+        let response = self
+            .worker_service
+            .get_status(
+                &worker_id,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(Json);
+
+        record.result(response)
+    }
+
+    /// Get the number of pending invocations of a worker
+    ///
+    /// Returns the number of invocations waiting in the worker's queue. This is a cheaper alternative to fetching the whole worker metadata when only the queue depth is needed.
+    #[oai(
+        path = "/:component_id/workers/:worker_name/pending-invocations",
+        method = "get",
+        operation_id = "get_worker_pending_invocation_count"
+    )]
+    async fn get_worker_pending_invocation_count(
+        &self,
+        component_id: Path<ComponentId>,
+        worker_name: Path<String>,
+    ) -> Result<Json<PendingInvocationCountResponse>> {
+        let worker_id = make_worker_id(component_id.0, worker_name.0)?;
+
+        let record = recorded_http_api_request!(
+            "get_worker_pending_invocation_count",
+            worker_id = worker_id.to_string()
+        );
+
+        let response = self
+            .worker_service
+            .get_pending_invocation_count(
+                &worker_id,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|pending_invocations| {
+                Json(PendingInvocationCountResponse {
+                    pending_invocations,
+                })
+            });
+
+        record.result(response)
+    }
+
+    /// List the outstanding promises of a worker
+    ///
+    /// Returns the promises created by the worker which were not completed yet, together with the index of the oplog entry which created them and their age.
+    #[oai(
+        path = "/:component_id/workers/:worker_name/promises",
+        method = "get",
+        operation_id = "list_worker_promises"
+    )]
+    async fn list_worker_promises(
+        &self,
+        component_id: Path<ComponentId>,
+        worker_name: Path<String>,
+    ) -> Result<Json<OutstandingPromisesResponse>> {
+        let worker_id = make_worker_id(component_id.0, worker_name.0)?;
+
+        let record =
+            recorded_http_api_request!("list_worker_promises", worker_id = worker_id.to_string());
+
+        let response = self
+            .worker_service
+            .list_promises(
+                &worker_id,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|promises| Json(OutstandingPromisesResponse { promises }));
+
+        record.result(response)
+    }
+
+    /// Get the sagas of a worker
+    ///
+    /// Returns the sagas of the worker which were neither completed nor compensated yet, with their state and their registered compensations. Sagas whose compensation failed are kept with the error of the failed compensation.
+    #[oai(
+        path = "/:component_id/workers/:worker_name/sagas",
+        method = "get",
+        operation_id = "get_worker_sagas"
+    )]
+    async fn get_worker_sagas(
+        &self,
+        component_id: Path<ComponentId>,
+        worker_name: Path<String>,
+    ) -> Result<Json<WorkerSagasResponse>> {
+        let worker_id = make_worker_id(component_id.0, worker_name.0)?;
+
+        let record =
+            recorded_http_api_request!("get_worker_sagas", worker_id = worker_id.to_string());
+
+        let response = self
+            .worker_service
+            .get_worker_sagas(
+                &worker_id,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|sagas| Json(WorkerSagasResponse { sagas }));
+
+        record.result(response)
+    }
+
+    /// Get the observability settings of a component
+    ///
+    /// Returns the log level, metrics, trace sampling and payload capture settings applied to all the workers of the component.
+    #[oai(
+        path = "/:component_id/observability",
+        method = "get",
+        operation_id = "get_component_observability"
+    )]
+    async fn get_component_observability(
+        &self,
+        component_id: Path<ComponentId>,
+    ) -> Result<Json<ComponentObservability>> {
+        let record = recorded_http_api_request!(
+            "get_component_observability",
+            component_id = component_id.0.to_string()
+        );
+
+        let response = self
+            .worker_service
+            .get_component_observability(&component_id.0, &EmptyAuthCtx::default())
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(Json);
+
+        record.result(response)
+    }
+
+    /// Update the observability settings of a component
+    ///
+    /// The new settings are stored with the component and immediately applied to all its running workers.
+    #[oai(
+        path = "/:component_id/observability",
+        method = "put",
+        operation_id = "update_component_observability"
+    )]
+    async fn update_component_observability(
+        &self,
+        component_id: Path<ComponentId>,
+        observability: Json<ComponentObservability>,
+    ) -> Result<Json<ComponentObservability>> {
+        let record = recorded_http_api_request!(
+            "update_component_observability",
+            component_id = component_id.0.to_string()
+        );
+
+        let response = self
+            .worker_service
+            .update_component_observability(
+                &component_id.0,
+                observability.0,
+                &EmptyAuthCtx::default(),
+            )
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(Json);
+
+        record.result(response)
+    }
+
+    /// Get the update policy of a component
+    ///
+    /// Returns whether the existing workers are updated automatically when a new version of the component is uploaded, and how.
+    #[oai(
+        path = "/:component_id/update-policy",
+        method = "get",
+        operation_id = "get_component_update_policy"
+    )]
+    async fn get_component_update_policy(
+        &self,
+        component_id: Path<ComponentId>,
+    ) -> Result<Json<ComponentUpdatePolicy>> {
+        let record = recorded_http_api_request!(
+            "get_component_update_policy",
+            component_id = component_id.0.to_string()
+        );
+
+        let response = self
+            .update_rollout_service
+            .get_update_policy(&component_id.0, &EmptyAuthCtx::default())
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(Json);
+
+        record.result(response)
+    }
+
+    /// Update the update policy of a component
+    ///
+    /// The policy is applied to the next uploaded versions of the component; the outdated workers matching its filter are updated in the background, with at most `maxConcurrentUpdatesPerExecutor` updates in progress on each worker executor.
+    #[oai(
+        path = "/:component_id/update-policy",
+        method = "put",
+        operation_id = "update_component_update_policy"
+    )]
+    async fn update_component_update_policy(
+        &self,
+        component_id: Path<ComponentId>,
+        policy: Json<ComponentUpdatePolicy>,
+    ) -> Result<Json<ComponentUpdatePolicy>> {
+        let record = recorded_http_api_request!(
+            "update_component_update_policy",
+            component_id = component_id.0.to_string()
+        );
+
+        let response = self
+            .update_rollout_service
+            .update_update_policy(&component_id.0, policy.0, &EmptyAuthCtx::default())
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(Json);
+
+        record.result(response)
+    }
+
+    /// Get the status of an update rollout
+    ///
+    /// Returns the number of workers of the component already updated to the target version, the ones with a pending or failed update, and the ones not updated yet, together with the failure details.
+    #[oai(
+        path = "/:component_id/update-rollouts/:target_version",
+        method = "get",
+        operation_id = "get_update_rollout_status"
+    )]
+    async fn get_update_rollout_status(
+        &self,
+        component_id: Path<ComponentId>,
+        target_version: Path<u64>,
+    ) -> Result<Json<UpdateRolloutStatus>> {
+        let record = recorded_http_api_request!(
+            "get_update_rollout_status",
+            component_id = component_id.0.to_string(),
+            target_version = target_version.0
+        );
+
+        let response = self
+            .update_rollout_service
+            .get_rollout_status(
+                &component_id.0,
+                target_version.0,
                 empty_worker_metadata(),
                 &EmptyAuthCtx::default(),
             )
@@ -647,15 +1567,80 @@ impl WorkerApi {
     }
 }
 
+impl WorkerApi {
+    /// Generates the name of a new worker according to the placement hint and creates the worker,
+    /// if there is a hint
+    async fn place_new_worker(
+        &self,
+        worker_id: TargetWorkerId,
+        placement_hint: Option<WorkerPlacementHint>,
+    ) -> Result<TargetWorkerId> {
+        match placement_hint {
+            Some(placement_hint) => Ok(self
+                .worker_service
+                .place_worker_with_hint(
+                    &worker_id,
+                    &placement_hint,
+                    empty_worker_metadata(),
+                    &EmptyAuthCtx::default(),
+                )
+                .await?),
+            None => Ok(worker_id),
+        }
+    }
+
+    /// Gets the parameters and the invocation context of an invocation from its request body
+    async fn resolve_invoke_parameters(
+        &self,
+        worker_id: &TargetWorkerId,
+        function: &str,
+        body: InvokeRequestBody,
+    ) -> Result<(Vec<TypeAnnotatedValue>, Option<InvocationContext>)> {
+        match body {
+            InvokeRequestBody::Json(params) => {
+                Ok((params.0.params, make_invocation_context(params.0.context)))
+            }
+            InvokeRequestBody::Wave(params) => {
+                let params = self
+                    .worker_service
+                    .resolve_wave_parameters(
+                        worker_id,
+                        function,
+                        &params.0,
+                        empty_worker_metadata(),
+                        &EmptyAuthCtx::default(),
+                    )
+                    .await?;
+                Ok((params, None))
+            }
+        }
+    }
+}
+
+fn invalid_worker_name(error: &str) -> WorkerApiBaseError {
+    WorkerApiBaseError::BadRequest(Json(ErrorsBody {
+        errors: vec![format!("Invalid worker name: {error}")],
+    }))
+}
+
+/// Worker id of a worker to be created, whose name must follow the naming rules
+fn make_new_worker_id(
+    component_id: ComponentId,
+    worker_name: String,
+) -> std::result::Result<WorkerId, WorkerApiBaseError> {
+    let worker_name = WorkerName::new(worker_name).map_err(invalid_worker_name)?;
+    Ok(WorkerId {
+        component_id,
+        worker_name,
+    })
+}
+
+/// Worker id of an existing worker, which may be given by the escaped form of its name
 fn make_worker_id(
     component_id: ComponentId,
     worker_name: String,
 ) -> std::result::Result<WorkerId, WorkerApiBaseError> {
-    validate_worker_name(&worker_name).map_err(|error| {
-        WorkerApiBaseError::BadRequest(Json(ErrorsBody {
-            errors: vec![format!("Invalid worker name: {error}")],
-        }))
-    })?;
+    let worker_name = WorkerName::parse(&worker_name).map_err(invalid_worker_name)?;
     Ok(WorkerId {
         component_id,
         worker_name,
@@ -666,16 +1651,170 @@ fn make_target_worker_id(
     component_id: ComponentId,
     worker_name: Option<String>,
 ) -> std::result::Result<TargetWorkerId, WorkerApiBaseError> {
-    if let Some(worker_name) = &worker_name {
-        validate_worker_name(worker_name).map_err(|error| {
-            WorkerApiBaseError::BadRequest(Json(ErrorsBody {
-                errors: vec![format!("Invalid worker name: {error}")],
-            }))
-        })?;
-    }
+    let worker_name = worker_name
+        .map(|worker_name| WorkerName::parse(&worker_name).map_err(invalid_worker_name))
+        .transpose()?;
 
     Ok(TargetWorkerId {
         component_id,
         worker_name,
     })
 }
+
+fn make_placement_hint(
+    affinity: Option<String>,
+    anti_affinity: Option<String>,
+) -> std::result::Result<Option<WorkerPlacementHint>, WorkerApiBaseError> {
+    // Accepts worker URNs, as well as the canonical and the legacy forms of worker ids
+    let parse_worker_id = |worker_id: &str| {
+        WorkerId::from_str(worker_id.trim()).map_err(|error| {
+            WorkerApiBaseError::BadRequest(Json(ErrorsBody {
+                errors: vec![format!("Invalid worker {worker_id}: {error}")],
+            }))
+        })
+    };
+
+    match (affinity, anti_affinity) {
+        (None, None) => Ok(None),
+        (Some(affinity), None) => Ok(Some(WorkerPlacementHint::Affinity(parse_worker_id(
+            &affinity,
+        )?))),
+        (None, Some(anti_affinity)) => Ok(Some(WorkerPlacementHint::AntiAffinity(
+            anti_affinity
+                .split(',')
+                .map(parse_worker_id)
+                .collect::<std::result::Result<Vec<_>, _>>()?,
+        ))),
+        (Some(_), Some(_)) => Err(WorkerApiBaseError::BadRequest(Json(ErrorsBody {
+            errors: vec!["Only one of affinity and anti_affinity can be specified".to_string()],
+        }))),
+    }
+}
+
+/// Gets the bytes to send in the body of a streamed invocation's response from a message of the
+/// executor, which ends the body with an error if the invocation failed
+fn result_stream_chunk(
+    message: std::result::Result<InvokeAndStreamWorkerResponse, tonic::Status>,
+) -> Option<std::io::Result<Vec<u8>>> {
+    match message {
+        Ok(InvokeAndStreamWorkerResponse {
+            result: Some(invoke_and_stream_worker_response::Result::Chunk(bytes)),
+        }) => Some(Ok(bytes)),
+        Ok(InvokeAndStreamWorkerResponse {
+            result: Some(invoke_and_stream_worker_response::Result::Done(_)),
+        }) => None,
+        Ok(InvokeAndStreamWorkerResponse {
+            result: Some(invoke_and_stream_worker_response::Result::Failure(err)),
+        }) => {
+            let error = GolemError::try_from(err)
+                .map(|err| err.to_string())
+                .unwrap_or_else(|err| err);
+            Some(Err(std::io::Error::other(error)))
+        }
+        Ok(InvokeAndStreamWorkerResponse { result: None }) => {
+            Some(Err(std::io::Error::other("Empty response")))
+        }
+        Err(status) => Some(Err(std::io::Error::other(status.message().to_string()))),
+    }
+}
+
+/// Makes the invocation context of the request from the context passed in its body
+fn make_invocation_context(context: Option<HashMap<String, String>>) -> Option<InvocationContext> {
+    context.map(|values| InvocationContext {
+        parent: None,
+        args: vec![],
+        env: HashMap::new(),
+        values,
+    })
+}
+
+/// Gets the deadline of waiting for an invocation's result, which has to be positive and at most
+/// `MAX_POLL_DEADLINE`
+fn make_poll_deadline(
+    deadline_millis: Option<u64>,
+) -> std::result::Result<Duration, WorkerApiBaseError> {
+    match deadline_millis.map(Duration::from_millis) {
+        None => Ok(DEFAULT_POLL_DEADLINE),
+        Some(deadline) if !deadline.is_zero() && deadline <= MAX_POLL_DEADLINE => Ok(deadline),
+        Some(_) => Err(WorkerApiBaseError::BadRequest(Json(ErrorsBody {
+            errors: vec![format!(
+                "Invalid deadline_millis: it has to be between 1 and {}",
+                MAX_POLL_DEADLINE.as_millis()
+            )],
+        }))),
+    }
+}
+
+fn make_invoke_and_poll_response(result: InvocationPollResult) -> InvokeAndPollResponse {
+    match result {
+        InvocationPollResult::Completed(result) => {
+            InvokeAndPollResponse::Completed(Json(InvokeResult { result }))
+        }
+        InvocationPollResult::Pending(idempotency_key) => {
+            InvokeAndPollResponse::Pending(Json(InvocationAwaitToken {
+                await_token: idempotency_key.value,
+            }))
+        }
+    }
+}
+
+/// Returns the results in WAVE if only `text/plain` is accepted by the client, and as JSON
+/// otherwise
+fn make_invoke_and_await_response(
+    result: TypeAnnotatedValue,
+    accept: Option<&str>,
+) -> Result<InvokeAndAwaitResponse> {
+    let wants_wave = accept.is_some_and(|accept| {
+        accept.contains("text/plain") && !accept.contains("application/json")
+    });
+    if wants_wave {
+        Ok(InvokeAndAwaitResponse::Wave(PlainText(wave_results(
+            &result,
+        )?)))
+    } else {
+        Ok(InvokeAndAwaitResponse::Json(Json(InvokeResult { result })))
+    }
+}
+
+// Requests without an idempotency key but with a request id get a key derived from the id, so a
+// client resending a request after a lost response does not invoke the function again
+fn request_idempotency_key(
+    idempotency_key: Option<IdempotencyKey>,
+    request: &poem::Request,
+    component_id: &ComponentId,
+    function: &str,
+) -> Option<IdempotencyKey> {
+    idempotency_key.or_else(|| {
+        request
+            .headers()
+            .get("x-request-id")
+            .and_then(|request_id| request_id.to_str().ok())
+            .map(|request_id| {
+                IdempotencyKey::from_request_id(request_id, &format!("{component_id}/{function}"))
+            })
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use test_r::test;
+
+    use super::*;
+
+    #[test]
+    fn poll_deadline_defaults_when_not_specified() {
+        assert_eq!(make_poll_deadline(None).unwrap(), DEFAULT_POLL_DEADLINE);
+        assert_eq!(
+            make_poll_deadline(Some(1500)).unwrap(),
+            Duration::from_millis(1500)
+        );
+    }
+
+    #[test]
+    fn poll_deadline_is_bounded() {
+        assert!(make_poll_deadline(Some(0)).is_err());
+        assert!(make_poll_deadline(Some(MAX_POLL_DEADLINE.as_millis() as u64)).is_ok());
+        assert!(make_poll_deadline(Some(MAX_POLL_DEADLINE.as_millis() as u64 + 1)).is_err());
+        assert!(make_poll_deadline(Some(u64::MAX)).is_err());
+    }
+}
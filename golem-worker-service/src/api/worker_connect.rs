@@ -17,16 +17,17 @@ use std::time::Duration;
 use crate::empty_worker_metadata;
 use crate::service::worker::WorkerService;
 use futures::StreamExt;
-use golem_common::model::{ComponentId, WorkerId};
+use golem_common::model::{ComponentId, WorkerId, WorkerName};
 use golem_common::recorded_http_api_request;
 use golem_service_base::auth::EmptyAuthCtx;
-use golem_service_base::model::{validate_worker_name, ErrorsBody};
+use golem_service_base::model::ErrorsBody;
 use golem_worker_service_base::api::WorkerApiBaseError;
 use golem_worker_service_base::service::worker::{proxy_worker_connection, ConnectWorkerStream};
 use poem::web::websocket::WebSocket;
-use poem::web::{Data, Path};
+use poem::web::{Data, Path, Query};
 use poem::*;
 use poem_openapi::payload::Json;
+use serde::Deserialize;
 use tracing::Instrument;
 
 #[derive(Clone)]
@@ -40,13 +41,22 @@ impl ConnectService {
     }
 }
 
+// The `backfill` query parameter is the number of the last stdout, stderr and log events
+// to replay from the oplog before the live events
+#[derive(Deserialize)]
+pub struct ConnectParams {
+    backfill: Option<u64>,
+}
+
 #[handler]
 pub async fn ws(
     Path((component_id, worker_name)): Path<(ComponentId, String)>,
+    Query(ConnectParams { backfill }): Query<ConnectParams>,
     websocket: WebSocket,
     Data(service): Data<&ConnectService>,
 ) -> Response {
-    connect_to_worker(service, component_id, worker_name)
+    let backfill_count = backfill.unwrap_or(0);
+    connect_to_worker(service, component_id, worker_name, backfill_count)
         .await
         .map(|(worker_id, worker_stream)| {
             websocket
@@ -76,8 +86,9 @@ async fn connect_to_worker(
     service: &ConnectService,
     component_id: ComponentId,
     worker_name: String,
+    backfill_count: u64,
 ) -> Result<(WorkerId, ConnectWorkerStream), Response> {
-    validate_worker_name(&worker_name).map_err(|e| {
+    let worker_name = WorkerName::parse(&worker_name).map_err(|e| {
         let error = WorkerApiBaseError::BadRequest(Json(ErrorsBody {
             errors: vec![format!("Invalid worker name: {e}")],
         }));
@@ -85,7 +96,7 @@ async fn connect_to_worker(
     })?;
     let worker_id = WorkerId {
         component_id: component_id.clone(),
-        worker_name: worker_name.clone(),
+        worker_name,
     };
 
     let record = recorded_http_api_request!("connect_worker", worker_id = worker_id.to_string());
@@ -94,6 +105,7 @@ async fn connect_to_worker(
         .worker_service
         .connect(
             &worker_id,
+            backfill_count,
             empty_worker_metadata(),
             &EmptyAuthCtx::default(),
         )
@@ -35,6 +35,7 @@ pub async fn start_grpc_server(addr: SocketAddr, services: &Services) -> Result<
             WorkerServiceServer::new(WorkerGrpcApi::new(
                 services.component_service.clone(),
                 services.worker_service.clone(),
+                services.update_rollout_service.clone(),
             ))
             .accept_compressed(CompressionEncoding::Gzip)
             .send_compressed(CompressionEncoding::Gzip),
@@ -13,8 +13,9 @@
 // limitations under the License.
 
 use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
+use std::time::Duration;
 use tap::TapFallible;
-use tonic::{Request, Response, Status};
+use tonic::{Code, Request, Response, Status};
 use tracing::Instrument;
 
 use golem_api_grpc::proto::golem::common::{Empty, ErrorBody, ErrorsBody};
@@ -23,16 +24,17 @@ use golem_api_grpc::proto::golem::worker::v1::{
     complete_promise_response, delete_worker_response, get_oplog_response,
     get_worker_metadata_response, get_workers_metadata_response, interrupt_worker_response,
     invoke_and_await_json_response, invoke_and_await_response, invoke_and_await_typed_response,
-    invoke_response, launch_new_worker_response, resume_worker_response, update_worker_response,
-    worker_error, worker_execution_error, CompletePromiseRequest, CompletePromiseResponse,
-    ConnectWorkerRequest, DeleteWorkerRequest, DeleteWorkerResponse, GetOplogRequest,
-    GetOplogResponse, GetOplogSuccessResponse, GetWorkerMetadataRequest, GetWorkerMetadataResponse,
-    GetWorkersMetadataRequest, GetWorkersMetadataResponse, GetWorkersMetadataSuccessResponse,
-    InterruptWorkerRequest, InterruptWorkerResponse, InvokeAndAwaitJsonRequest,
-    InvokeAndAwaitJsonResponse, InvokeAndAwaitRequest, InvokeAndAwaitResponse,
-    InvokeAndAwaitTypedResponse, InvokeJsonRequest, InvokeRequest, InvokeResponse,
-    LaunchNewWorkerRequest, LaunchNewWorkerResponse, LaunchNewWorkerSuccessResponse,
-    ResumeWorkerRequest, ResumeWorkerResponse, UnknownError, UpdateWorkerRequest,
+    invoke_response, launch_new_worker_response, resume_worker_response,
+    start_update_rollout_response, update_worker_response, worker_error, worker_execution_error,
+    CompletePromiseRequest, CompletePromiseResponse, ConnectWorkerRequest, DeleteWorkerRequest,
+    DeleteWorkerResponse, GetOplogRequest, GetOplogResponse, GetOplogSuccessResponse,
+    GetWorkerMetadataRequest, GetWorkerMetadataResponse, GetWorkersMetadataRequest,
+    GetWorkersMetadataResponse, GetWorkersMetadataSuccessResponse, InterruptWorkerRequest,
+    InterruptWorkerResponse, InvokeAndAwaitJsonRequest, InvokeAndAwaitJsonResponse,
+    InvokeAndAwaitRequest, InvokeAndAwaitResponse, InvokeAndAwaitTypedResponse, InvokeJsonRequest,
+    InvokeRequest, InvokeResponse, LaunchNewWorkerRequest, LaunchNewWorkerResponse,
+    LaunchNewWorkerSuccessResponse, ResumeWorkerRequest, ResumeWorkerResponse,
+    StartUpdateRolloutRequest, StartUpdateRolloutResponse, UnknownError, UpdateWorkerRequest,
     UpdateWorkerResponse, WorkerError as GrpcWorkerError, WorkerExecutionError,
 };
 use golem_api_grpc::proto::golem::worker::{InvokeResult, InvokeResultTyped, WorkerMetadata};
@@ -42,27 +44,36 @@ use golem_common::grpc::{
     proto_worker_id_string,
 };
 use golem_common::model::oplog::OplogIndex;
-use golem_common::model::{ComponentVersion, ScanCursor, TargetWorkerId, WorkerFilter, WorkerId};
+use golem_common::model::{
+    ComponentVersion, InvocationPriority, ScanCursor, TargetWorkerId, WorkerFilter, WorkerId,
+    WorkerName,
+};
 use golem_common::recorded_grpc_api_request;
 use golem_service_base::auth::EmptyAuthCtx;
-use golem_service_base::model::validate_worker_name;
+use golem_service_base::model::{ComponentUpdatePolicy, GolemError};
 use golem_worker_service_base::api::WorkerTraceErrorKind;
 use golem_worker_service_base::service::worker::ConnectWorkerStream;
 
-use crate::empty_worker_metadata;
 use crate::service::component::ComponentService;
-use crate::service::worker::WorkerService;
+use crate::service::worker::{UpdateRolloutService, WorkerService};
+use crate::{empty_worker_metadata, pin_to_version_alias};
 
 pub struct WorkerGrpcApi {
     component_service: ComponentService,
     worker_service: WorkerService,
+    update_rollout_service: UpdateRolloutService,
 }
 
 impl WorkerGrpcApi {
-    pub fn new(component_service: ComponentService, worker_service: WorkerService) -> Self {
+    pub fn new(
+        component_service: ComponentService,
+        worker_service: WorkerService,
+        update_rollout_service: UpdateRolloutService,
+    ) -> Self {
         Self {
             component_service,
             worker_service,
+            update_rollout_service,
         }
     }
 }
@@ -470,6 +481,34 @@ impl GrpcWorkerService for WorkerGrpcApi {
         }))
     }
 
+    async fn start_update_rollout(
+        &self,
+        request: Request<StartUpdateRolloutRequest>,
+    ) -> Result<Response<StartUpdateRolloutResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "start_update_rollout",
+            component_id = proto_component_id_string(&request.component_id),
+            target_version = request.target_version,
+        );
+
+        let response = match self
+            .start_update_rollout(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(()) => record.succeed(start_update_rollout_response::Result::Success(Empty {})),
+            Err(error) => record.fail(
+                start_update_rollout_response::Result::Error(error.clone()),
+                &WorkerTraceErrorKind(&error),
+            ),
+        };
+
+        Ok(Response::new(StartUpdateRolloutResponse {
+            result: Some(response),
+        }))
+    }
+
     async fn get_oplog(
         &self,
         request: Request<GetOplogRequest>,
@@ -508,34 +547,43 @@ impl WorkerGrpcApi {
             .and_then(|id| id.try_into().ok())
             .ok_or_else(|| bad_request_error("Missing component id"))?;
 
-        let latest_component = self
-            .component_service
-            .get_latest(&component_id, &EmptyAuthCtx::default())
-            .await
-            .tap_err(|error| {
-                tracing::error!(error = error.to_string(), "Error getting latest component")
-            })
-            .map_err(|_| GrpcWorkerError {
-                error: Some(worker_error::Error::NotFound(ErrorBody {
-                    error: format!("Component not found: {}", &component_id),
-                })),
-            })?;
+        let component = match &request.component_version_alias {
+            Some(alias) => {
+                self.component_service
+                    .get_by_version_alias(&component_id, alias, &EmptyAuthCtx::default())
+                    .await
+            }
+            None => {
+                self.component_service
+                    .get_latest(&component_id, &EmptyAuthCtx::default())
+                    .await
+            }
+        }
+        .tap_err(|error| tracing::error!(error = error.to_string(), "Error getting component"))
+        .map_err(|_| GrpcWorkerError {
+            error: Some(worker_error::Error::NotFound(ErrorBody {
+                error: format!("Component not found: {}", &component_id),
+            })),
+        })?;
 
-        let worker_id = validated_worker_id(component_id, request.name)?;
+        let worker_id = validated_new_worker_id(component_id, request.name)?;
+
+        let mut env = request.env;
+        pin_to_version_alias(&mut env, &request.component_version_alias);
 
         let worker = self
             .worker_service
             .create(
                 &worker_id,
-                latest_component.versioned_component_id.version,
+                component.versioned_component_id.version,
                 request.args,
-                request.env,
+                env,
                 empty_worker_metadata(),
                 &EmptyAuthCtx::default(),
             )
             .await?;
 
-        Ok((worker, latest_component.versioned_component_id.version))
+        Ok((worker, component.versioned_component_id.version))
     }
 
     async fn delete_worker(&self, request: DeleteWorkerRequest) -> Result<(), GrpcWorkerError> {
@@ -649,6 +697,7 @@ impl WorkerGrpcApi {
     }
 
     async fn invoke(&self, request: InvokeRequest) -> Result<(), GrpcWorkerError> {
+        let priority: InvocationPriority = request.priority().into();
         let worker_id = validate_protobuf_target_worker_id(request.worker_id)?;
 
         let params = request
@@ -662,7 +711,9 @@ impl WorkerGrpcApi {
                 request.function,
                 params.params,
                 request.context,
+                priority,
                 empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
             )
             .await?;
 
@@ -670,6 +721,7 @@ impl WorkerGrpcApi {
     }
 
     async fn invoke_json(&self, request: InvokeJsonRequest) -> Result<(), GrpcWorkerError> {
+        let priority: InvocationPriority = request.priority().into();
         let worker_id = validate_protobuf_target_worker_id(request.worker_id)?;
 
         let params = parse_json_invoke_parameters(&request.invoke_parameters)?;
@@ -686,7 +738,9 @@ impl WorkerGrpcApi {
                 request.function,
                 params,
                 request.context,
+                priority,
                 empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
             )
             .await?;
 
@@ -697,6 +751,7 @@ impl WorkerGrpcApi {
         &self,
         request: InvokeAndAwaitRequest,
     ) -> Result<InvokeResult, GrpcWorkerError> {
+        let priority: InvocationPriority = request.priority().into();
         let worker_id = validate_protobuf_target_worker_id(request.worker_id)?;
 
         let params = request
@@ -711,7 +766,10 @@ impl WorkerGrpcApi {
                 request.function,
                 params.params,
                 request.context,
+                priority,
+                request.timeout_millis.map(Duration::from_millis),
                 empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
             )
             .await?;
 
@@ -722,6 +780,7 @@ impl WorkerGrpcApi {
         &self,
         request: InvokeAndAwaitJsonRequest,
     ) -> Result<String, GrpcWorkerError> {
+        let priority: InvocationPriority = request.priority().into();
         let worker_id = validate_protobuf_target_worker_id(request.worker_id)?;
         let params = parse_json_invoke_parameters(&request.invoke_parameters)?;
 
@@ -738,7 +797,10 @@ impl WorkerGrpcApi {
                 request.function,
                 params,
                 request.context,
+                priority,
+                request.timeout_millis.map(Duration::from_millis),
                 empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
             )
             .await?;
 
@@ -757,6 +819,7 @@ impl WorkerGrpcApi {
         &self,
         request: InvokeAndAwaitRequest,
     ) -> Result<InvokeResultTyped, GrpcWorkerError> {
+        let priority: InvocationPriority = request.priority().into();
         let worker_id = validate_protobuf_target_worker_id(request.worker_id)?;
         let params = request
             .invoke_parameters
@@ -775,7 +838,10 @@ impl WorkerGrpcApi {
                 request.function,
                 params.params,
                 request.context,
+                priority,
+                request.timeout_millis.map(Duration::from_millis),
                 empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
             )
             .await?;
 
@@ -809,6 +875,7 @@ impl WorkerGrpcApi {
             .worker_service
             .connect(
                 &worker_id,
+                request.backfill_count,
                 empty_worker_metadata(),
                 &EmptyAuthCtx::default(),
             )
@@ -833,6 +900,35 @@ impl WorkerGrpcApi {
         Ok(())
     }
 
+    async fn start_update_rollout(
+        &self,
+        request: StartUpdateRolloutRequest,
+    ) -> Result<(), GrpcWorkerError> {
+        let component_id: golem_common::model::ComponentId = request
+            .component_id
+            .ok_or_else(|| bad_request_error("Missing component id"))?
+            .try_into()
+            .map_err(|_| bad_request_error("Invalid component id"))?;
+
+        let policy: ComponentUpdatePolicy = request
+            .policy
+            .ok_or_else(|| bad_request_error("Missing update policy"))?
+            .try_into()
+            .map_err(|error| bad_request_error(format!("Invalid update policy: {error}")))?;
+
+        self.update_rollout_service
+            .start_rollout(
+                &component_id,
+                request.target_version,
+                policy,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
     async fn get_oplog(
         &self,
         request: GetOplogRequest,
@@ -846,6 +942,7 @@ impl WorkerGrpcApi {
                 OplogIndex::from_u64(request.from_oplog_index),
                 request.cursor.map(|cursor| cursor.into()),
                 request.count,
+                request.invocations_before,
                 empty_worker_metadata(),
                 &EmptyAuthCtx::default(),
             )
@@ -875,12 +972,28 @@ impl WorkerGrpcApi {
     }
 }
 
+fn invalid_worker_name(error: &str) -> GrpcWorkerError {
+    bad_request_error(format!("Invalid worker name: {error}"))
+}
+
+/// Worker id of a worker to be created, whose name must follow the naming rules
+fn validated_new_worker_id(
+    component_id: golem_common::model::ComponentId,
+    worker_name: String,
+) -> Result<WorkerId, GrpcWorkerError> {
+    let worker_name = WorkerName::new(worker_name).map_err(invalid_worker_name)?;
+    Ok(WorkerId {
+        component_id,
+        worker_name,
+    })
+}
+
+/// Worker id of an existing worker, which may be given by the escaped form of its name
 fn validated_worker_id(
     component_id: golem_common::model::ComponentId,
     worker_name: String,
 ) -> Result<WorkerId, GrpcWorkerError> {
-    validate_worker_name(&worker_name)
-        .map_err(|error| bad_request_error(format!("Invalid worker name: {error}")))?;
+    let worker_name = WorkerName::parse(&worker_name).map_err(invalid_worker_name)?;
     Ok(WorkerId {
         component_id,
         worker_name,
@@ -891,10 +1004,9 @@ fn validated_target_worker_id(
     component_id: golem_common::model::ComponentId,
     worker_name: Option<String>,
 ) -> Result<TargetWorkerId, GrpcWorkerError> {
-    if let Some(worker_name) = &worker_name {
-        validate_worker_name(worker_name)
-            .map_err(|error| bad_request_error(format!("Invalid worker name: {error}")))?;
-    }
+    let worker_name = worker_name
+        .map(|worker_name| WorkerName::parse(&worker_name).map_err(invalid_worker_name))
+        .transpose()?;
     Ok(TargetWorkerId {
         component_id,
         worker_name,
@@ -908,7 +1020,7 @@ fn validate_protobuf_worker_id(
     let worker_id: WorkerId = worker_id
         .try_into()
         .map_err(|e| bad_request_error(format!("Invalid worker id: {e}")))?;
-    validated_worker_id(worker_id.component_id, worker_id.worker_name)
+    validated_worker_id(worker_id.component_id, worker_id.worker_name.into_string())
 }
 
 fn validate_protobuf_target_worker_id(
@@ -918,7 +1030,10 @@ fn validate_protobuf_target_worker_id(
     let worker_id: TargetWorkerId = worker_id
         .try_into()
         .map_err(|e| bad_request_error(format!("Invalid target worker id: {e}")))?;
-    validated_target_worker_id(worker_id.component_id, worker_id.worker_name)
+    validated_target_worker_id(
+        worker_id.component_id,
+        worker_id.worker_name.map(WorkerName::into_string),
+    )
 }
 
 fn bad_request_error<T>(error: T) -> GrpcWorkerError
@@ -940,6 +1055,9 @@ fn error_to_status(error: GrpcWorkerError) -> Status {
         Some(worker_error::Error::Unauthorized(ErrorBody { error })) => {
             Status::unauthenticated(error)
         }
+        Some(worker_error::Error::Forbidden(ErrorBody { error })) => {
+            Status::permission_denied(error)
+        }
         Some(worker_error::Error::LimitExceeded(ErrorBody { error })) => {
             Status::resource_exhausted(error)
         }
@@ -954,6 +1072,11 @@ fn error_to_status(error: GrpcWorkerError) -> Status {
         Some(worker_error::Error::InternalError(WorkerExecutionError {
             error: Some(worker_execution_error),
         })) => {
+            let code = GolemError::try_from(WorkerExecutionError {
+                error: Some(worker_execution_error.clone()),
+            })
+            .map(|error| error.code().grpc_code())
+            .unwrap_or(Code::Internal);
             let message = match worker_execution_error {
                 worker_execution_error::Error::InvalidRequest(err) => {
                     format!("Invalid Request: {}", err.details)
@@ -1030,8 +1153,24 @@ fn error_to_status(error: GrpcWorkerError) -> Status {
                 worker_execution_error::Error::ShardingNotReady(_) => {
                     "Sharding Not Ready".to_string()
                 }
+                worker_execution_error::Error::TooManyPendingInvocations(err) => format!(
+                    "Too Many Pending Invocations: Worker ID = {:?}, Pending Invocations = {}",
+                    err.worker_id, err.pending_invocations
+                ),
+                worker_execution_error::Error::InvocationTimedOut(err) => format!(
+                    "Invocation Timed Out: Worker ID = {:?}, Timeout = {} ms",
+                    err.worker_id, err.timeout_millis
+                ),
+                worker_execution_error::Error::ExecutorBusy(err) => format!(
+                    "Executor Busy: Max Active Workers = {}",
+                    err.max_active_workers
+                ),
+                worker_execution_error::Error::InvocationNotFound(err) => format!(
+                    "Invocation Not Found: Worker ID = {:?}, Idempotency Key = {:?}",
+                    err.worker_id, err.idempotency_key
+                ),
             };
-            Status::internal(message)
+            Status::new(code, message)
         }
         None => Status::unknown("Unknown error"),
     }
@@ -1,4 +1,6 @@
+use golem_common::model::COMPONENT_VERSION_ALIAS_ENV_VAR;
 use golem_worker_service_base::service::worker::WorkerRequestMetadata;
+use std::collections::HashMap;
 
 pub mod api;
 pub mod config;
@@ -17,3 +19,19 @@ fn empty_worker_metadata() -> WorkerRequestMetadata {
         limits: None,
     }
 }
+
+/// Records the component version alias a new worker is created through in its environment,
+/// so the worker follows the alias when it moves
+fn pin_to_version_alias(
+    env: &mut HashMap<String, String>,
+    component_version_alias: &Option<String>,
+) {
+    match component_version_alias {
+        Some(alias) => {
+            env.insert(COMPONENT_VERSION_ALIAS_ENV_VAR.to_string(), alias.clone());
+        }
+        None => {
+            env.remove(COMPONENT_VERSION_ALIAS_ENV_VAR);
+        }
+    }
+}
@@ -79,9 +79,10 @@ pub async fn app(
     let http_service1 = services.clone();
     let http_service2 = services.clone();
     let grpc_services = services.clone();
+    let multipart_config = config.multipart.clone();
 
     let custom_request_server = tokio::spawn(async move {
-        let route = api::custom_request_route(http_service1)
+        let route = api::custom_request_route(http_service1, multipart_config)
             .with(OpenTelemetryMetrics::new())
             .with(Tracing);
 
@@ -8,11 +8,15 @@ use golem_worker_service_base::api_definition::http::{
 };
 
 use golem_service_base::auth::{DefaultNamespace, EmptyAuthCtx};
-use golem_worker_service_base::app_config::WorkerServiceBaseConfig;
+use golem_worker_service_base::app_config::{HttpResponseCacheConfig, WorkerServiceBaseConfig};
 use golem_worker_service_base::http::InputHttpRequest;
 
 use golem_worker_service_base::repo::api_definition;
 use golem_worker_service_base::repo::api_deployment;
+use golem_worker_service_base::repo::api_key;
+use golem_worker_service_base::repo::api_project;
+use golem_worker_service_base::repo::invocation_attempt;
+use golem_worker_service_base::repo::update_rollout;
 use golem_worker_service_base::service::api_definition::{
     ApiDefinitionService, ApiDefinitionServiceDefault,
 };
@@ -24,7 +28,13 @@ use golem_worker_service_base::service::component::RemoteComponentService;
 use golem_worker_service_base::service::http::http_api_definition_validator::{
     HttpApiDefinitionValidator, RouteValidationError,
 };
-use golem_worker_service_base::service::worker::WorkerServiceDefault;
+use golem_worker_service_base::service::http::http_response_cache::{
+    DisabledHttpResponseCache, HttpResponseCache, RedisHttpResponseCache,
+};
+use golem_worker_service_base::service::http::jwt_validator::{DefaultJwtValidator, JwtValidator};
+use golem_worker_service_base::service::worker::{
+    InvocationAttempts, UpdateRolloutService, UpdateRolloutServiceDefault, WorkerServiceDefault,
+};
 use golem_worker_service_base::worker_bridge_execution::WorkerRequestExecutor;
 
 use golem_api_grpc::proto::golem::workerexecutor::v1::worker_executor_client::WorkerExecutorClient;
@@ -32,10 +42,18 @@ use golem_common::client::{GrpcClientConfig, MultiTargetGrpcClient};
 use golem_common::config::RetryConfig;
 
 use golem_common::config::DbConfig;
+use golem_common::redis::RedisPool;
 use golem_service_base::db;
 use golem_worker_service_base::service::api_deployment::{
     ApiDeploymentService, ApiDeploymentServiceDefault,
 };
+use golem_worker_service_base::service::api_key::{ApiKeyService, ApiKeyServiceDefault};
+use golem_worker_service_base::service::api_project::{
+    ApiProjectService, ApiProjectServiceDefault,
+};
+use golem_worker_service_base::service::rib_evaluation::{
+    RibEvaluationService, RibEvaluationServiceDefault,
+};
 use std::sync::Arc;
 use std::time::Duration;
 use tonic::codec::CompressionEncoding;
@@ -43,6 +61,7 @@ use tonic::codec::CompressionEncoding;
 #[derive(Clone)]
 pub struct Services {
     pub worker_service: worker::WorkerService,
+    pub update_rollout_service: worker::UpdateRolloutService,
     pub component_service: component::ComponentService,
     pub definition_service: Arc<
         dyn ApiDefinitionService<EmptyAuthCtx, DefaultNamespace, RouteValidationError>
@@ -50,9 +69,15 @@ pub struct Services {
             + Send,
     >,
     pub deployment_service: Arc<dyn ApiDeploymentService<DefaultNamespace> + Sync + Send>,
+    pub api_key_service: Arc<dyn ApiKeyService<DefaultNamespace> + Sync + Send>,
+    pub api_project_service:
+        Arc<dyn ApiProjectService<EmptyAuthCtx, DefaultNamespace> + Sync + Send>,
+    pub rib_evaluation_service: Arc<dyn RibEvaluationService<EmptyAuthCtx> + Sync + Send>,
     pub http_definition_lookup_service:
         Arc<dyn ApiDefinitionsLookup<InputHttpRequest, CompiledHttpApiDefinition> + Sync + Send>,
     pub worker_to_http_service: Arc<dyn WorkerRequestExecutor + Sync + Send>,
+    pub jwt_validator: Arc<dyn JwtValidator + Sync + Send>,
+    pub response_cache: Arc<dyn HttpResponseCache + Sync + Send>,
     pub api_definition_validator_service: Arc<
         dyn ApiDefinitionValidatorService<HttpApiDefinition, RouteValidationError> + Sync + Send,
     >,
@@ -69,6 +94,7 @@ impl Services {
         );
 
         let worker_executor_grpc_clients = MultiTargetGrpcClient::new(
+            "worker_executor",
             |channel| {
                 WorkerExecutorClient::new(channel)
                     .send_compressed(CompressionEncoding::Gzip)
@@ -86,6 +112,20 @@ impl Services {
             },
         );
 
+        {
+            let worker_executor_grpc_clients = worker_executor_grpc_clients.clone();
+            let pool_config = config.worker_executor_pool.clone();
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(pool_config.health_check_interval);
+                loop {
+                    interval.tick().await;
+                    worker_executor_grpc_clients
+                        .evict_unhealthy(pool_config.health_check_timeout)
+                        .await;
+                }
+            });
+        }
+
         let component_service: component::ComponentService = {
             let config = &config.component_service;
             let uri = config.uri();
@@ -94,18 +134,14 @@ impl Services {
             Arc::new(RemoteComponentService::new(uri, retry_config))
         };
 
-        let worker_service: worker::WorkerService = Arc::new(WorkerServiceDefault::new(
-            worker_executor_grpc_clients.clone(),
-            config.worker_executor_retries.clone(),
-            component_service.clone(),
-            routing_table_service.clone(),
-        ));
-
-        let worker_to_http_service: Arc<dyn WorkerRequestExecutor + Sync + Send> = Arc::new(
-            UnauthorisedWorkerRequestExecutor::new(worker_service.clone()),
-        );
-
-        let (api_definition_repo, api_deployment_repo) = match config.db.clone() {
+        let (
+            api_definition_repo,
+            api_deployment_repo,
+            api_key_repo,
+            api_project_repo,
+            invocation_attempt_repo,
+            update_rollout_repo,
+        ) = match config.db.clone() {
             DbConfig::Postgres(c) => {
                 let db_pool = db::create_postgres_pool(&c)
                     .await
@@ -118,7 +154,27 @@ impl Services {
                     Arc::new(api_deployment::DbApiDeploymentRepo::new(
                         db_pool.clone().into(),
                     ));
-                (api_definition_repo, api_deployment_repo)
+                let api_key_repo: Arc<dyn api_key::ApiKeyRepo + Sync + Send> =
+                    Arc::new(api_key::DbApiKeyRepo::new(db_pool.clone().into()));
+                let api_project_repo: Arc<dyn api_project::ApiProjectRepo + Sync + Send> =
+                    Arc::new(api_project::DbApiProjectRepo::new(db_pool.clone().into()));
+                let invocation_attempt_repo: Arc<
+                    dyn invocation_attempt::InvocationAttemptRepo + Sync + Send,
+                > = Arc::new(invocation_attempt::DbInvocationAttemptRepo::new(
+                    db_pool.clone().into(),
+                ));
+                let update_rollout_repo: Arc<dyn update_rollout::UpdateRolloutRepo + Sync + Send> =
+                    Arc::new(update_rollout::DbUpdateRolloutRepo::new(
+                        db_pool.clone().into(),
+                    ));
+                (
+                    api_definition_repo,
+                    api_deployment_repo,
+                    api_key_repo,
+                    api_project_repo,
+                    invocation_attempt_repo,
+                    update_rollout_repo,
+                )
             }
             DbConfig::Sqlite(c) => {
                 let db_pool = db::create_sqlite_pool(&c)
@@ -132,10 +188,63 @@ impl Services {
                     Arc::new(api_deployment::DbApiDeploymentRepo::new(
                         db_pool.clone().into(),
                     ));
-                (api_definition_repo, api_deployment_repo)
+                let api_key_repo: Arc<dyn api_key::ApiKeyRepo + Sync + Send> =
+                    Arc::new(api_key::DbApiKeyRepo::new(db_pool.clone().into()));
+                let api_project_repo: Arc<dyn api_project::ApiProjectRepo + Sync + Send> =
+                    Arc::new(api_project::DbApiProjectRepo::new(db_pool.clone().into()));
+                let invocation_attempt_repo: Arc<
+                    dyn invocation_attempt::InvocationAttemptRepo + Sync + Send,
+                > = Arc::new(invocation_attempt::DbInvocationAttemptRepo::new(
+                    db_pool.clone().into(),
+                ));
+                let update_rollout_repo: Arc<dyn update_rollout::UpdateRolloutRepo + Sync + Send> =
+                    Arc::new(update_rollout::DbUpdateRolloutRepo::new(
+                        db_pool.clone().into(),
+                    ));
+                (
+                    api_definition_repo,
+                    api_deployment_repo,
+                    api_key_repo,
+                    api_project_repo,
+                    invocation_attempt_repo,
+                    update_rollout_repo,
+                )
             }
         };
 
+        let worker_service: worker::WorkerService = Arc::new(WorkerServiceDefault::new(
+            worker_executor_grpc_clients.clone(),
+            config.worker_executor_retries.clone(),
+            component_service.clone(),
+            routing_table_service.clone(),
+            config.worker_placement.clone(),
+            Arc::new(InvocationAttempts::new(invocation_attempt_repo.clone())),
+        ));
+
+        let update_rollout_service: worker::UpdateRolloutService =
+            Arc::new(UpdateRolloutServiceDefault::new(
+                worker_service.clone(),
+                component_service.clone(),
+                routing_table_service.clone(),
+                update_rollout_repo.clone(),
+            ));
+
+        {
+            let update_rollout_service = update_rollout_service.clone();
+            tokio::spawn(async move {
+                if let Err(err) = update_rollout_service
+                    .resume_rollouts(&EmptyAuthCtx::default())
+                    .await
+                {
+                    tracing::error!("Failed to resume the worker update rollouts: {err}");
+                }
+            });
+        }
+
+        let worker_to_http_service: Arc<dyn WorkerRequestExecutor + Sync + Send> = Arc::new(
+            UnauthorisedWorkerRequestExecutor::new(worker_service.clone()),
+        );
+
         let api_definition_validator_service = Arc::new(HttpApiDefinitionValidator {});
 
         let definition_service: Arc<
@@ -155,15 +264,54 @@ impl Services {
                 api_definition_repo.clone(),
             ));
 
+        let api_key_service: Arc<dyn ApiKeyService<DefaultNamespace> + Sync + Send> = Arc::new(
+            ApiKeyServiceDefault::new(api_key_repo.clone(), api_deployment_repo.clone()),
+        );
+
+        let api_project_service: Arc<
+            dyn ApiProjectService<EmptyAuthCtx, DefaultNamespace> + Sync + Send,
+        > = Arc::new(ApiProjectServiceDefault::new(
+            component_service.clone(),
+            api_project_repo.clone(),
+            api_definition_repo.clone(),
+            api_deployment_repo.clone(),
+        ));
+
+        let rib_evaluation_service: Arc<dyn RibEvaluationService<EmptyAuthCtx> + Sync + Send> =
+            Arc::new(RibEvaluationServiceDefault::new(
+                component_service.clone(),
+                worker_to_http_service.clone(),
+            ));
+
         let http_definition_lookup_service =
             Arc::new(HttpApiDefinitionLookup::new(deployment_service.clone()));
 
+        let jwt_validator: Arc<dyn JwtValidator + Sync + Send> =
+            Arc::new(DefaultJwtValidator::default());
+
+        let response_cache: Arc<dyn HttpResponseCache + Sync + Send> = match &config.response_cache
+        {
+            HttpResponseCacheConfig::Disabled => Arc::new(DisabledHttpResponseCache),
+            HttpResponseCacheConfig::Redis(redis_config) => {
+                let redis = RedisPool::configured(redis_config)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Arc::new(RedisHttpResponseCache::new(redis))
+            }
+        };
+
         Ok(Services {
             worker_service,
+            update_rollout_service,
             definition_service,
             deployment_service,
+            api_key_service,
+            api_project_service,
+            rib_evaluation_service,
             http_definition_lookup_service,
             worker_to_http_service,
+            jwt_validator,
+            response_cache,
             component_service,
             api_definition_validator_service,
         })
@@ -4,3 +4,9 @@ use golem_service_base::auth::EmptyAuthCtx;
 
 pub type WorkerService =
     Arc<dyn golem_worker_service_base::service::worker::WorkerService<EmptyAuthCtx> + Sync + Send>;
+
+pub type UpdateRolloutService = Arc<
+    dyn golem_worker_service_base::service::worker::UpdateRolloutService<EmptyAuthCtx>
+        + Sync
+        + Send,
+>;
@@ -32,8 +32,7 @@ mod internal {
     use crate::empty_worker_metadata;
     use crate::worker_bridge_request_executor::UnauthorisedWorkerRequestExecutor;
 
-    use golem_common::model::WorkerId;
-    use golem_service_base::model::validate_worker_name;
+    use golem_common::model::{InvocationPriority, WorkerId, WorkerName};
     use golem_worker_service_base::worker_bridge_execution::{
         WorkerRequest, WorkerRequestExecutorError, WorkerResponse,
     };
@@ -43,8 +42,7 @@ mod internal {
         default_executor: &UnauthorisedWorkerRequestExecutor,
         worker_request_params: WorkerRequest,
     ) -> Result<WorkerResponse, WorkerRequestExecutorError> {
-        validate_worker_name(&worker_request_params.worker_name)?;
-        let worker_name = worker_request_params.worker_name;
+        let worker_name = WorkerName::parse(&worker_request_params.worker_name)?;
 
         let component_id = worker_request_params.component_id;
 
@@ -71,7 +69,7 @@ mod internal {
         // TODO: check if these are already added from span
         info!(
             component_id = component_id.to_string(),
-            worker_name,
+            worker_name = worker_name.as_str(),
             function_name = worker_request_params.function_name.to_string(),
             idempotency_key = idempotency_key_str,
             "Executing request",
@@ -80,7 +78,7 @@ mod internal {
         // TODO: check if these are already added from span
         debug!(
             component_id = component_id.to_string(),
-            worker_name,
+            worker_name = worker_name.as_str(),
             function_name = worker_request_params.function_name.to_string(),
             idempotency_key = idempotency_key_str,
             invocation_params = format!("{:?}", invoke_parameters),
@@ -95,7 +93,10 @@ mod internal {
                 worker_request_params.function_name,
                 invoke_parameters,
                 None,
+                InvocationPriority::Normal,
+                None,
                 empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
             )
             .await
             .map_err(|e| e.to_string())?;
@@ -21,7 +21,7 @@ use tokio::task::JoinSet;
 use tracing::warn;
 
 use crate::benchmarks::data::Data;
-use golem_common::model::{ComponentId, IdempotencyKey, WorkerId};
+use golem_common::model::{ComponentId, IdempotencyKey, WorkerId, WorkerName};
 use golem_test_framework::config::{CliParams, CliTestDependencies};
 use golem_test_framework::dsl::benchmark::{
     BenchmarkApi, BenchmarkRecorder, BenchmarkResult, ResultKey, RunConfig,
@@ -46,7 +46,7 @@ pub fn generate_worker_ids(size: usize, component_id: &ComponentId, prefix: &str
         let worker_name = format!("{prefix}-{i}");
         worker_ids.push(WorkerId {
             component_id: component_id.clone(),
-            worker_name,
+            worker_name: WorkerName::from_existing(worker_name),
         });
     }
     worker_ids
@@ -19,7 +19,7 @@ use tokio::task::JoinSet;
 
 use golem_api_grpc::proto::golem::shardmanager;
 use golem_api_grpc::proto::golem::shardmanager::v1::GetRoutingTableRequest;
-use golem_common::model::{RoutingTable, WorkerId};
+use golem_common::model::{RoutingTable, WorkerId, WorkerName};
 use golem_test_framework::config::{CliParams, TestDependencies};
 use golem_test_framework::dsl::benchmark::{Benchmark, BenchmarkRecorder, RunConfig};
 use golem_test_framework::dsl::TestDsl;
@@ -100,14 +100,14 @@ impl Benchmark for RpcCpuIntensive {
             // Rpc parent worker-id
             let parent_worker_id = WorkerId {
                 component_id: component_id.clone(),
-                worker_name: format!("parent_worker-{i}"),
+                worker_name: WorkerName::from_existing(format!("parent_worker-{i}")),
             };
 
             let child_worker_name = format!("child_worker-{i}");
 
             let child_worker_id = WorkerId {
                 component_id: child_component_id.clone(),
-                worker_name: child_worker_name.to_string(),
+                worker_name: WorkerName::from_existing(child_worker_name.clone()),
             };
 
             let mut env = HashMap::new();
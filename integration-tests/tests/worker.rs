@@ -27,8 +27,8 @@ use golem_common::model::oplog::{OplogIndex, WorkerResourceId};
 use golem_common::model::public_oplog::{ExportedFunctionInvokedParameters, PublicOplogEntry};
 use golem_common::model::{
     ComponentId, FilterComparator, IdempotencyKey, ScanCursor, StringFilterComparator,
-    TargetWorkerId, Timestamp, WorkerFilter, WorkerId, WorkerMetadata, WorkerResourceDescription,
-    WorkerStatus,
+    TargetWorkerId, Timestamp, WorkerFilter, WorkerId, WorkerMetadata, WorkerName,
+    WorkerResourceDescription, WorkerStatus,
 };
 use golem_test_framework::config::EnvBasedTestDependencies;
 use rand::seq::IteratorRandom;
@@ -49,7 +49,7 @@ async fn dynamic_worker_creation(deps: &EnvBasedTestDependencies, _tracing: &Tra
     let component_id = deps.store_component("environment-service").await;
     let worker_id = WorkerId {
         component_id: component_id.clone(),
-        worker_name: "dynamic-worker-creation-1".to_string(),
+        worker_name: WorkerName::from_existing("dynamic-worker-creation-1"),
     };
 
     let args = deps
@@ -181,7 +181,7 @@ async fn ephemeral_worker_creation_with_name_is_not_persistent(
     let component_id = deps.store_ephemeral_component("counters").await;
     let worker_id = TargetWorkerId {
         component_id: component_id.clone(),
-        worker_name: Some("test".to_string()),
+        worker_name: Some(WorkerName::from_existing("test")),
     };
 
     let _ = deps
@@ -885,7 +885,7 @@ async fn get_workers(deps: &EnvBasedTestDependencies, _tracing: &Tracing) {
                 Some(
                     WorkerFilter::new_name(
                         StringFilterComparator::Equal,
-                        worker_id.worker_name.clone(),
+                        worker_id.worker_name.to_string(),
                     )
                     .and(
                         WorkerFilter::new_status(FilterComparator::Equal, WorkerStatus::Idle).or(
@@ -967,7 +967,7 @@ async fn get_running_workers(deps: &EnvBasedTestDependencies, _tracing: &Tracing
                     let component_id: ComponentId = component_id.as_str().try_into().unwrap();
                     let worker_id = WorkerId {
                         component_id,
-                        worker_name: worker_name.clone(),
+                        worker_name: WorkerName::from_existing(worker_name.clone()),
                     };
                     let mut ids = polling_worker_ids_clone.lock().unwrap();
                     ids.insert(worker_id.clone());
@@ -1037,7 +1037,7 @@ async fn get_running_workers(deps: &EnvBasedTestDependencies, _tracing: &Tracing
                 Some(
                     WorkerFilter::new_name(
                         StringFilterComparator::Equal,
-                        worker_id.worker_name.clone(),
+                        worker_id.worker_name.to_string(),
                     )
                     .and(WorkerFilter::new_status(
                         FilterComparator::Equal,
@@ -1126,7 +1126,7 @@ async fn auto_update_on_idle_via_host_function(
     let runtime_svc = deps.store_component("runtime-service").await;
     let runtime_svc_worker = WorkerId {
         component_id: runtime_svc,
-        worker_name: "runtime-service".to_string(),
+        worker_name: WorkerName::from_existing("runtime-service"),
     };
     deps.invoke_and_await(
         &runtime_svc_worker,
@@ -1137,7 +1137,7 @@ async fn auto_update_on_idle_via_host_function(
                     Value::U64(worker_id.component_id.0.as_u64_pair().0),
                     Value::U64(worker_id.component_id.0.as_u64_pair().1),
                 ])]),
-                Value::String(worker_id.worker_name.clone()),
+                Value::String(worker_id.worker_name.to_string()),
             ]),
             Value::U64(target_version),
             Value::Enum(0),
@@ -1170,7 +1170,7 @@ async fn get_oplog_1(deps: &EnvBasedTestDependencies, _tracing: &Tracing) {
 
     let worker_id = WorkerId {
         component_id,
-        worker_name: "getoplog1".to_string(),
+        worker_name: WorkerName::from_existing("getoplog1"),
     };
 
     let idempotency_key1 = IdempotencyKey::fresh();